@@ -14,11 +14,15 @@ use bitfun_core::agentic::persistence;
 use bitfun_core::agentic::session;
 use bitfun_core::agentic::tools;
 use bitfun_core::infrastructure::try_get_path_manager_arc;
+use bitfun_core::service::{config, mcp, token_usage};
 
 /// Agentic system state
 pub struct AgenticSystem {
     pub coordinator: Arc<coordination::ConversationCoordinator>,
     pub event_queue: Arc<events::EventQueue>,
+    pub event_router: Arc<events::EventRouter>,
+    /// MCP servers (started on demand); None when the service failed to initialize
+    pub mcp_service: Option<Arc<mcp::MCPService>>,
 }
 
 /// Initialize Agentic system
@@ -87,10 +91,33 @@ pub async fn init_agentic_system() -> Result<AgenticSystem> {
     ));
 
     coordination::ConversationCoordinator::set_global(coordinator.clone());
+
+    // Token usage and spend tracking
+    let token_usage_service =
+        Arc::new(token_usage::TokenUsageService::new(path_manager.clone()).await?);
+    let token_usage_subscriber = Arc::new(token_usage::TokenUsageSubscriber::new(
+        token_usage_service.clone(),
+    ));
+    event_router.subscribe_internal("token_usage".to_string(), token_usage_subscriber);
+    token_usage::set_global_token_usage_service(token_usage_service);
+
+    let mcp_service = match config::get_global_config_service()
+        .await
+        .and_then(mcp::MCPService::new)
+    {
+        Ok(service) => Some(Arc::new(service)),
+        Err(e) => {
+            tracing::warn!("Failed to initialize MCP service: {}", e);
+            None
+        }
+    };
+
     tracing::info!("Agentic system initialization complete");
 
     Ok(AgenticSystem {
         coordinator,
         event_queue,
+        event_router,
+        mcp_service,
     })
 }
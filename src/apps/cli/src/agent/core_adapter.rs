@@ -3,27 +3,51 @@
 //! Adapts bitfun-core's Agentic system to CLI's Agent interface
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
+use super::tool_policy::ToolPolicy;
 use super::{Agent, AgentEvent, AgentResponse};
-use crate::session::{ToolCall, ToolCallStatus};
+use crate::session::{FlowItem, Message, Session, ToolCall, ToolCallStatus};
 use bitfun_core::agentic::coordination::{
     ConversationCoordinator, DialogSubmissionPolicy, DialogTriggerSource,
 };
-use bitfun_core::agentic::core::SessionConfig;
-use bitfun_core::agentic::events::EventQueue;
+use bitfun_core::agentic::core::{
+    is_system_reminder_only, strip_prompt_markup, Message as CoreMessage, MessageContent,
+    MessageRole, ProcessingPhase, SessionConfig,
+};
+use bitfun_core::agentic::events::{EventQueue, EventRouter};
+use bitfun_core::agentic::tools::implementations::review_findings_tool::ReviewReport;
+use bitfun_core::agentic::turn_summary;
+use bitfun_core::service::config::{types::AIConfig, GlobalConfigManager};
+use bitfun_core::service::session::TurnSummary;
+use bitfun_core::service::token_usage::get_global_token_usage_service;
 use bitfun_events::{AgenticEvent as CoreEvent, ToolEventData};
 
+/// How long the event queue is watched for a completed turn's summary
+const TURN_SUMMARY_WAIT: Duration = Duration::from_secs(30);
+
 /// Core-based Agent implementation
 pub struct CoreAgentAdapter {
     name: String,
     agent_type: String,
     coordinator: Arc<ConversationCoordinator>,
     event_queue: Arc<EventQueue>,
-    workspace_path: Option<PathBuf>,
-    session_id: Option<String>,
+    event_router: Arc<EventRouter>,
+    workspace_path: Mutex<Option<PathBuf>>,
+    session_id: Mutex<Option<String>>,
+    /// Answers tool confirmations itself; None leaves them to the UI
+    tool_policy: Option<ToolPolicy>,
+    /// Model rounds allowed per message before the turn is cancelled
+    max_rounds: Option<usize>,
+    /// Task waiting for the last turn's summary, with its stop flag
+    summary_watch: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>>,
 }
 
 impl CoreAgentAdapter {
@@ -31,6 +55,7 @@ impl CoreAgentAdapter {
         agent_type: String,
         coordinator: Arc<ConversationCoordinator>,
         event_queue: Arc<EventQueue>,
+        event_router: Arc<EventRouter>,
         workspace_path: Option<PathBuf>,
     ) -> Self {
         let name = match agent_type.as_str() {
@@ -43,18 +68,47 @@ impl CoreAgentAdapter {
             agent_type: agent_type.clone(),
             coordinator,
             event_queue,
-            workspace_path,
-            session_id: None,
+            event_router,
+            workspace_path: Mutex::new(workspace_path),
+            session_id: Mutex::new(None),
+            tool_policy: None,
+            max_rounds: None,
+            summary_watch: Mutex::new(None),
         }
     }
 
-    async fn ensure_session(&mut self) -> Result<String> {
-        if let Some(session_id) = &self.session_id {
-            return Ok(session_id.clone());
+    /// Confirm or reject tools that ask for permission according to `policy`
+    pub fn with_tool_policy(mut self, policy: ToolPolicy) -> Self {
+        self.tool_policy = Some(policy);
+        self
+    }
+
+    /// Cancel a turn once it starts more than `max_rounds` model rounds
+    pub fn with_max_rounds(mut self, max_rounds: Option<usize>) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Continue an existing (already restored) core session instead of creating one
+    pub fn with_session_id(self, session_id: Option<String>) -> Self {
+        *self.session_id.lock().unwrap_or_else(|e| e.into_inner()) = session_id;
+        self
+    }
+
+    async fn ensure_session(&self, event_tx: &mpsc::UnboundedSender<AgentEvent>) -> Result<String> {
+        if let Some(session_id) = self
+            .session_id
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            return Ok(session_id);
         }
 
         let workspace_path = self
             .workspace_path
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
             .clone()
             .or_else(|| std::env::current_dir().ok())
             .map(|path| path.to_string_lossy().to_string());
@@ -74,48 +128,150 @@ impl CoreAgentAdapter {
             )
             .await?;
 
-        self.session_id = Some(session.session_id.clone());
+        *self.session_id.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some(session.session_id.clone());
         tracing::info!("Created session: {}", session.session_id);
+        let _ = event_tx.send(AgentEvent::SessionStarted(session.session_id.clone()));
 
         Ok(session.session_id)
     }
 }
 
+impl CoreAgentAdapter {
+    /// Keep draining events after `turn_id` completed until its summary shows up
+    async fn watch_turn_summary(
+        &self,
+        session_id: String,
+        turn_id: String,
+        event_tx: mpsc::UnboundedSender<AgentEvent>,
+    ) {
+        let enabled = match self
+            .coordinator
+            .get_session_manager()
+            .get_session(&session_id)
+        {
+            Some(session) => turn_summary::is_enabled(&session.config).await,
+            None => false,
+        };
+        if !enabled {
+            return;
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let event_queue = self.event_queue.clone();
+        let event_router = self.event_router.clone();
+        let stop_flag = stop.clone();
+        let handle = tokio::spawn(async move {
+            let deadline = tokio::time::Instant::now() + TURN_SUMMARY_WAIT;
+            while !stop_flag.load(Ordering::Relaxed) && tokio::time::Instant::now() < deadline {
+                let events = event_queue.dequeue_batch(10).await;
+                if events.is_empty() {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+                for envelope in events {
+                    if let Err(e) = event_router.route(envelope.clone()).await {
+                        tracing::warn!("Internal event routing failed: {}", e);
+                    }
+                    if let CoreEvent::TurnSummaryGenerated {
+                        session_id: summary_session_id,
+                        turn_id: summary_turn_id,
+                        did,
+                        changed,
+                        next,
+                    } = envelope.event
+                    {
+                        if summary_session_id == session_id && summary_turn_id == turn_id {
+                            let summary = TurnSummary { did, changed, next };
+                            let _ = event_tx.send(AgentEvent::TurnSummary(summary));
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        *self.summary_watch.lock().unwrap_or_else(|e| e.into_inner()) = Some((stop, handle));
+    }
+
+    /// Stop watching for the last turn's summary so the next turn owns the event queue
+    async fn stop_summary_watch(&self) {
+        let watch = self
+            .summary_watch
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some((stop, handle)) = watch {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.await;
+        }
+    }
+}
+
+/// Report `next` to the UI unless the turn is already in that phase
+fn advance_phase(
+    phase: &mut ProcessingPhase,
+    next: ProcessingPhase,
+    event_tx: &mpsc::UnboundedSender<AgentEvent>,
+) {
+    if *phase != next {
+        *phase = next.clone();
+        let _ = event_tx.send(AgentEvent::Phase(next));
+    }
+}
+
+/// Seconds the tool pipeline waits for a confirmation (`ai.tool_confirmation_timeout_secs`)
+async fn confirmation_timeout_secs() -> Option<u64> {
+    let service = GlobalConfigManager::get_service().await.ok()?;
+    let ai_config: AIConfig = service.get_config(Some("ai")).await.unwrap_or_default();
+    ai_config.tool_confirmation_timeout_secs
+}
+
 #[async_trait::async_trait]
 impl Agent for CoreAgentAdapter {
     async fn process_message(
         &self,
         message: String,
+        mentions: Vec<String>,
         event_tx: mpsc::UnboundedSender<AgentEvent>,
     ) -> Result<AgentResponse> {
-        let mut self_mut = Self {
-            name: self.name.clone(),
-            agent_type: self.agent_type.clone(),
-            coordinator: self.coordinator.clone(),
-            event_queue: self.event_queue.clone(),
-            workspace_path: self.workspace_path.clone(),
-            session_id: self.session_id.clone(),
-        };
-
-        let session_id = self_mut.ensure_session().await?;
+        self.stop_summary_watch().await;
+        let session_id = self.ensure_session(&event_tx).await?;
         tracing::info!("Processing message: {}", message);
 
+        // Same context markers the desktop prepends for attached files
+        let (user_input, original_user_input) = if mentions.is_empty() {
+            (message.clone(), None)
+        } else {
+            let context: Vec<String> = mentions
+                .iter()
+                .map(|path| format!("[File: {}]", path))
+                .collect();
+            (
+                format!("{}\n\n{}", context.join("\n"), message),
+                Some(message.clone()),
+            )
+        };
+
         let _ = event_tx.send(AgentEvent::Thinking);
         self.coordinator
             .start_dialog_turn(
                 session_id.clone(),
-                message.clone(),
-                None,
+                user_input,
+                original_user_input,
                 None,
                 self.agent_type.clone(),
                 None,
-                DialogSubmissionPolicy::for_source(DialogTriggerSource::Cli),
+                DialogSubmissionPolicy::for_source(DialogTriggerSource::Cli)
+                    .with_require_tool_confirmation(self.tool_policy.is_some()),
             )
             .await?;
 
         let mut accumulated_text = String::new();
-        let mut tool_map: std::collections::HashMap<String, ToolCall> =
-            std::collections::HashMap::new();
+        // Keyed by tool ID, in call order
+        let mut tool_map: IndexMap<String, ToolCall> = IndexMap::new();
+        let mut rounds = 0;
+        let mut phase = ProcessingPhase::Starting;
+        let _ = event_tx.send(AgentEvent::Phase(phase.clone()));
 
         let event_queue = self.event_queue.clone();
         let session_id_clone = session_id.clone();
@@ -129,9 +285,14 @@ impl Agent for CoreAgentAdapter {
             }
 
             for envelope in events {
+                // Internal subscribers (token usage) see every event, as in the desktop app
+                if let Err(e) = self.event_router.route(envelope.clone()).await {
+                    tracing::warn!("Internal event routing failed: {}", e);
+                }
+
                 let event = envelope.event;
 
-                if event.session_id() != Some(&session_id_clone) {
+                if event.session_id() != session_id_clone {
                     continue;
                 }
 
@@ -139,6 +300,7 @@ impl Agent for CoreAgentAdapter {
 
                 match event {
                     CoreEvent::TextChunk { text, .. } => {
+                        advance_phase(&mut phase, ProcessingPhase::Streaming, &event_tx);
                         accumulated_text.push_str(&text);
                         let _ = event_tx.send(AgentEvent::TextChunk(text));
                     }
@@ -200,6 +362,7 @@ impl Agent for CoreAgentAdapter {
                             tool_name,
                             params,
                         } => {
+                            advance_phase(&mut phase, ProcessingPhase::ToolCalling, &event_tx);
                             tool_map.entry(tool_id.clone()).or_insert_with(|| ToolCall {
                                 tool_id: Some(tool_id.clone()),
                                 tool_name: tool_name.clone(),
@@ -246,13 +409,48 @@ impl Agent for CoreAgentAdapter {
 
                         ToolEventData::ConfirmationNeeded {
                             tool_id,
-                            tool_name: _,
-                            params: _,
+                            tool_name,
+                            params,
+                            summary,
                         } => {
+                            advance_phase(&mut phase, ProcessingPhase::ToolConfirming, &event_tx);
                             if let Some(tool) = tool_map.get_mut(&tool_id) {
                                 tool.status = ToolCallStatus::ConfirmationNeeded;
-                                tool.progress_message =
-                                    Some("Waiting for user confirmation".to_string());
+                                tool.progress_message = Some(summary.map_or_else(
+                                    || "Waiting for user confirmation".to_string(),
+                                    |summary| summary.text,
+                                ));
+                            }
+
+                            if let Some(policy) = &self.tool_policy {
+                                let answer = if policy.allows(&tool_name) {
+                                    self.coordinator
+                                        .confirm_tool(&session_id_clone, &tool_id, None)
+                                        .await
+                                } else {
+                                    let reason = format!(
+                                        "{} is not allowed (use --allow {} to permit it)",
+                                        tool_name, tool_name
+                                    );
+                                    let _ = event_tx.send(AgentEvent::ToolCallComplete {
+                                        tool_name: tool_name.clone(),
+                                        result: reason.clone(),
+                                        success: false,
+                                    });
+                                    self.coordinator
+                                        .reject_tool(&session_id_clone, &tool_id, reason)
+                                        .await
+                                };
+                                if let Err(e) = answer {
+                                    tracing::warn!("Failed to answer confirmation: {}", e);
+                                }
+                            } else {
+                                let _ = event_tx.send(AgentEvent::PermissionRequest {
+                                    tool_id,
+                                    tool_name,
+                                    parameters: params,
+                                    timeout_secs: confirmation_timeout_secs().await,
+                                });
                             }
                         }
 
@@ -330,9 +528,77 @@ impl Agent for CoreAgentAdapter {
                         _ => {}
                     },
 
-                    CoreEvent::DialogTurnCompleted { .. } => {
+                    CoreEvent::TokenUsageUpdated {
+                        input_tokens,
+                        output_tokens,
+                        max_context_tokens,
+                        is_subagent,
+                        cost_usd,
+                        workspace_path,
+                        ..
+                    } => {
+                        let _ = event_tx.send(AgentEvent::TokenUsage {
+                            input_tokens: input_tokens as u64,
+                            output_tokens: output_tokens.unwrap_or(0) as u64,
+                        });
+                        // A round's prompt is the whole context; subagents have their own
+                        if !is_subagent {
+                            let _ = event_tx.send(AgentEvent::ContextUsage {
+                                used_tokens: input_tokens as u64,
+                                window_tokens: max_context_tokens.map(|tokens| tokens as u64),
+                            });
+                        }
+                        let service =
+                            get_global_token_usage_service().filter(|_| cost_usd.is_some());
+                        if let Some(service) = service {
+                            let summary = service
+                                .get_spend_summary(
+                                    Some(&session_id_clone),
+                                    workspace_path.as_deref(),
+                                )
+                                .await;
+                            let _ = event_tx.send(AgentEvent::SpendUpdated {
+                                session_usd: summary.session_usd.unwrap_or(0.0),
+                                today_usd: summary.today_usd,
+                            });
+                        }
+                    }
+
+                    CoreEvent::SpendConfirmationRequired {
+                        turn_id,
+                        estimated_cost_usd,
+                        threshold_usd,
+                        ..
+                    } => {
+                        // The CLI has no approval prompt; decline so the turn doesn't hang
+                        let _ = self.coordinator.confirm_turn_spend(&turn_id, false);
+                        let error = format!(
+                            "Estimated round cost ${:.2} exceeds turn_confirm_usd (${:.2}); raise the limit to continue",
+                            estimated_cost_usd, threshold_usd
+                        );
+                        let _ = event_tx.send(AgentEvent::Error(error));
+                        let tool_calls: Vec<ToolCall> = tool_map.into_values().collect();
+
+                        return Ok(AgentResponse {
+                            tool_calls,
+                            success: false,
+                        });
+                    }
+
+                    CoreEvent::ReviewResult { report, .. } => {
+                        match serde_json::from_value::<ReviewReport>(report) {
+                            Ok(report) => {
+                                let _ = event_tx.send(AgentEvent::ReviewFindings(report));
+                            }
+                            Err(e) => tracing::warn!("Invalid review report: {}", e),
+                        }
+                    }
+
+                    CoreEvent::DialogTurnCompleted { turn_id, .. } => {
                         tracing::info!("Dialog turn completed");
                         let _ = event_tx.send(AgentEvent::Done);
+                        self.watch_turn_summary(session_id_clone, turn_id, event_tx.clone())
+                            .await;
                         let tool_calls: Vec<ToolCall> = tool_map.into_values().collect();
 
                         return Ok(AgentResponse {
@@ -341,9 +607,50 @@ impl Agent for CoreAgentAdapter {
                         });
                     }
 
-                    CoreEvent::DialogTurnFailed { error, .. } => {
+                    CoreEvent::ModelRoundStarted { .. } => {
+                        advance_phase(&mut phase, ProcessingPhase::Thinking, &event_tx);
+                        rounds += 1;
+                        if self.max_rounds.is_some_and(|max| rounds > max) {
+                            let _ = self
+                                .coordinator
+                                .cancel_active_turn_for_session(
+                                    &session_id_clone,
+                                    Duration::from_secs(5),
+                                )
+                                .await;
+                            let error = format!(
+                                "Stopped after {} model rounds (max turns reached)",
+                                rounds - 1
+                            );
+                            let _ = event_tx.send(AgentEvent::Error(error));
+                            let tool_calls: Vec<ToolCall> = tool_map.into_values().collect();
+
+                            return Ok(AgentResponse {
+                                tool_calls,
+                                success: false,
+                            });
+                        }
+                    }
+
+                    CoreEvent::DialogTurnCancelled { .. } => {
+                        let _ =
+                            event_tx.send(AgentEvent::Error("Dialog turn cancelled".to_string()));
+                        let tool_calls: Vec<ToolCall> = tool_map.into_values().collect();
+
+                        return Ok(AgentResponse {
+                            tool_calls,
+                            success: false,
+                        });
+                    }
+
+                    CoreEvent::DialogTurnFailed {
+                        error,
+                        user_message,
+                        ..
+                    } => {
                         tracing::error!("Execution error: {}", error);
-                        let _ = event_tx.send(AgentEvent::Error(error.clone()));
+                        let message = user_message.map_or(error, |m| m.text);
+                        let _ = event_tx.send(AgentEvent::Error(message));
                         let tool_calls: Vec<ToolCall> = tool_map.into_values().collect();
 
                         return Ok(AgentResponse {
@@ -374,4 +681,244 @@ impl Agent for CoreAgentAdapter {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn reset_session(&self) {
+        *self.session_id.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn set_workspace(&self, workspace_path: Option<PathBuf>) {
+        *self
+            .workspace_path
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = workspace_path;
+        self.reset_session();
+    }
+}
+
+/// Restore the core session behind `session` and rebuild its chat history from the
+/// persisted messages, so tool cards show the recorded calls and results
+pub async fn restore_session_history(
+    coordinator: &ConversationCoordinator,
+    session: &mut Session,
+) -> Result<()> {
+    let Some(core_session_id) = session.core_session_id.clone() else {
+        anyhow::bail!("Session has no core session to restore");
+    };
+    let workspace_path = session
+        .workspace
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine session workspace"))?;
+
+    coordinator
+        .restore_session(&workspace_path, &core_session_id)
+        .await?;
+    let messages = coordinator.get_messages(&core_session_id).await?;
+    let history = history_from_core_messages(&messages);
+    if !history.is_empty() {
+        session.replace_history(history);
+    }
+    Ok(())
+}
+
+/// Convert core messages to chat messages: one assistant message per turn, with text and
+/// tool cards in call order and each card completed from its matching tool result
+pub fn history_from_core_messages(messages: &[CoreMessage]) -> Vec<Message> {
+    let mut history: Vec<Message> = Vec::new();
+
+    for core_message in messages {
+        let timestamp = DateTime::<Utc>::from(core_message.timestamp);
+        match (&core_message.role, &core_message.content) {
+            (MessageRole::User, MessageContent::Text(text))
+            | (MessageRole::User, MessageContent::Multimodal { text, .. }) => {
+                if is_system_reminder_only(text) {
+                    continue;
+                }
+                let content = strip_prompt_markup(text);
+                history.push(Message {
+                    id: core_message.id.clone(),
+                    role: "user".to_string(),
+                    content: content.clone(),
+                    timestamp,
+                    flow_items: vec![FlowItem::Text {
+                        content,
+                        is_streaming: false,
+                    }],
+                    turn_summary: None,
+                });
+            }
+
+            (MessageRole::Assistant, content) => {
+                let (text, tool_calls) = match content {
+                    MessageContent::Text(text) => (text.as_str(), &[][..]),
+                    MessageContent::Mixed {
+                        text, tool_calls, ..
+                    } => (text.as_str(), tool_calls.as_slice()),
+                    _ => continue,
+                };
+
+                if history.last().map(|m| m.role.as_str()) != Some("assistant") {
+                    history.push(Message {
+                        id: core_message.id.clone(),
+                        role: "assistant".to_string(),
+                        content: String::new(),
+                        timestamp,
+                        flow_items: Vec::new(),
+                        turn_summary: None,
+                    });
+                }
+                let Some(message) = history.last_mut() else {
+                    continue;
+                };
+
+                if !text.trim().is_empty() {
+                    message.flow_items.push(FlowItem::Text {
+                        content: text.to_string(),
+                        is_streaming: false,
+                    });
+                    message.content = text.to_string();
+                }
+                for tool_call in tool_calls {
+                    message.flow_items.push(FlowItem::Tool {
+                        tool_call: ToolCall {
+                            tool_id: Some(tool_call.tool_id.clone()),
+                            tool_name: tool_call.tool_name.clone(),
+                            parameters: tool_call.arguments.clone(),
+                            result: None,
+                            // No recorded result means the turn stopped before it ran
+                            status: ToolCallStatus::Cancelled,
+                            progress: None,
+                            progress_message: None,
+                            duration_ms: None,
+                        },
+                    });
+                }
+            }
+
+            (
+                MessageRole::Tool,
+                MessageContent::ToolResult {
+                    tool_id,
+                    result,
+                    result_for_assistant,
+                    is_error,
+                    ..
+                },
+            ) => {
+                let tool = history
+                    .iter_mut()
+                    .rev()
+                    .flat_map(|message| message.flow_items.iter_mut())
+                    .find_map(|item| match item {
+                        FlowItem::Tool { tool_call }
+                            if tool_call.tool_id.as_deref() == Some(tool_id.as_str()) =>
+                        {
+                            Some(tool_call)
+                        }
+                        _ => None,
+                    });
+                if let Some(tool) = tool {
+                    tool.status = if *is_error {
+                        ToolCallStatus::Failed
+                    } else {
+                        ToolCallStatus::Success
+                    };
+                    tool.result = Some(
+                        result_for_assistant
+                            .clone()
+                            .unwrap_or_else(|| result.to_string()),
+                    );
+                    tool.progress = Some(1.0);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitfun_core::agentic::core::{render_user_query, ToolCall as CoreToolCall};
+
+    fn core_message(role: MessageRole, content: MessageContent) -> CoreMessage {
+        CoreMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            role,
+            content,
+            timestamp: std::time::SystemTime::now(),
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn rebuilds_tool_cards_from_calls_and_results() {
+        let messages = vec![
+            core_message(
+                MessageRole::User,
+                MessageContent::Text(render_user_query("list files")),
+            ),
+            core_message(
+                MessageRole::Assistant,
+                MessageContent::Mixed {
+                    reasoning_content: None,
+                    text: "Looking.".to_string(),
+                    tool_calls: vec![
+                        CoreToolCall {
+                            tool_id: "t1".to_string(),
+                            tool_name: "LS".to_string(),
+                            arguments: serde_json::json!({ "path": "." }),
+                            is_error: false,
+                            repaired: false,
+                            argument_error: None,
+                        },
+                        CoreToolCall {
+                            tool_id: "t2".to_string(),
+                            tool_name: "Read".to_string(),
+                            arguments: serde_json::json!({ "file_path": "x" }),
+                            is_error: false,
+                            repaired: false,
+                            argument_error: None,
+                        },
+                    ],
+                },
+            ),
+            core_message(
+                MessageRole::Tool,
+                MessageContent::ToolResult {
+                    tool_id: "t1".to_string(),
+                    tool_name: "LS".to_string(),
+                    result: serde_json::json!({ "entries": 2 }),
+                    result_for_assistant: Some("a.rs\nb.rs".to_string()),
+                    is_error: false,
+                    image_attachments: None,
+                },
+            ),
+            core_message(
+                MessageRole::Assistant,
+                MessageContent::Text("Two files.".to_string()),
+            ),
+        ];
+
+        let history = history_from_core_messages(&messages);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content, "list files");
+
+        let items = &history[1].flow_items;
+        assert_eq!(items.len(), 4);
+        let FlowItem::Tool { tool_call } = &items[1] else {
+            panic!("expected tool card");
+        };
+        assert_eq!(tool_call.status, ToolCallStatus::Success);
+        assert_eq!(tool_call.result.as_deref(), Some("a.rs\nb.rs"));
+        let FlowItem::Tool { tool_call } = &items[2] else {
+            panic!("expected tool card");
+        };
+        assert_eq!(tool_call.status, ToolCallStatus::Cancelled);
+        assert!(matches!(&items[3], FlowItem::Text { content, .. } if content == "Two files."));
+    }
 }
@@ -3,8 +3,13 @@
 /// Wraps interaction with bitfun-core's Agent system
 pub mod agentic_system;
 pub mod core_adapter;
+pub mod tool_policy;
 
 use anyhow::Result;
+use bitfun_core::agentic::core::ProcessingPhase;
+use bitfun_core::agentic::tools::implementations::review_findings_tool::ReviewReport;
+use bitfun_core::service::session::TurnSummary;
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 
 use crate::session::ToolCall;
@@ -29,6 +34,35 @@ pub enum AgentEvent {
         result: String,
         success: bool,
     },
+    /// Tool is waiting for the user to allow or deny it
+    PermissionRequest {
+        tool_id: String,
+        tool_name: String,
+        parameters: serde_json::Value,
+        /// Seconds until the request times out; None waits indefinitely
+        timeout_secs: Option<u64>,
+    },
+    /// Core session created for the conversation (session ID)
+    SessionStarted(String),
+    /// Token usage of one model round
+    TokenUsage {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+    /// Context filled by the latest main-agent round
+    ContextUsage {
+        used_tokens: u64,
+        /// Model context window; None when the model doesn't report one
+        window_tokens: Option<u64>,
+    },
+    /// Turn moved to another processing phase
+    Phase(ProcessingPhase),
+    /// Spend changed after a priced model round (USD)
+    SpendUpdated { session_usd: f64, today_usd: f64 },
+    /// Findings submitted by the Review agent
+    ReviewFindings(ReviewReport),
+    /// AI summary of the turn; comes after `Done`, if at all
+    TurnSummary(TurnSummary),
     /// Done
     Done,
     /// Error
@@ -47,13 +81,20 @@ pub struct AgentResponse {
 /// Agent interface
 #[async_trait::async_trait]
 pub trait Agent: Send + Sync {
-    /// Process user message
+    /// Process user message; `mentions` are workspace-relative files referenced with `@`
     async fn process_message(
         &self,
         message: String,
+        mentions: Vec<String>,
         event_tx: mpsc::UnboundedSender<AgentEvent>,
     ) -> Result<AgentResponse>;
 
     /// Get Agent name
     fn name(&self) -> &str;
+
+    /// Forget the current conversation so the next message starts a new one
+    fn reset_session(&self);
+
+    /// Work in `workspace_path` from now on; the next message starts a new conversation
+    fn set_workspace(&self, workspace_path: Option<PathBuf>);
 }
@@ -0,0 +1,67 @@
+//! Tool permission policy for non-interactive runs
+//!
+//! Read-only tools never ask for permission and always run. Tools that do
+//! ask (edits, writes, shell commands) are allowed only when `--allow`
+//! names them, and `--deny` overrides `--allow`.
+
+use std::collections::HashSet;
+
+/// Wildcard matching every tool in `--allow` / `--deny`
+const ALL_TOOLS: &str = "all";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolPolicy {
+    allow: HashSet<String>,
+    deny: HashSet<String>,
+}
+
+impl ToolPolicy {
+    /// Build from `--allow` / `--deny` tool names (case-insensitive, "all" matches any tool)
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        let normalize = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect()
+        };
+        Self {
+            allow: normalize(allow),
+            deny: normalize(deny),
+        }
+    }
+
+    /// Whether a tool that asks for permission may run
+    pub fn allows(&self, tool_name: &str) -> bool {
+        let matches = |names: &HashSet<String>| {
+            names.contains(ALL_TOOLS) || names.contains(&tool_name.to_lowercase())
+        };
+        matches(&self.allow) && !matches(&self.deny)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn denies_mutating_tools_unless_allowed() {
+        assert!(!ToolPolicy::default().allows("Edit"));
+
+        let policy = ToolPolicy::new(&names(&["edit", "Write"]), &[]);
+        assert!(policy.allows("Edit"));
+        assert!(policy.allows("Write"));
+        assert!(!policy.allows("Bash"));
+    }
+
+    #[test]
+    fn deny_overrides_allow_all() {
+        let policy = ToolPolicy::new(&names(&["all"]), &names(&["Bash"]));
+        assert!(policy.allows("Edit"));
+        assert!(!policy.allows("Bash"));
+    }
+}
@@ -4,6 +4,7 @@
 /// Only CLI-specific configuration is kept here (UI, shortcuts, etc.)
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -19,6 +20,12 @@ pub struct CliConfig {
     pub workspace: WorkspaceConfig,
     /// Shortcuts configuration
     pub shortcuts: ShortcutsConfig,
+    /// Tool permission rules
+    #[serde(default)]
+    pub permissions: PermissionsConfig,
+    /// Alerts while the terminal is in the background
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +38,12 @@ pub struct UiConfig {
     pub animation: bool,
     /// Color scheme
     pub color_scheme: String,
+    /// Whether tool cards start expanded, by tool name (collapsed when absent)
+    #[serde(default)]
+    pub tool_card_expanded: BTreeMap<String, bool>,
+    /// Capture the mouse in chat (wheel scrolling, clicks); F2 toggles it at runtime
+    #[serde(default)]
+    pub mouse_capture: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +74,48 @@ pub struct ShortcutsConfig {
     pub menu: String,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PermissionsConfig {
+    /// Tools that run without asking ("Allow always" in the permission prompt)
+    #[serde(default)]
+    pub always_allow: Vec<String>,
+}
+
+impl PermissionsConfig {
+    /// Whether `tool_name` has an allow rule (case-insensitive)
+    pub fn allows(&self, tool_name: &str) -> bool {
+        self.always_allow
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(tool_name))
+    }
+
+    /// Add an allow rule for `tool_name`; false if one already exists
+    pub fn allow_always(&mut self, tool_name: &str) -> bool {
+        if self.allows(tool_name) {
+            return false;
+        }
+        self.always_allow.push(tool_name.to_string());
+        true
+    }
+}
+
+/// Which events alert the user while the terminal is unfocused, and how
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Alert when a turn finishes
+    #[serde(default)]
+    pub turn_complete: bool,
+    /// Alert when a tool waits for permission
+    #[serde(default)]
+    pub permission_request: bool,
+    /// Show a desktop notification
+    #[serde(default)]
+    pub desktop: bool,
+    /// Ring the terminal bell
+    #[serde(default)]
+    pub bell: bool,
+}
+
 impl Default for CliConfig {
     fn default() -> Self {
         Self {
@@ -69,6 +124,8 @@ impl Default for CliConfig {
                 show_tips: true,
                 animation: true,
                 color_scheme: "default".to_string(),
+                tool_card_expanded: BTreeMap::new(),
+                mouse_capture: false,
             },
             behavior: BehaviorConfig {
                 auto_save: true,
@@ -89,6 +146,8 @@ impl Default for CliConfig {
                 interrupt: "Ctrl+C".to_string(),
                 menu: "Esc".to_string(),
             },
+            permissions: PermissionsConfig::default(),
+            notifications: NotificationsConfig::default(),
         }
     }
 }
@@ -164,4 +223,18 @@ impl CliConfig {
         fs::create_dir_all(&sessions_dir)?;
         Ok(sessions_dir)
     }
+
+    /// Get the input history file for a workspace (one file per workspace path)
+    pub fn input_history_file(workspace: Option<&str>) -> Result<PathBuf> {
+        // FNV-1a keeps file names stable across toolchain versions
+        let hash = workspace
+            .unwrap_or_default()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        Ok(Self::config_dir()?
+            .join("history")
+            .join(format!("{:016x}.json", hash)))
+    }
 }
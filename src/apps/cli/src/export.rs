@@ -0,0 +1,641 @@
+/// Transcript export (`/export markdown|html <path>`)
+///
+/// Both formats render the same `Transcript`, built from the session record
+/// rather than from what the TUI currently shows. Local images referenced by
+/// messages are copied into a `<name>_files` directory next to the export so
+/// the links keep working when the file is moved together with it.
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::session::{FlowItem, Session, ToolCall};
+use crate::ui::theme::{luminance, StyleKind, Theme};
+use crate::ui::tool_cards;
+use crate::ui::widgets::{DiffLineKind, DiffView};
+
+/// Tool output lines kept in an export
+const MAX_OUTPUT_LINES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    /// Format implied by the extension of `path`; Markdown by default
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+            .unwrap_or(Self::Markdown)
+    }
+}
+
+/// Session usage shown in the footer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: Option<f64>,
+}
+
+/// Content of one message, in flow order
+enum Block<'s> {
+    Text(String),
+    Tool {
+        call: &'s ToolCall,
+        /// Key parameter (path, command, pattern …)
+        detail: String,
+        diff: Option<DiffView>,
+    },
+}
+
+struct Entry<'s> {
+    heading: &'static str,
+    timestamp: DateTime<Utc>,
+    blocks: Vec<Block<'s>>,
+}
+
+/// Export data model of a session
+pub struct Transcript<'s> {
+    session: &'s Session,
+    entries: Vec<Entry<'s>>,
+    usage: Usage,
+    exported_at: DateTime<Utc>,
+}
+
+impl<'s> Transcript<'s> {
+    pub fn new(session: &'s Session, usage: Usage) -> Self {
+        let entries = session
+            .messages
+            .iter()
+            .map(|message| {
+                let heading = match message.role.as_str() {
+                    "user" => "You",
+                    "assistant" => "Assistant",
+                    "command" => "Command",
+                    _ => "System",
+                };
+                let blocks = if message.flow_items.is_empty() {
+                    vec![Block::Text(message.content.clone())]
+                } else {
+                    message
+                        .flow_items
+                        .iter()
+                        .map(|item| match item {
+                            FlowItem::Text { content, .. } => Block::Text(content.clone()),
+                            FlowItem::Tool { tool_call } => Block::Tool {
+                                call: tool_call,
+                                detail: tool_cards::extract_key_params(&tool_call.parameters),
+                                diff: tool_cards::file_diff(tool_call)
+                                    .filter(|_| tool_cards::is_diff_tool(&tool_call.tool_name)),
+                            },
+                        })
+                        .collect()
+                };
+                Entry {
+                    heading,
+                    timestamp: message.timestamp,
+                    blocks,
+                }
+            })
+            .collect();
+
+        Self {
+            session,
+            entries,
+            usage,
+            exported_at: Utc::now(),
+        }
+    }
+
+    /// Copy local images into `assets` and point the text at the copies
+    fn localize_images(&mut self, assets: &mut Assets) {
+        let workspace = self.session.workspace.as_deref().map(Path::new);
+        for entry in &mut self.entries {
+            for block in &mut entry.blocks {
+                if let Block::Text(text) = block {
+                    *text = assets.rewrite(text, workspace);
+                }
+            }
+        }
+    }
+
+    fn header_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        if let Some(workspace) = &self.session.workspace {
+            fields.push(("Workspace", workspace.clone()));
+        }
+        fields.push(("Agent", self.session.agent.clone()));
+        fields.push((
+            "Created",
+            self.session
+                .created_at
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        ));
+        fields.push((
+            "Exported",
+            self.exported_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        ));
+        fields
+    }
+
+    fn footer(&self) -> String {
+        let metadata = &self.session.metadata;
+        let mut footer = format!(
+            "{} messages · {} tool calls · {} input / {} output tokens",
+            self.session.messages.len(),
+            metadata.tool_calls,
+            self.usage.input_tokens,
+            self.usage.output_tokens
+        );
+        if let Some(usd) = self.usage.cost_usd {
+            footer.push_str(&format!(" · ${:.4}", usd));
+        }
+        footer
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.session.title);
+        for (label, value) in self.header_fields() {
+            out.push_str(&format!("- {}: {}\n", label, value));
+        }
+
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "\n## {} ({})\n\n",
+                entry.heading,
+                entry.timestamp.format("%H:%M:%S")
+            ));
+            for block in &entry.blocks {
+                match block {
+                    Block::Text(text) => {
+                        if !text.trim().is_empty() {
+                            out.push_str(text.trim_end());
+                            out.push_str("\n\n");
+                        }
+                    }
+                    Block::Tool { call, detail, diff } => {
+                        out.push_str(&format!(
+                            "<details>\n<summary>{}</summary>\n\n",
+                            escape_html(&tool_summary(call, detail))
+                        ));
+                        let (lang, body) = match diff {
+                            Some(diff) => ("diff", diff_text(diff)),
+                            None => ("", tool_output(call)),
+                        };
+                        if !body.is_empty() {
+                            let fence = fence_for(&body);
+                            out.push_str(&format!("{}{}\n{}\n{}\n\n", fence, lang, body, fence));
+                        }
+                        out.push_str("</details>\n\n");
+                    }
+                }
+            }
+        }
+
+        out.push_str(&format!("---\n\n_{}_\n", self.footer()));
+        out
+    }
+
+    pub fn to_html(&self, theme: &Theme) -> String {
+        let title = escape_html(&self.session.title);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<header>\n<h1>{}</h1>\n<dl>\n",
+            title,
+            stylesheet(theme),
+            title
+        );
+        for (label, value) in self.header_fields() {
+            out.push_str(&format!(
+                "<dt>{}</dt><dd>{}</dd>\n",
+                label,
+                escape_html(&value)
+            ));
+        }
+        out.push_str("</dl>\n</header>\n");
+
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "<section class=\"message {}\">\n<h2>{} <time datetime=\"{}\">{}</time></h2>\n",
+                entry.heading.to_ascii_lowercase(),
+                entry.heading,
+                entry.timestamp.to_rfc3339(),
+                entry.timestamp.format("%H:%M:%S")
+            ));
+            for block in &entry.blocks {
+                match block {
+                    Block::Text(text) => out.push_str(&markdown_to_html(text)),
+                    Block::Tool { call, detail, diff } => {
+                        out.push_str(&format!(
+                            "<details class=\"tool\">\n<summary>{}</summary>\n",
+                            escape_html(&tool_summary(call, detail))
+                        ));
+                        match diff {
+                            Some(diff) => out.push_str(&diff_html(diff)),
+                            None => {
+                                let output = tool_output(call);
+                                if !output.is_empty() {
+                                    out.push_str(&format!("<pre>{}</pre>\n", escape_html(&output)));
+                                }
+                            }
+                        }
+                        out.push_str("</details>\n");
+                    }
+                }
+            }
+            out.push_str("</section>\n");
+        }
+
+        out.push_str(&format!(
+            "<footer>{}</footer>\n</body>\n</html>\n",
+            escape_html(&self.footer())
+        ));
+        out
+    }
+}
+
+/// Write `session` to `path` as `format`; returns the number of images copied
+pub fn export_session(
+    session: &Session,
+    usage: Usage,
+    format: ExportFormat,
+    path: &Path,
+    theme: &Theme,
+) -> Result<usize> {
+    let mut transcript = Transcript::new(session, usage);
+    let mut assets = Assets::next_to(path);
+    transcript.localize_images(&mut assets);
+
+    let content = match format {
+        ExportFormat::Markdown => transcript.to_markdown(),
+        ExportFormat::Html => transcript.to_html(theme),
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(assets.copied.len())
+}
+
+/// Directory receiving copies of the images an export links to
+struct Assets {
+    /// `<name>_files`, relative to the export
+    link_prefix: String,
+    dir: PathBuf,
+    /// Source image -> link written into the export
+    copied: HashMap<PathBuf, String>,
+}
+
+impl Assets {
+    fn next_to(export: &Path) -> Self {
+        let stem = export
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "transcript".to_string());
+        let link_prefix = format!("{}_files", stem);
+        Self {
+            dir: export.with_file_name(&link_prefix),
+            link_prefix,
+            copied: HashMap::new(),
+        }
+    }
+
+    /// `text` with links to local images replaced by links to their copies
+    fn rewrite(&mut self, text: &str, workspace: Option<&Path>) -> String {
+        let mut text = text.to_string();
+        for url in image_urls(&text) {
+            let Some(source) = local_path(&url, workspace) else {
+                continue;
+            };
+            match self.copy(&source) {
+                Ok(link) => text = text.replace(&format!("]({}", url), &format!("]({}", link)),
+                Err(e) => tracing::warn!("Failed to copy image {}: {}", source.display(), e),
+            }
+        }
+        text
+    }
+
+    fn copy(&mut self, source: &Path) -> Result<String> {
+        if let Some(link) = self.copied.get(source) {
+            return Ok(link.clone());
+        }
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "image".to_string());
+        // Numbered so images with the same file name don't overwrite each other
+        let file_name = format!("{}-{}", self.copied.len() + 1, name);
+        fs::create_dir_all(&self.dir)?;
+        fs::copy(source, self.dir.join(&file_name))?;
+
+        let link = format!("{}/{}", self.link_prefix, file_name);
+        self.copied.insert(source.to_path_buf(), link.clone());
+        Ok(link)
+    }
+}
+
+/// Destinations of the Markdown images in `text`
+fn image_urls(text: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    for event in Parser::new_ext(text, Options::all()) {
+        if let Event::Start(Tag::Image { dest_url, .. }) = event {
+            if !urls.iter().any(|url| url == dest_url.as_ref()) {
+                urls.push(dest_url.into_string());
+            }
+        }
+    }
+    urls
+}
+
+/// Existing local file an image URL points at (relative to the workspace)
+fn local_path(url: &str, workspace: Option<&Path>) -> Option<PathBuf> {
+    let path = match url.strip_prefix("file://") {
+        Some(path) => PathBuf::from(path),
+        None if url.contains("://") || url.starts_with("data:") => return None,
+        None => PathBuf::from(url),
+    };
+    let path = match workspace {
+        Some(workspace) if path.is_relative() => workspace.join(path),
+        _ => path,
+    };
+    path.is_file().then_some(path)
+}
+
+/// "Bash `cargo test` — Success, 1.2s"
+fn tool_summary(call: &ToolCall, detail: &str) -> String {
+    let mut summary = call.tool_name.clone();
+    if !detail.is_empty() {
+        summary.push_str(&format!(" `{}`", detail));
+    }
+    summary.push_str(&format!(" — {:?}", call.status));
+    if let Some(ms) = call.duration_ms {
+        summary.push_str(&format!(", {:.1}s", ms as f64 / 1000.0));
+    }
+    summary
+}
+
+/// Tool result, cut to `MAX_OUTPUT_LINES`
+fn tool_output(call: &ToolCall) -> String {
+    let output = tool_cards::card_output(call, "").trim_end();
+    let total = output.lines().count();
+    if total <= MAX_OUTPUT_LINES {
+        return output.to_string();
+    }
+    let more = format!("… {} more lines", total - MAX_OUTPUT_LINES);
+    let mut kept: Vec<&str> = output.lines().take(MAX_OUTPUT_LINES).collect();
+    kept.push(&more);
+    kept.join("\n")
+}
+
+fn diff_prefix(kind: DiffLineKind) -> char {
+    match kind {
+        DiffLineKind::Added => '+',
+        DiffLineKind::Removed => '-',
+        DiffLineKind::Context => ' ',
+    }
+}
+
+/// Hunks as unified-diff body lines, separated by "@@"
+fn diff_text(diff: &DiffView) -> String {
+    let mut lines = Vec::new();
+    for (index, hunk) in diff.hunks.iter().enumerate() {
+        if index > 0 {
+            lines.push("@@".to_string());
+        }
+        for line in hunk {
+            lines.push(format!("{}{}", diff_prefix(line.kind), line.text));
+        }
+    }
+    lines.join("\n")
+}
+
+fn diff_html(diff: &DiffView) -> String {
+    let mut out = String::from("<pre class=\"diff\">");
+    for (index, hunk) in diff.hunks.iter().enumerate() {
+        if index > 0 {
+            out.push_str("<span class=\"hunk\">@@</span>\n");
+        }
+        for line in hunk {
+            let class = match line.kind {
+                DiffLineKind::Added => "add",
+                DiffLineKind::Removed => "del",
+                DiffLineKind::Context => "ctx",
+            };
+            out.push_str(&format!(
+                "<span class=\"{}\">{}{}</span>\n",
+                class,
+                diff_prefix(line.kind),
+                escape_html(&line.text)
+            ));
+        }
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+/// Code fence longer than any backtick run inside `body`
+fn fence_for(body: &str) -> String {
+    let longest = body
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat((longest + 1).max(3))
+}
+
+/// Message Markdown as HTML; raw HTML in the message is shown as text
+fn markdown_to_html(text: &str) -> String {
+    let events = Parser::new_ext(text, Options::all()).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        event => event,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// CSS color of a terminal color; None for `Color::Reset`
+fn css_color(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black | Color::Indexed(0) => (0, 0, 0),
+        Color::Red | Color::Indexed(1) => (205, 49, 49),
+        Color::Green | Color::Indexed(2) => (13, 188, 121),
+        Color::Yellow | Color::Indexed(3) => (229, 229, 16),
+        Color::Blue | Color::Indexed(4) => (36, 114, 200),
+        Color::Magenta | Color::Indexed(5) => (188, 63, 188),
+        Color::Cyan | Color::Indexed(6) => (17, 168, 205),
+        Color::Gray | Color::Indexed(7) => (229, 229, 229),
+        Color::DarkGray | Color::Indexed(8) => (102, 102, 102),
+        Color::LightRed | Color::Indexed(9) => (241, 76, 76),
+        Color::LightGreen | Color::Indexed(10) => (35, 209, 139),
+        Color::LightYellow | Color::Indexed(11) => (245, 245, 67),
+        Color::LightBlue | Color::Indexed(12) => (59, 142, 234),
+        Color::LightMagenta | Color::Indexed(13) => (214, 112, 214),
+        Color::LightCyan | Color::Indexed(14) => (41, 184, 219),
+        Color::White | Color::Indexed(15) => (255, 255, 255),
+        // Upper palette entries depend on the terminal; leave them to the default
+        Color::Indexed(_) => return None,
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// Minimal stylesheet in the colors of `theme`
+fn stylesheet(theme: &Theme) -> String {
+    let color = |kind: StyleKind, fallback: &str| {
+        css_color(theme.color(kind)).unwrap_or_else(|| fallback.to_string())
+    };
+    let background = css_color(theme.background).unwrap_or_else(|| "#111827".to_string());
+    let is_dark = match theme.background {
+        Color::Rgb(r, g, b) => luminance(r, g, b) < 128.0,
+        _ => true,
+    };
+    let text = color(StyleKind::Text, if is_dark { "#e5e7eb" } else { "#111827" });
+    let muted = color(StyleKind::Muted, "#9ca3af");
+    let border = color(StyleKind::Border, "#374151");
+
+    format!(
+        "body {{ background: {background}; color: {text}; font: 15px/1.5 system-ui, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; }}
+h1 {{ color: {title}; }}
+header dl {{ display: grid; grid-template-columns: max-content 1fr; gap: 0 1rem; color: {muted}; }}
+header dd {{ margin: 0; }}
+section {{ border-top: 1px solid {border}; padding: 0.5rem 0; }}
+h2 {{ font-size: 1rem; }}
+h2 time {{ color: {muted}; font-weight: normal; font-size: 0.85em; }}
+.user h2 {{ color: {user}; }}
+.assistant h2 {{ color: {assistant}; }}
+.command h2, .system h2 {{ color: {muted}; }}
+a {{ color: {primary}; }}
+img {{ max-width: 100%; }}
+pre, code {{ font-family: ui-monospace, monospace; font-size: 0.9em; }}
+pre {{ border: 1px solid {border}; border-radius: 4px; padding: 0.5rem; overflow-x: auto; }}
+details.tool {{ border-left: 3px solid {tool}; margin: 0.5rem 0; padding-left: 0.75rem; }}
+details.tool summary {{ cursor: pointer; color: {muted}; }}
+.diff .add {{ color: {add}; }}
+.diff .del {{ color: {del}; }}
+.diff .hunk {{ color: {muted}; }}
+footer {{ border-top: 1px solid {border}; color: {muted}; padding-top: 0.5rem; font-size: 0.9em; }}
+",
+        title = color(StyleKind::Title, "#3b82f6"),
+        user = color(StyleKind::User, "#22c55e"),
+        assistant = color(StyleKind::Assistant, "#3b82f6"),
+        primary = color(StyleKind::Primary, "#3b82f6"),
+        tool = color(StyleKind::ToolBorder, "#9ca3af"),
+        add = color(StyleKind::DiffAdd, "#22c55e"),
+        del = color(StyleKind::DiffRemove, "#ef4444"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::ToolCallStatus;
+
+    fn session() -> Session {
+        let mut session = Session::new("agentic".to_string(), Some("/work".to_string()));
+        session.title = "Fix <tests>".to_string();
+        session.add_message("user".to_string(), "run the tests".to_string());
+        session.add_message("assistant".to_string(), String::new());
+        session.update_last_message_text_flow("Running them.".to_string(), false);
+        session.add_tool_to_last_message(ToolCall {
+            tool_id: Some("t1".to_string()),
+            tool_name: "Bash".to_string(),
+            parameters: serde_json::json!({ "command": "cargo test" }),
+            result: Some("test result: ok".to_string()),
+            status: ToolCallStatus::Success,
+            progress: Some(1.0),
+            progress_message: None,
+            duration_ms: Some(1200),
+        });
+        session
+    }
+
+    #[test]
+    fn markdown_collapses_tool_details() {
+        let session = session();
+        let usage = Usage {
+            input_tokens: 10,
+            output_tokens: 5,
+            cost_usd: Some(0.25),
+        };
+        let markdown = Transcript::new(&session, usage).to_markdown();
+
+        assert!(markdown.starts_with("# Fix <tests>\n\n- Workspace: /work\n"));
+        assert!(markdown.contains(
+            "<details>\n<summary>Bash `cargo test` — Success, 1.2s</summary>\n\n```\ntest result: ok\n```"
+        ));
+        assert!(markdown
+            .ends_with("_2 messages · 1 tool calls · 10 input / 5 output tokens · $0.2500_\n"));
+    }
+
+    #[test]
+    fn html_escapes_and_uses_theme_colors() {
+        let session = session();
+        let html = Transcript::new(&session, Usage::default()).to_html(&Theme::dark());
+
+        assert!(html.contains("<title>Fix &lt;tests&gt;</title>"));
+        assert!(html.contains("background: #111827"));
+        assert!(html.contains("<pre>test result: ok</pre>"));
+    }
+
+    #[test]
+    fn fences_outrun_backticks_in_the_body() {
+        assert_eq!(fence_for("plain"), "```");
+        assert_eq!(fence_for("````rust"), "`````");
+    }
+
+    #[test]
+    fn copies_local_images_next_to_the_export() {
+        let dir = std::env::temp_dir().join(format!("bitfun-export-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("shot.png"), b"png").unwrap();
+
+        let mut assets = Assets::next_to(&dir.join("out/chat.md"));
+        let text = assets.rewrite(
+            "See ![shot](shot.png) and ![web](https://example.com/a.png)",
+            Some(&dir),
+        );
+        assert_eq!(
+            text,
+            "See ![shot](chat_files/1-shot.png) and ![web](https://example.com/a.png)"
+        );
+        assert!(dir.join("out/chat_files/1-shot.png").is_file());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
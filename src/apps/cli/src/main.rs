@@ -6,9 +6,13 @@ mod agent;
 /// - Single command execution
 /// - Batch task processing
 mod config;
+mod export;
+mod mcp;
 mod modes;
+mod prompts;
 mod session;
 mod ui;
+mod workspace;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
@@ -16,6 +20,8 @@ use clap::{Parser, Subcommand};
 use config::CliConfig;
 use modes::chat::ChatMode;
 use modes::exec::ExecMode;
+use session::Session;
+use ui::startup::StartupResult;
 
 #[derive(Parser)]
 #[command(name = "bitfun")]
@@ -28,6 +34,53 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Resume a session by ID (or unique ID prefix), or "last" for the most recent
+    #[arg(long, value_name = "ID|last")]
+    resume: Option<String>,
+
+    /// Workspace to open, skipping the startup picker (print mode default: current directory)
+    #[arg(short, long, value_name = "PATH")]
+    workspace: Option<String>,
+
+    /// Switch to this config profile before starting ("base" for no profile)
+    #[arg(long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+
+    #[command(flatten)]
+    print: PrintArgs,
+}
+
+/// Non-interactive print mode
+#[derive(clap::Args)]
+struct PrintArgs {
+    /// Run one prompt without the TUI and print the answer (prompt read from stdin when omitted)
+    #[arg(short = 'p', long = "print", value_name = "PROMPT", num_args = 0..=1, default_missing_value = "")]
+    prompt: Option<String>,
+
+    /// Print mode output format
+    #[arg(long, value_enum, default_value = "text", requires = "prompt")]
+    output_format: modes::print::OutputFormat,
+
+    /// Tools that need permission which may run, comma-separated, or "all" (read-only tools always run)
+    #[arg(long, value_delimiter = ',', value_name = "TOOLS", requires = "prompt")]
+    allow: Vec<String>,
+
+    /// Tools that may not run even when --allow matches them, comma-separated
+    #[arg(long, value_delimiter = ',', value_name = "TOOLS", requires = "prompt")]
+    deny: Vec<String>,
+
+    /// Give up after this many seconds (exit code 124)
+    #[arg(long, value_name = "SECS", requires = "prompt")]
+    timeout: Option<u64>,
+
+    /// Stop after this many model rounds
+    #[arg(long, value_name = "N", requires = "prompt")]
+    max_turns: Option<usize>,
+
+    /// Agent type for print mode
+    #[arg(long = "agent", default_value = "agentic", requires = "prompt")]
+    print_agent: String,
 }
 
 #[derive(Subcommand)]
@@ -71,6 +124,20 @@ enum Commands {
         confirm: bool,
     },
 
+    /// Review changes with the Review agent and list its findings by file
+    Review {
+        /// Git ref range to review (e.g. main..HEAD); the working tree changes when omitted
+        range: Option<String>,
+
+        /// Workspace path
+        #[arg(short, long)]
+        workspace: Option<String>,
+
+        /// Apply the suggested fixes after the review
+        #[arg(long)]
+        apply_fixes: bool,
+    },
+
     /// Execute batch tasks
     Batch {
         /// Task configuration file path
@@ -90,6 +157,18 @@ enum Commands {
         action: ConfigAction,
     },
 
+    /// MCP server management
+    Mcp {
+        #[command(subcommand)]
+        action: mcp::McpAction,
+    },
+
+    /// Agent prompt templates and their overrides
+    Prompts {
+        #[command(subcommand)]
+        action: prompts::PromptsAction,
+    },
+
     /// Invoke tool directly
     Tool {
         /// Tool name
@@ -100,10 +179,147 @@ enum Commands {
         params: Option<String>,
     },
 
+    /// Run the storage cleanup policies now
+    Cleanup {
+        /// Only list what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Manage persisted data
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+
+    /// Back up and restore config, skills, sessions and memory
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
+
+    /// Generate a Keep-a-Changelog fragment from the commit history
+    Changelog {
+        /// Start of the range (tag or commit; default: the last tag)
+        #[arg(long, value_name = "REV")]
+        since: Option<String>,
+
+        /// End of the range
+        #[arg(long, value_name = "REV")]
+        until: Option<String>,
+
+        /// Version heading (default: Unreleased)
+        #[arg(long, value_name = "VERSION")]
+        release: Option<String>,
+
+        /// Insert the fragment into CHANGELOG.md instead of printing it
+        #[arg(long)]
+        write: bool,
+
+        /// Leave out PR/issue references
+        #[arg(long)]
+        no_refs: bool,
+
+        /// List commit subjects as written instead of having the AI rewrite them
+        #[arg(long)]
+        raw: bool,
+    },
+
+    /// Debugging tools
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
+    },
+
+    /// Backend log settings
+    Log {
+        #[command(subcommand)]
+        action: LogAction,
+    },
+
     /// Health check
     Health,
 }
 
+#[derive(Subcommand)]
+enum StorageAction {
+    /// Import the JSON data files into SQLite databases, for `app.storage.backend = "sqlite"`
+    Migrate,
+}
+
+#[derive(Subcommand)]
+enum BackupAction {
+    /// Create a backup archive in the backups directory
+    Create {
+        /// Data to include (repeatable); defaults to app.storage.backup.categories
+        #[arg(long = "category", value_enum)]
+        categories: Vec<BackupCategoryArg>,
+    },
+    /// Restore a backup archive
+    Restore {
+        /// Backup archive (.tar.zst)
+        path: std::path::PathBuf,
+        /// Data to restore (repeatable); defaults to everything in the backup
+        #[arg(long = "category", value_enum)]
+        categories: Vec<BackupCategoryArg>,
+        /// Restore even over data written by a newer BitFun version
+        #[arg(long)]
+        force: bool,
+        /// Do not ask for confirmation before restoring
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// List backups, newest first
+    List,
+}
+
+/// Kind of data in a backup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum BackupCategoryArg {
+    Config,
+    Skills,
+    Sessions,
+    Memory,
+}
+
+impl From<BackupCategoryArg> for bitfun_core::infrastructure::storage::BackupCategory {
+    fn from(category: BackupCategoryArg) -> Self {
+        match category {
+            BackupCategoryArg::Config => Self::Config,
+            BackupCategoryArg::Skills => Self::Skills,
+            BackupCategoryArg::Sessions => Self::Sessions,
+            BackupCategoryArg::Memory => Self::Memory,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum DebugAction {
+    /// Replay a recorded backend event log (events.jsonl)
+    Replay {
+        /// Event log file
+        file: String,
+
+        /// Keep the original pauses between events
+        #[arg(long)]
+        realtime: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LogAction {
+    /// Override the log level of one target (module path prefix), e.g.
+    /// `bitfun log set-level bitfun_core::service::mcp debug`
+    SetLevel {
+        /// Log target prefix
+        target: String,
+        /// trace, debug, info, warn, error or off; "default" removes the override
+        level: String,
+    },
+    /// List the log level overrides
+    Levels,
+}
+
 #[derive(Subcommand)]
 enum SessionAction {
     /// List all sessions
@@ -128,6 +344,274 @@ enum ConfigAction {
     Edit,
     /// Reset to default configuration
     Reset,
+    /// Check the global and CLI config files for invalid values
+    Validate,
+    /// List API keys and tokens stored in plaintext, or move them into the OS keyring
+    Secrets {
+        /// Move the plaintext secrets into the secrets store
+        #[arg(long)]
+        migrate: bool,
+        /// Do not ask for confirmation before migrating
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Export settings (models, MCP servers, permissions, themes, profiles) to a bundle file
+    Export {
+        /// Bundle file to write
+        path: std::path::PathBuf,
+        /// Include API keys and tokens, encrypted with a passphrase
+        /// (read from BITFUN_SETTINGS_PASSPHRASE or prompted)
+        #[arg(long)]
+        include_secrets: bool,
+    },
+    /// Import settings from a bundle file
+    Import {
+        /// Bundle file to read
+        path: std::path::PathBuf,
+        /// How the bundle combines with the current settings
+        #[arg(long, value_enum, default_value = "merge")]
+        strategy: ImportStrategy,
+        /// Only show what would change
+        #[arg(long)]
+        dry_run: bool,
+        /// Do not ask for confirmation before applying
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// How imported settings combine with the current ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ImportStrategy {
+    /// Bundle values win; settings missing from the bundle are kept
+    Merge,
+    /// The bundle replaces the settings and MCP servers
+    Replace,
+}
+
+impl From<ImportStrategy> for bitfun_core::service::config::MergeStrategy {
+    fn from(strategy: ImportStrategy) -> Self {
+        match strategy {
+            ImportStrategy::Merge => Self::Merge,
+            ImportStrategy::Replace => Self::Replace,
+        }
+    }
+}
+
+/// Environment variable holding the settings bundle passphrase
+const SETTINGS_PASSPHRASE_ENV: &str = "BITFUN_SETTINGS_PASSPHRASE";
+
+/// Session named by `--resume`; sessions from another workspace are confirmed on stdin.
+/// `None` means the user declined.
+fn load_resume_session(reference: &str) -> Result<Option<Session>> {
+    use std::io::Write;
+
+    let session = Session::resolve(reference)?;
+    let current_dir = std::env::current_dir().ok();
+    if !session::is_other_workspace(session.workspace.as_deref(), current_dir.as_deref()) {
+        return Ok(Some(session));
+    }
+
+    print!(
+        "Session \"{}\" belongs to workspace {}. Open it? [y/N] ",
+        session.title,
+        session.workspace.as_deref().unwrap_or_default()
+    );
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes").then_some(session))
+}
+
+/// Workspace and resumed session picked on the startup page; `None` when the user exits
+fn startup_selection(result: StartupResult) -> Result<Option<(Option<String>, Option<Session>)>> {
+    match result {
+        StartupResult::NewSession(workspace) => Ok(Some((Some(workspace), None))),
+        StartupResult::ContinueSession(id) | StartupResult::LoadSession(id) => {
+            let session = Session::load(&id)?;
+            Ok(Some((session.workspace.clone(), Some(session))))
+        }
+        StartupResult::Exit => Ok(None),
+    }
+}
+
+/// Reconnect a resumed session to its core session and rebuild its history from there.
+/// Without a restorable core session the saved CLI history is shown and the next message
+/// starts a new core session.
+async fn restore_resumed_session(
+    agentic_system: &agent::agentic_system::AgenticSystem,
+    session: &mut Session,
+) {
+    if session.core_session_id.is_none() {
+        return;
+    }
+    if let Err(e) =
+        agent::core_adapter::restore_session_history(&agentic_system.coordinator, session).await
+    {
+        tracing::warn!(
+            "Failed to restore core session {:?}, continuing with saved history: {}",
+            session.core_session_id,
+            e
+        );
+        session.core_session_id = None;
+    }
+}
+
+/// Run `bitfun -p`: one prompt, no TUI; returns the process exit code
+async fn run_print_mode(
+    args: PrintArgs,
+    prompt: String,
+    workspace: Option<String>,
+    profile: Option<String>,
+) -> Result<i32> {
+    use modes::print::{PrintMode, PrintOptions};
+    use std::io::{IsTerminal, Read};
+
+    let prompt = if prompt.trim().is_empty() {
+        if std::io::stdin().is_terminal() {
+            anyhow::bail!("No prompt given: pass one to -p or pipe it on stdin");
+        }
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read prompt from stdin")?;
+        input
+    } else {
+        prompt
+    };
+    if prompt.trim().is_empty() {
+        anyhow::bail!("Prompt is empty");
+    }
+
+    let workspace_path =
+        resolve_workspace_path(workspace.as_deref()).or_else(|| std::env::current_dir().ok());
+
+    bitfun_core::service::config::initialize_global_config()
+        .await
+        .context("Failed to initialize global config service")?;
+    apply_profile(profile.as_deref()).await?;
+
+    bitfun_core::infrastructure::ai::AIClientFactory::initialize_global()
+        .await
+        .context("Failed to initialize global AIClientFactory")?;
+    let agentic_system = agent::agentic_system::init_agentic_system()
+        .await
+        .context("Failed to initialize agentic system")?;
+
+    // The tool policy makes each turn ask before tools that need permission, so the
+    // --allow/--deny lists can answer without touching the user's config
+    let options = PrintOptions {
+        output_format: args.output_format,
+        tool_policy: agent::tool_policy::ToolPolicy::new(&args.allow, &args.deny),
+        max_turns: args.max_turns,
+        timeout: args.timeout.map(std::time::Duration::from_secs),
+    };
+    PrintMode::new(
+        prompt,
+        args.print_agent,
+        &agentic_system,
+        workspace_path,
+        options,
+    )
+    .run()
+    .await
+}
+
+/// What `bitfun exec` (and `bitfun review`) runs
+struct ExecRequest {
+    message: String,
+    agent: String,
+    workspace: Option<String>,
+    output_patch: Option<String>,
+    confirm: bool,
+    apply_fixes: bool,
+}
+
+/// Run one message non-interactively and print the agent's events
+async fn run_exec(config: CliConfig, profile: Option<&str>, request: ExecRequest) -> Result<()> {
+    let ExecRequest {
+        message,
+        agent,
+        workspace,
+        output_patch,
+        confirm,
+        apply_fixes,
+    } = request;
+
+    let workspace_path_resolved =
+        resolve_workspace_path(workspace.as_deref()).or_else(|| std::env::current_dir().ok());
+    tracing::info!("CLI workspace: {:?}", workspace_path_resolved);
+
+    bitfun_core::service::config::initialize_global_config()
+        .await
+        .context("Failed to initialize global config service")?;
+    tracing::info!("Global config service initialized");
+    apply_profile(profile).await?;
+
+    let config_service = bitfun_core::service::config::get_global_config_service()
+        .await
+        .ok();
+    let original_skip_confirmation = if let Some(ref svc) = config_service {
+        let ai_config: bitfun_core::service::config::types::AIConfig =
+            svc.get_config(Some("ai")).await.unwrap_or_default();
+        ai_config.skip_tool_confirmation
+    } else {
+        false
+    };
+    if let Some(ref svc) = config_service {
+        let desired_skip = !confirm;
+        if let Err(e) = svc
+            .set_config("ai.skip_tool_confirmation", desired_skip)
+            .await
+        {
+            tracing::warn!("Failed to set tool confirmation toggle, continuing: {}", e);
+        }
+    }
+
+    use bitfun_core::infrastructure::ai::AIClientFactory;
+    AIClientFactory::initialize_global()
+        .await
+        .context("Failed to initialize global AIClientFactory")?;
+    tracing::info!("Global AI client factory initialized");
+
+    let agentic_system = agent::agentic_system::init_agentic_system()
+        .await
+        .context("Failed to initialize agentic system")?;
+    tracing::info!("Agentic system initialized");
+
+    let mut exec_mode = ExecMode::new(
+        config,
+        message,
+        agent,
+        &agentic_system,
+        workspace_path_resolved,
+        output_patch,
+    )
+    .with_apply_fixes(apply_fixes);
+    let run_result = exec_mode.run().await;
+
+    if let Some(ref svc) = config_service {
+        let _ = svc
+            .set_config("ai.skip_tool_confirmation", original_skip_confirmation)
+            .await;
+    }
+
+    run_result
+}
+
+/// Activate the config profile named by --profile
+async fn apply_profile(profile: Option<&str>) -> Result<()> {
+    use bitfun_core::service::config::{profiles::BASE_PROFILE, GlobalConfigManager};
+
+    let Some(profile) = profile else {
+        return Ok(());
+    };
+    let name = (profile != BASE_PROFILE).then_some(profile);
+    GlobalConfigManager::switch_profile(name)
+        .await
+        .with_context(|| format!("Failed to switch to config profile '{}'", profile))?;
+    tracing::info!("Config profile: {}", profile);
+    Ok(())
 }
 
 fn resolve_workspace_path(workspace: Option<&str>) -> Option<std::path::PathBuf> {
@@ -138,6 +622,53 @@ fn resolve_workspace_path(workspace: Option<&str>) -> Option<std::path::PathBuf>
     }
 }
 
+/// Fail early on a `--workspace` that is not a directory
+fn check_workspace_arg(workspace: Option<&str>) -> Result<()> {
+    match resolve_workspace_path(workspace) {
+        Some(path) if !path.is_dir() => anyhow::bail!("Not a directory: {}", path.display()),
+        _ => Ok(()),
+    }
+}
+
+/// Open `workspace` through the workspace service (recording it as recently
+/// opened); falls back to the plain path when the service refuses it
+async fn open_workspace(workspace: Option<&str>) -> Option<std::path::PathBuf> {
+    let path = resolve_workspace_path(workspace)?;
+    match workspace::open(&path).await {
+        Ok(root) => Some(root),
+        Err(e) => {
+            tracing::warn!("Continuing without registering the workspace: {:#}", e);
+            Some(path)
+        }
+    }
+}
+
+/// Writes crash reports for panics and mentions the one left by a previous run
+fn install_crash_reporter() {
+    use bitfun_core::infrastructure::crash_report::{
+        install_panic_hook, take_unnotified_reports, CrashReporterConfig,
+    };
+
+    let Ok(path_manager) = bitfun_core::infrastructure::try_get_path_manager_arc() else {
+        return;
+    };
+    let crashes_dir = path_manager.crashes_dir();
+    install_panic_hook(CrashReporterConfig {
+        crashes_dir: crashes_dir.clone(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        log_file: CliConfig::config_dir()
+            .ok()
+            .map(|dir| dir.join("logs").join("bitfun-cli.log")),
+    });
+
+    if let Some(latest) = take_unnotified_reports(&crashes_dir).first() {
+        eprintln!(
+            "BitFun crashed during a previous run; crash report: {}",
+            latest.path
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -148,7 +679,8 @@ async fn main() -> Result<()> {
         tracing::Level::INFO
     };
 
-    let is_tui_mode = matches!(cli.command, None | Some(Commands::Chat { .. }));
+    let is_print_mode = cli.print.prompt.is_some();
+    let is_tui_mode = !is_print_mode && matches!(cli.command, None | Some(Commands::Chat { .. }));
 
     if is_tui_mode {
         use std::fs::OpenOptions;
@@ -182,6 +714,32 @@ async fn main() -> Result<()> {
                 .with_target(false)
                 .init();
         }
+    } else if is_print_mode
+        || matches!(
+            cli.command,
+            Some(Commands::Mcp { .. })
+                | Some(Commands::Prompts { .. })
+                | Some(Commands::Cleanup { .. })
+                | Some(Commands::Storage { .. })
+                | Some(Commands::Backup { .. })
+                | Some(Commands::Changelog { .. })
+                | Some(Commands::Debug { .. })
+                | Some(Commands::Log { .. })
+                | Some(Commands::Config {
+                    action: ConfigAction::Validate
+                })
+        )
+    {
+        // stdout carries only the answer / command output
+        tracing_subscriber::fmt()
+            .with_max_level(if cli.verbose {
+                tracing::Level::DEBUG
+            } else {
+                tracing::Level::WARN
+            })
+            .with_writer(std::io::stderr)
+            .with_target(false)
+            .init();
     } else {
         tracing_subscriber::fmt()
             .with_max_level(log_level)
@@ -189,6 +747,16 @@ async fn main() -> Result<()> {
             .init();
     }
 
+    install_crash_reporter();
+
+    if let Some(prompt) = cli.print.prompt.clone() {
+        let exit_code = run_print_mode(cli.print, prompt, cli.workspace, cli.profile).await?;
+        if exit_code != 0 {
+            std::process::exit(exit_code);
+        }
+        return Ok(());
+    }
+
     let config = CliConfig::load().unwrap_or_else(|e| {
         if !is_tui_mode {
             eprintln!("Warning: Failed to load config: {}", e);
@@ -199,37 +767,52 @@ async fn main() -> Result<()> {
 
     match cli.command {
         Some(Commands::Chat { agent, workspace }) => {
-            let (workspace, mut startup_terminal) = if workspace.is_none() {
+            let workspace = workspace.or(cli.workspace);
+            check_workspace_arg(workspace.as_deref())?;
+            let resumed = match cli.resume.as_deref() {
+                Some(reference) => match load_resume_session(reference)? {
+                    Some(session) => Some(session),
+                    None => return Ok(()),
+                },
+                None => None,
+            };
+
+            let (workspace, mut resumed, mut startup_terminal) = if let Some(session) = resumed {
+                (session.workspace.clone(), Some(session), None)
+            } else if workspace.is_none() {
                 use ui::startup::StartupPage;
 
                 let mut terminal = ui::init_terminal()?;
                 let mut startup_page = StartupPage::new();
-                let selected_workspace = startup_page.run(&mut terminal)?;
+                let selection = startup_selection(startup_page.run(&mut terminal)?)?;
 
-                if selected_workspace.is_none() {
+                let Some((selected_workspace, resumed)) = selection else {
                     ui::restore_terminal(terminal)?;
                     println!("Goodbye!");
                     return Ok(());
-                }
+                };
 
-                (selected_workspace, Some(terminal))
+                (selected_workspace, resumed, Some(terminal))
             } else {
-                (workspace, None)
+                (workspace, None, None)
             };
 
+            // The startup page may have changed the theme
+            let theme = ui::theme::Theme::from_config(&CliConfig::load().unwrap_or_default());
             if let Some(ref mut term) = startup_terminal {
-                ui::render_loading(term, "Initializing system, please wait...")?;
+                ui::render_loading(term, &theme, "Initializing system, please wait...")?;
             } else {
                 println!("Initializing system, please wait...");
             }
 
-            let workspace_path = resolve_workspace_path(workspace.as_deref());
-            tracing::info!("CLI workspace: {:?}", workspace_path);
-
             bitfun_core::service::config::initialize_global_config()
                 .await
                 .context("Failed to initialize global config service")?;
             tracing::info!("Global config service initialized");
+            apply_profile(cli.profile.as_deref()).await?;
+
+            let workspace_path = open_workspace(workspace.as_deref()).await;
+            tracing::info!("CLI workspace: {:?}", workspace_path);
 
             let config_service = bitfun_core::service::config::get_global_config_service()
                 .await
@@ -261,14 +844,27 @@ async fn main() -> Result<()> {
                 .context("Failed to initialize agentic system")?;
             tracing::info!("Agentic system initialized");
 
+            if let Some(session) = resumed.as_mut() {
+                restore_resumed_session(&agentic_system, session).await;
+            }
+
             if let Some(ref mut term) = startup_terminal {
-                ui::render_loading(term, "System initialized, starting chat interface...")?;
+                ui::render_loading(
+                    term,
+                    &theme,
+                    "System initialized, starting chat interface...",
+                )?;
             } else {
                 println!("System initialized, starting chat interface...\n");
                 std::thread::sleep(std::time::Duration::from_millis(500));
             }
 
-            let mut chat_mode = ChatMode::new(config, agent, workspace_path, &agentic_system);
+            let agent = resumed
+                .as_ref()
+                .map(|session| session.agent.clone())
+                .unwrap_or(agent);
+            let mut chat_mode =
+                ChatMode::new(config, agent, workspace_path, &agentic_system, resumed);
             let chat_result = chat_mode.run(startup_terminal);
 
             if let Some(ref svc) = config_service {
@@ -288,63 +884,40 @@ async fn main() -> Result<()> {
             output_patch,
             confirm,
         }) => {
-            let workspace_path_resolved = resolve_workspace_path(workspace.as_deref())
-                .or_else(|| std::env::current_dir().ok());
-            tracing::info!("CLI workspace: {:?}", workspace_path_resolved);
-
-            bitfun_core::service::config::initialize_global_config()
-                .await
-                .context("Failed to initialize global config service")?;
-            tracing::info!("Global config service initialized");
-
-            let config_service = bitfun_core::service::config::get_global_config_service()
-                .await
-                .ok();
-            let original_skip_confirmation = if let Some(ref svc) = config_service {
-                let ai_config: bitfun_core::service::config::types::AIConfig =
-                    svc.get_config(Some("ai")).await.unwrap_or_default();
-                ai_config.skip_tool_confirmation
-            } else {
-                false
-            };
-            if let Some(ref svc) = config_service {
-                let desired_skip = !confirm;
-                if let Err(e) = svc
-                    .set_config("ai.skip_tool_confirmation", desired_skip)
-                    .await
-                {
-                    tracing::warn!("Failed to set tool confirmation toggle, continuing: {}", e);
-                }
-            }
-
-            use bitfun_core::infrastructure::ai::AIClientFactory;
-            AIClientFactory::initialize_global()
-                .await
-                .context("Failed to initialize global AIClientFactory")?;
-            tracing::info!("Global AI client factory initialized");
-
-            let agentic_system = agent::agentic_system::init_agentic_system()
-                .await
-                .context("Failed to initialize agentic system")?;
-            tracing::info!("Agentic system initialized");
-
-            let mut exec_mode = ExecMode::new(
+            run_exec(
                 config,
-                message,
-                agent,
-                &agentic_system,
-                workspace_path_resolved,
-                output_patch,
-            );
-            let run_result = exec_mode.run().await;
-
-            if let Some(ref svc) = config_service {
-                let _ = svc
-                    .set_config("ai.skip_tool_confirmation", original_skip_confirmation)
-                    .await;
-            }
+                cli.profile.as_deref(),
+                ExecRequest {
+                    message,
+                    agent,
+                    workspace,
+                    output_patch,
+                    confirm,
+                    apply_fixes: false,
+                },
+            )
+            .await?;
+        }
 
-            run_result?;
+        Some(Commands::Review {
+            range,
+            workspace,
+            apply_fixes,
+        }) => {
+            run_exec(
+                config,
+                cli.profile.as_deref(),
+                ExecRequest {
+                    message: modes::review::review_request(range.as_deref()),
+                    agent: modes::review::REVIEW_AGENT.to_string(),
+                    workspace,
+                    output_patch: None,
+                    // The Review agent only runs read-only tools
+                    confirm: false,
+                    apply_fixes,
+                },
+            )
+            .await?;
         }
 
         Some(Commands::Batch { tasks }) => {
@@ -358,7 +931,16 @@ async fn main() -> Result<()> {
         }
 
         Some(Commands::Config { action }) => {
-            handle_config_action(action, &config)?;
+            handle_config_action(action, &config).await?;
+        }
+
+        Some(Commands::Mcp { action }) => {
+            mcp::run(action).await?;
+        }
+
+        Some(Commands::Prompts { action }) => {
+            check_workspace_arg(cli.workspace.as_deref())?;
+            prompts::run(action, resolve_workspace_path(cli.workspace.as_deref()))?;
         }
 
         Some(Commands::Tool { name, params }) => {
@@ -369,6 +951,55 @@ async fn main() -> Result<()> {
             println!("\nWarning: Tool invocation feature coming soon");
         }
 
+        Some(Commands::Cleanup { dry_run }) => {
+            run_cleanup(dry_run).await?;
+        }
+
+        Some(Commands::Storage { action }) => {
+            handle_storage_action(action).await?;
+        }
+
+        Some(Commands::Backup { action }) => {
+            handle_backup_action(action).await?;
+        }
+
+        Some(Commands::Changelog {
+            since,
+            until,
+            release,
+            write,
+            no_refs,
+            raw,
+        }) => {
+            check_workspace_arg(cli.workspace.as_deref())?;
+            let repo_path = match resolve_workspace_path(cli.workspace.as_deref()) {
+                Some(path) => path,
+                None => std::env::current_dir()?,
+            };
+            let range = match (since, until) {
+                (Some(since), Some(until)) => Some(format!("{}..{}", since, until)),
+                (Some(since), None) => Some(since),
+                (None, Some(until)) => Some(format!("..{}", until)),
+                (None, None) => None,
+            };
+            let options = bitfun_core::function_agents::ChangelogOptions {
+                range,
+                version: release,
+                include_references: !no_refs,
+                humanize: !raw,
+                ..Default::default()
+            };
+            run_changelog(&repo_path, options, write).await?;
+        }
+
+        Some(Commands::Debug { action }) => {
+            handle_debug_action(action).await?;
+        }
+
+        Some(Commands::Log { action }) => {
+            handle_log_action(action).await?;
+        }
+
         Some(Commands::Health) => {
             println!("BitFun CLI is running normally");
             println!("Version: {}", env!("CARGO_PKG_VERSION"));
@@ -379,26 +1010,42 @@ async fn main() -> Result<()> {
             use modes::chat::ChatExitReason;
             use ui::startup::StartupPage;
 
+            check_workspace_arg(cli.workspace.as_deref())?;
+            let mut pending_resume = match cli.resume.as_deref() {
+                Some(reference) => match load_resume_session(reference)? {
+                    Some(session) => Some(session),
+                    None => return Ok(()),
+                },
+                None => None,
+            };
+            // Only skips the picker on the first pass; "back to menu" shows it
+            let mut pending_workspace = cli.workspace;
+
             loop {
                 let mut terminal = ui::init_terminal()?;
-                let mut startup_page = StartupPage::new();
-                let workspace = startup_page.run(&mut terminal)?;
+                let selection = match (pending_resume.take(), pending_workspace.take()) {
+                    (Some(session), _) => Some((session.workspace.clone(), Some(session))),
+                    (None, Some(workspace)) => Some((Some(workspace), None)),
+                    (None, None) => startup_selection(StartupPage::new().run(&mut terminal)?)?,
+                };
 
-                if workspace.is_none() {
+                let Some((workspace, mut resumed)) = selection else {
                     ui::restore_terminal(terminal)?;
                     println!("Goodbye!");
                     break;
-                }
-
-                ui::render_loading(&mut terminal, "Initializing system, please wait...")?;
+                };
 
-                let workspace_path = resolve_workspace_path(workspace.as_deref());
-                tracing::info!("CLI workspace: {:?}", workspace_path);
+                let theme = ui::theme::Theme::from_config(&CliConfig::load().unwrap_or_default());
+                ui::render_loading(&mut terminal, &theme, "Initializing system, please wait...")?;
 
                 bitfun_core::service::config::initialize_global_config()
                     .await
                     .context("Failed to initialize global config service")?;
                 tracing::info!("Global config service initialized");
+                apply_profile(cli.profile.as_deref()).await?;
+
+                let workspace_path = open_workspace(workspace.as_deref()).await;
+                tracing::info!("CLI workspace: {:?}", workspace_path);
 
                 let config_service = bitfun_core::service::config::get_global_config_service()
                     .await
@@ -425,14 +1072,27 @@ async fn main() -> Result<()> {
                     .context("Failed to initialize agentic system")?;
                 tracing::info!("Agentic system initialized");
 
+                if let Some(session) = resumed.as_mut() {
+                    restore_resumed_session(&agentic_system, session).await;
+                }
+
                 ui::render_loading(
                     &mut terminal,
+                    &theme,
                     "System initialized, starting chat interface...",
                 )?;
 
-                let agent = config.behavior.default_agent.clone();
-                let mut chat_mode =
-                    ChatMode::new(config.clone(), agent, workspace_path, &agentic_system);
+                let agent = resumed
+                    .as_ref()
+                    .map(|session| session.agent.clone())
+                    .unwrap_or_else(|| config.behavior.default_agent.clone());
+                let mut chat_mode = ChatMode::new(
+                    config.clone(),
+                    agent,
+                    workspace_path,
+                    &agentic_system,
+                    resumed,
+                );
                 let exit_reason = chat_mode.run(Some(terminal));
 
                 if let Some(ref svc) = config_service {
@@ -489,11 +1149,7 @@ fn handle_session_action(action: SessionAction) -> Result<()> {
         SessionAction::Show { id } => {
             use session::Session;
 
-            let session = if id == "last" {
-                Session::get_last()?.ok_or_else(|| anyhow::anyhow!("No history sessions"))?
-            } else {
-                Session::load(&id)?
-            };
+            let session = Session::resolve(&id)?;
 
             println!("Session Details\n");
             println!("Title: {}", session.title);
@@ -541,7 +1197,306 @@ fn handle_session_action(action: SessionAction) -> Result<()> {
     Ok(())
 }
 
-fn handle_config_action(action: ConfigAction, config: &CliConfig) -> Result<()> {
+/// Run `bitfun cleanup`: every configured storage cleanup policy, reporting each one
+async fn run_cleanup(dry_run: bool) -> Result<()> {
+    use bitfun_core::infrastructure::storage::CleanupService;
+    use bitfun_core::service::config::{get_global_config_service, GlobalConfig};
+
+    bitfun_core::service::config::initialize_global_config()
+        .await
+        .context("Failed to initialize global config service")?;
+    let config: GlobalConfig = get_global_config_service()
+        .await?
+        .get_config(None)
+        .await
+        .context("Failed to load config")?;
+    let path_manager = bitfun_core::infrastructure::try_get_path_manager_arc()?;
+    let service = CleanupService::new((*path_manager).clone(), config.app.storage.cleanup_policy())
+        .with_workspaces(workspace::known_paths().await);
+
+    let reports = service.run_policies(dry_run).await;
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    let mut total_bytes = 0u64;
+    for report in &reports {
+        let result = &report.result;
+        total_bytes += result.bytes_freed;
+        println!(
+            "{}: {} {} files ({:.2} MB)",
+            report.target.label(),
+            verb,
+            result.files_deleted,
+            result.bytes_freed as f64 / 1_048_576.0
+        );
+        if dry_run {
+            for path in &result.removed {
+                println!("  {}", path.display());
+            }
+        }
+    }
+    println!(
+        "{} {:.2} MB in total",
+        verb,
+        total_bytes as f64 / 1_048_576.0
+    );
+    Ok(())
+}
+
+async fn handle_storage_action(action: StorageAction) -> Result<()> {
+    use bitfun_core::infrastructure::storage::migrate_json_to_sqlite;
+
+    match action {
+        StorageAction::Migrate => {
+            let path_manager = bitfun_core::infrastructure::try_get_path_manager_arc()?;
+            for dir in [path_manager.user_data_dir(), path_manager.user_cron_dir()] {
+                if !dir.is_dir() {
+                    continue;
+                }
+                let report = migrate_json_to_sqlite(&dir).await?;
+                if report.already_imported {
+                    println!("{}: already migrated", dir.display());
+                    continue;
+                }
+                println!("{}: imported {} entries", dir.display(), report.imported);
+                for path in &report.skipped {
+                    println!("  skipped unreadable {}", path.display());
+                }
+            }
+            println!("Set app.storage.backend to \"sqlite\" to use the migrated data");
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_backup_action(action: BackupAction) -> Result<()> {
+    use bitfun_core::infrastructure::storage::{
+        read_manifest, BackupCategory, BackupService, RestoreOptions,
+    };
+    use std::io::Write;
+
+    let path_manager = bitfun_core::infrastructure::try_get_path_manager_arc()?;
+    let service = BackupService::new((*path_manager).clone());
+
+    match action {
+        BackupAction::Create { categories } => {
+            let categories: Vec<BackupCategory> = if categories.is_empty() {
+                bitfun_core::service::config::initialize_global_config()
+                    .await
+                    .context("Failed to initialize global config service")?;
+                let config: bitfun_core::service::config::GlobalConfig =
+                    bitfun_core::service::config::get_global_config_service()
+                        .await?
+                        .get_config(None)
+                        .await
+                        .context("Failed to load config")?;
+                config.app.storage.backup.categories
+            } else {
+                categories.into_iter().map(Into::into).collect()
+            };
+            let workspaces = workspace::known_paths().await;
+
+            let backup = service.create(&categories, &workspaces).await?;
+            println!("Created {}", backup.path.display());
+            for (category, count) in &backup.counts {
+                println!("  {:?}: {} files", category, count);
+            }
+            println!(
+                "  {} workspaces, {:.2} MB",
+                backup.workspaces.len(),
+                backup.size_bytes as f64 / 1_048_576.0
+            );
+        }
+        BackupAction::Restore {
+            path,
+            categories,
+            force,
+            yes,
+        } => {
+            let manifest = read_manifest(&path)?;
+            println!(
+                "Backup of {} by BitFun {}",
+                manifest.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                manifest.app_version
+            );
+            for (category, count) in &manifest.counts {
+                println!("  {:?}: {} files", category, count);
+            }
+            if !yes {
+                print!("Replace the current data with this backup? [y/N] ");
+                std::io::stdout().flush()?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                    println!("Cancelled");
+                    return Ok(());
+                }
+            }
+
+            let options = RestoreOptions {
+                categories: categories.into_iter().map(Into::into).collect(),
+                force,
+            };
+            let report = service.restore(&path, &options).await?;
+            println!("Restored {} files", report.files);
+            for workspace in &report.skipped_workspaces {
+                println!("  skipped missing workspace {}", workspace.display());
+            }
+            println!("Restart BitFun to load the restored data");
+        }
+        BackupAction::List => {
+            let backups = service.list().await?;
+            if backups.is_empty() {
+                println!("No backups in {}", path_manager.backups_dir().display());
+            }
+            for backup in &backups {
+                let categories: Vec<String> = backup
+                    .categories
+                    .iter()
+                    .map(|category| format!("{:?}", category).to_lowercase())
+                    .collect();
+                println!(
+                    "{}  {}  {:.2} MB  {}",
+                    backup.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    backup.path.display(),
+                    backup.size_bytes as f64 / 1_048_576.0,
+                    categories.join(",")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_changelog(
+    repo_path: &std::path::Path,
+    options: bitfun_core::function_agents::ChangelogOptions,
+    write: bool,
+) -> Result<()> {
+    use bitfun_core::function_agents::ChangelogFunctionAgent;
+    use bitfun_core::infrastructure::ai::AIClientFactory;
+
+    bitfun_core::service::config::initialize_global_config()
+        .await
+        .context("Failed to initialize global config service")?;
+    AIClientFactory::initialize_global()
+        .await
+        .context("Failed to initialize global AIClientFactory")?;
+    let agent = ChangelogFunctionAgent::new(AIClientFactory::get_global().await?);
+
+    let changelog = agent.generate_changelog(repo_path, options).await?;
+    for note in &changelog.notes {
+        eprintln!("Note: {}", note);
+    }
+
+    if write {
+        let path = agent.write_changelog(repo_path, &changelog).await?;
+        println!(
+            "Wrote {} commits ({}) to {}",
+            changelog.commit_count,
+            changelog.range,
+            path.display()
+        );
+    } else {
+        print!("{}", changelog.markdown);
+    }
+
+    Ok(())
+}
+
+/// Log level overrides live in `app.logging.target_levels`; a running desktop
+/// app picks up the change through its config file watcher.
+async fn handle_log_action(action: LogAction) -> Result<()> {
+    use std::collections::HashMap;
+
+    const LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+    bitfun_core::service::config::initialize_global_config()
+        .await
+        .context("Failed to initialize global config service")?;
+    let config_service = bitfun_core::service::config::get_global_config_service().await?;
+    let mut target_levels: HashMap<String, String> = config_service
+        .get_config(Some("app.logging.target_levels"))
+        .await
+        .unwrap_or_default();
+
+    match action {
+        LogAction::SetLevel { target, level } => {
+            let target = target.trim();
+            if target.is_empty() {
+                anyhow::bail!("Log target is required");
+            }
+            let level = level.trim().to_lowercase();
+            if level == "default" {
+                if target_levels.remove(target).is_none() {
+                    println!("No override for {}", target);
+                    return Ok(());
+                }
+            } else if LEVELS.contains(&level.as_str()) {
+                target_levels.insert(target.to_string(), level.clone());
+            } else {
+                anyhow::bail!(
+                    "Invalid log level '{}': expected one of {} or default",
+                    level,
+                    LEVELS.join("/")
+                );
+            }
+            config_service
+                .set_config("app.logging.target_levels", &target_levels)
+                .await
+                .context("Failed to save log level override")?;
+            if level == "default" {
+                println!("Removed log level override for {}", target);
+            } else {
+                println!("{} = {}", target, level);
+            }
+        }
+        LogAction::Levels => {
+            let global: String = config_service
+                .get_config(Some("app.logging.level"))
+                .await
+                .unwrap_or_else(|_| "debug".to_string());
+            println!("default = {}", global);
+            let mut targets: Vec<_> = target_levels.into_iter().collect();
+            targets.sort();
+            for (target, level) in targets {
+                println!("{} = {}", target, level);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_debug_action(action: DebugAction) -> Result<()> {
+    use bitfun_core::infrastructure::events::{replay_to_cli, CliEvent};
+
+    match action {
+        DebugAction::Replay { file, realtime } => {
+            let path = std::path::PathBuf::from(&file);
+            if !path.is_file() {
+                anyhow::bail!("Event log not found: {}", file);
+            }
+            let mut events = replay_to_cli(path, realtime);
+            let mut count = 0usize;
+            while let Some(event) = events.recv().await {
+                match event {
+                    CliEvent::Generic {
+                        event_name,
+                        payload,
+                    } => println!("{} {}", event_name, payload),
+                    other => println!("{:?}", other),
+                }
+                count += 1;
+            }
+            println!("Replayed {} events from {}", count, file);
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_config_action(action: ConfigAction, config: &CliConfig) -> Result<()> {
     match action {
         ConfigAction::Show => {
             println!("Current Configuration\n");
@@ -576,7 +1531,299 @@ fn handle_config_action(action: ConfigAction, config: &CliConfig) -> Result<()>
             default_config.save()?;
             println!("Reset to default configuration");
         }
+
+        ConfigAction::Validate => {
+            if !validate_config_files().await? {
+                std::process::exit(1);
+            }
+        }
+
+        ConfigAction::Secrets { migrate, yes } => {
+            migrate_plaintext_secrets(migrate, yes).await?;
+        }
+
+        ConfigAction::Export {
+            path,
+            include_secrets,
+        } => {
+            export_settings(&path, include_secrets).await?;
+        }
+
+        ConfigAction::Import {
+            path,
+            strategy,
+            dry_run,
+            yes,
+        } => {
+            import_settings(&path, strategy, dry_run, yes).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// List plaintext secrets in the global config; with `migrate`, move them into the secrets store
+async fn migrate_plaintext_secrets(migrate: bool, yes: bool) -> Result<()> {
+    use std::io::Write;
+
+    bitfun_core::service::config::initialize_global_config().await?;
+    let service = bitfun_core::service::config::get_global_config_service().await?;
+    let found = service.find_plaintext_secrets().await?;
+    if found.is_empty() {
+        println!("No plaintext secrets found in the config");
+        return Ok(());
+    }
+
+    println!("Plaintext secrets in the config:");
+    for secret in &found {
+        println!("  {}", secret.path);
+    }
+    if !migrate {
+        println!();
+        println!("Run `bitfun config secrets --migrate` to move them into the OS keyring");
+        return Ok(());
+    }
+
+    if !yes {
+        print!("Move {} secret(s) into the secrets store? [y/N] ", found.len());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    let moved = service.migrate_plaintext_secrets(None).await?;
+    for secret in &moved {
+        println!("  ✓ {} -> keyring:{}", secret.path, secret.name);
+    }
+    println!("Moved {} secret(s)", moved.len());
+    Ok(())
+}
+
+/// Write the global config to a settings bundle
+async fn export_settings(path: &std::path::Path, include_secrets: bool) -> Result<()> {
+    use bitfun_core::service::config::bundle;
+
+    bitfun_core::service::config::initialize_global_config().await?;
+    let service = bitfun_core::service::config::get_global_config_service().await?;
+
+    let passphrase = if include_secrets {
+        Some(settings_passphrase("Passphrase to encrypt the secrets: ", true)?)
+    } else {
+        None
+    };
+    let summary = bundle::export_settings(&service, path, passphrase.as_deref()).await?;
+
+    println!("Settings exported to {}", summary.path.display());
+    if include_secrets {
+        println!("Included {} secret(s), encrypted", summary.secrets_included);
+    } else {
+        println!("Secrets were left out; use --include-secrets to include them");
+    }
+    Ok(())
+}
+
+/// Import a settings bundle into the global config after showing what would change
+async fn import_settings(
+    path: &std::path::Path,
+    strategy: ImportStrategy,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    use bitfun_core::service::config::bundle::{self, ImportSettingsOptions};
+    use std::io::Write;
+
+    bitfun_core::service::config::initialize_global_config().await?;
+    let service = bitfun_core::service::config::get_global_config_service().await?;
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let passphrase = if bundle::open_bundle(&content)?.secrets.is_some() {
+        let passphrase =
+            settings_passphrase("Passphrase for the bundle secrets (empty to skip): ", false)?;
+        (!passphrase.is_empty()).then_some(passphrase)
+    } else {
+        None
+    };
+    let mut options = ImportSettingsOptions {
+        strategy: strategy.into(),
+        passphrase,
+        dry_run: true,
+    };
+
+    let preview = bundle::import_settings(&service, None, path, &options).await?;
+    print_import_report(&preview);
+    if dry_run {
+        return Ok(());
+    }
+    if preview.changes.is_empty() && preview.mcp_servers_removed.is_empty() {
+        println!("Settings are already up to date");
+        return Ok(());
+    }
+    if !yes {
+        print!("Apply {} change(s)? [y/N] ", preview.changes.len());
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Cancelled");
+            return Ok(());
+        }
     }
 
+    options.dry_run = false;
+    let report = bundle::import_settings(&service, None, path, &options).await?;
+    for warning in &report.warnings {
+        println!("  ! {}", warning);
+    }
+    println!("Imported {} change(s)", report.changes.len());
     Ok(())
 }
+
+fn print_import_report(report: &bitfun_core::service::config::SettingsImportReport) {
+    let show = |value: &Option<serde_json::Value>| {
+        value
+            .as_ref()
+            .map_or_else(|| "(unset)".to_string(), |v| v.to_string())
+    };
+
+    if report.changes.is_empty() {
+        println!("No setting changes");
+    } else {
+        println!("Changes:");
+        for change in &report.changes {
+            println!(
+                "  {}: {} -> {}",
+                change.path,
+                show(&change.before),
+                show(&change.after)
+            );
+        }
+    }
+    if !report.mcp_servers.is_empty() {
+        println!("MCP servers to register: {}", report.mcp_servers.join(", "));
+    }
+    if !report.mcp_servers_removed.is_empty() {
+        println!(
+            "MCP servers to remove: {}",
+            report.mcp_servers_removed.join(", ")
+        );
+    }
+    if !report.secrets_imported.is_empty() {
+        println!("Secrets to import: {}", report.secrets_imported.join(", "));
+    }
+    if !report.missing_secrets.is_empty() {
+        println!(
+            "Secrets to set after importing: {}",
+            report.missing_secrets.join(", ")
+        );
+    }
+    for warning in &report.warnings {
+        println!("  ! {}", warning);
+    }
+}
+
+/// Settings bundle passphrase from the environment, or typed without echo
+fn settings_passphrase(prompt: &str, required: bool) -> Result<String> {
+    let passphrase = match std::env::var(SETTINGS_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => read_hidden_line(prompt)?,
+    };
+    if required && passphrase.is_empty() {
+        anyhow::bail!("A passphrase is required to include secrets");
+    }
+    Ok(passphrase)
+}
+
+/// Read a line from the terminal without echoing it
+fn read_hidden_line(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use std::io::Write;
+
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+    crossterm::terminal::enable_raw_mode()?;
+    let mut line = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("Cancelled"))
+                }
+                KeyCode::Char(c) => line.push(c),
+                KeyCode::Backspace => {
+                    line.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    println!();
+    result?;
+    Ok(line)
+}
+
+/// Print every problem in the global config and the CLI config; false when any is an error
+async fn validate_config_files() -> Result<bool> {
+    use bitfun_core::service::config::schema;
+
+    let mut valid = true;
+
+    match bitfun_core::service::config::initialize_global_config().await {
+        Ok(()) => {
+            let service = bitfun_core::service::config::get_global_config_service().await?;
+            let result = service.validate().await?;
+            println!(
+                "Global config: {} error(s), {} warning(s)",
+                result.errors.len(),
+                result.warnings.len()
+            );
+            for error in &result.errors {
+                println!("  ✗ {}", schema::describe_error(error));
+            }
+            for warning in &result.warnings {
+                let mut line = format!("  ! {}: {}", warning.path, warning.message);
+                if let Some(suggestion) = &warning.suggestion {
+                    line.push_str(&format!("; did you mean \"{}\"?", suggestion));
+                }
+                println!("{}", line);
+            }
+            valid &= result.valid;
+        }
+        Err(e) => {
+            println!("Global config: failed to load");
+            println!("  ✗ {}", e);
+            valid = false;
+        }
+    }
+
+    let cli_path = CliConfig::config_path()?;
+    match std::fs::read_to_string(&cli_path) {
+        Ok(content) => match toml::from_str::<CliConfig>(&content) {
+            Ok(_) => println!("CLI config: OK ({})", cli_path.display()),
+            Err(e) => {
+                println!("CLI config: invalid ({})", cli_path.display());
+                for line in e.to_string().lines().filter(|l| !l.trim().is_empty()) {
+                    println!("  {}", line);
+                }
+                valid = false;
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("CLI config: not created yet (defaults)");
+        }
+        Err(e) => {
+            println!("CLI config: unreadable ({}): {}", cli_path.display(), e);
+            valid = false;
+        }
+    }
+
+    Ok(valid)
+}
@@ -0,0 +1,479 @@
+/// `bitfun mcp` subcommands
+///
+/// Servers are read and written through core's MCPConfigService, so user
+/// servers land in the same Cursor-format `mcp_servers` config the desktop
+/// app edits. Connections only live for the duration of one command.
+use anyhow::{Context, Result};
+use bitfun_core::service::config::{get_global_config_service, initialize_global_config};
+use bitfun_core::service::mcp::{ConfigLocation, MCPServerConfig, MCPServerType, MCPService};
+use clap::Subcommand;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Subcommand)]
+pub enum McpAction {
+    /// List configured MCP servers
+    List,
+    /// Add a user MCP server (stdio with --command, remote with --url)
+    Add {
+        /// Server ID
+        id: String,
+
+        /// Display name (defaults to the ID)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Command that starts a stdio server
+        #[arg(long, conflicts_with_all = ["url", "header"])]
+        command: Option<String>,
+
+        /// Arguments passed to --command
+        #[arg(long, num_args = 1.., allow_hyphen_values = true, requires = "command")]
+        args: Vec<String>,
+
+        /// Environment variable for the server process (KEY=VALUE, repeatable)
+        #[arg(long, value_name = "KEY=VALUE")]
+        env: Vec<String>,
+
+        /// URL of a remote (streamable HTTP / SSE) server
+        #[arg(long)]
+        url: Option<String>,
+
+        /// HTTP header sent to a remote server ("Name: value" or NAME=VALUE, repeatable)
+        #[arg(long, value_name = "HEADER")]
+        header: Vec<String>,
+
+        /// Save the server disabled
+        #[arg(long)]
+        disabled: bool,
+
+        /// Do not start the server automatically with BitFun
+        #[arg(long)]
+        no_auto_start: bool,
+    },
+    /// Remove a user MCP server
+    Remove { id: String },
+    /// Enable a user MCP server and start it
+    Enable { id: String },
+    /// Disable a user MCP server
+    Disable { id: String },
+    /// Restart (reconnect) a server and report its status
+    Restart { id: String },
+    /// List the tools a server provides
+    Tools { id: String },
+    /// Run initialize, ping and tools/list against a server and report timings
+    Test { id: String },
+}
+
+pub async fn run(action: McpAction) -> Result<()> {
+    initialize_global_config()
+        .await
+        .context("Failed to initialize global config service")?;
+    let service = MCPService::new(get_global_config_service().await?)
+        .context("Failed to initialize MCP service")?;
+
+    let result = match action {
+        McpAction::List => list(&service).await,
+        McpAction::Add {
+            id,
+            name,
+            command,
+            args,
+            env,
+            url,
+            header,
+            disabled,
+            no_auto_start,
+        } => {
+            let config = build_config(
+                id,
+                name,
+                command,
+                args,
+                &env,
+                url,
+                &header,
+                !disabled,
+                !no_auto_start,
+            )?;
+            add(&service, config).await
+        }
+        McpAction::Remove { id } => remove(&service, &id).await,
+        McpAction::Enable { id } => set_enabled(&service, &id, true).await,
+        McpAction::Disable { id } => set_enabled(&service, &id, false).await,
+        McpAction::Restart { id } => restart(&service, &id).await,
+        McpAction::Tools { id } => tools(&service, &id).await,
+        McpAction::Test { id } => test(&service, &id).await,
+    };
+
+    // Stdio servers are child processes of this command
+    if let Err(e) = service.server_manager().shutdown().await {
+        tracing::warn!("Failed to stop MCP servers: {}", e);
+    }
+    result
+}
+
+async fn list(service: &MCPService) -> Result<()> {
+    let configs = service.config_service().load_all_configs().await?;
+    if configs.is_empty() {
+        println!("No MCP servers configured");
+        return Ok(());
+    }
+
+    println!("MCP servers (total {})\n", configs.len());
+    for config in &configs {
+        let state = if config.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        println!("{} ({}) - {}", config.id, config.name, state);
+        println!(
+            "   Type: {} | Scope: {} | Auto start: {}",
+            type_label(&config.server_type),
+            location_label(&config.location),
+            config.auto_start
+        );
+        println!("   {}", endpoint(config));
+    }
+    Ok(())
+}
+
+async fn add(service: &MCPService, config: MCPServerConfig) -> Result<()> {
+    if service
+        .config_service()
+        .get_server_config(&config.id)
+        .await?
+        .is_some()
+    {
+        anyhow::bail!(
+            "MCP server '{}' already exists; remove it first to replace it",
+            config.id
+        );
+    }
+
+    service.config_service().save_server_config(&config).await?;
+    println!("Added MCP server: {} ({})", config.id, endpoint(&config));
+
+    if config.enabled {
+        start_and_report(service, &config.id).await;
+    }
+    Ok(())
+}
+
+async fn remove(service: &MCPService, id: &str) -> Result<()> {
+    user_config(service, id).await?;
+    service.server_manager().remove_server(id).await?;
+    println!("Removed MCP server: {}", id);
+    Ok(())
+}
+
+async fn set_enabled(service: &MCPService, id: &str, enabled: bool) -> Result<()> {
+    let mut config = user_config(service, id).await?;
+    if config.enabled == enabled {
+        println!(
+            "MCP server {} is already {}",
+            id,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        return Ok(());
+    }
+
+    config.enabled = enabled;
+    service.config_service().save_server_config(&config).await?;
+    if enabled {
+        println!("Enabled MCP server: {}", id);
+        start_and_report(service, id).await;
+    } else {
+        println!("Disabled MCP server: {}", id);
+    }
+    Ok(())
+}
+
+async fn restart(service: &MCPService, id: &str) -> Result<()> {
+    let manager = service.server_manager();
+    manager.restart_server(id).await?;
+    let status = manager.get_server_status(id).await?;
+    println!("Restarted {} ({:?})", id, status);
+    Ok(())
+}
+
+async fn tools(service: &MCPService, id: &str) -> Result<()> {
+    let manager = service.server_manager();
+    manager.start_server(id).await?;
+    let connection = manager
+        .get_connection(id)
+        .await
+        .with_context(|| format!("MCP server {} has no connection", id))?;
+
+    let mut tools = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = connection.list_tools(cursor).await?;
+        tools.extend(page.tools);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    if tools.is_empty() {
+        println!("{} provides no tools", id);
+        return Ok(());
+    }
+    println!("Tools of {} (total {})\n", id, tools.len());
+    for tool in &tools {
+        println!("{}", tool.name);
+        let description = tool.description.as_deref().or(tool.title.as_deref());
+        if let Some(line) = description.and_then(|d| d.lines().find(|l| !l.trim().is_empty())) {
+            println!("   {}", line.trim());
+        }
+    }
+    Ok(())
+}
+
+async fn test(service: &MCPService, id: &str) -> Result<()> {
+    let manager = service.server_manager();
+    println!("Testing MCP server: {}\n", id);
+
+    timed("connect", manager.start_server(id)).await?;
+    let connection = manager
+        .get_connection(id)
+        .await
+        .with_context(|| format!("MCP server {} has no connection", id))?;
+
+    let info = timed(
+        "initialize",
+        connection.initialize("BitFun CLI", env!("CARGO_PKG_VERSION")),
+    )
+    .await?;
+    timed("ping", connection.ping()).await?;
+    let tools = timed("tools/list", connection.list_tools(None)).await?;
+
+    println!();
+    println!(
+        "Server: {} {}",
+        info.server_info.name, info.server_info.version
+    );
+    println!(
+        "Tools: {}{}",
+        tools.tools.len(),
+        if tools.next_cursor.is_some() {
+            " (first page)"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+/// Await `step`, printing its outcome and duration
+async fn timed<T, E>(label: &str, step: impl Future<Output = Result<T, E>>) -> Result<T>
+where
+    E: std::fmt::Display,
+{
+    let started = Instant::now();
+    let result = step.await;
+    let elapsed = format_duration(started.elapsed());
+    match result {
+        Ok(value) => {
+            println!("  ✓ {:<12} {}", label, elapsed);
+            Ok(value)
+        }
+        Err(e) => {
+            println!("  ✗ {:<12} {}", label, elapsed);
+            anyhow::bail!("{} failed: {}", label, e)
+        }
+    }
+}
+
+/// Start `id` and print whether it came up; failures are reported, not returned
+async fn start_and_report(service: &MCPService, id: &str) {
+    let manager = service.server_manager();
+    match manager.start_server(id).await {
+        Ok(()) => match manager.get_server_status(id).await {
+            Ok(status) => println!("Started {} ({:?})", id, status),
+            Err(_) => println!("Started {}", id),
+        },
+        Err(e) => eprintln!("Warning: saved, but the server failed to start: {}", e),
+    }
+}
+
+/// Config of `id`, which must be a user-level server
+async fn user_config(service: &MCPService, id: &str) -> Result<MCPServerConfig> {
+    let config = service
+        .config_service()
+        .get_server_config(id)
+        .await?
+        .with_context(|| format!("MCP server not found: {}", id))?;
+    if !matches!(config.location, ConfigLocation::User) {
+        anyhow::bail!(
+            "MCP server '{}' is a {} server; only user servers can be changed here",
+            id,
+            location_label(&config.location)
+        );
+    }
+    Ok(config)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_config(
+    id: String,
+    name: Option<String>,
+    command: Option<String>,
+    args: Vec<String>,
+    env: &[String],
+    url: Option<String>,
+    headers: &[String],
+    enabled: bool,
+    auto_start: bool,
+) -> Result<MCPServerConfig> {
+    let server_type = match (&command, &url) {
+        (Some(_), None) => MCPServerType::Local,
+        (None, Some(url)) => {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                anyhow::bail!("--url must be an http(s) URL: {}", url);
+            }
+            MCPServerType::Remote
+        }
+        _ => anyhow::bail!("Pass either --command (stdio server) or --url (remote server)"),
+    };
+
+    let config = MCPServerConfig {
+        name: name.unwrap_or_else(|| id.clone()),
+        id,
+        server_type,
+        command,
+        args,
+        env: parse_pairs(env, "--env")?,
+        headers: parse_pairs(headers, "--header")?,
+        url,
+        auto_start,
+        enabled,
+        location: ConfigLocation::User,
+        capabilities: Vec::new(),
+        settings: HashMap::new(),
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+/// Parse `KEY=VALUE` (or curl-style `Name: value`) entries
+fn parse_pairs(entries: &[String], flag: &str) -> Result<HashMap<String, String>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let split = match (entry.find(':'), entry.find('=')) {
+                (Some(colon), Some(eq)) => Some(colon.min(eq)),
+                (colon, eq) => colon.or(eq),
+            };
+            let (key, value) = split
+                .map(|i| (entry[..i].trim(), entry[i + 1..].trim()))
+                .filter(|(key, _)| !key.is_empty())
+                .with_context(|| format!("{} expects KEY=VALUE, got '{}'", flag, entry))?;
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn endpoint(config: &MCPServerConfig) -> String {
+    match (&config.command, &config.url) {
+        (Some(command), _) if config.args.is_empty() => command.clone(),
+        (Some(command), _) => format!("{} {}", command, config.args.join(" ")),
+        (None, Some(url)) => url.clone(),
+        (None, None) => "-".to_string(),
+    }
+}
+
+fn type_label(server_type: &MCPServerType) -> &'static str {
+    match server_type {
+        MCPServerType::Local => "stdio",
+        MCPServerType::Remote => "remote",
+        MCPServerType::Container => "container",
+    }
+}
+
+fn location_label(location: &ConfigLocation) -> &'static str {
+    match location {
+        ConfigLocation::BuiltIn => "built-in",
+        ConfigLocation::User => "user",
+        ConfigLocation::Project => "project",
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration.as_secs() >= 1 {
+        format!("{:.2}s", duration.as_secs_f64())
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_env_and_header_pairs() {
+        let pairs = parse_pairs(
+            &[
+                "Authorization: Bearer a=b".to_string(),
+                "TOKEN=x:y".to_string(),
+            ],
+            "--header",
+        )
+        .unwrap();
+        assert_eq!(pairs["Authorization"], "Bearer a=b");
+        assert_eq!(pairs["TOKEN"], "x:y");
+        assert!(parse_pairs(&["novalue".to_string()], "--env").is_err());
+        assert!(parse_pairs(&["=value".to_string()], "--env").is_err());
+    }
+
+    #[test]
+    fn builds_stdio_and_remote_configs() {
+        let stdio = build_config(
+            "fs".to_string(),
+            None,
+            Some("npx".to_string()),
+            vec!["-y".to_string(), "server-fs".to_string()],
+            &["ROOT=/tmp".to_string()],
+            None,
+            &[],
+            true,
+            true,
+        )
+        .unwrap();
+        assert!(matches!(stdio.server_type, MCPServerType::Local));
+        assert_eq!(stdio.name, "fs");
+        assert_eq!(endpoint(&stdio), "npx -y server-fs");
+
+        let remote = build_config(
+            "docs".to_string(),
+            Some("Docs".to_string()),
+            None,
+            Vec::new(),
+            &[],
+            Some("https://example.com/mcp".to_string()),
+            &["X-Key: 1".to_string()],
+            true,
+            false,
+        )
+        .unwrap();
+        assert!(matches!(remote.server_type, MCPServerType::Remote));
+        assert_eq!(remote.headers["X-Key"], "1");
+
+        let neither = build_config(
+            "bad".to_string(),
+            None,
+            None,
+            Vec::new(),
+            &[],
+            None,
+            &[],
+            true,
+            true,
+        );
+        assert!(neither.is_err());
+    }
+}
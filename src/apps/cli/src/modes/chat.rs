@@ -2,21 +2,34 @@
 ///
 /// Interactive chat mode with TUI interface
 use anyhow::Result;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 use crate::agent::{agentic_system::AgenticSystem, core_adapter::CoreAgentAdapter, Agent};
 use crate::config::CliConfig;
+use crate::export::{export_session, ExportFormat, Usage};
 use crate::session::Session;
 use crate::ui::chat::ChatView;
+use crate::ui::clipboard::{self, CopyTarget};
+use crate::ui::notification::{self, AlertKind};
+use crate::ui::permission::{PermissionChoice, PermissionRequest};
+use crate::ui::status_line::format_tokens;
 use crate::ui::theme::Theme;
-use crate::ui::{init_terminal, restore_terminal};
+use crate::ui::tool_cards;
+use crate::ui::{edit_in_external_editor, init_terminal, restore_terminal, set_mouse_capture};
+use crate::workspace;
+use bitfun_core::agentic::coordination::ConversationCoordinator;
+use bitfun_core::agentic::turn_summary;
+use bitfun_core::service::{config, mcp};
+use bitfun_core::util::types::AvailableModel;
 use uuid;
 
 /// Chat mode exit reason
@@ -33,6 +46,25 @@ pub struct ChatMode {
     agent_name: String,
     workspace_path: Option<PathBuf>,
     agent: Arc<dyn Agent>,
+    /// Resumed session whose history is shown instead of starting fresh
+    resumed_session: Option<Session>,
+    coordinator: Arc<ConversationCoordinator>,
+    mcp_service: Option<Arc<mcp::MCPService>>,
+    /// Output of slash commands that finish in the background: (command, output)
+    command_tx: mpsc::UnboundedSender<(String, String)>,
+    command_rx: Option<mpsc::UnboundedReceiver<(String, String)>>,
+    /// Active model resolved in the background: (name, context window)
+    model_tx: mpsc::UnboundedSender<(String, Option<u64>)>,
+    model_rx: Option<mpsc::UnboundedReceiver<(String, Option<u64>)>>,
+    /// Workspaces opened by `/workspace <path>`: (command, root or error)
+    workspace_tx: mpsc::UnboundedSender<(String, Result<PathBuf, String>)>,
+    workspace_rx: Option<mpsc::UnboundedReceiver<(String, Result<PathBuf, String>)>>,
+    /// When the running turn was sent, for the completion alert
+    turn_started_at: Option<Instant>,
+    /// Assistant message of the last completed turn, which its summary attaches to
+    summary_target: Option<String>,
+    /// Whether the terminal has focus (per focus events; assumed until told otherwise)
+    terminal_focused: bool,
 }
 
 impl ChatMode {
@@ -41,20 +73,45 @@ impl ChatMode {
         agent_name: String,
         workspace_path: Option<PathBuf>,
         agentic_system: &AgenticSystem,
+        resumed_session: Option<Session>,
     ) -> Self {
         // Use the real CoreAgentAdapter
-        let agent = Arc::new(CoreAgentAdapter::new(
-            agent_name.clone(),
-            agentic_system.coordinator.clone(),
-            agentic_system.event_queue.clone(),
-            workspace_path.clone(),
-        )) as Arc<dyn Agent>;
+        let agent = Arc::new(
+            CoreAgentAdapter::new(
+                agent_name.clone(),
+                agentic_system.coordinator.clone(),
+                agentic_system.event_queue.clone(),
+                agentic_system.event_router.clone(),
+                workspace_path.clone(),
+            )
+            .with_session_id(
+                resumed_session
+                    .as_ref()
+                    .and_then(|session| session.core_session_id.clone()),
+            ),
+        ) as Arc<dyn Agent>;
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (model_tx, model_rx) = mpsc::unbounded_channel();
+        let (workspace_tx, workspace_rx) = mpsc::unbounded_channel();
 
         Self {
             config,
             agent_name,
             workspace_path,
             agent,
+            resumed_session,
+            coordinator: agentic_system.coordinator.clone(),
+            mcp_service: agentic_system.mcp_service.clone(),
+            command_tx,
+            command_rx: Some(command_rx),
+            model_tx,
+            model_rx: Some(model_rx),
+            workspace_tx,
+            workspace_rx: Some(workspace_rx),
+            turn_started_at: None,
+            summary_target: None,
+            terminal_focused: true,
         }
     }
 
@@ -71,23 +128,39 @@ impl ChatMode {
             Some(t) => t,
             None => init_terminal()?,
         };
-        let session = Session::new(
-            self.agent_name.clone(),
-            self.workspace_path
-                .as_ref()
-                .map(|path| path.to_string_lossy().to_string()),
-        );
+        let session = self.resumed_session.take().unwrap_or_else(|| {
+            Session::new(
+                self.agent_name.clone(),
+                self.workspace_path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string()),
+            )
+        });
 
-        let theme = match self.config.ui.theme.as_str() {
-            "light" => Theme::light(),
-            _ => Theme::dark(),
-        };
-        let mut chat_view = ChatView::new(session, theme);
+        let mut chat_view = ChatView::new(session, Theme::from_config(&self.config));
+        chat_view.set_tool_card_defaults(self.config.ui.tool_card_expanded.clone());
+        let mut mouse_capture = false;
+        if self.config.ui.mouse_capture {
+            mouse_capture = Self::toggle_mouse_capture(&mut terminal, false, &mut chat_view);
+        }
 
         let rt_handle = tokio::runtime::Handle::current();
         let (response_tx, mut response_rx) =
             mpsc::unbounded_channel::<crate::agent::AgentResponse>();
         let (stream_tx, mut stream_rx) = mpsc::unbounded_channel::<crate::agent::AgentEvent>();
+        let mut command_rx = self
+            .command_rx
+            .take()
+            .unwrap_or_else(|| mpsc::unbounded_channel().1);
+        let mut model_rx = self
+            .model_rx
+            .take()
+            .unwrap_or_else(|| mpsc::unbounded_channel().1);
+        let mut workspace_rx = self
+            .workspace_rx
+            .take()
+            .unwrap_or_else(|| mpsc::unbounded_channel().1);
+        self.refresh_active_model();
 
         let mut pending_response: Option<tokio::task::JoinHandle<Result<()>>> = None;
         let mut current_assistant_message_text = String::new();
@@ -149,6 +222,7 @@ impl ChatMode {
                                 chat_view.session.update_tool_in_last_message(&tid, |t| {
                                     t.progress_message = Some(message.clone());
                                 });
+                                chat_view.append_tool_output(&tid, &message);
                                 break;
                             }
                         }
@@ -188,17 +262,125 @@ impl ChatMode {
                                 false,
                             );
                         }
+                        self.summary_target =
+                            chat_view.session.messages.last().map(|m| m.id.clone());
+                    }
+
+                    AgentEvent::TurnSummary(summary) => {
+                        if let Some(message_id) = self.summary_target.take() {
+                            chat_view.session.set_turn_summary(&message_id, summary);
+                        }
                     }
 
                     AgentEvent::Error(err) => {
                         chat_view.set_status(Some(format!("Error: {}", err)));
                     }
 
+                    AgentEvent::SessionStarted(core_session_id) => {
+                        chat_view.session.core_session_id = Some(core_session_id);
+                    }
+
+                    AgentEvent::TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                    } => {
+                        chat_view
+                            .session
+                            .record_token_usage(input_tokens, output_tokens);
+                    }
+
+                    AgentEvent::SpendUpdated {
+                        session_usd,
+                        today_usd,
+                    } => {
+                        chat_view.set_spend(session_usd, today_usd);
+                    }
+
+                    AgentEvent::ContextUsage {
+                        used_tokens,
+                        window_tokens,
+                    } => {
+                        chat_view
+                            .status_line
+                            .set_context(used_tokens, window_tokens);
+                    }
+
+                    AgentEvent::Phase(phase) => {
+                        chat_view.status_line.set_phase(Some(phase));
+                    }
+
+                    AgentEvent::PermissionRequest {
+                        tool_id,
+                        tool_name,
+                        parameters,
+                        timeout_secs,
+                    } => {
+                        if self.config.permissions.allows(&tool_name) {
+                            self.reply_permission(&rt_handle, &chat_view, tool_id, true);
+                        } else {
+                            chat_view.set_status(Some(format!(
+                                "{} is waiting for permission",
+                                tool_name
+                            )));
+                            if !self.terminal_focused {
+                                notification::alert(
+                                    &self.config.notifications,
+                                    AlertKind::PermissionRequest,
+                                    &format!("{} needs permission", tool_name),
+                                    &tool_cards::extract_key_params(&parameters),
+                                );
+                            }
+                            chat_view.permission.push(PermissionRequest::new(
+                                tool_id,
+                                tool_name,
+                                parameters,
+                                timeout_secs,
+                            ));
+                        }
+                    }
+
                     _ => {}
                 }
             }
 
+            // The tool pipeline has already given up on these
+            for request in chat_view.permission.take_expired(Instant::now()) {
+                chat_view.set_status(Some(format!(
+                    "Permission request for {} timed out",
+                    request.tool_name()
+                )));
+            }
+
+            while let Ok((command, output)) = command_rx.try_recv() {
+                chat_view.add_command_output(&command, &output);
+            }
+
+            while let Ok((name, context_window)) = model_rx.try_recv() {
+                chat_view.status_line.set_model(name, context_window);
+            }
+
+            while let Ok((command, opened)) = workspace_rx.try_recv() {
+                match opened {
+                    Ok(root) => self.switch_workspace(&command, root, &mut chat_view),
+                    Err(e) => chat_view.add_command_output(&command, &format!("Error: {}", e)),
+                }
+            }
+
             if let Ok(_response) = response_rx.try_recv() {
+                chat_view.status_line.set_phase(None);
+                let started_at = self.turn_started_at.take();
+                if let (false, Some(started_at)) = (self.terminal_focused, started_at) {
+                    notification::alert(
+                        &self.config.notifications,
+                        AlertKind::TurnComplete,
+                        &format!("{} finished", self.agent_name),
+                        &format!(
+                            "{} · {}",
+                            notification::format_elapsed(started_at.elapsed().as_secs()),
+                            notification::sanitize(&current_assistant_message_text)
+                        ),
+                    );
+                }
                 current_assistant_message_text.clear();
                 current_tool_map.clear();
                 chat_view.set_loading(false);
@@ -215,6 +397,55 @@ impl ChatMode {
             if crossterm::event::poll(Duration::from_millis(16))? {
                 if let Ok(event) = crossterm::event::read() {
                     match event {
+                        Event::Key(key)
+                            if key.code == KeyCode::F(2) && key.kind == KeyEventKind::Press =>
+                        {
+                            mouse_capture = Self::toggle_mouse_capture(
+                                &mut terminal,
+                                mouse_capture,
+                                &mut chat_view,
+                            );
+                        }
+                        Event::FocusGained => self.terminal_focused = true,
+                        Event::FocusLost => self.terminal_focused = false,
+                        Event::Mouse(mouse) if !chat_view.permission.is_active() => {
+                            self.handle_mouse_event(mouse, &mut chat_view);
+                        }
+                        Event::Key(key)
+                            if key.code == KeyCode::Char('e')
+                                && key.modifiers == KeyModifiers::CONTROL
+                                && key.kind == KeyEventKind::Press
+                                && chat_view.search.is_none()
+                                && !chat_view.permission.is_active() =>
+                        {
+                            match edit_in_external_editor(&mut terminal, chat_view.input.text()) {
+                                Ok(text) => chat_view.input.set_text(text),
+                                Err(e) => {
+                                    chat_view.set_status(Some(format!("Editor failed: {}", e)))
+                                }
+                            }
+                            chat_view.update_completion();
+                        }
+                        Event::Key(key)
+                            if chat_view.focused_block.is_some()
+                                && !chat_view.permission.is_active()
+                                && key.kind == KeyEventKind::Press
+                                && key.modifiers == KeyModifiers::NONE
+                                && matches!(key.code, KeyCode::Char('y') | KeyCode::Char('d')) =>
+                        {
+                            self.handle_focus_action(key.code, &mut chat_view);
+                        }
+                        Event::Paste(text) => match chat_view.search.as_ref() {
+                            Some(_) => {
+                                for c in text.chars().filter(|c| !c.is_control()) {
+                                    chat_view.search_push_char(c);
+                                }
+                            }
+                            None => {
+                                chat_view.input.insert_str(&text);
+                                chat_view.update_completion();
+                            }
+                        },
                         Event::Key(key) => {
                             if let Some(reason) = self.handle_key_event(
                                 key,
@@ -229,6 +460,7 @@ impl ChatMode {
                                 should_quit = true;
                                 exit_reason = reason;
                             }
+                            chat_view.update_completion();
                         }
                         Event::Resize(_, _) => {}
                         _ => {}
@@ -241,6 +473,9 @@ impl ChatMode {
             }
         }
 
+        if mouse_capture {
+            let _ = set_mouse_capture(&mut terminal, false);
+        }
         restore_terminal(terminal)?;
         chat_view.session.save()?;
         tracing::info!("Session saved");
@@ -249,7 +484,7 @@ impl ChatMode {
     }
 
     fn handle_key_event(
-        &self,
+        &mut self,
         key: KeyEvent,
         chat_view: &mut ChatView,
         pending_response: &mut Option<tokio::task::JoinHandle<Result<()>>>,
@@ -263,6 +498,22 @@ impl ChatMode {
             return Ok(None);
         }
 
+        let is_quit = key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL;
+        if chat_view.permission.is_active() && !is_quit {
+            self.handle_permission_key(key, chat_view, rt_handle);
+            return Ok(None);
+        }
+        if chat_view.search.is_some() && !is_quit {
+            Self::handle_search_key(key, chat_view);
+            return Ok(None);
+        }
+        if chat_view.focused_block.is_some() && !is_quit && Self::handle_card_key(key, chat_view) {
+            return Ok(None);
+        }
+        if chat_view.completion_open() && Self::handle_completion_key(key, chat_view) {
+            return Ok(None);
+        }
+
         match (key.code, key.modifiers) {
             (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
                 tracing::info!("User requested quit");
@@ -279,6 +530,34 @@ impl ChatMode {
                 chat_view.clear_screen();
             }
 
+            (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
+                chat_view.start_search();
+            }
+
+            (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                self.open_model_picker(chat_view);
+            }
+
+            (KeyCode::Tab | KeyCode::BackTab, _)
+                if !chat_view.focus_block(key.code == KeyCode::Tab) =>
+            {
+                chat_view.set_status(Some("No tool cards yet".to_string()));
+            }
+
+            // `/` starts commands in the input, so it only opens search while browsing
+            (KeyCode::Char('/'), KeyModifiers::NONE)
+                if chat_view.browse_mode && chat_view.input.is_empty() =>
+            {
+                chat_view.start_search();
+            }
+
+            // Shift+Enter needs keyboard enhancement support; Alt+Enter works everywhere
+            (KeyCode::Enter, modifiers)
+                if modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) =>
+            {
+                chat_view.input.insert_char('\n');
+            }
+
             (KeyCode::Enter, _) => {
                 if pending_response.is_some() {
                     return Ok(None);
@@ -294,6 +573,8 @@ impl ChatMode {
 
                     chat_view.set_loading(true);
                     chat_view.set_status(Some(format!("{} is thinking...", self.agent_name)));
+                    self.turn_started_at = Some(Instant::now());
+                    self.summary_target = None;
                     chat_view
                         .session
                         .add_message("assistant".to_string(), String::new());
@@ -303,12 +584,13 @@ impl ChatMode {
 
                     let agent = Arc::clone(&self.agent);
                     let input_clone = input.clone();
+                    let mentions = chat_view.take_mentions(&input);
                     let resp_tx = response_tx.clone();
                     let stream_tx_clone = stream_tx.clone();
 
                     let handle_clone = rt_handle.spawn(async move {
                         match agent
-                            .process_message(input_clone, stream_tx_clone.clone())
+                            .process_message(input_clone, mentions, stream_tx_clone.clone())
                             .await
                         {
                             Ok(response) => {
@@ -336,28 +618,32 @@ impl ChatMode {
             }
 
             (KeyCode::Backspace, _) => {
-                chat_view.handle_backspace();
+                chat_view.input.backspace();
+            }
+
+            (KeyCode::Delete, _) => {
+                chat_view.input.delete();
             }
 
             (KeyCode::Left, _) => {
-                chat_view.move_cursor_left();
+                chat_view.input.move_left();
             }
             (KeyCode::Right, _) => {
-                chat_view.move_cursor_right();
+                chat_view.input.move_right();
             }
 
             (KeyCode::Up, _) => {
                 if chat_view.browse_mode {
                     chat_view.scroll_up(1);
                 } else {
-                    chat_view.history_prev();
+                    chat_view.cursor_up();
                 }
             }
             (KeyCode::Down, _) => {
                 if chat_view.browse_mode {
                     chat_view.scroll_down(1);
                 } else {
-                    chat_view.history_next();
+                    chat_view.cursor_down();
                 }
             }
 
@@ -372,19 +658,18 @@ impl ChatMode {
             }
 
             (KeyCode::Home, _) => {
-                chat_view.cursor = 0;
+                chat_view.input.move_line_start();
             }
 
             (KeyCode::End, _) => {
-                chat_view.cursor = chat_view.input.len();
+                chat_view.input.move_line_end();
             }
 
             (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
                 chat_view.input.clear();
-                chat_view.cursor = 0;
             }
 
-            (KeyCode::Char('e'), KeyModifiers::CONTROL) => {
+            (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
                 chat_view.toggle_browse_mode();
                 let status_msg = if chat_view.browse_mode {
                     "Entered browse mode, use ↑↓ or PageUp/PageDown to scroll"
@@ -413,9 +698,7 @@ impl ChatMode {
             }
 
             (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
-                if !c.is_control() && c != '\u{0}' {
-                    chat_view.handle_char(c);
-                }
+                chat_view.input.insert_char(c);
             }
 
             _ => {}
@@ -424,8 +707,256 @@ impl ChatMode {
         Ok(None)
     }
 
-    /// Handle shortcut commands
-    fn handle_command(&self, command: &str, chat_view: &mut ChatView) -> Result<()> {
+    /// Handle keys while a completion popup is open; false lets the key through
+    fn handle_completion_key(key: KeyEvent, chat_view: &mut ChatView) -> bool {
+        let has_candidates = chat_view.completion_has_candidates();
+
+        match key.code {
+            KeyCode::Esc => chat_view.dismiss_completion(),
+            KeyCode::Up if has_candidates => chat_view.completion_step(false),
+            KeyCode::Down if has_candidates => chat_view.completion_step(true),
+            KeyCode::Tab | KeyCode::Enter if has_candidates => chat_view.accept_completion(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handle keys while a tool card is focused; other keys unfocus it and go to the input
+    fn handle_card_key(key: KeyEvent, chat_view: &mut ChatView) -> bool {
+        let page = crate::ui::tool_cards::EXPANDED_CARD_LINES as isize;
+
+        match key.code {
+            KeyCode::Esc => chat_view.unfocus(),
+            KeyCode::Tab => {
+                chat_view.focus_block(true);
+            }
+            KeyCode::BackTab => {
+                chat_view.focus_block(false);
+            }
+            KeyCode::Enter => chat_view.toggle_focused_block(),
+            KeyCode::Up | KeyCode::Char('k') => chat_view.scroll_focused_block(-1),
+            KeyCode::Down | KeyCode::Char('j') => chat_view.scroll_focused_block(1),
+            KeyCode::PageUp => chat_view.scroll_focused_block(-page),
+            KeyCode::PageDown => chat_view.scroll_focused_block(page),
+            _ => {
+                chat_view.unfocus();
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Switch mouse capture away from `enabled`; returns the new state. Terminals
+    /// without mouse support never send events, so capture just stays inert there.
+    fn toggle_mouse_capture(
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        enabled: bool,
+        chat_view: &mut ChatView,
+    ) -> bool {
+        match set_mouse_capture(terminal, !enabled) {
+            Ok(()) if enabled => {
+                chat_view.show_toast(
+                    "Mouse capture off: drag to select text, F2 to turn it back on".to_string(),
+                );
+                false
+            }
+            Ok(()) => {
+                chat_view.show_toast(
+                    "Mouse capture on: wheel scrolls, click focuses (F2 to turn off)".to_string(),
+                );
+                true
+            }
+            Err(e) => {
+                chat_view.set_status(Some(format!("Mouse capture unavailable: {}", e)));
+                enabled
+            }
+        }
+    }
+
+    /// Wheel scrolls the conversation; clicks focus cards and code blocks or open the model picker
+    fn handle_mouse_event(&mut self, mouse: MouseEvent, chat_view: &mut ChatView) {
+        const WHEEL_LINES: usize = 3;
+
+        let (column, row) = (mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::ScrollUp => chat_view.scroll_up(WHEEL_LINES),
+            MouseEventKind::ScrollDown => chat_view.scroll_down(WHEEL_LINES),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if chat_view.on_model_segment(column, row) {
+                    self.open_model_picker(chat_view);
+                } else if let Some(key) = chat_view.block_at(column, row) {
+                    chat_view.click_block(key);
+                } else if chat_view.in_conversation(column, row) {
+                    chat_view.unfocus();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle keys while the permission prompt is open; it takes every key but Ctrl+C
+    fn handle_permission_key(
+        &mut self,
+        key: KeyEvent,
+        chat_view: &mut ChatView,
+        rt_handle: &tokio::runtime::Handle,
+    ) {
+        let choice = match key.code {
+            KeyCode::Left | KeyCode::Up | KeyCode::BackTab => {
+                chat_view.permission.step(false);
+                return;
+            }
+            KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
+                chat_view.permission.step(true);
+                return;
+            }
+            KeyCode::Enter => chat_view.permission.selected(),
+            KeyCode::Char('y') => PermissionChoice::AllowOnce,
+            KeyCode::Char('a') => PermissionChoice::AllowAlways,
+            KeyCode::Char('n') | KeyCode::Esc => PermissionChoice::Deny,
+            _ => return,
+        };
+        let Some((request, choice)) = chat_view.permission.answer(choice) else {
+            return;
+        };
+        let tool_name = request.tool_name().to_string();
+
+        let status = match choice {
+            PermissionChoice::AllowOnce => format!("Allowed {}", tool_name),
+            PermissionChoice::Deny => format!("Denied {}", tool_name),
+            PermissionChoice::AllowAlways => {
+                self.config.permissions.allow_always(&tool_name);
+                // Queued calls of the same tool are covered by the new rule
+                for queued in chat_view.permission.take_tool(&tool_name) {
+                    self.reply_permission(rt_handle, chat_view, queued.tool_id().to_string(), true);
+                }
+                match self.config.save() {
+                    Ok(()) => format!("{} is now always allowed", tool_name),
+                    Err(e) => format!("Allowed {}, but failed to save config: {}", tool_name, e),
+                }
+            }
+        };
+        self.reply_permission(
+            rt_handle,
+            chat_view,
+            request.tool_id().to_string(),
+            choice != PermissionChoice::Deny,
+        );
+        chat_view.set_status(Some(status));
+    }
+
+    /// Confirm or reject a tool waiting for permission
+    fn reply_permission(
+        &self,
+        rt_handle: &tokio::runtime::Handle,
+        chat_view: &ChatView,
+        tool_id: String,
+        allow: bool,
+    ) {
+        let Some(session_id) = chat_view.session.core_session_id.clone() else {
+            tracing::warn!("No session to answer permission request: {}", tool_id);
+            return;
+        };
+        let coordinator = self.coordinator.clone();
+        rt_handle.spawn(async move {
+            let reply = if allow {
+                coordinator.confirm_tool(&session_id, &tool_id, None).await
+            } else {
+                coordinator
+                    .reject_tool(&session_id, &tool_id, "User denied permission".to_string())
+                    .await
+            };
+            if let Err(e) = reply {
+                tracing::warn!("Failed to answer permission request: {}", e);
+            }
+        });
+    }
+
+    /// Copy the focused block (`y`) or save a card's expand state as the tool default (`d`)
+    fn handle_focus_action(&mut self, code: KeyCode, chat_view: &mut ChatView) {
+        let status = match code {
+            KeyCode::Char('y') => {
+                if let Some(text) = chat_view.focused_text() {
+                    Self::copy_text(&text, chat_view);
+                }
+                return;
+            }
+            KeyCode::Char('d') => match chat_view.remember_focused_card_default() {
+                Some((tool_name, expanded)) => {
+                    self.config
+                        .ui
+                        .tool_card_expanded
+                        .insert(tool_name.clone(), expanded);
+                    let state = if expanded { "expanded" } else { "collapsed" };
+                    match self.config.save() {
+                        Ok(()) => format!("{} cards now start {}", tool_name, state),
+                        Err(e) => format!("Failed to save config: {}", e),
+                    }
+                }
+                None => return,
+            },
+            _ => return,
+        };
+        chat_view.set_status(Some(status));
+    }
+
+    /// Copy `text` and report where it went; returns the report
+    fn copy_text(text: &str, chat_view: &mut ChatView) -> String {
+        match clipboard::copy(text) {
+            Ok(CopyTarget::System | CopyTarget::Terminal) => {
+                let report = format!("Copied ({} bytes)", text.len());
+                chat_view.show_toast(report.clone());
+                report
+            }
+            Ok(CopyTarget::File(path)) => {
+                let report = format!("No clipboard available, saved to {}", path.display());
+                chat_view.set_status(Some(report.clone()));
+                report
+            }
+            Err(e) => {
+                let report = format!("Copy failed: {}", e);
+                chat_view.set_status(Some(report.clone()));
+                report
+            }
+        }
+    }
+
+    /// Handle keys while scrollback search is active
+    fn handle_search_key(key: KeyEvent, chat_view: &mut ChatView) {
+        let editing = chat_view.search.as_ref().is_some_and(|s| s.editing);
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => chat_view.exit_search(),
+
+            (KeyCode::Enter, _) if editing => chat_view.search_commit(),
+            (KeyCode::Backspace, _) if editing => chat_view.search_backspace(),
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT)
+                if editing && !c.is_control() =>
+            {
+                chat_view.search_push_char(c);
+            }
+
+            (KeyCode::Char('n'), KeyModifiers::NONE) | (KeyCode::Enter, _) => {
+                chat_view.search_next()
+            }
+            (KeyCode::Char('N'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                chat_view.search_prev()
+            }
+            (KeyCode::Char('/'), KeyModifiers::NONE)
+            | (KeyCode::Char('f'), KeyModifiers::CONTROL) => chat_view.start_search(),
+
+            (KeyCode::Up, _) => chat_view.scroll_up(1),
+            (KeyCode::Down, _) => chat_view.scroll_down(1),
+            (KeyCode::PageUp, _) => chat_view.scroll_up(10),
+            (KeyCode::PageDown, _) => chat_view.scroll_down(10),
+
+            _ => {}
+        }
+    }
+
+    /// Handle slash commands; none of them send a model request
+    fn handle_command(&mut self, command: &str, chat_view: &mut ChatView) -> Result<()> {
+        let command = command.trim();
         let parts: Vec<&str> = command.split_whitespace().collect();
         if parts.is_empty() {
             return Ok(());
@@ -433,50 +964,94 @@ impl ChatMode {
 
         match parts[0] {
             "/help" => {
-                chat_view.add_message(
-                    "system".to_string(),
-                    "Available commands:\n\
-                     /help - Show help\n\
-                     /clear - Clear conversation\n\
-                     /agents - List available agents\n\
-                     /switch <agent> - Switch agent\n\
-                     /history - Show history\n\
-                     /export - Export session"
-                        .to_string(),
-                );
+                let help = chat_view
+                    .commands()
+                    .iter()
+                    .map(|cmd| {
+                        if cmd.usage.is_empty() {
+                            format!("{} - {}", cmd.name, cmd.description)
+                        } else {
+                            format!("{} {} - {}", cmd.name, cmd.usage, cmd.description)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                chat_view.add_command_output(command, &format!("Available commands:\n{}", help));
             }
             "/clear" => {
                 chat_view.clear_screen();
                 chat_view.set_status(Some("Conversation cleared".to_string()));
             }
+            "/model" => self.handle_model_command(command, parts.get(1).copied(), chat_view),
+            "/session" => self.handle_session_command(command, &parts[1..], chat_view)?,
+            "/compact" => match chat_view.session.core_session_id.clone() {
+                Some(session_id) => {
+                    let coordinator = self.coordinator.clone();
+                    self.spawn_command(command, async move {
+                        coordinator.request_compaction(&session_id).await?;
+                        Ok("Context will be compressed on your next message".to_string())
+                    });
+                }
+                None => chat_view.add_command_output(command, "Nothing to compact yet"),
+            },
+            "/usage" => {
+                let metadata = &chat_view.session.metadata;
+                let mut output = format!(
+                    "Tokens: {} input, {} output ({} total)\nMessages: {}",
+                    metadata.input_tokens,
+                    metadata.output_tokens,
+                    metadata.input_tokens + metadata.output_tokens,
+                    metadata.message_count
+                );
+                match chat_view.spend {
+                    Some((session_usd, today_usd)) => output.push_str(&format!(
+                        "\nSpend: ${:.4} this session, ${:.2} today",
+                        session_usd, today_usd
+                    )),
+                    None => output.push_str("\nSpend: not reported yet"),
+                }
+                chat_view.add_command_output(command, &output);
+            }
+            "/mcp" => self.handle_mcp_command(command, &parts[1..], chat_view),
+            "/env" => self.handle_env_command(command, &parts[1..], chat_view),
+            "/summary" => self.handle_summary_command(command, parts.get(1).copied(), chat_view),
+            "/export" => self.handle_export_command(command, &parts[1..], chat_view),
+            "/workspace" => self.handle_workspace_command(command, &parts[1..], chat_view),
+            "/theme" => self.handle_theme_command(command, parts.get(1).copied(), chat_view),
+            "/copy" => {
+                let output = match (parts.get(1).copied(), chat_view.last_code_block()) {
+                    (Some("last-code"), Some(block)) => Self::copy_text(&block.code, chat_view),
+                    (Some("last-code"), None) => "No code block to copy yet".to_string(),
+                    _ => "Usage: /copy last-code".to_string(),
+                };
+                chat_view.add_command_output(command, &output);
+            }
             "/agents" => {
-                chat_view.add_message(
-                    "system".to_string(),
+                chat_view.add_command_output(
+                    command,
                     "Available Agents:\n\
                      • agentic - General purpose agent\n\
                      • code-writer - Code writing expert\n\
                      • test-writer - Test writing expert\n\
                      • docs-writer - Documentation expert\n\
                      • rust-specialist - Rust expert\n\
-                     • visual-debugger - Visual debugging expert"
-                        .to_string(),
+                     • visual-debugger - Visual debugging expert",
                 );
             }
             "/switch" => {
                 if parts.len() > 1 {
-                    chat_view.add_message(
-                        "system".to_string(),
-                        format!("Warning: Agent switching feature coming soon\nTip: Use `bitfun chat --agent {}` to start a new session", parts[1]),
+                    chat_view.add_command_output(
+                        command,
+                        &format!("Warning: Agent switching feature coming soon\nTip: Use `bitfun chat --agent {}` to start a new session", parts[1]),
                     );
                 } else {
-                    chat_view
-                        .add_message("system".to_string(), "Usage: /switch <agent>".to_string());
+                    chat_view.add_command_output(command, "Usage: /switch <agent>");
                 }
             }
             "/history" => {
-                chat_view.add_message(
-                    "system".to_string(),
-                    format!(
+                chat_view.add_command_output(
+                    command,
+                    &format!(
                         "Current session statistics:\n\
                              • Messages: {}\n\
                              • Tool calls: {}\n\
@@ -487,19 +1062,10 @@ impl ChatMode {
                     ),
                 );
             }
-            "/export" => {
-                chat_view.add_message(
-                    "system".to_string(),
-                    format!(
-                        "Session auto-saved to: ~/.config/bitfun/sessions/{}.json",
-                        chat_view.session.id
-                    ),
-                );
-            }
             _ => {
-                chat_view.add_message(
-                    "system".to_string(),
-                    format!(
+                chat_view.add_command_output(
+                    command,
+                    &format!(
                         "Unknown command: {}\nUse /help to see available commands",
                         parts[0]
                     ),
@@ -509,4 +1075,504 @@ impl ChatMode {
 
         Ok(())
     }
+
+    /// Run a command in the background; its output is shown when it finishes
+    fn spawn_command<F>(&self, command: &str, task: F)
+    where
+        F: std::future::Future<Output = Result<String>> + Send + 'static,
+    {
+        let command = command.to_string();
+        let command_tx = self.command_tx.clone();
+        tokio::runtime::Handle::current().spawn(async move {
+            let output = task.await.unwrap_or_else(|e| format!("Error: {}", e));
+            let _ = command_tx.send((command, output));
+        });
+    }
+
+    /// `/workspace [path]`: list recent workspaces or switch to another one
+    fn handle_workspace_command(&self, command: &str, args: &[&str], chat_view: &mut ChatView) {
+        if args.is_empty() {
+            let current = self.workspace_path.clone();
+            self.spawn_command(command, async move {
+                let now = chrono::Utc::now();
+                let mut output = format!(
+                    "Current workspace: {}",
+                    current
+                        .as_ref()
+                        .map_or("none".to_string(), |path| path.display().to_string())
+                );
+                let recent = workspace::recent().await;
+                if !recent.is_empty() {
+                    output.push_str("\nRecent workspaces:");
+                }
+                for recent in recent {
+                    let git = match &recent.git {
+                        Some(git) if git.dirty => format!("  [{}*]", git.branch),
+                        Some(git) => format!("  [{}]", git.branch),
+                        None => String::new(),
+                    };
+                    output.push_str(&format!(
+                        "\n  {}{}  {}",
+                        recent.path,
+                        git,
+                        workspace::format_age(recent.last_opened, now)
+                    ));
+                }
+                output.push_str("\nSwitch with: /workspace <path>");
+                Ok(output)
+            });
+            return;
+        }
+        if chat_view.loading {
+            chat_view.add_command_output(
+                command,
+                "Wait for the current turn to finish before switching workspaces",
+            );
+            return;
+        }
+
+        let path = workspace::expand_path(&args.join(" "), self.workspace_path.as_deref());
+        let command = command.to_string();
+        let workspace_tx = self.workspace_tx.clone();
+        tokio::runtime::Handle::current().spawn(async move {
+            let opened = workspace::open(&path).await.map_err(|e| format!("{:#}", e));
+            let _ = workspace_tx.send((command, opened));
+        });
+    }
+
+    /// Rebind the agent to `root` and continue in a new session there
+    fn switch_workspace(&mut self, command: &str, root: PathBuf, chat_view: &mut ChatView) {
+        if let Err(e) = chat_view.session.save() {
+            tracing::warn!("Failed to save session before switching workspace: {}", e);
+        }
+        self.agent.set_workspace(Some(root.clone()));
+        chat_view.start_workspace_session(Session::new(
+            self.agent_name.clone(),
+            Some(root.to_string_lossy().to_string()),
+        ));
+        chat_view.add_command_output(
+            command,
+            &format!(
+                "Switched to workspace {}; started a new session",
+                root.display()
+            ),
+        );
+        self.workspace_path = Some(root);
+    }
+
+    /// List the configured models and start a `/model` command to pick one
+    fn open_model_picker(&self, chat_view: &mut ChatView) {
+        self.handle_model_command("/model", None, chat_view);
+        chat_view.input.set_text("/model ".to_string());
+    }
+
+    /// Resolve the primary model for the status line
+    fn refresh_active_model(&self) {
+        let model_tx = self.model_tx.clone();
+        tokio::runtime::Handle::current().spawn(async move {
+            use bitfun_core::service::config::types::GlobalConfig;
+
+            let Ok(config_service) = config::get_global_config_service().await else {
+                return;
+            };
+            let (Ok(models), Ok(global_config)) = (
+                config_service.get_available_models().await,
+                config_service.get_config::<GlobalConfig>(None).await,
+            ) else {
+                return;
+            };
+            let primary = global_config.ai.default_models.primary.unwrap_or_default();
+            if let Some(model) = models.into_iter().find(|model| model.id == primary) {
+                let _ = model_tx.send((model.name, Some(u64::from(model.context_window))));
+            }
+        });
+    }
+
+    /// `/model [name]`: list configured models or make one the primary model
+    fn handle_model_command(&self, command: &str, name: Option<&str>, chat_view: &ChatView) {
+        let name = name.map(str::to_string);
+        let core_session_id = chat_view.session.core_session_id.clone();
+        let coordinator = self.coordinator.clone();
+        let model_tx = self.model_tx.clone();
+
+        self.spawn_command(command, async move {
+            use bitfun_core::service::config::types::GlobalConfig;
+
+            let config_service = config::get_global_config_service().await?;
+            let models = config_service.get_available_models().await?;
+
+            let Some(name) = name else {
+                let global_config = config_service.get_config::<GlobalConfig>(None).await?;
+                let primary = global_config.ai.default_models.primary.unwrap_or_default();
+                let mut output = String::from("Configured models:");
+                for model in models.iter().filter(|model| model.enabled) {
+                    let marker = if model.id == primary { "*" } else { " " };
+                    output.push_str(&format!(
+                        "\n{} {} ({}, {})  {}",
+                        marker,
+                        model.name,
+                        model.model_name,
+                        model.provider,
+                        model_badges(model)
+                    ));
+                }
+                output.push_str("\nUsage: /model <name>");
+                return Ok(output);
+            };
+
+            let model = models
+                .into_iter()
+                .filter(|model| model.enabled)
+                .find(|model| {
+                    model.id == name
+                        || model.name.eq_ignore_ascii_case(&name)
+                        || model.model_name.eq_ignore_ascii_case(&name)
+                })
+                .ok_or_else(|| anyhow::anyhow!("No enabled model named '{}'", name))?;
+
+            config_service
+                .set_config("ai.default_models.primary", &Some(model.id.clone()))
+                .await?;
+            if let Some(session_id) = core_session_id {
+                coordinator
+                    .update_session_model(&session_id, &model.id)
+                    .await?;
+            }
+            let _ = model_tx.send((model.name.clone(), Some(u64::from(model.context_window))));
+
+            Ok(format!(
+                "Active model: {} ({})",
+                model.name, model.model_name
+            ))
+        });
+    }
+
+    /// `/session new|list|rename <title>`
+    fn handle_session_command(
+        &self,
+        command: &str,
+        args: &[&str],
+        chat_view: &mut ChatView,
+    ) -> Result<()> {
+        match args.first().copied() {
+            Some("new") => {
+                chat_view.session.save()?;
+                self.agent.reset_session();
+                chat_view.clear_screen();
+                chat_view.session = Session::new(
+                    self.agent_name.clone(),
+                    self.workspace_path
+                        .as_ref()
+                        .map(|path| path.to_string_lossy().to_string()),
+                );
+                chat_view.spend = None;
+                chat_view.status_line.reset_usage();
+                chat_view.add_command_output(command, "Started a new session");
+            }
+            Some("list") => {
+                let current_id = chat_view.session.id.clone();
+                let output = Session::list_all()?
+                    .iter()
+                    .take(10)
+                    .map(|info| {
+                        let marker = if info.id == current_id { "*" } else { " " };
+                        format!(
+                            "{} {}  {}  ({} messages, {})",
+                            marker,
+                            &info.id[..8.min(info.id.len())],
+                            info.title,
+                            info.message_count,
+                            info.updated_at.format("%m-%d %H:%M")
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let output = if output.is_empty() {
+                    "No saved sessions".to_string()
+                } else {
+                    format!(
+                        "Recent sessions:\n{}\nResume with: bitfun chat --resume <id>",
+                        output
+                    )
+                };
+                chat_view.add_command_output(command, &output);
+            }
+            Some("rename") if args.len() > 1 => {
+                chat_view.session.title = args[1..].join(" ");
+                chat_view.session.save()?;
+                let output = format!("Session renamed to \"{}\"", chat_view.session.title);
+                chat_view.add_command_output(command, &output);
+            }
+            _ => chat_view.add_command_output(command, "Usage: /session new|list|rename <title>"),
+        }
+        Ok(())
+    }
+
+    /// `/env [set <name> <value>|unset <name>]`: list or change the session's
+    /// variables for Bash commands; values may be `keyring:<name>` references
+    fn handle_env_command(&self, command: &str, args: &[&str], chat_view: &mut ChatView) {
+        let Some(session_id) = chat_view.session.core_session_id.clone() else {
+            chat_view.add_command_output(
+                command,
+                "No session yet; send a message first (new sessions start with the workspace's workspace.env)",
+            );
+            return;
+        };
+        let change = match args {
+            [] | ["list"] => None,
+            ["set", name, value @ ..] if !value.is_empty() => {
+                Some((name.to_string(), Some(value.join(" "))))
+            }
+            ["unset", name] => Some((name.to_string(), None)),
+            _ => {
+                chat_view
+                    .add_command_output(command, "Usage: /env [set <name> <value>|unset <name>]");
+                return;
+            }
+        };
+
+        let coordinator = self.coordinator.clone();
+        self.spawn_command(command, async move {
+            let mut env = coordinator
+                .get_session_manager()
+                .get_session(&session_id)
+                .map(|session| session.config.env)
+                .unwrap_or_default();
+
+            if let Some((name, value)) = change {
+                let output = match value {
+                    Some(value) => {
+                        env.insert(name.clone(), value);
+                        format!("Set {} for the next commands", name)
+                    }
+                    None if env.remove(&name).is_some() => {
+                        format!("Unset {} for the next commands", name)
+                    }
+                    None => return Ok(format!("{} is not set", name)),
+                };
+                coordinator.update_session_env(&session_id, env).await?;
+                return Ok(output);
+            }
+
+            if env.is_empty() {
+                return Ok("No session environment variables".to_string());
+            }
+            let lines = env
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>();
+            Ok(format!("Session environment:\n{}", lines.join("\n")))
+        });
+    }
+
+    /// `/summary [on|off]`: expand or collapse turn summaries, or turn them on or off for the session
+    fn handle_summary_command(&self, command: &str, arg: Option<&str>, chat_view: &mut ChatView) {
+        let skip = match arg {
+            None => {
+                let output = if chat_view.toggle_turn_summaries() {
+                    "Turn summaries expanded"
+                } else {
+                    "Turn summaries collapsed"
+                };
+                chat_view.add_command_output(command, output);
+                return;
+            }
+            Some("on") => false,
+            Some("off") => true,
+            Some(_) => {
+                chat_view.add_command_output(command, "Usage: /summary [on|off]");
+                return;
+            }
+        };
+        let Some(session_id) = chat_view.session.core_session_id.clone() else {
+            chat_view.add_command_output(command, "No session yet; send a message first");
+            return;
+        };
+
+        let coordinator = self.coordinator.clone();
+        self.spawn_command(command, async move {
+            coordinator
+                .update_session_skip_turn_summary(&session_id, skip)
+                .await?;
+            if skip {
+                return Ok("Turns in this session won't be summarized".to_string());
+            }
+            let session = coordinator.get_session_manager().get_session(&session_id);
+            let enabled = match session {
+                Some(session) => turn_summary::is_enabled(&session.config).await,
+                None => false,
+            };
+            Ok(if enabled {
+                "Turns in this session will be summarized".to_string()
+            } else {
+                "Turn summaries are allowed for this session; turn on app.ai_experience.enable_turn_summary to get them".to_string()
+            })
+        });
+    }
+
+    /// `/mcp list|restart <id>`
+    fn handle_mcp_command(&self, command: &str, args: &[&str], chat_view: &mut ChatView) {
+        let Some(mcp_service) = self.mcp_service.clone() else {
+            chat_view.add_command_output(command, "MCP service is not available");
+            return;
+        };
+
+        match (args.first().copied(), args.get(1)) {
+            (Some("list") | None, _) => self.spawn_command(command, async move {
+                let configs = mcp_service.config_service().load_all_configs().await?;
+                let statuses: std::collections::HashMap<_, _> = mcp_service
+                    .server_manager()
+                    .get_all_server_statuses()
+                    .await
+                    .into_iter()
+                    .collect();
+
+                if configs.is_empty() {
+                    return Ok("No MCP servers configured".to_string());
+                }
+                let lines = configs
+                    .iter()
+                    .map(|server| {
+                        let status = match statuses.get(&server.id) {
+                            _ if !server.enabled => "disabled".to_string(),
+                            Some(status) => format!("{:?}", status),
+                            None => "not started".to_string(),
+                        };
+                        format!("• {} ({}) - {}", server.id, server.name, status)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(format!("MCP servers:\n{}", lines.join("\n")))
+            }),
+            (Some("restart"), Some(id)) => {
+                let id = id.to_string();
+                self.spawn_command(command, async move {
+                    mcp_service.server_manager().restart_server(&id).await?;
+                    let status = mcp_service.server_manager().get_server_status(&id).await?;
+                    Ok(format!("Restarted {} ({:?})", id, status))
+                });
+            }
+            _ => chat_view.add_command_output(command, "Usage: /mcp list|restart <id>"),
+        }
+    }
+
+    /// `/theme [name]`: list themes or switch to one and remember it
+    fn handle_theme_command(
+        &mut self,
+        command: &str,
+        name: Option<&str>,
+        chat_view: &mut ChatView,
+    ) {
+        let Some(name) = name else {
+            let current = &chat_view.theme.name;
+            let themes = Theme::available()
+                .iter()
+                .map(|theme| {
+                    let marker = if theme == current { "*" } else { " " };
+                    format!("{} {}", marker, theme)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            chat_view.add_command_output(
+                command,
+                &format!("Themes:\n{}\nUsage: /theme <name>", themes),
+            );
+            return;
+        };
+
+        let output = match Theme::load(name) {
+            Ok(theme) => {
+                chat_view.set_theme(theme);
+                self.config.ui.theme = name.to_string();
+                match self.config.save() {
+                    Ok(()) => format!("Theme set to {}", name),
+                    Err(e) => format!("Theme set to {} (failed to save config: {})", name, e),
+                }
+            }
+            Err(e) => format!("Error: {}", e),
+        };
+        chat_view.add_command_output(command, &output);
+    }
+
+    /// `/export [markdown|html] <path>`: write the conversation to a file
+    ///
+    /// Without a format the extension of `path` decides (Markdown by default).
+    fn handle_export_command(&self, command: &str, args: &[&str], chat_view: &mut ChatView) {
+        let (format, path_args) = match args.split_first() {
+            Some((name, rest)) if !rest.is_empty() => match ExportFormat::from_name(name) {
+                Some(format) => (Some(format), rest),
+                None => (None, args),
+            },
+            _ => (None, args),
+        };
+        if path_args.is_empty() {
+            chat_view.add_command_output(
+                command,
+                &format!(
+                    "Usage: /export [markdown|html] <path>\nSession auto-saved to: ~/.config/bitfun/sessions/{}.json",
+                    chat_view.session.id
+                ),
+            );
+            return;
+        }
+
+        let mut target = PathBuf::from(path_args.join(" "));
+        if target.is_relative() {
+            let base = self
+                .workspace_path
+                .clone()
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_default();
+            target = base.join(target);
+        }
+        let format = format.unwrap_or_else(|| ExportFormat::from_path(&target));
+
+        let metadata = &chat_view.session.metadata;
+        let usage = Usage {
+            input_tokens: metadata.input_tokens,
+            output_tokens: metadata.output_tokens,
+            cost_usd: chat_view.spend.map(|(session_usd, _)| session_usd),
+        };
+        let output =
+            match export_session(&chat_view.session, usage, format, &target, &chat_view.theme) {
+                Ok(images) => {
+                    let mut output = format!(
+                        "Exported {} messages to {}",
+                        chat_view.session.messages.len(),
+                        target.display()
+                    );
+                    if images > 0 {
+                        output.push_str(&format!(" ({} images copied alongside)", images));
+                    }
+                    output
+                }
+                Err(e) => format!("Export failed: {:#}", e),
+            };
+        chat_view.add_command_output(command, &output);
+    }
+}
+
+/// Capability badges, context window and prices shown next to a model in `/model`
+fn model_badges(model: &AvailableModel) -> String {
+    let capabilities = [
+        (model.supports_tools, "tools"),
+        (model.supports_vision, "vision"),
+        (model.supports_reasoning, "reasoning"),
+    ]
+    .iter()
+    .filter(|(supported, _)| *supported)
+    .map(|(_, badge)| *badge)
+    .collect::<Vec<_>>();
+
+    let mut badges = format!(
+        "[{}] {} ctx",
+        capabilities.join(" "),
+        format_tokens(u64::from(model.context_window))
+    );
+    if let Some(pricing) = &model.pricing {
+        badges.push_str(&format!(
+            ", ${}/${} per MTok",
+            pricing.input_per_mtok, pricing.output_per_mtok
+        ));
+    }
+    badges
 }
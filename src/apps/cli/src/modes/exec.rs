@@ -2,6 +2,7 @@ use crate::agent::{
     agentic_system::AgenticSystem, core_adapter::CoreAgentAdapter, Agent, AgentEvent,
 };
 use crate::config::CliConfig;
+use crate::modes::review;
 /// Exec mode implementation
 ///
 /// Single command execution mode
@@ -18,6 +19,8 @@ pub struct ExecMode {
     workspace_path: Option<PathBuf>,
     /// None: no patch output, Some("-"): output to stdout, Some(path): save to file
     output_patch: Option<String>,
+    /// Apply the suggested patches of review findings
+    apply_fixes: bool,
 }
 
 impl ExecMode {
@@ -34,6 +37,7 @@ impl ExecMode {
             agent_type,
             agentic_system.coordinator.clone(),
             agentic_system.event_queue.clone(),
+            agentic_system.event_router.clone(),
             workspace_path.clone(),
         )) as Arc<dyn Agent>;
 
@@ -43,9 +47,16 @@ impl ExecMode {
             agent,
             workspace_path,
             output_patch,
+            apply_fixes: false,
         }
     }
 
+    /// Apply the suggested patches of the review findings once the turn ends
+    pub fn with_apply_fixes(mut self, apply_fixes: bool) -> Self {
+        self.apply_fixes = apply_fixes;
+        self
+    }
+
     fn get_git_diff(&self) -> Option<String> {
         let workspace = self.workspace_path.as_ref()?;
 
@@ -83,7 +94,9 @@ impl ExecMode {
         let agent = self.agent.clone();
         let message = self.message.clone();
 
-        let handle = tokio::spawn(async move { agent.process_message(message, event_tx).await });
+        let handle =
+            tokio::spawn(async move { agent.process_message(message, Vec::new(), event_tx).await });
+        let mut review_report = None;
 
         while let Some(event) = event_rx.recv().await {
             match event {
@@ -118,6 +131,22 @@ impl ExecMode {
                         println!("   [x] {}: {}", tool_name, result);
                     }
                 }
+                AgentEvent::PermissionRequest { tool_name, .. } => {
+                    println!(
+                        "   Waiting for permission: {} (approve it in chat mode, or use --print with --allow)",
+                        tool_name
+                    );
+                }
+                AgentEvent::SessionStarted(_)
+                | AgentEvent::TokenUsage { .. }
+                | AgentEvent::ContextUsage { .. }
+                | AgentEvent::Phase(_)
+                | AgentEvent::SpendUpdated { .. }
+                | AgentEvent::TurnSummary(_) => {}
+                AgentEvent::ReviewFindings(report) => {
+                    println!("\n{}", review::format_findings(&report));
+                    review_report = Some(report);
+                }
                 AgentEvent::Done => {
                     println!("\n");
                     break;
@@ -155,6 +184,18 @@ impl ExecMode {
             }
         }
 
+        if self.apply_fixes {
+            match (&review_report, &self.workspace_path) {
+                (Some(report), Some(workspace)) => {
+                    println!("\n--- Applying Suggested Fixes ---");
+                    let applied = review::apply_suggested_patches(report, workspace).await;
+                    println!("{} fixes applied", applied);
+                }
+                (None, _) => println!("\n(No review findings to apply)"),
+                (_, None) => eprintln!("Warning: No workspace, cannot apply fixes"),
+            }
+        }
+
         if let Some(ref output_target) = self.output_patch {
             println!("\n--- Generating Patch ---");
             if let Some(patch) = self.get_git_diff() {
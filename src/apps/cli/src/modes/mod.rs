@@ -1,3 +1,5 @@
 /// Different interaction modes
 pub mod chat;
 pub mod exec;
+pub mod print;
+pub mod review;
@@ -0,0 +1,254 @@
+/// Print mode implementation
+///
+/// Runs one prompt without the TUI for scripts and CI: progress goes to
+/// stderr, the final answer (or a JSON envelope) to stdout.
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::agent::{
+    agentic_system::AgenticSystem, core_adapter::CoreAgentAdapter, tool_policy::ToolPolicy, Agent,
+    AgentEvent,
+};
+use crate::session::ToolCall;
+use bitfun_core::agentic::coordination::ConversationCoordinator;
+
+/// Exit code when the turn fails
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code when `--timeout` expires (as with timeout(1))
+pub const EXIT_TIMEOUT: i32 = 124;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Final answer only
+    Text,
+    /// Envelope with the answer, tool calls and usage
+    Json,
+}
+
+/// Print mode options from the command line
+#[derive(Debug, Clone)]
+pub struct PrintOptions {
+    pub output_format: OutputFormat,
+    pub tool_policy: ToolPolicy,
+    pub max_turns: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+pub struct PrintMode {
+    message: String,
+    agent: Arc<dyn Agent>,
+    coordinator: Arc<ConversationCoordinator>,
+    options: PrintOptions,
+}
+
+/// What the turn produced
+#[derive(Debug, Default)]
+struct Outcome {
+    text: String,
+    tool_calls: Vec<ToolCall>,
+    session_id: Option<String>,
+    input_tokens: u64,
+    output_tokens: u64,
+    cost_usd: Option<f64>,
+    error: Option<String>,
+    timed_out: bool,
+}
+
+impl PrintMode {
+    pub fn new(
+        message: String,
+        agent_type: String,
+        agentic_system: &AgenticSystem,
+        workspace_path: Option<PathBuf>,
+        options: PrintOptions,
+    ) -> Self {
+        let agent = CoreAgentAdapter::new(
+            agent_type,
+            agentic_system.coordinator.clone(),
+            agentic_system.event_queue.clone(),
+            agentic_system.event_router.clone(),
+            workspace_path,
+        )
+        .with_tool_policy(options.tool_policy.clone())
+        .with_max_rounds(options.max_turns);
+
+        Self {
+            message,
+            agent: Arc::new(agent),
+            coordinator: agentic_system.coordinator.clone(),
+            options,
+        }
+    }
+
+    /// Run the prompt and print the result; returns the process exit code
+    pub async fn run(&self) -> Result<i32> {
+        tracing::info!("Print mode, Agent: {}", self.agent.name());
+
+        let outcome = self.execute().await;
+        match self.options.output_format {
+            OutputFormat::Text => match &outcome.error {
+                None => println!("{}", outcome.text.trim_end()),
+                Some(error) => eprintln!("Error: {}", error),
+            },
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&envelope(&outcome))?)
+            }
+        }
+
+        Ok(if outcome.timed_out {
+            EXIT_TIMEOUT
+        } else if outcome.error.is_some() {
+            EXIT_FAILURE
+        } else {
+            0
+        })
+    }
+
+    async fn execute(&self) -> Outcome {
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let agent = self.agent.clone();
+        let message = self.message.clone();
+        let mut handle =
+            tokio::spawn(async move { agent.process_message(message, Vec::new(), event_tx).await });
+
+        let mut outcome = Outcome::default();
+        let deadline = self
+            .options
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+
+        loop {
+            let event = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, event_rx.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        self.cancel(&mut outcome).await;
+                        break;
+                    }
+                },
+                None => event_rx.recv().await,
+            };
+            let Some(event) = event else {
+                break;
+            };
+
+            match event {
+                AgentEvent::Thinking => eprintln!("Thinking..."),
+                AgentEvent::TextChunk(chunk) => outcome.text.push_str(&chunk),
+                AgentEvent::ToolCallStart {
+                    tool_name,
+                    parameters,
+                } => eprintln!("Tool call: {} {}", tool_name, parameters),
+                AgentEvent::ToolCallProgress { message, .. } => {
+                    eprintln!("   In progress: {}", message)
+                }
+                AgentEvent::ToolCallComplete {
+                    tool_name, success, ..
+                } => eprintln!("   [{}] {}", if success { "+" } else { "x" }, tool_name),
+                AgentEvent::SessionStarted(session_id) => outcome.session_id = Some(session_id),
+                AgentEvent::TokenUsage {
+                    input_tokens,
+                    output_tokens,
+                } => {
+                    outcome.input_tokens += input_tokens;
+                    outcome.output_tokens += output_tokens;
+                }
+                AgentEvent::SpendUpdated { session_usd, .. } => {
+                    outcome.cost_usd = Some(session_usd)
+                }
+                // The tool policy answers confirmations, so none are forwarded here
+                AgentEvent::PermissionRequest { .. } => {}
+                AgentEvent::ContextUsage { .. }
+                | AgentEvent::Phase(_)
+                | AgentEvent::ReviewFindings(_)
+                | AgentEvent::TurnSummary(_) => {}
+                AgentEvent::Done => break,
+                AgentEvent::Error(error) => {
+                    outcome.error = Some(error);
+                    break;
+                }
+            }
+        }
+
+        let result = if outcome.timed_out {
+            // The cancelled turn normally ends within the cancel wait
+            match tokio::time::timeout(Duration::from_secs(5), &mut handle).await {
+                Ok(result) => result,
+                Err(_) => {
+                    handle.abort();
+                    outcome.error = Some(self.timeout_message());
+                    return outcome;
+                }
+            }
+        } else {
+            handle.await
+        };
+        match result {
+            Ok(Ok(response)) => {
+                outcome.tool_calls = response.tool_calls;
+                if !response.success && outcome.error.is_none() {
+                    outcome.error = Some("Execution failed".to_string());
+                }
+            }
+            Ok(Err(e)) => outcome.error = Some(e.to_string()),
+            Err(e) => outcome.error = Some(format!("Task failed: {}", e)),
+        }
+        if outcome.timed_out {
+            outcome.error = Some(self.timeout_message());
+        }
+        outcome
+    }
+
+    /// Stop the running turn after the timeout expired
+    async fn cancel(&self, outcome: &mut Outcome) {
+        outcome.timed_out = true;
+        eprintln!("{}", self.timeout_message());
+        if let Some(session_id) = &outcome.session_id {
+            if let Err(e) = self
+                .coordinator
+                .cancel_active_turn_for_session(session_id, Duration::from_secs(5))
+                .await
+            {
+                tracing::warn!("Failed to cancel timed out turn: {}", e);
+            }
+        }
+    }
+
+    fn timeout_message(&self) -> String {
+        let secs = self.options.timeout.unwrap_or_default().as_secs();
+        format!("Timed out after {}s", secs)
+    }
+}
+
+/// JSON output: final text, tool calls made and usage
+fn envelope(outcome: &Outcome) -> serde_json::Value {
+    let tool_calls: Vec<serde_json::Value> = outcome
+        .tool_calls
+        .iter()
+        .map(|tool_call| {
+            serde_json::json!({
+                "name": tool_call.tool_name,
+                "parameters": tool_call.parameters,
+                "status": tool_call.status,
+                "result": tool_call.result,
+                "duration_ms": tool_call.duration_ms,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "success": outcome.error.is_none(),
+        "result": outcome.text,
+        "error": outcome.error,
+        "session_id": outcome.session_id,
+        "tool_calls": tool_calls,
+        "usage": {
+            "input_tokens": outcome.input_tokens,
+            "output_tokens": outcome.output_tokens,
+            "cost_usd": outcome.cost_usd,
+        },
+    })
+}
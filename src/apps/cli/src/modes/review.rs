@@ -0,0 +1,145 @@
+/// Review findings output
+///
+/// `bitfun review` and `bitfun exec --agent Review` print the findings the
+/// Review agent submits as a list grouped by file, and can apply their
+/// suggested patches to the workspace.
+use bitfun_core::agentic::tools::implementations::apply_patch_tool::apply_patch_in_workspace;
+use bitfun_core::agentic::tools::implementations::review_findings_tool::{
+    ReviewFinding, ReviewReport,
+};
+use bitfun_core::agentic::workspace::LocalWorkspaceFs;
+use std::path::Path;
+
+/// Agent that produces review findings
+pub const REVIEW_AGENT: &str = "Review";
+
+/// Message that starts a review of `range`, or of the working tree when `None`
+pub fn review_request(range: Option<&str>) -> String {
+    match range {
+        Some(range) => format!(
+            "Review the changes in the git ref range `{}` and submit your findings.",
+            range
+        ),
+        None => "Review the uncommitted changes in the working tree and submit your findings."
+            .to_string(),
+    }
+}
+
+fn location(finding: &ReviewFinding) -> String {
+    match (finding.start_line, finding.end_line) {
+        (Some(start), Some(end)) if end > start => format!("{}:{}-{}", finding.file, start, end),
+        (Some(start), _) => format!("{}:{}", finding.file, start),
+        _ => finding.file.clone(),
+    }
+}
+
+/// Findings grouped by file (files in submission order), most severe first
+pub fn format_findings(report: &ReviewReport) -> String {
+    let mut files: Vec<&str> = Vec::new();
+    for finding in &report.findings {
+        if !files.contains(&finding.file.as_str()) {
+            files.push(&finding.file);
+        }
+    }
+
+    let mut out = String::from("Review findings\n");
+    if !report.summary.is_empty() {
+        out.push_str(&format!("{}\n", report.summary));
+    }
+    if report.findings.is_empty() {
+        out.push_str("\nNo findings\n");
+        return out;
+    }
+
+    for file in files {
+        let mut findings: Vec<&ReviewFinding> = report
+            .findings
+            .iter()
+            .filter(|finding| finding.file == file)
+            .collect();
+        findings.sort_by_key(|finding| (finding.severity, finding.start_line));
+
+        out.push_str(&format!("\n{}\n", file));
+        for finding in findings {
+            let line = match (finding.start_line, finding.end_line) {
+                (Some(start), Some(end)) if end > start => format!("{}-{}", start, end),
+                (Some(start), _) => start.to_string(),
+                _ => "-".to_string(),
+            };
+            out.push_str(&format!(
+                "  {:<8} {:>7}  [{}] {}{}\n",
+                finding.severity.as_str(),
+                line,
+                finding.category,
+                finding.message,
+                if finding.suggested_patch.is_some() {
+                    " (fix available)"
+                } else {
+                    ""
+                }
+            ));
+        }
+    }
+
+    let counts: Vec<String> = report
+        .severity_counts()
+        .iter()
+        .map(|(severity, count)| format!("{} {}", count, severity.as_str()))
+        .collect();
+    out.push_str(&format!(
+        "\n{} findings: {}\n",
+        report.findings.len(),
+        counts.join(", ")
+    ));
+    out
+}
+
+/// Applies every suggested patch below `root`; a patch that doesn't apply is
+/// reported and skipped. Returns the number of patches applied.
+pub async fn apply_suggested_patches(report: &ReviewReport, root: &Path) -> usize {
+    let mut applied = 0;
+    for finding in &report.findings {
+        let Some(patch) = &finding.suggested_patch else {
+            continue;
+        };
+        match apply_patch_in_workspace(&LocalWorkspaceFs, root, patch).await {
+            Ok(_) => {
+                applied += 1;
+                println!("   [+] Applied fix for {}", location(finding));
+            }
+            Err(e) => println!("   [x] Fix for {} not applied: {}", location(finding), e),
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_findings;
+    use bitfun_core::agentic::tools::implementations::review_findings_tool::ReviewReport;
+    use serde_json::json;
+
+    #[test]
+    fn groups_findings_by_file_and_severity() {
+        let report = ReviewReport::from_tool_input(
+            &json!({
+                "summary": "Two problems",
+                "findings": [
+                    { "file": "src/a.rs", "start_line": 9, "severity": "low", "category": "style", "message": "naming" },
+                    { "file": "src/b.rs", "severity": "info", "category": "tests", "message": "no test" },
+                    { "file": "src/a.rs", "start_line": 3, "end_line": 5, "severity": "high", "category": "correctness", "message": "overflow" }
+                ]
+            }),
+            None,
+        )
+        .unwrap();
+
+        let text = format_findings(&report);
+        let high = text.find("[correctness] overflow").unwrap();
+        let low = text.find("[style] naming").unwrap();
+        let b = text.find("\nsrc/b.rs\n").unwrap();
+        assert!(high < low && low < b);
+        assert!(text.contains("    3-5  [correctness]"));
+        assert!(text.ends_with("3 findings: 1 high, 1 low, 1 info\n"));
+    }
+}
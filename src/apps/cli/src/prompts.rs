@@ -0,0 +1,178 @@
+/// `bitfun prompts` subcommands
+///
+/// Agent prompt templates are embedded in core and can be overridden by
+/// `<workspace>/.bitfun/prompts/<name>.md` or the user-level prompts
+/// directory. `override` copies the embedded template out as a starting point.
+use anyhow::{Context, Result};
+use bitfun_core::agentic::agents::{
+    check_prompt_template, get_embedded_prompt, list_prompt_templates, prompt_override_paths,
+    resolve_prompt_template, PromptTemplateSource, PROMPT_PLACEHOLDERS,
+};
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand)]
+pub enum PromptsAction {
+    /// List prompt templates and where each one is loaded from
+    List,
+    /// Print the template that agents currently use
+    Show {
+        /// Template name
+        name: String,
+
+        /// Print the embedded template, ignoring overrides
+        #[arg(long)]
+        embedded: bool,
+    },
+    /// Copy the embedded template to the override directory for editing
+    Override {
+        /// Template name
+        name: String,
+
+        /// Write the user-level override instead of the workspace one
+        #[arg(long)]
+        user: bool,
+
+        /// Replace an existing override file
+        #[arg(long)]
+        force: bool,
+    },
+    /// List the placeholders templates can use
+    Placeholders,
+}
+
+pub fn run(action: PromptsAction, workspace: Option<PathBuf>) -> Result<()> {
+    let workspace = match workspace {
+        Some(workspace) => workspace,
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+
+    match action {
+        PromptsAction::List => list(&workspace),
+        PromptsAction::Show { name, embedded } => show(&name, &workspace, embedded),
+        PromptsAction::Override { name, user, force } => {
+            override_template(&name, &workspace, user, force)
+        }
+        PromptsAction::Placeholders => {
+            placeholders();
+            Ok(())
+        }
+    }
+}
+
+fn list(workspace: &Path) -> Result<()> {
+    let names = list_prompt_templates();
+    println!("Prompt templates (total {})\n", names.len());
+    for name in names {
+        match resolve_prompt_template(name, Some(workspace))? {
+            Some(template) => match &template.path {
+                Some(path) => println!(
+                    "{} - {} override: {}",
+                    name,
+                    source_label(template.source),
+                    path.display()
+                ),
+                None => println!("{} - embedded", name),
+            },
+            None => println!("{} - missing", name),
+        }
+    }
+    Ok(())
+}
+
+fn show(name: &str, workspace: &Path, embedded: bool) -> Result<()> {
+    let content = if embedded {
+        embedded_template(name)?.to_string()
+    } else {
+        let template = resolve_prompt_template(name, Some(workspace))?
+            .with_context(|| format!("Unknown prompt template: {}", name))?;
+        if let Some(path) = &template.path {
+            eprintln!(
+                "# {} override: {}",
+                source_label(template.source),
+                path.display()
+            );
+            report_check(name, &template.content);
+        }
+        template.content
+    };
+    print!("{}", content);
+    if !content.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+fn override_template(name: &str, workspace: &Path, user: bool, force: bool) -> Result<()> {
+    let content = embedded_template(name)?;
+    let source = if user {
+        PromptTemplateSource::User
+    } else {
+        PromptTemplateSource::Workspace
+    };
+    let path = prompt_override_paths(name, Some(workspace))
+        .into_iter()
+        .find(|(candidate, _)| *candidate == source)
+        .map(|(_, path)| path)
+        .context("No override path for this template")?;
+
+    if path.exists() && !force {
+        anyhow::bail!(
+            "Override already exists: {} (use --force to replace it)",
+            path.display()
+        );
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("Copied embedded template {} to {}", name, path.display());
+    println!("Edit the file to change the prompt; delete it to go back to the embedded one.");
+    Ok(())
+}
+
+fn placeholders() {
+    for placeholder in PROMPT_PLACEHOLDERS {
+        println!("{{{}}} - {}", placeholder.name, placeholder.description);
+        if !placeholder.required_by.is_empty() {
+            println!("   Required by: {}", placeholder.required_by.join(", "));
+        }
+    }
+}
+
+fn embedded_template(name: &str) -> Result<&'static str> {
+    get_embedded_prompt(name).with_context(|| {
+        format!(
+            "Unknown prompt template: {} (see `bitfun prompts list`)",
+            name
+        )
+    })
+}
+
+/// Warns on stderr about placeholder problems that would show up at render time
+fn report_check(name: &str, content: &str) {
+    let check = check_prompt_template(name, content);
+    if !check.unknown.is_empty() {
+        eprintln!(
+            "# warning: unknown placeholders, left as-is: {}",
+            check.unknown.join(", ")
+        );
+    }
+    if !check.missing_required.is_empty() {
+        eprintln!(
+            "# error: missing required placeholders: {}",
+            check.missing_required.join(", ")
+        );
+    }
+}
+
+fn source_label(source: PromptTemplateSource) -> &'static str {
+    match source {
+        PromptTemplateSource::Workspace => "workspace",
+        PromptTemplateSource::User => "user",
+        PromptTemplateSource::Embedded => "embedded",
+    }
+}
@@ -5,9 +5,10 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::CliConfig;
+use bitfun_core::service::session::TurnSummary;
 
 /// Session information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +25,9 @@ pub struct Session {
     pub workspace: Option<String>,
     /// Agent used
     pub agent: String,
+    /// Core session the conversation runs in (set once the first message is sent)
+    #[serde(default)]
+    pub core_session_id: Option<String>,
     /// Message list
     pub messages: Vec<Message>,
     /// Metadata
@@ -39,6 +43,12 @@ pub struct SessionMetadata {
     pub tool_calls: usize,
     /// Files modified count
     pub files_modified: usize,
+    /// Prompt tokens summed over all model rounds
+    #[serde(default)]
+    pub input_tokens: u64,
+    /// Completion tokens summed over all model rounds
+    #[serde(default)]
+    pub output_tokens: u64,
     /// Tags
     pub tags: Vec<String>,
 }
@@ -48,7 +58,7 @@ pub struct SessionMetadata {
 pub struct Message {
     /// Message ID
     pub id: String,
-    /// Role (user, assistant, system)
+    /// Role (user, assistant, system, command)
     pub role: String,
     /// Content (for simple text messages)
     pub content: String,
@@ -57,6 +67,9 @@ pub struct Message {
     /// Flow items (mixed text and tools in order)
     #[serde(default)]
     pub flow_items: Vec<FlowItem>,
+    /// AI summary of the turn this assistant message answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_summary: Option<TurnSummary>,
 }
 
 /// Flow item (inspired by flowchat architecture)
@@ -144,6 +157,7 @@ impl Session {
             updated_at: now,
             workspace,
             agent,
+            core_session_id: None,
             messages: Vec::new(),
             metadata: SessionMetadata::default(),
         }
@@ -157,6 +171,7 @@ impl Session {
             content,
             timestamp: Utc::now(),
             flow_items: Vec::new(),
+            turn_summary: None,
         };
 
         self.messages.push(message);
@@ -164,6 +179,31 @@ impl Session {
         self.updated_at = Utc::now();
     }
 
+    /// Attach the summary of the turn that `message_id` answered
+    pub fn set_turn_summary(&mut self, message_id: &str, summary: TurnSummary) {
+        if let Some(message) = self.messages.iter_mut().find(|m| m.id == message_id) {
+            message.turn_summary = Some(summary);
+            self.updated_at = Utc::now();
+        }
+    }
+
+    /// Replace the conversation with history rebuilt from the core session
+    pub fn replace_history(&mut self, messages: Vec<Message>) {
+        self.metadata.tool_calls = messages
+            .iter()
+            .flat_map(|message| &message.flow_items)
+            .filter(|item| matches!(item, FlowItem::Tool { .. }))
+            .count();
+        self.messages = messages;
+        self.metadata.message_count = self.messages.len();
+    }
+
+    /// Add token usage of one model round
+    pub fn record_token_usage(&mut self, input_tokens: u64, output_tokens: u64) {
+        self.metadata.input_tokens += input_tokens;
+        self.metadata.output_tokens += output_tokens;
+    }
+
     /// Add or update text flow of the last message
     pub fn update_last_message_text_flow(&mut self, content: String, is_streaming: bool) {
         if let Some(last_message) = self.messages.last_mut() {
@@ -290,6 +330,7 @@ impl Session {
             updated_at: session.updated_at,
             agent: session.agent,
             message_count: session.metadata.message_count,
+            total_tokens: session.metadata.input_tokens + session.metadata.output_tokens,
             workspace: session.workspace,
         })
     }
@@ -317,6 +358,41 @@ impl Session {
             Ok(None)
         }
     }
+
+    /// Load a session by ID, unique ID prefix, or "last" for the most recent
+    pub fn resolve(reference: &str) -> Result<Self> {
+        if reference == "last" {
+            return Self::get_last()?.ok_or_else(|| anyhow::anyhow!("No history sessions"));
+        }
+
+        let sessions = Self::list_all()?;
+        if sessions.iter().any(|info| info.id == reference) {
+            return Self::load(reference);
+        }
+
+        let matches: Vec<&SessionInfo> = sessions
+            .iter()
+            .filter(|info| info.id.starts_with(reference))
+            .collect();
+        match matches.as_slice() {
+            [info] => Self::load(&info.id),
+            [] => anyhow::bail!("Session not found: {}", reference),
+            _ => anyhow::bail!(
+                "Session ID prefix is ambiguous: {} ({} matches)",
+                reference,
+                matches.len()
+            ),
+        }
+    }
+}
+
+/// Whether `workspace` is set and differs from `current`
+pub fn is_other_workspace(workspace: Option<&str>, current: Option<&Path>) -> bool {
+    let (Some(workspace), Some(current)) = (workspace, current) else {
+        return false;
+    };
+    let normalize = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    normalize(Path::new(workspace)) != normalize(current)
 }
 
 /// Session info (lightweight)
@@ -328,5 +404,7 @@ pub struct SessionInfo {
     pub updated_at: DateTime<Utc>,
     pub agent: String,
     pub message_count: usize,
+    #[serde(default)]
+    pub total_tokens: u64,
     pub workspace: Option<String>,
 }
@@ -1,18 +1,183 @@
 /// Chat mode TUI interface
+use bitfun_core::infrastructure::offline;
+use bitfun_core::service::session::TurnSummary;
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::time::{Duration, Instant};
 use unicode_width::UnicodeWidthStr;
 
-use super::markdown::MarkdownRenderer;
+use super::markdown::{self, CodeBlock, MarkdownRenderer};
+use super::mention::{mention_at, FileIndex, MentionState, MAX_CANDIDATES};
+use super::permission::PermissionPrompt;
+use super::status_line::{self, StatusLine};
 use super::theme::{StyleKind, Theme};
-use super::widgets::{HelpText, Spinner};
-use crate::session::{FlowItem, Message, Session};
+use super::tool_cards::{self, CardView, ToolCardState};
+use super::widgets::{HelpText, InputEditor, InputHistory, Spinner};
+use crate::config::CliConfig;
+use crate::session::{FlowItem, Message, Session, ToolCall};
+
+/// A slash command offered in the chat input
+#[derive(Debug, Clone)]
+pub struct SlashCommand {
+    /// Command including the slash, e.g. "/model"
+    pub name: Cow<'static, str>,
+    /// Argument hint, e.g. "<name>"
+    pub usage: Cow<'static, str>,
+    pub description: Cow<'static, str>,
+}
+
+impl SlashCommand {
+    const fn builtin(name: &'static str, usage: &'static str, description: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            usage: Cow::Borrowed(usage),
+            description: Cow::Borrowed(description),
+        }
+    }
+}
+
+/// Built-in slash commands; each entry has a handler in `modes::chat`
+pub const SLASH_COMMANDS: &[SlashCommand] = &[
+    SlashCommand::builtin("/help", "", "Show available commands"),
+    SlashCommand::builtin("/clear", "", "Clear conversation"),
+    SlashCommand::builtin("/model", "[name]", "List models or switch the active model"),
+    SlashCommand::builtin(
+        "/session",
+        "new|list|rename <title>",
+        "Start, list or rename sessions",
+    ),
+    SlashCommand::builtin("/compact", "", "Compress context on the next message"),
+    SlashCommand::builtin("/usage", "", "Show token and cost usage"),
+    SlashCommand::builtin("/mcp", "list|restart <id>", "List or restart MCP servers"),
+    SlashCommand::builtin(
+        "/env",
+        "[set <name> <value>|unset <name>]",
+        "List or change environment variables for commands",
+    ),
+    SlashCommand::builtin(
+        "/summary",
+        "[on|off]",
+        "Expand or collapse turn summaries, or turn them on or off",
+    ),
+    SlashCommand::builtin(
+        "/export",
+        "[markdown|html] <path>",
+        "Export the conversation as Markdown or HTML",
+    ),
+    SlashCommand::builtin(
+        "/workspace",
+        "[path]",
+        "List recent workspaces or switch to another",
+    ),
+    SlashCommand::builtin("/copy", "last-code", "Copy the latest code block"),
+    SlashCommand::builtin("/theme", "[name]", "List themes or switch theme"),
+    SlashCommand::builtin("/agents", "", "List available agents"),
+    SlashCommand::builtin("/switch", "<agent>", "Switch agent"),
+    SlashCommand::builtin("/history", "", "Show session statistics"),
+];
+
+/// Open slash command completion popup
+#[derive(Debug, Clone, Default)]
+pub struct CommandCompletion {
+    /// Command text typed so far, e.g. "/mo"
+    pub prefix: String,
+    pub candidates: Vec<SlashCommand>,
+    pub selected: usize,
+}
+
+/// Stable key of a tool card: its tool id, or its position for older sessions
+fn card_key(message: &Message, index: usize, tool_call: &ToolCall) -> String {
+    match &tool_call.tool_id {
+        Some(tool_id) => tool_id.clone(),
+        None => format!("{}:{}", message.id, index),
+    }
+}
+
+/// Key prefix of the code blocks in one text block; block `n` is `{prefix}{n}`
+fn code_key_prefix(message: &Message, flow_index: Option<usize>) -> String {
+    match flow_index {
+        Some(index) => format!("code:{}:{}:", message.id, index),
+        None => format!("code:{}:-:", message.id),
+    }
+}
+
+fn push_code_targets(targets: &mut Vec<(String, FocusTarget)>, prefix: &str, content: &str) {
+    for (index, block) in markdown::code_blocks(content).into_iter().enumerate() {
+        targets.push((format!("{}{}", prefix, index), FocusTarget::Code(block)));
+    }
+}
+
+/// Split `text` into spans, styling the characters at `indices` with `matched`
+fn highlight_match_indices(
+    text: &str,
+    indices: &[usize],
+    style: Style,
+    matched: Style,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (position, c) in text.chars().enumerate() {
+        let is_matched = indices.contains(&position);
+        if is_matched != run_matched && !run.is_empty() {
+            let run_style = if run_matched { matched } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_matched = is_matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_matched { matched } else { style }));
+    }
+    spans
+}
+
+/// Commands starting with `prefix`; empty once the only match is fully typed
+fn command_candidates(commands: &[SlashCommand], prefix: &str) -> Vec<SlashCommand> {
+    let candidates: Vec<SlashCommand> = commands
+        .iter()
+        .filter(|command| command.name.starts_with(prefix))
+        .cloned()
+        .collect();
+    if candidates.len() == 1 && candidates[0].name == prefix {
+        return Vec::new();
+    }
+    candidates
+}
+
+/// One search hit: rendered line index and byte range within that line's text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchMatch {
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Scrollback search state
+#[derive(Debug, Clone, Default)]
+pub struct SearchState {
+    /// Search text (ASCII case-insensitive)
+    pub query: String,
+    /// Keystrokes edit the query; after Enter they navigate with n/N
+    pub editing: bool,
+    matches: Vec<SearchMatch>,
+    /// Focused match, an index into `matches`
+    current: usize,
+    /// Query the matches were computed for
+    matched_query: String,
+    /// Scroll to the focused match on the next render
+    pending_jump: bool,
+    /// (browse_mode, auto_scroll, scroll_offset) restored when search exits
+    saved_view: (bool, bool, usize),
+}
 
 /// Chat interface state
 pub struct ChatView {
@@ -20,10 +185,8 @@ pub struct ChatView {
     pub theme: Theme,
     /// Current session
     pub session: Session,
-    /// Input buffer
-    pub input: String,
-    /// Input cursor position
-    pub cursor: usize,
+    /// Multi-line input editor
+    pub input: InputEditor,
     /// List scroll state
     pub list_state: ListState,
     /// Whether to auto-scroll to bottom
@@ -34,37 +197,125 @@ pub struct ChatView {
     pub spinner: Spinner,
     /// Status message
     pub status: Option<String>,
-    /// Input history (for up/down arrows)
-    pub input_history: VecDeque<String>,
-    /// History position
-    pub history_index: Option<usize>,
+    /// Input history for up/down arrows, persisted per workspace
+    pub input_history: InputHistory,
     /// Markdown renderer
     markdown_renderer: MarkdownRenderer,
     /// Whether in browse mode (for scrolling through history)
     pub browse_mode: bool,
     /// Message scroll offset (from bottom up)
     pub scroll_offset: usize,
+    /// Spend in USD (session, today); None until a priced model round completes
+    pub spend: Option<(f64, f64)>,
+    /// Active scrollback search
+    pub search: Option<SearchState>,
+    /// Rendered line count and viewport height from the last frame
+    last_layout: (usize, usize),
+    /// Text width of the input area from the last frame
+    input_width: usize,
+    /// Width of the conversation area from the last frame
+    message_width: usize,
+    /// Workspace files for @-mention completion
+    file_index: FileIndex,
+    /// Open @-mention completion popup
+    pub mention: Option<MentionState>,
+    /// Start of a mention whose popup was dismissed with Esc
+    mention_dismissed: Option<usize>,
+    /// Files accepted from the popup since the last send
+    mentions: Vec<String>,
+    /// Commands offered for completion (built-ins plus any registered later)
+    commands: Vec<SlashCommand>,
+    /// Open slash command completion popup
+    command_completion: Option<CommandCompletion>,
+    /// Command popup dismissed with Esc until the input stops being a command
+    command_dismissed: bool,
+    /// Per-card expand and scroll state, keyed by `card_key`
+    tool_cards: HashMap<String, ToolCardState>,
+    /// Tool card or code block selected with Tab; keys go to it while set
+    pub focused_block: Option<String>,
+    /// Scroll the focused block into view on the next render
+    focus_jump_pending: bool,
+    /// Whether cards of a tool start expanded, from `ui.tool_card_expanded`
+    tool_card_defaults: BTreeMap<String, bool>,
+    /// Whether turn summaries show all three bullets instead of one line
+    turn_summaries_expanded: bool,
+    /// Short-lived status line message and when it was shown
+    toast: Option<(String, Instant)>,
+    /// Tool permission requests waiting for an answer; the modal takes keys while any are queued
+    pub permission: PermissionPrompt,
+    /// Model, context fill, cost and phase on the right of the status bar
+    pub status_line: StatusLine,
+    /// Conversation lines of each card and code block in the last frame, keyed like `focused_block`
+    block_lines: Vec<(Range<usize>, String)>,
+    /// Conversation list area in the last frame
+    conversation_area: Rect,
+    /// Screen cells of the model name in the status bar in the last frame
+    model_segment_area: Rect,
 }
 
+/// How long a toast stays in the status line
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Something Tab can focus in the conversation
+enum FocusTarget {
+    Card,
+    Code(CodeBlock),
+}
+
+/// Maximum number of rows the input area grows to before scrolling
+const MAX_INPUT_ROWS: usize = 8;
+/// Input prompt shown on the first row ("> ") and indent on continuation rows
+const INPUT_PROMPT_WIDTH: usize = 2;
+
 impl ChatView {
+    /// Switch to `theme`; the next frame is drawn with it
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.markdown_renderer = MarkdownRenderer::new(theme.clone());
+        self.spinner = Spinner::new(theme.style(StyleKind::Primary));
+        self.theme = theme;
+    }
+
     /// Create new Chat view
     pub fn new(session: Session, theme: Theme) -> Self {
         let markdown_renderer = MarkdownRenderer::new(theme.clone());
+        let input_history = Self::load_input_history(session.workspace.as_deref());
+        let file_index = FileIndex::new(session.workspace.clone());
         Self {
             spinner: Spinner::new(theme.style(StyleKind::Primary)),
             markdown_renderer,
             theme,
             session,
-            input: String::new(),
-            cursor: 0,
+            input: InputEditor::new(),
             list_state: ListState::default(),
             auto_scroll: true,
             loading: false,
             status: None,
-            input_history: VecDeque::with_capacity(50),
-            history_index: None,
+            input_history,
             browse_mode: false,
             scroll_offset: 0,
+            spend: None,
+            search: None,
+            last_layout: (0, 0),
+            input_width: 0,
+            message_width: 80,
+            file_index,
+            mention: None,
+            mention_dismissed: None,
+            mentions: Vec::new(),
+            commands: SLASH_COMMANDS.to_vec(),
+            command_completion: None,
+            command_dismissed: false,
+            tool_cards: HashMap::new(),
+            focused_block: None,
+            focus_jump_pending: false,
+            tool_card_defaults: BTreeMap::new(),
+            turn_summaries_expanded: false,
+            toast: None,
+            permission: PermissionPrompt::default(),
+            status_line: StatusLine::default(),
+            block_lines: Vec::new(),
+            conversation_area: Rect::default(),
+            model_segment_area: Rect::default(),
         }
     }
 
@@ -72,15 +323,35 @@ impl ChatView {
     pub fn render(&mut self, frame: &mut Frame) {
         let size = frame.area();
 
+        // Pick up a file index that finished building while the popup is open
+        if self
+            .mention
+            .as_ref()
+            .is_some_and(|mention| mention.generation != self.file_index.generation())
+        {
+            self.update_mention();
+        }
+
+        self.input_width = (size.width as usize).saturating_sub(2 + INPUT_PROMPT_WIDTH);
+        let input_rows = if self.search.is_some() {
+            1
+        } else {
+            self.input
+                .visual_rows(self.input_width)
+                .len()
+                .max(self.input.cursor_position(self.input_width).0 + 1)
+                .min(MAX_INPUT_ROWS)
+        };
+
         // Main layout: header + content + status bar + input + shortcuts
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // header
-                Constraint::Min(10),   // messages area
-                Constraint::Length(1), // status bar
-                Constraint::Length(3), // input area
-                Constraint::Length(1), // shortcuts hint
+                Constraint::Length(3),                     // header
+                Constraint::Min(10),                       // messages area
+                Constraint::Length(1),                     // status bar
+                Constraint::Length(input_rows as u16 + 2), // input area
+                Constraint::Length(1),                     // shortcuts hint
             ])
             .split(size);
 
@@ -89,7 +360,9 @@ impl ChatView {
         self.render_messages(frame, chunks[1]);
         self.render_status_bar(frame, chunks[2]);
         self.render_input(frame, chunks[3]);
+        self.render_completion_popup(frame, chunks[1]);
         self.render_shortcuts(frame, chunks[4]);
+        self.permission.render(frame, chunks[1], &self.theme);
     }
 
     /// Render header
@@ -109,9 +382,10 @@ impl ChatView {
             .border_style(self.theme.style(StyleKind::Border))
             .style(Style::default().bg(self.theme.background));
 
-        // Product name in purple and bold
-        let title_style = Style::default()
-            .fg(ratatui::style::Color::Rgb(147, 51, 234))
+        // Product name in the accent color and bold
+        let title_style = self
+            .theme
+            .style(StyleKind::Accent)
             .add_modifier(Modifier::BOLD);
 
         let text = vec![Line::from(vec![
@@ -143,6 +417,9 @@ impl ChatView {
 
         let inner = block.inner(area);
         frame.render_widget(block, area);
+        self.message_width = inner.width as usize;
+        self.conversation_area = inner;
+        self.block_lines.clear();
 
         if self.session.messages.is_empty() {
             let welcome = vec![
@@ -169,17 +446,61 @@ impl ChatView {
 
             frame.render_widget(paragraph, inner);
         } else {
-            let messages: Vec<ListItem> = self
-                .session
-                .messages
-                .iter()
-                .flat_map(|msg| self.render_message(msg))
-                .collect();
+            let mut lines: Vec<Line> = Vec::new();
+            let mut block_lines = Vec::new();
+            for msg in &self.session.messages {
+                let mut message_blocks = Vec::new();
+                let message_lines = self.render_message(msg, &mut message_blocks);
+                let start = lines.len();
+                block_lines.extend(
+                    message_blocks
+                        .into_iter()
+                        .map(|(range, key)| (range.start + start..range.end + start, key)),
+                );
+                lines.extend(message_lines);
+            }
+            let focused_line = self.focused_block.as_ref().and_then(|focused| {
+                block_lines
+                    .iter()
+                    .find(|(_, key)| key == focused)
+                    .map(|(range, _)| range.start)
+            });
+            self.block_lines = block_lines;
+
+            let total_lines = lines.len();
+            let visible_lines = inner.height as usize;
+            self.last_layout = (total_lines, visible_lines);
+            if std::mem::take(&mut self.focus_jump_pending) {
+                if let Some(line) = focused_line {
+                    let view_position = line.saturating_sub(visible_lines / 4);
+                    self.browse_mode = true;
+                    self.auto_scroll = false;
+                    self.scroll_offset = total_lines.saturating_sub(view_position + visible_lines);
+                }
+            }
+            if let Some(search) = self.search.as_mut() {
+                if search.matched_query != search.query {
+                    let texts: Vec<String> = lines.iter().map(line_text).collect();
+                    search.matches = find_matches(&texts, &search.query);
+                    search.matched_query = search.query.clone();
+                    // Start from the most recent hit, like searching backwards from the end
+                    search.current = search.matches.len().saturating_sub(1);
+                    search.pending_jump = true;
+                }
+                if std::mem::take(&mut search.pending_jump) {
+                    if let Some(hit) = search.matches.get(search.current) {
+                        let view_position = hit.line.saturating_sub(visible_lines / 2);
+                        self.browse_mode = true;
+                        self.auto_scroll = false;
+                        self.scroll_offset =
+                            total_lines.saturating_sub(view_position + visible_lines);
+                    }
+                }
+                lines = highlight_matches(lines, search, &self.theme);
+            }
+            let messages: Vec<ListItem> = lines.into_iter().map(ListItem::new).collect();
 
             if !messages.is_empty() {
-                let total_lines = messages.len();
-                let visible_lines = inner.height as usize;
-
                 if self.browse_mode {
                     let view_position = if self.scroll_offset >= total_lines {
                         0
@@ -247,12 +568,21 @@ impl ChatView {
         }
     }
 
-    fn render_message<'a>(&self, message: &'a Message) -> Vec<ListItem<'a>> {
+    /// Lines of one message; `blocks` receives the lines of each card and code block
+    fn render_message<'a>(
+        &self,
+        message: &'a Message,
+        blocks: &mut Vec<(Range<usize>, String)>,
+    ) -> Vec<Line<'a>> {
+        if message.role == "command" {
+            return self.render_command_output(message);
+        }
+
         let mut items = Vec::new();
 
         let role_style = match message.role.as_str() {
-            "user" => self.theme.style(StyleKind::Success),
-            "assistant" => self.theme.style(StyleKind::Primary),
+            "user" => self.theme.style(StyleKind::User),
+            "assistant" => self.theme.style(StyleKind::Assistant),
             _ => self.theme.style(StyleKind::Muted),
         };
 
@@ -264,16 +594,16 @@ impl ChatView {
 
         let time = message.timestamp.format("%H:%M:%S");
 
-        items.push(ListItem::new(Line::from(vec![Span::raw("")])));
+        items.push(Line::from(vec![Span::raw("")]));
 
-        items.push(ListItem::new(Line::from(vec![
+        items.push(Line::from(vec![
             Span::styled(role_prefix, role_style.add_modifier(Modifier::BOLD)),
             Span::raw(" "),
             Span::styled(format!("[{}]", time), self.theme.style(StyleKind::Muted)),
-        ])));
+        ]));
 
         if !message.flow_items.is_empty() {
-            for flow_item in &message.flow_items {
+            for (index, flow_item) in message.flow_items.iter().enumerate() {
                 match flow_item {
                     FlowItem::Text {
                         content,
@@ -282,38 +612,38 @@ impl ChatView {
                         if message.role == "assistant"
                             && MarkdownRenderer::has_markdown_syntax(content)
                         {
-                            let available_width = 80;
-                            let markdown_lines =
-                                self.markdown_renderer.render(content, available_width);
-
-                            for md_line in markdown_lines {
-                                let mut spans = vec![Span::raw("  ")];
-                                spans.extend(md_line.spans);
-                                items.push(ListItem::new(Line::from(spans)));
-                            }
+                            let code_prefix = code_key_prefix(message, Some(index));
+                            self.render_markdown(&mut items, &code_prefix, content, blocks);
                         } else {
                             let content_lines: Vec<&str> = content.lines().collect();
                             for line in content_lines {
-                                items.push(ListItem::new(Line::from(vec![
-                                    Span::raw("  "),
-                                    Span::raw(line),
-                                ])));
+                                items.push(Line::from(vec![Span::raw("  "), Span::raw(line)]));
                             }
                         }
 
                         if *is_streaming {
-                            items.push(ListItem::new(Line::from(vec![
+                            items.push(Line::from(vec![
                                 Span::raw("  "),
                                 Span::styled("▊", self.theme.style(StyleKind::Primary)),
-                            ])));
+                            ]));
                         }
                     }
 
                     FlowItem::Tool { tool_call } => {
-                        items.push(ListItem::new(Line::from("")));
-                        let tool_items =
-                            crate::ui::tool_cards::render_tool_card(tool_call, &self.theme);
-                        items.extend(tool_items);
+                        items.push(Line::from(""));
+                        let key = card_key(message, index, tool_call);
+                        let focused = self.focused_block.as_deref() == Some(key.as_str());
+                        let state = self.tool_cards.get(&key);
+                        let view = CardView {
+                            expanded: self.card_expanded(state, tool_call),
+                            focused,
+                            scroll: state.and_then(|state| state.scroll),
+                            live_output: state.map_or("", |state| state.live_output.as_str()),
+                            width: self.message_width,
+                        };
+                        let start = items.len();
+                        items.extend(tool_cards::render_tool_card(tool_call, &self.theme, view));
+                        blocks.push((start..items.len(), key));
                     }
                 }
             }
@@ -321,87 +651,299 @@ impl ChatView {
             if message.role == "assistant"
                 && MarkdownRenderer::has_markdown_syntax(&message.content)
             {
-                let available_width = 80;
-                let markdown_lines = self
-                    .markdown_renderer
-                    .render(&message.content, available_width);
-
-                for md_line in markdown_lines {
-                    let mut spans = vec![Span::raw("  ")];
-                    spans.extend(md_line.spans);
-                    items.push(ListItem::new(Line::from(spans)));
-                }
+                let code_prefix = code_key_prefix(message, None);
+                self.render_markdown(&mut items, &code_prefix, &message.content, blocks);
             } else {
                 let content_lines: Vec<&str> = message.content.lines().collect();
                 for line in content_lines {
-                    items.push(ListItem::new(Line::from(vec![
-                        Span::raw("  "),
-                        Span::raw(line),
-                    ])));
+                    items.push(Line::from(vec![Span::raw("  "), Span::raw(line)]));
                 }
             }
         }
 
+        if let Some(summary) = &message.turn_summary {
+            self.render_turn_summary(&mut items, summary);
+        }
+
         items
     }
 
+    /// Summary under a turn's answer: the first bullet, or all three when expanded
+    fn render_turn_summary<'a>(&self, items: &mut Vec<Line<'a>>, summary: &'a TurnSummary) {
+        let muted = self.theme.style(StyleKind::Muted);
+        items.push(Line::from(""));
+        if !self.turn_summaries_expanded {
+            items.push(Line::from(vec![
+                Span::styled("  ▸ Summary: ", muted),
+                Span::styled(summary.did.as_str(), muted),
+            ]));
+            return;
+        }
+        items.push(Line::from(Span::styled("  ▾ Summary", muted)));
+        for (label, text) in [
+            ("Did", &summary.did),
+            ("Changed", &summary.changed),
+            ("Next", &summary.next),
+        ] {
+            items.push(Line::from(vec![
+                Span::styled(
+                    format!("    {}: ", label),
+                    muted.add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(text.as_str(), muted),
+            ]));
+        }
+    }
+
     /// Render status bar
-    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
-        let status_text = if let Some(status) = &self.status {
+    fn render_status_bar(&mut self, frame: &mut Frame, area: Rect) {
+        let status_text = if let Some(search) = &self.search {
+            if search.query.is_empty() {
+                "Search: type to find in conversation".to_string()
+            } else if search.matches.is_empty() {
+                format!("Search \"{}\": no matches", search.query)
+            } else {
+                format!(
+                    "Search \"{}\": {}/{}",
+                    search.query,
+                    search.current + 1,
+                    search.matches.len()
+                )
+            }
+        } else if let Some((toast, _)) = self
+            .toast
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < TOAST_DURATION)
+        {
+            toast.clone()
+        } else if let Some(status) = &self.status {
             status.clone()
         } else {
-            format!(
+            let mut text = format!(
                 "Messages: {} | Tool calls: {} | Files modified: {}",
                 self.session.metadata.message_count,
                 self.session.metadata.tool_calls,
                 self.session.metadata.files_modified
-            )
+            );
+            if let Some((session_usd, today_usd)) = self.spend {
+                text.push_str(&format!(
+                    " | Spend: ${:.4} session, ${:.2} today",
+                    session_usd, today_usd
+                ));
+            }
+            text
         };
 
+        self.status_line.set_offline(offline::status());
+        // The loading indicator already advanced the spinner this frame
+        if self.status_line.is_busy() && !self.loading {
+            self.spinner.tick();
+        }
+        let segments = self.status_line.spans(self.spinner.current(), &self.theme);
+        let segments_width = (status_line::spans_width(&segments) as u16).min(area.width);
+        let [text_area, segments_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(segments_width)])
+            .areas(area);
+        let model = status_line::model_columns(&segments);
+        self.model_segment_area = Rect::new(
+            segments_area.x + model.start as u16,
+            segments_area.y,
+            (model.len() as u16).min(segments_area.width.saturating_sub(model.start as u16)),
+            1,
+        );
+
         let paragraph = Paragraph::new(status_text)
-            .style(self.theme.style(StyleKind::Muted))
+            .style(self.theme.style(StyleKind::StatusBar))
             .alignment(Alignment::Left);
+        frame.render_widget(paragraph, text_area);
 
-        frame.render_widget(paragraph, area);
+        let segments = Paragraph::new(Line::from(segments))
+            .style(self.theme.style(StyleKind::StatusBar))
+            .alignment(Alignment::Right);
+        frame.render_widget(segments, segments_area);
     }
 
     fn render_input(&self, frame: &mut Frame, area: Rect) {
+        if let Some(search) = &self.search {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.style(StyleKind::Warning))
+                .title(" Search ");
+            let paragraph = Paragraph::new(Line::from(vec![
+                Span::raw("/"),
+                Span::raw(search.query.as_str()),
+            ]))
+            .block(block);
+            frame.render_widget(paragraph, area);
+            if search.editing {
+                frame.set_cursor_position((
+                    area.x + 2 + search.query.width() as u16, // "/" + query
+                    area.y + 1,
+                ));
+            }
+            return;
+        }
+
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(self.theme.style(StyleKind::Primary))
             .title(" Input ");
 
-        let input_text = if self.input.is_empty() {
-            Span::styled("Enter message...", self.theme.style(StyleKind::Muted))
+        let visible_rows = (area.height as usize).saturating_sub(2).max(1);
+        let (cursor_row, cursor_column) = self.input.cursor_position(self.input_width);
+        let first_row = (cursor_row + 1).saturating_sub(visible_rows);
+
+        let lines: Vec<Line> = if self.input.is_empty() {
+            vec![Line::from(vec![
+                Span::raw("> "),
+                Span::styled(
+                    "Enter message... (Shift+Enter for newline)",
+                    self.theme.style(StyleKind::Muted),
+                ),
+            ])]
         } else {
-            Span::raw(&self.input)
+            let text = self.input.text();
+            self.input
+                .visual_rows(self.input_width)
+                .into_iter()
+                .enumerate()
+                .skip(first_row)
+                .take(visible_rows)
+                .map(|(row, (start, end))| {
+                    let prompt = if row == 0 { "> " } else { "  " };
+                    Line::from(vec![Span::raw(prompt), Span::raw(&text[start..end])])
+                })
+                .collect()
         };
 
-        let paragraph = Paragraph::new(Line::from(vec![Span::raw("> "), input_text])).block(block);
-
-        frame.render_widget(paragraph, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
 
-        // Set cursor position
         if !self.loading {
-            // Calculate display width to cursor position
-            let byte_pos = self.char_pos_to_byte_pos(self.cursor);
-            let display_width = self.input[..byte_pos].width() as u16;
-
             frame.set_cursor_position((
-                area.x + 3 + display_width, // "> " + display width
-                area.y + 1,
+                area.x + 1 + (INPUT_PROMPT_WIDTH + cursor_column) as u16,
+                area.y + 1 + (cursor_row - first_row) as u16,
             ));
         }
     }
 
+    /// Render the open completion popup at the bottom of the messages area
+    fn render_completion_popup(&self, frame: &mut Frame, area: Rect) {
+        let selected_style = self
+            .theme
+            .style(StyleKind::Primary)
+            .add_modifier(Modifier::REVERSED);
+
+        let (title, items): (&str, Vec<ListItem>) = if let Some(mention) = &self.mention {
+            (
+                " Files (Tab to insert) ",
+                self.mention_items(mention, selected_style),
+            )
+        } else if let Some(completion) = &self.command_completion {
+            let items = completion
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(i, command)| {
+                    let name_style = if i == completion.selected {
+                        selected_style
+                    } else {
+                        self.theme.style(StyleKind::Primary)
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(command.name.as_ref(), name_style),
+                        Span::raw(" "),
+                        Span::styled(command.usage.as_ref(), self.theme.style(StyleKind::Muted)),
+                        Span::raw("  "),
+                        Span::raw(command.description.as_ref()),
+                    ]))
+                })
+                .collect();
+            (" Commands (Tab to complete) ", items)
+        } else {
+            return;
+        };
+
+        let height = (items.len() as u16 + 2).min(area.height);
+        let width = area.width.min(72);
+        let popup = Rect::new(area.x, area.y + area.height - height, width, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.style(StyleKind::Primary))
+            .title(title);
+
+        frame.render_widget(Clear, popup);
+        frame.render_widget(List::new(items).block(block), popup);
+    }
+
+    fn mention_items<'a>(
+        &self,
+        mention: &'a MentionState,
+        selected_style: Style,
+    ) -> Vec<ListItem<'a>> {
+        if mention.candidates.is_empty() {
+            let hint = if self.file_index.is_building() {
+                "Indexing workspace files..."
+            } else {
+                "No matching files"
+            };
+            vec![ListItem::new(Span::styled(
+                hint,
+                self.theme.style(StyleKind::Muted),
+            ))]
+        } else {
+            mention
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(i, candidate)| {
+                    let style = if i == mention.selected {
+                        selected_style
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(Line::from(highlight_match_indices(
+                        &candidate.path,
+                        &candidate.match_indices,
+                        style,
+                        style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    )))
+                })
+                .collect()
+        }
+    }
+
     fn render_shortcuts(&self, frame: &mut Frame, area: Rect) {
         let help = HelpText {
-            shortcuts: if self.browse_mode {
+            shortcuts: if let Some(search) = &self.search {
+                if search.editing {
+                    vec![
+                        ("Enter".to_string(), "Done ".to_string()),
+                        ("Esc".to_string(), "Cancel".to_string()),
+                    ]
+                } else {
+                    vec![
+                        ("n/N".to_string(), "Next/Prev ".to_string()),
+                        ("/".to_string(), "Edit ".to_string()),
+                        ("Esc".to_string(), "Close".to_string()),
+                    ]
+                }
+            } else if self.focused_block.is_some() {
+                vec![
+                    ("Enter".to_string(), "Expand/Collapse ".to_string()),
+                    ("↑↓".to_string(), "Scroll ".to_string()),
+                    ("y".to_string(), "Copy ".to_string()),
+                    ("d".to_string(), "Set default ".to_string()),
+                    ("Tab".to_string(), "Next card ".to_string()),
+                    ("Esc".to_string(), "Unfocus".to_string()),
+                ]
+            } else if self.browse_mode {
                 // Browse mode shortcuts
                 vec![
                     ("↑↓".to_string(), "Scroll ".to_string()),
                     ("PgUp/PgDn".to_string(), "Page ".to_string()),
-                    ("Ctrl+E".to_string(), "Exit browse ".to_string()),
+                    ("/".to_string(), "Search ".to_string()),
+                    ("Ctrl+B".to_string(), "Exit browse ".to_string()),
                     ("Esc".to_string(), "To bottom ".to_string()),
                     ("Ctrl+M".to_string(), "Menu ".to_string()),
                 ]
@@ -409,7 +951,13 @@ impl ChatView {
                 // Normal mode shortcuts
                 vec![
                     ("↑↓".to_string(), "History ".to_string()),
-                    ("Ctrl+E".to_string(), "Browse ".to_string()),
+                    ("Shift+Enter".to_string(), "Newline ".to_string()),
+                    ("Ctrl+E".to_string(), "Editor ".to_string()),
+                    ("Ctrl+B".to_string(), "Browse ".to_string()),
+                    ("Ctrl+F".to_string(), "Search ".to_string()),
+                    ("Ctrl+P".to_string(), "Model ".to_string()),
+                    ("F2".to_string(), "Mouse ".to_string()),
+                    ("Tab".to_string(), "Cards/Code ".to_string()),
                     ("Ctrl+L".to_string(), "Clear ".to_string()),
                     ("Esc".to_string(), "Menu ".to_string()),
                     ("Ctrl+C".to_string(), "Quit".to_string()),
@@ -423,6 +971,59 @@ impl ChatView {
         frame.render_widget(paragraph, area);
     }
 
+    /// Assistant markdown, with the focused code block (if it is in here) marked
+    fn render_markdown(
+        &self,
+        items: &mut Vec<Line<'_>>,
+        code_prefix: &str,
+        content: &str,
+        blocks: &mut Vec<(Range<usize>, String)>,
+    ) {
+        let available_width = 80;
+        let focused = self
+            .focused_block
+            .as_deref()
+            .and_then(|key| key.strip_prefix(code_prefix))
+            .and_then(|index| index.parse().ok());
+        let (markdown_lines, code_ranges) =
+            self.markdown_renderer
+                .render(content, available_width, focused);
+
+        let start = items.len();
+        for (index, range) in code_ranges.into_iter().enumerate() {
+            blocks.push((
+                range.start + start..range.end + start,
+                format!("{}{}", code_prefix, index),
+            ));
+        }
+
+        for md_line in markdown_lines {
+            let mut spans = vec![Span::raw("  ")];
+            spans.extend(md_line.spans);
+            items.push(Line::from(spans));
+        }
+    }
+
+    /// Slash command output: the command line followed by a ruled block
+    fn render_command_output<'a>(&self, message: &'a Message) -> Vec<Line<'a>> {
+        let info = self.theme.style(StyleKind::Info);
+        let mut lines = message.content.lines();
+        let command = lines.next().unwrap_or_default();
+
+        let mut items = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Command:", info.add_modifier(Modifier::BOLD)),
+                Span::raw(" "),
+                Span::styled(command, info),
+            ]),
+        ];
+        items.extend(
+            lines.map(|line| Line::from(vec![Span::styled("  │ ", info), Span::raw(line)])),
+        );
+        items
+    }
+
     /// Add message to session
     pub fn add_message(&mut self, role: String, content: String) {
         self.session.add_message(role, content);
@@ -432,117 +1033,241 @@ impl ChatView {
 
     /// Send user input
     pub fn send_input(&mut self) -> Option<String> {
-        if self.input.trim().is_empty() {
+        if self.input.text().trim().is_empty() {
             return None;
         }
 
-        let input = self.input.clone();
+        let input = self.input.take();
+        self.input_history.push(input.clone());
 
-        // Add to history
-        self.input_history.push_front(input.clone());
-        if self.input_history.len() > 50 {
-            self.input_history.pop_back();
+        // Slash commands are echoed with their output instead
+        if !input.starts_with('/') {
+            // Add to session (will auto-trigger scroll)
+            self.add_message("user".to_string(), input.clone());
         }
-        self.history_index = None;
 
-        // Clear input
-        self.input.clear();
-        self.cursor = 0;
+        Some(input)
+    }
 
-        // Add to session (will auto-trigger scroll)
-        self.add_message("user".to_string(), input.clone());
+    /// Show the output of a slash command as a command block
+    pub fn add_command_output(&mut self, command: &str, output: &str) {
+        self.add_message("command".to_string(), format!("{}\n{}", command, output));
+    }
 
-        Some(input)
+    /// Commands offered in the completion popup
+    pub fn commands(&self) -> &[SlashCommand] {
+        &self.commands
     }
 
-    pub fn handle_char(&mut self, c: char) {
-        if c.is_control() || c == '\u{0}' {
-            return;
-        }
+    /// Files mentioned in `text` via the popup; clears the recorded mentions
+    pub fn take_mentions(&mut self, text: &str) -> Vec<String> {
+        std::mem::take(&mut self.mentions)
+            .into_iter()
+            .filter(|path| text.contains(&format!("@{}", path)))
+            .collect()
+    }
 
-        let byte_pos = self.char_pos_to_byte_pos(self.cursor);
-        self.input.insert(byte_pos, c);
-        self.cursor += 1;
+    /// Open, refresh or close completion popups for the text under the cursor
+    pub fn update_completion(&mut self) {
+        self.update_mention();
+        self.update_command_completion();
     }
 
-    pub fn handle_backspace(&mut self) {
-        if self.cursor > 0 && !self.input.is_empty() {
-            let byte_pos = self.char_pos_to_byte_pos(self.cursor - 1);
-            if byte_pos < self.input.len() {
-                self.input.remove(byte_pos);
-                self.cursor -= 1;
+    pub fn completion_open(&self) -> bool {
+        self.mention.is_some() || self.command_completion.is_some()
+    }
+
+    pub fn completion_has_candidates(&self) -> bool {
+        match (&self.mention, &self.command_completion) {
+            (Some(mention), _) => !mention.candidates.is_empty(),
+            (None, Some(completion)) => !completion.candidates.is_empty(),
+            (None, None) => false,
+        }
+    }
+
+    /// Move the popup selection down (`forward`) or up, wrapping around
+    pub fn completion_step(&mut self, forward: bool) {
+        if let Some(mention) = self.mention.as_mut() {
+            if forward {
+                mention.select_next();
+            } else {
+                mention.select_prev();
+            }
+        } else if let Some(completion) = self.command_completion.as_mut() {
+            let len = completion.candidates.len();
+            if len > 0 {
+                completion.selected = if forward {
+                    (completion.selected + 1) % len
+                } else {
+                    (completion.selected + len - 1) % len
+                };
             }
         }
     }
 
-    pub fn move_cursor_left(&mut self) {
-        if self.cursor > 0 {
-            self.cursor -= 1;
+    /// Insert the selected candidate of the open popup
+    pub fn accept_completion(&mut self) {
+        if self.mention.is_some() {
+            self.accept_mention();
+        } else if let Some(completion) = self.command_completion.take() {
+            if let Some(command) = completion.candidates.get(completion.selected) {
+                let text = self.input.text();
+                let token_end = text
+                    .find(char::is_whitespace)
+                    .unwrap_or(text.len())
+                    .max(self.input.cursor());
+                self.input
+                    .replace_range(0, token_end, &format!("{} ", command.name));
+            }
         }
     }
 
-    pub fn move_cursor_right(&mut self) {
-        let char_count = self.input.chars().count();
-        if self.cursor < char_count {
-            self.cursor += 1;
+    /// Close the open popup until its trigger text goes away
+    pub fn dismiss_completion(&mut self) {
+        if let Some(mention) = self.mention.take() {
+            self.mention_dismissed = Some(mention.start);
+        } else if self.command_completion.take().is_some() {
+            self.command_dismissed = true;
         }
     }
 
-    fn char_pos_to_byte_pos(&self, char_pos: usize) -> usize {
-        self.input
-            .char_indices()
-            .nth(char_pos)
-            .map(|(pos, _)| pos)
-            .unwrap_or(self.input.len())
+    /// Offer slash commands while the first word of the input is being typed
+    fn update_command_completion(&mut self) {
+        let prefix = &self.input.text()[..self.input.cursor()];
+        if !prefix.starts_with('/') || prefix.contains(char::is_whitespace) {
+            self.command_completion = None;
+            self.command_dismissed = false;
+            return;
+        }
+        if self.command_dismissed
+            || self
+                .command_completion
+                .as_ref()
+                .is_some_and(|completion| completion.prefix == prefix)
+        {
+            return;
+        }
+
+        let candidates = command_candidates(&self.commands, prefix);
+        self.command_completion = (!candidates.is_empty()).then(|| CommandCompletion {
+            prefix: prefix.to_string(),
+            candidates,
+            selected: 0,
+        });
     }
 
-    pub fn history_prev(&mut self) {
-        if self.input_history.is_empty() {
+    /// Open, refresh or close the mention popup for the `@token` under the cursor
+    fn update_mention(&mut self) {
+        let Some((start, query)) = mention_at(self.input.text(), self.input.cursor()) else {
+            self.mention = None;
+            self.mention_dismissed = None;
+            return;
+        };
+        if self.mention_dismissed == Some(start) {
             return;
         }
 
-        let new_index = match self.history_index {
-            None => 0,
-            Some(i) if i + 1 < self.input_history.len() => i + 1,
-            Some(i) => i,
+        let generation = self.file_index.generation();
+        if self.mention.as_ref().is_some_and(|mention| {
+            mention.start == start && mention.query == query && mention.generation == generation
+        }) {
+            return;
+        }
+
+        self.file_index.ensure_fresh();
+        self.mention = Some(MentionState {
+            start,
+            query: query.to_string(),
+            candidates: self.file_index.search(query, MAX_CANDIDATES),
+            selected: 0,
+            generation,
+        });
+    }
+
+    /// Replace the `@token` with the selected path; false when nothing is selected
+    fn accept_mention(&mut self) -> bool {
+        let Some(mention) = self.mention.take() else {
+            return false;
+        };
+        let Some(path) = mention
+            .candidates
+            .get(mention.selected)
+            .map(|candidate| candidate.path.clone())
+        else {
+            self.mention = Some(mention);
+            return false;
         };
 
-        if let Some(history_item) = self.input_history.get(new_index) {
-            self.input = history_item.clone();
-            self.cursor = self.input.len();
-            self.history_index = Some(new_index);
+        let end = self.input.cursor();
+        self.input
+            .replace_range(mention.start, end, &format!("@{} ", path));
+        if !self.mentions.contains(&path) {
+            self.mentions.push(path);
         }
+        true
     }
 
-    pub fn history_next(&mut self) {
-        match self.history_index {
-            None => {}
-            Some(0) => {
-                self.input.clear();
-                self.cursor = 0;
-                self.history_index = None;
-            }
-            Some(i) => {
-                let new_index = i - 1;
-                if let Some(history_item) = self.input_history.get(new_index) {
-                    self.input = history_item.clone();
-                    self.cursor = self.input.len();
-                    self.history_index = Some(new_index);
-                }
+    /// Move the cursor up a row, or to the previous history entry from the first row
+    pub fn cursor_up(&mut self) {
+        if self.input.move_up(self.input_width) {
+            return;
+        }
+        if let Some(entry) = self.input_history.prev(self.input.text()) {
+            let entry = entry.to_string();
+            self.input.set_text(entry);
+        }
+    }
+
+    /// Move the cursor down a row, or to the next history entry from the last row
+    pub fn cursor_down(&mut self) {
+        if self.input.move_down(self.input_width) {
+            return;
+        }
+        if let Some(entry) = self.input_history.next() {
+            self.input.set_text(entry);
+        }
+    }
+
+    fn load_input_history(workspace: Option<&str>) -> InputHistory {
+        match CliConfig::input_history_file(workspace) {
+            Ok(path) => InputHistory::load(path),
+            Err(e) => {
+                tracing::warn!("Input history unavailable: {}", e);
+                InputHistory::default()
             }
         }
     }
 
+    /// Show `session`, a fresh session bound to another workspace root; input
+    /// history and @-mention files follow the new workspace
+    pub fn start_workspace_session(&mut self, session: Session) {
+        self.clear_screen();
+        self.input_history = Self::load_input_history(session.workspace.as_deref());
+        self.file_index = FileIndex::new(session.workspace.clone());
+        self.mention = None;
+        self.mentions.clear();
+        self.session = session;
+        self.spend = None;
+        self.status_line.reset_usage();
+    }
+
     pub fn clear_screen(&mut self) {
         self.session.messages.clear();
         self.list_state.select(None);
         self.auto_scroll = true;
+        self.tool_cards.clear();
+        self.focused_block = None;
     }
 
     pub fn set_loading(&mut self, loading: bool) {
         self.loading = loading;
     }
 
+    pub fn set_spend(&mut self, session_usd: f64, today_usd: f64) {
+        self.spend = Some((session_usd, today_usd));
+        self.status_line.set_cost(session_usd);
+    }
+
     pub fn set_status(&mut self, status: Option<String>) {
         self.status = status;
     }
@@ -563,7 +1288,7 @@ impl ChatView {
                 .session
                 .messages
                 .iter()
-                .flat_map(|msg| self.render_message(msg))
+                .flat_map(|msg| self.render_message(msg, &mut Vec::new()))
                 .count();
 
             self.scroll_offset = (self.scroll_offset + lines).min(total_lines.saturating_sub(1));
@@ -590,7 +1315,7 @@ impl ChatView {
             .session
             .messages
             .iter()
-            .flat_map(|msg| self.render_message(msg))
+            .flat_map(|msg| self.render_message(msg, &mut Vec::new()))
             .count();
 
         self.browse_mode = true;
@@ -603,4 +1328,486 @@ impl ChatView {
         self.auto_scroll = true;
         self.scroll_offset = 0;
     }
+
+    /// Show turn summaries in full or as one line; returns whether they are expanded now
+    pub fn toggle_turn_summaries(&mut self) -> bool {
+        self.turn_summaries_expanded = !self.turn_summaries_expanded;
+        self.turn_summaries_expanded
+    }
+
+    /// Set which tools' cards start expanded
+    pub fn set_tool_card_defaults(&mut self, defaults: BTreeMap<String, bool>) {
+        self.tool_card_defaults = defaults;
+    }
+
+    fn card_expanded(&self, state: Option<&ToolCardState>, tool_call: &ToolCall) -> bool {
+        state.and_then(|state| state.expanded).unwrap_or_else(|| {
+            self.tool_card_defaults
+                .get(&tool_call.tool_name)
+                .copied()
+                .unwrap_or(false)
+        })
+    }
+
+    /// Focusable tool cards and assistant code blocks in conversation order
+    fn focus_targets(&self) -> Vec<(String, FocusTarget)> {
+        let mut targets = Vec::new();
+        for message in &self.session.messages {
+            let assistant = message.role == "assistant";
+            if message.flow_items.is_empty() {
+                if assistant {
+                    let prefix = code_key_prefix(message, None);
+                    push_code_targets(&mut targets, &prefix, &message.content);
+                }
+                continue;
+            }
+            for (index, item) in message.flow_items.iter().enumerate() {
+                match item {
+                    FlowItem::Text { content, .. } if assistant => {
+                        let prefix = code_key_prefix(message, Some(index));
+                        push_code_targets(&mut targets, &prefix, content);
+                    }
+                    FlowItem::Text { .. } => {}
+                    FlowItem::Tool { tool_call } => {
+                        targets.push((card_key(message, index, tool_call), FocusTarget::Card))
+                    }
+                }
+            }
+        }
+        targets
+    }
+
+    fn find_tool_call(&self, key: &str) -> Option<&ToolCall> {
+        self.session.messages.iter().find_map(|message| {
+            message
+                .flow_items
+                .iter()
+                .enumerate()
+                .find_map(|(index, item)| match item {
+                    FlowItem::Tool { tool_call } if card_key(message, index, tool_call) == key => {
+                        Some(tool_call)
+                    }
+                    _ => None,
+                })
+        })
+    }
+
+    /// Move focus to the next (`forward`) or previous card or code block, wrapping
+    /// around; the first press focuses the most recent one. False when there are none.
+    pub fn focus_block(&mut self, forward: bool) -> bool {
+        let keys: Vec<String> = self
+            .focus_targets()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        if keys.is_empty() {
+            return false;
+        }
+        let current = self
+            .focused_block
+            .as_ref()
+            .and_then(|focused| keys.iter().position(|key| key == focused));
+        let next = match current {
+            None => keys.len() - 1,
+            Some(i) if forward => (i + 1) % keys.len(),
+            Some(i) => (i + keys.len() - 1) % keys.len(),
+        };
+        self.focused_block = Some(keys[next].clone());
+        self.focus_jump_pending = true;
+        true
+    }
+
+    /// Card or code block drawn at screen cell (`column`, `row`) in the last frame
+    pub fn block_at(&self, column: u16, row: u16) -> Option<String> {
+        let area = self.conversation_area;
+        if !area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let line = self.list_state.offset() + (row - area.y) as usize;
+        block_at_line(&self.block_lines, line).map(str::to_string)
+    }
+
+    /// Whether (`column`, `row`) is inside the conversation area
+    pub fn in_conversation(&self, column: u16, row: u16) -> bool {
+        self.conversation_area.contains(Position::new(column, row))
+    }
+
+    /// Whether (`column`, `row`) is on the model name in the status bar
+    pub fn on_model_segment(&self, column: u16, row: u16) -> bool {
+        self.model_segment_area.contains(Position::new(column, row))
+    }
+
+    /// Focus the block with `key` (from `block_at`), or toggle it if already focused
+    pub fn click_block(&mut self, key: String) {
+        if self.focused_block.as_ref() == Some(&key) {
+            self.toggle_focused_block();
+        } else {
+            self.focused_block = Some(key);
+        }
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused_block = None;
+    }
+
+    /// Expand or collapse the focused card
+    pub fn toggle_focused_block(&mut self) {
+        let Some(key) = self.focused_block.clone() else {
+            return;
+        };
+        let Some(tool_call) = self.find_tool_call(&key) else {
+            return;
+        };
+        let expanded = self.card_expanded(self.tool_cards.get(&key), tool_call);
+        let state = self.tool_cards.entry(key).or_default();
+        state.expanded = Some(!expanded);
+        state.scroll = None;
+        self.focus_jump_pending = true;
+    }
+
+    /// Scroll the output of the focused card if it is expanded
+    pub fn scroll_focused_block(&mut self, delta: isize) {
+        let Some(key) = self.focused_block.clone() else {
+            return;
+        };
+        let Some(tool_call) = self.find_tool_call(&key) else {
+            return;
+        };
+        let state = self.tool_cards.get(&key);
+        if !self.card_expanded(state, tool_call) {
+            return;
+        }
+
+        let live_output = state.map_or("", |state| state.live_output.as_str());
+        let total = tool_cards::expanded_line_count(
+            tool_call,
+            live_output,
+            &self.theme,
+            self.message_width,
+        );
+        let current =
+            tool_cards::expanded_scroll(tool_call, state.and_then(|state| state.scroll), total);
+        let max = total.saturating_sub(tool_cards::EXPANDED_CARD_LINES);
+        let scroll = current.saturating_add_signed(delta).min(max);
+        self.tool_cards.entry(key).or_default().scroll = Some(scroll);
+    }
+
+    /// Raw content of the focused block: code, tool output, or tool parameters
+    /// when the tool has no output yet
+    pub fn focused_text(&self) -> Option<String> {
+        let key = self.focused_block.as_ref()?;
+        let Some(tool_call) = self.find_tool_call(key) else {
+            return self.focus_targets().into_iter().find_map(
+                |(target_key, target)| match target {
+                    FocusTarget::Code(block) if &target_key == key => Some(block.code),
+                    _ => None,
+                },
+            );
+        };
+        let live_output = self
+            .tool_cards
+            .get(key)
+            .map_or("", |state| state.live_output.as_str());
+        let output = tool_cards::card_output(tool_call, live_output);
+        Some(if output.is_empty() {
+            serde_json::to_string_pretty(&tool_call.parameters).unwrap_or_default()
+        } else {
+            output.to_string()
+        })
+    }
+
+    /// Most recent fenced code block in an assistant message
+    pub fn last_code_block(&self) -> Option<CodeBlock> {
+        self.focus_targets()
+            .into_iter()
+            .rev()
+            .find_map(|(_, target)| match target {
+                FocusTarget::Code(block) if block.fenced => Some(block),
+                _ => None,
+            })
+    }
+
+    /// Show `text` in the status line for a few seconds
+    pub fn show_toast(&mut self, text: String) {
+        self.toast = Some((text, Instant::now()));
+    }
+
+    /// Make the focused card's current expand state the default for its tool;
+    /// returns the tool name and the new default
+    pub fn remember_focused_card_default(&mut self) -> Option<(String, bool)> {
+        let key = self.focused_block.as_ref()?;
+        let tool_call = self.find_tool_call(key)?;
+        let expanded = self.card_expanded(self.tool_cards.get(key), tool_call);
+        let tool_name = tool_call.tool_name.clone();
+        self.tool_card_defaults.insert(tool_name.clone(), expanded);
+        Some((tool_name, expanded))
+    }
+
+    /// Append streamed progress output to a running tool's card
+    pub fn append_tool_output(&mut self, tool_id: &str, output: &str) {
+        let live_output = &mut self
+            .tool_cards
+            .entry(tool_id.to_string())
+            .or_default()
+            .live_output;
+        if !live_output.is_empty() && !live_output.ends_with('\n') {
+            live_output.push('\n');
+        }
+        live_output.push_str(output);
+    }
+
+    /// Enter search mode (or re-edit the query when already searching)
+    pub fn start_search(&mut self) {
+        match self.search.as_mut() {
+            Some(search) => search.editing = true,
+            None => {
+                self.search = Some(SearchState {
+                    editing: true,
+                    saved_view: (self.browse_mode, self.auto_scroll, self.scroll_offset),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    /// Leave search mode, restoring the scroll position from before the search
+    pub fn exit_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            (self.browse_mode, self.auto_scroll, self.scroll_offset) = search.saved_view;
+        }
+    }
+
+    pub fn search_push_char(&mut self, c: char) {
+        if let Some(search) = self.search.as_mut() {
+            search.query.push(c);
+        }
+    }
+
+    pub fn search_backspace(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            search.query.pop();
+        }
+    }
+
+    /// Stop editing the query; an empty query closes the search
+    pub fn search_commit(&mut self) {
+        match self.search.as_mut() {
+            Some(search) if !search.query.is_empty() => search.editing = false,
+            Some(_) => self.exit_search(),
+            None => {}
+        }
+    }
+
+    /// Focus the next (newer) match, wrapping around
+    pub fn search_next(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            if !search.matches.is_empty() {
+                search.current = (search.current + 1) % search.matches.len();
+                search.pending_jump = true;
+            }
+        }
+    }
+
+    /// Focus the previous (older) match, wrapping around
+    pub fn search_prev(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            if !search.matches.is_empty() {
+                search.current = (search.current + search.matches.len() - 1) % search.matches.len();
+                search.pending_jump = true;
+            }
+        }
+    }
+}
+
+/// Plain text of a rendered line
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Key of the block covering conversation line `line`
+fn block_at_line(blocks: &[(Range<usize>, String)], line: usize) -> Option<&str> {
+    blocks
+        .iter()
+        .find(|(range, _)| range.contains(&line))
+        .map(|(_, key)| key.as_str())
+}
+
+/// Non-overlapping ASCII case-insensitive occurrences of `query` in each line
+fn find_matches(texts: &[String], query: &str) -> Vec<SearchMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let needle = query.to_ascii_lowercase();
+    let mut matches = Vec::new();
+    for (line, text) in texts.iter().enumerate() {
+        let haystack = text.to_ascii_lowercase();
+        let mut from = 0;
+        while let Some(pos) = haystack[from..].find(&needle) {
+            let start = from + pos;
+            let end = start + needle.len();
+            matches.push(SearchMatch { line, start, end });
+            from = end;
+        }
+    }
+    matches
+}
+
+fn highlight_matches<'a>(
+    mut lines: Vec<Line<'a>>,
+    search: &SearchState,
+    theme: &Theme,
+) -> Vec<Line<'a>> {
+    let match_style = Style::default().bg(theme.warning).fg(theme.background);
+    let current_style = match_style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+
+    let mut i = 0;
+    while i < search.matches.len() {
+        let line_index = search.matches[i].line;
+        let mut ranges = Vec::new();
+        while i < search.matches.len() && search.matches[i].line == line_index {
+            let hit = search.matches[i];
+            let style = if i == search.current {
+                current_style
+            } else {
+                match_style
+            };
+            ranges.push((hit.start, hit.end, style));
+            i += 1;
+        }
+        if let Some(line) = lines.get_mut(line_index) {
+            *line = highlight_line(std::mem::take(line), &ranges);
+        }
+    }
+    lines
+}
+
+/// Restyle the byte ranges (in line-text coordinates) of `line`, splitting spans as needed
+fn highlight_line<'a>(line: Line<'a>, ranges: &[(usize, usize, Style)]) -> Line<'a> {
+    let mut spans = Vec::with_capacity(line.spans.len() + ranges.len() * 2);
+    let mut offset = 0;
+
+    for span in line.spans {
+        let text = span.content.as_ref();
+        let span_end = offset + text.len();
+        let mut cursor = 0;
+
+        for &(start, end, style) in ranges {
+            let start = start.clamp(offset, span_end) - offset;
+            let end = end.clamp(offset, span_end) - offset;
+            if start >= end {
+                continue;
+            }
+            if start > cursor {
+                spans.push(Span::styled(text[cursor..start].to_string(), span.style));
+            }
+            spans.push(Span::styled(
+                text[start..end].to_string(),
+                span.style.patch(style),
+            ));
+            cursor = end;
+        }
+
+        if cursor == 0 {
+            spans.push(span);
+        } else if cursor < text.len() {
+            spans.push(Span::styled(text[cursor..].to_string(), span.style));
+        }
+        offset = span_end;
+    }
+
+    Line {
+        spans,
+        style: line.style,
+        alignment: line.alignment,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn completes_commands_by_prefix() {
+        let names = |prefix: &str| -> Vec<String> {
+            command_candidates(SLASH_COMMANDS, prefix)
+                .into_iter()
+                .map(|command| command.name.into_owned())
+                .collect()
+        };
+
+        assert_eq!(names("/s"), vec!["/session", "/summary", "/switch"]);
+        assert_eq!(names("/mo"), vec!["/model"]);
+        assert!(names("/model").is_empty());
+        assert!(names("/unknown").is_empty());
+        assert_eq!(names("/").len(), SLASH_COMMANDS.len());
+    }
+
+    #[test]
+    fn finds_case_insensitive_matches_per_line() {
+        let texts = vec![
+            "Edit src/Main.rs".to_string(),
+            "nothing".to_string(),
+            "main.rs and MAIN.RS".to_string(),
+        ];
+        let matches = find_matches(&texts, "main.rs");
+        assert_eq!(
+            matches,
+            vec![
+                SearchMatch {
+                    line: 0,
+                    start: 9,
+                    end: 16
+                },
+                SearchMatch {
+                    line: 2,
+                    start: 0,
+                    end: 7
+                },
+                SearchMatch {
+                    line: 2,
+                    start: 12,
+                    end: 19
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_conversation_lines_to_blocks() {
+        let blocks = vec![
+            (2..6, "tool-1".to_string()),
+            (9..12, "code:m:0:0".to_string()),
+        ];
+        assert_eq!(block_at_line(&blocks, 1), None);
+        assert_eq!(block_at_line(&blocks, 2), Some("tool-1"));
+        assert_eq!(block_at_line(&blocks, 5), Some("tool-1"));
+        assert_eq!(block_at_line(&blocks, 6), None);
+        assert_eq!(block_at_line(&blocks, 11), Some("code:m:0:0"));
+    }
+
+    #[test]
+    fn highlight_splits_spans_across_a_match() {
+        let style = Style::default().bg(Color::Yellow);
+        let line = Line::from(vec![Span::raw("  read "), Span::raw("main.rs done")]);
+
+        let highlighted = highlight_line(line, &[(5, 11, style)]);
+        let parts: Vec<(&str, bool)> = highlighted
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.bg == Some(Color::Yellow)))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("  rea", false),
+                ("d ", true),
+                ("main", true),
+                (".rs done", false),
+            ]
+        );
+    }
 }
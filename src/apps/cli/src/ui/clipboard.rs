@@ -0,0 +1,77 @@
+/// Clipboard access for copy actions
+///
+/// Tries the system clipboard first, falls back to the terminal's OSC 52
+/// sequence over SSH, and writes a temp file when neither is reachable.
+use anyhow::Result;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Where copied text ended up
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyTarget {
+    /// System clipboard
+    System,
+    /// Terminal clipboard via OSC 52 (delivery cannot be confirmed)
+    Terminal,
+    /// No clipboard reachable; text written to this file
+    File(PathBuf),
+}
+
+/// Kept alive so X11/Wayland clipboard contents outlive the copy call
+static SYSTEM_CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Copy `text` using the best mechanism available in this environment
+pub fn copy(text: &str) -> Result<CopyTarget> {
+    if has_display() && copy_system(text).is_ok() {
+        return Ok(CopyTarget::System);
+    }
+    if is_remote_session() {
+        copy_osc52(text)?;
+        return Ok(CopyTarget::Terminal);
+    }
+
+    let path = std::env::temp_dir().join(format!("bitfun-copy-{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(&path, text)?;
+    Ok(CopyTarget::File(path))
+}
+
+fn copy_system(text: &str) -> Result<()> {
+    let mut clipboard = SYSTEM_CLIPBOARD
+        .lock()
+        .map_err(|_| anyhow::anyhow!("clipboard lock poisoned"))?;
+    if clipboard.is_none() {
+        *clipboard = Some(arboard::Clipboard::new()?);
+    }
+    if let Some(clipboard) = clipboard.as_mut() {
+        clipboard.set_text(text)?;
+    }
+    Ok(())
+}
+
+/// Write the OSC 52 "set clipboard" sequence; works inside tmux with `set-clipboard on`
+fn copy_osc52(text: &str) -> Result<()> {
+    use base64::Engine;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", encoded)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Whether a system clipboard can exist (Linux needs an X11 or Wayland display)
+fn has_display() -> bool {
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+fn is_remote_session() -> bool {
+    ["SSH_TTY", "SSH_CONNECTION", "TMUX"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+}
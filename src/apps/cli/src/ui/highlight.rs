@@ -0,0 +1,143 @@
+/// Syntax highlighting for fenced code blocks
+///
+/// Completed blocks are cached by content so redraws stay cheap; only the
+/// block that is still streaming gets highlighted again on every frame.
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme as SyntectTheme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Blocks larger than this are rendered plain to keep streaming smooth
+const MAX_HIGHLIGHT_BYTES: usize = 32 * 1024;
+const MAX_HIGHLIGHT_LINES: usize = 800;
+/// Completed blocks kept in the cache before it is reset
+const MAX_CACHED_BLOCKS: usize = 256;
+
+/// Indentation applied to every code line
+pub const CODE_INDENT: &str = "  ";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Code block highlighter
+pub struct CodeHighlighter {
+    /// Name of the bundled syntect theme
+    theme_name: String,
+    /// Highlighted lines of completed blocks, keyed by (language, code) hash
+    cache: Mutex<HashMap<u64, Arc<Vec<Line<'static>>>>>,
+}
+
+impl CodeHighlighter {
+    pub fn new(theme_name: String) -> Self {
+        Self {
+            theme_name,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Highlight `code` as `lang`
+    ///
+    /// Returns None when the language is unknown or the block is too large,
+    /// in which case the caller renders it plain. `complete` blocks are cached.
+    pub fn highlight(
+        &self,
+        lang: &str,
+        code: &str,
+        complete: bool,
+    ) -> Option<Arc<Vec<Line<'static>>>> {
+        if code.len() > MAX_HIGHLIGHT_BYTES || code.lines().count() > MAX_HIGHLIGHT_LINES {
+            return None;
+        }
+        let syntax = find_syntax(lang)?;
+        let theme = theme_set().themes.get(&self.theme_name)?;
+
+        let key = complete.then(|| cache_key(lang, code));
+        if let Some(key) = key {
+            if let Some(lines) = self.cache.lock().ok()?.get(&key) {
+                return Some(Arc::clone(lines));
+            }
+        }
+
+        let lines = Arc::new(highlight_lines(syntax, theme, code)?);
+
+        if let Some(key) = key {
+            if let Ok(mut cache) = self.cache.lock() {
+                if cache.len() >= MAX_CACHED_BLOCKS {
+                    cache.clear();
+                }
+                cache.insert(key, Arc::clone(&lines));
+            }
+        }
+        Some(lines)
+    }
+}
+
+/// Resolve a fence info string (e.g. "rust", "rust,ignore", "py") to a syntax
+fn find_syntax(lang: &str) -> Option<&'static SyntaxReference> {
+    let token = lang
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .filter(|token| !token.is_empty())?;
+    syntax_set().find_syntax_by_token(token)
+}
+
+fn cache_key(lang: &str, code: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    lang.hash(&mut hasher);
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn highlight_lines(
+    syntax: &SyntaxReference,
+    theme: &SyntectTheme,
+    code: &str,
+) -> Option<Vec<Line<'static>>> {
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut lines = Vec::new();
+
+    for source_line in LinesWithEndings::from(code) {
+        let ranges = highlighter.highlight_line(source_line, syntax_set()).ok()?;
+        let mut spans = vec![Span::raw(CODE_INDENT)];
+        for (style, text) in ranges {
+            let text = text.trim_end_matches(['\n', '\r']);
+            if !text.is_empty() {
+                spans.push(Span::styled(text.to_string(), convert_style(style)));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+
+    Some(lines)
+}
+
+/// Map a syntect style onto ratatui, keeping the terminal background
+fn convert_style(style: syntect::highlighting::Style) -> Style {
+    let fg = style.foreground;
+    let mut converted = Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        converted = converted.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        converted = converted.add_modifier(Modifier::UNDERLINED);
+    }
+    converted
+}
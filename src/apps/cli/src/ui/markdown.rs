@@ -4,22 +4,79 @@ use ratatui::{
     style::{Modifier, Style},
     text::{Line, Span},
 };
+use std::ops::Range;
 
+use super::highlight::{CodeHighlighter, CODE_INDENT};
 use super::theme::{StyleKind, Theme};
 
+/// Gutter replacing the code indent on lines of the focused code block
+pub const FOCUS_GUTTER: &str = "▌ ";
+
+/// Raw content of a code block
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    pub lang: String,
+    pub code: String,
+    /// Written with a ``` or ~~~ fence (as opposed to indented)
+    pub fenced: bool,
+}
+
+/// Code blocks of `markdown` in document order, indexed like `MarkdownRenderer::render`
+pub fn code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<CodeBlock> = None;
+
+    for event in Parser::new_ext(markdown, Options::all()) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let (lang, fenced) = match kind {
+                    pulldown_cmark::CodeBlockKind::Fenced(lang) => (lang.to_string(), true),
+                    pulldown_cmark::CodeBlockKind::Indented => (String::new(), false),
+                };
+                current = Some(CodeBlock {
+                    lang,
+                    code: String::new(),
+                    fenced,
+                });
+            }
+            Event::Text(text) => {
+                if let Some(block) = current.as_mut() {
+                    block.code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => blocks.extend(current.take()),
+            _ => {}
+        }
+    }
+
+    blocks
+}
+
 /// Markdown renderer
 pub struct MarkdownRenderer {
     /// Theme
     theme: Theme,
+    /// Fenced code block highlighter
+    highlighter: CodeHighlighter,
 }
 
 impl MarkdownRenderer {
     pub fn new(theme: Theme) -> Self {
-        Self { theme }
+        let highlighter = CodeHighlighter::new(theme.code_theme.clone());
+        Self { theme, highlighter }
     }
 
-    pub fn render(&self, markdown: &str, _width: usize) -> Vec<Line<'static>> {
+    /// Render `markdown`, marking the `focused_block`-th code block with `FOCUS_GUTTER`;
+    /// also returns the line range of each code block
+    pub fn render(
+        &self,
+        markdown: &str,
+        _width: usize,
+        focused_block: Option<usize>,
+    ) -> (Vec<Line<'static>>, Vec<Range<usize>>) {
         let mut lines = Vec::new();
+        let mut code_ranges = Vec::new();
+        let mut code_block_index = 0;
         let mut current_line_spans: Vec<Span<'static>> = Vec::new();
 
         // Style stack
@@ -27,11 +84,13 @@ impl MarkdownRenderer {
         let mut list_level: usize = 0;
         let mut in_code_block = false;
         let mut code_block_lang = String::new();
+        let mut code_block_text = String::new();
+        let mut code_block_closed = false;
 
         let options = Options::all();
-        let parser = Parser::new_ext(markdown, options);
+        let parser = Parser::new_ext(markdown, options).into_offset_iter();
 
-        for event in parser {
+        for (event, range) in parser {
             match event {
                 Event::Start(tag) => {
                     match tag {
@@ -72,8 +131,12 @@ impl MarkdownRenderer {
                         }
                         Tag::CodeBlock(kind) => {
                             in_code_block = true;
+                            code_block_text.clear();
+                            // Only a closed fence is final; the last block of a streaming reply is not
+                            code_block_closed = false;
                             if let pulldown_cmark::CodeBlockKind::Fenced(lang) = kind {
                                 code_block_lang = lang.to_string();
+                                code_block_closed = is_fence_closed(&markdown[range]);
                             }
                             // Add empty line before code block
                             if !current_line_spans.is_empty() {
@@ -154,6 +217,37 @@ impl MarkdownRenderer {
                             if !current_line_spans.is_empty() {
                                 lines.push(Line::from(std::mem::take(&mut current_line_spans)));
                             }
+                            let code_start = lines.len();
+                            match self.highlighter.highlight(
+                                &code_block_lang,
+                                &code_block_text,
+                                code_block_closed,
+                            ) {
+                                Some(highlighted) => lines.extend(highlighted.iter().cloned()),
+                                None => {
+                                    let style = self.compute_style(&style_stack, true);
+                                    for line in code_block_text.lines() {
+                                        lines.push(Line::from(Span::styled(
+                                            format!("{}{}", CODE_INDENT, line),
+                                            style,
+                                        )));
+                                    }
+                                }
+                            }
+                            if focused_block == Some(code_block_index) {
+                                let gutter = self.theme.style(StyleKind::Primary);
+                                for line in &mut lines[code_start..] {
+                                    if let Some(first) = line.spans.first_mut() {
+                                        if let Some(rest) = first.content.strip_prefix(CODE_INDENT)
+                                        {
+                                            *first = Span::styled(rest.to_string(), first.style);
+                                        }
+                                    }
+                                    line.spans.insert(0, Span::styled(FOCUS_GUTTER, gutter));
+                                }
+                            }
+                            code_ranges.push(code_start..lines.len());
+                            code_block_index += 1;
                             // Code block end marker
                             if !code_block_lang.is_empty() {
                                 lines.push(Line::from(Span::styled(
@@ -200,11 +294,8 @@ impl MarkdownRenderer {
                     let style = self.compute_style(&style_stack, in_code_block);
 
                     if in_code_block {
-                        // Code block: process each line separately
-                        for line in text.lines() {
-                            current_line_spans.push(Span::styled(format!("  {}", line), style));
-                            lines.push(Line::from(std::mem::take(&mut current_line_spans)));
-                        }
+                        // Code block: collected and rendered as a whole when it ends
+                        code_block_text.push_str(&text);
                     } else {
                         // Normal text
                         current_line_spans.push(Span::styled(text.to_string(), style));
@@ -248,7 +339,7 @@ impl MarkdownRenderer {
             lines.pop();
         }
 
-        lines
+        (lines, code_ranges)
     }
 
     fn compute_style(&self, stack: &[StyleModifier], in_code_block: bool) -> Style {
@@ -290,6 +381,24 @@ impl MarkdownRenderer {
     }
 }
 
+/// Whether a fenced code block's source ends with a matching closing fence
+fn is_fence_closed(block: &str) -> bool {
+    let mut block_lines = block.trim_end().lines();
+    let Some(open) = block_lines.next() else {
+        return false;
+    };
+    let open = open.trim_start();
+    let Some(fence_char) = open.chars().next() else {
+        return false;
+    };
+    let fence_len = open.chars().take_while(|&c| c == fence_char).count();
+
+    block_lines.last().is_some_and(|close| {
+        let close = close.trim();
+        close.len() >= fence_len && close.chars().all(|c| c == fence_char)
+    })
+}
+
 /// Style modifier
 #[derive(Debug, Clone, Copy)]
 enum StyleModifier {
@@ -317,7 +426,7 @@ mod tests {
     fn test_render_simple() {
         let theme = Theme::default();
         let renderer = MarkdownRenderer::new(theme);
-        let lines = renderer.render("**bold** text", 80);
+        let (lines, _) = renderer.render("**bold** text", 80, None);
         assert!(!lines.is_empty());
     }
 
@@ -326,7 +435,89 @@ mod tests {
         let theme = Theme::default();
         let renderer = MarkdownRenderer::new(theme);
         let markdown = "```rust\nfn main() {\n    println!(\"Hello\");\n}\n```";
-        let lines = renderer.render(markdown, 80);
+        let (lines, code_ranges) = renderer.render(markdown, 80, None);
         assert!(lines.len() > 3);
+        assert_eq!(code_ranges.len(), 1);
+        assert_eq!(code_ranges[0].len(), 3);
+    }
+
+    #[test]
+    fn test_code_block_highlighting() {
+        let renderer = MarkdownRenderer::new(Theme::default());
+
+        let (highlighted, _) = renderer.render(
+            "```rust
+let x = 1;
+```",
+            80,
+            None,
+        );
+        let code_line = highlighted
+            .iter()
+            .find(|line| line.spans.iter().any(|span| span.content.contains("let")))
+            .unwrap();
+        assert!(code_line.spans.len() > 2);
+
+        let (plain, _) = renderer.render(
+            "```nosuchlang
+let x = 1;
+```",
+            80,
+            None,
+        );
+        let code_line = plain
+            .iter()
+            .find(|line| line.spans.iter().any(|span| span.content.contains("let")))
+            .unwrap();
+        assert_eq!(code_line.spans.len(), 1);
+        assert_eq!(code_line.spans[0].content, "  let x = 1;");
+    }
+
+    #[test]
+    fn test_is_fence_closed() {
+        assert!(is_fence_closed(
+            "```rust
+fn main() {}
+```
+"
+        ));
+        assert!(is_fence_closed(
+            "~~~~
+code
+~~~~"
+        ));
+        assert!(!is_fence_closed(
+            "```rust
+fn main() {}
+"
+        ));
+        assert!(!is_fence_closed(
+            "````
+code
+```"
+        ));
+    }
+
+    #[test]
+    fn test_code_blocks_and_focus() {
+        let markdown = "Intro\n\n```rust\nlet a = 1;\n```\n\nThen\n\n```sh\necho hi\n```\n";
+        let blocks = code_blocks(markdown);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].lang, "sh");
+        assert_eq!(blocks[1].code, "echo hi\n");
+        assert!(blocks[1].fenced);
+
+        let renderer = MarkdownRenderer::new(Theme::default());
+        let (lines, _) = renderer.render(markdown, 80, Some(1));
+        let marked: Vec<String> = lines
+            .iter()
+            .filter(|line| {
+                line.spans
+                    .first()
+                    .is_some_and(|s| s.content == FOCUS_GUTTER)
+            })
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(marked, vec!["▌ echo hi".to_string()]);
     }
 }
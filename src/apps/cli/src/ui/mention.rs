@@ -0,0 +1,192 @@
+/// @-mention completion for workspace files
+///
+/// The file index is built in the background (ignore-aware, via core's
+/// FileTreeService) so large repositories never block the UI thread.
+use bitfun_core::infrastructure::filesystem::fuzzy_rank;
+use bitfun_core::infrastructure::FileTreeService;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bound on indexed files
+const MAX_INDEXED_FILES: usize = 50_000;
+/// An index younger than this is reused instead of rebuilt
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// Delay before a build starts, so a burst of `@` keystrokes triggers one walk
+const BUILD_DEBOUNCE: Duration = Duration::from_millis(150);
+/// Candidates shown in the popup
+pub const MAX_CANDIDATES: usize = 8;
+
+#[derive(Default)]
+struct IndexState {
+    files: Arc<Vec<String>>,
+    /// Bumped whenever `files` is replaced
+    generation: u64,
+    building: bool,
+    built_at: Option<Instant>,
+}
+
+/// A file offered by the popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionCandidate {
+    pub path: String,
+    /// Character positions in `path` matched by the query, for highlighting
+    pub match_indices: Vec<usize>,
+}
+
+/// Workspace file list for mention completion
+#[derive(Clone)]
+pub struct FileIndex {
+    workspace: Option<String>,
+    state: Arc<Mutex<IndexState>>,
+}
+
+impl FileIndex {
+    pub fn new(workspace: Option<String>) -> Self {
+        Self {
+            workspace,
+            state: Arc::new(Mutex::new(IndexState::default())),
+        }
+    }
+
+    /// Start a background rebuild unless one is running or the index is still fresh
+    pub fn ensure_fresh(&self) {
+        let Some(workspace) = self.workspace.clone() else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            let fresh = state
+                .built_at
+                .is_some_and(|built_at| built_at.elapsed() < REFRESH_INTERVAL);
+            if state.building || fresh {
+                return;
+            }
+            state.building = true;
+        }
+
+        let state = Arc::clone(&self.state);
+        handle.spawn(async move {
+            tokio::time::sleep(BUILD_DEBOUNCE).await;
+            let result = FileTreeService::default()
+                .build_file_index(&workspace, MAX_INDEXED_FILES)
+                .await;
+
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+            state.building = false;
+            state.built_at = Some(Instant::now());
+            match result {
+                Ok(files) => {
+                    tracing::debug!("Indexed {} workspace files for mentions", files.len());
+                    state.files = Arc::new(files);
+                    state.generation += 1;
+                }
+                Err(e) => tracing::warn!("Failed to index workspace files: {}", e),
+            }
+        });
+    }
+
+    /// Index generation; changes when a rebuild finishes
+    pub fn generation(&self) -> u64 {
+        self.state.lock().map(|state| state.generation).unwrap_or(0)
+    }
+
+    pub fn is_building(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.building)
+            .unwrap_or(false)
+    }
+
+    /// Best fuzzy matches for `query`, highest score first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<MentionCandidate> {
+        let files = match self.state.lock() {
+            Ok(state) => Arc::clone(&state.files),
+            Err(_) => return Vec::new(),
+        };
+
+        fuzzy_rank(files.as_slice(), query, limit)
+            .into_iter()
+            .map(|(position, found)| MentionCandidate {
+                path: files[position].clone(),
+                match_indices: found.indices,
+            })
+            .collect()
+    }
+}
+
+/// Open completion popup for the `@token` under the cursor
+#[derive(Debug, Clone, Default)]
+pub struct MentionState {
+    /// Byte offset of the `@` in the input
+    pub start: usize,
+    /// Text typed after the `@`
+    pub query: String,
+    pub candidates: Vec<MentionCandidate>,
+    pub selected: usize,
+    /// Index generation the candidates were computed from
+    pub generation: u64,
+}
+
+impl MentionState {
+    pub fn select_next(&mut self) {
+        if !self.candidates.is_empty() {
+            self.selected = (self.selected + 1) % self.candidates.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.candidates.is_empty() {
+            self.selected = (self.selected + self.candidates.len() - 1) % self.candidates.len();
+        }
+    }
+}
+
+/// The `@token` ending at `cursor`, as (byte offset of `@`, query after it)
+///
+/// A mention starts at the beginning of the input or after whitespace and
+/// runs up to the cursor without whitespace.
+pub fn mention_at(text: &str, cursor: usize) -> Option<(usize, &str)> {
+    let before = &text[..cursor];
+    let token_start = before.rfind(char::is_whitespace).map_or(0, |i| {
+        i + before[i..].chars().next().map_or(1, char::len_utf8)
+    });
+    let token = &before[token_start..];
+    token.strip_prefix('@').map(|query| (token_start, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_mention_token_before_cursor() {
+        assert_eq!(mention_at("@src/ma", 7), Some((0, "src/ma")));
+        assert_eq!(mention_at("look at @ui", 11), Some((8, "ui")));
+        assert_eq!(mention_at("look at @ui", 7), None);
+        assert_eq!(mention_at("mail@example", 12), None);
+        assert_eq!(mention_at("line\n@", 6), Some((5, "")));
+    }
+
+    #[test]
+    fn ranks_file_name_matches_first() {
+        let index = FileIndex::new(None);
+        index.state.lock().unwrap().files = Arc::new(vec![
+            "docs/chat-guide.md".to_string(),
+            "src/ui/chat.rs".to_string(),
+            "src/ui/widgets.rs".to_string(),
+        ]);
+
+        let results = index.search("chat", MAX_CANDIDATES);
+        let paths: Vec<&str> = results.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/ui/chat.rs", "docs/chat-guide.md"]);
+        assert_eq!(results[0].match_indices, vec![7, 8, 9, 10]);
+        assert!(index.search("zzz", MAX_CANDIDATES).is_empty());
+    }
+}
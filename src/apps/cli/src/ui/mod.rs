@@ -2,33 +2,52 @@
 ///
 /// Build terminal user interface using ratatui
 pub mod chat;
+pub mod clipboard;
+pub mod highlight;
 pub mod markdown;
+pub mod mention;
+pub mod notification;
+pub mod permission;
 pub mod startup;
+pub mod status_line;
 pub mod string_utils;
 pub mod theme;
 pub mod tool_cards;
 pub mod widgets;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use crossterm::{
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     text::{Line, Span},
     widgets::Paragraph,
     Terminal,
 };
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether keyboard enhancement flags were pushed (lets Shift+Enter be told apart from Enter)
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+/// Whether mouse events are captured; kept across external editor sessions
+static MOUSE_CAPTURED: AtomicBool = AtomicBool::new(false);
 
 /// Initialize terminal
 pub fn init_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
-    enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    enter_tui(&mut stdout)?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -36,15 +55,110 @@ pub fn init_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
 
 /// Restore terminal
 pub fn restore_terminal(mut terminal: Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    leave_tui(terminal.backend_mut())?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+fn enter_tui(out: &mut impl io::Write) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        out,
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )?;
+    if matches!(supports_keyboard_enhancement(), Ok(true)) {
+        execute!(
+            out,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+        KEYBOARD_ENHANCED.store(true, Ordering::Relaxed);
+    }
+    if MOUSE_CAPTURED.load(Ordering::Relaxed) {
+        execute!(out, EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+fn leave_tui(out: &mut impl io::Write) -> Result<()> {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+        execute!(out, PopKeyboardEnhancementFlags)?;
+    }
+    if MOUSE_CAPTURED.load(Ordering::Relaxed) {
+        execute!(out, DisableMouseCapture)?;
+    }
+    disable_raw_mode()?;
+    execute!(
+        out,
+        DisableFocusChange,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+    Ok(())
+}
+
+/// Turn mouse capture on or off; while off the terminal handles the mouse
+/// itself, so drag-to-select works (e.g. inside tmux)
+pub fn set_mouse_capture(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    enabled: bool,
+) -> Result<()> {
+    if enabled {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    } else {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    MOUSE_CAPTURED.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Suspend the TUI, edit `initial` in $VISUAL / $EDITOR and return the saved text
+pub fn edit_in_external_editor(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    initial: &str,
+) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+    // Allow editors configured with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let args: Vec<&str> = parts.collect();
+
+    let path = std::env::temp_dir().join(format!("bitfun-prompt-{}.md", uuid::Uuid::new_v4()));
+    std::fs::write(&path, initial)?;
+
+    leave_tui(terminal.backend_mut())?;
+    let status = std::process::Command::new(program)
+        .args(&args)
+        .arg(&path)
+        .status();
+    enter_tui(terminal.backend_mut())?;
+    terminal.clear()?;
+
+    let result = match status {
+        Ok(status) if status.success() => std::fs::read_to_string(&path).map_err(Into::into),
+        Ok(status) => Err(anyhow!("{} exited with {}", program, status)),
+        Err(e) => Err(anyhow!("Failed to launch {}: {}", program, e)),
+    };
+    let _ = std::fs::remove_file(&path);
+
+    Ok(result?.trim_end_matches(['\n', '\r']).to_string())
+}
+
 /// Render a loading/status message on the terminal (stays in alternate screen)
 pub fn render_loading(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    theme: &theme::Theme,
     message: &str,
 ) -> Result<()> {
     let msg = message.to_string();
@@ -61,8 +175,8 @@ pub fn render_loading(
 
         let text = vec![Line::from(Span::styled(
             msg,
-            Style::default()
-                .fg(Color::Cyan)
+            theme
+                .style(theme::StyleKind::Primary)
                 .add_modifier(Modifier::BOLD),
         ))];
 
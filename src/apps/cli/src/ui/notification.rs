@@ -0,0 +1,112 @@
+/// Background alerts for finished turns and permission requests
+///
+/// Alerts only fire while the terminal reports it has lost focus, so
+/// terminals without focus reporting never raise them.
+use std::io::{self, Write};
+
+use crate::config::NotificationsConfig;
+
+/// Longest notification body, in characters
+const MAX_BODY_CHARS: usize = 120;
+
+/// Event worth alerting about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    TurnComplete,
+    PermissionRequest,
+}
+
+/// Raise an alert for `kind` through the channels enabled in `config`
+pub fn alert(config: &NotificationsConfig, kind: AlertKind, title: &str, body: &str) {
+    let enabled = match kind {
+        AlertKind::TurnComplete => config.turn_complete,
+        AlertKind::PermissionRequest => config.permission_request,
+    };
+    if !enabled {
+        return;
+    }
+
+    if config.bell {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+    if config.desktop {
+        let (title, body) = (title.to_string(), sanitize(body));
+        // D-Bus round trips can block; keep them off the UI thread
+        std::thread::spawn(move || {
+            if let Err(e) = notify_rust::Notification::new()
+                .appname("BitFun")
+                .summary(&title)
+                .body(&body)
+                .show()
+            {
+                tracing::warn!("Desktop notification failed: {}", e);
+            }
+        });
+    }
+}
+
+/// First non-empty line of `text`, without control characters and truncated
+pub fn sanitize(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let mut clean = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip ANSI CSI sequences ("ESC [ ... final letter")
+            if chars.clone().next() == Some('[') {
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            }
+            continue;
+        }
+        // Notification servers may interpret markup in the body
+        if !c.is_control() && !matches!(c, '<' | '>' | '&') {
+            clean.push(c);
+        }
+    }
+
+    if clean.chars().count() <= MAX_BODY_CHARS {
+        return clean;
+    }
+    let mut truncated: String = clean.chars().take(MAX_BODY_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// "2m 05s" style duration
+pub fn format_elapsed(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_the_first_line() {
+        assert_eq!(
+            sanitize("\n  Done: \x1b[1mfixed\x1b[0m <b>tests</b>\nmore"),
+            "Done: fixed btests/b"
+        );
+
+        let long = "x".repeat(300);
+        let body = sanitize(&long);
+        assert_eq!(body.chars().count(), MAX_BODY_CHARS);
+        assert!(body.ends_with('…'));
+    }
+
+    #[test]
+    fn formats_elapsed_time() {
+        assert_eq!(format_elapsed(42), "42s");
+        assert_eq!(format_elapsed(125), "2m 05s");
+        assert_eq!(format_elapsed(3720), "1h 02m");
+    }
+}
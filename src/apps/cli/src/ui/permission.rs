@@ -0,0 +1,334 @@
+/// Tool permission prompt
+///
+/// Tools that need approval queue up here and are shown one at a time in a
+/// modal. Requests with a timeout show a countdown and drop out of the queue
+/// once the tool pipeline has stopped waiting for them.
+use bitfun_core::util::i18n::translate;
+use ratatui::{
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::string_utils::truncate_str;
+use super::theme::{StyleKind, Theme};
+use super::tool_cards;
+use crate::session::{ToolCall, ToolCallStatus};
+
+/// Command or diff lines shown in the prompt
+const PREVIEW_LINES: usize = 12;
+/// Widest the modal grows
+const MAX_PROMPT_WIDTH: u16 = 90;
+
+/// Answer to a permission request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionChoice {
+    AllowOnce,
+    /// Allow and add a rule so the tool never asks again
+    AllowAlways,
+    Deny,
+}
+
+impl PermissionChoice {
+    /// Choices in the order they are shown
+    pub const ALL: [Self; 3] = [Self::AllowOnce, Self::AllowAlways, Self::Deny];
+
+    fn label(self) -> String {
+        let (shortcut, key) = match self {
+            Self::AllowOnce => ('y', "cli-permission-allow-once"),
+            Self::AllowAlways => ('a', "cli-permission-allow-always"),
+            Self::Deny => ('n', "cli-permission-deny"),
+        };
+        format!("[{}] {}", shortcut, translate(key, &[]))
+    }
+}
+
+/// Tool call waiting for the user's answer
+#[derive(Debug, Clone)]
+pub struct PermissionRequest {
+    pub tool_call: ToolCall,
+    /// When the tool pipeline stops waiting; None waits indefinitely
+    deadline: Option<Instant>,
+}
+
+impl PermissionRequest {
+    pub fn new(
+        tool_id: String,
+        tool_name: String,
+        parameters: serde_json::Value,
+        timeout_secs: Option<u64>,
+    ) -> Self {
+        Self {
+            tool_call: ToolCall {
+                tool_id: Some(tool_id),
+                tool_name,
+                parameters,
+                result: None,
+                status: ToolCallStatus::ConfirmationNeeded,
+                progress: None,
+                progress_message: None,
+                duration_ms: None,
+            },
+            deadline: timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn tool_id(&self) -> &str {
+        self.tool_call.tool_id.as_deref().unwrap_or_default()
+    }
+
+    pub fn tool_name(&self) -> &str {
+        &self.tool_call.tool_name
+    }
+
+    /// Time left to answer; None when the request has no timeout
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    fn expired(&self, now: Instant) -> bool {
+        self.remaining(now).is_some_and(|left| left.is_zero())
+    }
+
+    /// What the tool is about to do, one entry per line
+    fn summary(&self, theme: &Theme, width: usize) -> Vec<Line<'static>> {
+        let params = &self.tool_call.parameters;
+        let mut lines = Vec::new();
+        if tool_cards::is_diff_tool(self.tool_name()) {
+            let path = tool_cards::extract_key_params(params);
+            lines.push(Line::from(Span::styled(
+                path,
+                theme.style(StyleKind::Primary),
+            )));
+            if let Some(diff) = tool_cards::file_diff(&self.tool_call) {
+                lines.extend(diff.render(theme, width));
+            }
+        } else if let Some(command) = params.get("command").and_then(|v| v.as_str()) {
+            for (index, line) in command.lines().enumerate() {
+                let prompt = if index == 0 { "$ " } else { "  " };
+                lines.push(Line::from(vec![
+                    Span::styled(prompt, theme.style(StyleKind::Muted)),
+                    Span::raw(line.to_string()),
+                ]));
+            }
+        } else {
+            let key = tool_cards::extract_key_params(params);
+            let text = if key.is_empty() {
+                params.to_string()
+            } else {
+                key
+            };
+            lines.push(Line::from(truncate_str(&text, width.max(8))));
+        }
+
+        if lines.len() > PREVIEW_LINES {
+            let hidden = lines.len() - PREVIEW_LINES;
+            lines.truncate(PREVIEW_LINES);
+            lines.push(Line::from(Span::styled(
+                format!("… {} more lines", hidden),
+                theme.style(StyleKind::Muted),
+            )));
+        }
+        lines
+    }
+}
+
+/// Queue of permission requests and the choice highlighted for the first one
+#[derive(Debug, Default)]
+pub struct PermissionPrompt {
+    queue: VecDeque<PermissionRequest>,
+    selected: usize,
+}
+
+impl PermissionPrompt {
+    pub fn push(&mut self, request: PermissionRequest) {
+        self.queue.push_back(request);
+    }
+
+    /// Whether the modal is shown (and takes the keyboard)
+    pub fn is_active(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&PermissionRequest> {
+        self.queue.front()
+    }
+
+    pub fn selected(&self) -> PermissionChoice {
+        PermissionChoice::ALL[self.selected]
+    }
+
+    /// Move the highlight to the next (or previous) choice, wrapping around
+    pub fn step(&mut self, forward: bool) {
+        let len = PermissionChoice::ALL.len();
+        self.selected = if forward {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+    }
+
+    /// Remove the current request to answer it with `choice`
+    pub fn answer(
+        &mut self,
+        choice: PermissionChoice,
+    ) -> Option<(PermissionRequest, PermissionChoice)> {
+        let request = self.queue.pop_front()?;
+        self.selected = 0;
+        Some((request, choice))
+    }
+
+    /// Remove every queued request for `tool_name` (after it was allowed always)
+    pub fn take_tool(&mut self, tool_name: &str) -> Vec<PermissionRequest> {
+        let (taken, waiting): (VecDeque<_>, VecDeque<_>) = self
+            .queue
+            .drain(..)
+            .partition(|request| request.tool_name() == tool_name);
+        self.queue = waiting;
+        taken.into()
+    }
+
+    /// Drop requests the tool pipeline no longer waits for; returns them
+    pub fn take_expired(&mut self, now: Instant) -> Vec<PermissionRequest> {
+        let (expired, waiting): (VecDeque<_>, VecDeque<_>) = self
+            .queue
+            .drain(..)
+            .partition(|request| request.expired(now));
+        self.queue = waiting;
+        if !expired.is_empty() {
+            self.selected = 0;
+        }
+        expired.into()
+    }
+
+    /// Draw the modal for the current request centered in `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(request) = self.current() else {
+            return;
+        };
+
+        let width = area.width.min(MAX_PROMPT_WIDTH);
+        let text_width = width.saturating_sub(4) as usize;
+
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                request.tool_name().to_string(),
+                theme.style(StyleKind::Warning).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" wants to run"),
+        ])];
+        lines.push(Line::raw(""));
+        lines.extend(request.summary(theme, text_width));
+        lines.push(Line::raw(""));
+
+        let mut choices = Vec::new();
+        for (index, choice) in PermissionChoice::ALL.into_iter().enumerate() {
+            let style = if index == self.selected {
+                theme
+                    .style(StyleKind::Primary)
+                    .add_modifier(Modifier::REVERSED)
+            } else {
+                theme.style(StyleKind::Text)
+            };
+            choices.push(Span::styled(format!(" {} ", choice.label()), style));
+            choices.push(Span::raw("  "));
+        }
+        lines.push(Line::from(choices));
+
+        let mut footer = vec![Span::styled(
+            "←→ select · Enter confirm",
+            theme.style(StyleKind::Hint),
+        )];
+        if let Some(left) = request.remaining(Instant::now()) {
+            footer.push(Span::styled(
+                format!(" · auto-deny in {}s", left.as_secs()),
+                theme.style(StyleKind::Warning),
+            ));
+        }
+        if self.queue.len() > 1 {
+            footer.push(Span::styled(
+                format!(" · {} more waiting", self.queue.len() - 1),
+                theme.style(StyleKind::Muted),
+            ));
+        }
+        lines.push(Line::from(footer));
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.style(StyleKind::Warning))
+            .title(" Permission required ");
+
+        frame.render_widget(Clear, popup);
+        frame.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(tool_name: &str, timeout_secs: Option<u64>) -> PermissionRequest {
+        PermissionRequest::new(
+            format!("{}-id", tool_name),
+            tool_name.to_string(),
+            serde_json::json!({ "command": "cargo test" }),
+            timeout_secs,
+        )
+    }
+
+    #[test]
+    fn answers_requests_in_arrival_order() {
+        let mut prompt = PermissionPrompt::default();
+        prompt.push(request("Bash", None));
+        prompt.push(request("Write", None));
+
+        prompt.step(false);
+        assert_eq!(prompt.selected(), PermissionChoice::Deny);
+        let (answered, choice) = prompt.answer(prompt.selected()).unwrap();
+        assert_eq!(answered.tool_id(), "Bash-id");
+        assert_eq!(choice, PermissionChoice::Deny);
+
+        // The next request starts back on "Allow once"
+        assert_eq!(prompt.selected(), PermissionChoice::AllowOnce);
+        assert_eq!(prompt.current().unwrap().tool_name(), "Write");
+    }
+
+    #[test]
+    fn drops_timed_out_requests() {
+        let mut prompt = PermissionPrompt::default();
+        prompt.push(request("Bash", Some(0)));
+        prompt.push(request("Edit", Some(60)));
+        prompt.push(request("Write", None));
+
+        let expired = prompt.take_expired(Instant::now());
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].tool_name(), "Bash");
+        assert_eq!(prompt.current().unwrap().tool_name(), "Edit");
+        let left = prompt.current().unwrap().remaining(Instant::now()).unwrap();
+        assert!(left > Duration::ZERO);
+    }
+
+    #[test]
+    fn bash_summary_shows_the_command() {
+        let theme = Theme::dark();
+        let lines = request("Bash", None).summary(&theme, 40);
+        let text: String = lines[0]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "$ cargo test");
+    }
+}
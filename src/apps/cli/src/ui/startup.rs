@@ -4,7 +4,7 @@ use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame, Terminal,
@@ -12,7 +12,10 @@ use ratatui::{
 use std::time::Duration;
 
 use crate::config::CliConfig;
-use crate::session::Session;
+use crate::session::{is_other_workspace, Session};
+use crate::ui::string_utils::fuzzy_match;
+use crate::ui::theme::{StyleKind, Theme};
+use crate::workspace::{self, RecentWorkspace};
 
 /// Startup menu result
 #[derive(Debug, Clone)]
@@ -39,6 +42,8 @@ pub struct StartupPage {
     page_state: PageState,
     /// Configuration
     config: CliConfig,
+    /// Theme
+    theme: Theme,
 }
 
 #[derive(Debug, Clone)]
@@ -73,13 +78,31 @@ enum PageState {
     Finished(StartupResult),
 }
 
-/// Workspace selection sub-page (input mode only)
+/// Workspace selection sub-page: recent workspaces or a typed path
 #[derive(Debug, Clone)]
 struct WorkspaceSelectPage {
     /// Custom input buffer
     custom_input: String,
     /// Custom input cursor position
     custom_cursor: usize,
+    /// Recently opened workspaces, newest first
+    recent: Vec<RecentWorkspace>,
+    /// Highlighted recent workspace, opened when the input is empty
+    selected: usize,
+    /// Why the last entered path was rejected
+    error: Option<String>,
+}
+
+impl WorkspaceSelectPage {
+    fn new() -> Self {
+        Self {
+            custom_input: String::new(),
+            custom_cursor: 0,
+            recent: workspace::recent_sync(),
+            selected: 0,
+            error: None,
+        }
+    }
 }
 
 /// Settings sub-page
@@ -103,7 +126,35 @@ struct AIModelsPage {
 #[derive(Debug, Clone)]
 struct HistoryPage {
     sessions: Vec<SessionItem>,
+    /// Index into the filtered list
     selected: usize,
+    /// Fuzzy filter over title, workspace and ID
+    query: String,
+    /// Session from another workspace waiting for y/n confirmation
+    confirm: Option<SessionItem>,
+}
+
+impl HistoryPage {
+    fn new(sessions: Vec<SessionItem>) -> Self {
+        Self {
+            sessions,
+            selected: 0,
+            query: String::new(),
+            confirm: None,
+        }
+    }
+
+    fn filtered(&self) -> Vec<&SessionItem> {
+        self.sessions
+            .iter()
+            .filter(|session| {
+                self.query.is_empty()
+                    || fuzzy_match(&self.query, &session.title)
+                    || fuzzy_match(&self.query, &session.workspace)
+                    || session.id.starts_with(&self.query)
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -132,6 +183,9 @@ struct SessionItem {
     workspace: String,
     agent: String,
     last_updated: String,
+    total_tokens: u64,
+    /// Workspace differs from the current directory
+    other_workspace: bool,
 }
 
 impl StartupPage {
@@ -174,11 +228,12 @@ impl StartupPage {
             selected: 0,
             list_state,
             page_state: PageState::MainMenu,
+            theme: Theme::from_config(&config),
             config,
         }
     }
 
-    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<Option<String>> {
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<StartupResult> {
         terminal.clear()?;
 
         loop {
@@ -186,18 +241,7 @@ impl StartupPage {
 
             // Check if finished
             if let PageState::Finished(result) = &self.page_state {
-                return match result {
-                    StartupResult::NewSession(ws) => Ok(Some(ws.clone())),
-                    StartupResult::Exit => Ok(None),
-                    StartupResult::ContinueSession(_id) => {
-                        // TODO: Implement session resume logic
-                        Ok(Some(".".to_string()))
-                    }
-                    StartupResult::LoadSession(_id) => {
-                        // TODO: Implement session loading logic
-                        Ok(Some(".".to_string()))
-                    }
-                };
+                return Ok(result.clone());
             }
 
             // Wait for event
@@ -252,22 +296,22 @@ impl StartupPage {
                 let icon = if is_selected { "▶" } else { " " };
 
                 let style = if is_selected {
-                    Style::default()
-                        .fg(Color::Cyan)
+                    self.theme
+                        .style(StyleKind::Primary)
                         .add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::White)
+                    self.theme.style(StyleKind::Text)
                 };
 
                 let content = vec![
                     Line::from(vec![
-                        Span::styled(icon, Style::default().fg(Color::Green)),
+                        Span::styled(icon, self.theme.style(StyleKind::Success)),
                         Span::raw("  "),
                         Span::styled(&item.name, style),
                     ]),
                     Line::from(vec![
                         Span::raw("    "),
-                        Span::styled(&item.description, Style::default().fg(Color::Gray)),
+                        Span::styled(&item.description, self.theme.style(StyleKind::Muted)),
                     ]),
                     Line::from(""),
                 ];
@@ -281,24 +325,24 @@ impl StartupPage {
                 .borders(Borders::ALL)
                 .title(" BitFun CLI - Main Menu ")
                 .title_alignment(Alignment::Center)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(self.theme.style(StyleKind::Primary)),
         );
 
         frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
 
         // Render hints
         let hints = Line::from(vec![
-            Span::styled(" ↑/↓ ", Style::default().fg(Color::Green)),
+            Span::styled(" ↑/↓ ", self.theme.style(StyleKind::Success)),
             Span::raw("Select  "),
-            Span::styled(" Enter ", Style::default().fg(Color::Green)),
+            Span::styled(" Enter ", self.theme.style(StyleKind::Success)),
             Span::raw("Confirm  "),
-            Span::styled(" Esc/q ", Style::default().fg(Color::Red)),
+            Span::styled(" Esc/q ", self.theme.style(StyleKind::Error)),
             Span::raw("Exit"),
         ]);
 
         let paragraph = Paragraph::new(hints)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray));
+            .style(self.theme.style(StyleKind::Muted));
 
         frame.render_widget(paragraph, chunks[2]);
     }
@@ -318,22 +362,8 @@ impl StartupPage {
                 "  ╚═════╝ ╚═╝   ╚═╝   ╚═╝      ╚═════╝ ╚═╝  ╚═══╝",
             ];
 
-            let colors = [
-                Color::Rgb(255, 0, 100),
-                Color::Rgb(255, 100, 0),
-                Color::Rgb(255, 200, 0),
-                Color::Rgb(100, 255, 0),
-                Color::Rgb(0, 255, 200),
-                Color::Rgb(100, 100, 255),
-            ];
-
             for (i, line) in logo.iter().enumerate() {
-                lines.push(Line::from(Span::styled(
-                    *line,
-                    Style::default()
-                        .fg(colors[i % colors.len()])
-                        .add_modifier(Modifier::BOLD),
-                )));
+                lines.push(Line::from(Span::styled(*line, self.theme.logo_style(i))));
             }
         } else {
             let logo = vec![
@@ -344,36 +374,23 @@ impl StartupPage {
                 " |____/|_|\\__|_|   \\__,_|_| |_|",
             ];
 
-            let colors = [
-                Color::Cyan,
-                Color::Blue,
-                Color::Magenta,
-                Color::Red,
-                Color::Yellow,
-            ];
-
             for (i, line) in logo.iter().enumerate() {
-                lines.push(Line::from(Span::styled(
-                    *line,
-                    Style::default()
-                        .fg(colors[i % colors.len()])
-                        .add_modifier(Modifier::BOLD),
-                )));
+                lines.push(Line::from(Span::styled(*line, self.theme.logo_style(i))));
             }
         }
 
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             "AI agent-driven command-line programming assistant",
-            Style::default()
-                .fg(Color::Gray)
+            self.theme
+                .style(StyleKind::Muted)
                 .add_modifier(Modifier::ITALIC),
         )));
 
         let version = format!("v{}", env!("CARGO_PKG_VERSION"));
         lines.push(Line::from(Span::styled(
             version,
-            Style::default().fg(Color::DarkGray),
+            self.theme.style(StyleKind::Hint),
         )));
 
         let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
@@ -391,16 +408,16 @@ impl StartupPage {
             .constraints([
                 Constraint::Length(3), // Title
                 Constraint::Length(3), // Input box
-                Constraint::Min(5),    // Help
+                Constraint::Min(5),    // Recent workspaces (or help)
                 Constraint::Length(5), // Hints
             ])
             .split(area);
 
         // Title
-        let title = Paragraph::new("Enter workspace path")
+        let title = Paragraph::new("Select a recent workspace or enter a path")
             .style(
-                Style::default()
-                    .fg(Color::Cyan)
+                self.theme
+                    .style(StyleKind::Primary)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center)
@@ -415,28 +432,129 @@ impl StartupPage {
         };
 
         let input_style = if page.custom_input.is_empty() {
-            Style::default().fg(Color::DarkGray)
+            self.theme.style(StyleKind::Hint)
         } else {
-            Style::default()
-                .fg(Color::Yellow)
+            self.theme
+                .style(StyleKind::Warning)
                 .add_modifier(Modifier::UNDERLINED)
         };
 
+        let input_title = match &page.error {
+            Some(error) => format!(" {} ", error),
+            None => " Workspace Path ".to_string(),
+        };
+        let input_border = if page.error.is_some() {
+            StyleKind::Error
+        } else {
+            StyleKind::Warning
+        };
         let input = Paragraph::new(input_display).style(input_style).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Workspace Path ")
-                .border_style(Style::default().fg(Color::Yellow)),
+                .title(input_title)
+                .border_style(self.theme.style(input_border)),
         );
         frame.render_widget(input, chunks[1]);
 
-        // Help
+        if !page.recent.is_empty() {
+            self.render_recent_workspaces(frame, chunks[2], page);
+        } else {
+            self.render_workspace_help(frame, chunks[2]);
+        }
+
+        // Hints
+        let mut keys = vec![
+            Span::styled(" Enter ", self.theme.style(StyleKind::Success)),
+            Span::raw("Open  "),
+        ];
+        if !page.recent.is_empty() {
+            keys.push(Span::styled(" ↑↓ ", self.theme.style(StyleKind::Primary)));
+            keys.push(Span::raw("Recent  "));
+        }
+        keys.extend([
+            Span::styled(" Esc ", self.theme.style(StyleKind::Error)),
+            Span::raw("Back to menu  "),
+            Span::styled(" Backspace ", self.theme.style(StyleKind::Warning)),
+            Span::raw("Delete"),
+        ]);
+        let hint = if page.recent.is_empty() {
+            " Type a path... "
+        } else {
+            " Type a path, or leave empty to open the highlighted workspace "
+        };
+        let hints_text = vec![
+            Line::from(keys),
+            Line::from(vec![Span::styled(hint, self.theme.style(StyleKind::Hint))]),
+        ];
+
+        let paragraph = Paragraph::new(hints_text)
+            .alignment(Alignment::Center)
+            .style(self.theme.style(StyleKind::Muted));
+
+        frame.render_widget(paragraph, chunks[3]);
+    }
+
+    fn render_recent_workspaces(&self, frame: &mut Frame, area: Rect, page: &WorkspaceSelectPage) {
+        let now = chrono::Utc::now();
+        let items: Vec<ListItem> = page
+            .recent
+            .iter()
+            .enumerate()
+            .map(|(i, recent)| {
+                let is_selected = i == page.selected && page.custom_input.is_empty();
+                let icon = if is_selected { "▶" } else { " " };
+                let name_style = if is_selected {
+                    self.theme
+                        .style(StyleKind::Primary)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    self.theme.style(StyleKind::Text)
+                };
+
+                let mut header = vec![
+                    Span::styled(format!(" {} ", icon), name_style),
+                    Span::styled(recent.name.clone(), name_style),
+                ];
+                if let Some(git) = &recent.git {
+                    header.push(Span::styled(
+                        format!("  {}", git.branch),
+                        self.theme.style(StyleKind::Info),
+                    ));
+                    if git.dirty {
+                        header.push(Span::styled(" *", self.theme.style(StyleKind::Warning)));
+                    }
+                }
+                header.push(Span::styled(
+                    format!("  {}", workspace::format_age(recent.last_opened, now)),
+                    self.theme.style(StyleKind::Hint),
+                ));
+
+                ListItem::new(vec![
+                    Line::from(header),
+                    Line::from(Span::styled(
+                        format!("    {}", recent.path),
+                        self.theme.style(StyleKind::Muted),
+                    )),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Recent Workspaces ")
+                .border_style(self.theme.style(StyleKind::Primary)),
+        );
+        frame.render_widget(list, area);
+    }
+
+    fn render_workspace_help(&self, frame: &mut Frame, area: Rect) {
         let help_lines = vec![
             Line::from(""),
             Line::from(vec![Span::styled(
                 "Tips:",
-                Style::default()
-                    .fg(Color::Cyan)
+                self.theme
+                    .style(StyleKind::Primary)
                     .add_modifier(Modifier::BOLD),
             )]),
             Line::from(vec![Span::raw(
@@ -444,17 +562,17 @@ impl StartupPage {
             )]),
             Line::from(vec![
                 Span::raw("  • Use "),
-                Span::styled(".", Style::default().fg(Color::Green)),
+                Span::styled(".", self.theme.style(StyleKind::Success)),
                 Span::raw(" for current directory"),
             ]),
             Line::from(vec![
                 Span::raw("  • Use "),
-                Span::styled("..", Style::default().fg(Color::Green)),
+                Span::styled("..", self.theme.style(StyleKind::Success)),
                 Span::raw(" for parent directory"),
             ]),
             Line::from(vec![
                 Span::raw("  • Path supports "),
-                Span::styled("~", Style::default().fg(Color::Green)),
+                Span::styled("~", self.theme.style(StyleKind::Success)),
                 Span::raw(" for home directory (e.g.: ~/projects)"),
             ]),
             Line::from(vec![Span::raw(
@@ -462,31 +580,9 @@ impl StartupPage {
             )]),
         ];
         let help = Paragraph::new(help_lines)
-            .style(Style::default().fg(Color::Gray))
+            .style(self.theme.style(StyleKind::Muted))
             .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(help, chunks[2]);
-
-        // Hints
-        let hints_text = vec![
-            Line::from(vec![
-                Span::styled(" Enter ", Style::default().fg(Color::Green)),
-                Span::raw("Confirm  "),
-                Span::styled(" Esc ", Style::default().fg(Color::Red)),
-                Span::raw("Back to menu  "),
-                Span::styled(" Backspace ", Style::default().fg(Color::Yellow)),
-                Span::raw("Delete"),
-            ]),
-            Line::from(vec![Span::styled(
-                " Type characters... ",
-                Style::default().fg(Color::DarkGray),
-            )]),
-        ];
-
-        let paragraph = Paragraph::new(hints_text)
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray));
-
-        frame.render_widget(paragraph, chunks[3]);
+        frame.render_widget(help, area);
     }
 
     fn render_settings(&mut self, frame: &mut Frame, area: Rect, page: &SettingsPage) {
@@ -502,8 +598,8 @@ impl StartupPage {
         // Title
         let title = Paragraph::new("Settings")
             .style(
-                Style::default()
-                    .fg(Color::Cyan)
+                self.theme
+                    .style(StyleKind::Primary)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center)
@@ -522,21 +618,21 @@ impl StartupPage {
                 let icon = if is_selected { "▶" } else { " " };
 
                 let style = if is_selected {
-                    Style::default()
-                        .fg(Color::Cyan)
+                    self.theme
+                        .style(StyleKind::Primary)
                         .add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::White)
+                    self.theme.style(StyleKind::Text)
                 };
 
                 let value_style = if is_editing {
-                    Style::default()
-                        .fg(Color::Yellow)
+                    self.theme
+                        .style(StyleKind::Warning)
                         .add_modifier(Modifier::UNDERLINED)
                 } else if setting.editable {
-                    Style::default().fg(Color::Green)
+                    self.theme.style(StyleKind::Success)
                 } else {
-                    Style::default().fg(Color::DarkGray)
+                    self.theme.style(StyleKind::Hint)
                 };
 
                 let display_value = if is_editing {
@@ -547,7 +643,7 @@ impl StartupPage {
 
                 let content = vec![
                     Line::from(vec![
-                        Span::styled(icon, Style::default().fg(Color::Green)),
+                        Span::styled(icon, self.theme.style(StyleKind::Success)),
                         Span::raw("  "),
                         Span::styled(&setting.name, style),
                         Span::raw(": "),
@@ -555,7 +651,7 @@ impl StartupPage {
                     ]),
                     Line::from(vec![
                         Span::raw("    "),
-                        Span::styled(&setting.description, Style::default().fg(Color::Gray)),
+                        Span::styled(&setting.description, self.theme.style(StyleKind::Muted)),
                     ]),
                     Line::from(""),
                 ];
@@ -570,7 +666,7 @@ impl StartupPage {
         let list = List::new(items).block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(self.theme.style(StyleKind::Primary)),
         );
 
         frame.render_stateful_widget(list, chunks[1], &mut list_state);
@@ -579,36 +675,36 @@ impl StartupPage {
         let hints_text = if page.editing.is_some() {
             vec![
                 Line::from(vec![
-                    Span::styled(" Enter ", Style::default().fg(Color::Green)),
+                    Span::styled(" Enter ", self.theme.style(StyleKind::Success)),
                     Span::raw("Save  "),
-                    Span::styled(" Esc ", Style::default().fg(Color::Red)),
+                    Span::styled(" Esc ", self.theme.style(StyleKind::Error)),
                     Span::raw("Cancel"),
                 ]),
                 Line::from(vec![Span::styled(
                     " Enter new value... ",
-                    Style::default().fg(Color::Yellow),
+                    self.theme.style(StyleKind::Warning),
                 )]),
             ]
         } else {
             vec![
                 Line::from(vec![
-                    Span::styled(" ↑/↓ ", Style::default().fg(Color::Green)),
+                    Span::styled(" ↑/↓ ", self.theme.style(StyleKind::Success)),
                     Span::raw("Select  "),
-                    Span::styled(" Enter ", Style::default().fg(Color::Green)),
+                    Span::styled(" Enter ", self.theme.style(StyleKind::Success)),
                     Span::raw("Edit  "),
-                    Span::styled(" Esc ", Style::default().fg(Color::Red)),
+                    Span::styled(" Esc ", self.theme.style(StyleKind::Error)),
                     Span::raw("Back"),
                 ]),
                 Line::from(vec![Span::styled(
                     " Changes will be auto-saved to config file ",
-                    Style::default().fg(Color::DarkGray),
+                    self.theme.style(StyleKind::Hint),
                 )]),
             ]
         };
 
         let paragraph = Paragraph::new(hints_text)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray));
+            .style(self.theme.style(StyleKind::Muted));
 
         frame.render_widget(paragraph, chunks[2]);
     }
@@ -623,46 +719,64 @@ impl StartupPage {
             ])
             .split(area);
 
+        let sessions = page.filtered();
+
         // Title
-        let title_text = format!("History Sessions (total {})", page.sessions.len());
+        let title_text = if page.query.is_empty() {
+            format!("History Sessions (total {})", page.sessions.len())
+        } else {
+            format!(
+                "History Sessions ({} of {})  Filter: {}",
+                sessions.len(),
+                page.sessions.len(),
+                page.query
+            )
+        };
         let title = Paragraph::new(title_text)
             .style(
-                Style::default()
-                    .fg(Color::Cyan)
+                self.theme
+                    .style(StyleKind::Primary)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(title, chunks[0]);
 
-        if page.sessions.is_empty() {
+        if sessions.is_empty() {
             // Empty state
+            let (message, hint) = if page.sessions.is_empty() {
+                (
+                    "No history sessions yet",
+                    "Select \"New Session\" to start your first conversation",
+                )
+            } else {
+                (
+                    "No sessions match the filter",
+                    "Backspace to edit the filter",
+                )
+            };
             let empty_text = vec![
                 Line::from(""),
                 Line::from(Span::styled(
-                    "No history sessions yet",
-                    Style::default()
-                        .fg(Color::Gray)
+                    message,
+                    self.theme
+                        .style(StyleKind::Muted)
                         .add_modifier(Modifier::ITALIC),
                 )),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "Select \"New Session\" to start your first conversation",
-                    Style::default().fg(Color::DarkGray),
-                )),
+                Line::from(Span::styled(hint, self.theme.style(StyleKind::Hint))),
             ];
             let paragraph = Paragraph::new(empty_text)
                 .alignment(Alignment::Center)
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::Cyan)),
+                        .border_style(self.theme.style(StyleKind::Primary)),
                 );
             frame.render_widget(paragraph, chunks[1]);
         } else {
             // Session list
-            let items: Vec<ListItem> = page
-                .sessions
+            let items: Vec<ListItem> = sessions
                 .iter()
                 .enumerate()
                 .map(|(i, session)| {
@@ -670,30 +784,55 @@ impl StartupPage {
                     let icon = if is_selected { "▶" } else { " " };
 
                     let style = if is_selected {
-                        Style::default()
-                            .fg(Color::Cyan)
+                        self.theme
+                            .style(StyleKind::Primary)
                             .add_modifier(Modifier::BOLD)
+                    } else if session.other_workspace {
+                        self.theme.style(StyleKind::Muted)
                     } else {
-                        Style::default().fg(Color::White)
+                        self.theme.style(StyleKind::Text)
                     };
 
+                    let mut workspace_spans = vec![
+                        Span::raw("    "),
+                        Span::styled("Agent: ", self.theme.style(StyleKind::Hint)),
+                        Span::styled(&session.agent, self.theme.style(StyleKind::Info)),
+                        Span::raw("  |  "),
+                        Span::styled("Workspace: ", self.theme.style(StyleKind::Hint)),
+                    ];
+                    if session.other_workspace {
+                        workspace_spans.push(Span::styled(
+                            &session.workspace,
+                            self.theme.style(StyleKind::Warning),
+                        ));
+                        workspace_spans.push(Span::styled(
+                            "  (other workspace)",
+                            self.theme
+                                .style(StyleKind::Warning)
+                                .add_modifier(Modifier::ITALIC),
+                        ));
+                    } else {
+                        workspace_spans.push(Span::styled(
+                            &session.workspace,
+                            self.theme.style(StyleKind::Success),
+                        ));
+                    }
+
                     let content = vec![
                         Line::from(vec![
-                            Span::styled(icon, Style::default().fg(Color::Green)),
+                            Span::styled(icon, self.theme.style(StyleKind::Success)),
                             Span::raw("  "),
                             Span::styled(&session.title, style),
                         ]),
+                        Line::from(workspace_spans),
                         Line::from(vec![
                             Span::raw("    "),
-                            Span::styled("Agent: ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(&session.agent, Style::default().fg(Color::Blue)),
+                            Span::styled(&session.last_updated, self.theme.style(StyleKind::Muted)),
                             Span::raw("  |  "),
-                            Span::styled("Workspace: ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(&session.workspace, Style::default().fg(Color::Green)),
-                        ]),
-                        Line::from(vec![
-                            Span::raw("    "),
-                            Span::styled(&session.last_updated, Style::default().fg(Color::Gray)),
+                            Span::styled(
+                                format!("{} tokens", session.total_tokens),
+                                self.theme.style(StyleKind::Hint),
+                            ),
                         ]),
                         Line::from(""),
                     ];
@@ -708,25 +847,40 @@ impl StartupPage {
             let list = List::new(items).block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .border_style(self.theme.style(StyleKind::Primary)),
             );
 
             frame.render_stateful_widget(list, chunks[1], &mut list_state);
         }
 
         // Hints
-        let hints = Line::from(vec![
-            Span::styled(" ↑/↓ ", Style::default().fg(Color::Green)),
-            Span::raw("Select  "),
-            Span::styled(" Enter ", Style::default().fg(Color::Green)),
-            Span::raw("Load  "),
-            Span::styled(" Esc ", Style::default().fg(Color::Red)),
-            Span::raw("Back"),
-        ]);
+        let hints = if let Some(session) = &page.confirm {
+            Line::from(vec![
+                Span::styled(
+                    format!("Open session from {}? ", session.workspace),
+                    self.theme.style(StyleKind::Warning),
+                ),
+                Span::styled(" y ", self.theme.style(StyleKind::Success)),
+                Span::raw("Open  "),
+                Span::styled(" n ", self.theme.style(StyleKind::Error)),
+                Span::raw("Cancel"),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled(" ↑/↓ ", self.theme.style(StyleKind::Success)),
+                Span::raw("Select  "),
+                Span::styled(" Type ", self.theme.style(StyleKind::Success)),
+                Span::raw("Filter  "),
+                Span::styled(" Enter ", self.theme.style(StyleKind::Success)),
+                Span::raw("Load  "),
+                Span::styled(" Esc ", self.theme.style(StyleKind::Error)),
+                Span::raw("Back"),
+            ])
+        };
 
         let paragraph = Paragraph::new(hints)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray));
+            .style(self.theme.style(StyleKind::Muted));
 
         frame.render_widget(paragraph, chunks[2]);
     }
@@ -745,8 +899,8 @@ impl StartupPage {
         let title_text = format!("AI Model Configuration (total {})", page.models.len());
         let title = Paragraph::new(title_text)
             .style(
-                Style::default()
-                    .fg(Color::Cyan)
+                self.theme
+                    .style(StyleKind::Primary)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center)
@@ -759,14 +913,14 @@ impl StartupPage {
                 Line::from(""),
                 Line::from(Span::styled(
                     "No models configured yet",
-                    Style::default()
-                        .fg(Color::Gray)
+                    self.theme
+                        .style(StyleKind::Muted)
                         .add_modifier(Modifier::ITALIC),
                 )),
                 Line::from(""),
                 Line::from(Span::styled(
                     "Press N to create your first model configuration",
-                    Style::default().fg(Color::DarkGray),
+                    self.theme.style(StyleKind::Hint),
                 )),
             ];
             let paragraph = Paragraph::new(empty_text)
@@ -774,7 +928,7 @@ impl StartupPage {
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::Cyan)),
+                        .border_style(self.theme.style(StyleKind::Primary)),
                 );
             frame.render_widget(paragraph, chunks[1]);
         } else {
@@ -788,11 +942,11 @@ impl StartupPage {
                     let icon = if is_selected { "▶" } else { " " };
 
                     let style = if is_selected {
-                        Style::default()
-                            .fg(Color::Cyan)
+                        self.theme
+                            .style(StyleKind::Primary)
                             .add_modifier(Modifier::BOLD)
                     } else {
-                        Style::default().fg(Color::White)
+                        self.theme.style(StyleKind::Text)
                     };
 
                     // Status marker
@@ -806,14 +960,14 @@ impl StartupPage {
 
                     let content = vec![
                         Line::from(vec![
-                            Span::styled(icon, Style::default().fg(Color::Green)),
+                            Span::styled(icon, self.theme.style(StyleKind::Success)),
                             Span::raw("  "),
                             Span::styled(
                                 status_icon,
-                                Style::default().fg(if model.is_default {
-                                    Color::Yellow
+                                self.theme.style(if model.is_default {
+                                    StyleKind::Warning
                                 } else {
-                                    Color::Green
+                                    StyleKind::Success
                                 }),
                             ),
                             Span::raw(" "),
@@ -821,11 +975,11 @@ impl StartupPage {
                         ]),
                         Line::from(vec![
                             Span::raw("      "),
-                            Span::styled("Provider: ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(&model.provider, Style::default().fg(Color::Blue)),
+                            Span::styled("Provider: ", self.theme.style(StyleKind::Hint)),
+                            Span::styled(&model.provider, self.theme.style(StyleKind::Info)),
                             Span::raw("  |  "),
-                            Span::styled("Model: ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(&model.model_name, Style::default().fg(Color::Magenta)),
+                            Span::styled("Model: ", self.theme.style(StyleKind::Hint)),
+                            Span::styled(&model.model_name, self.theme.style(StyleKind::Accent)),
                         ]),
                         Line::from(""),
                     ];
@@ -840,7 +994,7 @@ impl StartupPage {
             let list = List::new(items).block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .border_style(self.theme.style(StyleKind::Primary)),
             );
 
             frame.render_stateful_widget(list, chunks[1], &mut list_state);
@@ -849,30 +1003,30 @@ impl StartupPage {
         // Hints
         let hints_text = vec![
             Line::from(vec![
-                Span::styled(" ↑/↓ ", Style::default().fg(Color::Green)),
+                Span::styled(" ↑/↓ ", self.theme.style(StyleKind::Success)),
                 Span::raw("Select  "),
-                Span::styled(" Enter ", Style::default().fg(Color::Green)),
+                Span::styled(" Enter ", self.theme.style(StyleKind::Success)),
                 Span::raw("Set default  "),
-                Span::styled(" E ", Style::default().fg(Color::Yellow)),
+                Span::styled(" E ", self.theme.style(StyleKind::Warning)),
                 Span::raw("Edit  "),
-                Span::styled(" N ", Style::default().fg(Color::Cyan)),
+                Span::styled(" N ", self.theme.style(StyleKind::Primary)),
                 Span::raw("New"),
             ]),
             Line::from(vec![
-                Span::styled(" Esc ", Style::default().fg(Color::Red)),
+                Span::styled(" Esc ", self.theme.style(StyleKind::Error)),
                 Span::raw("Back  "),
-                Span::styled(" * ", Style::default().fg(Color::Yellow)),
+                Span::styled(" * ", self.theme.style(StyleKind::Warning)),
                 Span::raw("Default model  "),
-                Span::styled(" + ", Style::default().fg(Color::Green)),
+                Span::styled(" + ", self.theme.style(StyleKind::Success)),
                 Span::raw("Enabled  "),
-                Span::styled(" - ", Style::default().fg(Color::DarkGray)),
+                Span::styled(" - ", self.theme.style(StyleKind::Hint)),
                 Span::raw("Disabled"),
             ]),
         ];
 
         let paragraph = Paragraph::new(hints_text)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray));
+            .style(self.theme.style(StyleKind::Muted));
 
         frame.render_widget(paragraph, chunks[2]);
     }
@@ -980,31 +1134,30 @@ impl StartupPage {
                 match action {
                     MenuAction::NewSession => {
                         // Enter workspace input page
-                        self.page_state = PageState::WorkspaceSelect(WorkspaceSelectPage {
-                            custom_input: String::new(),
-                            custom_cursor: 0,
-                        });
+                        self.page_state = PageState::WorkspaceSelect(WorkspaceSelectPage::new());
                     }
                     MenuAction::ContinueLastSession => {
-                        // Load last session
-                        if let Ok(Some(session)) = Session::get_last() {
-                            self.page_state =
-                                PageState::Finished(StartupResult::ContinueSession(session.id));
+                        // Load last session, confirming first if it belongs elsewhere
+                        let sessions = Self::load_sessions();
+                        if let Some(last) = sessions.first().cloned() {
+                            if last.other_workspace {
+                                let mut page = HistoryPage::new(sessions);
+                                page.confirm = Some(last);
+                                self.page_state = PageState::History(page);
+                            } else {
+                                self.page_state =
+                                    PageState::Finished(StartupResult::ContinueSession(last.id));
+                            }
                         } else {
                             // No history session, enter new session
-                            self.page_state = PageState::WorkspaceSelect(WorkspaceSelectPage {
-                                custom_input: String::new(),
-                                custom_cursor: 0,
-                            });
+                            self.page_state =
+                                PageState::WorkspaceSelect(WorkspaceSelectPage::new());
                         }
                     }
                     MenuAction::BrowseHistory => {
                         // Enter history session browsing
                         let sessions = Self::load_sessions();
-                        self.page_state = PageState::History(HistoryPage {
-                            sessions,
-                            selected: 0,
-                        });
+                        self.page_state = PageState::History(HistoryPage::new(sessions));
                     }
                     MenuAction::Settings => {
                         // Enter settings page
@@ -1036,14 +1189,27 @@ impl StartupPage {
     ) -> Result<()> {
         match key.code {
             KeyCode::Enter => {
-                // If input is empty, use current directory
-                let path = if page.custom_input.is_empty() {
-                    ".".to_string()
+                // Empty input opens the highlighted recent workspace, or the current directory
+                let path = if !page.custom_input.is_empty() {
+                    workspace::expand_path(&page.custom_input, None)
+                        .to_string_lossy()
+                        .to_string()
+                } else if let Some(recent) = page.recent.get(page.selected) {
+                    recent.path.clone()
                 } else {
-                    // Handle path expansion (~ and relative paths)
-                    self.expand_path(&page.custom_input)
+                    ".".to_string()
                 };
-                self.page_state = PageState::Finished(StartupResult::NewSession(path));
+                if std::path::Path::new(&path).is_dir() {
+                    self.page_state = PageState::Finished(StartupResult::NewSession(path));
+                } else {
+                    page.error = Some(format!("Not a directory: {}", path));
+                }
+            }
+            KeyCode::Up => {
+                page.selected = page.selected.saturating_sub(1);
+            }
+            KeyCode::Down if page.selected + 1 < page.recent.len() => {
+                page.selected += 1;
             }
             KeyCode::Esc => {
                 // Return to main menu
@@ -1081,40 +1247,22 @@ impl StartupPage {
             KeyCode::Char(c) => {
                 page.custom_input.insert(page.custom_cursor, c);
                 page.custom_cursor += 1;
+                page.error = None;
             }
             _ => {}
         }
         Ok(())
     }
 
-    fn expand_path(&self, path: &str) -> String {
-        let path = path.trim();
-
-        // Handle paths starting with ~
-        if path.starts_with('~') {
-            if let Some(home) = dirs::home_dir() {
-                let rest = &path[1..];
-                return home
-                    .join(rest.trim_start_matches('/'))
-                    .to_string_lossy()
-                    .to_string();
-            }
-        }
-
-        // Handle relative and absolute paths
-        if let Ok(absolute) = std::fs::canonicalize(path) {
-            absolute.to_string_lossy().to_string()
-        } else {
-            // If path doesn't exist, still return original path (let subsequent code handle it)
-            path.to_string()
-        }
-    }
-
     fn handle_settings_key(&mut self, key: KeyEvent, page: &mut SettingsPage) -> Result<()> {
         if let Some(editing_idx) = page.editing {
             match key.code {
                 KeyCode::Enter => {
                     let setting = &mut page.settings[editing_idx];
+                    if setting.key == "ui.theme" && Theme::load(&page.edit_buffer).is_err() {
+                        // Keep editing until the name resolves to a theme
+                        return Ok(());
+                    }
                     setting.value = page.edit_buffer.clone();
                     self.update_config_value(&setting.key, &setting.value)?;
                     page.editing = None;
@@ -1175,28 +1323,51 @@ impl StartupPage {
     }
 
     fn handle_history_key(&mut self, key: KeyEvent, page: &mut HistoryPage) -> Result<()> {
+        if let Some(session) = page.confirm.take() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.page_state = PageState::Finished(StartupResult::LoadSession(session.id));
+            }
+            return Ok(());
+        }
+
+        let visible = page.filtered().len();
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+            KeyCode::Up => {
                 if page.selected > 0 {
                     page.selected -= 1;
                 }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if !page.sessions.is_empty() && page.selected < page.sessions.len() - 1 {
-                    page.selected += 1;
-                }
+            KeyCode::Down if visible > 0 && page.selected < visible - 1 => {
+                page.selected += 1;
             }
             KeyCode::Enter => {
-                if !page.sessions.is_empty() {
-                    let session_id = page.sessions[page.selected].id.clone();
-                    self.page_state = PageState::Finished(StartupResult::LoadSession(session_id));
+                if let Some(session) = page.filtered().get(page.selected).map(|s| (*s).clone()) {
+                    if session.other_workspace {
+                        page.confirm = Some(session);
+                    } else {
+                        self.page_state =
+                            PageState::Finished(StartupResult::LoadSession(session.id));
+                    }
                 }
             }
+            KeyCode::Backspace => {
+                page.query.pop();
+                page.selected = 0;
+            }
+            KeyCode::Char(c) => {
+                page.query.push(c);
+                page.selected = 0;
+            }
             KeyCode::Esc => {
-                // Return to main menu
-                self.page_state = PageState::MainMenu;
-                self.selected = 0;
-                self.list_state.select(Some(0));
+                if !page.query.is_empty() {
+                    page.query.clear();
+                    page.selected = 0;
+                } else {
+                    // Return to main menu
+                    self.page_state = PageState::MainMenu;
+                    self.selected = 0;
+                    self.list_state.select(Some(0));
+                }
             }
             _ => {}
         }
@@ -1284,7 +1455,9 @@ impl StartupPage {
                 key: "ui.theme".to_string(),
                 name: "Theme".to_string(),
                 value: config.ui.theme.clone(),
-                description: "Interface theme (dark, light)".to_string(),
+                description:
+                    "Interface theme (auto, dark, light, high-contrast or a file in themes/)"
+                        .to_string(),
                 editable: true,
             },
             SettingItem {
@@ -1347,16 +1520,19 @@ impl StartupPage {
     }
 
     fn load_sessions() -> Vec<SessionItem> {
+        let current_dir = std::env::current_dir().ok();
         Session::list_all()
             .ok()
             .unwrap_or_default()
             .into_iter()
             .map(|s| SessionItem {
+                other_workspace: is_other_workspace(s.workspace.as_deref(), current_dir.as_deref()),
                 id: s.id,
                 title: s.title,
                 workspace: s.workspace.unwrap_or_else(|| "None".to_string()),
                 agent: s.agent,
                 last_updated: s.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+                total_tokens: s.total_tokens,
             })
             .collect()
     }
@@ -1364,7 +1540,10 @@ impl StartupPage {
     fn update_config_value(&mut self, key: &str, value: &str) -> Result<()> {
         match key {
             "behavior.default_agent" => self.config.behavior.default_agent = value.to_string(),
-            "ui.theme" => self.config.ui.theme = value.to_string(),
+            "ui.theme" => {
+                self.theme = Theme::load(value)?;
+                self.config.ui.theme = value.to_string();
+            }
             "ui.show_tips" => {
                 if let Ok(v) = value.parse::<bool>() {
                     self.config.ui.show_tips = v;
@@ -0,0 +1,255 @@
+/// Model and usage segments of the status bar
+///
+/// Token and phase updates arrive far more often than the text is worth
+/// re-formatting, so the segments are rebuilt at most every `REFRESH_INTERVAL`.
+use bitfun_core::agentic::core::ProcessingPhase;
+use bitfun_core::infrastructure::offline::OfflineReason;
+use bitfun_core::util::i18n::translate;
+use ratatui::text::Span;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+use super::theme::{StyleKind, Theme};
+
+/// Minimum time between two rebuilds of the segments
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+/// Separator between segments
+const SEPARATOR: &str = " │ ";
+/// Index of the model name in `StatusLine::spans`
+const MODEL_SPAN: usize = 2;
+
+/// Segment texts as of the last refresh
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Segments {
+    /// Phase label; None while idle
+    phase: Option<String>,
+    /// Label shown while idle
+    ready: String,
+    model: String,
+    context: Option<String>,
+    cost: Option<String>,
+    offline: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct StatusLine {
+    model: Option<String>,
+    /// Context window configured for the model, used until a round reports one
+    model_window: Option<u64>,
+    /// Tokens in the latest round's prompt and the window they fill
+    context: Option<(u64, Option<u64>)>,
+    cost_usd: Option<f64>,
+    phase: Option<ProcessingPhase>,
+    offline: Option<OfflineReason>,
+    dirty: bool,
+    refreshed_at: Option<Instant>,
+    segments: Segments,
+}
+
+fn phase_label(phase: &ProcessingPhase) -> String {
+    let key = match phase {
+        ProcessingPhase::Starting => "cli-status-starting",
+        ProcessingPhase::Thinking => "cli-status-thinking",
+        ProcessingPhase::Streaming => "cli-status-responding",
+        ProcessingPhase::ToolCalling => "cli-status-running-tools",
+        ProcessingPhase::ToolConfirming => "cli-status-awaiting-approval",
+    };
+    translate(key, &[])
+}
+
+/// "1.2k" style token count
+pub(crate) fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        0..=999 => tokens.to_string(),
+        1_000..=999_999 => format!("{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("{:.1}M", tokens as f64 / 1_000_000.0),
+    }
+}
+
+impl StatusLine {
+    /// Active model and its configured context window
+    pub fn set_model(&mut self, name: String, context_window: Option<u64>) {
+        self.model = Some(name);
+        self.model_window = context_window;
+        self.dirty = true;
+    }
+
+    pub fn set_context(&mut self, used_tokens: u64, window_tokens: Option<u64>) {
+        self.context = Some((used_tokens, window_tokens));
+        self.dirty = true;
+    }
+
+    pub fn set_cost(&mut self, session_usd: f64) {
+        self.cost_usd = Some(session_usd);
+        self.dirty = true;
+    }
+
+    /// Current phase of the running turn; None once it ends
+    pub fn set_phase(&mut self, phase: Option<ProcessingPhase>) {
+        self.phase = phase;
+        self.dirty = true;
+    }
+
+    /// Why network features are off; None while online
+    pub fn set_offline(&mut self, reason: Option<OfflineReason>) {
+        if self.offline != reason {
+            self.offline = reason;
+            self.dirty = true;
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.phase.is_some()
+    }
+
+    /// Forget per-session usage (new session)
+    pub fn reset_usage(&mut self) {
+        self.context = None;
+        self.cost_usd = None;
+        self.dirty = true;
+    }
+
+    fn refresh(&mut self, now: Instant) {
+        let due = match self.refreshed_at {
+            Some(at) => self.dirty && now.duration_since(at) >= REFRESH_INTERVAL,
+            None => true,
+        };
+        if !due {
+            return;
+        }
+
+        let context = self.context.map(|(used, window)| {
+            match window.or(self.model_window).filter(|window| *window > 0) {
+                Some(window) => format!(
+                    "ctx {}% ({}/{})",
+                    (used * 100 / window).min(100),
+                    format_tokens(used),
+                    format_tokens(window)
+                ),
+                None => format!("ctx {}", format_tokens(used)),
+            }
+        });
+        self.segments = Segments {
+            phase: self.phase.as_ref().map(phase_label),
+            ready: translate("cli-status-ready", &[]),
+            model: self
+                .model
+                .clone()
+                .unwrap_or_else(|| translate("cli-status-default-model", &[])),
+            context,
+            cost: self.cost_usd.map(|usd| format!("${:.4}", usd)),
+            offline: self.offline.map(|_| translate("cli-status-offline", &[])),
+        };
+        self.dirty = false;
+        self.refreshed_at = Some(now);
+    }
+
+    /// Segments to draw, starting with the phase (led by `spinner` while busy)
+    pub fn spans(&mut self, spinner: &str, theme: &Theme) -> Vec<Span<'static>> {
+        self.refresh(Instant::now());
+        let segments = &self.segments;
+
+        let mut spans = vec![match &segments.phase {
+            Some(label) => Span::styled(
+                format!("{} {}", spinner, label),
+                theme.style(StyleKind::Primary),
+            ),
+            None => Span::styled(segments.ready.clone(), theme.style(StyleKind::Muted)),
+        }];
+        spans.push(Span::raw(SEPARATOR));
+        spans.push(Span::styled(
+            segments.model.clone(),
+            theme.style(StyleKind::Accent),
+        ));
+        for text in [&segments.context, &segments.cost].into_iter().flatten() {
+            spans.push(Span::raw(SEPARATOR));
+            spans.push(Span::raw(text.clone()));
+        }
+        if let Some(offline) = &segments.offline {
+            spans.push(Span::raw(SEPARATOR));
+            spans.push(Span::styled(
+                offline.clone(),
+                theme.style(StyleKind::Warning),
+            ));
+        }
+        spans.push(Span::raw(" "));
+        spans
+    }
+}
+
+/// Display width of `spans`
+pub fn spans_width(spans: &[Span]) -> usize {
+    spans.iter().map(|span| span.content.width()).sum()
+}
+
+/// Columns of the model name within `spans` from `StatusLine::spans`
+pub fn model_columns(spans: &[Span]) -> Range<usize> {
+    let start = spans_width(&spans[..MODEL_SPAN.min(spans.len())]);
+    let width = spans.get(MODEL_SPAN).map_or(0, |span| span.content.width());
+    start..start + width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitfun_core::service::i18n::LocaleId;
+    use bitfun_core::util::i18n::set_current_locale;
+
+    #[test]
+    fn formats_context_fill_against_the_model_window() {
+        let mut line = StatusLine::default();
+        line.set_model("gpt-x".to_string(), Some(200_000));
+        line.set_context(50_000, None);
+        line.refresh(Instant::now());
+        assert_eq!(
+            line.segments.context.as_deref(),
+            Some("ctx 25% (50.0k/200.0k)")
+        );
+
+        // A window reported by the round wins over the configured one
+        line.set_context(50_000, Some(100_000));
+        line.refresh(Instant::now() + REFRESH_INTERVAL);
+        assert_eq!(
+            line.segments.context.as_deref(),
+            Some("ctx 50% (50.0k/100.0k)")
+        );
+    }
+
+    #[test]
+    fn locates_the_model_segment() {
+        set_current_locale(LocaleId::EnUS);
+        let mut line = StatusLine::default();
+        line.set_model("gpt-x".to_string(), None);
+        let spans = line.spans("⠋", &Theme::dark());
+        // "Ready │ gpt-x"
+        assert_eq!(model_columns(&spans), 8..13);
+    }
+
+    #[test]
+    fn shows_offline_after_the_model() {
+        set_current_locale(LocaleId::EnUS);
+        let mut line = StatusLine::default();
+        line.set_model("gpt-x".to_string(), None);
+        line.set_offline(Some(OfflineReason::Configured));
+        let spans = line.spans("⠋", &Theme::dark());
+        assert_eq!(model_columns(&spans), 8..13);
+        assert_eq!(spans[spans.len() - 2].content, "Offline");
+    }
+
+    #[test]
+    fn throttles_rebuilds() {
+        let start = Instant::now();
+        let mut line = StatusLine::default();
+        line.set_cost(0.5);
+        line.refresh(start);
+        assert_eq!(line.segments.cost.as_deref(), Some("$0.5000"));
+
+        line.set_cost(0.75);
+        line.refresh(start + Duration::from_millis(10));
+        assert_eq!(line.segments.cost.as_deref(), Some("$0.5000"));
+
+        line.refresh(start + REFRESH_INTERVAL);
+        assert_eq!(line.segments.cost.as_deref(), Some("$0.7500"));
+    }
+}
@@ -39,3 +39,13 @@ pub fn prettify_result(s: &str) -> String {
 
     truncate_str(s, 80)
 }
+
+/// Case-insensitive subsequence match: every query character appears in `text` in order
+pub fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| chars.any(|c| c == q))
+}
@@ -1,16 +1,62 @@
 /// Theme and style definitions
+///
+/// Widgets take every color from a `Theme`, addressed by semantic role
+/// (`StyleKind`). Besides the built-in themes, a theme can be defined as a
+/// TOML file in the `themes` config directory:
+///
+/// ```toml
+/// base = "dark"                  # built-in theme to start from
+/// code_theme = "base16-ocean.dark"
+/// logo = ["#ff0064", "#ff6400"]
+///
+/// [colors]
+/// user = "light_green"
+/// background = "#101820"
+///
+/// [modifiers]
+/// assistant = ["bold"]
+/// ```
+use anyhow::{anyhow, Result};
 use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::config::CliConfig;
+
+/// Built-in theme names
+pub const BUILTIN_THEMES: &[&str] = &["dark", "light", "high-contrast"];
 
 #[derive(Debug, Clone)]
 pub struct Theme {
+    /// Name the theme was loaded by
+    pub name: String,
     pub primary: Color,
     pub success: Color,
     pub warning: Color,
     pub error: Color,
     pub info: Color,
     pub muted: Color,
-    pub background: Color,
+    pub title: Color,
     pub border: Color,
+    /// Regular text; `Color::Reset` keeps the terminal foreground
+    pub text: Color,
+    pub hint: Color,
+    pub user: Color,
+    pub assistant: Color,
+    pub tool_border: Color,
+    pub diff_add: Color,
+    pub diff_remove: Color,
+    pub status_bar: Color,
+    pub accent: Color,
+    pub background: Color,
+    /// Per-line colors of the startup logo
+    pub logo: Vec<Color>,
+    /// Bundled syntect theme used for code blocks
+    pub code_theme: String,
+    /// Modifiers added to a role's color
+    pub modifiers: HashMap<StyleKind, Modifier>,
 }
 
 impl Default for Theme {
@@ -21,48 +67,293 @@ impl Default for Theme {
 
 impl Theme {
     pub fn dark() -> Self {
+        let primary = Color::Rgb(59, 130, 246); // blue
+        let success = Color::Rgb(34, 197, 94); // green
+        let muted = Color::Rgb(156, 163, 175); // gray
         Self {
-            primary: Color::Rgb(59, 130, 246),  // blue
-            success: Color::Rgb(34, 197, 94),   // green
-            warning: Color::Rgb(251, 191, 36),  // yellow
-            error: Color::Rgb(239, 68, 68),     // red
-            info: Color::Rgb(147, 197, 253),    // light blue
-            muted: Color::Rgb(156, 163, 175),   // gray
+            name: "dark".to_string(),
+            primary,
+            success,
+            warning: Color::Rgb(251, 191, 36), // yellow
+            error: Color::Rgb(239, 68, 68),    // red
+            info: Color::Rgb(147, 197, 253),   // light blue
+            muted,
+            title: primary,
+            border: Color::Rgb(55, 65, 81), // border gray
+            text: Color::Reset,
+            hint: muted,
+            user: success,
+            assistant: primary,
+            tool_border: muted,
+            diff_add: success,
+            diff_remove: Color::Rgb(239, 68, 68),
+            status_bar: muted,
+            accent: Color::Rgb(147, 51, 234),   // purple
             background: Color::Rgb(17, 24, 39), // dark gray background
-            border: Color::Rgb(55, 65, 81),     // border gray
+            logo: vec![
+                Color::Rgb(255, 0, 100),
+                Color::Rgb(255, 100, 0),
+                Color::Rgb(255, 200, 0),
+                Color::Rgb(100, 255, 0),
+                Color::Rgb(0, 255, 200),
+                Color::Rgb(100, 100, 255),
+            ],
+            code_theme: "base16-ocean.dark".to_string(),
+            modifiers: default_modifiers(),
         }
     }
 
     pub fn light() -> Self {
+        let primary = Color::Rgb(37, 99, 235);
+        let success = Color::Rgb(22, 163, 74);
+        let muted = Color::Rgb(107, 114, 128);
         Self {
-            primary: Color::Rgb(37, 99, 235),
-            success: Color::Rgb(22, 163, 74),
-            warning: Color::Rgb(245, 158, 11),
+            name: "light".to_string(),
+            primary,
+            success,
+            warning: Color::Rgb(180, 83, 9),
             error: Color::Rgb(220, 38, 38),
-            info: Color::Rgb(59, 130, 246),
-            muted: Color::Rgb(107, 114, 128),
-            background: Color::Rgb(249, 250, 251),
+            info: Color::Rgb(29, 78, 216),
+            muted,
+            title: primary,
             border: Color::Rgb(209, 213, 219),
+            text: Color::Reset,
+            hint: muted,
+            user: success,
+            assistant: primary,
+            tool_border: muted,
+            diff_add: success,
+            diff_remove: Color::Rgb(220, 38, 38),
+            status_bar: muted,
+            accent: Color::Rgb(126, 34, 206),
+            background: Color::Rgb(249, 250, 251),
+            logo: vec![
+                Color::Rgb(190, 18, 60),
+                Color::Rgb(194, 65, 12),
+                Color::Rgb(161, 98, 7),
+                Color::Rgb(21, 128, 61),
+                Color::Rgb(15, 118, 110),
+                Color::Rgb(67, 56, 202),
+            ],
+            code_theme: "InspiredGitHub".to_string(),
+            modifiers: default_modifiers(),
         }
     }
 
-    pub fn style(&self, kind: StyleKind) -> Style {
+    /// Bright ANSI colors only, so it follows the terminal's own palette
+    pub fn high_contrast() -> Self {
+        let mut modifiers = default_modifiers();
+        modifiers.insert(StyleKind::Title, Modifier::BOLD | Modifier::UNDERLINED);
+        modifiers.insert(StyleKind::User, Modifier::BOLD);
+        modifiers.insert(StyleKind::Error, Modifier::BOLD);
+        modifiers.remove(&StyleKind::Hint);
+        Self {
+            name: "high-contrast".to_string(),
+            primary: Color::LightCyan,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            info: Color::White,
+            muted: Color::Gray,
+            title: Color::White,
+            border: Color::White,
+            text: Color::White,
+            hint: Color::Gray,
+            user: Color::LightGreen,
+            assistant: Color::LightCyan,
+            tool_border: Color::White,
+            diff_add: Color::LightGreen,
+            diff_remove: Color::LightRed,
+            status_bar: Color::White,
+            accent: Color::LightMagenta,
+            background: Color::Black,
+            logo: vec![Color::LightCyan, Color::White],
+            code_theme: "base16-eighties.dark".to_string(),
+            modifiers,
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Load a theme by name: "auto", a built-in theme, or `themes/<name>.toml`
+    pub fn load(name: &str) -> Result<Self> {
+        if name == "auto" {
+            let mut theme = match terminal_is_dark() {
+                Some(false) => Self::light(),
+                _ => Self::dark(),
+            };
+            theme.name = "auto".to_string();
+            return Ok(theme);
+        }
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+
+        let path = CliConfig::config_dir()?
+            .join("themes")
+            .join(format!("{}.toml", name));
+        if !path.exists() {
+            return Err(anyhow!(
+                "Unknown theme '{}' (available: {})",
+                name,
+                Self::available().join(", ")
+            ));
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let mut theme = Self::from_toml(&content)
+            .map_err(|e| anyhow!("Invalid theme {}: {}", path.display(), e))?;
+        theme.name = name.to_string();
+        Ok(theme)
+    }
+
+    /// Theme selected in the CLI config, falling back to dark when it cannot be loaded
+    pub fn from_config(config: &CliConfig) -> Self {
+        Self::load(&config.ui.theme).unwrap_or_else(|e| {
+            tracing::warn!("{}, using dark theme", e);
+            Self::dark()
+        })
+    }
+
+    /// Built-in themes followed by the theme files in the config directory
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = std::iter::once("auto")
+            .chain(BUILTIN_THEMES.iter().copied())
+            .map(str::to_string)
+            .collect();
+        let mut custom: Vec<String> = CliConfig::config_dir()
+            .ok()
+            .and_then(|dir| std::fs::read_dir(dir.join("themes")).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                (path.extension()? == "toml")
+                    .then(|| path.file_stem()?.to_str().map(str::to_string))?
+            })
+            .filter(|name| !names.contains(name))
+            .collect();
+        custom.sort();
+        names.extend(custom);
+        names
+    }
+
+    /// Parse a theme definition (see the module docs for the format)
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: ThemeFile = toml::from_str(content)?;
+        let base = file.base.as_deref().unwrap_or("dark");
+        let mut theme =
+            Self::builtin(base).ok_or_else(|| anyhow!("unknown base theme '{}'", base))?;
+
+        if let Some(code_theme) = file.code_theme {
+            theme.code_theme = code_theme;
+        }
+        if !file.logo.is_empty() {
+            theme.logo = file
+                .logo
+                .iter()
+                .map(|value| parse_color(value))
+                .collect::<Result<_>>()?;
+        }
+        for (role, value) in &file.colors {
+            let color = parse_color(value)?;
+            if role == "background" {
+                theme.background = color;
+                continue;
+            }
+            let kind = StyleKind::from_name(role)
+                .ok_or_else(|| anyhow!("unknown color role '{}'", role))?;
+            *theme.color_mut(kind) = color;
+        }
+        for (role, names) in &file.modifiers {
+            let kind = StyleKind::from_name(role)
+                .ok_or_else(|| anyhow!("unknown modifier role '{}'", role))?;
+            let modifier = names.iter().try_fold(Modifier::empty(), |acc, name| {
+                parse_modifier(name).map(|modifier| acc | modifier)
+            })?;
+            theme.modifiers.insert(kind, modifier);
+        }
+
+        Ok(theme)
+    }
+
+    pub fn color(&self, kind: StyleKind) -> Color {
         match kind {
-            StyleKind::Primary => Style::default().fg(self.primary),
-            StyleKind::Success => Style::default().fg(self.success),
-            StyleKind::Warning => Style::default().fg(self.warning),
-            StyleKind::Error => Style::default().fg(self.error),
-            StyleKind::Info => Style::default().fg(self.info),
-            StyleKind::Muted => Style::default().fg(self.muted),
-            StyleKind::Title => Style::default()
-                .fg(self.primary)
-                .add_modifier(Modifier::BOLD),
-            StyleKind::Border => Style::default().fg(self.border),
+            StyleKind::Primary => self.primary,
+            StyleKind::Success => self.success,
+            StyleKind::Warning => self.warning,
+            StyleKind::Error => self.error,
+            StyleKind::Info => self.info,
+            StyleKind::Muted => self.muted,
+            StyleKind::Title => self.title,
+            StyleKind::Border => self.border,
+            StyleKind::Text => self.text,
+            StyleKind::Hint => self.hint,
+            StyleKind::User => self.user,
+            StyleKind::Assistant => self.assistant,
+            StyleKind::ToolBorder => self.tool_border,
+            StyleKind::DiffAdd => self.diff_add,
+            StyleKind::DiffRemove => self.diff_remove,
+            StyleKind::StatusBar => self.status_bar,
+            StyleKind::Accent => self.accent,
         }
     }
+
+    fn color_mut(&mut self, kind: StyleKind) -> &mut Color {
+        match kind {
+            StyleKind::Primary => &mut self.primary,
+            StyleKind::Success => &mut self.success,
+            StyleKind::Warning => &mut self.warning,
+            StyleKind::Error => &mut self.error,
+            StyleKind::Info => &mut self.info,
+            StyleKind::Muted => &mut self.muted,
+            StyleKind::Title => &mut self.title,
+            StyleKind::Border => &mut self.border,
+            StyleKind::Text => &mut self.text,
+            StyleKind::Hint => &mut self.hint,
+            StyleKind::User => &mut self.user,
+            StyleKind::Assistant => &mut self.assistant,
+            StyleKind::ToolBorder => &mut self.tool_border,
+            StyleKind::DiffAdd => &mut self.diff_add,
+            StyleKind::DiffRemove => &mut self.diff_remove,
+            StyleKind::StatusBar => &mut self.status_bar,
+            StyleKind::Accent => &mut self.accent,
+        }
+    }
+
+    pub fn style(&self, kind: StyleKind) -> Style {
+        let style = Style::default().fg(self.color(kind));
+        match self.modifiers.get(&kind) {
+            Some(modifier) => style.add_modifier(*modifier),
+            None => style,
+        }
+    }
+
+    /// Style of line `index` of the startup logo
+    pub fn logo_style(&self, index: usize) -> Style {
+        let color = match self.logo.len() {
+            0 => self.accent,
+            len => self.logo[index % len],
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+fn default_modifiers() -> HashMap<StyleKind, Modifier> {
+    HashMap::from([
+        (StyleKind::Title, Modifier::BOLD),
+        (StyleKind::Hint, Modifier::DIM),
+    ])
+}
+
+/// Semantic style roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StyleKind {
     Primary,
     Success,
@@ -72,18 +363,245 @@ pub enum StyleKind {
     Muted,
     Title,
     Border,
+    /// Regular text
+    Text,
+    /// Secondary labels and key hints
+    Hint,
+    /// User messages
+    User,
+    /// Assistant messages
+    Assistant,
+    /// Tool card frame
+    ToolBorder,
+    DiffAdd,
+    DiffRemove,
+    StatusBar,
+    /// Brand color (product name)
+    Accent,
+}
+
+impl StyleKind {
+    /// Role name as written in theme files
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "primary" => Self::Primary,
+            "success" => Self::Success,
+            "warning" => Self::Warning,
+            "error" => Self::Error,
+            "info" => Self::Info,
+            "muted" => Self::Muted,
+            "title" => Self::Title,
+            "border" => Self::Border,
+            "text" => Self::Text,
+            "hint" => Self::Hint,
+            "user" => Self::User,
+            "assistant" => Self::Assistant,
+            "tool_border" => Self::ToolBorder,
+            "diff_add" => Self::DiffAdd,
+            "diff_remove" => Self::DiffRemove,
+            "status_bar" => Self::StatusBar,
+            "accent" => Self::Accent,
+            _ => return None,
+        })
+    }
 }
 
-pub fn tool_icon(tool_name: &str) -> (&'static str, Color) {
+/// Tool card icon
+pub fn tool_icon(tool_name: &str) -> &'static str {
     match tool_name {
-        "FileReadTool" => ("[R]", Color::Rgb(59, 130, 246)),
-        "FileWriteTool" => ("[W]", Color::Rgb(34, 197, 94)),
-        "FileEditTool" => ("[E]", Color::Rgb(251, 191, 36)),
-        "FileDeleteTool" => ("[D]", Color::Rgb(239, 68, 68)),
-        "BashTool" | "ShellTool" => ("[!]", Color::Rgb(147, 51, 234)),
-        "GitTool" => ("[G]", Color::Rgb(249, 115, 22)),
-        "SearchTool" => ("[S]", Color::Rgb(59, 130, 246)),
-        "AnalysisTool" => ("[A]", Color::Rgb(236, 72, 153)),
-        _ => ("[T]", Color::Rgb(156, 163, 175)),
+        "FileReadTool" => "[R]",
+        "FileWriteTool" => "[W]",
+        "FileEditTool" => "[E]",
+        "FileDeleteTool" => "[D]",
+        "BashTool" | "ShellTool" => "[!]",
+        "GitTool" => "[G]",
+        "SearchTool" => "[S]",
+        "AnalysisTool" => "[A]",
+        _ => "[T]",
+    }
+}
+
+/// Theme file contents
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    code_theme: Option<String>,
+    #[serde(default)]
+    logo: Vec<String>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+    #[serde(default)]
+    modifiers: HashMap<String, Vec<String>>,
+}
+
+/// "#rrggbb", an ANSI color name ("light_green", "gray") or a 256-color index
+fn parse_color(value: &str) -> Result<Color> {
+    Color::from_str(value).map_err(|_| anyhow!("invalid color '{}'", value))
+}
+
+fn parse_modifier(name: &str) -> Result<Modifier> {
+    Ok(match name {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" => Modifier::UNDERLINED,
+        "reversed" => Modifier::REVERSED,
+        "crossed_out" => Modifier::CROSSED_OUT,
+        _ => return Err(anyhow!("invalid modifier '{}'", name)),
+    })
+}
+
+/// Whether the terminal background is dark; None when it cannot be told
+///
+/// Asks the terminal with OSC 11 and falls back to `COLORFGBG`. The answer
+/// is cached, so the query runs at most once per process.
+pub fn terminal_is_dark() -> Option<bool> {
+    static IS_DARK: OnceLock<Option<bool>> = OnceLock::new();
+    *IS_DARK.get_or_init(|| {
+        query_background()
+            .map(|(r, g, b)| luminance(r, g, b) < 128.0)
+            .or_else(colorfgbg_is_dark)
+    })
+}
+
+/// Perceived brightness of a color, 0-255
+pub fn luminance(r: u8, g: u8, b: u8) -> f32 {
+    0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32
+}
+
+/// `COLORFGBG` is "fg;bg" (or "fg;default;bg") in ANSI color indices
+fn colorfgbg_is_dark() -> Option<bool> {
+    let value = std::env::var("COLORFGBG").ok()?;
+    let background: u8 = value.rsplit(';').next()?.parse().ok()?;
+    Some(!matches!(background, 7 | 9..=15))
+}
+
+/// Parse an OSC 11 reply such as "\x1b]11;rgb:1e1e/1e1e/2e2e\x07"
+fn parse_osc11(reply: &str) -> Option<(u8, u8, u8)> {
+    let rgb = reply.split("rgb:").nth(1)?;
+    let rgb = rgb
+        .trim_end_matches(['\x07', '\\'])
+        .trim_end_matches('\x1b');
+    let mut channels = rgb.split('/').map(|hex| {
+        let digits = hex.get(..hex.len().min(4))?;
+        let value = u32::from_str_radix(digits, 16).ok()?;
+        let max = (1u32 << (4 * digits.len() as u32)) - 1;
+        Some((value * 255 / max) as u8)
+    });
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+#[cfg(unix)]
+fn query_background() -> Option<(u8, u8, u8)> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use std::io::{IsTerminal, Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    let was_raw = is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+
+    let mut reply = Vec::new();
+    if tty
+        .write_all(b"\x1b]11;?\x07")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        // Terminals without OSC 11 never answer, so wait only briefly
+        let deadline = Instant::now() + Duration::from_millis(150);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let mut fds = libc::pollfd {
+                fd: tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: `fds` is a valid pollfd for an open descriptor and outlives the call
+            let ready = unsafe { libc::poll(&mut fds, 1, remaining.as_millis() as libc::c_int) };
+            let mut buf = [0u8; 64];
+            let read = match ready {
+                1.. => tty.read(&mut buf).unwrap_or(0),
+                _ => 0,
+            };
+            if read == 0 {
+                break;
+            }
+            reply.extend_from_slice(&buf[..read]);
+            if reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\") {
+                break;
+            }
+        }
+    }
+
+    if !was_raw {
+        let _ = disable_raw_mode();
+    }
+    parse_osc11(&String::from_utf8_lossy(&reply))
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<(u8, u8, u8)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_osc11_replies() {
+        assert_eq!(
+            parse_osc11("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+            Some((255, 255, 255))
+        );
+        assert_eq!(
+            parse_osc11("\x1b]11;rgb:1e/1e/2e\x1b\\"),
+            Some((30, 30, 46))
+        );
+        assert_eq!(parse_osc11(""), None);
+    }
+
+    #[test]
+    fn theme_file_overrides_base_theme() {
+        let theme = Theme::from_toml(
+            r##"
+base = "light"
+logo = ["red"]
+
+[colors]
+user = "#102030"
+background = "black"
+
+[modifiers]
+assistant = ["bold", "italic"]
+"##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.user, Color::Rgb(16, 32, 48));
+        assert_eq!(theme.background, Color::Black);
+        assert_eq!(theme.primary, Theme::light().primary);
+        assert_eq!(theme.logo, vec![Color::Red]);
+        assert_eq!(
+            theme.style(StyleKind::Assistant),
+            Style::default()
+                .fg(theme.assistant)
+                .add_modifier(Modifier::BOLD | Modifier::ITALIC)
+        );
+
+        assert!(Theme::from_toml("[colors]\nnot_a_role = \"red\"").is_err());
+        assert!(Theme::from_toml("[colors]\nuser = \"not-a-color\"").is_err());
     }
 }
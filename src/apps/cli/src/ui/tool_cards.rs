@@ -1,22 +1,133 @@
 /// Tool card rendering
-use ratatui::{
-    text::{Line, Span},
-    widgets::ListItem,
-};
+///
+/// Cards render collapsed as a short summary. Expanded cards show the full
+/// output in a scrollable window of `EXPANDED_CARD_LINES` lines.
+use ratatui::style::Modifier;
+use ratatui::text::{Line, Span};
 
 use super::string_utils::{prettify_result, truncate_str};
 use super::theme::{StyleKind, Theme};
+use super::widgets::DiffView;
 use crate::session::ToolCall;
 
-pub fn render_tool_card<'a>(tool_call: &'a ToolCall, theme: &Theme) -> Vec<ListItem<'a>> {
+/// Output lines visible at once in an expanded card
+pub const EXPANDED_CARD_LINES: usize = 15;
+/// Diff lines shown in a collapsed edit card
+const DIFF_PREVIEW_LINES: usize = 8;
+/// Columns taken by the card frame ("  │ ")
+const CARD_FRAME_WIDTH: usize = 4;
+
+/// Interactive state of one tool card
+#[derive(Debug, Clone, Default)]
+pub struct ToolCardState {
+    /// Explicit expand state; None uses the per-tool default
+    pub expanded: Option<bool>,
+    /// First visible output line; None starts at the top (or follows a running tool)
+    pub scroll: Option<usize>,
+    /// Progress output streamed while the tool runs
+    pub live_output: String,
+}
+
+/// How a card is drawn in the current frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CardView<'s> {
+    pub expanded: bool,
+    pub focused: bool,
+    pub scroll: Option<usize>,
+    pub live_output: &'s str,
+    /// Width available to the card, for wrapping diff lines
+    pub width: usize,
+}
+
+/// Full output of a card: the result, or the live output while running
+pub fn card_output<'s>(tool_call: &'s ToolCall, live_output: &'s str) -> &'s str {
+    match &tool_call.result {
+        Some(result) => result,
+        None if !live_output.is_empty() => live_output,
+        None => tool_call.progress_message.as_deref().unwrap_or_default(),
+    }
+}
+
+/// Tools whose cards show the file change as a diff
+pub fn is_diff_tool(tool_name: &str) -> bool {
+    matches!(
+        tool_name,
+        "Edit" | "Write" | "GetFileDiff" | "write_file" | "write_file_tool" | "search_replace"
+    )
+}
+
+/// File change made (or shown) by an edit, write or diff tool call
+///
+/// Uses the unified diff from GetFileDiff results, and otherwise diffs the
+/// edit's old and new strings; a write shows its whole content as added.
+pub fn file_diff(tool_call: &ToolCall) -> Option<DiffView> {
+    let text = |key: &str| tool_call.parameters.get(key).and_then(|v| v.as_str());
+    let result: Option<serde_json::Value> = tool_call
+        .result
+        .as_deref()
+        .and_then(|result| serde_json::from_str(result).ok());
+
+    let unified = result
+        .as_ref()
+        .filter(|result| result.get("diff_type").and_then(|v| v.as_str()) != Some("full"))
+        .and_then(|result| result.get("diff_content")?.as_str());
+    let diff = match (
+        unified,
+        text("old_string"),
+        text("new_string"),
+        text("content"),
+    ) {
+        (Some(unified), ..) => DiffView::from_unified(unified),
+        (None, Some(old), Some(new), _) => {
+            let start_line = result
+                .as_ref()
+                .and_then(|result| result.get("start_line")?.as_u64())
+                .map(|line| line as usize);
+            DiffView::from_texts(old, new, start_line)
+        }
+        (None, None, None, Some(content)) => DiffView::from_texts("", content, Some(1)),
+        _ => return None,
+    };
+    (!diff.is_empty()).then_some(diff)
+}
+
+/// Lines an expanded card scrolls through
+pub fn expanded_line_count(
+    tool_call: &ToolCall,
+    live_output: &str,
+    theme: &Theme,
+    width: usize,
+) -> usize {
+    match file_diff(tool_call).filter(|_| is_diff_tool(&tool_call.tool_name)) {
+        Some(diff) => diff
+            .render(theme, width.saturating_sub(CARD_FRAME_WIDTH))
+            .len(),
+        None => card_output(tool_call, live_output).lines().count(),
+    }
+}
+
+/// First visible output line of an expanded card with `total` output lines
+pub fn expanded_scroll(tool_call: &ToolCall, scroll: Option<usize>, total: usize) -> usize {
+    let max = total.saturating_sub(EXPANDED_CARD_LINES);
+    match scroll {
+        Some(scroll) => scroll.min(max),
+        // Running tools follow their newest output
+        None if tool_call.result.is_none() => max,
+        None => 0,
+    }
+}
+
+pub fn render_tool_card<'a>(
+    tool_call: &'a ToolCall,
+    theme: &Theme,
+    view: CardView,
+) -> Vec<Line<'a>> {
     let mut items = Vec::new();
 
     // Choose specialized renderer based on tool type
     match tool_call.tool_name.as_str() {
+        name if is_diff_tool(name) => render_diff_card(&mut items, tool_call, theme, view),
         "read_file" | "read_file_tool" => render_read_file_card(&mut items, tool_call, theme),
-        "write_file" | "write_file_tool" | "search_replace" => {
-            render_write_file_card(&mut items, tool_call, theme)
-        }
         "bash_tool" | "run_terminal_cmd" => render_bash_tool_card(&mut items, tool_call, theme),
         "codebase_search" => render_codebase_search_card(&mut items, tool_call, theme),
         "grep" => render_grep_card(&mut items, tool_call, theme),
@@ -24,17 +135,83 @@ pub fn render_tool_card<'a>(tool_call: &'a ToolCall, theme: &Theme) -> Vec<ListI
         _ => render_default_tool_card(&mut items, tool_call, theme),
     }
 
+    if view.expanded && !is_diff_tool(&tool_call.tool_name) {
+        // Every card ends with a one-line summary; the full output replaces it
+        items.pop();
+        render_expanded_output(&mut items, tool_call, theme, view);
+    }
+
+    if view.focused {
+        if let Some(span) = items.first_mut().and_then(|line| line.spans.first_mut()) {
+            *span = Span::styled(
+                "  ┏━ ",
+                theme.style(StyleKind::Primary).add_modifier(Modifier::BOLD),
+            );
+        }
+    }
+
     items
 }
 
-fn render_read_file_card<'a>(
-    items: &mut Vec<ListItem<'a>>,
+/// Card frame glyphs
+fn border(glyph: &'static str, theme: &Theme) -> Span<'static> {
+    Span::styled(glyph, theme.style(StyleKind::ToolBorder))
+}
+
+fn render_expanded_output<'a>(
+    items: &mut Vec<Line<'a>>,
+    tool_call: &ToolCall,
+    theme: &Theme,
+    view: CardView,
+) {
+    let output = card_output(tool_call, view.live_output);
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.is_empty() {
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
+            Span::styled("No output", theme.style(StyleKind::Muted)),
+        ]));
+        return;
+    }
+
+    let start = expanded_scroll(tool_call, view.scroll, lines.len());
+    let end = (start + EXPANDED_CARD_LINES).min(lines.len());
+    for line in &lines[start..end] {
+        items.push(Line::from(vec![
+            border("  │ ", theme),
+            Span::raw(line.to_string()),
+        ]));
+    }
+
+    push_expanded_footer(items, (start, end, lines.len()), theme, view);
+}
+
+/// Footer of an expanded card showing lines `start..end` of `total`
+fn push_expanded_footer(
+    items: &mut Vec<Line<'_>>,
+    (start, end, total): (usize, usize, usize),
+    theme: &Theme,
+    view: CardView,
+) {
+    let mut footer = format!("lines {}-{} of {}", start + 1, end, total);
+    if view.focused {
+        footer.push_str("  ↑↓ scroll · Enter collapse · y copy · d set default");
+    }
+    items.push(Line::from(vec![
+        border("  └─ ", theme),
+        Span::styled(footer, theme.style(StyleKind::Muted)),
+    ]));
+}
+
+/// Edit, write and diff tools: the change as a diff rather than the raw result
+fn render_diff_card<'a>(
+    items: &mut Vec<Line<'a>>,
     tool_call: &'a ToolCall,
     theme: &Theme,
+    view: CardView,
 ) {
     use crate::session::ToolCallStatus;
 
-    // Get file path
     let file_path = tool_call
         .parameters
         .get("file_path")
@@ -42,7 +219,12 @@ fn render_read_file_card<'a>(
         .and_then(|v| v.as_str())
         .unwrap_or("unknown");
 
-    // Status icon
+    let (label, title) = match tool_call.tool_name.as_str() {
+        "Write" | "write_file" | "write_file_tool" => ("[Write] ", "Write file"),
+        "GetFileDiff" => ("[Diff] ", "File diff"),
+        _ => ("[Edit] ", "Edit file"),
+    };
+
     let (status_icon, status_style) = match &tool_call.status {
         ToolCallStatus::Running | ToolCallStatus::Streaming => {
             ("*", theme.style(StyleKind::Primary))
@@ -52,44 +234,74 @@ fn render_read_file_card<'a>(
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    // Top border
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
-        Span::raw("[Read] "),
-        Span::styled("Read file", theme.style(StyleKind::Info)),
+    let diff = file_diff(tool_call);
+    let mut header = vec![
+        border("  ┌─ ", theme),
+        Span::raw(label),
+        Span::styled(title, theme.style(StyleKind::Warning)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ];
+    if let Some(diff) = &diff {
+        header.push(Span::styled(
+            format!("  +{}", diff.added),
+            theme.style(StyleKind::DiffAdd),
+        ));
+        header.push(Span::styled(
+            format!(" −{}", diff.removed),
+            theme.style(StyleKind::DiffRemove),
+        ));
+    }
+    items.push(Line::from(header));
 
-    // File path
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  │ "),
+    items.push(Line::from(vec![
+        border("  │ ", theme),
         Span::styled(file_path, theme.style(StyleKind::Primary)),
-    ])));
+    ]));
+
+    let rows = diff
+        .map(|diff| diff.render(theme, view.width.saturating_sub(CARD_FRAME_WIDTH)))
+        .unwrap_or_default();
+    let push_row = |items: &mut Vec<Line<'a>>, row: Line<'static>| {
+        let mut spans = vec![border("  │ ", theme)];
+        spans.extend(row.spans);
+        items.push(Line::from(spans));
+    };
 
-    // Result (if available)
-    if let Some(result) = &tool_call.result {
-        let summary = truncate_str(result, 80);
+    if view.expanded && !rows.is_empty() {
+        let total = rows.len();
+        let start = expanded_scroll(tool_call, view.scroll, total);
+        let end = (start + EXPANDED_CARD_LINES).min(total);
+        for row in rows.into_iter().skip(start).take(end - start) {
+            push_row(items, row);
+        }
+        push_expanded_footer(items, (start, end, total), theme, view);
+        return;
+    }
 
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
-            Span::styled(summary, theme.style(StyleKind::Muted)),
-        ])));
-    } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
-            Span::styled("Reading...", theme.style(StyleKind::Muted)),
-        ])));
+    let hidden = rows.len().saturating_sub(DIFF_PREVIEW_LINES);
+    for row in rows.into_iter().take(DIFF_PREVIEW_LINES) {
+        push_row(items, row);
     }
+
+    let footer = match (&tool_call.status, &tool_call.result) {
+        (ToolCallStatus::Failed, Some(error)) => {
+            Span::styled(truncate_str(error, 200), theme.style(StyleKind::Error))
+        }
+        (_, None) => Span::styled("Modifying...", theme.style(StyleKind::Muted)),
+        _ if hidden > 0 => Span::styled(
+            format!("{} more lines · Enter to expand", hidden),
+            theme.style(StyleKind::Muted),
+        ),
+        _ => Span::styled("Done", theme.style(StyleKind::Success)),
+    };
+    items.push(Line::from(vec![border("  └─ ", theme), footer]));
 }
 
-fn render_write_file_card<'a>(
-    items: &mut Vec<ListItem<'a>>,
-    tool_call: &'a ToolCall,
-    theme: &Theme,
-) {
+fn render_read_file_card<'a>(items: &mut Vec<Line<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
     use crate::session::ToolCallStatus;
 
+    // Get file path
     let file_path = tool_call
         .parameters
         .get("file_path")
@@ -97,44 +309,48 @@ fn render_write_file_card<'a>(
         .and_then(|v| v.as_str())
         .unwrap_or("unknown");
 
+    // Status icon
     let (status_icon, status_style) = match &tool_call.status {
-        ToolCallStatus::Running => ("*", theme.style(StyleKind::Primary)),
+        ToolCallStatus::Running | ToolCallStatus::Streaming => {
+            ("*", theme.style(StyleKind::Primary))
+        }
         ToolCallStatus::Success => ("+", theme.style(StyleKind::Success)),
         ToolCallStatus::Failed => ("x", theme.style(StyleKind::Error)),
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
-        Span::raw("[Edit] "),
-        Span::styled("Edit file", theme.style(StyleKind::Warning)),
+    // Top border
+    items.push(Line::from(vec![
+        border("  ┌─ ", theme),
+        Span::raw("[Read] "),
+        Span::styled("Read file", theme.style(StyleKind::Info)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ]));
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  │ "),
+    // File path
+    items.push(Line::from(vec![
+        border("  │ ", theme),
         Span::styled(file_path, theme.style(StyleKind::Primary)),
-    ])));
+    ]));
 
+    // Result (if available)
     if let Some(result) = &tool_call.result {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
-            Span::styled(result, theme.style(StyleKind::Success)),
-        ])));
+        let summary = truncate_str(result, 80);
+
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
+            Span::styled(summary, theme.style(StyleKind::Muted)),
+        ]));
     } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
-            Span::styled("Modifying...", theme.style(StyleKind::Muted)),
-        ])));
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
+            Span::styled("Reading...", theme.style(StyleKind::Muted)),
+        ]));
     }
 }
 
-fn render_bash_tool_card<'a>(
-    items: &mut Vec<ListItem<'a>>,
-    tool_call: &'a ToolCall,
-    theme: &Theme,
-) {
+fn render_bash_tool_card<'a>(items: &mut Vec<Line<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
     use crate::session::ToolCallStatus;
 
     let command = tool_call
@@ -152,21 +368,21 @@ fn render_bash_tool_card<'a>(
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
+    items.push(Line::from(vec![
+        border("  ┌─ ", theme),
         Span::raw("[Bash] "),
         Span::styled("Execute command", theme.style(StyleKind::Primary)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ]));
 
     // Command (limited length)
     let cmd_display = truncate_str(command, 60);
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  │ "),
+    items.push(Line::from(vec![
+        border("  │ ", theme),
         Span::styled(cmd_display, theme.style(StyleKind::Info)),
-    ])));
+    ]));
 
     // Output summary
     if let Some(result) = &tool_call.result {
@@ -179,20 +395,20 @@ fn render_bash_tool_card<'a>(
 
         let summary_short = truncate_str(&summary, 80);
 
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled(summary_short, theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled("Executing...", theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     }
 }
 
 fn render_codebase_search_card<'a>(
-    items: &mut Vec<ListItem<'a>>,
+    items: &mut Vec<Line<'a>>,
     tool_call: &'a ToolCall,
     theme: &Theme,
 ) {
@@ -211,18 +427,18 @@ fn render_codebase_search_card<'a>(
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
+    items.push(Line::from(vec![
+        border("  ┌─ ", theme),
         Span::raw("[Search] "),
         Span::styled("Code search", theme.style(StyleKind::Info)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ]));
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  │ "),
+    items.push(Line::from(vec![
+        border("  │ ", theme),
         Span::styled(query, theme.style(StyleKind::Primary)),
-    ])));
+    ]));
 
     if let Some(result) = &tool_call.result {
         // Try to parse result count
@@ -232,19 +448,19 @@ fn render_codebase_search_card<'a>(
             "Search complete"
         };
 
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled(summary, theme.style(StyleKind::Success)),
-        ])));
+        ]));
     } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled("Searching...", theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     }
 }
 
-fn render_grep_card<'a>(items: &mut Vec<ListItem<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
+fn render_grep_card<'a>(items: &mut Vec<Line<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
     use crate::session::ToolCallStatus;
 
     let pattern = tool_call
@@ -260,36 +476,36 @@ fn render_grep_card<'a>(items: &mut Vec<ListItem<'a>>, tool_call: &'a ToolCall,
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
+    items.push(Line::from(vec![
+        border("  ┌─ ", theme),
         Span::raw("[Grep] "),
         Span::styled("Text search", theme.style(StyleKind::Info)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ]));
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  │ "),
+    items.push(Line::from(vec![
+        border("  │ ", theme),
         Span::styled(pattern, theme.style(StyleKind::Primary)),
-    ])));
+    ]));
 
     if let Some(result) = &tool_call.result {
         let lines_count = result.lines().count();
         let summary = format!("Found {} matches", lines_count);
 
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled(summary, theme.style(StyleKind::Success)),
-        ])));
+        ]));
     } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled("Searching...", theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     }
 }
 
-fn render_list_dir_card<'a>(items: &mut Vec<ListItem<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
+fn render_list_dir_card<'a>(items: &mut Vec<Line<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
     use crate::session::ToolCallStatus;
 
     let path = tool_call
@@ -306,43 +522,39 @@ fn render_list_dir_card<'a>(items: &mut Vec<ListItem<'a>>, tool_call: &'a ToolCa
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
+    items.push(Line::from(vec![
+        border("  ┌─ ", theme),
         Span::raw("[List] "),
         Span::styled("List directory", theme.style(StyleKind::Info)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ]));
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  │ "),
+    items.push(Line::from(vec![
+        border("  │ ", theme),
         Span::styled(path, theme.style(StyleKind::Primary)),
-    ])));
+    ]));
 
     if let Some(result) = &tool_call.result {
         let items_count = result.lines().count();
         let summary = format!("{} items", items_count);
 
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled(summary, theme.style(StyleKind::Success)),
-        ])));
+        ]));
     } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled("Reading...", theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     }
 }
 
-fn render_default_tool_card<'a>(
-    items: &mut Vec<ListItem<'a>>,
-    tool_call: &'a ToolCall,
-    theme: &Theme,
-) {
+fn render_default_tool_card<'a>(items: &mut Vec<Line<'a>>, tool_call: &'a ToolCall, theme: &Theme) {
     use crate::session::ToolCallStatus;
 
-    let (icon, _color) = crate::ui::theme::tool_icon(&tool_call.tool_name);
+    let icon = crate::ui::theme::tool_icon(&tool_call.tool_name);
 
     let (status_icon, status_style) = match &tool_call.status {
         ToolCallStatus::Running | ToolCallStatus::Streaming => {
@@ -355,49 +567,50 @@ fn render_default_tool_card<'a>(
         _ => ("-", theme.style(StyleKind::Muted)),
     };
 
-    items.push(ListItem::new(Line::from(vec![
-        Span::raw("  ┌─ "),
+    items.push(Line::from(vec![
+        border("  ┌─ ", theme),
         Span::raw(icon),
         Span::raw(" "),
         Span::styled(&tool_call.tool_name, theme.style(StyleKind::Primary)),
         Span::raw(" "),
         Span::styled(status_icon, status_style),
-    ])));
+    ]));
 
     // Show parameter summary (only key fields)
     let param_summary = extract_key_params(&tool_call.parameters);
     if !param_summary.is_empty() {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  │ "),
+        items.push(Line::from(vec![
+            border("  │ ", theme),
             Span::styled(param_summary, theme.style(StyleKind::Info)),
-        ])));
+        ]));
     }
 
     // Progress info
     if let Some(progress_msg) = &tool_call.progress_message {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  │ "),
+        items.push(Line::from(vec![
+            border("  │ ", theme),
             Span::styled(progress_msg, theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     }
 
     // Result
     if let Some(result) = &tool_call.result {
         let summary = prettify_result(result);
 
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled(summary, theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     } else {
-        items.push(ListItem::new(Line::from(vec![
-            Span::raw("  └─ "),
+        items.push(Line::from(vec![
+            border("  └─ ", theme),
             Span::styled("Executing...", theme.style(StyleKind::Muted)),
-        ])));
+        ]));
     }
 }
 
-fn extract_key_params(params: &serde_json::Value) -> String {
+/// Most descriptive string parameter of a tool call (path, query, command...)
+pub fn extract_key_params(params: &serde_json::Value) -> String {
     if let Some(obj) = params.as_object() {
         let priority_keys = [
             "path",
@@ -428,3 +641,88 @@ fn extract_key_params(params: &serde_json::Value) -> String {
 
     String::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::ToolCallStatus;
+
+    fn grep_call(result: Option<String>) -> ToolCall {
+        ToolCall {
+            tool_id: Some("t1".to_string()),
+            tool_name: "grep".to_string(),
+            parameters: serde_json::json!({ "pattern": "fn main" }),
+            result,
+            status: ToolCallStatus::Success,
+            progress: None,
+            progress_message: None,
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn expanded_card_shows_a_scrolled_window_of_output() {
+        let output: Vec<String> = (1..=40).map(|i| format!("match {}", i)).collect();
+        let tool_call = grep_call(Some(output.join("\n")));
+        let theme = Theme::dark();
+
+        let collapsed = render_tool_card(&tool_call, &theme, CardView::default());
+        assert_eq!(collapsed.len(), 3);
+
+        let view = CardView {
+            expanded: true,
+            scroll: Some(30),
+            ..CardView::default()
+        };
+        let expanded = render_tool_card(&tool_call, &theme, view);
+        // header + pattern + window + footer
+        assert_eq!(expanded.len(), 2 + EXPANDED_CARD_LINES + 1);
+        assert_eq!(expanded[2].spans[1].content, "match 26");
+        assert_eq!(
+            expanded.last().unwrap().spans[1].content,
+            "lines 26-40 of 40"
+        );
+    }
+
+    #[test]
+    fn running_card_follows_live_output() {
+        let tool_call = grep_call(None);
+        assert_eq!(expanded_scroll(&tool_call, None, 40), 25);
+        assert_eq!(
+            expanded_scroll(&grep_call(Some(String::new())), None, 40),
+            0
+        );
+    }
+
+    #[test]
+    fn edit_card_shows_numbered_diff() {
+        let tool_call = ToolCall {
+            tool_name: "Edit".to_string(),
+            parameters: serde_json::json!({
+                "file_path": "src/lib.rs",
+                "old_string": "fn a() {}\n",
+                "new_string": "fn a() {}\nfn b() {}\n",
+            }),
+            result: Some(r#"{"success":true,"start_line":7}"#.to_string()),
+            ..grep_call(None)
+        };
+
+        let view = CardView {
+            width: 80,
+            ..CardView::default()
+        };
+        let lines = render_tool_card(&tool_call, &Theme::dark(), view);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert!(text[0].ends_with("+1 −0"));
+        assert_eq!(text[3], "  │ 8 + fn b() {}");
+        assert_eq!(text[4], "  └─ Done");
+    }
+}
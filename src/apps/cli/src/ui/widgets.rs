@@ -3,6 +3,11 @@ use ratatui::{
     style::Style,
     text::{Line, Span},
 };
+use similar::{ChangeTag, TextDiff};
+use std::path::PathBuf;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::theme::{StyleKind, Theme};
 
 pub struct Spinner {
     frame: usize,
@@ -44,3 +49,588 @@ impl HelpText {
         Line::from(spans)
     }
 }
+
+/// Multi-line prompt editor with soft wrapping
+///
+/// Text is edited as a single string; `visual_rows` splits it into rows at
+/// newlines and wherever a row would exceed the available width.
+#[derive(Debug, Default, Clone)]
+pub struct InputEditor {
+    text: String,
+    /// Cursor as a byte offset into `text`, always on a char boundary
+    cursor: usize,
+}
+
+impl InputEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Cursor as a byte offset into the text
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replace `start..end` (byte offsets) with `replacement`, leaving the cursor after it
+    pub fn replace_range(&mut self, start: usize, end: usize, replacement: &str) {
+        self.text.replace_range(start..end, replacement);
+        self.cursor = start + replacement.len();
+    }
+
+    /// Replace the text and move the cursor to its end
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Take the text, leaving the editor empty
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        if c.is_control() && c != '\n' {
+            return;
+        }
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Insert pasted text, normalizing line endings and expanding tabs
+    pub fn insert_str(&mut self, s: &str) {
+        let normalized: String = s
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .replace('\t', "    ")
+            .chars()
+            .filter(|&c| c == '\n' || !c.is_control())
+            .collect();
+        self.text.insert_str(self.cursor, &normalized);
+        self.cursor += normalized.len();
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    /// Move to the start of the current logical line
+    pub fn move_line_start(&mut self) {
+        self.cursor = self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1);
+    }
+
+    /// Move to the end of the current logical line
+    pub fn move_line_end(&mut self) {
+        self.cursor = self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i);
+    }
+
+    /// Byte ranges of the rows the text occupies when wrapped at `width` columns
+    pub fn visual_rows(&self, width: usize) -> Vec<(usize, usize)> {
+        let width = width.max(1);
+        let mut rows = Vec::new();
+        let mut line_start = 0;
+
+        for line in self.text.split('\n') {
+            let mut row_start = line_start;
+            let mut row_width = 0;
+            for (offset, c) in line.char_indices() {
+                let char_width = c.width().unwrap_or(0);
+                if row_width + char_width > width && row_width > 0 {
+                    rows.push((row_start, line_start + offset));
+                    row_start = line_start + offset;
+                    row_width = 0;
+                }
+                row_width += char_width;
+            }
+            rows.push((row_start, line_start + line.len()));
+            line_start += line.len() + 1;
+        }
+
+        rows
+    }
+
+    /// Cursor (row, column) when wrapped at `width` columns
+    pub fn cursor_position(&self, width: usize) -> (usize, usize) {
+        let rows = self.visual_rows(width);
+        let row = Self::row_of(&rows, self.cursor);
+        let (start, _) = rows[row];
+        let column = self.text[start..self.cursor].width();
+        if column >= width.max(1) {
+            // End of a full last row: the cursor sits at the start of the next one
+            (row + 1, 0)
+        } else {
+            (row, column)
+        }
+    }
+
+    /// Move one visual row up, keeping the column; false when already on the first row
+    pub fn move_up(&mut self, width: usize) -> bool {
+        let rows = self.visual_rows(width);
+        let row = Self::row_of(&rows, self.cursor);
+        if row == 0 {
+            return false;
+        }
+        let column = self.text[rows[row].0..self.cursor].width();
+        self.cursor = self.offset_at_column(rows[row - 1], column);
+        true
+    }
+
+    /// Move one visual row down, keeping the column; false when already on the last row
+    pub fn move_down(&mut self, width: usize) -> bool {
+        let rows = self.visual_rows(width);
+        let row = Self::row_of(&rows, self.cursor);
+        if row + 1 >= rows.len() {
+            return false;
+        }
+        let column = self.text[rows[row].0..self.cursor].width();
+        self.cursor = self.offset_at_column(rows[row + 1], column);
+        true
+    }
+
+    /// Row containing byte offset `pos`; a soft-wrap boundary belongs to the later row
+    fn row_of(rows: &[(usize, usize)], pos: usize) -> usize {
+        rows.iter()
+            .rposition(|&(start, _)| start <= pos)
+            .unwrap_or(0)
+    }
+
+    /// Byte offset within `row` closest to display column `column` without passing it
+    fn offset_at_column(&self, (start, end): (usize, usize), column: usize) -> usize {
+        let mut width = 0;
+        for (offset, c) in self.text[start..end].char_indices() {
+            width += c.width().unwrap_or(0);
+            if width > column {
+                return start + offset;
+            }
+        }
+        end
+    }
+}
+
+/// Maximum number of prompts kept in the input history
+const MAX_INPUT_HISTORY: usize = 500;
+
+/// Previously submitted prompts, oldest first, optionally persisted as JSON
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// Entry being shown while browsing; None when editing a fresh draft
+    index: Option<usize>,
+    /// Text that was in the editor when browsing started
+    draft: String,
+    path: Option<PathBuf>,
+}
+
+impl InputHistory {
+    /// Load history from `path`; a missing or unreadable file starts empty
+    pub fn load(path: PathBuf) -> Self {
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            entries,
+            path: Some(path),
+            ..Default::default()
+        }
+    }
+
+    /// Record a submitted prompt and stop browsing
+    pub fn push(&mut self, entry: String) {
+        self.index = None;
+        self.draft.clear();
+        if self.entries.last() == Some(&entry) {
+            return;
+        }
+        self.entries.push(entry);
+        if self.entries.len() > MAX_INPUT_HISTORY {
+            let excess = self.entries.len() - MAX_INPUT_HISTORY;
+            self.entries.drain(..excess);
+        }
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to save input history: {}", e);
+        }
+    }
+
+    /// Step to an older entry; `current` is kept as the draft when browsing starts
+    pub fn prev(&mut self, current: &str) -> Option<&str> {
+        let index = match self.index {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(0) => return None,
+            Some(i) => i - 1,
+        };
+        self.index = Some(index);
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Step to a newer entry, returning the saved draft past the newest one
+    pub fn next(&mut self) -> Option<String> {
+        let index = self.index?;
+        if index + 1 < self.entries.len() {
+            self.index = Some(index + 1);
+            self.entries.get(index + 1).cloned()
+        } else {
+            self.index = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(&self.entries)?)
+    }
+}
+
+/// Unchanged lines kept around each change in a diff
+pub const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line number in the file; None when the position is unknown
+    pub number: Option<usize>,
+    pub text: String,
+}
+
+/// Changes to one file as hunks of context, added and removed lines
+///
+/// Rendering wraps long lines to the given width, so the rows can go
+/// straight into a list without overflowing its area.
+#[derive(Debug, Clone, Default)]
+pub struct DiffView {
+    pub hunks: Vec<Vec<DiffLine>>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl DiffView {
+    /// Diff `old` against `new`; `first_line` is the file line number of
+    /// their first line, when known
+    pub fn from_texts(old: &str, new: &str, first_line: Option<usize>) -> Self {
+        let diff = TextDiff::from_lines(old, new);
+        let mut view = Self::default();
+        for group in diff.grouped_ops(DIFF_CONTEXT_LINES) {
+            let mut hunk = Vec::new();
+            for op in &group {
+                for change in diff.iter_changes(op) {
+                    let (kind, index) = match change.tag() {
+                        ChangeTag::Equal => (DiffLineKind::Context, change.new_index()),
+                        ChangeTag::Insert => (DiffLineKind::Added, change.new_index()),
+                        ChangeTag::Delete => (DiffLineKind::Removed, change.old_index()),
+                    };
+                    view.count(kind);
+                    hunk.push(DiffLine {
+                        kind,
+                        number: first_line.zip(index).map(|(first, index)| first + index),
+                        text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                    });
+                }
+            }
+            view.hunks.push(hunk);
+        }
+        view
+    }
+
+    /// Parse unified diff text (e.g. `git diff` output)
+    pub fn from_unified(diff: &str) -> Self {
+        let mut view = Self::default();
+        let (mut old_line, mut new_line) = (0, 0);
+        for line in diff.lines() {
+            if let Some(header) = line.strip_prefix("@@ ") {
+                // "@@ -old,len +new,len @@"
+                let start = |prefix: char| {
+                    header
+                        .split_whitespace()
+                        .find_map(|part| part.strip_prefix(prefix))
+                        .and_then(|range| range.split(',').next()?.parse::<usize>().ok())
+                        .unwrap_or(1)
+                };
+                old_line = start('-');
+                new_line = start('+');
+                view.hunks.push(Vec::new());
+                continue;
+            }
+            let Some(hunk) = view.hunks.last_mut() else {
+                // File headers before the first hunk
+                continue;
+            };
+            let (kind, number) = match line.chars().next() {
+                Some('+') => (DiffLineKind::Added, &mut new_line),
+                Some('-') => (DiffLineKind::Removed, &mut old_line),
+                Some(' ') | None => {
+                    old_line += 1;
+                    (DiffLineKind::Context, &mut new_line)
+                }
+                // "\ No newline at end of file"
+                _ => continue,
+            };
+            hunk.push(DiffLine {
+                kind,
+                number: Some(*number),
+                text: line.get(1..).unwrap_or_default().to_string(),
+            });
+            *number += 1;
+            view.count(kind);
+        }
+        view
+    }
+
+    fn count(&mut self, kind: DiffLineKind) {
+        match kind {
+            DiffLineKind::Added => self.added += 1,
+            DiffLineKind::Removed => self.removed += 1,
+            DiffLineKind::Context => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0
+    }
+
+    /// Diff rows wrapped to `width` columns: line number, marker, text
+    pub fn render(&self, theme: &Theme, width: usize) -> Vec<Line<'static>> {
+        let number_width = self
+            .hunks
+            .iter()
+            .flatten()
+            .filter_map(|line| line.number)
+            .max()
+            .map_or(0, |max| max.to_string().len() + 1);
+        let text_width = width.saturating_sub(number_width + 2).max(8);
+
+        let mut rows = Vec::new();
+        for (index, hunk) in self.hunks.iter().enumerate() {
+            if index > 0 {
+                rows.push(Line::from(Span::styled(
+                    format!("{:>width$}", "⋮", width = number_width.max(1)),
+                    theme.style(StyleKind::Hint),
+                )));
+            }
+            for line in hunk {
+                let (marker, style) = match line.kind {
+                    DiffLineKind::Context => (' ', theme.style(StyleKind::Text)),
+                    DiffLineKind::Added => ('+', theme.style(StyleKind::DiffAdd)),
+                    DiffLineKind::Removed => ('-', theme.style(StyleKind::DiffRemove)),
+                };
+                let number = match line.number {
+                    Some(number) if number_width > 0 => {
+                        format!("{:>width$} ", number, width = number_width - 1)
+                    }
+                    _ => " ".repeat(number_width),
+                };
+                let text = line.text.replace('\t', "    ");
+                for (row, chunk) in wrap_to_width(&text, text_width).into_iter().enumerate() {
+                    let (number, marker) = if row == 0 {
+                        (number.clone(), marker)
+                    } else {
+                        (" ".repeat(number_width), ' ')
+                    };
+                    rows.push(Line::from(vec![
+                        Span::styled(number, theme.style(StyleKind::Hint)),
+                        Span::styled(format!("{} ", marker), style),
+                        Span::styled(chunk, style),
+                    ]));
+                }
+            }
+        }
+        rows
+    }
+}
+
+/// Split `text` into pieces at most `width` columns wide, never splitting a char
+fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    let mut column = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if column + char_width > width && column > 0 {
+            pieces.push(String::new());
+            column = 0;
+        }
+        if let Some(piece) = pieces.last_mut() {
+            piece.push(c);
+        }
+        column += char_width;
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(text: &str, cursor: usize) -> InputEditor {
+        InputEditor {
+            text: text.to_string(),
+            cursor,
+        }
+    }
+
+    #[test]
+    fn wraps_rows_at_width_and_newlines() {
+        let input = editor("abcdefg\nhi", 0);
+        assert_eq!(input.visual_rows(3), vec![(0, 3), (3, 6), (6, 7), (8, 10)]);
+        assert_eq!(editor("", 0).visual_rows(3), vec![(0, 0)]);
+        assert_eq!(editor("ab\n", 0).visual_rows(3), vec![(0, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn wraps_wide_chars_without_splitting_them() {
+        // Each CJK char is two columns wide
+        let input = editor("你好世界", 0);
+        assert_eq!(input.visual_rows(5), vec![(0, 6), (6, 12)]);
+    }
+
+    #[test]
+    fn cursor_position_across_wrapped_rows() {
+        assert_eq!(editor("abcdefg", 2).cursor_position(3), (0, 2));
+        // A soft-wrap boundary belongs to the next row
+        assert_eq!(editor("abcdefg", 3).cursor_position(3), (1, 0));
+        assert_eq!(editor("abcdefg", 7).cursor_position(3), (2, 1));
+        // End of a full last row moves to the following row
+        assert_eq!(editor("abcdef", 6).cursor_position(3), (2, 0));
+        assert_eq!(editor("ab\ncd", 2).cursor_position(10), (0, 2));
+        assert_eq!(editor("ab\ncd", 3).cursor_position(10), (1, 0));
+        assert_eq!(editor("你好世界", 9).cursor_position(5), (1, 2));
+    }
+
+    #[test]
+    fn vertical_movement_keeps_column() {
+        let mut input = editor("abcdefg\nhi", 4);
+        assert!(input.move_up(3));
+        assert_eq!(input.cursor, 1);
+        assert!(!input.move_up(3));
+
+        assert!(input.move_down(3));
+        // Shorter row: clamp to its end
+        assert!(input.move_down(3));
+        assert_eq!(input.cursor, 7);
+        assert!(input.move_down(3));
+        assert_eq!(input.cursor, 9);
+        assert!(!input.move_down(3));
+    }
+
+    #[test]
+    fn editing_respects_char_boundaries() {
+        let mut input = InputEditor::new();
+        input.insert_str("héllo\r\nworld\t!");
+        assert_eq!(input.text(), "héllo\nworld    !");
+
+        input.move_line_start();
+        input.move_left();
+        input.backspace();
+        assert_eq!(input.text(), "héll\nworld    !");
+
+        input.move_line_start();
+        input.move_right();
+        input.delete();
+        assert_eq!(input.text(), "hll\nworld    !");
+    }
+
+    #[test]
+    fn history_browsing_restores_draft() {
+        let mut history = InputHistory::default();
+        history.push("first".to_string());
+        history.push("second".to_string());
+
+        assert_eq!(history.prev("draft"), Some("second"));
+        assert_eq!(history.prev(""), Some("first"));
+        assert_eq!(history.prev(""), None);
+        assert_eq!(history.next().as_deref(), Some("second"));
+        assert_eq!(history.next().as_deref(), Some("draft"));
+        assert_eq!(history.next(), None);
+    }
+
+    #[test]
+    fn diffs_texts_into_numbered_hunks() {
+        let old = (1..=20)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let new = old.replace("line 3\n", "line three\n") + "line 21\n";
+        let view = DiffView::from_texts(&old, &new, Some(10));
+
+        assert_eq!((view.added, view.removed), (2, 1));
+        assert_eq!(view.hunks.len(), 2);
+        let removed = view.hunks[0]
+            .iter()
+            .find(|line| line.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!(
+            (removed.number, removed.text.as_str()),
+            (Some(12), "line 3")
+        );
+    }
+
+    #[test]
+    fn parses_unified_diff() {
+        let diff =
+            "--- a/x.rs\n+++ b/x.rs\n@@ -4,3 +4,3 @@\n fn a() {\n-    old();\n+    new();\n }\n";
+        let view = DiffView::from_unified(diff);
+
+        assert_eq!((view.added, view.removed), (1, 1));
+        let numbers: Vec<_> = view.hunks[0].iter().map(|line| line.number).collect();
+        assert_eq!(numbers, [Some(4), Some(5), Some(5), Some(6)]);
+    }
+
+    #[test]
+    fn wraps_wide_diff_lines() {
+        let view = DiffView::from_texts("", &format!("{}\n", "中".repeat(10)), Some(1));
+        let rows = view.render(&Theme::dark(), 14);
+
+        // 2 gutter + 2 marker columns leave 10 columns, i.e. 5 wide chars per row
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.width() <= 14));
+    }
+}
@@ -0,0 +1,200 @@
+/// Workspace opening and the recent-workspace list
+///
+/// Workspaces are registered with core's WorkspaceService, which keeps the
+/// recent list (with last-opened times) shared with the desktop app.
+use anyhow::{Context, Result};
+use bitfun_core::service::workspace::{
+    get_global_workspace_service, set_global_workspace_service, WorkspaceService,
+};
+use bitfun_core::service::GitService;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Recent workspaces shown in pickers
+pub const MAX_RECENT: usize = 8;
+
+/// Branch and working tree state of a git workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitState {
+    pub branch: String,
+    /// Staged, unstaged or untracked changes present
+    pub dirty: bool,
+}
+
+/// Recently opened workspace
+#[derive(Debug, Clone)]
+pub struct RecentWorkspace {
+    pub name: String,
+    pub path: String,
+    pub last_opened: DateTime<Utc>,
+    /// None outside git repositories
+    pub git: Option<GitState>,
+}
+
+async fn service() -> Result<Arc<WorkspaceService>> {
+    if let Some(service) = get_global_workspace_service() {
+        return Ok(service);
+    }
+    let service = Arc::new(
+        WorkspaceService::new()
+            .await
+            .context("Failed to initialize workspace service")?,
+    );
+    set_global_workspace_service(service.clone());
+    Ok(service)
+}
+
+/// Validate `path` and open it as the current workspace; returns its root
+///
+/// Also creates the project directories (`.bitfun/…`) the path manager
+/// resolves caches, sessions and snapshots into.
+pub async fn open(path: &Path) -> Result<PathBuf> {
+    if !path.is_dir() {
+        anyhow::bail!("Not a directory: {}", path.display());
+    }
+    let service = service().await?;
+    let info = service
+        .open_workspace(path.to_path_buf())
+        .await
+        .with_context(|| format!("Failed to open workspace {}", path.display()))?;
+    if let Err(e) = service
+        .path_manager()
+        .initialize_project_directories(&info.root_path)
+        .await
+    {
+        tracing::warn!(
+            "Failed to prepare project directories in {}: {}",
+            info.root_path.display(),
+            e
+        );
+    }
+    Ok(info.root_path)
+}
+
+/// Most recently opened workspaces that still exist, newest first
+pub async fn recent() -> Vec<RecentWorkspace> {
+    let service = match service().await {
+        Ok(service) => service,
+        Err(e) => {
+            tracing::warn!("Recent workspaces unavailable: {:#}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut workspaces: Vec<_> = service
+        .get_recent_workspaces()
+        .await
+        .into_iter()
+        .filter(|info| info.root_path.is_dir())
+        .collect();
+    workspaces.sort_by_key(|w| std::cmp::Reverse(w.last_accessed));
+    workspaces.truncate(MAX_RECENT);
+
+    let mut recent = Vec::with_capacity(workspaces.len());
+    for info in workspaces {
+        recent.push(RecentWorkspace {
+            git: git_state(&info.root_path).await,
+            name: info.name,
+            path: info.root_path.to_string_lossy().to_string(),
+            last_opened: info.last_accessed,
+        });
+    }
+    recent
+}
+
+/// Root paths of every recent workspace that still exists
+pub async fn known_paths() -> Vec<PathBuf> {
+    match service().await {
+        Ok(service) => service
+            .get_recent_workspaces()
+            .await
+            .into_iter()
+            .map(|info| info.root_path)
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Recent workspaces unavailable: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Blocking `recent` for synchronous UI code running on the runtime
+pub fn recent_sync() -> Vec<RecentWorkspace> {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(recent()))
+}
+
+async fn git_state(path: &Path) -> Option<GitState> {
+    if !GitService::is_repository(path).await.unwrap_or(false) {
+        return None;
+    }
+    match GitService::get_status(path).await {
+        Ok(status) => Some(GitState {
+            dirty: !(status.staged.is_empty()
+                && status.unstaged.is_empty()
+                && status.untracked.is_empty()),
+            branch: status.current_branch,
+        }),
+        Err(e) => {
+            tracing::debug!("Git status failed for {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// "5m ago" style age of `time`
+pub fn format_age(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - time).num_seconds().max(0);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3_599 => format!("{}m ago", secs / 60),
+        3_600..=86_399 => format!("{}h ago", secs / 3_600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// Expand `~` and resolve `path` against `base` (or the current directory)
+pub fn expand_path(path: &str, base: Option<&Path>) -> PathBuf {
+    let path = path.trim();
+    let expanded = match path.strip_prefix('~') {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(path),
+        },
+        None => PathBuf::from(path),
+    };
+    let absolute = if expanded.is_relative() {
+        base.map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default()
+            .join(expanded)
+    } else {
+        expanded
+    };
+    std::fs::canonicalize(&absolute).unwrap_or(absolute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn formats_ages() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now), "just now");
+        assert_eq!(format_age(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(format_age(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(format_age(now - Duration::days(2), now), "2d ago");
+    }
+
+    #[test]
+    fn expands_relative_paths_against_the_base() {
+        let base = std::env::temp_dir();
+        assert_eq!(
+            expand_path("missing-dir", Some(&base)),
+            base.join("missing-dir")
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 use log::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -36,6 +37,10 @@ pub struct SessionConfigDTO {
     pub enable_tools: Option<bool>,
     pub safe_mode: Option<bool>,
     pub max_turns: Option<usize>,
+    #[serde(default)]
+    pub max_tool_calls_per_turn: Option<usize>,
+    #[serde(default)]
+    pub max_turn_duration_secs: Option<u64>,
     pub enable_context_compression: Option<bool>,
     pub compression_threshold: Option<f32>,
     pub model_name: Option<String>,
@@ -43,6 +48,12 @@ pub struct SessionConfigDTO {
     pub remote_connection_id: Option<String>,
     #[serde(default)]
     pub remote_ssh_host: Option<String>,
+    /// Added to the workspace's `workspace.env` defaults
+    #[serde(default)]
+    pub env: Option<BTreeMap<String, String>>,
+    /// Leave the session's turns without an AI summary
+    #[serde(default)]
+    pub skip_turn_summary: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -60,6 +71,22 @@ pub struct UpdateSessionModelRequest {
     pub model_name: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSessionTurnSummaryRequest {
+    pub session_id: String,
+    /// Leave the session's turns without an AI summary
+    pub skip: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSessionEnvRequest {
+    pub session_id: String,
+    /// Complete set of variables; values may be `keyring:<name>` references
+    pub env: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StartDialogTurnRequest {
@@ -151,6 +178,7 @@ pub struct CancelDialogTurnRequest {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CancelToolRequest {
+    pub session_id: String,
     pub tool_use_id: String,
     pub reason: Option<String>,
 }
@@ -195,6 +223,14 @@ pub struct ConfirmToolRequest {
     pub updated_input: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmTurnSpendRequest {
+    pub session_id: String,
+    pub turn_id: String,
+    pub approved: bool,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RejectToolRequest {
@@ -240,12 +276,16 @@ pub async fn create_session(
             enable_tools: c.enable_tools.unwrap_or(true),
             safe_mode: c.safe_mode.unwrap_or(true),
             max_turns: c.max_turns.unwrap_or(200),
+            max_tool_calls_per_turn: c.max_tool_calls_per_turn,
+            max_turn_duration_secs: c.max_turn_duration_secs,
             enable_context_compression: c.enable_context_compression.unwrap_or(true),
             compression_threshold: c.compression_threshold.unwrap_or(0.8),
             workspace_path: Some(request.workspace_path.clone()),
             remote_connection_id: remote_conn.clone(),
             remote_ssh_host: remote_ssh_host.clone(),
             model_id: c.model_name,
+            env: c.env.unwrap_or_default(),
+            skip_turn_summary: c.skip_turn_summary.unwrap_or(false),
         })
         .unwrap_or(SessionConfig {
             workspace_path: Some(request.workspace_path.clone()),
@@ -283,6 +323,30 @@ pub async fn update_session_model(
         .map_err(|e| format!("Failed to update session model: {}", e))
 }
 
+/// Replace the environment variables of a session's Bash commands and background processes.
+#[tauri::command]
+pub async fn update_session_env(
+    coordinator: State<'_, Arc<ConversationCoordinator>>,
+    request: UpdateSessionEnvRequest,
+) -> Result<(), String> {
+    coordinator
+        .update_session_env(&request.session_id, request.env)
+        .await
+        .map_err(|e| format!("Failed to update session env: {}", e))
+}
+
+/// Turn the AI summaries of a session's completed turns off or back on.
+#[tauri::command]
+pub async fn update_session_turn_summary(
+    coordinator: State<'_, Arc<ConversationCoordinator>>,
+    request: UpdateSessionTurnSummaryRequest,
+) -> Result<(), String> {
+    coordinator
+        .update_session_skip_turn_summary(&request.session_id, request.skip)
+        .await
+        .map_err(|e| format!("Failed to update session turn summary: {}", e))
+}
+
 /// Load the session into the coordinator process when it exists on disk but is not in memory.
 /// Uses the same remote→local session path mapping as `restore_session`.
 #[tauri::command]
@@ -498,7 +562,7 @@ pub async fn cancel_tool(
         .unwrap_or_else(|| "User cancelled".to_string());
 
     coordinator
-        .cancel_tool(&request.tool_use_id, reason)
+        .cancel_tool(&request.session_id, &request.tool_use_id, reason)
         .await
         .map_err(|e| {
             log::error!(
@@ -604,11 +668,21 @@ pub async fn confirm_tool_execution(
     request: ConfirmToolRequest,
 ) -> Result<(), String> {
     coordinator
-        .confirm_tool(&request.tool_id, request.updated_input)
+        .confirm_tool(&request.session_id, &request.tool_id, request.updated_input)
         .await
         .map_err(|e| format!("Confirm tool failed: {}", e))
 }
 
+#[tauri::command]
+pub async fn confirm_turn_spend(
+    coordinator: State<'_, Arc<ConversationCoordinator>>,
+    request: ConfirmTurnSpendRequest,
+) -> Result<(), String> {
+    coordinator
+        .confirm_turn_spend(&request.turn_id, request.approved)
+        .map_err(|e| format!("Confirm turn spend failed: {}", e))
+}
+
 #[tauri::command]
 pub async fn reject_tool_execution(
     coordinator: State<'_, Arc<ConversationCoordinator>>,
@@ -619,7 +693,7 @@ pub async fn reject_tool_execution(
         .unwrap_or_else(|| "User rejected".to_string());
 
     coordinator
-        .reject_tool(&request.tool_id, reason)
+        .reject_tool(&request.session_id, &request.tool_id, reason)
         .await
         .map_err(|e| format!("Reject tool failed: {}", e))
 }
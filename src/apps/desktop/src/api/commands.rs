@@ -1,12 +1,12 @@
 //! Commands API - Core Application Commands
 
 use crate::api::app_state::AppState;
-use crate::api::dto::WorkspaceInfoDto;
+use crate::api::dto::{RecentWorkspaceDto, RecentWorkspaceOpenDto, WorkspaceInfoDto};
 use bitfun_core::infrastructure::{file_watcher, FileOperationOptions, SearchMatchType};
 use bitfun_core::service::remote_ssh::workspace_state::is_remote_path;
 use bitfun_core::service::remote_ssh::{get_remote_workspace_manager, RemoteWorkspaceEntry};
 use bitfun_core::service::workspace::{
-    ScanOptions, WorkspaceInfo, WorkspaceKind, WorkspaceOpenOptions,
+    ScanOptions, WorkspaceInfo, WorkspaceKind, WorkspaceOpenOptions, WorkspaceQuickStats,
 };
 use log::{debug, error, info, warn};
 use serde::Deserialize;
@@ -102,6 +102,19 @@ pub struct ResetAssistantWorkspaceRequest {
     pub workspace_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspaceRequest {
+    pub workspace_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspacePinnedRequest {
+    pub workspace_id: String,
+    pub pinned: bool,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReorderOpenedWorkspacesRequest {
@@ -169,6 +182,38 @@ pub struct GetFileTreeRequest {
     pub max_depth: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFileTreeChangesRequest {
+    pub path: String,
+    pub since_version: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzySearchFilesRequest {
+    pub root_path: String,
+    pub query: String,
+    #[serde(default = "default_fuzzy_search_max_results")]
+    pub max_results: usize,
+}
+
+fn default_fuzzy_search_max_results() -> usize {
+    50
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDirectorySizeReportRequest {
+    pub root_path: String,
+    #[serde(default = "default_directory_size_report_limit")]
+    pub limit: usize,
+}
+
+fn default_directory_size_report_limit() -> usize {
+    20
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GetDirectoryChildrenRequest {
     pub path: String,
@@ -433,10 +478,37 @@ pub async fn initialize_ai(state: State<'_, AppState>) -> Result<String, String>
     ))
 }
 
+/// Puts the stored secrets back into a model config the frontend sent with
+/// redacted values, so an unchanged saved model can still be tested.
+async fn restore_model_secrets(
+    state: &AppState,
+    config: &mut bitfun_core::service::config::types::AIModelConfig,
+) -> Result<(), String> {
+    let models = state
+        .config_service
+        .get_ai_models()
+        .await
+        .map_err(|e| format!("Failed to get model configurations: {}", e))?;
+    let Some(stored) = models.iter().find(|m| m.id == config.id) else {
+        return Ok(());
+    };
+
+    let to_value = |model: &bitfun_core::service::config::types::AIModelConfig| {
+        serde_json::to_value(model).map_err(|e| format!("Failed to serialize model: {}", e))
+    };
+    let mut value = to_value(&*config)?;
+    bitfun_core::service::config::secrets::restore_redacted(&mut value, &to_value(stored)?);
+    *config = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to restore model secrets: {}", e))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn test_ai_config_connection(
-    request: TestAIConfigConnectionRequest,
+    state: State<'_, AppState>,
+    mut request: TestAIConfigConnectionRequest,
 ) -> Result<bitfun_core::util::types::ConnectionTestResult, String> {
+    restore_model_secrets(&state, &mut request.config).await?;
     let model_name = request.config.name.clone();
     let supports_image_input = request.config.capabilities.iter().any(|cap| {
         matches!(
@@ -530,8 +602,10 @@ pub async fn test_ai_config_connection(
 
 #[tauri::command]
 pub async fn list_ai_models_by_config(
-    request: ListAIModelsByConfigRequest,
+    state: State<'_, AppState>,
+    mut request: ListAIModelsByConfigRequest,
 ) -> Result<Vec<bitfun_core::util::types::RemoteModelInfo>, String> {
+    restore_model_secrets(&state, &mut request.config).await?;
     let config_name = request.config.name.clone();
     let ai_config = request
         .config
@@ -1240,6 +1314,99 @@ pub async fn get_recent_workspaces(
         .collect())
 }
 
+/// Pinned workspaces first, then recent ones, each flagged with whether its
+/// folder still exists.
+#[tauri::command]
+pub async fn list_recent_workspaces(
+    state: State<'_, AppState>,
+) -> Result<Vec<RecentWorkspaceDto>, String> {
+    Ok(state
+        .workspace_service
+        .list_recent_workspaces()
+        .await
+        .iter()
+        .map(RecentWorkspaceDto::from_recent_workspace_entry)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn set_workspace_pinned(
+    state: State<'_, AppState>,
+    request: SetWorkspacePinnedRequest,
+) -> Result<(), String> {
+    state
+        .workspace_service
+        .set_workspace_pinned(&request.workspace_id, request.pinned)
+        .await
+        .map_err(|e| format!("Failed to update pinned workspace: {}", e))
+}
+
+#[tauri::command]
+pub async fn remove_recent_workspace(
+    state: State<'_, AppState>,
+    request: RecentWorkspaceRequest,
+) -> Result<(), String> {
+    state
+        .workspace_service
+        .remove_from_recent_workspaces(&request.workspace_id)
+        .await
+        .map_err(|e| format!("Failed to remove recent workspace: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_workspace_quick_stats(
+    state: State<'_, AppState>,
+    request: RecentWorkspaceRequest,
+) -> Result<WorkspaceQuickStats, String> {
+    state
+        .workspace_service
+        .get_workspace_quick_stats(&request.workspace_id)
+        .await
+        .map_err(|e| format!("Failed to get workspace stats: {}", e))
+}
+
+/// Opens a workspace from the recents list. The returned session id is the
+/// last active session there, which the frontend restores.
+#[tauri::command]
+pub async fn open_recent_workspace(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    request: RecentWorkspaceRequest,
+) -> Result<RecentWorkspaceOpenDto, String> {
+    let result = state
+        .workspace_service
+        .open_recent_workspace(&request.workspace_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to open recent workspace: {}", e);
+            format!("Failed to open workspace: {}", e)
+        })?;
+
+    apply_active_workspace_context(&state, &app, &result.workspace).await;
+
+    if let Err(e) = state
+        .workspace_identity_watch_service
+        .sync_watched_workspaces()
+        .await
+    {
+        warn!(
+            "Failed to sync workspace identity watchers after open: {}",
+            e
+        );
+    }
+
+    info!(
+        "Recent workspace opened: name={}, path={}, last_session={:?}",
+        result.workspace.name,
+        result.workspace.root_path.display(),
+        result.last_session_id
+    );
+    Ok(RecentWorkspaceOpenDto {
+        workspace: WorkspaceInfoDto::from_workspace_info(&result.workspace),
+        last_session_id: result.last_session_id,
+    })
+}
+
 #[tauri::command]
 pub async fn cleanup_invalid_workspaces(
     state: State<'_, AppState>,
@@ -1377,7 +1544,7 @@ pub async fn get_file_tree(
                 .and_then(|n| n.to_str())
                 .unwrap_or(&request.path);
 
-            let root_node = serde_json::json!({
+            let mut root_node = serde_json::json!({
                 "path": request.path,
                 "name": root_name,
                 "isDirectory": true,
@@ -1386,6 +1553,10 @@ pub async fn get_file_tree(
                 "lastModified": null,
                 "children": nodes.into_iter().map(convert_node_to_json).collect::<Vec<_>>()
             });
+            // Watched workspaces are cached; the version lets the client follow tree deltas
+            if let Some(version) = filesystem_service.get_file_tree_version(&request.path).await {
+                root_node["treeVersion"] = serde_json::json!(version);
+            }
 
             Ok(serde_json::json!([root_node]))
         }
@@ -1396,6 +1567,19 @@ pub async fn get_file_tree(
     }
 }
 
+#[tauri::command]
+pub async fn get_file_tree_changes(
+    state: State<'_, AppState>,
+    request: GetFileTreeChangesRequest,
+) -> Result<bitfun_transport::FileTreeDeltaPayload, String> {
+    state
+        .filesystem_service
+        .get_file_tree_changes(&request.path, request.since_version)
+        .await
+        .map(|patch| patch.to_payload())
+        .map_err(|e| format!("Failed to get file tree changes: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_directory_children(
     state: State<'_, AppState>,
@@ -2020,6 +2204,7 @@ pub async fn search_files(
                         "matchType": match result.match_type {
                             SearchMatchType::FileName => "fileName",
                             SearchMatchType::Content => "content",
+                            SearchMatchType::Fuzzy => "fuzzy",
                         },
                         "lineNumber": result.line_number,
                         "matchedContent": result.matched_content,
@@ -2045,6 +2230,57 @@ pub async fn search_files(
     }
 }
 
+#[tauri::command]
+pub async fn fuzzy_search_files(
+    state: State<'_, AppState>,
+    request: FuzzySearchFilesRequest,
+) -> Result<serde_json::Value, String> {
+    let results = state
+        .filesystem_service
+        .fuzzy_search_files(&request.root_path, &request.query, request.max_results)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to fuzzy search files: root_path={}, query={}, error={}",
+                request.root_path, request.query, e
+            );
+            format!("Failed to search files: {}", e)
+        })?;
+
+    let json_results: Vec<serde_json::Value> = results
+        .into_iter()
+        .map(|result| {
+            serde_json::json!({
+                "path": result.path,
+                "name": result.name,
+                "isDirectory": result.is_directory,
+                "matchType": "fuzzy",
+                "score": result.score,
+                "matchIndices": result.match_indices,
+            })
+        })
+        .collect();
+    Ok(serde_json::json!(json_results))
+}
+
+#[tauri::command]
+pub async fn get_directory_size_report(
+    state: State<'_, AppState>,
+    request: GetDirectorySizeReportRequest,
+) -> Result<bitfun_core::infrastructure::filesystem::DirectorySizeReport, String> {
+    state
+        .filesystem_service
+        .directory_size_report(&request.root_path, request.limit)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to get directory size report: root_path={}, error={}",
+                request.root_path, e
+            );
+            format!("Failed to get directory sizes: {}", e)
+        })
+}
+
 #[tauri::command]
 pub async fn reload_global_config() -> Result<String, String> {
     match bitfun_core::service::config::reload_global_config().await {
@@ -2088,7 +2324,11 @@ pub async fn get_model_configs(
         Ok(models) => {
             let model_configs: Vec<serde_json::Value> = models
                 .into_iter()
-                .map(|model| serde_json::to_value(model).unwrap_or_default())
+                .map(|model| {
+                    let mut value = serde_json::to_value(model).unwrap_or_default();
+                    bitfun_core::service::config::secrets::redact_secrets(&mut value);
+                    value
+                })
                 .collect();
 
             Ok(model_configs)
@@ -1,6 +1,7 @@
 //! Configuration API
 
 use crate::api::app_state::AppState;
+use bitfun_core::service::config::bundle::{self, ImportSettingsOptions, MergeStrategy};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -9,6 +10,10 @@ use tauri::State;
 #[derive(Debug, Deserialize)]
 pub struct GetConfigRequest {
     pub path: Option<String>,
+    /// Return `{ value, sources }` with the layer (default, user, profile or
+    /// workspace) each value comes from.
+    #[serde(default)]
+    pub with_sources: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -25,6 +30,62 @@ pub struct ResetConfigRequest {
 #[derive(Debug, Deserialize, Default)]
 pub struct GetRuntimeLoggingInfoRequest {}
 
+#[derive(Debug, Deserialize)]
+pub struct SetLogTargetLevelRequest {
+    /// Log target prefix, e.g. `bitfun_core::service::mcp`.
+    pub target: String,
+    /// New level; `None` removes the override.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateConfigProfileRequest {
+    pub name: String,
+    #[serde(default)]
+    pub overlay: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SwitchConfigProfileRequest {
+    /// `None` switches back to the base config.
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteConfigProfileRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MigratePlaintextSecretsRequest {
+    /// Paths to migrate; `None` migrates every plaintext secret.
+    #[serde(default)]
+    pub paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportSettingsRequest {
+    pub path: String,
+    /// Include secrets, encrypted with `passphrase`.
+    #[serde(default)]
+    pub include_secrets: bool,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportSettingsRequest {
+    pub path: String,
+    #[serde(default)]
+    pub merge_strategy: MergeStrategy,
+    /// Passphrase for the bundle's secrets; without it they are skipped.
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// Only report what would change.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 fn to_json_value<T: Serialize>(value: T, context: &str) -> Result<Value, String> {
     serde_json::to_value(value).map_err(|e| format!("Failed to serialize {}: {}", context, e))
 }
@@ -36,10 +97,17 @@ pub async fn get_config(
 ) -> Result<Value, String> {
     let config_service = &state.config_service;
 
-    match config_service
-        .get_config::<Value>(request.path.as_deref())
-        .await
-    {
+    let result = if request.with_sources {
+        config_service
+            .get_redacted_config_with_sources(request.path.as_deref())
+            .await
+            .and_then(|annotated| Ok(serde_json::to_value(annotated)?))
+    } else {
+        config_service
+            .get_redacted_config(request.path.as_deref())
+            .await
+    };
+    match result {
         Ok(config) => Ok(config),
         Err(e) => {
             error!("Failed to get config: path={:?}, error={}", request.path, e);
@@ -56,7 +124,7 @@ pub async fn set_config(
     let config_service = &state.config_service;
 
     match config_service
-        .set_config(&request.path, request.value)
+        .set_redacted_config(&request.path, request.value)
         .await
     {
         Ok(_) => {
@@ -186,7 +254,7 @@ pub async fn import_config(state: State<'_, AppState>, config: Value) -> Result<
 pub async fn validate_config(state: State<'_, AppState>) -> Result<Value, String> {
     let config_service = &state.config_service;
 
-    match config_service.validate_config().await {
+    match config_service.validate().await {
         Ok(validation_result) => Ok(to_json_value(
             validation_result,
             "config validation result",
@@ -214,6 +282,192 @@ pub async fn reload_config(state: State<'_, AppState>) -> Result<String, String>
     }
 }
 
+#[tauri::command]
+pub async fn list_config_profiles(state: State<'_, AppState>) -> Result<Value, String> {
+    let (profiles, active) = state.config_service.list_profiles().await;
+    Ok(serde_json::json!({
+        "profiles": profiles,
+        "active": active,
+    }))
+}
+
+#[tauri::command]
+pub async fn create_config_profile(
+    state: State<'_, AppState>,
+    request: CreateConfigProfileRequest,
+) -> Result<(), String> {
+    let overlay = request
+        .overlay
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    state
+        .config_service
+        .create_profile(&request.name, overlay)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to create config profile: name={}, error={}",
+                request.name, e
+            );
+            format!("Failed to create config profile: {}", e)
+        })
+}
+
+#[tauri::command]
+pub async fn switch_config_profile(
+    state: State<'_, AppState>,
+    request: SwitchConfigProfileRequest,
+) -> Result<(), String> {
+    match bitfun_core::service::config::GlobalConfigManager::switch_profile(request.name.as_deref())
+        .await
+    {
+        Ok(_) => {
+            state.ai_client_factory.invalidate_cache();
+            info!(
+                "Config profile switched: {}",
+                request.name.as_deref().unwrap_or("<base>")
+            );
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to switch config profile: {}", e);
+            Err(format!("Failed to switch config profile: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn delete_config_profile(
+    state: State<'_, AppState>,
+    request: DeleteConfigProfileRequest,
+) -> Result<(), String> {
+    state
+        .config_service
+        .delete_profile(&request.name)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to delete config profile: name={}, error={}",
+                request.name, e
+            );
+            format!("Failed to delete config profile: {}", e)
+        })?;
+    state.ai_client_factory.invalidate_cache();
+    Ok(())
+}
+
+/// Lists configured models with capabilities, context window and prices resolved against the
+/// built-in table of known models.
+#[tauri::command]
+pub async fn get_available_models(state: State<'_, AppState>) -> Result<Value, String> {
+    let models = state
+        .config_service
+        .get_available_models()
+        .await
+        .map_err(|e| format!("Failed to list available models: {}", e))?;
+    to_json_value(models, "available models")
+}
+
+/// Lists secrets stored in plaintext in the user config.
+#[tauri::command]
+pub async fn find_plaintext_secrets(state: State<'_, AppState>) -> Result<Value, String> {
+    let found = state
+        .config_service
+        .find_plaintext_secrets()
+        .await
+        .map_err(|e| format!("Failed to scan config for plaintext secrets: {}", e))?;
+    to_json_value(found, "plaintext secrets")
+}
+
+/// Moves plaintext secrets (all, or those at `paths`) into the secrets store.
+#[tauri::command]
+pub async fn migrate_plaintext_secrets(
+    state: State<'_, AppState>,
+    request: MigratePlaintextSecretsRequest,
+) -> Result<Value, String> {
+    let moved = state
+        .config_service
+        .migrate_plaintext_secrets(request.paths.as_deref())
+        .await
+        .map_err(|e| {
+            error!("Failed to migrate plaintext secrets: error={}", e);
+            format!("Failed to migrate plaintext secrets: {}", e)
+        })?;
+
+    if let Err(e) = bitfun_core::service::config::reload_global_config().await {
+        warn!(
+            "Failed to sync global config after secrets migration: error={}",
+            e
+        );
+    }
+    state.ai_client_factory.invalidate_cache();
+    to_json_value(moved, "migrated secrets")
+}
+
+/// Writes the user config to a settings bundle file.
+#[tauri::command]
+pub async fn export_settings(
+    state: State<'_, AppState>,
+    request: ExportSettingsRequest,
+) -> Result<Value, String> {
+    let passphrase = if request.include_secrets {
+        Some(request.passphrase.as_deref().unwrap_or_default())
+    } else {
+        None
+    };
+    let summary = bundle::export_settings(
+        &state.config_service,
+        std::path::Path::new(&request.path),
+        passphrase,
+    )
+    .await
+    .map_err(|e| {
+        error!(
+            "Failed to export settings: path={}, error={}",
+            request.path, e
+        );
+        format!("Failed to export settings: {}", e)
+    })?;
+    to_json_value(summary, "settings export summary")
+}
+
+/// Imports a settings bundle file, or with `dry_run` only reports the changes.
+#[tauri::command]
+pub async fn import_settings(
+    state: State<'_, AppState>,
+    request: ImportSettingsRequest,
+) -> Result<Value, String> {
+    let options = ImportSettingsOptions {
+        strategy: request.merge_strategy,
+        passphrase: request.passphrase.filter(|p| !p.is_empty()),
+        dry_run: request.dry_run,
+    };
+    let report = bundle::import_settings(
+        &state.config_service,
+        state.mcp_service.as_deref(),
+        std::path::Path::new(&request.path),
+        &options,
+    )
+    .await
+    .map_err(|e| {
+        error!(
+            "Failed to import settings: path={}, error={}",
+            request.path, e
+        );
+        format!("Failed to import settings: {}", e)
+    })?;
+
+    if report.applied {
+        if let Err(e) = bitfun_core::service::config::reload_global_config().await {
+            warn!(
+                "Failed to sync global config after settings import: error={}",
+                e
+            );
+        }
+        state.ai_client_factory.invalidate_cache();
+    }
+    to_json_value(report, "settings import report")
+}
+
 #[tauri::command]
 pub async fn sync_config_to_global(_state: State<'_, AppState>) -> Result<String, String> {
     match bitfun_core::service::config::reload_global_config().await {
@@ -242,6 +496,67 @@ pub async fn get_runtime_logging_info(
     to_json_value(logging_info, "runtime logging info")
 }
 
+/// Sets or removes the level override of one log target. The override is
+/// saved to `app.logging.target_levels` and applied immediately.
+#[tauri::command]
+pub async fn set_log_target_level(
+    state: State<'_, AppState>,
+    request: SetLogTargetLevelRequest,
+) -> Result<Value, String> {
+    let target = request.target.trim();
+    if target.is_empty() {
+        return Err("Log target is required".to_string());
+    }
+
+    let config_service = &state.config_service;
+    let mut target_levels: std::collections::HashMap<String, String> = config_service
+        .get_config(Some("app.logging.target_levels"))
+        .await
+        .unwrap_or_default();
+
+    match request
+        .level
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(level) => {
+            let level = crate::logging::parse_log_level(level)
+                .ok_or_else(|| format!("Invalid log level: {}", level))?;
+            target_levels.insert(
+                target.to_string(),
+                crate::logging::level_to_str(level).to_string(),
+            );
+        }
+        None => {
+            target_levels.remove(target);
+        }
+    }
+
+    if let Err(e) = config_service
+        .set_config("app.logging.target_levels", &target_levels)
+        .await
+    {
+        error!(
+            "Failed to save log level override: target={}, error={}",
+            target, e
+        );
+        return Err(format!("Failed to save log level override: {}", e));
+    }
+    if let Err(e) = bitfun_core::service::config::reload_global_config().await {
+        warn!(
+            "Failed to sync global config after setting log level override: error={}",
+            e
+        );
+    }
+    crate::logging::apply_target_log_levels(&target_levels, "tauri_command");
+
+    to_json_value(
+        crate::logging::get_runtime_logging_info(),
+        "runtime logging info",
+    )
+}
+
 #[tauri::command]
 pub async fn get_mode_configs(state: State<'_, AppState>) -> Result<Value, String> {
     use bitfun_core::service::config::types::ModeConfig;
@@ -274,10 +589,7 @@ pub async fn get_mode_configs(state: State<'_, AppState>) -> Result<Value, Strin
             // Migrate older Claw sessions that only allowlisted "ComputerUse" before split mouse tools existed;
             // otherwise the tool pipeline rejects ComputerUseMouse* with "not in the allowed list".
             if mode_id == "Claw"
-                && config
-                    .available_tools
-                    .iter()
-                    .any(|t| t == "ComputerUse")
+                && config.available_tools.iter().any(|t| t == "ComputerUse")
                 && !config
                     .available_tools
                     .iter()
@@ -462,6 +774,44 @@ pub async fn reset_mode_config(
     }
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveAgentTools {
+    pub agent_type: String,
+    /// Tools the agent is offered after mode configs, agent overrides and the tool policy.
+    pub tools: Vec<String>,
+    pub tool_policy: bitfun_core::service::config::types::ToolPolicyConfig,
+}
+
+/// Tools agent `agent_type` gets in workspace `workspace_path` (the active
+/// workspace when omitted), with the tool policy that applies there.
+#[tauri::command]
+pub async fn get_effective_agent_tools(
+    state: State<'_, AppState>,
+    agent_type: String,
+    workspace_path: Option<String>,
+) -> Result<Value, String> {
+    let workspace_root = workspace_path.as_deref().map(std::path::Path::new);
+    let tools = state
+        .agent_registry
+        .get_agent_tools(&agent_type, workspace_root)
+        .await;
+    let tool_policy = state
+        .config_service
+        .get_tool_policy(workspace_root)
+        .await
+        .map_err(|e| format!("Failed to get tool policy: {}", e))?;
+
+    to_json_value(
+        EffectiveAgentTools {
+            agent_type,
+            tools,
+            tool_policy,
+        },
+        "effective agent tools",
+    )
+}
+
 #[tauri::command]
 pub async fn get_subagent_configs(state: State<'_, AppState>) -> Result<Value, String> {
     use bitfun_core::service::config::types::SubAgentConfig;
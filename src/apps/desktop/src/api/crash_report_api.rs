@@ -0,0 +1,31 @@
+//! Crash report API
+
+use bitfun_core::infrastructure::crash_report::{self, CrashReport, CrashReportSummary};
+use bitfun_core::infrastructure::get_path_manager_arc;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportRequest {
+    pub id: String,
+}
+
+#[tauri::command]
+pub async fn list_crash_reports() -> Result<Vec<CrashReportSummary>, String> {
+    let crashes_dir = get_path_manager_arc().crashes_dir();
+    tokio::task::spawn_blocking(move || crash_report::list_crash_reports(&crashes_dir))
+        .await
+        .map_err(|e| format!("Failed to list crash reports: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_crash_report(request: CrashReportRequest) -> Result<CrashReport, String> {
+    crash_report::get_crash_report(&get_path_manager_arc().crashes_dir(), &request.id)
+        .ok_or_else(|| format!("Crash report not found: {}", request.id))
+}
+
+#[tauri::command]
+pub async fn delete_crash_report(request: CrashReportRequest) -> Result<(), String> {
+    crash_report::delete_crash_report(&get_path_manager_arc().crashes_dir(), &request.id)
+        .map_err(|e| format!("Failed to delete crash report: {}", e))
+}
@@ -65,6 +65,7 @@ pub struct WorkspaceInfoDto {
     pub languages: Vec<String>,
     pub opened_at: String,
     pub last_accessed: String,
+    pub open_count: u32,
     pub description: Option<String>,
     pub tags: Vec<String>,
     pub statistics: Option<ProjectStatisticsDto>,
@@ -122,6 +123,7 @@ impl WorkspaceInfoDto {
             languages: info.languages.clone(),
             opened_at: info.opened_at.to_rfc3339(),
             last_accessed: info.last_accessed.to_rfc3339(),
+            open_count: info.open_count,
             description: info.description.clone(),
             tags: info.tags.clone(),
             statistics: info
@@ -143,6 +145,33 @@ impl WorkspaceInfoDto {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspaceDto {
+    pub workspace: WorkspaceInfoDto,
+    pub pinned: bool,
+    pub exists: bool,
+}
+
+impl RecentWorkspaceDto {
+    pub fn from_recent_workspace_entry(
+        entry: &bitfun_core::service::workspace::RecentWorkspaceEntry,
+    ) -> Self {
+        Self {
+            workspace: WorkspaceInfoDto::from_workspace_info(&entry.workspace),
+            pinned: entry.pinned,
+            exists: entry.exists,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspaceOpenDto {
+    pub workspace: WorkspaceInfoDto,
+    pub last_session_id: Option<String>,
+}
+
 impl WorkspaceIdentityDto {
     pub fn from_workspace_identity(
         identity: &bitfun_core::service::workspace::manager::WorkspaceIdentity,
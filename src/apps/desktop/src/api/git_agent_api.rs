@@ -1,7 +1,10 @@
 //! Git Agent API - Provides Tauri command interface for Git Function Agent
 
 use crate::api::app_state::AppState;
-use bitfun_core::function_agents::{CommitMessage, CommitMessageOptions, GitFunctionAgent};
+use bitfun_core::function_agents::{
+    Changelog, ChangelogFunctionAgent, ChangelogOptions, CommitMessage, CommitMessageOptions,
+    GitFunctionAgent, PrDescription, PrDescriptionFunctionAgent, PrDescriptionOptions,
+};
 use log::error;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -26,6 +29,32 @@ pub struct PreviewCommitMessageRequest {
     pub repo_path: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateChangelogRequest {
+    pub repo_path: String,
+    pub options: Option<ChangelogOptions>,
+    /// Insert the fragment into the repository's CHANGELOG.md
+    #[serde(default)]
+    pub write: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateChangelogResponse {
+    pub changelog: Changelog,
+    pub written_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratePrDescriptionRequest {
+    pub repo_path: String,
+    pub options: Option<PrDescriptionOptions>,
+    /// Also write the markdown here; relative paths are resolved against the repository
+    pub output_path: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PreviewCommitMessageResponse {
@@ -96,3 +125,69 @@ pub async fn preview_commit_message(
         deletions: message.changes_summary.total_deletions,
     })
 }
+
+#[tauri::command]
+pub async fn generate_changelog(
+    app_state: State<'_, AppState>,
+    request: GenerateChangelogRequest,
+) -> Result<GenerateChangelogResponse, String> {
+    let factory = app_state.ai_client_factory.clone();
+    let agent = ChangelogFunctionAgent::new(factory);
+    let repo_path = Path::new(&request.repo_path);
+
+    let changelog = agent
+        .generate_changelog(repo_path, request.options.unwrap_or_default())
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to generate changelog: repo_path={}, error={}",
+                request.repo_path, e
+            );
+            e.to_string()
+        })?;
+
+    let written_to = if request.write {
+        let path = agent
+            .write_changelog(repo_path, &changelog)
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    Ok(GenerateChangelogResponse {
+        changelog,
+        written_to,
+    })
+}
+
+#[tauri::command]
+pub async fn generate_pr_description(
+    app_state: State<'_, AppState>,
+    request: GeneratePrDescriptionRequest,
+) -> Result<PrDescription, String> {
+    let factory = app_state.ai_client_factory.clone();
+    let agent = PrDescriptionFunctionAgent::new(factory);
+    let repo_path = Path::new(&request.repo_path);
+
+    let description = agent
+        .generate_description(repo_path, request.options.unwrap_or_default())
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to generate PR description: repo_path={}, error={}",
+                request.repo_path, e
+            );
+            e.to_string()
+        })?;
+
+    if let Some(output_path) = &request.output_path {
+        agent
+            .write_markdown(&repo_path.join(output_path), &description)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(description)
+}
@@ -3,8 +3,8 @@
 use crate::api::app_state::AppState;
 use bitfun_core::infrastructure::storage::StorageOptions;
 use bitfun_core::service::git::{
-    GitAddParams, GitCommitParams, GitDiffParams, GitLogParams, GitPullParams, GitPushParams,
-    GitService,
+    GitAddParams, GitCommitParams, GitDiffParams, GitFetchParams, GitLogParams, GitPullParams,
+    GitPushParams, GitService,
 };
 use bitfun_core::service::git::{
     GitBranch, GitCommit, GitOperationResult, GitRepository, GitStatus,
@@ -47,6 +47,13 @@ pub struct GitCommitRequest {
     pub params: GitCommitParams,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFetchRequest {
+    pub repository_path: String,
+    pub params: GitFetchParams,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitPushRequest {
@@ -270,6 +277,22 @@ pub async fn git_commit(
         })
 }
 
+#[tauri::command]
+pub async fn git_fetch(
+    _state: State<'_, AppState>,
+    request: GitFetchRequest,
+) -> Result<GitOperationResult, String> {
+    GitService::fetch(&request.repository_path, request.params)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to fetch: path={}, error={}",
+                request.repository_path, e
+            );
+            format!("Failed to fetch: {}", e)
+        })
+}
+
 #[tauri::command]
 pub async fn git_push(
     _state: State<'_, AppState>,
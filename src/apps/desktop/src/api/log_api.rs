@@ -0,0 +1,531 @@
+//! Log viewer API
+//!
+//! Reads, tails and exports the session log files written by the log plugin,
+//! so the settings screen does not need filesystem access of its own.
+
+use crate::logging::{resolve_logs_root, session_log_dir, SESSION_DIR_PATTERN};
+use log::{debug, info, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+const LOG_LINES_EVENT: &str = "log://lines";
+const MAX_TAIL_LINES: usize = 5000;
+/// Bytes read from the end of a file when looking for its last lines.
+const TAIL_READ_WINDOW: u64 = 8 * 1024 * 1024;
+/// Minimum time between two emitted batches of a subscription.
+const TAIL_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+/// Lines per emitted batch; older lines of a larger batch are dropped.
+const MAX_LINES_PER_EVENT: usize = 500;
+
+static TAIL_SUBSCRIPTIONS: OnceLock<Mutex<HashMap<String, RecommendedWatcher>>> = OnceLock::new();
+static NEXT_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn tail_subscriptions() -> &'static Mutex<HashMap<String, RecommendedWatcher>> {
+    TAIL_SUBSCRIPTIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTarget {
+    App,
+    Ai,
+    Webview,
+}
+
+impl LogTarget {
+    fn file_name(self) -> &'static str {
+        match self {
+            LogTarget::App => "app.log",
+            LogTarget::Ai => "ai.log",
+            LogTarget::Webview => "webview.log",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLineFilter {
+    /// Most verbose level to keep, e.g. "warn" keeps warnings and errors.
+    pub level: Option<String>,
+    /// Case-insensitive text the line must contain.
+    pub contains: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadLogTailRequest {
+    pub target: LogTarget,
+    pub lines: Option<usize>,
+    /// Session directory name; defaults to the running session.
+    pub session: Option<String>,
+    pub filter: Option<LogLineFilter>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadLogTailResponse {
+    pub path: String,
+    pub lines: Vec<String>,
+    pub file_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailLogRequest {
+    pub target: LogTarget,
+    pub filter: Option<LogLineFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopTailLogRequest {
+    pub subscription_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLinesEvent {
+    pub subscription_id: String,
+    pub target: LogTarget,
+    pub lines: Vec<String>,
+    /// Matching lines left out because the batch was too large.
+    pub dropped: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileInfo {
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSessionInfo {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_current: bool,
+    pub files: Vec<LogFileInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportLogSessionRequest {
+    /// Session directory name; defaults to the running session.
+    pub session: Option<String>,
+    pub destination_path: String,
+}
+
+/// Applies a `LogLineFilter`. Continuation lines of a multi-line message carry
+/// no level and inherit the level of the line they continue.
+struct LineMatcher {
+    max_level: log::LevelFilter,
+    needle: Option<String>,
+    last_level: Option<log::Level>,
+}
+
+impl LineMatcher {
+    fn new(filter: Option<LogLineFilter>) -> Result<Self, String> {
+        let filter = filter.unwrap_or_default();
+        let max_level = match filter.level.as_deref().filter(|s| !s.trim().is_empty()) {
+            Some(level) => crate::logging::parse_log_level(level)
+                .ok_or_else(|| format!("Invalid log level: {}", level))?,
+            None => log::LevelFilter::Trace,
+        };
+        let needle = filter
+            .contains
+            .map(|s| s.to_lowercase())
+            .filter(|s| !s.is_empty());
+
+        Ok(Self {
+            max_level,
+            needle,
+            last_level: None,
+        })
+    }
+
+    fn matches(&mut self, line: &str) -> bool {
+        if let Some(level) = line_level(line) {
+            self.last_level = Some(level);
+        }
+        if self.last_level.is_some_and(|level| level > self.max_level) {
+            return false;
+        }
+        match &self.needle {
+            Some(needle) => line.to_lowercase().contains(needle),
+            None => true,
+        }
+    }
+}
+
+/// Extracts the level of a line formatted as `[time][tid:N][LEVEL][target] message`.
+fn line_level(line: &str) -> Option<log::Level> {
+    let mut parts = line.strip_prefix('[')?.splitn(4, "][");
+    parts.next()?;
+    if !parts.next()?.starts_with("tid:") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+fn current_session_dir() -> PathBuf {
+    session_log_dir().unwrap_or_else(resolve_logs_root)
+}
+
+fn resolve_session_dir(session: Option<&str>) -> Result<PathBuf, String> {
+    let Some(session) = session.filter(|s| !s.is_empty()) else {
+        return Ok(current_session_dir());
+    };
+
+    let pattern = regex::Regex::new(SESSION_DIR_PATTERN)
+        .map_err(|e| format!("Invalid session dir pattern: {}", e))?;
+    if !pattern.is_match(session) {
+        return Err(format!("Invalid log session: {}", session));
+    }
+
+    let dir = resolve_logs_root().join(session);
+    if !dir.is_dir() {
+        return Err(format!("Log session not found: {}", session));
+    }
+    Ok(dir)
+}
+
+fn read_tail(
+    path: &Path,
+    max_lines: usize,
+    mut matcher: LineMatcher,
+) -> std::io::Result<(Vec<String>, u64)> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e),
+    };
+    let size = file.metadata()?.len();
+    let start = size.saturating_sub(TAIL_READ_WINDOW);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+    let content = String::from_utf8_lossy(&buffer);
+
+    let mut lines = content.lines();
+    if start > 0 {
+        // The window most likely starts in the middle of a line.
+        lines.next();
+    }
+
+    let mut matched: Vec<String> = lines
+        .filter(|line| matcher.matches(line))
+        .map(str::to_string)
+        .collect();
+    if matched.len() > max_lines {
+        matched.drain(..matched.len() - max_lines);
+    }
+    Ok((matched, size))
+}
+
+/// Incremental reader of a tailed log file.
+struct TailState {
+    path: PathBuf,
+    offset: u64,
+    partial: String,
+    matcher: LineMatcher,
+}
+
+impl TailState {
+    /// Returns the complete lines appended since the last read that match the
+    /// filter, and how many matching lines were dropped to respect the batch
+    /// size.
+    fn read_appended(&mut self) -> std::io::Result<(Vec<String>, usize)> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+            Err(e) => return Err(e),
+        };
+        let size = file.metadata()?.len();
+
+        let mut skip_first = false;
+        if size < self.offset {
+            // The file was rotated and started over.
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if size - self.offset > TAIL_READ_WINDOW {
+            self.offset = size - TAIL_READ_WINDOW;
+            self.partial.clear();
+            skip_first = true;
+        }
+        if size == self.offset {
+            return Ok((Vec::new(), 0));
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buffer = Vec::new();
+        (&mut file)
+            .take(size - self.offset)
+            .read_to_end(&mut buffer)?;
+        self.offset += buffer.len() as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&buffer));
+
+        let Some(end) = self.partial.rfind('\n') else {
+            return Ok((Vec::new(), 0));
+        };
+        let complete: String = self.partial.drain(..=end).collect();
+
+        let mut lines = complete.lines();
+        if skip_first {
+            lines.next();
+        }
+        let mut matched: Vec<String> = lines
+            .filter(|line| self.matcher.matches(line))
+            .map(str::to_string)
+            .collect();
+        let dropped = matched.len().saturating_sub(MAX_LINES_PER_EVENT);
+        matched.drain(..dropped);
+        Ok((matched, dropped))
+    }
+}
+
+fn dir_files(dir: &Path) -> Vec<LogFileInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<LogFileInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| LogFileInfo {
+                name: entry.file_name().to_string_lossy().to_string(),
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    files
+}
+
+fn zip_session_dir(dir: &Path, destination: &Path) -> Result<(), String> {
+    let session_name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "logs".to_string());
+
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    let file =
+        File::create(destination).map_err(|e| format!("Failed to create export file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for log_file in dir_files(dir) {
+        let mut source = File::open(dir.join(&log_file.name))
+            .map_err(|e| format!("Failed to open log file {}: {}", log_file.name, e))?;
+        zip.start_file(format!("{}/{}", session_name, log_file.name), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", log_file.name, e))?;
+        std::io::copy(&mut source, &mut zip)
+            .map_err(|e| format!("Failed to add {} to archive: {}", log_file.name, e))?;
+    }
+
+    let mut file = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    file.flush()
+        .map_err(|e| format!("Failed to write archive: {}", e))
+}
+
+#[tauri::command]
+pub async fn read_log_tail(request: ReadLogTailRequest) -> Result<ReadLogTailResponse, String> {
+    let matcher = LineMatcher::new(request.filter)?;
+    let path = resolve_session_dir(request.session.as_deref())?.join(request.target.file_name());
+    let max_lines = request.lines.unwrap_or(200).clamp(1, MAX_TAIL_LINES);
+
+    let read_path = path.clone();
+    let (lines, file_size) =
+        tokio::task::spawn_blocking(move || read_tail(&read_path, max_lines, matcher))
+            .await
+            .map_err(|e| format!("Failed to read log: {}", e))?
+            .map_err(|e| format!("Failed to read log: {}", e))?;
+
+    Ok(ReadLogTailResponse {
+        path: path.to_string_lossy().to_string(),
+        lines,
+        file_size,
+    })
+}
+
+/// Streams lines appended to a log file of the running session as
+/// `log://lines` events until `stop_tail_log` is called. Returns the
+/// subscription id.
+#[tauri::command]
+pub async fn tail_log(app: AppHandle, request: TailLogRequest) -> Result<String, String> {
+    let matcher = LineMatcher::new(request.filter)?;
+    let dir = current_session_dir();
+    let path = dir.join(request.target.file_name());
+    let offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    let file_name = request.target.file_name();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event {
+            if event
+                .paths
+                .iter()
+                .any(|p| p.file_name().is_some_and(|name| name == file_name))
+            {
+                // A full channel already has a read pending.
+                let _ = tx.try_send(());
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to create log watcher: {}", e))?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch log directory: {}", e))?;
+
+    let subscription_id = format!(
+        "log-tail-{}",
+        NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    tail_subscriptions()
+        .lock()
+        .map_err(|e| format!("Failed to register log tail: {}", e))?
+        .insert(subscription_id.clone(), watcher);
+
+    let target = request.target;
+    let id = subscription_id.clone();
+    let mut state = TailState {
+        path,
+        offset,
+        partial: String::new(),
+        matcher,
+    };
+    tokio::spawn(async move {
+        // Ends when the watcher, and with it the sender, is dropped.
+        while rx.recv().await.is_some() {
+            let result = tokio::task::spawn_blocking(move || {
+                let result = state.read_appended();
+                (state, result)
+            })
+            .await;
+            let (returned, result) = match result {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!(
+                        "Log tail reader failed: subscription_id={}, error={}",
+                        id, e
+                    );
+                    break;
+                }
+            };
+            state = returned;
+
+            match result {
+                Ok((lines, dropped)) if !lines.is_empty() => {
+                    let payload = LogLinesEvent {
+                        subscription_id: id.clone(),
+                        target,
+                        lines,
+                        dropped,
+                    };
+                    if let Err(e) = app.emit(LOG_LINES_EVENT, payload) {
+                        warn!("Failed to emit log lines: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Failed to read appended log lines: {}", e),
+            }
+
+            tokio::time::sleep(TAIL_FLUSH_INTERVAL).await;
+        }
+        debug!("Log tail finished: subscription_id={}", id);
+    });
+
+    Ok(subscription_id)
+}
+
+#[tauri::command]
+pub async fn stop_tail_log(request: StopTailLogRequest) -> Result<(), String> {
+    tail_subscriptions()
+        .lock()
+        .map_err(|e| format!("Failed to stop log tail: {}", e))?
+        .remove(&request.subscription_id);
+    Ok(())
+}
+
+/// Lists the log session directories, newest first.
+#[tauri::command]
+pub async fn list_log_sessions() -> Result<Vec<LogSessionInfo>, String> {
+    tokio::task::spawn_blocking(|| {
+        let logs_root = resolve_logs_root();
+        let current = session_log_dir();
+        let pattern = regex::Regex::new(SESSION_DIR_PATTERN)
+            .map_err(|e| format!("Invalid session dir pattern: {}", e))?;
+
+        let entries = match std::fs::read_dir(&logs_root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read logs directory: {}", e)),
+        };
+
+        let mut sessions: Vec<LogSessionInfo> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !pattern.is_match(&name) {
+                    return None;
+                }
+                let path = entry.path();
+                let files = dir_files(&path);
+                Some(LogSessionInfo {
+                    size_bytes: files.iter().map(|f| f.size_bytes).sum(),
+                    is_current: current.as_deref() == Some(path.as_path()),
+                    path: path.to_string_lossy().to_string(),
+                    name,
+                    files,
+                })
+            })
+            .collect();
+        sessions.sort_by(|a, b| b.name.cmp(&a.name));
+        Ok(sessions)
+    })
+    .await
+    .map_err(|e| format!("Failed to list log sessions: {}", e))?
+}
+
+/// Writes the files of a log session into a zip archive for bug reports.
+/// Returns the archive path.
+#[tauri::command]
+pub async fn export_log_session(request: ExportLogSessionRequest) -> Result<String, String> {
+    let dir = resolve_session_dir(request.session.as_deref())?;
+    let destination = PathBuf::from(&request.destination_path);
+    if destination.as_os_str().is_empty() {
+        return Err("Destination path is required".to_string());
+    }
+
+    let archive = destination.clone();
+    let source = dir.clone();
+    tokio::task::spawn_blocking(move || zip_session_dir(&source, &archive))
+        .await
+        .map_err(|e| format!("Failed to export log session: {}", e))??;
+
+    info!(
+        "Log session exported: session_dir={}, destination={}",
+        dir.display(),
+        destination.display()
+    );
+    Ok(destination.to_string_lossy().to_string())
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use bitfun_core::service::lsp::types::{CompletionItem, LspPlugin};
+use bitfun_core::service::lsp::types::{CompletionItem, LspPlugin, PluginUpdate, PluginUpdateInfo};
 use bitfun_core::service::lsp::{get_global_lsp_manager, initialize_global_lsp_manager};
 
 #[derive(Debug, Deserialize)]
@@ -102,6 +102,15 @@ pub struct InstallPluginRequest {
     pub package_path: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePluginRequest {
+    pub package_path: String,
+    /// Allows reinstalling the same version or downgrading.
+    #[serde(default)]
+    pub force: bool,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UninstallPluginRequest {
@@ -362,6 +371,29 @@ pub async fn lsp_install_plugin(request: InstallPluginRequest) -> Result<String,
     Ok(plugin_id)
 }
 
+#[tauri::command]
+pub async fn lsp_update_plugin(request: UpdatePluginRequest) -> Result<PluginUpdate, String> {
+    let manager = get_global_lsp_manager().map_err(|e| format!("LSP not initialized: {}", e))?;
+
+    let package_path = PathBuf::from(request.package_path);
+
+    let guard = manager.read().await;
+    let update = guard
+        .update_plugin(package_path, request.force)
+        .await
+        .map_err(|e| format!("Failed to update plugin: {}", e))?;
+
+    Ok(update)
+}
+
+#[tauri::command]
+pub async fn lsp_check_plugin_updates() -> Result<Vec<PluginUpdateInfo>, String> {
+    let manager = get_global_lsp_manager().map_err(|e| format!("LSP not initialized: {}", e))?;
+
+    let guard = manager.read().await;
+    Ok(guard.check_plugin_updates().await)
+}
+
 #[tauri::command]
 pub async fn lsp_uninstall_plugin(request: UninstallPluginRequest) -> Result<(), String> {
     let manager = get_global_lsp_manager().map_err(|e| format!("LSP not initialized: {}", e))?;
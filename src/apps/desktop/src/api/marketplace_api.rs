@@ -0,0 +1,132 @@
+//! Plugin marketplace API
+
+use crate::api::app_state::AppState;
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::PathBuf;
+use tauri::State;
+
+use bitfun_core::infrastructure::get_path_manager_arc;
+use bitfun_core::service::lsp::get_global_lsp_manager;
+use bitfun_core::service::lsp::marketplace::{
+    cross_reference, download_package, load_index, LoadedIndex, MarketplaceListing,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListMarketplacePluginsRequest {
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallMarketplacePluginRequest {
+    pub plugin_id: String,
+}
+
+fn index_cache_path() -> PathBuf {
+    get_path_manager_arc()
+        .cache_root()
+        .join("marketplace")
+        .join("lsp-index.json")
+}
+
+async fn load_marketplace_index(
+    state: &State<'_, AppState>,
+    refresh: bool,
+) -> Result<LoadedIndex, String> {
+    let url = state
+        .config_service
+        .get_config::<String>(Some("app.plugins.marketplace_url"))
+        .await
+        .unwrap_or_default();
+    if url.trim().is_empty() {
+        return Err("Plugin marketplace URL is not configured".to_string());
+    }
+
+    load_index(url.trim(), &index_cache_path(), refresh)
+        .await
+        .map_err(|e| format!("Failed to load plugin marketplace: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_marketplace_plugins(
+    state: State<'_, AppState>,
+    request: ListMarketplacePluginsRequest,
+) -> Result<MarketplaceListing, String> {
+    let loaded = load_marketplace_index(&state, request.refresh).await?;
+
+    let manager = get_global_lsp_manager().map_err(|e| format!("LSP not initialized: {}", e))?;
+    let installed = manager.read().await.list_plugins().await;
+
+    Ok(MarketplaceListing {
+        plugins: cross_reference(&loaded.index, &installed),
+        fetched_at: loaded.fetched_at,
+        stale: loaded.stale,
+        error: loaded.error,
+    })
+}
+
+/// Downloads and installs a marketplace plugin, updating it in place when an
+/// older version is installed. Returns the plugin id.
+#[tauri::command]
+pub async fn install_marketplace_plugin(
+    state: State<'_, AppState>,
+    request: InstallMarketplacePluginRequest,
+) -> Result<String, String> {
+    let loaded = load_marketplace_index(&state, false).await?;
+    let entry = loaded
+        .index
+        .plugins
+        .iter()
+        .find(|entry| entry.id == request.plugin_id)
+        .ok_or_else(|| format!("Plugin not found in marketplace: {}", request.plugin_id))?;
+
+    let manager = get_global_lsp_manager().map_err(|e| format!("LSP not initialized: {}", e))?;
+    let guard = manager.read().await;
+    let installed = guard.list_plugins().await;
+    let status = cross_reference(&loaded.index, &installed)
+        .into_iter()
+        .find(|status| status.entry.id == entry.id);
+    if let Some(status) = &status {
+        if status.installed_version.is_some() && !status.update_available {
+            return Ok(entry.id.clone());
+        }
+    }
+
+    let temp_dir = get_path_manager_arc().temp_dir().join("marketplace");
+    let package_path = download_package(entry, &temp_dir)
+        .await
+        .map_err(|e| format!("Failed to download plugin: {}", e))?;
+
+    let is_update = status.is_some_and(|status| status.update_available);
+    let result = if is_update {
+        guard
+            .update_plugin(package_path.clone(), false)
+            .await
+            .map(|update| update.plugin_id)
+            .map_err(|e| format!("Failed to update plugin: {}", e))
+    } else {
+        guard
+            .install_plugin(package_path.clone())
+            .await
+            .map_err(|e| format!("Failed to install plugin: {}", e))
+    };
+
+    if let Err(e) = tokio::fs::remove_file(&package_path).await {
+        warn!(
+            "Failed to remove downloaded plugin package: path={}, error={}",
+            package_path.display(),
+            e
+        );
+    }
+
+    if let Ok(plugin_id) = &result {
+        info!(
+            "Installed marketplace plugin: id={}, version={}",
+            plugin_id, entry.version
+        );
+    }
+    result
+}
@@ -214,11 +214,16 @@ pub async fn load_mcp_json_config(state: State<'_, AppState>) -> Result<String,
         .as_ref()
         .ok_or_else(|| "MCP service not initialized".to_string())?;
 
-    mcp_service
+    let json_config = mcp_service
         .config_service()
         .load_mcp_json_config()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(&json_config).map_err(|e| e.to_string())?;
+    bitfun_core::service::config::secrets::redact_secrets(&mut value);
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -231,8 +236,25 @@ pub async fn save_mcp_json_config(
         .as_ref()
         .ok_or_else(|| "MCP service not initialized".to_string())?;
 
-    mcp_service
-        .config_service()
+    let config_service = mcp_service.config_service();
+
+    // Keep stored secrets wherever the edited JSON still holds the redaction placeholder
+    let json_config = match serde_json::from_str::<serde_json::Value>(&json_config) {
+        Ok(mut value) => {
+            let previous: serde_json::Value = config_service
+                .load_mcp_json_config()
+                .await
+                .ok()
+                .and_then(|previous| serde_json::from_str(&previous).ok())
+                .unwrap_or_default();
+            bitfun_core::service::config::secrets::restore_redacted(&mut value, &previous);
+            serde_json::to_string(&value).map_err(|e| e.to_string())?
+        }
+        // Let the config service report the parse error
+        Err(_) => json_config,
+    };
+
+    config_service
         .save_mcp_json_config(&json_config)
         .await
         .map_err(|e| e.to_string())
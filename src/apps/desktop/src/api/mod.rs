@@ -11,6 +11,7 @@ pub mod commands;
 pub mod computer_use_api;
 pub mod config_api;
 pub mod context_upload_api;
+pub mod crash_report_api;
 pub mod cron_api;
 pub mod diff_api;
 pub mod dto;
@@ -19,8 +20,10 @@ pub mod git_agent_api;
 pub mod git_api;
 pub mod i18n_api;
 pub mod insights_api;
+pub mod log_api;
 pub mod lsp_api;
 pub mod lsp_workspace_api;
+pub mod marketplace_api;
 pub mod mcp_api;
 pub mod miniapp_api;
 pub mod project_context_api;
@@ -36,6 +39,7 @@ pub mod storage_commands;
 pub mod subagent_api;
 pub mod system_api;
 pub mod terminal_api;
+pub mod token_usage_api;
 pub mod tool_api;
 
 pub use app_state::{AppState, AppStatistics, HealthStatus, RemoteWorkspace};
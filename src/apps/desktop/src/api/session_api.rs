@@ -171,8 +171,19 @@ pub async fn save_session_turn(
     let manager = PersistenceManager::new(path_manager.inner().clone())
         .map_err(|e| format!("Failed to create persistence manager: {}", e))?;
 
+    // The turn summary is added by the backend after completion; keep it when the UI re-saves
+    let mut turn_data = request.turn_data;
+    if turn_data.summary.is_none() {
+        if let Ok(Some(stored)) = manager
+            .load_dialog_turn(&workspace_path, &turn_data.session_id, turn_data.turn_index)
+            .await
+        {
+            turn_data.summary = stored.summary;
+        }
+    }
+
     manager
-        .save_dialog_turn(&workspace_path, &request.turn_data)
+        .save_dialog_turn(&workspace_path, &turn_data)
         .await
         .map_err(|e| format!("Failed to save session turn: {}", e))
 }
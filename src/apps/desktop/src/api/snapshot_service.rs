@@ -4,7 +4,8 @@ use bitfun_core::infrastructure::try_get_path_manager_arc;
 use bitfun_core::service::remote_ssh::workspace_state::is_remote_path;
 use bitfun_core::service::snapshot::{
     ensure_snapshot_manager_for_workspace, get_snapshot_manager_for_workspace,
-    initialize_snapshot_manager_for_workspace, OperationType, SnapshotConfig, SnapshotManager,
+    initialize_snapshot_manager_for_workspace, OperationType, SnapshotComparison, SnapshotConfig,
+    SnapshotManager, WorkspaceSnapshotInfo, WorkspaceSnapshotTrigger,
 };
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
@@ -168,6 +169,32 @@ pub struct SnapshotWorkspaceRequest {
     pub workspace_path: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureWorkspaceSnapshotRequest {
+    #[serde(alias = "workspacePath")]
+    pub workspace_path: String,
+    #[serde(default, alias = "sessionId")]
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSnapshotRequest {
+    #[serde(alias = "workspacePath")]
+    pub workspace_path: String,
+    #[serde(alias = "snapshotId")]
+    pub snapshot_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareWorkspaceSnapshotsRequest {
+    #[serde(alias = "workspacePath")]
+    pub workspace_path: String,
+    #[serde(alias = "fromSnapshotId")]
+    pub from_snapshot_id: String,
+    #[serde(alias = "toSnapshotId")]
+    pub to_snapshot_id: String,
+}
+
 #[tauri::command]
 pub async fn initialize_snapshot(
     app_handle: AppHandle,
@@ -943,3 +970,52 @@ pub async fn get_baseline_snapshot_diff(
         "modifiedContent": current_content,
     }))
 }
+
+#[tauri::command]
+pub async fn capture_workspace_snapshot(
+    request: CaptureWorkspaceSnapshotRequest,
+) -> Result<WorkspaceSnapshotInfo, String> {
+    let manager = ensure_snapshot_manager_ready(&request.workspace_path).await?;
+
+    manager
+        .capture_workspace_snapshot(
+            WorkspaceSnapshotTrigger::Manual,
+            request.session_id.as_deref(),
+        )
+        .await
+        .map_err(|e| format!("Failed to capture workspace snapshot: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_workspace_snapshots(
+    request: SnapshotWorkspaceRequest,
+) -> Result<Vec<WorkspaceSnapshotInfo>, String> {
+    let manager = ensure_snapshot_manager_ready(&request.workspace_path).await?;
+
+    manager
+        .list_workspace_snapshots()
+        .await
+        .map_err(|e| format!("Failed to list workspace snapshots: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_workspace_snapshot(request: WorkspaceSnapshotRequest) -> Result<(), String> {
+    let manager = ensure_snapshot_manager_ready(&request.workspace_path).await?;
+
+    manager
+        .delete_workspace_snapshot(&request.snapshot_id)
+        .await
+        .map_err(|e| format!("Failed to delete workspace snapshot: {}", e))
+}
+
+#[tauri::command]
+pub async fn compare_workspace_snapshots(
+    request: CompareWorkspaceSnapshotsRequest,
+) -> Result<SnapshotComparison, String> {
+    let manager = ensure_snapshot_manager_ready(&request.workspace_path).await?;
+
+    manager
+        .compare_snapshots(&request.from_snapshot_id, &request.to_snapshot_id)
+        .await
+        .map_err(|e| format!("Failed to compare workspace snapshots: {}", e))
+}
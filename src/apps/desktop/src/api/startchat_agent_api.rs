@@ -1,12 +1,13 @@
 //! Startchat Agent API
 
 use bitfun_core::function_agents::{
-    startchat_func_agent::Language, StartchatFunctionAgent, WorkStateAnalysis, WorkStateOptions,
+    startchat_func_agent::{BranchCreation, Language},
+    StartchatFunctionAgent, WorkStateAnalysis, WorkStateOptions,
 };
-use log::error;
+use log::{error, warn};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 use super::app_state::AppState;
 
@@ -30,6 +31,21 @@ pub struct GenerateGreetingRequest {
     pub repo_path: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSuggestedBranchRequest {
+    pub repo_path: String,
+    pub branch_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GreetingUpdatedEvent {
+    pub repo_path: String,
+    #[serde(flatten)]
+    pub creation: BranchCreation,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkStateSummaryResponse {
@@ -140,3 +156,35 @@ pub async fn get_work_state_summary(
         predicted_actions_count: analysis.predicted_actions.len(),
     })
 }
+
+/// Accepts a `CreateBranch` quick action and tells the welcome view to
+/// refresh its greeting for the new branch
+#[tauri::command]
+pub async fn create_suggested_branch(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    request: CreateSuggestedBranchRequest,
+) -> Result<BranchCreation, String> {
+    let agent = StartchatFunctionAgent::new(state.ai_client_factory.clone());
+
+    let creation = agent
+        .create_suggested_branch(Path::new(&request.repo_path), &request.branch_name)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to create suggested branch: repo_path={}, branch={}, error={}",
+                request.repo_path, request.branch_name, e
+            );
+            e.to_string()
+        })?;
+
+    let event = GreetingUpdatedEvent {
+        repo_path: request.repo_path,
+        creation: creation.clone(),
+    };
+    if let Err(e) = app_handle.emit("startchat://greeting-updated", event) {
+        warn!("Failed to emit greeting update: {}", e);
+    }
+
+    Ok(creation)
+}
@@ -1,7 +1,11 @@
 //! Storage Management API
 
 use crate::api::AppState;
-use bitfun_core::infrastructure::storage::{CleanupPolicy, CleanupResult, CleanupService};
+use bitfun_core::infrastructure::storage::{
+    BackupCategory, BackupInfo, BackupService, CacheUsage, CleanupPolicy, CleanupResult,
+    CleanupService, PolicyCleanupReport, RestoreOptions, RestoreReport,
+};
+use bitfun_core::service::config::GlobalConfig;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tauri::State;
@@ -75,13 +79,22 @@ pub struct ProjectStoragePathsInfo {
     pub logs_dir: PathBuf,
 }
 
+/// Cleanup policy from `app.storage`, or the default one
+async fn configured_policy(state: &State<'_, AppState>) -> CleanupPolicy {
+    match state.config_service.get_config::<GlobalConfig>(None).await {
+        Ok(config) => config.app.storage.cleanup_policy(),
+        Err(_) => CleanupPolicy::default(),
+    }
+}
+
 #[tauri::command]
 pub async fn cleanup_storage(state: State<'_, AppState>) -> Result<CleanupResult, String> {
     let workspace_service = &state.workspace_service;
     let path_manager = workspace_service.path_manager();
 
-    let policy = CleanupPolicy::default();
-    let cleanup_service = CleanupService::new((&**path_manager).clone(), policy);
+    let policy = configured_policy(&state).await;
+    let cleanup_service = CleanupService::new((&**path_manager).clone(), policy)
+        .with_workspaces(known_workspaces(&state).await);
 
     cleanup_service
         .cleanup_all()
@@ -97,7 +110,8 @@ pub async fn cleanup_storage_with_policy(
     let workspace_service = &state.workspace_service;
     let path_manager = workspace_service.path_manager();
 
-    let cleanup_service = CleanupService::new((&**path_manager).clone(), policy);
+    let cleanup_service = CleanupService::new((&**path_manager).clone(), policy)
+        .with_workspaces(known_workspaces(&state).await);
 
     cleanup_service
         .cleanup_all()
@@ -105,6 +119,95 @@ pub async fn cleanup_storage_with_policy(
         .map_err(|e| format!("Cleanup failed: {}", e))
 }
 
+/// Runs every configured cleanup policy now; with `dry_run` only reports what would be removed
+#[tauri::command]
+pub async fn run_cleanup_policies(
+    state: State<'_, AppState>,
+    dry_run: bool,
+) -> Result<Vec<PolicyCleanupReport>, String> {
+    let workspace_service = &state.workspace_service;
+    let path_manager = workspace_service.path_manager();
+
+    let policy = configured_policy(&state).await;
+    let cleanup_service = CleanupService::new((&**path_manager).clone(), policy)
+        .with_workspaces(known_workspaces(&state).await);
+
+    Ok(cleanup_service.run_policies(dry_run).await)
+}
+
+#[tauri::command]
+pub async fn get_cache_usage(state: State<'_, AppState>) -> Result<Vec<CacheUsage>, String> {
+    let workspace_service = &state.workspace_service;
+    let path_manager = workspace_service.path_manager();
+
+    let policy = configured_policy(&state).await;
+    let cleanup_service = CleanupService::new((&**path_manager).clone(), policy);
+
+    cleanup_service
+        .cache_usage()
+        .await
+        .map_err(|e| format!("Failed to get cache usage: {}", e))
+}
+
+/// Root paths of the recent workspaces, whose sessions and memory are backed up
+/// and whose expired sessions are cleaned up
+async fn known_workspaces(state: &State<'_, AppState>) -> Vec<PathBuf> {
+    state
+        .workspace_service
+        .get_recent_workspaces()
+        .await
+        .into_iter()
+        .map(|workspace| workspace.root_path)
+        .collect()
+}
+
+/// Creates a backup of `categories`, or of the configured ones when unset
+#[tauri::command]
+pub async fn create_backup(
+    state: State<'_, AppState>,
+    categories: Option<Vec<BackupCategory>>,
+) -> Result<BackupInfo, String> {
+    let path_manager = state.workspace_service.path_manager();
+    let categories = match categories {
+        Some(categories) => categories,
+        None => match state.config_service.get_config::<GlobalConfig>(None).await {
+            Ok(config) => config.app.storage.backup.categories,
+            Err(_) => BackupCategory::ALL.to_vec(),
+        },
+    };
+    let workspaces = known_workspaces(&state).await;
+
+    BackupService::new((&**path_manager).clone())
+        .create(&categories, &workspaces)
+        .await
+        .map_err(|e| format!("Backup failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>, String> {
+    let path_manager = state.workspace_service.path_manager();
+
+    BackupService::new((&**path_manager).clone())
+        .list()
+        .await
+        .map_err(|e| format!("Failed to list backups: {}", e))
+}
+
+/// Restores a backup archive; the app should be restarted afterwards
+#[tauri::command]
+pub async fn restore_backup(
+    state: State<'_, AppState>,
+    path: String,
+    options: Option<RestoreOptions>,
+) -> Result<RestoreReport, String> {
+    let path_manager = state.workspace_service.path_manager();
+
+    BackupService::new((&**path_manager).clone())
+        .restore(&PathBuf::from(path), &options.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Restore failed: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_storage_statistics(state: State<'_, AppState>) -> Result<StorageStats, String> {
     let workspace_service = &state.workspace_service;
@@ -3,7 +3,7 @@
 use crate::api::app_state::AppState;
 use bitfun_core::agentic::agents::{
     AgentCategory, AgentInfo, CustomSubagent, CustomSubagentConfig, CustomSubagentKind,
-    SubAgentSource,
+    CustomSubagentLoadError, SubAgentSource,
 };
 use bitfun_core::service::config::types::SubAgentConfig;
 use log::warn;
@@ -245,18 +245,18 @@ pub struct ReloadSubagentsRequest {
     pub workspace_path: Option<String>,
 }
 
+/// Re-scans the custom agent files; returns the files that were rejected
 #[tauri::command]
 pub async fn reload_subagents(
     state: State<'_, AppState>,
     request: ReloadSubagentsRequest,
-) -> Result<(), String> {
+) -> Result<Vec<CustomSubagentLoadError>, String> {
     let workspace_root = workspace_root_from_request(request.workspace_path.as_deref())
         .ok_or_else(|| "workspacePath is required to reload project subagents".to_string())?;
-    state
+    Ok(state
         .agent_registry
         .load_custom_subagents(workspace_root.as_path())
-        .await;
-    Ok(())
+        .await)
 }
 
 #[tauri::command]
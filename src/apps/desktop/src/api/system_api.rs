@@ -1,6 +1,8 @@
 //! System API
 
 use crate::api::app_state::AppState;
+use bitfun_core::infrastructure::ai::{get_global_http_client_factory, HttpTransportStats};
+use bitfun_core::infrastructure::offline::{self, OfflineReason};
 use bitfun_core::service::system;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -188,3 +190,26 @@ pub async fn send_system_notification(
     }
     builder.show().map_err(|e| e.to_string())
 }
+
+/// Connection pool settings and per-endpoint request latency of the shared AI HTTP clients.
+#[tauri::command]
+pub async fn get_http_transport_stats() -> Result<HttpTransportStats, String> {
+    Ok(get_global_http_client_factory().stats())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineStatusResponse {
+    pub offline: bool,
+    pub reason: Option<OfflineReason>,
+}
+
+/// Whether offline mode is on; later changes arrive as `backend-event-offlinemodechanged`.
+#[tauri::command]
+pub async fn get_offline_status() -> Result<OfflineStatusResponse, String> {
+    let reason = offline::status();
+    Ok(OfflineStatusResponse {
+        offline: reason.is_some(),
+        reason,
+    })
+}
@@ -0,0 +1,27 @@
+//! Token Usage and Spend API
+
+use crate::api::AppState;
+use bitfun_core::service::token_usage::SpendSummary;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSpendSummaryRequest {
+    pub session_id: Option<String>,
+    pub workspace_path: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_spend_summary(
+    state: State<'_, AppState>,
+    request: GetSpendSummaryRequest,
+) -> Result<SpendSummary, String> {
+    Ok(state
+        .token_usage_service
+        .get_spend_summary(
+            request.session_id.as_deref(),
+            request.workspace_path.as_deref(),
+        )
+        .await)
+}
@@ -118,7 +118,8 @@ fn is_relative_path(value: Option<&serde_json::Value>) -> bool {
 
 fn tool_requires_workspace_path(tool_name: &str, input: &serde_json::Value) -> bool {
     match tool_name {
-        "Bash" => true,
+        // Patch paths are always relative to the workspace root
+        "Bash" | "ApplyPatch" => true,
         "Glob" | "Grep" => input.get("path").is_none() || is_relative_path(input.get("path")),
         "Read" | "Write" | "Edit" | "GetFileDiff" => is_relative_path(input.get("file_path")),
         _ => false,
@@ -10,6 +10,7 @@ pub mod theme;
 use bitfun_core::agentic::tools::computer_use_capability::set_computer_use_desktop_available;
 use bitfun_core::agentic::tools::computer_use_host::ComputerUseHostRef;
 use bitfun_core::infrastructure::ai::AIClientFactory;
+use bitfun_core::infrastructure::events::{EventRecorder, EventRecorderConfig};
 use bitfun_core::infrastructure::{get_path_manager_arc, try_get_path_manager_arc};
 use bitfun_core::service::workspace::get_global_workspace_service;
 use bitfun_transport::{TauriTransportAdapter, TransportAdapter};
@@ -31,13 +32,16 @@ use api::clipboard_file_api::*;
 use api::commands::*;
 use api::computer_use_api::*;
 use api::config_api::*;
+use api::crash_report_api::*;
 use api::cron_api::*;
 use api::diff_api::*;
 use api::git_agent_api::*;
 use api::git_api::*;
 use api::i18n_api::*;
+use api::log_api::*;
 use api::lsp_api::*;
 use api::lsp_workspace_api::*;
+use api::marketplace_api::*;
 use api::mcp_api::*;
 use api::runtime_api::*;
 use api::session_api::*;
@@ -89,6 +93,7 @@ pub async fn run() {
     }
 
     let startup_log_level = resolve_runtime_log_level(log_config.level).await;
+    let startup_target_levels = resolve_target_log_levels().await;
 
     if let Err(e) = AIClientFactory::initialize_global().await {
         log::error!("Failed to initialize global AIClientFactory: {}", e);
@@ -130,6 +135,14 @@ pub async fn run() {
     let path_manager = get_path_manager_arc();
 
     setup_panic_hook();
+    // Installed after the app hook, which exits the process, so it runs first.
+    bitfun_core::infrastructure::crash_report::install_panic_hook(
+        bitfun_core::infrastructure::crash_report::CrashReporterConfig {
+            crashes_dir: path_manager.crashes_dir(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            log_file: Some(session_log_dir.join("app.log")),
+        },
+    );
 
     let run_result = tauri::Builder::default()
         .plugin(
@@ -142,6 +155,7 @@ pub async fn run() {
                 .level_for("h2", log::LevelFilter::Info)
                 .level_for("portable_pty", log::LevelFilter::Info)
                 .level_for("russh", log::LevelFilter::Info)
+                .filter(logging::record_enabled)
                 .targets(log_targets)
                 .rotation_strategy(RotationStrategy::KeepSome(3))
                 .max_file_size(10 * 1024 * 1024)
@@ -179,6 +193,7 @@ pub async fn run() {
             }
 
             logging::register_runtime_log_state(startup_log_level, session_log_dir.clone());
+            logging::apply_target_log_levels(&startup_target_levels, "startup");
 
             // Register bundled mobile-web resource path for remote connect.
             // tauri.conf.json maps "../../mobile-web/dist" -> "mobile-web/dist",
@@ -313,6 +328,8 @@ pub async fn run() {
             theme::show_main_window,
             api::agentic_api::create_session,
             api::agentic_api::update_session_model,
+            api::agentic_api::update_session_env,
+            api::agentic_api::update_session_turn_summary,
             api::agentic_api::ensure_coordinator_session,
             api::agentic_api::start_dialog_turn,
             api::agentic_api::ensure_assistant_bootstrap,
@@ -324,6 +341,8 @@ pub async fn run() {
             api::agentic_api::get_session_messages,
             api::agentic_api::confirm_tool_execution,
             api::agentic_api::reject_tool_execution,
+            api::agentic_api::confirm_turn_spend,
+            api::token_usage_api::get_spend_summary,
             api::agentic_api::cancel_tool,
             api::agentic_api::generate_session_title,
             api::agentic_api::get_available_modes,
@@ -365,9 +384,12 @@ pub async fn run() {
             export_local_file_to_path,
             reveal_in_explorer,
             get_file_tree,
+            get_file_tree_changes,
             get_directory_children,
             get_directory_children_paginated,
             search_files,
+            fuzzy_search_files,
+            get_directory_size_report,
             delete_file,
             delete_directory,
             create_file,
@@ -388,14 +410,33 @@ pub async fn run() {
             import_config,
             validate_config,
             reload_config,
+            list_config_profiles,
+            create_config_profile,
+            switch_config_profile,
+            delete_config_profile,
+            get_available_models,
+            find_plaintext_secrets,
+            migrate_plaintext_secrets,
+            export_settings,
+            import_settings,
             sync_config_to_global,
             get_global_config_health,
             get_runtime_logging_info,
+            set_log_target_level,
+            list_crash_reports,
+            get_crash_report,
+            delete_crash_report,
+            read_log_tail,
+            tail_log,
+            stop_tail_log,
+            list_log_sessions,
+            export_log_session,
             get_runtime_capabilities,
             get_mode_configs,
             get_mode_config,
             set_mode_config,
             reset_mode_config,
+            get_effective_agent_tools,
             get_subagent_configs,
             set_subagent_config,
             list_subagents,
@@ -420,6 +461,7 @@ pub async fn run() {
             git_get_commits,
             git_add_files,
             git_commit,
+            git_fetch,
             git_push,
             git_pull,
             git_checkout_branch,
@@ -441,10 +483,13 @@ pub async fn run() {
             save_git_repo_history,
             load_git_repo_history,
             preview_commit_message,
+            generate_changelog,
+            generate_pr_description,
             analyze_work_state,
             quick_analyze_work_state,
             generate_greeting_only,
             get_work_state_summary,
+            create_suggested_branch,
             compute_diff,
             apply_patch,
             save_merged_diff_content,
@@ -472,11 +517,20 @@ pub async fn run() {
             get_file_change_history,
             get_all_modified_files,
             get_baseline_snapshot_diff,
+            capture_workspace_snapshot,
+            list_workspace_snapshots,
+            delete_workspace_snapshot,
+            compare_workspace_snapshots,
             get_storage_paths,
             get_project_storage_paths,
             cleanup_storage,
             cleanup_storage_with_policy,
             get_storage_statistics,
+            get_cache_usage,
+            run_cleanup_policies,
+            create_backup,
+            list_backups,
+            restore_backup,
             initialize_project_storage,
             get_ai_rules,
             get_ai_rule,
@@ -542,6 +596,10 @@ pub async fn run() {
             lsp_find_references,
             lsp_format_document,
             lsp_install_plugin,
+            lsp_update_plugin,
+            lsp_check_plugin_updates,
+            list_marketplace_plugins,
+            install_marketplace_plugin,
             lsp_uninstall_plugin,
             lsp_list_plugins,
             lsp_get_plugin,
@@ -576,6 +634,11 @@ pub async fn run() {
             subscribe_config_updates,
             get_model_configs,
             get_recent_workspaces,
+            list_recent_workspaces,
+            set_workspace_pinned,
+            remove_recent_workspace,
+            get_workspace_quick_stats,
+            open_recent_workspace,
             cleanup_invalid_workspaces,
             get_opened_workspaces,
             open_workspace,
@@ -609,6 +672,8 @@ pub async fn run() {
             api::terminal_api::terminal_get_history,
             get_system_info,
             send_system_notification,
+            get_http_transport_stats,
+            get_offline_status,
             check_command_exists,
             check_commands_exist,
             run_system_command,
@@ -733,7 +798,7 @@ async fn init_agentic_system() -> anyhow::Result<(
     let session_manager = Arc::new(session::SessionManager::new(
         history_manager,
         compression_manager,
-        persistence_manager,
+        persistence_manager.clone(),
         Default::default(),
     ));
 
@@ -785,6 +850,7 @@ async fn init_agentic_system() -> anyhow::Result<(
         bitfun_core::service::token_usage::TokenUsageSubscriber::new(token_usage_service.clone()),
     );
     event_router.subscribe_internal("token_usage".to_string(), token_usage_subscriber);
+    bitfun_core::service::token_usage::set_global_token_usage_service(token_usage_service.clone());
 
     log::info!("Token usage service initialized and subscriber registered");
 
@@ -807,6 +873,66 @@ async fn init_agentic_system() -> anyhow::Result<(
     cron_service.start();
 
     log::info!("Cron service initialized and subscriber registered");
+
+    bitfun_core::infrastructure::storage::spawn_cleanup_scheduler(
+        (*path_manager).clone(),
+        || async {
+            let config = match bitfun_core::service::config::get_global_config_service().await {
+                Ok(service) => service
+                    .get_config::<bitfun_core::service::config::GlobalConfig>(None)
+                    .await
+                    .ok(),
+                Err(_) => None,
+            };
+            let policy = config
+                .map(|config| config.app.storage.cleanup_policy())
+                .unwrap_or_default();
+            let workspaces = match get_global_workspace_service() {
+                Some(service) => service
+                    .get_recent_workspaces()
+                    .await
+                    .into_iter()
+                    .map(|workspace| workspace.root_path)
+                    .collect(),
+                None => Vec::new(),
+            };
+            (policy, workspaces)
+        },
+    );
+    bitfun_core::infrastructure::storage::spawn_backup_scheduler(
+        (*path_manager).clone(),
+        || async {
+            let schedule = match bitfun_core::service::config::get_global_config_service().await {
+                Ok(service) => service
+                    .get_config::<bitfun_core::service::config::GlobalConfig>(None)
+                    .await
+                    .map(|config| config.app.storage.backup)
+                    .unwrap_or_default(),
+                Err(_) => Default::default(),
+            };
+            let workspaces = match get_global_workspace_service() {
+                Some(service) => service
+                    .get_recent_workspaces()
+                    .await
+                    .into_iter()
+                    .map(|workspace| workspace.root_path)
+                    .collect(),
+                None => Vec::new(),
+            };
+            (schedule, workspaces)
+        },
+    );
+    persistence::spawn_idle_payload_compression(persistence_manager, || async {
+        match get_global_workspace_service() {
+            Some(service) => service
+                .get_recent_workspaces()
+                .await
+                .into_iter()
+                .map(|workspace| workspace.root_path)
+                .collect(),
+            None => Vec::new(),
+        }
+    });
     log::info!("Agentic system initialized");
     Ok((
         coordinator,
@@ -943,9 +1069,44 @@ fn init_services(app_handle: tauri::AppHandle, default_log_level: log::LevelFilt
 
         let event_system = infrastructure::events::get_global_event_system();
         event_system.set_emitter(emitter).await;
+
+        if let Some(recorder) = start_event_recorder().await {
+            event_system.set_recorder(Some(recorder)).await;
+        }
+
+        infrastructure::crash_report::notify_pending_crash_reports(
+            &get_path_manager_arc().crashes_dir(),
+        )
+        .await;
     });
 }
 
+/// Records backend events into the session log directory unless
+/// `app.logging.record_events` turns it off
+async fn start_event_recorder() -> Option<Arc<EventRecorder>> {
+    use bitfun_core::service::config::get_global_config_service;
+
+    let enabled = match get_global_config_service().await {
+        Ok(config_service) => config_service
+            .get_config::<bool>(Some("app.logging.record_events"))
+            .await
+            .unwrap_or(true),
+        Err(_) => true,
+    };
+    if !enabled {
+        return None;
+    }
+
+    let dir = logging::session_log_dir()?;
+    match EventRecorder::start(EventRecorderConfig::new(dir)) {
+        Ok(recorder) => Some(Arc::new(recorder)),
+        Err(e) => {
+            log::warn!("Failed to start event recorder: {}", e);
+            None
+        }
+    }
+}
+
 async fn resolve_runtime_log_level(default_level: log::LevelFilter) -> log::LevelFilter {
     use bitfun_core::service::config::get_global_config_service;
 
@@ -968,6 +1129,18 @@ async fn resolve_runtime_log_level(default_level: log::LevelFilter) -> log::Leve
     default_level
 }
 
+async fn resolve_target_log_levels() -> std::collections::HashMap<String, String> {
+    use bitfun_core::service::config::get_global_config_service;
+
+    match get_global_config_service().await {
+        Ok(config_service) => config_service
+            .get_config(Some("app.logging.target_levels"))
+            .await
+            .unwrap_or_default(),
+        Err(_) => Default::default(),
+    }
+}
+
 fn spawn_runtime_log_level_listener(default_level: log::LevelFilter) {
     use bitfun_core::service::config::{subscribe_config_updates, ConfigUpdateEvent};
 
@@ -985,9 +1158,14 @@ fn spawn_runtime_log_level_listener(default_level: log::LevelFilter) {
                             );
                         }
                     }
+                    Ok(ConfigUpdateEvent::LogTargetLevelsUpdated { target_levels }) => {
+                        logging::apply_target_log_levels(&target_levels, "config_update_event");
+                    }
                     Ok(ConfigUpdateEvent::ConfigReloaded) => {
                         let level = resolve_runtime_log_level(default_level).await;
                         logging::apply_runtime_log_level(level, "config_reloaded");
+                        let target_levels = resolve_target_log_levels().await;
+                        logging::apply_target_log_levels(&target_levels, "config_reloaded");
                     }
                     Ok(_) => {}
                     Err(tokio::sync::broadcast::error::RecvError::Closed) => {
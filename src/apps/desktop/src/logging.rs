@@ -3,19 +3,22 @@
 use bitfun_core::infrastructure::get_path_manager_arc;
 use chrono::Local;
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicU8, Ordering},
-    OnceLock,
+    OnceLock, RwLock,
 };
 use std::thread;
 use tauri_plugin_log::{fern, Target, TargetKind};
 
-const SESSION_DIR_PATTERN: &str = r"^\d{8}T\d{6}$";
+pub(crate) const SESSION_DIR_PATTERN: &str = r"^\d{8}T\d{6}$";
 const MAX_LOG_SESSIONS: usize = 10;
 static SESSION_LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
 // Default to Debug in early development for easier diagnostics
 static CURRENT_LOG_LEVEL: AtomicU8 = AtomicU8::new(level_filter_to_u8(log::LevelFilter::Debug));
+/// Per-target overrides, longest prefix first.
+static TARGET_LOG_LEVELS: RwLock<Vec<(String, log::LevelFilter)>> = RwLock::new(Vec::new());
 
 fn get_thread_id() -> u64 {
     let thread_id = thread::current().id();
@@ -38,7 +41,7 @@ fn is_embedded_webdriver_mode() -> bool {
     cfg!(debug_assertions) && std::env::var_os("BITFUN_WEBDRIVER_PORT").is_some()
 }
 
-fn resolve_logs_root() -> PathBuf {
+pub(crate) fn resolve_logs_root() -> PathBuf {
     if let Some(path) = std::env::var_os("BITFUN_LOG_DIR").map(PathBuf::from) {
         return path;
     }
@@ -122,21 +125,105 @@ pub fn level_to_str(level: log::LevelFilter) -> &'static str {
 pub fn register_runtime_log_state(initial_level: log::LevelFilter, session_log_dir: PathBuf) {
     let _ = SESSION_LOG_DIR.set(session_log_dir);
     CURRENT_LOG_LEVEL.store(level_filter_to_u8(initial_level), Ordering::Relaxed);
-    log::set_max_level(initial_level);
+    update_max_level();
 }
 
 pub fn current_runtime_log_level() -> log::LevelFilter {
     u8_to_level_filter(CURRENT_LOG_LEVEL.load(Ordering::Relaxed))
 }
 
+/// The `log` crate drops records above the max level before any filter sees
+/// them, so it has to admit the most verbose override as well.
+fn update_max_level() {
+    let most_verbose_override = TARGET_LOG_LEVELS
+        .read()
+        .map(|levels| levels.iter().map(|(_, level)| *level).max())
+        .unwrap_or_default();
+    let global = current_runtime_log_level();
+    log::set_max_level(most_verbose_override.map_or(global, |level| level.max(global)));
+}
+
+/// Level that applies to `target`: the override with the longest matching
+/// module-path prefix, or the global runtime level.
+fn effective_level_for(target: &str) -> log::LevelFilter {
+    if let Ok(levels) = TARGET_LOG_LEVELS.read() {
+        for (prefix, level) in levels.iter() {
+            let matches = target
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"));
+            if matches {
+                return *level;
+            }
+        }
+    }
+    current_runtime_log_level()
+}
+
+/// Log dispatch filter applying the per-target overrides.
+pub fn record_enabled(metadata: &log::Metadata) -> bool {
+    metadata.level() <= effective_level_for(metadata.target())
+}
+
+/// Replaces the per-target overrides. Entries with an invalid level are
+/// skipped with a warning.
+pub fn apply_target_log_levels(target_levels: &HashMap<String, String>, source: &str) {
+    let mut levels: Vec<(String, log::LevelFilter)> = target_levels
+        .iter()
+        .filter_map(|(target, level)| match parse_log_level(level) {
+            Some(level) if !target.trim().is_empty() => Some((target.trim().to_string(), level)),
+            _ => {
+                log::warn!(
+                    "Ignoring invalid log level override: target={}, level={}",
+                    target,
+                    level
+                );
+                None
+            }
+        })
+        .collect();
+    levels.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+    match TARGET_LOG_LEVELS.write() {
+        Ok(mut current) => {
+            if *current == levels {
+                return;
+            }
+            *current = levels;
+        }
+        Err(e) => {
+            log::warn!("Failed to update log level overrides: {}", e);
+            return;
+        }
+    }
+    update_max_level();
+    log::info!(
+        "Runtime log level overrides updated: overrides={:?}, source={}",
+        target_log_levels(),
+        source
+    );
+}
+
+/// Current per-target overrides.
+pub fn target_log_levels() -> BTreeMap<String, String> {
+    TARGET_LOG_LEVELS
+        .read()
+        .map(|levels| {
+            levels
+                .iter()
+                .map(|(target, level)| (target.clone(), level_to_str(*level).to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub fn apply_runtime_log_level(level: log::LevelFilter, source: &str) {
     let old_level = current_runtime_log_level();
     if old_level == level {
         return;
     }
 
-    log::set_max_level(level);
     CURRENT_LOG_LEVEL.store(level_filter_to_u8(level), Ordering::Relaxed);
+    update_max_level();
     log::info!(
         "Runtime log level updated: old_level={}, new_level={}, source={}",
         level_to_str(old_level),
@@ -153,6 +240,8 @@ pub fn session_log_dir() -> Option<PathBuf> {
 #[serde(rename_all = "camelCase")]
 pub struct RuntimeLoggingInfo {
     pub effective_level: String,
+    /// Per-target level overrides in effect.
+    pub target_levels: BTreeMap<String, String>,
     pub session_log_dir: String,
     pub app_log_path: String,
     pub ai_log_path: String,
@@ -165,6 +254,7 @@ pub fn get_runtime_logging_info() -> RuntimeLoggingInfo {
 
     RuntimeLoggingInfo {
         effective_level: level_to_str(current_runtime_log_level()).to_string(),
+        target_levels: target_log_levels(),
         session_log_dir: session_dir.to_string_lossy().to_string(),
         app_log_path: session_dir.join("app.log").to_string_lossy().to_string(),
         ai_log_path: session_dir.join("ai.log").to_string_lossy().to_string(),
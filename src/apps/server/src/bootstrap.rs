@@ -116,6 +116,7 @@ pub async fn initialize(workspace: Option<String>) -> anyhow::Result<Arc<ServerA
     let token_usage_subscriber =
         Arc::new(token_usage::TokenUsageSubscriber::new(token_usage_service.clone()));
     event_router.subscribe_internal("token_usage".to_string(), token_usage_subscriber);
+    token_usage::set_global_token_usage_service(token_usage_service.clone());
 
     // Dialog scheduler
     let scheduler =
@@ -110,8 +110,8 @@ pub async fn dispatch(
         "get_config" => {
             let request = extract_request(&params)?;
             let key = request.get("key").and_then(|v| v.as_str());
-            let config: serde_json::Value = state.config_service
-                .get_config(key).await
+            let config = state.config_service
+                .get_redacted_config(key).await
                 .map_err(|e| anyhow!("{}", e))?;
             Ok(config)
         }
@@ -119,14 +119,16 @@ pub async fn dispatch(
             let request = extract_request(&params)?;
             let key = get_string(&request, "key")?;
             let value = request.get("value").cloned().ok_or_else(|| anyhow!("Missing value"))?;
-            state.config_service.set_config(&key, value).await
+            state.config_service.set_redacted_config(&key, value).await
                 .map_err(|e| anyhow!("{}", e))?;
             Ok(serde_json::json!("ok"))
         }
         "get_model_configs" => {
             let models = state.config_service.get_ai_models().await
                 .map_err(|e| anyhow!("{}", e))?;
-            Ok(serde_json::to_value(&models).unwrap_or_default())
+            let mut models = serde_json::to_value(&models).unwrap_or_default();
+            bitfun_core::service::config::secrets::redact_secrets(&mut models);
+            Ok(models)
         }
 
         // ── Agentic (Session / Dialog) ───────────────────────
@@ -232,23 +234,25 @@ pub async fn dispatch(
         }
         "confirm_tool_execution" => {
             let request = extract_request(&params)?;
+            let session_id = get_string(&request, "sessionId")?;
             let tool_id = get_string(&request, "toolId")?;
             let updated_input = request.get("updatedInput").cloned();
             state.coordinator
-                .confirm_tool(&tool_id, updated_input)
+                .confirm_tool(&session_id, &tool_id, updated_input)
                 .await
                 .map_err(|e| anyhow!("{}", e))?;
             Ok(serde_json::json!({ "success": true }))
         }
         "reject_tool_execution" => {
             let request = extract_request(&params)?;
+            let session_id = get_string(&request, "sessionId")?;
             let tool_id = get_string(&request, "toolId")?;
             let reason = request.get("reason")
                 .and_then(|v| v.as_str())
                 .unwrap_or("User rejected")
                 .to_string();
             state.coordinator
-                .reject_tool(&tool_id, reason)
+                .reject_tool(&session_id, &tool_id, reason)
                 .await
                 .map_err(|e| anyhow!("{}", e))?;
             Ok(serde_json::json!({ "success": true }))
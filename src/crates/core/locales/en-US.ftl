@@ -126,6 +126,43 @@ error-timeout = Request timeout
 error-server = Server error
 error-unauthorized = Unauthorized
 error-forbidden = Access forbidden
+error-ai-rate-limited = The AI provider is rate limiting requests. Wait a moment and try again.
+error-ai-auth = The AI provider rejected the credentials. Check the API key in the model settings.
+error-ai-server = The AI provider is temporarily unavailable. Try again shortly.
+error-ai-timeout = The AI provider took too long to respond. Try again.
+error-ai-network = Could not reach the AI provider. Check the network connection and try again.
+error-mcp-connection = Lost the connection to an MCP server. Try again once it is reachable.
+error-offline = Offline mode is on, so this needs a model served on this machine. Turn off offline mode to use online services again.
+error-operation-timeout = The operation timed out. Try again.
+error-cancelled = The operation was cancelled.
+error-generic = { $message }
+
+# ==================== Permission Prompts ====================
+permission-run-command = Allow running `{ $command }`?
+permission-write-file = Allow writing { $path }?
+permission-edit-file = Allow editing { $path }?
+permission-delete-file = Allow deleting { $path }?
+permission-fetch-url = Allow fetching { $url }?
+permission-use-tool = Allow using { $tool }?
+
+# ==================== Welcome ====================
+welcome-greeting-morning = Good morning
+welcome-greeting-afternoon = Good afternoon
+welcome-greeting-evening = Good evening
+welcome-greeting-subtitle = What would you like to work on?
+
+# ==================== CLI ====================
+cli-status-ready = Ready
+cli-status-starting = Starting
+cli-status-thinking = Thinking
+cli-status-responding = Responding
+cli-status-running-tools = Running tools
+cli-status-awaiting-approval = Awaiting approval
+cli-status-default-model = default model
+cli-status-offline = Offline
+cli-permission-allow-once = Allow once
+cli-permission-allow-always = Allow always
+cli-permission-deny = Deny
 
 # ==================== Time ====================
 time-just-now = just now
@@ -126,6 +126,43 @@ error-timeout = 请求超时
 error-server = 服务器错误
 error-unauthorized = 未授权
 error-forbidden = 禁止访问
+error-ai-rate-limited = AI 服务商正在限制请求频率，请稍候再试。
+error-ai-auth = AI 服务商拒绝了凭据，请检查模型设置中的 API Key。
+error-ai-server = AI 服务商暂时不可用，请稍后再试。
+error-ai-timeout = AI 服务商响应超时，请重试。
+error-ai-network = 无法连接到 AI 服务商，请检查网络连接后重试。
+error-mcp-connection = 与 MCP 服务器的连接已断开，请在其可用后重试。
+error-offline = 当前处于离线模式，只能使用本机运行的模型。关闭离线模式后即可重新使用在线服务。
+error-operation-timeout = 操作超时，请重试。
+error-cancelled = 操作已取消。
+error-generic = { $message }
+
+# ==================== 权限确认 ====================
+permission-run-command = 是否允许运行 `{ $command }`？
+permission-write-file = 是否允许写入 { $path }？
+permission-edit-file = 是否允许编辑 { $path }？
+permission-delete-file = 是否允许删除 { $path }？
+permission-fetch-url = 是否允许访问 { $url }？
+permission-use-tool = 是否允许使用 { $tool }？
+
+# ==================== 欢迎 ====================
+welcome-greeting-morning = 早上好
+welcome-greeting-afternoon = 下午好
+welcome-greeting-evening = 晚上好
+welcome-greeting-subtitle = 今天想做些什么？
+
+# ==================== 命令行 ====================
+cli-status-ready = 就绪
+cli-status-starting = 启动中
+cli-status-thinking = 思考中
+cli-status-responding = 回复中
+cli-status-running-tools = 正在执行工具
+cli-status-awaiting-approval = 等待确认
+cli-status-default-model = 默认模型
+cli-status-offline = 离线
+cli-permission-allow-once = 允许一次
+cli-permission-allow-always = 始终允许
+cli-permission-deny = 拒绝
 
 # ==================== 时间 ====================
 time-just-now = 刚刚
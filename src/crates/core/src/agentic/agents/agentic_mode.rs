@@ -15,8 +15,10 @@ impl AgenticMode {
                 "Write".to_string(),
                 "Edit".to_string(),
                 "Delete".to_string(),
+                "ReadLints".to_string(),
                 "Bash".to_string(),
                 "Grep".to_string(),
+                "CodebaseSearch".to_string(),
                 "Glob".to_string(),
                 "WebSearch".to_string(),
                 "TodoWrite".to_string(),
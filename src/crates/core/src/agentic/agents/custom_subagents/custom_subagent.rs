@@ -94,6 +94,16 @@ impl CustomSubagent {
 
     pub fn from_file(path: &str, kind: CustomSubagentKind) -> BitFunResult<Self> {
         let (metadata, content) = FrontMatterMarkdown::load(path)?;
+        Self::from_front_matter(&metadata, content, path, kind)
+    }
+
+    /// Build from parsed front matter; `tools` may be a comma-separated string or a list
+    fn from_front_matter(
+        metadata: &Value,
+        content: String,
+        path: &str,
+        kind: CustomSubagentKind,
+    ) -> BitFunResult<Self> {
         let name = metadata
             .get("name")
             .and_then(|v| v.as_str())
@@ -104,11 +114,15 @@ impl CustomSubagent {
             .and_then(|v| v.as_str())
             .ok_or_else(|| BitFunError::Agent("Missing description field".to_string()))?
             .to_string();
-        let tools: Vec<String> = metadata
-            .get("tools")
-            .and_then(|v| v.as_str())
-            .map(|s| s.split(',').map(|x| x.trim().to_string()).collect())
-            .unwrap_or_else(|| Self::DEFAULT_TOOLS.iter().map(|s| s.to_string()).collect());
+        let tools: Vec<String> = match metadata.get("tools") {
+            Some(Value::String(s)) => s.split(',').map(|x| x.trim().to_string()).collect(),
+            Some(Value::Sequence(items)) => items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .map(|x| x.trim().to_string())
+                .collect(),
+            _ => Self::DEFAULT_TOOLS.iter().map(|s| s.to_string()).collect(),
+        };
 
         let readonly = metadata
             .get("readonly")
@@ -200,3 +214,44 @@ impl CustomSubagent {
             .map_err(|e| BitFunError::Agent(e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> BitFunResult<CustomSubagent> {
+        let (metadata, content) = FrontMatterMarkdown::load_str(source)?;
+        CustomSubagent::from_front_matter(
+            &metadata,
+            content,
+            "reviewer.md",
+            CustomSubagentKind::Project,
+        )
+    }
+
+    #[test]
+    fn parses_front_matter_and_prompt() {
+        let agent = parse(
+            "---\nname: reviewer\ndescription: Reviews diffs\ntools: Read, Grep\nmodel: fast\nreadonly: false\n---\nYou review code.\n",
+        )
+        .unwrap();
+        assert_eq!(agent.name, "reviewer");
+        assert_eq!(agent.tools, ["Read", "Grep"]);
+        assert_eq!(agent.model, "fast");
+        assert!(!agent.readonly);
+        assert_eq!(agent.prompt.trim(), "You review code.");
+    }
+
+    #[test]
+    fn accepts_tool_lists_and_defaults() {
+        let agent = parse(
+            "---\nname: reviewer\ndescription: Reviews diffs\ntools:\n  - Read\n  - Bash\n---\nPrompt\n",
+        )
+        .unwrap();
+        assert_eq!(agent.tools, ["Read", "Bash"]);
+        assert!(agent.readonly);
+        assert_eq!(agent.model, "primary");
+
+        assert!(parse("---\nname: reviewer\n---\nPrompt\n").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::agentic::agents::Agent;
 use crate::infrastructure::get_path_manager_arc;
 use log::error;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -13,6 +14,14 @@ pub struct SubagentDirEntry {
     pub kind: CustomSubagentKind,
 }
 
+/// Custom subagent file that could not be registered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomSubagentLoadError {
+    pub path: String,
+    pub message: String,
+}
+
 /// Project subagent directory names (relative to workspace root, each item is in [".bitfun", "agents"] format)
 const PROJECT_AGENT_SUBDIRS: &[(&str, &str)] = &[
     (".bitfun", "agents"),
@@ -71,10 +80,13 @@ impl CustomSubagentLoader {
         entries
     }
 
-    /// Load custom subagents from all possible paths (only .md files).
+    /// Load custom subagents from all possible paths (only .md files), with the files that failed to parse.
     /// Agents with the same name are prioritized by path order: earlier paths have higher priority, later ones won't override already loaded agents with the same name.
-    pub fn load_custom_subagents(workspace_root: &Path) -> Vec<CustomSubagent> {
+    pub fn load_custom_subagents(
+        workspace_root: &Path,
+    ) -> (Vec<CustomSubagent>, Vec<CustomSubagentLoadError>) {
         let mut by_id: HashMap<String, CustomSubagent> = HashMap::new();
+        let mut errors = Vec::new();
         for entry in Self::get_possible_paths(workspace_root) {
             for md_path in Self::list_md_files(&entry.path) {
                 let path_str = md_path.to_string_lossy();
@@ -88,11 +100,15 @@ impl CustomSubagentLoader {
                             md_path.display(),
                             e
                         );
+                        errors.push(CustomSubagentLoadError {
+                            path: path_str.to_string(),
+                            message: e.to_string(),
+                        });
                     }
                 }
             }
         }
-        by_id.into_values().collect()
+        (by_id.into_values().collect(), errors)
     }
 
     /// List all .md files in directory (non-recursive)
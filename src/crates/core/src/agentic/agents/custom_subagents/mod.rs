@@ -2,4 +2,4 @@ mod custom_subagent;
 mod custom_subagent_loader;
 
 pub use custom_subagent::{CustomSubagent, CustomSubagentKind};
-pub use custom_subagent_loader::CustomSubagentLoader;
+pub use custom_subagent_loader::{CustomSubagentLoadError, CustomSubagentLoader};
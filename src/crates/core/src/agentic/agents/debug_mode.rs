@@ -1,6 +1,6 @@
 //! Debug Mode - Evidence-driven debugging mode
 
-use super::prompt_builder::{PromptBuilder, PromptBuilderContext};
+use super::prompt_builder::{load_prompt_template, PromptBuilder, PromptBuilderContext};
 use super::Agent;
 use crate::service::config::global::GlobalConfigManager;
 use crate::service::config::types::{DebugModeConfig, LanguageDebugTemplate};
@@ -12,8 +12,6 @@ use std::path::Path;
 
 pub struct DebugMode;
 
-include!(concat!(env!("OUT_DIR"), "/embedded_agents_prompt.rs"));
-
 impl DebugMode {
     pub fn new() -> Self {
         Self
@@ -290,8 +288,8 @@ impl Agent for DebugMode {
             project_info.languages, project_info.project_types
         );
 
-        let system_prompt_template = get_embedded_prompt("debug_mode")
-            .unwrap_or("Debug mode prompt not found in embedded files");
+        let system_prompt_template =
+            load_prompt_template("debug_mode", Some(Path::new(workspace_path)))?;
 
         let language_templates =
             Self::build_language_templates_prompt(&debug_config, &project_info.languages);
@@ -351,8 +349,10 @@ Below is a snapshot of the current workspace's file structure.
             "Write".to_string(),
             "Edit".to_string(),
             "Delete".to_string(),
+            "ReadLints".to_string(),
             "Bash".to_string(),
             "Grep".to_string(),
+            "CodebaseSearch".to_string(),
             "Glob".to_string(),
             "WebSearch".to_string(),
             "TodoWrite".to_string(),
@@ -11,6 +11,7 @@ impl ExploreAgent {
                 "LS".to_string(),
                 "Read".to_string(),
                 "Grep".to_string(),
+                "CodebaseSearch".to_string(),
                 "Glob".to_string(),
             ],
         }
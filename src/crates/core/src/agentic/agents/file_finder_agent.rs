@@ -12,6 +12,7 @@ impl FileFinderAgent {
                 "LS".to_string(),
                 "Read".to_string(),
                 "Grep".to_string(),
+                "CodebaseSearch".to_string(),
                 "Glob".to_string(),
             ],
         }
@@ -11,6 +11,7 @@ mod claw_mode;
 mod cowork_mode;
 mod debug_mode;
 mod plan_mode;
+mod review_agent;
 // Built-in subagents
 mod explore_agent;
 mod file_finder_agent;
@@ -24,18 +25,25 @@ use async_trait::async_trait;
 pub use claw_mode::ClawMode;
 pub use code_review_agent::CodeReviewAgent;
 pub use cowork_mode::CoworkMode;
-pub use custom_subagents::{CustomSubagent, CustomSubagentKind};
+pub use custom_subagents::{CustomSubagent, CustomSubagentKind, CustomSubagentLoadError};
 pub use debug_mode::DebugMode;
 pub use explore_agent::ExploreAgent;
 pub use file_finder_agent::FileFinderAgent;
 pub use generate_doc_agent::GenerateDocAgent;
 pub use plan_mode::PlanMode;
-pub use prompt_builder::{PromptBuilder, PromptBuilderContext};
+pub use prompt_builder::{
+    check_prompt_template, list_prompt_templates, load_prompt_template, prompt_override_paths,
+    resolve_prompt_template, template_placeholders, PromptBuilder, PromptBuilderContext,
+    PromptPlaceholder, PromptTemplate, PromptTemplateCheck, PromptTemplateSource,
+    PROMPT_PLACEHOLDERS,
+};
 pub use registry::{
-    get_agent_registry, AgentCategory, AgentInfo, AgentRegistry, CustomSubagentConfig,
-    SubAgentSource,
+    get_agent_registry, get_tool_policy, AgentCategory, AgentInfo, AgentRegistry,
+    CustomSubagentConfig, SubAgentSource,
 };
+pub use review_agent::ReviewAgent;
 use std::any::Any;
+use std::path::Path;
 
 // Include embedded prompts generated at compile time
 include!(concat!(env!("OUT_DIR"), "/embedded_agents_prompt.rs"));
@@ -66,12 +74,11 @@ pub trait Agent: Send + Sync + 'static {
     async fn build_prompt(&self, context: &PromptBuilderContext) -> BitFunResult<String> {
         let prompt_components = PromptBuilder::new(context.clone());
         let template_name = self.prompt_template_name(context.model_name.as_deref());
-        let system_prompt_template = get_embedded_prompt(template_name).ok_or_else(|| {
-            BitFunError::Agent(format!("{} not found in embedded files", template_name))
-        })?;
+        let system_prompt_template =
+            load_prompt_template(template_name, Some(Path::new(&context.workspace_path)))?;
 
         let prompt = prompt_components
-            .build_prompt_from_template(system_prompt_template)
+            .build_prompt_from_template(&system_prompt_template)
             .await?;
 
         Ok(prompt)
@@ -97,14 +104,7 @@ pub trait Agent: Send + Sync + 'static {
     /// index is not used for now (Cursor first time enter plan mode and keep plan mode will use different reminder)
     async fn get_system_reminder(&self, _index: usize) -> BitFunResult<String> {
         if let Some(system_reminder_template_name) = self.system_reminder_template_name() {
-            let system_reminder =
-                get_embedded_prompt(system_reminder_template_name).ok_or_else(|| {
-                    BitFunError::Agent(format!(
-                        "{} not found in embedded files",
-                        system_reminder_template_name
-                    ))
-                })?;
-            Ok(system_reminder.to_string())
+            load_prompt_template(system_reminder_template_name, None)
         } else {
             Ok("".to_string())
         }
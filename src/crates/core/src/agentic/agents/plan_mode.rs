@@ -16,6 +16,7 @@ impl PlanMode {
                 "Write".to_string(),
                 "Edit".to_string(),
                 "Grep".to_string(),
+                "CodebaseSearch".to_string(),
                 "Glob".to_string(),
                 "AskUserQuestion".to_string(),
                 "CreatePlan".to_string(),
@@ -1,3 +1,9 @@
 mod prompt_builder;
+mod prompt_templates;
 
 pub use prompt_builder::{PromptBuilder, PromptBuilderContext};
+pub use prompt_templates::{
+    check_prompt_template, list_prompt_templates, load_prompt_template, prompt_override_paths,
+    resolve_prompt_template, template_placeholders, PromptPlaceholder, PromptTemplate,
+    PromptTemplateCheck, PromptTemplateSource, PROMPT_PLACEHOLDERS,
+};
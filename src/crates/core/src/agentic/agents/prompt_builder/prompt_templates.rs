@@ -0,0 +1,297 @@
+//! Prompt templates with workspace and user overrides
+//!
+//! A template named `<name>` resolves, in order, to
+//! `<workspace>/.bitfun/prompts/<name>.md`, the user-level
+//! `prompts/<name>.md` under the BitFun config directory, and finally the
+//! template embedded in the crate. Overrides are checked against the
+//! documented placeholders when they are rendered.
+
+use crate::agentic::agents::{get_all_embedded_prompt_names, get_embedded_prompt};
+use crate::infrastructure::get_path_manager_arc;
+use crate::util::errors::{BitFunError, BitFunResult};
+use log::{debug, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Placeholder that the prompt builder replaces when rendering a template.
+#[derive(Debug, Clone, Copy)]
+pub struct PromptPlaceholder {
+    /// Name inside the braces, e.g. `ENV_INFO` for `{ENV_INFO}`
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Templates that cannot work without it; an override of one of them must keep it
+    pub required_by: &'static [&'static str],
+}
+
+/// Placeholders supported in prompt templates.
+pub const PROMPT_PLACEHOLDERS: &[PromptPlaceholder] = &[
+    PromptPlaceholder {
+        name: "ENV_INFO",
+        description: "Operating system, date and workspace path",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "PROJECT_LAYOUT",
+        description: "Snapshot of the workspace file tree",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "PROJECT_CONTEXT_FILES",
+        description: "Enabled project context documents; filter with `{PROJECT_CONTEXT_FILES:include=general,design}` or `:exclude=review`",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "RULES",
+        description: "User and project AI rules",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "MEMORIES",
+        description: "Saved AI memories",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "AGENT_MEMORY",
+        description: "Workspace agent memory",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "PERSONA",
+        description: "Assistant persona from the workspace persona files",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "LANGUAGE_PREFERENCE",
+        description: "Reply language from the app settings",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "CLAW_WORKSPACE",
+        description: "Location of the Claw assistant workspace",
+        required_by: &["claw_mode"],
+    },
+    PromptPlaceholder {
+        name: "VISUAL_MODE",
+        description: "Diagram output instructions when visual mode is on",
+        required_by: &[],
+    },
+    PromptPlaceholder {
+        name: "LOG_PATH",
+        description: "Debug mode: file the instrumented code writes logs to",
+        required_by: &["debug_mode"],
+    },
+    PromptPlaceholder {
+        name: "INGEST_PORT",
+        description: "Debug mode: port of the log ingest server",
+        required_by: &["debug_mode"],
+    },
+    PromptPlaceholder {
+        name: "LANGUAGE_TEMPLATES",
+        description: "Debug mode: logging snippets for the detected languages",
+        required_by: &[],
+    },
+];
+
+static PLACEHOLDER_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{([A-Z][A-Z0-9_]*)(?::[^}\n]*)?\}").expect("valid placeholder pattern")
+});
+
+/// Where a prompt template was loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptTemplateSource {
+    Workspace,
+    User,
+    Embedded,
+}
+
+/// A resolved prompt template.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub content: String,
+    pub source: PromptTemplateSource,
+    /// Override file; `None` for embedded templates
+    pub path: Option<PathBuf>,
+}
+
+/// Placeholder problems in a template override.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptTemplateCheck {
+    /// Placeholders the prompt builder does not know; left in the prompt as-is
+    pub unknown: Vec<String>,
+    /// Required placeholders of the template that the override dropped
+    pub missing_required: Vec<String>,
+}
+
+/// Names of the embedded templates, sorted.
+pub fn list_prompt_templates() -> Vec<&'static str> {
+    let mut names = get_all_embedded_prompt_names();
+    names.sort_unstable();
+    names
+}
+
+/// Override files for `name`, most specific first.
+pub fn prompt_override_paths(
+    name: &str,
+    workspace_root: Option<&Path>,
+) -> Vec<(PromptTemplateSource, PathBuf)> {
+    let path_manager = get_path_manager_arc();
+    let file_name = format!("{}.md", name);
+    let mut paths = Vec::new();
+    if let Some(workspace_root) = workspace_root {
+        paths.push((
+            PromptTemplateSource::Workspace,
+            path_manager
+                .project_prompts_dir(workspace_root)
+                .join(&file_name),
+        ));
+    }
+    paths.push((
+        PromptTemplateSource::User,
+        path_manager.user_prompts_dir().join(file_name),
+    ));
+    paths
+}
+
+/// Resolves `name` to an override file or the embedded template.
+pub fn resolve_prompt_template(
+    name: &str,
+    workspace_root: Option<&Path>,
+) -> BitFunResult<Option<PromptTemplate>> {
+    for (source, path) in prompt_override_paths(name, workspace_root) {
+        if !path.is_file() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            BitFunError::Agent(format!(
+                "Failed to read prompt override {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        debug!(
+            "Using prompt override: template={}, path={}",
+            name,
+            path.display()
+        );
+        return Ok(Some(PromptTemplate {
+            name: name.to_string(),
+            content,
+            source,
+            path: Some(path),
+        }));
+    }
+
+    Ok(get_embedded_prompt(name).map(|content| PromptTemplate {
+        name: name.to_string(),
+        content: content.to_string(),
+        source: PromptTemplateSource::Embedded,
+        path: None,
+    }))
+}
+
+/// Loads the template `name` for rendering.
+///
+/// Unknown placeholders in an override are logged; an override that drops a
+/// required placeholder is an error.
+pub fn load_prompt_template(name: &str, workspace_root: Option<&Path>) -> BitFunResult<String> {
+    let template = resolve_prompt_template(name, workspace_root)?
+        .ok_or_else(|| BitFunError::Agent(format!("{} not found in embedded files", name)))?;
+    let Some(path) = &template.path else {
+        return Ok(template.content);
+    };
+
+    let check = check_prompt_template(name, &template.content);
+    if !check.unknown.is_empty() {
+        warn!(
+            "Prompt override {} uses unknown placeholders, left as-is: {}",
+            path.display(),
+            check.unknown.join(", ")
+        );
+    }
+    if !check.missing_required.is_empty() {
+        return Err(BitFunError::Agent(format!(
+            "Prompt override {} is missing required placeholders: {}",
+            path.display(),
+            check
+                .missing_required
+                .iter()
+                .map(|name| format!("{{{}}}", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    Ok(template.content)
+}
+
+/// Names of the placeholders used in `content`, in order of first use.
+pub fn template_placeholders(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for captures in PLACEHOLDER_PATTERN.captures_iter(content) {
+        let name = &captures[1];
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Checks the placeholders of an override of template `name`.
+pub fn check_prompt_template(name: &str, content: &str) -> PromptTemplateCheck {
+    let used = template_placeholders(content);
+    let unknown = used
+        .iter()
+        .filter(|used| !PROMPT_PLACEHOLDERS.iter().any(|p| p.name == used.as_str()))
+        .cloned()
+        .collect();
+    let missing_required = PROMPT_PLACEHOLDERS
+        .iter()
+        .filter(|p| p.required_by.contains(&name))
+        .filter(|p| !used.iter().any(|used| used == p.name))
+        .map(|p| p.name.to_string())
+        .collect();
+    PromptTemplateCheck {
+        unknown,
+        missing_required,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_placeholders_with_filters() {
+        let content = "{ENV_INFO}\n{PROJECT_CONTEXT_FILES:include=general}\n{ENV_INFO} {lowercase} {TEAM_STYLE}";
+        assert_eq!(
+            template_placeholders(content),
+            ["ENV_INFO", "PROJECT_CONTEXT_FILES", "TEAM_STYLE"]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_and_missing_required_placeholders() {
+        let check = check_prompt_template("debug_mode", "{ENV_INFO} {LOG_PATH} {TEAM_STYLE}");
+        assert_eq!(check.unknown, ["TEAM_STYLE"]);
+        assert_eq!(check.missing_required, ["INGEST_PORT"]);
+
+        let check = check_prompt_template("agentic_mode", "Be terse. {RULES}");
+        assert_eq!(check, PromptTemplateCheck::default());
+    }
+
+    #[test]
+    fn embedded_templates_use_only_documented_placeholders() {
+        for name in list_prompt_templates() {
+            let content = get_embedded_prompt(name).unwrap();
+            assert_eq!(
+                check_prompt_template(name, content),
+                PromptTemplateCheck::default(),
+                "template {}",
+                name
+            );
+        }
+    }
+}
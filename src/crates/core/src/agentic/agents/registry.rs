@@ -1,13 +1,16 @@
 use super::{
     Agent, AgenticMode, ClawMode, CodeReviewAgent, CoworkMode, DebugMode, ExploreAgent,
-    FileFinderAgent, GenerateDocAgent, PlanMode,
+    FileFinderAgent, GenerateDocAgent, PlanMode, ReviewAgent,
 };
 use crate::agentic::agents::custom_subagents::{
-    CustomSubagent, CustomSubagentKind, CustomSubagentLoader,
+    CustomSubagent, CustomSubagentKind, CustomSubagentLoadError, CustomSubagentLoader,
 };
 use crate::agentic::tools::get_all_registered_tool_names;
+use crate::infrastructure::offline;
 use crate::service::config::global::GlobalConfigManager;
-use crate::service::config::types::{ModeConfig, SubAgentConfig};
+use crate::service::config::types::{
+    AgentOverrideConfig, ModeConfig, SubAgentConfig, ToolPolicyConfig,
+};
 use crate::service::config::GlobalConfig;
 use crate::util::errors::{BitFunError, BitFunResult};
 use log::{debug, error, warn};
@@ -110,7 +113,7 @@ impl AgentInfo {
 
 fn default_model_id_for_builtin_agent(agent_type: &str) -> &'static str {
     match agent_type {
-        "agentic" | "Cowork" | "Plan" | "debug" | "Claw" => "auto",
+        "agentic" | "Cowork" | "Plan" | "debug" | "Claw" | "Review" => "auto",
         _ => "primary",
     }
 }
@@ -137,6 +140,54 @@ async fn get_subagent_configs() -> HashMap<String, SubAgentConfig> {
     }
 }
 
+async fn get_agent_overrides() -> HashMap<String, AgentOverrideConfig> {
+    if let Ok(config_service) = GlobalConfigManager::get_service().await {
+        config_service
+            .get_config(Some("agents"))
+            .await
+            .unwrap_or_default()
+    } else {
+        HashMap::new()
+    }
+}
+
+/// tool policy of the workspace at `workspace_root`; a policy that cannot be
+/// read leaves tools unrestricted
+pub async fn get_tool_policy(workspace_root: Option<&Path>) -> ToolPolicyConfig {
+    let Ok(config_service) = GlobalConfigManager::get_service().await else {
+        return ToolPolicyConfig::default();
+    };
+    config_service
+        .get_tool_policy(workspace_root)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("[AgentRegistry] Failed to read tool policy: {}", e);
+            ToolPolicyConfig::default()
+        })
+}
+
+/// apply `additional_tools` and `removed_tools` of an agent override to `tools`;
+/// additional tools not in `valid_tools` are dropped with a warning
+fn apply_tool_overrides(
+    agent_type: &str,
+    mut tools: Vec<String>,
+    overrides: &AgentOverrideConfig,
+    valid_tools: &[String],
+) -> Vec<String> {
+    for tool in &overrides.additional_tools {
+        if !valid_tools.contains(tool) {
+            warn!(
+                "[AgentRegistry] Agent '{}' override adds unknown tool '{}', ignored",
+                agent_type, tool
+            );
+        } else if !tools.contains(tool) {
+            tools.push(tool.clone());
+        }
+    }
+    tools.retain(|tool| !overrides.removed_tools.contains(tool));
+    tools
+}
+
 /// Agent category
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentCategory {
@@ -154,6 +205,8 @@ pub struct AgentRegistry {
     agents: RwLock<HashMap<String, AgentEntry>>,
     /// workspace root -> (project subagent id -> agent_entry)
     project_subagents: RwLock<HashMap<PathBuf, HashMap<String, AgentEntry>>>,
+    /// workspace root -> custom subagent files rejected by the last load
+    custom_subagent_errors: RwLock<HashMap<PathBuf, Vec<CustomSubagentLoadError>>>,
 }
 
 impl AgentRegistry {
@@ -248,6 +301,7 @@ impl AgentRegistry {
             Arc::new(DebugMode::new()),
             Arc::new(PlanMode::new()),
             Arc::new(ClawMode::new()),
+            Arc::new(ReviewAgent::new()),
         ];
         for mode in modes {
             register(&mut agents, mode, AgentCategory::Mode, None);
@@ -279,6 +333,7 @@ impl AgentRegistry {
         Self {
             agents: RwLock::new(agents),
             project_subagents: RwLock::new(HashMap::new()),
+            custom_subagent_errors: RwLock::new(HashMap::new()),
         }
     }
 
@@ -355,6 +410,8 @@ impl AgentRegistry {
     /// get agent tools from config
     /// if not set, return default tools
     /// tool configuration synchronization is implemented through tool_config_sync, here only read configuration
+    /// the workspace tool policy is applied last, so no override can bring back a denied tool;
+    /// network tools are left out while offline
     pub async fn get_agent_tools(
         &self,
         agent_type: &str,
@@ -364,7 +421,7 @@ impl AgentRegistry {
         let Some(entry) = entry else {
             return Vec::new();
         };
-        match entry.category {
+        let tools = match entry.category {
             AgentCategory::Mode => {
                 let mode_configs = get_mode_configs().await;
                 mode_configs
@@ -373,7 +430,48 @@ impl AgentRegistry {
                     .unwrap_or_else(|| entry.agent.default_tools())
             }
             AgentCategory::SubAgent | AgentCategory::Hidden => entry.agent.default_tools(),
+        };
+
+        let mut tools = match get_agent_overrides().await.get(agent_type) {
+            Some(overrides) => {
+                let valid_tools = get_all_registered_tool_names().await;
+                apply_tool_overrides(agent_type, tools, overrides, &valid_tools)
+            }
+            None => tools,
+        };
+        let policy = get_tool_policy(workspace_root).await;
+        let offline = offline::is_offline();
+        tools.retain(|tool| policy.permits(tool) && !(offline && offline::is_network_tool(tool)));
+        tools
+    }
+
+    /// get model ID from the `agents.<agent_type>.model` override
+    /// an override naming a model that is not configured is ignored with a warning
+    pub async fn get_override_model_id(&self, agent_type: &str) -> Option<String> {
+        let model = get_agent_overrides()
+            .await
+            .remove(agent_type)?
+            .model
+            .filter(|model| !model.trim().is_empty())?;
+        let mut valid_models = Self::get_valid_model_ids().await;
+        valid_models.push("auto".to_string());
+        if !valid_models.contains(&model) {
+            warn!(
+                "[AgentRegistry] Agent '{}' override uses unknown model '{}', ignored",
+                agent_type, model
+            );
+            return None;
         }
+        Some(model)
+    }
+
+    /// get max model rounds per dialog turn from the `agents.<agent_type>.max_turns` override
+    pub async fn get_agent_max_turns(&self, agent_type: &str) -> Option<usize> {
+        get_agent_overrides()
+            .await
+            .get(agent_type)
+            .and_then(|overrides| overrides.max_turns)
+            .filter(|max_turns| *max_turns > 0)
     }
 
     /// get all mode agent information (including enabled status, used for frontend mode selector etc.)
@@ -405,6 +503,7 @@ impl AgentRegistry {
                     "Cowork" => 1,
                     "Plan" => 2,
                     "debug" => 3,
+                    "Review" => 4,
                     _ => 99,
                 }
             };
@@ -478,12 +577,16 @@ impl AgentRegistry {
     }
 
     /// load custom subagent: clear project/user source subagents, reload from workspace and register
-    pub async fn load_custom_subagents(&self, workspace_root: &Path) {
+    /// returns the files that were rejected (parse errors, names of built-in agents)
+    pub async fn load_custom_subagents(
+        &self,
+        workspace_root: &Path,
+    ) -> Vec<CustomSubagentLoadError> {
         // get valid tools and models list for verification
         let valid_tools = get_all_registered_tool_names().await;
         let valid_models = Self::get_valid_model_ids().await;
 
-        let custom = CustomSubagentLoader::load_custom_subagents(workspace_root);
+        let (custom, mut errors) = CustomSubagentLoader::load_custom_subagents(workspace_root);
         let mut map = self.write_agents();
         map.retain(|_, entry| {
             !(entry.category == AgentCategory::SubAgent
@@ -493,6 +596,21 @@ impl AgentRegistry {
         for mut sub in custom {
             let id = sub.id().to_string();
             let source = SubAgentSource::from_custom_kind(sub.kind);
+            // built-in agents keep their names; a custom agent must pick another one
+            if map.iter().any(|(existing_id, existing)| {
+                existing.custom_config.is_none() && existing_id.eq_ignore_ascii_case(&id)
+            }) {
+                let message = format!(
+                    "Custom agent '{}' uses the name of a built-in agent; rename it in the front matter",
+                    id
+                );
+                error!("{}: {}", sub.path, message);
+                errors.push(CustomSubagentLoadError {
+                    path: sub.path.clone(),
+                    message,
+                });
+                continue;
+            }
             // validate and correct tools and model
             Self::validate_custom_subagent(&mut sub, &valid_tools, &valid_models);
             // create CustomSubagentConfig cache configuration information
@@ -534,6 +652,29 @@ impl AgentRegistry {
         drop(map);
         self.write_project_subagents()
             .insert(workspace_root.to_path_buf(), project_entries);
+        match self.custom_subagent_errors.write() {
+            Ok(mut guard) => {
+                guard.insert(workspace_root.to_path_buf(), errors.clone());
+            }
+            Err(poisoned) => {
+                poisoned
+                    .into_inner()
+                    .insert(workspace_root.to_path_buf(), errors.clone());
+            }
+        }
+        errors
+    }
+
+    /// custom subagent files rejected by the last load for `workspace_root`
+    pub fn get_custom_subagent_errors(
+        &self,
+        workspace_root: &Path,
+    ) -> Vec<CustomSubagentLoadError> {
+        let errors = match self.custom_subagent_errors.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        errors.get(workspace_root).cloned().unwrap_or_default()
     }
 
     /// get valid model ID list: ai.models id + "primary" + "fast"
@@ -594,6 +735,9 @@ impl AgentRegistry {
     pub fn clear_custom_subagents(&self) {
         let before = self.read_project_subagents().len();
         self.write_project_subagents().clear();
+        if let Ok(mut errors) = self.custom_subagent_errors.write() {
+            errors.clear();
+        }
         debug!("Cleared project subagent caches: workspaces {}", before);
     }
 
@@ -726,6 +870,7 @@ impl AgentRegistry {
     }
 
     /// get model ID used by agent from agent_models[agent_type] in configuration
+    /// - any agent: `agents.<agent_type>.model` override takes precedence
     /// - custom subagent: read model configuration from custom_config cache
     /// - built-in subagent/mode: read model configuration from global configuration ai.agent_models
     pub async fn get_model_id_for_agent(
@@ -742,6 +887,14 @@ impl AgentRegistry {
             )));
         }
 
+        if let Some(model) = self.get_override_model_id(agent_type).await {
+            debug!(
+                "[AgentRegistry] Agent '{}' using model from agents override: {}",
+                agent_type, model
+            );
+            return Ok(model);
+        }
+
         // check if it is a custom subagent, if so, read from cache
         if let Some(entry) = self.find_agent_entry(agent_type, workspace_root) {
             if let Some(config) = entry.custom_config {
@@ -809,11 +962,12 @@ pub fn get_agent_registry() -> Arc<AgentRegistry> {
 
 #[cfg(test)]
 mod tests {
-    use super::default_model_id_for_builtin_agent;
+    use super::{apply_tool_overrides, default_model_id_for_builtin_agent};
+    use crate::service::config::types::{AgentOverrideConfig, ToolPolicyConfig};
 
     #[test]
     fn top_level_modes_default_to_auto() {
-        for agent_type in ["agentic", "Cowork", "Plan", "debug", "Claw"] {
+        for agent_type in ["agentic", "Cowork", "Plan", "debug", "Claw", "Review"] {
             assert_eq!(default_model_id_for_builtin_agent(agent_type), "auto");
         }
     }
@@ -823,4 +977,56 @@ mod tests {
         assert_eq!(default_model_id_for_builtin_agent("Explore"), "primary");
         assert_eq!(default_model_id_for_builtin_agent("CodeReview"), "primary");
     }
+
+    #[test]
+    fn tool_overrides_add_known_tools_and_remove_tools() {
+        let overrides = AgentOverrideConfig {
+            additional_tools: vec!["WebFetch".to_string(), "NoSuchTool".to_string()],
+            removed_tools: vec!["Bash".to_string()],
+            ..Default::default()
+        };
+        let valid_tools: Vec<String> = ["Read", "Bash", "WebFetch"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let tools = apply_tool_overrides(
+            "Explore",
+            vec!["Read".to_string(), "Bash".to_string()],
+            &overrides,
+            &valid_tools,
+        );
+        assert_eq!(tools, ["Read", "WebFetch"]);
+    }
+
+    #[test]
+    fn tool_policy_filters_overridden_tools() {
+        let overrides = AgentOverrideConfig {
+            additional_tools: vec!["WebFetch".to_string()],
+            ..Default::default()
+        };
+        let valid_tools: Vec<String> = ["Read", "Grep", "Bash", "WebFetch"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let mut tools = apply_tool_overrides(
+            "agentic",
+            vec!["Read".to_string(), "Grep".to_string(), "Bash".to_string()],
+            &overrides,
+            &valid_tools,
+        );
+
+        let policy = ToolPolicyConfig {
+            allowed_tools: vec![],
+            denied_tools: vec!["Bash".to_string(), "WebFetch".to_string()],
+        };
+        tools.retain(|tool| policy.permits(tool));
+        assert_eq!(tools, ["Read", "Grep"]);
+
+        let policy = ToolPolicyConfig {
+            allowed_tools: vec!["Read".to_string()],
+            denied_tools: vec![],
+        };
+        tools.retain(|tool| policy.permits(tool));
+        assert_eq!(tools, ["Read"]);
+    }
 }
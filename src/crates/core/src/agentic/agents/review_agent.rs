@@ -0,0 +1,68 @@
+//! Review Agent - audits a diff and submits structured findings
+//!
+//! Works on a ref range or the working tree, inspects context with read-only
+//! tools and ends with `submit_review_findings`. Suggested fixes are unified
+//! diffs, so they can be applied afterwards with `ApplyPatch`.
+
+use super::Agent;
+use async_trait::async_trait;
+
+pub struct ReviewAgent {
+    default_tools: Vec<String>,
+}
+
+impl ReviewAgent {
+    pub fn new() -> Self {
+        Self {
+            default_tools: vec![
+                // Changes under review
+                "GetFileDiff".to_string(),
+                "Git".to_string(),
+                // Context gathering tools (read-only)
+                "Read".to_string(),
+                "Grep".to_string(),
+                "Glob".to_string(),
+                "LS".to_string(),
+                // Findings submission tool
+                "submit_review_findings".to_string(),
+            ],
+        }
+    }
+}
+
+impl Default for ReviewAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Agent for ReviewAgent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn id(&self) -> &str {
+        "Review"
+    }
+
+    fn name(&self) -> &str {
+        "Review"
+    }
+
+    fn description(&self) -> &str {
+        "Audit a diff or the working tree changes and report findings with suggested fixes"
+    }
+
+    fn prompt_template_name(&self, _model_name: Option<&str>) -> &str {
+        "review_agent"
+    }
+
+    fn default_tools(&self) -> Vec<String> {
+        self.default_tools.clone()
+    }
+
+    fn is_readonly(&self) -> bool {
+        true
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! Top-level component that integrates all subsystems and provides a unified interface
 
-use super::{scheduler::DialogSubmissionPolicy, turn_outcome::TurnOutcome};
+use super::subagent_cache::{SubagentCacheKey, SubagentResultCache};
+use super::{scheduler::DialogSubmissionPolicy, structured_response, turn_outcome::TurnOutcome};
 use crate::agentic::agents::get_agent_registry;
 use crate::agentic::core::{
     has_prompt_markup, Message, MessageContent, ProcessingPhase, PromptEnvelope, Session,
-    SessionConfig, SessionState, SessionSummary, TurnStats,
+    SessionConfig, SessionState, SessionSummary, TurnBudget, TurnBudgetKind, TurnStats,
 };
 use crate::agentic::events::{
     AgenticEvent, EventPriority, EventQueue, EventRouter, EventSubscriber,
@@ -16,12 +17,17 @@ use crate::agentic::round_preempt::DialogRoundPreemptSource;
 use crate::agentic::image_analysis::ImageContextData;
 use crate::agentic::session::SessionManager;
 use crate::agentic::tools::pipeline::{SubagentParentInfo, ToolPipeline};
+use crate::agentic::turn_cancellation::turn_scope;
+use crate::agentic::turn_summary;
 use crate::agentic::WorkspaceBinding;
+use crate::infrastructure::filesystem::file_watcher::get_global_file_watcher;
 use crate::service::bootstrap::{
     initialize_workspace_persona_files, is_workspace_bootstrap_pending,
 };
+use crate::service::snapshot::{self, WorkspaceSnapshotTrigger};
 use crate::util::errors::{BitFunError, BitFunResult};
 use log::{debug, error, info, warn};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::OnceLock;
@@ -36,6 +42,28 @@ use tokio_util::sync::CancellationToken;
 pub struct SubagentResult {
     /// AI text response
     pub text: String,
+    /// Budget the subagent ran out of; `text` is then its progress summary
+    pub budget_exhausted: Option<TurnBudgetKind>,
+    /// `text` parsed as JSON when a response schema was requested and matched
+    pub structured: Option<serde_json::Value>,
+    /// Why `text` did not match the requested schema (after one retry)
+    pub schema_errors: Vec<String>,
+    /// Reused from an identical earlier run instead of running the subagent
+    pub cached: bool,
+}
+
+/// Optional constraints for `execute_subagent`
+#[derive(Debug, Clone, Default)]
+pub struct SubagentOptions {
+    /// Tighter per-turn limits than the defaults; the subagent wraps up and
+    /// summarizes when one runs out
+    pub budget: Option<TurnBudget>,
+    /// JSON Schema the final response must match; the subagent is asked once
+    /// more when it doesn't
+    pub response_schema: Option<serde_json::Value>,
+    /// Reuse the result of an identical run in the same parent session and
+    /// unchanged workspace for this long; no caching when `None`
+    pub cache_ttl: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -80,21 +108,29 @@ pub enum AssistantBootstrapEnsureOutcome {
 
 const ASSISTANT_BOOTSTRAP_AGENT_TYPE: &str = "Claw";
 
+/// How long an aborted turn's streams, tools and subagents get to stop before
+/// the turn is reported aborted anyway
+const TURN_ABORT_GRACE: Duration = Duration::from_secs(5);
+
 /// Cancel token cleanup guard
 ///
 /// Automatically cleans up cancel tokens in ExecutionEngine when dropped
 struct CancelTokenGuard {
     execution_engine: Arc<ExecutionEngine>,
+    session_id: String,
     dialog_turn_id: String,
 }
 
 impl Drop for CancelTokenGuard {
     fn drop(&mut self) {
         let execution_engine = self.execution_engine.clone();
+        let session_id = self.session_id.clone();
         let dialog_turn_id = self.dialog_turn_id.clone();
 
         tokio::spawn(async move {
-            execution_engine.cleanup_cancel_token(&dialog_turn_id).await;
+            execution_engine
+                .cleanup_cancel_token(&session_id, &dialog_turn_id)
+                .await;
         });
     }
 }
@@ -110,6 +146,8 @@ pub struct ConversationCoordinator {
     scheduler_notify_tx: OnceLock<mpsc::Sender<(String, TurnOutcome)>>,
     /// Round-boundary yield (same source as scheduler's yield flags); injected after construction
     round_preempt_source: OnceLock<Arc<dyn DialogRoundPreemptSource>>,
+    /// Results of subagent runs whose callers opted into caching
+    subagent_cache: SubagentResultCache,
 }
 
 impl ConversationCoordinator {
@@ -243,6 +281,54 @@ impl ConversationCoordinator {
         }
     }
 
+    /// Summarize a completed turn in the background and announce it with
+    /// `TurnSummaryGenerated`; `DialogTurnCompleted` has already gone out
+    fn spawn_turn_summary(
+        session_manager: Arc<SessionManager>,
+        event_queue: Arc<EventQueue>,
+        session_id: String,
+        turn_id: String,
+        user_input: String,
+        messages: Vec<Message>,
+        final_response: String,
+    ) {
+        let Some(session) = session_manager.get_session(&session_id) else {
+            return;
+        };
+        tokio::spawn(async move {
+            if !turn_summary::is_enabled(&session.config).await {
+                return;
+            }
+            let summary =
+                match turn_summary::summarize_turn(&user_input, &messages, &final_response).await {
+                    Ok(Some(summary)) => summary,
+                    Ok(None) => return,
+                    Err(e) => {
+                        debug!("Turn summary generation failed: {e}");
+                        return;
+                    }
+                };
+            if let Err(e) = session_manager
+                .set_turn_summary(&session_id, &turn_id, summary.clone())
+                .await
+            {
+                debug!("Failed to persist turn summary: {e}");
+            }
+            let _ = event_queue
+                .enqueue(
+                    AgenticEvent::TurnSummaryGenerated {
+                        session_id,
+                        turn_id,
+                        did: summary.did,
+                        changed: summary.changed,
+                        next: summary.next,
+                    },
+                    Some(EventPriority::Normal),
+                )
+                .await;
+        });
+    }
+
     fn ensure_user_message_metadata_object(
         metadata: Option<serde_json::Value>,
     ) -> serde_json::Value {
@@ -301,6 +387,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             event_router,
             scheduler_notify_tx: OnceLock::new(),
             round_preempt_source: OnceLock::new(),
+            subagent_cache: SubagentResultCache::default(),
         }
     }
 
@@ -404,6 +491,39 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
         Ok(())
     }
 
+    /// Replace the environment variables of the session's tool processes
+    pub async fn update_session_env(
+        &self,
+        session_id: &str,
+        env: BTreeMap<String, String>,
+    ) -> BitFunResult<()> {
+        self.session_manager
+            .update_session_env(session_id, env)
+            .await?;
+        info!("Coordinator updated session env: session_id={}", session_id);
+        Ok(())
+    }
+
+    /// Turn the AI summaries of the session's completed turns off or back on
+    pub async fn update_session_skip_turn_summary(
+        &self,
+        session_id: &str,
+        skip: bool,
+    ) -> BitFunResult<()> {
+        self.session_manager
+            .update_session_skip_turn_summary(session_id, skip)
+            .await
+    }
+
+    /// Compress the session context before its next model request, even below the threshold
+    pub async fn request_compaction(&self, session_id: &str) -> BitFunResult<()> {
+        self.session_manager
+            .set_manual_compression(session_id, true)
+            .await?;
+        info!("Manual compaction requested: session_id={}", session_id);
+        Ok(())
+    }
+
     /// Create a new session with explicit creator identity.
     pub async fn create_session_with_workspace_and_creator(
         &self,
@@ -879,6 +999,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                     .await?
             }
         };
+        crate::infrastructure::crash_report::set_active_session(Some(&session_id));
 
         let requested_agent_type = agent_type.trim().to_string();
         let provisional_agent_type = if !requested_agent_type.is_empty() {
@@ -1190,10 +1311,14 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             context: context_vars,
             subagent_parent_info: None,
             skip_tool_confirmation: submission_policy.skip_tool_confirmation,
+            require_tool_confirmation: submission_policy.require_tool_confirmation,
             workspace_services,
             round_preempt: self.round_preempt_source.get().cloned(),
+            system_prompt_suffix: None,
         };
 
+        let summary_user_input = original_user_input.clone();
+
         // Auto-generate session title on first message
         if turn_index == 0 && !suppress_session_title_generation {
             let sm = self.session_manager.clone();
@@ -1236,6 +1361,16 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             });
         }
 
+        // Cowork runs and turns whose tools skip confirmation are bracketed by workspace
+        // snapshots, so the desktop can show what changed during the run
+        let run_snapshot_trigger = if effective_agent_type == "Cowork" {
+            Some(WorkspaceSnapshotTrigger::CoworkRun)
+        } else if submission_policy.skip_tool_confirmation {
+            Some(WorkspaceSnapshotTrigger::RiskyTurn)
+        } else {
+            None
+        };
+
         // Start async execution task
         let session_manager = self.session_manager.clone();
         let execution_engine = self.execution_engine.clone();
@@ -1262,6 +1397,18 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                 )
                 .await;
 
+            let run_snapshot_id = match (run_snapshot_trigger, &session_workspace_path) {
+                (Some(trigger), Some(workspace_path)) => {
+                    snapshot::snapshot_workspace_before_run(
+                        Path::new(workspace_path),
+                        &session_id_clone,
+                        trigger,
+                    )
+                    .await
+                }
+                _ => None,
+            };
+
             let workspace_turn_status = match execution_engine
                 .execute_dialog_turn(effective_agent_type_clone, messages, execution_context)
                 .await
@@ -1284,13 +1431,24 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                             final_response.clone(),
                             TurnStats {
                                 total_rounds: execution_result.total_rounds,
-                                total_tools: 0, // TODO: get from execution_result
+                                total_tools: execution_result.total_tools,
                                 total_tokens: 0,
                                 duration_ms: 0,
+                                budget_exhausted: execution_result.budget_exhausted,
                             },
                         )
                         .await;
 
+                    Self::spawn_turn_summary(
+                        session_manager.clone(),
+                        event_queue.clone(),
+                        session_id_clone.clone(),
+                        turn_id_clone.clone(),
+                        summary_user_input,
+                        execution_result.new_messages.clone(),
+                        final_response.clone(),
+                    );
+
                     let _ = session_manager
                         .update_session_state(&session_id_clone, SessionState::Idle)
                         .await;
@@ -1339,12 +1497,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                                 &session_id_clone,
                                 &turn_id_clone,
                                 String::new(),
-                                TurnStats {
-                                    total_rounds: 0,
-                                    total_tools: 0,
-                                    total_tokens: 0,
-                                    duration_ms: 0,
-                                },
+                                TurnStats::default(),
                             )
                             .await;
 
@@ -1366,8 +1519,12 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                         let error_text = e.to_string();
                         error!("Dialog turn execution failed: {}", error_text);
 
-                        let recoverable =
-                            !matches!(&e, BitFunError::AIClient(_) | BitFunError::Timeout(_));
+                        let recoverable = !matches!(
+                            &e,
+                            BitFunError::AIClient(_)
+                                | BitFunError::AIRequest { .. }
+                                | BitFunError::Timeout(_)
+                        );
 
                         let _ = event_queue
                             .enqueue(
@@ -1375,6 +1532,9 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                                     session_id: session_id_clone.clone(),
                                     turn_id: turn_id_clone.clone(),
                                     error: error_text.clone(),
+                                    error_code: Some(e.error_code().to_string()),
+                                    retryable: e.is_retryable(),
+                                    user_message: Some(e.localized_message()),
                                     subagent_parent_info: None,
                                 },
                                 Some(EventPriority::Critical),
@@ -1410,6 +1570,20 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                 }
             };
 
+            if let (Some(before_snapshot_id), Some(workspace_path)) =
+                (run_snapshot_id, session_workspace_path.clone())
+            {
+                let session_id = session_id_clone.clone();
+                tokio::spawn(async move {
+                    snapshot::snapshot_workspace_after_run(
+                        Path::new(&workspace_path),
+                        &session_id,
+                        &before_snapshot_id,
+                    )
+                    .await;
+                });
+            }
+
             if let (Some(ref wp), Some(status)) = (&session_workspace_path, workspace_turn_status) {
                 Self::finalize_turn_in_workspace(
                     &session_id_clone,
@@ -1422,13 +1596,41 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                 )
                 .await;
             }
+
+            Self::close_finished_background_terminals(&session_id_clone).await;
         });
 
         Ok(())
     }
 
+    /// Close the background terminal sessions the turn left behind once their
+    /// command has finished; the persistent shell and still-running commands
+    /// (e.g. dev servers) are kept.
+    async fn close_finished_background_terminals(session_id: &str) {
+        use crate::service::terminal::TerminalApi;
+        let Ok(terminal_api) = TerminalApi::from_singleton() else {
+            return;
+        };
+        let binding = terminal_api.session_manager().binding();
+        match binding.close_finished_background_sessions(session_id).await {
+            Ok(closed) if !closed.is_empty() => debug!(
+                "Closed finished background terminal sessions: session_id={}, terminals={:?}",
+                session_id, closed
+            ),
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Failed to close finished background terminal sessions: session_id={}, error={}",
+                session_id, e
+            ),
+        }
+    }
+
     /// Cancel dialog turn execution
     /// Immediately set state to Idle to allow new dialog, old turn ends naturally via cancel token
+    ///
+    /// Once the turn's model stream, tools, subagents and background commands
+    /// have stopped (or the grace period ran out), `DialogTurnAborted` is sent
+    /// as the turn's last event.
     pub async fn cancel_dialog_turn(
         &self,
         session_id: &str,
@@ -1470,7 +1672,11 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
         // Step 3: Async cleanup of old turn (let it end naturally via cancel token, non-blocking)
         let execution_engine = self.execution_engine.clone();
         let tool_pipeline = self.tool_pipeline.clone();
+        let event_queue = self.event_queue.clone();
+        let session_id_clone = session_id.to_string();
         let dialog_turn_id_clone = dialog_turn_id.to_string();
+        // Taken before cancelling: the scope leaves the registry when the turn ends
+        let scope = turn_scope(session_id, dialog_turn_id);
 
         tokio::spawn(async move {
             debug!(
@@ -1479,7 +1685,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             );
 
             if let Err(e) = execution_engine
-                .cancel_dialog_turn(&dialog_turn_id_clone)
+                .cancel_dialog_turn(&session_id_clone, &dialog_turn_id_clone)
                 .await
             {
                 warn!("Failed to cancel execution engine: {}", e);
@@ -1492,6 +1698,34 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                 warn!("Failed to cancel tool execution: {}", e);
             }
 
+            let unfinished = match scope {
+                Some(scope) => scope.abort(TURN_ABORT_GRACE).await,
+                None => Vec::new(),
+            };
+            if !unfinished.is_empty() {
+                warn!(
+                    "Aborted turn still has work running after {}s: dialog_turn_id={}, running={:?}",
+                    TURN_ABORT_GRACE.as_secs(),
+                    dialog_turn_id_clone,
+                    unfinished
+                );
+            }
+
+            // Chunks still queued would render after the frontend reset the turn
+            event_queue
+                .discard_stream_chunks(&dialog_turn_id_clone)
+                .await;
+            let _ = event_queue
+                .enqueue(
+                    AgenticEvent::DialogTurnAborted {
+                        session_id: session_id_clone,
+                        turn_id: dialog_turn_id_clone.clone(),
+                        unfinished,
+                    },
+                    Some(EventPriority::Critical),
+                )
+                .await;
+
             debug!("Async cleanup completed: {}", dialog_turn_id_clone);
         });
 
@@ -1517,7 +1751,10 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
         self.cancel_dialog_turn(session_id, &current_turn_id).await?;
 
         let deadline = Instant::now() + wait_timeout;
-        while self.execution_engine.has_active_turn(&current_turn_id) {
+        while self
+            .execution_engine
+            .has_active_turn(session_id, &current_turn_id)
+        {
             if Instant::now() >= deadline {
                 warn!(
                     "Timed out waiting for active turn cancellation: session_id={}, dialog_turn_id={}, timeout_ms={}",
@@ -1585,6 +1822,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
     /// Subscribe to internal events
     ///
     /// For internal systems to subscribe to events (e.g., logging, monitoring)
+    #[track_caller]
     pub fn subscribe_internal<H>(&self, subscriber_id: String, handler: H)
     where
         H: EventSubscriber + 'static,
@@ -1603,22 +1841,42 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
     /// Confirm tool execution
     pub async fn confirm_tool(
         &self,
+        session_id: &str,
         tool_id: &str,
         updated_input: Option<serde_json::Value>,
     ) -> BitFunResult<()> {
         self.tool_pipeline
-            .confirm_tool(tool_id, updated_input)
+            .confirm_tool(session_id, tool_id, updated_input)
             .await
     }
 
     /// Reject tool execution
-    pub async fn reject_tool(&self, tool_id: &str, reason: String) -> BitFunResult<()> {
-        self.tool_pipeline.reject_tool(tool_id, reason).await
+    pub async fn reject_tool(
+        &self,
+        session_id: &str,
+        tool_id: &str,
+        reason: String,
+    ) -> BitFunResult<()> {
+        self.tool_pipeline
+            .reject_tool(session_id, tool_id, reason)
+            .await
+    }
+
+    /// Approve or reject a turn waiting on its per-turn spend threshold
+    pub fn confirm_turn_spend(&self, turn_id: &str, approved: bool) -> BitFunResult<()> {
+        self.execution_engine.confirm_turn_spend(turn_id, approved)
     }
 
     /// Cancel tool execution
-    pub async fn cancel_tool(&self, tool_id: &str, reason: String) -> BitFunResult<()> {
-        self.tool_pipeline.cancel_tool(tool_id, reason).await
+    pub async fn cancel_tool(
+        &self,
+        session_id: &str,
+        tool_id: &str,
+        reason: String,
+    ) -> BitFunResult<()> {
+        self.tool_pipeline
+            .cancel_tool(session_id, tool_id, reason)
+            .await
     }
 
     /// Execute subagent task directly
@@ -1630,8 +1888,10 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
     /// - subagent_parent_info: Parent info (tool call context)
     /// - context: Additional context
     /// - cancel_token: Optional cancel token (for async cancellation)
+    /// - options: Turn budget, required response schema and result caching
     ///
     /// Returns SubagentResult with the final text response
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute_subagent(
         &self,
         agent_type: String,
@@ -1640,6 +1900,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
         workspace_path: Option<String>,
         context: Option<std::collections::HashMap<String, String>>,
         cancel_token: Option<&CancellationToken>,
+        options: SubagentOptions,
     ) -> BitFunResult<SubagentResult> {
         // Check cancel token (before creating session)
         if let Some(token) = cancel_token {
@@ -1660,8 +1921,64 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
                 "workspace_path is required when creating a subagent session".to_string(),
             )
         })?;
+
+        // Without a watched file tree there is no way to tell whether the
+        // workspace changed, so such runs are not cached
+        let cache_key = match options.cache_ttl {
+            Some(_) => get_global_file_watcher()
+                .tree_version(Path::new(&workspace_path))
+                .await
+                .map(|version| {
+                    SubagentCacheKey::new(
+                        &subagent_parent_info.session_id,
+                        &agent_type,
+                        &task_description,
+                        context.as_ref(),
+                        &options,
+                        &workspace_path,
+                        version,
+                    )
+                }),
+            None => None,
+        };
+        if let (Some(key), Some(ttl)) = (&cache_key, options.cache_ttl) {
+            if let Some((mut cached, age)) = self.subagent_cache.get(key, ttl) {
+                debug!(
+                    "Reusing cached subagent result: agent_type={}, parent_session={}, age={}s",
+                    agent_type,
+                    subagent_parent_info.session_id,
+                    age.as_secs()
+                );
+                cached.cached = true;
+                let _ = self
+                    .event_queue
+                    .enqueue(
+                        AgenticEvent::SubagentResultCached {
+                            session_id: subagent_parent_info.session_id.clone(),
+                            turn_id: subagent_parent_info.dialog_turn_id.clone(),
+                            tool_call_id: subagent_parent_info.tool_call_id.clone(),
+                            subagent_type: agent_type,
+                            age_secs: age.as_secs(),
+                        },
+                        Some(EventPriority::Normal),
+                    )
+                    .await;
+                return Ok(cached);
+            }
+        }
+
         let mut subagent_config = SessionConfig::default();
         subagent_config.workspace_path = Some(workspace_path);
+        // Subagent commands run with the parent session's environment
+        if let Some(parent) = self
+            .session_manager
+            .get_session(&subagent_parent_info.session_id)
+        {
+            subagent_config.env = parent.config.env;
+        }
+        if let Some(budget) = options.budget {
+            subagent_config.restrict_turn_budget(budget);
+        }
         let session = self
             .create_subagent_session(
                 format!("Subagent: {}", task_description),
@@ -1696,8 +2013,11 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             let child_token = parent_token.child_token();
 
             // Register to ExecutionEngine (forwarded to RoundExecutor), using dialog_turn_id as key
-            self.execution_engine
-                .register_cancel_token(&dialog_turn_id, child_token.clone());
+            self.execution_engine.register_cancel_token(
+                &session.session_id,
+                &dialog_turn_id,
+                child_token.clone(),
+            );
 
             debug!(
                 "Registered cancel token to RoundExecutor: dialog_turn_id={}",
@@ -1707,6 +2027,7 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             // Create cleanup guard to ensure token cleanup on function exit
             Some(CancelTokenGuard {
                 execution_engine: self.execution_engine.clone(),
+                session_id: session.session_id.clone(),
                 dialog_turn_id: dialog_turn_id.clone(),
             })
         } else {
@@ -1724,26 +2045,34 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             context: context.unwrap_or_default(),
             subagent_parent_info: Some(subagent_parent_info),
             skip_tool_confirmation: false,
+            require_tool_confirmation: false,
             workspace_services: subagent_services,
             round_preempt: self.round_preempt_source.get().cloned(),
+            system_prompt_suffix: options
+                .response_schema
+                .as_ref()
+                .map(structured_response::response_format_instructions),
         };
 
-        let initial_messages = vec![Message::user(task_description)];
-
         let result = self
-            .execution_engine
-            .execute_dialog_turn(agent_type, initial_messages, execution_context)
+            .run_subagent_turn(
+                agent_type,
+                task_description,
+                execution_context,
+                cancel_token,
+                options.response_schema.as_ref(),
+            )
             .await;
 
         // cleanup_guard automatically cleans up token on scope exit (via Drop trait)
 
-        // Extract text response
-        let response_text = match result {
-            Ok(exec_result) => match exec_result.final_message.content {
-                MessageContent::Mixed { text, .. } => text,
-                MessageContent::Text(text) => text,
-                _ => String::new(),
-            },
+        let subagent_result = match result {
+            Ok(subagent_result) => {
+                if let Some(key) = cache_key {
+                    self.subagent_cache.insert(key, &subagent_result);
+                }
+                subagent_result
+            }
             Err(e) => {
                 error!(
                     "Subagent execution failed: session={}, error={}",
@@ -1779,9 +2108,87 @@ Update the persona files and delete BOOTSTRAP.md as soon as bootstrap is complet
             );
         }
 
-        Ok(SubagentResult {
-            text: response_text,
-        })
+        Ok(subagent_result)
+    }
+
+    /// Runs the subagent's dialog turn; with a response schema, a response that
+    /// doesn't match is sent back once for correction.
+    async fn run_subagent_turn(
+        &self,
+        agent_type: String,
+        task_description: String,
+        execution_context: ExecutionContext,
+        cancel_token: Option<&CancellationToken>,
+        response_schema: Option<&serde_json::Value>,
+    ) -> BitFunResult<SubagentResult> {
+        fn response_text(message: &Message) -> String {
+            match &message.content {
+                MessageContent::Mixed { text, .. } | MessageContent::Text(text) => text.clone(),
+                _ => String::new(),
+            }
+        }
+
+        let exec_result = self
+            .execution_engine
+            .execute_dialog_turn(
+                agent_type.clone(),
+                vec![Message::user(task_description.clone())],
+                execution_context.clone(),
+            )
+            .await?;
+        let mut result = SubagentResult {
+            text: response_text(&exec_result.final_message),
+            budget_exhausted: exec_result.budget_exhausted,
+            structured: None,
+            schema_errors: Vec::new(),
+            cached: false,
+        };
+        let Some(schema) = response_schema else {
+            return Ok(result);
+        };
+
+        let errors = match structured_response::parse_structured_response(&result.text, schema) {
+            Ok(value) => {
+                result.structured = Some(value);
+                return Ok(result);
+            }
+            Err(errors) => errors,
+        };
+        debug!(
+            "Subagent response does not match schema, asking again: session={}, errors={:?}",
+            execution_context.session_id, errors
+        );
+
+        // The finished turn dropped its cancel token; link the retry to the caller's again
+        if let Some(parent_token) = cancel_token {
+            self.execution_engine.register_cancel_token(
+                &execution_context.session_id,
+                &execution_context.dialog_turn_id,
+                parent_token.child_token(),
+            );
+        }
+        let retry_messages = vec![
+            Message::user(task_description),
+            Message::assistant(result.text.clone()),
+            Message::user(structured_response::correction_prompt(&errors)),
+        ];
+        let exec_result = self
+            .execution_engine
+            .execute_dialog_turn(agent_type, retry_messages, execution_context)
+            .await?;
+        result.text = response_text(&exec_result.final_message);
+        result.budget_exhausted = exec_result.budget_exhausted;
+        match structured_response::parse_structured_response(&result.text, schema) {
+            Ok(value) => result.structured = Some(value),
+            Err(errors) => {
+                warn!(
+                    "Subagent response still does not match schema after retry: errors={:?}",
+                    errors
+                );
+                result.schema_errors = errors;
+            }
+        }
+        Ok(result)
     }
 
     /// Clean up subagent session resources
@@ -5,6 +5,8 @@
 pub mod coordinator;
 pub mod scheduler;
 pub mod state_manager;
+pub mod structured_response;
+pub mod subagent_cache;
 pub mod turn_outcome;
 
 pub use coordinator::*;
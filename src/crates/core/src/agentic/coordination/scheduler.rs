@@ -16,9 +16,8 @@ use crate::agentic::core::{PromptEnvelope, SessionState};
 use crate::agentic::image_analysis::ImageContextData;
 use crate::agentic::round_preempt::{DialogRoundPreemptSource, SessionRoundYieldFlags};
 use crate::agentic::session::SessionManager;
-use dashmap::DashMap;
+use crate::agentic::session_runtime::{existing_session_runtime, session_runtime};
 use log::{debug, info, warn};
-use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::OnceLock;
 use std::time::SystemTime;
@@ -31,14 +30,8 @@ const MAX_QUEUE_DEPTH: usize = 20;
 /// or was placed in the per-session queue.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DialogSubmitOutcome {
-    Started {
-        session_id: String,
-        turn_id: String,
-    },
-    Queued {
-        session_id: String,
-        turn_id: String,
-    },
+    Started { session_id: String, turn_id: String },
+    Queued { session_id: String, turn_id: String },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -53,6 +46,9 @@ pub struct DialogSubmissionPolicy {
     pub trigger_source: DialogTriggerSource,
     pub queue_priority: DialogQueuePriority,
     pub skip_tool_confirmation: bool,
+    /// Ask before tools that need permission even when the user config skips confirmation,
+    /// so the submitter can answer (e.g. a CLI `--allow`/`--deny` policy)
+    pub require_tool_confirmation: bool,
 }
 
 impl DialogSubmissionPolicy {
@@ -65,6 +61,7 @@ impl DialogSubmissionPolicy {
             trigger_source,
             queue_priority,
             skip_tool_confirmation,
+            require_tool_confirmation: false,
         }
     }
 
@@ -91,6 +88,11 @@ impl DialogSubmissionPolicy {
         self.skip_tool_confirmation = skip_tool_confirmation;
         self
     }
+
+    pub const fn with_require_tool_confirmation(mut self, require_tool_confirmation: bool) -> Self {
+        self.require_tool_confirmation = require_tool_confirmation;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,8 +101,9 @@ pub struct AgentSessionReplyRoute {
     pub source_workspace_path: String,
 }
 
+/// Turn dispatched by the scheduler, kept until its outcome arrives
 #[derive(Debug, Clone)]
-struct ActiveTurn {
+pub(crate) struct ActiveTurn {
     workspace_path: Option<String>,
     policy: DialogSubmissionPolicy,
     reply_route: Option<AgentSessionReplyRoute>,
@@ -141,13 +144,12 @@ pub struct QueuedTurn {
 /// All user-facing callers (frontend Tauri commands, remote server, bot router)
 /// should submit messages through this scheduler instead of calling
 /// ConversationCoordinator directly.
+///
+/// Queued messages and the dispatched turn are kept in each session's
+/// [`SessionRuntime`](crate::agentic::session_runtime::SessionRuntime).
 pub struct DialogScheduler {
     coordinator: Arc<ConversationCoordinator>,
     session_manager: Arc<SessionManager>,
-    /// Per-session priority message queues
-    queues: Arc<DashMap<String, VecDeque<QueuedTurn>>>,
-    /// Currently active turn metadata keyed by target session ID
-    active_turns: Arc<DashMap<String, ActiveTurn>>,
     /// Cloneable sender given to ConversationCoordinator for turn outcome notifications
     outcome_tx: mpsc::Sender<(String, TurnOutcome)>,
     /// When a user submits while `Processing`, engine yields after the current model round.
@@ -169,10 +171,8 @@ impl DialogScheduler {
         let scheduler = Arc::new(Self {
             coordinator,
             session_manager,
-            queues: Arc::new(DashMap::new()),
-            active_turns: Arc::new(DashMap::new()),
             outcome_tx,
-            round_yield_flags: Arc::new(SessionRoundYieldFlags::default()),
+            round_yield_flags: Arc::new(SessionRoundYieldFlags),
         });
 
         let scheduler_for_handler = Arc::clone(&scheduler);
@@ -262,11 +262,7 @@ impl DialogScheduler {
             }
 
             Some(SessionState::Idle) => {
-                let queue_non_empty = self
-                    .queues
-                    .get(&session_id)
-                    .map(|q| !q.is_empty())
-                    .unwrap_or(false);
+                let queue_non_empty = self.queue_depth(&session_id) > 0;
 
                 if queue_non_empty {
                     self.enqueue(&session_id, queued_turn.clone())?;
@@ -307,15 +303,18 @@ impl DialogScheduler {
 
     /// Number of messages currently queued for a session.
     pub fn queue_depth(&self, session_id: &str) -> usize {
-        self.queues.get(session_id).map(|q| q.len()).unwrap_or(0)
+        existing_session_runtime(session_id)
+            .map(|runtime| runtime.queued_turns().len())
+            .unwrap_or(0)
     }
 
     // ── Private helpers ──────────────────────────────────────────────────────
 
     fn enqueue(&self, session_id: &str, queued_turn: QueuedTurn) -> Result<(), String> {
-        let queue_len = self.queues.get(session_id).map(|q| q.len()).unwrap_or(0);
+        let runtime = session_runtime(session_id);
+        let mut queue = runtime.queued_turns();
 
-        if queue_len >= MAX_QUEUE_DEPTH {
+        if queue.len() >= MAX_QUEUE_DEPTH {
             warn!(
                 "Queue full, rejecting message: session_id={}, max={}",
                 session_id, MAX_QUEUE_DEPTH
@@ -326,55 +325,47 @@ impl DialogScheduler {
             ));
         }
 
-        self.queues
-            .entry(session_id.to_string())
-            .or_default()
-            .push_back(queued_turn.clone());
-        if let Some(mut queue) = self.queues.get_mut(session_id) {
-            if let Some(reordered_turn) = queue.pop_back() {
-                let insert_at = queue.iter().position(|existing| {
-                    existing.policy.queue_priority < reordered_turn.policy.queue_priority
-                });
-                if let Some(index) = insert_at {
-                    queue.insert(index, reordered_turn);
-                } else {
-                    queue.push_back(reordered_turn);
-                }
-            }
+        let priority = queued_turn.policy.queue_priority;
+        let insert_at = queue
+            .iter()
+            .position(|existing| existing.policy.queue_priority < priority);
+        match insert_at {
+            Some(index) => queue.insert(index, queued_turn),
+            None => queue.push_back(queued_turn),
         }
 
-        let new_len = self.queues.get(session_id).map(|q| q.len()).unwrap_or(0);
         debug!(
             "Message queued: session_id={}, queue_depth={}, priority={:?}",
-            session_id, new_len, queued_turn.policy.queue_priority
+            session_id,
+            queue.len(),
+            priority
         );
         Ok(())
     }
 
     fn clear_queue(&self, session_id: &str) {
-        if let Some(mut queue) = self.queues.get_mut(session_id) {
-            let count = queue.len();
-            queue.clear();
-            if count > 0 {
-                info!(
-                    "Cleared {} queued messages: session_id={}",
-                    count, session_id
-                );
-            }
+        let Some(runtime) = existing_session_runtime(session_id) else {
+            return;
+        };
+        let mut queue = runtime.queued_turns();
+        let count = queue.len();
+        queue.clear();
+        if count > 0 {
+            info!(
+                "Cleared {} queued messages: session_id={}",
+                count, session_id
+            );
         }
     }
 
     fn dequeue_next(&self, session_id: &str) -> Option<QueuedTurn> {
-        self.queues
-            .get_mut(session_id)
-            .and_then(|mut q| q.pop_front())
+        existing_session_runtime(session_id)?
+            .queued_turns()
+            .pop_front()
     }
 
     fn requeue_front(&self, session_id: &str, turn: QueuedTurn) {
-        self.queues
-            .entry(session_id.to_string())
-            .or_default()
-            .push_front(turn);
+        session_runtime(session_id).queued_turns().push_front(turn);
     }
 
     async fn try_start_next_queued(&self, session_id: &str) -> Result<Option<String>, String> {
@@ -390,7 +381,7 @@ impl DialogScheduler {
             return Ok(None);
         };
 
-        let remaining = self.queues.get(session_id).map(|q| q.len()).unwrap_or(0);
+        let remaining = self.queue_depth(session_id);
         info!(
             "Dispatching queued message: session_id={}, priority={:?}, remaining_queue_depth={}",
             session_id, next_turn.policy.queue_priority, remaining
@@ -405,7 +396,11 @@ impl DialogScheduler {
         }
     }
 
-    async fn start_turn(&self, session_id: &str, queued_turn: &QueuedTurn) -> Result<String, String> {
+    async fn start_turn(
+        &self,
+        session_id: &str,
+        queued_turn: &QueuedTurn,
+    ) -> Result<String, String> {
         let res = match queued_turn
             .image_contexts
             .as_ref()
@@ -442,10 +437,7 @@ impl DialogScheduler {
 
         res.map_err(|e| e.to_string())?;
 
-        self.active_turns.insert(
-            session_id.to_string(),
-            ActiveTurn::from_queued_turn(queued_turn),
-        );
+        session_runtime(session_id).set_dispatched_turn(ActiveTurn::from_queued_turn(queued_turn));
 
         let resolved = self
             .session_manager
@@ -537,7 +529,8 @@ Status: {status}"
         while let Some((session_id, outcome)) = outcome_rx.recv().await {
             self.round_yield_flags.clear(&session_id);
 
-            let active_turn = self.active_turns.remove(&session_id).map(|(_, turn)| turn);
+            let active_turn = existing_session_runtime(&session_id)
+                .and_then(|runtime| runtime.take_dispatched_turn());
             if let Some(active_turn) = active_turn.as_ref() {
                 self.forward_agent_session_reply(&session_id, active_turn, &outcome)
                     .await;
@@ -0,0 +1,81 @@
+//! Structured subagent responses
+//!
+//! Prompt text asking a subagent to answer with JSON matching a schema, and
+//! parsing/validation of the answer.
+
+use crate::util::{extract_json_from_ai_response, validate_json_schema};
+use serde_json::Value;
+
+/// System prompt section requiring the final response to match `schema`.
+pub fn response_format_instructions(schema: &Value) -> String {
+    format!(
+        "\n\n# Response format\nYour final response must be a single JSON value matching the JSON Schema below, with no other text and no code fences. Use tools as usual while working; only the final response has to follow this format.\n\n{}\n",
+        serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+    )
+}
+
+/// Follow-up message after a response that did not match the schema.
+pub fn correction_prompt(errors: &[String]) -> String {
+    format!(
+        "Your final response did not match the required JSON Schema:\n{}\n\nRespond again with only the corrected JSON value.",
+        errors
+            .iter()
+            .map(|error| format!("- {}", error))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+/// Parses `text` as JSON (tolerating code fences and surrounding prose) and
+/// validates it against `schema`.
+pub fn parse_structured_response(text: &str, schema: &Value) -> Result<Value, Vec<String>> {
+    let trimmed = text.trim();
+    let value = serde_json::from_str::<Value>(trimmed)
+        .ok()
+        .or_else(|| {
+            extract_json_from_ai_response(trimmed).and_then(|json| serde_json::from_str(&json).ok())
+        })
+        .ok_or_else(|| vec!["the response is not valid JSON".to_string()])?;
+
+    let errors = validate_json_schema(schema, &value);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["files"],
+            "properties": { "files": { "type": "array", "items": { "type": "string" } } }
+        })
+    }
+
+    #[test]
+    fn accepts_fenced_json_with_prose() {
+        let text = "Here is the result:\n```json\n{\"files\": [\"src/lib.rs\"]}\n```";
+        assert_eq!(
+            parse_structured_response(text, &schema()),
+            Ok(json!({ "files": ["src/lib.rs"] }))
+        );
+    }
+
+    #[test]
+    fn rejects_prose_and_schema_mismatches() {
+        assert_eq!(
+            parse_structured_response("I found two files.", &schema()),
+            Err(vec!["the response is not valid JSON".to_string()])
+        );
+        let errors =
+            parse_structured_response("{\"files\": \"src/lib.rs\"}", &schema()).unwrap_err();
+        assert_eq!(errors, ["$.files: expected array, got string"]);
+        assert!(correction_prompt(&errors).contains("- $.files: expected array"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Subagent result cache
+//!
+//! Callers that opt in get the result of an identical earlier subagent run in
+//! the same parent session instead of running the subagent again (retried
+//! plans, repeated research prompts). Entries are keyed by the version of the
+//! workspace's cached file tree, so any file change in the workspace makes
+//! earlier results unreachable, and they expire after the caller's TTL.
+
+use super::coordinator::{SubagentOptions, SubagentResult};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Results kept at most; the oldest are dropped first.
+const MAX_ENTRIES: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubagentCacheKey {
+    pub parent_session_id: String,
+    pub subagent_type: String,
+    /// Hash of the task, its context and the options that shape the answer
+    pub prompt_hash: u64,
+    pub workspace_path: String,
+    /// File tree version of the workspace when the result was produced
+    pub workspace_version: u64,
+}
+
+impl SubagentCacheKey {
+    pub fn new(
+        parent_session_id: &str,
+        subagent_type: &str,
+        task_description: &str,
+        context: Option<&HashMap<String, String>>,
+        options: &SubagentOptions,
+        workspace_path: &str,
+        workspace_version: u64,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        task_description.hash(&mut hasher);
+        if let Some(context) = context {
+            let mut entries: Vec<_> = context.iter().collect();
+            entries.sort();
+            entries.hash(&mut hasher);
+        }
+        if let Some(budget) = options.budget {
+            budget.max_rounds.hash(&mut hasher);
+            budget.max_tool_calls.hash(&mut hasher);
+            budget.max_duration_secs.hash(&mut hasher);
+        }
+        if let Some(schema) = &options.response_schema {
+            schema.to_string().hash(&mut hasher);
+        }
+        Self {
+            parent_session_id: parent_session_id.to_string(),
+            subagent_type: subagent_type.to_string(),
+            prompt_hash: hasher.finish(),
+            workspace_path: workspace_path.to_string(),
+            workspace_version,
+        }
+    }
+}
+
+/// Finished subagent results by key.
+#[derive(Default)]
+pub struct SubagentResultCache {
+    entries: Mutex<HashMap<SubagentCacheKey, (SubagentResult, Instant)>>,
+}
+
+impl SubagentResultCache {
+    /// The cached result for `key` and its age, unless it is older than `ttl`.
+    pub fn get(&self, key: &SubagentCacheKey, ttl: Duration) -> Option<(SubagentResult, Duration)> {
+        self.get_at(key, ttl, Instant::now())
+    }
+
+    /// Remembers a result; incomplete results (budget ran out, schema not
+    /// matched) are not worth repeating and are skipped.
+    pub fn insert(&self, key: SubagentCacheKey, result: &SubagentResult) {
+        self.insert_at(key, result, Instant::now());
+    }
+
+    fn get_at(
+        &self,
+        key: &SubagentCacheKey,
+        ttl: Duration,
+        now: Instant,
+    ) -> Option<(SubagentResult, Duration)> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let (result, stored_at) = entries.get(key)?;
+        let age = now.saturating_duration_since(*stored_at);
+        (age <= ttl).then(|| (result.clone(), age))
+    }
+
+    fn insert_at(&self, key: SubagentCacheKey, result: &SubagentResult, now: Instant) {
+        if result.budget_exhausted.is_some() || !result.schema_errors.is_empty() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // Entries of older workspace versions can never be hit again
+        entries.retain(|existing, _| {
+            existing.workspace_path != key.workspace_path
+                || existing.workspace_version == key.workspace_version
+        });
+        while entries.len() >= MAX_ENTRIES {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (_, stored_at))| *stored_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+        entries.insert(key, (result.clone(), now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agentic::core::TurnBudgetKind;
+
+    fn result(text: &str) -> SubagentResult {
+        SubagentResult {
+            text: text.to_string(),
+            budget_exhausted: None,
+            structured: None,
+            schema_errors: Vec::new(),
+            cached: false,
+        }
+    }
+
+    fn key(prompt: &str, version: u64) -> SubagentCacheKey {
+        SubagentCacheKey::new(
+            "session-1",
+            "Explore",
+            prompt,
+            None,
+            &SubagentOptions::default(),
+            "/ws",
+            version,
+        )
+    }
+
+    #[test]
+    fn hits_until_ttl_expires() {
+        let cache = SubagentResultCache::default();
+        let start = Instant::now();
+        cache.insert_at(key("find the parser", 3), &result("src/parser.rs"), start);
+
+        let ttl = Duration::from_secs(60);
+        let (hit, age) = cache
+            .get_at(
+                &key("find the parser", 3),
+                ttl,
+                start + Duration::from_secs(10),
+            )
+            .unwrap();
+        assert_eq!(hit.text, "src/parser.rs");
+        assert_eq!(age, Duration::from_secs(10));
+        assert!(cache
+            .get_at(
+                &key("find the parser", 3),
+                ttl,
+                start + Duration::from_secs(61)
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn workspace_changes_and_other_prompts_miss() {
+        let cache = SubagentResultCache::default();
+        let now = Instant::now();
+        let ttl = Duration::from_secs(60);
+        cache.insert_at(key("find the parser", 3), &result("src/parser.rs"), now);
+
+        assert!(cache.get_at(&key("find the parser", 4), ttl, now).is_none());
+        assert!(cache.get_at(&key("find the lexer", 3), ttl, now).is_none());
+
+        let schema_options = SubagentOptions {
+            response_schema: Some(serde_json::json!({ "type": "array" })),
+            ..SubagentOptions::default()
+        };
+        let with_schema = SubagentCacheKey::new(
+            "session-1",
+            "Explore",
+            "find the parser",
+            None,
+            &schema_options,
+            "/ws",
+            3,
+        );
+        assert!(cache.get_at(&with_schema, ttl, now).is_none());
+
+        // A newer version drops the stale entries
+        cache.insert_at(key("find the lexer", 4), &result("src/lexer.rs"), now);
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn incomplete_results_are_not_cached() {
+        let cache = SubagentResultCache::default();
+        let now = Instant::now();
+        let mut partial = result("got halfway");
+        partial.budget_exhausted = Some(TurnBudgetKind::ToolCalls);
+        cache.insert_at(key("find the parser", 3), &partial, now);
+        assert!(cache
+            .get_at(&key("find the parser", 3), Duration::from_secs(60), now)
+            .is_none());
+    }
+}
@@ -76,4 +76,158 @@ pub struct TurnStats {
     pub total_tools: usize,
     pub total_tokens: usize,
     pub duration_ms: u64,
+    /// Budget that ended the turn early
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_exhausted: Option<TurnBudgetKind>,
+}
+
+// ============ Turn Budget ============
+
+/// Limits on a single dialog turn; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnBudget {
+    pub max_rounds: Option<usize>,
+    pub max_tool_calls: Option<usize>,
+    pub max_duration_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnBudgetKind {
+    Rounds,
+    ToolCalls,
+    Duration,
+}
+
+impl TurnBudgetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rounds => "rounds",
+            Self::ToolCalls => "tool_calls",
+            Self::Duration => "duration",
+        }
+    }
+
+    /// Human-readable name for prompts
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Rounds => "model round",
+            Self::ToolCalls => "tool call",
+            Self::Duration => "time",
+        }
+    }
+}
+
+/// How much of one budget a turn has used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BudgetUsage {
+    pub kind: TurnBudgetKind,
+    /// In rounds, tool calls or seconds
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl BudgetUsage {
+    pub fn fraction(&self) -> f64 {
+        if self.limit == 0 {
+            1.0
+        } else {
+            self.used as f64 / self.limit as f64
+        }
+    }
+}
+
+impl TurnBudget {
+    /// The tighter of each limit
+    pub fn min(self, other: TurnBudget) -> Self {
+        fn tighter<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            max_rounds: tighter(self.max_rounds, other.max_rounds),
+            max_tool_calls: tighter(self.max_tool_calls, other.max_tool_calls),
+            max_duration_secs: tighter(self.max_duration_secs, other.max_duration_secs),
+        }
+    }
+
+    /// The most consumed budget, if any limit is set
+    pub fn usage(&self, stats: &TurnStats) -> Option<BudgetUsage> {
+        [
+            self.max_rounds.map(|limit| BudgetUsage {
+                kind: TurnBudgetKind::Rounds,
+                used: stats.total_rounds as u64,
+                limit: limit as u64,
+            }),
+            self.max_tool_calls.map(|limit| BudgetUsage {
+                kind: TurnBudgetKind::ToolCalls,
+                used: stats.total_tools as u64,
+                limit: limit as u64,
+            }),
+            self.max_duration_secs.map(|limit| BudgetUsage {
+                kind: TurnBudgetKind::Duration,
+                used: stats.duration_ms / 1000,
+                limit,
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .max_by(|a, b| a.fraction().total_cmp(&b.fraction()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(total_rounds: usize, total_tools: usize, duration_ms: u64) -> TurnStats {
+        TurnStats {
+            total_rounds,
+            total_tools,
+            duration_ms,
+            ..TurnStats::default()
+        }
+    }
+
+    #[test]
+    fn reports_the_most_consumed_budget() {
+        let budget = TurnBudget {
+            max_rounds: Some(10),
+            max_tool_calls: Some(40),
+            max_duration_secs: Some(600),
+        };
+        let usage = budget.usage(&stats(2, 36, 60_000)).unwrap();
+        assert_eq!(usage.kind, TurnBudgetKind::ToolCalls);
+        assert!((usage.fraction() - 0.9).abs() < f64::EPSILON);
+
+        let usage = budget.usage(&stats(2, 4, 660_000)).unwrap();
+        assert_eq!(usage.kind, TurnBudgetKind::Duration);
+        assert!(usage.fraction() > 1.0);
+
+        assert!(TurnBudget::default().usage(&stats(100, 100, 0)).is_none());
+    }
+
+    #[test]
+    fn min_keeps_the_tighter_limit() {
+        let session = TurnBudget {
+            max_rounds: Some(200),
+            max_tool_calls: None,
+            max_duration_secs: Some(600),
+        };
+        let task = TurnBudget {
+            max_rounds: None,
+            max_tool_calls: Some(20),
+            max_duration_secs: Some(1200),
+        };
+        assert_eq!(
+            session.min(task),
+            TurnBudget {
+                max_rounds: Some(200),
+                max_tool_calls: Some(20),
+                max_duration_secs: Some(600),
+            }
+        );
+    }
 }
@@ -2,6 +2,7 @@ use super::prompt_markup::is_system_reminder_only;
 use crate::agentic::image_analysis::ImageContextData;
 use crate::util::types::{Message as AIMessage, ToolCall as AIToolCall, ToolImageAttachment};
 use crate::util::TokenCounter;
+use ai_stream_handlers::UnifiedServerToolEvent;
 use log::warn;
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
@@ -60,6 +61,9 @@ pub struct MessageMetadata {
     /// Anthropic extended thinking signature (for passing back in multi-turn conversations)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thinking_signature: Option<String>,
+    /// Web searches the provider ran itself for this message, with the citations of the answer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server_tool_events: Vec<UnifiedServerToolEvent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_kind: Option<MessageSemanticKind>,
 }
@@ -86,6 +90,8 @@ impl From<Message> for AIMessage {
         };
         let keep_thinking = msg.metadata.keep_thinking;
         let thinking_signature = msg.metadata.thinking_signature.clone();
+        let server_tool_events =
+            Some(msg.metadata.server_tool_events.clone()).filter(|events| !events.is_empty());
 
         match msg.content {
             MessageContent::Text(text) => {
@@ -113,6 +119,7 @@ impl From<Message> for AIMessage {
                     tool_call_id: None,
                     name: None,
                     tool_image_attachments: None,
+                    server_tool_events: None,
                 }
             }
             MessageContent::Multimodal { text, images } => {
@@ -146,6 +153,7 @@ impl From<Message> for AIMessage {
                     tool_call_id: None,
                     name: None,
                     tool_image_attachments: None,
+                    server_tool_events: None,
                 }
             }
             MessageContent::Mixed {
@@ -202,6 +210,7 @@ impl From<Message> for AIMessage {
                     tool_call_id: None,
                     name: None,
                     tool_image_attachments: None,
+                    server_tool_events,
                 }
             }
             MessageContent::ToolResult {
@@ -238,6 +247,7 @@ impl From<Message> for AIMessage {
                     tool_call_id: Some(tool_id),
                     name: Some(tool_name),
                     tool_image_attachments: image_attachments.clone(),
+                    server_tool_events: None,
                 }
             }
         }
@@ -384,6 +394,12 @@ impl Message {
         self
     }
 
+    /// Set the server-side tool calls made while producing the message
+    pub fn with_server_tool_events(mut self, events: Vec<UnifiedServerToolEvent>) -> Self {
+        self.metadata.server_tool_events = events;
+        self
+    }
+
     /// Get message's token count
     pub fn get_tokens(&mut self) -> usize {
         if let Some(tokens) = self.metadata.tokens {
@@ -531,6 +547,12 @@ pub struct ToolCall {
     pub arguments: serde_json::Value,
     /// Record whether tool parameters are valid
     pub is_error: bool,
+    /// Arguments only parsed after lenient repair of malformed JSON
+    #[serde(default)]
+    pub repaired: bool,
+    /// Why the arguments could not be parsed (set when `is_error`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_error: Option<String>,
 }
 
 impl ToolCall {
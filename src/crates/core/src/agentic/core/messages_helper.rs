@@ -72,6 +72,28 @@ impl MessageHelper {
         messages.iter().map(|m| AIMessage::from(m)).collect()
     }
 
+    /// Drop reasoning the provider would reject when it is replayed on later rounds.
+    ///
+    /// Anthropic only accepts thinking blocks with their original signature (and requires them
+    /// for tool use within a turn), so signed reasoning is kept and unsigned reasoning, e.g.
+    /// produced by another provider earlier in the session, is stripped. Other formats replay
+    /// reasoning as plain text and are left unchanged.
+    pub fn apply_reasoning_replay_rules(messages: &mut [AIMessage], provider: &str) {
+        if !provider.eq_ignore_ascii_case("anthropic") {
+            return;
+        }
+        for msg in messages.iter_mut().filter(|m| m.role == "assistant") {
+            let signed = msg
+                .thinking_signature
+                .as_deref()
+                .is_some_and(|signature| !signature.is_empty());
+            if !signed {
+                msg.reasoning_content = None;
+                msg.thinking_signature = None;
+            }
+        }
+    }
+
     pub fn group_messages_by_turns(mut messages: Vec<Message>) -> Vec<Vec<Message>> {
         let mut turns = Vec::new();
         if messages.is_empty() {
@@ -158,3 +180,48 @@ impl MessageHelper {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MessageHelper;
+    use crate::util::types::Message as AIMessage;
+
+    fn assistant(reasoning: &str, signature: Option<&str>) -> AIMessage {
+        AIMessage {
+            role: "assistant".to_string(),
+            content: Some("answer".to_string()),
+            reasoning_content: Some(reasoning.to_string()),
+            thinking_signature: signature.map(str::to_string),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+            tool_image_attachments: None,
+            server_tool_events: None,
+        }
+    }
+
+    #[test]
+    fn anthropic_replay_keeps_only_signed_reasoning() {
+        let mut messages = vec![
+            assistant("signed", Some("sig_1")),
+            assistant("unsigned", None),
+            assistant("empty signature", Some("")),
+        ];
+
+        MessageHelper::apply_reasoning_replay_rules(&mut messages, "anthropic");
+
+        assert_eq!(messages[0].reasoning_content.as_deref(), Some("signed"));
+        assert_eq!(messages[1].reasoning_content, None);
+        assert_eq!(messages[2].reasoning_content, None);
+        assert_eq!(messages[2].thinking_signature, None);
+    }
+
+    #[test]
+    fn other_providers_keep_reasoning() {
+        let mut messages = vec![assistant("unsigned", None)];
+
+        MessageHelper::apply_reasoning_replay_rules(&mut messages, "openai");
+
+        assert_eq!(messages[0].reasoning_content.as_deref(), Some("unsigned"));
+    }
+}
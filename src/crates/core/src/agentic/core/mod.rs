@@ -9,7 +9,9 @@ pub mod model_round;
 pub mod prompt_markup;
 pub mod session;
 pub mod state;
-pub use dialog_turn::{DialogTurn, DialogTurnState, TurnStats};
+pub use dialog_turn::{
+    BudgetUsage, DialogTurn, DialogTurnState, TurnBudget, TurnBudgetKind, TurnStats,
+};
 pub use message::{
     Message, MessageContent, MessageRole, MessageSemanticKind, ToolCall, ToolResult,
 };
@@ -1,5 +1,7 @@
+use super::dialog_turn::TurnBudget;
 use super::state::SessionState;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 use uuid::Uuid;
 
@@ -52,6 +54,9 @@ pub struct CompressionState {
     pub last_compression_at: Option<SystemTime>,
     /// Compression trigger count
     pub compression_count: usize,
+    /// Compress before the next model request regardless of the threshold
+    #[serde(default)]
+    pub manual_requested: bool,
 }
 
 impl Default for CompressionState {
@@ -59,6 +64,7 @@ impl Default for CompressionState {
         Self {
             last_compression_at: None,
             compression_count: 0,
+            manual_requested: false,
         }
     }
 }
@@ -67,6 +73,7 @@ impl CompressionState {
     pub fn increment_compression_count(&mut self) {
         self.last_compression_at = Some(SystemTime::now());
         self.compression_count += 1;
+        self.manual_requested = false;
     }
 }
 
@@ -120,7 +127,14 @@ pub struct SessionConfig {
     pub auto_compact: bool,
     pub enable_tools: bool,
     pub safe_mode: bool,
+    /// Model rounds per dialog turn
     pub max_turns: usize,
+    /// Tool calls per dialog turn before it wraps up (unlimited when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls_per_turn: Option<usize>,
+    /// Wall-clock seconds per dialog turn before it wraps up (unlimited when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turn_duration_secs: Option<u64>,
     pub enable_context_compression: bool,
     /// Compression threshold (token usage rate), compression triggered when exceeded
     pub compression_threshold: f32,
@@ -140,6 +154,13 @@ pub struct SessionConfig {
     /// Model config ID used by this session (for token usage tracking)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model_id: Option<String>,
+    /// Environment variables for the session's Bash commands and background processes.
+    /// Values may be `keyring:<name>` references into the secrets store.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Leave this session's turns without an AI summary even when turn summaries are on
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_turn_summary: bool,
 }
 
 impl Default for SessionConfig {
@@ -150,14 +171,36 @@ impl Default for SessionConfig {
             enable_tools: true,
             safe_mode: true,
             max_turns: 200,
+            max_tool_calls_per_turn: None,
+            max_turn_duration_secs: None,
             enable_context_compression: true,
             compression_threshold: 0.8, // 80%
             workspace_path: None,
             remote_connection_id: None,
             remote_ssh_host: None,
             model_id: None,
+            env: BTreeMap::new(),
+            skip_turn_summary: false,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn turn_budget(&self) -> TurnBudget {
+        TurnBudget {
+            max_rounds: Some(self.max_turns),
+            max_tool_calls: self.max_tool_calls_per_turn,
+            max_duration_secs: self.max_turn_duration_secs,
         }
     }
+
+    /// Tightens the per-turn budget; limits already lower than `budget` are kept.
+    pub fn restrict_turn_budget(&mut self, budget: TurnBudget) {
+        let budget = self.turn_budget().min(budget);
+        self.max_turns = budget.max_rounds.unwrap_or(self.max_turns);
+        self.max_tool_calls_per_turn = budget.max_tool_calls;
+        self.max_turn_duration_secs = budget.max_duration_secs;
+    }
 }
 
 /// Session summary (for list display)
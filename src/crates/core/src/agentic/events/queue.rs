@@ -3,7 +3,7 @@
 //! Provides priority queue and batch processing functionality
 
 use super::types::{AgenticEvent, EventEnvelope, EventPriority};
-use crate::util::errors::BitFunResult;
+use crate::util::errors::{BitFunError, BitFunResult};
 use log::{debug, trace, warn};
 use std::collections::BinaryHeap;
 use std::sync::Arc;
@@ -64,11 +64,25 @@ impl EventQueue {
     }
 
     /// Enqueue event
+    ///
+    /// Events without a session ID are rejected: with several sessions running
+    /// at once, subscribers could not tell which session such an event is for.
     pub async fn enqueue(
         &self,
         event: AgenticEvent,
         priority: Option<EventPriority>,
     ) -> BitFunResult<String> {
+        if event.session_id().is_empty() {
+            warn!(
+                "Rejecting event without session ID: event={}",
+                event.event_name()
+            );
+            return Err(BitFunError::Validation(format!(
+                "Event {} has no session ID",
+                event.event_name()
+            )));
+        }
+
         let priority = priority.unwrap_or_else(|| event.default_priority());
         let envelope = EventEnvelope::new(event, priority);
         let event_id = envelope.id.clone();
@@ -144,7 +158,7 @@ impl EventQueue {
             let mut new_queue = BinaryHeap::new();
 
             while let Some(std::cmp::Reverse(envelope)) = queue.pop() {
-                if envelope.event.session_id() != Some(session_id) {
+                if envelope.event.session_id() != session_id {
                     new_queue.push(std::cmp::Reverse(envelope));
                 }
             }
@@ -164,6 +178,32 @@ impl EventQueue {
         Ok(())
     }
 
+    /// Drop queued text and thinking chunks of a turn, e.g. after it was aborted
+    pub async fn discard_stream_chunks(&self, turn_id: &str) {
+        let discarded = {
+            let mut queue = self.queue.lock().await;
+            let before = queue.len();
+            queue.retain(|std::cmp::Reverse(envelope)| {
+                !matches!(
+                    &envelope.event,
+                    AgenticEvent::TextChunk { turn_id: id, .. }
+                        | AgenticEvent::ThinkingChunk { turn_id: id, .. }
+                        if id == turn_id
+                )
+            });
+            let discarded = before - queue.len();
+            self.stats.lock().await.pending_events = queue.len();
+            discarded
+        };
+
+        if discarded > 0 {
+            debug!(
+                "Discarded queued stream chunks: turn_id={}, count={}",
+                turn_id, discarded
+            );
+        }
+    }
+
     /// Get queue statistics
     pub async fn stats(&self) -> QueueStats {
         self.stats.lock().await.clone()
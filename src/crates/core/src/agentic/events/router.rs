@@ -3,10 +3,19 @@
 //! Responsible for distributing events to internal subscribers (frontend events are sent directly using Tauri emit)
 
 use super::types::{AgenticEvent, EventEnvelope};
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
 use crate::util::errors::BitFunResult;
 use dashmap::DashMap;
-use log::{debug, trace, warn};
-use std::sync::Arc;
+use futures::FutureExt;
+use log::{debug, error, trace, warn};
+use std::panic::{AssertUnwindSafe, Location};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+
+/// Panics after which a subscriber is unsubscribed
+pub const MAX_SUBSCRIBER_PANICS: u32 = 3;
+/// Backend event sent when a subscriber is unsubscribed for panicking
+pub const SUBSCRIBER_REMOVED_EVENT: &str = "event-subscriber-removed";
 
 /// Event subscriber trait
 ///
@@ -14,6 +23,117 @@ use std::sync::Arc;
 #[async_trait::async_trait]
 pub trait EventSubscriber: Send + Sync + 'static {
     async fn on_event(&self, event: &AgenticEvent) -> BitFunResult<()>;
+
+    /// Events this subscriber wants; read once when it is subscribed
+    fn filter(&self) -> EventFilter {
+        EventFilter::all()
+    }
+}
+
+/// Event names a subscription matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventPattern {
+    All,
+    Exact(String),
+    Prefix(String),
+}
+
+impl EventPattern {
+    /// `*` matches every event, a trailing `*` matches by prefix
+    /// (`agentic://dialog-turn-*`), anything else matches one name
+    pub fn parse(pattern: &str) -> Self {
+        match pattern.strip_suffix('*') {
+            Some("") => Self::All,
+            Some(prefix) => Self::Prefix(prefix.to_string()),
+            None => Self::Exact(pattern.to_string()),
+        }
+    }
+
+    pub fn matches(&self, event_name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Exact(name) => event_name == name,
+            Self::Prefix(prefix) => event_name.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+/// Which events a subscription receives: a name pattern, optionally limited
+/// to one session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    pattern: EventPattern,
+    session_id: Option<String>,
+}
+
+impl EventFilter {
+    pub fn all() -> Self {
+        Self {
+            pattern: EventPattern::All,
+            session_id: None,
+        }
+    }
+
+    /// Filter on event names, see [`EventPattern::parse`]
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: EventPattern::parse(pattern),
+            session_id: None,
+        }
+    }
+
+    /// Only events of the given session
+    pub fn for_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn matches(&self, event: &AgenticEvent) -> bool {
+        let session_matches = match &self.session_id {
+            Some(session_id) => event.session_id() == session_id.as_str(),
+            None => true,
+        };
+        session_matches && self.pattern.matches(event.event_name())
+    }
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+#[derive(Clone)]
+struct Subscription {
+    filter: EventFilter,
+    subscriber: Arc<dyn EventSubscriber>,
+    /// Where the subscriber was registered, for panic reports
+    registered_at: &'static Location<'static>,
+    panics: Arc<AtomicU32>,
+}
+
+type Subscriptions = DashMap<String, Subscription>;
+
+/// Keeps a subscription made with [`EventRouter::subscribe`] alive;
+/// dropping it unsubscribes
+pub struct SubscriptionGuard {
+    id: String,
+    subscriptions: Weak<Subscriptions>,
+}
+
+impl SubscriptionGuard {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        if let Some(subscriptions) = self.subscriptions.upgrade() {
+            subscriptions.remove(&self.id);
+            debug!("Dropped event subscription: subscriber_id={}", self.id);
+        }
+    }
 }
 
 /// Event router
@@ -22,34 +142,38 @@ pub trait EventSubscriber: Send + Sync + 'static {
 /// - Manage internal subscribers
 /// - Distribute events to all subscribers
 pub struct EventRouter {
-    /// Internal subscribers (by subscriber ID)
-    internal_subscribers: Arc<DashMap<String, Arc<dyn EventSubscriber>>>,
+    /// Internal subscribers (by subscriber ID) and the events they receive
+    internal_subscribers: Arc<Subscriptions>,
+    next_subscription_id: AtomicU64,
 }
 
 impl EventRouter {
     pub fn new() -> Self {
         Self {
             internal_subscribers: Arc::new(DashMap::new()),
+            next_subscription_id: AtomicU64::new(1),
         }
     }
 
-    /// Route event to internal subscribers
+    /// Subscribers whose filter matches the event
     ///
-    /// Note: frontend events are sent directly using lib.rs:emit_to_frontend(), not through this router
-    pub async fn route(&self, envelope: EventEnvelope) -> BitFunResult<()> {
-        let event = &envelope.event;
-
-        // First collect subscribers list (avoid holding DashMap iterator across await points)
-        let subscribers: Vec<(String, Arc<dyn EventSubscriber>)> = self
-            .internal_subscribers
+    /// Collected up front to avoid holding DashMap references across await points
+    fn matching_subscribers(&self, event: &AgenticEvent) -> Vec<(String, Subscription)> {
+        self.internal_subscribers
             .iter()
+            .filter(|entry| entry.value().filter.matches(event))
             .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect();
+            .collect()
+    }
+
+    async fn dispatch(&self, event: &AgenticEvent) {
+        let subscribers = self.matching_subscribers(event);
 
         // Only log if there are subscribers (to avoid flooding)
         if !subscribers.is_empty() {
             trace!(
-                "Routing event to {} subscribers: {:?}",
+                "Routing event {} to {} subscribers: {:?}",
+                event.event_name(),
                 subscribers.len(),
                 subscribers
                     .iter()
@@ -58,47 +182,146 @@ impl EventRouter {
             );
         }
 
-        // Send to all internal subscribers
-        for (subscriber_id, subscriber) in subscribers {
-            if let Err(e) = subscriber.on_event(event).await {
-                warn!(
+        for (subscriber_id, subscription) in subscribers {
+            // An earlier handler may have unsubscribed this one
+            if !self.internal_subscribers.contains_key(&subscriber_id) {
+                continue;
+            }
+            // A panicking handler must not take event delivery down with it
+            match AssertUnwindSafe(subscription.subscriber.on_event(event))
+                .catch_unwind()
+                .await
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!(
                     "Internal subscriber {} failed to process event: {}",
                     subscriber_id, e
-                );
+                ),
+                Err(panic) => {
+                    self.handle_panic(&subscriber_id, &subscription, event, panic)
+                        .await
+                }
             }
         }
+    }
+
+    async fn handle_panic(
+        &self,
+        subscriber_id: &str,
+        subscription: &Subscription,
+        event: &AgenticEvent,
+        panic: Box<dyn std::any::Any + Send>,
+    ) {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let panics = subscription.panics.fetch_add(1, Ordering::Relaxed) + 1;
+        error!(
+            "Internal subscriber {} (registered at {}) panicked on {}: {} ({} of {} allowed)",
+            subscriber_id,
+            subscription.registered_at,
+            event.event_name(),
+            message,
+            panics,
+            MAX_SUBSCRIBER_PANICS
+        );
+        if panics < MAX_SUBSCRIBER_PANICS {
+            return;
+        }
 
+        // Only this registration; the id may have been reused since
+        let removed = self
+            .internal_subscribers
+            .remove_if(subscriber_id, |_, current| {
+                Arc::ptr_eq(&current.panics, &subscription.panics)
+            })
+            .is_some();
+        if !removed {
+            return;
+        }
+        error!(
+            "Unsubscribed internal subscriber {} after {} panics",
+            subscriber_id, panics
+        );
+        if let Err(e) = emit_global_event(BackendEvent::Custom {
+            event_name: SUBSCRIBER_REMOVED_EVENT.to_string(),
+            payload: serde_json::json!({
+                "subscriberId": subscriber_id,
+                "registeredAt": subscription.registered_at.to_string(),
+                "panics": panics,
+                "lastPanic": message,
+            }),
+        })
+        .await
+        {
+            warn!("Failed to emit subscriber removal event: {}", e);
+        }
+    }
+
+    /// Route event to internal subscribers
+    ///
+    /// Note: frontend events are sent directly using lib.rs:emit_to_frontend(), not through this router
+    pub async fn route(&self, envelope: EventEnvelope) -> BitFunResult<()> {
+        self.dispatch(&envelope.event).await;
         Ok(())
     }
 
     /// Route batch of events
     pub async fn route_batch(&self, envelopes: Vec<EventEnvelope>) -> BitFunResult<()> {
-        // First collect subscribers list (avoid holding DashMap iterator across await points)
-        let subscribers: Vec<(String, Arc<dyn EventSubscriber>)> = self
-            .internal_subscribers
-            .iter()
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect();
-
         for envelope in envelopes {
-            let event = &envelope.event;
-            for (subscriber_id, subscriber) in &subscribers {
-                if let Err(e) = subscriber.on_event(event).await {
-                    warn!(
-                        "Internal subscriber {} failed to process event: {}",
-                        subscriber_id, e
-                    );
-                }
-            }
+            self.dispatch(&envelope.event).await;
         }
         Ok(())
     }
 
-    /// Add internal subscriber
+    /// Add internal subscriber, receiving the events its [`EventSubscriber::filter`] matches
+    #[track_caller]
     pub fn subscribe_internal(&self, subscriber_id: String, subscriber: Arc<dyn EventSubscriber>) {
-        self.internal_subscribers
-            .insert(subscriber_id.clone(), subscriber);
-        debug!("Added internal subscriber: subscriber_id={}", subscriber_id);
+        let filter = subscriber.filter();
+        self.subscribe_internal_filtered(subscriber_id, filter, subscriber);
+    }
+
+    /// Add internal subscriber receiving only the events `filter` matches
+    #[track_caller]
+    pub fn subscribe_internal_filtered(
+        &self,
+        subscriber_id: String,
+        filter: EventFilter,
+        subscriber: Arc<dyn EventSubscriber>,
+    ) {
+        debug!(
+            "Added internal subscriber: subscriber_id={}, filter={:?}",
+            subscriber_id, filter
+        );
+        self.internal_subscribers.insert(
+            subscriber_id,
+            Subscription {
+                filter,
+                subscriber,
+                registered_at: Location::caller(),
+                panics: Arc::new(AtomicU32::new(0)),
+            },
+        );
+    }
+
+    /// Subscribe for as long as the returned guard is kept
+    #[track_caller]
+    pub fn subscribe(
+        &self,
+        filter: EventFilter,
+        subscriber: Arc<dyn EventSubscriber>,
+    ) -> SubscriptionGuard {
+        let id = format!(
+            "subscription-{}",
+            self.next_subscription_id.fetch_add(1, Ordering::Relaxed)
+        );
+        self.subscribe_internal_filtered(id.clone(), filter, subscriber);
+        SubscriptionGuard {
+            id,
+            subscriptions: Arc::downgrade(&self.internal_subscribers),
+        }
     }
 
     /// Remove internal subscriber
@@ -114,6 +337,13 @@ impl EventRouter {
     pub fn subscriber_count(&self) -> usize {
         self.internal_subscribers.len()
     }
+
+    /// Times a subscriber has panicked while handling events
+    pub fn subscriber_panic_count(&self, subscriber_id: &str) -> Option<u32> {
+        self.internal_subscribers
+            .get(subscriber_id)
+            .map(|subscription| subscription.panics.load(Ordering::Relaxed))
+    }
 }
 
 impl Default for EventRouter {
@@ -121,3 +351,179 @@ impl Default for EventRouter {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn seen(&self) -> Vec<String> {
+            self.seen.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Recorder {
+        async fn on_event(&self, event: &AgenticEvent) -> BitFunResult<()> {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(event.event_name().to_string());
+            Ok(())
+        }
+    }
+
+    fn envelope(event: AgenticEvent) -> EventEnvelope {
+        let priority = event.default_priority();
+        EventEnvelope::new(event, priority)
+    }
+
+    fn deleted(session_id: &str) -> AgenticEvent {
+        AgenticEvent::SessionDeleted {
+            session_id: session_id.to_string(),
+        }
+    }
+
+    fn title(session_id: &str) -> AgenticEvent {
+        AgenticEvent::SessionTitleGenerated {
+            session_id: session_id.to_string(),
+            title: "Title".to_string(),
+            method: "ai".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_patterns() {
+        assert_eq!(EventPattern::parse("*"), EventPattern::All);
+        assert_eq!(
+            EventPattern::parse("cowork://*"),
+            EventPattern::Prefix("cowork://".to_string())
+        );
+        assert_eq!(
+            EventPattern::parse("agentic://text-chunk"),
+            EventPattern::Exact("agentic://text-chunk".to_string())
+        );
+        assert!(EventPattern::parse("agentic://session-*").matches("agentic://session-deleted"));
+        assert!(!EventPattern::parse("agentic://session-*").matches("agentic://text-chunk"));
+    }
+
+    #[tokio::test]
+    async fn overlapping_subscriptions_each_get_their_matches() {
+        let router = EventRouter::new();
+        let all = Arc::new(Recorder::default());
+        let exact = Arc::new(Recorder::default());
+        let prefix = Arc::new(Recorder::default());
+        let session = Arc::new(Recorder::default());
+
+        router.subscribe_internal("all".to_string(), all.clone());
+        let _exact = router.subscribe(EventFilter::new("agentic://session-deleted"), exact.clone());
+        let _prefix = router.subscribe(EventFilter::new("agentic://session-*"), prefix.clone());
+        let _session = router.subscribe(
+            EventFilter::new("agentic://session-*").for_session("s2"),
+            session.clone(),
+        );
+
+        router
+            .route_batch(vec![envelope(deleted("s1")), envelope(title("s2"))])
+            .await
+            .unwrap();
+
+        let both = vec![
+            "agentic://session-deleted".to_string(),
+            "agentic://session-title-generated".to_string(),
+        ];
+        assert_eq!(all.seen(), both);
+        assert_eq!(prefix.seen(), both);
+        assert_eq!(exact.seen(), vec!["agentic://session-deleted"]);
+        assert_eq!(session.seen(), vec!["agentic://session-title-generated"]);
+    }
+
+    #[tokio::test]
+    async fn dropping_the_guard_unsubscribes() {
+        let router = EventRouter::new();
+        let recorder = Arc::new(Recorder::default());
+        let guard = router.subscribe(EventFilter::all(), recorder.clone());
+        assert_eq!(router.subscriber_count(), 1);
+
+        router.route(envelope(deleted("s1"))).await.unwrap();
+        drop(guard);
+        router.route(envelope(deleted("s1"))).await.unwrap();
+
+        assert_eq!(router.subscriber_count(), 0);
+        assert_eq!(recorder.seen().len(), 1);
+    }
+
+    /// Drops the other subscriber's guard when it receives an event
+    struct Unsubscriber {
+        other: Mutex<Option<SubscriptionGuard>>,
+        calls: Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Unsubscriber {
+        async fn on_event(&self, _event: &AgenticEvent) -> BitFunResult<()> {
+            *self.calls.lock().unwrap() += 1;
+            self.other.lock().unwrap().take();
+            Ok(())
+        }
+    }
+
+    struct Panicking;
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Panicking {
+        async fn on_event(&self, _event: &AgenticEvent) -> BitFunResult<()> {
+            panic!("handler bug");
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_handlers_are_isolated_and_eventually_removed() {
+        let router = EventRouter::new();
+        let recorder = Arc::new(Recorder::default());
+        router.subscribe_internal("panicking".to_string(), Arc::new(Panicking));
+        router.subscribe_internal("recorder".to_string(), recorder.clone());
+
+        router.route(envelope(deleted("s1"))).await.unwrap();
+        assert_eq!(router.subscriber_panic_count("panicking"), Some(1));
+
+        for _ in 1..MAX_SUBSCRIBER_PANICS {
+            router.route(envelope(deleted("s1"))).await.unwrap();
+        }
+        assert_eq!(router.subscriber_panic_count("panicking"), None);
+        assert_eq!(router.subscriber_count(), 1);
+
+        router.route(envelope(title("s1"))).await.unwrap();
+        assert_eq!(recorder.seen().len(), MAX_SUBSCRIBER_PANICS as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn unsubscribing_during_dispatch_skips_the_removed_handler() {
+        let router = EventRouter::new();
+        let first = Arc::new(Unsubscriber {
+            other: Mutex::new(None),
+            calls: Mutex::new(0),
+        });
+        let second = Arc::new(Unsubscriber {
+            other: Mutex::new(None),
+            calls: Mutex::new(0),
+        });
+        let first_guard = router.subscribe(EventFilter::all(), first.clone());
+        let second_guard = router.subscribe(EventFilter::all(), second.clone());
+        *first.other.lock().unwrap() = Some(second_guard);
+        *second.other.lock().unwrap() = Some(first_guard);
+
+        // Whichever handler runs first removes the other before it is called
+        router.route(envelope(deleted("s1"))).await.unwrap();
+
+        let calls = *first.calls.lock().unwrap() + *second.calls.lock().unwrap();
+        assert_eq!(calls, 1);
+        assert_eq!(router.subscriber_count(), 1);
+    }
+}
@@ -2,31 +2,48 @@
 //!
 //! Executes complete dialog turns, managing loops of multiple model rounds
 
+use super::loop_guard::{LoopGuard, LoopVerdict};
+use super::recent_changes::{collect_recent_changes, RecentChangesDigest};
 use super::round_executor::RoundExecutor;
 use super::types::{ExecutionContext, ExecutionResult, RoundContext};
-use crate::agentic::agents::{get_agent_registry, PromptBuilderContext};
-use crate::agentic::core::{Message, MessageContent, MessageHelper, MessageSemanticKind, Session};
+use crate::agentic::agents::{get_agent_registry, get_tool_policy, PromptBuilderContext};
+use crate::agentic::core::{
+    render_system_reminder, BudgetUsage, Message, MessageContent, MessageHelper,
+    MessageSemanticKind, Session, TurnBudget, TurnBudgetKind, TurnStats,
+};
 use crate::agentic::events::{AgenticEvent, EventPriority, EventQueue};
 use crate::agentic::image_analysis::{
-    build_multimodal_message_with_images, process_image_contexts_for_provider, ImageContextData,
-    ImageLimits,
+    build_multimodal_message_with_images, prepare_tool_image_attachments,
+    process_image_contexts_for_provider, ImageContextData, ImageLimits,
 };
 use crate::agentic::session::SessionManager;
 use crate::agentic::tools::{get_all_registered_tools, SubagentParentInfo};
+use crate::agentic::turn_cancellation::ensure_turn_scope;
 use crate::agentic::WorkspaceBinding;
-use crate::infrastructure::ai::get_global_ai_client_factory;
+use crate::infrastructure::ai::{
+    estimate_tokens, get_global_ai_client_factory, AIClient, TokenizerKind,
+};
+use crate::service::code_index::get_global_code_index_service;
 use crate::service::config::get_global_config_service;
-use crate::service::config::types::{ModelCapability, ModelCategory};
+use crate::service::config::types::{
+    LoopGuardConfig, RecentChangesContextConfig, SpendGuardrailsConfig,
+};
+use crate::service::token_usage::{check_spend, get_global_token_usage_service};
 use crate::util::errors::{BitFunError, BitFunResult};
 use crate::util::token_counter::TokenCounter;
 use crate::util::types::Message as AIMessage;
 use crate::util::types::ToolDefinition;
+use dashmap::DashMap;
 use log::{debug, error, info, trace, warn};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 
+/// Share of a turn budget after which the model is told to wrap up
+const BUDGET_WRAP_UP_FRACTION: f64 = 0.8;
+
 /// Execution engine configuration
 #[derive(Debug, Clone)]
 pub struct ExecutionEngineConfig {
@@ -45,6 +62,10 @@ pub struct ExecutionEngine {
     event_queue: Arc<EventQueue>,
     session_manager: Arc<SessionManager>,
     config: ExecutionEngineConfig,
+    /// Turns waiting for the user to approve an over-threshold round (turn_id -> approval)
+    spend_confirmations: Arc<DashMap<String, oneshot::Sender<bool>>>,
+    /// Date the daily spend warning was last emitted, so it fires once per day
+    spend_warned_on: Mutex<Option<String>>,
 }
 
 impl ExecutionEngine {
@@ -59,6 +80,8 @@ impl ExecutionEngine {
             event_queue,
             session_manager,
             config,
+            spend_confirmations: Arc::new(DashMap::new()),
+            spend_warned_on: Mutex::new(None),
         }
     }
 
@@ -172,12 +195,18 @@ impl ExecutionEngine {
             .get_config(Some("ai"))
             .await
             .unwrap_or_default();
+        // A model picked for the session wins, except "auto" when the agent has an override
+        let has_agent_override = agent_registry
+            .get_override_model_id(agent_type)
+            .await
+            .is_some();
         let configured_model_id = session
             .config
             .model_id
             .as_ref()
             .map(|model_id| model_id.trim())
             .filter(|model_id| !model_id.is_empty())
+            .filter(|model_id| !(has_agent_override && *model_id == "auto"))
             .map(str::to_string)
             .unwrap_or(fallback_model_id.clone());
         let resolved_configured_model_id =
@@ -257,11 +286,13 @@ impl ExecutionEngine {
         workspace_path: Option<&Path>,
         current_turn_id: &str,
         attach_images: bool,
+        image_max_dimension: Option<u32>,
+        image_quality: Option<u8>,
     ) -> BitFunResult<Vec<AIMessage>> {
         /// Only the last this many **messages** that contain images keep their images for the API.
         const MAX_IMAGE_BEARING_MESSAGE_ROUNDS: usize = 2;
 
-        let limits = ImageLimits::for_provider(provider);
+        let limits = ImageLimits::for_model(provider, image_max_dimension, image_quality);
 
         let mut result = Vec::with_capacity(messages.len());
         let mut attached_image_count = 0usize;
@@ -314,10 +345,10 @@ impl ExecutionEngine {
 
                     match process_image_contexts_for_provider(
                         &filtered_images,
-                        provider,
+                        &limits,
                         workspace_path,
                     )
-                        .await
+                    .await
                     {
                         Ok(processed) => {
                             let next_count = attached_image_count + processed.len();
@@ -374,7 +405,8 @@ impl ExecutionEngine {
                                     )));
                                 }
                                 attached_image_count = next_count;
-                                ai.tool_image_attachments = Some(atts);
+                                ai.tool_image_attachments =
+                                    Some(prepare_tool_image_attachments(atts, &limits));
                             } else {
                                 let dropped = atts.len();
                                 let content_str = ai.content.as_deref().unwrap_or("");
@@ -394,9 +426,34 @@ impl ExecutionEngine {
             }
         }
 
+        MessageHelper::apply_reasoning_replay_rules(&mut result, provider);
+
         Ok(result)
     }
 
+    /// Reminder that a turn budget is nearly (`exhausted == false`) or fully used up.
+    fn budget_reminder(usage: BudgetUsage, exhausted: bool) -> Message {
+        let amount = match usage.kind {
+            TurnBudgetKind::Duration => format!("{} of {} seconds", usage.used, usage.limit),
+            kind => format!("{} of {} {}s", usage.used, usage.limit, kind.label()),
+        };
+        let text = if exhausted {
+            format!(
+                "This turn has reached its {} budget ({}). Tools are disabled for this final response; any tool call will be rejected. Reply with a summary of what you accomplished, what is still outstanding, and the next steps to resume the work.",
+                usage.kind.label(),
+                amount
+            )
+        } else {
+            format!(
+                "This turn has used {} of its {} budget. Start wrapping up: finish the step you are on, don't start new work, and be ready to summarize your progress and what is still outstanding.",
+                amount,
+                usage.kind.label()
+            )
+        };
+        Message::user(render_system_reminder(&text))
+            .with_semantic_kind(MessageSemanticKind::InternalReminder)
+    }
+
     fn render_multimodal_as_text(
         text: &str,
         images: &[ImageContextData],
@@ -439,6 +496,7 @@ impl ExecutionEngine {
         subagent_parent_info: Option<SubagentParentInfo>,
         messages: Vec<Message>,
         current_tokens: usize,
+        model: &str,
         context_window: usize,
         tool_definitions: &Option<Vec<ToolDefinition>>,
         system_prompt_message: Message,
@@ -457,7 +515,7 @@ impl ExecutionEngine {
         let old_messages_len = messages.len();
         // Preprocess turns
         let (turn_index_to_keep, turns) = compression_manager
-            .preprocess_turns(session_id, context_window, messages)
+            .preprocess_turns(session_id, model, context_window, messages)
             .await?;
         if turn_index_to_keep == 0 {
             return Ok(None);
@@ -472,7 +530,11 @@ impl ExecutionEngine {
                 session_id: session_id.to_string(),
                 turn_id: dialog_turn_id.to_string(),
                 compression_id: compression_id.clone(),
-                trigger: "auto".to_string(),
+                trigger: if session.compression_state.manual_requested {
+                    "manual".to_string()
+                } else {
+                    "auto".to_string()
+                },
                 tokens_before: current_tokens,
                 context_window,
                 threshold: session.config.compression_threshold,
@@ -565,9 +627,17 @@ impl ExecutionEngine {
         let start_time = std::time::Instant::now();
         let initial_count = initial_messages.len();
 
+        let session_id = context.session_id.clone();
         let dialog_turn_id = context.dialog_turn_id.clone();
 
-        info!("Starting dialog turn: dialog_turn_id={}", dialog_turn_id);
+        info!(
+            "Starting dialog turn: session_id={}, dialog_turn_id={}",
+            session_id, dialog_turn_id
+        );
+
+        // The turn owns its root cancellation token from the start, so an abort
+        // during context preparation is seen too (subagents registered theirs already)
+        ensure_turn_scope(&session_id, &dialog_turn_id);
 
         // Execute actual logic
         let result = self
@@ -582,7 +652,7 @@ impl ExecutionEngine {
 
         // Cleanup cancellation token
         self.round_executor
-            .cleanup_dialog_turn(&dialog_turn_id)
+            .cleanup_dialog_turn(&session_id, &dialog_turn_id)
             .await;
         debug!(
             "Cleaned up cancel token (final cleanup): dialog_turn_id={}",
@@ -597,12 +667,10 @@ impl ExecutionEngine {
         &self,
         agent_type: String,
         initial_messages: Vec<Message>,
-        context: ExecutionContext,
+        mut context: ExecutionContext,
         start_time: std::time::Instant,
         initial_count: usize,
     ) -> BitFunResult<ExecutionResult> {
-        let event_subagent_parent_info =
-            context.subagent_parent_info.clone().map(|info| info.into());
         let dialog_turn_id = context.dialog_turn_id.clone();
 
         debug!(
@@ -660,6 +728,12 @@ impl ExecutionEngine {
             current_agent.name(),
             model_id
         );
+        // Subagent events record the model the subagent actually ran on
+        if let Some(parent_info) = context.subagent_parent_info.as_mut() {
+            parent_info.model_id = Some(model_id.clone());
+        }
+        let event_subagent_parent_info =
+            context.subagent_parent_info.clone().map(|info| info.into());
 
         let ai_client_factory = get_global_ai_client_factory().await.map_err(|e| {
             BitFunError::AIClient(format!("Failed to get AI client factory: {}", e))
@@ -680,12 +754,24 @@ impl ExecutionEngine {
         let support_preserved_thinking = ai_client.config.support_preserved_thinking;
         let context_window = ai_client.config.context_window as usize;
 
+        // Mode tools are needed by the system prompt (project map) as well as for step 4
+        let allowed_tools = agent_registry
+            .get_agent_tools(
+                &agent_type,
+                context
+                    .workspace
+                    .as_ref()
+                    .map(|workspace| workspace.root_path()),
+            )
+            .await;
+
         // 3. Get System Prompt from current Agent
         debug!(
             "Building system prompt from agent: {}, model={}",
             current_agent.name(),
             ai_client.config.model
         );
+        let mut recent_changes = None;
         let system_prompt = {
             let workspace_str = context
                 .workspace
@@ -698,17 +784,54 @@ impl ExecutionEngine {
                     Some(ai_client.config.model.clone()),
                 )
             });
-            current_agent
+            let mut system_prompt = current_agent
                 .get_system_prompt(prompt_context.as_ref())
-                .await?
+                .await?;
+            if allowed_tools.iter().any(|tool| tool == "CodebaseSearch") {
+                if let Some(project_map) = project_map_section(context.workspace.as_ref()) {
+                    system_prompt.push_str(&project_map);
+                }
+            }
+            // Only the main session's first turn; later turns already have the conversation
+            if context.turn_index == 0 && context.subagent_parent_info.is_none() {
+                recent_changes = recent_changes_digest(context.workspace.as_ref()).await;
+                if let Some(digest) = &recent_changes {
+                    system_prompt.push_str(&digest.text);
+                }
+            }
+            if let Some(suffix) = &context.system_prompt_suffix {
+                system_prompt.push_str(suffix);
+            }
+            system_prompt
         };
         debug!("System prompt built, length: {} bytes", system_prompt.len());
+        if let Some(digest) = recent_changes {
+            self.emit_event(
+                AgenticEvent::RecentChangesAttached {
+                    session_id: context.session_id.clone(),
+                    turn_id: context.dialog_turn_id.clone(),
+                    files: digest.files,
+                    estimated_tokens: digest.estimated_tokens,
+                },
+                EventPriority::Normal,
+            )
+            .await;
+        }
         let system_prompt_message = Message::system(system_prompt.clone());
 
         // Add System Prompt to the beginning of message list (only for this execution, not persisted)
         let mut messages = vec![system_prompt_message.clone()];
         messages.extend(initial_messages);
 
+        let max_rounds = agent_registry
+            .get_agent_max_turns(&agent_type)
+            .await
+            .unwrap_or(self.config.max_rounds);
+        let turn_budget = session.config.turn_budget().min(TurnBudget {
+            max_rounds: Some(max_rounds),
+            ..TurnBudget::default()
+        });
+
         let mut round_index = 0;
         let mut total_tools = 0;
         let mut last_assistant_message = Message::assistant("".to_string());
@@ -736,21 +859,12 @@ impl ExecutionEngine {
         );
 
         // 4. Get available tools list (read tool configuration for current mode from global config)
-        let allowed_tools = agent_registry
-            .get_agent_tools(
-                &agent_type,
-                context
-                    .workspace
-                    .as_ref()
-                    .map(|workspace| workspace.root_path()),
-            )
-            .await;
         let enable_tools = context
             .context
             .get("enable_tools")
             .and_then(|v| v.parse::<bool>().ok())
             .unwrap_or(true);
-        let (available_tools, tool_definitions) = if enable_tools {
+        let (mut available_tools, mut tool_definitions) = if enable_tools {
             debug!(
                 "Agent tools: agent={}, tool_count={}",
                 agent_type,
@@ -765,12 +879,25 @@ impl ExecutionEngine {
         } else {
             (vec![], None)
         };
+        // The model searches the web itself; offering both search tools confuses it
+        if ai_client.config.uses_native_web_search() {
+            available_tools.retain(|name| name != "WebSearch");
+            if let Some(definitions) = tool_definitions.as_mut() {
+                definitions.retain(|definition| definition.name != "WebSearch");
+            }
+        }
 
         let enable_context_compression = session.config.enable_context_compression;
         let compression_threshold = session.config.compression_threshold;
+        let mut manual_compression = session.compression_state.manual_requested;
         // Detect whether the primary model supports multimodal image inputs.
         // When false, multimodal user messages are converted to text placeholders before the provider call.
-        let (resolved_primary_model_id, primary_supports_image_understanding) = {
+        let (
+            resolved_primary_model_id,
+            primary_supports_image_understanding,
+            spend_guardrails,
+            loop_guard_config,
+        ) = {
             let config_service = get_global_config_service().await.ok();
             if let Some(service) = config_service {
                 let ai_config: crate::service::config::types::AIConfig =
@@ -796,19 +923,24 @@ impl ExecutionEngine {
                         })
                     });
 
-                let supports = model_cfg.is_some_and(|m| {
-                    m.capabilities
-                        .iter()
-                        .any(|cap| matches!(cap, ModelCapability::ImageUnderstanding))
-                        || matches!(m.category, ModelCategory::Multimodal)
-                });
+                let supports = model_cfg.is_some_and(|m| m.supports_image_input());
 
-                (resolved_id, supports)
+                (
+                    resolved_id,
+                    supports,
+                    ai_config.spend_guardrails,
+                    ai_config.loop_guard,
+                )
             } else {
                 warn!(
                     "Config service unavailable, assuming primary model is text-only for image input gating"
                 );
-                (model_id.clone(), false)
+                (
+                    model_id.clone(),
+                    false,
+                    SpendGuardrailsConfig::default(),
+                    LoopGuardConfig::default(),
+                )
             }
         };
 
@@ -851,15 +983,38 @@ impl ExecutionEngine {
             }
         }
 
+        // Set once the user approves this turn's estimated cost
+        let mut spend_confirmed = false;
+        let mut loop_guard = LoopGuard::new(loop_guard_config);
+        let mut wrap_up_requested = false;
+        // Set when a budget runs out; the round it is set for is the turn's last
+        let mut budget_exhausted: Option<BudgetUsage> = None;
+
         // Loop to execute model rounds
         loop {
-            // Check round limit
-            if round_index >= self.config.max_rounds {
-                warn!(
-                    "Reached max rounds limit: {}, stopping execution",
-                    self.config.max_rounds
-                );
-                break;
+            // Count the round about to run, so the last round a budget allows is the final one
+            let budget_usage = turn_budget.usage(&TurnStats {
+                total_rounds: round_index + 1,
+                total_tools,
+                duration_ms: start_time.elapsed().as_millis() as u64,
+                ..TurnStats::default()
+            });
+            if let Some(usage) = budget_usage {
+                if usage.fraction() >= 1.0 {
+                    warn!(
+                        "Turn budget exhausted, running a final round without tools: session_id={}, dialog_turn_id={}, budget={}, used={}, limit={}",
+                        context.session_id,
+                        context.dialog_turn_id,
+                        usage.kind.as_str(),
+                        usage.used,
+                        usage.limit
+                    );
+                    budget_exhausted = Some(usage);
+                    messages.push(Self::budget_reminder(usage, true));
+                } else if usage.fraction() >= BUDGET_WRAP_UP_FRACTION && !wrap_up_requested {
+                    wrap_up_requested = true;
+                    messages.push(Self::budget_reminder(usage, false));
+                }
             }
 
             MessageHelper::compute_keep_thinking_flags(
@@ -880,8 +1035,15 @@ impl ExecutionEngine {
             );
 
             let token_usage_ratio = current_tokens as f32 / context_window as f32;
-            let should_compress =
-                enable_context_compression && token_usage_ratio >= compression_threshold;
+            let should_compress = manual_compression
+                || (enable_context_compression && token_usage_ratio >= compression_threshold);
+            // A manual request keeps a share of the current context rather than of the whole
+            // window, so it compresses even when the conversation is far below the threshold
+            let compression_window = if manual_compression {
+                current_tokens.min(context_window)
+            } else {
+                context_window
+            };
 
             if !should_compress {
                 debug!(
@@ -905,7 +1067,8 @@ impl ExecutionEngine {
                         context.subagent_parent_info.clone(),
                         messages.clone(),
                         current_tokens,
-                        context_window,
+                        &ai_client.config.model,
+                        compression_window,
                         &tool_definitions,
                         system_prompt_message.clone(),
                     )
@@ -933,6 +1096,17 @@ impl ExecutionEngine {
                         );
                     }
                 }
+
+                if manual_compression {
+                    manual_compression = false;
+                    if let Err(e) = self
+                        .session_manager
+                        .set_manual_compression(&context.session_id, false)
+                        .await
+                    {
+                        warn!("Failed to clear manual compression request: {}", e);
+                    }
+                }
             }
 
             // Create round context
@@ -940,6 +1114,10 @@ impl ExecutionEngine {
             if context.skip_tool_confirmation {
                 round_context_vars.insert("skip_tool_confirmation".to_string(), "true".to_string());
             }
+            if context.require_tool_confirmation {
+                round_context_vars
+                    .insert("require_tool_confirmation".to_string(), "true".to_string());
+            }
             let round_context = RoundContext {
                 session_id: context.session_id.clone(),
                 subagent_parent_info: context.subagent_parent_info.clone(),
@@ -954,6 +1132,7 @@ impl ExecutionEngine {
                 context_vars: round_context_vars,
                 cancellation_token: CancellationToken::new(),
                 workspace_services: context.workspace_services.clone(),
+                skip_tool_execution: budget_exhausted.is_some(),
             };
 
             // Execute single model round
@@ -972,9 +1151,32 @@ impl ExecutionEngine {
                     .map(|workspace| workspace.root_path()),
                 &context.dialog_turn_id,
                 primary_supports_image_understanding,
+                ai_client.config.image_max_dimension,
+                ai_client.config.image_quality,
             )
             .await?;
 
+            self.check_context_budget(
+                &context,
+                &ai_client.config.model,
+                &ai_messages,
+                tool_definitions.as_deref(),
+                context_window,
+            )
+            .await?;
+
+            if spend_guardrails.is_enabled() {
+                self.check_spend_guardrails(
+                    &context,
+                    &ai_client,
+                    &spend_guardrails,
+                    &ai_messages,
+                    tool_definitions.as_deref(),
+                    &mut spend_confirmed,
+                )
+                .await?;
+            }
+
             let round_result = self
                 .round_executor
                 .execute_round(
@@ -1033,6 +1235,10 @@ impl ExecutionEngine {
 
             total_tools += round_result.tool_calls.len();
 
+            if budget_exhausted.is_some() {
+                break;
+            }
+
             // If no more rounds, dialog turn ends
             if !round_result.has_more_rounds {
                 debug!(
@@ -1042,6 +1248,47 @@ impl ExecutionEngine {
                 break;
             }
 
+            match loop_guard.record_round(&round_result.tool_calls) {
+                LoopVerdict::Continue => {}
+                LoopVerdict::Nudge(report) => {
+                    info!(
+                        "Agent is repeating tool calls, reminding it: session_id={}, dialog_turn_id={}, {}",
+                        context.session_id,
+                        context.dialog_turn_id,
+                        report.describe()
+                    );
+                    // Only sent with this turn's requests, like the compression reminders
+                    messages.push(
+                        Message::user(render_system_reminder(&format!(
+                            "You have {}. Repeating it will not give a different result. Review the results you already have and try a different approach, or stop and explain what is blocking you.",
+                            report.describe()
+                        )))
+                        .with_semantic_kind(MessageSemanticKind::InternalReminder),
+                    );
+                }
+                LoopVerdict::Stop(report) => {
+                    let description = report.describe();
+                    warn!(
+                        "Agent is stuck repeating tool calls, ending dialog turn: session_id={}, dialog_turn_id={}, {}",
+                        context.session_id, context.dialog_turn_id, description
+                    );
+                    self.emit_event(
+                        AgenticEvent::LoopDetected {
+                            session_id: context.session_id.clone(),
+                            turn_id: context.dialog_turn_id.clone(),
+                            cycle_length: report.cycle_length,
+                            repetitions: report.repetitions,
+                            tool_names: report.tool_names,
+                            description,
+                            subagent_parent_info: event_subagent_parent_info.clone(),
+                        },
+                        EventPriority::High,
+                    )
+                    .await;
+                    break;
+                }
+            }
+
             // Queued user message while this turn was running: stop after a full model round
             // (AI response + tool execution for this round are already persisted).
             // No special deferral for tool-confirmation phases: we do not require the user to
@@ -1060,7 +1307,9 @@ impl ExecutionEngine {
 
             // Check if cancelled after each round
             let dialog_turn_cancelled =
-                !self.round_executor.has_active_dialog_turn(&dialog_turn_id);
+                !self
+                    .round_executor
+                    .has_active_dialog_turn(&context.session_id, &dialog_turn_id);
             if dialog_turn_cancelled {
                 debug!(
                     "Dialog turn cancelled, stopping execution: dialog_turn_id={}",
@@ -1101,6 +1350,21 @@ impl ExecutionEngine {
             total_tools
         );
 
+        if let Some(usage) = budget_exhausted {
+            self.emit_event(
+                AgenticEvent::TurnBudgetExhausted {
+                    session_id: context.session_id.clone(),
+                    turn_id: context.dialog_turn_id.clone(),
+                    budget: usage.kind.as_str().to_string(),
+                    used: usage.used,
+                    limit: usage.limit,
+                    subagent_parent_info: event_subagent_parent_info.clone(),
+                },
+                EventPriority::High,
+            )
+            .await;
+        }
+
         // Emit dialog turn completed event
         debug!("Preparing to send DialogTurnCompleted event");
 
@@ -1153,15 +1417,29 @@ impl ExecutionEngine {
         Ok(ExecutionResult {
             final_message: last_assistant_message,
             total_rounds: round_index + 1,
+            total_tools,
+            budget_exhausted: budget_exhausted.map(|usage| usage.kind),
             success: true,
             new_messages,
         })
     }
 
     /// Cancel dialog turn execution
-    pub async fn cancel_dialog_turn(&self, dialog_turn_id: &str) -> BitFunResult<()> {
-        debug!("Cancelling dialog turn: dialog_turn_id={}", dialog_turn_id);
-        let result = self.round_executor.cancel_dialog_turn(dialog_turn_id).await;
+    pub async fn cancel_dialog_turn(
+        &self,
+        session_id: &str,
+        dialog_turn_id: &str,
+    ) -> BitFunResult<()> {
+        debug!(
+            "Cancelling dialog turn: session_id={}, dialog_turn_id={}",
+            session_id, dialog_turn_id
+        );
+        // Dropping the sender rejects a pending spend confirmation
+        self.spend_confirmations.remove(dialog_turn_id);
+        let result = self
+            .round_executor
+            .cancel_dialog_turn(session_id, dialog_turn_id)
+            .await;
         if result.is_ok() {
             debug!(
                 "Dialog turn cancelled successfully: dialog_turn_id={}",
@@ -1176,25 +1454,47 @@ impl ExecutionEngine {
         result
     }
 
+    /// Answer a `SpendConfirmationRequired` event for a waiting turn
+    pub fn confirm_turn_spend(&self, dialog_turn_id: &str, approved: bool) -> BitFunResult<()> {
+        let (_, sender) = self
+            .spend_confirmations
+            .remove(dialog_turn_id)
+            .ok_or_else(|| {
+                BitFunError::NotFound(format!(
+                    "No spend confirmation pending for dialog turn: {}",
+                    dialog_turn_id
+                ))
+            })?;
+        let _ = sender.send(approved);
+        Ok(())
+    }
+
     /// Check if dialog turn is still active (used to detect cancellation)
-    pub fn has_active_turn(&self, dialog_turn_id: &str) -> bool {
-        self.round_executor.has_active_dialog_turn(dialog_turn_id)
+    pub fn has_active_turn(&self, session_id: &str, dialog_turn_id: &str) -> bool {
+        self.round_executor
+            .has_active_dialog_turn(session_id, dialog_turn_id)
     }
 
     /// Register cancellation token (for external control, e.g., execute_subagent)
-    pub fn register_cancel_token(&self, dialog_turn_id: &str, token: CancellationToken) {
+    pub fn register_cancel_token(
+        &self,
+        session_id: &str,
+        dialog_turn_id: &str,
+        token: CancellationToken,
+    ) {
         self.round_executor
-            .register_cancel_token(dialog_turn_id, token)
+            .register_cancel_token(session_id, dialog_turn_id, token)
     }
 
     /// Cleanup cancellation token (for external calls)
-    pub async fn cleanup_cancel_token(&self, dialog_turn_id: &str) {
+    pub async fn cleanup_cancel_token(&self, session_id: &str, dialog_turn_id: &str) {
         self.round_executor
-            .cleanup_dialog_turn(dialog_turn_id)
+            .cleanup_dialog_turn(session_id, dialog_turn_id)
             .await
     }
 
     /// Get available tool names and definitions: 1. Tool itself is enabled 2. Allowed in mode or is MCP tool
+    /// permitted by the workspace tool policy (mode tools have the policy applied already)
     async fn get_available_tools_and_definitions(
         &self,
         mode_allowed_tools: &[String],
@@ -1203,6 +1503,7 @@ impl ExecutionEngine {
     ) -> (Vec<String>, Option<Vec<ToolDefinition>>) {
         // Use get_all_registered_tools to get all tools including MCP tools
         let all_tools = get_all_registered_tools().await;
+        let tool_policy = get_tool_policy(workspace.map(|workspace| workspace.root_path())).await;
 
         // Filter tools: 1) Check if enabled 2) Check if mode allows
         let mut tool_definitions = Vec::new();
@@ -1231,7 +1532,9 @@ impl ExecutionEngine {
 
             let tool_name = tool.name().to_string();
             // MCP tools are automatically allowed (all tools starting with mcp_)
-            if mode_allowed_tools.contains(&tool_name) || tool_name.starts_with("mcp_") {
+            if mode_allowed_tools.contains(&tool_name)
+                || (tool_name.starts_with("mcp_") && tool_policy.permits(&tool_name))
+            {
                 let description = tool
                     .description_with_context(Some(&description_context))
                     .await
@@ -1253,20 +1556,21 @@ impl ExecutionEngine {
             ("TerminalControl", 3),
             ("Glob", 4),
             ("Grep", 5),
-            ("Read", 6),
-            ("Edit", 7),
-            ("Write", 8),
-            ("Delete", 9),
-            ("WebFetch", 10),
-            ("WebSearch", 11),
-            ("TodoWrite", 12),
-            ("Skill", 13),
-            ("Log", 14),
-            ("MermaidInteractive", 15),
-            ("ComputerUse", 16),
-            ("ComputerUseMousePrecise", 17),
-            ("ComputerUseMouseStep", 18),
-            ("ComputerUseMouseClick", 19),
+            ("CodebaseSearch", 6),
+            ("Read", 7),
+            ("Edit", 8),
+            ("Write", 9),
+            ("Delete", 10),
+            ("WebFetch", 11),
+            ("WebSearch", 12),
+            ("TodoWrite", 13),
+            ("Skill", 14),
+            ("Log", 15),
+            ("MermaidInteractive", 16),
+            ("ComputerUse", 17),
+            ("ComputerUseMousePrecise", 18),
+            ("ComputerUseMouseStep", 19),
+            ("ComputerUseMouseClick", 20),
         ]
         .into_iter()
         .map(|(k, v)| (k.to_string(), v))
@@ -1278,12 +1582,195 @@ impl ExecutionEngine {
         (enabled_tool_names, Some(tool_definitions))
     }
 
+    /// Pre-flight check on the exact payload about to be sent. With an exact tokenizer an
+    /// oversized request fails here instead of at the provider; estimates only warn.
+    async fn check_context_budget(
+        &self,
+        context: &ExecutionContext,
+        model: &str,
+        ai_messages: &[AIMessage],
+        tools: Option<&[ToolDefinition]>,
+        context_window: usize,
+    ) -> BitFunResult<()> {
+        let estimated_tokens = estimate_tokens(model, ai_messages, tools);
+        if estimated_tokens <= context_window {
+            return Ok(());
+        }
+
+        if TokenizerKind::for_model(model).is_exact() {
+            return Err(BitFunError::AIClient(format!(
+                "Request needs {} tokens, exceeding the {}-token context window of model {}",
+                estimated_tokens, context_window, model
+            )));
+        }
+
+        warn!(
+            "Request may exceed context window: session={}, model={}, estimated_tokens={}, context_window={}",
+            context.session_id, model, estimated_tokens, context_window
+        );
+        self.emit_event(
+            AgenticEvent::ContextWindowWarning {
+                session_id: context.session_id.clone(),
+                turn_id: context.dialog_turn_id.clone(),
+                model: model.to_string(),
+                estimated_tokens,
+                context_window,
+                subagent_parent_info: context.subagent_parent_info.clone().map(|info| info.into()),
+            },
+            EventPriority::High,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Enforce spend guardrails before a model round: stop once today's spend hits the hard
+    /// limit, warn once a day past the warning threshold, and wait for the user when the
+    /// round's estimated prompt cost exceeds the per-turn threshold.
+    async fn check_spend_guardrails(
+        &self,
+        context: &ExecutionContext,
+        ai_client: &AIClient,
+        guardrails: &SpendGuardrailsConfig,
+        ai_messages: &[AIMessage],
+        tools: Option<&[ToolDefinition]>,
+        spend_confirmed: &mut bool,
+    ) -> BitFunResult<()> {
+        let summary = match get_global_token_usage_service() {
+            Some(service) => Some(service.get_spend_summary(None, None).await),
+            None => None,
+        };
+        let spent_today_usd = summary.as_ref().map_or(0.0, |s| s.today_usd);
+        let estimated_turn_usd = ai_client.config.pricing.as_ref().map(|pricing| {
+            let prompt_tokens = estimate_tokens(&ai_client.config.model, ai_messages, tools);
+            pricing.cost_usd(prompt_tokens.min(u32::MAX as usize) as u32, 0, 0)
+        });
+
+        let check = check_spend(guardrails, spent_today_usd, estimated_turn_usd);
+
+        if let Some(limit) = check.stop_at {
+            return Err(BitFunError::Validation(format!(
+                "Daily spend ${:.2} reached the hard stop of ${:.2}",
+                spent_today_usd, limit
+            )));
+        }
+
+        if let (Some(threshold_usd), Some(summary)) = (check.warn_at, summary.as_ref()) {
+            let first_today = {
+                let mut warned_on = self
+                    .spend_warned_on
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                let first = warned_on.as_deref() != Some(summary.date.as_str());
+                *warned_on = Some(summary.date.clone());
+                first
+            };
+            if first_today {
+                warn!(
+                    "Daily spend reached warning threshold: spent_today_usd={:.4}, threshold_usd={:.2}",
+                    spent_today_usd, threshold_usd
+                );
+                self.emit_event(
+                    AgenticEvent::SpendWarning {
+                        session_id: context.session_id.clone(),
+                        turn_id: context.dialog_turn_id.clone(),
+                        spent_today_usd,
+                        threshold_usd,
+                    },
+                    EventPriority::High,
+                )
+                .await;
+            }
+        }
+
+        let (Some(threshold_usd), Some(estimated_cost_usd)) =
+            (check.confirm_at, estimated_turn_usd)
+        else {
+            return Ok(());
+        };
+        if *spend_confirmed {
+            return Ok(());
+        }
+        if context.skip_tool_confirmation || context.subagent_parent_info.is_some() {
+            // Nobody is watching an unattended turn to approve it
+            return Err(BitFunError::Validation(format!(
+                "Estimated round cost ${:.2} exceeds the per-turn limit of ${:.2}",
+                estimated_cost_usd, threshold_usd
+            )));
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.spend_confirmations
+            .insert(context.dialog_turn_id.clone(), tx);
+        info!(
+            "Waiting for spend confirmation: session={}, turn={}, estimated_cost_usd={:.4}, threshold_usd={:.2}",
+            context.session_id, context.dialog_turn_id, estimated_cost_usd, threshold_usd
+        );
+        self.emit_event(
+            AgenticEvent::SpendConfirmationRequired {
+                session_id: context.session_id.clone(),
+                turn_id: context.dialog_turn_id.clone(),
+                estimated_cost_usd,
+                threshold_usd,
+            },
+            EventPriority::High,
+        )
+        .await;
+
+        let approved = rx.await.unwrap_or(false);
+        self.spend_confirmations.remove(&context.dialog_turn_id);
+        if !approved {
+            return Err(BitFunError::Cancelled(format!(
+                "Spend of ${:.2} for this turn was not approved",
+                estimated_cost_usd
+            )));
+        }
+
+        *spend_confirmed = true;
+        Ok(())
+    }
+
     /// Emit event
     async fn emit_event(&self, event: AgenticEvent, priority: EventPriority) {
         let _ = self.event_queue.enqueue(event, Some(priority)).await;
     }
 }
 
+/// Budget for the project map appended to the system prompt
+const PROJECT_MAP_MAX_CHARS: usize = 12_000;
+
+/// Project map block priming agents that can use `CodebaseSearch`. Starts the
+/// workspace's code index on first use; until its first build completes the
+/// prompt goes without the map.
+fn project_map_section(workspace: Option<&WorkspaceBinding>) -> Option<String> {
+    let workspace = workspace.filter(|workspace| !workspace.is_remote())?;
+    let index = get_global_code_index_service().workspace(workspace.root_path());
+    index.ensure_started();
+    let map = index.project_map(PROJECT_MAP_MAX_CHARS)?;
+    Some(format!(
+        "\n\n# Project Map\nSource files of the workspace with their leading doc comment and top-level definitions (`kind name:line`). Use CodebaseSearch to locate other symbols.\n<project_map>\n{}</project_map>\n",
+        map
+    ))
+}
+
+/// Digest of recently modified files for a session's first turn, when
+/// `ai.recent_changes_context` is enabled for the workspace.
+async fn recent_changes_digest(
+    workspace: Option<&WorkspaceBinding>,
+) -> Option<RecentChangesDigest> {
+    let workspace = workspace.filter(|workspace| !workspace.is_remote())?;
+    let config = get_global_config_service()
+        .await
+        .ok()?
+        .get_config::<RecentChangesContextConfig>(Some("ai.recent_changes_context"))
+        .await
+        .unwrap_or_default();
+    if !config.enabled {
+        return None;
+    }
+    collect_recent_changes(workspace.root_path(), &config).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::ExecutionEngine;
@@ -0,0 +1,344 @@
+//! Loop guard
+//!
+//! Fingerprints the tool calls of every model round and notices when an agent
+//! keeps making the same calls: one round repeated back to back, or a short
+//! cycle of rounds (e.g. edit, test, revert, edit, test, revert). The engine
+//! first reminds the model, then ends the turn.
+
+use crate::agentic::core::ToolCall;
+use crate::service::config::types::LoopGuardConfig;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// What the engine should do after a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopVerdict {
+    Continue,
+    /// Remind the model that it is repeating itself.
+    Nudge(LoopReport),
+    /// End the turn.
+    Stop(LoopReport),
+}
+
+/// A detected repetition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopReport {
+    /// Rounds per cycle; 1 when the same round repeats.
+    pub cycle_length: usize,
+    /// Consecutive repetitions of the cycle.
+    pub repetitions: usize,
+    /// Tools called in the repeated rounds, in order.
+    pub tool_names: Vec<String>,
+}
+
+impl LoopReport {
+    pub fn describe(&self) -> String {
+        if self.cycle_length == 1 {
+            format!(
+                "made the same {} call with the same input {} times in a row",
+                self.tool_names.join(", "),
+                self.repetitions
+            )
+        } else {
+            format!(
+                "repeated the same cycle of {} rounds ({}) {} times",
+                self.cycle_length,
+                self.tool_names.join(" -> "),
+                self.repetitions
+            )
+        }
+    }
+}
+
+struct RoundFingerprint {
+    hash: u64,
+    tool_names: Vec<String>,
+}
+
+/// Tracks the rounds of one dialog turn.
+pub struct LoopGuard {
+    config: LoopGuardConfig,
+    rounds: Vec<RoundFingerprint>,
+    /// Whether the current loop has already been nudged
+    nudged: bool,
+}
+
+impl LoopGuard {
+    pub fn new(config: LoopGuardConfig) -> Self {
+        Self {
+            config,
+            rounds: Vec::new(),
+            nudged: false,
+        }
+    }
+
+    /// Records the tool calls of a finished round.
+    pub fn record_round(&mut self, tool_calls: &[ToolCall]) -> LoopVerdict {
+        if !self.config.enabled || tool_calls.is_empty() {
+            return LoopVerdict::Continue;
+        }
+        self.rounds.push(fingerprint(tool_calls));
+
+        let Some(report) = self.detect() else {
+            self.nudged = false;
+            return LoopVerdict::Continue;
+        };
+        if report.repetitions >= self.config.stop_after.max(1) {
+            LoopVerdict::Stop(report)
+        } else if report.repetitions >= self.config.nudge_after.max(1) {
+            if self.nudged {
+                LoopVerdict::Continue
+            } else {
+                self.nudged = true;
+                LoopVerdict::Nudge(report)
+            }
+        } else {
+            self.nudged = false;
+            LoopVerdict::Continue
+        }
+    }
+
+    /// The cycle with the most trailing repetitions (shorter cycles win ties).
+    fn detect(&self) -> Option<LoopReport> {
+        let count = self.rounds.len();
+        let mut best: Option<LoopReport> = None;
+        for period in 1..=self.config.max_cycle_length.max(1) {
+            if period * 2 > count {
+                break;
+            }
+            let matching = (period..count)
+                .rev()
+                .take_while(|&i| self.rounds[i].hash == self.rounds[i - period].hash)
+                .count();
+            let repetitions = (matching + period) / period;
+            if repetitions < 2 || best.as_ref().is_some_and(|b| b.repetitions >= repetitions) {
+                continue;
+            }
+            best = Some(LoopReport {
+                cycle_length: period,
+                repetitions,
+                tool_names: self.rounds[count - period..]
+                    .iter()
+                    .flat_map(|round| round.tool_names.iter().cloned())
+                    .collect(),
+            });
+        }
+        best
+    }
+}
+
+/// Hash of a round's calls; call order and object key order don't matter.
+fn fingerprint(tool_calls: &[ToolCall]) -> RoundFingerprint {
+    let mut calls: Vec<String> = tool_calls
+        .iter()
+        .map(|call| {
+            let mut key = call.tool_name.clone();
+            key.push('\0');
+            write_canonical(&call.arguments, &mut key);
+            key
+        })
+        .collect();
+    calls.sort();
+    let mut hasher = DefaultHasher::new();
+    calls.hash(&mut hasher);
+
+    RoundFingerprint {
+        hash: hasher.finish(),
+        tool_names: tool_calls
+            .iter()
+            .map(|call| call.tool_name.clone())
+            .collect(),
+    }
+}
+
+/// Serializes `value` with sorted object keys, leaving out null fields.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().filter(|(_, v)| !v.is_null()).collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(tool_name: &str, arguments: Value) -> ToolCall {
+        ToolCall {
+            tool_id: uuid::Uuid::new_v4().to_string(),
+            tool_name: tool_name.to_string(),
+            arguments,
+            is_error: false,
+            repaired: false,
+            argument_error: None,
+        }
+    }
+
+    fn guard() -> LoopGuard {
+        LoopGuard::new(LoopGuardConfig {
+            enabled: true,
+            nudge_after: 3,
+            stop_after: 5,
+            max_cycle_length: 3,
+        })
+    }
+
+    fn run(guard: &mut LoopGuard, rounds: Vec<Vec<ToolCall>>) -> Vec<LoopVerdict> {
+        rounds
+            .iter()
+            .map(|calls| guard.record_round(calls))
+            .collect()
+    }
+
+    #[test]
+    fn nudges_once_then_stops_on_identical_calls() {
+        let mut guard = guard();
+        let read = || vec![call("Read", json!({ "file_path": "/ws/a.rs" }))];
+        let verdicts = run(&mut guard, vec![read(); 5]);
+
+        assert_eq!(
+            verdicts[..2],
+            [LoopVerdict::Continue, LoopVerdict::Continue]
+        );
+        let LoopVerdict::Nudge(report) = &verdicts[2] else {
+            panic!("expected a nudge, got {:?}", verdicts[2]);
+        };
+        assert_eq!(report.cycle_length, 1);
+        assert_eq!(report.repetitions, 3);
+        assert_eq!(verdicts[3], LoopVerdict::Continue);
+        assert!(matches!(&verdicts[4], LoopVerdict::Stop(r) if r.repetitions == 5));
+    }
+
+    #[test]
+    fn normalizes_key_order_and_null_fields() {
+        let mut guard = guard();
+        let verdicts = run(
+            &mut guard,
+            vec![
+                vec![call("Grep", json!({ "pattern": "foo", "path": "src" }))],
+                vec![call("Grep", json!({ "path": "src", "pattern": "foo" }))],
+                vec![call(
+                    "Grep",
+                    json!({ "pattern": "foo", "path": "src", "glob": null }),
+                )],
+            ],
+        );
+        assert!(matches!(verdicts[2], LoopVerdict::Nudge(_)));
+    }
+
+    #[test]
+    fn different_inputs_are_not_a_loop() {
+        let mut guard = guard();
+        let rounds: Vec<_> = (0..8)
+            .map(|i| {
+                vec![call(
+                    "Read",
+                    json!({ "file_path": format!("/ws/{}.rs", i) }),
+                )]
+            })
+            .collect();
+        assert!(run(&mut guard, rounds)
+            .iter()
+            .all(|verdict| *verdict == LoopVerdict::Continue));
+    }
+
+    #[test]
+    fn detects_short_cycles() {
+        let mut guard = guard();
+        let edit = || {
+            vec![call(
+                "Edit",
+                json!({ "file_path": "a.rs", "old": "x", "new": "y" }),
+            )]
+        };
+        let revert = || {
+            vec![call(
+                "Edit",
+                json!({ "file_path": "a.rs", "old": "y", "new": "x" }),
+            )]
+        };
+        let rounds: Vec<_> = (0..10)
+            .map(|i| if i % 2 == 0 { edit() } else { revert() })
+            .collect();
+        let verdicts = run(&mut guard, rounds);
+
+        let first_nudge = verdicts
+            .iter()
+            .position(|verdict| matches!(verdict, LoopVerdict::Nudge(_)))
+            .unwrap();
+        assert_eq!(first_nudge, 5);
+        let LoopVerdict::Stop(report) = &verdicts[9] else {
+            panic!("expected a stop, got {:?}", verdicts[9]);
+        };
+        assert_eq!(report.cycle_length, 2);
+        assert_eq!(report.tool_names, ["Edit", "Edit"]);
+        assert!(report.describe().contains("cycle of 2 rounds"));
+    }
+
+    #[test]
+    fn progress_resets_the_nudge() {
+        let mut guard = guard();
+        let test = || vec![call("Bash", json!({ "command": "cargo test" }))];
+        let mut rounds = vec![test(); 3];
+        rounds.push(vec![call("Edit", json!({ "file_path": "a.rs" }))]);
+        rounds.extend(vec![test(); 3]);
+        let verdicts = run(&mut guard, rounds);
+
+        assert!(matches!(verdicts[2], LoopVerdict::Nudge(_)));
+        assert_eq!(verdicts[3], LoopVerdict::Continue);
+        assert!(matches!(verdicts[6], LoopVerdict::Nudge(_)));
+    }
+
+    #[test]
+    fn parallel_calls_ignore_order() {
+        let mut guard = guard();
+        let a = call("Read", json!({ "file_path": "a" }));
+        let b = call("Read", json!({ "file_path": "b" }));
+        let verdicts = run(
+            &mut guard,
+            vec![
+                vec![a.clone(), b.clone()],
+                vec![b.clone(), a.clone()],
+                vec![a, b],
+            ],
+        );
+        assert!(matches!(verdicts[2], LoopVerdict::Nudge(_)));
+    }
+
+    #[test]
+    fn disabled_guard_never_intervenes() {
+        let mut guard = LoopGuard::new(LoopGuardConfig {
+            enabled: false,
+            ..LoopGuardConfig::default()
+        });
+        let read = || vec![call("Read", json!({ "file_path": "a" }))];
+        assert!(run(&mut guard, vec![read(); 10])
+            .iter()
+            .all(|verdict| *verdict == LoopVerdict::Continue));
+    }
+}
@@ -3,11 +3,14 @@
 //! Responsible for AI interaction and model round control
 
 pub mod execution_engine;
+pub mod loop_guard;
+pub mod recent_changes;
 pub mod round_executor;
 pub mod stream_processor;
 pub mod types;
 
 pub use execution_engine::*;
+pub use loop_guard::{LoopGuard, LoopReport, LoopVerdict};
 pub use round_executor::*;
 pub use stream_processor::*;
 pub use types::{ExecutionContext, ExecutionResult, FinishReason, RoundContext, RoundResult};
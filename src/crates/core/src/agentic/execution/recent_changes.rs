@@ -0,0 +1,236 @@
+//! Recent changes context
+//!
+//! Starting a session about "the bug I just introduced" should not require pointing the agent
+//! at files. For the first turn of a session, a digest of recently modified workspace files
+//! (path, change type and a short diff excerpt) is added to the system prompt. Git state comes
+//! from the startchat agent's work state analysis; the file watcher contributes the newest
+//! edits first, including in workspaces without git.
+
+use crate::function_agents::startchat_func_agent::{FileChangeType, WorkStateAnalyzer};
+use crate::infrastructure::filesystem::file_watcher::{
+    get_global_file_watcher, FileWatchEventKind,
+};
+use crate::service::config::types::RecentChangesContextConfig;
+use crate::util::token_counter::TokenCounter;
+use log::debug;
+use std::path::Path;
+
+const DIGEST_HEADER: &str = "\n\n# Recent Changes\nFiles the user changed recently in this workspace, newest first. The request may be about this work in progress; check these files before looking elsewhere.\n<recent_changes>\n";
+const DIGEST_FOOTER: &str = "</recent_changes>\n";
+
+/// A changed file as listed in the digest
+#[derive(Debug, Clone, PartialEq)]
+struct RecentChange {
+    /// Relative to the workspace root
+    path: String,
+    /// `Modified`, `Untracked`, ...
+    change_type: String,
+    excerpt: Option<String>,
+}
+
+/// System prompt section describing the user's recent changes
+#[derive(Debug, Clone)]
+pub struct RecentChangesDigest {
+    /// Listed files, relative to the workspace root
+    pub files: Vec<String>,
+    pub text: String,
+    pub estimated_tokens: usize,
+}
+
+/// Digest of the files changed recently below `root`; `None` when nothing changed.
+pub async fn collect_recent_changes(
+    root: &Path,
+    config: &RecentChangesContextConfig,
+) -> Option<RecentChangesDigest> {
+    let git_files = WorkStateAnalyzer::analyze_git_state(root)
+        .await
+        .map(|state| state.modified_files)
+        .unwrap_or_default();
+    let since = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .saturating_sub(config.lookback_minutes.saturating_mul(60));
+
+    // Watcher events come first: they are ordered by recency, git status is not
+    let mut changes: Vec<RecentChange> = Vec::new();
+    for event in get_global_file_watcher().recent_changes(root, since) {
+        let Ok(relative) = Path::new(&event.path).strip_prefix(root) else {
+            continue;
+        };
+        let path = relative.to_string_lossy().replace('\\', "/");
+        if path.is_empty() || path == ".git" || path.starts_with(".git/") {
+            continue;
+        }
+        if root.join(relative).is_dir() {
+            continue;
+        }
+        let change_type = match git_files.iter().find(|file| file.path == path) {
+            Some(file) => file.change_type.to_string(),
+            None => match event.kind {
+                FileWatchEventKind::Create => "Created".to_string(),
+                FileWatchEventKind::Modify => "Modified".to_string(),
+                FileWatchEventKind::Remove => "Deleted".to_string(),
+                FileWatchEventKind::Rename { .. } => "Renamed".to_string(),
+                FileWatchEventKind::Other => continue,
+            },
+        };
+        changes.push(RecentChange {
+            path,
+            change_type,
+            excerpt: None,
+        });
+    }
+    for file in &git_files {
+        // Renames are reported as `old -> new`
+        let path = file
+            .path
+            .rsplit(" -> ")
+            .next()
+            .unwrap_or(&file.path)
+            .trim_matches('"')
+            .to_string();
+        if !changes.iter().any(|change| change.path == path) {
+            changes.push(RecentChange {
+                path,
+                change_type: file.change_type.to_string(),
+                excerpt: None,
+            });
+        }
+    }
+    changes.truncate(config.max_files);
+
+    for change in changes.iter_mut() {
+        let excerpt = if change.change_type == FileChangeType::Deleted.to_string() {
+            None
+        } else if change.change_type == FileChangeType::Untracked.to_string()
+            || change.change_type == "Created"
+        {
+            file_head(&root.join(&change.path), config.max_excerpt_chars).await
+        } else {
+            WorkStateAnalyzer::get_file_diff(root, &change.path).ok()
+        };
+        change.excerpt = excerpt
+            .filter(|excerpt| !excerpt.trim().is_empty())
+            .map(|excerpt| truncate_chars(excerpt.trim_end(), config.max_excerpt_chars));
+    }
+
+    let digest = render_digest(&changes, config.token_budget);
+    if let Some(digest) = &digest {
+        debug!(
+            "Recent changes digest built: root={}, files={}, estimated_tokens={}",
+            root.display(),
+            digest.files.len(),
+            digest.estimated_tokens
+        );
+    }
+    digest
+}
+
+/// Start of a new file's text content; `None` for unreadable or binary files.
+async fn file_head(path: &Path, max_chars: usize) -> Option<String> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    // Up to 4 bytes per char; a cut inside a multi-byte char is dropped by from_utf8_lossy
+    let head = &bytes[..bytes.len().min(max_chars.saturating_mul(4))];
+    if head.contains(&0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(head).into_owned())
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}\n... (truncated)", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+/// Lists `changes` in order while they fit `token_budget`; an excerpt that doesn't fit is left
+/// out but its file is still listed when the line alone fits.
+fn render_digest(changes: &[RecentChange], token_budget: usize) -> Option<RecentChangesDigest> {
+    let mut text = DIGEST_HEADER.to_string();
+    let mut used =
+        TokenCounter::estimate_tokens(DIGEST_HEADER) + TokenCounter::estimate_tokens(DIGEST_FOOTER);
+    let mut files = Vec::new();
+
+    for change in changes {
+        let line = format!("- `{}` ({})\n", change.path, change.change_type);
+        let line_tokens = TokenCounter::estimate_tokens(&line);
+        if used + line_tokens > token_budget {
+            break;
+        }
+        used += line_tokens;
+        text.push_str(&line);
+        files.push(change.path.clone());
+
+        if let Some(excerpt) = &change.excerpt {
+            let block = format!("```\n{}\n```\n", excerpt);
+            let block_tokens = TokenCounter::estimate_tokens(&block);
+            if used + block_tokens <= token_budget {
+                used += block_tokens;
+                text.push_str(&block);
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return None;
+    }
+    text.push_str(DIGEST_FOOTER);
+    Some(RecentChangesDigest {
+        files,
+        text,
+        estimated_tokens: used,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_digest, truncate_chars, RecentChange};
+
+    fn change(path: &str, excerpt: Option<&str>) -> RecentChange {
+        RecentChange {
+            path: path.to_string(),
+            change_type: "Modified".to_string(),
+            excerpt: excerpt.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn digest_lists_files_in_order_with_excerpts() {
+        let digest = render_digest(
+            &[
+                change("src/a.rs", Some("-old\n+new")),
+                change("src/b.rs", None),
+            ],
+            2000,
+        )
+        .expect("digest");
+
+        assert_eq!(digest.files, vec!["src/a.rs", "src/b.rs"]);
+        let a_at = digest.text.find("- `src/a.rs` (Modified)").unwrap();
+        let b_at = digest.text.find("- `src/b.rs` (Modified)").unwrap();
+        assert!(a_at < b_at);
+        assert!(digest.text.contains("```\n-old\n+new\n```"));
+        assert!(digest.text.ends_with("</recent_changes>\n"));
+    }
+
+    #[test]
+    fn digest_drops_excerpts_and_files_beyond_token_budget() {
+        let large = "x".repeat(4000);
+        let changes = [change("src/a.rs", Some(&large)), change("src/b.rs", None)];
+        let digest = render_digest(&changes, 200).expect("digest");
+        assert_eq!(digest.files, vec!["src/a.rs", "src/b.rs"]);
+        assert!(!digest.text.contains(&large));
+        assert!(digest.estimated_tokens <= 200);
+
+        assert!(render_digest(&changes, 10).is_none());
+        assert!(render_digest(&[], 2000).is_none());
+    }
+
+    #[test]
+    fn truncates_excerpts_at_char_boundary() {
+        assert_eq!(truncate_chars("héllo", 2), "hé\n... (truncated)");
+        assert_eq!(truncate_chars("hi", 5), "hi");
+    }
+}
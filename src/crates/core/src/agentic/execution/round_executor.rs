@@ -4,18 +4,20 @@
 
 use super::stream_processor::StreamProcessor;
 use super::types::{FinishReason, RoundContext, RoundResult};
-use crate::agentic::core::Message;
+use crate::agentic::core::{Message, ToolResult};
 use crate::agentic::events::{AgenticEvent, EventPriority, EventQueue};
 use crate::agentic::tools::computer_use_host::ComputerUseHostRef;
 use crate::agentic::tools::pipeline::{ToolExecutionContext, ToolExecutionOptions, ToolPipeline};
 use crate::agentic::tools::registry::get_global_tool_registry;
+use crate::agentic::turn_cancellation::{
+    ensure_turn_scope, insert_turn_scope, remove_turn_scope, turn_scope,
+};
 use crate::agentic::MessageContent;
-use crate::infrastructure::ai::AIClient;
+use crate::infrastructure::ai::{AIClient, StreamRequestOptions};
 use crate::service::config::GlobalConfigManager;
 use crate::util::errors::{BitFunError, BitFunResult};
 use crate::util::types::Message as AIMessage;
 use crate::util::types::ToolDefinition;
-use dashmap::DashMap;
 use log::{debug, error, warn};
 use std::sync::Arc;
 use std::time::Duration;
@@ -26,11 +28,11 @@ pub struct RoundExecutor {
     stream_processor: Arc<StreamProcessor>,
     tool_pipeline: Option<Arc<ToolPipeline>>,
     event_queue: Arc<EventQueue>,
-    /// Cancellation tokens: use dialog_turn_id as key
-    cancellation_tokens: Arc<DashMap<String, CancellationToken>>,
 }
 
 impl RoundExecutor {
+    /// Re-sends when the stream fails before any output; failed requests are
+    /// retried by the AI client, not here
     const MAX_RETRIES_WITHOUT_OUTPUT: usize = 1;
     const RETRY_BASE_DELAY_MS: u64 = 500;
 
@@ -43,7 +45,6 @@ impl RoundExecutor {
             stream_processor,
             tool_pipeline: Some(tool_pipeline),
             event_queue,
-            cancellation_tokens: Arc::new(DashMap::new()),
         }
     }
 
@@ -68,19 +69,10 @@ impl RoundExecutor {
 
         let round_id = uuid::Uuid::new_v4().to_string();
 
-        // Create or reuse cancellation token
-        let cancel_token = if let Some(existing_token) = self
-            .cancellation_tokens
-            .get(&context.dialog_turn_id.clone())
-        {
-            existing_token.clone()
-        } else {
-            // Create new token
-            let new_token = CancellationToken::new();
-            self.cancellation_tokens
-                .insert(context.dialog_turn_id.clone(), new_token.clone());
-            new_token
-        };
+        // Root token of the turn; the model stream runs as one of its children
+        let scope = ensure_turn_scope(&context.session_id, &context.dialog_turn_id);
+        let cancel_token = scope.token().clone();
+        let stream_child = scope.register(format!("model stream (round {})", round_id));
 
         // Emit model round started event
         self.emit_event(
@@ -97,7 +89,7 @@ impl RoundExecutor {
 
         let max_attempts = Self::MAX_RETRIES_WITHOUT_OUTPUT + 1;
         let mut attempt_index = 0usize;
-        let stream_result = loop {
+        let (stream_result, served_by_model) = loop {
             debug!(
                 "Sending request: model={}, messages={}, tools={}, attempt={}/{}",
                 context.model_name,
@@ -108,38 +100,40 @@ impl RoundExecutor {
             );
 
             // Use dynamically obtained client for call
+            let request_options = StreamRequestOptions {
+                session_id: Some(context.session_id.clone()),
+                dialog_turn_id: Some(context.dialog_turn_id.clone()),
+                cancel_token: Some(stream_child.token().clone()),
+                ..Default::default()
+            };
             let stream_response = match ai_client
-                .send_message_stream(ai_messages.clone(), tool_definitions.clone())
+                .send_message_stream_with_options(
+                    ai_messages.clone(),
+                    tool_definitions.clone(),
+                    request_options,
+                )
                 .await
             {
                 Ok(response) => response,
                 Err(e) => {
-                    error!("AI request failed: {}", e);
-                    let err_msg = e.to_string();
-                    let can_retry = attempt_index < max_attempts - 1
-                        && Self::is_transient_network_error(&err_msg);
-                    if can_retry {
-                        let delay_ms = Self::retry_delay_ms(attempt_index);
-                        warn!(
-                            "Retrying request after transient error with no output: session_id={}, round_id={}, attempt={}/{}, delay_ms={}, error={}",
-                            context.session_id,
-                            round_id,
-                            attempt_index + 1,
-                            max_attempts,
-                            delay_ms,
-                            err_msg
+                    if cancel_token.is_cancelled() {
+                        debug!(
+                            "AI request aborted by cancellation: session_id={}",
+                            context.session_id
                         );
-                        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-                        attempt_index += 1;
-                        continue;
+                        return Err(BitFunError::Cancelled("Execution cancelled".to_string()));
                     }
-                    return Err(BitFunError::AIClient(err_msg));
+                    // The client already retried the request (and walked the fallback chain)
+                    error!("AI request failed: {}", e);
+                    return Err(BitFunError::from_ai_error(e));
                 }
             };
 
             // Destructure StreamResponse: get stream and raw SSE data receiver
             let ai_stream = stream_response.stream;
             let raw_sse_rx = stream_response.raw_sse_rx;
+            // Set when a fallback model served the round instead of the configured one
+            let served_by_model = stream_response.served_by_model;
 
             // Check cancellation token before calling stream processing
             if cancel_token.is_cancelled() {
@@ -168,7 +162,7 @@ impl RoundExecutor {
                     context.dialog_turn_id.clone(),
                     round_id.clone(),
                     subagent_parent_info.clone(),
-                    &cancel_token,
+                    stream_child.token(),
                 )
                 .await
             {
@@ -188,13 +182,12 @@ impl RoundExecutor {
                         attempt_index += 1;
                         continue;
                     }
-                    break result;
+                    break (result, served_by_model);
                 }
                 Err(stream_err) => {
-                    let err_msg = stream_err.error.to_string();
                     let can_retry = !stream_err.has_effective_output
                         && attempt_index < max_attempts - 1
-                        && Self::is_transient_network_error(&err_msg);
+                        && stream_err.error.is_retryable();
                     if can_retry {
                         let delay_ms = Self::retry_delay_ms(attempt_index);
                         warn!(
@@ -204,7 +197,7 @@ impl RoundExecutor {
                             attempt_index + 1,
                             max_attempts,
                             delay_ms,
-                            err_msg
+                            stream_err.error
                         );
                         tokio::time::sleep(Duration::from_millis(delay_ms)).await;
                         attempt_index += 1;
@@ -215,6 +208,8 @@ impl RoundExecutor {
             }
         };
 
+        drop(stream_child);
+
         // Model returned successfully (output to AI log file)
         let tool_names: Vec<&str> = stream_result
             .tool_calls
@@ -254,7 +249,21 @@ impl RoundExecutor {
                     output_tokens: Some(usage.candidates_token_count as usize),
                     total_tokens: usage.total_token_count as usize,
                     max_context_tokens: context_window,
+                    cached_tokens: usage.cached_content_token_count.map(|n| n as usize),
                     is_subagent,
+                    cost_usd: ai_client
+                        .pricing_for(served_by_model.as_deref())
+                        .map(|pricing| {
+                            pricing.cost_usd(
+                                usage.prompt_token_count,
+                                usage.candidates_token_count,
+                                usage.cached_content_token_count.unwrap_or(0),
+                            ) + pricing.web_search_cost_usd(usage.web_search_requests.unwrap_or(0))
+                        }),
+                    workspace_path: context
+                        .workspace
+                        .as_ref()
+                        .map(|workspace| workspace.root_path_string()),
                 },
                 EventPriority::Normal,
             )
@@ -274,6 +283,7 @@ impl RoundExecutor {
                 turn_id: context.dialog_turn_id.clone(),
                 round_id: round_id.clone(),
                 has_tool_calls: !stream_result.tool_calls.is_empty(),
+                served_by_model,
                 subagent_parent_info: event_subagent_parent_info.clone(),
             },
             EventPriority::High,
@@ -299,7 +309,8 @@ impl RoundExecutor {
             )
             .with_turn_id(context.dialog_turn_id.clone())
             .with_round_id(round_id.clone())
-            .with_thinking_signature(stream_result.thinking_signature.clone());
+            .with_thinking_signature(stream_result.thinking_signature.clone())
+            .with_server_tool_events(stream_result.server_tool_events.clone());
 
             debug!("Returning RoundResult: has_more_rounds=false");
 
@@ -332,7 +343,29 @@ impl RoundExecutor {
             stream_result.tool_calls.len()
         );
 
-        let tool_results = if let Some(tool_pipeline) = &self.tool_pipeline {
+        let tool_results = if context.skip_tool_execution {
+            debug!(
+                "Skipping tool execution for final round: session_id={}, tool_calls={}",
+                context.session_id,
+                stream_result.tool_calls.len()
+            );
+            stream_result
+                .tool_calls
+                .iter()
+                .map(|tool_call| {
+                    let error = "Not run: tools are disabled for the final response of this turn";
+                    ToolResult {
+                        tool_id: tool_call.tool_id.clone(),
+                        tool_name: tool_call.tool_name.clone(),
+                        result: serde_json::json!({ "error": error }),
+                        result_for_assistant: Some(error.to_string()),
+                        is_error: true,
+                        duration_ms: None,
+                        image_attachments: None,
+                    }
+                })
+                .collect()
+        } else if let Some(tool_pipeline) = &self.tool_pipeline {
             // Create tool execution context
             let tool_context = ToolExecutionContext {
                 session_id: context.session_id.clone(),
@@ -374,7 +407,15 @@ impl RoundExecutor {
                     .map(|v| v == "true")
                     .unwrap_or(false);
 
-                let needs_confirm = if skip_confirmation || skip_from_context {
+                let require_from_context = context
+                    .context_vars
+                    .get("require_tool_confirmation")
+                    .map(|v| v == "true")
+                    .unwrap_or(false);
+
+                let skip = !require_from_context && (skip_confirmation || skip_from_context);
+
+                let needs_confirm = if skip {
                     false
                 } else {
                     // Otherwise judge based on tool's needs_permissions()
@@ -429,7 +470,8 @@ impl RoundExecutor {
         )
         .with_turn_id(context.dialog_turn_id.clone())
         .with_round_id(round_id.clone())
-        .with_thinking_signature(stream_result.thinking_signature.clone());
+        .with_thinking_signature(stream_result.thinking_signature.clone())
+        .with_server_tool_events(stream_result.server_tool_events.clone());
 
         debug!(
             "Tool execution completed, creating message: assistant_msg_len={}, tool_results={}",
@@ -480,24 +522,37 @@ impl RoundExecutor {
     }
 
     /// Check if dialog turn is still active (used to detect cancellation)
-    pub fn has_active_dialog_turn(&self, dialog_turn_id: &str) -> bool {
-        self.cancellation_tokens.contains_key(dialog_turn_id)
+    pub fn has_active_dialog_turn(&self, session_id: &str, dialog_turn_id: &str) -> bool {
+        turn_scope(session_id, dialog_turn_id).is_some_and(|scope| !scope.is_cancelled())
     }
 
     /// Register cancellation token (for external control, e.g., execute_subagent)
-    pub fn register_cancel_token(&self, dialog_turn_id: &str, token: CancellationToken) {
-        self.cancellation_tokens
-            .insert(dialog_turn_id.to_string(), token);
+    pub fn register_cancel_token(
+        &self,
+        session_id: &str,
+        dialog_turn_id: &str,
+        token: CancellationToken,
+    ) {
+        insert_turn_scope(session_id, dialog_turn_id, token);
     }
 
     /// Cancel dialog turn (using dialog_turn_id)
-    pub async fn cancel_dialog_turn(&self, dialog_turn_id: &str) -> BitFunResult<()> {
-        debug!("Cancelling dialog turn: dialog_turn_id={}", dialog_turn_id);
+    ///
+    /// The scope stays registered until the turn ends so an abort can wait
+    /// for the turn's work to stop.
+    pub async fn cancel_dialog_turn(
+        &self,
+        session_id: &str,
+        dialog_turn_id: &str,
+    ) -> BitFunResult<()> {
+        debug!(
+            "Cancelling dialog turn: session_id={}, dialog_turn_id={}",
+            session_id, dialog_turn_id
+        );
 
-        if let Some((_, token)) = self.cancellation_tokens.remove(dialog_turn_id) {
-            debug!("Found cancel token, triggering cancellation");
-            token.cancel();
-            debug!("Cancel token triggered and cleaned up");
+        if let Some(scope) = turn_scope(session_id, dialog_turn_id) {
+            scope.cancel();
+            debug!("Cancel token triggered");
         } else {
             debug!("Cancel token not found (dialog may have completed or not started)");
         }
@@ -505,9 +560,9 @@ impl RoundExecutor {
         Ok(())
     }
 
-    /// Cleanup dialog turn token (called on normal completion)
-    pub async fn cleanup_dialog_turn(&self, dialog_turn_id: &str) {
-        if self.cancellation_tokens.remove(dialog_turn_id).is_some() {
+    /// Cleanup dialog turn token (called when the turn ends)
+    pub async fn cleanup_dialog_turn(&self, session_id: &str, dialog_turn_id: &str) {
+        if remove_turn_scope(session_id, dialog_turn_id).is_some() {
             debug!("Cleaned up cancel token: dialog_turn_id={}", dialog_turn_id);
         }
     }
@@ -520,86 +575,33 @@ impl RoundExecutor {
     fn retry_delay_ms(attempt_index: usize) -> u64 {
         Self::RETRY_BASE_DELAY_MS * (1u64 << attempt_index.min(3))
     }
-
-    fn is_transient_network_error(error_message: &str) -> bool {
-        let msg = error_message.to_lowercase();
-
-        let non_retryable_keywords = [
-            "invalid api key",
-            "unauthorized",
-            "forbidden",
-            "model not found",
-            "unsupported model",
-            "invalid request",
-            "bad request",
-            "prompt is too long",
-            "content policy",
-            "proxy authentication required",
-            "client error 400",
-            "client error 401",
-            "client error 403",
-            "client error 404",
-            "client error 422",
-            "sse parsing error",
-            "schema error",
-            "unknown api format",
-        ];
-
-        let transient_keywords = [
-            "transport error",
-            "error decoding response body",
-            "stream closed before response completed",
-            "stream processing error",
-            "sse stream error",
-            "sse error",
-            "sse timeout",
-            "stream data timeout",
-            "timeout",
-            "connection reset",
-            "broken pipe",
-            "unexpected eof",
-            "connection refused",
-            "temporarily unavailable",
-            "gateway timeout",
-            "proxy",
-            "tunnel",
-            "dns",
-            "network",
-            "econnreset",
-            "econnrefused",
-            "etimedout",
-            "rate limit",
-            "too many requests",
-            "429",
-        ];
-
-        if non_retryable_keywords.iter().any(|k| msg.contains(k)) {
-            return false;
-        }
-
-        transient_keywords.iter().any(|k| msg.contains(k))
-    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RoundExecutor;
+    use crate::util::errors::BitFunError;
 
     #[test]
     fn detects_transient_stream_transport_error() {
         let msg = "Error: Stream processing error: SSE Error: Transport Error: Error decoding response body";
-        assert!(RoundExecutor::is_transient_network_error(msg));
+        assert!(BitFunError::ai(msg).is_retryable());
     }
 
     #[test]
     fn rejects_non_retryable_auth_error() {
         let msg = "OpenAI Streaming API client error 401: unauthorized";
-        assert!(!RoundExecutor::is_transient_network_error(msg));
+        assert!(!BitFunError::ai(msg).is_retryable());
     }
 
     #[test]
     fn rejects_sse_schema_error() {
         let msg = "Stream processing error: SSE data schema error: missing field choices";
-        assert!(!RoundExecutor::is_transient_network_error(msg));
+        assert!(!BitFunError::ai(msg).is_retryable());
+    }
+
+    #[test]
+    fn retries_provider_rate_limits_but_not_rejections() {
+        assert!(BitFunError::ai_status(429, "Too Many Requests").is_retryable());
+        assert!(!BitFunError::ai_status(400, "Bad Request").is_retryable());
     }
 }
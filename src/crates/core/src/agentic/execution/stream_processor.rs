@@ -10,12 +10,14 @@ use crate::agentic::events::{
 use crate::agentic::tools::SubagentParentInfo;
 use crate::util::errors::BitFunError;
 use crate::util::types::ai::GeminiUsage;
-use crate::util::JsonChecker;
-use ai_stream_handlers::UnifiedResponse;
+use crate::util::{repair_truncated_json, JsonChecker};
+use ai_stream_handlers::{UnifiedResponse, UnifiedServerToolEvent};
 use futures::StreamExt;
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 
 //==============================================================================
@@ -111,6 +113,10 @@ impl SseLogCollector {
 struct ToolCallBuffer {
     tool_id: String,
     tool_name: String,
+    /// Provider-side position of the call, when the provider reports one
+    index: Option<usize>,
+    /// Order in which the call was first seen in the stream
+    seq: usize,
     json_checker: JsonChecker,
 }
 
@@ -119,16 +125,12 @@ impl ToolCallBuffer {
         Self {
             tool_id: String::new(),
             tool_name: String::new(),
+            index: None,
+            seq: 0,
             json_checker: JsonChecker::new(),
         }
     }
 
-    fn reset(&mut self) {
-        self.tool_id.clear();
-        self.tool_name.clear();
-        self.json_checker.reset();
-    }
-
     fn append(&mut self, s: &str) {
         self.json_checker.append(s);
     }
@@ -137,15 +139,43 @@ impl ToolCallBuffer {
         self.json_checker.is_valid()
     }
 
+    /// Parse the accumulated arguments: strict first, then lenient repair. Arguments that stay
+    /// unparseable yield an error call, which the tool pipeline answers with an error result
+    /// asking the model to re-issue it.
     fn to_tool_call(&self) -> ToolCall {
-        let arguments = serde_json::from_str(&self.json_checker.get_buffer());
-        let is_error = arguments.is_err();
-        ToolCall {
+        let raw = self.json_checker.get_buffer();
+        let mut tool_call = ToolCall {
             tool_id: self.tool_id.clone(),
             tool_name: self.tool_name.clone(),
-            arguments: arguments.unwrap_or(json!({})),
-            is_error,
+            arguments: json!({}),
+            is_error: false,
+            repaired: false,
+            argument_error: None,
+        };
+
+        let strict_error = match serde_json::from_str::<Value>(&raw) {
+            Ok(arguments) => {
+                tool_call.arguments = arguments;
+                return tool_call;
+            }
+            Err(e) => e,
+        };
+
+        match repair_truncated_json(&raw).and_then(|fixed| serde_json::from_str(&fixed).ok()) {
+            Some(arguments) => {
+                warn!(
+                    "Repaired malformed tool arguments: tool_name={}, tool_id={}, error={}",
+                    self.tool_name, self.tool_id, strict_error
+                );
+                tool_call.arguments = arguments;
+                tool_call.repaired = true;
+            }
+            None => {
+                tool_call.is_error = true;
+                tool_call.argument_error = Some(strict_error.to_string());
+            }
         }
+        tool_call
     }
 }
 
@@ -161,6 +191,8 @@ pub struct StreamResult {
     pub usage: Option<GeminiUsage>,
     /// Provider-specific metadata captured from the stream tail.
     pub provider_metadata: Option<Value>,
+    /// Tool calls the provider ran itself (web search) and the citations of the answer
+    pub server_tool_events: Vec<UnifiedServerToolEvent>,
     /// Whether this stream produced any user-visible output (text/thinking/tool events)
     pub has_effective_output: bool,
 }
@@ -195,11 +227,17 @@ struct StreamContext {
     thinking_signature: Option<String>,
     full_text: String,
     tool_calls: Vec<ToolCall>,
+    /// `ToolCallBuffer::seq` of each entry in `tool_calls`, kept sorted
+    tool_call_seqs: Vec<usize>,
     usage: Option<GeminiUsage>,
     provider_metadata: Option<Value>,
+    server_tool_events: Vec<UnifiedServerToolEvent>,
+    /// When each server tool call started, by id
+    server_tool_started_at: HashMap<String, Instant>,
 
-    // Current tool call state
-    tool_call_buffer: ToolCallBuffer,
+    // Open tool calls, in the order they started
+    tool_call_buffers: Vec<ToolCallBuffer>,
+    next_tool_call_seq: usize,
 
     // Counters and flags
     text_chunks_count: usize,
@@ -226,9 +264,13 @@ impl StreamContext {
             thinking_signature: None,
             full_text: String::new(),
             tool_calls: Vec::new(),
+            tool_call_seqs: Vec::new(),
             usage: None,
             provider_metadata: None,
-            tool_call_buffer: ToolCallBuffer::new(),
+            server_tool_events: Vec::new(),
+            server_tool_started_at: HashMap::new(),
+            tool_call_buffers: Vec::new(),
+            next_tool_call_seq: 0,
             text_chunks_count: 0,
             thinking_chunks_count: 0,
             thinking_completed_sent: false,
@@ -244,6 +286,7 @@ impl StreamContext {
             tool_calls: self.tool_calls,
             usage: self.usage,
             provider_metadata: self.provider_metadata,
+            server_tool_events: self.server_tool_events,
             has_effective_output: self.has_effective_output,
         }
     }
@@ -252,20 +295,87 @@ impl StreamContext {
         self.has_effective_output
             && !self.full_text.is_empty()
             && self.tool_calls.is_empty()
-            && self.tool_call_buffer.tool_id.is_empty()
+            && self.tool_call_buffers.is_empty()
+    }
+
+    /// Move a call into the results, keeping stream order even when calls complete out of order
+    fn finish_tool_call(&mut self, buffer: ToolCallBuffer) {
+        let position = self.tool_call_seqs.partition_point(|seq| *seq < buffer.seq);
+        self.tool_calls.insert(position, buffer.to_tool_call());
+        self.tool_call_seqs.insert(position, buffer.seq);
     }
 
-    /// Force finish tool_call_buffer, used to handle cases where toolcall parameters are not fully closed
+    /// Force finish open tool calls, used to handle cases where toolcall parameters are not fully closed
     /// E.g., when new toolcall arrives and before returning results
-    fn force_finish_tool_call_buffer(&mut self) {
-        if !self.tool_call_buffer.tool_id.is_empty() {
-            error!("force finish tool_call_buffer: {:?}", self.tool_call_buffer);
+    fn force_finish_tool_call_buffers(&mut self) {
+        for buffer in std::mem::take(&mut self.tool_call_buffers) {
+            error!("force finish tool_call_buffer: {:?}", buffer);
             // Add to results even if parameters are incomplete, to avoid dialog turn interruption due to no tool calls
             // Caller can detect is_error=true to mark tool execution error
-            self.tool_calls.push(self.tool_call_buffer.to_tool_call());
-            self.tool_call_buffer.reset();
+            self.finish_tool_call(buffer);
         }
     }
+
+    /// Find the open call a tool-call fragment belongs to, starting a new one when the fragment
+    /// carries an unseen id. Returns the buffer position and whether it was just started, or
+    /// None when the fragment can't be attributed to any call.
+    ///
+    /// Fragments with a provider index are keyed by (index, id), so interleaved parallel calls
+    /// accumulate separately. Without an index, calls are assumed to stream one after another:
+    /// a new id closes the previous call and id-less fragments extend the latest one.
+    fn route_tool_call_fragment(
+        &mut self,
+        index: Option<usize>,
+        tool_id: Option<&str>,
+    ) -> Option<(usize, bool)> {
+        match index {
+            Some(index) => {
+                if let Some(pos) = self
+                    .tool_call_buffers
+                    .iter()
+                    .position(|buffer| buffer.index == Some(index))
+                {
+                    match tool_id {
+                        // Index reused for a different call: the previous one is done
+                        Some(id) if id != self.tool_call_buffers[pos].tool_id => {
+                            let buffer = self.tool_call_buffers.remove(pos);
+                            self.finish_tool_call(buffer);
+                        }
+                        _ => return Some((pos, false)),
+                    }
+                }
+            }
+            None => match tool_id {
+                Some(id) => {
+                    if let Some(pos) = self
+                        .tool_call_buffers
+                        .iter()
+                        .position(|buffer| buffer.tool_id == id)
+                    {
+                        return Some((pos, false));
+                    }
+                    self.force_finish_tool_call_buffers();
+                }
+                None => {
+                    return self
+                        .tool_call_buffers
+                        .len()
+                        .checked_sub(1)
+                        .map(|pos| (pos, false))
+                }
+            },
+        }
+
+        // Empty tool_id indicates abnormal premature closure, stop processing subsequent data for this tool_call
+        let tool_id = tool_id?;
+        let mut buffer = ToolCallBuffer::new();
+        buffer.tool_id = tool_id.to_string();
+        buffer.index = index;
+        buffer.seq = self.next_tool_call_seq;
+        self.next_tool_call_seq += 1;
+        self.tool_call_buffers.push(buffer);
+        Some((self.tool_call_buffers.len() - 1, true))
+    }
 }
 
 /// Stream processor
@@ -341,7 +451,7 @@ impl StreamProcessor {
 
     /// Execute graceful shutdown from context
     async fn graceful_shutdown_from_ctx(&self, ctx: &mut StreamContext, reason: String) {
-        ctx.force_finish_tool_call_buffer();
+        ctx.force_finish_tool_call_buffers();
         self.graceful_shutdown(
             ctx.session_id.clone(),
             ctx.dialog_turn_id.clone(),
@@ -419,6 +529,9 @@ impl StreamProcessor {
                     session_id: session_id.clone(),
                     turn_id: turn_id.clone(),
                     error: reason,
+                    error_code: None,
+                    retryable: false,
+                    user_message: None,
                     subagent_parent_info: event_subagent_parent_info.clone(),
                 }
             };
@@ -446,6 +559,7 @@ impl StreamProcessor {
             total_token_count: response_usage.total_token_count,
             reasoning_token_count: response_usage.reasoning_token_count,
             cached_content_token_count: response_usage.cached_content_token_count,
+            web_search_requests: response_usage.web_search_requests,
         });
         debug!(
             "Received token usage stats: input={}, output={}, total={}",
@@ -461,82 +575,149 @@ impl StreamProcessor {
         ctx: &mut StreamContext,
         tool_call: ai_stream_handlers::UnifiedToolCall,
     ) {
-        // Handle tool ID and name
-        if let Some(tool_id) = tool_call.id {
-            if !tool_id.is_empty() {
-                ctx.has_effective_output = true;
-                // Some providers repeat the tool id on every delta; only treat a new id as a new tool call.
-                let is_new_tool = ctx.tool_call_buffer.tool_id != tool_id;
-                if is_new_tool {
-                    // Clear previous tool_call state
-                    ctx.force_finish_tool_call_buffer();
-
-                    // Normally tool_name should not be empty
-                    let tool_name = tool_call.name.unwrap_or_default();
-                    debug!("Tool detected: {}", tool_name);
-                    ctx.tool_call_buffer.tool_id = tool_id.clone();
-                    ctx.tool_call_buffer.tool_name = tool_name.clone();
-                    ctx.tool_call_buffer.json_checker.reset();
-
-                    // Send early detection event
-                    let _ = self
-                        .event_queue
-                        .enqueue(
-                            AgenticEvent::ToolEvent {
-                                session_id: ctx.session_id.clone(),
-                                turn_id: ctx.dialog_turn_id.clone(),
-                                tool_event: ToolEventData::EarlyDetected {
-                                    tool_id: tool_id,
-                                    tool_name: tool_name,
-                                },
-                                subagent_parent_info: ctx.event_subagent_parent_info.clone(),
-                            },
-                            None,
-                        )
-                        .await;
-                } else if ctx.tool_call_buffer.tool_name.is_empty() {
-                    // Best-effort: keep name if provider repeats it.
-                    ctx.tool_call_buffer.tool_name = tool_call.name.unwrap_or_default();
-                }
-            }
+        // Some providers repeat the tool id on every delta; only an unseen id starts a new tool call.
+        let tool_id = tool_call.id.filter(|id| !id.is_empty());
+        if tool_id.is_some() {
+            ctx.has_effective_output = true;
+        }
+        let Some((pos, is_new_tool)) =
+            ctx.route_tool_call_fragment(tool_call.index, tool_id.as_deref())
+        else {
+            return;
+        };
+
+        if is_new_tool {
+            // Normally tool_name should not be empty
+            let tool_name = tool_call.name.unwrap_or_default();
+            debug!("Tool detected: {}", tool_name);
+            let buffer = &mut ctx.tool_call_buffers[pos];
+            buffer.tool_name = tool_name.clone();
+
+            // Send early detection event
+            let _ = self
+                .event_queue
+                .enqueue(
+                    AgenticEvent::ToolEvent {
+                        session_id: ctx.session_id.clone(),
+                        turn_id: ctx.dialog_turn_id.clone(),
+                        tool_event: ToolEventData::EarlyDetected {
+                            tool_id: buffer.tool_id.clone(),
+                            tool_name,
+                        },
+                        subagent_parent_info: ctx.event_subagent_parent_info.clone(),
+                    },
+                    None,
+                )
+                .await;
+        } else if ctx.tool_call_buffers[pos].tool_name.is_empty() {
+            // Best-effort: keep name if provider repeats it.
+            ctx.tool_call_buffers[pos].tool_name = tool_call.name.unwrap_or_default();
         }
 
         // Handle tool parameters
         if let Some(tool_call_arguments) = tool_call.arguments {
-            // Empty tool_id indicates abnormal premature closure, stop processing subsequent data for this tool_call
-            if !ctx.tool_call_buffer.tool_id.is_empty() {
-                ctx.has_effective_output = true;
-                ctx.tool_call_buffer.append(&tool_call_arguments);
-
-                // Send partial parameters event
-                let _ = self
-                    .event_queue
-                    .enqueue(
-                        AgenticEvent::ToolEvent {
-                            session_id: ctx.session_id.clone(),
-                            turn_id: ctx.dialog_turn_id.clone(),
-                            tool_event: ToolEventData::ParamsPartial {
-                                tool_id: ctx.tool_call_buffer.tool_id.clone(),
-                                tool_name: ctx.tool_call_buffer.tool_name.clone(),
-                                params: tool_call_arguments,
-                            },
-                            subagent_parent_info: ctx.event_subagent_parent_info.clone(),
+            ctx.has_effective_output = true;
+            let buffer = &mut ctx.tool_call_buffers[pos];
+            buffer.append(&tool_call_arguments);
+
+            // Send partial parameters event
+            let _ = self
+                .event_queue
+                .enqueue(
+                    AgenticEvent::ToolEvent {
+                        session_id: ctx.session_id.clone(),
+                        turn_id: ctx.dialog_turn_id.clone(),
+                        tool_event: ToolEventData::ParamsPartial {
+                            tool_id: buffer.tool_id.clone(),
+                            tool_name: buffer.tool_name.clone(),
+                            params: tool_call_arguments,
                         },
-                        None,
-                    )
-                    .await;
-            }
+                        subagent_parent_info: ctx.event_subagent_parent_info.clone(),
+                    },
+                    None,
+                )
+                .await;
         }
 
         // Check if JSON is complete
-        if ctx.tool_call_buffer.is_valid() {
-            let tool_call = ctx.tool_call_buffer.to_tool_call();
-            ctx.tool_calls.push(tool_call);
-
-            // Clear buffer
-            // Normally there should be no delta data after parameters are complete, but this has been triggered in practice, possibly due to network issues or model output anomalies
-            // reset clears the id, subsequent data for this tool_call will not be processed
-            ctx.tool_call_buffer.reset();
+        // Normally there should be no delta data after parameters are complete, but this has been triggered in practice, possibly due to network issues or model output anomalies
+        // Closing the call drops its id, so id-less data that follows is not attributed to it
+        if ctx.tool_call_buffers[pos].is_valid() {
+            let buffer = ctx.tool_call_buffers.remove(pos);
+            ctx.finish_tool_call(buffer);
+        }
+    }
+
+    /// Handle a tool the provider ran itself: shown like a local tool call and kept so the
+    /// assistant message can send it back
+    async fn handle_server_tool_event(
+        &self,
+        ctx: &mut StreamContext,
+        event: UnifiedServerToolEvent,
+    ) {
+        ctx.has_effective_output = true;
+        let tool_event = match &event {
+            UnifiedServerToolEvent::Use { id, name, input } => {
+                debug!("Server tool started: {} ({})", name, id);
+                ctx.server_tool_started_at
+                    .insert(id.clone(), Instant::now());
+                Some(ToolEventData::Started {
+                    tool_id: id.clone(),
+                    tool_name: name.clone(),
+                    params: input.clone(),
+                })
+            }
+            UnifiedServerToolEvent::Result {
+                tool_use_id,
+                name,
+                content,
+            } => Some(ToolEventData::Completed {
+                tool_id: tool_use_id.clone(),
+                tool_name: name.clone(),
+                result: Self::displayable_server_tool_result(content),
+                result_for_assistant: None,
+                duration_ms: ctx
+                    .server_tool_started_at
+                    .get(tool_use_id)
+                    .map(|started| started.elapsed().as_millis() as u64)
+                    .unwrap_or(0),
+            }),
+            UnifiedServerToolEvent::Citation { .. } => None,
+        };
+        ctx.server_tool_events.push(event);
+
+        if let Some(tool_event) = tool_event {
+            let _ = self
+                .event_queue
+                .enqueue(
+                    AgenticEvent::ToolEvent {
+                        session_id: ctx.session_id.clone(),
+                        turn_id: ctx.dialog_turn_id.clone(),
+                        tool_event,
+                        subagent_parent_info: ctx.event_subagent_parent_info.clone(),
+                    },
+                    None,
+                )
+                .await;
+        }
+    }
+
+    /// Server tool result without the opaque encrypted payloads only the provider can read
+    fn displayable_server_tool_result(content: &Value) -> Value {
+        match content {
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(Self::displayable_server_tool_result)
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(key, _)| !key.starts_with("encrypted_"))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ),
+            other => other.clone(),
         }
     }
 
@@ -791,6 +972,11 @@ impl StreamProcessor {
                             return err;
                         }
                     }
+
+                    if let Some(server_tool) = response.server_tool {
+                        self.send_thinking_end_if_needed(&mut ctx).await;
+                        self.handle_server_tool_event(&mut ctx, server_tool).await;
+                    }
                 }
             }
         }
@@ -805,9 +991,236 @@ impl StreamProcessor {
             flush_sse_on_error(&sse_collector, "Has incomplete tool calls").await;
         }
 
-        ctx.force_finish_tool_call_buffer();
+        ctx.force_finish_tool_call_buffers();
         self.log_stream_result(&ctx);
 
         Ok(ctx.into_result())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{StreamProcessor, ToolCallBuffer};
+    use crate::agentic::events::EventQueue;
+    use ai_stream_handlers::{UnifiedResponse, UnifiedServerToolEvent, UnifiedToolCall};
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn assemble(deltas: &[&str]) -> crate::agentic::core::ToolCall {
+        let mut buffer = ToolCallBuffer::new();
+        buffer.tool_id = "call_1".to_string();
+        buffer.tool_name = "Write".to_string();
+        for delta in deltas {
+            buffer.append(delta);
+        }
+        buffer.to_tool_call()
+    }
+
+    #[test]
+    fn valid_arguments_parse_strictly() {
+        let call = assemble(&["{\"file_path\":", " \"a.txt\", \"content\"", ": \"hi\"}"]);
+        assert!(call.is_valid());
+        assert!(!call.repaired);
+        assert_eq!(
+            call.arguments,
+            json!({ "file_path": "a.txt", "content": "hi" })
+        );
+    }
+
+    #[test]
+    fn trailing_comma_from_proxy_is_repaired() {
+        // Captured from an OpenAI-compatible proxy that re-serializes argument fragments
+        let call = assemble(&["{\"pattern\": \"TODO\",", " \"paths\": [\"src\",]", ",}"]);
+        assert!(call.is_valid());
+        assert!(call.repaired);
+        assert_eq!(
+            call.arguments,
+            json!({ "pattern": "TODO", "paths": ["src"] })
+        );
+    }
+
+    #[test]
+    fn truncated_stream_is_repaired() {
+        // Stream cut off mid-string; the buffer is force-finished without its closing brace
+        let call = assemble(&[
+            "{\"file_path\": \"notes.md\", ",
+            "\"content\": \"# Title\\n\\nFirst para",
+        ]);
+        assert!(call.is_valid());
+        assert!(call.repaired);
+        assert_eq!(call.arguments["content"], json!("# Title\n\nFirst para"));
+    }
+
+    #[test]
+    fn unrepairable_arguments_become_error_call() {
+        let call = assemble(&["{\"file_path\": \"a.txt\", \"content\": hello world}"]);
+        assert!(!call.is_valid());
+        assert!(!call.repaired);
+        assert_eq!(call.arguments, json!({}));
+        assert!(call.argument_error.is_some());
+    }
+
+    fn fragment(
+        index: Option<usize>,
+        id: Option<&str>,
+        name: Option<&str>,
+        args: &str,
+    ) -> UnifiedResponse {
+        UnifiedResponse {
+            tool_call: Some(UnifiedToolCall {
+                id: id.map(str::to_string),
+                name: name.map(str::to_string),
+                arguments: Some(args.to_string()),
+                index,
+            }),
+            ..Default::default()
+        }
+    }
+
+    async fn process(fragments: Vec<UnifiedResponse>) -> Vec<crate::agentic::core::ToolCall> {
+        let processor = StreamProcessor::new(Arc::new(EventQueue::new(Default::default())));
+        let stream = futures::stream::iter(fragments.into_iter().map(Ok)).boxed();
+        processor
+            .process_stream(
+                stream,
+                None,
+                "session".to_string(),
+                "turn".to_string(),
+                "round".to_string(),
+                None,
+                &tokio_util::sync::CancellationToken::new(),
+            )
+            .await
+            .expect("stream result")
+            .tool_calls
+    }
+
+    #[tokio::test]
+    async fn interleaved_parallel_calls_accumulate_by_index() {
+        // OpenAI-compatible stream with two parallel calls whose fragments interleave;
+        // only the first fragment of each call carries the id
+        let tool_calls = process(vec![
+            fragment(Some(0), Some("call_a"), Some("Read"), ""),
+            fragment(Some(1), Some("call_b"), Some("Grep"), ""),
+            fragment(Some(0), None, None, "{\"file_path\": "),
+            fragment(Some(1), None, None, "{\"pattern\": \"TODO\"}"),
+            fragment(Some(0), None, None, "\"src/lib.rs\"}"),
+        ])
+        .await;
+
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].tool_id, "call_a");
+        assert_eq!(tool_calls[0].tool_name, "Read");
+        assert_eq!(
+            tool_calls[0].arguments,
+            json!({ "file_path": "src/lib.rs" })
+        );
+        assert_eq!(tool_calls[1].tool_id, "call_b");
+        assert_eq!(tool_calls[1].tool_name, "Grep");
+        assert_eq!(tool_calls[1].arguments, json!({ "pattern": "TODO" }));
+        assert!(tool_calls
+            .iter()
+            .all(|call| call.is_valid() && !call.repaired));
+    }
+
+    #[tokio::test]
+    async fn reused_index_with_new_id_starts_new_call() {
+        // Some proxies report index 0 for every call
+        let tool_calls = process(vec![
+            fragment(
+                Some(0),
+                Some("call_a"),
+                Some("Read"),
+                "{\"file_path\": \"a.rs\"",
+            ),
+            fragment(Some(0), Some("call_b"), Some("Read"), "{\"file_path\": "),
+            fragment(Some(0), None, None, "\"b.rs\"}"),
+        ])
+        .await;
+
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].tool_id, "call_a");
+        assert!(tool_calls[0].repaired);
+        assert_eq!(tool_calls[0].arguments, json!({ "file_path": "a.rs" }));
+        assert_eq!(tool_calls[1].tool_id, "call_b");
+        assert_eq!(tool_calls[1].arguments, json!({ "file_path": "b.rs" }));
+    }
+
+    #[tokio::test]
+    async fn sequential_calls_without_index_keep_arrival_order() {
+        // Anthropic-style stream: calls never overlap and carry no index
+        let tool_calls = process(vec![
+            fragment(None, Some("toolu_1"), Some("Read"), ""),
+            fragment(None, None, None, "{\"file_path\": \"a.rs\"}"),
+            fragment(None, Some("toolu_2"), Some("Read"), ""),
+            fragment(None, None, None, "{\"file_path\": \"b.rs\"}"),
+        ])
+        .await;
+
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].arguments, json!({ "file_path": "a.rs" }));
+        assert_eq!(tool_calls[1].arguments, json!({ "file_path": "b.rs" }));
+    }
+
+    #[tokio::test]
+    async fn server_tool_events_are_kept_without_becoming_local_calls() {
+        let processor = StreamProcessor::new(Arc::new(EventQueue::new(Default::default())));
+        let server_tool = |event: UnifiedServerToolEvent| UnifiedResponse {
+            server_tool: Some(event),
+            ..Default::default()
+        };
+        let stream = futures::stream::iter(
+            vec![
+                server_tool(UnifiedServerToolEvent::Use {
+                    id: "srvtoolu_1".to_string(),
+                    name: "web_search".to_string(),
+                    input: json!({ "query": "rust 2024" }),
+                }),
+                server_tool(UnifiedServerToolEvent::Result {
+                    tool_use_id: "srvtoolu_1".to_string(),
+                    name: "web_search".to_string(),
+                    content: json!([{ "url": "https://blog.rust-lang.org", "encrypted_content": "abc" }]),
+                }),
+                UnifiedResponse {
+                    text: Some("Rust 2024 shipped.".to_string()),
+                    ..Default::default()
+                },
+                server_tool(UnifiedServerToolEvent::Citation {
+                    citation: json!({ "url": "https://blog.rust-lang.org" }),
+                }),
+            ]
+            .into_iter()
+            .map(Ok),
+        )
+        .boxed();
+
+        let result = processor
+            .process_stream(
+                stream,
+                None,
+                "session".to_string(),
+                "turn".to_string(),
+                "round".to_string(),
+                None,
+                &tokio_util::sync::CancellationToken::new(),
+            )
+            .await
+            .expect("stream result");
+
+        assert!(result.tool_calls.is_empty());
+        assert_eq!(result.full_text, "Rust 2024 shipped.");
+        assert_eq!(result.server_tool_events.len(), 3);
+        assert!(matches!(
+            &result.server_tool_events[1],
+            UnifiedServerToolEvent::Result { content, .. }
+                if content[0]["encrypted_content"] == "abc"
+        ));
+        assert_eq!(
+            StreamProcessor::displayable_server_tool_result(&json!([
+                { "url": "https://blog.rust-lang.org", "encrypted_content": "abc" }
+            ])),
+            json!([{ "url": "https://blog.rust-lang.org" }])
+        );
+    }
+}
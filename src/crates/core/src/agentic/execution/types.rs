@@ -21,10 +21,14 @@ pub struct ExecutionContext {
     pub context: HashMap<String, String>,
     pub subagent_parent_info: Option<SubagentParentInfo>,
     pub skip_tool_confirmation: bool,
+    /// Ask before tools that need permission regardless of `ai.skip_tool_confirmation`
+    pub require_tool_confirmation: bool,
     /// Workspace I/O services (filesystem + shell) injected into tools
     pub workspace_services: Option<WorkspaceServices>,
     /// When set, engine may end the turn after a full model round if a user message was queued.
     pub round_preempt: Option<Arc<dyn DialogRoundPreemptSource>>,
+    /// Appended to the agent's system prompt (e.g. a required response format)
+    pub system_prompt_suffix: Option<String>,
 }
 
 /// Round context
@@ -43,6 +47,8 @@ pub struct RoundContext {
     pub context_vars: HashMap<String, String>,
     pub cancellation_token: CancellationToken,
     pub workspace_services: Option<WorkspaceServices>,
+    /// Answer tool calls with an error instead of running them (final round of a turn over budget)
+    pub skip_tool_execution: bool,
 }
 
 /// Round result
@@ -80,6 +86,9 @@ pub struct ExecutionResult {
     /// Last assistant message
     pub final_message: Message,
     pub total_rounds: usize,
+    pub total_tools: usize,
+    /// Budget that ended the turn early
+    pub budget_exhausted: Option<crate::agentic::core::TurnBudgetKind>,
     pub success: bool,
     /// All new messages generated by this execution (including AI responses and tool results)
     pub new_messages: Vec<Message>,
@@ -0,0 +1,216 @@
+//! Image Analysis Cache
+//!
+//! On-disk cache of vision-model analyses keyed by (model, processed image bytes, prompt), so
+//! the same screenshot attached twice costs one model call. The full prompt is part of the
+//! key, which invalidates entries whenever the analysis prompt template changes. Entries
+//! expire after a TTL; size is bounded by the `image_analysis` cache quota and the cleanup
+//! policy of the same name.
+
+use super::types::ImageAnalysisResult;
+use crate::infrastructure::filesystem::{register_cache_consumer, CacheType};
+use crate::infrastructure::try_get_path_manager_arc;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a cached analysis is served
+pub const IMAGE_ANALYSIS_CACHE_TTL: Duration = Duration::from_secs(30 * 24 * 3600);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Unix seconds
+    cached_at: u64,
+    result: ImageAnalysisResult,
+}
+
+/// Cache key inputs
+pub struct ImageAnalysisCacheKey<'a> {
+    /// Provider and model the analysis came from
+    pub model: &'a str,
+    /// Image bytes exactly as sent to the model
+    pub image_data: &'a [u8],
+    /// Full analysis prompt, including the template and any user context
+    pub prompt: &'a str,
+}
+
+impl ImageAnalysisCacheKey<'_> {
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.model.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(Sha256::digest(self.image_data));
+        hasher.update([0u8]);
+        hasher.update(self.prompt.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageAnalysisCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ImageAnalysisCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// Cache under the app's `cache/image_analysis` directory
+    pub fn global() -> Option<Self> {
+        let dir = try_get_path_manager_arc()
+            .ok()?
+            .cache_dir(CacheType::ImageAnalysis);
+        register_cache_consumer("image-analysis", CacheType::ImageAnalysis, dir.clone());
+        Some(Self::new(dir, IMAGE_ANALYSIS_CACHE_TTL))
+    }
+
+    fn entry_path(&self, key: &ImageAnalysisCacheKey<'_>) -> PathBuf {
+        let digest = key.digest();
+        self.dir.join(&digest[..2]).join(format!("{}.json", digest))
+    }
+
+    /// Cached analysis for `key`, with `cache_hit` set; expired entries are removed
+    pub async fn get(
+        &self,
+        key: &ImageAnalysisCacheKey<'_>,
+        image_id: &str,
+    ) -> Option<ImageAnalysisResult> {
+        let path = self.entry_path(key);
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        let entry: CacheEntry = match serde_json::from_slice(&bytes) {
+            Ok(entry) => entry,
+            Err(e) => {
+                debug!(
+                    "Dropping unreadable image analysis cache entry {:?}: {}",
+                    path, e
+                );
+                let _ = tokio::fs::remove_file(&path).await;
+                return None;
+            }
+        };
+
+        let age = now_secs().saturating_sub(entry.cached_at);
+        if age > self.ttl.as_secs() {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+
+        let mut result = entry.result;
+        result.image_id = image_id.to_string();
+        result.cache_hit = true;
+        Some(result)
+    }
+
+    /// Best effort: a failed write only costs a re-analysis later
+    pub async fn put(&self, key: &ImageAnalysisCacheKey<'_>, result: &ImageAnalysisResult) {
+        let path = self.entry_path(key);
+        let entry = CacheEntry {
+            cached_at: now_secs(),
+            result: ImageAnalysisResult {
+                cache_hit: false,
+                ..result.clone()
+            },
+        };
+
+        let result = async {
+            let bytes = serde_json::to_vec(&entry).map_err(std::io::Error::other)?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, bytes).await
+        }
+        .await;
+        if let Err(e) = result {
+            debug!(
+                "Failed to write image analysis cache entry {:?}: {}",
+                path, e
+            );
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis(image_id: &str) -> ImageAnalysisResult {
+        ImageAnalysisResult {
+            image_id: image_id.to_string(),
+            summary: "A terminal with a failing build".to_string(),
+            detailed_description: "cargo reports a missing import".to_string(),
+            detected_elements: vec!["terminal".to_string()],
+            confidence: 0.9,
+            analysis_time_ms: 1200,
+            ocr: None,
+            ocr_only: false,
+            original_size: 2048,
+            processed_size: 1024,
+            cache_hit: false,
+            file_name: None,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn hits_only_for_same_model_image_and_prompt() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let cache = ImageAnalysisCache::new(dir.to_path_buf(), IMAGE_ANALYSIS_CACHE_TTL);
+        let key = ImageAnalysisCacheKey {
+            model: "openai/gpt-4o",
+            image_data: b"png-bytes",
+            prompt: "Describe the image",
+        };
+
+        cache.put(&key, &analysis("img_1")).await;
+
+        let hit = cache.get(&key, "img_2").await.unwrap();
+        assert!(hit.cache_hit);
+        assert_eq!(hit.image_id, "img_2");
+        assert_eq!(hit.summary, "A terminal with a failing build");
+
+        let other_model = ImageAnalysisCacheKey {
+            model: "anthropic/claude",
+            ..key
+        };
+        assert!(cache.get(&other_model, "img_2").await.is_none());
+        let other_prompt = ImageAnalysisCacheKey {
+            prompt: "Describe the image in detail",
+            ..key
+        };
+        assert!(cache.get(&other_prompt, "img_2").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_not_served() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let cache = ImageAnalysisCache::new(dir.to_path_buf(), Duration::from_secs(60));
+        let key = ImageAnalysisCacheKey {
+            model: "openai/gpt-4o",
+            image_data: b"png-bytes",
+            prompt: "Describe the image",
+        };
+
+        let path = cache.entry_path(&key);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let stale = CacheEntry {
+            cached_at: now_secs() - 120,
+            result: analysis("img_1"),
+        };
+        std::fs::write(&path, serde_json::to_vec(&stale).unwrap()).unwrap();
+
+        assert!(cache.get(&key, "img_1").await.is_none());
+        assert!(!path.exists());
+    }
+}
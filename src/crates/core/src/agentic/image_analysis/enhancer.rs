@@ -2,6 +2,7 @@
 //!
 //! Synthesizes image analysis results and other context into user messages
 
+use super::ocr::is_text_dominant;
 use super::types::ImageAnalysisResult;
 use serde_json::Value;
 
@@ -27,12 +28,31 @@ impl MessageEnhancer {
                 .push_str(" image(s). AI's understanding of the image content is as follows:\n\n");
 
             for (idx, analysis) in image_analyses.iter().enumerate() {
-                enhanced.push_str(&format!("[Image {}]\n", idx + 1));
+                match &analysis.file_name {
+                    Some(file_name) => {
+                        enhanced.push_str(&format!("[Image {}: {}]\n", idx + 1, file_name))
+                    }
+                    None => enhanced.push_str(&format!("[Image {}]\n", idx + 1)),
+                }
+                if let Some(error) = &analysis.error {
+                    enhanced.push_str(&format!("• Analysis failed: {}\n\n", error));
+                    continue;
+                }
                 enhanced.push_str(&format!("• Summary: {}\n", analysis.summary));
-                enhanced.push_str(&format!(
-                    "• Detailed description: {}\n",
-                    analysis.detailed_description
-                ));
+                if !analysis.ocr_only {
+                    enhanced.push_str(&format!(
+                        "• Detailed description: {}\n",
+                        analysis.detailed_description
+                    ));
+                }
+
+                if let Some(ocr) = analysis.ocr.as_ref().filter(|ocr| is_text_dominant(ocr)) {
+                    enhanced.push_str(&format!(
+                        "• Text in image (OCR, {:.0}% confidence):\n```\n{}\n```\n",
+                        ocr.confidence * 100.0,
+                        ocr.text
+                    ));
+                }
 
                 if !analysis.detected_elements.is_empty() {
                     enhanced.push_str("• Key elements: ");
@@ -47,6 +67,14 @@ impl MessageEnhancer {
 
                 enhanced.push_str("\n");
             }
+
+            if image_analyses
+                .iter()
+                .any(|analysis| analysis.error.is_some())
+            {
+                enhanced.push_str("Images marked as failed could not be analyzed. If the question depends on them, say so instead of guessing their content.\n");
+            }
+            enhanced.push_str("The above image analysis has already been performed. Do NOT suggest the user to view or re-analyze the image. Respond directly to the user's question based on the analysis.\n\n");
         }
 
         // 2. Other contexts (files, code snippets, etc.)
@@ -61,8 +89,6 @@ impl MessageEnhancer {
             enhanced.push_str("\n");
         }
 
-        enhanced.push_str("The above image analysis has already been performed. Do NOT suggest the user to view or re-analyze the image. Respond directly to the user's question based on the analysis.\n\n");
-
         // 3. Separator
         enhanced.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
 
@@ -106,3 +132,78 @@ impl MessageEnhancer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agentic::image_analysis::types::OcrText;
+
+    fn analysis() -> ImageAnalysisResult {
+        ImageAnalysisResult {
+            image_id: "img_1".to_string(),
+            summary: "A login form".to_string(),
+            detailed_description: "Username and password fields".to_string(),
+            detected_elements: vec![],
+            confidence: 0.9,
+            analysis_time_ms: 10,
+            ocr: None,
+            ocr_only: false,
+            original_size: 0,
+            processed_size: 0,
+            cache_hit: false,
+            file_name: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn includes_confident_ocr_text_instead_of_description() {
+        let text = "error[E0425]: cannot find value `config` in this scope".to_string();
+        let ocr_only = ImageAnalysisResult {
+            detailed_description: String::new(),
+            ocr: Some(OcrText {
+                text: text.clone(),
+                confidence: 0.92,
+            }),
+            ocr_only: true,
+            ..analysis()
+        };
+        let enhanced = MessageEnhancer::enhance_with_image_analysis("Fix this", &[ocr_only], &[]);
+        assert!(enhanced.contains(&text));
+        assert!(!enhanced.contains("Detailed description"));
+
+        let unsure = ImageAnalysisResult {
+            ocr: Some(OcrText {
+                text: text.clone(),
+                confidence: 0.3,
+            }),
+            ..analysis()
+        };
+        let enhanced = MessageEnhancer::enhance_with_image_analysis("Fix this", &[unsure], &[]);
+        assert!(!enhanced.contains(&text));
+        assert!(enhanced.contains("Username and password fields"));
+    }
+
+    #[test]
+    fn keeps_order_file_names_and_failed_images() {
+        let first = ImageAnalysisResult {
+            file_name: Some("login.png".to_string()),
+            ..analysis()
+        };
+        let failed = ImageAnalysisResult {
+            image_id: "img_2".to_string(),
+            file_name: Some("trace.jpg".to_string()),
+            error: Some("Image context missing path or data".to_string()),
+            ..analysis()
+        };
+        let enhanced =
+            MessageEnhancer::enhance_with_image_analysis("What broke?", &[first, failed], &[]);
+
+        let first_at = enhanced.find("[Image 1: login.png]").unwrap();
+        let failed_at = enhanced.find("[Image 2: trace.jpg]").unwrap();
+        assert!(first_at < failed_at);
+        assert!(enhanced.contains("• Analysis failed: Image context missing path or data"));
+        assert_eq!(enhanced.matches("Username and password fields").count(), 1);
+        assert!(enhanced.contains("could not be analyzed"));
+    }
+}
@@ -2,20 +2,23 @@
 
 use super::types::{ImageContextData, ImageLimits};
 use crate::service::config::get_global_config_service;
-use crate::service::config::types::{
-    AIConfig as ServiceAIConfig, AIModelConfig, ModelCapability, ModelCategory,
-};
+use crate::service::config::types::{AIConfig as ServiceAIConfig, AIModelConfig};
 use crate::util::errors::{BitFunError, BitFunResult};
-use crate::util::types::Message;
+use crate::util::types::{Message, ToolImageAttachment};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::PngEncoder;
 use image::imageops::FilterType;
+use image::metadata::Orientation;
 use image::ColorType;
 use image::DynamicImage;
+use image::ImageDecoder;
 use image::ImageEncoder;
 use image::ImageFormat;
+use image::ImageReader;
+use log::warn;
 use serde_json::json;
+use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
@@ -25,6 +28,8 @@ pub struct ProcessedImage {
     pub mime_type: String,
     pub width: u32,
     pub height: u32,
+    /// Size of the input before preprocessing (bytes)
+    pub original_size: usize,
 }
 
 pub fn resolve_vision_model_from_ai_config(
@@ -55,12 +60,7 @@ pub fn resolve_vision_model_from_ai_config(
         return Err(BitFunError::service(format!("Model is disabled: {}", id)));
     }
 
-    let supports_image_understanding = model
-        .capabilities
-        .iter()
-        .any(|cap| matches!(cap, ModelCapability::ImageUnderstanding))
-        || matches!(model.category, ModelCategory::Multimodal);
-    if !supports_image_understanding {
+    if !model.supports_image_input() {
         return Err(BitFunError::service(format!(
             "Model does not support image understanding: {}",
             id
@@ -165,29 +165,62 @@ pub fn optimize_image_with_size_limit(
     fallback_mime: Option<&str>,
     max_output_size: Option<usize>,
 ) -> BitFunResult<ProcessedImage> {
-    let limits = ImageLimits::for_provider(provider);
+    optimize_image_with_limits(
+        image_data,
+        &ImageLimits::for_provider(provider),
+        fallback_mime,
+        max_output_size,
+    )
+}
+
+/// Resize/compress to fit `limits`, with an optional extra size cap as in
+/// `optimize_image_with_size_limit`.
+///
+/// Metadata (EXIF including GPS, XMP, text chunks) never survives: images that already fit
+/// are stripped in place, everything else is re-encoded with its EXIF orientation applied.
+pub fn optimize_image_with_limits(
+    image_data: Vec<u8>,
+    limits: &ImageLimits,
+    fallback_mime: Option<&str>,
+    max_output_size: Option<usize>,
+) -> BitFunResult<ProcessedImage> {
     let effective_max = match max_output_size {
         Some(cap) => cap.min(limits.max_size),
         None => limits.max_size,
     };
+    let original_size = image_data.len();
 
-    let guessed_format = image::guess_format(&image_data).ok();
-    let dynamic = image::load_from_memory(&image_data)
+    let mut decoder = ImageReader::new(Cursor::new(&image_data))
+        .with_guessed_format()
+        .map_err(|e| BitFunError::validation(format!("Failed to read image data: {}", e)))?
+        .into_decoder()
         .map_err(|e| BitFunError::validation(format!("Failed to decode image data: {}", e)))?;
-
-    let (orig_width, orig_height) = (dynamic.width(), dynamic.height());
+    let (orig_width, orig_height) = decoder.dimensions();
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
     let needs_resize = orig_width > limits.max_width || orig_height > limits.max_height;
 
-    if !needs_resize && image_data.len() <= effective_max {
+    if !needs_resize
+        && image_data.len() <= effective_max
+        && orientation == Orientation::NoTransforms
+    {
+        drop(decoder);
         let mime_type = detect_mime_type_from_bytes(&image_data, fallback_mime)?;
+        let data = strip_image_metadata(&image_data).unwrap_or(image_data);
         return Ok(ProcessedImage {
-            data: image_data,
+            data,
             mime_type,
             width: orig_width,
             height: orig_height,
+            original_size,
         });
     }
 
+    let guessed_format = image::guess_format(&image_data).ok();
+    let mut dynamic = DynamicImage::from_decoder(decoder)
+        .map_err(|e| BitFunError::validation(format!("Failed to decode image data: {}", e)))?;
+    dynamic.apply_orientation(orientation);
+
+    let needs_resize = dynamic.width() > limits.max_width || dynamic.height() > limits.max_height;
     let mut working = if needs_resize {
         dynamic.resize(limits.max_width, limits.max_height, FilterType::Triangle)
     } else {
@@ -199,10 +232,13 @@ pub fn optimize_image_with_size_limit(
         _ => ImageFormat::Png,
     };
 
-    let mut encoded = encode_dynamic_image(&working, preferred_format, 85)?;
+    let quality = limits.jpeg_quality;
+    let mut encoded = encode_dynamic_image(&working, preferred_format, quality)?;
 
     if encoded.0.len() > effective_max {
-        for quality in [80u8, 65, 50, 35] {
+        for quality in
+            std::iter::once(quality).chain([80u8, 65, 50, 35].into_iter().filter(|q| *q < quality))
+        {
             encoded = encode_dynamic_image(&working, ImageFormat::Jpeg, quality)?;
             if encoded.0.len() <= effective_max {
                 break;
@@ -238,9 +274,111 @@ pub fn optimize_image_with_size_limit(
         mime_type: encoded.1,
         width: working.width(),
         height: working.height(),
+        original_size,
     })
 }
 
+/// Apply the sizing policy to tool-result images (MCP passthrough, screenshots) before they
+/// are sent to a model. Attachments that can't be decoded are passed through unchanged.
+pub fn prepare_tool_image_attachments(
+    attachments: Vec<ToolImageAttachment>,
+    limits: &ImageLimits,
+) -> Vec<ToolImageAttachment> {
+    attachments
+        .into_iter()
+        .map(|attachment| {
+            let data = match BASE64.decode(attachment.data_base64.as_bytes()) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Tool image attachment is not valid base64: {}", e);
+                    return attachment;
+                }
+            };
+            match optimize_image_with_limits(data, limits, Some(&attachment.mime_type), None) {
+                Ok(processed) if processed.data.len() == processed.original_size => attachment,
+                Ok(processed) => ToolImageAttachment {
+                    mime_type: processed.mime_type,
+                    data_base64: BASE64.encode(&processed.data),
+                },
+                Err(e) => {
+                    warn!("Failed to preprocess tool image attachment: {}", e);
+                    attachment
+                }
+            }
+        })
+        .collect()
+}
+
+/// Remove metadata from an encoded JPEG or PNG without touching the pixel data: EXIF/XMP
+/// (APP1), IPTC (APP13) and comments for JPEG; `eXIf` and text chunks for PNG. Returns
+/// `None` when the format isn't handled or nothing was removed.
+pub fn strip_image_metadata(image_data: &[u8]) -> Option<Vec<u8>> {
+    let stripped = match image::guess_format(image_data).ok()? {
+        ImageFormat::Jpeg => strip_jpeg_metadata(image_data)?,
+        ImageFormat::Png => strip_png_metadata(image_data)?,
+        _ => return None,
+    };
+    (stripped.len() < image_data.len()).then_some(stripped)
+}
+
+fn strip_jpeg_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    const APP1: u8 = 0xE1;
+    const APP13: u8 = 0xED;
+    const COM: u8 = 0xFE;
+    const SOS: u8 = 0xDA;
+
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(data.get(..2)?);
+    let mut pos = 2;
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        // Standalone markers and fill bytes have no length field.
+        if marker == 0xFF || (0xD0..=0xD7).contains(&marker) || marker == 0x01 {
+            out.push(data[pos]);
+            pos += 1;
+            continue;
+        }
+        if marker == SOS {
+            out.extend_from_slice(&data[pos..]);
+            return Some(out);
+        }
+        let len = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+        let segment = data.get(pos..pos + 2 + len)?;
+        if !matches!(marker, APP1 | APP13 | COM) {
+            out.extend_from_slice(segment);
+        }
+        pos += 2 + len;
+    }
+}
+
+fn strip_png_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    const DROPPED_CHUNKS: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(data.get(..8)?);
+    let mut pos = 8;
+    while pos < data.len() {
+        let len = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        // length + type + data + crc
+        let chunk = data.get(pos..pos + 12 + len)?;
+        let chunk_type = &chunk[4..8];
+        if !DROPPED_CHUNKS
+            .iter()
+            .any(|dropped| dropped.as_slice() == chunk_type)
+        {
+            out.extend_from_slice(chunk);
+        }
+        pos += 12 + len;
+        if chunk_type == b"IEND" {
+            break;
+        }
+    }
+    Some(out)
+}
+
 pub fn build_multimodal_message(
     prompt: &str,
     image_data: &[u8],
@@ -273,6 +411,7 @@ pub fn build_multimodal_message(
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     } else if provider_lower.contains("gemini") || provider_lower.contains("google") {
         Message {
@@ -294,6 +433,7 @@ pub fn build_multimodal_message(
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     } else {
         // Default to OpenAI-compatible payload shape for OpenAI and most OpenAI-compatible providers.
@@ -317,6 +457,7 @@ pub fn build_multimodal_message(
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     };
 
@@ -325,11 +466,9 @@ pub fn build_multimodal_message(
 
 pub async fn process_image_contexts_for_provider(
     image_contexts: &[ImageContextData],
-    provider: &str,
+    limits: &ImageLimits,
     workspace_path: Option<&Path>,
 ) -> BitFunResult<Vec<ProcessedImage>> {
-    let limits = ImageLimits::for_provider(provider);
-
     if image_contexts.len() > limits.max_images_per_request {
         return Err(BitFunError::validation(format!(
             "Too many images in one request: {} > {}",
@@ -357,7 +496,7 @@ pub async fn process_image_contexts_for_provider(
         };
 
         let processed =
-            optimize_image_for_provider(image_data, provider, fallback_mime.as_deref())?;
+            optimize_image_with_limits(image_data, limits, fallback_mime.as_deref(), None)?;
         results.push(processed);
     }
 
@@ -433,6 +572,7 @@ pub fn build_multimodal_message_with_images(
         tool_call_id: None,
         name: None,
         tool_image_attachments: None,
+        server_tool_events: None,
     }])
 }
 
@@ -487,3 +627,95 @@ fn encode_dynamic_image(
 
     Ok((buffer, mime))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    /// Noisy image that compresses poorly, like a busy screenshot.
+    fn noisy_png(width: u32, height: u32) -> Vec<u8> {
+        let mut seed: u32 = 0x2545_f491;
+        let image = RgbImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let [r, g, b, _] = seed.to_le_bytes();
+            Rgb([r, g, b])
+        });
+        encode_dynamic_image(&DynamicImage::ImageRgb8(image), ImageFormat::Png, 85)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn downscales_large_images_to_provider_limits() {
+        let data = noisy_png(2600, 1800);
+        let original_size = data.len();
+        let limits = ImageLimits::for_provider("anthropic");
+
+        let processed = optimize_image_with_limits(data, &limits, None, None).unwrap();
+
+        assert!(processed.width <= limits.max_width);
+        assert!(processed.height <= limits.max_height);
+        assert!(processed.data.len() <= limits.max_size);
+        assert_eq!(processed.original_size, original_size);
+        assert!(processed.data.len() < original_size);
+    }
+
+    #[test]
+    fn respects_explicit_size_cap_and_max_dimension() {
+        let data = noisy_png(1800, 1200);
+        let limits = ImageLimits::for_model("openai", Some(1024), Some(70));
+
+        let processed = optimize_image_with_limits(data, &limits, None, Some(200 * 1024)).unwrap();
+
+        assert!(processed.width <= 1024 && processed.height <= 1024);
+        assert!(processed.data.len() <= 200 * 1024);
+        assert_eq!(processed.mime_type, "image/jpeg");
+    }
+
+    #[test]
+    fn strips_exif_from_images_that_already_fit() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(32, 16, Rgb([200, 10, 10])));
+        let jpeg = encode_dynamic_image(&image, ImageFormat::Jpeg, 90)
+            .unwrap()
+            .0;
+
+        let exif_payload = b"Exif\0\0GPSLatitude=52.5200;GPSLongitude=13.4050";
+        let mut with_exif = jpeg[..2].to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1]);
+        with_exif.extend_from_slice(&((exif_payload.len() + 2) as u16).to_be_bytes());
+        with_exif.extend_from_slice(exif_payload);
+        with_exif.extend_from_slice(&jpeg[2..]);
+
+        let processed =
+            optimize_image_with_limits(with_exif, &ImageLimits::default(), None, None).unwrap();
+
+        assert!(!processed
+            .data
+            .windows(b"GPSLatitude".len())
+            .any(|w| w == b"GPSLatitude"));
+        assert_eq!((processed.width, processed.height), (32, 16));
+        let decoded = image::load_from_memory(&processed.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (32, 16));
+    }
+
+    #[test]
+    fn quality_override_is_clamped() {
+        assert_eq!(
+            ImageLimits::default()
+                .with_jpeg_quality(Some(0))
+                .jpeg_quality,
+            1
+        );
+        assert_eq!(
+            ImageLimits::default()
+                .with_jpeg_quality(Some(120))
+                .jpeg_quality,
+            100
+        );
+        assert_eq!(
+            ImageLimits::default().with_jpeg_quality(None).jpeg_quality,
+            crate::agentic::image_analysis::DEFAULT_JPEG_QUALITY
+        );
+    }
+}
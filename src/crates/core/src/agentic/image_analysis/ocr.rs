@@ -0,0 +1,145 @@
+//! Local OCR
+//!
+//! Extracts the literal text of screenshots (error dialogs, terminal output) with Tesseract
+//! before the vision model runs. Built only with the `ocr` feature; without it, or when the
+//! Tesseract runtime or its language data is missing, OCR reports itself unavailable and
+//! image analysis falls back to the vision model alone.
+
+use super::types::OcrText;
+use log::debug;
+#[cfg(feature = "ocr")]
+use log::warn;
+use std::sync::OnceLock;
+
+/// Minimum OCR confidence (0-1) for the text to stand in for a model description.
+pub const OCR_TEXT_CONFIDENCE_THRESHOLD: f32 = 0.8;
+
+/// Minimum non-whitespace characters for an image to count as text-dominant.
+pub const OCR_MIN_TEXT_CHARS: usize = 40;
+
+#[cfg(feature = "ocr")]
+const OCR_LANGUAGE: &str = "eng";
+
+/// Whether the OCR engine can be initialized. Probed once per process.
+pub fn is_ocr_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let available = probe_engine();
+        if !available {
+            debug!("Local OCR is unavailable, image analysis will use the vision model only");
+        }
+        available
+    })
+}
+
+#[cfg(feature = "ocr")]
+fn probe_engine() -> bool {
+    match leptess::LepTess::new(None, OCR_LANGUAGE) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Failed to initialize Tesseract: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(not(feature = "ocr"))]
+fn probe_engine() -> bool {
+    false
+}
+
+/// Runs OCR on encoded image bytes (PNG, JPEG, ...). Returns `None` when OCR is unavailable,
+/// fails, or finds no text. Blocking; call from `spawn_blocking`.
+pub fn extract_text(image_data: &[u8]) -> Option<OcrText> {
+    if !is_ocr_available() {
+        return None;
+    }
+    run_engine(image_data)
+}
+
+#[cfg(feature = "ocr")]
+fn run_engine(image_data: &[u8]) -> Option<OcrText> {
+    let mut engine = leptess::LepTess::new(None, OCR_LANGUAGE)
+        .map_err(|e| warn!("Failed to initialize Tesseract: {}", e))
+        .ok()?;
+    engine
+        .set_image_from_mem(image_data)
+        .map_err(|e| warn!("OCR could not read image: {}", e))
+        .ok()?;
+    let raw = engine
+        .get_utf8_text()
+        .map_err(|e| warn!("OCR returned invalid text: {}", e))
+        .ok()?;
+
+    let text = normalize_ocr_text(&raw);
+    if text.is_empty() {
+        return None;
+    }
+    Some(OcrText {
+        text,
+        confidence: (engine.mean_text_conf().clamp(0, 100) as f32) / 100.0,
+    })
+}
+
+#[cfg(not(feature = "ocr"))]
+fn run_engine(_image_data: &[u8]) -> Option<OcrText> {
+    None
+}
+
+/// Trims trailing whitespace and collapses runs of blank lines left by the OCR layout pass.
+pub fn normalize_ocr_text(raw: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in raw.lines().map(str::trim_end) {
+        if line.trim().is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(if line.trim().is_empty() { "" } else { line });
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Whether the OCR text is reliable and substantial enough to replace a vision-model
+/// description.
+pub fn is_text_dominant(ocr: &OcrText) -> bool {
+    ocr.confidence >= OCR_TEXT_CONFIDENCE_THRESHOLD
+        && ocr.text.chars().filter(|c| !c.is_whitespace()).count() >= OCR_MIN_TEXT_CHARS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_blank_lines_and_trailing_space() {
+        let raw = "\n\nerror: cannot find value `x`   \n\n\n  --> src/main.rs:2:5\n\n";
+        assert_eq!(
+            normalize_ocr_text(raw),
+            "error: cannot find value `x`\n\n  --> src/main.rs:2:5"
+        );
+    }
+
+    #[test]
+    fn text_dominance_needs_confidence_and_length() {
+        let long = "Permission denied (publickey). Connection closed by remote host.".to_string();
+        let confident = OcrText {
+            text: long.clone(),
+            confidence: 0.9,
+        };
+        assert!(is_text_dominant(&confident));
+
+        let unsure = OcrText {
+            text: long,
+            confidence: 0.5,
+        };
+        assert!(!is_text_dominant(&unsure));
+
+        let short = OcrText {
+            text: "OK".to_string(),
+            confidence: 0.95,
+        };
+        assert!(!is_text_dominant(&short));
+    }
+}
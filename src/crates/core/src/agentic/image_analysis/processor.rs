@@ -2,22 +2,101 @@
 //!
 //! Handles image loading, preprocessing, multimodal message construction, and response parsing.
 
+use super::cache::{ImageAnalysisCache, ImageAnalysisCacheKey};
 use super::image_processing::{
-    build_multimodal_message, decode_data_url, detect_mime_type_from_bytes, load_image_from_path,
-    optimize_image_with_size_limit, resolve_image_path,
+    build_multimodal_message, build_multimodal_message_with_images, decode_data_url,
+    detect_mime_type_from_bytes, load_image_from_path, optimize_image_with_limits,
+    resolve_image_path, ProcessedImage,
+};
+use super::ocr;
+use super::types::{
+    AnalyzeImagesRequest, ImageAnalysisResult, ImageContextData, ImageLimits, OcrText,
 };
-use super::types::{AnalyzeImagesRequest, ImageAnalysisResult, ImageContextData};
 use crate::infrastructure::ai::AIClient;
 use crate::service::config::types::AIModelConfig;
 use crate::util::errors::*;
 use log::{debug, error, info, warn};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+
+/// Images preprocessed or analyzed at the same time
+const MAX_CONCURRENT_IMAGE_ANALYSES: usize = 4;
+
+/// Images described together in one multi-image request
+const MAX_IMAGES_PER_BATCH_REQUEST: usize = 5;
+
+/// Size budget for an image sent to the vision model
+const IMAGE_ANALYSIS_MAX_BYTES: usize = 1024 * 1024;
+
+/// Shared inputs of one `analyze_images` call
+struct AnalysisContext {
+    model: AIModelConfig,
+    /// `provider/model`, the model part of cache keys
+    model_key: String,
+    /// Single-image prompt; batch results are cached under it too
+    prompt: String,
+    user_context: Option<String>,
+    workspace_path: Option<PathBuf>,
+    ai_client: Arc<AIClient>,
+    cache: Option<ImageAnalysisCache>,
+}
+
+impl AnalysisContext {
+    fn cache_key<'a>(&'a self, processed: &'a ProcessedImage) -> ImageAnalysisCacheKey<'a> {
+        ImageAnalysisCacheKey {
+            model: &self.model_key,
+            image_data: &processed.data,
+            prompt: &self.prompt,
+        }
+    }
+
+    /// Fills in timing, sizes and OCR text, and caches the model's analysis
+    async fn finish(
+        &self,
+        mut result: ImageAnalysisResult,
+        image: PendingImage,
+    ) -> ImageAnalysisResult {
+        result.analysis_time_ms = image.started.elapsed().as_millis() as u64;
+        result.ocr = image.ocr;
+        result.original_size = image.processed.original_size as u64;
+        result.processed_size = image.processed.data.len() as u64;
+
+        if let Some(cache) = &self.cache {
+            cache.put(&self.cache_key(&image.processed), &result).await;
+        }
+
+        info!(
+            "Image analysis completed: image_id={}, duration={}ms",
+            image.image_id, result.analysis_time_ms
+        );
+        result
+    }
+}
+
+/// An image that still needs the vision model
+struct PendingImage {
+    image_id: String,
+    file_name: Option<String>,
+    processed: ProcessedImage,
+    ocr: Option<OcrText>,
+    started: Instant,
+}
+
+enum PreparedImage {
+    Done(ImageAnalysisResult),
+    Pending(PendingImage),
+}
 
 /// Image Analyzer
 pub struct ImageAnalyzer {
     workspace_path: Option<PathBuf>,
     ai_client: Arc<AIClient>,
+    cache: Option<ImageAnalysisCache>,
 }
 
 impl ImageAnalyzer {
@@ -25,97 +104,244 @@ impl ImageAnalyzer {
         Self {
             workspace_path,
             ai_client,
+            cache: ImageAnalysisCache::global(),
         }
     }
 
     /// Analyze multiple images
+    ///
+    /// Images are preprocessed a few at a time; when the provider accepts several images per
+    /// prompt, the ones that still need the vision model are described together in one request.
+    /// Results keep the order of `request.images`. An image that cannot be analyzed yields a
+    /// result with `error` set instead of failing the batch; the call only fails when no image
+    /// could be analyzed.
     pub async fn analyze_images(
         &self,
         request: AnalyzeImagesRequest,
         model_config: &AIModelConfig,
     ) -> BitFunResult<Vec<ImageAnalysisResult>> {
-        info!("Starting analysis of {} images", request.images.len());
-
-        let mut tasks = vec![];
-
-        for img_ctx in request.images {
-            let model = model_config.clone();
-            let user_msg = request.user_message.clone();
-            let workspace = self.workspace_path.clone();
-            let ai_client = self.ai_client.clone();
-
-            let task = tokio::spawn(async move {
-                Self::analyze_single_image(
-                    img_ctx,
-                    &model,
-                    user_msg.as_deref(),
-                    workspace,
-                    ai_client,
-                )
-                .await
-            });
-
-            tasks.push(task);
+        let total = request.images.len();
+        info!("Starting analysis of {} images", total);
+
+        let ctx = Arc::new(AnalysisContext {
+            prompt: Self::build_image_analysis_prompt(request.user_message.as_deref()),
+            model_key: format!("{}/{}", model_config.provider, model_config.model_name),
+            user_context: request.user_message.clone(),
+            model: model_config.clone(),
+            workspace_path: self.workspace_path.clone(),
+            ai_client: self.ai_client.clone(),
+            cache: self.cache.clone(),
+        });
+        let labels: Vec<(String, Option<String>)> = request
+            .images
+            .iter()
+            .map(|img| (img.id.clone(), image_file_name(img)))
+            .collect();
+        let mut results: Vec<Option<ImageAnalysisResult>> = vec![None; total];
+
+        // 1. Load, OCR, preprocess and check the cache
+        let prepared = run_bounded(
+            request
+                .images
+                .into_iter()
+                .map(|img| {
+                    let ctx = ctx.clone();
+                    async move { Self::prepare_image(img, &ctx).await }
+                })
+                .collect(),
+        )
+        .await;
+
+        let mut pending = Vec::new();
+        for (index, outcome) in prepared.into_iter().enumerate() {
+            match outcome.and_then(|prepared| prepared.map_err(|e| e.to_string())) {
+                Ok(PreparedImage::Done(result)) => results[index] = Some(result),
+                Ok(PreparedImage::Pending(image)) => pending.push((index, image)),
+                Err(e) => results[index] = Some(failed_result(&labels[index].0, e)),
+            }
         }
 
-        let mut results = vec![];
-        for task in tasks {
-            match task.await {
-                Ok(Ok(result)) => results.push(result),
-                Ok(Err(e)) => {
-                    error!("Image analysis failed: {:?}", e);
-                    return Err(e);
+        // 2. One multi-image request per chunk; images it leaves undescribed fall through
+        if pending.len() > 1 && supports_multi_image_prompt(&ctx.model.provider) {
+            let mut chunks: Vec<Vec<(usize, PendingImage)>> = Vec::new();
+            for item in pending {
+                match chunks.last_mut() {
+                    Some(chunk) if chunk.len() < MAX_IMAGES_PER_BATCH_REQUEST => chunk.push(item),
+                    _ => chunks.push(vec![item]),
                 }
-                Err(e) => {
-                    error!("Image analysis task failed: {:?}", e);
-                    return Err(BitFunError::service(format!(
-                        "Image analysis task failed: {}",
-                        e
-                    )));
+            }
+            let chunk_indices: Vec<Vec<usize>> = chunks
+                .iter()
+                .map(|chunk| chunk.iter().map(|(index, _)| *index).collect())
+                .collect();
+
+            let outcomes = run_bounded(
+                chunks
+                    .into_iter()
+                    .map(|chunk| {
+                        let ctx = ctx.clone();
+                        async move { Self::analyze_batch(chunk, &ctx).await }
+                    })
+                    .collect(),
+            )
+            .await;
+
+            pending = Vec::new();
+            for (indices, outcome) in chunk_indices.into_iter().zip(outcomes) {
+                match outcome {
+                    Ok((done, leftover)) => {
+                        for (index, result) in done {
+                            results[index] = Some(result);
+                        }
+                        pending.extend(leftover);
+                    }
+                    Err(e) => {
+                        for index in indices {
+                            results[index] = Some(failed_result(&labels[index].0, e.clone()));
+                        }
+                    }
                 }
             }
         }
 
-        info!("All image analysis completed");
+        // 3. Everything else, one request per image
+        let pending_indices: Vec<usize> = pending.iter().map(|(index, _)| *index).collect();
+        let outcomes = run_bounded(
+            pending
+                .into_iter()
+                .map(|(_, image)| {
+                    let ctx = ctx.clone();
+                    async move { Self::analyze_pending_image(image, &ctx).await }
+                })
+                .collect(),
+        )
+        .await;
+        for (index, outcome) in pending_indices.into_iter().zip(outcomes) {
+            results[index] = Some(
+                match outcome.and_then(|result| result.map_err(|e| e.to_string())) {
+                    Ok(result) => result,
+                    Err(e) => failed_result(&labels[index].0, e),
+                },
+            );
+        }
+
+        let results: Vec<ImageAnalysisResult> = results
+            .into_iter()
+            .zip(labels)
+            .map(|(result, (image_id, file_name))| {
+                let mut result = result.unwrap_or_else(|| {
+                    failed_result(&image_id, "Image analysis did not complete".to_string())
+                });
+                result.file_name = file_name;
+                result
+            })
+            .collect();
+
+        let failed: Vec<&ImageAnalysisResult> = results
+            .iter()
+            .filter(|result| result.error.is_some())
+            .collect();
+        if total > 0 && failed.len() == total {
+            let reason = failed[0].error.clone().unwrap_or_default();
+            error!("Image analysis failed for all {} images: {}", total, reason);
+            return Err(BitFunError::service(format!(
+                "Image analysis failed for all {} images: {}",
+                total, reason
+            )));
+        }
+        if !failed.is_empty() {
+            warn!(
+                "Image analysis completed with {} of {} images failed",
+                failed.len(),
+                total
+            );
+        } else {
+            info!("All image analysis completed");
+        }
         Ok(results)
     }
 
-    async fn analyze_single_image(
+    /// Everything up to the vision-model call; OCR-only and cached images finish here
+    async fn prepare_image(
         image_ctx: ImageContextData,
-        model: &AIModelConfig,
-        user_context: Option<&str>,
-        workspace_path: Option<PathBuf>,
-        ai_client: Arc<AIClient>,
-    ) -> BitFunResult<ImageAnalysisResult> {
-        let start = std::time::Instant::now();
+        ctx: &AnalysisContext,
+    ) -> BitFunResult<PreparedImage> {
+        let started = Instant::now();
+        let model = &ctx.model;
 
         debug!("Analyzing image: {}", image_ctx.id);
 
         let (image_data, fallback_mime) =
-            Self::load_image_from_context(&image_ctx, workspace_path.as_deref()).await?;
+            Self::load_image_from_context(&image_ctx, ctx.workspace_path.as_deref()).await?;
 
-        const IMAGE_ANALYSIS_MAX_BYTES: usize = 1024 * 1024;
-        let processed = optimize_image_with_size_limit(
-            image_data,
+        let ocr_text = if model.image_ocr.unwrap_or(true) {
+            Self::run_ocr(&image_ctx.id, &image_data).await
+        } else {
+            None
+        };
+        if let Some(ocr_text) = ocr_text.as_ref().filter(|ocr| ocr::is_text_dominant(ocr)) {
+            info!(
+                "Image is text-dominant, skipping vision model: image_id={}, ocr_confidence={:.2}",
+                image_ctx.id, ocr_text.confidence
+            );
+            let mut result = Self::ocr_only_result(&image_ctx.id, ocr_text.clone());
+            result.analysis_time_ms = started.elapsed().as_millis() as u64;
+            result.original_size = image_data.len() as u64;
+            return Ok(PreparedImage::Done(result));
+        }
+
+        let limits = ImageLimits::for_model(
             &model.provider,
+            model.image_max_dimension,
+            model.image_quality,
+        );
+        let processed = optimize_image_with_limits(
+            image_data,
+            &limits,
             fallback_mime.as_deref(),
             Some(IMAGE_ANALYSIS_MAX_BYTES),
         )?;
 
         debug!(
-            "Image processing completed: mime={}, size={}KB, dimensions={}x{}",
+            "Image processing completed: mime={}, size={}KB -> {}KB, dimensions={}x{}",
             processed.mime_type,
+            processed.original_size / 1024,
             processed.data.len() / 1024,
             processed.width,
             processed.height
         );
 
-        let analysis_prompt = Self::build_image_analysis_prompt(user_context);
+        if let Some(cache) = &ctx.cache {
+            if let Some(mut cached) = cache.get(&ctx.cache_key(&processed), &image_ctx.id).await {
+                cached.analysis_time_ms = started.elapsed().as_millis() as u64;
+                cached.ocr = ocr_text;
+                info!(
+                    "Image analysis served from cache: image_id={}, model={}",
+                    image_ctx.id, model.model_name
+                );
+                return Ok(PreparedImage::Done(cached));
+            }
+        }
 
+        Ok(PreparedImage::Pending(PendingImage {
+            file_name: image_file_name(&image_ctx),
+            image_id: image_ctx.id,
+            processed,
+            ocr: ocr_text,
+            started,
+        }))
+    }
+
+    /// Describes one image with its own vision-model call
+    async fn analyze_pending_image(
+        image: PendingImage,
+        ctx: &AnalysisContext,
+    ) -> BitFunResult<ImageAnalysisResult> {
+        let model = &ctx.model;
         let messages = build_multimodal_message(
-            &analysis_prompt,
-            &processed.data,
-            &processed.mime_type,
+            &ctx.prompt,
+            &image.processed.data,
+            &image.processed.mime_type,
             &model.provider,
         )?;
 
@@ -127,24 +353,147 @@ impl ImageAnalyzer {
 
         debug!(
             "Calling vision model: image_id={}, model={}",
-            image_ctx.id, model.model_name
+            image.image_id, model.model_name
         );
-        let ai_response = ai_client.send_message(messages, None).await.map_err(|e| {
-            error!("AI call failed: {}", e);
-            BitFunError::service(format!("Image analysis AI call failed: {}", e))
-        })?;
+        let ai_response = ctx
+            .ai_client
+            .send_message(messages, None)
+            .await
+            .map_err(|e| {
+                error!("AI call failed: {}", e);
+                BitFunError::service(format!("Image analysis AI call failed: {}", e))
+            })?;
 
         debug!("AI response content: {}", ai_response.text);
 
-        let mut analysis_result = Self::parse_analysis_response(&ai_response.text, &image_ctx.id);
-        analysis_result.analysis_time_ms = start.elapsed().as_millis() as u64;
+        let result = Self::parse_analysis_response(&ai_response.text, &image.image_id);
+        Ok(ctx.finish(result, image).await)
+    }
+
+    /// Describes a chunk of images in one multi-image request. Returns the analyzed images and
+    /// the ones the response did not cover (all of them if the request failed).
+    async fn analyze_batch(
+        images: Vec<(usize, PendingImage)>,
+        ctx: &AnalysisContext,
+    ) -> (
+        Vec<(usize, ImageAnalysisResult)>,
+        Vec<(usize, PendingImage)>,
+    ) {
+        let model = &ctx.model;
+        let file_names: Vec<Option<&str>> = images
+            .iter()
+            .map(|(_, image)| image.file_name.as_deref())
+            .collect();
+        let prompt = Self::build_batch_analysis_prompt(&file_names, ctx.user_context.as_deref());
+        let processed: Vec<ProcessedImage> = images
+            .iter()
+            .map(|(_, image)| image.processed.clone())
+            .collect();
 
-        info!(
-            "Image analysis completed: image_id={}, duration={}ms",
-            image_ctx.id, analysis_result.analysis_time_ms
+        debug!(
+            "Calling vision model with {} images: model={}",
+            images.len(),
+            model.model_name
         );
+        let response =
+            match build_multimodal_message_with_images(&prompt, &processed, &model.provider) {
+                Ok(messages) => ctx
+                    .ai_client
+                    .send_message(messages, None)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(
+                    "Multi-image analysis failed, analyzing images individually: count={}, error={}",
+                    images.len(),
+                    e
+                );
+                return (Vec::new(), images);
+            }
+        };
+
+        debug!("AI response content: {}", response.text);
+
+        let image_ids: Vec<&str> = images
+            .iter()
+            .map(|(_, image)| image.image_id.as_str())
+            .collect();
+        let parsed = Self::parse_batch_analysis_response(&response.text, &image_ids);
+
+        let mut done = Vec::new();
+        let mut leftover = Vec::new();
+        for ((index, image), result) in images.into_iter().zip(parsed) {
+            match result {
+                Some(result) => done.push((index, ctx.finish(result, image).await)),
+                None => {
+                    debug!(
+                        "Multi-image response did not describe image, retrying individually: image_id={}",
+                        image.image_id
+                    );
+                    leftover.push((index, image));
+                }
+            }
+        }
+        (done, leftover)
+    }
+
+    async fn run_ocr(image_id: &str, image_data: &[u8]) -> Option<OcrText> {
+        if !ocr::is_ocr_available() {
+            return None;
+        }
 
-        Ok(analysis_result)
+        let data = image_data.to_vec();
+        match tokio::task::spawn_blocking(move || ocr::extract_text(&data)).await {
+            Ok(result) => {
+                if let Some(text) = &result {
+                    debug!(
+                        "OCR completed: image_id={}, chars={}, confidence={:.2}",
+                        image_id,
+                        text.text.chars().count(),
+                        text.confidence
+                    );
+                }
+                result
+            }
+            Err(e) => {
+                warn!("OCR task failed: image_id={}, error={}", image_id, e);
+                None
+            }
+        }
+    }
+
+    fn ocr_only_result(image_id: &str, ocr_text: OcrText) -> ImageAnalysisResult {
+        let summary = ocr_text
+            .text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| {
+                format!(
+                    "Text-dominant image starting with \"{}\"",
+                    line.trim().chars().take(120).collect::<String>()
+                )
+            })
+            .unwrap_or_else(|| "Text-dominant image".to_string());
+
+        ImageAnalysisResult {
+            image_id: image_id.to_string(),
+            summary,
+            detailed_description: String::new(),
+            detected_elements: Vec::new(),
+            confidence: ocr_text.confidence,
+            analysis_time_ms: 0,
+            ocr: Some(ocr_text),
+            ocr_only: true,
+            original_size: 0,
+            processed_size: 0,
+            cache_hit: false,
+            file_name: None,
+            error: None,
+        }
     }
 
     async fn load_image_from_context(
@@ -197,33 +546,109 @@ impl ImageAnalyzer {
         prompt
     }
 
-    fn parse_analysis_response(response: &str, image_id: &str) -> ImageAnalysisResult {
+    /// Prompt for several images in one request; answers are matched back by `index`
+    fn build_batch_analysis_prompt(
+        file_names: &[Option<&str>],
+        user_context: Option<&str>,
+    ) -> String {
+        let count = file_names.len();
+        let mut prompt = format!(
+            "You are given {} images, numbered 1 to {} in the order they are attached:\n",
+            count, count
+        );
+        for (idx, file_name) in file_names.iter().enumerate() {
+            match file_name {
+                Some(name) => prompt.push_str(&format!("- Image {}: {}\n", idx + 1, name)),
+                None => prompt.push_str(&format!("- Image {}\n", idx + 1)),
+            }
+        }
+        prompt.push_str(
+            "\nAnalyze each image in detail, separately. Output in the following JSON format, with exactly one entry per image:\n\n\
+            ```json\n\
+            {\n  \
+              \"images\": [\n    \
+                {\n      \
+                  \"index\": <image number>,\n      \
+                  \"summary\": \"<one-sentence summary of image content>\",\n      \
+                  \"detailed_description\": \"<detailed description of elements, layout, text, etc.>\",\n      \
+                  \"detected_elements\": [\"<key element 1>\", \"<key element 2>\", ...],\n      \
+                  \"confidence\": <number between 0-1, representing analysis confidence>\n    \
+                }\n  \
+              ]\n\
+            }\n\
+            ```\n\n\
+            Requirements:\n\
+            1. Describe every image on its own; never merge or compare images inside one entry\n\
+            2. summary should be concise and accurate, 1-2 sentences\n\
+            3. detailed_description should be comprehensive, including colors, positions, relationships, etc.\n\
+            4. detected_elements should extract 5-10 key elements\n\
+            5. If an image contains code, architecture diagrams, flowcharts, or other technical content, focus on technical details\n\
+            6. Output JSON directly, no additional explanations\n",
+        );
+
+        if let Some(context) = user_context {
+            prompt.push_str(&format!(
+                "\nThe user's question is: \"{}\"\nPlease analyze in conjunction with the user's intent.\n",
+                context
+            ));
+        }
+
+        prompt
+    }
+
+    /// One slot per entry of `image_ids`; `None` where the response has no usable description
+    fn parse_batch_analysis_response(
+        response: &str,
+        image_ids: &[&str],
+    ) -> Vec<Option<ImageAnalysisResult>> {
+        let mut results: Vec<Option<ImageAnalysisResult>> = vec![None; image_ids.len()];
+
         let extracted = crate::util::extract_json_from_ai_response(response);
         let json_str = extracted.as_deref().unwrap_or(response);
+        let Ok(parsed) = serde_json::from_str::<Value>(json_str) else {
+            warn!(
+                "Multi-image analysis response is not valid JSON: images={}",
+                image_ids.len()
+            );
+            return results;
+        };
 
-        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(json_str) {
-            return ImageAnalysisResult {
-                image_id: image_id.to_string(),
-                summary: parsed["summary"]
-                    .as_str()
-                    .unwrap_or("Image analysis completed")
-                    .to_string(),
-                detailed_description: parsed["detailed_description"]
-                    .as_str()
-                    .unwrap_or(response)
-                    .to_string(),
-                detected_elements: parsed["detected_elements"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_str())
-                            .map(String::from)
-                            .collect()
-                    })
-                    .unwrap_or_default(),
-                confidence: parsed["confidence"].as_f64().unwrap_or(0.8) as f32,
-                analysis_time_ms: 0,
+        let entries = parsed["images"]
+            .as_array()
+            .or_else(|| parsed.as_array())
+            .cloned()
+            .unwrap_or_default();
+        for entry in &entries {
+            let Some(index) = entry["index"].as_u64() else {
+                continue;
+            };
+            if entry["summary"].as_str().is_none() {
+                continue;
+            }
+            let Some(slot) = (index as usize)
+                .checked_sub(1)
+                .and_then(|idx| results.get_mut(idx))
+            else {
+                continue;
             };
+            if slot.is_none() {
+                *slot = Some(Self::analysis_from_json(
+                    entry,
+                    image_ids[index as usize - 1],
+                    "",
+                ));
+            }
+        }
+
+        results
+    }
+
+    fn parse_analysis_response(response: &str, image_id: &str) -> ImageAnalysisResult {
+        let extracted = crate::util::extract_json_from_ai_response(response);
+        let json_str = extracted.as_deref().unwrap_or(response);
+
+        if let Ok(parsed) = serde_json::from_str::<Value>(json_str) {
+            return Self::analysis_from_json(&parsed, image_id, response);
         }
 
         warn!(
@@ -251,7 +676,197 @@ impl ImageAnalyzer {
             detected_elements: Vec::new(),
             confidence: 0.5,
             analysis_time_ms: 0,
+            ocr: None,
+            ocr_only: false,
+            original_size: 0,
+            processed_size: 0,
+            cache_hit: false,
+            file_name: None,
+            error: None,
+        }
+    }
+
+    fn analysis_from_json(
+        parsed: &Value,
+        image_id: &str,
+        fallback_description: &str,
+    ) -> ImageAnalysisResult {
+        ImageAnalysisResult {
+            image_id: image_id.to_string(),
+            summary: parsed["summary"]
+                .as_str()
+                .unwrap_or("Image analysis completed")
+                .to_string(),
+            detailed_description: parsed["detailed_description"]
+                .as_str()
+                .unwrap_or(fallback_description)
+                .to_string(),
+            detected_elements: parsed["detected_elements"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            confidence: parsed["confidence"].as_f64().unwrap_or(0.8) as f32,
+            analysis_time_ms: 0,
+            ocr: None,
+            ocr_only: false,
+            original_size: 0,
+            processed_size: 0,
+            cache_hit: false,
+            file_name: None,
+            error: None,
+        }
+    }
+}
+
+/// Runs `tasks` with at most `MAX_CONCURRENT_IMAGE_ANALYSES` in flight. Outputs keep the order
+/// of `tasks`; a panicked task yields `Err` in its own slot.
+async fn run_bounded<T, F>(tasks: Vec<F>) -> Vec<Result<T, String>>
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let mut outputs: Vec<Option<Result<T, String>>> = (0..tasks.len()).map(|_| None).collect();
+    let mut queue = tasks.into_iter().enumerate();
+    let mut running = JoinSet::new();
+    let mut positions = HashMap::new();
+
+    loop {
+        while running.len() < MAX_CONCURRENT_IMAGE_ANALYSES {
+            let Some((index, task)) = queue.next() else {
+                break;
+            };
+            positions.insert(running.spawn(task).id(), index);
+        }
+
+        let Some(joined) = running.join_next_with_id().await else {
+            break;
+        };
+        let (id, output) = match joined {
+            Ok((id, output)) => (id, Ok(output)),
+            Err(e) => {
+                error!("Image analysis task failed: {:?}", e);
+                (e.id(), Err(format!("Image analysis task failed: {}", e)))
+            }
+        };
+        if let Some(index) = positions.remove(&id) {
+            outputs[index] = Some(output);
         }
     }
 
+    outputs
+        .into_iter()
+        .map(|output| output.unwrap_or_else(|| Err("Image analysis task did not run".to_string())))
+        .collect()
+}
+
+fn failed_result(image_id: &str, error: String) -> ImageAnalysisResult {
+    warn!(
+        "Image analysis failed: image_id={}, error={}",
+        image_id, error
+    );
+    ImageAnalysisResult {
+        image_id: image_id.to_string(),
+        summary: String::new(),
+        detailed_description: String::new(),
+        detected_elements: Vec::new(),
+        confidence: 0.0,
+        analysis_time_ms: 0,
+        ocr: None,
+        ocr_only: false,
+        original_size: 0,
+        processed_size: 0,
+        cache_hit: false,
+        file_name: None,
+        error: Some(error),
+    }
+}
+
+/// Attachment name from the context metadata, else the file name of its path
+fn image_file_name(ctx: &ImageContextData) -> Option<String> {
+    ctx.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("name"))
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            ctx.image_path
+                .as_deref()
+                .and_then(|path| Path::new(path).file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+}
+
+/// Providers whose vision APIs take several images in one user message
+fn supports_multi_image_prompt(provider: &str) -> bool {
+    let provider = provider.to_lowercase();
+    [
+        "openai",
+        "response",
+        "anthropic",
+        "gemini",
+        "google",
+        "openrouter",
+    ]
+    .iter()
+    .any(|known| provider.contains(known))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_response_is_matched_back_by_index() {
+        let response = r#"```json
+{
+  "images": [
+    {"index": 2, "summary": "A stack trace", "detailed_description": "Panic in main", "detected_elements": ["terminal"], "confidence": 0.9},
+    {"index": 1, "summary": "A login form", "detailed_description": "Two fields", "detected_elements": [], "confidence": 0.7},
+    {"index": 1, "summary": "Duplicate entry"},
+    {"index": 7, "summary": "Out of range"}
+  ]
+}
+```"#;
+        let results =
+            ImageAnalyzer::parse_batch_analysis_response(response, &["img_a", "img_b", "img_c"]);
+
+        assert_eq!(results.len(), 3);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.image_id, "img_a");
+        assert_eq!(first.summary, "A login form");
+        let second = results[1].as_ref().unwrap();
+        assert_eq!(second.image_id, "img_b");
+        assert_eq!(second.detected_elements, vec!["terminal".to_string()]);
+        assert!(results[2].is_none());
+
+        let unparsable = ImageAnalyzer::parse_batch_analysis_response("no json here", &["img_a"]);
+        assert!(unparsable[0].is_none());
+    }
+
+    #[tokio::test]
+    async fn bounded_runner_keeps_order_and_isolates_panics() {
+        let tasks: Vec<_> = (0..10u64)
+            .map(|i| async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10 - i)).await;
+                if i == 3 {
+                    panic!("image 3 exploded");
+                }
+                i * 10
+            })
+            .collect();
+
+        let outputs = run_bounded(tasks).await;
+
+        assert_eq!(outputs.len(), 10);
+        assert!(outputs[3].is_err());
+        for (i, output) in outputs.iter().enumerate().filter(|(i, _)| *i != 3) {
+            assert_eq!(output.as_ref().unwrap(), &(i as u64 * 10));
+        }
+    }
 }
@@ -33,6 +33,35 @@ pub struct ImageAnalysisResult {
     pub confidence: f32,
     /// Analysis time (milliseconds)
     pub analysis_time_ms: u64,
+    /// Text extracted by local OCR
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr: Option<OcrText>,
+    /// Whether the vision model was skipped because the OCR text covers the image
+    #[serde(default)]
+    pub ocr_only: bool,
+    /// Size of the image as attached (bytes)
+    #[serde(default)]
+    pub original_size: u64,
+    /// Size of the image sent to the vision model after preprocessing (bytes, 0 if not sent)
+    #[serde(default)]
+    pub processed_size: u64,
+    /// Served from the analysis cache; no model call was made
+    #[serde(default)]
+    pub cache_hit: bool,
+    /// File name of the attached image, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    /// Why this image could not be analyzed; other images in the batch are unaffected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Text extracted from an image by local OCR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrText {
+    pub text: String,
+    /// Mean recognition confidence (0-1)
+    pub confidence: f32,
 }
 
 /// Image analysis request
@@ -92,6 +121,9 @@ pub struct ImageMetadata {
     pub file_size: Option<u64>,
 }
 
+/// JPEG quality for re-encoded images when the model doesn't set one
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
 /// Image model limits configuration
 #[derive(Debug, Clone)]
 pub struct ImageLimits {
@@ -103,6 +135,8 @@ pub struct ImageLimits {
     pub max_height: u32,
     /// Maximum number of images per request (no app-side cap; provider APIs may still reject).
     pub max_images_per_request: usize,
+    /// JPEG quality used when an image has to be re-encoded
+    pub jpeg_quality: u8,
 }
 
 impl Default for ImageLimits {
@@ -112,6 +146,7 @@ impl Default for ImageLimits {
             max_width: 2048,
             max_height: 2048,
             max_images_per_request: usize::MAX,
+            jpeg_quality: DEFAULT_JPEG_QUALITY,
         }
     }
 }
@@ -125,20 +160,47 @@ impl ImageLimits {
                 max_width: 2048,
                 max_height: 2048,
                 max_images_per_request: usize::MAX,
+                jpeg_quality: DEFAULT_JPEG_QUALITY,
             },
             "anthropic" => Self {
                 max_size: 5 * 1024 * 1024, // 5MB
                 max_width: 1568,
                 max_height: 2390,
                 max_images_per_request: usize::MAX,
+                jpeg_quality: DEFAULT_JPEG_QUALITY,
             },
             "google" | "gemini" => Self {
                 max_size: 10 * 1024 * 1024, // 10MB
                 max_width: 4096,
                 max_height: 4096,
                 max_images_per_request: usize::MAX,
+                jpeg_quality: DEFAULT_JPEG_QUALITY,
             },
             _ => Self::default(),
         }
     }
+
+    /// Cap both dimensions at `max_dimension` (never raises the provider limit)
+    pub fn with_max_dimension(mut self, max_dimension: Option<u32>) -> Self {
+        if let Some(max_dimension) = max_dimension.filter(|d| *d > 0) {
+            self.max_width = self.max_width.min(max_dimension);
+            self.max_height = self.max_height.min(max_dimension);
+        }
+        self
+    }
+
+    /// Override the re-encoding quality (clamped to 1-100)
+    pub fn with_jpeg_quality(mut self, quality: Option<u8>) -> Self {
+        if let Some(quality) = quality {
+            self.jpeg_quality = quality.clamp(1, 100);
+        }
+        self
+    }
+
+    /// Limits for a configured model: provider limits narrowed by the model's image settings
+    pub fn for_model(provider: &str, max_dimension: Option<u32>, jpeg_quality: Option<u8>) -> Self {
+        Self::for_provider(provider)
+            .with_max_dimension(max_dimension)
+            .with_jpeg_quality(jpeg_quality)
+    }
 }
@@ -464,6 +464,8 @@ fn rebuild_messages_from_turns(turns: &[DialogTurnData]) -> Vec<Message> {
                     tool_name: ti.tool_name.clone(),
                     arguments: ti.tool_call.input.clone(),
                     is_error: false,
+                    repaired: false,
+                    argument_error: None,
                 })
                 .collect();
 
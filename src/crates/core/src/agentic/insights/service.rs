@@ -1519,7 +1519,7 @@ fn is_rate_limit_error(e: &BitFunError) -> bool {
 }
 
 fn is_retryable_error(e: &BitFunError) -> bool {
-    if is_rate_limit_error(e) {
+    if e.is_retryable() || is_rate_limit_error(e) {
         return true;
     }
     let msg = e.to_string().to_lowercase();
@@ -22,12 +22,21 @@ pub mod coordination;
 /// Round-boundary yield when user queues a message during an active turn
 pub mod round_preempt;
 
+/// Root cancellation token per dialog turn, with the work registered under it
+pub mod turn_cancellation;
+
+/// Per-session execution state, kept apart from other sessions running at the same time
+pub mod session_runtime;
+
 // Image analysis module
 pub mod image_analysis;
 
 // Ephemeral side-question module (used by desktop /btw overlay)
 pub mod side_question;
 
+// Three-bullet summaries of completed dialog turns
+pub mod turn_summary;
+
 // Agents module
 pub mod agents;
 pub mod workspace;
@@ -46,5 +55,7 @@ pub use execution::*;
 pub use image_analysis::{ImageAnalyzer, MessageEnhancer};
 pub use persistence::PersistenceManager;
 pub use session::*;
+pub use session_runtime::{ActiveToolExecution, SessionRuntime};
 pub use side_question::*;
+pub use turn_cancellation::{turn_scope, TurnChild, TurnScope};
 pub use workspace::{WorkspaceBackend, WorkspaceBinding};
@@ -7,6 +7,7 @@ use crate::agentic::core::{
     strip_prompt_markup, CompressionState, Message, MessageContent, Session, SessionConfig,
     SessionState, SessionSummary,
 };
+use crate::infrastructure::storage::compression;
 use crate::infrastructure::PathManager;
 use crate::service::session::{
     DialogTurnData, SessionMetadata, SessionStatus, SessionTranscriptExport,
@@ -16,7 +17,7 @@ use crate::util::errors::{BitFunError, BitFunResult};
 use log::{debug, info, warn};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
@@ -31,6 +32,11 @@ const JSON_WRITE_MAX_RETRIES: usize = 5;
 const JSON_WRITE_RETRY_BASE_DELAY_MS: u64 = 30;
 const SESSION_TRANSCRIPT_PREVIEW_CHAR_LIMIT: usize = 120;
 
+/// Idle time before a large turn or snapshot file is compressed in the background
+const PAYLOAD_COMPRESSION_IDLE: Duration = Duration::from_secs(30 * 60);
+const PAYLOAD_COMPRESSION_STARTUP_DELAY: Duration = Duration::from_secs(10 * 60);
+const PAYLOAD_COMPRESSION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 static JSON_FILE_WRITE_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
 static SESSION_INDEX_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
 
@@ -167,6 +173,14 @@ pub struct PersistenceManager {
     path_manager: Arc<PathManager>,
 }
 
+/// Result of compressing idle session payloads
+#[derive(Debug, Clone, Default)]
+pub struct PayloadCompressionReport {
+    pub files: usize,
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+}
+
 impl PersistenceManager {
     pub fn new(path_manager: Arc<PathManager>) -> BitFunResult<Self> {
         Ok(Self { path_manager })
@@ -297,15 +311,25 @@ impl PersistenceManager {
         Ok(dir)
     }
 
-    async fn read_json_optional<T: DeserializeOwned>(
+    /// Reads `path` or its compressed `.zst` form, whichever is current
+    async fn read_json_optional<T: DeserializeOwned + Send + 'static>(
         &self,
         path: &Path,
     ) -> BitFunResult<Option<T>> {
-        if !path.exists() {
+        let Some(path) = compression::existing_form(path) else {
             return Ok(None);
+        };
+
+        if compression::is_compressed(&path) {
+            // Decompressed while parsing, so the plain JSON is never held in full
+            let value =
+                tokio::task::spawn_blocking(move || compression::read_json_file::<T>(&path))
+                    .await
+                    .map_err(|e| BitFunError::io(format!("Failed to read JSON file: {}", e)))??;
+            return Ok(Some(value));
         }
 
-        let content = fs::read_to_string(path).await.map_err(|e| {
+        let content = fs::read_to_string(&path).await.map_err(|e| {
             BitFunError::io(format!(
                 "Failed to read JSON file {}: {}",
                 path.display(),
@@ -325,6 +349,69 @@ impl PersistenceManager {
     }
 
     async fn write_json_atomic<T: Serialize>(&self, path: &Path, value: &T) -> BitFunResult<()> {
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| BitFunError::serialization(format!("Failed to serialize JSON: {}", e)))?;
+        self.write_bytes_atomic(path, json.into_bytes()).await
+    }
+
+    /// Writes a turn or context snapshot payload, zstd-compressed as `<name>.json.zst`
+    /// once it reaches the configured size; the other form is removed afterwards
+    async fn write_json_payload<T: Serialize>(&self, path: &Path, value: &T) -> BitFunResult<()> {
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| BitFunError::serialization(format!("Failed to serialize JSON: {}", e)))?;
+        let compressed_path = compression::compressed_path(path);
+
+        if compression::should_compress(json.len()) {
+            let packed = compression::compress(json.as_bytes())?;
+            compression::log_ratio(path, json.len(), packed.len());
+            self.write_bytes_atomic(&compressed_path, packed).await?;
+            Self::remove_stale_file(path).await;
+        } else {
+            self.write_bytes_atomic(path, json.into_bytes()).await?;
+            Self::remove_stale_file(&compressed_path).await;
+        }
+        Ok(())
+    }
+
+    /// Removes the superseded form of a payload. Readers prefer the newer form,
+    /// so a file that cannot be removed is only wasted space.
+    async fn remove_stale_file(path: &Path) {
+        match fs::remove_file(path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove stale file {}: {}", path.display(), e),
+        }
+    }
+
+    /// Removes both forms of a payload, returning whether any existed
+    async fn remove_payload(path: &Path) -> BitFunResult<bool> {
+        let mut removed = false;
+        for path in [compression::compressed_path(path), path.to_path_buf()] {
+            match fs::remove_file(&path).await {
+                Ok(()) => removed = true,
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(BitFunError::io(format!(
+                        "Failed to delete {}: {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Index of a `<prefix>NNNN.json` or `<prefix>NNNN.json.zst` payload file
+    fn payload_index(path: &Path, prefix: &str) -> Option<usize> {
+        let name = path.file_name()?.to_str()?;
+        let stem = name
+            .strip_suffix(".json.zst")
+            .or_else(|| name.strip_suffix(".json"))?;
+        stem.strip_prefix(prefix)?.parse().ok()
+    }
+
+    async fn write_bytes_atomic(&self, path: &Path, json_bytes: Vec<u8>) -> BitFunResult<()> {
         let parent = path.parent().ok_or_else(|| {
             BitFunError::io(format!(
                 "Target path has no parent directory: {}",
@@ -336,12 +423,9 @@ impl PersistenceManager {
             .await
             .map_err(|e| BitFunError::io(format!("Failed to create parent directory: {}", e)))?;
 
-        let json = serde_json::to_string_pretty(value)
-            .map_err(|e| BitFunError::serialization(format!("Failed to serialize JSON: {}", e)))?;
         let lock = Self::get_file_write_lock(path).await;
         let _lock_guard = lock.lock().await;
 
-        let json_bytes = json.into_bytes();
         let mut last_replace_error: Option<std::io::Error> = None;
 
         for attempt in 0..=JSON_WRITE_MAX_RETRIES {
@@ -1359,7 +1443,7 @@ impl PersistenceManager {
             messages: Self::sanitize_messages_for_persistence(messages),
         };
 
-        self.write_json_atomic(
+        self.write_json_payload(
             &self.context_snapshot_path(workspace_path, session_id, turn_index),
             &snapshot,
         )
@@ -1402,17 +1486,7 @@ impl PersistenceManager {
             .await
             .map_err(|e| BitFunError::io(format!("Failed to iterate snapshots directory: {}", e)))?
         {
-            let path = entry.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("json") {
-                continue;
-            }
-            let Some(stem) = path.file_stem().and_then(|value| value.to_str()) else {
-                continue;
-            };
-            let Some(index_str) = stem.strip_prefix("context-") else {
-                continue;
-            };
-            if let Ok(index) = index_str.parse::<usize>() {
+            if let Some(index) = Self::payload_index(&entry.path(), "context-") {
                 latest = Some(latest.map(|value| value.max(index)).unwrap_or(index));
             }
         }
@@ -1451,16 +1525,7 @@ impl PersistenceManager {
             .map_err(|e| BitFunError::io(format!("Failed to iterate snapshots directory: {}", e)))?
         {
             let path = entry.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("json") {
-                continue;
-            }
-            let Some(stem) = path.file_stem().and_then(|value| value.to_str()) else {
-                continue;
-            };
-            let Some(index_str) = stem.strip_prefix("context-") else {
-                continue;
-            };
-            let Ok(index) = index_str.parse::<usize>() else {
+            let Some(index) = Self::payload_index(&path, "context-") else {
                 continue;
             };
             if index >= turn_index {
@@ -1654,7 +1719,7 @@ impl PersistenceManager {
             schema_version: SESSION_SCHEMA_VERSION,
             turn: turn.clone(),
         };
-        self.write_json_atomic(
+        self.write_json_payload(
             &self.turn_path(workspace_path, &turn.session_id, turn.turn_index),
             &file,
         )
@@ -1699,7 +1764,8 @@ impl PersistenceManager {
             return Ok(Vec::new());
         }
 
-        let mut indexed_paths = Vec::new();
+        // A turn caught between its plain and compressed forms is read once
+        let mut indices = BTreeSet::new();
         let mut entries = fs::read_dir(&turns_dir)
             .await
             .map_err(|e| BitFunError::io(format!("Failed to read turns directory: {}", e)))?;
@@ -1709,28 +1775,19 @@ impl PersistenceManager {
             .await
             .map_err(|e| BitFunError::io(format!("Failed to iterate turns directory: {}", e)))?
         {
-            let path = entry.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("json") {
-                continue;
+            if let Some(index) = Self::payload_index(&entry.path(), "turn-") {
+                indices.insert(index);
             }
-            let Some(stem) = path.file_stem().and_then(|value| value.to_str()) else {
-                continue;
-            };
-            let Some(index_str) = stem.strip_prefix("turn-") else {
-                continue;
-            };
-            let Ok(index) = index_str.parse::<usize>() else {
-                continue;
-            };
-            indexed_paths.push((index, path));
         }
 
-        indexed_paths.sort_by_key(|(index, _)| *index);
-
-        let mut turns = Vec::with_capacity(indexed_paths.len());
-        for (_, path) in indexed_paths {
+        let mut turns = Vec::with_capacity(indices.len());
+        for index in indices {
             if let Some(file) = self
-                .read_json_optional::<StoredDialogTurnFile>(&path)
+                .read_json_optional::<StoredDialogTurnFile>(&self.turn_path(
+                    workspace_path,
+                    session_id,
+                    index,
+                ))
                 .await?
             {
                 turns.push(file.turn);
@@ -1957,10 +2014,7 @@ impl PersistenceManager {
             .filter(|value| value.turn_index > turn_index)
         {
             let path = self.turn_path(workspace_path, session_id, turn.turn_index);
-            if path.exists() {
-                fs::remove_file(&path)
-                    .await
-                    .map_err(|e| BitFunError::io(format!("Failed to delete turn file: {}", e)))?;
+            if Self::remove_payload(&path).await? {
                 deleted += 1;
             }
         }
@@ -2001,10 +2055,7 @@ impl PersistenceManager {
             .filter(|value| value.turn_index >= turn_index)
         {
             let path = self.turn_path(workspace_path, session_id, turn.turn_index);
-            if path.exists() {
-                fs::remove_file(&path)
-                    .await
-                    .map_err(|e| BitFunError::io(format!("Failed to delete turn file: {}", e)))?;
+            if Self::remove_payload(&path).await? {
                 deleted += 1;
             }
         }
@@ -2031,6 +2082,112 @@ impl PersistenceManager {
         Ok(deleted)
     }
 
+    // ============ Payload compression ============
+
+    /// Compresses plain turn and context snapshot files of every session in the
+    /// workspace that are over the compression threshold and unmodified for `idle_for`
+    pub async fn compress_idle_payloads(
+        &self,
+        workspace_path: &Path,
+        idle_for: Duration,
+    ) -> BitFunResult<PayloadCompressionReport> {
+        let mut report = PayloadCompressionReport::default();
+        let settings = compression::compression_settings();
+        let sessions_dir = self.project_sessions_dir(workspace_path);
+        if !settings.enabled || !sessions_dir.exists() {
+            return Ok(report);
+        }
+
+        let mut sessions = fs::read_dir(&sessions_dir)
+            .await
+            .map_err(|e| BitFunError::io(format!("Failed to read sessions directory: {}", e)))?;
+        while let Some(session) = sessions
+            .next_entry()
+            .await
+            .map_err(|e| BitFunError::io(format!("Failed to iterate sessions directory: {}", e)))?
+        {
+            for (dir, prefix) in [
+                (session.path().join("turns"), "turn-"),
+                (session.path().join("snapshots"), "context-"),
+            ] {
+                let Ok(mut entries) = fs::read_dir(&dir).await else {
+                    continue;
+                };
+                while let Ok(Some(entry)) = entries.next_entry().await {
+                    let path = entry.path();
+                    if compression::is_compressed(&path)
+                        || Self::payload_index(&path, prefix).is_none()
+                    {
+                        continue;
+                    }
+                    let Ok(metadata) = entry.metadata().await else {
+                        continue;
+                    };
+                    let idle = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|elapsed| elapsed >= idle_for);
+                    if !idle || metadata.len() < settings.min_bytes {
+                        continue;
+                    }
+
+                    match self.compress_payload_file(&path).await {
+                        Ok(Some(compressed_len)) => {
+                            report.files += 1;
+                            report.original_bytes += metadata.len();
+                            report.compressed_bytes += compressed_len;
+                        }
+                        Ok(None) => {}
+                        Err(e) => warn!("Failed to compress {}: {}", path.display(), e),
+                    }
+                }
+            }
+        }
+
+        if report.files > 0 {
+            info!(
+                "Compressed {} idle session files in {}: {} -> {} bytes ({:.1}x)",
+                report.files,
+                workspace_path.display(),
+                report.original_bytes,
+                report.compressed_bytes,
+                report.original_bytes as f64 / report.compressed_bytes.max(1) as f64
+            );
+        }
+        Ok(report)
+    }
+
+    /// Replaces a plain payload file by its compressed form, returning the compressed
+    /// size, or `None` if the file went away in the meantime
+    async fn compress_payload_file(&self, path: &Path) -> BitFunResult<Option<u64>> {
+        // Holding the plain file's lock keeps a concurrent save of the same payload
+        // from landing between the read and the removal
+        let lock = Self::get_file_write_lock(path).await;
+        let _lock_guard = lock.lock().await;
+
+        let content = match fs::read(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(BitFunError::io(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        let packed = tokio::task::spawn_blocking(move || compression::compress(&content))
+            .await
+            .map_err(|e| BitFunError::io(format!("Failed to compress payload: {}", e)))??;
+        let compressed_len = packed.len() as u64;
+
+        self.write_bytes_atomic(&compression::compressed_path(path), packed)
+            .await?;
+        Self::remove_stale_file(path).await;
+        Ok(Some(compressed_len))
+    }
+
     pub async fn touch_session(&self, workspace_path: &Path, session_id: &str) -> BitFunResult<()> {
         if let Some(mut metadata) = self
             .load_session_metadata(workspace_path, session_id)
@@ -2266,15 +2423,47 @@ impl PersistenceManager {
     }
 }
 
+/// Periodically compresses idle session payloads of the workspaces returned by
+/// `workspaces_source`
+pub fn spawn_idle_payload_compression<F, Fut>(
+    manager: Arc<PersistenceManager>,
+    workspaces_source: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Vec<PathBuf>> + Send,
+{
+    tokio::spawn(async move {
+        tokio::time::sleep(PAYLOAD_COMPRESSION_STARTUP_DELAY).await;
+        loop {
+            for workspace_path in workspaces_source().await {
+                if let Err(e) = manager
+                    .compress_idle_payloads(&workspace_path, PAYLOAD_COMPRESSION_IDLE)
+                    .await
+                {
+                    warn!(
+                        "Idle session compression failed for {}: {}",
+                        workspace_path.display(),
+                        e
+                    );
+                }
+            }
+            tokio::time::sleep(PAYLOAD_COMPRESSION_INTERVAL).await;
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::PersistenceManager;
+    use crate::infrastructure::storage::compression;
     use crate::infrastructure::PathManager;
     use crate::service::session::{
         DialogTurnData, SessionMetadata, SessionTranscriptExportOptions, UserMessageData,
     };
     use std::path::{Path, PathBuf};
     use std::sync::Arc;
+    use std::time::Duration;
     use uuid::Uuid;
 
     struct TestWorkspace {
@@ -2388,4 +2577,66 @@ mod tests {
         assert!(transcript.contains("## Turn 0"));
         assert!(transcript.contains("hello transcript"));
     }
+    #[tokio::test]
+    async fn large_turns_are_stored_compressed_and_read_back() {
+        let workspace = TestWorkspace::new();
+        let manager = PersistenceManager::new(Arc::new(PathManager::new().expect("path manager")))
+            .expect("persistence manager");
+        let session_id = Uuid::new_v4().to_string();
+        let metadata = SessionMetadata::new(
+            session_id.clone(),
+            "Compression test".to_string(),
+            "agent".to_string(),
+            "model".to_string(),
+        );
+        manager
+            .save_session_metadata(workspace.path(), &metadata)
+            .await
+            .expect("metadata should save");
+
+        let user_message = UserMessageData {
+            id: "user-1".to_string(),
+            content: "large tool output ".repeat(20_000),
+            timestamp: 0,
+            metadata: None,
+        };
+        let turn = DialogTurnData::new("turn-1".to_string(), 0, session_id.clone(), user_message);
+        manager
+            .save_dialog_turn(workspace.path(), &turn)
+            .await
+            .expect("turn should save");
+
+        let plain_path = manager.turn_path(workspace.path(), &session_id, 0);
+        let packed_path = compression::compressed_path(&plain_path);
+        assert!(packed_path.exists());
+        assert!(!plain_path.exists());
+
+        let turns = manager
+            .load_session_turns(workspace.path(), &session_id)
+            .await
+            .expect("turns should load");
+        assert_eq!(turns.len(), 1);
+        assert_eq!(turns[0].user_message.content, turn.user_message.content);
+
+        // Files written before compression existed are migrated once idle
+        let plain = compression::read_to_string(&packed_path).unwrap();
+        std::fs::write(&plain_path, &plain).unwrap();
+        std::fs::remove_file(&packed_path).unwrap();
+        let report = manager
+            .compress_idle_payloads(workspace.path(), Duration::ZERO)
+            .await
+            .expect("idle compression should run");
+        assert_eq!(report.files, 1);
+        assert_eq!(report.original_bytes, plain.len() as u64);
+        assert!(report.compressed_bytes * 10 < report.original_bytes);
+        assert!(packed_path.exists());
+        assert!(!plain_path.exists());
+
+        let packed = std::fs::read(&packed_path).unwrap();
+        std::fs::write(&packed_path, &packed[..packed.len() / 2]).unwrap();
+        assert!(manager
+            .load_dialog_turn(workspace.path(), &session_id, 0)
+            .await
+            .is_err());
+    }
 }
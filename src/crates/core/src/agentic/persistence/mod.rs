@@ -4,4 +4,4 @@
 
 pub mod manager;
 
-pub use manager::PersistenceManager;
+pub use manager::{spawn_idle_payload_compression, PayloadCompressionReport, PersistenceManager};
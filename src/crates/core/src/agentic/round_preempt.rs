@@ -3,8 +3,7 @@
 //! The [`DialogRoundPreemptSource`] is implemented by [`DialogScheduler`](super::scheduler::DialogScheduler)
 //! and read by [`ExecutionEngine`](super::execution::ExecutionEngine) after each completed model round.
 
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use crate::agentic::session_runtime::{existing_session_runtime, session_runtime};
 
 /// Observes whether the current dialog turn should end after the latest model round
 /// (so a queued user message can start as a new turn).
@@ -24,29 +23,25 @@ impl DialogRoundPreemptSource for NoopDialogRoundPreemptSource {
     fn clear_yield_after_round(&self, _session_id: &str) {}
 }
 
-/// Shared flag storage keyed by session; scheduler sets, engine reads and clears.
+/// Flags kept in each session's [`SessionRuntime`](crate::agentic::session_runtime::SessionRuntime);
+/// scheduler sets, engine reads and clears.
 #[derive(Debug, Default)]
-pub struct SessionRoundYieldFlags {
-    inner: dashmap::DashMap<String, Arc<AtomicBool>>,
-}
+pub struct SessionRoundYieldFlags;
 
 impl SessionRoundYieldFlags {
     pub fn request_yield(&self, session_id: &str) {
-        self.inner
-            .entry(session_id.to_string())
-            .or_insert_with(|| Arc::new(AtomicBool::new(false)))
-            .store(true, Ordering::SeqCst);
+        session_runtime(session_id).request_yield_after_round();
     }
 
     pub fn should_yield(&self, session_id: &str) -> bool {
-        self.inner
-            .get(session_id)
-            .map(|r| r.value().load(Ordering::SeqCst))
-            .unwrap_or(false)
+        existing_session_runtime(session_id)
+            .is_some_and(|runtime| runtime.should_yield_after_round())
     }
 
     pub fn clear(&self, session_id: &str) {
-        self.inner.remove(session_id);
+        if let Some(runtime) = existing_session_runtime(session_id) {
+            runtime.clear_yield_after_round();
+        }
     }
 }
 
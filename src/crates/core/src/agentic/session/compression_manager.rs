@@ -3,10 +3,12 @@
 //! Responsible for managing session context compression
 
 use crate::agentic::core::{
-    render_system_reminder, Message, MessageHelper, MessageRole, MessageSemanticKind,
+    render_system_reminder, Message, MessageContent, MessageHelper, MessageRole,
+    MessageSemanticKind,
 };
 use crate::agentic::persistence::PersistenceManager;
-use crate::infrastructure::ai::{get_global_ai_client_factory, AIClient};
+use crate::infrastructure::ai::tokenizer::IMAGE_ATTACHMENT_TOKENS;
+use crate::infrastructure::ai::{estimate_tokens, get_global_ai_client_factory, AIClient};
 use crate::util::errors::{BitFunError, BitFunResult};
 use crate::util::types::Message as AIMessage;
 use anyhow;
@@ -113,6 +115,20 @@ impl CompressionManager {
             .unwrap_or_default()
     }
 
+    /// Count a turn with the model's tokenizer; images are not part of the converted text
+    fn estimate_turn_tokens(model: &str, turn: &[Message]) -> usize {
+        let image_count: usize = turn
+            .iter()
+            .map(|m| match &m.content {
+                MessageContent::Multimodal { images, .. } => images.len(),
+                _ => 0,
+            })
+            .sum();
+
+        estimate_tokens(model, &MessageHelper::convert_messages(turn), None)
+            + image_count * IMAGE_ATTACHMENT_TOKENS
+    }
+
     fn get_turn_index_to_keep(&self, turns_tokens: &[usize], token_limit: usize) -> usize {
         let mut sum = 0;
         let mut result = turns_tokens.len();
@@ -132,6 +148,7 @@ impl CompressionManager {
     pub async fn preprocess_turns(
         &self,
         session_id: &str,
+        model: &str,
         context_window: usize,
         mut messages: Vec<Message>,
     ) -> BitFunResult<(usize, Vec<TurnWithTokens>)> {
@@ -161,11 +178,11 @@ impl CompressionManager {
             return Ok((0, Vec::new()));
         }
 
-        let mut turns_messages = MessageHelper::group_messages_by_turns(all_messages);
+        let turns_messages = MessageHelper::group_messages_by_turns(all_messages);
         let turns_count = turns_messages.len();
         let turns_tokens: Vec<usize> = turns_messages
-            .iter_mut()
-            .map(|turn| turn.iter_mut().map(|m| m.get_tokens()).sum::<usize>())
+            .iter()
+            .map(|turn| Self::estimate_turn_tokens(model, turn))
             .collect();
         // Print message count and token count for each turn
         {
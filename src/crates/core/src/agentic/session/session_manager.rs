@@ -10,14 +10,17 @@ use crate::agentic::image_analysis::ImageContextData;
 use crate::agentic::persistence::PersistenceManager;
 use crate::agentic::session::{CompressionManager, MessageHistoryManager};
 use crate::infrastructure::ai::get_global_ai_client_factory;
+use crate::service::config::global::GlobalConfigManager;
+use crate::service::config::types::is_valid_env_name;
 use crate::service::session::{
-    DialogTurnData, ModelRoundData, TextItemData, TurnStatus, UserMessageData,
+    DialogTurnData, ModelRoundData, TextItemData, TurnStatus, TurnSummary, UserMessageData,
 };
 use crate::service::snapshot::ensure_snapshot_manager_for_workspace;
 use crate::util::errors::{BitFunError, BitFunResult};
 use dashmap::DashMap;
 use log::{debug, error, info, warn};
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -62,6 +65,29 @@ impl SessionManager {
         config.workspace_path.as_ref().map(PathBuf::from)
     }
 
+    /// `workspace.env` of a local session's workspace, which new sessions start with
+    async fn workspace_env_defaults(config: &SessionConfig) -> BTreeMap<String, String> {
+        let Some(workspace_path) = config.workspace_path.as_deref() else {
+            return BTreeMap::new();
+        };
+        if config.remote_connection_id.is_some() {
+            return BTreeMap::new();
+        }
+        let Ok(config_service) = GlobalConfigManager::get_service().await else {
+            return BTreeMap::new();
+        };
+        match config_service
+            .get_workspace_config(Some(Path::new(workspace_path)))
+            .await
+        {
+            Ok(workspace) => workspace.env,
+            Err(e) => {
+                warn!("Failed to read workspace env defaults: {}", e);
+                BTreeMap::new()
+            }
+        }
+    }
+
     /// Resolve the effective storage path for a session's workspace.
     /// Remote workspaces use [`get_effective_session_path`] (same as coordinator / session Tauri APIs).
     async fn effective_workspace_path_from_config(config: &SessionConfig) -> Option<PathBuf> {
@@ -240,7 +266,7 @@ impl SessionManager {
         session_id: Option<String>,
         session_name: String,
         agent_type: String,
-        config: SessionConfig,
+        mut config: SessionConfig,
         created_by: Option<String>,
     ) -> BitFunResult<Session> {
         let _workspace_path = Self::session_workspace_from_config(&config).ok_or_else(|| {
@@ -261,6 +287,11 @@ impl SessionManager {
             )));
         }
 
+        // Variables given for this session win over the workspace defaults
+        let mut env = Self::workspace_env_defaults(&config).await;
+        env.append(&mut config.env);
+        config.env = env;
+
         let mut session = if let Some(id) = session_id {
             Session::new_with_id(id, session_name, agent_type.clone(), config)
         } else {
@@ -437,6 +468,84 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Replace the session's environment variables (in-memory + persistence)
+    ///
+    /// Tools read them on every call, so the next tool call picks them up.
+    pub async fn update_session_env(
+        &self,
+        session_id: &str,
+        env: BTreeMap<String, String>,
+    ) -> BitFunResult<()> {
+        if let Some(name) = env.keys().find(|name| !is_valid_env_name(name)) {
+            return Err(BitFunError::Validation(format!(
+                "'{}' is not a valid environment variable name",
+                name
+            )));
+        }
+
+        if let Some(mut session) = self.sessions.get_mut(session_id) {
+            session.config.env = env;
+            session.updated_at = SystemTime::now();
+            session.last_activity_at = SystemTime::now();
+        } else {
+            return Err(BitFunError::NotFound(format!(
+                "Session not found: {}",
+                session_id
+            )));
+        }
+
+        if self.config.enable_persistence {
+            let effective_path = self.effective_session_workspace_path(session_id).await;
+            if let (Some(workspace_path), Some(session)) =
+                (effective_path, self.sessions.get(session_id))
+            {
+                self.persistence_manager
+                    .save_session(&workspace_path, &session)
+                    .await?;
+            }
+        }
+
+        debug!("Session env updated: session_id={}", session_id);
+
+        Ok(())
+    }
+
+    /// Turn the session's AI turn summaries off or back on (in-memory + persistence)
+    pub async fn update_session_skip_turn_summary(
+        &self,
+        session_id: &str,
+        skip: bool,
+    ) -> BitFunResult<()> {
+        if let Some(mut session) = self.sessions.get_mut(session_id) {
+            session.config.skip_turn_summary = skip;
+            session.updated_at = SystemTime::now();
+        } else {
+            return Err(BitFunError::NotFound(format!(
+                "Session not found: {}",
+                session_id
+            )));
+        }
+
+        if self.config.enable_persistence {
+            let effective_path = self.effective_session_workspace_path(session_id).await;
+            if let (Some(workspace_path), Some(session)) =
+                (effective_path, self.sessions.get(session_id))
+            {
+                self.persistence_manager
+                    .save_session(&workspace_path, &session)
+                    .await?;
+            }
+        }
+
+        debug!(
+            "Session turn summaries {}: session_id={}",
+            if skip { "skipped" } else { "enabled" },
+            session_id
+        );
+
+        Ok(())
+    }
+
     /// Update session activity time
     pub fn touch_session(&self, session_id: &str) {
         if let Some(mut session) = self.sessions.get_mut(session_id) {
@@ -510,6 +619,8 @@ impl SessionManager {
 
         // 5. Remove from memory
         self.sessions.remove(session_id);
+        crate::agentic::tools::file_read_state::clear_session_file_hashes(session_id);
+        crate::agentic::session_runtime::remove_session_runtime(session_id);
 
         info!("Session deletion completed: session_id={}", session_id);
 
@@ -877,6 +988,7 @@ impl SessionManager {
             });
         }
         turn.status = TurnStatus::Completed;
+        turn.stopped_at_budget = stats.budget_exhausted;
         turn.duration_ms = Some(stats.duration_ms);
         turn.end_time = Some(completion_timestamp);
 
@@ -927,6 +1039,45 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Attach the AI summary to a completed dialog turn and persist it
+    pub async fn set_turn_summary(
+        &self,
+        session_id: &str,
+        turn_id: &str,
+        summary: TurnSummary,
+    ) -> BitFunResult<()> {
+        if !self.config.enable_persistence {
+            return Ok(());
+        }
+        let workspace_path = self
+            .effective_session_workspace_path(session_id)
+            .await
+            .ok_or_else(|| {
+                BitFunError::Validation(format!(
+                    "Session workspace_path is missing: {}",
+                    session_id
+                ))
+            })?;
+        let turn_index = self
+            .sessions
+            .get(session_id)
+            .and_then(|session| session.dialog_turn_ids.iter().position(|id| id == turn_id))
+            .ok_or_else(|| BitFunError::NotFound(format!("Dialog turn not found: {}", turn_id)))?;
+        let mut turn = self
+            .persistence_manager
+            .load_dialog_turn(&workspace_path, session_id, turn_index)
+            .await?
+            .ok_or_else(|| BitFunError::NotFound(format!("Dialog turn not found: {}", turn_id)))?;
+
+        turn.summary = Some(summary);
+        self.persistence_manager
+            .save_dialog_turn(&workspace_path, &turn)
+            .await?;
+
+        debug!("Turn summary saved: turn_id={}", turn_id);
+        Ok(())
+    }
+
     /// Mark a dialog turn as failed and persist it.
     /// Unlike `complete_dialog_turn`, this sets the state to `Failed` with an error message.
     pub async fn fail_dialog_turn(
@@ -1172,6 +1323,23 @@ impl SessionManager {
         self.compression_manager.clone()
     }
 
+    /// Request (or withdraw) a manual compression before the session's next model request
+    pub async fn set_manual_compression(
+        &self,
+        session_id: &str,
+        requested: bool,
+    ) -> BitFunResult<()> {
+        let mut compression_state = self
+            .get_compression_state(session_id)
+            .ok_or_else(|| BitFunError::NotFound(format!("Session not found: {}", session_id)))?;
+        if compression_state.manual_requested == requested {
+            return Ok(());
+        }
+        compression_state.manual_requested = requested;
+        self.update_compression_state(session_id, compression_state)
+            .await
+    }
+
     /// Update session's compression state
     pub async fn update_compression_state(
         &self,
@@ -1254,6 +1422,7 @@ impl SessionManager {
                 tool_call_id: None,
                 name: None,
                 tool_image_attachments: None,
+                server_tool_events: None,
             },
             Message {
                 role: "user".to_string(),
@@ -1264,6 +1433,7 @@ impl SessionManager {
                 tool_call_id: None,
                 name: None,
                 tool_image_attachments: None,
+                server_tool_events: None,
             },
         ];
 
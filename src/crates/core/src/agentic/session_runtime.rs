@@ -0,0 +1,266 @@
+//! Session runtimes
+//!
+//! Execution state that belongs to one session lives in that session's
+//! [`SessionRuntime`]: the cancellation scopes of its turns in flight, the
+//! tool calls it is running, the messages queued behind its current turn and
+//! whether that turn should yield after its model round. Runtimes are found
+//! through a registry keyed by session ID, so sessions running at the same
+//! time (several desktop tabs, the CLI next to the desktop) never share or
+//! overwrite each other's state, even when models reuse tool call IDs.
+//!
+//! The processing phase stays on the session itself
+//! ([`Session::state`](crate::agentic::core::Session)), which the session
+//! manager already keeps per session and persists.
+
+use crate::agentic::coordination::scheduler::{ActiveTurn, QueuedTurn};
+use crate::agentic::turn_cancellation::TurnScope;
+use dashmap::DashMap;
+use log::debug;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use tokio_util::sync::CancellationToken;
+
+static SESSION_RUNTIMES: LazyLock<DashMap<String, Arc<SessionRuntime>>> =
+    LazyLock::new(DashMap::new);
+
+/// A tool call the session is running.
+#[derive(Debug, Clone)]
+pub struct ActiveToolExecution {
+    pub dialog_turn_id: String,
+    pub tool_name: String,
+    pub cancellation_token: CancellationToken,
+}
+
+/// Registration of a running tool call; dropping it forgets the call.
+pub struct TrackedTool {
+    runtime: Arc<SessionRuntime>,
+    tool_id: String,
+}
+
+impl Drop for TrackedTool {
+    fn drop(&mut self) {
+        self.runtime.active_tools.remove(&self.tool_id);
+    }
+}
+
+/// Execution state of one session.
+pub struct SessionRuntime {
+    session_id: String,
+    /// Cancellation scopes of the turns in flight (dialog turn ID -> scope)
+    turn_scopes: DashMap<String, Arc<TurnScope>>,
+    /// Tool calls running (tool call ID -> execution)
+    active_tools: DashMap<String, ActiveToolExecution>,
+    /// Messages waiting for the current turn to end, highest priority first
+    queued_turns: Mutex<VecDeque<QueuedTurn>>,
+    /// Turn the scheduler dispatched, until its outcome arrives
+    dispatched_turn: Mutex<Option<ActiveTurn>>,
+    /// The current turn should end after its model round so a queued message can start
+    yield_after_round: AtomicBool,
+}
+
+impl SessionRuntime {
+    fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            turn_scopes: DashMap::new(),
+            active_tools: DashMap::new(),
+            queued_turns: Mutex::new(VecDeque::new()),
+            dispatched_turn: Mutex::new(None),
+            yield_after_round: AtomicBool::new(false),
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Scope of a turn in flight.
+    pub fn turn_scope(&self, dialog_turn_id: &str) -> Option<Arc<TurnScope>> {
+        self.turn_scopes
+            .get(dialog_turn_id)
+            .map(|scope| Arc::clone(&scope))
+    }
+
+    /// Scope of a turn, started with a new root token if the turn has none yet.
+    pub fn ensure_turn_scope(&self, dialog_turn_id: &str) -> Arc<TurnScope> {
+        self.turn_scopes
+            .entry(dialog_turn_id.to_string())
+            .or_insert_with(|| TurnScope::new(CancellationToken::new()))
+            .clone()
+    }
+
+    /// Starts the scope of a turn with `root` as its root token, replacing an
+    /// earlier scope of the turn.
+    pub fn insert_turn_scope(
+        &self,
+        dialog_turn_id: &str,
+        root: CancellationToken,
+    ) -> Arc<TurnScope> {
+        let scope = TurnScope::new(root);
+        if let Some(previous) = self
+            .turn_scopes
+            .insert(dialog_turn_id.to_string(), Arc::clone(&scope))
+        {
+            previous.finished().cancel();
+        }
+        scope
+    }
+
+    /// Ends the scope of a turn.
+    pub fn remove_turn_scope(&self, dialog_turn_id: &str) -> Option<Arc<TurnScope>> {
+        let (_, scope) = self.turn_scopes.remove(dialog_turn_id)?;
+        scope.finished().cancel();
+        Some(scope)
+    }
+
+    /// IDs of the turns in flight.
+    pub fn turns_in_flight(&self) -> Vec<String> {
+        self.turn_scopes
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
+    /// Records a tool call that started running, until the returned guard is dropped.
+    pub fn track_tool(
+        self: &Arc<Self>,
+        tool_id: &str,
+        execution: ActiveToolExecution,
+    ) -> TrackedTool {
+        self.active_tools.insert(tool_id.to_string(), execution);
+        TrackedTool {
+            runtime: Arc::clone(self),
+            tool_id: tool_id.to_string(),
+        }
+    }
+
+    /// Forgets a tool call, returning it so it can be cancelled.
+    pub fn untrack_tool(&self, tool_id: &str) -> Option<ActiveToolExecution> {
+        self.active_tools
+            .remove(tool_id)
+            .map(|(_, execution)| execution)
+    }
+
+    /// Tool calls running, by tool call ID.
+    pub fn active_tools(&self) -> Vec<(String, ActiveToolExecution)> {
+        self.active_tools
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Messages waiting for the current turn to end.
+    pub fn queued_turns(&self) -> MutexGuard<'_, VecDeque<QueuedTurn>> {
+        self.queued_turns
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn set_dispatched_turn(&self, turn: ActiveTurn) {
+        *self
+            .dispatched_turn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(turn);
+    }
+
+    pub(crate) fn take_dispatched_turn(&self) -> Option<ActiveTurn> {
+        self.dispatched_turn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+    }
+
+    pub fn request_yield_after_round(&self) {
+        self.yield_after_round.store(true, Ordering::SeqCst);
+    }
+
+    pub fn should_yield_after_round(&self) -> bool {
+        self.yield_after_round.load(Ordering::SeqCst)
+    }
+
+    pub fn clear_yield_after_round(&self) {
+        self.yield_after_round.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether the session has nothing in flight or queued.
+    fn is_idle(&self) -> bool {
+        self.turn_scopes.is_empty()
+            && self.active_tools.is_empty()
+            && self.queued_turns().is_empty()
+    }
+}
+
+/// Runtime of a session, created on first use.
+pub fn session_runtime(session_id: &str) -> Arc<SessionRuntime> {
+    if let Some(runtime) = SESSION_RUNTIMES.get(session_id) {
+        return Arc::clone(&runtime);
+    }
+    SESSION_RUNTIMES
+        .entry(session_id.to_string())
+        .or_insert_with(|| Arc::new(SessionRuntime::new(session_id.to_string())))
+        .clone()
+}
+
+/// Runtime of a session, if it has one.
+pub fn existing_session_runtime(session_id: &str) -> Option<Arc<SessionRuntime>> {
+    SESSION_RUNTIMES
+        .get(session_id)
+        .map(|runtime| Arc::clone(&runtime))
+}
+
+/// Drops the runtime of a deleted session, cancelling the turns it still has in flight.
+pub fn remove_session_runtime(session_id: &str) -> Option<Arc<SessionRuntime>> {
+    let (_, runtime) = SESSION_RUNTIMES.remove(session_id)?;
+    for entry in runtime.turn_scopes.iter() {
+        entry.value().cancel();
+    }
+    debug!(
+        "Removed session runtime: session_id={}, idle={}",
+        session_id,
+        runtime.is_idle()
+    );
+    Some(runtime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtimes_keep_tool_calls_of_sessions_apart() {
+        let first = session_runtime("runtime-test-a");
+        let second = session_runtime("runtime-test-b");
+        assert!(Arc::ptr_eq(&first, &session_runtime("runtime-test-a")));
+
+        // Providers may number tool calls per response, so IDs repeat across sessions
+        let mut tracked = Vec::new();
+        for (runtime, turn) in [(&first, "turn-a"), (&second, "turn-b")] {
+            let scope = runtime.ensure_turn_scope(turn);
+            tracked.push(runtime.track_tool(
+                "call_0",
+                ActiveToolExecution {
+                    dialog_turn_id: turn.to_string(),
+                    tool_name: "Read".to_string(),
+                    cancellation_token: scope.token().child_token(),
+                },
+            ));
+        }
+
+        first.turn_scope("turn-a").unwrap().cancel();
+        let (_, second_tool) = &second.active_tools()[0];
+        assert_eq!(second_tool.dialog_turn_id, "turn-b");
+        assert!(!second_tool.cancellation_token.is_cancelled());
+        assert!(second.turn_scope("turn-a").is_none());
+
+        drop(tracked);
+        assert!(first.active_tools().is_empty());
+        assert!(second.active_tools().is_empty());
+
+        let removed = remove_session_runtime("runtime-test-b").unwrap();
+        assert!(removed.turn_scope("turn-b").unwrap().is_cancelled());
+        assert!(existing_session_runtime("runtime-test-b").is_none());
+        assert!(existing_session_runtime("runtime-test-a").is_some());
+        remove_session_runtime("runtime-test-a");
+    }
+}
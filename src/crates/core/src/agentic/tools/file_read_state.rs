@@ -0,0 +1,56 @@
+//! Content hashes of the files each session has seen
+//!
+//! Read records the hash of the content it returned, and Write/Edit record
+//! what they wrote. Before overwriting a file, Write and Edit compare its
+//! current hash with the recorded one: a mismatch means the file changed
+//! behind the agent's back (user edit, another agent) and the agent would be
+//! working from a stale copy.
+
+use super::framework::ToolUseContext;
+use crate::util::errors::{BitFunError, BitFunResult};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// (session id, file path) -> content hash
+static SEEN_HASHES: LazyLock<Mutex<HashMap<(String, String), String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Records that the session of `context` has seen `path` with content `hash`.
+pub fn record_file_hash(context: &ToolUseContext, path: &str, hash: String) {
+    let Some(session_id) = context.session_id.as_ref() else {
+        return;
+    };
+    let mut seen = SEEN_HASHES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    seen.insert((session_id.clone(), path.to_string()), hash);
+}
+
+/// Fails when the session has seen `path` with content other than `current_hash`.
+pub fn ensure_not_stale(
+    context: &ToolUseContext,
+    path: &str,
+    current_hash: &str,
+) -> BitFunResult<()> {
+    let Some(session_id) = context.session_id.as_ref() else {
+        return Ok(());
+    };
+    let seen = SEEN_HASHES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match seen.get(&(session_id.clone(), path.to_string())) {
+        Some(hash) if hash != current_hash => Err(BitFunError::tool(format!(
+            "File has been modified since it was last read: {}. Read it again before writing to it.",
+            path
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Forgets everything recorded for a session.
+pub fn clear_session_file_hashes(session_id: &str) {
+    let mut seen = SEEN_HASHES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    seen.retain(|(session, _), _| session != session_id);
+}
@@ -219,6 +219,18 @@ pub trait Tool: Send + Sync {
         !self.is_readonly()
     }
 
+    /// Whether this call must be confirmed by the user before running; unlike
+    /// `needs_permissions` it may consult settings and the workspace (e.g. Bash
+    /// skips confirmation for commands that run sandboxed). Only consulted for
+    /// calls where `needs_permissions` is true.
+    async fn requires_confirmation(
+        &self,
+        input: &Value,
+        _workspace: Option<&WorkspaceBinding>,
+    ) -> bool {
+        self.needs_permissions(Some(input))
+    }
+
     /// Whether to support streaming output
     fn supports_streaming(&self) -> bool {
         false
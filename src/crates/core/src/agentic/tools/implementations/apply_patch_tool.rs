@@ -0,0 +1,448 @@
+//! Apply patch tool
+//!
+//! Applies a unified diff to workspace files, e.g. the suggested fix of a
+//! review finding. Every hunk is checked before anything is written, and
+//! files written before a failed write are restored, so a patch applies
+//! entirely or not at all.
+
+use super::util::{ensure_within_workspace, resolve_path_with_workspace};
+use crate::agentic::tools::file_read_state::record_file_hash;
+use crate::agentic::tools::framework::{
+    Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
+};
+use crate::agentic::workspace::{LocalWorkspaceFs, WorkspaceFileSystem};
+use crate::infrastructure::filesystem::{
+    content_sha256_hex, decode_text, encode_text, TextEncoding,
+};
+use crate::service::diff::{apply_patch_hunks, parse_patch, PatchFile};
+use crate::util::errors::{BitFunError, BitFunResult};
+use async_trait::async_trait;
+use log::warn;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Component, Path};
+
+/// What a patch changed in one file
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchedFile {
+    /// Absolute path
+    pub path: String,
+    /// The patch created the file
+    pub created: bool,
+    pub hunks: usize,
+    #[serde(skip)]
+    content_hash: String,
+}
+
+/// Workspace-relative file a patch section writes
+fn patch_target(file: &PatchFile) -> BitFunResult<&str> {
+    let path = file.path();
+    let Some(new_path) = file.new_path.as_deref() else {
+        return Err(BitFunError::tool(format!(
+            "Deleting files is not supported by patches, use Delete for {}",
+            path
+        )));
+    };
+    if file.old_path.as_deref().is_some_and(|old| old != new_path) {
+        return Err(BitFunError::tool(format!(
+            "Renaming files is not supported by patches: {} -> {}",
+            file.old_path.as_deref().unwrap_or_default(),
+            new_path
+        )));
+    }
+    let relative = Path::new(path);
+    if path.is_empty()
+        || relative.is_absolute()
+        || relative
+            .components()
+            .any(|component| matches!(component, Component::ParentDir))
+    {
+        return Err(BitFunError::tool(format!(
+            "Patch paths must be relative to the workspace root: {}",
+            path
+        )));
+    }
+    Ok(path)
+}
+
+/// Content a patch writes to one file
+struct PlannedWrite {
+    path: String,
+    bytes: Vec<u8>,
+    /// Content the patch was applied to, `None` when it creates the file
+    original: Option<Vec<u8>>,
+    hunks: usize,
+}
+
+/// Applies `patch`, whose paths are relative to `root`. Nothing is written
+/// unless every hunk of every file applies and no file changed since it was
+/// read; if a write still fails, the files already written are restored.
+pub async fn apply_patch_in_workspace(
+    fs: &dyn WorkspaceFileSystem,
+    root: &Path,
+    patch: &str,
+) -> BitFunResult<Vec<PatchedFile>> {
+    let files = parse_patch(patch).map_err(BitFunError::tool)?;
+
+    let mut writes = Vec::with_capacity(files.len());
+    for file in &files {
+        let path = resolve_path_with_workspace(patch_target(file)?, Some(root))?;
+        let existing = if file.old_path.is_some() {
+            let bytes = fs
+                .read_file(&path)
+                .await
+                .map_err(|e| BitFunError::tool(format!("Failed to read {}: {}", path, e)))?;
+            Some(bytes)
+        } else if fs.exists(&path).await.unwrap_or(false) {
+            return Err(BitFunError::tool(format!(
+                "The patch creates {} but it already exists",
+                path
+            )));
+        } else {
+            None
+        };
+
+        // Patched in UTF-8, saved in the encoding the file had
+        let (content, encoding) = match &existing {
+            Some(bytes) => {
+                let decoded = decode_text(bytes).ok_or_else(|| {
+                    BitFunError::tool(format!("Cannot patch binary file: {}", path))
+                })?;
+                (decoded.text, decoded.encoding)
+            }
+            None => (String::new(), TextEncoding::UTF8),
+        };
+        let patched = apply_patch_hunks(&content, &file.hunks)
+            .map_err(|e| BitFunError::tool(format!("{}: {}", file.path(), e)))?;
+        writes.push(PlannedWrite {
+            bytes: encode_text(&patched, encoding)?,
+            path,
+            original: existing,
+            hunks: file.hunks.len(),
+        });
+    }
+
+    // Patching awaits between reads, so check every file again before the first write
+    for write in &writes {
+        let unchanged = match &write.original {
+            Some(original) => fs
+                .read_file(&write.path)
+                .await
+                .is_ok_and(|current| current == *original),
+            None => !fs.exists(&write.path).await.unwrap_or(false),
+        };
+        if !unchanged {
+            return Err(BitFunError::tool(format!(
+                "{} changed while the patch was being applied, no file was changed",
+                write.path
+            )));
+        }
+    }
+
+    let mut patched_files = Vec::with_capacity(writes.len());
+    for (index, write) in writes.iter().enumerate() {
+        let written = match &write.original {
+            // Fails if the file changed since it was checked above
+            Some(original) => {
+                fs.write_file_if_unchanged(&write.path, &write.bytes, &content_sha256_hex(original))
+                    .await
+            }
+            None => fs.write_file(&write.path, &write.bytes).await,
+        };
+        if let Err(e) = written {
+            rollback_writes(fs, &writes[..index]).await;
+            return Err(BitFunError::tool(format!(
+                "Failed to write {}: {}; files already patched were restored",
+                write.path, e
+            )));
+        }
+        patched_files.push(PatchedFile {
+            path: write.path.clone(),
+            created: write.original.is_none(),
+            hunks: write.hunks,
+            content_hash: content_sha256_hex(&write.bytes),
+        });
+    }
+    Ok(patched_files)
+}
+
+/// Restores files written by a patch that failed part way through
+async fn rollback_writes(fs: &dyn WorkspaceFileSystem, written: &[PlannedWrite]) {
+    for write in written.iter().rev() {
+        let restored = match &write.original {
+            Some(original) => fs.write_file(&write.path, original).await,
+            None => fs.remove_file(&write.path).await,
+        };
+        if let Err(e) = restored {
+            warn!(
+                "Failed to restore {} after a failed patch: {}",
+                write.path, e
+            );
+        }
+    }
+}
+
+pub struct ApplyPatchTool;
+
+impl ApplyPatchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ApplyPatchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for ApplyPatchTool {
+    fn name(&self) -> &str {
+        "ApplyPatch"
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok(r#"Applies a unified diff to files in the workspace.
+
+Usage:
+- `patch` is a unified diff with `--- a/<path>` / `+++ b/<path>` file headers and `@@` hunks; paths are relative to the workspace root.
+- New files use `--- /dev/null`. Deleting and renaming files is not supported.
+- Hunks are located by their context and removed lines, so line numbers in the `@@` headers may be approximate, but the context must match the file exactly.
+- Either every hunk applies or no file is changed."#
+            .to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff to apply, with paths relative to the workspace root"
+                }
+            },
+            "required": ["patch"],
+            "additionalProperties": false
+        })
+    }
+
+    fn is_readonly(&self) -> bool {
+        false
+    }
+
+    fn is_concurrency_safe(&self, _input: Option<&Value>) -> bool {
+        false
+    }
+
+    async fn validate_input(
+        &self,
+        input: &Value,
+        _context: Option<&ToolUseContext>,
+    ) -> ValidationResult {
+        let message = match input.get("patch").and_then(|v| v.as_str()) {
+            Some(patch) => match parse_patch(patch) {
+                Ok(files) => files
+                    .iter()
+                    .find_map(|file| patch_target(file).err())
+                    .map(|e| e.to_string()),
+                Err(e) => Some(e),
+            },
+            None => Some("patch is required".to_string()),
+        };
+        ValidationResult {
+            result: message.is_none(),
+            error_code: message.as_ref().map(|_| 400),
+            message,
+            meta: None,
+        }
+    }
+
+    fn render_tool_use_message(&self, input: &Value, _options: &ToolRenderOptions) -> String {
+        let files = input
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .and_then(|patch| parse_patch(patch).ok())
+            .map(|files| {
+                files
+                    .iter()
+                    .map(|file| file.path().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        format!("Applying patch to {}", files.join(", "))
+    }
+
+    async fn call_impl(
+        &self,
+        input: &Value,
+        context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        let patch = input
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| BitFunError::tool("patch is required".to_string()))?;
+        let root = context.workspace_root().ok_or_else(|| {
+            BitFunError::tool("ApplyPatch needs a workspace to resolve patch paths".to_string())
+        })?;
+
+        for file in parse_patch(patch).map_err(BitFunError::tool)? {
+            let path = resolve_path_with_workspace(patch_target(&file)?, Some(root))?;
+            ensure_within_workspace(&path, input, context)?;
+        }
+
+        let local_fs = LocalWorkspaceFs;
+        let fs = context
+            .ws_fs()
+            .unwrap_or(&local_fs as &dyn WorkspaceFileSystem);
+        let patched_files = apply_patch_in_workspace(fs, root, patch).await?;
+        for file in &patched_files {
+            record_file_hash(context, &file.path, file.content_hash.clone());
+        }
+
+        let summary = patched_files
+            .iter()
+            .map(|file| {
+                let action = if file.created { "created" } else { "modified" };
+                format!("{} ({}, hunks: {})", file.path, action, file.hunks)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(vec![ToolResult::Result {
+            data: json!({
+                "success": true,
+                "files": patched_files,
+            }),
+            result_for_assistant: Some(format!("Applied patch: {}", summary)),
+            image_attachments: None,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_patch_in_workspace;
+    use crate::agentic::workspace::WorkspaceFileSystem;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    /// In-memory file system; the first write also replaces the content of
+    /// `edit_on_first_write`, as if another process edited it meanwhile
+    #[derive(Default)]
+    struct MemoryFs {
+        files: Mutex<HashMap<String, Vec<u8>>>,
+        edit_on_first_write: Mutex<Option<(String, Vec<u8>)>>,
+    }
+
+    impl MemoryFs {
+        fn file(&self, path: &str) -> Option<String> {
+            let files = self.files.lock().unwrap();
+            files
+                .get(path)
+                .map(|bytes| String::from_utf8_lossy(bytes).to_string())
+        }
+    }
+
+    #[async_trait]
+    impl WorkspaceFileSystem for MemoryFs {
+        async fn read_file(&self, path: &str) -> anyhow::Result<Vec<u8>> {
+            let files = self.files.lock().unwrap();
+            files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No such file: {}", path))
+        }
+
+        async fn read_file_text(&self, path: &str) -> anyhow::Result<String> {
+            Ok(String::from_utf8(self.read_file(path).await?)?)
+        }
+
+        async fn write_file(&self, path: &str, contents: &[u8]) -> anyhow::Result<()> {
+            let mut files = self.files.lock().unwrap();
+            files.insert(path.to_string(), contents.to_vec());
+            if let Some((other, edited)) = self.edit_on_first_write.lock().unwrap().take() {
+                files.insert(other, edited);
+            }
+            Ok(())
+        }
+
+        async fn remove_file(&self, path: &str) -> anyhow::Result<()> {
+            let mut files = self.files.lock().unwrap();
+            files
+                .remove(path)
+                .map(|_| ())
+                .ok_or_else(|| anyhow::anyhow!("No such file: {}", path))
+        }
+
+        async fn exists(&self, path: &str) -> anyhow::Result<bool> {
+            Ok(self.files.lock().unwrap().contains_key(path))
+        }
+
+        async fn is_file(&self, path: &str) -> anyhow::Result<bool> {
+            self.exists(path).await
+        }
+
+        async fn is_dir(&self, _path: &str) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    const PATCH: &str = "\
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-one
++ONE
+--- a/b.txt
++++ b/b.txt
+@@ -1 +1 @@
+-two
++TWO
+--- /dev/null
++++ b/c.txt
+@@ -0,0 +1 @@
++three
+";
+
+    fn workspace() -> MemoryFs {
+        let fs = MemoryFs::default();
+        {
+            let mut files = fs.files.lock().unwrap();
+            files.insert("/ws/a.txt".to_string(), b"one\n".to_vec());
+            files.insert("/ws/b.txt".to_string(), b"two\n".to_vec());
+        }
+        fs
+    }
+
+    #[tokio::test]
+    async fn applies_every_file() {
+        let fs = workspace();
+        let patched = apply_patch_in_workspace(&fs, Path::new("/ws"), PATCH)
+            .await
+            .unwrap();
+
+        assert_eq!(patched.len(), 3);
+        assert!(patched[2].created);
+        assert_eq!(fs.file("/ws/a.txt").as_deref(), Some("ONE\n"));
+        assert_eq!(fs.file("/ws/b.txt").as_deref(), Some("TWO\n"));
+        assert_eq!(fs.file("/ws/c.txt").as_deref(), Some("three\n"));
+    }
+
+    #[tokio::test]
+    async fn restores_written_files_when_a_later_file_changed() {
+        let fs = workspace();
+        *fs.edit_on_first_write.lock().unwrap() =
+            Some(("/ws/b.txt".to_string(), b"edited\n".to_vec()));
+
+        let error = apply_patch_in_workspace(&fs, Path::new("/ws"), PATCH)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("/ws/b.txt"), "{}", error);
+        assert_eq!(fs.file("/ws/a.txt").as_deref(), Some("one\n"));
+        assert_eq!(fs.file("/ws/b.txt").as_deref(), Some("edited\n"));
+        assert_eq!(fs.file("/ws/c.txt"), None);
+    }
+}
@@ -0,0 +1,202 @@
+//! Session environment for Bash commands
+//!
+//! A session's `env` is read on every Bash call, so a change applies from the
+//! next command on. New terminal sessions are created with the variables; the
+//! persistent shell outlives such changes, so each command is prefixed with
+//! the exports and unsets needed since that shell last ran one. Values pulled
+//! from the secrets store are masked in what the command prints.
+
+use crate::agentic::coordination::get_global_coordinator;
+use crate::agentic::tools::framework::ToolUseContext;
+use crate::infrastructure::secrets::{parse_secret_ref, resolve_secret_or_empty};
+use crate::service::config::secrets::REDACTED_SECRET;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use terminal_core::shell::ShellType;
+
+/// Variables each terminal session last received, by terminal session ID
+static APPLIED: LazyLock<Mutex<HashMap<String, BTreeMap<String, String>>>> =
+    LazyLock::new(Default::default);
+
+fn applied() -> MutexGuard<'static, HashMap<String, BTreeMap<String, String>>> {
+    APPLIED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Environment of a chat session with secret references resolved
+#[derive(Debug, Default)]
+pub struct SessionEnv {
+    vars: BTreeMap<String, String>,
+    /// Values that came from the secrets store, longest first
+    secrets: Vec<String>,
+}
+
+impl SessionEnv {
+    /// Environment of the session the tool runs in
+    pub async fn load(context: &ToolUseContext) -> Self {
+        let env = context
+            .session_id
+            .as_deref()
+            .and_then(|id| {
+                get_global_coordinator()?
+                    .get_session_manager()
+                    .get_session(id)
+            })
+            .map(|session| session.config.env)
+            .unwrap_or_default();
+        if !env.values().any(|value| parse_secret_ref(value).is_some()) {
+            return Self::resolve(&env);
+        }
+        // Secrets store lookups block
+        tokio::task::spawn_blocking(move || Self::resolve(&env))
+            .await
+            .unwrap_or_default()
+    }
+
+    fn resolve(env: &BTreeMap<String, String>) -> Self {
+        let mut resolved = Self::default();
+        for (name, value) in env {
+            let is_secret = parse_secret_ref(value).is_some();
+            let value = resolve_secret_or_empty(value);
+            if is_secret && !value.is_empty() {
+                resolved.secrets.push(value.clone());
+            }
+            resolved.vars.insert(name.clone(), value);
+        }
+        // A secret containing another one is masked whole
+        resolved
+            .secrets
+            .sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        resolved
+    }
+
+    /// `base` plus the session's variables, for creating a terminal session
+    pub fn merged_with(&self, mut base: HashMap<String, String>) -> HashMap<String, String> {
+        base.extend(self.vars.clone());
+        base
+    }
+
+    /// Records that `terminal_session_id` was created with these variables
+    pub fn mark_applied(&self, terminal_session_id: &str) {
+        applied().insert(terminal_session_id.to_string(), self.vars.clone());
+    }
+
+    /// Commands bringing the shell of `terminal_session_id` up to date,
+    /// to run right before the next command
+    pub fn update_prefix(&self, terminal_session_id: &str, shell: &ShellType) -> String {
+        let previous = applied()
+            .insert(terminal_session_id.to_string(), self.vars.clone())
+            .unwrap_or_default();
+        let mut prefix = String::new();
+        for (name, value) in &self.vars {
+            if previous.get(name) != Some(value) {
+                prefix.push_str(&set_statement(shell, name, value));
+            }
+        }
+        for name in previous
+            .keys()
+            .filter(|name| !self.vars.contains_key(*name))
+        {
+            prefix.push_str(&unset_statement(shell, name));
+        }
+        prefix
+    }
+
+    /// Commands setting every variable, for shells started per command (remote workspaces)
+    pub fn export_prefix(&self) -> String {
+        self.vars
+            .iter()
+            .map(|(name, value)| set_statement(&ShellType::Bash, name, value))
+            .collect()
+    }
+
+    /// `text` with values from the secrets store replaced by [`REDACTED_SECRET`]
+    pub fn mask(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), REDACTED_SECRET)
+        })
+    }
+}
+
+/// Forgets a closed terminal session
+pub fn forget(terminal_session_id: &str) {
+    applied().remove(terminal_session_id);
+}
+
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn set_statement(shell: &ShellType, name: &str, value: &str) -> String {
+    match shell {
+        ShellType::Fish => format!(
+            "set -gx {} '{}'; ",
+            name,
+            value.replace('\\', r"\\").replace('\'', r"\'")
+        ),
+        ShellType::PowerShell | ShellType::PowerShellCore => {
+            format!("$env:{} = '{}'; ", name, value.replace('\'', "''"))
+        }
+        ShellType::Cmd => format!("set \"{}={}\" & ", name, value),
+        ShellType::Csh => format!("setenv {} {}; ", name, posix_quote(value)),
+        _ => format!("export {}={}; ", name, posix_quote(value)),
+    }
+}
+
+fn unset_statement(shell: &ShellType, name: &str) -> String {
+    match shell {
+        ShellType::Fish => format!("set -e {}; ", name),
+        ShellType::PowerShell | ShellType::PowerShellCore => {
+            format!("Remove-Item Env:{} -ErrorAction SilentlyContinue; ", name)
+        }
+        ShellType::Cmd => format!("set \"{}=\" & ", name),
+        ShellType::Csh => format!("unsetenv {}; ", name),
+        _ => format!("unset {}; ", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> SessionEnv {
+        SessionEnv {
+            vars: vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            secrets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn prefixes_only_what_changed_since_the_last_command() {
+        let terminal = "test-terminal-prefix";
+        env(&[("NODE_ENV", "test")]).mark_applied(terminal);
+
+        let next = env(&[("NODE_ENV", "test"), ("DATABASE_URL", "postgres://it's")]);
+        assert_eq!(
+            next.update_prefix(terminal, &ShellType::Bash),
+            r"export DATABASE_URL='postgres://it'\''s'; "
+        );
+        assert_eq!(next.update_prefix(terminal, &ShellType::Bash), "");
+
+        assert_eq!(
+            env(&[]).update_prefix(terminal, &ShellType::PowerShell),
+            "Remove-Item Env:DATABASE_URL -ErrorAction SilentlyContinue; \
+             Remove-Item Env:NODE_ENV -ErrorAction SilentlyContinue; "
+        );
+        forget(terminal);
+    }
+
+    #[test]
+    fn masks_secret_values() {
+        let mut session_env = env(&[("TOKEN", "abc123")]);
+        session_env.secrets = vec!["abc123".to_string()];
+        assert_eq!(
+            session_env.mask("token=abc123\n"),
+            format!("token={}\n", REDACTED_SECRET)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Sandbox profiles for Bash commands
+//!
+//! Wraps a command so it can only write inside the workspace, the temp
+//! directories and any extra configured paths, optionally without network
+//! access. Linux uses bubblewrap (`bwrap`), macOS uses `sandbox-exec`; other
+//! platforms have no sandbox and commands run unrestricted.
+//!
+//! Commands run in the persistent PTY shell, so the sandbox has to be applied
+//! per command by wrapping it; in-process mechanisms such as landlock cannot
+//! be applied to an already running shell.
+
+use crate::service::config::{get_global_config_service, BashSandboxConfig};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static BACKEND: LazyLock<Option<SandboxBackend>> = LazyLock::new(SandboxBackend::detect);
+
+/// Program used to confine a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SandboxBackend {
+    Bubblewrap(PathBuf),
+    SandboxExec(PathBuf),
+}
+
+impl SandboxBackend {
+    fn detect() -> Option<Self> {
+        if cfg!(target_os = "linux") {
+            which::which("bwrap").ok().map(Self::Bubblewrap)
+        } else if cfg!(target_os = "macos") {
+            let path = PathBuf::from("/usr/bin/sandbox-exec");
+            path.exists().then_some(Self::SandboxExec(path))
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bubblewrap(_) => "bubblewrap",
+            Self::SandboxExec(_) => "sandbox-exec",
+        }
+    }
+}
+
+/// Why a command was not sandboxed although the sandbox is enabled.
+pub fn unavailable_reason() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "bubblewrap (bwrap) is not installed; install it to run commands sandboxed"
+    } else if cfg!(target_os = "macos") {
+        "sandbox-exec was not found"
+    } else {
+        "no command sandbox is available on this platform"
+    }
+}
+
+/// What a sandboxed command may do.
+#[derive(Debug, Clone)]
+pub struct SandboxProfile {
+    pub writable_paths: Vec<PathBuf>,
+    pub allow_network: bool,
+}
+
+impl SandboxProfile {
+    /// Workspace, temp directories and the configured extra paths.
+    pub fn for_workspace(workspace_root: &Path, config: &BashSandboxConfig) -> Self {
+        let mut writable_paths = vec![workspace_root.to_path_buf(), std::env::temp_dir()];
+        if cfg!(unix) {
+            writable_paths.push(PathBuf::from("/tmp"));
+        }
+        writable_paths.extend(config.writable_paths.iter().map(PathBuf::from));
+        writable_paths.dedup();
+        Self {
+            writable_paths,
+            allow_network: config.allow_network,
+        }
+    }
+}
+
+/// How a Bash call is going to run.
+#[derive(Debug, Clone)]
+pub enum SandboxPlan {
+    /// The sandbox is turned off in the settings.
+    Disabled,
+    /// The call asked to run outside the sandbox.
+    Bypassed,
+    /// The sandbox is on but cannot be used here.
+    Unavailable(&'static str),
+    Sandboxed(SandboxBackend),
+}
+
+impl SandboxPlan {
+    pub fn resolve(config: &BashSandboxConfig, disable_requested: bool) -> Self {
+        if !config.enabled {
+            Self::Disabled
+        } else if disable_requested {
+            Self::Bypassed
+        } else {
+            match BACKEND.as_ref() {
+                Some(backend) => Self::Sandboxed(backend.clone()),
+                None => Self::Unavailable(unavailable_reason()),
+            }
+        }
+    }
+
+    pub fn is_sandboxed(&self) -> bool {
+        matches!(self, Self::Sandboxed(_))
+    }
+}
+
+/// Sandbox settings (`ai.bash_sandbox`).
+pub async fn load_sandbox_config() -> BashSandboxConfig {
+    match get_global_config_service().await {
+        Ok(service) => service
+            .get_config::<BashSandboxConfig>(Some("ai.bash_sandbox"))
+            .await
+            .unwrap_or_default(),
+        Err(_) => BashSandboxConfig::default(),
+    }
+}
+
+/// Quotes `value` as one POSIX shell word (also valid for fish).
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn path_quote(path: &Path) -> String {
+    shell_quote(&path.to_string_lossy())
+}
+
+/// Escapes `value` for a string literal in a sandbox-exec profile.
+fn profile_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', r"\\").replace('"', "\\\""))
+}
+
+/// `sandbox-exec` profile (SBPL) for `profile`.
+fn seatbelt_profile(profile: &SandboxProfile) -> String {
+    let mut rules = vec![
+        "(version 1)".to_string(),
+        "(allow default)".to_string(),
+        "(deny file-write*)".to_string(),
+    ];
+    let mut writable = vec![
+        "(literal \"/dev/null\")".to_string(),
+        "(literal \"/dev/tty\")".to_string(),
+        "(regex #\"^/dev/fd/\")".to_string(),
+        "(regex #\"^/dev/ttys\")".to_string(),
+    ];
+    for path in &profile.writable_paths {
+        let path = path.to_string_lossy();
+        writable.push(format!("(subpath {})", profile_string(&path)));
+        // /tmp and /var are symlinks into /private on macOS
+        if path.starts_with("/tmp") || path.starts_with("/var/") {
+            writable.push(format!(
+                "(subpath {})",
+                profile_string(&format!("/private{}", path))
+            ));
+        }
+    }
+    rules.push(format!("(allow file-write* {})", writable.join(" ")));
+    if !profile.allow_network {
+        rules.push("(deny network-outbound (remote ip))".to_string());
+        rules.push("(deny network-inbound (local ip))".to_string());
+    }
+    // One line: the wrapped command is typed into the PTY shell
+    rules.join(" ")
+}
+
+/// Rewrites `command` to run confined by `backend`; the result is a single
+/// shell command line starting a new `/bin/sh` in `cwd`.
+pub fn wrap_command(
+    backend: &SandboxBackend,
+    profile: &SandboxProfile,
+    command: &str,
+    cwd: &Path,
+) -> String {
+    match backend {
+        SandboxBackend::Bubblewrap(bwrap) => {
+            let mut args = vec![
+                path_quote(bwrap),
+                "--ro-bind / /".to_string(),
+                "--dev /dev".to_string(),
+                "--proc /proc".to_string(),
+            ];
+            for path in profile.writable_paths.iter().filter(|path| path.exists()) {
+                let path = path_quote(path);
+                args.push(format!("--bind {} {}", path, path));
+            }
+            if !profile.allow_network {
+                args.push("--unshare-net".to_string());
+            }
+            args.push("--die-with-parent".to_string());
+            args.push(format!("--chdir {}", path_quote(cwd)));
+            args.push(format!("/bin/sh -c {}", shell_quote(command)));
+            args.join(" ")
+        }
+        SandboxBackend::SandboxExec(sandbox_exec) => format!(
+            "cd {} && {} -p {} /bin/sh -c {}",
+            path_quote(cwd),
+            path_quote(sandbox_exec),
+            shell_quote(&seatbelt_profile(profile)),
+            shell_quote(command)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(allow_network: bool) -> SandboxProfile {
+        SandboxProfile {
+            writable_paths: vec![PathBuf::from("/work/it's here"), PathBuf::from("/tmp")],
+            allow_network,
+        }
+    }
+
+    #[test]
+    fn quotes_single_quotes() {
+        assert_eq!(shell_quote("echo 'hi'"), r"'echo '\''hi'\'''");
+        assert_eq!(profile_string(r#"a"b\c"#), r#""a\"b\\c""#);
+    }
+
+    #[test]
+    fn bubblewrap_blocks_network_unless_allowed() {
+        let backend = SandboxBackend::Bubblewrap(PathBuf::from("/usr/bin/bwrap"));
+        let wrapped = wrap_command(&backend, &profile(false), "cargo test", Path::new("/work"));
+        assert!(wrapped.starts_with("'/usr/bin/bwrap' --ro-bind / /"));
+        assert!(wrapped.contains("--bind '/tmp' '/tmp'"));
+        assert!(wrapped.contains("--unshare-net"));
+        assert!(wrapped.ends_with("--chdir '/work' /bin/sh -c 'cargo test'"));
+
+        let wrapped = wrap_command(&backend, &profile(true), "cargo test", Path::new("/work"));
+        assert!(!wrapped.contains("--unshare-net"));
+    }
+
+    #[test]
+    fn seatbelt_profile_allows_writes_to_profile_paths_only() {
+        let rules = seatbelt_profile(&profile(false));
+        assert!(rules.contains("(deny file-write*)"));
+        assert!(rules.contains("(subpath \"/work/it's here\")"));
+        assert!(rules.contains("(subpath \"/private/tmp\")"));
+        assert!(rules.contains("(deny network-outbound (remote ip))"));
+        assert!(!seatbelt_profile(&profile(true)).contains("network"));
+    }
+
+    #[test]
+    fn plan_follows_config() {
+        let mut config = BashSandboxConfig::default();
+        assert!(matches!(
+            SandboxPlan::resolve(&config, false),
+            SandboxPlan::Disabled
+        ));
+        config.enabled = true;
+        assert!(matches!(
+            SandboxPlan::resolve(&config, true),
+            SandboxPlan::Bypassed
+        ));
+        assert!(!matches!(
+            SandboxPlan::resolve(&config, false),
+            SandboxPlan::Disabled
+        ));
+    }
+}
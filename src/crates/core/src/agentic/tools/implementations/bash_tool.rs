@@ -1,6 +1,10 @@
+use super::bash_env::{self, SessionEnv};
+use super::bash_sandbox::{load_sandbox_config, wrap_command, SandboxPlan, SandboxProfile};
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
+use crate::agentic::turn_cancellation::turn_scope;
+use crate::agentic::WorkspaceBinding;
 use crate::infrastructure::events::event_system::get_global_event_system;
 use crate::infrastructure::events::event_system::BackendEvent::{
     ToolExecutionProgress, ToolTerminalReady,
@@ -12,12 +16,13 @@ use async_trait::async_trait;
 use futures::StreamExt;
 use log::{debug, error, info};
 use serde_json::{json, Value};
+use std::path::Path;
 use std::time::{Duration, Instant};
 use terminal_core::session::SessionSource;
 use terminal_core::shell::{ShellDetector, ShellType};
 use terminal_core::{
-    CommandCompletionReason, CommandStreamEvent, ExecuteCommandRequest, SendCommandRequest,
-    SignalRequest, TerminalApi, TerminalBindingOptions, TerminalSessionBinding,
+    CloseSessionRequest, CommandCompletionReason, CommandStreamEvent, ExecuteCommandRequest,
+    SendCommandRequest, SignalRequest, TerminalApi, TerminalBindingOptions, TerminalSessionBinding,
 };
 use tokio::io::AsyncWriteExt;
 use tool_runtime::util::ansi_cleaner::strip_ansi;
@@ -58,6 +63,32 @@ struct ResolvedShell {
     display_name: String,
 }
 
+/// Command as requested and as sent to the shell (wrapped when sandboxed)
+struct PreparedCommand<'a> {
+    requested: &'a str,
+    to_run: String,
+    sandbox: SandboxPlan,
+}
+
+impl PreparedCommand<'_> {
+    /// `<sandbox>` note for the model; empty when the sandbox is turned off
+    fn sandbox_note(&self) -> String {
+        match &self.sandbox {
+            SandboxPlan::Disabled => String::new(),
+            SandboxPlan::Sandboxed(backend) => format!(
+                "<sandbox>Ran sandboxed ({}): writes are limited to the workspace and temp directories.</sandbox>",
+                backend.name()
+            ),
+            SandboxPlan::Bypassed => {
+                "<sandbox>Ran outside the sandbox as requested.</sandbox>".to_string()
+            }
+            SandboxPlan::Unavailable(reason) => {
+                format!("<sandbox>Ran unsandboxed: {}.</sandbox>", reason)
+            }
+        }
+    }
+}
+
 /// Bash tool
 pub struct BashTool;
 
@@ -82,6 +113,38 @@ impl BashTool {
         env
     }
 
+    fn sandbox_disabled_by_input(input: &Value) -> bool {
+        input
+            .get("disable_sandbox")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Wrap the command in the configured sandbox, if it can be applied
+    async fn prepare_command<'a>(
+        input: &Value,
+        command: &'a str,
+        workspace_root: &Path,
+        cwd: &str,
+    ) -> PreparedCommand<'a> {
+        let config = load_sandbox_config().await;
+        let sandbox = SandboxPlan::resolve(&config, Self::sandbox_disabled_by_input(input));
+        let to_run = match &sandbox {
+            SandboxPlan::Sandboxed(backend) => wrap_command(
+                backend,
+                &SandboxProfile::for_workspace(workspace_root, &config),
+                command,
+                Path::new(cwd),
+            ),
+            _ => command.to_string(),
+        };
+        PreparedCommand {
+            requested: command,
+            to_run,
+            sandbox,
+        }
+    }
+
     /// Resolve shell configuration for bash tool.
     /// If configured shell doesn't support integration, falls back to system default.
     async fn resolve_shell() -> ResolvedShell {
@@ -229,6 +292,9 @@ Usage notes:
   - If the output exceeds {MAX_OUTPUT_LENGTH} characters, output will be truncated before being returned to you.
   - You can use the `run_in_background` parameter to run the command in a new dedicated background terminal session. The tool returns the background session ID immediately without waiting for the command to finish. Only use this for long-running processes (e.g., dev servers, watchers) where you don't need the output right away. You do not need to append '&' to the command. NOTE: `timeout_ms` is ignored when `run_in_background` is true.
   - Each result includes a `<terminal_session_id>` tag identifying the terminal session. The persistent shell session ID remains constant throughout the entire conversation; background sessions each have their own unique ID.
+  - The shell is persistent: the working directory, exported environment variables and activated virtualenvs carry over to later commands in the same session. Pass `session_id` to run a command in another live session of this conversation (e.g. a background session whose command has finished); by default the persistent shell is used.
+  - If the sandbox is enabled in settings, commands run sandboxed: they can only write inside the workspace and temp directories and may have no network access. Each result reports this in a `<sandbox>` tag. Sandboxed commands run in a subshell, so `cd` and `export` inside them do not carry over. Set `disable_sandbox` only when a command genuinely needs network access or must write elsewhere (e.g. installing dependencies); the user is asked to approve such commands.
+  - Sessions left idle for a long time are closed, and background sessions whose command has finished are closed when your turn ends. If a session no longer exists, run the command without `session_id` and redo any setup it needs.
   - The output may include the command echo and/or the shell prompt (e.g., `PS C:\path>`). Do not treat these as part of the command's actual result.
   - Avoid interactive commands that may block waiting for user input or open a pager/editor. Prefer non-interactive variants and explicit flags. For example, use `git --no-pager diff` instead of `git diff`, and avoid commands that prompt for confirmation unless the User explicitly asks for them.
   
@@ -279,6 +345,14 @@ Usage notes:
                     "type": "number",
                     "description": "Optional timeout in milliseconds (default 120000, max 600000). Ignored when run_in_background is true."
                 },
+                "session_id": {
+                    "type": "string",
+                    "description": "Optional terminal session ID (from a previous <terminal_session_id>) to run the command in. Defaults to this conversation's persistent shell. Cannot be combined with run_in_background."
+                },
+                "disable_sandbox": {
+                    "type": "boolean",
+                    "description": "Run the command outside the sandbox (only relevant when the sandbox is enabled). Requires user approval."
+                },
                 "run_in_background": {
                     "type": "boolean",
                     "description": "If true, runs the command in a new dedicated background terminal session and returns the session ID immediately without waiting for completion. Useful for long-running processes like dev servers or file watchers. timeout_ms is ignored when this is true."
@@ -305,6 +379,20 @@ Usage notes:
         true
     }
 
+    async fn requires_confirmation(
+        &self,
+        input: &Value,
+        workspace: Option<&WorkspaceBinding>,
+    ) -> bool {
+        // Remote commands run over SSH, outside any local sandbox
+        if workspace.map(WorkspaceBinding::is_remote).unwrap_or(true) {
+            return true;
+        }
+        let config = load_sandbox_config().await;
+        let sandbox = SandboxPlan::resolve(&config, Self::sandbox_disabled_by_input(input));
+        !(config.auto_allow_sandboxed && sandbox.is_sandboxed())
+    }
+
     async fn validate_input(
         &self,
         input: &Value,
@@ -368,6 +456,18 @@ Usage notes:
             };
         }
 
+        if run_in_background && input.get("session_id").is_some() {
+            return ValidationResult {
+                result: false,
+                message: Some(
+                    "session_id cannot be combined with run_in_background; background commands always get a new session"
+                        .to_string(),
+                ),
+                error_code: Some(400),
+                meta: None,
+            };
+        }
+
         // Warn if timeout_ms is set alongside run_in_background
         if run_in_background && input.get("timeout_ms").is_some() {
             return ValidationResult {
@@ -427,6 +527,9 @@ Usage notes:
             .and_then(|v| v.as_str())
             .ok_or_else(|| BitFunError::tool("command is required".to_string()))?;
 
+        // Read on every call so a changed session env applies to this command
+        let session_env = SessionEnv::load(context).await;
+
         // Remote workspace: execute via injected workspace shell
         if context.is_remote() {
             if let Some(ws_shell) = context.ws_shell() {
@@ -440,12 +543,16 @@ Usage notes:
                     .and_then(|v| v.as_u64())
                     .unwrap_or(120_000);
 
+                // Every remote command starts a new shell, so all variables are set each time
+                let remote_command = format!("{}{}", session_env.export_prefix(), command_str);
                 let (stdout, stderr, exit_code) = ws_shell
-                    .exec(command_str, Some(timeout_ms))
+                    .exec(&remote_command, Some(timeout_ms))
                     .await
                     .map_err(|e| {
                         BitFunError::tool(format!("Remote command execution failed: {}", e))
                     })?;
+                let stdout = session_env.mask(&stdout);
+                let stderr = session_env.mask(&stderr);
 
                 let output = if stderr.is_empty() {
                     stdout.clone()
@@ -472,7 +579,8 @@ Usage notes:
                         "working_directory": working_directory,
                         "execution_time_ms": execution_time_ms,
                         "duration_ms": execution_time_ms,
-                        "is_remote": true
+                        "is_remote": true,
+                        "sandboxed": false
                     }),
                     result_for_assistant: Some(format!(
                         "[Remote SSH] Command executed on remote server:\n{}\n\nExit code: {}",
@@ -531,9 +639,13 @@ Usage notes:
                 workspace_path.clone()
             };
 
+            let command =
+                Self::prepare_command(input, command_str, Path::new(&workspace_path), &initial_cwd)
+                    .await;
             return self
                 .call_background(
-                    command_str,
+                    &command,
+                    session_env,
                     chat_session_id,
                     &initial_cwd,
                     context,
@@ -545,34 +657,59 @@ Usage notes:
                 .await;
         }
 
-        // 3. Foreground: get or create the primary terminal session
-        let primary_session_id = binding
-            .get_or_create(
+        // 3. Foreground: use the requested session, or get or create the primary one
+        let requested_session_id = input
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|id| !id.is_empty());
+        let primary_session_id = if let Some(requested_session_id) = requested_session_id {
+            Self::resolve_requested_session(
+                requested_session_id,
                 chat_session_id,
-                TerminalBindingOptions {
-                    working_directory: Some(workspace_path.clone()),
-                    session_id: Some(chat_session_id.to_string()),
-                    session_name: Some(format!(
-                        "Chat-{}",
-                        &chat_session_id[..8.min(chat_session_id.len())]
-                    )),
-                    shell_type: shell_type.clone(),
-                    env: Some(Self::noninteractive_env()),
-                    source: Some(SessionSource::Agent),
-                    ..Default::default()
-                },
+                &terminal_api,
+                &binding,
             )
-            .await
-            .map_err(|e| BitFunError::tool(format!("Failed to create Terminal session: {}", e)))?;
+            .await?
+        } else {
+            let creating = binding.get(chat_session_id).is_none();
+            let terminal_session_id = binding
+                .get_or_create(
+                    chat_session_id,
+                    TerminalBindingOptions {
+                        working_directory: Some(workspace_path.clone()),
+                        session_id: Some(chat_session_id.to_string()),
+                        session_name: Some(format!(
+                            "Chat-{}",
+                            &chat_session_id[..8.min(chat_session_id.len())]
+                        )),
+                        shell_type: shell_type.clone(),
+                        env: Some(session_env.merged_with(Self::noninteractive_env())),
+                        source: Some(SessionSource::Agent),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(|e| {
+                    BitFunError::tool(format!("Failed to create Terminal session: {}", e))
+                })?;
+            if creating {
+                session_env.mark_applied(&terminal_session_id);
+            }
+            terminal_session_id
+        };
 
         Self::emit_terminal_ready_event(&tool_use_id, &primary_session_id);
 
-        // Get actual working directory from primary session
-        let primary_cwd = terminal_api
-            .get_session(&primary_session_id)
-            .await
-            .map(|s| s.cwd)
-            .unwrap_or_else(|_| workspace_path.clone());
+        // Get actual working directory and shell from primary session
+        let (primary_cwd, session_shell) = match terminal_api.get_session(&primary_session_id).await
+        {
+            Ok(session) => (session.cwd, session.shell_type),
+            Err(_) => (
+                workspace_path.clone(),
+                shell_type.unwrap_or(ShellType::Bash),
+            ),
+        };
 
         // --- Foreground execution ---
 
@@ -593,10 +730,18 @@ Usage notes:
             command_str, chat_session_id, tool_use_id
         );
 
+        let command =
+            Self::prepare_command(input, command_str, Path::new(&workspace_path), &primary_cwd)
+                .await;
+
         // 4. Create streaming execution request
         let request = ExecuteCommandRequest {
             session_id: primary_session_id.clone(),
-            command: command_str.to_string(),
+            command: format!(
+                "{}{}",
+                session_env.update_prefix(&primary_session_id, &session_shell),
+                command.to_run
+            ),
             timeout_ms,
             prevent_history: Some(true),
         };
@@ -664,6 +809,7 @@ Usage notes:
                     debug!("Bash command started execution, command_id: {}", command_id);
                 }
                 CommandStreamEvent::Output { data } => {
+                    let data = session_env.mask(&data);
                     accumulated_output.push_str(&data);
 
                     let progress_event = ToolExecutionProgress(ToolExecutionProgressInfo {
@@ -699,7 +845,7 @@ Usage notes:
                     }
 
                     if !total_output.is_empty() {
-                        accumulated_output = total_output;
+                        accumulated_output = session_env.mask(&total_output);
                     }
                     break;
                 }
@@ -729,15 +875,17 @@ Usage notes:
             "working_directory": primary_cwd,
             "execution_time_ms": execution_time_ms,
             "terminal_session_id": primary_session_id,
+            "sandboxed": command.sandbox.is_sandboxed(),
         });
 
-        let result_for_assistant = self.render_result(
+        let mut result_for_assistant = self.render_result(
             &primary_session_id,
             &accumulated_output,
             was_interrupted,
             timed_out,
             final_exit_code.unwrap_or(-1),
         );
+        result_for_assistant.push_str(&command.sandbox_note());
 
         Ok(vec![ToolResult::Result {
             data: result_data,
@@ -748,11 +896,97 @@ Usage notes:
 }
 
 impl BashTool {
+    /// Validate a caller-supplied session ID: it must belong to this chat
+    /// session, still exist and not be running another command.
+    async fn resolve_requested_session(
+        requested_session_id: &str,
+        chat_session_id: &str,
+        terminal_api: &TerminalApi,
+        binding: &TerminalSessionBinding,
+    ) -> BitFunResult<String> {
+        if !binding.owns(chat_session_id, requested_session_id) {
+            let mut available: Vec<String> = binding.get(chat_session_id).into_iter().collect();
+            available.extend(binding.list_background_sessions(chat_session_id));
+            return Err(BitFunError::tool(format!(
+                "Terminal session '{}' does not exist or has been closed. Available sessions: {}. Omit session_id to use the persistent shell.",
+                requested_session_id,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            )));
+        }
+
+        let session_manager = terminal_api.session_manager();
+        if session_manager
+            .is_command_running(requested_session_id)
+            .await
+        {
+            return Err(BitFunError::tool(format!(
+                "Terminal session '{}' is still running a command. Use TerminalControl to stop it, or run the command in another session.",
+                requested_session_id
+            )));
+        }
+
+        Ok(requested_session_id.to_string())
+    }
+
+    /// Closes the background session if the turn that started it is aborted;
+    /// once the turn has ended normally the command keeps running.
+    fn stop_with_aborted_turn(context: &ToolUseContext, bg_session_id: &str) {
+        let (Some(session_id), Some(dialog_turn_id)) = (
+            context.session_id.as_deref(),
+            context.dialog_turn_id.as_deref(),
+        ) else {
+            return;
+        };
+        let Some(scope) = turn_scope(session_id, dialog_turn_id) else {
+            return;
+        };
+        let child = scope.register(format!("background command ({})", bg_session_id));
+        let turn_finished = scope.finished().clone();
+        let bg_session_id = bg_session_id.to_string();
+        tokio::spawn(async move {
+            tokio::select! {
+                biased;
+                _ = child.token().cancelled() => {
+                    debug!(
+                        "Turn aborted, closing background terminal session: {}",
+                        bg_session_id
+                    );
+                    if let Ok(terminal_api) = TerminalApi::from_singleton() {
+                        if let Err(e) = terminal_api
+                            .close_session(CloseSessionRequest {
+                                session_id: bg_session_id.clone(),
+                                immediate: Some(true),
+                            })
+                            .await
+                        {
+                            debug!(
+                                "Could not close background terminal session {}: {}",
+                                bg_session_id, e
+                            );
+                        }
+                        terminal_api
+                            .session_manager()
+                            .binding()
+                            .forget_background_session(&bg_session_id);
+                        bash_env::forget(&bg_session_id);
+                    }
+                }
+                _ = turn_finished.cancelled() => {}
+            }
+            drop(child);
+        });
+    }
+
     /// Execute a command in a new background terminal session.
     /// Returns immediately with the new session ID.
     async fn call_background(
         &self,
-        command_str: &str,
+        command: &PreparedCommand<'_>,
+        session_env: SessionEnv,
         chat_session_id: &str,
         initial_cwd: &str,
         context: &ToolUseContext,
@@ -763,7 +997,7 @@ impl BashTool {
     ) -> BitFunResult<Vec<ToolResult>> {
         debug!(
             "Bash tool starting background command: {}, owner: {}",
-            command_str, chat_session_id
+            command.requested, chat_session_id
         );
 
         // Create a dedicated background terminal session sharing the primary session's cwd
@@ -775,7 +1009,7 @@ impl BashTool {
                     session_id: None,
                     session_name: None,
                     shell_type,
-                    env: Some(Self::noninteractive_env()),
+                    env: Some(session_env.merged_with(Self::noninteractive_env())),
                     source: Some(SessionSource::Agent),
                     ..Default::default()
                 },
@@ -787,6 +1021,7 @@ impl BashTool {
                     e
                 ))
             })?;
+        session_env.mark_applied(&bg_session_id);
 
         let tool_use_id = context
             .tool_call_id
@@ -801,7 +1036,7 @@ impl BashTool {
         terminal_api
             .send_command(SendCommandRequest {
                 session_id: bg_session_id.clone(),
-                command: command_str.to_string(),
+                command: command.to_run.clone(),
             })
             .await
             .map_err(|e| BitFunError::tool(format!("Failed to send background command: {}", e)))?;
@@ -810,6 +1045,7 @@ impl BashTool {
             "Background command started, session_id: {}, owner: {}",
             bg_session_id, chat_session_id
         );
+        Self::stop_with_aborted_turn(context, &bg_session_id);
 
         // Determine output file path: <workspace>/.bitfun/terminals/<bg_session_id>.txt
         let output_file_path = context.workspace_root().map(|ws| {
@@ -852,6 +1088,7 @@ impl BashTool {
                 let mut writer = tokio::io::BufWriter::new(file);
 
                 while let Some(data) = output_rx.recv().await {
+                    let data = session_env.mask(&data);
                     if let Err(e) = writer.write_all(data.as_bytes()).await {
                         error!(
                             "Failed to write output for bg session {}: {}",
@@ -864,6 +1101,7 @@ impl BashTool {
 
                 // Channel closed means session was destroyed - delete the log file
                 drop(writer);
+                bash_env::forget(&bg_id_for_log);
                 if let Err(e) = tokio::fs::remove_file(&file_path).await {
                     debug!(
                         "Could not remove output file for bg session {} (may already be gone): {}",
@@ -886,7 +1124,7 @@ impl BashTool {
 
         let result_data = json!({
             "success": true,
-            "command": command_str,
+            "command": command.requested,
             "output": format!("Command started in background terminal session.{}", output_file_note),
             "exit_code": null,
             "interrupted": false,
@@ -894,11 +1132,14 @@ impl BashTool {
             "execution_time_ms": execution_time_ms,
             "terminal_session_id": bg_session_id,
             "output_file": output_file_str,
+            "sandboxed": command.sandbox.is_sandboxed(),
         });
 
         let result_for_assistant = format!(
-            "Command started in background terminal session (id: {}).{}",
-            bg_session_id, output_file_note
+            "Command started in background terminal session (id: {}).{}{}",
+            bg_session_id,
+            output_file_note,
+            command.sandbox_note()
         );
 
         Ok(vec![ToolResult::Result {
@@ -0,0 +1,149 @@
+use crate::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use crate::service::code_index::{get_global_code_index_service, CodeSearchHit};
+use crate::util::errors::{BitFunError, BitFunResult};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+/// How long a search waits for the first build of the index before answering from what it has
+const BUILD_WAIT: Duration = Duration::from_secs(20);
+
+fn format_hit(hit: &CodeSearchHit) -> String {
+    match &hit.symbol {
+        Some(symbol) => {
+            let mut line = format!(
+                "{}:{}-{} {} {}",
+                hit.path,
+                symbol.start_line,
+                symbol.end_line,
+                symbol.kind.as_str(),
+                symbol.name
+            );
+            if let Some(container) = &symbol.container {
+                line.push_str(&format!(" (in {})", container));
+            }
+            line
+        }
+        None => match &hit.summary {
+            Some(summary) => format!("{} — {}", hit.path, summary),
+            None => hit.path.clone(),
+        },
+    }
+}
+
+pub struct CodebaseSearchTool;
+
+impl CodebaseSearchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for CodebaseSearchTool {
+    fn name(&self) -> &str {
+        "CodebaseSearch"
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok(r#"Searches the workspace's symbol index for functions, methods, types, modules and files by name
+- Query with one or more words: identifiers ("SessionManager"), parts of names ("create session") or path fragments ("session/store")
+- Returns ranked results as `path:start-end kind name`, so you can Read exactly the lines you need
+- Prefer this over Grep when looking for where something is defined; use Grep for text inside function bodies, string literals or comments
+- Covers Rust, Python, JavaScript, TypeScript and Go files that are not ignored by .gitignore / .bitfunignore
+"#
+        .to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to look up in symbol names, file paths and file summaries"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "The maximum number of results to return. Defaults to 20."
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    fn is_readonly(&self) -> bool {
+        true
+    }
+
+    fn is_concurrency_safe(&self, _input: Option<&Value>) -> bool {
+        true
+    }
+
+    fn needs_permissions(&self, _input: Option<&Value>) -> bool {
+        false
+    }
+
+    async fn call_impl(
+        &self,
+        input: &Value,
+        context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        let query = input
+            .get("query")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .ok_or_else(|| BitFunError::tool("query is required".to_string()))?;
+        let limit = input
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|v| (v as usize).clamp(1, MAX_LIMIT))
+            .unwrap_or(DEFAULT_LIMIT);
+
+        if context.is_remote() {
+            return Err(BitFunError::tool(
+                "CodebaseSearch is not available for remote workspaces; use Grep or Glob instead"
+                    .to_string(),
+            ));
+        }
+        let workspace_root = context.workspace_root().ok_or_else(|| {
+            BitFunError::tool("workspace_path is required for CodebaseSearch".to_string())
+        })?;
+
+        let index = get_global_code_index_service().workspace(workspace_root);
+        index.ensure_started();
+        let complete = index.is_ready() || index.wait_until_ready(BUILD_WAIT).await;
+
+        let hits = index.search(query, limit);
+        let status = index.status();
+        let mut result_text = if hits.is_empty() {
+            format!("No symbols or files found matching '{}'", query)
+        } else {
+            hits.iter().map(format_hit).collect::<Vec<_>>().join("\n")
+        };
+        if !complete {
+            result_text.push_str(&format!(
+                "\n\nNote: the code index is still being built ({} files scanned so far); results may be incomplete. Use Grep or Glob if you don't find what you need.",
+                index.progress()
+            ));
+        } else if status.truncated {
+            result_text.push_str(
+                "\n\nNote: the workspace exceeds the index size limit; some files are not indexed.",
+            );
+        }
+
+        Ok(vec![ToolResult::Result {
+            data: json!({
+                "query": query,
+                "results": hits,
+                "result_count": hits.len(),
+                "index": status,
+            }),
+            result_for_assistant: Some(result_text),
+            image_attachments: None,
+        }])
+    }
+}
@@ -1,3 +1,4 @@
+use super::util::{allows_outside_workspace, ensure_within_workspace};
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
@@ -87,6 +88,10 @@ Important notes:
                 "recursive": {
                     "type": "boolean",
                     "description": "If true, recursively delete directories and their contents. Required when deleting non-empty directories. Default: false"
+                },
+                "allow_outside_workspace": {
+                    "type": "boolean",
+                    "description": "Follow a symlink that leads outside the workspace. The user is asked to approve the call. Default: false"
                 }
             },
             "required": ["path"]
@@ -101,8 +106,8 @@ Important notes:
         false
     }
 
-    fn needs_permissions(&self, _input: Option<&Value>) -> bool {
-        false
+    fn needs_permissions(&self, input: Option<&Value>) -> bool {
+        allows_outside_workspace(input)
     }
 
     async fn validate_input(
@@ -270,7 +275,12 @@ Important notes:
         }]);
         }
 
+        // Deleting a symlink removes the link itself; only the directories
+        // leading to it must stay inside the workspace
         let path = Path::new(path_str);
+        if let Some(parent) = path.parent() {
+            ensure_within_workspace(&parent.to_string_lossy(), input, context)?;
+        }
         let is_directory = path.is_dir();
 
         debug!(
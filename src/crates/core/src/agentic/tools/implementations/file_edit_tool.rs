@@ -1,5 +1,7 @@
-use super::util::resolve_path_with_workspace;
+use super::util::{ensure_within_workspace, resolve_path_with_workspace};
+use crate::agentic::tools::file_read_state::{ensure_not_stale, record_file_hash};
 use crate::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use crate::infrastructure::filesystem::{content_sha256_hex, decode_text, encode_text};
 use crate::util::errors::{BitFunError, BitFunResult};
 use async_trait::async_trait;
 use serde_json::{json, Value};
@@ -53,6 +55,10 @@ Usage:
                     "type": "boolean",
                     "default": false,
                     "description": "Replace all occurences of old_string (default false)"
+                },
+                "allow_outside_workspace": {
+                    "type": "boolean",
+                    "description": "Follow a symlink that leads outside the workspace. The user is asked to approve the call. Default: false"
                 }
             },
             "required": ["file_path", "old_string", "new_string"],
@@ -94,14 +100,22 @@ Usage:
             .unwrap_or(false);
 
         let resolved_path = resolve_path_with_workspace(file_path, context.workspace_root())?;
+        ensure_within_workspace(&resolved_path, input, context)?;
 
         // When WorkspaceServices is available (both local and remote),
         // use the abstract FS to read → edit in memory → write back.
         if let Some(ws_fs) = context.ws_fs() {
-            let content = ws_fs
-                .read_file_text(&resolved_path)
+            let bytes = ws_fs
+                .read_file(&resolved_path)
                 .await
                 .map_err(|e| BitFunError::tool(format!("Failed to read file: {}", e)))?;
+            let read_hash = content_sha256_hex(&bytes);
+            ensure_not_stale(context, &resolved_path, &read_hash)?;
+            // Edited in UTF-8, saved in the encoding the file had
+            let decoded = decode_text(&bytes).ok_or_else(|| {
+                BitFunError::tool(format!("Cannot edit binary file: {}", resolved_path))
+            })?;
+            let content = decoded.text;
 
             let (new_content, match_count) = if replace_all {
                 let count = content.matches(old_string).count();
@@ -126,10 +140,15 @@ Usage:
                 (content.replacen(old_string, new_string, 1), 1)
             };
 
+            let new_bytes = encode_text(&new_content, decoded.encoding)
+                .map_err(|e| BitFunError::tool(e.to_string()))?;
+
+            // Fails if the file changed between the read above and this write
             ws_fs
-                .write_file(&resolved_path, new_content.as_bytes())
+                .write_file_if_unchanged(&resolved_path, &new_bytes, &read_hash)
                 .await
                 .map_err(|e| BitFunError::tool(format!("Failed to write file: {}", e)))?;
+            record_file_hash(context, &resolved_path, content_sha256_hex(&new_bytes));
 
             let result = ToolResult::Result {
                 data: json!({
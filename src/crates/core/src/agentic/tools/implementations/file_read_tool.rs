@@ -1,7 +1,11 @@
-use super::util::resolve_path_with_workspace;
+use super::util::{
+    allows_outside_workspace, ensure_within_workspace, resolve_path_with_workspace,
+};
+use crate::agentic::tools::file_read_state::record_file_hash;
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
+use crate::infrastructure::filesystem::{content_sha256_hex, decode_text, DecodedText};
 use crate::service::ai_rules::get_global_ai_rules_service;
 use crate::util::errors::{BitFunError, BitFunResult};
 use async_trait::async_trait;
@@ -71,6 +75,29 @@ impl FileReadTool {
             content: truncated_lines.join("\n"),
         }
     }
+
+    /// Tells the model about a non-UTF-8 encoding or undecodable bytes
+    fn encoding_note(decoded: &DecodedText) -> Option<String> {
+        let name = decoded.encoding.name();
+        let mut notes = Vec::new();
+        if name != "UTF-8" {
+            notes.push(format!(
+                "This file is encoded as {}; it is shown converted to UTF-8 and Edit/Write save it as {} again.",
+                name, name
+            ));
+        }
+        if decoded.lossy {
+            notes.push(format!(
+                "Some bytes are not valid {} and are shown as U+FFFD; editing the file replaces them.",
+                name
+            ));
+        }
+        if notes.is_empty() {
+            None
+        } else {
+            Some(format!("<encoding>\n{}\n</encoding>", notes.join("\n")))
+        }
+    }
 }
 
 #[async_trait]
@@ -112,6 +139,10 @@ Usage:
                 "limit": {
                     "type": "number",
                     "description": "The number of lines to read. Only provide if the file is too large to read at once."
+                },
+                "allow_outside_workspace": {
+                    "type": "boolean",
+                    "description": "Follow a symlink that leads outside the workspace. The user is asked to approve the call. Default: false"
                 }
             },
             "required": ["file_path"],
@@ -127,8 +158,8 @@ Usage:
         true
     }
 
-    fn needs_permissions(&self, _input: Option<&Value>) -> bool {
-        false
+    fn needs_permissions(&self, input: Option<&Value>) -> bool {
+        allows_outside_workspace(input)
     }
 
     async fn validate_input(
@@ -230,14 +261,26 @@ Usage:
             .unwrap_or(self.default_max_lines_to_read as u64) as usize;
 
         let resolved_path = resolve_path_with_workspace(file_path, context.workspace_root())?;
+        ensure_within_workspace(&resolved_path, input, context)?;
 
         // Use the workspace file system from context — works for both local and remote.
+        let mut encoding = None;
+        let mut encoding_note = None;
         let read_file_result = if let Some(ws_fs) = context.ws_fs() {
-            let content = ws_fs
-                .read_file_text(&resolved_path)
+            let bytes = ws_fs
+                .read_file(&resolved_path)
                 .await
                 .map_err(|e| BitFunError::tool(format!("Failed to read file: {}", e)))?;
-            self.format_lines(&content, start_line, limit)
+            let decoded = decode_text(&bytes).ok_or_else(|| {
+                BitFunError::tool(format!(
+                    "Cannot read {}: it is a binary file, not text",
+                    resolved_path
+                ))
+            })?;
+            record_file_hash(context, &resolved_path, content_sha256_hex(&bytes));
+            encoding = Some(decoded.encoding.name());
+            encoding_note = Self::encoding_note(&decoded);
+            self.format_lines(&decoded.text, start_line, limit)
         } else {
             read_file(&resolved_path, start_line, limit, self.max_line_chars)
                 .map_err(|e| BitFunError::tool(e))?
@@ -267,6 +310,11 @@ Usage:
             read_file_result.content
         );
 
+        if let Some(note) = &encoding_note {
+            result_for_assistant.push_str("\n\n");
+            result_for_assistant.push_str(note);
+        }
+
         if let Some(rules_content) = &file_rules.formatted_content {
             result_for_assistant.push_str("\n\n");
             result_for_assistant.push_str(rules_content);
@@ -282,6 +330,7 @@ Usage:
                 "lines_read": lines_read,
                 "start_line": read_file_result.start_line,
                 "size": read_file_result.content.len(),
+                "encoding": encoding,
                 "matched_rules_count": file_rules.matched_count
             }),
             result_for_assistant: Some(result_for_assistant),
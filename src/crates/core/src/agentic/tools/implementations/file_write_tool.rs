@@ -1,7 +1,14 @@
-use super::util::resolve_path_with_workspace;
+use super::util::{
+    allows_outside_workspace, ensure_within_workspace, resolve_path_with_workspace,
+};
+use crate::agentic::tools::file_read_state::{ensure_not_stale, record_file_hash};
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
+use crate::infrastructure::filesystem::{
+    atomic_write, content_sha256_hex, detect_encoding, encode_text, FileOperationOptions,
+    TextEncoding,
+};
 use crate::util::errors::{BitFunError, BitFunResult};
 use async_trait::async_trait;
 use serde_json::{json, Value};
@@ -14,6 +21,10 @@ impl FileWriteTool {
     pub fn new() -> Self {
         Self
     }
+
+    fn encode(content: &str, encoding: TextEncoding) -> BitFunResult<Vec<u8>> {
+        encode_text(content, encoding).map_err(|e| BitFunError::tool(e.to_string()))
+    }
 }
 
 #[async_trait]
@@ -44,6 +55,10 @@ Usage:
                 "content": {
                     "type": "string",
                     "description": "The content to write to the file"
+                },
+                "allow_outside_workspace": {
+                    "type": "boolean",
+                    "description": "Follow a symlink that leads outside the workspace. The user is asked to approve the call. Default: false"
                 }
             },
             "required": ["file_path", "content"],
@@ -59,8 +74,8 @@ Usage:
         false
     }
 
-    fn needs_permissions(&self, _input: Option<&Value>) -> bool {
-        false
+    fn needs_permissions(&self, input: Option<&Value>) -> bool {
+        allows_outside_workspace(input)
     }
 
     async fn validate_input(
@@ -131,32 +146,69 @@ Usage:
             .ok_or_else(|| BitFunError::tool("file_path is required".to_string()))?;
 
         let resolved_path = resolve_path_with_workspace(file_path, context.workspace_root())?;
+        ensure_within_workspace(&resolved_path, input, context)?;
 
         let content = input
             .get("content")
             .and_then(|v| v.as_str())
             .ok_or_else(|| BitFunError::tool("content is required".to_string()))?;
 
-        if let Some(ws_fs) = context.ws_fs() {
-            ws_fs
-                .write_file(&resolved_path, content.as_bytes())
-                .await
-                .map_err(|e| BitFunError::tool(format!("Failed to write file: {}", e)))?;
+        let written = if let Some(ws_fs) = context.ws_fs() {
+            // Overwriting a text file: refuse if it changed since the agent saw it,
+            // and keep its encoding
+            let existing = if ws_fs.is_file(&resolved_path).await.unwrap_or(false) {
+                ws_fs
+                    .read_file(&resolved_path)
+                    .await
+                    .ok()
+                    .and_then(|bytes| detect_encoding(&bytes).map(|encoding| (bytes, encoding)))
+            } else {
+                None
+            };
+            let write_result = match existing {
+                Some((existing, encoding)) => {
+                    let existing_hash = content_sha256_hex(&existing);
+                    ensure_not_stale(context, &resolved_path, &existing_hash)?;
+                    let data = Self::encode(content, encoding)?;
+                    ws_fs
+                        .write_file_if_unchanged(&resolved_path, &data, &existing_hash)
+                        .await
+                        .map(|_| data)
+                }
+                None => {
+                    let data = content.as_bytes().to_vec();
+                    ws_fs.write_file(&resolved_path, &data).await.map(|_| data)
+                }
+            };
+            write_result.map_err(|e| BitFunError::tool(format!("Failed to write file: {}", e)))?
         } else {
             if let Some(parent) = Path::new(&resolved_path).parent() {
                 fs::create_dir_all(parent)
                     .await
                     .map_err(|e| BitFunError::tool(format!("Failed to create directory: {}", e)))?;
             }
-            fs::write(&resolved_path, content).await.map_err(|e| {
+            let encoding = match fs::read(&resolved_path).await {
+                Ok(existing) => detect_encoding(&existing).unwrap_or(TextEncoding::UTF8),
+                Err(_) => TextEncoding::UTF8,
+            };
+            let data = Self::encode(content, encoding)?;
+            atomic_write(
+                Path::new(&resolved_path),
+                &data,
+                &FileOperationOptions::default(),
+            )
+            .await
+            .map_err(|e| {
                 BitFunError::tool(format!("Failed to write file {}: {}", resolved_path, e))
             })?;
-        }
+            data
+        };
+        record_file_hash(context, &resolved_path, content_sha256_hex(&written));
 
         let result = ToolResult::Result {
             data: json!({
                 "file_path": resolved_path,
-                "bytes_written": content.len(),
+                "bytes_written": written.len(),
                 "success": true
             }),
             result_for_assistant: Some(format!("Successfully wrote to {}", resolved_path)),
@@ -2,6 +2,7 @@ use super::util::resolve_path_with_workspace;
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
+use crate::service::diff::DiffService;
 use crate::service::git::git_service::GitService;
 use crate::service::git::git_types::GitDiffParams;
 use crate::service::git::git_utils::get_repository_root;
@@ -249,6 +250,31 @@ impl GetFileDiffTool {
         })))
     }
 
+    /// Adds structured hunks to `data`; the model gets the unified diff itself
+    fn diff_tool_result(&self, mut data: Value) -> ToolResult {
+        let text = |key: &str| data.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+        // Full content is not a change, so it has no hunks
+        let hunks = if text("diff_type") == "full" {
+            Vec::new()
+        } else {
+            DiffService::default()
+                .compute_diff(text("original_content"), text("modified_content"))
+                .hunks
+        };
+        let result_for_assistant = format!(
+            "{}\n```diff\n{}\n```",
+            self.render_tool_result_message(&data),
+            text("diff_content").trim_end()
+        );
+        data["hunks"] = json!(hunks);
+
+        ToolResult::Result {
+            data,
+            result_for_assistant: Some(result_for_assistant),
+            image_attachments: None,
+        }
+    }
+
     /// Return full file content
     fn return_full_content(&self, file_path: &Path) -> BitFunResult<Value> {
         let content = fs::read_to_string(file_path)
@@ -292,7 +318,7 @@ Usage:
 - The file_path parameter must be an absolute path, not a relative path.
 - The diff is returned in unified diff format, showing additions (+) and deletions (-).
 - The response includes diff_type indicating the source: "baseline", "git", or "full".
-- The response includes stats for additions and deletions.
+- The response includes stats for additions and deletions, and the changes as structured hunks (line numbers in the original and modified file).
 - This tool is read-only and safe to use for code review and analysis.
 "#
             .to_string(),
@@ -425,12 +451,7 @@ Usage:
             match result {
                 Ok(data) => {
                     debug!("GetFileDiff tool using baseline diff");
-                    let result_for_assistant = self.render_tool_result_message(&data);
-                    return Ok(vec![ToolResult::Result {
-                        data,
-                        result_for_assistant: Some(result_for_assistant),
-            image_attachments: None,
-        }]);
+                    return Ok(vec![self.diff_tool_result(data)]);
                 }
                 Err(e) => {
                     warn!(
@@ -447,12 +468,7 @@ Usage:
             match result {
                 Ok(data) => {
                     debug!("GetFileDiff tool using git diff");
-                    let result_for_assistant = self.render_tool_result_message(&data);
-                    return Ok(vec![ToolResult::Result {
-                        data,
-                        result_for_assistant: Some(result_for_assistant),
-            image_attachments: None,
-        }]);
+                    return Ok(vec![self.diff_tool_result(data)]);
                 }
                 Err(e) => {
                     warn!(
@@ -467,12 +483,6 @@ Usage:
         // Priority 3: Return full file content
         debug!("GetFileDiff tool returning full file content");
         let data = self.return_full_content(&path)?;
-        let result_for_assistant = self.render_tool_result_message(&data);
-
-        Ok(vec![ToolResult::Result {
-            data,
-            result_for_assistant: Some(result_for_assistant),
-            image_attachments: None,
-        }])
+        Ok(vec![self.diff_tool_result(data)])
     }
 }
@@ -5,44 +5,50 @@
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
+use crate::service::diff::DiffLineType;
 use crate::service::git::{
-    execute_git_command, GitAddParams, GitCommitParams, GitDiffParams, GitLogParams, GitPullParams,
-    GitPushParams, GitService,
+    execute_git_command, GitAddParams, GitBlameResult, GitCommitParams, GitDiffParams,
+    GitFetchParams, GitFileHunks, GitFileLog, GitLogParams, GitPullParams, GitPushParams,
+    GitService,
 };
 use crate::util::errors::{BitFunError, BitFunResult};
 use async_trait::async_trait;
 use log::debug;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 
 /// Allowed Git operation types
 const ALLOWED_OPERATIONS: &[&str] = &[
-    "status",      // View working tree status
-    "diff",        // View differences
-    "log",         // View commit history
-    "add",         // Add files to staging area
-    "commit",      // Commit changes
-    "branch",      // Branch operations
-    "checkout",    // Switch branches
-    "switch",      // Switch branches (new syntax)
-    "pull",        // Pull remote changes
-    "push",        // Push to remote
-    "fetch",       // Fetch remote updates
-    "merge",       // Merge branches
-    "rebase",      // Rebase operations
-    "stash",       // Stash changes
-    "reset",       // Reset changes
-    "restore",     // Restore files
-    "show",        // Show objects
-    "tag",         // Tag operations
-    "remote",      // Remote repository operations
-    "clone",       // Clone repository
-    "init",        // Initialize repository
-    "blame",       // View file history
-    "cherry-pick", // Cherry-pick commits
-    "rev-parse",   // Parse references
-    "describe",    // Describe version
-    "shortlog",    // Short log
-    "clean",       // Clean working directory
+    "status",        // View working tree status
+    "diff",          // View differences
+    "log",           // View commit history
+    "add",           // Add files to staging area
+    "commit",        // Commit changes
+    "branch",        // Branch operations
+    "checkout",      // Switch branches
+    "switch",        // Switch branches (new syntax)
+    "pull",          // Pull remote changes
+    "push",          // Push to remote
+    "fetch",         // Fetch remote updates
+    "merge",         // Merge branches
+    "rebase",        // Rebase operations
+    "stash",         // Stash changes
+    "reset",         // Reset changes
+    "restore",       // Restore files
+    "show",          // Show objects
+    "tag",           // Tag operations
+    "remote",        // Remote repository operations
+    "clone",         // Clone repository
+    "init",          // Initialize repository
+    "blame",         // Attribute lines to commits
+    "file_log",      // Commits touching a file, following renames
+    "cherry-pick",   // Cherry-pick commits
+    "rev-parse",     // Parse references
+    "describe",      // Describe version
+    "shortlog",      // Short log
+    "clean",         // Clean working directory
+    "stage_hunks",   // Stage individual hunks listed by `diff --hunks`
+    "unstage_hunks", // Unstage individual hunks listed by `diff --staged --hunks`
 ];
 
 /// Dangerous Git operations (require special warning)
@@ -136,6 +142,10 @@ impl GitTool {
         let staged = args_str.contains("--staged") || args_str.contains("--cached");
         let stat = args_str.contains("--stat");
 
+        if args_str.contains("--hunks") {
+            return Self::execute_list_hunks(repo_path, args_str, staged).await;
+        }
+
         let params = GitDiffParams {
             staged: Some(staged),
             stat: Some(stat),
@@ -156,6 +166,274 @@ impl GitTool {
         }))
     }
 
+    /// List hunks with their ids, for a later stage_hunks/unstage_hunks call
+    async fn execute_list_hunks(
+        repo_path: &str,
+        args_str: &str,
+        staged: bool,
+    ) -> BitFunResult<Value> {
+        let files: Vec<String> = args_str
+            .split_whitespace()
+            .filter(|s| !s.starts_with('-'))
+            .map(|s| s.to_string())
+            .collect();
+
+        let file_hunks = GitService::list_hunks(repo_path, staged, &files)
+            .await
+            .map_err(|e| BitFunError::tool(format!("Git diff failed: {}", e)))?;
+
+        Ok(json!({
+            "success": true,
+            "exit_code": 0,
+            "stdout": Self::render_hunks(&file_hunks),
+            "stderr": "",
+            "data": file_hunks
+        }))
+    }
+
+    fn render_hunks(file_hunks: &[GitFileHunks]) -> String {
+        let mut output_lines = vec![];
+        for file in file_hunks {
+            match &file.old_path {
+                Some(old_path) => {
+                    output_lines.push(format!("{} (renamed from {})", file.path, old_path))
+                }
+                None => output_lines.push(file.path.clone()),
+            }
+            if file.binary {
+                output_lines
+                    .push("  binary file, can only be staged as a whole with add".to_string());
+                continue;
+            }
+            for hunk in &file.hunks {
+                output_lines.push(format!("  [{}] {}", hunk.id, hunk.header));
+                for line in &hunk.hunk.lines {
+                    let prefix = match line.line_type {
+                        DiffLineType::Add => '+',
+                        DiffLineType::Delete => '-',
+                        DiffLineType::Context => ' ',
+                    };
+                    output_lines.push(format!("    {}{}", prefix, line.content));
+                }
+            }
+        }
+
+        if output_lines.is_empty() {
+            "No changes".to_string()
+        } else {
+            output_lines.join("\n")
+        }
+    }
+
+    /// Execute stage_hunks/unstage_hunks operation using GitService
+    async fn execute_stage_hunks(
+        repo_path: &str,
+        args: Option<&str>,
+        unstage: bool,
+    ) -> BitFunResult<Value> {
+        let hunk_ids: Vec<String> = args
+            .unwrap_or("")
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect();
+
+        let result = if unstage {
+            GitService::unstage_hunks(repo_path, &hunk_ids).await
+        } else {
+            GitService::stage_hunks(repo_path, &hunk_ids).await
+        }
+        .map_err(|e| BitFunError::tool(format!("Git hunk staging failed: {}", e)))?;
+
+        let verb = if unstage { "Unstaged" } else { "Staged" };
+        Ok(json!({
+            "success": result.success,
+            "exit_code": if result.success { 0 } else { 1 },
+            "stdout": format!("{} {} hunk(s): {}", verb, hunk_ids.len(), hunk_ids.join(", ")),
+            "stderr": result.error.unwrap_or_default(),
+            "execution_time_ms": result.duration
+        }))
+    }
+
+    /// Split blame/file_log arguments into the file path and flag values
+    fn parse_history_args(args: Option<&str>) -> (Option<String>, HashMap<&'static str, String>) {
+        let mut path = None;
+        let mut flags = HashMap::new();
+        let mut tokens = args.unwrap_or("").split_whitespace();
+        while let Some(token) = tokens.next() {
+            let (flag, inline) = match token.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => match token.strip_prefix("-L").filter(|rest| !rest.is_empty()) {
+                    Some(range) => ("-L", Some(range.to_string())),
+                    None => (token, None),
+                },
+            };
+            let key = match flag {
+                "-L" => "range",
+                "--skip" => "skip",
+                "-n" | "--max-count" => "limit",
+                _ if !token.starts_with('-') => {
+                    path = Some(token.to_string());
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some(value) = inline.or_else(|| tokens.next().map(|v| v.to_string())) {
+                flags.insert(key, value);
+            }
+        }
+        (path, flags)
+    }
+
+    /// Execute blame operation using GitService
+    async fn execute_blame(repo_path: &str, args: Option<&str>) -> BitFunResult<Value> {
+        let (path, flags) = Self::parse_history_args(args);
+        let path = path.ok_or_else(|| {
+            BitFunError::tool(
+                "blame requires a file path, e.g. \"src/lib.rs -L 10,40\"".to_string(),
+            )
+        })?;
+
+        let (start_line, end_line) = match flags.get("range") {
+            Some(range) => {
+                let parse_line = |text: &str| {
+                    text.parse::<u32>()
+                        .map_err(|_| BitFunError::tool(format!("Invalid line range: {}", range)))
+                };
+                let (start, end) = range.split_once(',').unwrap_or((range.as_str(), ""));
+                let end = if end.is_empty() {
+                    None
+                } else {
+                    Some(parse_line(end)?)
+                };
+                (Some(parse_line(start)?), end)
+            }
+            None => (None, None),
+        };
+
+        let result = GitService::blame(repo_path, &path, start_line, end_line)
+            .await
+            .map_err(|e| BitFunError::tool(format!("Git blame failed: {}", e)))?;
+
+        Ok(json!({
+            "success": true,
+            "exit_code": 0,
+            "stdout": Self::render_blame(&result),
+            "stderr": "",
+            "data": result
+        }))
+    }
+
+    fn render_blame(result: &GitBlameResult) -> String {
+        let mut output_lines = vec![result.path.clone()];
+        for group in &result.groups {
+            let short_hash: String = group.commit.chars().take(8).collect();
+            output_lines.push(format!(
+                "\n{} {} {} \"{}\" (lines {}-{}){}",
+                short_hash,
+                group.author,
+                group.date,
+                group.summary,
+                group.start_line,
+                group.end_line,
+                if result.shallow && group.boundary {
+                    " [shallow boundary]"
+                } else {
+                    ""
+                }
+            ));
+            for (offset, line) in group.lines.iter().enumerate() {
+                output_lines.push(format!(
+                    "  {} | {}",
+                    group.start_line as usize + offset,
+                    line
+                ));
+            }
+        }
+
+        if result.truncated {
+            output_lines.push(format!(
+                "\n[Output capped at {} lines, blame a narrower range with -L]",
+                crate::service::git::history::MAX_BLAME_LINES
+            ));
+        }
+        if result.shallow && result.groups.iter().any(|g| g.boundary) {
+            output_lines.push(
+                "[Shallow clone: lines marked as shallow boundary may be older than shown; run `git fetch --unshallow` for full history]"
+                    .to_string(),
+            );
+        }
+        output_lines.join("\n")
+    }
+
+    /// Execute file_log operation using GitService
+    async fn execute_file_log(repo_path: &str, args: Option<&str>) -> BitFunResult<Value> {
+        let (path, flags) = Self::parse_history_args(args);
+        let path = path.ok_or_else(|| {
+            BitFunError::tool(
+                "file_log requires a file path, e.g. \"src/lib.rs --skip 20\"".to_string(),
+            )
+        })?;
+        let skip = match flags.get("skip") {
+            Some(skip) => skip
+                .parse()
+                .map_err(|_| BitFunError::tool(format!("Invalid --skip value: {}", skip)))?,
+            None => 0,
+        };
+        let limit = match flags.get("limit") {
+            Some(limit) => Some(
+                limit
+                    .parse()
+                    .map_err(|_| BitFunError::tool(format!("Invalid -n value: {}", limit)))?,
+            ),
+            None => None,
+        };
+
+        let log = GitService::get_file_log(repo_path, &path, skip, limit)
+            .await
+            .map_err(|e| BitFunError::tool(format!("Git file log failed: {}", e)))?;
+
+        Ok(json!({
+            "success": true,
+            "exit_code": 0,
+            "stdout": Self::render_file_log(&log),
+            "stderr": "",
+            "data": log
+        }))
+    }
+
+    fn render_file_log(log: &GitFileLog) -> String {
+        let mut output_lines = vec![format!("History of {}", log.path)];
+        for entry in &log.entries {
+            let mut line = format!(
+                "{} {} {} {}",
+                entry.short_hash, entry.date, entry.author, entry.subject
+            );
+            if let Some(old_path) = &entry.old_path {
+                line.push_str(&format!(" (renamed from {})", old_path));
+            } else if entry.path != log.path {
+                line.push_str(&format!(" (as {})", entry.path));
+            }
+            output_lines.push(line);
+        }
+
+        if log.entries.is_empty() {
+            output_lines.push("No more commits".to_string());
+        }
+        if log.has_more {
+            output_lines.push(format!(
+                "[More commits: use --skip {}]",
+                log.skip + log.entries.len()
+            ));
+        } else if log.shallow {
+            output_lines.push(
+                "[Shallow clone: older commits are missing; run `git fetch --unshallow` for full history]"
+                    .to_string(),
+            );
+        }
+        output_lines.join("\n")
+    }
+
     /// Execute log operation using GitService
     async fn execute_log(repo_path: &str, args: Option<&str>) -> BitFunResult<Value> {
         let args_str = args.unwrap_or("");
@@ -309,19 +587,61 @@ impl GitTool {
         }))
     }
 
-    /// Execute push operation using GitService
-    async fn execute_push(repo_path: &str, args: Option<&str>) -> BitFunResult<Value> {
-        let args_str = args.unwrap_or("");
-        let parts: Vec<&str> = args_str
+    /// Splits remote operation args into positional arguments and flags
+    fn split_remote_args(args: Option<&str>) -> (Vec<&str>, Vec<&str>) {
+        args.unwrap_or("")
             .split_whitespace()
-            .filter(|s| !s.starts_with('-'))
-            .collect();
+            .partition(|token| !token.starts_with('-'))
+    }
+
+    fn is_force_push(args: Option<&str>) -> bool {
+        let (_, flags) = Self::split_remote_args(args);
+        flags
+            .iter()
+            .any(|flag| matches!(*flag, "-f" | "--force") || flag.starts_with("--force-with-lease"))
+    }
+
+    /// Execute fetch operation using GitService
+    async fn execute_fetch(repo_path: &str, args: Option<&str>) -> BitFunResult<Value> {
+        let (parts, flags) = Self::split_remote_args(args);
+
+        let params = GitFetchParams {
+            remote: parts.first().map(|s| s.to_string()),
+            prune: Some(flags.iter().any(|flag| matches!(*flag, "-p" | "--prune"))),
+        };
+
+        let result = GitService::fetch(repo_path, params)
+            .await
+            .map_err(|e| BitFunError::tool(format!("Git fetch failed: {}", e)))?;
+
+        Ok(json!({
+            "success": result.success,
+            "exit_code": if result.success { 0 } else { 1 },
+            "stdout": result.output.unwrap_or_default(),
+            "stderr": result.error.unwrap_or_default(),
+            "execution_time_ms": result.duration
+        }))
+    }
+
+    /// Execute push operation using GitService
+    async fn execute_push(
+        repo_path: &str,
+        args: Option<&str>,
+        confirm_force: bool,
+    ) -> BitFunResult<Value> {
+        let (parts, flags) = Self::split_remote_args(args);
+        let force = Self::is_force_push(args);
 
         let params = GitPushParams {
-            remote: parts.get(0).map(|s| s.to_string()),
+            remote: parts.first().map(|s| s.to_string()),
             branch: parts.get(1).map(|s| s.to_string()),
-            force: Some(args_str.contains("--force") || args_str.contains("-f")),
-            set_upstream: Some(args_str.contains("-u") || args_str.contains("--set-upstream")),
+            force: Some(force),
+            force_confirmed: Some(force && confirm_force),
+            set_upstream: Some(
+                flags
+                    .iter()
+                    .any(|flag| matches!(*flag, "-u" | "--set-upstream")),
+            ),
         };
 
         let result = GitService::push(repo_path, params)
@@ -339,16 +659,12 @@ impl GitTool {
 
     /// Execute pull operation using GitService
     async fn execute_pull(repo_path: &str, args: Option<&str>) -> BitFunResult<Value> {
-        let args_str = args.unwrap_or("");
-        let parts: Vec<&str> = args_str
-            .split_whitespace()
-            .filter(|s| !s.starts_with('-'))
-            .collect();
+        let (parts, flags) = Self::split_remote_args(args);
 
         let params = GitPullParams {
-            remote: parts.get(0).map(|s| s.to_string()),
+            remote: parts.first().map(|s| s.to_string()),
             branch: parts.get(1).map(|s| s.to_string()),
-            rebase: Some(args_str.contains("--rebase")),
+            rebase: Some(flags.contains(&"--rebase")),
         };
 
         let result = GitService::pull(repo_path, params)
@@ -549,8 +865,11 @@ This tool provides a safe and convenient way to execute Git commands. It support
 - **remote**: Manage set of tracked repositories
 - **clone**: Clone a repository into a new directory
 - **init**: Create an empty Git repository
-- **blame**: Show what revision and author last modified each line
+- **blame**: Show what revision and author last modified each line, grouped by commit (`<path> -L start,end`)
+- **file_log**: Show the commits that touched a file, following renames (`<path> --skip N -n N`)
 - **cherry-pick**: Apply the changes introduced by some existing commits
+- **stage_hunks**: Stage individual hunks by the ids `diff --hunks` lists
+- **unstage_hunks**: Unstage individual hunks by the ids `diff --staged --hunks` lists
 
 ## Usage Examples
 
@@ -589,6 +908,29 @@ This tool provides a safe and convenient way to execute Git commands. It support
    {"operation": "switch", "args": "main"}
    ```
 
+8. Stage only some of the changes in a file (e.g. the fix but not the debug prints):
+   ```json
+   {"operation": "diff", "args": "--hunks src/lib.rs"}
+   ```
+   then pass the ids of the wanted hunks:
+   ```json
+   {"operation": "stage_hunks", "args": "h3f2a9c1b04e7 h91d0c2e5aa40"}
+   ```
+   Hunk ids change when the hunk's content changes, so list the hunks again after editing the file. Binary files have no hunks and must be staged with `add`.
+
+9. Push the current branch and set its upstream:
+   ```json
+   {"operation": "push", "args": "-u origin feature/new-feature"}
+   ```
+
+10. Find who last changed a function and why, then how the file evolved:
+   ```json
+   {"operation": "blame", "args": "src/parser.rs -L 120,160"}
+   ```
+   ```json
+   {"operation": "file_log", "args": "src/parser.rs -n 10"}
+   ```
+
 ## Safety Notes
 
 - This tool validates operations to ensure only allowed Git commands are executed
@@ -596,6 +938,8 @@ This tool provides a safe and convenient way to execute Git commands. It support
 - Never run `git config` to modify user settings
 - Always verify changes before committing
 - Use `--dry-run` for push/pull operations when unsure
+- fetch, pull and push never prompt for credentials. They use the token stored for the remote's host (e.g. `github_token`) or the SSH agent; an authentication error names the secret to configure, so relay it to the user instead of retrying
+- A force-push is done with `--force-with-lease` and only with `"confirm_force": true`, which requires the user's explicit request
 
 ## Commit Message Guidelines
 
@@ -626,6 +970,10 @@ When creating commits, use this format for the commit message:
                 "working_directory": {
                     "type": "string",
                     "description": "The directory to run the Git command in (defaults to current workspace)"
+                },
+                "confirm_force": {
+                    "type": "boolean",
+                    "description": "Required for `push --force`. Set to true only if the user explicitly asked for this force-push"
                 }
             },
             "required": ["operation"],
@@ -654,6 +1002,7 @@ When creating commits, use this format for the commit message:
                     "remote",
                     "tag",
                     "blame",
+                    "file_log",
                     "describe",
                     "shortlog",
                     "rev-parse",
@@ -724,6 +1073,24 @@ When creating commits, use this format for the commit message:
             };
         }
 
+        if operation == "push"
+            && Self::is_force_push(Some(args))
+            && !input
+                .get("confirm_force")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        {
+            return ValidationResult {
+                result: false,
+                message: Some(
+                    "Force-push needs confirm_force: true, and only after the user explicitly asked for it"
+                        .to_string(),
+                ),
+                error_code: Some(403),
+                meta: None,
+            };
+        }
+
         // Check if operation is dangerous, add warning message
         if Self::is_dangerous_operation(operation, args) {
             return ValidationResult {
@@ -838,10 +1205,21 @@ When creating commits, use this format for the commit message:
             "log" => Self::execute_log(&repo_path, args).await?,
             "add" => Self::execute_add(&repo_path, args).await?,
             "commit" => Self::execute_commit(&repo_path, args).await?,
-            "push" => Self::execute_push(&repo_path, args).await?,
+            "fetch" => Self::execute_fetch(&repo_path, args).await?,
+            "push" => {
+                let confirm_force = input
+                    .get("confirm_force")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                Self::execute_push(&repo_path, args, confirm_force).await?
+            }
             "pull" => Self::execute_pull(&repo_path, args).await?,
             "checkout" | "switch" => Self::execute_checkout(&repo_path, args).await?,
             "branch" => Self::execute_branch(&repo_path, args).await?,
+            "blame" => Self::execute_blame(&repo_path, args).await?,
+            "file_log" => Self::execute_file_log(&repo_path, args).await?,
+            "stage_hunks" => Self::execute_stage_hunks(&repo_path, args, false).await?,
+            "unstage_hunks" => Self::execute_stage_hunks(&repo_path, args, true).await?,
             // Other operations use generic command execution
             _ => Self::execute_generic(&repo_path, operation, args).await?,
         };
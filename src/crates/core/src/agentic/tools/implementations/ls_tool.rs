@@ -6,6 +6,7 @@ use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
 use crate::agentic::util::list_files::{format_files_list, list_files};
+use crate::infrastructure::filesystem::{DirectorySizeReport, FileTreeService};
 use crate::util::errors::{BitFunError, BitFunResult};
 use async_trait::async_trait;
 use chrono::{DateTime, Local};
@@ -29,6 +30,57 @@ impl LSTool {
     }
 }
 
+/// Number of largest files/directories reported with `sizes`
+const LARGEST_ENTRIES_LIMIT: usize = 10;
+
+/// Format a byte count as a short human-readable size
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Render the size report below the listing
+fn format_size_report(report: &DirectorySizeReport) -> String {
+    let mut text = format!(
+        "\nSizes (total {}):\n",
+        format_size(report.total_size_bytes)
+    );
+    for node in &report.entries {
+        let name = if node.is_directory {
+            format!("{}/", node.name)
+        } else {
+            node.name.clone()
+        };
+        text.push_str(&format!(
+            "  {:>10}  {}\n",
+            format_size(node.size.unwrap_or(0)),
+            name
+        ));
+    }
+    if !report.largest_entries.is_empty() {
+        text.push_str("\nLargest entries:\n");
+        for entry in &report.largest_entries {
+            let suffix = if entry.is_directory { "/" } else { "" };
+            text.push_str(&format!(
+                "  {:>10}  {}{}\n",
+                format_size(entry.size_bytes),
+                entry.path,
+                suffix
+            ));
+        }
+    }
+    text
+}
+
 /// Format system time as readable string
 fn format_time(time: SystemTime) -> String {
     let datetime: DateTime<Local> = time.into();
@@ -48,7 +100,8 @@ Usage:
 - The path parameter must be an absolute path, not a relative path
 - You can optionally provide an array of glob patterns to ignore with the ignore parameter
 - Hidden files (files starting with '.') are automatically excluded
-- Results are sorted by modification time (newest first)"#
+- Results are sorted by modification time (newest first)
+- Set sizes to true to also get recursive directory sizes and the largest files/directories (local workspaces only)"#
             .to_string())
     }
 
@@ -71,6 +124,10 @@ Usage:
                     "type": "number",
                     "description": "The maximum number of entries to return. Defaults to 100."
                 },
+                "sizes": {
+                    "type": "boolean",
+                    "description": "Also report recursive directory sizes and the largest files/directories under `path`. Defaults to false."
+                },
             },
             "required": ["path"],
             "additionalProperties": false
@@ -180,6 +237,11 @@ Usage:
             .map(|v| v as usize)
             .unwrap_or(self.default_limit);
 
+        let include_sizes = input
+            .get("sizes")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         // Remote workspace: execute ls via SSH shell
         if context.is_remote() {
             let ws_shell = context.ws_shell().ok_or_else(|| {
@@ -292,13 +354,35 @@ Usage:
             result_text.push_str(&format!("\n(showing up to {} entries)", limit));
         }
 
+        // Sizes follow the same ignore settings as the workspace file tree
+        let mut data = json!({
+            "path": path,
+            "entries": entries_json,
+            "total": total_entries,
+            "limit": limit
+        });
+        if include_sizes {
+            let report = FileTreeService::default()
+                .directory_size_report(path, LARGEST_ENTRIES_LIMIT)
+                .await
+                .map_err(|e| BitFunError::tool(format!("Failed to compute sizes: {}", e)))?;
+            result_text.push('\n');
+            result_text.push_str(&format_size_report(&report));
+            data["total_size_bytes"] = json!(report.total_size_bytes);
+            data["sizes"] = json!(report
+                .entries
+                .iter()
+                .map(|node| json!({
+                    "path": node.path,
+                    "size_bytes": node.size.unwrap_or(0),
+                    "is_dir": node.is_directory,
+                }))
+                .collect::<Vec<Value>>());
+            data["largest_entries"] = json!(report.largest_entries);
+        }
+
         let result = ToolResult::Result {
-            data: json!({
-                "path": path,
-                "entries": entries_json,
-                "total": total_entries,
-                "limit": limit
-            }),
+            data,
             result_for_assistant: Some(result_text),
             image_attachments: None,
         };
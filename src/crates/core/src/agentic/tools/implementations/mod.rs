@@ -1,8 +1,12 @@
 //! Tool implementation module
 
+pub mod apply_patch_tool;
 pub mod ask_user_question_tool;
+pub mod bash_env;
+pub mod bash_sandbox;
 pub mod bash_tool;
 pub mod code_review_tool;
+pub mod codebase_search_tool;
 pub mod computer_use_tool;
 pub mod computer_use_mouse_precise_tool;
 pub mod computer_use_mouse_step_tool;
@@ -22,6 +26,8 @@ pub mod log_tool;
 pub mod ls_tool;
 pub mod mermaid_interactive_tool;
 pub mod miniapp_init_tool;
+pub mod read_lints_tool;
+pub mod review_findings_tool;
 pub mod session_control_tool;
 pub mod session_message_tool;
 pub mod session_history_tool;
@@ -33,9 +39,11 @@ pub mod todo_write_tool;
 pub mod util;
 pub mod web_tools;
 
+pub use apply_patch_tool::ApplyPatchTool;
 pub use ask_user_question_tool::AskUserQuestionTool;
 pub use bash_tool::BashTool;
 pub use code_review_tool::CodeReviewTool;
+pub use codebase_search_tool::CodebaseSearchTool;
 pub use computer_use_tool::ComputerUseTool;
 pub use computer_use_mouse_precise_tool::ComputerUseMousePreciseTool;
 pub use computer_use_mouse_step_tool::ComputerUseMouseStepTool;
@@ -54,6 +62,8 @@ pub use log_tool::LogTool;
 pub use ls_tool::LSTool;
 pub use mermaid_interactive_tool::MermaidInteractiveTool;
 pub use miniapp_init_tool::InitMiniAppTool;
+pub use read_lints_tool::ReadLintsTool;
+pub use review_findings_tool::ReviewFindingsTool;
 pub use session_control_tool::SessionControlTool;
 pub use session_message_tool::SessionMessageTool;
 pub use session_history_tool::SessionHistoryTool;
@@ -0,0 +1,262 @@
+use crate::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use crate::service::config::{get_global_config_service, LspConfig};
+use crate::service::lsp::{
+    get_global_diagnostics_store, open_workspace, DiagnosticSeverity, FileDiagnostics,
+};
+use crate::util::errors::{BitFunError, BitFunResult};
+use async_trait::async_trait;
+use log::debug;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Diagnostics listed in the result; counts still cover all of them
+const MAX_LISTED_DIAGNOSTICS: usize = 200;
+
+fn format_file(path: &Path, file: &FileDiagnostics, out: &mut Vec<String>) {
+    let mut diagnostics: Vec<_> = file.diagnostics.iter().collect();
+    diagnostics.sort_by_key(|d| {
+        (
+            DiagnosticSeverity::from_lsp(d.severity),
+            d.range.start.line,
+            d.range.start.character,
+        )
+    });
+    for diagnostic in diagnostics {
+        let severity = DiagnosticSeverity::from_lsp(diagnostic.severity);
+        let code = match &diagnostic.code {
+            Some(Value::String(code)) => format!("[{}]", code),
+            Some(Value::Number(code)) => format!("[{}]", code),
+            _ => String::new(),
+        };
+        let source = diagnostic
+            .source
+            .as_ref()
+            .map(|source| format!(" ({})", source))
+            .unwrap_or_default();
+        out.push(format!(
+            "{}:{}:{}: {}{} {}{}",
+            path.display(),
+            diagnostic.range.start.line + 1,
+            diagnostic.range.start.character + 1,
+            severity.as_str(),
+            code,
+            diagnostic.message.lines().next().unwrap_or_default(),
+            source
+        ));
+    }
+}
+
+pub struct ReadLintsTool;
+
+impl ReadLintsTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for ReadLintsTool {
+    fn name(&self) -> &str {
+        "ReadLints"
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok(r#"Reads compiler and linter diagnostics (errors, warnings) reported by the workspace's language servers
+- Pass the files you edited in `paths` to get fresh diagnostics for them; the current content on disk is sent to the language server first
+- Omit `paths` to list every file that currently has diagnostics
+- Use it after editing code to catch errors you introduced before finishing the task; fix errors you caused, and don't chase pre-existing ones unless asked
+- Only languages with a configured or installed language server report diagnostics
+"#
+        .to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Files to check, absolute or relative to the workspace root"
+                }
+            }
+        })
+    }
+
+    fn is_readonly(&self) -> bool {
+        true
+    }
+
+    fn is_concurrency_safe(&self, _input: Option<&Value>) -> bool {
+        true
+    }
+
+    fn needs_permissions(&self, _input: Option<&Value>) -> bool {
+        false
+    }
+
+    async fn call_impl(
+        &self,
+        input: &Value,
+        context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        if context.is_remote() {
+            return Err(BitFunError::tool(
+                "ReadLints is not available for remote workspaces; run the project's build or lint command with Bash instead"
+                    .to_string(),
+            ));
+        }
+        let workspace_root = context
+            .workspace_root()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| {
+                BitFunError::tool("workspace_path is required for ReadLints".to_string())
+            })?;
+        let paths: Vec<PathBuf> = input
+            .get("paths")
+            .and_then(|v| v.as_array())
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|path| path.as_str())
+                    .map(|path| {
+                        let path = Path::new(path);
+                        if path.is_absolute() {
+                            path.to_path_buf()
+                        } else {
+                            workspace_root.join(path)
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let store = get_global_diagnostics_store();
+        let mut unsupported = Vec::new();
+        let mut pending = Vec::new();
+        let mut silent = Vec::new();
+
+        if !paths.is_empty() {
+            let config = match get_global_config_service().await {
+                Ok(service) => service
+                    .get_config::<LspConfig>(Some("editor.lsp"))
+                    .await
+                    .unwrap_or_default(),
+                Err(_) => LspConfig::default(),
+            };
+            let manager = open_workspace(workspace_root.clone())
+                .await
+                .map_err(|e| BitFunError::tool(format!("Failed to start LSP: {}", e)))?;
+
+            // Subscribe before syncing so no report is missed
+            let mut updates = store.subscribe();
+            for path in &paths {
+                if !path.is_file() {
+                    return Err(BitFunError::tool(format!(
+                        "File not found: {}",
+                        path.display()
+                    )));
+                }
+                match manager.sync_file(path).await {
+                    Ok(true) => pending.push(path.clone()),
+                    Ok(false) => unsupported.push(path.clone()),
+                    Err(e) => {
+                        debug!("Failed to sync {} to LSP: {}", path.display(), e);
+                        unsupported.push(path.clone());
+                    }
+                }
+            }
+
+            // Wait for a fresh report of every synced file, sharing one deadline
+            let deadline = Instant::now() + Duration::from_millis(config.diagnostics_timeout_ms);
+            let mut waiting: HashSet<PathBuf> = pending.iter().cloned().collect();
+            while !waiting.is_empty() {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match tokio::time::timeout(remaining, updates.recv()).await {
+                    Ok(Ok(path)) => {
+                        waiting.remove(&path);
+                    }
+                    // Missed updates may include ours; the store has the latest either way
+                    Ok(Err(broadcast::error::RecvError::Lagged(_))) => waiting.clear(),
+                    Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => break,
+                }
+            }
+            silent = waiting.into_iter().collect();
+            silent.sort();
+        }
+
+        let files: Vec<(PathBuf, FileDiagnostics)> = if paths.is_empty() {
+            store.under(&workspace_root)
+        } else {
+            pending
+                .iter()
+                .filter_map(|path| store.get(path).map(|file| (path.clone(), file)))
+                .collect()
+        };
+
+        let mut lines = Vec::new();
+        let mut errors = 0;
+        let mut warnings = 0;
+        for (path, file) in &files {
+            errors += file.count(DiagnosticSeverity::Error);
+            warnings += file.count(DiagnosticSeverity::Warning);
+            let display_path = path.strip_prefix(&workspace_root).unwrap_or(path);
+            format_file(display_path, file, &mut lines);
+        }
+        let total = lines.len();
+        lines.truncate(MAX_LISTED_DIAGNOSTICS);
+
+        let mut result_text = if total == 0 {
+            "No diagnostics found.".to_string()
+        } else {
+            let mut text = format!(
+                "{} errors, {} warnings ({} diagnostics)\n{}",
+                errors,
+                warnings,
+                total,
+                lines.join("\n")
+            );
+            if total > MAX_LISTED_DIAGNOSTICS {
+                text.push_str(&format!("\n... {} more", total - MAX_LISTED_DIAGNOSTICS));
+            }
+            text
+        };
+        if !silent.is_empty() {
+            result_text.push_str(&format!(
+                "\nThe language server has not reported on these files yet (it may still be indexing): {}",
+                silent
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if !unsupported.is_empty() {
+            result_text.push_str(&format!(
+                "\nNo language server available for: {}",
+                unsupported
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        Ok(vec![ToolResult::Result {
+            data: json!({
+                "files": files
+                    .iter()
+                    .map(|(path, file)| json!({ "path": path, "diagnostics": file }))
+                    .collect::<Vec<_>>(),
+                "error_count": errors,
+                "warning_count": warnings,
+                "unsupported": unsupported,
+            }),
+            result_for_assistant: Some(result_text),
+            image_attachments: None,
+        }])
+    }
+}
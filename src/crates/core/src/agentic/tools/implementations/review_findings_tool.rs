@@ -0,0 +1,381 @@
+//! Review findings submission tool
+//!
+//! The Review agent ends by submitting its findings here. The input is
+//! normalized into a `ReviewReport` so the desktop annotations, the CLI list
+//! and `ApplyPatch` can rely on its shape.
+
+use crate::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use crate::service::diff::parse_patch;
+use crate::util::errors::{BitFunError, BitFunResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// Severity of a finding, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+impl ReviewSeverity {
+    pub const ALL: [ReviewSeverity; 5] = [
+        ReviewSeverity::Critical,
+        ReviewSeverity::High,
+        ReviewSeverity::Medium,
+        ReviewSeverity::Low,
+        ReviewSeverity::Info,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewSeverity::Critical => "critical",
+            ReviewSeverity::High => "high",
+            ReviewSeverity::Medium => "medium",
+            ReviewSeverity::Low => "low",
+            ReviewSeverity::Info => "info",
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        Self::ALL
+            .into_iter()
+            .find(|severity| severity.as_str().eq_ignore_ascii_case(text))
+    }
+}
+
+/// One problem found in the reviewed changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewFinding {
+    /// Relative to the workspace root
+    pub file: String,
+    /// 1-based lines in the new version of the file; `None` for the file as a whole
+    pub start_line: Option<u32>,
+    pub end_line: Option<u32>,
+    pub severity: ReviewSeverity,
+    /// Lowercase, e.g. `correctness` or `security`
+    pub category: String,
+    pub message: String,
+    /// Unified diff fixing the problem, applicable with `ApplyPatch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_patch: Option<String>,
+}
+
+/// Result of a review, as submitted by the Review agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewReport {
+    pub summary: String,
+    pub findings: Vec<ReviewFinding>,
+}
+
+impl ReviewReport {
+    /// Normalizes a `submit_review_findings` input. Common variations (`line`,
+    /// `description`, severity case, paths under `workspace_root`) are accepted;
+    /// anything that would leave a finding unusable is an error for the model
+    /// to correct.
+    pub fn from_tool_input(input: &Value, workspace_root: Option<&Path>) -> Result<Self, String> {
+        let summary = input
+            .get("summary")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let findings = input
+            .get("findings")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "findings must be an array (empty when nothing was found)".to_string())?
+            .iter()
+            .enumerate()
+            .map(|(index, finding)| {
+                finding
+                    .as_object()
+                    .ok_or_else(|| "not an object".to_string())
+                    .and_then(|finding| parse_finding(finding, workspace_root))
+                    .map_err(|e| format!("findings[{}]: {}", index, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { summary, findings })
+    }
+
+    /// Number of findings per severity, most severe first, leaving out zeros
+    pub fn severity_counts(&self) -> Vec<(ReviewSeverity, usize)> {
+        ReviewSeverity::ALL
+            .into_iter()
+            .map(|severity| {
+                let count = self
+                    .findings
+                    .iter()
+                    .filter(|finding| finding.severity == severity)
+                    .count();
+                (severity, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+fn parse_finding(
+    finding: &Map<String, Value>,
+    workspace_root: Option<&Path>,
+) -> Result<ReviewFinding, String> {
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| finding.get(*key).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+    };
+
+    let file = text(&["file", "path"]).ok_or("file is required")?;
+    let file = match workspace_root.and_then(|root| Path::new(file).strip_prefix(root).ok()) {
+        Some(relative) => relative.to_string_lossy().replace('\\', "/"),
+        None => file.trim_start_matches("./").to_string(),
+    };
+
+    let line = |keys: &[&str]| -> Result<Option<u32>, String> {
+        let Some((key, value)) = keys
+            .iter()
+            .find_map(|key| finding.get(*key).map(|value| (*key, value)))
+        else {
+            return Ok(None);
+        };
+        let number = match value {
+            Value::Null => return Ok(None),
+            Value::Number(number) => number.as_u64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        };
+        match number {
+            Some(0) => Ok(None),
+            Some(number) => u32::try_from(number)
+                .map(Some)
+                .map_err(|_| format!("{} is out of range", key)),
+            None => Err(format!("{} must be a positive line number", key)),
+        }
+    };
+    let start_line = line(&["start_line", "line"])?;
+    // A range ending before its start is read as a single line
+    let end_line = match (start_line, line(&["end_line"])?) {
+        (Some(start), Some(end)) if end >= start => Some(end),
+        (start, _) => start,
+    };
+
+    let severity = text(&["severity"]).ok_or("severity is required")?;
+    let severity = ReviewSeverity::parse(severity).ok_or_else(|| {
+        let allowed: Vec<&str> = ReviewSeverity::ALL.iter().map(|s| s.as_str()).collect();
+        format!(
+            "unknown severity `{}`, expected one of: {}",
+            severity,
+            allowed.join(", ")
+        )
+    })?;
+    let category = text(&["category"]).unwrap_or("general").to_lowercase();
+    let message = text(&["message", "description"]).ok_or("message is required")?;
+
+    let suggested_patch = match text(&["suggested_patch"]) {
+        Some(_) => {
+            // Kept verbatim: trimming would drop a trailing blank context line
+            let patch = finding
+                .get("suggested_patch")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            parse_patch(patch)
+                .map_err(|e| format!("suggested_patch is not a unified diff: {}", e))?;
+            Some(patch.to_string())
+        }
+        None => None,
+    };
+
+    Ok(ReviewFinding {
+        file,
+        start_line,
+        end_line,
+        severity,
+        category,
+        message: message.to_string(),
+        suggested_patch,
+    })
+}
+
+pub struct ReviewFindingsTool;
+
+impl ReviewFindingsTool {
+    pub const NAME: &'static str = "submit_review_findings";
+
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ReviewFindingsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for ReviewFindingsTool {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok("Submit the findings of a review. Call it exactly once, after inspecting the changes; submit an empty findings list when nothing needs to change.".to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "summary": {
+                    "type": "string",
+                    "description": "1-3 sentences on the reviewed changes and their overall risk"
+                },
+                "findings": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "file": {
+                                "type": "string",
+                                "description": "Path relative to the workspace root"
+                            },
+                            "start_line": {
+                                "type": ["integer", "null"],
+                                "description": "First affected line in the new version of the file; null for the whole file"
+                            },
+                            "end_line": {
+                                "type": ["integer", "null"],
+                                "description": "Last affected line; null or equal to start_line for a single line"
+                            },
+                            "severity": {
+                                "type": "string",
+                                "enum": ["critical", "high", "medium", "low", "info"]
+                            },
+                            "category": {
+                                "type": "string",
+                                "description": "e.g. correctness, security, performance, reliability, maintainability, tests"
+                            },
+                            "message": {
+                                "type": "string",
+                                "description": "What is wrong and why it matters"
+                            },
+                            "suggested_patch": {
+                                "type": ["string", "null"],
+                                "description": "Unified diff (--- a/<file>, +++ b/<file>, @@ hunks) fixing the problem, or null"
+                            }
+                        },
+                        "required": ["file", "severity", "category", "message"]
+                    }
+                }
+            },
+            "required": ["summary", "findings"],
+            "additionalProperties": false
+        })
+    }
+
+    fn is_readonly(&self) -> bool {
+        true
+    }
+
+    fn is_concurrency_safe(&self, _input: Option<&Value>) -> bool {
+        true
+    }
+
+    async fn call_impl(
+        &self,
+        input: &Value,
+        context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        let report = ReviewReport::from_tool_input(input, context.workspace_root())
+            .map_err(|e| BitFunError::tool(format!("Invalid review findings: {}", e)))?;
+
+        let counts = report
+            .severity_counts()
+            .iter()
+            .map(|(severity, count)| format!("{} {}", count, severity.as_str()))
+            .collect::<Vec<_>>();
+        let result_for_assistant = if counts.is_empty() {
+            "Review submitted with no findings".to_string()
+        } else {
+            format!("Review submitted: {}", counts.join(", "))
+        };
+
+        Ok(vec![ToolResult::Result {
+            data: serde_json::to_value(&report)?,
+            result_for_assistant: Some(result_for_assistant),
+            image_attachments: None,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReviewReport, ReviewSeverity};
+    use serde_json::{json, Value};
+    use std::path::Path;
+
+    #[test]
+    fn normalizes_fixture_findings() {
+        let input: Value = serde_json::from_str(include_str!("fixtures/review_findings.json"))
+            .expect("fixture input");
+        let expected: Value =
+            serde_json::from_str(include_str!("fixtures/review_findings.expected.json"))
+                .expect("fixture output");
+
+        let report = ReviewReport::from_tool_input(&input, Some(Path::new("/repo"))).unwrap();
+        assert_eq!(serde_json::to_value(&report).unwrap(), expected);
+        assert_eq!(
+            report.severity_counts(),
+            vec![
+                (ReviewSeverity::Critical, 1),
+                (ReviewSeverity::High, 1),
+                (ReviewSeverity::Info, 1)
+            ]
+        );
+
+        // The normalized report is also what events carry and clients read back
+        let round_trip: ReviewReport = serde_json::from_value(expected).unwrap();
+        assert_eq!(round_trip, report);
+    }
+
+    #[test]
+    fn rejects_unusable_findings() {
+        let finding = |extra: Value| {
+            let mut finding = json!({
+                "file": "src/lib.rs",
+                "severity": "low",
+                "category": "style",
+                "message": "m"
+            });
+            finding
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            json!({ "summary": "s", "findings": [finding] })
+        };
+
+        let error = ReviewReport::from_tool_input(&finding(json!({ "severity": "blocker" })), None)
+            .unwrap_err();
+        assert!(error.starts_with("findings[0]: unknown severity `blocker`"));
+
+        let error = ReviewReport::from_tool_input(
+            &finding(json!({ "suggested_patch": "replace line 3 with x" })),
+            None,
+        )
+        .unwrap_err();
+        assert!(error.contains("suggested_patch is not a unified diff"));
+
+        assert!(
+            ReviewReport::from_tool_input(&finding(json!({ "start_line": -1 })), None).is_err()
+        );
+        assert!(ReviewReport::from_tool_input(&json!({ "summary": "s" }), None).is_err());
+        assert!(ReviewReport::from_tool_input(&finding(json!({})), None).is_ok());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::agentic::agents::{get_agent_registry, AgentInfo};
-use crate::agentic::coordination::get_global_coordinator;
+use crate::agentic::coordination::{get_global_coordinator, SubagentOptions};
+use crate::agentic::core::TurnBudget;
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
@@ -9,6 +10,10 @@ use crate::util::errors::{BitFunError, BitFunResult};
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::path::Path;
+use std::time::Duration;
+
+/// How long an Explore/FileFinder result is reused for the same task
+const RESEARCH_RESULT_TTL: Duration = Duration::from_secs(600);
 
 pub struct TaskTool;
 
@@ -62,6 +67,8 @@ Usage notes:
 - Provide clear, detailed prompt so the agent can work autonomously and return exactly the information you need.
 - If 'workspace_path' is omitted, the task inherits the current workspace by default.
 - The 'workspace_path' parameter must still be provided explicitly for the Explore and FileFinder agent.
+- Set 'max_tool_calls' or 'max_duration_secs' to keep a task small; when a limit is reached the agent stops and reports its progress and what is left
+- Set 'response_schema' (a JSON Schema) when you need machine-readable output; the agent's final response is validated against it and returned as JSON
 - Launch multiple agents concurrently whenever possible, to maximize performance; to do that, use a single message with multiple tool calls
 - When the agent is done, it will return a single message back to you.
 - The agent's outputs should generally be trusted
@@ -174,6 +181,20 @@ impl Tool for TaskTool {
                 "workspace_path": {
                     "type": "string",
                     "description": "The absolute path of the workspace for this task. If omitted, inherits the current workspace. Explore/FileFinder must provide it explicitly."
+                },
+                "max_tool_calls": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional limit on the agent's tool calls"
+                },
+                "max_duration_secs": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional limit on the agent's running time in seconds"
+                },
+                "response_schema": {
+                    "type": "object",
+                    "description": "Optional JSON Schema the agent's final response must match"
                 }
             },
             "required": [
@@ -269,7 +290,10 @@ impl Tool for TaskTool {
         let current_workspace_path = context
             .workspace_root()
             .map(|path| path.to_string_lossy().into_owned());
-        if subagent_type == "Explore" || subagent_type == "FileFinder" {
+        // Read-only research agents; repeating their task in an unchanged
+        // workspace reuses the earlier answer
+        let research_agent = subagent_type == "Explore" || subagent_type == "FileFinder";
+        if research_agent {
             let workspace_path = requested_workspace_path
                 .as_deref()
                 .or(current_workspace_path.as_deref())
@@ -343,6 +367,15 @@ impl Tool for TaskTool {
             ));
         };
 
+        let budget = TurnBudget {
+            max_rounds: None,
+            max_tool_calls: input
+                .get("max_tool_calls")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize),
+            max_duration_secs: input.get("max_duration_secs").and_then(|v| v.as_u64()),
+        };
+
         // Get global coordinator
         let coordinator = get_global_coordinator()
             .ok_or_else(|| BitFunError::tool("coordinator not initialized".to_string()))?;
@@ -356,21 +389,59 @@ impl Tool for TaskTool {
                     tool_call_id,
                     session_id,
                     dialog_turn_id,
+                    model_id: None,
                 },
                 Some(effective_workspace_path),
                 None,
                 context.cancellation_token.as_ref(),
+                SubagentOptions {
+                    budget: Some(budget),
+                    response_schema: input.get("response_schema").cloned(),
+                    cache_ttl: research_agent.then_some(RESEARCH_RESULT_TTL),
+                },
             )
             .await?;
 
         let duration = start_time.elapsed().as_millis();
 
-        Ok(vec![ToolResult::Result {
-            data: json!({"duration": duration}),
-            result_for_assistant: Some(format!(
+        let result_for_assistant = match result.budget_exhausted {
+            Some(kind) => format!(
+                "Subagent '{}' stopped at its {} budget before finishing. Its progress report:\n<result>\n{}\n</result>",
+                subagent_type,
+                kind.label(),
+                result.text
+            ),
+            None => format!(
                 "Subagent '{}' completed successfully with result:\n<result>\n{}\n</result>",
                 subagent_type, result.text
-            )),
+            ),
+        };
+        let result_for_assistant = if result.cached {
+            format!(
+                "{}\n(Reused from an identical earlier task; the workspace has not changed since.)",
+                result_for_assistant
+            )
+        } else {
+            result_for_assistant
+        };
+        let result_for_assistant = if result.schema_errors.is_empty() {
+            result_for_assistant
+        } else {
+            format!(
+                "{}\nThe result does not match the requested response_schema:\n{}",
+                result_for_assistant,
+                result.schema_errors.join("\n")
+            )
+        };
+
+        Ok(vec![ToolResult::Result {
+            data: json!({
+                "duration": duration,
+                "stopped_at_budget": result.budget_exhausted,
+                "structured": result.structured,
+                "cached": result.cached,
+            }),
+            result_for_assistant: Some(result_for_assistant),
             image_attachments: None,
         }])
     }
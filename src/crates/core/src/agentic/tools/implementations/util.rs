@@ -1,7 +1,45 @@
+use crate::agentic::tools::framework::ToolUseContext;
+use crate::infrastructure::filesystem::ensure_contained;
 use crate::util::errors::{BitFunError, BitFunResult};
+use serde_json::Value;
 use std::path::Path;
 use std::path::{Component, PathBuf};
 
+/// Input flag that lets a call follow a symlink out of the workspace
+pub const ALLOW_OUTSIDE_WORKSPACE: &str = "allow_outside_workspace";
+
+/// Whether the call set `allow_outside_workspace`; such calls always ask the user first
+pub fn allows_outside_workspace(input: Option<&Value>) -> bool {
+    input
+        .and_then(|input| input.get(ALLOW_OUTSIDE_WORKSPACE))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Refuses a local path inside the workspace that a symlink, junction or
+/// mount point leads out of it, unless the call set `allow_outside_workspace`
+pub fn ensure_within_workspace(
+    resolved_path: &str,
+    input: &Value,
+    context: &ToolUseContext,
+) -> BitFunResult<()> {
+    let workspace_root = match context.workspace_root() {
+        Some(root) if !context.is_remote() => root,
+        _ => return Ok(()),
+    };
+    ensure_contained(
+        Path::new(resolved_path),
+        workspace_root,
+        allows_outside_workspace(Some(input)),
+    )
+    .map_err(|e| {
+        BitFunError::tool(format!(
+            "{}. Set {} to true to ask the user to allow it.",
+            e, ALLOW_OUTSIDE_WORKSPACE
+        ))
+    })
+}
+
 pub fn normalize_path(path: &str) -> String {
     let path = Path::new(path);
     let mut components = Vec::new();
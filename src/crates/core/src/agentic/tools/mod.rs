@@ -2,6 +2,7 @@
 
 pub mod computer_use_capability;
 pub mod computer_use_host;
+pub mod file_read_state;
 pub mod framework;
 pub mod image_context;
 pub mod implementations;
@@ -2,17 +2,19 @@
 //!
 //! Manages the status and lifecycle of tool execution tasks
 
-use super::types::ToolTask;
+use super::types::{ToolTask, ToolTaskKey};
 use crate::agentic::core::ToolExecutionState;
 use crate::agentic::events::{AgenticEvent, EventQueue, ToolEventData};
+use crate::agentic::tools::implementations::ReviewFindingsTool;
+use crate::util::i18n::{self, LocalizedText};
 use dashmap::DashMap;
 use log::debug;
 use std::sync::Arc;
 
 /// Tool state manager
 pub struct ToolStateManager {
-    /// Tool task status (by tool ID)
-    tasks: Arc<DashMap<String, ToolTask>>,
+    /// Tool task status (by session and tool call ID)
+    tasks: Arc<DashMap<ToolTaskKey, ToolTask>>,
 
     /// Event queue
     event_queue: Arc<EventQueue>,
@@ -45,6 +47,23 @@ impl ToolStateManager {
         }
     }
 
+    /// Question shown when the user has to approve a tool call, naming what it touches
+    fn permission_summary(tool_name: &str, params: &serde_json::Value) -> LocalizedText {
+        let param = |name: &str| params.get(name).and_then(|v| v.as_str());
+        let (key, arg_name, arg_value) = match tool_name {
+            "Bash" => ("permission-run-command", "command", param("command")),
+            "Write" => ("permission-write-file", "path", param("file_path")),
+            "Edit" => ("permission-edit-file", "path", param("file_path")),
+            "Delete" => ("permission-delete-file", "path", param("path")),
+            "WebFetch" => ("permission-fetch-url", "url", param("url")),
+            _ => ("permission-use-tool", "tool", Some(tool_name)),
+        };
+        match arg_value {
+            Some(value) => i18n::localize(key, &[(arg_name, value)]),
+            None => i18n::localize("permission-use-tool", &[("tool", tool_name)]),
+        }
+    }
+
     pub fn new(event_queue: Arc<EventQueue>) -> Self {
         Self {
             tasks: Arc::new(DashMap::new()),
@@ -53,15 +72,17 @@ impl ToolStateManager {
     }
 
     /// Create task
-    pub async fn create_task(&self, task: ToolTask) -> String {
-        let tool_id = task.tool_call.tool_id.clone();
-        self.tasks.insert(tool_id.clone(), task);
-        tool_id
+    pub async fn create_task(&self, task: ToolTask) -> ToolTaskKey {
+        let key = task.key();
+        self.tasks.insert(key.clone(), task);
+        key
     }
 
     /// Update task state
-    pub async fn update_state(&self, tool_id: &str, new_state: ToolExecutionState) {
-        if let Some(mut task) = self.tasks.get_mut(tool_id) {
+    pub async fn update_state(&self, key: &ToolTaskKey, new_state: ToolExecutionState) {
+        // The entry is released before the event is sent, so other sessions' tools
+        // are not held up on the map while the event queue is busy
+        let updated_task = self.tasks.get_mut(key).map(|mut task| {
             let old_state = task.state.clone();
             task.state = new_state.clone();
 
@@ -79,28 +100,57 @@ impl ToolStateManager {
             }
 
             debug!(
-                "Tool state changed: tool_id={}, old_state={:?}, new_state={:?}",
-                tool_id,
+                "Tool state changed: task={}, old_state={:?}, new_state={:?}",
+                key,
                 format!("{:?}", old_state).split('{').next().unwrap_or(""),
                 format!("{:?}", new_state).split('{').next().unwrap_or("")
             );
 
-            // Send state change event
-            self.emit_state_change_event(task.clone()).await;
+            task.clone()
+        });
+
+        // Send state change event
+        if let Some(task) = updated_task {
+            self.emit_state_change_event(task).await;
         }
     }
 
     /// Get task
-    pub fn get_task(&self, tool_id: &str) -> Option<ToolTask> {
-        self.tasks.get(tool_id).map(|t| t.clone())
+    pub fn get_task(&self, key: &ToolTaskKey) -> Option<ToolTask> {
+        self.tasks.get(key).map(|t| t.clone())
+    }
+
+    /// Finds the task a client acts on (tool confirmation from the UI) in `session_id`.
+    /// Subagent tools run in their own session but are shown in the parent's, so a
+    /// subagent task of `session_id` matches too; tasks of other sessions never do.
+    pub fn find_task(&self, session_id: &str, tool_id: &str) -> Option<ToolTaskKey> {
+        let key = ToolTaskKey {
+            session_id: session_id.to_string(),
+            tool_id: tool_id.to_string(),
+        };
+        if self.tasks.contains_key(&key) {
+            return Some(key);
+        }
+        self.tasks
+            .iter()
+            .find(|entry| {
+                entry.key().tool_id == tool_id
+                    && entry
+                        .value()
+                        .context
+                        .subagent_parent_info
+                        .as_ref()
+                        .is_some_and(|parent| parent.session_id == session_id)
+            })
+            .map(|entry| entry.key().clone())
     }
 
     /// Update task arguments
-    pub fn update_task_arguments(&self, tool_id: &str, new_arguments: serde_json::Value) {
-        if let Some(mut task) = self.tasks.get_mut(tool_id) {
+    pub fn update_task_arguments(&self, key: &ToolTaskKey, new_arguments: serde_json::Value) {
+        if let Some(mut task) = self.tasks.get_mut(key) {
             debug!(
-                "Updated tool arguments: tool_id={}, old_args={:?}, new_args={:?}",
-                tool_id, task.tool_call.arguments, new_arguments
+                "Updated tool arguments: task={}, old_args={:?}, new_args={:?}",
+                key, task.tool_call.arguments, new_arguments
             );
             task.tool_call.arguments = new_arguments;
         }
@@ -125,8 +175,8 @@ impl ToolStateManager {
     }
 
     /// Delete task
-    pub fn remove_task(&self, tool_id: &str) {
-        self.tasks.remove(tool_id);
+    pub fn remove_task(&self, key: &ToolTaskKey) {
+        self.tasks.remove(key);
     }
 
     /// Clear all tasks of a session
@@ -138,8 +188,8 @@ impl ToolStateManager {
             .map(|entry| entry.key().clone())
             .collect();
 
-        for tool_id in to_remove {
-            self.tasks.remove(&tool_id);
+        for key in to_remove {
+            self.tasks.remove(&key);
         }
 
         debug!("Cleared session tool tasks: session_id={}", session_id);
@@ -179,6 +229,7 @@ impl ToolStateManager {
                     tool_id: task.tool_call.tool_id.clone(),
                     tool_name: task.tool_call.tool_name.clone(),
                     params: params.clone(),
+                    summary: Some(Self::permission_summary(&task.tool_call.tool_name, params)),
                 }
             }
 
@@ -215,6 +266,22 @@ impl ToolStateManager {
             },
         };
 
+        // Review findings also get their own event, so clients can show them without
+        // parsing tool results
+        let review_result = match &task.state {
+            ToolExecutionState::Completed { result, .. }
+                if task.tool_call.tool_name == ReviewFindingsTool::NAME =>
+            {
+                Some(AgenticEvent::ReviewResult {
+                    session_id: task.context.session_id.clone(),
+                    turn_id: task.context.dialog_turn_id.clone(),
+                    tool_call_id: task.tool_call.tool_id.clone(),
+                    report: result.content(),
+                })
+            }
+            _ => None,
+        };
+
         let event_subagent_parent_info = task.context.subagent_parent_info.map(|info| info.into());
         let event = AgenticEvent::ToolEvent {
             session_id: task.context.session_id,
@@ -224,6 +291,9 @@ impl ToolStateManager {
         };
 
         let _ = self.event_queue.enqueue(event, None).await;
+        if let Some(review_result) = review_result {
+            let _ = self.event_queue.enqueue(review_result, None).await;
+        }
     }
 
     /// Get statistics
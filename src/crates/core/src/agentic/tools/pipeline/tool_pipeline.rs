@@ -7,12 +7,16 @@ use super::state_manager::ToolStateManager;
 use super::types::*;
 use crate::agentic::core::{ToolCall, ToolExecutionState, ToolResult as ModelToolResult};
 use crate::agentic::events::types::ToolEventData;
+use crate::agentic::session_runtime::{
+    existing_session_runtime, session_runtime, ActiveToolExecution,
+};
+use crate::agentic::tools::computer_use_host::ComputerUseHostRef;
 use crate::agentic::tools::framework::{
     ToolOptions, ToolResult as FrameworkToolResult, ToolUseContext,
 };
-use crate::agentic::tools::computer_use_host::ComputerUseHostRef;
 use crate::agentic::tools::image_context::ImageContextProviderRef;
 use crate::agentic::tools::registry::ToolRegistry;
+use crate::agentic::turn_cancellation::turn_scope;
 use crate::util::errors::{BitFunError, BitFunResult};
 use dashmap::DashMap;
 use futures::future::join_all;
@@ -193,6 +197,57 @@ fn convert_to_framework_result(model_result: &ModelToolResult) -> FrameworkToolR
     }
 }
 
+/// Error result for a call whose arguments could not be parsed, asking the model to re-issue it
+fn invalid_arguments_result(tool_call: &ToolCall) -> ModelToolResult {
+    let detail = tool_call
+        .argument_error
+        .clone()
+        .unwrap_or_else(|| "arguments are not a valid JSON object".to_string());
+    let message = format!(
+        "The arguments for tool '{}' could not be parsed as JSON ({}). The tool was not run. \
+        Re-issue the call with complete, valid JSON arguments.",
+        tool_call.tool_name, detail
+    );
+    ModelToolResult {
+        tool_id: tool_call.tool_id.clone(),
+        tool_name: tool_call.tool_name.clone(),
+        result: serde_json::json!({
+            "error": "invalid_tool_arguments",
+            "tool_name": tool_call.tool_name,
+            "detail": detail,
+            "message": message,
+        }),
+        result_for_assistant: Some(message),
+        is_error: true,
+        duration_ms: None,
+        image_attachments: None,
+    }
+}
+
+/// Error result for a mutating call whose arguments only parsed after repair, which may have
+/// cut them short; asks the model to re-issue it
+fn repaired_arguments_result(tool_call: &ToolCall) -> ModelToolResult {
+    let message = format!(
+        "The arguments for tool '{}' were incomplete or malformed JSON and had to be repaired, \
+        so they may be truncated. The tool was not run because it makes changes. \
+        Re-issue the call with complete, valid JSON arguments.",
+        tool_call.tool_name
+    );
+    ModelToolResult {
+        tool_id: tool_call.tool_id.clone(),
+        tool_name: tool_call.tool_name.clone(),
+        result: serde_json::json!({
+            "error": "repaired_tool_arguments",
+            "tool_name": tool_call.tool_name,
+            "message": message,
+        }),
+        result_for_assistant: Some(message),
+        is_error: true,
+        duration_ms: None,
+        image_attachments: None,
+    }
+}
+
 /// Confirmation response type
 #[derive(Debug, Clone)]
 pub enum ConfirmationResponse {
@@ -204,10 +259,8 @@ pub enum ConfirmationResponse {
 pub struct ToolPipeline {
     tool_registry: Arc<TokioRwLock<ToolRegistry>>,
     state_manager: Arc<ToolStateManager>,
-    /// Confirmation channel management (tool_id -> oneshot sender)
-    confirmation_channels: Arc<DashMap<String, oneshot::Sender<ConfirmationResponse>>>,
-    /// Cancellation token management (tool_id -> CancellationToken)
-    cancellation_tokens: Arc<DashMap<String, CancellationToken>>,
+    /// Confirmation channel management (task -> oneshot sender)
+    confirmation_channels: Arc<DashMap<ToolTaskKey, oneshot::Sender<ConfirmationResponse>>>,
     /// Image context provider (dependency injection)
     image_context_provider: Option<ImageContextProviderRef>,
     computer_use_host: Option<ComputerUseHostRef>,
@@ -224,7 +277,6 @@ impl ToolPipeline {
             tool_registry,
             state_manager,
             confirmation_channels: Arc::new(DashMap::new()),
-            cancellation_tokens: Arc::new(DashMap::new()),
             image_context_provider,
             computer_use_host,
         }
@@ -262,8 +314,8 @@ impl ToolPipeline {
         let mut tasks = Vec::new();
         for tool_call in tool_calls {
             let task = ToolTask::new(tool_call, context.clone(), options.clone());
-            let tool_id = self.state_manager.create_task(task).await;
-            tasks.push(tool_id);
+            let task_key = self.state_manager.create_task(task).await;
+            tasks.push(task_key);
         }
 
         // Execute tasks: only when allow_parallel is true and all tools are concurrency safe
@@ -290,7 +342,7 @@ impl ToolPipeline {
     /// Execute tools in parallel
     async fn execute_parallel(
         &self,
-        task_ids: Vec<String>,
+        task_ids: Vec<ToolTaskKey>,
     ) -> BitFunResult<Vec<ToolExecutionResult>> {
         let futures: Vec<_> = task_ids
             .iter()
@@ -339,7 +391,7 @@ impl ToolPipeline {
     /// Execute tools sequentially
     async fn execute_sequential(
         &self,
-        task_ids: Vec<String>,
+        task_ids: Vec<ToolTaskKey>,
     ) -> BitFunResult<Vec<ToolExecutionResult>> {
         let mut results = Vec::new();
 
@@ -379,16 +431,20 @@ impl ToolPipeline {
     }
 
     /// Execute single tool
-    async fn execute_single_tool(&self, tool_id: String) -> BitFunResult<ToolExecutionResult> {
+    async fn execute_single_tool(
+        &self,
+        task_key: ToolTaskKey,
+    ) -> BitFunResult<ToolExecutionResult> {
         let start_time = Instant::now();
+        let tool_id = task_key.tool_id.clone();
 
-        debug!("Starting tool execution: tool_id={}", tool_id);
+        debug!("Starting tool execution: task={}", task_key);
 
         // Get task
         let task = self
             .state_manager
-            .get_task(&tool_id)
-            .ok_or_else(|| BitFunError::NotFound(format!("Tool task not found: {}", tool_id)))?;
+            .get_task(&task_key)
+            .ok_or_else(|| BitFunError::NotFound(format!("Tool task not found: {}", task_key)))?;
 
         let tool_name = task.tool_call.tool_name.clone();
         let tool_args = task.tool_call.arguments.clone();
@@ -399,7 +455,31 @@ impl ToolPipeline {
             tool_name, tool_id
         );
 
-        if tool_name.is_empty() || tool_is_error {
+        if tool_is_error && !tool_name.is_empty() {
+            let result = invalid_arguments_result(&task.tool_call);
+            warn!(
+                "Tool arguments invalid, asking model to re-issue: tool_name={}, tool_id={}",
+                tool_name, tool_id
+            );
+            self.state_manager
+                .update_state(
+                    &task_key,
+                    ToolExecutionState::Failed {
+                        error: result.result_for_assistant.clone().unwrap_or_default(),
+                        is_retryable: false,
+                    },
+                )
+                .await;
+
+            return Ok(ToolExecutionResult {
+                tool_id,
+                tool_name,
+                result,
+                execution_time_ms: start_time.elapsed().as_millis() as u64,
+            });
+        }
+
+        if tool_name.is_empty() {
             let error_msg = format!(
                 "Missing tool name or tool arguments are invalid. \
                 This may be caused by network errors (packet loss, connection issues) or model output anomalies. \
@@ -407,7 +487,7 @@ impl ToolPipeline {
             );
             self.state_manager
                 .update_state(
-                    &tool_id,
+                    &task_key,
                     ToolExecutionState::Failed {
                         error: error_msg.clone(),
                         is_retryable: false,
@@ -432,7 +512,7 @@ impl ToolPipeline {
             // Update state to failed
             self.state_manager
                 .update_state(
-                    &tool_id,
+                    &task_key,
                     ToolExecutionState::Failed {
                         error: error_msg.clone(),
                         is_retryable: false,
@@ -443,10 +523,22 @@ impl ToolPipeline {
             return Err(BitFunError::Validation(error_msg));
         }
 
-        // Create cancellation token
-        let cancellation_token = CancellationToken::new();
-        self.cancellation_tokens
-            .insert(tool_id.clone(), cancellation_token.clone());
+        // Create cancellation token; within a turn it is a child of the turn's
+        // root token, and the registration is held until the tool returns
+        let turn_child = turn_scope(&task.context.session_id, &task.context.dialog_turn_id)
+            .map(|scope| scope.register(format!("tool {} ({})", tool_name, tool_id)));
+        let cancellation_token = turn_child
+            .as_ref()
+            .map(|child| child.token().clone())
+            .unwrap_or_default();
+        let _tracked_tool = session_runtime(&task.context.session_id).track_tool(
+            &tool_id,
+            ActiveToolExecution {
+                dialog_turn_id: task.context.dialog_turn_id.clone(),
+                tool_name: tool_name.clone(),
+                cancellation_token: cancellation_token.clone(),
+            },
+        );
 
         debug!("Executing tool: tool_name={}", tool_name);
 
@@ -464,10 +556,37 @@ impl ToolPipeline {
                 })?
         };
 
+        if task.tool_call.repaired && !tool.is_readonly() {
+            let result = repaired_arguments_result(&task.tool_call);
+            warn!(
+                "Tool arguments were repaired, asking model to re-issue: tool_name={}, tool_id={}",
+                tool_name, tool_id
+            );
+            self.state_manager
+                .update_state(
+                    &task_key,
+                    ToolExecutionState::Failed {
+                        error: result.result_for_assistant.clone().unwrap_or_default(),
+                        is_retryable: false,
+                    },
+                )
+                .await;
+
+            return Ok(ToolExecutionResult {
+                tool_id,
+                tool_name,
+                result,
+                execution_time_ms: start_time.elapsed().as_millis() as u64,
+            });
+        }
+
         let is_streaming = tool.supports_streaming();
 
-        let needs_confirmation =
-            task.options.confirm_before_run && tool.needs_permissions(Some(&tool_args));
+        let needs_confirmation = task.options.confirm_before_run
+            && tool.needs_permissions(Some(&tool_args))
+            && tool
+                .requires_confirmation(&tool_args, task.context.workspace.as_ref())
+                .await;
 
         if needs_confirmation {
             info!("Tool requires confirmation: tool_name={}", tool_name);
@@ -481,11 +600,11 @@ impl ToolPipeline {
                 None => std::time::SystemTime::now() + Duration::from_secs(ONE_YEAR_SECS),
             };
 
-            self.confirmation_channels.insert(tool_id.clone(), tx);
+            self.confirmation_channels.insert(task_key.clone(), tx);
 
             self.state_manager
                 .update_state(
-                    &tool_id,
+                    &task_key,
                     ToolExecutionState::AwaitingConfirmation {
                         params: tool_args.clone(),
                         timeout_at,
@@ -523,7 +642,7 @@ impl ToolPipeline {
                 Some(Ok(ConfirmationResponse::Rejected(reason))) => {
                     self.state_manager
                         .update_state(
-                            &tool_id,
+                            &task_key,
                             ToolExecutionState::Cancelled {
                                 reason: format!("User rejected: {}", reason),
                             },
@@ -539,7 +658,7 @@ impl ToolPipeline {
                     // Channel closed
                     self.state_manager
                         .update_state(
-                            &tool_id,
+                            &task_key,
                             ToolExecutionState::Cancelled {
                                 reason: "Confirmation channel closed".to_string(),
                             },
@@ -551,7 +670,7 @@ impl ToolPipeline {
                 None => {
                     self.state_manager
                         .update_state(
-                            &tool_id,
+                            &task_key,
                             ToolExecutionState::Cancelled {
                                 reason: "Confirmation timeout".to_string(),
                             },
@@ -566,19 +685,18 @@ impl ToolPipeline {
                 }
             }
 
-            self.confirmation_channels.remove(&tool_id);
+            self.confirmation_channels.remove(&task_key);
         }
 
         if cancellation_token.is_cancelled() {
             self.state_manager
                 .update_state(
-                    &tool_id,
+                    &task_key,
                     ToolExecutionState::Cancelled {
                         reason: "Tool was cancelled before execution".to_string(),
                     },
                 )
                 .await;
-            self.cancellation_tokens.remove(&tool_id);
             return Err(BitFunError::Cancelled(
                 "Tool was cancelled before execution".to_string(),
             ));
@@ -588,7 +706,7 @@ impl ToolPipeline {
         if is_streaming {
             self.state_manager
                 .update_state(
-                    &tool_id,
+                    &task_key,
                     ToolExecutionState::Streaming {
                         started_at: std::time::SystemTime::now(),
                         chunks_received: 0,
@@ -598,7 +716,7 @@ impl ToolPipeline {
         } else {
             self.state_manager
                 .update_state(
-                    &tool_id,
+                    &task_key,
                     ToolExecutionState::Running {
                         started_at: std::time::SystemTime::now(),
                         progress: None,
@@ -611,15 +729,13 @@ impl ToolPipeline {
             .execute_with_retry(&task, cancellation_token.clone(), tool)
             .await;
 
-        self.cancellation_tokens.remove(&tool_id);
-
         match result {
             Ok(tool_result) => {
                 let duration_ms = start_time.elapsed().as_millis() as u64;
 
                 self.state_manager
                     .update_state(
-                        &tool_id,
+                        &task_key,
                         ToolExecutionState::Completed {
                             result: convert_to_framework_result(&tool_result),
                             duration_ms,
@@ -645,7 +761,7 @@ impl ToolPipeline {
 
                 self.state_manager
                     .update_state(
-                        &tool_id,
+                        &task_key,
                         ToolExecutionState::Failed {
                             error: error_msg.clone(),
                             is_retryable,
@@ -860,7 +976,7 @@ impl ToolPipeline {
                 // Update state
                 self.state_manager
                     .update_state(
-                        &task.tool_call.tool_id,
+                        &task.key(),
                         ToolExecutionState::Streaming {
                             started_at: std::time::SystemTime::now(),
                             chunks_received,
@@ -881,28 +997,40 @@ impl ToolPipeline {
     }
 
     /// Cancel tool execution
-    pub async fn cancel_tool(&self, tool_id: &str, reason: String) -> BitFunResult<()> {
+    pub async fn cancel_tool(
+        &self,
+        session_id: &str,
+        tool_id: &str,
+        reason: String,
+    ) -> BitFunResult<()> {
+        let task_key = self.find_task_key(session_id, tool_id)?;
+        self.cancel_task(&task_key, reason).await
+    }
+
+    async fn cancel_task(&self, task_key: &ToolTaskKey, reason: String) -> BitFunResult<()> {
         // 1. Trigger cancellation token
-        if let Some((_, token)) = self.cancellation_tokens.remove(tool_id) {
-            token.cancel();
-            debug!("Cancellation token triggered: tool_id={}", tool_id);
+        let execution = existing_session_runtime(&task_key.session_id)
+            .and_then(|runtime| runtime.untrack_tool(&task_key.tool_id));
+        if let Some(execution) = execution {
+            execution.cancellation_token.cancel();
+            debug!("Cancellation token triggered: task={}", task_key);
         } else {
             debug!(
-                "Cancellation token not found (tool may have completed): tool_id={}",
-                tool_id
+                "Cancellation token not found (tool may have completed): task={}",
+                task_key
             );
         }
 
         // 2. Clean up confirmation channel (if waiting for confirmation)
-        if let Some((_, _tx)) = self.confirmation_channels.remove(tool_id) {
+        if let Some((_, _tx)) = self.confirmation_channels.remove(task_key) {
             // Channel will be automatically closed, causing await rx to return Err
-            debug!("Cleared confirmation channel: tool_id={}", tool_id);
+            debug!("Cleared confirmation channel: task={}", task_key);
         }
 
         // 3. Update state to cancelled
         self.state_manager
             .update_state(
-                tool_id,
+                task_key,
                 ToolExecutionState::Cancelled {
                     reason: reason.clone(),
                 },
@@ -910,12 +1038,23 @@ impl ToolPipeline {
             .await;
 
         info!(
-            "Tool execution cancelled: tool_id={}, reason={}",
-            tool_id, reason
+            "Tool execution cancelled: task={}, reason={}",
+            task_key, reason
         );
         Ok(())
     }
 
+    fn find_task_key(&self, session_id: &str, tool_id: &str) -> BitFunResult<ToolTaskKey> {
+        self.state_manager
+            .find_task(session_id, tool_id)
+            .ok_or_else(|| {
+                BitFunError::NotFound(format!(
+                    "Tool task not found: session_id={}, tool_id={}",
+                    session_id, tool_id
+                ))
+            })
+    }
+
     /// Cancel all tools for a dialog turn
     pub async fn cancel_dialog_turn_tools(&self, dialog_turn_id: &str) -> BitFunResult<()> {
         info!(
@@ -944,7 +1083,7 @@ impl ToolPipeline {
                     "Cancelling tool: tool_id={}, state={:?}",
                     task.tool_call.tool_id, task.state
                 );
-                self.cancel_tool(&task.tool_call.tool_id, "Dialog turn cancelled".to_string())
+                self.cancel_task(&task.key(), "Dialog turn cancelled".to_string())
                     .await?;
                 cancelled_count += 1;
             } else {
@@ -966,12 +1105,14 @@ impl ToolPipeline {
     /// Confirm tool execution
     pub async fn confirm_tool(
         &self,
+        session_id: &str,
         tool_id: &str,
         updated_input: Option<serde_json::Value>,
     ) -> BitFunResult<()> {
+        let task_key = self.find_task_key(session_id, tool_id)?;
         let task = self
             .state_manager
-            .get_task(tool_id)
+            .get_task(&task_key)
             .ok_or_else(|| BitFunError::NotFound(format!("Tool task not found: {}", tool_id)))?;
 
         // Check if the state is waiting for confirmation
@@ -985,11 +1126,12 @@ impl ToolPipeline {
         // If the user modified the parameters, update the task parameters first
         if let Some(new_args) = updated_input {
             debug!("User updated tool arguments: tool_id={}", tool_id);
-            self.state_manager.update_task_arguments(tool_id, new_args);
+            self.state_manager
+                .update_task_arguments(&task_key, new_args);
         }
 
         // Get sender from map and send confirmation response
-        if let Some((_, tx)) = self.confirmation_channels.remove(tool_id) {
+        if let Some((_, tx)) = self.confirmation_channels.remove(&task_key) {
             let _ = tx.send(ConfirmationResponse::Confirmed);
             info!("User confirmed tool execution: tool_id={}", tool_id);
             Ok(())
@@ -1002,10 +1144,16 @@ impl ToolPipeline {
     }
 
     /// Reject tool execution
-    pub async fn reject_tool(&self, tool_id: &str, reason: String) -> BitFunResult<()> {
+    pub async fn reject_tool(
+        &self,
+        session_id: &str,
+        tool_id: &str,
+        reason: String,
+    ) -> BitFunResult<()> {
+        let task_key = self.find_task_key(session_id, tool_id)?;
         let task = self
             .state_manager
-            .get_task(tool_id)
+            .get_task(&task_key)
             .ok_or_else(|| BitFunError::NotFound(format!("Tool task not found: {}", tool_id)))?;
 
         // Check if the state is waiting for confirmation
@@ -1017,7 +1165,7 @@ impl ToolPipeline {
         }
 
         // Get sender from map and send rejection response
-        if let Some((_, tx)) = self.confirmation_channels.remove(tool_id) {
+        if let Some((_, tx)) = self.confirmation_channels.remove(&task_key) {
             let _ = tx.send(ConfirmationResponse::Rejected(reason.clone()));
             info!(
                 "User rejected tool execution: tool_id={}, reason={}",
@@ -1028,7 +1176,7 @@ impl ToolPipeline {
             // If the channel does not exist, mark it as cancelled directly
             self.state_manager
                 .update_state(
-                    tool_id,
+                    &task_key,
                     ToolExecutionState::Cancelled {
                         reason: format!("User rejected: {}", reason),
                     },
@@ -5,6 +5,7 @@ use crate::agentic::events::SubagentParentInfo as EventSubagentParentInfo;
 use crate::agentic::workspace::WorkspaceServices;
 use crate::agentic::WorkspaceBinding;
 use std::collections::HashMap;
+use std::fmt;
 use std::time::SystemTime;
 
 /// Tool execution options
@@ -36,6 +37,8 @@ pub struct SubagentParentInfo {
     pub tool_call_id: String,
     pub session_id: String,
     pub dialog_turn_id: String,
+    /// Model the subagent runs on; set once it is resolved
+    pub model_id: Option<String>,
 }
 
 impl From<SubagentParentInfo> for EventSubagentParentInfo {
@@ -44,6 +47,7 @@ impl From<SubagentParentInfo> for EventSubagentParentInfo {
             tool_call_id: info.tool_call_id,
             session_id: info.session_id,
             dialog_turn_id: info.dialog_turn_id,
+            model_id: info.model_id,
         }
     }
 }
@@ -64,6 +68,20 @@ pub struct ToolExecutionContext {
     pub workspace_services: Option<WorkspaceServices>,
 }
 
+/// Identifies a tool task. Tool call IDs come from the model and are only
+/// unique within a session, so tasks are keyed by both.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ToolTaskKey {
+    pub session_id: String,
+    pub tool_id: String,
+}
+
+impl fmt::Display for ToolTaskKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.session_id, self.tool_id)
+    }
+}
+
 /// Tool execution task
 #[derive(Debug, Clone)]
 pub struct ToolTask {
@@ -92,6 +110,13 @@ impl ToolTask {
             completed_at: None,
         }
     }
+
+    pub fn key(&self) -> ToolTaskKey {
+        ToolTaskKey {
+            session_id: self.context.session_id.clone(),
+            tool_id: self.tool_call.tool_id.clone(),
+        }
+    }
 }
 
 /// Tool execution result wrapper
@@ -85,9 +85,11 @@ impl ToolRegistry {
         self.register_tool(Arc::new(FileReadTool::new()));
         self.register_tool(Arc::new(GlobTool::new()));
         self.register_tool(Arc::new(GrepTool::new()));
+        self.register_tool(Arc::new(CodebaseSearchTool::new()));
         self.register_tool(Arc::new(FileWriteTool::new()));
         self.register_tool(Arc::new(FileEditTool::new()));
         self.register_tool(Arc::new(DeleteFileTool::new()));
+        self.register_tool(Arc::new(ReadLintsTool::new()));
         self.register_tool(Arc::new(BashTool::new()));
         self.register_tool(Arc::new(TerminalControlTool::new()));
         self.register_tool(Arc::new(SessionControlTool::new()));
@@ -131,6 +133,10 @@ impl ToolRegistry {
         // Code review submit tool
         self.register_tool(Arc::new(CodeReviewTool::new()));
 
+        // Review mode: findings submission and applying their suggested patches
+        self.register_tool(Arc::new(ReviewFindingsTool::new()));
+        self.register_tool(Arc::new(ApplyPatchTool::new()));
+
         // MiniApp Agent tool (single InitMiniApp)
         self.register_tool(Arc::new(InitMiniAppTool::new()));
 
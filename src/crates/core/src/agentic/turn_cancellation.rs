@@ -0,0 +1,213 @@
+//! Turn cancellation scopes
+//!
+//! Every dialog turn in flight has a scope holding its root cancellation
+//! token. Work done for the turn (the model stream, each tool call, subagents
+//! through their Task call, background commands) registers as a child and
+//! keeps the returned guard while it runs. Aborting a turn cancels the root
+//! and then waits, up to a grace period, until every guard is dropped, so the
+//! turn is only reported aborted once nothing of it is still running.
+//!
+//! Scopes are held by the [`SessionRuntime`](crate::agentic::session_runtime::SessionRuntime)
+//! of the turn's session.
+
+use crate::agentic::session_runtime::{existing_session_runtime, session_runtime};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Cancellation scope of one dialog turn.
+pub struct TurnScope {
+    root: CancellationToken,
+    /// Cancelled when the turn ends, whether it completed or was aborted
+    finished: CancellationToken,
+    children: Mutex<BTreeMap<u64, String>>,
+    next_child_id: AtomicU64,
+    children_changed: Notify,
+}
+
+/// Registration of work running for a turn; dropping it confirms the work ended.
+pub struct TurnChild {
+    scope: Arc<TurnScope>,
+    id: u64,
+    token: CancellationToken,
+}
+
+impl TurnChild {
+    /// Cancelled when the turn is aborted.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for TurnChild {
+    fn drop(&mut self) {
+        self.scope
+            .children
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+        self.scope.children_changed.notify_waiters();
+    }
+}
+
+impl TurnScope {
+    pub fn new(root: CancellationToken) -> Arc<Self> {
+        Arc::new(Self {
+            root,
+            finished: CancellationToken::new(),
+            children: Mutex::new(BTreeMap::new()),
+            next_child_id: AtomicU64::new(0),
+            children_changed: Notify::new(),
+        })
+    }
+
+    pub fn token(&self) -> &CancellationToken {
+        &self.root
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.root.is_cancelled()
+    }
+
+    pub fn cancel(&self) {
+        self.root.cancel();
+    }
+
+    /// Cancelled when the turn ends; lets work that outlives a tool call (such
+    /// as a background command watcher) stop watching for an abort.
+    pub fn finished(&self) -> &CancellationToken {
+        &self.finished
+    }
+
+    /// Registers work running for the turn; `label` names it in logs when it
+    /// does not stop in time.
+    pub fn register(self: &Arc<Self>, label: impl Into<String>) -> TurnChild {
+        let id = self.next_child_id.fetch_add(1, Ordering::Relaxed);
+        self.children
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, label.into());
+        TurnChild {
+            scope: Arc::clone(self),
+            id,
+            token: self.root.child_token(),
+        }
+    }
+
+    /// Labels of the registered work that is still running, oldest first.
+    pub fn running(&self) -> Vec<String> {
+        self.children
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Cancels the turn and waits up to `grace` for its registered work to
+    /// end; returns the labels of the work still running after that.
+    pub async fn abort(&self, grace: Duration) -> Vec<String> {
+        self.cancel();
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            // Listen before checking so a guard dropped in between is not missed
+            let changed = self.children_changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+            if self
+                .children
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_empty()
+            {
+                return Vec::new();
+            }
+            if tokio::time::timeout_at(deadline, changed).await.is_err() {
+                return self.running();
+            }
+        }
+    }
+}
+
+/// Scope of a turn in flight.
+pub fn turn_scope(session_id: &str, dialog_turn_id: &str) -> Option<Arc<TurnScope>> {
+    existing_session_runtime(session_id)?.turn_scope(dialog_turn_id)
+}
+
+/// Scope of a turn, started with a new root token if the turn has none yet.
+pub fn ensure_turn_scope(session_id: &str, dialog_turn_id: &str) -> Arc<TurnScope> {
+    session_runtime(session_id).ensure_turn_scope(dialog_turn_id)
+}
+
+/// Starts the scope of a turn with `root` as its root token (for a subagent, a
+/// child of the calling tool's token), replacing an earlier scope of the turn.
+pub fn insert_turn_scope(
+    session_id: &str,
+    dialog_turn_id: &str,
+    root: CancellationToken,
+) -> Arc<TurnScope> {
+    session_runtime(session_id).insert_turn_scope(dialog_turn_id, root)
+}
+
+/// Ends the scope of a turn.
+pub fn remove_turn_scope(session_id: &str, dialog_turn_id: &str) -> Option<Arc<TurnScope>> {
+    existing_session_runtime(session_id)?.remove_turn_scope(dialog_turn_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn abort_waits_for_children_to_stop() {
+        let scope = TurnScope::new(CancellationToken::new());
+        let child = scope.register("tool Read");
+        let worker = tokio::spawn(async move {
+            child.token().cancelled().await;
+            // Some cleanup before confirming
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(child);
+        });
+
+        assert_eq!(scope.running(), ["tool Read"]);
+        let unfinished = scope.abort(Duration::from_secs(5)).await;
+        assert!(unfinished.is_empty());
+        worker.await.unwrap();
+        assert!(scope.running().is_empty());
+    }
+
+    #[tokio::test]
+    async fn abort_reports_children_that_outlive_the_grace_period() {
+        let scope = TurnScope::new(CancellationToken::new());
+        let _stuck = scope.register("tool Stuck");
+        let quick = scope.register("model stream");
+        drop(quick);
+
+        let unfinished = scope.abort(Duration::from_millis(50)).await;
+        assert_eq!(unfinished, ["tool Stuck"]);
+    }
+
+    #[test]
+    fn registry_tracks_turns_until_removed() {
+        let session = "session-registry-test";
+        let scope = ensure_turn_scope(session, "turn-registry-test");
+        assert!(Arc::ptr_eq(
+            &scope,
+            &ensure_turn_scope(session, "turn-registry-test")
+        ));
+        assert!(turn_scope("other-session-registry-test", "turn-registry-test").is_none());
+
+        let parent = CancellationToken::new();
+        let replaced = insert_turn_scope(session, "turn-registry-test", parent.child_token());
+        assert!(scope.finished().is_cancelled());
+        parent.cancel();
+        assert!(replaced.is_cancelled());
+
+        assert!(remove_turn_scope(session, "turn-registry-test").is_some());
+        assert!(replaced.finished().is_cancelled());
+        assert!(turn_scope(session, "turn-registry-test").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+//! Turn summary
+//!
+//! After a dialog turn completes, one non-streaming call to the fast model condenses
+//! the turn's tool calls and their outcomes into three bullets: what was done, what
+//! changed and what comes next. Off unless `app.ai_experience.enable_turn_summary`
+//! is set; a session opts out with `skip_turn_summary`.
+
+use crate::agentic::core::{Message, MessageContent, SessionConfig};
+use crate::infrastructure::ai::get_global_ai_client_factory;
+use crate::service::session::TurnSummary;
+use crate::util::errors::{BitFunError, BitFunResult};
+use crate::util::types::Message as AIMessage;
+
+/// Characters kept of the user request and of the final response
+const TEXT_CHARS: usize = 600;
+/// Characters kept of each tool call's arguments and result
+const TOOL_CHARS: usize = 200;
+/// Tool calls listed before the rest are only counted
+const MAX_TOOL_CALLS: usize = 40;
+
+/// Whether turns of a session with `config` get a summary
+pub async fn is_enabled(config: &SessionConfig) -> bool {
+    if config.skip_turn_summary {
+        return false;
+    }
+    match crate::service::config::get_global_config_service().await {
+        Ok(service) => service
+            .get_config::<bool>(Some("app.ai_experience.enable_turn_summary"))
+            .await
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Summarizes a completed turn; None when it called no tools or the answer can't be read
+pub async fn summarize_turn(
+    user_input: &str,
+    messages: &[Message],
+    final_response: &str,
+) -> BitFunResult<Option<TurnSummary>> {
+    let Some(digest) = build_digest(user_input, messages, final_response) else {
+        return Ok(None);
+    };
+
+    let language_instruction = match crate::service::get_global_i18n_service().await {
+        Some(service) => match service.get_current_locale().await {
+            crate::service::LocaleId::ZhCN => "使用简体中文",
+            crate::service::LocaleId::EnUS => "Use English",
+        },
+        None => "Use English",
+    };
+    let system_prompt = format!(
+        "You summarize what an AI coding agent did in one turn, from its tool calls and their results.\n\nReply with exactly three lines and nothing else:\nDid: <the work done, one sentence>\nChanged: <files or state that changed, or \"nothing\">\nNext: <what remains or the natural next step, or \"nothing\">\n\nRequirements:\n- {} for the text, but keep the labels Did, Changed and Next in English\n- Only state what the tool results show; a failed call changed nothing\n- At most 25 words per line",
+        language_instruction
+    );
+
+    let messages = vec![
+        ai_message("system", system_prompt),
+        ai_message("user", digest),
+    ];
+    let ai_client = get_global_ai_client_factory()
+        .await
+        .map_err(|e| BitFunError::AIClient(format!("Failed to get AI client factory: {}", e)))?
+        .get_client_resolved("fast")
+        .await
+        .map_err(|e| BitFunError::AIClient(format!("Failed to get AI client: {}", e)))?;
+    let response = ai_client
+        .send_message(messages, None)
+        .await
+        .map_err(|e| BitFunError::ai(format!("AI call failed: {}", e)))?;
+
+    Ok(parse_summary(&response.text))
+}
+
+fn ai_message(role: &str, content: String) -> AIMessage {
+    AIMessage {
+        role: role.to_string(),
+        content: Some(content),
+        reasoning_content: None,
+        thinking_signature: None,
+        tool_calls: None,
+        tool_call_id: None,
+        name: None,
+        tool_image_attachments: None,
+        server_tool_events: None,
+    }
+}
+
+/// Request, tool calls with their outcomes and final answer of a turn, as model input
+fn build_digest(user_input: &str, messages: &[Message], final_response: &str) -> Option<String> {
+    let mut calls = Vec::new();
+    for message in messages {
+        match &message.content {
+            MessageContent::Mixed { tool_calls, .. } => {
+                for call in tool_calls {
+                    calls.push((
+                        call.tool_id.as_str(),
+                        format!(
+                            "{} {}",
+                            call.tool_name,
+                            clip(&call.arguments.to_string(), TOOL_CHARS)
+                        ),
+                        None,
+                    ));
+                }
+            }
+            MessageContent::ToolResult {
+                tool_id,
+                result,
+                result_for_assistant,
+                is_error,
+                ..
+            } => {
+                let output = result_for_assistant
+                    .clone()
+                    .unwrap_or_else(|| result.to_string());
+                let outcome = format!(
+                    "{}: {}",
+                    if *is_error { "failed" } else { "ok" },
+                    clip(&output, TOOL_CHARS)
+                );
+                if let Some(call) = calls.iter_mut().find(|(id, _, _)| id == tool_id) {
+                    call.2 = Some(outcome);
+                }
+            }
+            _ => {}
+        }
+    }
+    if calls.is_empty() {
+        return None;
+    }
+
+    let mut digest = format!("Request: {}\n\nTool calls:\n", clip(user_input, TEXT_CHARS));
+    for (_, call, outcome) in calls.iter().take(MAX_TOOL_CALLS) {
+        digest.push_str(&format!(
+            "- {} -> {}\n",
+            call,
+            outcome.as_deref().unwrap_or("no result")
+        ));
+    }
+    if calls.len() > MAX_TOOL_CALLS {
+        digest.push_str(&format!("- ... {} more\n", calls.len() - MAX_TOOL_CALLS));
+    }
+    if !final_response.trim().is_empty() {
+        digest.push_str(&format!(
+            "\nFinal response: {}",
+            clip(final_response.trim(), TEXT_CHARS)
+        ));
+    }
+    Some(digest)
+}
+
+/// Reads the `Did:` / `Changed:` / `Next:` lines; None unless all three are there
+fn parse_summary(text: &str) -> Option<TurnSummary> {
+    let (mut did, mut changed, mut next) = (None, None, None);
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['-', '*', '•', ' ']);
+        let Some(colon) = line.find([':', '：']) else {
+            continue;
+        };
+        let (label, value) = line.split_at(colon);
+        let value = value.trim_start_matches([':', '：']);
+        let value = value.trim().trim_start_matches("**").trim();
+        if value.is_empty() {
+            continue;
+        }
+        match label.trim().trim_matches('*').to_ascii_lowercase().as_str() {
+            "did" => did = Some(value.to_string()),
+            "changed" => changed = Some(value.to_string()),
+            "next" => next = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some(TurnSummary {
+        did: did?,
+        changed: changed?,
+        next: next?,
+    })
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agentic::core::message::MessageMetadata;
+    use crate::agentic::core::{MessageRole, ToolCall};
+    use serde_json::json;
+    use std::time::SystemTime;
+
+    fn message(role: MessageRole, content: MessageContent) -> Message {
+        Message {
+            id: String::new(),
+            role,
+            content,
+            timestamp: SystemTime::now(),
+            metadata: MessageMetadata::default(),
+        }
+    }
+
+    #[test]
+    fn digest_pairs_tool_calls_with_their_results() {
+        let messages = vec![
+            message(
+                MessageRole::Assistant,
+                MessageContent::Mixed {
+                    reasoning_content: None,
+                    text: String::new(),
+                    tool_calls: vec![ToolCall {
+                        tool_id: "t1".to_string(),
+                        tool_name: "Edit".to_string(),
+                        arguments: json!({ "file_path": "src/lib.rs" }),
+                        is_error: false,
+                        repaired: false,
+                        argument_error: None,
+                    }],
+                },
+            ),
+            message(
+                MessageRole::Tool,
+                MessageContent::ToolResult {
+                    tool_id: "t1".to_string(),
+                    tool_name: "Edit".to_string(),
+                    result: json!({}),
+                    result_for_assistant: Some("1 replacement".to_string()),
+                    is_error: false,
+                    image_attachments: None,
+                },
+            ),
+        ];
+
+        let digest = build_digest("fix the build", &messages, "Done.").unwrap();
+        assert!(digest.starts_with("Request: fix the build\n"));
+        assert!(digest.contains("- Edit {\"file_path\":\"src/lib.rs\"} -> ok: 1 replacement\n"));
+        assert!(digest.ends_with("Final response: Done."));
+
+        assert_eq!(build_digest("hi", &[], "Hello!"), None);
+    }
+
+    #[test]
+    fn parses_labelled_lines() {
+        let summary = parse_summary(
+            "- **Did:** Fixed the failing build\n- Changed: src/lib.rs\nNext：运行测试\n",
+        )
+        .unwrap();
+        assert_eq!(summary.did, "Fixed the failing build");
+        assert_eq!(summary.changed, "src/lib.rs");
+        assert_eq!(summary.next, "运行测试");
+
+        assert_eq!(parse_summary("Did: something\nChanged: nothing"), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::infrastructure::filesystem::{atomic_write, content_sha256_hex, FileOperationOptions};
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -90,6 +91,21 @@ pub trait WorkspaceFileSystem: Send + Sync {
     async fn read_file(&self, path: &str) -> anyhow::Result<Vec<u8>>;
     async fn read_file_text(&self, path: &str) -> anyhow::Result<String>;
     async fn write_file(&self, path: &str, contents: &[u8]) -> anyhow::Result<()>;
+    /// Writes `contents` only while the file still has the content hash
+    /// `expected_hash` (see `content_sha256_hex`).
+    async fn write_file_if_unchanged(
+        &self,
+        path: &str,
+        contents: &[u8],
+        expected_hash: &str,
+    ) -> anyhow::Result<()> {
+        let current = self.read_file(path).await?;
+        if content_sha256_hex(&current) != expected_hash {
+            anyhow::bail!("File was modified since it was last read: {}", path);
+        }
+        self.write_file(path, contents).await
+    }
+    async fn remove_file(&self, path: &str) -> anyhow::Result<()>;
     async fn exists(&self, path: &str) -> anyhow::Result<bool>;
     async fn is_file(&self, path: &str) -> anyhow::Result<bool>;
     async fn is_dir(&self, path: &str) -> anyhow::Result<bool>;
@@ -149,7 +165,26 @@ impl WorkspaceFileSystem for LocalWorkspaceFs {
         if let Some(parent) = Path::new(path).parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
-        Ok(tokio::fs::write(path, contents).await?)
+        atomic_write(Path::new(path), contents, &FileOperationOptions::default()).await?;
+        Ok(())
+    }
+
+    async fn write_file_if_unchanged(
+        &self,
+        path: &str,
+        contents: &[u8],
+        expected_hash: &str,
+    ) -> anyhow::Result<()> {
+        let options = FileOperationOptions {
+            expected_hash: Some(expected_hash.to_string()),
+            ..Default::default()
+        };
+        atomic_write(Path::new(path), contents, &options).await?;
+        Ok(())
+    }
+
+    async fn remove_file(&self, path: &str) -> anyhow::Result<()> {
+        Ok(tokio::fs::remove_file(path).await?)
     }
 
     async fn exists(&self, path: &str) -> anyhow::Result<bool> {
@@ -240,6 +275,13 @@ impl WorkspaceFileSystem for RemoteWorkspaceFs {
             .map_err(|e| anyhow::anyhow!("{}", e))
     }
 
+    async fn remove_file(&self, path: &str) -> anyhow::Result<()> {
+        self.file_service
+            .remove_file(&self.connection_id, path)
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
     async fn exists(&self, path: &str) -> anyhow::Result<bool> {
         self.file_service
             .exists(&self.connection_id, path)
@@ -0,0 +1,191 @@
+use super::types::*;
+use crate::function_agents::git_func_agent::AIAnalysisService;
+use crate::infrastructure::ai::AIClientFactory;
+use crate::util::types::Message;
+/**
+ * AI service layer
+ *
+ * Merges and rewrites grouped changelog entries through the same
+ * non-streaming JSON request the commit message agent uses
+ */
+use log::debug;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Prompt template constants (embedded at compile time)
+const CHANGELOG_PROMPT: &str = include_str!("prompts/changelog.md");
+
+/// Entries in the prompt are capped so a long release still fits
+const MAX_PROMPT_ENTRIES: usize = 400;
+
+#[derive(Debug, Deserialize)]
+struct AIChangelogResponse {
+    sections: HashMap<String, Vec<AIChangelogEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AIChangelogEntry {
+    text: String,
+    #[serde(default)]
+    commits: Vec<String>,
+}
+
+pub struct AIChangelogService {
+    analysis: AIAnalysisService,
+}
+
+impl AIChangelogService {
+    pub async fn new_with_agent_config(
+        factory: Arc<AIClientFactory>,
+        agent_name: &str,
+    ) -> AgentResult<Self> {
+        Ok(Self {
+            analysis: AIAnalysisService::new_with_agent_config(factory, agent_name).await?,
+        })
+    }
+
+    /// Asks the AI to merge related entries and rewrite them for users.
+    /// Entries keep the references of the commits they cover; commits the
+    /// AI leaves out keep their original entry.
+    pub async fn humanize_sections(
+        &self,
+        sections: &BTreeMap<ChangelogSection, Vec<ChangelogEntry>>,
+        commits: &[ChangelogCommit],
+        language: &Language,
+    ) -> AgentResult<BTreeMap<ChangelogSection, Vec<ChangelogEntry>>> {
+        let prompt = build_prompt(sections, language);
+        debug!("Humanizing changelog: prompt_length={}", prompt.len());
+
+        let response = self.analysis.call_ai(vec![Message::user(prompt)]).await?;
+        let json = crate::util::extract_json_from_ai_response(&response)
+            .ok_or_else(|| AgentError::analysis_error("Cannot extract JSON from response"))?;
+        let parsed: AIChangelogResponse = serde_json::from_str(&json).map_err(|e| {
+            AgentError::analysis_error(format!("Failed to parse AI response: {}", e))
+        })?;
+
+        Ok(merge_response(sections, commits, parsed))
+    }
+}
+
+fn build_prompt(
+    sections: &BTreeMap<ChangelogSection, Vec<ChangelogEntry>>,
+    language: &Language,
+) -> String {
+    let language_desc = match language {
+        Language::Chinese => "Chinese",
+        Language::English => "English",
+    };
+
+    let mut entries = Vec::new();
+    for (section, section_entries) in sections {
+        entries.push(format!("### {}", section.title()));
+        for entry in section_entries.iter().take(MAX_PROMPT_ENTRIES) {
+            entries.push(format!("- [{}] {}", entry.commits.join(","), entry.text));
+        }
+        if section_entries.len() > MAX_PROMPT_ENTRIES {
+            entries.push(format!(
+                "- ... {} more entries omitted",
+                section_entries.len() - MAX_PROMPT_ENTRIES
+            ));
+        }
+    }
+
+    CHANGELOG_PROMPT
+        .replace("{language_desc}", language_desc)
+        .replace("{entries}", &entries.join("\n"))
+}
+
+fn merge_response(
+    sections: &BTreeMap<ChangelogSection, Vec<ChangelogEntry>>,
+    commits: &[ChangelogCommit],
+    response: AIChangelogResponse,
+) -> BTreeMap<ChangelogSection, Vec<ChangelogEntry>> {
+    let references: HashMap<&str, &[String]> = commits
+        .iter()
+        .map(|commit| (commit.short_hash.as_str(), commit.references.as_slice()))
+        .collect();
+
+    let mut merged: BTreeMap<ChangelogSection, Vec<ChangelogEntry>> = BTreeMap::new();
+    let mut covered = HashSet::new();
+    for (title, entries) in response.sections {
+        let Some(section) = ChangelogSection::from_title(&title) else {
+            continue;
+        };
+        for entry in entries {
+            let hashes: Vec<String> = entry
+                .commits
+                .into_iter()
+                .filter(|hash| references.contains_key(hash.as_str()))
+                .filter(|hash| covered.insert(hash.clone()))
+                .collect();
+            // An entry that covers no known commit was made up
+            if hashes.is_empty() || entry.text.trim().is_empty() {
+                continue;
+            }
+
+            let mut entry_references: Vec<String> = Vec::new();
+            for hash in &hashes {
+                for reference in references[hash.as_str()] {
+                    if !entry_references.contains(reference) {
+                        entry_references.push(reference.clone());
+                    }
+                }
+            }
+            merged.entry(section).or_default().push(ChangelogEntry {
+                text: entry.text.trim().trim_start_matches("- ").to_string(),
+                references: entry_references,
+                commits: hashes,
+            });
+        }
+    }
+
+    for (section, entries) in sections {
+        for entry in entries {
+            if entry.commits.iter().any(|hash| !covered.contains(hash)) {
+                merged.entry(*section).or_default().push(entry.clone());
+            }
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function_agents::changelog_func_agent::changelog_generator::{
+        group_commits, parse_commit,
+    };
+
+    #[test]
+    fn merges_ai_entries_and_keeps_uncovered_commits() {
+        let commits = vec![
+            parse_commit("a1", "a1", "feat: add export (#1)"),
+            parse_commit("b2", "b2", "feat: export to csv (#2)"),
+            parse_commit("c3", "c3", "fix: crash on empty file"),
+        ];
+        let sections = group_commits(&commits);
+        let response: AIChangelogResponse = serde_json::from_str(
+            r#"{"sections": {
+                "added": [
+                    {"text": "Export sessions to CSV", "commits": ["a1", "b2"]},
+                    {"text": "Invented entry", "commits": ["zz"]}
+                ],
+                "Unknown": [{"text": "Nope", "commits": ["c3"]}]
+            }}"#,
+        )
+        .unwrap();
+
+        let merged = merge_response(&sections, &commits, response);
+
+        let added = &merged[&ChangelogSection::Added];
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].text, "Export sessions to CSV");
+        assert_eq!(added[0].references, vec!["#1", "#2"]);
+        assert_eq!(
+            merged[&ChangelogSection::Fixed][0].text,
+            "Crash on empty file"
+        );
+    }
+}
@@ -0,0 +1,541 @@
+use super::ai_service::AIChangelogService;
+use super::types::*;
+use crate::function_agents::git_func_agent::conventions;
+use crate::infrastructure::ai::AIClientFactory;
+use crate::service::git::execute_git_command;
+/**
+ * Changelog Function Agent - changelog generator
+ *
+ * Collects the commits of a revision range, groups them into
+ * Keep-a-Changelog sections and renders the fragment
+ */
+use log::{debug, info, warn};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+const CHANGELOG_HEADER: &str = "# Changelog\n\nAll notable changes to this project will be documented in this file.\n\nThe format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/).\n";
+
+/// `#123`, `owner/repo#123` or `GH-123`
+const REFERENCE: &str = r"(?:[\w.-]+/[\w.-]+)?#\d+|\bGH-\d+\b";
+
+/// References and PR/issue URLs
+static REFERENCE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"https?://\S+/(?:pull|issues|merge_requests)/\d+|{}",
+        REFERENCE
+    ))
+    .expect("valid reference pattern")
+});
+
+/// References appended to a subject, e.g. `(#123)` or `(closes #1, #2)`
+static TRAILING_REFERENCES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\s*\((?:(?:closes|fixes|resolves|refs?)\s+)?(?:{0})(?:\s*,\s*(?:{0}))*\)\s*$",
+        REFERENCE
+    ))
+    .expect("valid trailing reference pattern")
+});
+
+const FIELD_SEPARATOR: char = '\u{1f}';
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// Commit range resolved against the repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedRange {
+    /// Argument for `git log`
+    pub revision: String,
+    pub notes: Vec<String>,
+}
+
+pub struct ChangelogGenerator;
+
+impl ChangelogGenerator {
+    pub async fn generate_changelog(
+        repo_path: &Path,
+        options: ChangelogOptions,
+        factory: Arc<AIClientFactory>,
+    ) -> AgentResult<Changelog> {
+        info!("Generating changelog: repo_path={:?}", repo_path);
+
+        let range = Self::resolve_range(repo_path, options.range.as_deref()).await?;
+        let commits = Self::collect_commits(repo_path, &range.revision).await?;
+        debug!(
+            "Collected commits: range={}, count={}",
+            range.revision,
+            commits.len()
+        );
+
+        let mut sections = group_commits(&commits);
+        if options.humanize && !sections.is_empty() {
+            let ai_service =
+                AIChangelogService::new_with_agent_config(factory, "changelog-func-agent").await?;
+            match ai_service
+                .humanize_sections(&sections, &commits, &options.language)
+                .await
+            {
+                Ok(humanized) => sections = humanized,
+                Err(e) => warn!("Failed to humanize changelog, using commit subjects: {}", e),
+            }
+        }
+
+        let mut changelog = Changelog {
+            version: options
+                .version
+                .clone()
+                .unwrap_or_else(|| "Unreleased".to_string()),
+            date: options
+                .version
+                .as_ref()
+                .map(|_| chrono::Local::now().format("%Y-%m-%d").to_string()),
+            range: range.revision,
+            commit_count: commits.len(),
+            sections,
+            notes: range.notes,
+            markdown: String::new(),
+        };
+        changelog.markdown = render_markdown(&changelog, options.include_references);
+
+        Ok(changelog)
+    }
+
+    /// Turns the requested range into a `git log` revision. Without tags the
+    /// whole history is used; in a shallow clone a start revision that was not
+    /// fetched falls back to the available history instead of failing.
+    pub async fn resolve_range(
+        repo_path: &Path,
+        range: Option<&str>,
+    ) -> AgentResult<ResolvedRange> {
+        let repo = repo_path.to_string_lossy().to_string();
+
+        if execute_git_command(&repo, &["rev-parse", "--verify", "HEAD"])
+            .await
+            .is_err()
+        {
+            return Err(AgentError::invalid_input("Repository has no commits yet"));
+        }
+        let shallow = execute_git_command(&repo, &["rev-parse", "--is-shallow-repository"])
+            .await
+            .map(|out| out.trim() == "true")
+            .unwrap_or(false);
+
+        let mut notes = Vec::new();
+        let (start, end) = match range.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(range) => match range.split_once("..") {
+                Some((start, end)) => (
+                    Some(start.trim_end_matches('.').to_string()),
+                    Some(end.trim_start_matches('.').to_string()).filter(|e| !e.is_empty()),
+                ),
+                None => (Some(range.to_string()), None),
+            },
+            None => match execute_git_command(&repo, &["describe", "--tags", "--abbrev=0"]).await {
+                Ok(tag) => (Some(tag.trim().to_string()), None),
+                Err(_) => {
+                    notes.push("No tags found; the changelog covers the whole history".to_string());
+                    (None, None)
+                }
+            },
+        };
+        let end = end.unwrap_or_else(|| "HEAD".to_string());
+
+        let start = match start.filter(|s| !s.is_empty()) {
+            Some(start) => {
+                let spec = format!("{}^{{commit}}", start);
+                let exists =
+                    execute_git_command(&repo, &["rev-parse", "--verify", "--quiet", &spec])
+                        .await
+                        .is_ok();
+                if exists {
+                    Some(start)
+                } else if shallow {
+                    notes.push(format!(
+                        "{} is not part of this shallow clone; the changelog covers all fetched history",
+                        start
+                    ));
+                    None
+                } else {
+                    return Err(AgentError::invalid_input(format!(
+                        "Unknown revision: {}",
+                        start
+                    )));
+                }
+            }
+            None => None,
+        };
+
+        if shallow && start.is_none() && !notes.iter().any(|n| n.contains("shallow")) {
+            notes.push("Shallow clone: commits before the fetched history are missing".to_string());
+        }
+
+        Ok(ResolvedRange {
+            revision: match start {
+                Some(start) => format!("{}..{}", start, end),
+                None => end,
+            },
+            notes,
+        })
+    }
+
+    async fn collect_commits(
+        repo_path: &Path,
+        revision: &str,
+    ) -> AgentResult<Vec<ChangelogCommit>> {
+        let format = format!(
+            "--format=%H{0}%h{0}%B{1}",
+            FIELD_SEPARATOR, RECORD_SEPARATOR
+        );
+        let log = execute_git_command(
+            &repo_path.to_string_lossy(),
+            &["log", "--no-merges", &format, revision],
+        )
+        .await
+        .map_err(|e| AgentError::git_error(format!("Failed to read commit log: {}", e)))?;
+
+        Ok(parse_log(&log))
+    }
+
+    /// Inserts the fragment into `CHANGELOG.md`, replacing a section with the
+    /// same heading, and returns the file's path
+    pub async fn write_changelog(repo_path: &Path, changelog: &Changelog) -> AgentResult<PathBuf> {
+        let path = repo_path.join(CHANGELOG_FILE);
+        let existing = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(AgentError::internal_error(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        let content = insert_fragment(existing.as_deref(), &changelog.version, &changelog.markdown);
+        tokio::fs::write(&path, content).await.map_err(|e| {
+            AgentError::internal_error(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+        info!("Changelog written: path={}", path.display());
+        Ok(path)
+    }
+}
+
+/// Parses `git log` output written with the record/field separators
+pub fn parse_log(log: &str) -> Vec<ChangelogCommit> {
+    log.split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, FIELD_SEPARATOR);
+            let hash = fields.next()?.trim();
+            let short_hash = fields.next()?.trim();
+            let message = fields.next()?;
+            (!hash.is_empty()).then(|| parse_commit(hash, short_hash, message))
+        })
+        .collect()
+}
+
+pub fn parse_commit(hash: &str, short_hash: &str, message: &str) -> ChangelogCommit {
+    let (subject, _, footer) = conventions::split_message(message);
+    let references = extract_references(message);
+    let description = TRAILING_REFERENCES
+        .replace(&subject.description, "")
+        .trim()
+        .to_string();
+
+    ChangelogCommit {
+        hash: hash.to_string(),
+        short_hash: short_hash.to_string(),
+        commit_type: subject.commit_type,
+        scope: subject.scope,
+        breaking: subject.breaking || footer.is_some(),
+        description,
+        references,
+    }
+}
+
+/// PR/issue references in order of appearance, without duplicates
+pub fn extract_references(message: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    REFERENCE_PATTERN
+        .find_iter(message)
+        .map(|m| m.as_str().trim_end_matches(['.', ',', ')']).to_string())
+        .filter(|reference| seen.insert(reference.clone()))
+        .collect()
+}
+
+/// Section a commit belongs to; `None` for changes users don't see
+/// (docs, tests, tooling) unless they are breaking
+pub fn section_for(commit: &ChangelogCommit) -> Option<ChangelogSection> {
+    let internal = matches!(
+        commit.commit_type,
+        Some(
+            CommitType::Docs
+                | CommitType::Style
+                | CommitType::Test
+                | CommitType::Chore
+                | CommitType::CI
+        )
+    );
+    if internal && !commit.breaking {
+        return None;
+    }
+
+    let text = commit.description.to_lowercase();
+    let security = commit.scope.as_deref() == Some("security")
+        || text.contains("security")
+        || text.contains("vulnerab")
+        || text.contains("cve-");
+
+    let section = match commit.commit_type {
+        Some(CommitType::Fix) if security => ChangelogSection::Security,
+        _ if text.starts_with("deprecate") => ChangelogSection::Deprecated,
+        _ if text.starts_with("remove ") || text.starts_with("drop ") => ChangelogSection::Removed,
+        Some(CommitType::Feat) => ChangelogSection::Added,
+        Some(CommitType::Fix) => ChangelogSection::Fixed,
+        // Commits that don't follow the convention can't be classified, so
+        // they are listed rather than dropped
+        _ => ChangelogSection::Changed,
+    };
+    Some(section)
+}
+
+/// One entry per commit, grouped by section
+pub fn group_commits(
+    commits: &[ChangelogCommit],
+) -> BTreeMap<ChangelogSection, Vec<ChangelogEntry>> {
+    let mut sections: BTreeMap<ChangelogSection, Vec<ChangelogEntry>> = BTreeMap::new();
+    // git log lists newest first; changelogs read better oldest first
+    for commit in commits.iter().rev() {
+        if let Some(section) = section_for(commit) {
+            sections.entry(section).or_default().push(entry_for(commit));
+        }
+    }
+    sections
+}
+
+pub fn entry_for(commit: &ChangelogCommit) -> ChangelogEntry {
+    let mut text = capitalize(&commit.description);
+    if let Some(scope) = &commit.scope {
+        text = format!("**{}:** {}", scope, text);
+    }
+    if commit.breaking {
+        text = format!("**Breaking:** {}", text);
+    }
+
+    ChangelogEntry {
+        text,
+        references: commit.references.clone(),
+        commits: vec![commit.short_hash.clone()],
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn section_heading(version: &str, date: Option<&str>) -> String {
+    match date {
+        Some(date) => format!("## [{}] - {}", version, date),
+        None => format!("## [{}]", version),
+    }
+}
+
+pub fn render_markdown(changelog: &Changelog, include_references: bool) -> String {
+    let mut lines = vec![section_heading(
+        &changelog.version,
+        changelog.date.as_deref(),
+    )];
+
+    for (section, entries) in &changelog.sections {
+        if entries.is_empty() {
+            continue;
+        }
+        lines.push(String::new());
+        lines.push(format!("### {}", section.title()));
+        lines.push(String::new());
+        for entry in entries {
+            if include_references && !entry.references.is_empty() {
+                lines.push(format!(
+                    "- {} ({})",
+                    entry.text,
+                    entry.references.join(", ")
+                ));
+            } else {
+                lines.push(format!("- {}", entry.text));
+            }
+        }
+    }
+
+    if changelog.sections.values().all(Vec::is_empty) {
+        lines.push(String::new());
+        lines.push("No notable changes.".to_string());
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// `existing` with `fragment` placed above the newest release. A section with
+/// the same version is replaced, and so is `Unreleased` when a version is
+/// cut, since its changes are the ones being released.
+pub fn insert_fragment(existing: Option<&str>, version: &str, fragment: &str) -> String {
+    let existing = existing
+        .filter(|content| !content.trim().is_empty())
+        .unwrap_or(CHANGELOG_HEADER);
+    let lines: Vec<&str> = existing.lines().collect();
+
+    let is_release = |line: &str| line.starts_with("## ");
+    let replaced = |line: &str| {
+        line.starts_with(&format!("## [{}]", version)) || line.starts_with("## [Unreleased]")
+    };
+    let first_release = lines.iter().position(|line| is_release(line));
+    let same_version = lines.iter().position(|line| replaced(line));
+
+    let (before, after) = match (same_version, first_release) {
+        (Some(start), _) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| is_release(line))
+                .map(|offset| start + 1 + offset)
+                .unwrap_or(lines.len());
+            (&lines[..start], &lines[end..])
+        }
+        (None, Some(start)) => (&lines[..start], &lines[start..]),
+        (None, None) => (&lines[..], &lines[lines.len()..]),
+    };
+
+    let mut content = before.join("\n").trim_end().to_string();
+    content.push_str("\n\n");
+    content.push_str(fragment.trim_end());
+    content.push('\n');
+    if !after.is_empty() {
+        content.push('\n');
+        content.push_str(after.join("\n").trim_end());
+        content.push('\n');
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_record(hash: &str, message: &str) -> String {
+        format!(
+            "{}{}{}{}{}{}\n",
+            hash,
+            FIELD_SEPARATOR,
+            &hash[..7],
+            FIELD_SEPARATOR,
+            message,
+            RECORD_SEPARATOR
+        )
+    }
+
+    fn sample_log() -> String {
+        [
+            log_record("cccccccccc", "fix(security): escape shell arguments (#41)\n"),
+            log_record("bbbbbbbbbb", "docs: describe the changelog command\n"),
+            log_record(
+                "aaaaaaaaaa",
+                "feat(cli)!: add changelog command\n\nCloses #40, see GH-7.\n\nBREAKING CHANGE: `--since` replaces `--from`\n",
+            ),
+            log_record("9999999999", "Update README\n"),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn parses_conventional_commits_and_references() {
+        let commits = parse_log(&sample_log());
+        assert_eq!(commits.len(), 4);
+
+        assert_eq!(commits[0].commit_type, Some(CommitType::Fix));
+        assert_eq!(commits[0].description, "escape shell arguments");
+        assert_eq!(commits[0].references, vec!["#41"]);
+
+        let feature = &commits[2];
+        assert_eq!(feature.short_hash, "aaaaaaa");
+        assert_eq!(feature.scope.as_deref(), Some("cli"));
+        assert!(feature.breaking);
+        assert_eq!(feature.references, vec!["#40", "GH-7"]);
+
+        assert_eq!(commits[3].commit_type, None);
+        assert_eq!(commits[3].description, "Update README");
+    }
+
+    #[test]
+    fn groups_commits_into_sections() {
+        let sections = group_commits(&parse_log(&sample_log()));
+
+        assert_eq!(
+            sections.keys().copied().collect::<Vec<_>>(),
+            vec![
+                ChangelogSection::Added,
+                ChangelogSection::Changed,
+                ChangelogSection::Security
+            ]
+        );
+        assert_eq!(
+            sections[&ChangelogSection::Added][0].text,
+            "**Breaking:** **cli:** Add changelog command"
+        );
+        assert_eq!(
+            sections[&ChangelogSection::Changed][0].text,
+            "Update README"
+        );
+    }
+
+    #[test]
+    fn renders_keep_a_changelog_markdown() {
+        let commits = parse_log(&sample_log());
+        let mut changelog = Changelog {
+            version: "0.4.0".to_string(),
+            date: Some("2026-10-17".to_string()),
+            range: "v0.3.0..HEAD".to_string(),
+            commit_count: commits.len(),
+            sections: group_commits(&commits),
+            notes: Vec::new(),
+            markdown: String::new(),
+        };
+
+        let markdown = render_markdown(&changelog, true);
+        assert!(markdown.starts_with("## [0.4.0] - 2026-10-17\n\n### Added\n\n"));
+        assert!(markdown.contains("- **security:** Escape shell arguments (#41)\n"));
+        assert!(markdown.contains("- **Breaking:** **cli:** Add changelog command (#40, GH-7)\n"));
+        assert!(!render_markdown(&changelog, false).contains("#41"));
+
+        changelog.sections.clear();
+        assert!(render_markdown(&changelog, true).contains("No notable changes."));
+    }
+
+    #[test]
+    fn inserts_fragments_above_older_releases() {
+        let created = insert_fragment(None, "Unreleased", "## [Unreleased]\n\n- One\n");
+        assert!(created.starts_with("# Changelog\n"));
+        assert!(created.ends_with("## [Unreleased]\n\n- One\n"));
+
+        let existing =
+            "# Changelog\n\n## [Unreleased]\n\n- Old\n\n## [0.3.0] - 2026-01-01\n\n- Initial\n";
+        let updated = insert_fragment(Some(existing), "Unreleased", "## [Unreleased]\n\n- New\n");
+        assert_eq!(
+            updated,
+            "# Changelog\n\n## [Unreleased]\n\n- New\n\n## [0.3.0] - 2026-01-01\n\n- Initial\n"
+        );
+
+        let released = insert_fragment(
+            Some(&updated),
+            "0.4.0",
+            "## [0.4.0] - 2026-10-17\n\n- New\n",
+        );
+        assert_eq!(
+            released,
+            "# Changelog\n\n## [0.4.0] - 2026-10-17\n\n- New\n\n## [0.3.0] - 2026-01-01\n\n- Initial\n"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+pub mod ai_service;
+pub mod changelog_generator;
+/**
+ * Changelog Function Agent - module entry
+ *
+ * Provides changelog generation from commit history:
+ * - Keep-a-Changelog fragments grouped by conventional commit type
+ * - Optional insertion into the repository's CHANGELOG.md
+ */
+pub mod types;
+
+pub use ai_service::AIChangelogService;
+pub use changelog_generator::ChangelogGenerator;
+pub use types::*;
+
+use crate::infrastructure::ai::AIClientFactory;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Provides changelog generation functionality
+pub struct ChangelogFunctionAgent {
+    factory: Arc<AIClientFactory>,
+}
+
+impl ChangelogFunctionAgent {
+    pub fn new(factory: Arc<AIClientFactory>) -> Self {
+        Self { factory }
+    }
+
+    pub async fn generate_changelog(
+        &self,
+        repo_path: &Path,
+        options: ChangelogOptions,
+    ) -> AgentResult<Changelog> {
+        ChangelogGenerator::generate_changelog(repo_path, options, self.factory.clone()).await
+    }
+
+    /// Adds the fragment to the repository's CHANGELOG.md, creating it if needed
+    pub async fn write_changelog(
+        &self,
+        repo_path: &Path,
+        changelog: &Changelog,
+    ) -> AgentResult<PathBuf> {
+        ChangelogGenerator::write_changelog(repo_path, changelog).await
+    }
+}
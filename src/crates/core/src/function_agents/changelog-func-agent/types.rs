@@ -0,0 +1,150 @@
+/**
+ * Changelog Function Agent - type definitions
+ *
+ * Defines data structures for changelog generation
+ */
+pub use crate::function_agents::git_func_agent::types::{
+    AgentError, AgentErrorType, AgentResult, CommitType, Language,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogOptions {
+    /// Revision range such as `v0.3.0..HEAD`; a single revision means
+    /// `<rev>..HEAD`. `None` covers everything since the last tag.
+    #[serde(default)]
+    pub range: Option<String>,
+
+    /// Heading of the fragment; `None` writes an `Unreleased` section
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Keep PR/issue references such as `#123` or `ABC-42` on the entries
+    #[serde(default = "default_true")]
+    pub include_references: bool,
+
+    /// Let the AI merge related commits and rewrite them for users;
+    /// otherwise every commit becomes one entry as written
+    #[serde(default = "default_true")]
+    pub humanize: bool,
+
+    #[serde(default = "default_language")]
+    pub language: Language,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_language() -> Language {
+    Language::English
+}
+
+impl Default for ChangelogOptions {
+    fn default() -> Self {
+        Self {
+            range: None,
+            version: None,
+            include_references: true,
+            humanize: true,
+            language: Language::English,
+        }
+    }
+}
+
+/// Keep-a-Changelog section, in the order sections are written
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangelogSection {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+impl ChangelogSection {
+    pub const ALL: [ChangelogSection; 6] = [
+        ChangelogSection::Added,
+        ChangelogSection::Changed,
+        ChangelogSection::Deprecated,
+        ChangelogSection::Removed,
+        ChangelogSection::Fixed,
+        ChangelogSection::Security,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ChangelogSection::Added => "Added",
+            ChangelogSection::Changed => "Changed",
+            ChangelogSection::Deprecated => "Deprecated",
+            ChangelogSection::Removed => "Removed",
+            ChangelogSection::Fixed => "Fixed",
+            ChangelogSection::Security => "Security",
+        }
+    }
+
+    pub fn from_title(title: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|section| section.title().eq_ignore_ascii_case(title.trim()))
+    }
+}
+
+/// A commit of the range, parsed as a conventional commit where possible
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogCommit {
+    pub hash: String,
+
+    pub short_hash: String,
+
+    /// `None` for commits without a conventional `type:` prefix
+    pub commit_type: Option<CommitType>,
+
+    pub scope: Option<String>,
+
+    pub breaking: bool,
+
+    /// Subject without the type prefix and trailing references
+    pub description: String,
+
+    /// PR/issue references found anywhere in the message
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogEntry {
+    pub text: String,
+
+    pub references: Vec<String>,
+
+    /// Short hashes of the commits the entry covers
+    pub commits: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Changelog {
+    /// Version heading, `Unreleased` when none was given
+    pub version: String,
+
+    /// Release date, only set for versioned fragments
+    pub date: Option<String>,
+
+    /// Revision range the fragment covers, as passed to `git log`
+    pub range: String,
+
+    pub commit_count: usize,
+
+    pub sections: BTreeMap<ChangelogSection, Vec<ChangelogEntry>>,
+
+    /// Caveats about the history, e.g. no tags or a shallow clone
+    pub notes: Vec<String>,
+
+    /// The fragment in Keep-a-Changelog markdown
+    pub markdown: String,
+}
@@ -1,8 +1,9 @@
+use super::conventions::{self, CommitSubject};
 use super::types::{
     AICommitAnalysis, AgentError, AgentResult, CommitFormat, CommitMessageOptions, CommitType,
     Language, ProjectContext,
 };
-use crate::infrastructure::ai::AIClient;
+use crate::infrastructure::ai::{AIClient, StreamRequestOptions};
 use crate::util::types::Message;
 /**
  * AI service layer
@@ -39,11 +40,16 @@ impl AIAnalysisService {
         Ok(Self { ai_client })
     }
 
+    /// Asks the AI for a commit message. The returned title is the formatted
+    /// subject line; `scope` overrides the scope the AI picks. A subject that
+    /// is too long or uses a disallowed type is sent back to the AI up to
+    /// `options.max_reprompts` times before it is fixed up locally.
     pub async fn generate_commit_message_ai(
         &self,
         diff_content: &str,
         project_context: &ProjectContext,
         options: &CommitMessageOptions,
+        scope: Option<&str>,
     ) -> AgentResult<AICommitAnalysis> {
         if diff_content.is_empty() {
             return Err(AgentError::invalid_input("Code changes are empty"));
@@ -51,20 +57,81 @@ impl AIAnalysisService {
 
         let processed_diff = self.truncate_diff_if_needed(diff_content, 50000);
 
-        let prompt = self.build_commit_prompt(&processed_diff, project_context, options);
+        let prompt = self.build_commit_prompt(&processed_diff, project_context, options, scope);
+        let mut messages = vec![Message::user(prompt)];
+        let mut reprompts = 0;
+
+        loop {
+            let ai_response = self.call_ai(messages.clone()).await?;
+            let mut analysis = self.parse_commit_response(&ai_response)?;
+
+            let mut subject = conventions::parse_subject(&analysis.title);
+            subject.commit_type = Some(analysis.commit_type.clone());
+            subject.scope = scope.map(str::to_string).or(analysis.scope.clone());
+            subject.breaking = analysis.breaking_changes.is_some();
+            let formatted = conventions::format_subject(&options.format, &subject);
+
+            let Some(violation) = conventions::subject_violation(options, &subject, &formatted)
+            else {
+                analysis.title = formatted;
+                analysis.scope = subject.scope;
+                return Ok(analysis);
+            };
+
+            if reprompts >= options.max_reprompts {
+                warn!(
+                    "AI did not fix the commit subject after {} attempts, adjusting it: {}",
+                    reprompts, violation
+                );
+                return Ok(self.enforce_conventions(analysis, subject, options));
+            }
 
-        let ai_response = self.call_ai(&prompt).await?;
+            debug!("Re-prompting AI for commit subject: {}", violation);
+            reprompts += 1;
+            messages.push(Message::assistant(ai_response));
+            messages.push(Message::user(format!(
+                "{} Reply with the complete JSON again.",
+                violation
+            )));
+        }
+    }
 
-        self.parse_commit_response(&ai_response)
+    /// Falls back to an allowed type and truncates the subject
+    fn enforce_conventions(
+        &self,
+        mut analysis: AICommitAnalysis,
+        mut subject: CommitSubject,
+        options: &CommitMessageOptions,
+    ) -> AICommitAnalysis {
+        if !options.allows_type(&analysis.commit_type) {
+            analysis.commit_type = if options.allows_type(&CommitType::Chore) {
+                CommitType::Chore
+            } else {
+                options.allowed_types[0].clone()
+            };
+            subject.commit_type = Some(analysis.commit_type.clone());
+        }
+
+        analysis.title =
+            conventions::fit_subject(&options.format, &subject, options.max_title_length);
+        analysis.scope = subject.scope;
+        analysis
     }
 
-    async fn call_ai(&self, prompt: &str) -> AgentResult<String> {
-        debug!("Sending request to AI: prompt_length={}", prompt.len());
+    /// Non-streaming request whose reply is expected to carry a JSON object
+    pub(crate) async fn call_ai(&self, messages: Vec<Message>) -> AgentResult<String> {
+        debug!("Sending request to AI: messages={}", messages.len());
 
-        let messages = vec![Message::user(prompt.to_string())];
         let response = self
             .ai_client
-            .send_message(messages, None)
+            .send_message_with_options(
+                messages,
+                None,
+                StreamRequestOptions {
+                    streaming: Some(false),
+                    ..Default::default()
+                },
+            )
             .await
             .map_err(|e| {
                 error!("AI call failed: {}", e);
@@ -91,6 +158,7 @@ impl AIAnalysisService {
         diff_content: &str,
         project_context: &ProjectContext,
         options: &CommitMessageOptions,
+        scope: Option<&str>,
     ) -> String {
         let language_desc = match options.language {
             Language::Chinese => "Chinese",
@@ -104,11 +172,18 @@ impl AIAnalysisService {
             CommitFormat::Custom => "Custom Format",
         };
 
+        let scope_desc = match scope {
+            Some(scope) => format!("{} (already decided, the title must not repeat it)", scope),
+            None => "choose one if the change has a clear module".to_string(),
+        };
+
         COMMIT_MESSAGE_PROMPT
             .replace("{project_type}", &project_context.project_type)
             .replace("{tech_stack}", &project_context.tech_stack.join(", "))
             .replace("{format_desc}", format_desc)
             .replace("{language_desc}", language_desc)
+            .replace("{scope_desc}", &scope_desc)
+            .replace("{allowed_types}", &conventions::allowed_types_desc(options))
             .replace("{diff_content}", diff_content)
             .replace("{max_title_length}", &options.max_title_length.to_string())
     }
@@ -129,7 +204,9 @@ impl AIAnalysisService {
                 .ok_or_else(|| AgentError::analysis_error("Missing title field"))?
                 .to_string(),
             body: value["body"].as_str().map(|s| s.to_string()),
-            breaking_changes: value["breaking_changes"].as_str().map(|s| s.to_string()),
+            breaking_changes: value["breaking_changes"]
+                .as_str()
+                .and_then(conventions::normalize_breaking_footer),
             reasoning: value["reasoning"]
                 .as_str()
                 .unwrap_or("AI analysis")
@@ -157,18 +234,6 @@ impl AIAnalysisService {
     }
 
     fn parse_commit_type(&self, s: &str) -> AgentResult<CommitType> {
-        match s.to_lowercase().as_str() {
-            "feat" | "feature" => Ok(CommitType::Feat),
-            "fix" => Ok(CommitType::Fix),
-            "docs" | "doc" => Ok(CommitType::Docs),
-            "style" => Ok(CommitType::Style),
-            "refactor" => Ok(CommitType::Refactor),
-            "perf" | "performance" => Ok(CommitType::Perf),
-            "test" => Ok(CommitType::Test),
-            "chore" => Ok(CommitType::Chore),
-            "ci" => Ok(CommitType::CI),
-            "revert" => Ok(CommitType::Revert),
-            _ => Ok(CommitType::Chore),
-        }
+        Ok(s.parse().unwrap_or(CommitType::Chore))
     }
 }
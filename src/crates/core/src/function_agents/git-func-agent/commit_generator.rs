@@ -1,5 +1,6 @@
 use super::ai_service::AIAnalysisService;
 use super::context_analyzer::ContextAnalyzer;
+use super::conventions::{self, DiffFile};
 use super::types::*;
 use crate::infrastructure::ai::AIClientFactory;
 use crate::service::git::{GitDiffParams, GitService};
@@ -58,11 +59,18 @@ impl CommitGenerator {
             project_context.project_type, project_context.tech_stack
         );
 
+        let diff_files = conventions::parse_diff(&diff_content);
+        let scope = if options.infer_scope {
+            conventions::infer_scope(&diff_files)
+        } else {
+            None
+        };
+
         let ai_service =
             AIAnalysisService::new_with_agent_config(factory, "git-func-agent").await?;
 
         let ai_analysis = ai_service
-            .generate_commit_message_ai(&diff_content, &project_context, &options)
+            .generate_commit_message_ai(&diff_content, &project_context, &options, scope.as_deref())
             .await?;
 
         debug!(
@@ -70,18 +78,41 @@ impl CommitGenerator {
             ai_analysis.commit_type, ai_analysis.confidence
         );
 
-        let changes_summary = Self::build_changes_summary(&status, &changed_files);
+        let mut changes_summary = Self::build_changes_summary(&diff_files, &changed_files);
+        // Hunk-level staging leaves the rest of these files out of the commit
+        changes_summary.partially_staged_files = status
+            .staged
+            .iter()
+            .filter(|staged| status.unstaged.iter().any(|u| u.path == staged.path))
+            .map(|staged| staged.path.clone())
+            .collect();
 
-        let full_message = Self::assemble_full_message(
-            &ai_analysis.title,
-            &ai_analysis.body,
-            &ai_analysis.breaking_changes,
-        );
+        let body = if options.include_body {
+            Self::compose_body(ai_analysis.body.as_deref(), &diff_files)
+        } else {
+            None
+        };
+
+        let mut footer = ai_analysis.breaking_changes;
+        let mut title = ai_analysis.title;
+        if footer.is_none() && options.detect_breaking_changes {
+            footer = conventions::detect_breaking_change(&diff_files);
+            if footer.is_some() {
+                // The `!` marker has to fit within the subject length too
+                let mut subject = conventions::parse_subject(&title);
+                subject.breaking = true;
+                title =
+                    conventions::fit_subject(&options.format, &subject, options.max_title_length);
+            }
+        }
+
+        let full_message =
+            conventions::assemble_message(&title, body.as_deref(), footer.as_deref());
 
         Ok(CommitMessage {
-            title: ai_analysis.title,
-            body: ai_analysis.body,
-            footer: ai_analysis.breaking_changes,
+            title,
+            body,
+            footer,
             full_message,
             commit_type: ai_analysis.commit_type,
             scope: ai_analysis.scope,
@@ -106,32 +137,41 @@ impl CommitGenerator {
         Ok(diff)
     }
 
-    fn build_changes_summary(
-        status: &crate::service::git::GitStatus,
-        changed_files: &[String],
-    ) -> ChangesSummary {
-        let total_additions = status.staged.iter().map(|_| 10u32).sum::<u32>()
-            + status.unstaged.iter().map(|_| 10u32).sum::<u32>();
-        let total_deletions = status.staged.iter().map(|_| 5u32).sum::<u32>()
-            + status.unstaged.iter().map(|_| 5u32).sum::<u32>();
+    /// The AI's description followed by the changes grouped per directory
+    fn compose_body(ai_body: Option<&str>, diff_files: &[DiffFile]) -> Option<String> {
+        let parts: Vec<String> = ai_body
+            .map(str::trim)
+            .filter(|body| !body.is_empty())
+            .map(str::to_string)
+            .into_iter()
+            .chain(conventions::cluster_summary(diff_files))
+            .collect();
 
-        let file_changes: Vec<FileChange> = changed_files
-            .iter()
-            .map(|path| {
-                let file_type = super::utils::infer_file_type(path);
-                FileChange {
+        Some(parts.join("\n\n")).filter(|body| !body.is_empty())
+    }
+
+    fn build_changes_summary(diff_files: &[DiffFile], changed_files: &[String]) -> ChangesSummary {
+        let file_changes: Vec<FileChange> = if diff_files.is_empty() {
+            changed_files
+                .iter()
+                .map(|path| FileChange {
                     path: path.clone(),
-                    change_type: FileChangeType::Modified, // Simplified handling
-                    additions: 10,
-                    deletions: 5,
-                    file_type,
-                }
-            })
-            .collect();
+                    change_type: FileChangeType::Modified,
+                    additions: 0,
+                    deletions: 0,
+                    file_type: super::utils::infer_file_type(path),
+                })
+                .collect()
+        } else {
+            diff_files.iter().map(DiffFile::to_file_change).collect()
+        };
 
-        let affected_modules: Vec<String> = changed_files
+        let total_additions = file_changes.iter().map(|f| f.additions).sum();
+        let total_deletions = file_changes.iter().map(|f| f.deletions).sum();
+
+        let affected_modules: Vec<String> = file_changes
             .iter()
-            .filter_map(|path| super::utils::extract_module_name(path))
+            .filter_map(|change| super::utils::extract_module_name(&change.path))
             .collect::<std::collections::HashSet<_>>()
             .into_iter()
             .take(3)
@@ -142,34 +182,11 @@ impl CommitGenerator {
         ChangesSummary {
             total_additions,
             total_deletions,
-            files_changed: changed_files.len() as u32,
+            files_changed: file_changes.len() as u32,
             file_changes,
             affected_modules,
             change_patterns,
+            partially_staged_files: Vec::new(),
         }
     }
-
-    fn assemble_full_message(
-        title: &str,
-        body: &Option<String>,
-        footer: &Option<String>,
-    ) -> String {
-        let mut parts = vec![title.to_string()];
-
-        if let Some(body_text) = body {
-            if !body_text.is_empty() {
-                parts.push(String::new());
-                parts.push(body_text.clone());
-            }
-        }
-
-        if let Some(footer_text) = footer {
-            if !footer_text.is_empty() {
-                parts.push(String::new());
-                parts.push(footer_text.clone());
-            }
-        }
-
-        parts.join("\n")
-    }
 }
@@ -0,0 +1,633 @@
+/**
+ * Git Function Agent - commit conventions
+ *
+ * Diff heuristics (scope, file clusters, breaking changes) and the
+ * formatting/parsing of subjects for each CommitFormat
+ */
+use super::types::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Directories that only hold other modules; scope inference looks past them
+const CONTAINER_DIRS: &[&str] = &[
+    "src", "lib", "libs", "crates", "packages", "apps", "modules",
+];
+
+/// Clusters listed in a generated body before the rest is summarized
+const MAX_BODY_CLUSTERS: usize = 8;
+
+pub const BREAKING_CHANGE_PREFIX: &str = "BREAKING CHANGE:";
+
+/// One file of a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffFile {
+    pub path: String,
+    pub change_type: FileChangeType,
+    pub additions: u32,
+    pub deletions: u32,
+    pub added_lines: Vec<String>,
+    pub removed_lines: Vec<String>,
+}
+
+impl DiffFile {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            change_type: FileChangeType::Modified,
+            additions: 0,
+            deletions: 0,
+            added_lines: Vec::new(),
+            removed_lines: Vec::new(),
+        }
+    }
+
+    fn weight(&self) -> u32 {
+        (self.additions + self.deletions).max(1)
+    }
+
+    pub fn to_file_change(&self) -> FileChange {
+        FileChange {
+            path: self.path.clone(),
+            change_type: self.change_type.clone(),
+            additions: self.additions,
+            deletions: self.deletions,
+            file_type: super::utils::infer_file_type(&self.path),
+        }
+    }
+}
+
+/// Splits `git diff` output into per-file line statistics
+pub fn parse_diff(diff: &str) -> Vec<DiffFile> {
+    let mut files = Vec::new();
+    let mut current: Option<DiffFile> = None;
+    let mut in_hunk = false;
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.extend(current.take());
+            let path = rest
+                .split(" b/")
+                .last()
+                .unwrap_or(rest)
+                .trim_start_matches("a/")
+                .to_string();
+            current = Some(DiffFile::new(path));
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = current.as_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            in_hunk = true;
+        } else if !in_hunk {
+            if line.starts_with("new file mode") {
+                file.change_type = FileChangeType::Added;
+            } else if line.starts_with("deleted file mode") {
+                file.change_type = FileChangeType::Deleted;
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.change_type = FileChangeType::Renamed;
+                file.path = path.to_string();
+            }
+        } else if let Some(added) = line.strip_prefix('+') {
+            file.additions += 1;
+            file.added_lines.push(added.to_string());
+        } else if let Some(removed) = line.strip_prefix('-') {
+            file.deletions += 1;
+            file.removed_lines.push(removed.to_string());
+        }
+    }
+
+    files.extend(current);
+    files
+}
+
+/// Scope of a path: its first directory below any container directories,
+/// e.g. `src/crates/core/src/service/x.rs` -> `core`. Files that live in the
+/// repository root or directly in a container directory have none.
+fn path_scope(path: &str) -> Option<String> {
+    let mut dirs: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    dirs.pop();
+
+    dirs.into_iter()
+        .find(|dir| !CONTAINER_DIRS.contains(&dir.to_lowercase().as_str()))
+        .map(sanitize_scope)
+        .filter(|scope| !scope.is_empty())
+}
+
+fn sanitize_scope(dir: &str) -> String {
+    dir.trim_start_matches('.')
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// The scope owning more than half of the changed lines, if any
+pub fn infer_scope(files: &[DiffFile]) -> Option<String> {
+    let mut weights: HashMap<String, u32> = HashMap::new();
+    let mut total = 0;
+    for file in files {
+        total += file.weight();
+        if let Some(scope) = path_scope(&file.path) {
+            *weights.entry(scope).or_default() += file.weight();
+        }
+    }
+
+    weights
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .filter(|(_, weight)| weight * 2 > total)
+        .map(|(scope, _)| scope)
+}
+
+/// Body summarizing the changes grouped by directory, largest cluster first
+pub fn cluster_summary(files: &[DiffFile]) -> Option<String> {
+    let mut clusters: BTreeMap<String, Vec<&DiffFile>> = BTreeMap::new();
+    for file in files {
+        let dir = match file.path.rsplit_once('/') {
+            Some((dir, _)) => dir.to_string(),
+            None => ".".to_string(),
+        };
+        clusters.entry(dir).or_default().push(file);
+    }
+    if clusters.is_empty() {
+        return None;
+    }
+
+    let mut clusters: Vec<_> = clusters.into_iter().collect();
+    clusters.sort_by_key(|(_, files)| {
+        std::cmp::Reverse(files.iter().map(|f| f.additions + f.deletions).sum::<u32>())
+    });
+
+    let mut lines: Vec<String> = clusters
+        .iter()
+        .take(MAX_BODY_CLUSTERS)
+        .map(|(dir, files)| {
+            let names: Vec<&str> = files
+                .iter()
+                .map(|f| f.path.rsplit('/').next().unwrap_or(&f.path))
+                .collect();
+            let additions: u32 = files.iter().map(|f| f.additions).sum();
+            let deletions: u32 = files.iter().map(|f| f.deletions).sum();
+            format!(
+                "- {}: {} (+{} -{})",
+                dir,
+                names.join(", "),
+                additions,
+                deletions
+            )
+        })
+        .collect();
+    if clusters.len() > MAX_BODY_CLUSTERS {
+        lines.push(format!(
+            "- and {} more directories",
+            clusters.len() - MAX_BODY_CLUSTERS
+        ));
+    }
+
+    Some(lines.join("\n"))
+}
+
+/// Name declared by a public Rust or exported TypeScript/JavaScript item
+pub fn public_item_name(line: &str) -> Option<&str> {
+    const PREFIXES: &[&str] = &[
+        "pub async fn ",
+        "pub fn ",
+        "pub struct ",
+        "pub enum ",
+        "pub trait ",
+        "pub type ",
+        "pub const ",
+        "pub static ",
+        "pub mod ",
+        "export async function ",
+        "export function ",
+        "export default function ",
+        "export class ",
+        "export interface ",
+        "export type ",
+        "export const ",
+        "export enum ",
+    ];
+
+    let line = line.trim_start();
+    let rest = PREFIXES
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))?;
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| !name.is_empty())
+}
+
+/// Footer text when the diff removes public items that are not re-added
+/// anywhere else in the diff (moving an item between files is not breaking)
+pub fn detect_breaking_change(files: &[DiffFile]) -> Option<String> {
+    let code_files = files
+        .iter()
+        .filter(|file| !super::utils::is_test_file(&file.path));
+
+    let mut added = BTreeSet::new();
+    let mut removed = BTreeSet::new();
+    for file in code_files {
+        added.extend(file.added_lines.iter().filter_map(|l| public_item_name(l)));
+        removed.extend(
+            file.removed_lines
+                .iter()
+                .filter_map(|l| public_item_name(l)),
+        );
+    }
+
+    let gone: Vec<String> = removed
+        .difference(&added)
+        .map(|name| format!("`{}`", name))
+        .collect();
+    if gone.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "{} removed public API {}",
+        BREAKING_CHANGE_PREFIX,
+        gone.join(", ")
+    ))
+}
+
+/// Prefixes a footer with `BREAKING CHANGE:` unless it already has a token
+pub fn normalize_breaking_footer(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if text.starts_with("BREAKING CHANGE") || text.starts_with("BREAKING-CHANGE") {
+        Some(text.to_string())
+    } else {
+        Some(format!("{} {}", BREAKING_CHANGE_PREFIX, text))
+    }
+}
+
+/// The parts of a subject line
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitSubject {
+    pub commit_type: Option<CommitType>,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+fn uses_type_prefix(format: &CommitFormat) -> bool {
+    matches!(format, CommitFormat::Conventional | CommitFormat::Angular)
+}
+
+/// `type(scope)!: description` for conventional formats, the bare
+/// description for plain ones
+pub fn format_subject(format: &CommitFormat, subject: &CommitSubject) -> String {
+    let description = subject.description.trim();
+    let Some(commit_type) = subject
+        .commit_type
+        .as_ref()
+        .filter(|_| uses_type_prefix(format))
+    else {
+        return description.to_string();
+    };
+
+    let scope = subject
+        .scope
+        .as_deref()
+        .filter(|scope| !scope.is_empty())
+        .map(|scope| format!("({})", scope))
+        .unwrap_or_default();
+    let bang = if subject.breaking { "!" } else { "" };
+    format!("{}{}{}: {}", commit_type, scope, bang, description)
+}
+
+/// Inverse of `format_subject`. A `type(scope)!:` prefix is recognized in
+/// any format, since plain subjects written by hand or by the AI often have
+/// one; a prefix that is not a known commit type stays in the description.
+pub fn parse_subject(line: &str) -> CommitSubject {
+    let line = line.trim();
+    let plain = CommitSubject {
+        commit_type: None,
+        scope: None,
+        breaking: false,
+        description: line.to_string(),
+    };
+
+    let Some((prefix, description)) = line.split_once(':') else {
+        return plain;
+    };
+    let (prefix, breaking) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+    let (type_name, scope) = match prefix.split_once('(') {
+        Some((type_name, scope)) => match scope.strip_suffix(')') {
+            Some(scope) => (type_name, Some(scope.trim().to_string())),
+            None => return plain,
+        },
+        None => (prefix, None),
+    };
+    if type_name.contains(char::is_whitespace) {
+        return plain;
+    }
+    let Ok(commit_type) = type_name.parse::<CommitType>() else {
+        return plain;
+    };
+
+    CommitSubject {
+        commit_type: Some(commit_type),
+        scope: scope.filter(|scope| !scope.is_empty()),
+        breaking,
+        description: description.trim().to_string(),
+    }
+}
+
+/// Full message from its parts, separated by blank lines
+pub fn assemble_message(title: &str, body: Option<&str>, footer: Option<&str>) -> String {
+    let mut parts = vec![title.to_string()];
+    for part in [body, footer].into_iter().flatten() {
+        if !part.trim().is_empty() {
+            parts.push(part.trim().to_string());
+        }
+    }
+    parts.join("\n\n")
+}
+
+/// Splits a full message into subject, body and breaking-change footer
+pub fn split_message(message: &str) -> (CommitSubject, Option<String>, Option<String>) {
+    let message = message.trim();
+    let (title, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let mut paragraphs: Vec<&str> = rest
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    let footer = match paragraphs.last() {
+        Some(last)
+            if last.starts_with("BREAKING CHANGE") || last.starts_with("BREAKING-CHANGE") =>
+        {
+            paragraphs.pop().map(str::to_string)
+        }
+        _ => None,
+    };
+    let body = Some(paragraphs.join("\n\n")).filter(|body| !body.is_empty());
+
+    (parse_subject(title), body, footer)
+}
+
+/// Truncates a description so the formatted subject fits `max_length`
+/// characters, cutting at a word boundary when there is one
+pub fn fit_subject(format: &CommitFormat, subject: &CommitSubject, max_length: usize) -> String {
+    let formatted = format_subject(format, subject);
+    let overflow = formatted.chars().count().saturating_sub(max_length);
+    if overflow == 0 {
+        return formatted;
+    }
+
+    let keep = subject.description.chars().count().saturating_sub(overflow);
+    let mut description: String = subject.description.chars().take(keep).collect();
+    if let Some(space) = description.rfind(' ') {
+        if space > 0 {
+            description.truncate(space);
+        }
+    }
+    let shortened = CommitSubject {
+        description: description.trim_end().to_string(),
+        ..subject.clone()
+    };
+    format_subject(format, &shortened)
+}
+
+/// What the AI has to fix in a subject, if anything
+pub fn subject_violation(
+    options: &CommitMessageOptions,
+    subject: &CommitSubject,
+    formatted: &str,
+) -> Option<String> {
+    if let Some(commit_type) = &subject.commit_type {
+        if !options.allows_type(commit_type) {
+            return Some(format!(
+                "The type \"{}\" is not allowed. Use one of: {}.",
+                commit_type,
+                allowed_types_desc(options)
+            ));
+        }
+    }
+
+    let length = formatted.chars().count();
+    if length > options.max_title_length {
+        return Some(format!(
+            "The subject line \"{}\" is {} characters long, but at most {} are allowed. Shorten the title.",
+            formatted, length, options.max_title_length
+        ));
+    }
+
+    None
+}
+
+pub fn allowed_types_desc(options: &CommitMessageOptions) -> String {
+    if options.allowed_types.is_empty() {
+        return "feat, fix, docs, style, refactor, perf, test, chore, ci, revert".to_string();
+    }
+    options
+        .allowed_types
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEATURE_DIFF: &str = "\
+diff --git a/src/crates/core/src/service/git/mod.rs b/src/crates/core/src/service/git/mod.rs
+index 1111111..2222222 100644
+--- a/src/crates/core/src/service/git/mod.rs
++++ b/src/crates/core/src/service/git/mod.rs
+@@ -1,3 +1,6 @@
+ pub mod git_service;
++pub mod blame;
++
++pub use blame::BlameLine;
+diff --git a/src/crates/core/src/service/git/blame.rs b/src/crates/core/src/service/git/blame.rs
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/src/crates/core/src/service/git/blame.rs
+@@ -0,0 +1,4 @@
++pub struct BlameLine {
++    pub line: u32,
++    pub author: String,
++}
+diff --git a/README.md b/README.md
+index 4444444..5555555 100644
+--- a/README.md
++++ b/README.md
+@@ -1,1 +1,2 @@
+ # BitFun
++Now with blame.
+";
+
+    const BREAKING_DIFF: &str = "\
+diff --git a/src/web-ui/src/api/client.ts b/src/web-ui/src/api/client.ts
+index 1111111..2222222 100644
+--- a/src/web-ui/src/api/client.ts
++++ b/src/web-ui/src/api/client.ts
+@@ -1,6 +1,3 @@
+-export function fetchSessions() {
+-  return request('sessions');
+-}
+ export function fetchSession(id: string) {
+-  return request('session/' + id);
++  return request(`session/${id}`);
+ }
+diff --git a/src/web-ui/src/api/helpers.ts b/src/web-ui/src/api/helpers.ts
+deleted file mode 100644
+index 3333333..0000000
+--- a/src/web-ui/src/api/helpers.ts
++++ /dev/null
+@@ -1,2 +0,0 @@
+-export const retry = 3;
+--- a comment that looks like a header
+diff --git a/src/web-ui/src/api/moved.ts b/src/web-ui/src/api/moved.ts
+new file mode 100644
+--- /dev/null
++++ b/src/web-ui/src/api/moved.ts
+@@ -0,0 +1 @@
++export const retry = 3;
+";
+
+    #[test]
+    fn parses_diff_statistics() {
+        let files = parse_diff(BREAKING_DIFF);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/web-ui/src/api/client.ts");
+        assert_eq!((files[0].additions, files[0].deletions), (1, 4));
+        assert_eq!(files[1].change_type, FileChangeType::Deleted);
+        assert_eq!(files[1].deletions, 2);
+        assert_eq!(files[2].change_type, FileChangeType::Added);
+    }
+
+    #[test]
+    fn infers_scope_from_the_dominant_directory() {
+        assert_eq!(
+            infer_scope(&parse_diff(FEATURE_DIFF)),
+            Some("core".to_string())
+        );
+        assert_eq!(
+            infer_scope(&parse_diff(BREAKING_DIFF)),
+            Some("web-ui".to_string())
+        );
+
+        // A change split evenly between two modules has no dominant scope
+        let files = parse_diff(&format!("{}{}", FEATURE_DIFF, BREAKING_DIFF));
+        assert_eq!(files.len(), 6);
+        assert_eq!(infer_scope(&files), None);
+
+        // Root-level files never have a scope
+        let readme: Vec<_> = parse_diff(FEATURE_DIFF)
+            .into_iter()
+            .filter(|file| file.path == "README.md")
+            .collect();
+        assert_eq!(infer_scope(&readme), None);
+    }
+
+    #[test]
+    fn detects_removed_public_api() {
+        let footer = detect_breaking_change(&parse_diff(BREAKING_DIFF)).unwrap();
+        // `retry` moved to another file, so only `fetchSessions` is gone
+        assert_eq!(
+            footer,
+            "BREAKING CHANGE: removed public API `fetchSessions`"
+        );
+        assert_eq!(detect_breaking_change(&parse_diff(FEATURE_DIFF)), None);
+    }
+
+    #[test]
+    fn summarizes_changes_per_cluster() {
+        let body = cluster_summary(&parse_diff(FEATURE_DIFF)).unwrap();
+        assert_eq!(
+            body,
+            "- src/crates/core/src/service/git: mod.rs, blame.rs (+7 -0)\n- .: README.md (+1 -0)"
+        );
+    }
+
+    #[test]
+    fn subjects_round_trip_through_each_format() {
+        let subject = CommitSubject {
+            commit_type: Some(CommitType::Feat),
+            scope: Some("core".to_string()),
+            breaking: true,
+            description: "add git blame support".to_string(),
+        };
+
+        let conventional = format_subject(&CommitFormat::Conventional, &subject);
+        assert_eq!(conventional, "feat(core)!: add git blame support");
+        assert_eq!(parse_subject(&conventional), subject);
+
+        let plain = format_subject(&CommitFormat::Simple, &subject);
+        assert_eq!(plain, "add git blame support");
+        let parsed = parse_subject(&plain);
+        assert_eq!(parsed.description, subject.description);
+        assert_eq!(format_subject(&CommitFormat::Simple, &parsed), plain);
+
+        // Unknown prefixes are part of the description
+        assert_eq!(
+            parse_subject("Note: fix later").description,
+            "Note: fix later"
+        );
+        assert_eq!(
+            parse_subject("fix: handle empty diff").commit_type,
+            Some(CommitType::Fix)
+        );
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let title = "fix(core): handle empty diff";
+        let body = "Return an error instead of panicking.\n\n- src/core: a.rs (+1 -1)";
+        let footer = "BREAKING CHANGE: removed public API `diff`";
+        let message = assemble_message(title, Some(body), Some(footer));
+
+        let (subject, parsed_body, parsed_footer) = split_message(&message);
+        assert_eq!(format_subject(&CommitFormat::Conventional, &subject), title);
+        assert_eq!(parsed_body.as_deref(), Some(body));
+        assert_eq!(parsed_footer.as_deref(), Some(footer));
+    }
+
+    #[test]
+    fn enforces_subject_length_and_allowed_types() {
+        let options = CommitMessageOptions {
+            max_title_length: 30,
+            allowed_types: vec![CommitType::Feat, CommitType::Fix],
+            ..Default::default()
+        };
+        let subject = parse_subject("feat(core): add support for blaming individual lines");
+        let formatted = format_subject(&CommitFormat::Conventional, &subject);
+        assert!(subject_violation(&options, &subject, &formatted)
+            .unwrap()
+            .contains("at most 30"));
+
+        let fitted = fit_subject(&CommitFormat::Conventional, &subject, 30);
+        assert_eq!(fitted, "feat(core): add support for");
+        assert_eq!(
+            subject_violation(&options, &parse_subject(&fitted), &fitted),
+            None
+        );
+
+        let chore = parse_subject("chore: bump deps");
+        assert!(subject_violation(&options, &chore, "chore: bump deps")
+            .unwrap()
+            .contains("feat, fix"));
+    }
+}
@@ -1,6 +1,7 @@
 pub mod ai_service;
 pub mod commit_generator;
 pub mod context_analyzer;
+pub mod conventions;
 /**
  * Git Function Agent - module entry
  *
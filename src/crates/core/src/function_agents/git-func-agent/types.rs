@@ -23,6 +23,23 @@ pub struct CommitMessageOptions {
 
     #[serde(default = "default_language")]
     pub language: Language,
+
+    /// Derive the scope from the dominant top-level directory of the staged diff
+    #[serde(default = "default_true")]
+    pub infer_scope: bool,
+
+    /// Commit types the team allows; empty allows every type
+    #[serde(default)]
+    pub allowed_types: Vec<CommitType>,
+
+    /// Add a `BREAKING CHANGE:` footer when the diff removes public API
+    #[serde(default = "default_true")]
+    pub detect_breaking_changes: bool,
+
+    /// How many times the AI is asked to fix a subject that is too long or
+    /// uses a type outside `allowed_types`
+    #[serde(default = "default_max_reprompts")]
+    pub max_reprompts: u32,
 }
 
 fn default_commit_format() -> CommitFormat {
@@ -37,6 +54,10 @@ fn default_max_length() -> usize {
     72
 }
 
+fn default_max_reprompts() -> u32 {
+    2
+}
+
 fn default_language() -> Language {
     Language::Chinese
 }
@@ -49,10 +70,20 @@ impl Default for CommitMessageOptions {
             max_title_length: 72,
             include_body: true,
             language: Language::Chinese,
+            infer_scope: true,
+            allowed_types: Vec::new(),
+            detect_breaking_changes: true,
+            max_reprompts: 2,
         }
     }
 }
 
+impl CommitMessageOptions {
+    pub fn allows_type(&self, commit_type: &CommitType) -> bool {
+        self.allowed_types.is_empty() || self.allowed_types.contains(commit_type)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CommitFormat {
     /// Conventional Commits spec
@@ -136,6 +167,26 @@ impl fmt::Display for CommitType {
     }
 }
 
+impl std::str::FromStr for CommitType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "feat" | "feature" => Ok(CommitType::Feat),
+            "fix" => Ok(CommitType::Fix),
+            "docs" | "doc" => Ok(CommitType::Docs),
+            "style" => Ok(CommitType::Style),
+            "refactor" => Ok(CommitType::Refactor),
+            "perf" | "performance" => Ok(CommitType::Perf),
+            "test" | "tests" => Ok(CommitType::Test),
+            "chore" | "build" => Ok(CommitType::Chore),
+            "ci" => Ok(CommitType::CI),
+            "revert" => Ok(CommitType::Revert),
+            other => Err(format!("Unknown commit type: {}", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangesSummary {
@@ -150,6 +201,11 @@ pub struct ChangesSummary {
     pub affected_modules: Vec<String>,
 
     pub change_patterns: Vec<ChangePattern>,
+
+    /// Staged files that also have unstaged hunks; the message only covers
+    /// the staged part of them
+    #[serde(default)]
+    pub partially_staged_files: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -4,16 +4,26 @@
  * Provides various function agents for automating specific tasks
  */
 
+#[path = "changelog-func-agent/mod.rs"]
+pub mod changelog_func_agent;
+
 #[path = "git-func-agent/mod.rs"]
 pub mod git_func_agent;
 
+#[path = "pr-description-func-agent/mod.rs"]
+pub mod pr_description_func_agent;
+
 #[path = "startchat-func-agent/mod.rs"]
 pub mod startchat_func_agent;
 
+pub use changelog_func_agent::ChangelogFunctionAgent;
 pub use git_func_agent::GitFunctionAgent;
+pub use pr_description_func_agent::PrDescriptionFunctionAgent;
 pub use startchat_func_agent::StartchatFunctionAgent;
 
+pub use changelog_func_agent::{Changelog, ChangelogOptions, ChangelogSection};
 pub use git_func_agent::{CommitFormat, CommitMessage, CommitMessageOptions, CommitType};
+pub use pr_description_func_agent::{PrDescription, PrDescriptionOptions};
 
 pub use startchat_func_agent::{
     CurrentWorkState, GitWorkState, GreetingMessage, PredictedAction, QuickAction,
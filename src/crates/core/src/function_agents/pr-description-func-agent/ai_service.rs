@@ -0,0 +1,104 @@
+use super::types::*;
+use crate::function_agents::git_func_agent::AIAnalysisService;
+use crate::infrastructure::ai::AIClientFactory;
+use crate::util::types::Message;
+/**
+ * AI service layer
+ *
+ * Generates PR descriptions and condenses long commit lists through the
+ * non-streaming JSON request shared with the commit message agent
+ */
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Prompt template constants (embedded at compile time)
+const PR_DESCRIPTION_PROMPT: &str = include_str!("prompts/pr_description.md");
+const COMMIT_BATCH_PROMPT: &str = include_str!("prompts/commit_batch.md");
+
+/// Items a batch of commits is condensed to
+const MAX_BATCH_ITEMS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+struct BatchSummary {
+    items: Vec<String>,
+}
+
+pub struct AIPrDescriptionService {
+    analysis: AIAnalysisService,
+}
+
+impl AIPrDescriptionService {
+    pub async fn new_with_agent_config(
+        factory: Arc<AIClientFactory>,
+        agent_name: &str,
+    ) -> AgentResult<Self> {
+        Ok(Self {
+            analysis: AIAnalysisService::new_with_agent_config(factory, agent_name).await?,
+        })
+    }
+
+    /// Condenses a batch of commits to a few summary items
+    pub async fn summarize_commits(
+        &self,
+        commits: &[PrCommit],
+        language: &Language,
+    ) -> AgentResult<Vec<String>> {
+        let commit_lines: Vec<String> = commits
+            .iter()
+            .map(|c| format!("- {} {}", c.short_hash, c.subject))
+            .collect();
+        let prompt = COMMIT_BATCH_PROMPT
+            .replace("{language_desc}", language_desc(language))
+            .replace("{max_items}", &MAX_BATCH_ITEMS.to_string())
+            .replace("{commits}", &commit_lines.join("\n"));
+
+        let summary: BatchSummary = self.request_json(prompt).await?;
+        Ok(summary.items.into_iter().take(MAX_BATCH_ITEMS).collect())
+    }
+
+    pub async fn describe_changes(
+        &self,
+        commit_lines: &[String],
+        file_stats: &str,
+        hunks: &str,
+        options: &PrDescriptionOptions,
+    ) -> AgentResult<AIPrDescription> {
+        let commits: Vec<String> = commit_lines
+            .iter()
+            .map(|line| format!("- {}", line))
+            .collect();
+        let prompt = PR_DESCRIPTION_PROMPT
+            .replace("{language_desc}", language_desc(&options.language))
+            .replace("{max_title_length}", &options.max_title_length.to_string())
+            .replace("{commits}", &commits.join("\n"))
+            .replace("{file_stats}", file_stats)
+            .replace("{hunks}", hunks);
+
+        let description: AIPrDescription = self.request_json(prompt).await?;
+        if description.title.trim().is_empty() {
+            return Err(AgentError::analysis_error("Missing title field"));
+        }
+        Ok(description)
+    }
+
+    async fn request_json<T: DeserializeOwned>(&self, prompt: String) -> AgentResult<T> {
+        debug!(
+            "Sending PR description request: prompt_length={}",
+            prompt.len()
+        );
+        let response = self.analysis.call_ai(vec![Message::user(prompt)]).await?;
+        let json = crate::util::extract_json_from_ai_response(&response)
+            .ok_or_else(|| AgentError::analysis_error("Cannot extract JSON from response"))?;
+        serde_json::from_str(&json)
+            .map_err(|e| AgentError::analysis_error(format!("Failed to parse AI response: {}", e)))
+    }
+}
+
+fn language_desc(language: &Language) -> &'static str {
+    match language {
+        Language::Chinese => "Chinese",
+        Language::English => "English",
+    }
+}
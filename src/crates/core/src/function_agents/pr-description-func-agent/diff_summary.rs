@@ -0,0 +1,230 @@
+/**
+ * PR Description Function Agent - diff summary
+ *
+ * Reduces a branch diff to per-file statistics plus the hunks most likely
+ * to matter for a reviewer, within a size budget
+ */
+use crate::function_agents::git_func_agent::{conventions, utils};
+
+/// Files whose content says nothing about the change
+const GENERATED_FILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "poetry.lock",
+    "go.sum",
+];
+
+/// Hunks longer than this are cut when they are included
+const MAX_HUNK_LINES: usize = 80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffHunk {
+    pub path: String,
+    /// `@@` header followed by the hunk's lines
+    pub text: String,
+    pub additions: u32,
+    pub deletions: u32,
+    /// Whether a changed line declares a public item
+    pub touches_public_api: bool,
+}
+
+impl DiffHunk {
+    /// How much a reviewer needs to see this hunk; `None` for generated files
+    pub fn score(&self) -> Option<u32> {
+        if is_generated(&self.path) {
+            return None;
+        }
+
+        let mut score = (self.additions + self.deletions).min(40);
+        if self.touches_public_api {
+            score += 30;
+        }
+        if utils::is_test_file(&self.path) || utils::is_doc_file(&self.path) {
+            score /= 2;
+        }
+        Some(score)
+    }
+}
+
+fn is_generated(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    GENERATED_FILES.contains(&name) || name.ends_with(".min.js") || name.ends_with(".snap")
+}
+
+pub fn split_hunks(diff: &str) -> Vec<DiffHunk> {
+    let mut hunks = Vec::new();
+    let mut path = String::new();
+    let mut current: Option<DiffHunk> = None;
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            hunks.extend(current.take());
+            path = rest.split(" b/").last().unwrap_or(rest).to_string();
+            continue;
+        }
+        if line.starts_with("@@") {
+            hunks.extend(current.take());
+            current = Some(DiffHunk {
+                path: path.clone(),
+                text: line.to_string(),
+                additions: 0,
+                deletions: 0,
+                touches_public_api: false,
+            });
+            continue;
+        }
+        let Some(hunk) = current.as_mut() else {
+            continue;
+        };
+
+        let changed = if let Some(added) = line.strip_prefix('+') {
+            hunk.additions += 1;
+            Some(added)
+        } else if let Some(removed) = line.strip_prefix('-') {
+            hunk.deletions += 1;
+            Some(removed)
+        } else {
+            None
+        };
+        if changed.is_some_and(|l| conventions::public_item_name(l).is_some()) {
+            hunk.touches_public_api = true;
+        }
+        hunk.text.push('\n');
+        hunk.text.push_str(line);
+    }
+
+    hunks.extend(current);
+    hunks
+}
+
+/// The highest-scoring hunks that fit in `budget` characters, in diff order
+pub fn select_key_hunks(hunks: &[DiffHunk], budget: usize) -> Vec<DiffHunk> {
+    let mut ranked: Vec<(usize, u32)> = hunks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, hunk)| Some((index, hunk.score()?)))
+        .collect();
+    ranked.sort_by_key(|&(index, score)| (std::cmp::Reverse(score), index));
+
+    let mut used = 0;
+    let mut selected: Vec<(usize, DiffHunk)> = Vec::new();
+    for (index, _) in ranked {
+        let hunk = truncate_hunk(&hunks[index]);
+        let size = hunk.text.len() + hunk.path.len() + 8;
+        if used + size > budget {
+            continue;
+        }
+        used += size;
+        selected.push((index, hunk));
+    }
+
+    selected.sort_by_key(|(index, _)| *index);
+    selected.into_iter().map(|(_, hunk)| hunk).collect()
+}
+
+fn truncate_hunk(hunk: &DiffHunk) -> DiffHunk {
+    let lines: Vec<&str> = hunk.text.lines().collect();
+    if lines.len() <= MAX_HUNK_LINES {
+        return hunk.clone();
+    }
+    let mut text = lines[..MAX_HUNK_LINES].join("\n");
+    text.push_str(&format!(
+        "\n... {} more lines",
+        lines.len() - MAX_HUNK_LINES
+    ));
+    DiffHunk {
+        text,
+        ..hunk.clone()
+    }
+}
+
+/// Hunks grouped under their file, as sent to the AI
+pub fn render_hunks(hunks: &[DiffHunk]) -> String {
+    let mut out = String::new();
+    let mut last_path: Option<&str> = None;
+    for hunk in hunks {
+        if last_path != Some(hunk.path.as_str()) {
+            out.push_str(&format!("--- {}\n", hunk.path));
+            last_path = Some(&hunk.path);
+        }
+        out.push_str(&hunk.text);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRANCH_DIFF: &str = "\
+diff --git a/src/api/session.rs b/src/api/session.rs
+--- a/src/api/session.rs
++++ b/src/api/session.rs
+@@ -10,3 +10,4 @@ impl Session {
+-    pub fn title(&self) -> &str {
++    pub fn title(&self) -> Option<&str> {
++        // titles are optional now
+@@ -40,2 +41,2 @@ impl Session {
+-    let x = 1;
++    let x = 2;
+diff --git a/Cargo.lock b/Cargo.lock
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1,2 +1,40 @@
++name = \"dep\"
++version = \"1.0.0\"
+diff --git a/tests/session_test.rs b/tests/session_test.rs
+--- a/tests/session_test.rs
++++ b/tests/session_test.rs
+@@ -1,1 +1,2 @@
++    assert!(session.title().is_none());
+";
+
+    #[test]
+    fn splits_hunks_per_file() {
+        let hunks = split_hunks(BRANCH_DIFF);
+        assert_eq!(hunks.len(), 4);
+        assert_eq!(hunks[0].path, "src/api/session.rs");
+        assert!(hunks[0].touches_public_api);
+        assert_eq!((hunks[0].additions, hunks[0].deletions), (2, 1));
+        assert!(!hunks[1].touches_public_api);
+        assert_eq!(hunks[2].path, "Cargo.lock");
+    }
+
+    #[test]
+    fn selects_public_api_hunks_first_and_skips_lockfiles() {
+        let hunks = split_hunks(BRANCH_DIFF);
+
+        let all = select_key_hunks(&hunks, 10_000);
+        let paths: Vec<&str> = all.iter().map(|h| h.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "src/api/session.rs",
+                "src/api/session.rs",
+                "tests/session_test.rs"
+            ]
+        );
+
+        // Only room for one hunk: the public signature change wins
+        let budget = hunks[0].text.len() + hunks[0].path.len() + 8;
+        let key = select_key_hunks(&hunks, budget);
+        assert_eq!(key.len(), 1);
+        assert!(key[0].text.contains("Option<&str>"));
+
+        let rendered = render_hunks(&key);
+        assert!(rendered.starts_with("--- src/api/session.rs\n@@ -10,3"));
+    }
+
+    #[test]
+    fn cuts_long_hunks() {
+        let body: String = (0..200).map(|i| format!("\n+line {}", i)).collect();
+        let diff = format!("diff --git a/a.rs b/a.rs\n@@ -0,0 +1,200 @@{}", body);
+        let hunks = select_key_hunks(&split_hunks(&diff), 100_000);
+        assert_eq!(hunks[0].text.lines().count(), MAX_HUNK_LINES + 1);
+        assert!(hunks[0].text.ends_with("... 121 more lines"));
+    }
+}
@@ -0,0 +1,47 @@
+pub mod ai_service;
+pub mod diff_summary;
+pub mod pr_generator;
+/**
+ * PR Description Function Agent - module entry
+ *
+ * Provides pull request description generation:
+ * - Title and structured description of the current branch vs its base
+ * - Markdown compatible with PULL_REQUEST_TEMPLATE files
+ */
+pub mod types;
+
+pub use ai_service::AIPrDescriptionService;
+pub use pr_generator::PrGenerator;
+pub use types::*;
+
+use crate::infrastructure::ai::AIClientFactory;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Provides PR description generation functionality
+pub struct PrDescriptionFunctionAgent {
+    factory: Arc<AIClientFactory>,
+}
+
+impl PrDescriptionFunctionAgent {
+    pub fn new(factory: Arc<AIClientFactory>) -> Self {
+        Self { factory }
+    }
+
+    pub async fn generate_description(
+        &self,
+        repo_path: &Path,
+        options: PrDescriptionOptions,
+    ) -> AgentResult<PrDescription> {
+        PrGenerator::generate_description(repo_path, options, self.factory.clone()).await
+    }
+
+    /// Writes the description's markdown to `path`
+    pub async fn write_markdown(
+        &self,
+        path: &Path,
+        description: &PrDescription,
+    ) -> AgentResult<()> {
+        PrGenerator::write_markdown(path, description).await
+    }
+}
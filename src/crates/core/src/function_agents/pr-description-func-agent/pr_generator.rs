@@ -0,0 +1,304 @@
+use super::ai_service::AIPrDescriptionService;
+use super::diff_summary;
+use super::types::*;
+use crate::function_agents::git_func_agent::conventions;
+use crate::infrastructure::ai::AIClientFactory;
+use crate::service::git::execute_git_command;
+/**
+ * PR Description Function Agent - description generator
+ *
+ * Compares the current branch with its base and turns the commits and a
+ * size-capped diff summary into a PR title and description
+ */
+use log::{debug, info};
+use std::path::Path;
+use std::sync::Arc;
+
+pub const DEFAULT_TEMPLATE: &str = "## Summary\n\n{summary}\n\n## Changes\n\n{changes}\n\n## Test notes\n\n{test_notes}\n\n## Risk and rollback\n\n**Risk:** {risk}\n\n**Rollback:** {rollback}\n";
+
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Base candidates tried in order when none is configured
+const DEFAULT_BASES: &[&str] = &["main", "master", "origin/main", "origin/master"];
+
+pub struct PrGenerator;
+
+impl PrGenerator {
+    pub async fn generate_description(
+        repo_path: &Path,
+        options: PrDescriptionOptions,
+        factory: Arc<AIClientFactory>,
+    ) -> AgentResult<PrDescription> {
+        let repo = repo_path.to_string_lossy().to_string();
+        let head = git(&repo, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+        let base = match options.base.clone() {
+            Some(base) => base,
+            None => Self::detect_base(&repo).await?,
+        };
+        info!("Generating PR description: head={}, base={}", head, base);
+
+        let merge_base = git(&repo, &["merge-base", &base, "HEAD"])
+            .await
+            .map_err(|e| {
+                AgentError::invalid_input(format!(
+                    "{} has no common history with {}: {}",
+                    head, base, e
+                ))
+            })?;
+        let range = format!("{}..HEAD", merge_base);
+
+        let log_format = format!("--format=%h{}%s", FIELD_SEPARATOR);
+        let log = git(
+            &repo,
+            &["log", "--no-merges", "--reverse", &log_format, &range],
+        )
+        .await?;
+        let commits = parse_commits(&log);
+        let diff = git(&repo, &["diff", &merge_base, "HEAD"]).await?;
+        if commits.is_empty() && diff.trim().is_empty() {
+            return Err(AgentError::invalid_input(format!(
+                "{} has no changes compared to {}",
+                head, base
+            )));
+        }
+
+        let file_changes: Vec<FileChange> = conventions::parse_diff(&diff)
+            .iter()
+            .map(|file| file.to_file_change())
+            .collect();
+        let hunks = diff_summary::split_hunks(&diff);
+        let key_hunks = diff_summary::select_key_hunks(&hunks, options.max_diff_chars);
+        debug!(
+            "Branch summary: commits={}, files={}, hunks={}/{}",
+            commits.len(),
+            file_changes.len(),
+            key_hunks.len(),
+            hunks.len()
+        );
+
+        let ai_service =
+            AIPrDescriptionService::new_with_agent_config(factory, "pr-description-func-agent")
+                .await?;
+
+        // Long branches are condensed batch by batch so the final prompt
+        // stays small whatever the number of commits
+        let commit_lines: Vec<String> = if commits.len() > options.commit_batch_size {
+            let mut summaries = Vec::new();
+            for batch in commits.chunks(options.commit_batch_size.max(1)) {
+                summaries.extend(
+                    ai_service
+                        .summarize_commits(batch, &options.language)
+                        .await?,
+                );
+            }
+            summaries
+        } else {
+            commits
+                .iter()
+                .map(|c| format!("{} {}", c.short_hash, c.subject))
+                .collect()
+        };
+
+        let generated = ai_service
+            .describe_changes(
+                &commit_lines,
+                &file_stats(&file_changes),
+                &diff_summary::render_hunks(&key_hunks),
+                &options,
+            )
+            .await?;
+
+        let title = fit_title(&generated.title, options.max_title_length);
+        let mut description = PrDescription {
+            title,
+            summary: generated.summary,
+            changes: generated.changes,
+            test_notes: generated.test_notes,
+            risk: generated.risk,
+            rollback: generated.rollback,
+            base,
+            head,
+            commits,
+            file_changes,
+            markdown: String::new(),
+        };
+        description.markdown = render_template(
+            options.template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+            &description,
+        );
+
+        Ok(description)
+    }
+
+    async fn detect_base(repo: &str) -> AgentResult<String> {
+        if let Ok(remote_head) = git(
+            repo,
+            &["symbolic-ref", "--quiet", "refs/remotes/origin/HEAD"],
+        )
+        .await
+        {
+            if let Some(base) = remote_head.strip_prefix("refs/remotes/") {
+                return Ok(base.to_string());
+            }
+        }
+
+        for candidate in DEFAULT_BASES {
+            if git(repo, &["rev-parse", "--verify", "--quiet", candidate])
+                .await
+                .is_ok()
+            {
+                return Ok(candidate.to_string());
+            }
+        }
+
+        Err(AgentError::invalid_input(
+            "Cannot determine the base branch, please specify one",
+        ))
+    }
+
+    /// Writes the rendered description, e.g. to a file a PR tool picks up
+    pub async fn write_markdown(path: &Path, description: &PrDescription) -> AgentResult<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                AgentError::internal_error(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        tokio::fs::write(path, &description.markdown)
+            .await
+            .map_err(|e| {
+                AgentError::internal_error(format!("Failed to write {}: {}", path.display(), e))
+            })
+    }
+}
+
+async fn git(repo: &str, args: &[&str]) -> AgentResult<String> {
+    execute_git_command(repo, args)
+        .await
+        .map(|out| out.trim().to_string())
+        .map_err(|e| AgentError::git_error(format!("git {} failed: {}", args.join(" "), e)))
+}
+
+pub fn parse_commits(log: &str) -> Vec<PrCommit> {
+    log.lines()
+        .filter_map(|line| {
+            let (hash, subject) = line.split_once(FIELD_SEPARATOR)?;
+            Some(PrCommit {
+                short_hash: hash.trim().to_string(),
+                subject: subject.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// One line per file, largest change first
+pub fn file_stats(file_changes: &[FileChange]) -> String {
+    let mut files: Vec<&FileChange> = file_changes.iter().collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.additions + f.deletions));
+    files
+        .iter()
+        .map(|f| {
+            format!(
+                "{} ({:?}, +{} -{})",
+                f.path, f.change_type, f.additions, f.deletions
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Trims a title to `max_length` characters at a word boundary
+pub fn fit_title(title: &str, max_length: usize) -> String {
+    let title = title.trim().lines().next().unwrap_or_default();
+    if title.chars().count() <= max_length {
+        return title.to_string();
+    }
+    let mut cut: String = title.chars().take(max_length).collect();
+    if let Some(space) = cut.rfind(' ') {
+        if space > 0 {
+            cut.truncate(space);
+        }
+    }
+    cut.trim_end().to_string()
+}
+
+fn bullet_list(items: &[String]) -> String {
+    if items.is_empty() {
+        return "- None".to_string();
+    }
+    items
+        .iter()
+        .map(|item| format!("- {}", item.trim().trim_start_matches("- ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn render_template(template: &str, description: &PrDescription) -> String {
+    let or_none = |text: &str| {
+        if text.trim().is_empty() {
+            "None".to_string()
+        } else {
+            text.trim().to_string()
+        }
+    };
+
+    template
+        .replace("{title}", &description.title)
+        .replace("{summary}", &or_none(&description.summary))
+        .replace("{changes}", &bullet_list(&description.changes))
+        .replace("{test_notes}", &bullet_list(&description.test_notes))
+        .replace("{risk}", &or_none(&description.risk))
+        .replace("{rollback}", &or_none(&description.rollback))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn description() -> PrDescription {
+        PrDescription {
+            title: "Make session titles optional".to_string(),
+            summary: "Sessions no longer need a title.".to_string(),
+            changes: vec!["Return `Option` from `Session::title`".to_string()],
+            test_notes: Vec::new(),
+            risk: "Callers that unwrap titles break.".to_string(),
+            rollback: String::new(),
+            base: "main".to_string(),
+            head: "optional-titles".to_string(),
+            commits: Vec::new(),
+            file_changes: Vec::new(),
+            markdown: String::new(),
+        }
+    }
+
+    #[test]
+    fn renders_the_default_template() {
+        let markdown = render_template(DEFAULT_TEMPLATE, &description());
+        assert!(markdown.starts_with("## Summary\n\nSessions no longer need a title.\n"));
+        assert!(markdown.contains("## Changes\n\n- Return `Option` from `Session::title`\n"));
+        assert!(markdown.contains("## Test notes\n\n- None\n"));
+        assert!(markdown.contains("**Rollback:** None\n"));
+    }
+
+    #[test]
+    fn renders_custom_templates() {
+        let markdown = render_template("# {title}\n\n{summary}\n", &description());
+        assert_eq!(
+            markdown,
+            "# Make session titles optional\n\nSessions no longer need a title.\n"
+        );
+    }
+
+    #[test]
+    fn parses_commits_and_fits_titles() {
+        let log = format!("abc1234{0}feat: one\ndef5678{0}fix: two\n", FIELD_SEPARATOR);
+        let commits = parse_commits(&log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[1].subject, "fix: two");
+
+        assert_eq!(
+            fit_title("Make session titles optional everywhere", 30),
+            "Make session titles optional"
+        );
+        assert_eq!(fit_title("Short\nsecond line", 30), "Short");
+    }
+}
@@ -0,0 +1,122 @@
+/**
+ * PR Description Function Agent - type definitions
+ *
+ * Defines data structures for pull request description generation
+ */
+pub use crate::function_agents::git_func_agent::types::{
+    AgentError, AgentErrorType, AgentResult, FileChange, Language,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrDescriptionOptions {
+    /// Branch or commit the PR merges into; detected from `origin/HEAD`,
+    /// then `main`/`master`, when not set
+    #[serde(default)]
+    pub base: Option<String>,
+
+    /// Markdown template with `{title}`, `{summary}`, `{changes}`,
+    /// `{test_notes}`, `{risk}` and `{rollback}` placeholders
+    #[serde(default)]
+    pub template: Option<String>,
+
+    #[serde(default = "default_max_title_length")]
+    pub max_title_length: usize,
+
+    /// Budget for the diff excerpt sent to the AI
+    #[serde(default = "default_max_diff_chars")]
+    pub max_diff_chars: usize,
+
+    /// Branches with more commits are summarized in batches of this size first
+    #[serde(default = "default_commit_batch_size")]
+    pub commit_batch_size: usize,
+
+    #[serde(default = "default_language")]
+    pub language: Language,
+}
+
+fn default_max_title_length() -> usize {
+    72
+}
+
+fn default_max_diff_chars() -> usize {
+    20_000
+}
+
+fn default_commit_batch_size() -> usize {
+    50
+}
+
+fn default_language() -> Language {
+    Language::English
+}
+
+impl Default for PrDescriptionOptions {
+    fn default() -> Self {
+        Self {
+            base: None,
+            template: None,
+            max_title_length: default_max_title_length(),
+            max_diff_chars: default_max_diff_chars(),
+            commit_batch_size: default_commit_batch_size(),
+            language: default_language(),
+        }
+    }
+}
+
+/// A commit on the branch that is not on the base
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrCommit {
+    pub short_hash: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrDescription {
+    pub title: String,
+
+    pub summary: String,
+
+    pub changes: Vec<String>,
+
+    pub test_notes: Vec<String>,
+
+    pub risk: String,
+
+    pub rollback: String,
+
+    /// Base the branch was compared against
+    pub base: String,
+
+    pub head: String,
+
+    pub commits: Vec<PrCommit>,
+
+    pub file_changes: Vec<FileChange>,
+
+    /// The description rendered through the template
+    pub markdown: String,
+}
+
+/// Fields the AI fills in
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AIPrDescription {
+    pub title: String,
+
+    pub summary: String,
+
+    #[serde(default)]
+    pub changes: Vec<String>,
+
+    #[serde(default)]
+    pub test_notes: Vec<String>,
+
+    #[serde(default)]
+    pub risk: String,
+
+    #[serde(default)]
+    pub rollback: String,
+}
@@ -1,5 +1,5 @@
 use super::types::*;
-use crate::infrastructure::ai::AIClient;
+use crate::infrastructure::ai::{AIClient, StreamRequestOptions};
 use crate::util::types::Message;
 /**
  * AI analysis service
@@ -59,7 +59,14 @@ impl AIWorkStateService {
         let messages = vec![Message::user(prompt.to_string())];
         let response = self
             .ai_client
-            .send_message(messages, None)
+            .send_message_with_options(
+                messages,
+                None,
+                StreamRequestOptions {
+                    streaming: Some(false),
+                    ..Default::default()
+                },
+            )
             .await
             .map_err(|e| {
                 error!("AI call failed: {}", e);
@@ -209,10 +216,17 @@ impl AIWorkStateService {
             quick_actions.truncate(6);
         }
 
+        let suggested_branch = parsed["suggested_branch"]
+            .as_str()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+
         debug!(
-            "Parsing completed: predicted_actions={}, quick_actions={}",
+            "Parsing completed: predicted_actions={}, quick_actions={}, suggested_branch={:?}",
             predicted_actions.len(),
-            quick_actions.len()
+            quick_actions.len(),
+            suggested_branch
         );
 
         Ok(AIGeneratedAnalysis {
@@ -220,6 +234,7 @@ impl AIWorkStateService {
             ongoing_work,
             predicted_actions,
             quick_actions,
+            suggested_branch,
         })
     }
 
@@ -289,6 +304,7 @@ impl AIWorkStateService {
                 command,
                 icon,
                 action_type,
+                branch_name: None,
             });
         }
 
@@ -0,0 +1,259 @@
+use super::types::*;
+use crate::function_agents::git_func_agent::CommitType;
+use crate::service::git::GitService;
+/**
+ * Branch suggestion
+ *
+ * Turns the branch name the AI derives from uncommitted changes into a
+ * "create branch" quick action when the user works on a default branch
+ */
+use log::{debug, warn};
+use std::path::Path;
+
+/// Branches work should not pile up on
+const DEFAULT_BRANCHES: &[&str] = &["main", "master", "develop", "trunk"];
+
+/// Upper bound for a suggested branch name, prefix included
+pub const MAX_BRANCH_NAME_LENGTH: usize = 48;
+
+pub fn is_default_branch(branch: &str) -> bool {
+    DEFAULT_BRANCHES.contains(&branch)
+}
+
+/// Whether the user sits on a default branch with work that is not committed
+pub fn should_suggest_branch(git_state: &GitWorkState) -> bool {
+    is_default_branch(&git_state.current_branch)
+        && (git_state.unstaged_files > 0 || git_state.staged_files > 0)
+}
+
+/// Normalizes a raw suggestion such as `Feature: Task cancellation` to
+/// `feat/task-cancellation`, cut to `MAX_BRANCH_NAME_LENGTH` and made unique
+/// against `existing` with a numeric suffix
+pub fn sanitize_branch_name(raw: &str, existing: &[String]) -> Option<String> {
+    let raw = raw.trim().trim_matches('`');
+    let (commit_type, description) = match raw.split_once(['/', ':']) {
+        Some((prefix, rest)) => match prefix.parse::<CommitType>() {
+            Ok(commit_type) => (commit_type, rest),
+            Err(_) => (CommitType::Feat, raw),
+        },
+        None => (CommitType::Feat, raw),
+    };
+
+    let slug = kebab_case(description);
+    if slug.is_empty() {
+        return None;
+    }
+
+    let prefix = format!("{}/", commit_type);
+    let name = format!(
+        "{}{}",
+        prefix,
+        cut_slug(&slug, MAX_BRANCH_NAME_LENGTH - prefix.len())
+    );
+    if !existing.contains(&name) {
+        return Some(name);
+    }
+
+    (2..).find_map(|n| {
+        let suffix = format!("-{}", n);
+        let base = cut_slug(&slug, MAX_BRANCH_NAME_LENGTH - prefix.len() - suffix.len());
+        let candidate = format!("{}{}{}", prefix, base, suffix);
+        (!existing.contains(&candidate)).then_some(candidate)
+    })
+}
+
+fn kebab_case(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Cuts a kebab-case slug to `max_length` at a word boundary where possible
+fn cut_slug(slug: &str, max_length: usize) -> &str {
+    if slug.len() <= max_length {
+        return slug;
+    }
+    let cut = &slug[..max_length];
+    match cut.rfind('-') {
+        Some(dash) if dash > 0 => &cut[..dash],
+        _ => cut,
+    }
+}
+
+/// Builds the "create branch" quick action, if the git state calls for one
+pub fn branch_quick_action(
+    git_state: Option<&GitWorkState>,
+    suggestion: Option<&str>,
+    existing: &[String],
+    language: &Language,
+) -> Option<QuickAction> {
+    if !git_state.is_some_and(should_suggest_branch) {
+        return None;
+    }
+    let branch_name = sanitize_branch_name(suggestion?, existing)?;
+
+    let (title, command) = match language {
+        Language::Chinese => (
+            format!("创建分支 {}", branch_name),
+            format!("将当前未提交的修改移到新分支 {}", branch_name),
+        ),
+        Language::English => (
+            format!("Create branch {}", branch_name),
+            format!(
+                "Move my uncommitted changes to a new branch {}",
+                branch_name
+            ),
+        ),
+    };
+
+    Some(QuickAction {
+        title,
+        command,
+        icon: String::new(),
+        action_type: QuickActionType::CreateBranch,
+        branch_name: Some(branch_name),
+    })
+}
+
+/// Local branch names plus remote ones without their remote prefix
+pub async fn existing_branches(repo_path: &Path) -> Vec<String> {
+    match GitService::get_branches(repo_path, true).await {
+        Ok(branches) => branches
+            .into_iter()
+            .map(|branch| {
+                if branch.remote {
+                    match branch.name.split_once('/') {
+                        Some((_, name)) => name.to_string(),
+                        None => branch.name,
+                    }
+                } else {
+                    branch.name
+                }
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Failed to list branches: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Creates the suggested branch from HEAD and switches to it; uncommitted
+/// changes move along with the checkout
+pub async fn create_branch(repo_path: &Path, branch_name: &str) -> AgentResult<BranchCreation> {
+    if sanitize_branch_name(branch_name, &[]).as_deref() != Some(branch_name) {
+        return Err(AgentError::invalid_input(format!(
+            "Not a suggested branch name: {}",
+            branch_name
+        )));
+    }
+    if existing_branches(repo_path)
+        .await
+        .iter()
+        .any(|existing| existing == branch_name)
+    {
+        return Err(AgentError::invalid_input(format!(
+            "Branch already exists: {}",
+            branch_name
+        )));
+    }
+
+    GitService::create_branch(repo_path, branch_name, None)
+        .await
+        .map_err(|e| AgentError::git_error(format!("Failed to create branch: {}", e)))?;
+    debug!("Created suggested branch: {}", branch_name);
+
+    Ok(BranchCreation {
+        branch_name: branch_name.to_string(),
+        greeting: GreetingMessage {
+            title: String::new(),
+            subtitle: String::new(),
+            tagline: Some(branch_name.to_string()),
+            title_i18n: None,
+            subtitle_i18n: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_state(branch: &str, unstaged: u32, staged: u32) -> GitWorkState {
+        GitWorkState {
+            current_branch: branch.to_string(),
+            unstaged_files: unstaged,
+            staged_files: staged,
+            unpushed_commits: 0,
+            ahead_behind: None,
+            modified_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn sanitizes_suggested_names() {
+        assert_eq!(
+            sanitize_branch_name("Feature: Task Cancellation!", &[]).as_deref(),
+            Some("feat/task-cancellation")
+        );
+        assert_eq!(
+            sanitize_branch_name("fix/crash_on empty  file", &[]).as_deref(),
+            Some("fix/crash-on-empty-file")
+        );
+        assert_eq!(
+            sanitize_branch_name("add session export", &[]).as_deref(),
+            Some("feat/add-session-export")
+        );
+        assert_eq!(sanitize_branch_name("fix/???", &[]), None);
+
+        let long = sanitize_branch_name(
+            "refactor/split the work state analyzer into smaller focused modules",
+            &[],
+        )
+        .unwrap();
+        assert!(long.len() <= MAX_BRANCH_NAME_LENGTH);
+        assert_eq!(long, "refactor/split-the-work-state-analyzer-into");
+    }
+
+    #[test]
+    fn makes_names_unique() {
+        let existing = vec![
+            "feat/task-cancellation".to_string(),
+            "feat/task-cancellation-2".to_string(),
+        ];
+        assert_eq!(
+            sanitize_branch_name("feat/task-cancellation", &existing).as_deref(),
+            Some("feat/task-cancellation-3")
+        );
+    }
+
+    #[test]
+    fn suggests_branch_only_for_uncommitted_work_on_default_branches() {
+        let suggestion = Some("feat/task cancellation");
+        let existing = vec!["main".to_string()];
+
+        let action = branch_quick_action(
+            Some(&git_state("main", 2, 1)),
+            suggestion,
+            &existing,
+            &Language::English,
+        )
+        .unwrap();
+        assert_eq!(action.action_type, QuickActionType::CreateBranch);
+        assert_eq!(
+            action.branch_name.as_deref(),
+            Some("feat/task-cancellation")
+        );
+        assert_eq!(action.title, "Create branch feat/task-cancellation");
+
+        let none = |state: Option<&GitWorkState>, suggestion: Option<&str>| {
+            branch_quick_action(state, suggestion, &existing, &Language::English).is_none()
+        };
+        assert!(none(Some(&git_state("feat/other", 2, 0)), suggestion));
+        assert!(none(Some(&git_state("master", 0, 0)), suggestion));
+        assert!(none(Some(&git_state("main", 1, 0)), None));
+        assert!(none(None, suggestion));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod ai_service;
+pub mod branch_suggestion;
 /**
  * Startchat Function Agent - module entry
  *
@@ -58,4 +59,13 @@ impl StartchatFunctionAgent {
 
         self.analyze_work_state(repo_path, options).await
     }
+
+    /// Accept a `CreateBranch` quick action: create the branch and switch to it
+    pub async fn create_suggested_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> AgentResult<BranchCreation> {
+        branch_suggestion::create_branch(repo_path, branch_name).await
+    }
 }
@@ -3,6 +3,7 @@
  *
  * Defines data structures for work state analysis and greeting info at session start
  */
+use crate::util::i18n::LocalizedText;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -69,6 +70,13 @@ pub struct GreetingMessage {
     pub subtitle: String,
 
     pub tagline: Option<String>,
+
+    /// Translation key and arguments of `title`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_i18n: Option<LocalizedText>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle_i18n: Option<LocalizedText>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -256,6 +264,10 @@ pub struct QuickAction {
     pub icon: String,
 
     pub action_type: QuickActionType,
+
+    /// Branch to create when the action is a `CreateBranch` suggestion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -264,6 +276,8 @@ pub enum QuickActionType {
     ViewStatus,
     Commit,
     Visualize,
+    /// Executed by the backend rather than sent to the AI as a command
+    CreateBranch,
     Custom,
 }
 
@@ -277,6 +291,20 @@ pub struct AIGeneratedAnalysis {
     pub predicted_actions: Vec<PredictedAction>,
 
     pub quick_actions: Vec<QuickAction>,
+
+    /// Raw branch name the AI derived from the changes, before sanitizing
+    pub suggested_branch: Option<String>,
+}
+
+/// Result of accepting a `CreateBranch` quick action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchCreation {
+    pub branch_name: String,
+
+    /// Greeting to show once the work moved to the new branch; the tagline
+    /// names the branch, the rest is filled in by the frontend
+    pub greeting: GreetingMessage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,5 +1,8 @@
+use super::branch_suggestion;
 use super::types::*;
 use crate::infrastructure::ai::AIClientFactory;
+use crate::service::i18n::LocaleId;
+use crate::util::i18n;
 use chrono::{Local, Timelike};
 /**
  * Work state analyzer
@@ -51,12 +54,29 @@ impl WorkStateAnalyzer {
         } else {
             Vec::new()
         };
-        let quick_actions = if options.include_quick_actions {
+        let mut quick_actions = if options.include_quick_actions {
             ai_analysis.quick_actions
         } else {
             Vec::new()
         };
 
+        if options.include_quick_actions
+            && git_state
+                .as_ref()
+                .is_some_and(branch_suggestion::should_suggest_branch)
+        {
+            let existing = branch_suggestion::existing_branches(repo_path).await;
+            if let Some(action) = branch_suggestion::branch_quick_action(
+                git_state.as_ref(),
+                ai_analysis.suggested_branch.as_deref(),
+                &existing,
+                &options.language,
+            ) {
+                debug!("Suggesting branch: {:?}", action.branch_name);
+                quick_actions.insert(0, action);
+            }
+        }
+
         let current_state = CurrentWorkState {
             summary,
             git_state,
@@ -73,12 +93,25 @@ impl WorkStateAnalyzer {
         })
     }
 
-    fn generate_greeting(_options: &WorkStateOptions) -> GreetingMessage {
-        // Frontend uses its own static greeting from i18n.
+    fn generate_greeting(options: &WorkStateOptions) -> GreetingMessage {
+        let locale = match options.language {
+            Language::Chinese => LocaleId::ZhCN,
+            Language::English => LocaleId::EnUS,
+        };
+        let title_key = match Local::now().hour() {
+            5..=11 => "welcome-greeting-morning",
+            12..=17 => "welcome-greeting-afternoon",
+            _ => "welcome-greeting-evening",
+        };
+        let title = i18n::localize_in(&locale, title_key, &[]);
+        let subtitle = i18n::localize_in(&locale, "welcome-greeting-subtitle", &[]);
+
         GreetingMessage {
-            title: String::new(),
-            subtitle: String::new(),
+            title: title.text.clone(),
+            subtitle: subtitle.text.clone(),
             tagline: None,
+            title_i18n: Some(title),
+            subtitle_i18n: Some(subtitle),
         }
     }
 
@@ -113,6 +146,20 @@ impl WorkStateAnalyzer {
         Ok(diff)
     }
 
+    /// Working tree and staged changes of one file against `HEAD`
+    pub(crate) fn get_file_diff(repo_path: &Path, file_path: &str) -> AgentResult<String> {
+        let output = crate::util::process_manager::create_command("git")
+            .arg("diff")
+            .arg("HEAD")
+            .arg("--")
+            .arg(file_path)
+            .current_dir(repo_path)
+            .output()
+            .map_err(|e| AgentError::git_error(format!("Failed to get file diff: {}", e)))?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
     async fn generate_complete_analysis_with_ai(
         factory: Arc<AIClientFactory>,
         git_state: &Option<GitWorkState>,
@@ -130,7 +177,8 @@ impl WorkStateAnalyzer {
             .await
     }
 
-    async fn analyze_git_state(repo_path: &Path) -> AgentResult<GitWorkState> {
+    /// Branch, change counts and the first few modified files, without any AI analysis
+    pub(crate) async fn analyze_git_state(repo_path: &Path) -> AgentResult<GitWorkState> {
         let current_branch = Self::get_current_branch(repo_path)?;
 
         let status_output = crate::util::process_manager::create_command("git")
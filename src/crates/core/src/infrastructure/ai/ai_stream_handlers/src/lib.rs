@@ -5,4 +5,10 @@ pub use stream_handler::handle_anthropic_stream;
 pub use stream_handler::handle_gemini_stream;
 pub use stream_handler::handle_openai_stream;
 pub use stream_handler::handle_responses_stream;
-pub use types::unified::{UnifiedResponse, UnifiedTokenUsage, UnifiedToolCall};
+pub use stream_handler::{
+    parse_anthropic_message, parse_openai_completion, parse_responses_completion,
+};
+pub use stream_handler::{StreamLimits, DEFAULT_IDLE_TIMEOUT};
+pub use types::unified::{
+    UnifiedResponse, UnifiedServerToolEvent, UnifiedTokenUsage, UnifiedToolCall,
+};
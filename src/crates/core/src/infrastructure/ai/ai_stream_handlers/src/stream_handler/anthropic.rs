@@ -1,17 +1,127 @@
+use super::stream_limits::StreamLimits;
 use super::stream_stats::StreamStats;
 use crate::types::anthropic::{
-    AnthropicSSEError, ContentBlock, ContentBlockDelta, ContentBlockStart, MessageDelta,
+    AnthropicSSEError, ContentBlock, ContentBlockDelta, ContentBlockStart, Delta, MessageDelta,
     MessageStart, Usage,
 };
-use crate::types::unified::UnifiedResponse;
+use crate::types::unified::{
+    UnifiedResponse, UnifiedServerToolEvent, UnifiedTokenUsage, UnifiedToolCall,
+};
 use anyhow::{anyhow, Result};
 use eventsource_stream::Eventsource;
-use futures::StreamExt;
 use log::{error, trace};
 use reqwest::Response;
-use std::time::Duration;
+use serde_json::Value;
 use tokio::sync::mpsc;
-use tokio::time::timeout;
+
+/// Convert a non-streaming Messages API response into the events the stream handler would emit
+///
+/// Each content block becomes the start/delta pair the stream would have produced, followed by
+/// one closing event carrying usage and the stop reason.
+pub fn parse_anthropic_message(body: Value) -> Result<Vec<UnifiedResponse>> {
+    if body.get("type").and_then(Value::as_str) == Some("error") {
+        let sse_error: AnthropicSSEError = serde_json::from_value(body.clone())
+            .map_err(|e| anyhow!("Response parsing error: {e}, data: {}", body))?;
+        return Err(anyhow!(String::from(sse_error.error)));
+    }
+
+    let mut responses = Vec::new();
+    let blocks = body
+        .get("content")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for block in blocks {
+        let text_field = |name: &str| block.get(name).and_then(Value::as_str).map(str::to_string);
+        match block.get("type").and_then(Value::as_str) {
+            Some("thinking") => {
+                responses.push(UnifiedResponse {
+                    reasoning_content: text_field("thinking"),
+                    ..Default::default()
+                });
+                if let Some(signature) = text_field("signature") {
+                    responses.push(UnifiedResponse {
+                        thinking_signature: Some(signature),
+                        ..Default::default()
+                    });
+                }
+            }
+            Some("text") => {
+                responses.push(UnifiedResponse {
+                    text: text_field("text"),
+                    ..Default::default()
+                });
+                let citations = block
+                    .get("citations")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                responses.extend(citations.into_iter().map(|citation| UnifiedResponse {
+                    server_tool: Some(UnifiedServerToolEvent::Citation { citation }),
+                    ..Default::default()
+                }));
+            }
+            Some("tool_use") => {
+                responses.push(UnifiedResponse {
+                    tool_call: Some(UnifiedToolCall {
+                        id: text_field("id"),
+                        name: text_field("name"),
+                        arguments: None,
+                        index: None,
+                    }),
+                    ..Default::default()
+                });
+                let input = block
+                    .get("input")
+                    .cloned()
+                    .unwrap_or_else(|| Value::Object(Default::default()));
+                responses.push(UnifiedResponse {
+                    tool_call: Some(UnifiedToolCall {
+                        id: None,
+                        name: None,
+                        arguments: Some(input.to_string()),
+                        index: None,
+                    }),
+                    ..Default::default()
+                });
+            }
+            Some("server_tool_use") => responses.push(UnifiedResponse {
+                server_tool: Some(UnifiedServerToolEvent::Use {
+                    id: text_field("id").unwrap_or_default(),
+                    name: text_field("name").unwrap_or_default(),
+                    input: block.get("input").cloned().unwrap_or(Value::Null),
+                }),
+                ..Default::default()
+            }),
+            Some("web_search_tool_result") => responses.push(UnifiedResponse {
+                server_tool: Some(UnifiedServerToolEvent::Result {
+                    tool_use_id: text_field("tool_use_id").unwrap_or_default(),
+                    name: "web_search".to_string(),
+                    content: block.get("content").cloned().unwrap_or(Value::Null),
+                }),
+                ..Default::default()
+            }),
+            _ => {}
+        }
+    }
+
+    let usage = body
+        .get("usage")
+        .cloned()
+        .and_then(|usage| serde_json::from_value::<Usage>(usage).ok())
+        .filter(|usage| !usage.is_empty())
+        .map(UnifiedTokenUsage::from);
+    responses.push(UnifiedResponse {
+        usage,
+        finish_reason: body
+            .get("stop_reason")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        ..Default::default()
+    });
+
+    Ok(responses)
+}
 
 /// Convert a byte stream into a structured response stream
 ///
@@ -19,18 +129,22 @@ use tokio::time::timeout;
 /// * `response` - HTTP response
 /// * `tx_event` - parsed event sender
 /// * `tx_raw_sse` - optional raw SSE sender (collect raw data for diagnostics)
+/// * `limits` - idle timeout, overall deadline and cancellation for the stream
 pub async fn handle_anthropic_stream(
     response: Response,
     tx_event: mpsc::UnboundedSender<Result<UnifiedResponse>>,
     tx_raw_sse: Option<mpsc::UnboundedSender<String>>,
+    limits: StreamLimits,
 ) {
     let mut stream = response.bytes_stream().eventsource();
-    let idle_timeout = Duration::from_secs(600);
+    let deadline_at = limits.deadline_at();
     let mut usage = Usage::default();
     let mut stats = StreamStats::new("Anthropic");
+    // Server tool call whose input is still streaming: (id, name, input from the start event, deltas)
+    let mut pending_server_tool: Option<(String, String, Value, String)> = None;
 
     loop {
-        let sse_event = timeout(idle_timeout, stream.next()).await;
+        let sse_event = limits.next_item(&mut stream, deadline_at).await;
         let sse = match sse_event {
             Ok(Some(Ok(sse))) => sse,
             Ok(None) => {
@@ -47,11 +161,12 @@ pub async fn handle_anthropic_stream(
                 let _ = tx_event.send(Err(anyhow!(error_msg)));
                 return;
             }
-            Err(_) => {
-                let error_msg = "SSE Timeout: idle timeout waiting for SSE";
-                stats.log_summary("sse_stream_timeout");
-                error!("{}", error_msg);
-                let _ = tx_event.send(Err(anyhow!(error_msg)));
+            Err(interrupt) => {
+                stats.log_summary(interrupt.summary_reason());
+                if let Some(error_msg) = interrupt.error_message("SSE stream") {
+                    error!("{}", error_msg);
+                    let _ = tx_event.send(Err(anyhow!(error_msg)));
+                }
                 return;
             }
         };
@@ -90,14 +205,17 @@ pub async fn handle_anthropic_stream(
                         continue;
                     }
                 };
-                if matches!(
-                    content_block_start.content_block,
-                    ContentBlock::ToolUse { .. }
-                ) {
-                    let unified_response = UnifiedResponse::from(content_block_start);
-                    trace!("Anthropic unified response: {:?}", unified_response);
-                    stats.record_unified_response(&unified_response);
-                    let _ = tx_event.send(Ok(unified_response));
+                match content_block_start.content_block {
+                    ContentBlock::ServerToolUse { id, name, input } => {
+                        pending_server_tool = Some((id, name, input, String::new()));
+                    }
+                    ContentBlock::ToolUse { .. } | ContentBlock::WebSearchToolResult { .. } => {
+                        let unified_response = UnifiedResponse::from(content_block_start);
+                        trace!("Anthropic unified response: {:?}", unified_response);
+                        stats.record_unified_response(&unified_response);
+                        let _ = tx_event.send(Ok(unified_response));
+                    }
+                    _ => {}
                 }
             }
             "content_block_delta" => {
@@ -110,6 +228,12 @@ pub async fn handle_anthropic_stream(
                         continue;
                     }
                 };
+                if let (Some((_, _, _, input_json)), Delta::InputJsonDelta { partial_json }) =
+                    (pending_server_tool.as_mut(), &content_block_delta.delta)
+                {
+                    input_json.push_str(partial_json);
+                    continue;
+                }
                 match UnifiedResponse::try_from(content_block_delta) {
                     Ok(unified_response) => {
                         trace!("Anthropic unified response: {:?}", unified_response);
@@ -122,6 +246,23 @@ pub async fn handle_anthropic_stream(
                     }
                 };
             }
+            "content_block_stop" => {
+                let Some((id, name, start_input, input_json)) = pending_server_tool.take() else {
+                    continue;
+                };
+                let input = if input_json.trim().is_empty() {
+                    start_input
+                } else {
+                    serde_json::from_str(&input_json).unwrap_or(Value::String(input_json))
+                };
+                let unified_response = UnifiedResponse {
+                    server_tool: Some(UnifiedServerToolEvent::Use { id, name, input }),
+                    ..Default::default()
+                };
+                trace!("Anthropic unified response: {:?}", unified_response);
+                stats.record_unified_response(&unified_response);
+                let _ = tx_event.send(Ok(unified_response));
+            }
             "message_delta" => {
                 let mut message_delta: MessageDelta = match serde_json::from_str(&data) {
                     Ok(message_delta) => message_delta,
@@ -170,3 +311,94 @@ pub async fn handle_anthropic_stream(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_anthropic_message;
+    use crate::types::unified::UnifiedServerToolEvent;
+
+    #[test]
+    fn parses_non_streaming_message_blocks_in_stream_order() {
+        let body = serde_json::json!({
+            "type": "message",
+            "content": [
+                { "type": "thinking", "thinking": "plan", "signature": "sig" },
+                { "type": "text", "text": "Reading" },
+                { "type": "tool_use", "id": "toolu_1", "name": "read_file", "input": { "path": "a" } }
+            ],
+            "stop_reason": "tool_use",
+            "usage": { "input_tokens": 12, "output_tokens": 4 }
+        });
+
+        let responses = parse_anthropic_message(body).expect("parsed message");
+
+        assert_eq!(responses.len(), 6);
+        assert_eq!(responses[0].reasoning_content.as_deref(), Some("plan"));
+        assert_eq!(responses[1].thinking_signature.as_deref(), Some("sig"));
+        assert_eq!(responses[2].text.as_deref(), Some("Reading"));
+        let start = responses[3].tool_call.as_ref().expect("tool start");
+        assert_eq!(start.name.as_deref(), Some("read_file"));
+        let args = responses[4].tool_call.as_ref().expect("tool args");
+        assert_eq!(args.arguments.as_deref(), Some(r#"{"path":"a"}"#));
+        assert_eq!(responses[5].finish_reason.as_deref(), Some("tool_use"));
+        assert_eq!(
+            responses[5].usage.as_ref().map(|u| u.total_token_count),
+            Some(16)
+        );
+    }
+
+    #[test]
+    fn parses_non_streaming_web_search_blocks_and_citations() {
+        let body = serde_json::json!({
+            "type": "message",
+            "content": [
+                { "type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": { "query": "rust 2024" } },
+                { "type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": [
+                    { "type": "web_search_result", "url": "https://blog.rust-lang.org", "title": "Rust Blog", "encrypted_content": "abc" }
+                ] },
+                { "type": "text", "text": "Rust 2024 shipped.", "citations": [
+                    { "type": "web_search_result_location", "url": "https://blog.rust-lang.org", "cited_text": "shipped" }
+                ] }
+            ],
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 10, "output_tokens": 5, "server_tool_use": { "web_search_requests": 1 } }
+        });
+
+        let responses = parse_anthropic_message(body).expect("parsed message");
+
+        assert_eq!(responses.len(), 5);
+        assert!(matches!(
+            &responses[0].server_tool,
+            Some(UnifiedServerToolEvent::Use { id, input, .. })
+                if id == "srvtoolu_1" && input["query"] == "rust 2024"
+        ));
+        assert!(matches!(
+            &responses[1].server_tool,
+            Some(UnifiedServerToolEvent::Result { tool_use_id, content, .. })
+                if tool_use_id == "srvtoolu_1" && content[0]["encrypted_content"] == "abc"
+        ));
+        assert_eq!(responses[2].text.as_deref(), Some("Rust 2024 shipped."));
+        assert!(matches!(
+            &responses[3].server_tool,
+            Some(UnifiedServerToolEvent::Citation { citation })
+                if citation["cited_text"] == "shipped"
+        ));
+        assert_eq!(
+            responses[4]
+                .usage
+                .as_ref()
+                .and_then(|u| u.web_search_requests),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn non_streaming_error_body_is_reported() {
+        let body = serde_json::json!({
+            "type": "error",
+            "error": { "type": "overloaded_error", "message": "Overloaded" }
+        });
+        let error = parse_anthropic_message(body).expect_err("error body");
+        assert_eq!(error.to_string(), "overloaded_error: Overloaded");
+    }
+}
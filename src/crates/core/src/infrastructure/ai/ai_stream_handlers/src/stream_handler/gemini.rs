@@ -1,16 +1,14 @@
+use super::stream_limits::StreamLimits;
 use super::stream_stats::StreamStats;
 use crate::types::gemini::GeminiSSEData;
 use crate::types::unified::UnifiedResponse;
 use anyhow::{anyhow, Result};
 use eventsource_stream::Eventsource;
-use futures::StreamExt;
 use log::{error, trace};
 use reqwest::Response;
 use serde_json::Value;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::timeout;
 
 static GEMINI_STREAM_ID_SEQ: AtomicU64 = AtomicU64::new(1);
 
@@ -75,15 +73,16 @@ pub async fn handle_gemini_stream(
     response: Response,
     tx_event: mpsc::UnboundedSender<Result<UnifiedResponse>>,
     tx_raw_sse: Option<mpsc::UnboundedSender<String>>,
+    limits: StreamLimits,
 ) {
     let mut stream = response.bytes_stream().eventsource();
-    let idle_timeout = Duration::from_secs(600);
+    let deadline_at = limits.deadline_at();
     let mut received_finish_reason = false;
     let mut tool_call_state = GeminiToolCallState::new();
     let mut stats = StreamStats::new("Gemini");
 
     loop {
-        let sse_event = timeout(idle_timeout, stream.next()).await;
+        let sse_event = limits.next_item(&mut stream, deadline_at).await;
         let sse = match sse_event {
             Ok(Some(Ok(sse))) => sse,
             Ok(None) => {
@@ -104,14 +103,12 @@ pub async fn handle_gemini_stream(
                 let _ = tx_event.send(Err(anyhow!(error_msg)));
                 return;
             }
-            Err(_) => {
-                let error_msg = format!(
-                    "Gemini SSE stream timeout after {}s",
-                    idle_timeout.as_secs()
-                );
-                stats.log_summary("sse_stream_timeout");
-                error!("{}", error_msg);
-                let _ = tx_event.send(Err(anyhow!(error_msg)));
+            Err(interrupt) => {
+                stats.log_summary(interrupt.summary_reason());
+                if let Some(error_msg) = interrupt.error_message("Gemini SSE stream") {
+                    error!("{}", error_msg);
+                    let _ = tx_event.send(Err(anyhow!(error_msg)));
+                }
                 return;
             }
         };
@@ -200,6 +197,7 @@ mod tests {
             id: None,
             name: Some("get_weather".to_string()),
             arguments: Some("{\"city\":".to_string()),
+            index: None,
         };
         state.assign_id(&mut first);
 
@@ -207,6 +205,7 @@ mod tests {
             id: None,
             name: Some("get_weather".to_string()),
             arguments: Some("\"Paris\"}".to_string()),
+            index: None,
         };
         state.assign_id(&mut second);
 
@@ -225,6 +224,7 @@ mod tests {
             id: None,
             name: Some("get_weather".to_string()),
             arguments: Some("{}".to_string()),
+            index: None,
         };
         state.assign_id(&mut first);
         state.on_non_tool_response();
@@ -233,6 +233,7 @@ mod tests {
             id: None,
             name: Some("get_weather".to_string()),
             arguments: Some("{}".to_string()),
+            index: None,
         };
         state.assign_id(&mut second);
 
@@ -252,11 +253,13 @@ mod tests {
             id: None,
             name: Some("grep".to_string()),
             arguments: Some("{}".to_string()),
+            index: None,
         };
         let mut second = UnifiedToolCall {
             id: None,
             name: Some("read".to_string()),
             arguments: Some("{}".to_string()),
+            index: None,
         };
 
         first_state.assign_id(&mut first);
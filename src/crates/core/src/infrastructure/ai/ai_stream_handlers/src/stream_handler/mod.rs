@@ -1,10 +1,12 @@
+mod stream_limits;
 mod stream_stats;
 mod anthropic;
 mod gemini;
 mod openai;
 mod responses;
 
-pub use anthropic::handle_anthropic_stream;
+pub use anthropic::{handle_anthropic_stream, parse_anthropic_message};
 pub use gemini::handle_gemini_stream;
-pub use openai::handle_openai_stream;
-pub use responses::handle_responses_stream;
+pub use openai::{handle_openai_stream, parse_openai_completion};
+pub use responses::{handle_responses_stream, parse_responses_completion};
+pub use stream_limits::{StreamLimits, DEFAULT_IDLE_TIMEOUT};
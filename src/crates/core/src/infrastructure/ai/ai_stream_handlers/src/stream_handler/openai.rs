@@ -1,17 +1,15 @@
+use super::stream_limits::StreamLimits;
 use super::stream_stats::StreamStats;
 use crate::types::openai::OpenAISSEData;
 use crate::types::unified::{UnifiedResponse, UnifiedTokenUsage};
 use anyhow::{anyhow, Result};
 use eventsource_stream::Eventsource;
-use futures::StreamExt;
 use log::{error, trace, warn};
 use reqwest::Response;
 use serde_json::Value;
 use std::collections::HashSet;
 use std::mem;
-use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::timeout;
 
 const OPENAI_CHAT_COMPLETION_CHUNK_OBJECT: &str = "chat.completion.chunk";
 const INLINE_THINK_OPEN_TAG: &str = "<think>";
@@ -370,20 +368,67 @@ fn extract_sse_api_error_message(event_json: &Value) -> Option<String> {
     Some("An error occurred during streaming".to_string())
 }
 
+/// Convert a non-streaming chat completion into the events the stream handler would emit
+///
+/// A completion's `message` has the shape of a chunk's `delta`, except that tool calls carry no
+/// `index`, so the body is reshaped into a single chunk and run through the same normalizer.
+pub fn parse_openai_completion(
+    mut body: Value,
+    inline_think_in_text: bool,
+) -> Result<Vec<UnifiedResponse>> {
+    if let Some(api_error_message) = extract_sse_api_error_message(&body) {
+        return Err(anyhow!("API error: {}, data: {}", api_error_message, body));
+    }
+
+    if let Some(choices) = body.get_mut("choices").and_then(Value::as_array_mut) {
+        for choice in choices.iter_mut() {
+            let Some(choice_obj) = choice.as_object_mut() else {
+                continue;
+            };
+            let Some(mut message) = choice_obj.remove("message") else {
+                continue;
+            };
+            if let Some(tool_calls) = message.get_mut("tool_calls").and_then(Value::as_array_mut) {
+                for (index, tool_call) in tool_calls.iter_mut().enumerate() {
+                    if let Some(tool_call_obj) = tool_call.as_object_mut() {
+                        tool_call_obj
+                            .entry("index")
+                            .or_insert_with(|| Value::from(index));
+                    }
+                }
+            }
+            choice_obj.insert("delta".to_string(), message);
+        }
+    }
+
+    let completion: OpenAISSEData = serde_json::from_value(body.clone())
+        .map_err(|e| anyhow!("Completion schema error: {}, data: {}", e, body))?;
+
+    let mut normalizer = OpenAIResponseNormalizer::new(inline_think_in_text);
+    let mut responses = Vec::new();
+    for unified_response in completion.into_unified_responses() {
+        responses.extend(normalizer.normalize_response(unified_response));
+    }
+    responses.extend(normalizer.flush());
+    Ok(responses)
+}
+
 /// Convert a byte stream into a structured response stream
 ///
 /// # Arguments
 /// * `response` - HTTP response
 /// * `tx_event` - parsed event sender
 /// * `tx_raw_sse` - optional raw SSE sender (collect raw data for diagnostics)
+/// * `limits` - idle timeout, overall deadline and cancellation for the stream
 pub async fn handle_openai_stream(
     response: Response,
     tx_event: mpsc::UnboundedSender<Result<UnifiedResponse>>,
     tx_raw_sse: Option<mpsc::UnboundedSender<String>>,
     inline_think_in_text: bool,
+    limits: StreamLimits,
 ) {
     let mut stream = response.bytes_stream().eventsource();
-    let idle_timeout = Duration::from_secs(600);
+    let deadline_at = limits.deadline_at();
     let mut stats = StreamStats::new("OpenAI");
     // Track whether a chunk with `finish_reason` was received.
     // Some providers (e.g. MiniMax) close the stream after the final chunk
@@ -393,7 +438,7 @@ pub async fn handle_openai_stream(
     let mut normalizer = OpenAIResponseNormalizer::new(inline_think_in_text);
 
     loop {
-        let sse_event = timeout(idle_timeout, stream.next()).await;
+        let sse_event = limits.next_item(&mut stream, deadline_at).await;
         let sse = match sse_event {
             Ok(Some(Ok(sse))) => sse,
             Ok(None) => {
@@ -418,11 +463,12 @@ pub async fn handle_openai_stream(
                 let _ = tx_event.send(Err(anyhow!(error_msg)));
                 return;
             }
-            Err(_) => {
-                let error_msg = format!("SSE stream timeout after {}s", idle_timeout.as_secs());
-                stats.log_summary("sse_stream_timeout");
-                error!("{}", error_msg);
-                let _ = tx_event.send(Err(anyhow!(error_msg)));
+            Err(interrupt) => {
+                stats.log_summary(interrupt.summary_reason());
+                if let Some(error_msg) = interrupt.error_message("SSE stream") {
+                    error!("{}", error_msg);
+                    let _ = tx_event.send(Err(anyhow!(error_msg)));
+                }
                 return;
             }
         };
@@ -542,8 +588,8 @@ pub async fn handle_openai_stream(
 mod tests {
     use super::{
         extract_sse_api_error_message, is_valid_chat_completion_chunk_weak,
-        longest_suffix_prefix_len, InlineThinkActivation, InlineThinkMode, OpenAIInlineThinkParser,
-        OpenAIToolCallFilter,
+        longest_suffix_prefix_len, parse_openai_completion, InlineThinkActivation, InlineThinkMode,
+        OpenAIInlineThinkParser, OpenAIToolCallFilter,
     };
     use crate::types::unified::{UnifiedResponse, UnifiedToolCall};
 
@@ -612,6 +658,7 @@ mod tests {
                 id: Some("call_1".to_string()),
                 name: Some("read_file".to_string()),
                 arguments: Some("{\"path\":\"a.txt\"}".to_string()),
+                index: None,
             }),
             ..Default::default()
         };
@@ -620,6 +667,7 @@ mod tests {
                 id: Some("call_1".to_string()),
                 name: None,
                 arguments: Some(String::new()),
+                index: None,
             }),
             ..Default::default()
         };
@@ -637,6 +685,7 @@ mod tests {
                 id: Some("call_1".to_string()),
                 name: Some("read_file".to_string()),
                 arguments: Some("{\"path\":\"a.txt\"}".to_string()),
+                index: None,
             }),
             ..Default::default()
         };
@@ -645,6 +694,7 @@ mod tests {
                 id: Some("call_1".to_string()),
                 name: None,
                 arguments: None,
+                index: None,
             }),
             finish_reason: Some("tool_calls".to_string()),
             ..Default::default()
@@ -767,4 +817,48 @@ mod tests {
         assert_eq!(responses[0].text.as_deref(), Some("<think>abc</think>done"));
         assert!(responses[0].reasoning_content.is_none());
     }
+
+    #[test]
+    fn parses_non_streaming_completion_with_tool_calls() {
+        let body = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "gpt-test",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": "Checking",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "read_file", "arguments": "{\"path\":\"a\"}" }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 }
+        });
+
+        let responses = parse_openai_completion(body, false).expect("parsed completion");
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].text.as_deref(), Some("Checking"));
+        assert_eq!(responses[0].finish_reason.as_deref(), Some("tool_calls"));
+        assert_eq!(
+            responses[0].usage.as_ref().map(|u| u.total_token_count),
+            Some(15)
+        );
+        let tool_call = responses[1].tool_call.as_ref().expect("tool call");
+        assert_eq!(tool_call.id.as_deref(), Some("call_1"));
+        assert_eq!(tool_call.arguments.as_deref(), Some("{\"path\":\"a\"}"));
+    }
+
+    #[test]
+    fn non_streaming_completion_error_is_reported() {
+        let body = serde_json::json!({ "error": { "message": "quota exceeded" } });
+        let error = parse_openai_completion(body, false).expect_err("api error");
+        assert!(error.to_string().contains("quota exceeded"));
+    }
 }
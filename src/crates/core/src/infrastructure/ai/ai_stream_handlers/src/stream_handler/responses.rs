@@ -1,3 +1,4 @@
+use super::stream_limits::StreamLimits;
 use super::stream_stats::StreamStats;
 use crate::types::responses::{
     parse_responses_output_item, ResponsesCompleted, ResponsesDone, ResponsesStreamEvent,
@@ -5,14 +6,11 @@ use crate::types::responses::{
 use crate::types::unified::UnifiedResponse;
 use anyhow::{anyhow, Result};
 use eventsource_stream::Eventsource;
-use futures::StreamExt;
 use log::{error, trace};
 use reqwest::Response;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::timeout;
 
 #[derive(Debug, Default, Clone)]
 struct InProgressToolCall {
@@ -126,6 +124,7 @@ fn handle_function_call_output_item_done(
                     id,
                     name,
                     arguments: Some(delta),
+                    index: Some(output_index),
                 }),
                 ..Default::default()
             };
@@ -141,6 +140,53 @@ fn handle_function_call_output_item_done(
     );
 }
 
+/// Parse a non-streaming Responses API body (`stream: false`) into unified responses.
+///
+/// Output items are replayed in order; tool calls keep their output index so callers can tell
+/// them apart. A closing event carries usage and the finish reason derived from `status`.
+pub fn parse_responses_completion(body: Value) -> Result<Vec<UnifiedResponse>> {
+    if let Some(error) = body.get("error").filter(|error| !error.is_null()) {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .or_else(|| error.as_str())
+            .unwrap_or("Responses API returned an error");
+        return Err(anyhow!("API error: {}, data: {}", message, body));
+    }
+
+    let completed: ResponsesDone = serde_json::from_value(body.clone())
+        .map_err(|e| anyhow!("Responses schema error: {}, data: {}", e, body))?;
+
+    let mut responses = Vec::new();
+    if let Some(output) = body.get("output").and_then(Value::as_array) {
+        for (index, item) in output.iter().enumerate() {
+            let Some(mut unified_response) = parse_responses_output_item(item.clone()) else {
+                continue;
+            };
+            if let Some(tool_call) = unified_response.tool_call.as_mut() {
+                tool_call.index = Some(index);
+            }
+            responses.push(unified_response);
+        }
+    }
+
+    let finish_reason = match body.get("status").and_then(Value::as_str) {
+        Some("incomplete") => body
+            .get("incomplete_details")
+            .and_then(|details| details.get("reason"))
+            .and_then(Value::as_str)
+            .map(|reason| format!("incomplete:{reason}"))
+            .unwrap_or_else(|| "incomplete".to_string()),
+        _ => "stop".to_string(),
+    };
+    responses.push(UnifiedResponse {
+        usage: completed.usage.map(Into::into),
+        finish_reason: Some(finish_reason),
+        ..Default::default()
+    });
+    Ok(responses)
+}
+
 fn extract_api_error_message(event_json: &Value) -> Option<String> {
     let response = event_json.get("response")?;
     let error = response.get("error")?;
@@ -163,9 +209,10 @@ pub async fn handle_responses_stream(
     response: Response,
     tx_event: mpsc::UnboundedSender<Result<UnifiedResponse>>,
     tx_raw_sse: Option<mpsc::UnboundedSender<String>>,
+    limits: StreamLimits,
 ) {
     let mut stream = response.bytes_stream().eventsource();
-    let idle_timeout = Duration::from_secs(600);
+    let deadline_at = limits.deadline_at();
     // Some providers close the stream after emitting the terminal event and may not send `[DONE]`.
     let mut received_finish_reason = false;
     let mut received_text_delta = false;
@@ -174,7 +221,7 @@ pub async fn handle_responses_stream(
     let mut stats = StreamStats::new("Responses");
 
     loop {
-        let sse_event = timeout(idle_timeout, stream.next()).await;
+        let sse_event = limits.next_item(&mut stream, deadline_at).await;
         let sse = match sse_event {
             Ok(Some(Ok(sse))) => sse,
             Ok(None) => {
@@ -195,14 +242,12 @@ pub async fn handle_responses_stream(
                 let _ = tx_event.send(Err(anyhow!(error_msg)));
                 return;
             }
-            Err(_) => {
-                let error_msg = format!(
-                    "Responses SSE stream timeout after {}s",
-                    idle_timeout.as_secs()
-                );
-                stats.log_summary("sse_stream_timeout");
-                error!("{}", error_msg);
-                let _ = tx_event.send(Err(anyhow!(error_msg)));
+            Err(interrupt) => {
+                stats.log_summary(interrupt.summary_reason());
+                if let Some(error_msg) = interrupt.error_message("Responses SSE stream") {
+                    error!("{}", error_msg);
+                    let _ = tx_event.send(Err(anyhow!(error_msg)));
+                }
                 return;
             }
         };
@@ -318,6 +363,7 @@ pub async fn handle_responses_stream(
                         id,
                         name,
                         arguments: Some(delta),
+                        index: Some(output_index),
                     }),
                     ..Default::default()
                 };
@@ -387,6 +433,7 @@ pub async fn handle_responses_stream(
                                             id,
                                             name,
                                             arguments: Some(delta),
+                                            index: Some(idx),
                                         }),
                                         ..Default::default()
                                     };
@@ -528,13 +575,94 @@ pub async fn handle_responses_stream(
 #[cfg(test)]
 mod tests {
     use super::{
-        super::stream_stats::StreamStats,
-        extract_api_error_message, handle_function_call_output_item_done, InProgressToolCall,
+        super::stream_limits::StreamLimits, super::stream_stats::StreamStats,
+        extract_api_error_message, handle_function_call_output_item_done, handle_responses_stream,
+        parse_responses_completion, InProgressToolCall,
     };
+    use crate::types::unified::UnifiedResponse;
     use serde_json::json;
     use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
     use tokio::sync::mpsc;
 
+    const TEXT_FIXTURE: &str = "\
+event: response.created
+data: {\"type\":\"response.created\",\"response\":{\"id\":\"resp_1\",\"status\":\"in_progress\"}}
+
+event: response.output_item.added
+data: {\"type\":\"response.output_item.added\",\"output_index\":0,\"item\":{\"type\":\"message\",\"role\":\"assistant\",\"content\":[]}}
+
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"output_index\":0,\"content_index\":0,\"delta\":\"Hello\"}
+
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"output_index\":0,\"content_index\":0,\"delta\":\", world\"}
+
+event: response.output_item.done
+data: {\"type\":\"response.output_item.done\",\"output_index\":0,\"item\":{\"type\":\"message\",\"role\":\"assistant\",\"content\":[{\"type\":\"output_text\",\"text\":\"Hello, world\"}]}}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_1\",\"status\":\"completed\",\"usage\":{\"input_tokens\":12,\"input_tokens_details\":{\"cached_tokens\":4},\"output_tokens\":3,\"total_tokens\":15}}}
+
+";
+
+    const TOOL_CALL_FIXTURE: &str = "\
+event: response.output_item.added
+data: {\"type\":\"response.output_item.added\",\"output_index\":0,\"item\":{\"type\":\"function_call\",\"call_id\":\"call_1\",\"name\":\"get_weather\",\"arguments\":\"\"}}
+
+event: response.function_call_arguments.delta
+data: {\"type\":\"response.function_call_arguments.delta\",\"output_index\":0,\"delta\":\"{\\\"city\\\":\"}
+
+event: response.function_call_arguments.delta
+data: {\"type\":\"response.function_call_arguments.delta\",\"output_index\":0,\"delta\":\"\\\"Bei\"}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_2\",\"status\":\"completed\",\"output\":[{\"type\":\"function_call\",\"call_id\":\"call_1\",\"name\":\"get_weather\",\"arguments\":\"{\\\"city\\\":\\\"Beijing\\\"}\"}],\"usage\":{\"input_tokens\":20,\"output_tokens\":8,\"total_tokens\":28}}}
+
+";
+
+    /// Serve `body` once as an SSE response from a local socket.
+    async fn sse_fixture_response(body: &'static str) -> reqwest::Response {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            );
+            socket.write_all(head.as_bytes()).await.expect("write head");
+            socket.write_all(body.as_bytes()).await.expect("write body");
+        });
+        reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .expect("client")
+            .post(format!("http://{addr}/v1/responses"))
+            .send()
+            .await
+            .expect("fixture response")
+    }
+
+    async fn collect_stream(body: &'static str) -> Vec<anyhow::Result<UnifiedResponse>> {
+        let (tx_event, mut rx_event) = mpsc::unbounded_channel();
+        handle_responses_stream(
+            sse_fixture_response(body).await,
+            tx_event,
+            None,
+            StreamLimits::default(),
+        )
+        .await;
+        let mut events = Vec::new();
+        while let Ok(event) = rx_event.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
     #[test]
     fn extracts_api_error_message_from_response_error() {
         let event = json!({
@@ -610,4 +738,129 @@ mod tests {
             Some("{\"city\":\"Beijing\"}")
         );
     }
+
+    #[tokio::test]
+    async fn streams_text_deltas_and_completed_usage_from_fixture() {
+        let events = collect_stream(TEXT_FIXTURE)
+            .await
+            .into_iter()
+            .map(|event| event.expect("ok response"))
+            .collect::<Vec<_>>();
+
+        let text = events
+            .iter()
+            .filter_map(|event| event.text.as_deref())
+            .collect::<String>();
+        // The full message on `output_item.done` must not duplicate the streamed deltas.
+        assert_eq!(text, "Hello, world");
+
+        let last = events.last().expect("completed event");
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+        let usage = last.usage.as_ref().expect("usage");
+        assert_eq!(usage.prompt_token_count, 12);
+        assert_eq!(usage.candidates_token_count, 3);
+        assert_eq!(usage.total_token_count, 15);
+        assert_eq!(usage.cached_content_token_count, Some(4));
+    }
+
+    #[tokio::test]
+    async fn streams_function_call_arguments_and_fills_tail_on_completed() {
+        let events = collect_stream(TOOL_CALL_FIXTURE)
+            .await
+            .into_iter()
+            .map(|event| event.expect("ok response"))
+            .collect::<Vec<_>>();
+
+        let tool_calls = events
+            .iter()
+            .filter_map(|event| event.tool_call.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(tool_calls[0].id.as_deref(), Some("call_1"));
+        assert_eq!(tool_calls[0].name.as_deref(), Some("get_weather"));
+        assert!(tool_calls[1..].iter().all(|tc| tc.id.is_none()));
+        assert!(tool_calls.iter().all(|tc| tc.index == Some(0)));
+        let arguments = tool_calls
+            .iter()
+            .filter_map(|tc| tc.arguments.as_deref())
+            .collect::<String>();
+        assert_eq!(arguments, "{\"city\":\"Beijing\"}");
+
+        let last = events.last().expect("completed event");
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+        assert_eq!(last.usage.as_ref().map(|u| u.total_token_count), Some(28));
+    }
+
+    #[tokio::test]
+    async fn stream_closed_before_completion_is_an_error() {
+        const TRUNCATED: &str = "\
+event: response.output_text.delta
+data: {\"type\":\"response.output_text.delta\",\"output_index\":0,\"delta\":\"Hel\"}
+
+";
+        let events = collect_stream(TRUNCATED).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].as_ref().expect("text delta").text.as_deref(),
+            Some("Hel")
+        );
+        assert!(events[1].is_err());
+    }
+
+    #[test]
+    fn parses_non_streaming_response_output_items() {
+        let responses = parse_responses_completion(json!({
+            "id": "resp_3",
+            "status": "completed",
+            "output": [
+                { "type": "reasoning", "summary": [] },
+                {
+                    "type": "message",
+                    "role": "assistant",
+                    "content": [{ "type": "output_text", "text": "Checking." }]
+                },
+                {
+                    "type": "function_call",
+                    "call_id": "call_9",
+                    "name": "read_file",
+                    "arguments": "{\"path\":\"a.rs\"}"
+                }
+            ],
+            "usage": { "input_tokens": 5, "output_tokens": 7, "total_tokens": 12 }
+        }))
+        .expect("parsed");
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].text.as_deref(), Some("Checking."));
+        let tool_call = responses[1].tool_call.as_ref().expect("tool call");
+        assert_eq!(tool_call.id.as_deref(), Some("call_9"));
+        assert_eq!(tool_call.index, Some(2));
+        assert_eq!(responses[2].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(
+            responses[2].usage.as_ref().map(|u| u.total_token_count),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn non_streaming_incomplete_and_error_bodies() {
+        let responses = parse_responses_completion(json!({
+            "id": "resp_4",
+            "status": "incomplete",
+            "incomplete_details": { "reason": "max_output_tokens" },
+            "output": []
+        }))
+        .expect("parsed");
+        assert_eq!(
+            responses[0].finish_reason.as_deref(),
+            Some("incomplete:max_output_tokens")
+        );
+
+        let error = parse_responses_completion(json!({
+            "id": "resp_5",
+            "status": "failed",
+            "error": { "message": "quota exceeded" }
+        }))
+        .expect_err("error body");
+        assert!(error.to_string().contains("quota exceeded"));
+    }
 }
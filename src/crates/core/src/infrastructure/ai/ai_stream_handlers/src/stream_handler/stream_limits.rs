@@ -0,0 +1,148 @@
+use futures::{Stream, StreamExt};
+use std::time::Duration;
+use tokio::time::{sleep, sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Idle timeout used when the model config does not set one
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Time limits and cancellation applied while reading an SSE stream
+#[derive(Debug, Clone)]
+pub struct StreamLimits {
+    /// Max wait between two consecutive SSE events
+    pub idle_timeout: Duration,
+    /// Max total streaming duration, measured from when reading starts; `None` means no cap
+    pub deadline: Option<Duration>,
+    /// Closes the HTTP stream as soon as the turn is aborted
+    pub cancel_token: Option<CancellationToken>,
+}
+
+impl Default for StreamLimits {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            deadline: None,
+            cancel_token: None,
+        }
+    }
+}
+
+/// Why reading the stream stopped before it ended on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum StreamInterrupt {
+    IdleTimeout(Duration),
+    DeadlineExceeded(Duration),
+    Cancelled,
+}
+
+impl StreamInterrupt {
+    /// Reason recorded in the stream stats summary
+    pub(super) fn summary_reason(&self) -> &'static str {
+        match self {
+            Self::IdleTimeout(_) => "sse_stream_timeout",
+            Self::DeadlineExceeded(_) => "sse_stream_deadline_exceeded",
+            Self::Cancelled => "sse_stream_cancelled",
+        }
+    }
+
+    /// Error forwarded to the consumer; cancellation is not an error, the consumer already knows
+    pub(super) fn error_message(&self, prefix: &str) -> Option<String> {
+        match self {
+            Self::IdleTimeout(idle) => Some(format!(
+                "{} idle timeout: no event received for {}s",
+                prefix,
+                idle.as_secs()
+            )),
+            Self::DeadlineExceeded(deadline) => Some(format!(
+                "{} deadline exceeded: response not completed within {}s",
+                prefix,
+                deadline.as_secs()
+            )),
+            Self::Cancelled => None,
+        }
+    }
+}
+
+impl StreamLimits {
+    /// Absolute deadline for a stream that starts now
+    pub(super) fn deadline_at(&self) -> Option<Instant> {
+        self.deadline.map(|deadline| Instant::now() + deadline)
+    }
+
+    /// Wait for the next stream item, giving up on cancellation, deadline or idle timeout
+    pub(super) async fn next_item<S>(
+        &self,
+        stream: &mut S,
+        deadline_at: Option<Instant>,
+    ) -> Result<Option<S::Item>, StreamInterrupt>
+    where
+        S: Stream + Unpin,
+    {
+        let cancelled = async {
+            match &self.cancel_token {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        let deadline_reached = async {
+            match deadline_at {
+                Some(at) => sleep_until(at).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            _ = cancelled => Err(StreamInterrupt::Cancelled),
+            _ = deadline_reached => Err(StreamInterrupt::DeadlineExceeded(
+                self.deadline.unwrap_or_default(),
+            )),
+            item = stream.next() => Ok(item),
+            _ = sleep(self.idle_timeout) => Err(StreamInterrupt::IdleTimeout(self.idle_timeout)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn idle_timeout_and_deadline_are_reported_separately() {
+        let limits = StreamLimits {
+            idle_timeout: Duration::from_millis(20),
+            deadline: Some(Duration::from_secs(30)),
+            cancel_token: None,
+        };
+        let mut silent = futures::stream::pending::<()>();
+
+        let deadline_at = limits.deadline_at();
+        assert_eq!(
+            limits.next_item(&mut silent, deadline_at).await,
+            Err(StreamInterrupt::IdleTimeout(Duration::from_millis(20)))
+        );
+
+        let expired = Some(Instant::now());
+        assert_eq!(
+            limits.next_item(&mut silent, expired).await,
+            Err(StreamInterrupt::DeadlineExceeded(Duration::from_secs(30)))
+        );
+    }
+
+    #[tokio::test]
+    async fn cancellation_wins_over_pending_stream() {
+        let token = CancellationToken::new();
+        let limits = StreamLimits {
+            cancel_token: Some(token.clone()),
+            ..Default::default()
+        };
+        token.cancel();
+
+        let mut silent = futures::stream::pending::<()>();
+        assert_eq!(
+            limits.next_item(&mut silent, None).await,
+            Err(StreamInterrupt::Cancelled)
+        );
+        assert_eq!(StreamInterrupt::Cancelled.error_message("SSE stream"), None);
+    }
+}
@@ -80,6 +80,10 @@ impl StreamStats {
             self.increment("out:thinking_signature");
             classified = true;
         }
+        if response.server_tool.is_some() {
+            self.increment("out:server_tool");
+            classified = true;
+        }
         if response.provider_metadata.is_some() {
             self.increment("out:provider_metadata");
             classified = true;
@@ -1,5 +1,6 @@
-use super::unified::{UnifiedResponse, UnifiedTokenUsage, UnifiedToolCall};
+use super::unified::{UnifiedResponse, UnifiedServerToolEvent, UnifiedTokenUsage, UnifiedToolCall};
 use serde::Deserialize;
+use serde_json::Value;
 
 #[derive(Debug, Deserialize)]
 pub struct MessageStart {
@@ -17,6 +18,12 @@ pub struct Usage {
     output_tokens: Option<u32>,
     cache_read_input_tokens: Option<u32>,
     cache_creation_input_tokens: Option<u32>,
+    server_tool_use: Option<ServerToolUsage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerToolUsage {
+    web_search_requests: Option<u32>,
 }
 
 impl Default for Usage {
@@ -26,6 +33,7 @@ impl Default for Usage {
             output_tokens: None,
             cache_read_input_tokens: None,
             cache_creation_input_tokens: None,
+            server_tool_use: None,
         }
     }
 }
@@ -44,6 +52,9 @@ impl Usage {
         if other.cache_creation_input_tokens.is_some() {
             self.cache_creation_input_tokens = other.cache_creation_input_tokens;
         }
+        if other.server_tool_use.is_some() {
+            self.server_tool_use = other.server_tool_use.clone();
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -51,6 +62,7 @@ impl Usage {
             && self.output_tokens.is_none()
             && self.cache_read_input_tokens.is_none()
             && self.cache_creation_input_tokens.is_none()
+            && self.server_tool_use.is_none()
     }
 }
 
@@ -65,13 +77,12 @@ impl From<Usage> for UnifiedTokenUsage {
             candidates_token_count,
             total_token_count: prompt_token_count + candidates_token_count,
             reasoning_token_count: None,
-            cached_content_token_count: match (
-                value.cache_read_input_tokens,
-                value.cache_creation_input_tokens,
-            ) {
-                (None, None) => None,
-                (read, creation) => Some(read.unwrap_or(0) + creation.unwrap_or(0)),
-            },
+            // Only cache hits count as cached content, matching the OpenAI and Gemini fields;
+            // cache writes stay part of the prompt count
+            cached_content_token_count: value.cache_read_input_tokens,
+            web_search_requests: value
+                .server_tool_use
+                .and_then(|server_tool_use| server_tool_use.web_search_requests),
         }
     }
 }
@@ -98,6 +109,7 @@ impl From<MessageDelta> for UnifiedResponse {
             usage: value.usage.map(UnifiedTokenUsage::from),
             finish_reason: value.delta.stop_reason,
             provider_metadata: None,
+            server_tool: None,
         }
     }
 }
@@ -116,6 +128,16 @@ pub enum ContentBlock {
     Text,
     #[serde(rename = "tool_use")]
     ToolUse { id: String, name: String },
+    /// Call of a tool Anthropic runs itself; its input streams as `input_json_delta`
+    #[serde(rename = "server_tool_use")]
+    ServerToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    #[serde(rename = "web_search_tool_result")]
+    WebSearchToolResult { tool_use_id: String, content: Value },
     #[serde(other)]
     Unknown,
 }
@@ -129,9 +151,20 @@ impl From<ContentBlockStart> for UnifiedResponse {
                     id: Some(id),
                     name: Some(name),
                     arguments: None,
+                    index: None,
                 };
                 result.tool_call = Some(tool_call);
             }
+            ContentBlock::WebSearchToolResult {
+                tool_use_id,
+                content,
+            } => {
+                result.server_tool = Some(UnifiedServerToolEvent::Result {
+                    tool_use_id,
+                    name: "web_search".to_string(),
+                    content,
+                });
+            }
             _ => {}
         }
         result
@@ -140,7 +173,7 @@ impl From<ContentBlockStart> for UnifiedResponse {
 
 #[derive(Debug, Deserialize)]
 pub struct ContentBlockDelta {
-    delta: Delta,
+    pub delta: Delta,
 }
 
 #[derive(Debug, Deserialize)]
@@ -154,6 +187,8 @@ pub enum Delta {
     InputJsonDelta { partial_json: String },
     #[serde(rename = "signature_delta")]
     SignatureDelta { signature: String },
+    #[serde(rename = "citations_delta")]
+    CitationsDelta { citation: Value },
     #[serde(other)]
     Unknown,
 }
@@ -174,12 +209,16 @@ impl TryFrom<ContentBlockDelta> for UnifiedResponse {
                     id: None,
                     name: None,
                     arguments: Some(partial_json),
+                    index: None,
                 };
                 result.tool_call = Some(tool_call);
             }
             Delta::SignatureDelta { signature } => {
                 result.thinking_signature = Some(signature);
             }
+            Delta::CitationsDelta { citation } => {
+                result.server_tool = Some(UnifiedServerToolEvent::Citation { citation });
+            }
             Delta::Unknown => {
                 return Err("Unsupported anthropic delta type".to_string());
             }
@@ -205,3 +244,73 @@ impl From<AnthropicSSEErrorDetails> for String {
         format!("{}: {}", value.error_type, value.message)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_search_result_block_becomes_server_tool_result() {
+        let start: ContentBlockStart = serde_json::from_value(serde_json::json!({
+            "index": 1,
+            "content_block": {
+                "type": "web_search_tool_result",
+                "tool_use_id": "srvtoolu_1",
+                "content": [{ "type": "web_search_result", "url": "https://example.com" }]
+            }
+        }))
+        .unwrap();
+
+        let response = UnifiedResponse::from(start);
+        let Some(UnifiedServerToolEvent::Result {
+            tool_use_id,
+            name,
+            content,
+        }) = response.server_tool
+        else {
+            panic!("expected a server tool result");
+        };
+        assert_eq!(tool_use_id, "srvtoolu_1");
+        assert_eq!(name, "web_search");
+        assert_eq!(content[0]["url"], "https://example.com");
+    }
+
+    #[test]
+    fn citations_delta_is_kept() {
+        let delta: ContentBlockDelta = serde_json::from_value(serde_json::json!({
+            "index": 2,
+            "delta": {
+                "type": "citations_delta",
+                "citation": { "type": "web_search_result_location", "url": "https://example.com" }
+            }
+        }))
+        .unwrap();
+
+        let response = UnifiedResponse::try_from(delta).unwrap();
+        assert!(matches!(
+            response.server_tool,
+            Some(UnifiedServerToolEvent::Citation { citation })
+                if citation["url"] == "https://example.com"
+        ));
+    }
+
+    #[test]
+    fn usage_counts_web_search_requests() {
+        let mut usage = Usage::default();
+        usage.update(
+            &serde_json::from_value(serde_json::json!({ "input_tokens": 100, "output_tokens": 1 }))
+                .unwrap(),
+        );
+        usage.update(
+            &serde_json::from_value(serde_json::json!({
+                "output_tokens": 40,
+                "server_tool_use": { "web_search_requests": 2 }
+            }))
+            .unwrap(),
+        );
+
+        let unified = UnifiedTokenUsage::from(usage);
+        assert_eq!(unified.total_token_count, 140);
+        assert_eq!(unified.web_search_requests, Some(2));
+    }
+}
@@ -104,6 +104,7 @@ impl From<GeminiUsageMetadata> for UnifiedTokenUsage {
             total_token_count: usage.total_token_count,
             reasoning_token_count,
             cached_content_token_count: usage.cached_content_token_count,
+            web_search_requests: None,
         }
     }
 }
@@ -363,10 +364,12 @@ impl GeminiSSEData {
                             id: None,
                             name: function_call.name,
                             arguments: serde_json::to_string(&arguments).ok(),
+                            index: None,
                         }),
                         usage: usage.take(),
                         finish_reason: finish_reason.take(),
                         provider_metadata: None,
+                        server_tool: None,
                     });
                     continue;
                 }
@@ -381,6 +384,7 @@ impl GeminiSSEData {
                             usage: usage.take(),
                             finish_reason: finish_reason.take(),
                             provider_metadata: None,
+                            server_tool: None,
                         });
                         continue;
                     }
@@ -398,6 +402,7 @@ impl GeminiSSEData {
                             usage: usage.take(),
                             finish_reason: finish_reason.take(),
                             provider_metadata: None,
+                            server_tool: None,
                         });
                         continue;
                     }
@@ -412,6 +417,7 @@ impl GeminiSSEData {
                         usage: usage.take(),
                         finish_reason: finish_reason.take(),
                         provider_metadata: None,
+                        server_tool: None,
                     });
                     continue;
                 }
@@ -425,6 +431,7 @@ impl GeminiSSEData {
                         usage: usage.take(),
                         finish_reason: finish_reason.take(),
                         provider_metadata: None,
+                        server_tool: None,
                     });
                 }
             }
@@ -459,6 +466,7 @@ impl GeminiSSEData {
                 usage: usage.take(),
                 finish_reason: finish_reason.take(),
                 provider_metadata: Some(provider_metadata),
+                server_tool: None,
             });
         }
 
@@ -27,6 +27,7 @@ impl From<OpenAIUsage> for UnifiedTokenUsage {
             cached_content_token_count: usage
                 .prompt_tokens_details
                 .and_then(|prompt_tokens_details| prompt_tokens_details.cached_tokens),
+            web_search_requests: None,
         }
     }
 }
@@ -62,7 +63,6 @@ struct Delta {
 
 #[derive(Debug, Deserialize, Clone)]
 struct OpenAIToolCall {
-    #[allow(dead_code)]
     index: usize,
     #[allow(dead_code)]
     id: Option<String>,
@@ -81,6 +81,7 @@ impl From<OpenAIToolCall> for UnifiedToolCall {
                 .function
                 .as_ref()
                 .and_then(|f| f.arguments.clone()),
+            index: Some(tool_call.index),
         }
     }
 }
@@ -178,6 +179,7 @@ impl OpenAISSEData {
                 usage: usage.take(),
                 finish_reason: finish_reason.take(),
                 provider_metadata: None,
+                server_tool: None,
             });
         }
 
@@ -196,6 +198,7 @@ impl OpenAISSEData {
                         None
                     },
                     provider_metadata: None,
+                    server_tool: None,
                 });
             }
         }
@@ -209,6 +212,7 @@ impl OpenAISSEData {
                 usage,
                 finish_reason,
                 provider_metadata: None,
+                server_tool: None,
             });
         }
 
@@ -295,6 +299,34 @@ mod tests {
         assert!(responses[1].usage.is_none());
     }
 
+    #[test]
+    fn keeps_tool_call_index_on_id_less_fragments() {
+        // Second fragment of an interleaved parallel call: only `index` identifies the call
+        let raw = r#"{
+            "id": "chatcmpl_test",
+            "created": 123,
+            "model": "gpt-test",
+            "choices": [{
+                "index": 0,
+                "delta": {
+                    "tool_calls": [{
+                        "index": 1,
+                        "function": { "arguments": "{\"pattern\":" }
+                    }]
+                },
+                "finish_reason": null
+            }]
+        }"#;
+
+        let sse_data: OpenAISSEData = serde_json::from_str(raw).expect("valid openai sse data");
+        let responses = sse_data.into_unified_responses();
+        let tool_call = responses[0].tool_call.as_ref().expect("tool call");
+
+        assert_eq!(tool_call.index, Some(1));
+        assert!(tool_call.id.is_none());
+        assert_eq!(tool_call.arguments.as_deref(), Some("{\"pattern\":"));
+    }
+
     #[test]
     fn handles_empty_choices_with_usage_chunk() {
         let raw = r#"{
@@ -62,6 +62,7 @@ impl From<ResponsesUsage> for UnifiedTokenUsage {
             cached_content_token_count: usage
                 .input_tokens_details
                 .map(|details| details.cached_tokens),
+            web_search_requests: None,
         }
     }
 }
@@ -87,10 +88,12 @@ pub fn parse_responses_output_item(item_value: Value) -> Option<UnifiedResponse>
                     .get("arguments")
                     .and_then(Value::as_str)
                     .map(ToString::to_string),
+                index: None,
             }),
             usage: None,
             finish_reason: None,
             provider_metadata: None,
+            server_tool: None,
         }),
         "message" => {
             let text = item_value
@@ -115,6 +118,7 @@ pub fn parse_responses_output_item(item_value: Value) -> Option<UnifiedResponse>
                 usage: None,
                 finish_reason: None,
                 provider_metadata: None,
+                server_tool: None,
             })
         }
         _ => None,
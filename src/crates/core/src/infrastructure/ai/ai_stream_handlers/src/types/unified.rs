@@ -6,6 +6,32 @@ pub struct UnifiedToolCall {
     pub id: Option<String>,
     pub name: Option<String>,
     pub arguments: Option<String>,
+    /// Position of the call in the provider's output (OpenAI `index`, Responses
+    /// `output_index`); fragments of parallel calls may interleave and only the first carries
+    /// the id. None for providers that stream calls one after another.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+}
+
+/// Activity of a tool the provider runs itself during the response (Anthropic `web_search`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UnifiedServerToolEvent {
+    /// The model called a server tool
+    Use {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    /// What the provider returned for a server tool call; `content` is kept verbatim so the
+    /// call can be sent back in later requests
+    Result {
+        tool_use_id: String,
+        name: String,
+        content: Value,
+    },
+    /// A source the following answer text cites
+    Citation { citation: Value },
 }
 
 /// Unified AI response format
@@ -21,6 +47,8 @@ pub struct UnifiedResponse {
     pub finish_reason: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider_metadata: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_tool: Option<UnifiedServerToolEvent>,
 }
 
 impl Default for UnifiedResponse {
@@ -33,6 +61,7 @@ impl Default for UnifiedResponse {
             usage: None,
             finish_reason: None,
             provider_metadata: None,
+            server_tool: None,
         }
     }
 }
@@ -47,4 +76,7 @@ pub struct UnifiedTokenUsage {
     pub reasoning_token_count: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cached_content_token_count: Option<u32>,
+    /// Server-side web searches the provider ran and bills per request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search_requests: Option<u32>,
 }
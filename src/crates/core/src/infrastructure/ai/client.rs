@@ -2,23 +2,53 @@
 //!
 //! Uses a modular architecture to separate provider-specific logic into the providers module
 
+use crate::infrastructure::ai::embeddings::{
+    default_batch_size, resolve_embeddings_url, EmbeddingCache, EmbeddingResult, EmbeddingUsage,
+    EmbeddingsResponse,
+};
+use crate::infrastructure::ai::http_client_factory::{
+    get_global_http_client_factory, HttpClientOptions, HttpClientStats,
+};
 use crate::infrastructure::ai::providers::anthropic::AnthropicMessageConverter;
 use crate::infrastructure::ai::providers::gemini::GeminiMessageConverter;
 use crate::infrastructure::ai::providers::openai::OpenAIMessageConverter;
+use crate::infrastructure::ai::request_log;
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
+use crate::infrastructure::offline;
+use crate::infrastructure::secrets;
 use crate::service::config::ProxyConfig;
+use crate::util::errors::BitFunError;
 use crate::util::types::*;
-use crate::util::JsonChecker;
+use crate::util::{expand_env_vars, JsonChecker};
 use ai_stream_handlers::{
     handle_anthropic_stream, handle_gemini_stream, handle_openai_stream, handle_responses_stream,
+    parse_anthropic_message, parse_openai_completion, parse_responses_completion, StreamLimits,
     UnifiedResponse,
 };
 use anyhow::{anyhow, Result};
 use futures::StreamExt;
-use log::{debug, error, info, warn};
-use reqwest::{Client, Proxy};
+use log::{debug, error, warn};
+use rand::Rng;
+use reqwest::Client;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Per-request options for streaming dispatch
+#[derive(Debug, Clone, Default)]
+pub struct StreamRequestOptions {
+    /// Session the request belongs to (used to tag retry events)
+    pub session_id: Option<String>,
+    /// Dialog turn the request belongs to (used to tag retry events)
+    pub dialog_turn_id: Option<String>,
+    /// Aborts in-flight connection attempts, pending retry waits and the open stream
+    pub cancel_token: Option<CancellationToken>,
+    /// Overrides the model's streaming setting; `Some(false)` requests one complete JSON
+    /// response (OpenAI and Anthropic formats) that is replayed as a stream
+    pub streaming: Option<bool>,
+}
 
 /// Streamed response result with the parsed stream and optional raw SSE receiver
 pub struct StreamResponse {
@@ -26,12 +56,25 @@ pub struct StreamResponse {
     pub stream: std::pin::Pin<Box<dyn futures::Stream<Item = Result<UnifiedResponse>> + Send>>,
     /// Raw SSE receiver (for error diagnostics)
     pub raw_sse_rx: Option<mpsc::UnboundedReceiver<String>>,
+    /// Model that served the request when a fallback took over (None = configured model)
+    pub served_by_model: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct AIClient {
     client: Client,
+    /// Counters of the shared connection pool `client` belongs to
+    http_stats: Arc<HttpClientStats>,
     pub config: AIConfig,
+    /// Clients tried in order when this model fails before producing output
+    fallback_clients: Vec<Arc<AIClient>>,
+}
+
+/// API key and custom headers of one request, secret references resolved
+#[derive(Default)]
+struct RequestCredentials {
+    api_key: String,
+    custom_headers: Vec<(String, String)>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,9 +127,11 @@ impl AIClient {
     const TEST_IMAGE_EXPECTED_CODE: &'static str = "BYGR";
     const TEST_IMAGE_PNG_BASE64: &'static str =
         "iVBORw0KGgoAAAANSUhEUgAAAQAAAAEACAIAAADTED8xAAACBklEQVR42u3ZsREAIAwDMYf9dw4txwJupI7Wua+YZEPBfO91h4ZjAgQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABIAAQAAgABAACAAEAAIAAYAAQAAgABAACAAEAAIAAYAAQAAgABAAAAAAAEDRZI3QGf7jDvEPAAIAAYAAQAAgABAACAAEAAIAAYAAQAAgABAACAAEAAIAAYAAQAAgABAACAABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAACAAEAAIAAQAAgABgABAAAjABAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQAAgABAACAAGAAEAAIAAQALwuLkoG8OSfau4AAAAASUVORK5CYII=";
-    const STREAM_CONNECT_TIMEOUT_SECS: u64 = 10;
-    const HTTP_POOL_IDLE_TIMEOUT_SECS: u64 = 30;
-    const HTTP_TCP_KEEPALIVE_SECS: u64 = 60;
+    const DEFAULT_MAX_RETRIES: u32 = 3;
+    const RETRY_BASE_DELAY_MS: u64 = 1000;
+    const RETRY_MAX_DELAY_MS: u64 = 30_000;
+    const MAX_RETRY_AFTER_SECS: u64 = 60;
+    const DEFAULT_PROMPT_CACHE_MIN_TOKENS: u32 = 1024;
 
     fn image_test_response_matches_expected(response: &str) -> bool {
         let upper = response.to_ascii_uppercase();
@@ -227,7 +272,7 @@ impl AIClient {
     async fn list_openai_models(&self) -> Result<Vec<RemoteModelInfo>> {
         let url = self.resolve_openai_models_url();
         let response = self
-            .apply_openai_headers(self.client.get(&url))
+            .apply_openai_headers(self.client.get(&url), &self.request_credentials().await)
             .send()
             .await?
             .error_for_status()?;
@@ -248,7 +293,11 @@ impl AIClient {
     async fn list_anthropic_models(&self) -> Result<Vec<RemoteModelInfo>> {
         let url = self.resolve_anthropic_models_url();
         let response = self
-            .apply_anthropic_headers(self.client.get(&url), &url)
+            .apply_anthropic_headers(
+                self.client.get(&url),
+                &url,
+                &self.request_credentials().await,
+            )
             .send()
             .await?
             .error_for_status()?;
@@ -277,7 +326,7 @@ impl AIClient {
         debug!("Gemini models list URL: {}", url);
 
         let response = self
-            .apply_gemini_headers(self.client.get(&url))
+            .apply_gemini_headers(self.client.get(&url), &self.request_credentials().await)
             .send()
             .await?
             .error_for_status()?;
@@ -308,90 +357,56 @@ impl AIClient {
         ))
     }
 
-    /// Create an AIClient without proxy (backward compatible)
+    /// Create an AIClient without a global proxy (the model's own proxy still applies)
     pub fn new(config: AIConfig) -> Self {
-        let skip_ssl_verify = config.skip_ssl_verify;
-        let client = Self::create_http_client(None, skip_ssl_verify);
-        Self { client, config }
+        Self::new_with_proxy(config, None)
     }
 
-    /// Create an AIClient with proxy configuration
+    /// Create an AIClient with proxy configuration; the model's own proxy, when set, takes
+    /// precedence over `proxy_config`. A CA bundle on either one is trusted, the model's first.
     pub fn new_with_proxy(config: AIConfig, proxy_config: Option<ProxyConfig>) -> Self {
-        let skip_ssl_verify = config.skip_ssl_verify;
-        let client = Self::create_http_client(proxy_config, skip_ssl_verify);
-        Self { client, config }
-    }
-
-    /// Create an HTTP client (supports proxy config and SSL verification control)
-    fn create_http_client(proxy_config: Option<ProxyConfig>, skip_ssl_verify: bool) -> Client {
-        let mut builder = Client::builder()
-            // SSE requests can legitimately stay open for a long time while the model
-            // thinks or executes tools. Keep only connect timeout here and let the
-            // stream handlers enforce idle timeouts between chunks.
-            .connect_timeout(std::time::Duration::from_secs(
-                Self::STREAM_CONNECT_TIMEOUT_SECS,
-            ))
-            .user_agent("BitFun/1.0")
-            .pool_idle_timeout(std::time::Duration::from_secs(
-                Self::HTTP_POOL_IDLE_TIMEOUT_SECS,
-            ))
-            .pool_max_idle_per_host(4)
-            .tcp_keepalive(Some(std::time::Duration::from_secs(
-                Self::HTTP_TCP_KEEPALIVE_SECS,
-            )))
-            .danger_accept_invalid_certs(skip_ssl_verify);
-
-        if skip_ssl_verify {
-            warn!("SSL certificate verification disabled - security risk, use only in test environments");
-        }
-
-        // rustls mode does not support http2_keep_alive_interval/http2_keep_alive_timeout.
-        if let Some(proxy_cfg) = proxy_config {
-            if proxy_cfg.enabled && !proxy_cfg.url.is_empty() {
-                match Self::build_proxy(&proxy_cfg) {
-                    Ok(proxy) => {
-                        info!("Using proxy: {}", proxy_cfg.url);
-                        builder = builder.proxy(proxy);
-                    }
-                    Err(e) => {
-                        error!(
-                            "Proxy configuration failed: {}, proceeding without proxy",
-                            e
-                        );
-                        builder = builder.no_proxy();
-                    }
-                }
-            } else {
-                builder = builder.no_proxy();
-            }
-        } else {
-            builder = builder.no_proxy();
-        }
-
-        match builder.build() {
-            Ok(client) => client,
-            Err(e) => {
-                error!(
-                    "HTTP client initialization failed: {}, using default client",
-                    e
-                );
-                Client::new()
-            }
+        let global_ca_bundle = proxy_config
+            .as_ref()
+            .and_then(|proxy| proxy.ca_bundle_path.clone());
+        let proxy_config = config.proxy.clone().or(proxy_config);
+        let ca_bundle_path = proxy_config
+            .as_ref()
+            .and_then(|proxy| proxy.ca_bundle_path.clone())
+            .or(global_ca_bundle);
+
+        let pooled = get_global_http_client_factory().client_for(&HttpClientOptions {
+            base_url: &config.base_url,
+            proxy: proxy_config.as_ref(),
+            custom_headers: config.custom_headers.as_ref(),
+            skip_ssl_verify: config.skip_ssl_verify,
+            ca_bundle_path: ca_bundle_path.as_deref(),
+        });
+        Self {
+            client: pooled.client,
+            http_stats: pooled.stats,
+            config,
+            fallback_clients: Vec::new(),
         }
     }
 
-    fn build_proxy(config: &ProxyConfig) -> Result<Proxy> {
-        let mut proxy =
-            Proxy::all(&config.url).map_err(|e| anyhow!("Failed to create proxy: {}", e))?;
+    /// Attach the fallback chain tried when this model fails (non-retryable error or
+    /// exhausted retries). Fallback clients are used as-is; their own chains are not followed.
+    pub fn with_fallbacks(mut self, fallback_clients: Vec<Arc<AIClient>>) -> Self {
+        self.fallback_clients = fallback_clients;
+        self
+    }
 
-        if let (Some(username), Some(password)) = (&config.username, &config.password) {
-            if !username.is_empty() && !password.is_empty() {
-                proxy = proxy.basic_auth(username, password);
-                debug!("Proxy authentication configured for user: {}", username);
-            }
+    /// Pricing of the model that served a request: this model, or the fallback named by
+    /// `served_by_model`
+    pub fn pricing_for(&self, served_by_model: Option<&str>) -> Option<&ModelPricing> {
+        match served_by_model {
+            None => self.config.pricing.as_ref(),
+            Some(model) => self
+                .fallback_clients
+                .iter()
+                .find(|fallback| fallback.config.model == model)
+                .and_then(|fallback| fallback.config.pricing.as_ref()),
         }
-
-        Ok(proxy)
     }
 
     fn get_api_format(&self) -> &str {
@@ -425,6 +440,7 @@ impl AIClient {
         model_name: &str,
         api_format: &str,
         max_tokens: Option<u32>,
+        budget_tokens: Option<u32>,
     ) {
         if Self::is_dashscope_url(url) && api_format.eq_ignore_ascii_case("openai") {
             request_body["enable_thinking"] = serde_json::json!(enable);
@@ -443,11 +459,9 @@ impl AIClient {
                     "type".to_string(),
                     serde_json::Value::String("enabled".to_string()),
                 );
-                if let Some(m) = max_tokens {
-                    obj.insert(
-                        "budget_tokens".to_string(),
-                        serde_json::json!(10000u32.min(m * 3 / 4)),
-                    );
+                if let Some(budget) = budget_tokens.or(max_tokens.map(|m| 10000u32.min(m * 3 / 4)))
+                {
+                    obj.insert("budget_tokens".to_string(), serde_json::json!(budget));
                 }
                 serde_json::Value::Object(obj)
             } else {
@@ -513,16 +527,71 @@ impl AIClient {
         self.config.custom_headers_mode.as_deref() != Some("replace")
     }
 
+    /// Credentials for the next request, with `keyring:` and `${VAR}` references
+    /// resolved. Resolved per request so a rotated key is used without
+    /// recreating the client; keyring lookups block (D-Bus, file decrypt), so
+    /// they run on the blocking pool instead of the async runtime.
+    async fn request_credentials(&self) -> RequestCredentials {
+        let api_key = self.config.api_key.clone();
+        let custom_headers: Vec<(String, String)> = self
+            .config
+            .custom_headers
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let resolve = move || RequestCredentials {
+            api_key: secrets::resolve_secret_or_empty(&api_key),
+            custom_headers: custom_headers
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match secrets::parse_secret_ref(&value) {
+                        Some(_) => secrets::resolve_secret_or_empty(&value),
+                        None => expand_env_vars(&value),
+                    };
+                    (key, value)
+                })
+                .collect(),
+        };
+
+        let has_secret_ref = secrets::parse_secret_ref(&self.config.api_key).is_some()
+            || self
+                .config
+                .custom_headers
+                .iter()
+                .flatten()
+                .any(|(_, value)| secrets::parse_secret_ref(value).is_some());
+        if !has_secret_ref {
+            return resolve();
+        }
+        tokio::task::spawn_blocking(resolve)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to resolve request credentials: {}", e);
+                RequestCredentials::default()
+            })
+    }
+
     /// Apply custom headers to the builder
     fn apply_custom_headers(
         &self,
         mut builder: reqwest::RequestBuilder,
+        credentials: &RequestCredentials,
     ) -> reqwest::RequestBuilder {
-        if let Some(custom_headers) = &self.config.custom_headers {
-            if !custom_headers.is_empty() {
-                for (key, value) in custom_headers {
-                    builder = builder.header(key.as_str(), value.as_str());
-                }
+        if !credentials.custom_headers.is_empty() {
+            // Values may carry credentials; only names are logged
+            debug!(
+                "Applying custom headers: model={}, headers=[{}]",
+                self.config.model,
+                credentials
+                    .custom_headers
+                    .iter()
+                    .map(|(key, _)| format!("{}: [REDACTED]", key))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            for (key, value) in &credentials.custom_headers {
+                builder = builder.header(key.as_str(), value.as_str());
             }
         }
         builder
@@ -532,6 +601,7 @@ impl AIClient {
     fn apply_openai_headers(
         &self,
         mut builder: reqwest::RequestBuilder,
+        credentials: &RequestCredentials,
     ) -> reqwest::RequestBuilder {
         let has_custom_headers = self
             .config
@@ -541,19 +611,19 @@ impl AIClient {
         let is_merge_mode = self.is_merge_headers_mode();
 
         if has_custom_headers && !is_merge_mode {
-            return self.apply_custom_headers(builder);
+            return self.apply_custom_headers(builder, credentials);
         }
 
         builder = builder
             .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.config.api_key));
+            .header("Authorization", format!("Bearer {}", credentials.api_key));
 
         if self.config.base_url.contains("openbitfun.com") {
             builder = builder.header("X-Verification-Code", "from_bitfun");
         }
 
         if has_custom_headers && is_merge_mode {
-            builder = self.apply_custom_headers(builder);
+            builder = self.apply_custom_headers(builder, credentials);
         }
 
         builder
@@ -564,6 +634,7 @@ impl AIClient {
         &self,
         mut builder: reqwest::RequestBuilder,
         url: &str,
+        credentials: &RequestCredentials,
     ) -> reqwest::RequestBuilder {
         let has_custom_headers = self
             .config
@@ -573,16 +644,16 @@ impl AIClient {
         let is_merge_mode = self.is_merge_headers_mode();
 
         if has_custom_headers && !is_merge_mode {
-            return self.apply_custom_headers(builder);
+            return self.apply_custom_headers(builder, credentials);
         }
 
         builder = builder.header("Content-Type", "application/json");
 
         if url.contains("bigmodel.cn") {
-            builder = builder.header("Authorization", format!("Bearer {}", self.config.api_key));
+            builder = builder.header("Authorization", format!("Bearer {}", credentials.api_key));
         } else {
             builder = builder
-                .header("x-api-key", &self.config.api_key)
+                .header("x-api-key", &credentials.api_key)
                 .header("anthropic-version", "2023-06-01");
         }
 
@@ -591,7 +662,7 @@ impl AIClient {
         }
 
         if has_custom_headers && is_merge_mode {
-            builder = self.apply_custom_headers(builder);
+            builder = self.apply_custom_headers(builder, credentials);
         }
 
         builder
@@ -601,6 +672,7 @@ impl AIClient {
     fn apply_gemini_headers(
         &self,
         mut builder: reqwest::RequestBuilder,
+        credentials: &RequestCredentials,
     ) -> reqwest::RequestBuilder {
         let has_custom_headers = self
             .config
@@ -610,23 +682,20 @@ impl AIClient {
         let is_merge_mode = self.is_merge_headers_mode();
 
         if has_custom_headers && !is_merge_mode {
-            return self.apply_custom_headers(builder);
+            return self.apply_custom_headers(builder, credentials);
         }
 
         builder = builder
             .header("Content-Type", "application/json")
-            .header("x-goog-api-key", &self.config.api_key)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.config.api_key),
-            );
+            .header("x-goog-api-key", &credentials.api_key)
+            .header("Authorization", format!("Bearer {}", credentials.api_key));
 
         if self.config.base_url.contains("openbitfun.com") {
             builder = builder.header("X-Verification-Code", "from_bitfun");
         }
 
         if has_custom_headers && is_merge_mode {
-            builder = self.apply_custom_headers(builder);
+            builder = self.apply_custom_headers(builder, credentials);
         }
 
         builder
@@ -813,6 +882,7 @@ impl AIClient {
             total_token_count: usage.total_token_count,
             reasoning_token_count: usage.reasoning_token_count,
             cached_content_token_count: usage.cached_content_token_count,
+            web_search_requests: usage.web_search_requests,
         }
     }
 
@@ -843,8 +913,15 @@ impl AIClient {
             &model_name,
             "openai",
             self.config.max_tokens,
+            self.config.thinking_budget_tokens,
         );
 
+        if self.config.enable_thinking_process {
+            if let Some(ref effort) = self.config.reasoning_effort {
+                request_body["reasoning_effort"] = serde_json::json!(effort);
+            }
+        }
+
         if let Some(max_tokens) = self.config.max_tokens {
             request_body["max_tokens"] = serde_json::json!(max_tokens);
         }
@@ -972,7 +1049,7 @@ impl AIClient {
         url: &str,
         system_message: Option<String>,
         anthropic_messages: Vec<serde_json::Value>,
-        anthropic_tools: Option<Vec<serde_json::Value>>,
+        mut anthropic_tools: Option<Vec<serde_json::Value>>,
         extra_body: Option<serde_json::Value>,
     ) -> serde_json::Value {
         let max_tokens = self.config.max_tokens.unwrap_or(8192);
@@ -998,6 +1075,7 @@ impl AIClient {
             &model_name,
             "anthropic",
             Some(max_tokens),
+            self.config.thinking_budget_tokens,
         );
 
         if let Some(system) = system_message {
@@ -1018,6 +1096,12 @@ impl AIClient {
             serde_json::to_string_pretty(&request_body).unwrap_or_else(|_| "serialization failed".to_string())
         );
 
+        if self.config.uses_native_web_search() {
+            anthropic_tools
+                .get_or_insert_with(Vec::new)
+                .push(AnthropicMessageConverter::web_search_tool());
+        }
+
         if let Some(tools) = anthropic_tools {
             let tool_names = tools
                 .iter()
@@ -1029,6 +1113,18 @@ impl AIClient {
             }
         }
 
+        if self.config.enable_prompt_caching {
+            let min_tokens = self
+                .config
+                .prompt_cache_min_tokens
+                .unwrap_or(Self::DEFAULT_PROMPT_CACHE_MIN_TOKENS);
+            let breakpoints = AnthropicMessageConverter::apply_prompt_caching(
+                &mut request_body,
+                min_tokens as usize,
+            );
+            debug!(target: "ai::anthropic_stream_request", "Prompt cache breakpoints: {}", breakpoints);
+        }
+
         request_body
     }
 
@@ -1186,9 +1282,11 @@ impl AIClient {
         u.trim_end_matches('/')
     }
 
+    /// Tool name from either the Chat Completions (`function.name`) or Responses (`name`) shape.
     fn extract_openai_tool_name(tool: &serde_json::Value) -> String {
         tool.get("function")
             .and_then(|f| f.get("name"))
+            .or_else(|| tool.get("name"))
             .and_then(|n| n.as_str())
             .unwrap_or("unknown")
             .to_string()
@@ -1210,9 +1308,23 @@ impl AIClient {
         &self,
         messages: Vec<Message>,
         tools: Option<Vec<ToolDefinition>>,
+    ) -> Result<StreamResponse> {
+        self.send_message_stream_with_options(messages, tools, StreamRequestOptions::default())
+            .await
+    }
+
+    /// Send a streaming message request with per-request options
+    ///
+    /// `options` tags retry events with the owning session and lets the caller abort
+    /// pending retries through its cancellation token.
+    pub async fn send_message_stream_with_options(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        options: StreamRequestOptions,
     ) -> Result<StreamResponse> {
         let custom_body = self.config.custom_request_body.clone();
-        self.send_message_stream_with_extra_body(messages, tools, custom_body)
+        self.dispatch_message_stream(messages, tools, custom_body, &options)
             .await
     }
 
@@ -1227,164 +1339,548 @@ impl AIClient {
         tools: Option<Vec<ToolDefinition>>,
         extra_body: Option<serde_json::Value>,
     ) -> Result<StreamResponse> {
-        let max_tries = 3;
-        match self.get_api_format().to_lowercase().as_str() {
+        self.dispatch_message_stream(
+            messages,
+            tools,
+            extra_body,
+            &StreamRequestOptions::default(),
+        )
+        .await
+    }
+
+    /// Dispatch to this model, then walk the fallback chain if it fails before any output.
+    async fn dispatch_message_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        extra_body: Option<serde_json::Value>,
+        options: &StreamRequestOptions,
+    ) -> Result<StreamResponse> {
+        if self.fallback_clients.is_empty() {
+            return self
+                .dispatch_to_provider(messages, tools, extra_body, options)
+                .await;
+        }
+
+        let mut last_error = match self
+            .dispatch_to_provider(messages.clone(), tools.clone(), extra_body, options)
+            .await
+        {
+            Ok(response) => return Ok(response),
+            Err(e) => e,
+        };
+        let mut failed_model = self.config.model.clone();
+        let needs_tools = tools.as_ref().is_some_and(|t| !t.is_empty());
+
+        for fallback in &self.fallback_clients {
+            if options
+                .cancel_token
+                .as_ref()
+                .is_some_and(|token| token.is_cancelled())
+            {
+                break;
+            }
+
+            if needs_tools && !fallback.config.supports_tools {
+                debug!(
+                    "Skipping fallback model without tool support: model={}",
+                    fallback.config.model
+                );
+                continue;
+            }
+
+            warn!(
+                "Falling back to model {} after {} failed: {}",
+                fallback.config.model, failed_model, last_error
+            );
+            self.notify_fallback(options, &failed_model, &fallback.config.model, &last_error)
+                .await;
+
+            match fallback
+                .dispatch_to_provider(
+                    messages.clone(),
+                    tools.clone(),
+                    fallback.config.custom_request_body.clone(),
+                    options,
+                )
+                .await
+            {
+                Ok(mut response) => {
+                    response.served_by_model = Some(fallback.config.model.clone());
+                    return Ok(response);
+                }
+                Err(e) => {
+                    failed_model = fallback.config.model.clone();
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    async fn notify_fallback(
+        &self,
+        options: &StreamRequestOptions,
+        from_model: &str,
+        to_model: &str,
+        error: &anyhow::Error,
+    ) {
+        let info = AIModelFallbackInfo {
+            session_id: options.session_id.clone(),
+            dialog_turn_id: options.dialog_turn_id.clone(),
+            from_model: from_model.to_string(),
+            to_model: to_model.to_string(),
+            error: error.to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        };
+
+        if let Err(e) = emit_global_event(BackendEvent::AIModelFallback(info)).await {
+            debug!("Failed to emit AI model fallback event: {}", e);
+        }
+    }
+
+    async fn dispatch_to_provider(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        extra_body: Option<serde_json::Value>,
+        options: &StreamRequestOptions,
+    ) -> Result<StreamResponse> {
+        let request_log_id =
+            request_log::log_request(&self.config.model, &messages, tools.as_deref());
+
+        let response = match self.get_api_format().to_lowercase().as_str() {
             "openai" => {
-                self.send_openai_stream(messages, tools, extra_body, max_tries)
+                self.send_openai_stream(messages, tools, extra_body, options)
                     .await
             }
             format if Self::is_gemini_api_format(format) => {
-                self.send_gemini_stream(messages, tools, extra_body, max_tries)
+                self.send_gemini_stream(messages, tools, extra_body, options)
                     .await
             }
             format if Self::is_responses_api_format(format) => {
-                self.send_responses_stream(messages, tools, extra_body, max_tries)
+                self.send_responses_stream(messages, tools, extra_body, options)
                     .await
             }
             "anthropic" => {
-                self.send_anthropic_stream(messages, tools, extra_body, max_tries)
+                self.send_anthropic_stream(messages, tools, extra_body, options)
                     .await
             }
             _ => Err(anyhow!("Unknown API format: {}", self.get_api_format())),
+        }?;
+
+        Ok(match request_log_id {
+            Some(id) => request_log::attach(id, &self.config.model, response),
+            None => response,
+        })
+    }
+
+    /// Idle timeout, overall deadline and cancellation applied while reading the stream
+    fn stream_limits(&self, options: &StreamRequestOptions) -> StreamLimits {
+        let defaults = StreamLimits::default();
+        StreamLimits {
+            idle_timeout: self
+                .config
+                .stream_idle_timeout_secs
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(defaults.idle_timeout),
+            deadline: self
+                .config
+                .stream_deadline_secs
+                .map(std::time::Duration::from_secs),
+            cancel_token: options.cancel_token.clone(),
         }
     }
 
-    /// Send an OpenAI streaming request with retries
+    /// Whether to request SSE; call-site options win over the model's `disable_streaming`
+    fn should_stream(&self, options: &StreamRequestOptions) -> bool {
+        options.streaming.unwrap_or(!self.config.disable_streaming)
+    }
+
+    /// Send a request with `stream: false` and replay the parsed body as a response stream.
     ///
-    /// # Parameters
-    /// - `messages`: message list
-    /// - `tools`: tool definitions
-    /// - `extra_body`: extra request body parameters
-    /// - `max_tries`: max attempts (including the first)
-    async fn send_openai_stream(
+    /// Uses the same retry/cancellation dispatch as streaming requests, so callers see the
+    /// same `StreamResponse` and the same sequence of events either way.
+    async fn send_completion_request<F, P>(
         &self,
-        messages: Vec<Message>,
-        tools: Option<Vec<ToolDefinition>>,
-        extra_body: Option<serde_json::Value>,
-        max_tries: usize,
-    ) -> Result<StreamResponse> {
-        let url = self.config.request_url.clone();
+        api_label: &str,
+        url: &str,
+        mut request_body: serde_json::Value,
+        apply_headers: F,
+        options: &StreamRequestOptions,
+        parse: P,
+    ) -> Result<StreamResponse>
+    where
+        F: Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+        P: FnOnce(serde_json::Value) -> Result<Vec<UnifiedResponse>>,
+    {
+        request_body["stream"] = serde_json::Value::Bool(false);
+        if let Some(request_obj) = request_body.as_object_mut() {
+            request_obj.remove("stream_options");
+            request_obj.remove("tool_stream");
+        }
+
+        let response = self
+            .dispatch_stream_request(api_label, url, &request_body, apply_headers, options)
+            .await?;
+        let read_body = response.text();
+        let body_text = match &options.cancel_token {
+            Some(token) => {
+                tokio::select! {
+                    _ = token.cancelled() => {
+                        return Err(anyhow!("{} request cancelled", api_label));
+                    }
+                    result = read_body => result,
+                }
+            }
+            None => read_body.await,
+        }
+        .map_err(|e| anyhow!("{} failed to read response body: {}", api_label, e))?;
+
+        let body: serde_json::Value = serde_json::from_str(&body_text).map_err(|e| {
+            anyhow!(
+                "{} returned invalid JSON: {}, data: {}",
+                api_label,
+                e,
+                body_text
+            )
+        })?;
+        let events = parse(body)?;
         debug!(
-            "OpenAI config: model={}, request_url={}, max_tries={}",
-            self.config.model, self.config.request_url, max_tries
+            "{} non-streaming response parsed into {} events",
+            api_label,
+            events.len()
         );
 
-        // Use OpenAI message converter
-        let openai_messages = OpenAIMessageConverter::convert_messages(messages);
-        let openai_tools = OpenAIMessageConverter::convert_tools(tools);
+        let (tx, rx) = mpsc::unbounded_channel();
+        for event in events {
+            let _ = tx.send(Ok(event));
+        }
+        let (tx_raw, rx_raw) = mpsc::unbounded_channel();
+        let _ = tx_raw.send(body_text);
 
-        // Build request body
-        let request_body =
-            self.build_openai_request_body(&url, openai_messages, openai_tools, extra_body);
+        Ok(StreamResponse {
+            stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
+            raw_sse_rx: Some(rx_raw),
+            served_by_model: None,
+        })
+    }
+
+    /// Max attempts per request (including the first)
+    fn max_request_attempts(&self) -> usize {
+        self.config.max_retries.unwrap_or(Self::DEFAULT_MAX_RETRIES) as usize + 1
+    }
+
+    /// Statuses worth retrying: rate limits, overload and transient gateway failures.
+    /// Other client errors are permanent and fail immediately.
+    fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+        matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504 | 529)
+    }
 
+    /// Parse the provider-suggested wait from `retry-after-ms` or `retry-after`
+    /// (delta-seconds or HTTP-date).
+    fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+        let header_str = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+
+        if let Some(ms) = header_str("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+            if ms.is_finite() && ms >= 0.0 {
+                return Some(std::time::Duration::from_millis(ms as u64));
+            }
+        }
+
+        let value = header_str("retry-after")?;
+        if let Ok(secs) = value.parse::<f64>() {
+            if secs.is_finite() && secs >= 0.0 {
+                return Some(std::time::Duration::from_millis((secs * 1000.0) as u64));
+            }
+            return None;
+        }
+
+        let retry_at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        let wait_ms = retry_at
+            .with_timezone(&chrono::Utc)
+            .signed_duration_since(chrono::Utc::now())
+            .num_milliseconds()
+            .max(0);
+        Some(std::time::Duration::from_millis(wait_ms as u64))
+    }
+
+    /// Delay before the next attempt: honor Retry-After when present, otherwise
+    /// exponential backoff with jitter. Both are capped.
+    fn retry_delay(
+        attempt: usize,
+        retry_after: Option<std::time::Duration>,
+    ) -> std::time::Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(std::time::Duration::from_secs(Self::MAX_RETRY_AFTER_SECS));
+        }
+
+        let backoff_ms =
+            (Self::RETRY_BASE_DELAY_MS << attempt.min(6)).min(Self::RETRY_MAX_DELAY_MS);
+        let jitter_ms = rand::thread_rng().gen_range(0..=backoff_ms / 4);
+        std::time::Duration::from_millis(backoff_ms + jitter_ms)
+    }
+
+    async fn notify_retry(
+        &self,
+        options: &StreamRequestOptions,
+        attempt: usize,
+        max_attempts: usize,
+        delay: std::time::Duration,
+        status: Option<reqwest::StatusCode>,
+        error: &anyhow::Error,
+    ) {
+        let rate_limited = status.is_some_and(|s| s == reqwest::StatusCode::TOO_MANY_REQUESTS);
+        let info = AIRequestRetryInfo {
+            session_id: options.session_id.clone(),
+            dialog_turn_id: options.dialog_turn_id.clone(),
+            model: self.config.model.clone(),
+            attempt: attempt as u32,
+            max_attempts: max_attempts as u32,
+            delay_ms: delay.as_millis() as u64,
+            status_code: status.map(|s| s.as_u16()),
+            rate_limited,
+            error: error.to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        };
+
+        if let Err(e) = emit_global_event(BackendEvent::AIRequestRetrying(info)).await {
+            debug!("Failed to emit AI request retry event: {}", e);
+        }
+    }
+
+    /// Wait before retrying. Returns false if the request was cancelled meanwhile.
+    async fn wait_before_retry(delay: std::time::Duration, options: &StreamRequestOptions) -> bool {
+        match &options.cancel_token {
+            Some(token) => {
+                tokio::select! {
+                    _ = token.cancelled() => false,
+                    _ = tokio::time::sleep(delay) => true,
+                }
+            }
+            None => {
+                tokio::time::sleep(delay).await;
+                true
+            }
+        }
+    }
+
+    /// POST a streaming request, retrying rate limits and transient failures.
+    ///
+    /// Only failures before the response is accepted are retried: once the HTTP stream is
+    /// handed back, no output has reached the session yet, and nothing is ever re-sent
+    /// after that point. While offline, remote endpoints fail fast instead of being retried.
+    async fn dispatch_stream_request<F>(
+        &self,
+        api_label: &str,
+        url: &str,
+        request_body: &serde_json::Value,
+        apply_headers: F,
+        options: &StreamRequestOptions,
+    ) -> Result<reqwest::Response>
+    where
+        F: Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    {
+        let max_attempts = self.max_request_attempts();
         let mut last_error = None;
-        let base_wait_time_ms = 500;
+        let mut last_status = None;
 
-        for attempt in 0..max_tries {
+        for attempt in 0..max_attempts {
+            offline::check_endpoint(url)?;
             let request_start_time = std::time::Instant::now();
+            let send = apply_headers(self.client.post(url))
+                .json(request_body)
+                .send();
+
+            let response_result = match &options.cancel_token {
+                Some(token) => {
+                    tokio::select! {
+                        _ = token.cancelled() => {
+                            return Err(anyhow!("{} request cancelled", api_label));
+                        }
+                        result = send => result,
+                    }
+                }
+                None => send.await,
+            };
 
-            // Send request - apply request headers
-            let request_builder = self.apply_openai_headers(self.client.post(&url));
-            let response_result = request_builder.json(&request_body).send().await;
-
-            let response = match response_result {
+            let elapsed = request_start_time.elapsed();
+            let connect_time = elapsed.as_millis();
+            let (error, status, retry_after) = match response_result {
                 Ok(resp) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
                     let status = resp.status();
-
-                    if status.is_client_error() {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        error!(
-                            "OpenAI Streaming API client error {}: {}",
-                            status, error_text
-                        );
-                        return Err(anyhow!(
-                            "OpenAI Streaming API client error {}: {}",
-                            status,
-                            error_text
-                        ));
-                    }
+                    self.http_stats.record(elapsed, status.is_success());
+                    offline::record_reachable();
 
                     if status.is_success() {
                         debug!(
-                            "Stream request connected: {}ms, status: {}, attempt: {}/{}",
+                            "{} request connected: {}ms, status: {}, attempt: {}/{}",
+                            api_label,
                             connect_time,
                             status,
                             attempt + 1,
-                            max_tries
-                        );
-                        resp
-                    } else {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        let error =
-                            anyhow!("OpenAI Streaming API error {}: {}", status, error_text);
-                        warn!(
-                            "Stream request failed (attempt {}/{}): {}",
-                            attempt + 1,
-                            max_tries,
-                            error
+                            max_attempts
                         );
-                        last_error = Some(error);
+                        return Ok(resp);
+                    }
 
-                        if attempt < max_tries - 1 {
-                            let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                            debug!("Retrying after {}ms (attempt {})", delay_ms, attempt + 2);
-                            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                        }
-                        continue;
+                    let retry_after = Self::parse_retry_after(resp.headers());
+                    let error_text = resp
+                        .text()
+                        .await
+                        .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
+
+                    if !Self::is_retryable_status(status) {
+                        let kind = if status.is_client_error() {
+                            "client error"
+                        } else {
+                            "error"
+                        };
+                        error!("{} {} {}: {}", api_label, kind, status, error_text);
+                        return Err(BitFunError::ai_status(
+                            status.as_u16(),
+                            format!("{} {} {}: {}", api_label, kind, status, error_text),
+                        )
+                        .into());
                     }
+
+                    (
+                        anyhow!("{} error {}: {}", api_label, status, error_text),
+                        Some(status),
+                        retry_after,
+                    )
                 }
                 Err(e) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let error = anyhow!("Stream request connection failed: {}", e);
-                    warn!(
-                        "Stream request connection failed: {}ms, attempt {}/{}, error: {}",
-                        connect_time,
-                        attempt + 1,
-                        max_tries,
-                        e
-                    );
-                    last_error = Some(error);
-
-                    if attempt < max_tries - 1 {
-                        let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                        debug!("Retrying after {}ms (attempt {})", delay_ms, attempt + 2);
-                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                    }
-                    continue;
+                    self.http_stats.record(elapsed, false);
+                    offline::record_request_error(&e);
+                    (
+                        anyhow!("{} request connection failed: {}", api_label, e),
+                        None,
+                        None,
+                    )
                 }
             };
 
-            // Success: create channels and return
-            let (tx, rx) = mpsc::unbounded_channel();
-            let (tx_raw, rx_raw) = mpsc::unbounded_channel();
+            warn!(
+                "{} request failed: {}ms, attempt {}/{}, error: {}",
+                api_label,
+                connect_time,
+                attempt + 1,
+                max_attempts,
+                error
+            );
 
-            tokio::spawn(handle_openai_stream(
-                response,
-                tx,
-                Some(tx_raw),
-                self.config.inline_think_in_text,
-            ));
+            if attempt + 1 < max_attempts {
+                let delay = Self::retry_delay(attempt, retry_after);
+                debug!(
+                    "Retrying {} after {}ms (attempt {})",
+                    api_label,
+                    delay.as_millis(),
+                    attempt + 2
+                );
+                self.notify_retry(options, attempt + 1, max_attempts, delay, status, &error)
+                    .await;
+                if !Self::wait_before_retry(delay, options).await {
+                    return Err(anyhow!("{} request cancelled", api_label));
+                }
+            }
 
-            return Ok(StreamResponse {
-                stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
-                raw_sse_rx: Some(rx_raw),
-            });
+            last_error = Some(error);
+            last_status = status;
         }
 
         let error_msg = format!(
-            "Stream request failed after {} attempts: {}",
-            max_tries,
+            "{} request failed after {} attempts: {}",
+            api_label,
+            max_attempts,
             last_error.unwrap_or_else(|| anyhow!("Unknown error"))
         );
         error!("{}", error_msg);
-        Err(anyhow!(error_msg))
+        Err(match last_status {
+            Some(status) => BitFunError::ai_status(status.as_u16(), error_msg),
+            None => BitFunError::ai_network(error_msg),
+        }
+        .into())
+    }
+
+    /// Send an OpenAI streaming request with retries
+    ///
+    /// # Parameters
+    /// - `messages`: message list
+    /// - `tools`: tool definitions
+    /// - `extra_body`: extra request body parameters
+    /// - `options`: per-request options (retry event tagging, cancellation)
+    async fn send_openai_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        extra_body: Option<serde_json::Value>,
+        options: &StreamRequestOptions,
+    ) -> Result<StreamResponse> {
+        let url = self.config.request_url.clone();
+        debug!(
+            "OpenAI config: model={}, request_url={}, max_attempts={}",
+            self.config.model,
+            self.config.request_url,
+            self.max_request_attempts()
+        );
+
+        // Use OpenAI message converter
+        let openai_messages = OpenAIMessageConverter::convert_messages(messages);
+        let openai_tools = OpenAIMessageConverter::convert_tools(tools);
+
+        // Build request body
+        let request_body =
+            self.build_openai_request_body(&url, openai_messages, openai_tools, extra_body);
+        let credentials = self.request_credentials().await;
+
+        if !self.should_stream(options) {
+            let inline_think_in_text = self.config.inline_think_in_text;
+            return self
+                .send_completion_request(
+                    "OpenAI API",
+                    &url,
+                    request_body,
+                    |builder| self.apply_openai_headers(builder, &credentials),
+                    options,
+                    |body| parse_openai_completion(body, inline_think_in_text),
+                )
+                .await;
+        }
+
+        let response = self
+            .dispatch_stream_request(
+                "OpenAI Streaming API",
+                &url,
+                &request_body,
+                |builder| self.apply_openai_headers(builder, &credentials),
+                options,
+            )
+            .await?;
+
+        // Success: create channels and return
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (tx_raw, rx_raw) = mpsc::unbounded_channel();
+
+        tokio::spawn(handle_openai_stream(
+            response,
+            tx,
+            Some(tx_raw),
+            self.config.inline_think_in_text,
+            self.stream_limits(options),
+        ));
+
+        Ok(StreamResponse {
+            stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
+            raw_sse_rx: Some(rx_raw),
+            served_by_model: None,
+        })
     }
 
     /// Send a Gemini streaming request with retries.
@@ -1393,12 +1889,14 @@ impl AIClient {
         messages: Vec<Message>,
         tools: Option<Vec<ToolDefinition>>,
         extra_body: Option<serde_json::Value>,
-        max_tries: usize,
+        options: &StreamRequestOptions,
     ) -> Result<StreamResponse> {
         let url = Self::resolve_gemini_request_url(&self.config.request_url, &self.config.model);
         debug!(
-            "Gemini config: model={}, request_url={}, max_tries={}",
-            self.config.model, url, max_tries
+            "Gemini config: model={}, request_url={}, max_attempts={}",
+            self.config.model,
+            url,
+            self.max_request_attempts()
         );
 
         let (system_instruction, contents) =
@@ -1406,116 +1904,33 @@ impl AIClient {
         let gemini_tools = GeminiMessageConverter::convert_tools(tools);
         let request_body =
             self.build_gemini_request_body(system_instruction, contents, gemini_tools, extra_body);
+        let credentials = self.request_credentials().await;
 
-        let mut last_error = None;
-        let base_wait_time_ms = 500;
-
-        for attempt in 0..max_tries {
-            let request_start_time = std::time::Instant::now();
-            let request_builder = self.apply_gemini_headers(self.client.post(&url));
-            let response_result = request_builder.json(&request_body).send().await;
-
-            let response = match response_result {
-                Ok(resp) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let status = resp.status();
-
-                    if status.is_client_error() {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        error!(
-                            "Gemini Streaming API client error {}: {}",
-                            status, error_text
-                        );
-                        return Err(anyhow!(
-                            "Gemini Streaming API client error {}: {}",
-                            status,
-                            error_text
-                        ));
-                    }
-
-                    if status.is_success() {
-                        debug!(
-                            "Gemini stream request connected: {}ms, status: {}, attempt: {}/{}",
-                            connect_time,
-                            status,
-                            attempt + 1,
-                            max_tries
-                        );
-                        resp
-                    } else {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        let error =
-                            anyhow!("Gemini Streaming API error {}: {}", status, error_text);
-                        warn!(
-                            "Gemini stream request failed: {}ms, attempt {}/{}, error: {}",
-                            connect_time,
-                            attempt + 1,
-                            max_tries,
-                            error
-                        );
-                        last_error = Some(error);
-
-                        if attempt < max_tries - 1 {
-                            let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                            debug!(
-                                "Retrying Gemini after {}ms (attempt {})",
-                                delay_ms,
-                                attempt + 2
-                            );
-                            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                        }
-                        continue;
-                    }
-                }
-                Err(e) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let error = anyhow!("Gemini stream request connection failed: {}", e);
-                    warn!(
-                        "Gemini stream request connection failed: {}ms, attempt {}/{}, error: {}",
-                        connect_time,
-                        attempt + 1,
-                        max_tries,
-                        e
-                    );
-                    last_error = Some(error);
-
-                    if attempt < max_tries - 1 {
-                        let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                        debug!(
-                            "Retrying Gemini after {}ms (attempt {})",
-                            delay_ms,
-                            attempt + 2
-                        );
-                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                    }
-                    continue;
-                }
-            };
+        let response = self
+            .dispatch_stream_request(
+                "Gemini Streaming API",
+                &url,
+                &request_body,
+                |builder| self.apply_gemini_headers(builder, &credentials),
+                options,
+            )
+            .await?;
 
-            let (tx, rx) = mpsc::unbounded_channel();
-            let (tx_raw, rx_raw) = mpsc::unbounded_channel();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (tx_raw, rx_raw) = mpsc::unbounded_channel();
 
-            tokio::spawn(handle_gemini_stream(response, tx, Some(tx_raw)));
+        tokio::spawn(handle_gemini_stream(
+            response,
+            tx,
+            Some(tx_raw),
+            self.stream_limits(options),
+        ));
 
-            return Ok(StreamResponse {
-                stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
-                raw_sse_rx: Some(rx_raw),
-            });
-        }
-
-        let error_msg = format!(
-            "Gemini stream request failed after {} attempts: {}",
-            max_tries,
-            last_error.unwrap_or_else(|| anyhow!("Unknown error"))
-        );
-        error!("{}", error_msg);
-        Err(anyhow!(error_msg))
+        Ok(StreamResponse {
+            stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
+            raw_sse_rx: Some(rx_raw),
+            served_by_model: None,
+        })
     }
 
     /// Send a Responses API streaming request with retries.
@@ -1524,120 +1939,65 @@ impl AIClient {
         messages: Vec<Message>,
         tools: Option<Vec<ToolDefinition>>,
         extra_body: Option<serde_json::Value>,
-        max_tries: usize,
+        options: &StreamRequestOptions,
     ) -> Result<StreamResponse> {
         let url = self.config.request_url.clone();
         debug!(
-            "Responses config: model={}, request_url={}, max_tries={}",
-            self.config.model, self.config.request_url, max_tries
+            "Responses config: model={}, request_url={}, max_attempts={}",
+            self.config.model,
+            self.config.request_url,
+            self.max_request_attempts()
         );
 
         let (instructions, response_input) =
             OpenAIMessageConverter::convert_messages_to_responses_input(messages);
-        let openai_tools = OpenAIMessageConverter::convert_tools(tools);
+        let response_tools = OpenAIMessageConverter::convert_tools_to_responses(tools);
         let request_body = self.build_responses_request_body(
             instructions,
             response_input,
-            openai_tools,
+            response_tools,
             extra_body,
         );
+        let credentials = self.request_credentials().await;
 
-        let mut last_error = None;
-        let base_wait_time_ms = 500;
-
-        for attempt in 0..max_tries {
-            let request_start_time = std::time::Instant::now();
-            let request_builder = self.apply_openai_headers(self.client.post(&url));
-            let response_result = request_builder.json(&request_body).send().await;
-
-            let response = match response_result {
-                Ok(resp) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let status = resp.status();
-
-                    if status.is_client_error() {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        error!("Responses API client error {}: {}", status, error_text);
-                        return Err(anyhow!(
-                            "Responses API client error {}: {}",
-                            status,
-                            error_text
-                        ));
-                    }
-
-                    if status.is_success() {
-                        debug!(
-                            "Responses request connected: {}ms, status: {}, attempt: {}/{}",
-                            connect_time,
-                            status,
-                            attempt + 1,
-                            max_tries
-                        );
-                        resp
-                    } else {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        let error = anyhow!("Responses API error {}: {}", status, error_text);
-                        warn!(
-                            "Responses request failed (attempt {}/{}): {}",
-                            attempt + 1,
-                            max_tries,
-                            error
-                        );
-                        last_error = Some(error);
-
-                        if attempt < max_tries - 1 {
-                            let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                            debug!("Retrying after {}ms (attempt {})", delay_ms, attempt + 2);
-                            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                        }
-                        continue;
-                    }
-                }
-                Err(e) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let error = anyhow!("Responses request connection failed: {}", e);
-                    warn!(
-                        "Responses request connection failed: {}ms, attempt {}/{}, error: {}",
-                        connect_time,
-                        attempt + 1,
-                        max_tries,
-                        e
-                    );
-                    last_error = Some(error);
-
-                    if attempt < max_tries - 1 {
-                        let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                        debug!("Retrying after {}ms (attempt {})", delay_ms, attempt + 2);
-                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                    }
-                    continue;
-                }
-            };
+        if !self.should_stream(options) {
+            return self
+                .send_completion_request(
+                    "Responses API",
+                    &url,
+                    request_body,
+                    |builder| self.apply_openai_headers(builder, &credentials),
+                    options,
+                    parse_responses_completion,
+                )
+                .await;
+        }
 
-            let (tx, rx) = mpsc::unbounded_channel();
-            let (tx_raw, rx_raw) = mpsc::unbounded_channel();
+        let response = self
+            .dispatch_stream_request(
+                "Responses API",
+                &url,
+                &request_body,
+                |builder| self.apply_openai_headers(builder, &credentials),
+                options,
+            )
+            .await?;
 
-            tokio::spawn(handle_responses_stream(response, tx, Some(tx_raw)));
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (tx_raw, rx_raw) = mpsc::unbounded_channel();
 
-            return Ok(StreamResponse {
-                stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
-                raw_sse_rx: Some(rx_raw),
-            });
-        }
+        tokio::spawn(handle_responses_stream(
+            response,
+            tx,
+            Some(tx_raw),
+            self.stream_limits(options),
+        ));
 
-        let error_msg = format!(
-            "Responses request failed after {} attempts: {}",
-            max_tries,
-            last_error.unwrap_or_else(|| anyhow!("Unknown error"))
-        );
-        error!("{}", error_msg);
-        Err(anyhow!(error_msg))
+        Ok(StreamResponse {
+            stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
+            raw_sse_rx: Some(rx_raw),
+            served_by_model: None,
+        })
     }
 
     /// Send an Anthropic streaming request with retries
@@ -1646,18 +2006,20 @@ impl AIClient {
     /// - `messages`: message list
     /// - `tools`: tool definitions
     /// - `extra_body`: extra request body parameters
-    /// - `max_tries`: max attempts (including the first)
+    /// - `options`: per-request options (retry event tagging, cancellation)
     async fn send_anthropic_stream(
         &self,
         messages: Vec<Message>,
         tools: Option<Vec<ToolDefinition>>,
         extra_body: Option<serde_json::Value>,
-        max_tries: usize,
+        options: &StreamRequestOptions,
     ) -> Result<StreamResponse> {
         let url = self.config.request_url.clone();
         debug!(
-            "Anthropic config: model={}, request_url={}, max_tries={}",
-            self.config.model, self.config.request_url, max_tries
+            "Anthropic config: model={}, request_url={}, max_attempts={}",
+            self.config.model,
+            self.config.request_url,
+            self.max_request_attempts()
         );
 
         // Use Anthropic message converter
@@ -1673,110 +2035,48 @@ impl AIClient {
             anthropic_tools,
             extra_body,
         );
+        let credentials = self.request_credentials().await;
 
-        let mut last_error = None;
-        let base_wait_time_ms = 500;
-
-        for attempt in 0..max_tries {
-            let request_start_time = std::time::Instant::now();
-
-            // Send request - apply Anthropic-style request headers
-            let request_builder = self.apply_anthropic_headers(self.client.post(&url), &url);
-            let response_result = request_builder.json(&request_body).send().await;
-
-            let response = match response_result {
-                Ok(resp) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let status = resp.status();
-
-                    if status.is_client_error() {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        error!(
-                            "Anthropic Streaming API client error {}: {}",
-                            status, error_text
-                        );
-                        return Err(anyhow!(
-                            "Anthropic Streaming API client error {}: {}",
-                            status,
-                            error_text
-                        ));
-                    }
-
-                    if status.is_success() {
-                        debug!(
-                            "Stream request connected: {}ms, status: {}, attempt: {}/{}",
-                            connect_time,
-                            status,
-                            attempt + 1,
-                            max_tries
-                        );
-                        resp
-                    } else {
-                        let error_text = resp
-                            .text()
-                            .await
-                            .unwrap_or_else(|e| format!("Failed to read error response: {}", e));
-                        let error =
-                            anyhow!("Anthropic Streaming API error {}: {}", status, error_text);
-                        warn!(
-                            "Stream request failed (attempt {}/{}): {}",
-                            attempt + 1,
-                            max_tries,
-                            error
-                        );
-                        last_error = Some(error);
-
-                        if attempt < max_tries - 1 {
-                            let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                            debug!("Retrying after {}ms (attempt {})", delay_ms, attempt + 2);
-                            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                        }
-                        continue;
-                    }
-                }
-                Err(e) => {
-                    let connect_time = request_start_time.elapsed().as_millis();
-                    let error = anyhow!("Stream request connection failed: {}", e);
-                    warn!(
-                        "Stream request connection failed: {}ms, attempt {}/{}, error: {}",
-                        connect_time,
-                        attempt + 1,
-                        max_tries,
-                        e
-                    );
-                    last_error = Some(error);
-
-                    if attempt < max_tries - 1 {
-                        let delay_ms = base_wait_time_ms * (1 << attempt.min(3));
-                        debug!("Retrying after {}ms (attempt {})", delay_ms, attempt + 2);
-                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                    }
-                    continue;
-                }
-            };
-
-            // Success: create channels and return
-            let (tx, rx) = mpsc::unbounded_channel();
-            let (tx_raw, rx_raw) = mpsc::unbounded_channel();
-
-            tokio::spawn(handle_anthropic_stream(response, tx, Some(tx_raw)));
-
-            return Ok(StreamResponse {
-                stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
-                raw_sse_rx: Some(rx_raw),
-            });
+        if !self.should_stream(options) {
+            return self
+                .send_completion_request(
+                    "Anthropic API",
+                    &url,
+                    request_body,
+                    |builder| self.apply_anthropic_headers(builder, &url, &credentials),
+                    options,
+                    parse_anthropic_message,
+                )
+                .await;
         }
 
-        let error_msg = format!(
-            "Stream request failed after {} attempts: {}",
-            max_tries,
-            last_error.unwrap_or_else(|| anyhow!("Unknown error"))
-        );
-        error!("{}", error_msg);
-        Err(anyhow!(error_msg))
+        // Send request - apply Anthropic-style request headers
+        let response = self
+            .dispatch_stream_request(
+                "Anthropic Streaming API",
+                &url,
+                &request_body,
+                |builder| self.apply_anthropic_headers(builder, &url, &credentials),
+                options,
+            )
+            .await?;
+
+        // Success: create channels and return
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (tx_raw, rx_raw) = mpsc::unbounded_channel();
+
+        tokio::spawn(handle_anthropic_stream(
+            response,
+            tx,
+            Some(tx_raw),
+            self.stream_limits(options),
+        ));
+
+        Ok(StreamResponse {
+            stream: Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx)),
+            raw_sse_rx: Some(rx_raw),
+            served_by_model: None,
+        })
     }
 
     /// Send a message and wait for the full response (non-streaming)
@@ -1790,6 +2090,22 @@ impl AIClient {
             .await
     }
 
+    /// Send a message and wait for the full response, with per-request options
+    ///
+    /// Set `options.streaming` to `Some(false)` to skip SSE entirely for batch-style callers.
+    pub async fn send_message_with_options(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        options: StreamRequestOptions,
+    ) -> Result<GeminiResponse> {
+        let custom_body = self.config.custom_request_body.clone();
+        let stream_response = self
+            .dispatch_message_stream(messages, tools, custom_body, &options)
+            .await?;
+        Self::collect_response(stream_response).await
+    }
+
     /// Send a message and wait for the full response (non-streaming, with extra body overrides)
     pub async fn send_message_with_extra_body(
         &self,
@@ -1800,6 +2116,97 @@ impl AIClient {
         let stream_response = self
             .send_message_stream_with_extra_body(messages, tools, extra_body)
             .await?;
+        Self::collect_response(stream_response).await
+    }
+
+    /// Embed texts through an OpenAI-compatible `/embeddings` endpoint.
+    ///
+    /// Inputs already in the on-disk cache are not sent; the rest go out in batches of the
+    /// provider's max size, with rate limits retried like chat requests.
+    ///
+    /// # Parameters
+    /// - `texts`: inputs, embeddings are returned in the same order
+    /// - `model`: embedding model; None = the model config's `embedding_model`, then `model`
+    pub async fn embed(&self, texts: &[String], model: Option<&str>) -> Result<EmbeddingResult> {
+        let model = model
+            .or(self.config.embedding_model.as_deref())
+            .unwrap_or(&self.config.model);
+        let url = self
+            .config
+            .embeddings_url
+            .clone()
+            .unwrap_or_else(|| resolve_embeddings_url(&self.config.base_url));
+        let batch_size = self
+            .config
+            .embedding_batch_size
+            .unwrap_or_else(|| default_batch_size(&url))
+            .max(1);
+        let cache = EmbeddingCache::global();
+
+        let mut embeddings: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        if let Some(cache) = &cache {
+            for (slot, text) in embeddings.iter_mut().zip(texts) {
+                *slot = cache.get(model, text).await;
+            }
+        }
+        let pending: Vec<usize> = (0..texts.len())
+            .filter(|&i| embeddings[i].is_none())
+            .collect();
+        let cached_count = texts.len() - pending.len();
+        debug!(
+            "Embedding {} text(s) with {}: {} cached, batch size {}",
+            texts.len(),
+            model,
+            cached_count,
+            batch_size
+        );
+
+        let mut usage = EmbeddingUsage::default();
+        let credentials = self.request_credentials().await;
+        for batch in pending.chunks(batch_size) {
+            let input: Vec<&str> = batch.iter().map(|&i| texts[i].as_str()).collect();
+            let request_body = serde_json::json!({
+                "model": model,
+                "input": input,
+                "encoding_format": "float",
+            });
+
+            let response = self
+                .dispatch_stream_request(
+                    "Embeddings API",
+                    &url,
+                    &request_body,
+                    |builder| self.apply_openai_headers(builder, &credentials),
+                    &StreamRequestOptions::default(),
+                )
+                .await?;
+            let parsed: EmbeddingsResponse = response
+                .json()
+                .await
+                .map_err(|e| anyhow!("Failed to parse embeddings response: {}", e))?;
+            let (vectors, batch_usage) = parsed.into_ordered(batch.len())?;
+            usage.add(&batch_usage);
+
+            for (&i, vector) in batch.iter().zip(vectors) {
+                if let Some(cache) = &cache {
+                    cache.put(model, &texts[i], &vector).await;
+                }
+                embeddings[i] = Some(vector);
+            }
+        }
+
+        Ok(EmbeddingResult {
+            embeddings: embeddings
+                .into_iter()
+                .map(Option::unwrap_or_default)
+                .collect(),
+            usage,
+            cached_count,
+        })
+    }
+
+    /// Drain a response stream into a single response with assembled tool calls
+    async fn collect_response(stream_response: StreamResponse) -> Result<GeminiResponse> {
         let mut stream = stream_response.stream;
 
         let mut full_text = String::new();
@@ -2021,6 +2428,7 @@ impl AIClient {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }];
 
         match self.send_message(test_messages, None).await {
@@ -2065,6 +2473,7 @@ impl AIClient {
     }
 
     pub async fn list_models(&self) -> Result<Vec<RemoteModelInfo>> {
+        offline::check_endpoint(&self.config.base_url)?;
         match self.get_api_format().to_ascii_lowercase().as_str() {
             "openai" | "response" | "responses" => self.list_openai_models().await,
             "anthropic" => self.list_anthropic_models().await,
@@ -2081,6 +2490,7 @@ impl AIClient {
 mod tests {
     use super::AIClient;
     use crate::infrastructure::ai::providers::gemini::GeminiMessageConverter;
+    use crate::infrastructure::ai::providers::openai::OpenAIMessageConverter;
     use crate::util::types::{AIConfig, ToolDefinition};
     use serde_json::json;
 
@@ -2103,6 +2513,22 @@ mod tests {
             custom_headers_mode: None,
             skip_ssl_verify: false,
             reasoning_effort: None,
+            thinking_budget_tokens: None,
+            max_retries: None,
+            disable_streaming: false,
+            stream_idle_timeout_secs: None,
+            stream_deadline_secs: None,
+            enable_prompt_caching: false,
+            prompt_cache_min_tokens: None,
+            native_web_search: false,
+            embedding_model: None,
+            embeddings_url: None,
+            embedding_batch_size: None,
+            pricing: None,
+            image_max_dimension: None,
+            image_quality: None,
+            proxy: None,
+            supports_tools: true,
             custom_request_body,
         })
     }
@@ -2127,6 +2553,22 @@ mod tests {
             custom_headers_mode: None,
             skip_ssl_verify: false,
             reasoning_effort: None,
+            thinking_budget_tokens: None,
+            max_retries: None,
+            disable_streaming: false,
+            stream_idle_timeout_secs: None,
+            stream_deadline_secs: None,
+            enable_prompt_caching: false,
+            prompt_cache_min_tokens: None,
+            native_web_search: false,
+            embedding_model: None,
+            embeddings_url: None,
+            embedding_batch_size: None,
+            pricing: None,
+            image_max_dimension: None,
+            image_quality: None,
+            proxy: None,
+            supports_tools: true,
             custom_request_body: None,
         });
 
@@ -2156,6 +2598,22 @@ mod tests {
             custom_headers_mode: None,
             skip_ssl_verify: false,
             reasoning_effort: None,
+            thinking_budget_tokens: None,
+            max_retries: None,
+            disable_streaming: false,
+            stream_idle_timeout_secs: None,
+            stream_deadline_secs: None,
+            enable_prompt_caching: false,
+            prompt_cache_min_tokens: None,
+            native_web_search: false,
+            embedding_model: None,
+            embeddings_url: None,
+            embedding_batch_size: None,
+            pricing: None,
+            image_max_dimension: None,
+            image_quality: None,
+            proxy: None,
+            supports_tools: true,
             custom_request_body: None,
         });
 
@@ -2186,6 +2644,22 @@ mod tests {
             custom_headers_mode: None,
             skip_ssl_verify: false,
             reasoning_effort: None,
+            thinking_budget_tokens: None,
+            max_retries: None,
+            disable_streaming: false,
+            stream_idle_timeout_secs: None,
+            stream_deadline_secs: None,
+            enable_prompt_caching: false,
+            prompt_cache_min_tokens: None,
+            native_web_search: false,
+            embedding_model: None,
+            embeddings_url: None,
+            embedding_batch_size: None,
+            pricing: None,
+            image_max_dimension: None,
+            image_quality: None,
+            proxy: None,
+            supports_tools: true,
             custom_request_body: None,
         });
 
@@ -2265,6 +2739,22 @@ mod tests {
             custom_headers_mode: None,
             skip_ssl_verify: false,
             reasoning_effort: None,
+            thinking_budget_tokens: None,
+            max_retries: None,
+            disable_streaming: false,
+            stream_idle_timeout_secs: None,
+            stream_deadline_secs: None,
+            enable_prompt_caching: false,
+            prompt_cache_min_tokens: None,
+            native_web_search: false,
+            embedding_model: None,
+            embeddings_url: None,
+            embedding_batch_size: None,
+            pricing: None,
+            image_max_dimension: None,
+            image_quality: None,
+            proxy: None,
+            supports_tools: true,
             custom_request_body: None,
         });
 
@@ -2293,6 +2783,131 @@ mod tests {
         assert!(request_body.get("toolConfig").is_none());
     }
 
+    #[test]
+    fn anthropic_request_declares_web_search_server_tool_when_enabled() {
+        let mut client = make_test_client("anthropic", None);
+        let messages = vec![json!({ "role": "user", "content": "news?" })];
+        let request_body = client.build_anthropic_request_body(
+            "https://example.com/v1/messages",
+            None,
+            messages.clone(),
+            None,
+            None,
+        );
+        assert!(request_body.get("tools").is_none());
+
+        client.config.native_web_search = true;
+        let request_body = client.build_anthropic_request_body(
+            "https://example.com/v1/messages",
+            None,
+            messages,
+            Some(vec![
+                json!({ "name": "Read", "input_schema": { "type": "object" } }),
+            ]),
+            None,
+        );
+        assert_eq!(request_body["tools"][0]["name"], json!("Read"));
+        assert_eq!(
+            request_body["tools"][1],
+            json!({ "type": "web_search_20250305", "name": "web_search" })
+        );
+    }
+
+    #[test]
+    fn responses_request_uses_flat_function_tools() {
+        let client = make_test_client("responses", None);
+        let tools =
+            OpenAIMessageConverter::convert_tools_to_responses(Some(vec![ToolDefinition {
+                name: "get_weather".to_string(),
+                description: "Get weather".to_string(),
+                parameters: json!({ "type": "object" }),
+            }]));
+        let request_body = client.build_responses_request_body(
+            Some("Be brief".to_string()),
+            vec![json!({ "role": "user", "content": "weather?" })],
+            tools,
+            None,
+        );
+
+        assert_eq!(request_body["instructions"], json!("Be brief"));
+        assert_eq!(request_body["stream"], json!(true));
+        assert_eq!(
+            request_body["tools"][0],
+            json!({
+                "type": "function",
+                "name": "get_weather",
+                "description": "Get weather",
+                "parameters": { "type": "object" }
+            })
+        );
+        assert_eq!(request_body["tool_choice"], json!("auto"));
+        assert_eq!(
+            AIClient::extract_openai_tool_name(&request_body["tools"][0]),
+            "get_weather"
+        );
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_milliseconds() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("retry-after", "8".parse().unwrap());
+        assert_eq!(
+            AIClient::parse_retry_after(&headers),
+            Some(std::time::Duration::from_secs(8))
+        );
+
+        headers.insert("retry-after-ms", "1500".parse().unwrap());
+        assert_eq!(
+            AIClient::parse_retry_after(&headers),
+            Some(std::time::Duration::from_millis(1500))
+        );
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(
+            AIClient::parse_retry_after(&headers),
+            Some(std::time::Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn retry_delay_honors_retry_after_and_caps_backoff() {
+        assert_eq!(
+            AIClient::retry_delay(0, Some(std::time::Duration::from_secs(8))),
+            std::time::Duration::from_secs(8)
+        );
+        assert_eq!(
+            AIClient::retry_delay(0, Some(std::time::Duration::from_secs(3600))),
+            std::time::Duration::from_secs(AIClient::MAX_RETRY_AFTER_SECS)
+        );
+
+        let first = AIClient::retry_delay(0, None).as_millis() as u64;
+        assert!(
+            (AIClient::RETRY_BASE_DELAY_MS..=AIClient::RETRY_BASE_DELAY_MS * 5 / 4)
+                .contains(&first)
+        );
+
+        let late = AIClient::retry_delay(10, None).as_millis() as u64;
+        assert!(late <= AIClient::RETRY_MAX_DELAY_MS * 5 / 4);
+    }
+
+    #[test]
+    fn only_rate_limits_and_transient_server_errors_are_retryable() {
+        for code in [429u16, 500, 502, 503, 504, 529] {
+            assert!(AIClient::is_retryable_status(
+                reqwest::StatusCode::from_u16(code).unwrap()
+            ));
+        }
+        for code in [400u16, 401, 403, 404, 422, 501] {
+            assert!(!AIClient::is_retryable_status(
+                reqwest::StatusCode::from_u16(code).unwrap()
+            ));
+        }
+    }
+
     #[test]
     fn streaming_http_client_does_not_apply_global_request_timeout() {
         let client = make_test_client("openai", None);
@@ -2304,4 +2919,166 @@ mod tests {
 
         assert_eq!(request.timeout(), None);
     }
+
+    #[tokio::test]
+    async fn routes_requests_through_model_proxy_except_bypassed_hosts() {
+        use crate::service::config::ProxyConfig;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Minimal HTTP server: reports each request head and answers with `body`
+        async fn spawn_server(
+            body: &'static str,
+        ) -> (
+            std::net::SocketAddr,
+            tokio::sync::mpsc::UnboundedReceiver<String>,
+        ) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            tokio::spawn(async move {
+                while let Ok((mut socket, _)) = listener.accept().await {
+                    let mut buf = vec![0u8; 8192];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let _ = tx.send(String::from_utf8_lossy(&buf[..n]).to_string());
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                }
+            });
+            (addr, rx)
+        }
+
+        let (proxy_addr, mut proxy_rx) = spawn_server("via-proxy").await;
+        let (direct_addr, mut direct_rx) = spawn_server("direct").await;
+
+        std::env::set_var("BITFUN_TEST_PROXY_ORG", "org-42");
+        let mut config = make_test_client("openai", None).config;
+        config.custom_headers = Some(
+            [(
+                "X-Api-Org".to_string(),
+                "${env:BITFUN_TEST_PROXY_ORG}".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        config.proxy = Some(ProxyConfig {
+            enabled: true,
+            url: format!("http://{}", proxy_addr),
+            username: None,
+            password: None,
+            no_proxy: vec!["127.0.0.1".to_string()],
+            ca_bundle_path: None,
+        });
+        let client = AIClient::new(config);
+
+        let proxied = client
+            .apply_openai_headers(
+                client.client.get("http://gateway.example.test/v1/models"),
+                &client.request_credentials().await,
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(proxied.text().await.unwrap(), "via-proxy");
+        let head = proxy_rx.recv().await.unwrap();
+        assert!(head.starts_with("GET http://gateway.example.test/v1/models HTTP/1.1"));
+        assert!(head.to_ascii_lowercase().contains("x-api-org: org-42"));
+
+        let bypassed = client
+            .client
+            .get(format!("http://{}/v1/models", direct_addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(bypassed.text().await.unwrap(), "direct");
+        assert!(direct_rx
+            .recv()
+            .await
+            .unwrap()
+            .starts_with("GET /v1/models HTTP/1.1"));
+        assert!(proxy_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn falls_back_when_the_primary_model_fails() {
+        use crate::util::types::Message;
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Minimal HTTP server: reports each request head and answers with `status` and `body`
+        async fn spawn_server(
+            status: &'static str,
+            body: &'static str,
+        ) -> (
+            std::net::SocketAddr,
+            tokio::sync::mpsc::UnboundedReceiver<String>,
+        ) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            tokio::spawn(async move {
+                while let Ok((mut socket, _)) = listener.accept().await {
+                    let mut buf = vec![0u8; 8192];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let _ = tx.send(String::from_utf8_lossy(&buf[..n]).to_string());
+                    let response = format!(
+                        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                }
+            });
+            (addr, rx)
+        }
+
+        let (primary_addr, mut primary_rx) =
+            spawn_server("400 Bad Request", r#"{"error":"model unavailable"}"#).await;
+        let (fallback_addr, mut fallback_rx) = spawn_server(
+            "200 OK",
+            r#"{"id":"r1","object":"chat.completion","created":0,"model":"fallback-model","choices":[{"index":0,"message":{"role":"assistant","content":"from fallback"},"finish_reason":"stop"}]}"#,
+        )
+        .await;
+
+        let model_config = |model: &str, addr: std::net::SocketAddr| {
+            let mut config = make_test_client("openai", None).config;
+            config.model = model.to_string();
+            config.base_url = format!("http://{}/{}", addr, model);
+            config.request_url = format!("http://{}/{}/chat/completions", addr, model);
+            config.disable_streaming = true;
+            config.max_retries = Some(0);
+            config
+        };
+        let fallback = Arc::new(AIClient::new(model_config("fallback-model", fallback_addr)));
+        let client = AIClient::new(model_config("primary-model", primary_addr))
+            .with_fallbacks(vec![fallback]);
+
+        let mut response = client
+            .send_message_stream(vec![Message::user("hello".to_string())], None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.served_by_model.as_deref(), Some("fallback-model"));
+        assert!(primary_rx
+            .recv()
+            .await
+            .unwrap()
+            .starts_with("POST /primary-model/chat/completions HTTP/1.1"));
+        assert!(fallback_rx
+            .recv()
+            .await
+            .unwrap()
+            .starts_with("POST /fallback-model/chat/completions HTTP/1.1"));
+
+        let mut text = String::new();
+        while let Some(chunk) = response.stream.next().await {
+            text.push_str(chunk.unwrap().text.as_deref().unwrap_or_default());
+        }
+        assert_eq!(text, "from fallback");
+    }
 }
@@ -93,6 +93,58 @@ impl AIClientFactory {
         self.get_or_create_client(&resolved_model_id).await
     }
 
+    /// Build clients for the model's fallback chain.
+    ///
+    /// Unknown, disabled, or self references are skipped; fallback clients carry no chain
+    /// of their own so a misconfigured cycle cannot recurse.
+    fn build_fallback_clients(
+        global_config: &crate::service::config::GlobalConfig,
+        model_config: &crate::service::config::types::AIModelConfig,
+        proxy_config: Option<crate::service::config::ProxyConfig>,
+    ) -> Vec<Arc<AIClient>> {
+        let mut seen = std::collections::HashSet::new();
+        seen.insert(model_config.id.clone());
+
+        model_config
+            .fallback_models
+            .iter()
+            .filter_map(|model_ref| {
+                let Some(model_id) =
+                    Self::resolve_model_reference_in_config(global_config, model_ref)
+                else {
+                    warn!(
+                        "Fallback model not found, skipping: model={}, fallback={}",
+                        model_config.id, model_ref
+                    );
+                    return None;
+                };
+                if !seen.insert(model_id.clone()) {
+                    return None;
+                }
+
+                let fallback_config = global_config.ai.models.iter().find(|m| m.id == model_id)?;
+                if !fallback_config.enabled {
+                    debug!("Fallback model disabled, skipping: {}", model_id);
+                    return None;
+                }
+
+                match AIConfig::try_from(fallback_config.clone()) {
+                    Ok(ai_config) => Some(Arc::new(AIClient::new_with_proxy(
+                        ai_config,
+                        proxy_config.clone(),
+                    ))),
+                    Err(e) => {
+                        warn!(
+                            "Fallback model configuration conversion failed: model={}, error={}",
+                            model_id, e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
     pub fn invalidate_cache(&self) {
         let mut cache = match self.client_cache.write() {
             Ok(cache) => cache,
@@ -170,13 +222,14 @@ impl AIClientFactory {
         let ai_config = AIConfig::try_from(model_config.clone())
             .map_err(|e| anyhow!("AI configuration conversion failed: {}", e))?;
 
-        let proxy_config = if global_config.ai.proxy.enabled {
-            Some(global_config.ai.proxy.clone())
-        } else {
-            None
-        };
+        // Passed even when disabled: its CA bundle still applies to direct connections
+        let proxy_config = Some(global_config.ai.proxy.clone());
 
-        let client = Arc::new(AIClient::new_with_proxy(ai_config, proxy_config));
+        let fallback_clients =
+            Self::build_fallback_clients(&global_config, model_config, proxy_config.clone());
+        let client = Arc::new(
+            AIClient::new_with_proxy(ai_config, proxy_config).with_fallbacks(fallback_clients),
+        );
 
         {
             let mut cache = match self.client_cache.write() {
@@ -310,6 +363,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_fallback_clients_skips_self_unknown_and_disabled_models() {
+        let mut config = GlobalConfig::default();
+        let mut primary = build_model("model-primary", "Primary Chat", "claude-sonnet-4.5");
+        primary.fallback_models = vec![
+            "model-primary".to_string(),
+            "missing".to_string(),
+            "Disabled".to_string(),
+            "gpt-4.1".to_string(),
+            "model-fallback".to_string(),
+        ];
+        let fallback = build_model("model-fallback", "Fallback", "gpt-4.1");
+        let mut disabled = build_model("model-disabled", "Disabled", "gemini-2.5-pro");
+        disabled.enabled = false;
+        config.ai.models = vec![primary.clone(), fallback, disabled];
+
+        let clients = AIClientFactory::build_fallback_clients(&config, &primary, None);
+
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].config.model, "gpt-4.1");
+    }
+
     #[test]
     fn resolve_fast_selection_falls_back_to_primary_when_fast_missing() {
         let mut config = GlobalConfig::default();
@@ -0,0 +1,218 @@
+//! Embeddings support
+//!
+//! Types for OpenAI-compatible `/embeddings` endpoints and an on-disk cache keyed by
+//! (model, content hash), so re-embedding unchanged content costs nothing.
+
+use crate::infrastructure::filesystem::{register_cache_consumer, CacheType};
+use crate::infrastructure::try_get_path_manager_arc;
+use anyhow::{anyhow, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// OpenAI accepts up to 2048 inputs per request
+const OPENAI_MAX_BATCH_SIZE: usize = 2048;
+/// DashScope text-embedding models accept 10 inputs per request
+const DASHSCOPE_MAX_BATCH_SIZE: usize = 10;
+/// Conservative default for other OpenAI-compatible servers
+const DEFAULT_MAX_BATCH_SIZE: usize = 64;
+
+/// Token usage reported by the embeddings endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingUsage {
+    #[serde(default)]
+    pub prompt_tokens: u32,
+    #[serde(default)]
+    pub total_tokens: u32,
+}
+
+impl EmbeddingUsage {
+    pub(crate) fn add(&mut self, other: &EmbeddingUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// Embeddings in input order; cached inputs are not counted in `usage`
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingResult {
+    pub embeddings: Vec<Vec<f32>>,
+    pub usage: EmbeddingUsage,
+    /// Number of inputs served from the on-disk cache
+    pub cached_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+    #[serde(default)]
+    usage: Option<EmbeddingUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: Option<usize>,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingsResponse {
+    /// Vectors in input order; servers may return `data` out of order
+    pub(crate) fn into_ordered(self, expected: usize) -> Result<(Vec<Vec<f32>>, EmbeddingUsage)> {
+        if self.data.len() != expected {
+            return Err(anyhow!(
+                "Embeddings response has {} vectors for {} inputs",
+                self.data.len(),
+                expected
+            ));
+        }
+
+        let mut ordered: Vec<Option<Vec<f32>>> = vec![None; expected];
+        for (position, item) in self.data.into_iter().enumerate() {
+            let index = item.index.unwrap_or(position);
+            let slot = ordered
+                .get_mut(index)
+                .ok_or_else(|| anyhow!("Embeddings response index {} out of range", index))?;
+            *slot = Some(item.embedding);
+        }
+
+        let embeddings = ordered
+            .into_iter()
+            .enumerate()
+            .map(|(index, vector)| {
+                vector.ok_or_else(|| anyhow!("Embeddings response is missing index {}", index))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok((embeddings, self.usage.unwrap_or_default()))
+    }
+}
+
+/// `{base}/embeddings`, where base drops a trailing chat/responses endpoint
+pub(crate) fn resolve_embeddings_url(base_url: &str) -> String {
+    let mut base = base_url.trim().trim_end_matches('/').to_string();
+    for suffix in ["/chat/completions", "/responses", "/embeddings"] {
+        if base.ends_with(suffix) {
+            base.truncate(base.len() - suffix.len());
+            break;
+        }
+    }
+    format!("{}/embeddings", base)
+}
+
+pub(crate) fn default_batch_size(url: &str) -> usize {
+    if url.contains("api.openai.com") {
+        OPENAI_MAX_BATCH_SIZE
+    } else if url.contains("dashscope") {
+        DASHSCOPE_MAX_BATCH_SIZE
+    } else {
+        DEFAULT_MAX_BATCH_SIZE
+    }
+}
+
+/// Embedding vectors stored as little-endian f32 files under the embeddings cache dir
+#[derive(Debug, Clone)]
+pub struct EmbeddingCache {
+    dir: PathBuf,
+}
+
+impl EmbeddingCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Cache under the app's `cache/embeddings` directory
+    pub fn global() -> Option<Self> {
+        let dir = try_get_path_manager_arc()
+            .ok()?
+            .cache_dir(CacheType::Embeddings);
+        register_cache_consumer("embeddings", CacheType::Embeddings, dir.clone());
+        Some(Self::new(dir))
+    }
+
+    fn entry_path(&self, model: &str, text: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(text.as_bytes());
+        let key = hex::encode(hasher.finalize());
+        self.dir.join(&key[..2]).join(format!("{}.bin", key))
+    }
+
+    pub async fn get(&self, model: &str, text: &str) -> Option<Vec<f32>> {
+        let bytes = tokio::fs::read(self.entry_path(model, text)).await.ok()?;
+        if bytes.is_empty() || bytes.len() % 4 != 0 {
+            return None;
+        }
+        Some(
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        )
+    }
+
+    /// Best effort: a failed write only costs a re-embed later
+    pub async fn put(&self, model: &str, text: &str, embedding: &[f32]) {
+        let path = self.entry_path(model, text);
+        let bytes: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        let result = async {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, bytes).await
+        }
+        .await;
+        if let Err(e) = result {
+            debug!("Failed to write embedding cache entry {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_embeddings_url_from_chat_endpoint() {
+        assert_eq!(
+            resolve_embeddings_url("https://api.openai.com/v1/chat/completions"),
+            "https://api.openai.com/v1/embeddings"
+        );
+        assert_eq!(
+            resolve_embeddings_url("http://localhost:11434/v1/"),
+            "http://localhost:11434/v1/embeddings"
+        );
+    }
+
+    #[test]
+    fn response_vectors_follow_input_order() {
+        let response: EmbeddingsResponse = serde_json::from_value(serde_json::json!({
+            "data": [
+                { "index": 1, "embedding": [0.5, 0.5] },
+                { "index": 0, "embedding": [1.0, 0.0] }
+            ],
+            "usage": { "prompt_tokens": 6, "total_tokens": 6 }
+        }))
+        .unwrap();
+
+        let (embeddings, usage) = response.into_ordered(2).unwrap();
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.5, 0.5]]);
+        assert_eq!(usage.total_tokens, 6);
+    }
+
+    #[tokio::test]
+    async fn cache_round_trips_by_model_and_content() {
+        let dir = std::env::temp_dir().join(format!("bitfun-embeddings-{}", uuid::Uuid::new_v4()));
+        let cache = EmbeddingCache::new(dir.clone());
+
+        cache.put("m1", "hello", &[0.25, -1.5]).await;
+        assert_eq!(cache.get("m1", "hello").await, Some(vec![0.25, -1.5]));
+        assert_eq!(cache.get("m2", "hello").await, None);
+        assert_eq!(cache.get("m1", "hello!").await, None);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
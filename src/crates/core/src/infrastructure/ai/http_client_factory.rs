@@ -0,0 +1,487 @@
+//! Shared HTTP clients for AI requests
+//!
+//! Clients are cached by (origin, proxy, custom headers, TLS settings), so parallel sessions
+//! talking to the same endpoint reuse pooled connections instead of repeating TLS handshakes.
+
+use crate::service::config::ProxyConfig;
+use anyhow::{anyhow, Result};
+use log::{debug, error, info, warn};
+use reqwest::{Certificate, Client, NoProxy, Proxy, Url};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+const TCP_KEEPALIVE_SECS: u64 = 60;
+const HTTP2_KEEPALIVE_INTERVAL_SECS: u64 = 30;
+const HTTP2_KEEPALIVE_TIMEOUT_SECS: u64 = 10;
+
+/// Connection settings an AI client needs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpClientOptions<'a> {
+    pub base_url: &'a str,
+    pub proxy: Option<&'a ProxyConfig>,
+    /// Unexpanded custom headers; only their hash is used
+    pub custom_headers: Option<&'a HashMap<String, String>>,
+    pub skip_ssl_verify: bool,
+    /// PEM bundle trusted in addition to the system roots
+    pub ca_bundle_path: Option<&'a str>,
+}
+
+/// Cache key; clients with equal keys share one connection pool
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct HttpClientKey {
+    origin: String,
+    proxy_url: Option<String>,
+    proxy_username: Option<String>,
+    proxy_password: Option<String>,
+    no_proxy: Vec<String>,
+    headers_hash: u64,
+    skip_ssl_verify: bool,
+    ca_bundle_path: Option<String>,
+}
+
+impl HttpClientKey {
+    fn new(options: &HttpClientOptions<'_>) -> Self {
+        let proxy = active_proxy(options.proxy);
+        Self {
+            origin: origin_of(options.base_url),
+            proxy_url: proxy.map(|proxy| proxy.url.trim().to_string()),
+            proxy_username: proxy.and_then(|proxy| proxy.username.clone()),
+            proxy_password: proxy.and_then(|proxy| proxy.password.clone()),
+            no_proxy: proxy
+                .map(|proxy| proxy.no_proxy.clone())
+                .unwrap_or_default(),
+            headers_hash: headers_hash(options.custom_headers),
+            skip_ssl_verify: options.skip_ssl_verify,
+            ca_bundle_path: options
+                .ca_bundle_path
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(str::to_string),
+        }
+    }
+}
+
+fn active_proxy(proxy: Option<&ProxyConfig>) -> Option<&ProxyConfig> {
+    proxy.filter(|proxy| proxy.enabled && !proxy.url.trim().is_empty())
+}
+
+/// `scheme://host[:port]`; unparseable URLs are keyed as-is
+fn origin_of(base_url: &str) -> String {
+    let base_url = base_url.trim();
+    Url::parse(base_url)
+        .map(|url| url.origin().ascii_serialization())
+        .unwrap_or_else(|_| base_url.to_string())
+}
+
+fn headers_hash(headers: Option<&HashMap<String, String>>) -> u64 {
+    let mut entries: Vec<_> = headers
+        .map(|headers| headers.iter().collect())
+        .unwrap_or_default();
+    entries.sort();
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Request counters for one pooled client
+#[derive(Debug, Default)]
+pub struct HttpClientStats {
+    requests: AtomicU64,
+    failures: AtomicU64,
+    /// Latency of the first request, which pays for connection setup (`u64::MAX` = none yet)
+    cold_latency_ms: AtomicU64,
+    warm_requests: AtomicU64,
+    warm_latency_ms_total: AtomicU64,
+}
+
+impl HttpClientStats {
+    fn new() -> Self {
+        Self {
+            cold_latency_ms: AtomicU64::new(u64::MAX),
+            ..Default::default()
+        }
+    }
+
+    /// Record one request; `latency` is the time until response headers arrived
+    pub fn record(&self, latency: Duration, success: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let latency_ms = latency.as_millis() as u64;
+        let first = self
+            .cold_latency_ms
+            .compare_exchange(u64::MAX, latency_ms, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok();
+        if !first {
+            self.warm_requests.fetch_add(1, Ordering::Relaxed);
+            self.warm_latency_ms_total
+                .fetch_add(latency_ms, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Client handed out by the factory, with the counters of its pool
+#[derive(Debug, Clone)]
+pub struct PooledHttpClient {
+    pub client: Client,
+    pub stats: Arc<HttpClientStats>,
+}
+
+struct PoolEntry {
+    client: Client,
+    stats: Arc<HttpClientStats>,
+    created_at: Instant,
+    reuses: u64,
+}
+
+/// One cached client as reported to the transport debug view
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpClientPoolInfo {
+    pub origin: String,
+    pub proxied: bool,
+    pub custom_ca: bool,
+    pub skip_ssl_verify: bool,
+    pub age_secs: u64,
+    /// Times the cached client was handed out again instead of building a new one
+    pub reuses: u64,
+    pub requests: u64,
+    pub failures: u64,
+    /// Time to response headers of the first request, including connection setup
+    pub cold_latency_ms: Option<u64>,
+    /// Mean time to response headers of later requests
+    pub avg_warm_latency_ms: Option<f64>,
+}
+
+/// Pool settings and per-client counters
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpTransportStats {
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout_secs: u64,
+    pub http2_keep_alive_interval_secs: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub clients: Vec<HttpClientPoolInfo>,
+}
+
+/// Builds and caches the reqwest clients used by `AIClient`
+pub struct HttpClientFactory {
+    clients: Mutex<HashMap<HttpClientKey, PoolEntry>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+static HTTP_CLIENT_FACTORY: LazyLock<HttpClientFactory> = LazyLock::new(HttpClientFactory::new);
+
+/// Process-wide factory shared by all AI clients
+pub fn get_global_http_client_factory() -> &'static HttpClientFactory {
+    &HTTP_CLIENT_FACTORY
+}
+
+impl Default for HttpClientFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpClientFactory {
+    pub fn new() -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cached client for these settings, built on first use
+    pub fn client_for(&self, options: &HttpClientOptions<'_>) -> PooledHttpClient {
+        let key = HttpClientKey::new(options);
+        let mut clients = self
+            .clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(entry) = clients.get_mut(&key) {
+            entry.reuses += 1;
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return PooledHttpClient {
+                client: entry.client.clone(),
+                stats: entry.stats.clone(),
+            };
+        }
+
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        debug!("Creating HTTP client for {}", key.origin);
+        let entry = PoolEntry {
+            client: build_client(options),
+            stats: Arc::new(HttpClientStats::new()),
+            created_at: Instant::now(),
+            reuses: 0,
+        };
+        let pooled = PooledHttpClient {
+            client: entry.client.clone(),
+            stats: entry.stats.clone(),
+        };
+        clients.insert(key, entry);
+        pooled
+    }
+
+    pub fn stats(&self) -> HttpTransportStats {
+        let clients = self
+            .clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut infos: Vec<HttpClientPoolInfo> = clients
+            .iter()
+            .map(|(key, entry)| {
+                let stats = &entry.stats;
+                let cold_latency_ms = stats.cold_latency_ms.load(Ordering::Relaxed);
+                let warm_requests = stats.warm_requests.load(Ordering::Relaxed);
+                HttpClientPoolInfo {
+                    origin: key.origin.clone(),
+                    proxied: key.proxy_url.is_some(),
+                    custom_ca: key.ca_bundle_path.is_some(),
+                    skip_ssl_verify: key.skip_ssl_verify,
+                    age_secs: entry.created_at.elapsed().as_secs(),
+                    reuses: entry.reuses,
+                    requests: stats.requests.load(Ordering::Relaxed),
+                    failures: stats.failures.load(Ordering::Relaxed),
+                    cold_latency_ms: (cold_latency_ms != u64::MAX).then_some(cold_latency_ms),
+                    avg_warm_latency_ms: (warm_requests > 0).then(|| {
+                        stats.warm_latency_ms_total.load(Ordering::Relaxed) as f64
+                            / warm_requests as f64
+                    }),
+                }
+            })
+            .collect();
+        infos.sort_by(|a, b| a.origin.cmp(&b.origin));
+
+        HttpTransportStats {
+            pool_max_idle_per_host: POOL_MAX_IDLE_PER_HOST,
+            pool_idle_timeout_secs: POOL_IDLE_TIMEOUT_SECS,
+            http2_keep_alive_interval_secs: HTTP2_KEEPALIVE_INTERVAL_SECS,
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            clients: infos,
+        }
+    }
+}
+
+fn build_client(options: &HttpClientOptions<'_>) -> Client {
+    let mut builder = Client::builder()
+        // SSE requests can legitimately stay open for a long time while the model
+        // thinks or executes tools. Keep only connect timeout here and let the
+        // stream handlers enforce idle timeouts between chunks.
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .user_agent("BitFun/1.0")
+        .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .tcp_keepalive(Some(Duration::from_secs(TCP_KEEPALIVE_SECS)))
+        .http2_keep_alive_interval(Duration::from_secs(HTTP2_KEEPALIVE_INTERVAL_SECS))
+        .http2_keep_alive_timeout(Duration::from_secs(HTTP2_KEEPALIVE_TIMEOUT_SECS))
+        .http2_keep_alive_while_idle(true)
+        .danger_accept_invalid_certs(options.skip_ssl_verify);
+
+    if options.skip_ssl_verify {
+        warn!(
+            "SSL certificate verification disabled - security risk, use only in test environments"
+        );
+    }
+
+    if let Some(path) = options
+        .ca_bundle_path
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        match load_ca_bundle(path) {
+            Ok(certificates) => {
+                info!(
+                    "Trusting {} extra CA certificate(s) from {}",
+                    certificates.len(),
+                    path
+                );
+                for certificate in certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+            Err(e) => error!("{}, using system roots only", e),
+        }
+    }
+
+    match active_proxy(options.proxy) {
+        Some(proxy_cfg) => match build_proxy(proxy_cfg) {
+            Ok(proxy) => {
+                info!("Using proxy: {}", proxy_cfg.url);
+                builder = builder.proxy(proxy);
+            }
+            Err(e) => {
+                error!(
+                    "Proxy configuration failed: {}, proceeding without proxy",
+                    e
+                );
+                builder = builder.no_proxy();
+            }
+        },
+        None => builder = builder.no_proxy(),
+    }
+
+    match builder.build() {
+        Ok(client) => client,
+        Err(e) => {
+            error!(
+                "HTTP client initialization failed: {}, using default client",
+                e
+            );
+            Client::new()
+        }
+    }
+}
+
+fn load_ca_bundle(path: &str) -> Result<Vec<Certificate>> {
+    let pem =
+        std::fs::read(path).map_err(|e| anyhow!("Failed to read CA bundle {}: {}", path, e))?;
+    let certificates = Certificate::from_pem_bundle(&pem)
+        .map_err(|e| anyhow!("Failed to parse CA bundle {}: {}", path, e))?;
+    if certificates.is_empty() {
+        return Err(anyhow!("CA bundle {} contains no certificates", path));
+    }
+    Ok(certificates)
+}
+
+fn build_proxy(config: &ProxyConfig) -> Result<Proxy> {
+    let mut proxy =
+        Proxy::all(config.url.trim()).map_err(|e| anyhow!("Failed to create proxy: {}", e))?;
+
+    if !config.no_proxy.is_empty() {
+        proxy = proxy.no_proxy(NoProxy::from_string(&config.no_proxy.join(",")));
+    }
+
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        if !username.is_empty() && !password.is_empty() {
+            proxy = proxy.basic_auth(username, password);
+            debug!("Proxy authentication configured for user: {}", username);
+        }
+    }
+
+    Ok(proxy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn clients_are_shared_per_origin_proxy_and_headers() {
+        let factory = HttpClientFactory::new();
+        let options = HttpClientOptions {
+            base_url: "https://api.example.com/v1/chat/completions",
+            ..Default::default()
+        };
+
+        factory.client_for(&options);
+        factory.client_for(&HttpClientOptions {
+            base_url: "https://api.example.com/v1/responses",
+            ..options
+        });
+        let headers: HashMap<String, String> = [("X-Org".to_string(), "a".to_string())]
+            .into_iter()
+            .collect();
+        factory.client_for(&HttpClientOptions {
+            custom_headers: Some(&headers),
+            ..options
+        });
+        factory.client_for(&HttpClientOptions {
+            base_url: "https://other.example.com/v1",
+            ..options
+        });
+
+        let stats = factory.stats();
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(stats.cache_misses, 3);
+        assert_eq!(stats.clients.len(), 3);
+        assert_eq!(stats.clients[0].origin, "https://api.example.com");
+    }
+
+    #[test]
+    fn stats_split_cold_and_warm_latency() {
+        let stats = HttpClientStats::new();
+        stats.record(Duration::from_millis(120), true);
+        stats.record(Duration::from_millis(500), false);
+        stats.record(Duration::from_millis(20), true);
+        stats.record(Duration::from_millis(30), true);
+
+        let factory = HttpClientFactory::new();
+        factory.clients.lock().unwrap().insert(
+            HttpClientKey::new(&HttpClientOptions::default()),
+            PoolEntry {
+                client: Client::new(),
+                stats: Arc::new(stats),
+                created_at: Instant::now(),
+                reuses: 0,
+            },
+        );
+
+        let info = &factory.stats().clients[0];
+        assert_eq!(info.requests, 4);
+        assert_eq!(info.failures, 1);
+        assert_eq!(info.cold_latency_ms, Some(120));
+        assert_eq!(info.avg_warm_latency_ms, Some(25.0));
+    }
+
+    #[tokio::test]
+    async fn back_to_back_requests_reuse_one_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(AtomicU64::new(0));
+        let accepted_by_server = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                accepted_by_server.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    while matches!(socket.read(&mut buf).await, Ok(n) if n > 0) {
+                        let response = "HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok";
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let base_url = format!("http://{}/v1", addr);
+        let factory = HttpClientFactory::new();
+        let options = HttpClientOptions {
+            base_url: &base_url,
+            ..Default::default()
+        };
+
+        for _ in 0..5 {
+            let pooled = factory.client_for(&options);
+            let started = Instant::now();
+            let response = pooled.client.get(&base_url).send().await.unwrap();
+            pooled.stats.record(started.elapsed(), true);
+            assert_eq!(response.text().await.unwrap(), "ok");
+        }
+
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        let stats = factory.stats();
+        assert_eq!(stats.cache_hits, 4);
+        assert_eq!(stats.clients[0].requests, 5);
+        assert!(stats.clients[0].avg_warm_latency_ms.is_some());
+    }
+}
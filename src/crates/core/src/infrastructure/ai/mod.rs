@@ -4,11 +4,20 @@
 
 pub mod client;
 pub mod client_factory;
+pub mod embeddings;
+pub mod http_client_factory;
 pub mod providers;
+pub mod request_log;
+pub mod tokenizer;
 
 pub use ai_stream_handlers;
 
-pub use client::{AIClient, StreamResponse};
+pub use client::{AIClient, StreamRequestOptions, StreamResponse};
 pub use client_factory::{
     get_global_ai_client_factory, initialize_global_ai_client_factory, AIClientFactory,
 };
+pub use embeddings::{EmbeddingCache, EmbeddingResult, EmbeddingUsage};
+pub use http_client_factory::{
+    get_global_http_client_factory, HttpClientFactory, HttpTransportStats,
+};
+pub use tokenizer::{count_text_tokens, estimate_tokens, TokenizerKind};
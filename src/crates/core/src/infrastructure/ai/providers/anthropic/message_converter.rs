@@ -3,9 +3,14 @@
 //! Converts the unified message format to Anthropic Claude API format
 
 use crate::util::types::{Message, ToolDefinition};
+use crate::util::TokenCounter;
+use ai_stream_handlers::UnifiedServerToolEvent;
 use log::warn;
 use serde_json::{json, Value};
 
+/// Anthropic accepts at most this many `cache_control` breakpoints per request
+const MAX_CACHE_BREAKPOINTS: usize = 4;
+
 pub struct AnthropicMessageConverter;
 
 impl AnthropicMessageConverter {
@@ -143,6 +148,14 @@ impl AnthropicMessageConverter {
             }
         }
 
+        // Server tool calls precede the answer text, as in the original response
+        content.extend(
+            msg.server_tool_events
+                .iter()
+                .flatten()
+                .filter_map(Self::server_tool_block),
+        );
+
         if let Some(text) = msg.content {
             if !text.is_empty() {
                 content.push(json!({
@@ -173,6 +186,28 @@ impl AnthropicMessageConverter {
         }
     }
 
+    /// Content block replaying a server tool call; citations stay with the plain answer text
+    fn server_tool_block(event: &UnifiedServerToolEvent) -> Option<Value> {
+        match event {
+            UnifiedServerToolEvent::Use { id, name, input } => Some(json!({
+                "type": "server_tool_use",
+                "id": id,
+                "name": name,
+                "input": input
+            })),
+            UnifiedServerToolEvent::Result {
+                tool_use_id,
+                name,
+                content,
+            } => Some(json!({
+                "type": format!("{}_tool_result", name),
+                "tool_use_id": tool_use_id,
+                "content": content
+            })),
+            UnifiedServerToolEvent::Citation { .. } => None,
+        }
+    }
+
     fn convert_tool_result_message(msg: Message) -> Value {
         let tool_call_id = msg.tool_call_id.unwrap_or_default();
         let text = msg.content.unwrap_or_default();
@@ -208,6 +243,120 @@ impl AnthropicMessageConverter {
         })
     }
 
+    /// Mark tools, system prompt and the conversation prefix with `cache_control` breakpoints
+    ///
+    /// Breakpoints follow Anthropic's prefix order (tools, system, messages) and are only placed
+    /// once the estimated prefix reaches `min_tokens`, since shorter prefixes are never cached.
+    /// The last two messages are marked so the next round reads what this round writes.
+    /// Returns the number of breakpoints placed.
+    pub fn apply_prompt_caching(request_body: &mut Value, min_tokens: usize) -> usize {
+        let mut placed = 0;
+        let mut prefix_tokens = 0;
+
+        if let Some(tools) = request_body.get_mut("tools").and_then(Value::as_array_mut) {
+            prefix_tokens += tools
+                .iter()
+                .map(|tool| TokenCounter::estimate_tokens(&tool.to_string()))
+                .sum::<usize>();
+            if prefix_tokens >= min_tokens {
+                if let Some(last_tool) = tools.last_mut() {
+                    Self::mark_cache_breakpoint(last_tool);
+                    placed += 1;
+                }
+            }
+        }
+
+        if let Some(system) = request_body
+            .get_mut("system")
+            .filter(|system| system.as_str() != Some(""))
+        {
+            if let Value::String(text) = system {
+                let text = std::mem::take(text);
+                *system = json!([{ "type": "text", "text": text }]);
+            }
+            prefix_tokens += TokenCounter::estimate_tokens(&system.to_string());
+            if prefix_tokens >= min_tokens {
+                if let Some(last_block) = system.as_array_mut().and_then(|b| b.last_mut()) {
+                    Self::mark_cache_breakpoint(last_block);
+                    placed += 1;
+                }
+            }
+        }
+
+        let Some(messages) = request_body
+            .get_mut("messages")
+            .and_then(Value::as_array_mut)
+        else {
+            return placed;
+        };
+        let message_tokens: Vec<usize> = messages
+            .iter()
+            .map(|msg| TokenCounter::estimate_tokens(&msg.to_string()))
+            .collect();
+        let first_marked = messages.len().saturating_sub(2);
+        for (index, message) in messages.iter_mut().enumerate() {
+            prefix_tokens += message_tokens[index];
+            if index < first_marked || prefix_tokens < min_tokens {
+                continue;
+            }
+            if placed >= MAX_CACHE_BREAKPOINTS {
+                break;
+            }
+            if Self::mark_message_breakpoint(message) {
+                placed += 1;
+            }
+        }
+
+        placed
+    }
+
+    /// Put the breakpoint on the last block of a message that can carry one
+    fn mark_message_breakpoint(message: &mut Value) -> bool {
+        let Some(content) = message.get_mut("content") else {
+            return false;
+        };
+        if let Value::String(text) = content {
+            if text.is_empty() {
+                return false;
+            }
+            let text = std::mem::take(text);
+            *content = json!([{ "type": "text", "text": text }]);
+        }
+
+        // Thinking blocks and empty text blocks reject cache_control
+        let Some(block) = content.as_array_mut().and_then(|blocks| {
+            blocks
+                .iter_mut()
+                .rev()
+                .find(|block| match block.get("type").and_then(Value::as_str) {
+                    Some("thinking") | Some("redacted_thinking") => false,
+                    Some("text") => block
+                        .get("text")
+                        .and_then(Value::as_str)
+                        .is_some_and(|text| !text.is_empty()),
+                    _ => true,
+                })
+        }) else {
+            return false;
+        };
+        Self::mark_cache_breakpoint(block);
+        true
+    }
+
+    fn mark_cache_breakpoint(block: &mut Value) {
+        if let Some(obj) = block.as_object_mut() {
+            obj.insert("cache_control".to_string(), json!({ "type": "ephemeral" }));
+        }
+    }
+
+    /// Declaration of the server-side web search tool
+    pub fn web_search_tool() -> Value {
+        json!({
+            "type": "web_search_20250305",
+            "name": "web_search"
+        })
+    }
+
     /// Convert tool definitions to Anthropic format
     pub fn convert_tools(tools: Option<Vec<ToolDefinition>>) -> Option<Vec<Value>> {
         tools.map(|tool_defs| {
@@ -224,3 +373,97 @@ impl AnthropicMessageConverter {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AnthropicMessageConverter;
+    use crate::util::types::Message;
+    use ai_stream_handlers::UnifiedServerToolEvent;
+    use serde_json::json;
+
+    fn cache_marks(value: &serde_json::Value) -> usize {
+        value.to_string().matches("cache_control").count()
+    }
+
+    #[test]
+    fn marks_tools_system_and_last_two_messages() {
+        let mut body = json!({
+            "tools": [
+                { "name": "read", "description": "Read a file", "input_schema": {} },
+                { "name": "write", "description": "Write a file", "input_schema": {} }
+            ],
+            "system": "You are a helpful coding assistant",
+            "messages": [
+                { "role": "user", "content": "first question" },
+                { "role": "assistant", "content": [
+                    { "type": "text", "text": "first answer" },
+                    { "type": "thinking", "thinking": "", "signature": "" }
+                ]},
+                { "role": "user", "content": "second question" }
+            ]
+        });
+
+        assert_eq!(
+            AnthropicMessageConverter::apply_prompt_caching(&mut body, 0),
+            4
+        );
+        assert_eq!(cache_marks(&body), 4);
+        assert!(body["tools"][1].get("cache_control").is_some());
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert!(body["system"][0].get("cache_control").is_some());
+        assert!(body["messages"][0].get("cache_control").is_none());
+        assert!(body["messages"][1]["content"][0]
+            .get("cache_control")
+            .is_some());
+        assert!(body["messages"][2]["content"][0]
+            .get("cache_control")
+            .is_some());
+    }
+
+    #[test]
+    fn skips_prefixes_below_min_tokens() {
+        let mut body = json!({
+            "system": "short",
+            "messages": [{ "role": "user", "content": "hi" }]
+        });
+
+        assert_eq!(
+            AnthropicMessageConverter::apply_prompt_caching(&mut body, 1024),
+            0
+        );
+        assert_eq!(cache_marks(&body), 0);
+    }
+
+    #[test]
+    fn replays_web_search_blocks_before_answer_text() {
+        let mut answer = Message::assistant("Rust 2024 shipped.".to_string());
+        answer.server_tool_events = Some(vec![
+            UnifiedServerToolEvent::Use {
+                id: "srvtoolu_1".to_string(),
+                name: "web_search".to_string(),
+                input: json!({ "query": "rust 2024" }),
+            },
+            UnifiedServerToolEvent::Result {
+                tool_use_id: "srvtoolu_1".to_string(),
+                name: "web_search".to_string(),
+                content: json!([{ "type": "web_search_result", "encrypted_content": "abc" }]),
+            },
+            UnifiedServerToolEvent::Citation {
+                citation: json!({ "url": "https://blog.rust-lang.org" }),
+            },
+        ]);
+
+        let (_, messages) = AnthropicMessageConverter::convert_messages(vec![
+            Message::user("news?".to_string()),
+            answer,
+        ]);
+
+        let content = messages[1]["content"].as_array().expect("content blocks");
+        assert_eq!(content.len(), 3);
+        assert_eq!(content[0]["type"], json!("server_tool_use"));
+        assert_eq!(content[0]["input"]["query"], json!("rust 2024"));
+        assert_eq!(content[1]["type"], json!("web_search_tool_result"));
+        assert_eq!(content[1]["content"][0]["encrypted_content"], json!("abc"));
+        assert_eq!(content[2]["type"], json!("text"));
+    }
+}
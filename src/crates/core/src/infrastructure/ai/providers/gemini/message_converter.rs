@@ -665,6 +665,7 @@ mod tests {
                 tool_call_id: None,
                 name: None,
                 tool_image_attachments: None,
+                server_tool_events: None,
             },
             Message {
                 role: "tool".to_string(),
@@ -675,6 +676,7 @@ mod tests {
                 tool_call_id: Some("call_1".to_string()),
                 name: Some("get_weather".to_string()),
                 tool_image_attachments: None,
+                server_tool_events: None,
             },
         ];
 
@@ -718,6 +720,7 @@ mod tests {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }];
 
         let (_, contents) =
@@ -755,6 +758,7 @@ mod tests {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }];
 
         let (_, contents) = GeminiMessageConverter::convert_messages(messages, "gemini-2.5-pro");
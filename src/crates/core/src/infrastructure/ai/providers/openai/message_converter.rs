@@ -160,15 +160,8 @@ impl OpenAIMessageConverter {
                             }));
                         }
                     }
-                    Some("image_url") if role != "assistant" => {
-                        let image_url = item.get("image_url").and_then(|value| {
-                            value
-                                .get("url")
-                                .and_then(Value::as_str)
-                                .or_else(|| value.as_str())
-                        });
-
-                        if let Some(image_url) = image_url {
+                    Some("image_url") | Some("image") if role != "assistant" => {
+                        if let Some(image_url) = Self::image_part_url(item) {
                             content_items.push(json!({
                                 "type": "input_image",
                                 "image_url": image_url,
@@ -190,6 +183,56 @@ impl OpenAIMessageConverter {
         }
     }
 
+    /// URL of an image content part: OpenAI `image_url` (object or string form) or an
+    /// Anthropic-style base64 `image` block, the latter as a data URL.
+    fn image_part_url(item: &Value) -> Option<String> {
+        match item.get("type").and_then(Value::as_str)? {
+            "image_url" => {
+                let value = item.get("image_url")?;
+                value
+                    .get("url")
+                    .and_then(Value::as_str)
+                    .or_else(|| value.as_str())
+                    .map(str::to_string)
+            }
+            "image" => {
+                let source = item.get("source")?;
+                if source.get("type").and_then(Value::as_str) != Some("base64") {
+                    return None;
+                }
+                let media_type = source.get("media_type").and_then(Value::as_str)?;
+                let data = source.get("data").and_then(Value::as_str)?;
+                Some(format!("data:{};base64,{}", media_type, data))
+            }
+            _ => None,
+        }
+    }
+
+    /// Rewrite image parts of a Chat Completions content array into the
+    /// `{"type":"image_url","image_url":{"url":...}}` shape vision models expect.
+    fn normalize_chat_content_parts(parts: Vec<Value>) -> Vec<Value> {
+        parts
+            .into_iter()
+            .map(|item| match item.get("type").and_then(Value::as_str) {
+                Some("image_url") | Some("image") => match Self::image_part_url(&item) {
+                    Some(url) => {
+                        let mut image_url = json!({ "url": url });
+                        if let Some(detail) = item
+                            .get("image_url")
+                            .and_then(|value| value.get("detail"))
+                            .cloned()
+                        {
+                            image_url["detail"] = detail;
+                        }
+                        json!({ "type": "image_url", "image_url": image_url })
+                    }
+                    None => item,
+                },
+                _ => item,
+            })
+            .collect()
+    }
+
     fn responses_text_item_type(role: &str) -> &'static str {
         if role == "assistant" {
             "output_text"
@@ -259,12 +302,8 @@ impl OpenAIMessageConverter {
                     warn!("[OpenAI] Message content is empty: role={}", msg.role);
                 }
             } else {
-                if let Ok(parsed) = serde_json::from_str::<Value>(&content) {
-                    if parsed.is_array() {
-                        openai_msg["content"] = parsed;
-                    } else {
-                        openai_msg["content"] = Value::String(content);
-                    }
+                if let Ok(Value::Array(parts)) = serde_json::from_str::<Value>(&content) {
+                    openai_msg["content"] = Value::Array(Self::normalize_chat_content_parts(parts));
                 } else {
                     openai_msg["content"] = Value::String(content);
                 }
@@ -343,6 +382,24 @@ impl OpenAIMessageConverter {
                 .collect()
         })
     }
+
+    /// Convert tool definitions to the Responses API shape, where the function fields sit at
+    /// the top level instead of under `function`.
+    pub fn convert_tools_to_responses(tools: Option<Vec<ToolDefinition>>) -> Option<Vec<Value>> {
+        tools.map(|tool_defs| {
+            tool_defs
+                .into_iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters
+                    })
+                })
+                .collect()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -374,6 +431,7 @@ mod tests {
                 tool_call_id: Some("call_1".to_string()),
                 name: Some("get_weather".to_string()),
                 tool_image_attachments: None,
+                server_tool_events: None,
             },
         ];
 
@@ -412,6 +470,7 @@ mod tests {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }];
 
         let (_, input) = OpenAIMessageConverter::convert_messages_to_responses_input(messages);
@@ -435,6 +494,7 @@ mod tests {
                 mime_type: "image/jpeg".to_string(),
                 data_base64: "AAA".to_string(),
             }]),
+            server_tool_events: None,
         }];
 
         let (_, input) = OpenAIMessageConverter::convert_messages_to_responses_input(messages);
@@ -465,6 +525,7 @@ mod tests {
                 mime_type: "image/jpeg".to_string(),
                 data_base64: "YmFi".to_string(),
             }]),
+            server_tool_events: None,
         };
 
         let openai = OpenAIMessageConverter::convert_messages(vec![msg]);
@@ -473,4 +534,88 @@ mod tests {
         assert_eq!(content[1]["type"], json!("text"));
         assert_eq!(content[1]["text"], json!("ok"));
     }
+
+    fn user_message(content: serde_json::Value) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: Some(content.to_string()),
+            reasoning_content: None,
+            thinking_signature: None,
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+            tool_image_attachments: None,
+            server_tool_events: None,
+        }
+    }
+
+    #[test]
+    fn keeps_image_url_parts_in_chat_completions_user_content() {
+        let msg = user_message(json!([
+            {
+                "type": "image_url",
+                "image_url": { "url": "data:image/png;base64,abc", "detail": "high" }
+            },
+            { "type": "text", "text": "What is this?" }
+        ]));
+
+        let openai = OpenAIMessageConverter::convert_messages(vec![msg]);
+        assert_eq!(
+            openai[0],
+            json!({
+                "role": "user",
+                "content": [
+                    {
+                        "type": "image_url",
+                        "image_url": { "url": "data:image/png;base64,abc", "detail": "high" }
+                    },
+                    { "type": "text", "text": "What is this?" }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn normalizes_other_image_shapes_to_chat_completions_image_url() {
+        let msg = user_message(json!([
+            {
+                "type": "image",
+                "source": { "type": "base64", "media_type": "image/jpeg", "data": "/9j/" }
+            },
+            { "type": "image_url", "image_url": "https://example.com/a.png" },
+            { "type": "text", "text": "Compare" }
+        ]));
+
+        let openai = OpenAIMessageConverter::convert_messages(vec![msg]);
+        assert_eq!(
+            openai[0]["content"],
+            json!([
+                {
+                    "type": "image_url",
+                    "image_url": { "url": "data:image/jpeg;base64,/9j/" }
+                },
+                {
+                    "type": "image_url",
+                    "image_url": { "url": "https://example.com/a.png" }
+                },
+                { "type": "text", "text": "Compare" }
+            ])
+        );
+    }
+
+    #[test]
+    fn converts_base64_image_block_to_responses_input_image() {
+        let msg = user_message(json!([
+            {
+                "type": "image",
+                "source": { "type": "base64", "media_type": "image/png", "data": "abc" }
+            }
+        ]));
+
+        let (_, input) = OpenAIMessageConverter::convert_messages_to_responses_input(vec![msg]);
+        assert_eq!(
+            input[0]["content"],
+            json!([{ "type": "input_image", "image_url": "data:image/png;base64,abc" }])
+        );
+    }
 }
@@ -0,0 +1,435 @@
+//! Opt-in request/response logging
+//!
+//! Writes one JSON line per request, raw SSE event and reconstructed response to the
+//! `ai::request_log` target, which the desktop logger routes into `ai.log`.
+
+use super::client::StreamResponse;
+use crate::service::config::types::AIRequestLogConfig;
+use crate::util::types::{Message, ToolDefinition};
+use ai_stream_handlers::{UnifiedResponse, UnifiedTokenUsage};
+use anyhow::Result;
+use futures::StreamExt;
+use log::info;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::sync::{LazyLock, RwLock};
+use tokio::sync::mpsc;
+
+const LOG_TARGET: &str = "ai::request_log";
+
+/// Tools whose results are file bodies
+const FILE_CONTENT_TOOLS: &[&str] = &["Read", "GetFileDiff"];
+/// Tool-call argument keys that carry file bodies
+const FILE_CONTENT_ARGS: &[&str] = &["content", "old_string", "new_string", "file_text"];
+
+static CONFIG: LazyLock<RwLock<AIRequestLogConfig>> = LazyLock::new(Default::default);
+
+static DATA_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"data:([\w/+.-]+);base64,[A-Za-z0-9+/=]+").unwrap());
+
+static SECRET_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+        r"\bsk-[A-Za-z0-9_-]{16,}",
+        r"\bAKIA[0-9A-Z]{16}\b",
+        r"\bgh[pousr]_[A-Za-z0-9]{20,}",
+        r"\bxox[abpr]-[A-Za-z0-9-]{10,}",
+        r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+        r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]{16,}",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect()
+});
+
+/// `api_key = "..."`, `password: ...`; the key name is kept, the value masked
+static SECRET_ASSIGNMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\b((?:api[_-]?key|secret|password|passwd|access[_-]?token|auth[_-]?token)["']?\s*[:=]\s*["']?)[^\s"',]{8,}"#,
+    )
+    .unwrap()
+});
+
+/// Replace the active settings; called by the config manager on load and on every change
+pub fn apply_config(config: &AIRequestLogConfig) {
+    if let Ok(mut current) = CONFIG.write() {
+        *current = config.clone();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    CONFIG.read().map(|config| config.enabled).unwrap_or(false)
+}
+
+/// Masks API keys, tokens and private keys in `text`, regardless of the
+/// request-log settings; used for crash reports.
+pub fn redact_secrets(text: &str) -> String {
+    let mut text = text.to_string();
+    for pattern in SECRET_PATTERNS.iter() {
+        text = pattern.replace_all(&text, "[REDACTED]").into_owned();
+    }
+    SECRET_ASSIGNMENT
+        .replace_all(&text, "${1}[REDACTED]")
+        .into_owned()
+}
+
+/// Settings snapshot, `None` while logging is off
+fn active_config() -> Option<AIRequestLogConfig> {
+    CONFIG
+        .read()
+        .ok()
+        .filter(|config| config.enabled)
+        .map(|config| config.clone())
+}
+
+struct Redactor {
+    config: AIRequestLogConfig,
+}
+
+impl Redactor {
+    fn text(&self, text: &str) -> String {
+        let mut text = DATA_URL
+            .replace_all(text, "data:$1;base64,[omitted]")
+            .into_owned();
+
+        if self.config.redact_secrets {
+            text = redact_secrets(&text);
+        }
+
+        truncate_chars(&text, self.config.max_content_chars)
+    }
+
+    fn file_body(&self, text: &str) -> String {
+        if self.config.redact_file_contents {
+            format!("[file content redacted: {} chars]", text.chars().count())
+        } else {
+            self.text(text)
+        }
+    }
+
+    /// Tool-call arguments as JSON, with file bodies masked
+    fn arguments(&self, arguments: &Value) -> Value {
+        match arguments {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Value::String(s) if FILE_CONTENT_ARGS.contains(&key.as_str()) => {
+                                Value::String(self.file_body(s))
+                            }
+                            Value::String(s) => Value::String(self.text(s)),
+                            other => other.clone(),
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            ),
+            other => Value::String(self.text(&other.to_string())),
+        }
+    }
+
+    fn raw_arguments(&self, arguments: &str) -> Value {
+        match serde_json::from_str::<Value>(arguments) {
+            Ok(parsed) => self.arguments(&parsed),
+            Err(_) => Value::String(self.text(arguments)),
+        }
+    }
+
+    fn message(&self, message: &Message) -> Value {
+        let content = message.content.as_deref().map(|content| {
+            let is_file_result = message.role == "tool"
+                && message
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| FILE_CONTENT_TOOLS.contains(&name));
+            if is_file_result {
+                self.file_body(content)
+            } else {
+                self.text(content)
+            }
+        });
+
+        let tool_calls: Option<Vec<Value>> = message.tool_calls.as_ref().map(|calls| {
+            calls
+                .iter()
+                .map(|call| {
+                    let arguments = serde_json::to_value(&call.arguments).unwrap_or_default();
+                    json!({ "name": call.name, "arguments": self.arguments(&arguments) })
+                })
+                .collect()
+        });
+
+        json!({
+            "role": message.role,
+            "name": message.name,
+            "content": content,
+            "reasoning_chars": message.reasoning_content.as_ref().map(|r| r.chars().count()),
+            "tool_calls": tool_calls,
+            "images": message.tool_image_attachments.as_ref().map(|images| images.len()),
+        })
+    }
+
+    /// Serialize an entry, collapsing it to a string prefix when it exceeds the size cap
+    fn write(&self, entry: Value) {
+        let line = entry.to_string();
+        if line.len() <= self.config.max_entry_bytes {
+            info!(target: LOG_TARGET, "{}", line);
+            return;
+        }
+
+        let mut cut = self.config.max_entry_bytes;
+        while !line.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let capped = json!({
+            "kind": entry.get("kind"),
+            "request_id": entry.get("request_id"),
+            "truncated_bytes": line.len() - cut,
+            "entry": &line[..cut],
+        });
+        info!(target: LOG_TARGET, "{}", capped);
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}…[+{} chars]", kept, total - max_chars)
+}
+
+/// Log an outgoing request; returns the id that ties its SSE and response entries together
+pub(crate) fn log_request(
+    model: &str,
+    messages: &[Message],
+    tools: Option<&[ToolDefinition]>,
+) -> Option<String> {
+    let config = active_config()?;
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let redactor = Redactor { config };
+
+    let tool_names: Option<Vec<&str>> =
+        tools.map(|tools| tools.iter().map(|tool| tool.name.as_str()).collect());
+    redactor.write(json!({
+        "kind": "request",
+        "request_id": request_id,
+        "timestamp": chrono::Utc::now().timestamp_millis(),
+        "model": model,
+        "messages": messages.iter().map(|m| redactor.message(m)).collect::<Vec<_>>(),
+        "tools": tool_names,
+    }));
+
+    Some(request_id)
+}
+
+/// Tap the response so raw SSE events are logged as they arrive and the reconstructed
+/// response is logged once the stream is finished or dropped
+pub(crate) fn attach(
+    request_id: String,
+    model: &str,
+    mut response: StreamResponse,
+) -> StreamResponse {
+    let Some(config) = active_config() else {
+        return response;
+    };
+
+    if let Some(mut raw_rx) = response.raw_sse_rx.take() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let redactor = Redactor {
+            config: config.clone(),
+        };
+        let request_id = request_id.clone();
+        tokio::spawn(async move {
+            while let Some(data) = raw_rx.recv().await {
+                redactor.write(json!({
+                    "kind": "raw_sse",
+                    "request_id": request_id,
+                    "data": redactor.text(&data),
+                }));
+                let _ = tx.send(data);
+            }
+        });
+        response.raw_sse_rx = Some(rx);
+    }
+
+    let mut recorder = ResponseRecorder {
+        redactor: Redactor { config },
+        request_id,
+        model: model.to_string(),
+        text: String::new(),
+        reasoning: String::new(),
+        tool_calls: Vec::new(),
+        usage: None,
+        finish_reason: None,
+        error: None,
+    };
+    response.stream = Box::pin(response.stream.inspect(move |item| recorder.record(item)));
+    response
+}
+
+/// Accumulates streamed chunks; writes the response entry when dropped with the stream
+struct ResponseRecorder {
+    redactor: Redactor,
+    request_id: String,
+    model: String,
+    text: String,
+    reasoning: String,
+    /// (id, name, arguments)
+    tool_calls: Vec<(Option<String>, Option<String>, String)>,
+    usage: Option<UnifiedTokenUsage>,
+    finish_reason: Option<String>,
+    error: Option<String>,
+}
+
+impl ResponseRecorder {
+    fn record(&mut self, item: &Result<UnifiedResponse>) {
+        let chunk = match item {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        if let Some(text) = &chunk.text {
+            self.text.push_str(text);
+        }
+        if let Some(reasoning) = &chunk.reasoning_content {
+            self.reasoning.push_str(reasoning);
+        }
+        if let Some(tool_call) = &chunk.tool_call {
+            let starts_new_call = tool_call.id.is_some() || self.tool_calls.is_empty();
+            if starts_new_call {
+                self.tool_calls
+                    .push((tool_call.id.clone(), tool_call.name.clone(), String::new()));
+            }
+            if let Some(last) = self.tool_calls.last_mut() {
+                if last.1.is_none() {
+                    last.1 = tool_call.name.clone();
+                }
+                if let Some(arguments) = &tool_call.arguments {
+                    last.2.push_str(arguments);
+                }
+            }
+        }
+        if chunk.usage.is_some() {
+            self.usage = chunk.usage.clone();
+        }
+        if chunk.finish_reason.is_some() {
+            self.finish_reason = chunk.finish_reason.clone();
+        }
+    }
+}
+
+impl Drop for ResponseRecorder {
+    fn drop(&mut self) {
+        let redactor = &self.redactor;
+        let tool_calls: Vec<Value> = self
+            .tool_calls
+            .iter()
+            .map(|(id, name, arguments)| {
+                json!({ "id": id, "name": name, "arguments": redactor.raw_arguments(arguments) })
+            })
+            .collect();
+
+        redactor.write(json!({
+            "kind": "response",
+            "request_id": self.request_id,
+            "timestamp": chrono::Utc::now().timestamp_millis(),
+            "model": self.model,
+            "text": redactor.text(&self.text),
+            "reasoning": redactor.text(&self.reasoning),
+            "tool_calls": tool_calls,
+            "finish_reason": self.finish_reason,
+            "usage": self.usage,
+            "error": self.error,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor() -> Redactor {
+        Redactor {
+            config: AIRequestLogConfig {
+                enabled: true,
+                max_content_chars: 64,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn secrets_and_file_bodies_are_masked() {
+        let redactor = redactor();
+        assert_eq!(
+            redactor.text("key sk-abcdefghijklmnopqrstuv and api_key=\"hunter2hunter2\""),
+            "key [REDACTED] and api_key=\"[REDACTED]\""
+        );
+        assert_eq!(
+            redactor.text("see data:image/png;base64,iVBORw0KGgo="),
+            "see data:image/png;base64,[omitted]"
+        );
+
+        let read_result = Message {
+            role: "tool".to_string(),
+            name: Some("Read".to_string()),
+            ..Message::user("fn main() {}".to_string())
+        };
+        assert_eq!(
+            redactor.message(&read_result)["content"],
+            "[file content redacted: 12 chars]"
+        );
+
+        let arguments = redactor.raw_arguments(r#"{"file_path":"a.rs","content":"secret body"}"#);
+        assert_eq!(arguments["file_path"], "a.rs");
+        assert_eq!(arguments["content"], "[file content redacted: 11 chars]");
+    }
+
+    #[test]
+    fn long_content_is_truncated() {
+        let text = "x".repeat(100);
+        assert_eq!(
+            redactor().text(&text),
+            format!("{}…[+36 chars]", "x".repeat(64))
+        );
+    }
+
+    #[test]
+    fn streamed_tool_call_chunks_are_merged() {
+        let mut recorder = ResponseRecorder {
+            redactor: redactor(),
+            request_id: "req".to_string(),
+            model: "m".to_string(),
+            text: String::new(),
+            reasoning: String::new(),
+            tool_calls: Vec::new(),
+            usage: None,
+            finish_reason: None,
+            error: None,
+        };
+        let chunk = |id: Option<&str>, name: Option<&str>, args: &str| -> Result<UnifiedResponse> {
+            Ok(UnifiedResponse {
+                tool_call: Some(ai_stream_handlers::UnifiedToolCall {
+                    id: id.map(str::to_string),
+                    name: name.map(str::to_string),
+                    arguments: Some(args.to_string()),
+                    index: None,
+                }),
+                ..Default::default()
+            })
+        };
+
+        recorder.record(&chunk(Some("call_1"), Some("Grep"), "{\"pattern\""));
+        recorder.record(&chunk(None, None, ":\"todo\"}"));
+        recorder.record(&chunk(Some("call_2"), Some("Read"), "{}"));
+
+        assert_eq!(recorder.tool_calls.len(), 2);
+        assert_eq!(recorder.tool_calls[0].2, "{\"pattern\":\"todo\"}");
+        assert_eq!(recorder.tool_calls[1].1.as_deref(), Some("Read"));
+    }
+}
@@ -0,0 +1,273 @@
+//! Local token estimation
+//!
+//! Counts request tokens before sending so compression and context-window checks
+//! don't have to wait for the provider's usage report.
+
+use crate::util::types::{Message, ToolDefinition};
+use serde_json::Value;
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+use tiktoken_rs::CoreBPE;
+
+/// Per-message framing overhead (role markers, separators), as in OpenAI's cookbook
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Extra token when a message carries a `name`
+const TOKENS_PER_NAME: usize = 1;
+/// Every reply is primed with `<|start|>assistant<|message|>`
+const REPLY_PRIMING_TOKENS: usize = 3;
+/// Framing around each tool call or tool definition
+const TOKENS_PER_TOOL: usize = 10;
+/// Flat cost for an attached image (a 1024x1024 image split into 512px tiles)
+pub const IMAGE_ATTACHMENT_TOKENS: usize = 850;
+
+/// Anthropic's published rule of thumb: one token is about 3.5 English characters
+const ANTHROPIC_CHARS_PER_TOKEN: f64 = 3.5;
+/// Generic fallback for models without a known tokenizer
+const FALLBACK_CHARS_PER_TOKEN: usize = 4;
+
+/// How tokens are counted for a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerKind {
+    /// Exact BPE count with the model's tiktoken encoding
+    Bpe(Tokenizer),
+    /// Character-based approximation for Claude models
+    Anthropic,
+    /// chars / 4
+    Approximate,
+}
+
+impl TokenizerKind {
+    /// Pick the tokenizer for a model name; provider prefixes like `openai/` are ignored
+    pub fn for_model(model: &str) -> Self {
+        let name = model
+            .rsplit('/')
+            .next()
+            .unwrap_or(model)
+            .trim()
+            .to_lowercase();
+
+        if name.contains("claude") {
+            return Self::Anthropic;
+        }
+
+        match get_tokenizer(&name) {
+            Some(tokenizer) => Self::Bpe(tokenizer),
+            None => Self::Approximate,
+        }
+    }
+
+    /// Whether counts are exact for this model rather than an estimate
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Bpe(_))
+    }
+
+    pub fn count_text(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+
+        match self {
+            Self::Bpe(tokenizer) => bpe_for(*tokenizer).encode_ordinary(text).len(),
+            Self::Anthropic => {
+                // CJK and other non-ASCII text runs close to one token per character
+                let (ascii, non_ascii) = text.chars().fold((0usize, 0usize), |(a, n), c| {
+                    if c.is_ascii() {
+                        (a + 1, n)
+                    } else {
+                        (a, n + 1)
+                    }
+                });
+                (ascii as f64 / ANTHROPIC_CHARS_PER_TOKEN).ceil() as usize + non_ascii
+            }
+            Self::Approximate => text.chars().count().div_ceil(FALLBACK_CHARS_PER_TOKEN),
+        }
+    }
+}
+
+fn bpe_for(tokenizer: Tokenizer) -> &'static CoreBPE {
+    match tokenizer {
+        Tokenizer::O200kHarmony => tiktoken_rs::o200k_harmony_singleton(),
+        Tokenizer::O200kBase => tiktoken_rs::o200k_base_singleton(),
+        Tokenizer::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+        Tokenizer::P50kBase => tiktoken_rs::p50k_base_singleton(),
+        Tokenizer::P50kEdit => tiktoken_rs::p50k_edit_singleton(),
+        Tokenizer::R50kBase | Tokenizer::Gpt2 => tiktoken_rs::r50k_base_singleton(),
+    }
+}
+
+/// Count tokens in a plain string for the given model
+pub fn count_text_tokens(model: &str, text: &str) -> usize {
+    TokenizerKind::for_model(model).count_text(text)
+}
+
+/// Estimate the prompt tokens of a request: messages, tool definitions and reply priming
+pub fn estimate_tokens(
+    model: &str,
+    messages: &[Message],
+    tools: Option<&[ToolDefinition]>,
+) -> usize {
+    let kind = TokenizerKind::for_model(model);
+
+    let mut total = REPLY_PRIMING_TOKENS;
+    for message in messages {
+        total += estimate_message_tokens(kind, message);
+    }
+    if let Some(tools) = tools {
+        total += estimate_tool_definitions_tokens(kind, tools);
+    }
+
+    total
+}
+
+fn estimate_message_tokens(kind: TokenizerKind, message: &Message) -> usize {
+    let mut total = TOKENS_PER_MESSAGE + kind.count_text(&message.role);
+
+    if let Some(content) = &message.content {
+        total += estimate_content_tokens(kind, content);
+    }
+    if let Some(reasoning) = &message.reasoning_content {
+        total += kind.count_text(reasoning);
+    }
+    if let Some(name) = &message.name {
+        total += TOKENS_PER_NAME + kind.count_text(name);
+    }
+    if let Some(tool_calls) = &message.tool_calls {
+        for tool_call in tool_calls {
+            total += TOKENS_PER_TOOL + kind.count_text(&tool_call.name);
+            if let Ok(arguments) = serde_json::to_string(&tool_call.arguments) {
+                total += kind.count_text(&arguments);
+            }
+        }
+    }
+    if let Some(images) = &message.tool_image_attachments {
+        total += images.len() * IMAGE_ATTACHMENT_TOKENS;
+    }
+
+    total
+}
+
+/// Multimodal messages carry their content as a JSON array of provider blocks; count the
+/// text parts and charge a flat rate per image instead of tokenizing base64 data
+fn estimate_content_tokens(kind: TokenizerKind, content: &str) -> usize {
+    if !content.trim_start().starts_with('[') {
+        return kind.count_text(content);
+    }
+    let Ok(blocks) = serde_json::from_str::<Vec<Value>>(content) else {
+        return kind.count_text(content);
+    };
+
+    blocks
+        .iter()
+        .map(|block| {
+            if let Some(text) = block.get("text").and_then(Value::as_str) {
+                kind.count_text(text)
+            } else if block.get("image_url").is_some()
+                || block.get("inline_data").is_some()
+                || block.get("type").and_then(Value::as_str) == Some("image")
+            {
+                IMAGE_ATTACHMENT_TOKENS
+            } else {
+                kind.count_text(&block.to_string())
+            }
+        })
+        .sum()
+}
+
+fn estimate_tool_definitions_tokens(kind: TokenizerKind, tools: &[ToolDefinition]) -> usize {
+    tools
+        .iter()
+        .map(|tool| {
+            let parameters = serde_json::to_string(&tool.parameters).unwrap_or_default();
+            TOKENS_PER_TOOL
+                + kind.count_text(&tool.name)
+                + kind.count_text(&tool.description)
+                + kind.count_text(&parameters)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_system(name: &str, content: &str) -> Message {
+        Message {
+            name: Some(name.to_string()),
+            ..Message::system(content.to_string())
+        }
+    }
+
+    /// Example conversation from OpenAI's "How to count tokens with tiktoken" cookbook
+    fn cookbook_messages() -> Vec<Message> {
+        vec![
+            Message::system("You are a helpful, pattern-following assistant that translates corporate jargon into plain English.".to_string()),
+            named_system("example_user", "New synergies will help drive top-line growth."),
+            named_system("example_assistant", "Things working well together will increase revenue."),
+            named_system("example_user", "Let's circle back when we have more bandwidth to touch base on opportunities for increased leverage."),
+            named_system("example_assistant", "Let's talk later when we're less busy about how to do better."),
+            Message::user("This late pivot means we don't have time to boil the ocean for the client deliverable.".to_string()),
+        ]
+    }
+
+    fn assert_within(actual: usize, expected: usize, tolerance: f64) {
+        let diff = (actual as f64 - expected as f64).abs() / expected as f64;
+        assert!(
+            diff <= tolerance,
+            "estimated {} tokens, expected {} (±{:.0}%)",
+            actual,
+            expected,
+            tolerance * 100.0
+        );
+    }
+
+    #[test]
+    fn openai_counts_match_cookbook_fixtures() {
+        assert_eq!(count_text_tokens("gpt-4", "tiktoken is great!"), 6);
+        assert_eq!(count_text_tokens("openai/gpt-4o", "hello world"), 2);
+
+        let messages = cookbook_messages();
+        assert_within(estimate_tokens("gpt-4-0613", &messages, None), 129, 0.02);
+        assert_within(estimate_tokens("gpt-4o", &messages, None), 124, 0.02);
+    }
+
+    #[test]
+    fn image_blocks_are_charged_a_flat_rate() {
+        let content = serde_json::json!([
+            { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", "A".repeat(40_000)) } },
+            { "type": "text", "text": "hello world" }
+        ]);
+        let messages = vec![Message::user(content.to_string())];
+
+        let plain = estimate_tokens("gpt-4o", &[Message::user("hello world".to_string())], None);
+        assert_eq!(
+            estimate_tokens("gpt-4o", &messages, None),
+            plain + IMAGE_ATTACHMENT_TOKENS
+        );
+    }
+
+    #[test]
+    fn approximations_stay_close_to_bpe_counts() {
+        assert_eq!(
+            TokenizerKind::for_model("claude-sonnet-4-5"),
+            TokenizerKind::Anthropic
+        );
+        assert_eq!(
+            TokenizerKind::for_model("deepseek-chat"),
+            TokenizerKind::Approximate
+        );
+        assert_eq!(count_text_tokens("deepseek-chat", "abcdefgh"), 2);
+
+        // Approximations should err on the high side so budget checks never under-count
+        let messages = cookbook_messages();
+        let reference = estimate_tokens("gpt-4", &messages, None);
+        for model in ["claude-sonnet-4-5", "deepseek-chat"] {
+            let estimate = estimate_tokens(model, &messages, None);
+            assert!(
+                estimate >= reference,
+                "{} under-counted: {}",
+                model,
+                estimate
+            );
+            assert_within(estimate, reference, 0.5);
+        }
+    }
+}
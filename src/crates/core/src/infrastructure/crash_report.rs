@@ -0,0 +1,431 @@
+//! Crash report collection
+//!
+//! A panic hook writes a JSON report per panic to the crashes directory. Tasks
+//! started with [`spawn_monitored`] are named in the report and their panics
+//! are logged instead of vanishing with the task. On the next start the
+//! reports written since the last notice are announced with
+//! [`CRASH_REPORTS_EVENT`].
+
+use super::ai::request_log::redact_secrets;
+use super::events::{emit_global_event, BackendEvent};
+use chrono::{Local, Utc};
+use futures::FutureExt;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom};
+use std::panic::{AssertUnwindSafe, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{OnceLock, RwLock};
+use std::task::{Context, Poll};
+
+pub const CRASH_REPORTS_EVENT: &str = "crash-reports-available";
+/// Reports kept on disk; older ones are removed when a new one is written.
+pub const MAX_CRASH_REPORTS: usize = 20;
+const LOG_TAIL_LINES: usize = 200;
+const LOG_TAIL_BYTES: u64 = 512 * 1024;
+/// Longer log lines are cut, so file bodies that ended up in the log are not
+/// copied into the report.
+const MAX_LOG_LINE_CHARS: usize = 500;
+const REPORT_PREFIX: &str = "crash-";
+/// Name of the newest report already announced.
+const NOTIFIED_MARKER: &str = ".last-notified";
+
+static REPORTER: OnceLock<CrashReporterConfig> = OnceLock::new();
+static ACTIVE_SESSION: RwLock<Option<String>> = RwLock::new(None);
+
+thread_local! {
+    static CURRENT_TASK: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone)]
+pub struct CrashReporterConfig {
+    pub crashes_dir: PathBuf,
+    pub app_version: String,
+    /// Log file whose last lines go into the report.
+    pub log_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    /// Name given to [`spawn_monitored`], when the panic happened in such a task.
+    pub task: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub session_id: Option<String>,
+    pub log_tail: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub created_at: String,
+    pub message: String,
+    pub path: String,
+}
+
+impl CrashReport {
+    fn summary(&self, path: &Path) -> CrashReportSummary {
+        CrashReportSummary {
+            id: self.id.clone(),
+            created_at: self.created_at.clone(),
+            message: self.message.clone(),
+            path: path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// Installs the crash-report panic hook in front of the current one, which
+/// still runs afterwards. Only the first call has an effect.
+pub fn install_panic_hook(config: CrashReporterConfig) {
+    if REPORTER.set(config).is_err() {
+        return;
+    }
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(config) = REPORTER.get() {
+            // A failing report must not hide the original panic.
+            let _ = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let report = build_report(config, info);
+                match write_report(&config.crashes_dir, &report) {
+                    Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                    Err(e) => eprintln!("Failed to write crash report: {}", e),
+                }
+            }));
+        }
+        previous(info);
+    }));
+}
+
+/// Records the session the user is working in, for crash reports.
+pub fn set_active_session(session_id: Option<&str>) {
+    if let Ok(mut active) = ACTIVE_SESSION.write() {
+        *active = session_id.map(str::to_string);
+    }
+}
+
+/// Spawns `future` on the tokio runtime. A panic in it produces a crash
+/// report naming the task and is logged as an error.
+pub fn spawn_monitored<F>(name: &str, future: F) -> tokio::task::JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let name = name.to_string();
+    let task = NamedTask {
+        name: name.clone(),
+        inner: Box::pin(future),
+    };
+    tokio::spawn(async move {
+        if AssertUnwindSafe(task).catch_unwind().await.is_err() {
+            error!("Task panicked: task={}", name);
+        }
+    })
+}
+
+/// Sets the task name for the panic hook while the inner future is polled.
+struct NamedTask<F> {
+    name: String,
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future<Output = ()>> Future for NamedTask<F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let previous = CURRENT_TASK.with(|task| task.replace(Some(self.name.clone())));
+        let result = self.inner.as_mut().poll(cx);
+        CURRENT_TASK.with(|task| *task.borrow_mut() = previous);
+        result
+    }
+}
+
+fn build_report(config: &CrashReporterConfig, info: &PanicHookInfo<'_>) -> CrashReport {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic message".to_string());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+    let log_tail = config
+        .log_file
+        .as_deref()
+        .map(read_log_tail)
+        .unwrap_or_default();
+
+    let now = Local::now();
+    CrashReport {
+        id: format!(
+            "{}{}-{}",
+            REPORT_PREFIX,
+            now.format("%Y%m%dT%H%M%S%3f"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        ),
+        created_at: now.with_timezone(&Utc).to_rfc3339(),
+        app_version: config.app_version.clone(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().map(str::to_string),
+        task: CURRENT_TASK.with(|task| task.borrow().clone()),
+        message: redact_secrets(&message),
+        location,
+        backtrace: redact_secrets(&backtrace),
+        session_id: ACTIVE_SESSION.read().ok().and_then(|s| s.clone()),
+        log_tail,
+    }
+}
+
+/// Last lines of the log file, with secrets masked and long lines cut.
+fn read_log_tail(path: &Path) -> Vec<String> {
+    let Ok(mut file) = fs::File::open(path) else {
+        return Vec::new();
+    };
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = size.saturating_sub(LOG_TAIL_BYTES);
+    let mut buffer = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut buffer).is_err() {
+        return Vec::new();
+    }
+
+    let content = String::from_utf8_lossy(&buffer);
+    let mut lines: Vec<&str> = content.lines().collect();
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(LOG_TAIL_LINES);
+
+    lines[skip..]
+        .iter()
+        .map(|line| {
+            let line = redact_secrets(line);
+            match line.char_indices().nth(MAX_LOG_LINE_CHARS) {
+                Some((cut, _)) => format!("{}… [truncated]", &line[..cut]),
+                None => line,
+            }
+        })
+        .collect()
+}
+
+fn write_report(crashes_dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(crashes_dir)?;
+    let path = crashes_dir.join(format!("{}.json", report.id));
+    let content = serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?;
+    fs::write(&path, content)?;
+    prune_reports(crashes_dir, MAX_CRASH_REPORTS);
+    Ok(path)
+}
+
+/// Report files, oldest first. Names start with a timestamp, so name order is
+/// creation order.
+fn report_files(crashes_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(crashes_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(REPORT_PREFIX) && name.ends_with(".json"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn prune_reports(crashes_dir: &Path, keep: usize) {
+    let files = report_files(crashes_dir);
+    let excess = files.len().saturating_sub(keep);
+    for path in &files[..excess] {
+        if let Err(e) = fs::remove_file(path) {
+            warn!(
+                "Failed to remove old crash report: path={}, error={}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+fn read_report(path: &Path) -> Option<CrashReport> {
+    let content = fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+/// Stored reports, newest first.
+pub fn list_crash_reports(crashes_dir: &Path) -> Vec<CrashReportSummary> {
+    report_files(crashes_dir)
+        .iter()
+        .rev()
+        .filter_map(|path| read_report(path).map(|report| report.summary(path)))
+        .collect()
+}
+
+pub fn get_crash_report(crashes_dir: &Path, id: &str) -> Option<CrashReport> {
+    if !is_valid_id(id) {
+        return None;
+    }
+    read_report(&crashes_dir.join(format!("{}.json", id)))
+}
+
+pub fn delete_crash_report(crashes_dir: &Path, id: &str) -> std::io::Result<()> {
+    if !is_valid_id(id) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid crash report id: {}", id),
+        ));
+    }
+    fs::remove_file(crashes_dir.join(format!("{}.json", id)))
+}
+
+fn is_valid_id(id: &str) -> bool {
+    id.starts_with(REPORT_PREFIX)
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Reports written since the last call, newest first; each report is
+/// returned once.
+pub fn take_unnotified_reports(crashes_dir: &Path) -> Vec<CrashReportSummary> {
+    let marker = crashes_dir.join(NOTIFIED_MARKER);
+    let last_notified = fs::read_to_string(&marker).unwrap_or_default();
+    let last_notified = last_notified.trim();
+
+    let files = report_files(crashes_dir);
+    let Some(newest) = files.last() else {
+        return Vec::new();
+    };
+    let newest_name = newest
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if newest_name.as_str() <= last_notified {
+        return Vec::new();
+    }
+    if let Err(e) = fs::write(&marker, &newest_name) {
+        debug!("Failed to update crash report marker: {}", e);
+    }
+
+    files
+        .iter()
+        .rev()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name > last_notified)
+        })
+        .filter_map(|path| read_report(path).map(|report| report.summary(path)))
+        .collect()
+}
+
+/// Announces the reports written since the last start with
+/// [`CRASH_REPORTS_EVENT`].
+pub async fn notify_pending_crash_reports(crashes_dir: &Path) {
+    let reports = take_unnotified_reports(crashes_dir);
+    if reports.is_empty() {
+        return;
+    }
+
+    warn!("Found {} new crash report(s)", reports.len());
+    if let Err(e) = emit_global_event(BackendEvent::Custom {
+        event_name: CRASH_REPORTS_EVENT.to_string(),
+        payload: serde_json::json!({ "reports": reports }),
+    })
+    .await
+    {
+        debug!("Failed to emit crash reports event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn panic_in_monitored_task_writes_report() {
+        let root = std::env::temp_dir().join(format!("bitfun-crash-{}", uuid::Uuid::new_v4()));
+        let crashes_dir = root.join("crashes");
+        let log_file = root.join("app.log");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            &log_file,
+            "[t][tid:1][INFO][app] starting\n[t][tid:1][INFO][app] api_key=\"abcdefghijklmnop\"\n",
+        )
+        .unwrap();
+
+        install_panic_hook(CrashReporterConfig {
+            crashes_dir: crashes_dir.clone(),
+            app_version: "1.2.3".to_string(),
+            log_file: Some(log_file),
+        });
+        set_active_session(Some("session-1"));
+
+        let handle = spawn_monitored("crash-test", async {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            panic!("boom with sk-abcdefghijklmnopqrstuvwx");
+        });
+        handle.await.unwrap();
+
+        let reports = list_crash_reports(&crashes_dir);
+        assert_eq!(reports.len(), 1);
+        let report = get_crash_report(&crashes_dir, &reports[0].id).unwrap();
+        assert_eq!(report.task.as_deref(), Some("crash-test"));
+        assert_eq!(report.session_id.as_deref(), Some("session-1"));
+        assert_eq!(report.app_version, "1.2.3");
+        assert_eq!(report.message, "boom with [REDACTED]");
+        assert_eq!(report.log_tail.len(), 2);
+        assert!(!report.log_tail[1].contains("abcdefghijklmnop"));
+
+        assert_eq!(take_unnotified_reports(&crashes_dir).len(), 1);
+        assert!(take_unnotified_reports(&crashes_dir).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn prune_keeps_newest_reports() {
+        let dir = std::env::temp_dir().join(format!("bitfun-crash-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        for i in 0..5 {
+            fs::write(
+                dir.join(format!("crash-2024010{}T000000000-x.json", i)),
+                "{}",
+            )
+            .unwrap();
+        }
+
+        prune_reports(&dir, 2);
+
+        let names: Vec<String> = report_files(&dir)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "crash-20240103T000000000-x.json",
+                "crash-20240104T000000000-x.json"
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,11 @@
 //! Backend event system for tool execution and custom events
 
+use crate::infrastructure::events::recorder::EventRecorder;
 use crate::infrastructure::events::EventEmitter;
-use crate::util::types::event::{ToolExecutionProgressInfo, ToolTerminalReadyInfo};
+use crate::infrastructure::offline::OfflineModeInfo;
+use crate::util::types::event::{
+    AIModelFallbackInfo, AIRequestRetryInfo, ToolExecutionProgressInfo, ToolTerminalReadyInfo,
+};
 use anyhow::Result;
 use log::{error, trace, warn};
 use serde::{Deserialize, Serialize};
@@ -18,6 +22,9 @@ pub enum BackendEvent {
         session_id: String,
         questions: serde_json::Value,
     },
+    AIRequestRetrying(AIRequestRetryInfo),
+    AIModelFallback(AIModelFallbackInfo),
+    OfflineModeChanged(OfflineModeInfo),
     Custom {
         event_name: String,
         payload: serde_json::Value,
@@ -26,12 +33,14 @@ pub enum BackendEvent {
 
 pub struct BackendEventSystem {
     emitter: Arc<Mutex<Option<Arc<dyn EventEmitter>>>>,
+    recorder: Arc<Mutex<Option<Arc<EventRecorder>>>>,
 }
 
 impl BackendEventSystem {
     pub fn new() -> Self {
         Self {
             emitter: Arc::new(Mutex::new(None)),
+            recorder: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -40,33 +49,54 @@ impl BackendEventSystem {
         *e = Some(emitter);
     }
 
+    /// Records every event emitted from now on; `None` stops recording
+    pub async fn set_recorder(&self, recorder: Option<Arc<EventRecorder>>) {
+        *self.recorder.lock().await = recorder;
+    }
+
+    pub async fn recorder(&self) -> Option<Arc<EventRecorder>> {
+        self.recorder.lock().await.clone()
+    }
+
     pub async fn emit(&self, event: BackendEvent) -> Result<()> {
         trace!("Emitting event: {:?}", event);
 
+        let recorder = self.recorder.lock().await.clone();
         let emitter_guard = self.emitter.lock().await;
-        if let Some(ref emitter) = *emitter_guard {
-            let event_name = match &event {
-                BackendEvent::Custom { event_name, .. } => event_name.clone(),
-                BackendEvent::ToolExecutionProgress(_) => {
-                    "backend-event-toolexecutionprogress".to_string()
-                }
-                BackendEvent::ToolTerminalReady(_) => "backend-event-toolterminalready".to_string(),
-                BackendEvent::ToolAwaitingUserInput { .. } => {
-                    "backend-event-toolawaitinguserinput".to_string()
+        if emitter_guard.is_none() && recorder.is_none() {
+            return Ok(());
+        }
+
+        let event_name = match &event {
+            BackendEvent::Custom { event_name, .. } => event_name.clone(),
+            BackendEvent::ToolExecutionProgress(_) => {
+                "backend-event-toolexecutionprogress".to_string()
+            }
+            BackendEvent::ToolTerminalReady(_) => "backend-event-toolterminalready".to_string(),
+            BackendEvent::ToolAwaitingUserInput { .. } => {
+                "backend-event-toolawaitinguserinput".to_string()
+            }
+            BackendEvent::AIRequestRetrying(_) => "backend-event-airequestretrying".to_string(),
+            BackendEvent::AIModelFallback(_) => "backend-event-aimodelfallback".to_string(),
+            BackendEvent::OfflineModeChanged(_) => "backend-event-offlinemodechanged".to_string(),
+        };
+
+        let event_data = match &event {
+            BackendEvent::Custom { payload, .. } => payload.clone(),
+            _ => match serde_json::to_value(&event) {
+                Ok(v) => v,
+                Err(e) => {
+                    error!("Failed to serialize event: {}", e);
+                    return Ok(());
                 }
-            };
-
-            let event_data = match &event {
-                BackendEvent::Custom { payload, .. } => payload.clone(),
-                _ => match serde_json::to_value(&event) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        error!("Failed to serialize event: {}", e);
-                        return Ok(());
-                    }
-                },
-            };
+            },
+        };
 
+        if let Some(recorder) = recorder {
+            recorder.record(&event_name, &event_data);
+        }
+
+        if let Some(ref emitter) = *emitter_guard {
             if let Err(e) = emitter.emit(&event_name, event_data).await {
                 warn!("Failed to emit to frontend: {}", e);
             }
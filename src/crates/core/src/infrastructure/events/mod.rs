@@ -2,10 +2,15 @@
 
 pub mod emitter;
 pub mod event_system;
+pub mod recorder;
 
-pub use bitfun_transport::TransportEmitter;
+pub use bitfun_transport::{CliEvent, TransportEmitter};
 pub use emitter::EventEmitter;
 pub use event_system::BackendEventSystem as BackendEventManager;
 pub use event_system::{
     emit_global_event, get_global_event_system, BackendEvent, BackendEventSystem,
 };
+pub use recorder::{
+    read_recorded_events, replay_recorded_events, replay_to_cli, EventRecorder,
+    EventRecorderConfig, RecordedEvent,
+};
@@ -0,0 +1,383 @@
+//! Backend event recording and replay
+//!
+//! The recorder appends every emitted backend event as one JSON line, so a
+//! misbehaving agent run can be replayed later. Writing happens on a
+//! background thread behind a buffered writer; `record` only enqueues the
+//! event, which keeps recording cheap enough to leave on. Large strings such
+//! as file contents are replaced by their size and hash before writing.
+
+use crate::infrastructure::events::EventEmitter;
+use crate::util::errors::{BitFunError, BitFunResult};
+use bitfun_transport::{CliEvent, CliTransportAdapter, TransportEmitter};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// File the recorder writes to inside its directory
+pub const EVENT_LOG_FILE: &str = "events.jsonl";
+/// How often buffered events reach the disk
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Longest pause reproduced between events by a real-time replay
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(5);
+
+/// One line of the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedEvent {
+    /// Unix time in milliseconds
+    pub timestamp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub event_name: String,
+    pub payload: Value,
+}
+
+impl RecordedEvent {
+    pub fn new(event_name: &str, payload: Value) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            session_id: find_session_id(&payload),
+            event_name: event_name.to_string(),
+            payload,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventRecorderConfig {
+    pub dir: PathBuf,
+    /// The log rotates once it grows past this size
+    pub max_file_bytes: u64,
+    /// Rotated files kept next to the current one
+    pub max_rotated_files: usize,
+    /// Strings longer than this are replaced by their size and hash
+    pub redact_over_bytes: usize,
+}
+
+impl EventRecorderConfig {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_file_bytes: 16 * 1024 * 1024,
+            max_rotated_files: 4,
+            redact_over_bytes: 8 * 1024,
+        }
+    }
+}
+
+enum RecorderMessage {
+    Event(RecordedEvent),
+    Flush(mpsc::Sender<()>),
+}
+
+/// Appends backend events to `events.jsonl` from a background writer thread
+pub struct EventRecorder {
+    tx: mpsc::Sender<RecorderMessage>,
+    path: PathBuf,
+}
+
+impl EventRecorder {
+    /// Opens the log in `config.dir` and starts the writer thread
+    pub fn start(config: EventRecorderConfig) -> BitFunResult<Self> {
+        std::fs::create_dir_all(&config.dir).map_err(|e| {
+            BitFunError::io(format!(
+                "Failed to create event log directory {:?}: {}",
+                config.dir, e
+            ))
+        })?;
+        let path = config.dir.join(EVENT_LOG_FILE);
+        let writer = LogWriter::open(path.clone(), &config)?;
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("bitfun-event-recorder".to_string())
+            .spawn(move || writer.run(rx))
+            .map_err(|e| BitFunError::io(format!("Failed to start event recorder: {}", e)))?;
+
+        debug!("Recording backend events to {:?}", path);
+        Ok(Self { tx, path })
+    }
+
+    /// Current log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queues an event for writing; never blocks on the disk
+    pub fn record(&self, event_name: &str, payload: &Value) {
+        let _ = self.tx.send(RecorderMessage::Event(RecordedEvent::new(
+            event_name,
+            payload.clone(),
+        )));
+    }
+
+    /// Waits until every event recorded so far is on disk
+    pub fn flush(&self) {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        if self.tx.send(RecorderMessage::Flush(ack_tx)).is_ok() {
+            let _ = ack_rx.recv_timeout(Duration::from_secs(5));
+        }
+    }
+}
+
+struct LogWriter {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    written: u64,
+    max_file_bytes: u64,
+    max_rotated_files: usize,
+    redact_over_bytes: usize,
+}
+
+impl LogWriter {
+    fn open(path: PathBuf, config: &EventRecorderConfig) -> BitFunResult<Self> {
+        let file = Self::open_file(&path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            written,
+            max_file_bytes: config.max_file_bytes,
+            max_rotated_files: config.max_rotated_files,
+            redact_over_bytes: config.redact_over_bytes,
+        })
+    }
+
+    fn open_file(path: &Path) -> BitFunResult<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| BitFunError::io(format!("Failed to open event log {:?}: {}", path, e)))
+    }
+
+    fn run(mut self, rx: mpsc::Receiver<RecorderMessage>) {
+        loop {
+            match rx.recv_timeout(FLUSH_INTERVAL) {
+                Ok(RecorderMessage::Event(mut event)) => {
+                    redact_large_strings(&mut event.payload, self.redact_over_bytes);
+                    if let Err(e) = self.write(&event) {
+                        warn!("Failed to record event {}: {}", event.event_name, e);
+                    }
+                }
+                Ok(RecorderMessage::Flush(ack)) => {
+                    let _ = self.writer.flush();
+                    let _ = ack.send(());
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let _ = self.writer.flush();
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    let _ = self.writer.flush();
+                    return;
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, event: &RecordedEvent) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        if self.written > 0 && self.written + line.len() as u64 > self.max_file_bytes {
+            self.rotate()?;
+        }
+        self.writer.write_all(&line)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// events.jsonl becomes events.1.jsonl, events.1.jsonl becomes
+    /// events.2.jsonl and so on; the oldest beyond the limit is dropped
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.writer.flush()?;
+        let rotated = |index: usize| self.path.with_extension(format!("{}.jsonl", index));
+
+        let _ = std::fs::remove_file(rotated(self.max_rotated_files.max(1)));
+        for index in (1..self.max_rotated_files.max(1)).rev() {
+            let from = rotated(index);
+            if from.exists() {
+                std::fs::rename(&from, rotated(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated(1))?;
+
+        let file = Self::open_file(&self.path).map_err(|e| std::io::Error::other(e.to_string()))?;
+        self.writer = BufWriter::new(file);
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Session the event belongs to, from a `session_id`/`sessionId` field at
+/// the top level or one level down
+fn find_session_id(payload: &Value) -> Option<String> {
+    let direct = |value: &Value| {
+        ["session_id", "sessionId"]
+            .iter()
+            .find_map(|key| value.get(key).and_then(Value::as_str))
+            .map(str::to_string)
+    };
+    direct(payload).or_else(|| {
+        payload
+            .as_object()?
+            .values()
+            .filter(|value| value.is_object())
+            .find_map(direct)
+    })
+}
+
+/// Replaces strings longer than `limit` bytes with a placeholder naming their
+/// size and hash, so logs do not carry whole files
+pub fn redact_large_strings(value: &mut Value, limit: usize) {
+    match value {
+        Value::String(text) if text.len() > limit => {
+            let hash = hex::encode(Sha256::digest(text.as_bytes()));
+            *text = format!("[redacted {} bytes, sha256 {}]", text.len(), &hash[..16]);
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_large_strings(item, limit)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| redact_large_strings(field, limit)),
+        _ => {}
+    }
+}
+
+/// Reads an event log; lines that do not parse are skipped
+pub fn read_recorded_events(path: &Path) -> BitFunResult<Vec<RecordedEvent>> {
+    let file = File::open(path)
+        .map_err(|e| BitFunError::io(format!("Failed to open event log {:?}: {}", path, e)))?;
+    let mut events = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| BitFunError::io(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(event) => events.push(event),
+            Err(e) => warn!("Skipping line {} of {:?}: {}", index + 1, path, e),
+        }
+    }
+    Ok(events)
+}
+
+/// Emits the events of a log again, in order. With `realtime` the original
+/// gaps between events are kept (capped at a few seconds).
+pub async fn replay_recorded_events(
+    path: &Path,
+    emitter: Arc<dyn EventEmitter>,
+    realtime: bool,
+) -> BitFunResult<usize> {
+    let events = read_recorded_events(path)?;
+    let mut previous: Option<i64> = None;
+    for event in &events {
+        if let (true, Some(previous)) = (realtime, previous) {
+            let gap = Duration::from_millis((event.timestamp - previous).max(0) as u64);
+            tokio::time::sleep(gap.min(MAX_REPLAY_GAP)).await;
+        }
+        previous = Some(event.timestamp);
+        emitter
+            .emit(&event.event_name, event.payload.clone())
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to replay event: {}", e)))?;
+    }
+    Ok(events.len())
+}
+
+/// Replays a log through a `CliTransportAdapter`; the receiver yields the
+/// events as the CLI would render them
+pub fn replay_to_cli(path: PathBuf, realtime: bool) -> UnboundedReceiver<CliEvent> {
+    let (adapter, rx) = CliTransportAdapter::create_channel();
+    let emitter: Arc<dyn EventEmitter> = Arc::new(TransportEmitter::new(Arc::new(adapter)));
+    tokio::spawn(async move {
+        if let Err(e) = replay_recorded_events(&path, emitter, realtime).await {
+            warn!("Replay of {:?} stopped: {}", path, e);
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_redacted_events_with_session_ids() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let recorder = EventRecorder::start(EventRecorderConfig {
+            redact_over_bytes: 16,
+            ..EventRecorderConfig::new(dir.to_path_buf())
+        })
+        .unwrap();
+
+        recorder.record(
+            "backend-event-toolexecutionprogress",
+            &json!({"type": "ToolExecutionProgress", "value": {"session_id": "s1", "content": "x".repeat(100)}}),
+        );
+        recorder.record("file-changed", &json!({"path": "/a.rs"}));
+        recorder.flush();
+
+        let events = read_recorded_events(recorder.path()).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].session_id.as_deref(), Some("s1"));
+        let content = events[0].payload["value"]["content"].as_str().unwrap();
+        assert!(content.starts_with("[redacted 100 bytes"));
+        assert_eq!(events[1].session_id, None);
+        assert_eq!(events[1].payload, json!({"path": "/a.rs"}));
+    }
+
+    #[test]
+    fn rotates_by_size() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let recorder = EventRecorder::start(EventRecorderConfig {
+            max_file_bytes: 200,
+            max_rotated_files: 2,
+            ..EventRecorderConfig::new(dir.to_path_buf())
+        })
+        .unwrap();
+
+        for index in 0..20 {
+            recorder.record("tick", &json!({"index": index, "pad": "0123456789"}));
+        }
+        recorder.flush();
+
+        let current = read_recorded_events(recorder.path()).unwrap();
+        let rotated = read_recorded_events(&dir.join("events.1.jsonl")).unwrap();
+        assert!(dir.join("events.2.jsonl").exists());
+        assert!(!dir.join("events.3.jsonl").exists());
+        assert_eq!(current.last().unwrap().payload["index"], 19);
+        assert!(rotated.last().unwrap().payload["index"].as_i64().unwrap() < 19);
+        assert!(std::fs::metadata(recorder.path()).unwrap().len() <= 200);
+    }
+
+    #[tokio::test]
+    async fn replays_into_the_cli_adapter() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let recorder = EventRecorder::start(EventRecorderConfig::new(dir.to_path_buf())).unwrap();
+        recorder.record("first", &json!({"n": 1}));
+        recorder.record("second", &json!({"n": 2}));
+        recorder.flush();
+
+        let mut rx = replay_to_cli(recorder.path().to_path_buf(), false);
+        let mut names = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if let CliEvent::Generic { event_name, .. } = event {
+                names.push(event_name);
+            }
+        }
+        assert_eq!(names, vec!["first", "second"]);
+    }
+}
@@ -1,13 +1,22 @@
 //! File operation service
 //!
-//! Provides safe file read/write and operations
+//! Provides safe file read/write and operations. Writes go to a temporary file
+//! in the target's directory that is then renamed over the target, so a crash
+//! or a concurrent reader never sees a truncated file. Text is decoded from
+//! its detected encoding and written back in it (see `text_encoding`).
 
+use super::text_encoding::{self, TextEncoding};
 use crate::util::errors::*;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tokio::fs;
 
+/// Serializes the "check expected hash, then rename" step of writes in this process.
+static COMMIT_LOCK: Mutex<()> = Mutex::new(());
+
 /// Same rules as web `normalizeTextForDiskSyncComparison` (BOM strip, CRLF/CR → LF).
 pub fn normalize_text_for_editor_disk_sync(text: &str) -> String {
     let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
@@ -18,6 +27,11 @@ fn sha256_hex(data: &[u8]) -> String {
     hex::encode(Sha256::digest(data))
 }
 
+/// SHA-256 (hex, lowercase) of raw file content, the form `FileOperationOptions::expected_hash` takes.
+pub fn content_sha256_hex(data: &[u8]) -> String {
+    sha256_hex(data)
+}
+
 pub struct FileOperationService {
     max_file_size_mb: u64,
     allowed_extensions: Option<Vec<String>>,
@@ -30,6 +44,17 @@ pub struct FileOperationOptions {
     pub allowed_extensions: Option<Vec<String>>,
     pub restricted_paths: Vec<PathBuf>,
     pub backup_on_overwrite: bool,
+    /// Content hash (see `content_sha256_hex`) the caller last saw; the write
+    /// fails with a conflict when the file changed or disappeared since.
+    pub expected_hash: Option<String>,
+    /// fsync the file and its directory before the write returns
+    pub durable: bool,
+    /// Keep the modification time of the file being overwritten
+    pub preserve_mtime: bool,
+    /// Encoding label (`utf-8`, `shift_jis`, `utf-16le-bom`, ...) for text
+    /// writes; by default an existing file keeps its encoding and new files
+    /// are UTF-8.
+    pub encoding: Option<String>,
 }
 
 impl Default for FileOperationOptions {
@@ -50,6 +75,10 @@ impl Default for FileOperationOptions {
                 PathBuf::from("/boot"),
             ],
             backup_on_overwrite: true,
+            expected_hash: None,
+            durable: false,
+            preserve_mtime: false,
+            encoding: None,
         }
     }
 }
@@ -67,15 +96,24 @@ pub struct FileInfo {
     pub extension: Option<String>,
     pub mime_type: Option<String>,
     pub permissions: Option<String>,
+    /// Detected text encoding, e.g. `UTF-8` or `windows-1252`; `None` for
+    /// directories and binary files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileReadResult {
     pub content: String,
+    /// Encoding the text was decoded from (`UTF-8`, `Shift_JIS`, ...), or
+    /// `base64` for binary content
     pub encoding: String,
     pub size: u64,
     pub is_binary: bool,
     pub line_count: Option<usize>,
+    /// Invalid bytes were replaced with U+FFFD while decoding
+    #[serde(default)]
+    pub lossy: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +121,9 @@ pub struct FileWriteResult {
     pub bytes_written: u64,
     pub backup_created: bool,
     pub backup_path: Option<String>,
+    /// Hash of the written content, to pass as `expected_hash` on the next write
+    #[serde(default)]
+    pub content_hash: String,
 }
 
 impl Default for FileOperationService {
@@ -132,57 +173,43 @@ impl FileOperationService {
             )));
         }
 
-        match fs::read_to_string(path).await {
-            Ok(content) => {
-                let line_count = content.lines().count();
+        let bytes = fs::read(path)
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to read file: {}", e)))?;
+
+        match text_encoding::decode_text(&bytes) {
+            Some(decoded) => Ok(FileReadResult {
+                line_count: Some(decoded.text.lines().count()),
+                content: decoded.text,
+                encoding: decoded.encoding.name().to_string(),
+                size: file_size,
+                is_binary: false,
+                lossy: decoded.lossy,
+            }),
+            None => {
+                use base64::Engine;
+                let engine = base64::engine::general_purpose::STANDARD;
                 Ok(FileReadResult {
-                    content,
-                    encoding: "UTF-8".to_string(),
+                    content: engine.encode(&bytes),
+                    encoding: "base64".to_string(),
                     size: file_size,
-                    is_binary: false,
-                    line_count: Some(line_count),
+                    is_binary: true,
+                    line_count: None,
+                    lossy: false,
                 })
             }
-            Err(_) => {
-                let bytes = fs::read(path)
-                    .await
-                    .map_err(|e| BitFunError::service(format!("Failed to read file: {}", e)))?;
-
-                let is_binary = self.is_binary_content(&bytes);
-
-                if is_binary {
-                    use base64::Engine;
-                    let engine = base64::engine::general_purpose::STANDARD;
-                    Ok(FileReadResult {
-                        content: engine.encode(&bytes),
-                        encoding: "base64".to_string(),
-                        size: file_size,
-                        is_binary: true,
-                        line_count: None,
-                    })
-                } else {
-                    let content = String::from_utf8_lossy(&bytes).to_string();
-                    Ok(FileReadResult {
-                        content: content.clone(),
-                        encoding: "UTF-8-lossy".to_string(),
-                        size: file_size,
-                        is_binary: false,
-                        line_count: Some(content.lines().count()),
-                    })
-                }
-            }
         }
     }
 
     /// SHA-256 (hex, lowercase) of `bytes` using the same normalization as the web editor sync check,
     /// or raw-byte hash when content is treated as binary (matches `read_file` heuristics).
     pub fn editor_sync_sha256_hex_from_raw_bytes(&self, bytes: &[u8]) -> String {
-        if self.is_binary_content(bytes) {
-            sha256_hex(bytes)
-        } else {
-            let content = String::from_utf8_lossy(bytes);
-            let normalized = normalize_text_for_editor_disk_sync(content.as_ref());
-            sha256_hex(normalized.as_bytes())
+        match text_encoding::decode_text(bytes) {
+            Some(decoded) => {
+                let normalized = normalize_text_for_editor_disk_sync(&decoded.text);
+                sha256_hex(normalized.as_bytes())
+            }
+            None => sha256_hex(bytes),
         }
     }
 
@@ -232,36 +259,9 @@ impl FileOperationService {
         content: &str,
         options: FileOperationOptions,
     ) -> BitFunResult<FileWriteResult> {
-        let path = Path::new(file_path);
-
-        self.validate_file_access(path, true).await?;
-
-        let mut backup_created = false;
-        let mut backup_path = None;
-
-        if options.backup_on_overwrite && path.exists() {
-            let backup_file_path = self.create_backup(path).await?;
-            backup_created = true;
-            backup_path = Some(backup_file_path);
-        }
-
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).await.map_err(|e| {
-                BitFunError::service(format!("Failed to create parent directory: {}", e))
-            })?;
-        }
-
-        fs::write(path, content)
-            .await
-            .map_err(|e| BitFunError::service(format!("Failed to write file: {}", e)))?;
-
-        let bytes_written = content.len() as u64;
-
-        Ok(FileWriteResult {
-            bytes_written,
-            backup_created,
-            backup_path,
-        })
+        let encoding = self.target_encoding(Path::new(file_path), &options).await?;
+        let data = text_encoding::encode_text(content, encoding)?;
+        self.write_bytes(file_path, &data, options).await
     }
 
     pub async fn write_binary_file(
@@ -269,6 +269,15 @@ impl FileOperationService {
         file_path: &str,
         data: &[u8],
         options: FileOperationOptions,
+    ) -> BitFunResult<FileWriteResult> {
+        self.write_bytes(file_path, data, options).await
+    }
+
+    async fn write_bytes(
+        &self,
+        file_path: &str,
+        data: &[u8],
+        options: FileOperationOptions,
     ) -> BitFunResult<FileWriteResult> {
         let path = Path::new(file_path);
 
@@ -289,16 +298,30 @@ impl FileOperationService {
             })?;
         }
 
-        fs::write(path, data)
-            .await
-            .map_err(|e| BitFunError::service(format!("Failed to write binary file: {}", e)))?;
-
-        let bytes_written = data.len() as u64;
+        atomic_write(path, data, &options).await?;
 
         Ok(FileWriteResult {
-            bytes_written,
+            bytes_written: data.len() as u64,
             backup_created,
             backup_path,
+            content_hash: sha256_hex(data),
+        })
+    }
+
+    /// Encoding for a text write: the override in `options`, else the
+    /// encoding of the existing file, else UTF-8.
+    async fn target_encoding(
+        &self,
+        path: &Path,
+        options: &FileOperationOptions,
+    ) -> BitFunResult<TextEncoding> {
+        if let Some(label) = options.encoding.as_deref() {
+            return TextEncoding::for_label(label)
+                .ok_or_else(|| BitFunError::validation(format!("Unknown encoding: {}", label)));
+        }
+        Ok(match fs::read(path).await {
+            Ok(existing) => text_encoding::detect_encoding(&existing).unwrap_or(TextEncoding::UTF8),
+            Err(_) => TextEncoding::UTF8,
         })
     }
 
@@ -437,6 +460,14 @@ impl FileOperationService {
 
         let permissions = self.get_permissions_string(path).await;
 
+        let encoding = if metadata.is_file() {
+            self.detect_file_encoding(path)
+                .await
+                .map(|encoding| encoding.name().to_string())
+        } else {
+            None
+        };
+
         Ok(FileInfo {
             path: file_path.to_string(),
             name: file_name,
@@ -449,6 +480,7 @@ impl FileOperationService {
             extension,
             mime_type,
             permissions,
+            encoding,
         })
     }
 
@@ -559,25 +591,15 @@ impl FileOperationService {
         Ok(backup_path.to_string_lossy().to_string())
     }
 
-    fn is_binary_content(&self, data: &[u8]) -> bool {
-        const SAMPLE_SIZE: usize = 512;
-        let sample = if data.len() > SAMPLE_SIZE {
-            &data[..SAMPLE_SIZE]
-        } else {
-            data
-        };
+    /// Encoding detected from the start of the file; `None` for binary or unreadable files
+    async fn detect_file_encoding(&self, path: &Path) -> Option<TextEncoding> {
+        use tokio::io::AsyncReadExt;
+        const SAMPLE_SIZE: u64 = 64 * 1024;
 
-        if sample.contains(&0) {
-            return true;
-        }
-
-        let non_printable_count = sample
-            .iter()
-            .filter(|&&b| b < 32 && b != 9 && b != 10 && b != 13)
-            .count();
-
-        let non_printable_ratio = non_printable_count as f64 / sample.len() as f64;
-        non_printable_ratio > 0.1
+        let file = fs::File::open(path).await.ok()?;
+        let mut sample = Vec::new();
+        file.take(SAMPLE_SIZE).read_to_end(&mut sample).await.ok()?;
+        text_encoding::detect_encoding(&sample)
     }
 
     fn detect_mime_type(&self, path: &Path) -> Option<String> {
@@ -650,6 +672,146 @@ impl FileOperationService {
     }
 }
 
+/// Replaces `path` with `data` atomically: the data goes to a temporary file
+/// in the same directory, which is renamed over the target. Symlinks are
+/// written through and an existing file keeps its permissions. Only the
+/// `expected_hash`, `durable` and `preserve_mtime` options apply.
+pub async fn atomic_write(
+    path: &Path,
+    data: &[u8],
+    options: &FileOperationOptions,
+) -> BitFunResult<()> {
+    let path = path.to_path_buf();
+    let data = data.to_vec();
+    let expected_hash = options.expected_hash.clone();
+    let (durable, preserve_mtime) = (options.durable, options.preserve_mtime);
+    tokio::task::spawn_blocking(move || {
+        write_atomic_blocking(
+            &path,
+            &data,
+            expected_hash.as_deref(),
+            durable,
+            preserve_mtime,
+        )
+    })
+    .await
+    .map_err(|e| BitFunError::service(format!("File write task failed: {}", e)))?
+}
+
+fn write_atomic_blocking(
+    path: &Path,
+    data: &[u8],
+    expected_hash: Option<&str>,
+    durable: bool,
+    preserve_mtime: bool,
+) -> BitFunResult<()> {
+    let target = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    };
+    let file_name = target.file_name().ok_or_else(|| {
+        BitFunError::service(format!("Path has no file name: {}", path.display()))
+    })?;
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let temp_path = dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        uuid::Uuid::new_v4().simple()
+    ));
+    let previous = std::fs::metadata(&target).ok();
+
+    let result = write_temp_file(&temp_path, data, previous.as_ref(), durable, preserve_mtime)
+        .and_then(|()| {
+            let _guard = COMMIT_LOCK
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(expected_hash) = expected_hash {
+                ensure_unchanged(&target, expected_hash)?;
+            }
+            std::fs::rename(&temp_path, &target)
+                .map_err(|e| BitFunError::service(format!("Failed to replace file: {}", e)))
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result?;
+
+    #[cfg(unix)]
+    if durable {
+        // Persist the rename itself
+        if let Ok(dir) = std::fs::File::open(&dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+fn write_temp_file(
+    temp_path: &Path,
+    data: &[u8],
+    previous: Option<&std::fs::Metadata>,
+    durable: bool,
+    preserve_mtime: bool,
+) -> BitFunResult<()> {
+    let io_error = |e: std::io::Error| {
+        BitFunError::service(format!(
+            "Failed to write temporary file {}: {}",
+            temp_path.display(),
+            e
+        ))
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)
+        .map_err(io_error)?;
+    file.write_all(data).map_err(io_error)?;
+    if let Some(previous) = previous {
+        file.set_permissions(previous.permissions())
+            .map_err(io_error)?;
+        if preserve_mtime {
+            if let Ok(modified) = previous.modified() {
+                file.set_modified(modified).map_err(io_error)?;
+            }
+        }
+    }
+    if durable {
+        file.sync_all().map_err(io_error)?;
+    }
+    Ok(())
+}
+
+fn ensure_unchanged(path: &Path, expected_hash: &str) -> BitFunResult<()> {
+    let current = match std::fs::read(path) {
+        Ok(bytes) => sha256_hex(&bytes),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(BitFunError::conflict(format!(
+                "File was deleted since it was last read: {}",
+                path.display()
+            )));
+        }
+        Err(e) => {
+            return Err(BitFunError::service(format!(
+                "Failed to read file {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    if !current.eq_ignore_ascii_case(expected_hash) {
+        return Err(BitFunError::conflict(format!(
+            "File was modified since it was last read: {}",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod editor_sync_hash_tests {
     use super::*;
@@ -670,3 +832,163 @@ mod editor_sync_hash_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod atomic_write_tests {
+    use super::*;
+
+    fn options() -> FileOperationOptions {
+        FileOperationOptions {
+            restricted_paths: Vec::new(),
+            backup_on_overwrite: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn replaces_content_and_reports_hash() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("notes.txt");
+        let path_str = path.to_string_lossy().to_string();
+        let svc = FileOperationService::new(options());
+
+        let first = svc.write_file(&path_str, "one", options()).await.unwrap();
+        assert_eq!(first.bytes_written, 3);
+        assert_eq!(first.content_hash, content_sha256_hex(b"one"));
+
+        let second = svc
+            .write_file(
+                &path_str,
+                "two",
+                FileOperationOptions {
+                    expected_hash: Some(first.content_hash),
+                    durable: true,
+                    ..options()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.content_hash, content_sha256_hex(b"two"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "two");
+        // No temporary files are left behind
+        assert_eq!(std::fs::read_dir(dir).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_write_when_file_changed() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("notes.txt");
+        let path_str = path.to_string_lossy().to_string();
+        let svc = FileOperationService::new(options());
+
+        let written = svc.write_file(&path_str, "one", options()).await.unwrap();
+        std::fs::write(&path, "changed elsewhere").unwrap();
+
+        let result = svc
+            .write_file(
+                &path_str,
+                "two",
+                FileOperationOptions {
+                    expected_hash: Some(written.content_hash.clone()),
+                    ..options()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(BitFunError::Conflict(_))));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "changed elsewhere");
+        assert_eq!(std::fs::read_dir(dir).unwrap().count(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+        let result = svc
+            .write_file(
+                &path_str,
+                "two",
+                FileOperationOptions {
+                    expected_hash: Some(written.content_hash),
+                    ..options()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(BitFunError::Conflict(_))));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_encoding_of_existing_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("legacy.txt");
+        let path_str = path.to_string_lossy().to_string();
+        let svc = FileOperationService::new(options());
+        std::fs::write(&path, b"Caf\xE9 cr\xE8me br\xFBl\xE9e\r\n").unwrap();
+
+        let read = svc.read_file(&path_str).await.unwrap();
+        assert_eq!(read.content, "Café crème brûlée\r\n");
+        assert_eq!(read.encoding, "windows-1252");
+        assert!(!read.lossy);
+        let info = svc.get_file_info(&path_str).await.unwrap();
+        assert_eq!(info.encoding.as_deref(), Some("windows-1252"));
+
+        svc.write_file(&path_str, "Crème brûlée\r\n", options())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"Cr\xE8me br\xFBl\xE9e\r\n");
+
+        let result = svc.write_file(&path_str, "日本語", options()).await;
+        assert!(matches!(result, Err(BitFunError::Validation(_))));
+
+        svc.write_file(
+            &path_str,
+            "日本語",
+            FileOperationOptions {
+                encoding: Some("utf-8".to_string()),
+                ..options()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "日本語");
+
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let read = svc.read_file(&path_str).await.unwrap();
+        assert!(read.is_binary);
+        assert_eq!(svc.get_file_info(&path_str).await.unwrap().encoding, None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn keeps_permissions_and_optionally_mtime() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("run.sh");
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o750)).unwrap();
+        let old_mtime =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(old_mtime)
+            .unwrap();
+
+        atomic_write(
+            &path,
+            b"#!/bin/sh\necho hi\n",
+            &FileOperationOptions {
+                preserve_mtime: true,
+                ..options()
+            },
+        )
+        .await
+        .unwrap();
+
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+        assert_eq!(metadata.modified().unwrap(), old_mtime);
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! Provides file tree building, directory scanning, and file search
 
+use super::file_tree_cache::FileTreeCache;
+use super::file_watcher::get_global_file_watcher;
+use super::fuzzy_match::fuzzy_rank;
+use super::ignore_rules::{configure_walk, IgnoreRules};
 use crate::util::errors::*;
 use log::warn;
 
@@ -15,6 +19,9 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::fs;
 
+/// Upper bound on files indexed for fuzzy search of an unwatched workspace
+const MAX_FUZZY_INDEX_FILES: usize = 100_000;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileTreeNode {
     pub id: String,
@@ -100,6 +107,16 @@ pub struct FileTreeOptions {
     pub skip_patterns: Vec<String>,
     pub max_file_size_mb: Option<u64>,
     pub follow_symlinks: bool,
+    /// Include entries excluded by `.gitignore`, `.git/info/exclude`, the
+    /// global gitignore or `.bitfunignore`
+    pub include_ignored: bool,
+    /// Make `build_tree_with_stats` set each directory node's `size` to the
+    /// total size of the files below it and fill
+    /// `FileTreeStatistics::largest_entries`. Costs an extra pass over the
+    /// tree, so it is off by default.
+    pub compute_directory_sizes: bool,
+    /// Number of entries kept in `FileTreeStatistics::largest_entries`
+    pub largest_entries_limit: usize,
 }
 
 impl Default for FileTreeOptions {
@@ -125,6 +142,9 @@ impl Default for FileTreeOptions {
             ],
             max_file_size_mb: Some(100),
             follow_symlinks: false,
+            include_ignored: false,
+            compute_directory_sizes: false,
+            largest_entries_limit: 20,
         }
     }
 }
@@ -140,10 +160,127 @@ pub struct FileTreeStatistics {
     pub large_files: Vec<(String, u64)>, // (path, size) for files > 10MB
     pub symlinks_count: usize,
     pub hidden_files_count: usize,
+    /// Entries skipped by ignore files
+    #[serde(default)]
+    pub ignored_entries_count: usize,
+    /// Largest files and directories, biggest first; only filled with
+    /// `FileTreeOptions::compute_directory_sizes`
+    #[serde(default)]
+    pub largest_entries: Vec<LargestEntry>,
+}
+
+/// A file or directory and its size; a directory's size is the total of the
+/// files below it that the tree shows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LargestEntry {
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_directory: bool,
+}
+
+/// What takes up space in a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectorySizeReport {
+    pub root_path: String,
+    pub total_size_bytes: u64,
+    /// Top-level entries, largest first, without children; directory sizes
+    /// are aggregated
+    pub entries: Vec<FileTreeNode>,
+    /// Largest files and directories anywhere in the tree, biggest first
+    pub largest_entries: Vec<LargestEntry>,
+}
+
+/// Keeps the `limit` largest entries offered to it.
+struct LargestEntries {
+    limit: usize,
+    entries: Vec<LargestEntry>,
+}
+
+impl LargestEntries {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            entries: Vec::new(),
+        }
+    }
+
+    fn offer(&mut self, node: &FileTreeNode, size_bytes: u64) {
+        if self.limit == 0 {
+            return;
+        }
+        self.entries.push(LargestEntry {
+            path: node.path.clone(),
+            size_bytes,
+            is_directory: node.is_directory,
+        });
+        // Trim in batches rather than sorting on every insert
+        if self.entries.len() >= self.limit * 2 {
+            self.trim();
+        }
+    }
+
+    fn trim(&mut self) {
+        self.entries.sort_by(|a, b| {
+            b.size_bytes
+                .cmp(&a.size_bytes)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        self.entries.truncate(self.limit);
+    }
+
+    fn into_sorted(mut self) -> Vec<LargestEntry> {
+        self.trim();
+        self.entries
+    }
+}
+
+/// Sets the `size` of every directory in `nodes` to the total size of the
+/// files below it, offers every entry to `largest`, and returns the total.
+fn aggregate_directory_sizes(nodes: &mut [FileTreeNode], largest: &mut LargestEntries) -> u64 {
+    let mut total = 0;
+    for node in nodes {
+        let size = if node.is_directory {
+            let size = node
+                .children
+                .as_mut()
+                .map_or(0, |children| aggregate_directory_sizes(children, largest));
+            node.size = Some(size);
+            size
+        } else {
+            node.size.unwrap_or(0)
+        };
+        largest.offer(node, size);
+        total += size;
+    }
+    total
+}
+
+/// Builds a size report from a full tree of `root_path`.
+pub(super) fn directory_size_report(
+    root_path: &Path,
+    mut nodes: Vec<FileTreeNode>,
+    limit: usize,
+) -> DirectorySizeReport {
+    let mut largest = LargestEntries::new(limit);
+    let total_size_bytes = aggregate_directory_sizes(&mut nodes, &mut largest);
+    for node in &mut nodes {
+        if node.children.is_some() {
+            node.children = Some(Vec::new());
+        }
+    }
+    nodes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    DirectorySizeReport {
+        root_path: root_path.to_string_lossy().to_string(),
+        total_size_bytes,
+        entries: nodes,
+        largest_entries: largest.into_sorted(),
+    }
 }
 
 pub struct FileTreeService {
     options: FileTreeOptions,
+    /// Trees of watched workspaces, kept current by file watch events
+    pub(super) tree_caches: tokio::sync::Mutex<HashMap<PathBuf, FileTreeCache>>,
 }
 
 fn lock_search_results(
@@ -166,7 +303,14 @@ impl Default for FileTreeService {
 
 impl FileTreeService {
     pub fn new(options: FileTreeOptions) -> Self {
-        Self { options }
+        Self {
+            options,
+            tree_caches: Default::default(),
+        }
+    }
+
+    pub fn options(&self) -> &FileTreeOptions {
+        &self.options
     }
 
     pub async fn build_tree(&self, root_path: &str) -> Result<Vec<FileTreeNode>, String> {
@@ -185,9 +329,24 @@ impl FileTreeService {
             return Err("Path is not a directory".to_string());
         }
 
+        // Watched workspaces are served from the incrementally updated cache
+        if get_global_file_watcher().is_watching(&root_path_buf).await {
+            return self
+                .cached_tree(&root_path_buf)
+                .await
+                .map(|(_, nodes)| nodes);
+        }
+
+        let ignore_rules = self.ignore_rules(&root_path_buf);
         let mut visited = HashSet::new();
-        self.build_tree_recursive(&root_path_buf, &root_path_buf, &mut visited, 0)
-            .await
+        self.build_tree_recursive(
+            &root_path_buf,
+            &root_path_buf,
+            ignore_rules.as_ref(),
+            &mut visited,
+            0,
+        )
+        .await
     }
 
     pub async fn build_tree_with_stats(
@@ -207,6 +366,8 @@ impl FileTreeService {
                 large_files: Vec::new(),
                 symlinks_count: 0,
                 hidden_files_count: 0,
+                ignored_entries_count: 0,
+                largest_entries: Vec::new(),
             };
             return Ok((nodes, stats));
         }
@@ -231,12 +392,16 @@ impl FileTreeService {
             large_files: Vec::new(),
             symlinks_count: 0,
             hidden_files_count: 0,
+            ignored_entries_count: 0,
+            largest_entries: Vec::new(),
         };
 
-        let nodes = self
+        let ignore_rules = self.ignore_rules(&root_path_buf);
+        let mut nodes = self
             .build_tree_recursive_with_stats(
                 &root_path_buf,
                 &root_path_buf,
+                ignore_rules.as_ref(),
                 &mut visited,
                 0,
                 &mut stats,
@@ -244,13 +409,61 @@ impl FileTreeService {
             .await
             .map_err(|e| BitFunError::service(e))?;
 
+        if self.options.compute_directory_sizes {
+            let mut largest = LargestEntries::new(self.options.largest_entries_limit);
+            aggregate_directory_sizes(&mut nodes, &mut largest);
+            stats.largest_entries = largest.into_sorted();
+        }
+
         Ok((nodes, stats))
     }
 
-    fn build_tree_recursive<'a>(
+    /// Aggregated directory sizes and the `limit` largest files and
+    /// directories of a workspace
+    ///
+    /// Counts the entries the tree shows, so ignore files, skip patterns and
+    /// the size limit apply as they do there. A tree this service already
+    /// caches is reused, and so is the report until the tree changes.
+    pub async fn directory_size_report(
+        &self,
+        root_path: &str,
+        limit: usize,
+    ) -> BitFunResult<DirectorySizeReport> {
+        if crate::service::remote_ssh::workspace_state::is_remote_path(root_path).await {
+            return Err(BitFunError::service(
+                "Directory sizes are not available for remote workspaces".to_string(),
+            ));
+        }
+
+        let root_path_buf = PathBuf::from(root_path);
+        if !root_path_buf.is_dir() {
+            return Err(BitFunError::service("Directory does not exist".to_string()));
+        }
+
+        if let Some(report) = self.cached_size_report(&root_path_buf, limit).await {
+            return Ok(report);
+        }
+
+        let ignore_rules = self.ignore_rules(&root_path_buf);
+        let mut visited = HashSet::new();
+        let nodes = self
+            .build_tree_recursive(
+                &root_path_buf,
+                &root_path_buf,
+                ignore_rules.as_ref(),
+                &mut visited,
+                0,
+            )
+            .await
+            .map_err(BitFunError::service)?;
+        Ok(directory_size_report(&root_path_buf, nodes, limit))
+    }
+
+    pub(super) fn build_tree_recursive<'a>(
         &'a self,
         path: &'a PathBuf,
         root_path: &'a PathBuf,
+        ignore_rules: Option<&'a IgnoreRules>,
         visited: &'a mut HashSet<PathBuf>,
         depth: u32,
     ) -> std::pin::Pin<
@@ -300,112 +513,140 @@ impl FileTreeService {
             });
 
             for entry in entries {
-                let file_name = entry.file_name();
-                let file_name_str = file_name.to_string_lossy();
-
-                if self.should_skip_file(&file_name_str) {
+                let entry_path = entry.path();
+                let Some(mut node) = self
+                    .entry_node(&entry_path, root_path, ignore_rules, depth)
+                    .await
+                else {
                     continue;
+                };
+
+                if node.is_directory
+                    && (node.is_symlink != Some(true) || self.options.follow_symlinks)
+                {
+                    let child_rules = ignore_rules.map(|rules| rules.child(&entry_path));
+                    match self
+                        .build_tree_recursive(
+                            &entry_path,
+                            root_path,
+                            child_rules.as_ref(),
+                            visited,
+                            depth + 1,
+                        )
+                        .await
+                    {
+                        Ok(children) => {
+                            node = node.with_children(children);
+                        }
+                        Err(_) => {
+                            node = node.with_children(vec![]);
+                        }
+                    }
                 }
 
-                let entry_path = entry.path();
-                let relative_path = entry_path
-                    .strip_prefix(root_path)
-                    .unwrap_or(&entry_path)
-                    .to_string_lossy()
-                    .to_string();
+                nodes.push(node);
+            }
 
-                let file_type = match entry.file_type().await {
-                    Ok(ft) => ft,
-                    Err(_) => match std::fs::symlink_metadata(&entry_path) {
-                        Ok(metadata) => metadata.file_type(),
-                        Err(e) => {
-                            warn!(
-                                "Failed to get file type, skipping: {} ({})",
-                                entry_path.display(),
-                                e
-                            );
-                            continue;
-                        }
-                    },
-                };
+            Ok(nodes)
+        })
+    }
 
-                let is_directory = file_type.is_dir();
-                let is_symlink = file_type.is_symlink();
+    /// Builds the node for one directory entry, without children
+    ///
+    /// Returns `None` for entries that are missing, skipped by the options or
+    /// excluded by `ignore_rules`.
+    pub(super) async fn entry_node(
+        &self,
+        entry_path: &Path,
+        root_path: &Path,
+        ignore_rules: Option<&IgnoreRules>,
+        depth: u32,
+    ) -> Option<FileTreeNode> {
+        let file_name_str = entry_path.file_name()?.to_string_lossy().to_string();
 
-                let metadata = entry.metadata().await.ok();
-                let size = if is_directory {
-                    None
-                } else {
-                    metadata.as_ref().map(|m| m.len())
-                };
+        if self.should_skip_file(&file_name_str) {
+            return None;
+        }
 
-                if let (Some(size_bytes), Some(max_mb)) = (size, self.options.max_file_size_mb) {
-                    if size_bytes > max_mb * 1024 * 1024 {
-                        continue;
-                    }
-                }
+        let relative_path = entry_path
+            .strip_prefix(root_path)
+            .unwrap_or(entry_path)
+            .to_string_lossy()
+            .to_string();
+
+        let metadata = match fs::symlink_metadata(entry_path).await {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!(
+                    "Failed to get file type, skipping: {} ({})",
+                    entry_path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+        let file_type = metadata.file_type();
 
-                let last_modified = metadata.and_then(|m| {
-                    m.modified().ok().and_then(|t| {
-                        let datetime: chrono::DateTime<chrono::Utc> = t.into();
-                        Some(datetime.format("%Y-%m-%d %H:%M:%S").to_string())
-                    })
-                });
+        let is_directory = file_type.is_dir();
+        let is_symlink = file_type.is_symlink();
 
-                let extension = if !is_directory {
-                    entry_path
-                        .extension()
-                        .map(|ext| ext.to_string_lossy().to_string())
-                } else {
-                    None
-                };
+        if ignore_rules.is_some_and(|rules| rules.is_ignored(entry_path, is_directory)) {
+            return None;
+        }
 
-                let mime_type = if self.options.include_mime_types && !is_directory {
-                    self.detect_mime_type(&entry_path)
-                } else {
-                    None
-                };
+        let size = if is_directory {
+            None
+        } else {
+            Some(metadata.len())
+        };
 
-                let permissions = self.get_permissions_string(&entry_path).await;
+        if let (Some(size_bytes), Some(max_mb)) = (size, self.options.max_file_size_mb) {
+            if size_bytes > max_mb * 1024 * 1024 {
+                return None;
+            }
+        }
 
-                let mut node = FileTreeNode::new(
-                    relative_path,
-                    file_name_str.to_string(),
-                    entry_path.to_string_lossy().to_string(),
-                    is_directory,
-                )
-                .with_metadata(size, last_modified)
-                .with_extension(extension)
-                .with_depth(depth)
-                .with_enhanced_info(is_symlink, permissions, mime_type, None);
+        let last_modified = metadata.modified().ok().map(|t| {
+            let datetime: chrono::DateTime<chrono::Utc> = t.into();
+            datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+        });
 
-                if is_directory {
-                    if !is_symlink || self.options.follow_symlinks {
-                        match self
-                            .build_tree_recursive(&entry_path, root_path, visited, depth + 1)
-                            .await
-                        {
-                            Ok(children) => {
-                                node = node.with_children(children);
-                            }
-                            Err(_) => {
-                                node = node.with_children(vec![]);
-                            }
-                        }
-                    }
-                }
+        let extension = if !is_directory {
+            entry_path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+        } else {
+            None
+        };
 
-                nodes.push(node);
-            }
+        let mime_type = if self.options.include_mime_types && !is_directory {
+            self.detect_mime_type(entry_path)
+        } else {
+            None
+        };
 
-            Ok(nodes)
-        })
+        let permissions = self.get_permissions_string(entry_path).await;
+
+        Some(
+            FileTreeNode::new(
+                relative_path,
+                file_name_str,
+                entry_path.to_string_lossy().to_string(),
+                is_directory,
+            )
+            .with_metadata(size, last_modified)
+            .with_extension(extension)
+            .with_depth(depth)
+            .with_enhanced_info(is_symlink, permissions, mime_type, None),
+        )
     }
 
     fn build_tree_recursive_with_stats<'a>(
         &'a self,
         path: &'a PathBuf,
         root_path: &'a PathBuf,
+        ignore_rules: Option<&'a IgnoreRules>,
         visited: &'a mut HashSet<PathBuf>,
         depth: u32,
         stats: &'a mut FileTreeStatistics,
@@ -496,6 +737,11 @@ impl FileTreeService {
                 let is_directory = file_type.is_dir();
                 let is_symlink = file_type.is_symlink();
 
+                if ignore_rules.is_some_and(|rules| rules.is_ignored(&entry_path, is_directory)) {
+                    stats.ignored_entries_count += 1;
+                    continue;
+                }
+
                 if is_directory {
                     stats.total_directories += 1;
                 } else {
@@ -576,10 +822,12 @@ impl FileTreeService {
 
                 if is_directory {
                     if !is_symlink || self.options.follow_symlinks {
+                        let child_rules = ignore_rules.map(|rules| rules.child(&entry_path));
                         match self
                             .build_tree_recursive_with_stats(
                                 &entry_path,
                                 root_path,
+                                child_rules.as_ref(),
                                 visited,
                                 depth + 1,
                                 stats,
@@ -603,6 +851,11 @@ impl FileTreeService {
         })
     }
 
+    /// Ignore rules for a walk from `dir`; `None` when ignored entries are included
+    pub(super) fn ignore_rules(&self, dir: &Path) -> Option<IgnoreRules> {
+        (!self.options.include_ignored).then(|| IgnoreRules::for_dir(dir))
+    }
+
     fn should_skip_file(&self, file_name: &str) -> bool {
         // Skip hidden files and directories (unless explicitly included)
         // But .gitignore and .bitfun are always shown
@@ -668,6 +921,7 @@ impl FileTreeService {
             return Err("Path is not a directory".to_string());
         }
 
+        let ignore_rules = self.ignore_rules(&path_buf);
         let mut nodes = Vec::new();
 
         let mut read_dir = fs::read_dir(&path_buf)
@@ -689,6 +943,13 @@ impl FileTreeService {
             let entry_path = entry.path();
             let is_directory = entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false);
 
+            if ignore_rules
+                .as_ref()
+                .is_some_and(|rules| rules.is_ignored(&entry_path, is_directory))
+            {
+                continue;
+            }
+
             let node = FileTreeNode::new(
                 entry_path.to_string_lossy().to_string(),
                 file_name_str.to_string(),
@@ -787,6 +1048,100 @@ impl FileTreeService {
         }
     }
 
+    /// List workspace files as sorted relative paths (`/`-separated)
+    ///
+    /// Respects ignore files and the configured skip patterns, stopping after
+    /// `max_files` entries. The walk runs on the blocking pool.
+    pub async fn build_file_index(
+        &self,
+        root_path: &str,
+        max_files: usize,
+    ) -> BitFunResult<Vec<String>> {
+        let root_path_buf = PathBuf::from(root_path);
+        if !root_path_buf.is_dir() {
+            return Err(BitFunError::service("Directory does not exist".to_string()));
+        }
+
+        let service = FileTreeService::new(self.options.clone());
+        tokio::task::spawn_blocking(move || {
+            let mut builder = WalkBuilder::new(&root_path_buf);
+            let walker = configure_walk(&mut builder, service.options.include_ignored)
+                .hidden(false)
+                .follow_links(service.options.follow_symlinks)
+                .max_depth(service.options.max_depth.map(|depth| depth as usize))
+                .filter_entry(move |entry| {
+                    entry.depth() == 0
+                        || !service.should_skip_file(&entry.file_name().to_string_lossy())
+                })
+                .build();
+
+            let mut files = Vec::new();
+            for entry in walker.flatten() {
+                let is_file = entry.file_type().is_some_and(|ft| ft.is_file());
+                if !is_file {
+                    continue;
+                }
+                if let Ok(relative) = entry.path().strip_prefix(&root_path_buf) {
+                    files.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+                if files.len() >= max_files {
+                    break;
+                }
+            }
+            files.sort();
+            files
+        })
+        .await
+        .map_err(|e| BitFunError::service(format!("File index task failed: {}", e)))
+    }
+
+    /// Fuzzy file name search for quick-open style pickers
+    ///
+    /// Ranks the workspace files against `query` (see [`fuzzy_rank`]) and
+    /// returns at most `max_results` of them, best first. Watched workspaces
+    /// are searched through the cached tree index; others are walked first.
+    pub async fn fuzzy_search_files(
+        &self,
+        root_path: &str,
+        query: &str,
+        max_results: usize,
+    ) -> BitFunResult<Vec<FileSearchResult>> {
+        let root = PathBuf::from(root_path);
+        let index = if get_global_file_watcher().is_watching(&root).await {
+            self.cached_file_index(&root)
+                .await
+                .map_err(BitFunError::service)?
+        } else {
+            Arc::new(
+                self.build_file_index(root_path, MAX_FUZZY_INDEX_FILES)
+                    .await?,
+            )
+        };
+
+        let query = query.to_string();
+        tokio::task::spawn_blocking(move || {
+            fuzzy_rank(index.as_slice(), &query, max_results)
+                .into_iter()
+                .map(|(position, found)| {
+                    let relative = &index[position];
+                    let name = relative.rsplit('/').next().unwrap_or(relative);
+                    FileSearchResult {
+                        path: root.join(relative).to_string_lossy().to_string(),
+                        name: name.to_string(),
+                        is_directory: false,
+                        match_type: SearchMatchType::Fuzzy,
+                        line_number: None,
+                        matched_content: None,
+                        score: Some(found.score),
+                        match_indices: Some(found.indices),
+                    }
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| BitFunError::service(format!("Fuzzy search task failed: {}", e)))
+    }
+
     pub async fn search_files(
         &self,
         root_path: &str,
@@ -835,12 +1190,9 @@ impl FileTreeService {
         let pattern = pattern.to_string();
         let filename_pattern = Arc::new(filename_pattern);
 
-        let walker = WalkBuilder::new(&root_path_buf)
+        let mut builder = WalkBuilder::new(&root_path_buf);
+        let walker = configure_walk(&mut builder, self.options.include_ignored)
             .hidden(false)
-            .ignore(true)
-            .git_ignore(true)
-            .git_global(false)
-            .git_exclude(false)
             .threads(
                 std::thread::available_parallelism()
                     .map(|count| count.get())
@@ -910,6 +1262,8 @@ impl FileTreeService {
                                 match_type: SearchMatchType::FileName,
                                 line_number: None,
                                 matched_content: None,
+                                score: None,
+                                match_indices: None,
                             });
                         }
 
@@ -972,6 +1326,8 @@ impl FileTreeService {
                             match_type: SearchMatchType::FileName,
                             line_number: None,
                             matched_content: None,
+                            score: None,
+                            match_indices: None,
                         });
                     }
 
@@ -1010,7 +1366,17 @@ impl FileTreeService {
             })
         });
 
-        let final_results = lock_search_results(&results).clone();
+        // The walk is parallel; sort so results come back in the same order every time
+        let mut final_results = lock_search_results(&results).clone();
+        final_results.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then_with(|| {
+                    matches!(a.match_type, SearchMatchType::Content)
+                        .cmp(&matches!(b.match_type, SearchMatchType::Content))
+                })
+                .then_with(|| a.line_number.cmp(&b.line_number))
+        });
 
         Ok(final_results)
     }
@@ -1128,6 +1494,8 @@ impl Sink for FileContentSinkThreadSafe {
             match_type: SearchMatchType::Content,
             line_number: Some(line_number),
             matched_content: Some(matched_line),
+            score: None,
+            match_indices: None,
         });
 
         let should_continue = results.len() < self.max_results;
@@ -1147,10 +1515,17 @@ pub struct FileSearchResult {
     pub match_type: SearchMatchType,
     pub line_number: Option<usize>,
     pub matched_content: Option<String>,
+    /// Ranking score of a fuzzy match; higher is better
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<i64>,
+    /// Character positions of the fuzzy match in the path relative to the search root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_indices: Option<Vec<usize>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SearchMatchType {
     FileName,
     Content,
+    Fuzzy,
 }
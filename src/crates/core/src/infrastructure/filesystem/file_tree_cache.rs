@@ -0,0 +1,820 @@
+//! Incrementally updated file trees
+//!
+//! A watched workspace keeps its tree in memory as a flat, path-ordered map of
+//! nodes. File watch events are applied to it in place, each batch of changes
+//! bumps the tree version, and a client holding version N can ask for the
+//! changes since N instead of reloading the whole tree. A periodic full
+//! rebuild heals changes the watcher missed.
+
+use super::file_tree::{directory_size_report, DirectorySizeReport, FileTreeNode, FileTreeService};
+use super::file_watcher::{FileWatchEvent, FileWatchEventKind};
+use super::ignore_rules::IgnoreRules;
+use bitfun_transport::{FileTreeDeltaPayload, FileWatchEventPayload};
+use log::{debug, warn};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Number of changes kept for "changes since version N" requests.
+pub const TREE_CHANGE_LOG_CAPACITY: usize = 20_000;
+/// How often cached trees are rebuilt to heal missed watch events.
+pub const TREE_RECONCILE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// One change to a cached tree.
+#[derive(Debug, Clone)]
+pub enum FileTreeChange {
+    /// A new entry; directories are followed by their descendants
+    Create(FileTreeNode),
+    /// Size, modification time or permissions of an entry changed
+    Modify(FileTreeNode),
+    /// An entry and everything below it is gone
+    Remove(String),
+}
+
+impl FileTreeChange {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Create(node) | Self::Modify(node) => &node.path,
+            Self::Remove(path) => path,
+        }
+    }
+
+    fn to_payload(&self, timestamp: i64) -> FileWatchEventPayload {
+        let (event_type, node) = match self {
+            Self::Create(node) => ("create", Some(node)),
+            Self::Modify(node) => ("modify", Some(node)),
+            Self::Remove(_) => ("delete", None),
+        };
+        FileWatchEventPayload {
+            path: self.path().to_string(),
+            event_type: event_type.to_string(),
+            timestamp,
+            node: node.and_then(|node| serde_json::to_value(node).ok()),
+        }
+    }
+}
+
+/// Changes that bring a tree from `base_version` to `version`.
+#[derive(Debug, Clone)]
+pub struct FileTreePatch {
+    pub workspace_path: String,
+    pub base_version: u64,
+    pub version: u64,
+    /// The change log no longer reaches back to `base_version`; reload the tree
+    pub reset: bool,
+    pub changes: Vec<FileTreeChange>,
+}
+
+impl FileTreePatch {
+    pub fn to_payload(&self) -> FileTreeDeltaPayload {
+        let timestamp = chrono::Utc::now().timestamp();
+        FileTreeDeltaPayload {
+            workspace_path: self.workspace_path.clone(),
+            base_version: self.base_version,
+            version: self.version,
+            reset: self.reset,
+            changes: self
+                .changes
+                .iter()
+                .map(|change| change.to_payload(timestamp))
+                .collect(),
+        }
+    }
+}
+
+/// Cached tree of one workspace.
+pub struct FileTreeCache {
+    root: PathBuf,
+    /// Nodes by absolute path, without children. Directories the tree
+    /// descends into keep `Some(vec![])` as a marker.
+    nodes: BTreeMap<PathBuf, FileTreeNode>,
+    version: u64,
+    log: VecDeque<(u64, FileTreeChange)>,
+    /// Oldest version a patch can still be built from
+    log_base: u64,
+    log_capacity: usize,
+    reconciled_at: Instant,
+    /// Relative file paths for fuzzy search, with the version they were built at
+    file_index: Option<(u64, Arc<Vec<String>>)>,
+    /// Last size report, with the version and entry limit it was built for
+    size_report: Option<(u64, usize, Arc<DirectorySizeReport>)>,
+}
+
+impl FileTreeCache {
+    pub(super) async fn build(service: &FileTreeService, root: &Path) -> Result<Self, String> {
+        let nodes = scan(service, root).await?;
+        debug!(
+            "File tree cached: root={}, entries={}",
+            root.display(),
+            nodes.len()
+        );
+        Ok(Self {
+            root: root.to_path_buf(),
+            nodes,
+            version: 0,
+            log: VecDeque::new(),
+            log_base: 0,
+            log_capacity: TREE_CHANGE_LOG_CAPACITY,
+            reconciled_at: Instant::now(),
+            file_index: None,
+            size_report: None,
+        })
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The tree in the shape `FileTreeService::build_tree` returns.
+    pub fn snapshot(&self) -> Vec<FileTreeNode> {
+        let mut children: HashMap<&Path, Vec<&FileTreeNode>> = HashMap::new();
+        for (path, node) in &self.nodes {
+            let parent = path.parent().unwrap_or(&self.root);
+            children.entry(parent).or_default().push(node);
+        }
+        assemble(&self.root, &children)
+    }
+
+    /// Files of the tree as sorted relative paths (`/`-separated), rebuilt
+    /// lazily after the tree changes.
+    pub fn file_index(&mut self) -> Arc<Vec<String>> {
+        if let Some((version, index)) = &self.file_index {
+            if *version == self.version {
+                return Arc::clone(index);
+            }
+        }
+        let index: Arc<Vec<String>> = Arc::new(
+            self.nodes
+                .iter()
+                .filter(|(_, node)| !node.is_directory)
+                .filter_map(|(path, _)| path.strip_prefix(&self.root).ok())
+                .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                .collect(),
+        );
+        self.file_index = Some((self.version, Arc::clone(&index)));
+        index
+    }
+
+    /// Directory sizes of the tree, rebuilt lazily after the tree changes.
+    pub fn size_report(&mut self, limit: usize) -> Arc<DirectorySizeReport> {
+        if let Some((version, cached_limit, report)) = &self.size_report {
+            if *version == self.version && *cached_limit == limit {
+                return Arc::clone(report);
+            }
+        }
+        let report = Arc::new(directory_size_report(&self.root, self.snapshot(), limit));
+        self.size_report = Some((self.version, limit, Arc::clone(&report)));
+        report
+    }
+
+    /// Changes since `version`, or a reset patch when the log does not reach back that far.
+    pub fn changes_since(&self, version: u64) -> FileTreePatch {
+        let reset = version > self.version || version < self.log_base;
+        let changes = if reset {
+            Vec::new()
+        } else {
+            self.log
+                .iter()
+                .filter(|(logged, _)| *logged > version)
+                .map(|(_, change)| change.clone())
+                .collect()
+        };
+        FileTreePatch {
+            workspace_path: self.workspace_path(),
+            base_version: version,
+            version: self.version,
+            reset,
+            changes,
+        }
+    }
+
+    /// Applies a batch of watch events; `None` when the tree did not change.
+    ///
+    /// Paths are re-read from disk rather than trusting the event kind, so
+    /// duplicate, reordered or coalesced events end in the same tree.
+    pub(super) async fn apply_events(
+        &mut self,
+        service: &FileTreeService,
+        events: &[FileWatchEvent],
+    ) -> Option<FileTreePatch> {
+        let mut paths = BTreeSet::new();
+        for event in events {
+            let changed: Vec<PathBuf> = match &event.kind {
+                FileWatchEventKind::Rename { from, to } => {
+                    vec![PathBuf::from(from), PathBuf::from(to)]
+                }
+                _ => vec![PathBuf::from(&event.path)],
+            };
+            // Adding or removing an entry changes its directory's modification time
+            if !matches!(event.kind, FileWatchEventKind::Modify) {
+                paths.extend(
+                    changed
+                        .iter()
+                        .filter_map(|path| path.parent())
+                        .map(Path::to_path_buf),
+                );
+            }
+            paths.extend(changed);
+        }
+
+        let base_version = self.version;
+        let mut changes = Vec::new();
+        let mut rules_by_dir = HashMap::new();
+        // Entries that were not cached when the batch started: a rescan of
+        // one covers every event below it
+        let mut synced: Vec<PathBuf> = Vec::new();
+        for path in paths {
+            let Some(target) = self.sync_target(&path) else {
+                continue;
+            };
+            if synced.iter().any(|done| target.starts_with(done)) {
+                continue;
+            }
+            let was_cached = self.nodes.contains_key(&target);
+            self.sync_path(service, &target, &mut rules_by_dir, &mut changes)
+                .await;
+            if !was_cached {
+                synced.push(target);
+            }
+        }
+
+        self.commit(base_version, changes)
+    }
+
+    /// Rebuilds the tree from disk and records the differences as changes.
+    pub(super) async fn reconcile(&mut self, service: &FileTreeService) -> Option<FileTreePatch> {
+        self.reconciled_at = Instant::now();
+        let fresh = match scan(service, &self.root).await {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                warn!(
+                    "Failed to reconcile file tree {}: {}",
+                    self.root.display(),
+                    e
+                );
+                return None;
+            }
+        };
+
+        let mut removals = Vec::new();
+        let mut updates = Vec::new();
+        let mut removed_root: Option<&PathBuf> = None;
+        for (path, old) in &self.nodes {
+            if removed_root.is_some_and(|removed| path.starts_with(removed)) {
+                continue;
+            }
+            let replaced = match fresh.get(path) {
+                None => true,
+                Some(new) => {
+                    new.is_directory != old.is_directory || new.is_symlink != old.is_symlink
+                }
+            };
+            if replaced {
+                removals.push(FileTreeChange::Remove(old.path.clone()));
+                removed_root = Some(path);
+            }
+        }
+        for (path, new) in &fresh {
+            match self.nodes.get(path) {
+                Some(old)
+                    if old.is_directory == new.is_directory && old.is_symlink == new.is_symlink =>
+                {
+                    if metadata_changed(old, new) {
+                        updates.push(FileTreeChange::Modify(new.clone()));
+                    }
+                }
+                _ => updates.push(FileTreeChange::Create(new.clone())),
+            }
+        }
+
+        self.nodes = fresh;
+        removals.extend(updates);
+        if !removals.is_empty() {
+            debug!(
+                "File tree reconciled with {} missed changes: root={}",
+                removals.len(),
+                self.root.display()
+            );
+        }
+        let base_version = self.version;
+        self.commit(base_version, removals)
+    }
+
+    pub(super) fn needs_reconcile(&self) -> bool {
+        self.reconciled_at.elapsed() >= TREE_RECONCILE_INTERVAL
+    }
+
+    fn workspace_path(&self) -> String {
+        self.root.to_string_lossy().to_string()
+    }
+
+    /// Entry to re-read for an event at `path`: the path itself, or its
+    /// topmost ancestor the tree does not have yet.
+    fn sync_target(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut target = self.root.clone();
+        for component in relative.components() {
+            target.push(component);
+            if !self.nodes.contains_key(&target) {
+                return Some(target);
+            }
+        }
+        (target != self.root).then_some(target)
+    }
+
+    async fn sync_path(
+        &mut self,
+        service: &FileTreeService,
+        target: &Path,
+        rules_by_dir: &mut HashMap<PathBuf, Option<IgnoreRules>>,
+        changes: &mut Vec<FileTreeChange>,
+    ) {
+        let Some(parent) = target.parent() else {
+            return;
+        };
+        let rules = rules_by_dir
+            .entry(parent.to_path_buf())
+            .or_insert_with(|| service.ignore_rules(parent))
+            .clone();
+        let depth = target
+            .strip_prefix(&self.root)
+            .map(|relative| relative.components().count().saturating_sub(1) as u32)
+            .unwrap_or(0);
+        let within_depth = service
+            .options()
+            .max_depth
+            .is_none_or(|max_depth| depth <= max_depth);
+
+        let node = if within_depth {
+            service
+                .entry_node(target, &self.root, rules.as_ref(), depth)
+                .await
+        } else {
+            None
+        };
+
+        match (self.nodes.get(target).cloned(), node) {
+            (None, None) => {}
+            (Some(_), None) => self.remove_subtree(target, changes),
+            (None, Some(node)) => {
+                self.insert_subtree(service, node, rules.as_ref(), changes)
+                    .await
+            }
+            (Some(old), Some(mut node)) => {
+                if old.is_directory != node.is_directory || old.is_symlink != node.is_symlink {
+                    self.remove_subtree(target, changes);
+                    self.insert_subtree(service, node, rules.as_ref(), changes)
+                        .await;
+                } else if metadata_changed(&old, &node) {
+                    node.children = old.children.clone();
+                    self.nodes.insert(target.to_path_buf(), node.clone());
+                    changes.push(FileTreeChange::Modify(node));
+                }
+            }
+        }
+    }
+
+    async fn insert_subtree(
+        &mut self,
+        service: &FileTreeService,
+        mut node: FileTreeNode,
+        parent_rules: Option<&IgnoreRules>,
+        changes: &mut Vec<FileTreeChange>,
+    ) {
+        let path = PathBuf::from(&node.path);
+        let descend = node.is_directory
+            && (node.is_symlink != Some(true) || service.options().follow_symlinks);
+        if descend {
+            node.children = Some(Vec::new());
+        }
+        let depth = node.depth.unwrap_or(0);
+        self.nodes.insert(path.clone(), node.clone());
+        changes.push(FileTreeChange::Create(node));
+
+        if descend {
+            let child_rules = parent_rules.map(|rules| rules.child(&path));
+            let mut visited = HashSet::new();
+            let children = service
+                .build_tree_recursive(
+                    &path,
+                    &self.root,
+                    child_rules.as_ref(),
+                    &mut visited,
+                    depth + 1,
+                )
+                .await
+                .unwrap_or_default();
+            let mut descendants = BTreeMap::new();
+            flatten(children, &mut descendants);
+            for (path, node) in descendants {
+                changes.push(FileTreeChange::Create(node.clone()));
+                self.nodes.insert(path, node);
+            }
+        }
+    }
+
+    fn remove_subtree(&mut self, target: &Path, changes: &mut Vec<FileTreeChange>) {
+        let removed: Vec<PathBuf> = self
+            .nodes
+            .range(target.to_path_buf()..)
+            .take_while(|(path, _)| path.starts_with(target))
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in removed {
+            self.nodes.remove(&path);
+        }
+        changes.push(FileTreeChange::Remove(target.to_string_lossy().to_string()));
+    }
+
+    fn commit(&mut self, base_version: u64, changes: Vec<FileTreeChange>) -> Option<FileTreePatch> {
+        if changes.is_empty() {
+            return None;
+        }
+        self.version += 1;
+        for change in &changes {
+            self.log.push_back((self.version, change.clone()));
+        }
+        while self.log.len() > self.log_capacity {
+            if let Some((version, _)) = self.log.pop_front() {
+                self.log_base = self.log_base.max(version);
+            }
+        }
+        Some(FileTreePatch {
+            workspace_path: self.workspace_path(),
+            base_version,
+            version: self.version,
+            reset: false,
+            changes,
+        })
+    }
+}
+
+impl FileTreeService {
+    /// Tree of `root` from the cache, with its version; the cache is built on first use.
+    pub async fn cached_tree(&self, root: &Path) -> Result<(u64, Vec<FileTreeNode>), String> {
+        let mut caches = self.tree_caches.lock().await;
+        if !caches.contains_key(root) {
+            let cache = FileTreeCache::build(self, root).await?;
+            caches.insert(root.to_path_buf(), cache);
+        }
+        let cache = &caches[root];
+        Ok((cache.version(), cache.snapshot()))
+    }
+
+    /// File index of the cached tree of `root`; the cache is built on first use.
+    pub async fn cached_file_index(&self, root: &Path) -> Result<Arc<Vec<String>>, String> {
+        let mut caches = self.tree_caches.lock().await;
+        if !caches.contains_key(root) {
+            let cache = FileTreeCache::build(self, root).await?;
+            caches.insert(root.to_path_buf(), cache);
+        }
+        Ok(caches
+            .get_mut(root)
+            .map(FileTreeCache::file_index)
+            .unwrap_or_default())
+    }
+
+    /// Size report of `root` when its tree is cached; unlike the other
+    /// accessors this does not build the cache.
+    pub async fn cached_size_report(
+        &self,
+        root: &Path,
+        limit: usize,
+    ) -> Option<DirectorySizeReport> {
+        let mut caches = self.tree_caches.lock().await;
+        let report = caches.get_mut(root)?.size_report(limit);
+        Some((*report).clone())
+    }
+
+    /// Current version of the cached tree of `root`, if it is cached.
+    pub async fn tree_version(&self, root: &Path) -> Option<u64> {
+        let caches = self.tree_caches.lock().await;
+        caches.get(root).map(FileTreeCache::version)
+    }
+
+    /// Changes to the cached tree of `root` since `version`.
+    pub async fn tree_changes_since(&self, root: &Path, version: u64) -> Option<FileTreePatch> {
+        let caches = self.tree_caches.lock().await;
+        caches.get(root).map(|cache| cache.changes_since(version))
+    }
+
+    /// Applies watch events to every cached tree they touch.
+    pub async fn apply_watch_events(&self, events: &[FileWatchEvent]) -> Vec<FileTreePatch> {
+        let mut caches = self.tree_caches.lock().await;
+        let mut patches = Vec::new();
+        for cache in caches.values_mut() {
+            if let Some(patch) = cache.apply_events(self, events).await {
+                patches.push(patch);
+            }
+        }
+        patches
+    }
+
+    /// Drops trees that are no longer watched and rebuilds the ones due for
+    /// reconciliation.
+    pub async fn reconcile_trees(&self, is_watched: impl Fn(&Path) -> bool) -> Vec<FileTreePatch> {
+        let mut caches = self.tree_caches.lock().await;
+        caches.retain(|root, _| is_watched(root));
+        let mut patches = Vec::new();
+        for cache in caches.values_mut() {
+            if !cache.needs_reconcile() {
+                continue;
+            }
+            if let Some(patch) = cache.reconcile(self).await {
+                patches.push(patch);
+            }
+        }
+        patches
+    }
+}
+
+async fn scan(
+    service: &FileTreeService,
+    root: &Path,
+) -> Result<BTreeMap<PathBuf, FileTreeNode>, String> {
+    let root = root.to_path_buf();
+    let rules = service.ignore_rules(&root);
+    let mut visited = HashSet::new();
+    let tree = service
+        .build_tree_recursive(&root, &root, rules.as_ref(), &mut visited, 0)
+        .await?;
+    let mut nodes = BTreeMap::new();
+    flatten(tree, &mut nodes);
+    Ok(nodes)
+}
+
+fn flatten(tree: Vec<FileTreeNode>, nodes: &mut BTreeMap<PathBuf, FileTreeNode>) {
+    for mut node in tree {
+        let children = node.children.as_mut().map(std::mem::take);
+        nodes.insert(PathBuf::from(&node.path), node);
+        if let Some(children) = children {
+            flatten(children, nodes);
+        }
+    }
+}
+
+fn assemble(dir: &Path, children: &HashMap<&Path, Vec<&FileTreeNode>>) -> Vec<FileTreeNode> {
+    let Some(entries) = children.get(dir) else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<FileTreeNode> = entries
+        .iter()
+        .map(|node| {
+            let mut node = (*node).clone();
+            if node.children.is_some() {
+                node.children = Some(assemble(Path::new(&node.path), children));
+            }
+            node
+        })
+        .collect();
+    nodes.sort_by(|a, b| match (a.is_directory, b.is_directory) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.cmp(&b.name),
+    });
+    nodes
+}
+
+fn metadata_changed(old: &FileTreeNode, new: &FileTreeNode) -> bool {
+    old.size != new.size
+        || old.last_modified != new.last_modified
+        || old.permissions != new.permissions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(path: &Path, kind: FileWatchEventKind) -> FileWatchEvent {
+        FileWatchEvent {
+            path: path.to_string_lossy().to_string(),
+            kind,
+            timestamp: 0,
+        }
+    }
+
+    async fn assert_matches_disk(cache: &FileTreeCache, service: &FileTreeService, root: &Path) {
+        let fresh = service.build_tree(&root.to_string_lossy()).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(cache.snapshot()).unwrap(),
+            serde_json::to_value(fresh).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_event_bursts_incrementally() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let service = FileTreeService::default();
+        let mut cache = FileTreeCache::build(&service, root).await.unwrap();
+
+        // Thousands of creates, each reported twice, with modifies mixed in
+        let mut events = Vec::new();
+        for d in 0..40 {
+            let dir = root.join(format!("dir{:02}", d));
+            std::fs::create_dir_all(&dir).unwrap();
+            events.push(event(&dir, FileWatchEventKind::Create));
+            for f in 0..50 {
+                let file = dir.join(format!("file{:02}.txt", f));
+                std::fs::write(&file, "x").unwrap();
+                events.push(event(&file, FileWatchEventKind::Create));
+                events.push(event(&file, FileWatchEventKind::Modify));
+                events.push(event(&file, FileWatchEventKind::Create));
+            }
+        }
+        let patch = cache.apply_events(&service, &events).await.unwrap();
+        assert_eq!((patch.base_version, patch.version), (0, 1));
+        assert_eq!(patch.changes.len(), 40 + 40 * 50);
+        assert_matches_disk(&cache, &service, root).await;
+
+        // Removals, renames and events for paths that never existed
+        let mut events = Vec::new();
+        for d in 0..20 {
+            let dir = root.join(format!("dir{:02}", d));
+            for f in 0..50 {
+                events.push(event(
+                    &dir.join(format!("file{:02}.txt", f)),
+                    FileWatchEventKind::Remove,
+                ));
+            }
+            std::fs::remove_dir_all(&dir).unwrap();
+            events.push(event(&dir, FileWatchEventKind::Remove));
+        }
+        for d in 20..30 {
+            let from = root.join(format!("dir{:02}", d)).join("file00.txt");
+            let to = root.join(format!("dir{:02}", d)).join("renamed.txt");
+            std::fs::rename(&from, &to).unwrap();
+            events.push(event(
+                &to,
+                FileWatchEventKind::Rename {
+                    from: from.to_string_lossy().to_string(),
+                    to: to.to_string_lossy().to_string(),
+                },
+            ));
+        }
+        events.push(event(&root.join("missing.txt"), FileWatchEventKind::Remove));
+        let patch = cache.apply_events(&service, &events).await.unwrap();
+        assert_eq!((patch.base_version, patch.version), (1, 2));
+        // One removal per directory, a removal and a creation per rename; the renamed
+        // files' directories are modified too if their mtime moved to the next second
+        let structural = patch
+            .changes
+            .iter()
+            .filter(|change| !matches!(change, FileTreeChange::Modify(_)))
+            .count();
+        assert_eq!(structural, 20 + 10 * 2);
+        assert_matches_disk(&cache, &service, root).await;
+
+        // Events that change nothing do not bump the version
+        let events = vec![event(
+            &root.join("dir30").join("file01.txt"),
+            FileWatchEventKind::Modify,
+        )];
+        assert!(cache.apply_events(&service, &events).await.is_none());
+        assert_eq!(cache.version(), 2);
+    }
+
+    #[tokio::test]
+    async fn reports_directory_sizes_until_the_tree_changes() {
+        use crate::infrastructure::filesystem::FileTreeOptions;
+
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src").join("nested")).unwrap();
+        std::fs::create_dir_all(root.join("logs")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules").join("pkg")).unwrap();
+        std::fs::write(root.join(".gitignore"), "logs/\n").unwrap();
+        std::fs::write(root.join("README.md"), vec![b'r'; 10]).unwrap();
+        std::fs::write(root.join("src").join("main.rs"), vec![b'm'; 100]).unwrap();
+        std::fs::write(
+            root.join("src").join("nested").join("big.bin"),
+            vec![0; 1000],
+        )
+        .unwrap();
+        // Ignored by .gitignore and by the skip patterns
+        std::fs::write(root.join("logs").join("huge.log"), vec![b'l'; 5000]).unwrap();
+        std::fs::write(
+            root.join("node_modules").join("pkg").join("index.js"),
+            vec![b'n'; 5000],
+        )
+        .unwrap();
+
+        let service = FileTreeService::default();
+        let root_str = root.to_string_lossy().to_string();
+        let report = service.directory_size_report(&root_str, 3).await.unwrap();
+        assert_eq!(report.total_size_bytes, 6 + 10 + 100 + 1000);
+        let top: Vec<(&str, Option<u64>)> = report
+            .entries
+            .iter()
+            .map(|node| (node.name.as_str(), node.size))
+            .collect();
+        assert_eq!(
+            top,
+            [
+                ("src", Some(1100)),
+                ("README.md", Some(10)),
+                (".gitignore", Some(6))
+            ]
+        );
+        let largest: Vec<(PathBuf, u64)> = report
+            .largest_entries
+            .iter()
+            .map(|entry| (PathBuf::from(&entry.path), entry.size_bytes))
+            .collect();
+        assert_eq!(
+            largest,
+            [
+                (root.join("src"), 1100),
+                (root.join("src").join("nested"), 1000),
+                (root.join("src").join("nested").join("big.bin"), 1000),
+            ]
+        );
+
+        // Served from the cached tree, and rebuilt once it changes
+        service.cached_tree(root).await.unwrap();
+        let cached = service.directory_size_report(&root_str, 3).await.unwrap();
+        assert_eq!(cached.largest_entries, report.largest_entries);
+        let extra = root.join("src").join("extra.rs");
+        std::fs::write(&extra, vec![b'e'; 50]).unwrap();
+        service
+            .apply_watch_events(&[event(&extra, FileWatchEventKind::Create)])
+            .await;
+        let updated = service.directory_size_report(&root_str, 3).await.unwrap();
+        assert_eq!(updated.total_size_bytes, report.total_size_bytes + 50);
+        assert_eq!(updated.largest_entries[0].size_bytes, 1150);
+
+        // The statistics walk agrees
+        let service = FileTreeService::new(FileTreeOptions {
+            compute_directory_sizes: true,
+            largest_entries_limit: 3,
+            ..Default::default()
+        });
+        let (nodes, stats) = service.build_tree_with_stats(&root_str).await.unwrap();
+        assert_eq!(stats.largest_entries, updated.largest_entries);
+        let src = nodes.iter().find(|node| node.name == "src").unwrap();
+        assert_eq!(src.size, Some(1150));
+    }
+
+    #[tokio::test]
+    async fn serves_changes_since_a_version() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let service = FileTreeService::default();
+        let mut cache = FileTreeCache::build(&service, root).await.unwrap();
+        cache.log_capacity = 3;
+
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            let path = root.join(name);
+            std::fs::write(&path, name).unwrap();
+            cache
+                .apply_events(&service, &[event(&path, FileWatchEventKind::Create)])
+                .await
+                .unwrap();
+        }
+
+        let patch = cache.changes_since(1);
+        assert!(!patch.reset);
+        assert_eq!(patch.version, 3);
+        let paths: Vec<&str> = patch.changes.iter().map(|c| c.path()).collect();
+        assert_eq!(
+            paths,
+            [
+                root.join("b.txt").to_string_lossy(),
+                root.join("c.txt").to_string_lossy()
+            ]
+        );
+        assert!(cache.changes_since(3).changes.is_empty());
+
+        // The log only holds three changes
+        let path = root.join("d.txt");
+        std::fs::write(&path, "d").unwrap();
+        cache
+            .apply_events(&service, &[event(&path, FileWatchEventKind::Create)])
+            .await
+            .unwrap();
+        assert!(cache.changes_since(0).reset);
+        assert!(!cache.changes_since(1).reset);
+        assert!(cache.changes_since(9).reset);
+    }
+
+    #[tokio::test]
+    async fn reconcile_heals_missed_events() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("old")).unwrap();
+        std::fs::write(root.join("old").join("a.txt"), "a").unwrap();
+        let service = FileTreeService::default();
+        let mut cache = FileTreeCache::build(&service, root).await.unwrap();
+
+        std::fs::remove_dir_all(root.join("old")).unwrap();
+        std::fs::create_dir_all(root.join("new")).unwrap();
+        std::fs::write(root.join("new").join("b.txt"), "b").unwrap();
+
+        let patch = cache.reconcile(&service).await.unwrap();
+        assert!(matches!(&patch.changes[0], FileTreeChange::Remove(path) if path.ends_with("old")));
+        assert_eq!(patch.changes.len(), 3);
+        assert_matches_disk(&cache, &service, root).await;
+        assert!(cache.reconcile(&service).await.is_none());
+    }
+}
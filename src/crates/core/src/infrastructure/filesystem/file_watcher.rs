@@ -1,14 +1,20 @@
 //! File watcher service
 //!
-//! Uses the notify crate to watch filesystem changes and send them to the frontend via Tauri events
+//! Uses the notify crate to watch filesystem changes and send them to the frontend via Tauri events.
+//! Raw events are filtered, merged per path into their net effect while the stream is busy, and
+//! delivered as one batch once it has been quiet for the debounce interval.
 
+use super::file_tree::FileTreeService;
+use super::file_tree_cache::{FileTreePatch, TREE_RECONCILE_INTERVAL};
 use crate::infrastructure::events::EventEmitter;
 use log::{debug, error};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex as StdMutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, Weak};
 use tokio::sync::{Mutex, RwLock};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,10 +46,16 @@ impl From<&EventKind> for FileWatchEventKind {
     }
 }
 
+/// Receives every delivered batch of watch events, e.g. to keep an index current.
+pub trait FileWatchListener: Send + Sync {
+    fn on_file_events(&self, events: &[FileWatchEvent]);
+}
+
 #[derive(Debug, Clone)]
 pub struct FileWatcherConfig {
     pub watch_recursively: bool,
     pub ignore_hidden_files: bool,
+    /// Quiet period after the last event before a batch is delivered
     pub debounce_interval_ms: u64,
     pub max_events_per_interval: usize,
 }
@@ -59,17 +71,193 @@ impl Default for FileWatcherConfig {
     }
 }
 
+/// Event counters since the watcher was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWatcherStats {
+    /// Raw events received from notify
+    pub received: u64,
+    /// Events dropped by the ignore filters, or of a kind that is not reported
+    pub ignored: u64,
+    /// Events merged into another event for the same path, or cancelled out
+    pub coalesced: u64,
+    /// Events delivered after coalescing
+    pub delivered: u64,
+    /// Batches delivered
+    pub batches: u64,
+}
+
+#[derive(Default)]
+struct WatchCounters {
+    received: AtomicU64,
+    ignored: AtomicU64,
+    coalesced: AtomicU64,
+    delivered: AtomicU64,
+    batches: AtomicU64,
+}
+
+impl WatchCounters {
+    fn snapshot(&self) -> FileWatcherStats {
+        FileWatcherStats {
+            received: self.received.load(Ordering::Relaxed),
+            ignored: self.ignored.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            batches: self.batches.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Pending events merged per path into their net effect.
+#[derive(Default)]
+struct EventCoalescer {
+    pending: BTreeMap<String, FileWatchEvent>,
+}
+
+impl EventCoalescer {
+    /// Adds `event`; returns how many events were absorbed by merging.
+    fn push(&mut self, event: FileWatchEvent) -> u64 {
+        match self.pending.entry(event.path.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(event);
+                0
+            }
+            Entry::Occupied(mut entry) => match merge_kinds(&entry.get().kind, event.kind) {
+                Some(kind) => {
+                    let pending = entry.get_mut();
+                    pending.kind = kind;
+                    pending.timestamp = event.timestamp;
+                    1
+                }
+                None => {
+                    entry.remove();
+                    2
+                }
+            },
+        }
+    }
+
+    /// Takes the pending events, ordered by path.
+    fn drain(&mut self) -> Vec<FileWatchEvent> {
+        std::mem::take(&mut self.pending).into_values().collect()
+    }
+}
+
+/// Net effect of `previous` followed by `next` on the same path; `None` when
+/// they cancel out, e.g. a file created and removed within one burst.
+fn merge_kinds(
+    previous: &FileWatchEventKind,
+    next: FileWatchEventKind,
+) -> Option<FileWatchEventKind> {
+    use FileWatchEventKind::*;
+    Some(match (previous, next) {
+        (Create, Remove) => return None,
+        (Create, Create | Modify | Other) => Create,
+        (Remove, Create | Modify) => Modify,
+        (Remove, Other) | (Modify, Other) | (Rename { .. }, Modify | Other) => previous.clone(),
+        (_, next) => next,
+    })
+}
+
+/// Delivered events most recently seen, oldest first, bounded to `RECENT_EVENTS_CAPACITY`.
+#[derive(Default)]
+struct RecentEvents {
+    events: VecDeque<FileWatchEvent>,
+}
+
+const RECENT_EVENTS_CAPACITY: usize = 512;
+
+impl RecentEvents {
+    fn record(&mut self, events: &[FileWatchEvent]) {
+        self.events.extend(events.iter().cloned());
+        let excess = self.events.len().saturating_sub(RECENT_EVENTS_CAPACITY);
+        self.events.drain(..excess);
+    }
+
+    /// Latest event per path below `root` at or after `since` (Unix seconds), newest first.
+    fn latest_under(&self, root: &Path, since: u64) -> Vec<FileWatchEvent> {
+        let mut seen = HashSet::new();
+        self.events
+            .iter()
+            .rev()
+            .take_while(|event| event.timestamp >= since)
+            .filter(|event| Path::new(&event.path).starts_with(root))
+            .filter(|event| seen.insert(event.path.clone()))
+            .cloned()
+            .collect()
+    }
+}
+
 pub struct FileWatcher {
     emitter: Arc<Mutex<Option<Arc<dyn EventEmitter>>>>,
     watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
     watched_paths: Arc<RwLock<HashMap<PathBuf, FileWatcherConfig>>>,
-    event_buffer: Arc<StdMutex<Vec<FileWatchEvent>>>,
+    event_buffer: Arc<StdMutex<EventCoalescer>>,
+    counters: Arc<WatchCounters>,
+    /// File tree services whose cached trees follow the watch events
+    tree_services: Arc<StdMutex<Vec<Weak<FileTreeService>>>>,
+    listeners: Arc<StdMutex<Vec<Weak<dyn FileWatchListener>>>>,
+    recent_events: Arc<StdMutex<RecentEvents>>,
     config: FileWatcherConfig,
 }
 
+fn lock_tree_services(
+    tree_services: &StdMutex<Vec<Weak<FileTreeService>>>,
+) -> std::sync::MutexGuard<'_, Vec<Weak<FileTreeService>>> {
+    match tree_services.lock() {
+        Ok(services) => services,
+        Err(poisoned) => {
+            error!("File watcher tree service mutex was poisoned, recovering lock");
+            poisoned.into_inner()
+        }
+    }
+}
+
+fn lock_listeners(
+    listeners: &StdMutex<Vec<Weak<dyn FileWatchListener>>>,
+) -> std::sync::MutexGuard<'_, Vec<Weak<dyn FileWatchListener>>> {
+    match listeners.lock() {
+        Ok(listeners) => listeners,
+        Err(poisoned) => {
+            error!("File watcher listener mutex was poisoned, recovering lock");
+            poisoned.into_inner()
+        }
+    }
+}
+
+/// Registered listeners that are still alive; dropped ones are pruned.
+fn live_listeners(
+    listeners: &StdMutex<Vec<Weak<dyn FileWatchListener>>>,
+) -> Vec<Arc<dyn FileWatchListener>> {
+    let mut listeners = lock_listeners(listeners);
+    listeners.retain(|listener| listener.strong_count() > 0);
+    listeners.iter().filter_map(Weak::upgrade).collect()
+}
+
+/// Registered tree services that are still alive; dropped ones are pruned.
+fn live_tree_services(
+    tree_services: &StdMutex<Vec<Weak<FileTreeService>>>,
+) -> Vec<Arc<FileTreeService>> {
+    let mut services = lock_tree_services(tree_services);
+    services.retain(|service| service.strong_count() > 0);
+    services.iter().filter_map(Weak::upgrade).collect()
+}
+
+fn lock_recent_events(
+    recent_events: &StdMutex<RecentEvents>,
+) -> std::sync::MutexGuard<'_, RecentEvents> {
+    match recent_events.lock() {
+        Ok(recent) => recent,
+        Err(poisoned) => {
+            error!("File watcher recent events mutex was poisoned, recovering lock");
+            poisoned.into_inner()
+        }
+    }
+}
+
 fn lock_event_buffer(
-    event_buffer: &StdMutex<Vec<FileWatchEvent>>,
-) -> std::sync::MutexGuard<'_, Vec<FileWatchEvent>> {
+    event_buffer: &StdMutex<EventCoalescer>,
+) -> std::sync::MutexGuard<'_, EventCoalescer> {
     match event_buffer.lock() {
         Ok(buffer) => buffer,
         Err(poisoned) => {
@@ -85,11 +273,78 @@ impl FileWatcher {
             emitter: Arc::new(Mutex::new(None)),
             watcher: Arc::new(Mutex::new(None)),
             watched_paths: Arc::new(RwLock::new(HashMap::new())),
-            event_buffer: Arc::new(StdMutex::new(Vec::new())),
+            event_buffer: Arc::new(StdMutex::new(EventCoalescer::default())),
+            counters: Arc::new(WatchCounters::default()),
+            tree_services: Arc::new(StdMutex::new(Vec::new())),
+            listeners: Arc::new(StdMutex::new(Vec::new())),
+            recent_events: Arc::new(StdMutex::new(RecentEvents::default())),
             config,
         }
     }
 
+    /// Feeds watch events to the cached trees of `service`.
+    pub fn register_tree_service(&self, service: &Arc<FileTreeService>) {
+        lock_tree_services(&self.tree_services).push(Arc::downgrade(service));
+    }
+
+    /// Feeds every delivered batch to `listener` until it is dropped.
+    pub fn register_listener(&self, listener: Weak<dyn FileWatchListener>) {
+        lock_listeners(&self.listeners).push(listener);
+    }
+
+    /// Version of the cached tree of `root` in a registered tree service;
+    /// `None` when no service has the tree cached.
+    pub async fn tree_version(&self, root: &Path) -> Option<u64> {
+        for service in live_tree_services(&self.tree_services) {
+            if let Some(version) = service.tree_version(root).await {
+                return Some(version);
+            }
+        }
+        None
+    }
+
+    /// Files below `root` changed at or after `since` (Unix seconds), one event per path with
+    /// the newest first. Only the last few hundred delivered events are remembered.
+    pub fn recent_changes(&self, root: &Path, since: u64) -> Vec<FileWatchEvent> {
+        lock_recent_events(&self.recent_events).latest_under(root, since)
+    }
+
+    /// Event counters, e.g. to see how much of a burst was suppressed.
+    pub fn stats(&self) -> FileWatcherStats {
+        self.counters.snapshot()
+    }
+
+    /// Returns whether `path` is inside a recursively watched path.
+    pub async fn is_watching(&self, path: &Path) -> bool {
+        let watched_paths = self.watched_paths.read().await;
+        watched_paths
+            .iter()
+            .any(|(watched, config)| config.watch_recursively && path.starts_with(watched))
+    }
+
+    /// Rebuilds cached trees that are due for reconciliation and emits the
+    /// changes that the watcher missed.
+    pub async fn reconcile_trees(&self) {
+        let watched: Vec<PathBuf> = {
+            let watched_paths = self.watched_paths.read().await;
+            watched_paths
+                .iter()
+                .filter(|(_, config)| config.watch_recursively)
+                .map(|(path, _)| path.clone())
+                .collect()
+        };
+
+        let mut patches = Vec::new();
+        for service in live_tree_services(&self.tree_services) {
+            patches.extend(
+                service
+                    .reconcile_trees(|root| watched.iter().any(|path| root.starts_with(path)))
+                    .await,
+            );
+        }
+        Self::emit_tree_patches(&patches, &self.emitter).await;
+    }
+
     pub async fn set_emitter(&self, emitter: Arc<dyn EventEmitter>) {
         let mut e = self.emitter.lock().await;
         *e = Some(emitter);
@@ -163,6 +418,10 @@ impl FileWatcher {
         }
 
         let event_buffer = self.event_buffer.clone();
+        let counters = self.counters.clone();
+        let tree_services = self.tree_services.clone();
+        let listeners = self.listeners.clone();
+        let recent_events = self.recent_events.clone();
         let emitter_arc = self.emitter.clone();
         let config = self.config.clone();
         let watched_paths = self.watched_paths.clone();
@@ -180,12 +439,22 @@ impl FileWatcher {
             loop {
                 match rx.recv_timeout(poll) {
                     Ok(Ok(event)) => {
+                        counters.received.fetch_add(1, Ordering::Relaxed);
                         let ignore = rt.block_on(Self::should_ignore_event(&event, &watched_paths));
-                        if !ignore {
-                            if let Some(file_event) = Self::convert_event(&event) {
-                                lock_event_buffer(&event_buffer).push(file_event);
+                        let file_event = if ignore {
+                            None
+                        } else {
+                            Self::convert_event(&event)
+                        };
+                        match file_event {
+                            Some(file_event) => {
+                                let merged = lock_event_buffer(&event_buffer).push(file_event);
+                                counters.coalesced.fetch_add(merged, Ordering::Relaxed);
                                 last_event_time = Some(std::time::Instant::now());
                             }
+                            None => {
+                                counters.ignored.fetch_add(1, Ordering::Relaxed);
+                            }
                         }
                     }
                     Ok(Err(e)) => eprintln!("Watch error: {:?}", e),
@@ -196,7 +465,14 @@ impl FileWatcher {
                 // Flush only after events have been quiet for the debounce window.
                 if let Some(t) = last_event_time {
                     if t.elapsed() >= debounce {
-                        rt.block_on(Self::flush_events_static(&event_buffer, &emitter_arc));
+                        rt.block_on(Self::flush_events_static(
+                            &event_buffer,
+                            &counters,
+                            &tree_services,
+                            &listeners,
+                            &recent_events,
+                            &emitter_arc,
+                        ));
                         last_event_time = None;
                     }
                 }
@@ -217,20 +493,22 @@ impl FileWatcher {
             None => return true,
         };
 
-        let mut matching_config = None;
+        let mut matching = None;
         for (watch_path, config) in paths.iter() {
-            if event_path.starts_with(watch_path) {
-                matching_config = Some(config);
+            if let Ok(relative) = event_path.strip_prefix(watch_path) {
+                matching = Some((relative, config));
                 break;
             }
         }
 
-        let config = match matching_config {
-            Some(config) => config,
+        let (relative_path, config) = match matching {
+            Some(matching) => matching,
             None => return true,
         };
 
-        if Self::is_in_excluded_directory(event_path) {
+        // Only the part below the watched path counts, so a workspace that
+        // itself lives under e.g. `build/` is still watched
+        if Self::is_in_excluded_directory(relative_path) {
             return true;
         }
 
@@ -254,7 +532,6 @@ impl FileWatcher {
     fn is_in_excluded_directory(path: &Path) -> bool {
         const EXCLUDED_DIRS: &[&str] = &[
             "node_modules",
-            ".git",
             ".svn",
             ".hg",
             "target",
@@ -283,17 +560,32 @@ impl FileWatcher {
             "htmlcov",
         ];
 
-        for component in path.components() {
-            if let Some(os_str) = component.as_os_str().to_str() {
-                if EXCLUDED_DIRS.contains(&os_str) {
-                    return true;
-                }
+        let components: Vec<&str> = path
+            .components()
+            .filter_map(|component| component.as_os_str().to_str())
+            .collect();
+        for (i, name) in components.iter().enumerate() {
+            if *name == ".git" {
+                return !Self::is_watched_git_path(&components[i + 1..]);
+            }
+            if EXCLUDED_DIRS.contains(name) {
+                return true;
             }
         }
 
         false
     }
 
+    /// Git internals worth reporting: `HEAD` and refs, which change on
+    /// checkout, commit and fetch. Everything else under `.git` is noise.
+    fn is_watched_git_path(relative: &[&str]) -> bool {
+        match relative {
+            ["HEAD"] => true,
+            ["refs", .., name] => !name.ends_with(".lock"),
+            _ => false,
+        }
+    }
+
     fn is_temporary_file(path: &Path) -> bool {
         if let Some(file_name) = path.file_name() {
             if let Some(name_str) = file_name.to_str() {
@@ -339,45 +631,36 @@ impl FileWatcher {
     }
 
     async fn flush_events_static(
-        event_buffer: &Arc<StdMutex<Vec<FileWatchEvent>>>,
+        event_buffer: &Arc<StdMutex<EventCoalescer>>,
+        counters: &WatchCounters,
+        tree_services: &Arc<StdMutex<Vec<Weak<FileTreeService>>>>,
+        listeners: &Arc<StdMutex<Vec<Weak<dyn FileWatchListener>>>>,
+        recent_events: &Arc<StdMutex<RecentEvents>>,
         emitter_arc: &Arc<Mutex<Option<Arc<dyn EventEmitter>>>>,
     ) {
-        let events = {
-            let mut buffer = lock_event_buffer(event_buffer);
-            if buffer.is_empty() {
-                return;
-            }
-            buffer.drain(..).collect::<Vec<_>>()
-        };
+        let events = lock_event_buffer(event_buffer).drain();
+        if events.is_empty() {
+            return;
+        }
+        lock_recent_events(recent_events).record(&events);
+        counters
+            .delivered
+            .fetch_add(events.len() as u64, Ordering::Relaxed);
+        counters.batches.fetch_add(1, Ordering::Relaxed);
+
+        let mut tree_patches = Vec::new();
+        for service in live_tree_services(tree_services) {
+            tree_patches.extend(service.apply_watch_events(&events).await);
+        }
+        Self::emit_tree_patches(&tree_patches, emitter_arc).await;
+
+        for listener in live_listeners(listeners) {
+            listener.on_file_events(&events);
+        }
 
         let emitter_guard = emitter_arc.lock().await;
         if let Some(emitter) = emitter_guard.as_ref() {
-            let mut event_array = Vec::new();
-
-            for event in &events {
-                let kind = match event.kind {
-                    FileWatchEventKind::Create => "create",
-                    FileWatchEventKind::Modify => "modify",
-                    FileWatchEventKind::Remove => "remove",
-                    FileWatchEventKind::Rename { ref from, ref to } => {
-                        event_array.push(serde_json::json!({
-                            "path": to,
-                            "kind": "rename",
-                            "from": from,
-                            "to": to,
-                            "timestamp": event.timestamp
-                        }));
-                        continue;
-                    }
-                    FileWatchEventKind::Other => "other",
-                };
-
-                event_array.push(serde_json::json!({
-                    "path": event.path,
-                    "kind": kind,
-                    "timestamp": event.timestamp
-                }));
-            }
+            let event_array = Self::batch_entries(&events);
 
             if let Err(e) = emitter
                 .emit("file-system-changed", serde_json::json!(event_array))
@@ -385,13 +668,94 @@ impl FileWatcher {
             {
                 error!("Failed to emit file-system-changed events: {}", e);
             } else {
-                debug!("Emitted {} file system change events", event_array.len());
+                debug!(
+                    "Emitted {} file system changes in {} entries",
+                    events.len(),
+                    event_array.len()
+                );
             }
         } else {
             debug!("EventEmitter not configured, skipping file watch events");
         }
     }
 
+    /// One entry per event kind listing every path with that kind (`path` is
+    /// the first of them); renames get an entry each.
+    fn batch_entries(events: &[FileWatchEvent]) -> Vec<serde_json::Value> {
+        let mut entries = Vec::new();
+        let mut grouped: Vec<(&str, Vec<&str>, u64)> = Vec::new();
+        for event in events {
+            let kind = match &event.kind {
+                FileWatchEventKind::Create => "create",
+                FileWatchEventKind::Modify => "modify",
+                FileWatchEventKind::Remove => "remove",
+                FileWatchEventKind::Rename { from, to } => {
+                    entries.push(serde_json::json!({
+                        "path": to,
+                        "kind": "rename",
+                        "from": from,
+                        "to": to,
+                        "timestamp": event.timestamp
+                    }));
+                    continue;
+                }
+                FileWatchEventKind::Other => "other",
+            };
+            match grouped
+                .iter_mut()
+                .find(|(group_kind, _, _)| *group_kind == kind)
+            {
+                Some((_, paths, timestamp)) => {
+                    paths.push(event.path.as_str());
+                    *timestamp = (*timestamp).max(event.timestamp);
+                }
+                None => grouped.push((kind, vec![event.path.as_str()], event.timestamp)),
+            }
+        }
+
+        entries.extend(grouped.into_iter().map(|(kind, paths, timestamp)| {
+            serde_json::json!({
+                "path": paths[0],
+                "paths": paths,
+                "kind": kind,
+                "timestamp": timestamp
+            })
+        }));
+        entries
+    }
+
+    async fn emit_tree_patches(
+        patches: &[FileTreePatch],
+        emitter_arc: &Arc<Mutex<Option<Arc<dyn EventEmitter>>>>,
+    ) {
+        if patches.is_empty() {
+            return;
+        }
+        let emitter_guard = emitter_arc.lock().await;
+        let Some(emitter) = emitter_guard.as_ref() else {
+            return;
+        };
+        for patch in patches {
+            let payload = match serde_json::to_value(patch.to_payload()) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    error!("Failed to serialize file tree delta: {}", e);
+                    continue;
+                }
+            };
+            if let Err(e) = emitter.emit("file-tree-changed", payload).await {
+                error!("Failed to emit file-tree-changed event: {}", e);
+            } else {
+                debug!(
+                    "Emitted file tree delta: workspace={}, version={}, changes={}",
+                    patch.workspace_path,
+                    patch.version,
+                    patch.changes.len()
+                );
+            }
+        }
+    }
+
     pub async fn get_watched_paths(&self) -> Vec<String> {
         let watched_paths = self.watched_paths.read().await;
         watched_paths
@@ -437,5 +801,213 @@ pub fn initialize_file_watcher(emitter: Arc<dyn EventEmitter>) {
 
     tokio::spawn(async move {
         watcher.set_emitter(emitter).await;
+
+        // Periodic full rebuild of cached trees heals missed watch events
+        let mut interval = tokio::time::interval(TREE_RECONCILE_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            watcher.reconcile_trees().await;
+        }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    fn event(path: &str, kind: FileWatchEventKind) -> FileWatchEvent {
+        FileWatchEvent {
+            path: path.to_string(),
+            kind,
+            timestamp: 0,
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingEmitter {
+        emitted: StdMutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    #[async_trait]
+    impl EventEmitter for RecordingEmitter {
+        async fn emit(&self, event_name: &str, payload: serde_json::Value) -> anyhow::Result<()> {
+            self.emitted
+                .lock()
+                .unwrap()
+                .push((event_name.to_string(), payload));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn recent_events_keep_latest_change_per_path_under_root() {
+        let timed = |path: &str, kind, timestamp| FileWatchEvent {
+            timestamp,
+            ..event(path, kind)
+        };
+        let mut recent = RecentEvents::default();
+        recent.record(&[
+            timed("/ws/a.rs", FileWatchEventKind::Create, 10),
+            timed("/ws/b.rs", FileWatchEventKind::Modify, 20),
+            timed("/other/c.rs", FileWatchEventKind::Modify, 30),
+        ]);
+        recent.record(&[timed("/ws/a.rs", FileWatchEventKind::Modify, 40)]);
+
+        let latest = recent.latest_under(Path::new("/ws"), 15);
+        let summary: Vec<(&str, u64)> = latest
+            .iter()
+            .map(|event| (event.path.as_str(), event.timestamp))
+            .collect();
+        assert_eq!(summary, vec![("/ws/a.rs", 40), ("/ws/b.rs", 20)]);
+
+        recent.record(&vec![
+            event("/ws/x", FileWatchEventKind::Modify);
+            RECENT_EVENTS_CAPACITY
+        ]);
+        assert_eq!(recent.events.len(), RECENT_EVENTS_CAPACITY);
+    }
+
+    #[test]
+    fn coalesces_events_per_path_into_net_effect() {
+        use FileWatchEventKind::*;
+
+        let mut coalescer = EventCoalescer::default();
+        let absorbed: u64 = [
+            ("a", Create),
+            ("a", Modify),
+            ("a", Modify),
+            ("b", Modify),
+            ("b", Modify),
+            ("c", Create),
+            ("c", Remove),
+            ("d", Remove),
+            ("d", Create),
+            ("e", Modify),
+            ("e", Remove),
+        ]
+        .into_iter()
+        .map(|(path, kind)| coalescer.push(event(path, kind)))
+        .sum();
+        assert_eq!(absorbed, 7);
+
+        let events = coalescer.drain();
+        let summary: Vec<(&str, &str)> = events
+            .iter()
+            .map(|event| {
+                let kind = match event.kind {
+                    Create => "create",
+                    Modify => "modify",
+                    Remove => "remove",
+                    _ => "other",
+                };
+                (event.path.as_str(), kind)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a", "create"),
+                ("b", "modify"),
+                ("d", "modify"),
+                ("e", "remove")
+            ]
+        );
+        assert!(coalescer.drain().is_empty());
+
+        let entries = FileWatcher::batch_entries(&events);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1]["kind"], "modify");
+        assert_eq!(entries[1]["paths"], serde_json::json!(["b", "d"]));
+    }
+
+    #[test]
+    fn ignores_build_output_and_git_internals_except_refs() {
+        for ignored in [
+            "target/debug/deps/app.o",
+            "web/node_modules/react/index.js",
+            ".git/objects/ab/cdef",
+            ".git/index",
+            ".git/refs/heads/main.lock",
+        ] {
+            assert!(
+                FileWatcher::is_in_excluded_directory(Path::new(ignored)),
+                "{}",
+                ignored
+            );
+        }
+        for watched in [
+            "src/main.rs",
+            ".git/HEAD",
+            ".git/refs/heads/main",
+            ".git/refs/remotes/origin/feature/x",
+        ] {
+            assert!(
+                !FileWatcher::is_in_excluded_directory(Path::new(watched)),
+                "{}",
+                watched
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn delivers_a_burst_as_one_filtered_batch() {
+        let dir = std::env::temp_dir().join(format!("bitfun-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("target").join("debug")).unwrap();
+        std::fs::create_dir_all(dir.join(".git").join("refs").join("heads")).unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        let watcher = FileWatcher::new(FileWatcherConfig {
+            debounce_interval_ms: 200,
+            ..Default::default()
+        });
+        let emitter = Arc::new(RecordingEmitter::default());
+        watcher.set_emitter(emitter.clone()).await;
+        watcher
+            .watch_path(&dir.to_string_lossy(), None)
+            .await
+            .unwrap();
+
+        for i in 0..50 {
+            std::fs::write(dir.join("main.rs"), format!("fn main() {{ {} }}", i)).unwrap();
+            std::fs::write(
+                dir.join("target").join("debug").join(format!("obj{}.o", i)),
+                "x",
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.join(".git").join("HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let started = std::time::Instant::now();
+        while watcher.stats().batches == 0 && started.elapsed() < Duration::from_secs(5) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        watcher.unwatch_path(&dir.to_string_lossy()).await.unwrap();
+
+        let stats = watcher.stats();
+        assert!(stats.batches >= 1);
+        assert!(stats.ignored >= 50, "{:?}", stats);
+        assert!(stats.coalesced > 0, "{:?}", stats);
+        assert!(stats.delivered + stats.coalesced <= stats.received - stats.ignored);
+
+        let mut paths = Vec::new();
+        for (name, payload) in emitter.emitted.lock().unwrap().iter() {
+            assert_eq!(name, "file-system-changed");
+            for entry in payload.as_array().unwrap() {
+                for path in entry["paths"].as_array().unwrap() {
+                    paths.push(PathBuf::from(path.as_str().unwrap()));
+                }
+            }
+        }
+        assert!(paths.contains(&dir.join("main.rs")));
+        assert!(paths.contains(&dir.join(".git").join("HEAD")));
+        assert!(!paths
+            .iter()
+            .any(|path| path.starts_with(dir.join("target"))));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,341 @@
+//! Fuzzy path matching
+//!
+//! fzf-style subsequence matching: every query character must appear in the
+//! candidate in order (case-insensitive, whitespace in the query is ignored).
+//! Among all alignments the best-scoring one is picked; matches at word and
+//! path-segment starts, consecutive runs and hits in the file name score
+//! higher, gaps score lower.
+
+use std::cmp::Ordering;
+
+const SCORE_MATCH: i64 = 16;
+const SCORE_GAP_START: i64 = -3;
+const SCORE_GAP_EXTENSION: i64 = -1;
+const BONUS_PATH_SEPARATOR: i64 = 9;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL_CASE: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 4;
+const BONUS_FILE_NAME: i64 = 2;
+/// The first query character decides most of the ranking, so its bonus counts double
+const BONUS_FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+const NO_MATCH: i64 = i64::MIN / 2;
+
+/// A successful fuzzy match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Character (not byte) positions of the matched query characters, ascending
+    pub indices: Vec<usize>,
+}
+
+/// Matcher for one query; reuses its buffers across candidates.
+pub struct FuzzyMatcher {
+    query: Vec<char>,
+    /// Case-folded characters of the candidate's match window
+    lower: Vec<char>,
+    /// Bonus for a hit at each position of the window
+    bonus: Vec<i64>,
+    scores: Vec<i64>,
+    /// Column of the previous query character on the best path to each cell
+    from: Vec<usize>,
+}
+
+impl FuzzyMatcher {
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(fold_case)
+                .collect(),
+            lower: Vec::new(),
+            bonus: Vec::new(),
+            scores: Vec::new(),
+            from: Vec::new(),
+        }
+    }
+
+    /// Whether the query is empty, i.e. matches everything with score 0
+    pub fn is_empty(&self) -> bool {
+        self.query.is_empty()
+    }
+
+    /// Best match of the query in `candidate`, or `None` when it is not a subsequence
+    pub fn matches(&mut self, candidate: &str) -> Option<FuzzyMatch> {
+        if self.query.is_empty() {
+            return Some(FuzzyMatch {
+                score: 0,
+                indices: Vec::new(),
+            });
+        }
+        let (start, end) = self.prepare(candidate)?;
+        let width = end - start;
+        let (mut col, score) = self.align(width)?;
+
+        let mut indices = vec![0; self.query.len()];
+        for row in (0..self.query.len()).rev() {
+            indices[row] = start + col;
+            col = self.from[row * width + col];
+        }
+        Some(FuzzyMatch { score, indices })
+    }
+
+    /// Score of the best match, without the positions
+    pub fn score(&mut self, candidate: &str) -> Option<i64> {
+        if self.query.is_empty() {
+            return Some(0);
+        }
+        let (start, end) = self.prepare(candidate)?;
+        self.align(end - start).map(|(_, score)| score)
+    }
+
+    /// Finds the window the alignment has to lie in, from the first hit of
+    /// the first query character to the last hit of the last one, and loads
+    /// its characters and bonuses into the buffers. Candidates that cannot
+    /// match are rejected without copying anything.
+    fn prepare(&mut self, candidate: &str) -> Option<(usize, usize)> {
+        let last = self.query[self.query.len() - 1];
+        let mut matched = 0;
+        let (mut start, mut end, mut file_name_start) = (0, 0, 0);
+        for (i, c) in candidate.chars().enumerate() {
+            let folded = fold_case(c);
+            if matched < self.query.len() && folded == self.query[matched] {
+                if matched == 0 {
+                    start = i;
+                }
+                matched += 1;
+            }
+            if folded == last {
+                end = i + 1;
+            }
+            if c == '/' || c == '\\' {
+                file_name_start = i + 1;
+            }
+        }
+        if matched < self.query.len() {
+            return None;
+        }
+
+        self.lower.clear();
+        self.bonus.clear();
+        let mut previous = None;
+        for (i, c) in candidate
+            .chars()
+            .enumerate()
+            .skip(start.saturating_sub(1))
+            .take(end - start.saturating_sub(1))
+        {
+            if i >= start {
+                let mut bonus = char_bonus(previous, c);
+                if i >= file_name_start {
+                    bonus += BONUS_FILE_NAME;
+                }
+                self.lower.push(fold_case(c));
+                self.bonus.push(bonus);
+            }
+            previous = Some(c);
+        }
+        Some((start, end))
+    }
+
+    /// Smith-Waterman style alignment of the query within the prepared window;
+    /// returns the column of the last query character and the score.
+    fn align(&mut self, width: usize) -> Option<(usize, i64)> {
+        let rows = self.query.len();
+        self.scores.clear();
+        self.scores.resize(rows * width, NO_MATCH);
+        self.from.clear();
+        self.from.resize(rows * width, 0);
+
+        for row in 0..rows {
+            let wanted = self.query[row];
+            // Best score of the previous row at least two columns back, with
+            // the gap penalty up to the current column already applied.
+            let mut gap_best = NO_MATCH;
+            let mut gap_from = 0;
+            for col in 0..width {
+                if row > 0 && col >= 2 {
+                    let candidate = self.scores[(row - 1) * width + col - 2] + SCORE_GAP_START;
+                    if candidate > gap_best + SCORE_GAP_EXTENSION {
+                        gap_best = candidate;
+                        gap_from = col - 2;
+                    } else {
+                        gap_best += SCORE_GAP_EXTENSION;
+                    }
+                }
+                if self.lower[col] != wanted {
+                    continue;
+                }
+
+                let cell = row * width + col;
+                let bonus = self.bonus[col];
+                if row == 0 {
+                    self.scores[cell] = SCORE_MATCH + bonus * BONUS_FIRST_CHAR_MULTIPLIER;
+                    continue;
+                }
+
+                let mut best = NO_MATCH;
+                let mut best_from = 0;
+                if col >= 1 {
+                    let previous = self.scores[(row - 1) * width + col - 1];
+                    if previous > NO_MATCH {
+                        best = previous + SCORE_MATCH + bonus + BONUS_CONSECUTIVE;
+                        best_from = col - 1;
+                    }
+                }
+                if gap_best > NO_MATCH / 2 && gap_best + SCORE_MATCH + bonus > best {
+                    best = gap_best + SCORE_MATCH + bonus;
+                    best_from = gap_from;
+                }
+                if best > NO_MATCH {
+                    self.scores[cell] = best;
+                    self.from[cell] = best_from;
+                }
+            }
+        }
+
+        let last_row = (rows - 1) * width;
+        (0..width)
+            .map(|col| (col, self.scores[last_row + col]))
+            .filter(|(_, score)| *score > NO_MATCH / 2)
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+    }
+}
+
+/// Matches a single candidate; use [`FuzzyMatcher`] or [`fuzzy_rank`] for many.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    FuzzyMatcher::new(query).matches(candidate)
+}
+
+/// Ranks `candidates` against `query`, best first, keeping at most `limit`.
+///
+/// Returns the candidate positions with their matches. Equal scores prefer
+/// shorter candidates, then alphabetical order.
+pub fn fuzzy_rank<S: AsRef<str>>(
+    candidates: &[S],
+    query: &str,
+    limit: usize,
+) -> Vec<(usize, FuzzyMatch)> {
+    let mut matcher = FuzzyMatcher::new(query);
+    let mut ranked: Vec<(usize, i64)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, candidate)| matcher.score(candidate.as_ref()).map(|score| (i, score)))
+        .collect();
+
+    let compare = |a: &(usize, i64), b: &(usize, i64)| -> Ordering {
+        let (a_text, b_text) = (candidates[a.0].as_ref(), candidates[b.0].as_ref());
+        b.1.cmp(&a.1)
+            .then_with(|| a_text.len().cmp(&b_text.len()))
+            .then_with(|| a_text.cmp(b_text))
+    };
+    if limit == 0 {
+        return Vec::new();
+    }
+    if limit < ranked.len() {
+        ranked.select_nth_unstable_by(limit - 1, compare);
+        ranked.truncate(limit);
+    }
+    ranked.sort_unstable_by(compare);
+
+    // Positions are only worked out for the results that are returned
+    ranked
+        .into_iter()
+        .filter_map(|(i, _)| matcher.matches(candidates[i].as_ref()).map(|m| (i, m)))
+        .collect()
+}
+
+fn fold_case(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+fn char_bonus(previous: Option<char>, current: char) -> i64 {
+    match previous {
+        None => BONUS_BOUNDARY,
+        Some('/' | '\\') => BONUS_PATH_SEPARATOR,
+        Some('_' | '-' | '.' | ' ') => BONUS_BOUNDARY,
+        Some(previous) if previous.is_lowercase() && current.is_uppercase() => BONUS_CAMEL_CASE,
+        Some(previous) if !previous.is_ascii_digit() && current.is_ascii_digit() => {
+            BONUS_CAMEL_CASE
+        }
+        Some(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_subsequences_with_positions() {
+        let found = fuzzy_match("fts", "src/file_tree_service.rs").unwrap();
+        assert_eq!(found.indices, vec![4, 9, 14]);
+        assert_eq!(fuzzy_match("FTS", "src/file_tree_service.rs"), Some(found));
+        assert!(fuzzy_match("stf", "src/file_tree.rs").is_none());
+        assert_eq!(
+            fuzzy_match("", "anything").unwrap().indices,
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn prefers_boundaries_over_the_first_occurrence() {
+        // A greedy scan would take the `m` of `command` and the `o` after it
+        let found = fuzzy_match("mod", "commands/mod.rs").unwrap();
+        assert_eq!(found.indices, vec![9, 10, 11]);
+    }
+
+    #[test]
+    fn ranks_file_names_and_tight_matches_first() {
+        let candidates = [
+            "src/main_window/dialog.rs",
+            "docs/chat-guide.md",
+            "src/ui/chat.rs",
+            "src/ui/widgets.rs",
+            "crates/core/src/agentic/tools/mod.rs",
+        ];
+        let ranked: Vec<&str> = fuzzy_rank(&candidates, "chat", 10)
+            .into_iter()
+            .map(|(i, _)| candidates[i])
+            .collect();
+        assert_eq!(ranked, vec!["src/ui/chat.rs", "docs/chat-guide.md"]);
+
+        let top = fuzzy_rank(&candidates, "mw", 1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(candidates[top[0].0], "src/main_window/dialog.rs");
+    }
+
+    /// Rough benchmark: `cargo test -p bitfun-core --release fuzzy_rank_50k -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn fuzzy_rank_50k_paths() {
+        let paths: Vec<String> = (0..50_000)
+            .map(|i| {
+                format!(
+                    "crates/module_{}/src/component_{}/file_tree_part_{}.rs",
+                    i % 97,
+                    i % 13,
+                    i
+                )
+            })
+            .collect();
+
+        for query in ["ftp", "module12comp3", "src/file_tree_part_4999", "zzz"] {
+            let started = std::time::Instant::now();
+            let ranked = fuzzy_rank(&paths, query, 50);
+            let elapsed = started.elapsed();
+            println!(
+                "{:>28}: {:>3} results in {:?}",
+                query,
+                ranked.len(),
+                elapsed
+            );
+            assert!(elapsed < std::time::Duration::from_millis(20));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Ignore rules for workspace walks
+//!
+//! Layers the rules git applies (the global excludes file, `.git/info/exclude`
+//! and `.gitignore` files) with BitFun's own `.bitfunignore`. `.bitfunignore`
+//! uses gitignore syntax and wins over `.gitignore` in the same directory;
+//! rules from a deeper directory win over rules from its parents.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use log::debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// BitFun-specific ignore file, read in every directory like `.gitignore`.
+pub const BITFUN_IGNORE_FILE: &str = ".bitfunignore";
+
+const GIT_IGNORE_FILE: &str = ".gitignore";
+
+/// Stack of ignore matchers, from the most general to the most specific.
+#[derive(Clone, Default)]
+pub struct IgnoreRules {
+    layers: Vec<Arc<Gitignore>>,
+}
+
+impl IgnoreRules {
+    /// Rules in effect for the entries of `dir`: the global gitignore, the
+    /// repository's `.git/info/exclude`, and the ignore files of `dir` and of
+    /// its ancestors up to the repository root.
+    pub fn for_dir(dir: &Path) -> Self {
+        let mut rules = Self::default();
+
+        let (global, error) = Gitignore::global();
+        if let Some(error) = error {
+            debug!("Failed to read global gitignore: {}", error);
+        }
+        rules.push(global);
+
+        let ancestors: Vec<&Path> = match find_repo_root(dir) {
+            Some(repo_root) => {
+                rules.push(build_matcher(
+                    repo_root,
+                    &[repo_root.join(".git").join("info").join("exclude")],
+                ));
+                dir.ancestors()
+                    .take_while(|ancestor| ancestor.starts_with(repo_root))
+                    .collect()
+            }
+            None => vec![dir],
+        };
+        for ancestor in ancestors.into_iter().rev() {
+            rules = rules.child(ancestor);
+        }
+        rules
+    }
+
+    /// Rules for the entries of `dir`, a subdirectory of the directory these
+    /// rules apply to.
+    pub fn child(&self, dir: &Path) -> Self {
+        let files: Vec<PathBuf> = [GIT_IGNORE_FILE, BITFUN_IGNORE_FILE]
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        let mut rules = self.clone();
+        if !files.is_empty() {
+            rules.push(build_matcher(dir, &files));
+        }
+        rules
+    }
+
+    /// Whether `path` is excluded; the most specific matching rule decides.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for layer in self.layers.iter().rev() {
+            match layer.matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    fn push(&mut self, matcher: Gitignore) {
+        if !matcher.is_empty() {
+            self.layers.push(Arc::new(matcher));
+        }
+    }
+}
+
+/// Applies the same layering to a `WalkBuilder`, or turns all ignore files
+/// off when `include_ignored` is set.
+pub fn configure_walk(builder: &mut WalkBuilder, include_ignored: bool) -> &mut WalkBuilder {
+    builder
+        .ignore(!include_ignored)
+        .git_ignore(!include_ignored)
+        .git_global(!include_ignored)
+        .git_exclude(!include_ignored)
+        .require_git(false);
+    if !include_ignored {
+        builder.add_custom_ignore_filename(BITFUN_IGNORE_FILE);
+    }
+    builder
+}
+
+fn find_repo_root(dir: &Path) -> Option<&Path> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+}
+
+fn build_matcher(root: &Path, files: &[PathBuf]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for file in files.iter().filter(|file| file.is_file()) {
+        if let Some(error) = builder.add(file) {
+            debug!("Failed to parse ignore file {}: {}", file.display(), error);
+        }
+    }
+    builder.build().unwrap_or_else(|error| {
+        debug!(
+            "Failed to build ignore rules for {}: {}",
+            root.display(),
+            error
+        );
+        Gitignore::empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_gitignore_exclude_and_bitfunignore() {
+        let root = std::env::temp_dir().join(format!("bitfun-ignore-{}", uuid::Uuid::new_v4()));
+        let src = root.join("src");
+        std::fs::create_dir_all(root.join(".git").join("info")).unwrap();
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(root.join(".git/info/exclude"), "scratch/\n").unwrap();
+        std::fs::write(root.join(GIT_IGNORE_FILE), "target/\n*.log\n").unwrap();
+        std::fs::write(root.join(BITFUN_IGNORE_FILE), "fixtures/\n!keep.log\n").unwrap();
+        std::fs::write(src.join(GIT_IGNORE_FILE), "generated.rs\n").unwrap();
+
+        let rules = IgnoreRules::for_dir(&root);
+        assert!(rules.is_ignored(&root.join("target"), true));
+        assert!(rules.is_ignored(&root.join("scratch"), true));
+        assert!(rules.is_ignored(&root.join("fixtures"), true));
+        assert!(rules.is_ignored(&root.join("debug.log"), false));
+        assert!(!rules.is_ignored(&root.join("keep.log"), false));
+        assert!(!rules.is_ignored(&root.join("src"), true));
+
+        let src_rules = rules.child(&src);
+        assert!(src_rules.is_ignored(&src.join("generated.rs"), false));
+        assert!(src_rules.is_ignored(&src.join("trace.log"), false));
+        assert!(!src_rules.is_ignored(&src.join("main.rs"), false));
+        // Opening the subdirectory directly picks up the parent rules
+        assert!(IgnoreRules::for_dir(&src).is_ignored(&src.join("trace.log"), false));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -4,20 +4,31 @@
 
 pub mod file_operations;
 pub mod file_tree;
+pub mod file_tree_cache;
 pub mod file_watcher;
+pub mod fuzzy_match;
+pub mod ignore_rules;
+pub mod path_containment;
 pub mod path_manager;
+pub mod text_encoding;
 
 pub use file_operations::{
-    normalize_text_for_editor_disk_sync, FileInfo, FileOperationOptions, FileOperationService,
-    FileReadResult, FileWriteResult,
+    atomic_write, content_sha256_hex, normalize_text_for_editor_disk_sync, FileInfo,
+    FileOperationOptions, FileOperationService, FileReadResult, FileWriteResult,
 };
 pub use file_tree::{
-    FileSearchResult, FileTreeNode, FileTreeOptions, FileTreeService, FileTreeStatistics,
-    SearchMatchType,
+    DirectorySizeReport, FileSearchResult, FileTreeNode, FileTreeOptions, FileTreeService,
+    FileTreeStatistics, LargestEntry, SearchMatchType,
 };
+pub use file_tree_cache::{FileTreeCache, FileTreeChange, FileTreePatch};
 pub use file_watcher::initialize_file_watcher;
+pub use fuzzy_match::{fuzzy_match, fuzzy_rank, FuzzyMatch, FuzzyMatcher};
+pub use ignore_rules::{IgnoreRules, BITFUN_IGNORE_FILE};
 #[cfg(feature = "tauri-support")]
 pub use file_watcher::{get_watched_paths, start_file_watch, stop_file_watch};
+pub use path_containment::{ensure_contained, real_path, symlink_escape};
 pub use path_manager::{
-    get_path_manager_arc, try_get_path_manager_arc, CacheType, PathManager, StorageLevel,
+    cache_consumers, get_path_manager_arc, register_cache_consumer, try_get_path_manager_arc,
+    unregister_cache_consumer, CacheConsumer, CacheType, PathManager, StorageLevel,
 };
+pub use text_encoding::{decode_text, detect_encoding, encode_text, DecodedText, TextEncoding};
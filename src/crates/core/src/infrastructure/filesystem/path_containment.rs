@@ -0,0 +1,150 @@
+//! Workspace containment of symlinked paths
+//!
+//! A path can sit inside the workspace by name while a symlink, Windows
+//! junction or mount point along the way leads somewhere else entirely.
+//! These helpers resolve where a path really ends up and report when that is
+//! outside the workspace root. Paths that are outside the workspace by name
+//! are not this module's concern.
+
+use crate::util::errors::{BitFunError, BitFunResult};
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Links followed by hand (dangling ones) before giving up on a path
+const MAX_LINK_HOPS: usize = 40;
+
+/// Resolves every symlink, junction and mount point in `path`.
+///
+/// Components that do not exist yet are appended to the real path of their
+/// nearest existing ancestor, so paths about to be created resolve too. A
+/// dangling symlink is followed to its target, since writing through it
+/// creates the target. Symlink loops are an error.
+pub fn real_path(path: &Path) -> BitFunResult<PathBuf> {
+    let mut pending: Vec<OsString> = Vec::new();
+    let mut current = path.to_path_buf();
+    let mut hops = 0;
+
+    loop {
+        match std::fs::canonicalize(&current) {
+            Ok(real) => {
+                return Ok(pending
+                    .iter()
+                    .rev()
+                    .fold(real, |real, component| real.join(component)))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                if let Ok(target) = std::fs::read_link(&current) {
+                    hops += 1;
+                    if hops > MAX_LINK_HOPS {
+                        return Err(BitFunError::validation(format!(
+                            "Cannot resolve {}: too many levels of symbolic links",
+                            path.display()
+                        )));
+                    }
+                    current = match current.parent() {
+                        Some(parent) => parent.join(target),
+                        None => target,
+                    };
+                    continue;
+                }
+                match (current.parent(), current.file_name()) {
+                    (Some(parent), Some(name)) => {
+                        pending.push(name.to_os_string());
+                        current = parent.to_path_buf();
+                    }
+                    _ => return Ok(path.to_path_buf()),
+                }
+            }
+            Err(e) => {
+                return Err(BitFunError::validation(format!(
+                    "Cannot resolve {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        }
+    }
+}
+
+/// Where `path` really leads when it is inside `root` by name but a symlink,
+/// junction or mount point takes it outside; `None` otherwise.
+pub fn symlink_escape(path: &Path, root: &Path) -> BitFunResult<Option<PathBuf>> {
+    if !path.starts_with(root) {
+        return Ok(None);
+    }
+    let real_root = real_path(root)?;
+    let real = real_path(path)?;
+    if real.starts_with(&real_root) {
+        Ok(None)
+    } else {
+        Ok(Some(real))
+    }
+}
+
+/// Fails when `path` escapes `root` through a symlink, junction or mount
+/// point, unless `allow_escape` is set.
+pub fn ensure_contained(path: &Path, root: &Path, allow_escape: bool) -> BitFunResult<()> {
+    match symlink_escape(path, root)? {
+        Some(real) if !allow_escape => Err(BitFunError::validation(format!(
+            "{} resolves to {}, which is outside the workspace {}",
+            path.display(),
+            real.display(),
+            root.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn refuses_symlinks_leading_out_of_the_workspace() {
+        let workspace_temp = tempfile::tempdir().unwrap();
+        let workspace = workspace_temp.path();
+        let outside_temp = tempfile::tempdir().unwrap();
+        let outside = outside_temp.path();
+        std::fs::write(outside.join("secret.txt"), "s").unwrap();
+        std::fs::create_dir(workspace.join("src")).unwrap();
+        symlink(outside, workspace.join("escape")).unwrap();
+        symlink(workspace.join("src"), workspace.join("inner")).unwrap();
+        symlink(outside.join("new.txt"), workspace.join("dangling")).unwrap();
+
+        let secret = workspace.join("escape").join("secret.txt");
+        assert_eq!(
+            symlink_escape(&secret, workspace).unwrap(),
+            Some(real_path(outside).unwrap().join("secret.txt"))
+        );
+        assert!(ensure_contained(&secret, workspace, false).is_err());
+        assert!(ensure_contained(&secret, workspace, true).is_ok());
+
+        // Files about to be created resolve through their parents and dangling links
+        let new_file = workspace.join("escape").join("sub").join("new.txt");
+        assert!(symlink_escape(&new_file, workspace).unwrap().is_some());
+        let dangling = workspace.join("dangling");
+        assert!(symlink_escape(&dangling, workspace).unwrap().is_some());
+
+        let inner = workspace.join("inner").join("main.rs");
+        assert_eq!(symlink_escape(&inner, workspace).unwrap(), None);
+        assert_eq!(
+            symlink_escape(&outside.join("secret.txt"), workspace).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn looped_symlinks_are_an_error() {
+        let workspace_temp = tempfile::tempdir().unwrap();
+        let workspace = workspace_temp.path();
+        symlink(workspace.join("b"), workspace.join("a")).unwrap();
+        symlink(workspace.join("a"), workspace.join("b")).unwrap();
+        symlink(workspace.join("self"), workspace.join("self")).unwrap();
+
+        assert!(symlink_escape(&workspace.join("a").join("file.txt"), workspace).is_err());
+        assert!(symlink_escape(&workspace.join("self"), workspace).is_err());
+        assert!(ensure_contained(&workspace.join("b"), workspace, true).is_err());
+    }
+}
@@ -5,8 +5,9 @@
 use crate::util::errors::*;
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 /// Storage level
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -22,7 +23,8 @@ pub enum StorageLevel {
 }
 
 /// Cache type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CacheType {
     /// AI model cache
     Models,
@@ -32,6 +34,58 @@ pub enum CacheType {
     Git,
     /// Code index cache
     Index,
+    /// Fetched web page cache
+    WebFetch,
+    /// Checkpoint snapshot cache
+    Checkpoints,
+    /// Vision-model image analysis results
+    ImageAnalysis,
+}
+
+impl CacheType {
+    pub const ALL: [CacheType; 7] = [
+        CacheType::Models,
+        CacheType::Embeddings,
+        CacheType::Git,
+        CacheType::Index,
+        CacheType::WebFetch,
+        CacheType::Checkpoints,
+        CacheType::ImageAnalysis,
+    ];
+
+    /// Directory name under the cache root
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            CacheType::Models => "models",
+            CacheType::Embeddings => "embeddings",
+            CacheType::Git => "git",
+            CacheType::Index => "index",
+            CacheType::WebFetch => "web_fetch",
+            CacheType::Checkpoints => "checkpoints",
+            CacheType::ImageAnalysis => "image_analysis",
+        }
+    }
+
+    /// Size limit used when the config does not set one
+    pub fn default_quota_mb(&self) -> u64 {
+        match self {
+            CacheType::Models => 2048,
+            CacheType::Embeddings => 512,
+            CacheType::Git => 256,
+            CacheType::Index => 512,
+            CacheType::WebFetch => 128,
+            CacheType::Checkpoints => 1024,
+            CacheType::ImageAnalysis => 64,
+        }
+    }
+}
+
+/// A component that currently stores data in a cache directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheConsumer {
+    pub name: String,
+    pub cache_type: CacheType,
+    pub dir: PathBuf,
 }
 
 /// Path manager
@@ -51,6 +105,11 @@ impl PathManager {
         Ok(Self { user_root })
     }
 
+    /// Create a path manager rooted at `user_root` instead of the system config directory
+    pub fn with_user_root(user_root: PathBuf) -> Self {
+        Self { user_root }
+    }
+
     /// Get user config root directory
     ///
     /// - Windows: %APPDATA%\BitFun\
@@ -177,11 +236,26 @@ impl PathManager {
         self.user_config_dir().join("app.json")
     }
 
+    /// Get encrypted secrets file path: ~/.config/bitfun/config/secrets.enc
+    pub fn secrets_file(&self) -> PathBuf {
+        self.user_config_dir().join("secrets.enc")
+    }
+
+    /// Get secrets encryption key file path: ~/.config/bitfun/config/secrets.key
+    pub fn secrets_key_file(&self) -> PathBuf {
+        self.user_config_dir().join("secrets.key")
+    }
+
     /// Get user agent directory: ~/.config/bitfun/agents/
     pub fn user_agents_dir(&self) -> PathBuf {
         self.user_root.join("agents")
     }
 
+    /// Get user prompt overrides directory: ~/.config/bitfun/prompts/
+    pub fn user_prompts_dir(&self) -> PathBuf {
+        self.user_root.join("prompts")
+    }
+
     /// Get agent templates directory: ~/.config/bitfun/agents/templates/
     pub fn agent_templates_dir(&self) -> PathBuf {
         self.user_agents_dir().join("templates")
@@ -231,13 +305,18 @@ impl PathManager {
 
     /// Get cache directory for a specific type
     pub fn cache_dir(&self, cache_type: CacheType) -> PathBuf {
-        let subdir = match cache_type {
-            CacheType::Models => "models",
-            CacheType::Embeddings => "embeddings",
-            CacheType::Git => "git",
-            CacheType::Index => "index",
-        };
-        self.cache_root().join(subdir)
+        self.cache_root().join(cache_type.dir_name())
+    }
+
+    /// Get a workspace's cache directory for a specific type:
+    /// ~/.config/bitfun/cache/{type}/workspaces/{workspace_hash}/
+    ///
+    /// Scoped caches stay under [`Self::cache_dir`], so the per-type quota
+    /// covers every workspace.
+    pub fn workspace_cache_dir(&self, workspace_path: &Path, cache_type: CacheType) -> PathBuf {
+        self.cache_dir(cache_type)
+            .join("workspaces")
+            .join(Self::workspace_hash(workspace_path))
     }
 
     /// Get user data directory: ~/.config/bitfun/data/
@@ -320,6 +399,11 @@ impl PathManager {
         self.user_data_dir().join("rules")
     }
 
+    /// Get tool trash directory: ~/.config/bitfun/data/trash/
+    pub fn tool_trash_dir(&self) -> PathBuf {
+        self.user_data_dir().join("trash")
+    }
+
     /// Get history directory: ~/.config/bitfun/data/history/
     pub fn history_dir(&self) -> PathBuf {
         self.user_data_dir().join("history")
@@ -340,6 +424,11 @@ impl PathManager {
         self.user_root.join("logs")
     }
 
+    /// Get crash reports directory: ~/.config/bitfun/crashes/
+    pub fn crashes_dir(&self) -> PathBuf {
+        self.user_root.join("crashes")
+    }
+
     /// Get backups directory: ~/.config/bitfun/backups/
     pub fn backups_dir(&self) -> PathBuf {
         self.user_root.join("backups")
@@ -350,6 +439,11 @@ impl PathManager {
         self.user_root.join("temp")
     }
 
+    /// Get cowork temporary workspaces directory: ~/.config/bitfun/temp/cowork/
+    pub fn cowork_temp_dir(&self) -> PathBuf {
+        self.temp_dir().join("cowork")
+    }
+
     /// Get project config root directory: {project}/.bitfun/
     pub fn project_root(&self, workspace_path: &Path) -> PathBuf {
         workspace_path.join(".bitfun")
@@ -370,6 +464,11 @@ impl PathManager {
         self.project_root(workspace_path).join("agents")
     }
 
+    /// Get project prompt overrides directory: {project}/.bitfun/prompts/
+    pub fn project_prompts_dir(&self, workspace_path: &Path) -> PathBuf {
+        self.project_root(workspace_path).join("prompts")
+    }
+
     /// Get project-level rules directory: {project}/.bitfun/rules/
     pub fn project_rules_dir(&self, workspace_path: &Path) -> PathBuf {
         self.project_root(workspace_path).join("rules")
@@ -430,6 +529,11 @@ impl PathManager {
         self.project_root(workspace_path).join("plans")
     }
 
+    /// Get project memory directory: {project}/.bitfun/memory/
+    pub fn project_memory_dir(&self, workspace_path: &Path) -> PathBuf {
+        self.project_root(workspace_path).join("memory")
+    }
+
     /// Compute a hash of the workspace path (used for directory names)
     pub fn workspace_hash(workspace_path: &Path) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -460,10 +564,6 @@ impl PathManager {
             self.agent_templates_dir(),
             self.workspaces_dir(),
             self.cache_root(),
-            self.cache_dir(CacheType::Models),
-            self.cache_dir(CacheType::Embeddings),
-            self.cache_dir(CacheType::Git),
-            self.cache_dir(CacheType::Index),
             self.user_data_dir(),
             self.user_cron_dir(),
             self.user_rules_dir(),
@@ -476,7 +576,10 @@ impl PathManager {
             self.temp_dir(),
         ];
 
-        for dir in dirs {
+        for dir in dirs
+            .into_iter()
+            .chain(CacheType::ALL.iter().map(|t| self.cache_dir(*t)))
+        {
             self.ensure_dir(&dir).await?;
         }
 
@@ -613,9 +716,49 @@ pub fn try_get_path_manager_arc() -> BitFunResult<Arc<PathManager>> {
     }
 }
 
+/// Cache consumers currently registered, keyed by name
+static CACHE_CONSUMERS: OnceLock<RwLock<HashMap<String, CacheConsumer>>> = OnceLock::new();
+
+fn cache_consumers_map() -> &'static RwLock<HashMap<String, CacheConsumer>> {
+    CACHE_CONSUMERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register a component storing data in a cache directory; registering the
+/// same name again replaces the entry
+pub fn register_cache_consumer(name: &str, cache_type: CacheType, dir: PathBuf) {
+    let consumer = CacheConsumer {
+        name: name.to_string(),
+        cache_type,
+        dir,
+    };
+    match cache_consumers_map().write() {
+        Ok(mut consumers) => {
+            consumers.insert(name.to_string(), consumer);
+        }
+        Err(e) => error!("Cache consumer registry poisoned: {}", e),
+    }
+}
+
+/// Remove a cache consumer registered with [`register_cache_consumer`]
+pub fn unregister_cache_consumer(name: &str) {
+    if let Ok(mut consumers) = cache_consumers_map().write() {
+        consumers.remove(name);
+    }
+}
+
+/// Registered cache consumers, sorted by name
+pub fn cache_consumers() -> Vec<CacheConsumer> {
+    let mut consumers: Vec<CacheConsumer> = cache_consumers_map()
+        .read()
+        .map(|consumers| consumers.values().cloned().collect())
+        .unwrap_or_default();
+    consumers.sort_by(|a, b| a.name.cmp(&b.name));
+    consumers
+}
+
 #[cfg(test)]
 mod tests {
-    use super::PathManager;
+    use super::{CacheType, PathManager};
 
     #[test]
     fn assistant_workspace_paths_use_personal_assistant_subdir() {
@@ -671,4 +814,18 @@ mod tests {
         assert!(pm.is_local_assistant_workspace_path(&legacy.to_string_lossy()));
         assert!(!pm.is_local_assistant_workspace_path("/tmp/not-bitfun"));
     }
+
+    #[test]
+    fn workspace_cache_dirs_are_scoped_under_the_type_dir() {
+        let pm = PathManager::with_user_root(std::env::temp_dir().join("bitfun-pm-test"));
+        let a = pm.workspace_cache_dir(std::path::Path::new("/work/a"), CacheType::WebFetch);
+        let b = pm.workspace_cache_dir(std::path::Path::new("/work/b"), CacheType::WebFetch);
+
+        assert_ne!(a, b);
+        assert!(a.starts_with(pm.cache_dir(CacheType::WebFetch).join("workspaces")));
+        assert_eq!(
+            a,
+            pm.workspace_cache_dir(std::path::Path::new("/work/a"), CacheType::WebFetch)
+        );
+    }
 }
@@ -0,0 +1,250 @@
+//! Text encoding detection
+//!
+//! Files are decoded by byte order mark first, then as UTF-8 when the bytes
+//! are valid UTF-8, and otherwise by `chardetng`'s guess (legacy encodings
+//! such as Shift_JIS, GBK or windows-1252). Content that looks binary is not
+//! decoded at all. Text written back is encoded in the encoding it was read
+//! with, so round-tripping a legacy file does not silently convert it.
+
+use crate::util::errors::{BitFunError, BitFunResult};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// Bytes inspected by the binary check.
+const BINARY_SAMPLE_SIZE: usize = 512;
+
+/// Encoding of a text file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEncoding {
+    pub encoding: &'static Encoding,
+    /// The file starts with a byte order mark
+    pub bom: bool,
+}
+
+impl TextEncoding {
+    pub const UTF8: Self = Self {
+        encoding: UTF_8,
+        bom: false,
+    };
+
+    /// WHATWG name of the encoding, e.g. `UTF-8`, `Shift_JIS` or `windows-1252`
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    /// Encoding for a label such as `utf-8`, `latin1` or `sjis`; a `-bom`
+    /// suffix (`utf-8-bom`) asks for a byte order mark.
+    pub fn for_label(label: &str) -> Option<Self> {
+        let label = label.trim();
+        let (label, bom) = match label
+            .to_ascii_lowercase()
+            .strip_suffix("-bom")
+            .map(str::to_string)
+        {
+            Some(stripped) => (stripped, true),
+            None => (label.to_string(), false),
+        };
+        Encoding::for_label(label.as_bytes()).map(|encoding| Self { encoding, bom })
+    }
+
+    fn is_utf16(&self) -> bool {
+        self.encoding == UTF_16LE || self.encoding == UTF_16BE
+    }
+}
+
+/// Text decoded from a file.
+#[derive(Debug, Clone)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: TextEncoding,
+    /// Some bytes were invalid in `encoding` and were replaced with U+FFFD
+    pub lossy: bool,
+}
+
+/// Whether `bytes` look like binary content rather than text.
+///
+/// UTF-16 files with a byte order mark are text even though they contain
+/// NUL bytes.
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    if Encoding::for_bom(bytes).is_some() {
+        return false;
+    }
+    let sample = &bytes[..bytes.len().min(BINARY_SAMPLE_SIZE)];
+    if sample.is_empty() {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+
+    let control_count = sample
+        .iter()
+        .filter(|&&b| b < 32 && !matches!(b, b'\t' | b'\n' | b'\r'))
+        .count();
+    control_count as f64 / sample.len() as f64 > 0.1
+}
+
+/// Detects the encoding of `bytes`; `None` when they look binary.
+///
+/// `bytes` may be a prefix of the file; a multi-byte character cut off at the
+/// end does not count against UTF-8.
+pub fn detect_encoding(bytes: &[u8]) -> Option<TextEncoding> {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return Some(TextEncoding {
+            encoding,
+            bom: true,
+        });
+    }
+    if looks_binary(bytes) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => return Some(TextEncoding::UTF8),
+        Err(e) if e.error_len().is_none() => return Some(TextEncoding::UTF8),
+        Err(_) => {}
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    Some(TextEncoding {
+        encoding: detector.guess(None, true),
+        bom: false,
+    })
+}
+
+/// Decodes file content to UTF-8; `None` when it looks binary.
+pub fn decode_text(bytes: &[u8]) -> Option<DecodedText> {
+    let encoding = detect_encoding(bytes)?;
+    Some(decode_with(bytes, encoding))
+}
+
+/// Decodes file content known to be in `encoding`.
+pub fn decode_with(bytes: &[u8], encoding: TextEncoding) -> DecodedText {
+    let (text, lossy) = if encoding.bom {
+        encoding.encoding.decode_with_bom_removal(bytes)
+    } else {
+        encoding.encoding.decode_without_bom_handling(bytes)
+    };
+    DecodedText {
+        text: text.into_owned(),
+        encoding,
+        lossy,
+    }
+}
+
+/// Encodes `text` for writing in `encoding`.
+///
+/// Fails when `text` has characters the encoding cannot represent, instead of
+/// writing substitutes.
+pub fn encode_text(text: &str, encoding: TextEncoding) -> BitFunResult<Vec<u8>> {
+    // Text that still carries its BOM must not end up with two
+    let text = match encoding.bom {
+        true => text.strip_prefix('\u{FEFF}').unwrap_or(text),
+        false => text,
+    };
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    if encoding.is_utf16() {
+        let little_endian = encoding.encoding == UTF_16LE;
+        if encoding.bom {
+            bytes.extend_from_slice(if little_endian {
+                &[0xFF, 0xFE]
+            } else {
+                &[0xFE, 0xFF]
+            });
+        }
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&if little_endian {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            });
+        }
+        return Ok(bytes);
+    }
+
+    if encoding.encoding == UTF_8 {
+        if encoding.bom {
+            bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+        }
+        bytes.extend_from_slice(text.as_bytes());
+        return Ok(bytes);
+    }
+
+    let (encoded, _, unmappable) = encoding.encoding.encode(text);
+    if unmappable {
+        return Err(BitFunError::validation(format!(
+            "Content has characters that cannot be written as {}; write it with another encoding",
+            encoding.name()
+        )));
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_bom_utf8_and_legacy_encodings() {
+        assert_eq!(detect_encoding(b"plain ascii"), Some(TextEncoding::UTF8));
+        assert_eq!(
+            detect_encoding("h\u{e9}llo".as_bytes()),
+            Some(TextEncoding::UTF8)
+        );
+
+        let with_bom = detect_encoding(b"\xEF\xBB\xBFhi").unwrap();
+        assert_eq!((with_bom.name(), with_bom.bom), ("UTF-8", true));
+
+        let utf16 = decode_text(b"\xFF\xFEh\0i\0").unwrap();
+        assert_eq!(utf16.text, "hi");
+        assert_eq!(utf16.encoding.name(), "UTF-16LE");
+
+        let (sjis, _, _) = encoding_rs::SHIFT_JIS.encode("日本語のテキストファイルです。");
+        let decoded = decode_text(&sjis).unwrap();
+        assert_eq!(decoded.encoding.name(), "Shift_JIS");
+        assert_eq!(decoded.text, "日本語のテキストファイルです。");
+        assert!(!decoded.lossy);
+
+        let latin = decode_text(b"Caf\xE9 cr\xE8me br\xFBl\xE9e").unwrap();
+        assert_eq!(latin.encoding.name(), "windows-1252");
+        assert_eq!(latin.text, "Café crème brûlée");
+    }
+
+    #[test]
+    fn refuses_binary_content() {
+        assert!(decode_text(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").is_none());
+        assert!(looks_binary(&[0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x00]));
+        assert!(!looks_binary(b"line one\r\n\tline two\n"));
+    }
+
+    #[test]
+    fn round_trips_and_rejects_unmappable_characters() {
+        for label in [
+            "utf-8",
+            "utf-8-bom",
+            "utf-16le-bom",
+            "utf-16be",
+            "shift_jis",
+        ] {
+            let encoding = TextEncoding::for_label(label).unwrap();
+            let bytes = encode_text("テスト text", encoding).unwrap();
+            assert_eq!(
+                decode_with(&bytes, encoding).text,
+                "テスト text",
+                "{}",
+                label
+            );
+        }
+
+        let utf8_bom = TextEncoding::for_label("utf-8-bom").unwrap();
+        assert_eq!(
+            encode_text("\u{FEFF}a", utf8_bom).unwrap(),
+            b"\xEF\xBB\xBFa"
+        );
+
+        let latin1 = TextEncoding::for_label("latin1").unwrap();
+        assert_eq!(encode_text("café", latin1).unwrap(), b"caf\xE9");
+        assert!(encode_text("テスト", latin1).is_err());
+    }
+}
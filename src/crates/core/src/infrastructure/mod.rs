@@ -1,11 +1,14 @@
 //! Infrastructure module
 //!
-//! Provides low-level services: AI clients, storage, event system
+//! Provides low-level services: AI clients, storage, secrets, event system
 
 pub mod ai;
+pub mod crash_report;
 pub mod debug_log;
 pub mod events;
 pub mod filesystem;
+pub mod offline;
+pub mod secrets;
 pub mod storage;
 
 pub use ai::AIClient;
@@ -0,0 +1,298 @@
+//! Offline mode
+//!
+//! With `app.offline_mode` on, or after repeated DNS failures, features that
+//! need the internet step aside instead of timing out: network tools are left
+//! out of agent toolsets, remote MCP servers are disconnected and shown as
+//! offline, and AI requests fail fast unless the model is served on this
+//! machine. Turning the flag off (or the network answering again) re-enables
+//! everything without a restart.
+
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
+use crate::util::errors::BitFunError;
+use log::{debug, info, warn};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Tools that only work with internet access
+pub const NETWORK_TOOLS: &[&str] = &["WebSearch", "WebFetch"];
+
+/// Consecutive DNS failures after which the network counts as unreachable
+const DNS_FAILURE_THRESHOLD: u32 = 3;
+
+/// While offline was detected, one request is let through this often to
+/// notice that the network is back
+const RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Why offline mode is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineReason {
+    /// `app.offline_mode` is set
+    Configured,
+    /// Host names stopped resolving
+    DnsFailures,
+}
+
+impl OfflineReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfflineReason::Configured => "configured",
+            OfflineReason::DnsFailures => "dns_failures",
+        }
+    }
+}
+
+/// Payload of `BackendEvent::OfflineModeChanged`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineModeInfo {
+    pub offline: bool,
+    /// `None` when back online
+    pub reason: Option<OfflineReason>,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Default)]
+struct OfflineState {
+    configured: bool,
+    dns_failures: u32,
+    /// Last DNS failure once the threshold was reached
+    detected_at: Option<Instant>,
+}
+
+impl OfflineState {
+    fn reason(&self) -> Option<OfflineReason> {
+        if self.configured {
+            Some(OfflineReason::Configured)
+        } else if self.detected_at.is_some() {
+            Some(OfflineReason::DnsFailures)
+        } else {
+            None
+        }
+    }
+
+    fn record_dns_failure(&mut self, now: Instant) {
+        self.dns_failures = self.dns_failures.saturating_add(1);
+        if self.dns_failures >= DNS_FAILURE_THRESHOLD {
+            self.detected_at = Some(now);
+        }
+    }
+
+    fn record_reachable(&mut self) {
+        self.dns_failures = 0;
+        self.detected_at = None;
+    }
+
+    /// Error for a request to a remote endpoint, or `None` when it may go out
+    fn blocked(&self, now: Instant) -> Option<String> {
+        if self.configured {
+            return Some("offline mode is on".to_string());
+        }
+        let detected_at = self.detected_at?;
+        let retry_in = RECHECK_INTERVAL
+            .checked_sub(now.duration_since(detected_at))
+            .filter(|retry_in| !retry_in.is_zero())?;
+        Some(format!(
+            "the network looks unreachable after repeated DNS failures; retrying in {}s",
+            retry_in.as_secs().max(1)
+        ))
+    }
+}
+
+static STATE: LazyLock<Mutex<OfflineState>> = LazyLock::new(Default::default);
+
+static STATUS: LazyLock<watch::Sender<Option<OfflineReason>>> =
+    LazyLock::new(|| watch::channel(None).0);
+
+fn update(change: impl FnOnce(&mut OfflineState)) {
+    let reason = {
+        let mut state = STATE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        change(&mut state);
+        state.reason()
+    };
+    if !STATUS.send_if_modified(|current| std::mem::replace(current, reason) != reason) {
+        return;
+    }
+
+    match reason {
+        Some(reason) => warn!("Offline mode on: reason={}", reason.as_str()),
+        None => info!("Offline mode off"),
+    }
+    let info = OfflineModeInfo {
+        offline: reason.is_some(),
+        reason,
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+    };
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(async move {
+            if let Err(e) = emit_global_event(BackendEvent::OfflineModeChanged(info)).await {
+                debug!("Failed to emit offline mode event: {}", e);
+            }
+        });
+    }
+}
+
+/// Applies `app.offline_mode`; called by the config manager on load and on every change
+pub fn apply_config(offline_mode: bool) {
+    update(|state| {
+        state.configured = offline_mode;
+        if !offline_mode {
+            // Turning the flag off is a request to try the network again
+            state.record_reachable();
+        }
+    });
+}
+
+/// Why offline mode is on, `None` when online
+pub fn status() -> Option<OfflineReason> {
+    *STATUS.borrow()
+}
+
+pub fn is_offline() -> bool {
+    status().is_some()
+}
+
+/// Notified with the new status whenever offline mode turns on or off
+pub fn subscribe() -> watch::Receiver<Option<OfflineReason>> {
+    STATUS.subscribe()
+}
+
+pub fn is_network_tool(tool: &str) -> bool {
+    NETWORK_TOOLS.contains(&tool)
+}
+
+/// Whether `url` points at this machine (loopback address or `localhost`)
+pub fn is_local_endpoint(url: &str) -> bool {
+    let Ok(url) = Url::parse(url.trim()) else {
+        return false;
+    };
+    match url.host_str() {
+        Some(host) => {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            host.eq_ignore_ascii_case("localhost")
+                || host.to_ascii_lowercase().ends_with(".localhost")
+                || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+        }
+        None => false,
+    }
+}
+
+/// Fails fast for a request to `url` while offline; endpoints on this machine always pass
+pub fn check_endpoint(url: &str) -> Result<(), BitFunError> {
+    if is_local_endpoint(url) {
+        return Ok(());
+    }
+    let blocked = STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .blocked(Instant::now());
+    match blocked {
+        Some(reason) => Err(BitFunError::Offline(format!(
+            "{} cannot be reached: {}",
+            host_of(url),
+            reason
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.trim().to_string())
+}
+
+/// Counts a failed request towards detecting that the network is gone
+pub fn record_request_error(error: &reqwest::Error) {
+    let mut text = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        text.push_str(": ");
+        text.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    if is_dns_failure(&text) {
+        update(|state| state.record_dns_failure(Instant::now()));
+    }
+}
+
+/// Records that a remote endpoint answered, ending detected offline mode
+pub fn record_reachable() {
+    update(OfflineState::record_reachable);
+}
+
+fn is_dns_failure(message: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "dns error",
+        "failed to lookup address",
+        "name or service not known",
+        "nodename nor servname",
+        "temporary failure in name resolution",
+        "no such host is known",
+    ];
+    let message = message.to_lowercase();
+    KEYWORDS.iter().any(|keyword| message.contains(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_local_endpoints() {
+        for url in [
+            "http://localhost:11434/v1",
+            "http://127.0.0.1:8080",
+            "http://[::1]:1234/v1",
+            "https://model.localhost/api",
+        ] {
+            assert!(is_local_endpoint(url), "{}", url);
+        }
+        for url in [
+            "https://api.openai.com/v1",
+            "http://192.168.1.20:11434",
+            "not a url",
+        ] {
+            assert!(!is_local_endpoint(url), "{}", url);
+        }
+    }
+
+    #[test]
+    fn detects_offline_after_repeated_dns_failures() {
+        let start = Instant::now();
+        let mut state = OfflineState::default();
+        state.record_dns_failure(start);
+        state.record_dns_failure(start);
+        assert_eq!(state.reason(), None);
+        assert!(state.blocked(start).is_none());
+
+        state.record_dns_failure(start);
+        assert_eq!(state.reason(), Some(OfflineReason::DnsFailures));
+        assert!(state.blocked(start + Duration::from_secs(5)).is_some());
+        // A request is let through once the recheck interval has passed
+        assert!(state.blocked(start + RECHECK_INTERVAL).is_none());
+
+        state.record_reachable();
+        assert_eq!(state.reason(), None);
+
+        state.configured = true;
+        assert_eq!(state.reason(), Some(OfflineReason::Configured));
+        assert!(state.blocked(start + RECHECK_INTERVAL).is_some());
+    }
+
+    #[test]
+    fn classifies_dns_failures() {
+        assert!(is_dns_failure(
+            "error sending request for url (https://api.example.com/v1): client error (Connect): dns error: failed to lookup address information: nodename nor servname provided, or not known"
+        ));
+        assert!(!is_dns_failure(
+            "error sending request: client error (Connect): tcp connect error: Connection refused"
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+//! Encrypted-file backend, used when no OS keyring is available.
+//!
+//! All secrets are kept as one AES-256-GCM encrypted JSON map. The key is a
+//! random 32-byte file next to it, readable only by the user, so secrets stay
+//! out of config files that get copied, synced or attached to bug reports.
+
+use super::SecretsStore;
+use crate::util::errors::*;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const FILE_VERSION: u32 = 1;
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+
+/// On-disk layout of the secrets file.
+#[derive(Serialize, Deserialize)]
+struct EncryptedSecrets {
+    version: u32,
+    nonce: String,
+    data: String,
+}
+
+/// Secrets stored in an encrypted file.
+pub struct EncryptedFileStore {
+    data_file: PathBuf,
+    key_file: PathBuf,
+    /// Serializes read-modify-write cycles on the file.
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(data_file: PathBuf, key_file: PathBuf) -> Self {
+        Self {
+            data_file,
+            key_file,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn cipher(&self) -> BitFunResult<Aes256Gcm> {
+        let key = match std::fs::read(&self.key_file) {
+            Ok(key) => key,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut key = vec![0u8; KEY_SIZE];
+                OsRng.fill_bytes(&mut key);
+                write_private(&self.key_file, &key)?;
+                key
+            }
+            Err(e) => {
+                return Err(BitFunError::io(format!(
+                    "Failed to read secrets key {}: {}",
+                    self.key_file.display(),
+                    e
+                )))
+            }
+        };
+        Aes256Gcm::new_from_slice(&key).map_err(|_| {
+            BitFunError::service(format!(
+                "Invalid secrets key {}: expected {} bytes",
+                self.key_file.display(),
+                KEY_SIZE
+            ))
+        })
+    }
+
+    fn read_all(&self) -> BitFunResult<BTreeMap<String, String>> {
+        let content = match std::fs::read_to_string(&self.data_file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => {
+                return Err(BitFunError::io(format!(
+                    "Failed to read secrets file {}: {}",
+                    self.data_file.display(),
+                    e
+                )))
+            }
+        };
+
+        let file: EncryptedSecrets = serde_json::from_str(&content)?;
+        if file.version != FILE_VERSION {
+            return Err(BitFunError::service(format!(
+                "Unsupported secrets file version {}",
+                file.version
+            )));
+        }
+        let nonce = decode(&file.nonce)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(BitFunError::service("Corrupt secrets file: bad nonce"));
+        }
+        let plaintext = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(&nonce), decode(&file.data)?.as_ref())
+            .map_err(|_| {
+                BitFunError::service(format!(
+                    "Failed to decrypt secrets file {}",
+                    self.data_file.display()
+                ))
+            })?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn write_all(&self, secrets: &BTreeMap<String, String>) -> BitFunResult<()> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                serde_json::to_vec(secrets)?.as_ref(),
+            )
+            .map_err(|_| BitFunError::service("Failed to encrypt secrets"))?;
+
+        let file = EncryptedSecrets {
+            version: FILE_VERSION,
+            nonce: BASE64.encode(nonce),
+            data: BASE64.encode(ciphertext),
+        };
+        write_private(&self.data_file, &serde_json::to_vec_pretty(&file)?)
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, String>)) -> BitFunResult<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut secrets = self.read_all()?;
+        change(&mut secrets);
+        self.write_all(&secrets)
+    }
+}
+
+impl SecretsStore for EncryptedFileStore {
+    fn backend_name(&self) -> &'static str {
+        "encrypted-file"
+    }
+
+    fn get(&self, name: &str) -> BitFunResult<Option<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.read_all()?.remove(name))
+    }
+
+    fn set(&self, name: &str, value: &str) -> BitFunResult<()> {
+        self.update(|secrets| {
+            secrets.insert(name.to_string(), value.to_string());
+        })
+    }
+
+    fn delete(&self, name: &str) -> BitFunResult<()> {
+        self.update(|secrets| {
+            secrets.remove(name);
+        })
+    }
+}
+
+fn decode(value: &str) -> BitFunResult<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| BitFunError::service(format!("Corrupt secrets file: {}", e)))
+}
+
+/// Writes `path` atomically with permissions restricted to the current user.
+fn write_private(path: &Path, content: &[u8]) -> BitFunResult<()> {
+    let io_error =
+        |e: std::io::Error| BitFunError::io(format!("Failed to write {}: {}", path.display(), e));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, content).map_err(io_error)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600)).map_err(io_error)?;
+    }
+    std::fs::rename(&tmp, path).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrips_secrets_through_the_encrypted_file() {
+        let dir = std::env::temp_dir().join(format!("bitfun-secrets-{}", uuid::Uuid::new_v4()));
+        let data_file = dir.join("secrets.enc");
+        let store = EncryptedFileStore::new(data_file.clone(), dir.join("secrets.key"));
+
+        assert_eq!(store.get("openai").unwrap(), None);
+        store.set("openai", "sk-test-123").unwrap();
+        store.set("github", "ghp_456").unwrap();
+        assert_eq!(store.get("openai").unwrap().as_deref(), Some("sk-test-123"));
+        assert!(!std::fs::read_to_string(&data_file)
+            .unwrap()
+            .contains("sk-test-123"));
+
+        store.delete("openai").unwrap();
+        assert_eq!(store.get("openai").unwrap(), None);
+        assert_eq!(store.get("github").unwrap().as_deref(), Some("ghp_456"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,65 @@
+//! OS keyring backend: macOS Keychain, Windows Credential Manager or Secret Service.
+
+use super::SecretsStore;
+use crate::util::errors::*;
+use keyring::Entry;
+use log::debug;
+
+/// Keyring service the secrets are filed under.
+const KEYRING_SERVICE: &str = "BitFun";
+/// Entry looked up to check whether a keyring is reachable.
+const PROBE_ENTRY: &str = "__bitfun_probe__";
+
+/// Secrets stored in the OS keyring, one entry per secret.
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// Returns whether a keyring can be reached (e.g. a Secret Service daemon is running).
+    pub fn is_available() -> bool {
+        match Entry::new(KEYRING_SERVICE, PROBE_ENTRY).and_then(|entry| entry.get_password()) {
+            Ok(_) | Err(keyring::Error::NoEntry) => true,
+            Err(e) => {
+                debug!("OS keyring unavailable: {}", e);
+                false
+            }
+        }
+    }
+
+    fn entry(name: &str) -> BitFunResult<Entry> {
+        Entry::new(KEYRING_SERVICE, name).map_err(|e| keyring_error("open", name, e))
+    }
+}
+
+impl SecretsStore for KeyringStore {
+    fn backend_name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn get(&self, name: &str) -> BitFunResult<Option<String>> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keyring_error("read", name, e)),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> BitFunResult<()> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| keyring_error("write", name, e))
+    }
+
+    fn delete(&self, name: &str) -> BitFunResult<()> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keyring_error("delete", name, e)),
+        }
+    }
+}
+
+fn keyring_error(action: &str, name: &str, error: keyring::Error) -> BitFunError {
+    BitFunError::service(format!(
+        "Failed to {} secret '{}' in OS keyring: {}",
+        action, name, error
+    ))
+}
@@ -0,0 +1,156 @@
+//! Secrets storage
+//!
+//! Credentials such as provider API keys and MCP headers are kept out of the
+//! config files: the config holds a reference like `keyring:openai_api_key`
+//! and the value lives in the OS keyring (macOS Keychain, Windows Credential
+//! Manager, Secret Service). Where no keyring is available, secrets go to an
+//! AES-256-GCM encrypted file in the user config directory.
+//!
+//! References are resolved each time a request is made, so a rotated secret
+//! takes effect on the next request without a restart.
+
+mod file_store;
+mod keyring_store;
+
+pub use file_store::EncryptedFileStore;
+pub use keyring_store::KeyringStore;
+
+use crate::infrastructure::try_get_path_manager_arc;
+use crate::util::errors::*;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// Prefix marking a config value as a reference into the secrets store.
+pub const SECRET_REF_PREFIX: &str = "keyring:";
+
+/// Backend holding secret values by name.
+///
+/// Synchronous, and lookups may block (a D-Bus round trip to the keyring, a
+/// file read and decrypt), so async code resolves references on the blocking
+/// pool with `tokio::task::spawn_blocking`.
+pub trait SecretsStore: Send + Sync {
+    /// Backend name for logs and the UI.
+    fn backend_name(&self) -> &'static str;
+    /// Returns the secret `name`, or `None` when it is not stored.
+    fn get(&self, name: &str) -> BitFunResult<Option<String>>;
+    fn set(&self, name: &str, value: &str) -> BitFunResult<()>;
+    /// Removes the secret `name`; removing a missing secret is not an error.
+    fn delete(&self, name: &str) -> BitFunResult<()>;
+}
+
+static SECRETS_STORE: OnceLock<Arc<dyn SecretsStore>> = OnceLock::new();
+
+/// Returns the process-wide secrets store, choosing the backend on first use.
+pub fn secrets_store() -> BitFunResult<Arc<dyn SecretsStore>> {
+    if let Some(store) = SECRETS_STORE.get() {
+        return Ok(store.clone());
+    }
+
+    let store: Arc<dyn SecretsStore> = if KeyringStore::is_available() {
+        Arc::new(KeyringStore)
+    } else {
+        let path_manager = try_get_path_manager_arc()?;
+        warn!("OS keyring unavailable, storing secrets in an encrypted file");
+        Arc::new(EncryptedFileStore::new(
+            path_manager.secrets_file(),
+            path_manager.secrets_key_file(),
+        ))
+    };
+    info!(
+        "Secrets store initialized: backend={}",
+        store.backend_name()
+    );
+    Ok(SECRETS_STORE.get_or_init(|| store).clone())
+}
+
+/// Returns the secret name when `value` is a `keyring:<name>` reference.
+pub fn parse_secret_ref(value: &str) -> Option<&str> {
+    value
+        .trim()
+        .strip_prefix(SECRET_REF_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Builds the config value referencing the secret `name`.
+pub fn secret_ref(name: &str) -> String {
+    format!("{}{}", SECRET_REF_PREFIX, name)
+}
+
+/// Checks that `name` is usable as a secret name: ASCII letters, digits, `.`, `_` and `-`.
+pub fn validate_secret_name(name: &str) -> BitFunResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(BitFunError::validation(format!(
+            "Invalid secret name '{}': use letters, digits, '.', '_' or '-'",
+            name
+        )))
+    }
+}
+
+/// Resolves `value` if it is a secret reference; other values are returned unchanged.
+pub fn resolve_secret(value: &str) -> BitFunResult<String> {
+    let Some(name) = parse_secret_ref(value) else {
+        return Ok(value.to_string());
+    };
+    secrets_store()?
+        .get(name)?
+        .ok_or_else(|| BitFunError::NotFound(format!("Secret '{}' is not stored", name)))
+}
+
+/// Like [`resolve_secret`], but logs failures and resolves to an empty string.
+///
+/// Used where a request is about to be sent: the request then fails with the
+/// provider's authentication error instead of leaking the reference.
+pub fn resolve_secret_or_empty(value: &str) -> String {
+    resolve_secret(value).unwrap_or_else(|e| {
+        warn!(
+            "Failed to resolve secret reference '{}': {}",
+            value.trim(),
+            e
+        );
+        String::new()
+    })
+}
+
+/// Resolves every secret reference among the values of `map`.
+pub fn resolve_secret_map(map: &HashMap<String, String>) -> HashMap<String, String> {
+    map.iter()
+        .map(|(key, value)| (key.clone(), resolve_secret_or_empty(value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_secret_references() {
+        assert_eq!(
+            parse_secret_ref("keyring:openai_api_key"),
+            Some("openai_api_key")
+        );
+        assert_eq!(parse_secret_ref(" keyring: github "), Some("github"));
+        assert_eq!(parse_secret_ref("keyring:"), None);
+        assert_eq!(parse_secret_ref("sk-plaintext"), None);
+        assert_eq!(secret_ref("a"), "keyring:a");
+    }
+
+    #[test]
+    fn plain_values_resolve_to_themselves() {
+        assert_eq!(resolve_secret("sk-plaintext").unwrap(), "sk-plaintext");
+    }
+
+    #[test]
+    fn validates_secret_names() {
+        assert!(validate_secret_name("ai.models.gpt-4o_api_key").is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name("a b").is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Storage backends behind PersistenceService
+//!
+//! Entries are JSON documents addressed by a key such as `jobs` or
+//! `sessions/<id>`. The file backend keeps one `<key>.json` file per entry
+//! (`<key>.json.zst` once it is large enough to be compressed);
+//! the SQLite backend keeps them in one database with indexed keys, which
+//! makes listing and searching thousands of entries cheap.
+
+use super::compression;
+use super::persistence::StorageOptions;
+use crate::util::errors::*;
+use async_trait::async_trait;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, LazyLock};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+/// Which backend stores persisted entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackendKind {
+    /// One JSON file per entry
+    #[default]
+    File,
+    /// A SQLite database (`storage.db`) in the storage directory
+    Sqlite,
+}
+
+static DEFAULT_BACKEND: AtomicU8 = AtomicU8::new(0);
+
+/// Backend used by PersistenceService instances created from now on
+pub fn set_default_storage_backend(kind: StorageBackendKind) {
+    DEFAULT_BACKEND.store(kind as u8, Ordering::Relaxed);
+}
+
+pub fn default_storage_backend() -> StorageBackendKind {
+    match DEFAULT_BACKEND.load(Ordering::Relaxed) {
+        1 => StorageBackendKind::Sqlite,
+        _ => StorageBackendKind::File,
+    }
+}
+
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    fn kind(&self) -> StorageBackendKind;
+
+    /// Stored JSON of an entry
+    async fn read(&self, key: &str) -> BitFunResult<Option<String>>;
+
+    async fn write(&self, key: &str, json: String, options: &StorageOptions) -> BitFunResult<()>;
+
+    /// Whether the entry existed
+    async fn delete(&self, key: &str) -> BitFunResult<bool>;
+
+    /// Keys starting with `prefix`, sorted
+    async fn list_keys(&self, prefix: &str) -> BitFunResult<Vec<String>>;
+
+    /// Keys starting with `prefix` whose stored JSON contains `needle`, sorted
+    async fn search(&self, prefix: &str, needle: &str) -> BitFunResult<Vec<String>>;
+}
+
+/// Global file lock map to prevent concurrent writes to the same file
+static FILE_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Get or create a lock for the specified file
+async fn get_file_lock(path: &Path) -> Arc<Mutex<()>> {
+    let mut locks = FILE_LOCKS.lock().await;
+    locks
+        .entry(path.to_path_buf())
+        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .clone()
+}
+
+/// Directory of file backups, skipped when listing entries
+const BACKUP_DIR: &str = "backups";
+
+/// One `<key>.json` file per entry
+pub struct FileBackend {
+    base_dir: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self { base_dir }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.base_dir.join(format!("{}.json", key))
+    }
+
+    /// Every entry key with the file that holds it
+    pub(crate) fn entries(base_dir: &Path) -> Vec<(String, PathBuf)> {
+        let mut entries = Vec::new();
+        let mut pending = vec![base_dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let Ok(read_dir) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    if dir != base_dir || entry.file_name() != BACKUP_DIR {
+                        pending.push(path);
+                    }
+                    continue;
+                }
+                let plain = if compression::is_compressed(&path) {
+                    path.with_extension("")
+                } else {
+                    path.clone()
+                };
+                if plain.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                    continue;
+                }
+                let stem = plain.with_extension("");
+                let Ok(relative) = stem.strip_prefix(base_dir) else {
+                    continue;
+                };
+                let key = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                entries.push((key, plain));
+            }
+        }
+        entries.sort();
+        // An entry caught between its plain and compressed forms is listed once,
+        // with whichever form is current
+        entries.dedup_by(|a, b| a.0 == b.0);
+        entries
+            .into_iter()
+            .filter_map(|(key, plain)| Some((key, compression::existing_form(&plain)?)))
+            .collect()
+    }
+
+    async fn create_backup(&self, file_path: &Path, max_backups: usize) -> BitFunResult<()> {
+        let backup_dir = self.base_dir.join(BACKUP_DIR);
+        if !backup_dir.exists() {
+            fs::create_dir_all(&backup_dir).await.map_err(|e| {
+                BitFunError::service(format!("Failed to create backup directory: {}", e))
+            })?;
+        }
+
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| BitFunError::service("Invalid file name".to_string()))?;
+
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let backup_name = format!("{}_{}", timestamp, file_name);
+        let backup_path = backup_dir.join(backup_name);
+
+        fs::copy(file_path, &backup_path)
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to create backup: {}", e)))?;
+
+        self.cleanup_old_backups(&backup_dir, file_name, max_backups)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn cleanup_old_backups(
+        &self,
+        backup_dir: &Path,
+        file_pattern: &str,
+        max_backups: usize,
+    ) -> BitFunResult<()> {
+        let mut backups = Vec::new();
+        let mut read_dir = fs::read_dir(backup_dir)
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to read backup directory: {}", e)))?;
+
+        while let Some(entry) = read_dir
+            .next_entry()
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to read backup entry: {}", e)))?
+        {
+            if let Some(file_name) = entry.file_name().to_str() {
+                if file_name.ends_with(file_pattern) {
+                    if let Ok(metadata) = entry.metadata().await {
+                        if let Ok(modified) = metadata.modified() {
+                            backups.push((entry.path(), modified));
+                        }
+                    }
+                }
+            }
+        }
+
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.1));
+
+        if backups.len() > max_backups {
+            for (path, _) in backups.into_iter().skip(max_backups) {
+                if let Err(e) = fs::remove_file(&path).await {
+                    warn!("Failed to remove old backup {:?}: {}", path, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageBackend for FileBackend {
+    fn kind(&self) -> StorageBackendKind {
+        StorageBackendKind::File
+    }
+
+    async fn read(&self, key: &str) -> BitFunResult<Option<String>> {
+        let Some(file_path) = compression::existing_form(&self.entry_path(key)) else {
+            return Ok(None);
+        };
+
+        let content = tokio::task::spawn_blocking(move || compression::read_to_string(&file_path))
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to read file: {}", e)))??;
+        Ok(Some(content))
+    }
+
+    /// Atomic write + file lock to prevent concurrency issues
+    async fn write(&self, key: &str, json: String, options: &StorageOptions) -> BitFunResult<()> {
+        let file_path = self.entry_path(key);
+
+        let lock = get_file_lock(&file_path).await;
+        let _guard = lock.lock().await;
+
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).await.map_err(|e| {
+                    BitFunError::service(format!("Failed to create directory {:?}: {}", parent, e))
+                })?;
+            }
+        }
+
+        if options.create_backup {
+            if let Some(existing) = compression::existing_form(&file_path) {
+                self.create_backup(&existing, options.backup_count).await?;
+            }
+        }
+
+        let compressed_path = compression::compressed_path(&file_path);
+        let (target_path, stale_path, data) =
+            if options.compress && compression::should_compress(json.len()) {
+                let packed = compression::compress(json.as_bytes())?;
+                compression::log_ratio(&file_path, json.len(), packed.len());
+                (compressed_path, file_path.clone(), packed)
+            } else {
+                (file_path.clone(), compressed_path, json.into_bytes())
+            };
+
+        // Use atomic writes: write to a temp file first, then rename to avoid corruption on interruption.
+        let temp_path = file_path.with_extension("json.tmp");
+
+        fs::write(&temp_path, &data)
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to write temp file: {}", e)))?;
+
+        fs::rename(&temp_path, &target_path).await.map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            BitFunError::service(format!("Failed to rename temp file: {}", e))
+        })?;
+
+        if stale_path.exists() {
+            if let Err(e) = fs::remove_file(&stale_path).await {
+                warn!("Failed to remove stale file {:?}: {}", stale_path, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> BitFunResult<bool> {
+        let json_path = self.entry_path(key);
+        let mut existed = false;
+
+        for path in [compression::compressed_path(&json_path), json_path] {
+            if path.exists() {
+                fs::remove_file(&path).await.map_err(|e| {
+                    BitFunError::service(format!("Failed to delete JSON file: {}", e))
+                })?;
+                existed = true;
+            }
+        }
+
+        Ok(existed)
+    }
+
+    async fn list_keys(&self, prefix: &str) -> BitFunResult<Vec<String>> {
+        let base_dir = self.base_dir.clone();
+        let prefix = prefix.to_string();
+        tokio::task::spawn_blocking(move || {
+            Self::entries(&base_dir)
+                .into_iter()
+                .map(|(key, _)| key)
+                .filter(|key| key.starts_with(&prefix))
+                .collect()
+        })
+        .await
+        .map_err(|e| BitFunError::service(format!("Failed to list entries: {}", e)))
+    }
+
+    async fn search(&self, prefix: &str, needle: &str) -> BitFunResult<Vec<String>> {
+        let base_dir = self.base_dir.clone();
+        let prefix = prefix.to_string();
+        let needle = needle.to_string();
+        tokio::task::spawn_blocking(move || {
+            Self::entries(&base_dir)
+                .into_iter()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .filter(|(_, path)| {
+                    compression::read_to_string(path)
+                        .map(|content| content.contains(&needle))
+                        .unwrap_or(false)
+                })
+                .map(|(key, _)| key)
+                .collect()
+        })
+        .await
+        .map_err(|e| BitFunError::service(format!("Failed to search entries: {}", e)))
+    }
+}
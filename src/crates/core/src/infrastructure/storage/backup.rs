@@ -0,0 +1,1010 @@
+//! Backup and restore of user data
+//!
+//! A backup is a `bitfun-backup-<timestamp>.tar.zst` archive in the backups
+//! directory. Its first entry is `manifest.json` (app version, file counts and
+//! SHA-256 hashes); the files of each category follow under `config/`,
+//! `skills/`, `sessions/<workspace hash>/` and `memory/<workspace hash>/`.
+//!
+//! Restoring validates the whole archive into staging directories next to the
+//! live ones before swapping them in, so a corrupted archive never leaves
+//! data half restored.
+
+use crate::infrastructure::filesystem::content_sha256_hex;
+use crate::infrastructure::PathManager;
+use crate::util::errors::*;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Archive layout version written by this build
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const BACKUP_PREFIX: &str = "bitfun-backup-";
+const BACKUP_EXTENSION: &str = ".tar.zst";
+const ZSTD_LEVEL: i32 = 3;
+const TAR_BLOCK_SIZE: usize = 512;
+
+/// Time between scheduler checks for a due backup
+const SCHEDULER_TICK: Duration = Duration::from_secs(60 * 60);
+/// Delay before the scheduler's first check, keeping startup free of disk scans
+const SCHEDULER_STARTUP_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// Kind of user data a backup can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupCategory {
+    /// App config and secrets: ~/.config/bitfun/config/
+    Config,
+    /// User skills
+    Skills,
+    /// Persisted sessions of each workspace: {project}/.bitfun/sessions/
+    Sessions,
+    /// Agent memory of each workspace: {project}/.bitfun/memory/
+    Memory,
+}
+
+impl BackupCategory {
+    pub const ALL: [BackupCategory; 4] = [
+        BackupCategory::Config,
+        BackupCategory::Skills,
+        BackupCategory::Sessions,
+        BackupCategory::Memory,
+    ];
+
+    fn archive_dir(&self) -> &'static str {
+        match self {
+            BackupCategory::Config => "config",
+            BackupCategory::Skills => "skills",
+            BackupCategory::Sessions => "sessions",
+            BackupCategory::Memory => "memory",
+        }
+    }
+
+    /// Whether the category is stored per workspace
+    fn per_workspace(&self) -> bool {
+        matches!(self, BackupCategory::Sessions | BackupCategory::Memory)
+    }
+}
+
+/// First entry of every backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    /// App version that wrote the backup
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    pub categories: Vec<BackupCategory>,
+    /// Workspaces whose sessions and memory are included, by workspace hash
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, PathBuf>,
+    /// Number of files per category
+    pub counts: BTreeMap<BackupCategory, usize>,
+    pub files: Vec<BackupFile>,
+}
+
+/// A file in a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// Path inside the archive, `/`-separated
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// A backup archive found in the backups directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    pub categories: Vec<BackupCategory>,
+    pub counts: BTreeMap<BackupCategory, usize>,
+    pub workspaces: Vec<PathBuf>,
+}
+
+impl BackupInfo {
+    fn new(path: PathBuf, manifest: BackupManifest) -> Self {
+        Self {
+            size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path,
+            app_version: manifest.app_version,
+            created_at: manifest.created_at,
+            categories: manifest.categories,
+            counts: manifest.counts,
+            workspaces: manifest.workspaces.into_values().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreOptions {
+    /// Categories to restore; empty restores everything in the backup
+    #[serde(default)]
+    pub categories: Vec<BackupCategory>,
+    /// Restore even if the local data was written by a newer app version
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub categories: Vec<BackupCategory>,
+    pub files: usize,
+    /// Directories replaced by their backed up content
+    pub restored_dirs: Vec<PathBuf>,
+    /// Workspaces in the backup that do not exist on this machine
+    pub skipped_workspaces: Vec<PathBuf>,
+}
+
+/// Scheduled backups, configured in `app.storage.backup`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSchedule {
+    pub enabled: bool,
+    pub interval_hours: u64,
+    /// Backups kept; older ones are deleted after each scheduled backup
+    pub keep_last: usize,
+    pub categories: Vec<BackupCategory>,
+}
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            keep_last: 7,
+            categories: BackupCategory::ALL.to_vec(),
+        }
+    }
+}
+
+/// A directory whose files a backup holds
+struct BackupRoot {
+    category: BackupCategory,
+    /// `/`-separated directory inside the archive
+    archive_dir: String,
+    dir: PathBuf,
+}
+
+/// A directory being restored, and where its new content is staged
+struct RestoreTarget {
+    archive_dir: String,
+    dir: PathBuf,
+    staging: PathBuf,
+    files: usize,
+}
+
+pub struct BackupService {
+    path_manager: PathManager,
+}
+
+impl BackupService {
+    pub fn new(path_manager: PathManager) -> Self {
+        Self { path_manager }
+    }
+
+    /// Writes a backup of `categories`; sessions and memory are taken from `workspaces`
+    pub async fn create(
+        &self,
+        categories: &[BackupCategory],
+        workspaces: &[PathBuf],
+    ) -> BitFunResult<BackupInfo> {
+        let path_manager = self.path_manager.clone();
+        let categories = categories.to_vec();
+        let workspaces = workspaces.to_vec();
+        tokio::task::spawn_blocking(move || create_backup(&path_manager, &categories, &workspaces))
+            .await
+            .map_err(|e| BitFunError::service(format!("Backup task failed: {}", e)))?
+    }
+
+    /// Backups in the backups directory, newest first
+    pub async fn list(&self) -> BitFunResult<Vec<BackupInfo>> {
+        let backups_dir = self.path_manager.backups_dir();
+        tokio::task::spawn_blocking(move || list_backups(&backups_dir))
+            .await
+            .map_err(|e| BitFunError::service(format!("Failed to list backups: {}", e)))?
+    }
+
+    pub async fn restore(
+        &self,
+        archive: &Path,
+        options: &RestoreOptions,
+    ) -> BitFunResult<RestoreReport> {
+        let path_manager = self.path_manager.clone();
+        let archive = archive.to_path_buf();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || restore_backup(&path_manager, &archive, &options))
+            .await
+            .map_err(|e| BitFunError::service(format!("Restore task failed: {}", e)))?
+    }
+
+    /// Deletes all but the newest `keep_last` backups, returning the deleted archives
+    pub async fn prune(&self, keep_last: usize) -> BitFunResult<Vec<PathBuf>> {
+        let mut removed = Vec::new();
+        for backup in self.list().await?.into_iter().skip(keep_last) {
+            match tokio::fs::remove_file(&backup.path).await {
+                Ok(()) => removed.push(backup.path),
+                Err(e) => warn!("Failed to remove old backup {:?}: {}", backup.path, e),
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Reads the manifest of a backup archive without unpacking it
+pub fn read_manifest(archive: &Path) -> BitFunResult<BackupManifest> {
+    let mut archive_reader = open_archive(archive)?;
+    let mut entries = archive_reader
+        .entries()
+        .map_err(|e| corrupted(archive, e))?;
+    read_manifest_entry(&mut entries, archive)
+}
+
+fn backup_roots(
+    path_manager: &PathManager,
+    categories: &[BackupCategory],
+    workspaces: &BTreeMap<String, PathBuf>,
+) -> Vec<BackupRoot> {
+    let mut roots = Vec::new();
+    for category in categories {
+        match category {
+            BackupCategory::Config | BackupCategory::Skills => roots.push(BackupRoot {
+                category: *category,
+                archive_dir: category.archive_dir().to_string(),
+                dir: match category {
+                    BackupCategory::Config => path_manager.user_config_dir(),
+                    _ => path_manager.user_skills_dir(),
+                },
+            }),
+            BackupCategory::Sessions | BackupCategory::Memory => {
+                for (hash, workspace) in workspaces {
+                    roots.push(BackupRoot {
+                        category: *category,
+                        archive_dir: format!("{}/{}", category.archive_dir(), hash),
+                        dir: match category {
+                            BackupCategory::Sessions => {
+                                path_manager.project_sessions_dir(workspace)
+                            }
+                            _ => path_manager.project_memory_dir(workspace),
+                        },
+                    });
+                }
+            }
+        }
+    }
+    roots
+}
+
+fn create_backup(
+    path_manager: &PathManager,
+    categories: &[BackupCategory],
+    workspaces: &[PathBuf],
+) -> BitFunResult<BackupInfo> {
+    let mut categories = categories.to_vec();
+    categories.sort();
+    categories.dedup();
+    if categories.is_empty() {
+        return Err(BitFunError::validation("No backup categories selected"));
+    }
+
+    let workspaces: BTreeMap<String, PathBuf> = if categories.iter().any(|c| c.per_workspace()) {
+        workspaces
+            .iter()
+            .map(|workspace| (PathManager::workspace_hash(workspace), workspace.clone()))
+            .collect()
+    } else {
+        BTreeMap::new()
+    };
+
+    let backups_dir = path_manager.backups_dir();
+    std::fs::create_dir_all(&backups_dir)
+        .map_err(|e| BitFunError::io(format!("Failed to create backups directory: {}", e)))?;
+
+    let created_at = Utc::now();
+    let name = format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        created_at.format("%Y%m%d-%H%M%S-%3f"),
+        BACKUP_EXTENSION
+    );
+    let path = backups_dir.join(&name);
+    let files_part = backups_dir.join(format!(".{}.files", name));
+    let archive_part = backups_dir.join(format!(".{}.tmp", name));
+
+    let mut manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at,
+        counts: categories.iter().map(|category| (*category, 0)).collect(),
+        categories,
+        workspaces,
+        files: Vec::new(),
+    };
+    let roots = backup_roots(path_manager, &manifest.categories, &manifest.workspaces);
+
+    let result = (|| -> std::io::Result<()> {
+        // The files go into their own zstd frame first; the manifest needs their
+        // hashes, and is then written as the leading frame of the archive.
+        // Concatenated frames decompress as one stream.
+        let encoder = zstd::stream::write::Encoder::new(File::create(&files_part)?, ZSTD_LEVEL)?;
+        let mut builder = tar::Builder::new(encoder);
+        for root in &roots {
+            for (relative, file_path) in collect_files(&root.dir) {
+                let data = match std::fs::read(&file_path) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!("Skipping unreadable file {:?} in backup: {}", file_path, e);
+                        continue;
+                    }
+                };
+                let archive_path = format!("{}/{}", root.archive_dir, relative);
+                let mut header = tar::Header::new_gnu();
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(modified_secs(&file_path));
+                builder.append_data(&mut header, &archive_path, data.as_slice())?;
+
+                manifest.files.push(BackupFile {
+                    path: archive_path,
+                    size: data.len() as u64,
+                    sha256: content_sha256_hex(&data),
+                });
+                *manifest.counts.entry(root.category).or_default() += 1;
+            }
+        }
+        builder.into_inner()?.finish()?;
+
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        let mut archive = File::create(&archive_part)?;
+        let mut encoder = zstd::stream::write::Encoder::new(&mut archive, ZSTD_LEVEL)?;
+        write_tar_entry(&mut encoder, MANIFEST_FILE, &manifest_json)?;
+        encoder.finish()?;
+        std::io::copy(&mut File::open(&files_part)?, &mut archive)?;
+        archive.sync_all()?;
+        std::fs::rename(&archive_part, &path)
+    })();
+
+    let _ = std::fs::remove_file(&files_part);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&archive_part);
+        return Err(BitFunError::io(format!("Failed to write backup: {}", e)));
+    }
+
+    info!(
+        "Backup created: path={}, files={}",
+        path.display(),
+        manifest.files.len()
+    );
+    Ok(BackupInfo::new(path, manifest))
+}
+
+fn list_backups(backups_dir: &Path) -> BitFunResult<Vec<BackupInfo>> {
+    let Ok(read_dir) = std::fs::read_dir(backups_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut backups = Vec::new();
+    for entry in read_dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with(BACKUP_PREFIX) || !name.ends_with(BACKUP_EXTENSION) {
+            continue;
+        }
+        let path = entry.path();
+        match read_manifest(&path) {
+            Ok(manifest) => backups.push(BackupInfo::new(path, manifest)),
+            Err(e) => warn!("Ignoring unreadable backup {:?}: {}", path, e),
+        }
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+fn restore_backup(
+    path_manager: &PathManager,
+    archive: &Path,
+    options: &RestoreOptions,
+) -> BitFunResult<RestoreReport> {
+    let mut archive_reader = open_archive(archive)?;
+    let mut entries = archive_reader
+        .entries()
+        .map_err(|e| corrupted(archive, e))?;
+    let manifest = read_manifest_entry(&mut entries, archive)?;
+
+    if !options.force {
+        if let Some(local_version) = local_data_version(path_manager) {
+            if compare_versions(&local_version, &manifest.app_version) == Ordering::Greater {
+                return Err(BitFunError::conflict(format!(
+                    "The local data was written by BitFun {}, newer than the backup ({}); use force to restore anyway",
+                    local_version, manifest.app_version
+                )));
+            }
+        }
+    }
+
+    let categories = if options.categories.is_empty() {
+        manifest.categories.clone()
+    } else {
+        if let Some(missing) = options
+            .categories
+            .iter()
+            .find(|category| !manifest.categories.contains(category))
+        {
+            return Err(BitFunError::validation(format!(
+                "The backup does not contain {:?}",
+                missing
+            )));
+        }
+        options.categories.clone()
+    };
+
+    let mut report = RestoreReport {
+        categories: categories.clone(),
+        ..Default::default()
+    };
+    let mut workspaces = manifest.workspaces.clone();
+    workspaces.retain(|_, workspace| {
+        let exists = workspace.is_dir();
+        if !exists && categories.iter().any(|c| c.per_workspace()) {
+            report.skipped_workspaces.push(workspace.clone());
+        }
+        exists
+    });
+
+    let staging_id = uuid::Uuid::new_v4();
+    let mut targets: Vec<RestoreTarget> = backup_roots(path_manager, &categories, &workspaces)
+        .into_iter()
+        .map(|root| RestoreTarget {
+            staging: sibling(&root.dir, &format!("restore-{}", staging_id)),
+            archive_dir: root.archive_dir,
+            dir: root.dir,
+            files: 0,
+        })
+        .collect();
+
+    let staged = stage_files(&mut entries, &manifest, &mut targets, archive);
+    if let Err(e) = staged {
+        for target in &targets {
+            let _ = std::fs::remove_dir_all(&target.staging);
+        }
+        return Err(e);
+    }
+
+    swap_in(&targets)?;
+
+    report.files = targets.iter().map(|target| target.files).sum();
+    report.restored_dirs = targets.into_iter().map(|target| target.dir).collect();
+    info!(
+        "Backup restored: path={}, files={}",
+        archive.display(),
+        report.files
+    );
+    Ok(report)
+}
+
+/// Unpacks the files of `targets` into their staging directories, checking each
+/// against the manifest; every file the manifest lists for them must be present
+fn stage_files<R: Read>(
+    entries: &mut tar::Entries<'_, R>,
+    manifest: &BackupManifest,
+    targets: &mut [RestoreTarget],
+    archive: &Path,
+) -> BitFunResult<()> {
+    for target in targets.iter() {
+        std::fs::create_dir_all(&target.staging).map_err(|e| {
+            BitFunError::io(format!(
+                "Failed to create staging directory {:?}: {}",
+                target.staging, e
+            ))
+        })?;
+    }
+
+    let expected: HashMap<&str, &BackupFile> = manifest
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file))
+        .collect();
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| corrupted(archive, e))?;
+        let entry_path = entry.path().map_err(|e| corrupted(archive, e))?;
+        let Some(archive_path) = normalized_archive_path(&entry_path) else {
+            return Err(corrupted(archive, "unsafe path in archive"));
+        };
+        let Some(file) = expected.get(archive_path.as_str()) else {
+            return Err(corrupted(
+                archive,
+                format!("{} is not listed in the manifest", archive_path),
+            ));
+        };
+        let Some(target) = targets.iter_mut().find(|target| {
+            archive_path
+                .strip_prefix(target.archive_dir.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+        }) else {
+            continue;
+        };
+
+        let mut data = Vec::with_capacity(file.size as usize);
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| corrupted(archive, e))?;
+        if data.len() as u64 != file.size || content_sha256_hex(&data) != file.sha256 {
+            return Err(corrupted(
+                archive,
+                format!("{} does not match its hash", archive_path),
+            ));
+        }
+
+        let relative = &archive_path[target.archive_dir.len() + 1..];
+        let destination = target.staging.join(relative);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| BitFunError::io(format!("Failed to stage {}: {}", relative, e)))?;
+        }
+        std::fs::write(&destination, &data)
+            .map_err(|e| BitFunError::io(format!("Failed to stage {}: {}", relative, e)))?;
+        target.files += 1;
+    }
+
+    let listed = manifest
+        .files
+        .iter()
+        .filter(|file| {
+            targets.iter().any(|target| {
+                file.path
+                    .strip_prefix(target.archive_dir.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+        .count();
+    let staged: usize = targets.iter().map(|target| target.files).sum();
+    if staged != listed {
+        return Err(corrupted(
+            archive,
+            format!("{} of {} files are missing", listed - staged, listed),
+        ));
+    }
+    Ok(())
+}
+
+/// Replaces each target directory by its staged content. If any swap fails, the
+/// directories already swapped are put back.
+fn swap_in(targets: &[RestoreTarget]) -> BitFunResult<()> {
+    let mut swapped: Vec<(&RestoreTarget, Option<PathBuf>)> = Vec::new();
+    let mut failure = None;
+
+    for target in targets {
+        let previous = sibling(&target.dir, &format!("previous-{}", uuid::Uuid::new_v4()));
+        let had_previous = target.dir.exists();
+        if had_previous {
+            if let Err(e) = std::fs::rename(&target.dir, &previous) {
+                failure = Some(format!("Failed to move aside {:?}: {}", target.dir, e));
+                break;
+            }
+        }
+        if let Err(e) = std::fs::rename(&target.staging, &target.dir) {
+            if had_previous {
+                let _ = std::fs::rename(&previous, &target.dir);
+            }
+            failure = Some(format!("Failed to restore {:?}: {}", target.dir, e));
+            break;
+        }
+        swapped.push((target, had_previous.then_some(previous)));
+    }
+
+    if let Some(message) = failure {
+        for (target, previous) in swapped.into_iter().rev() {
+            let _ = std::fs::rename(&target.dir, &target.staging);
+            if let Some(previous) = previous {
+                let _ = std::fs::rename(&previous, &target.dir);
+            }
+        }
+        for target in targets {
+            let _ = std::fs::remove_dir_all(&target.staging);
+        }
+        return Err(BitFunError::io(message));
+    }
+
+    for (_, previous) in swapped {
+        if let Some(previous) = previous {
+            if let Err(e) = std::fs::remove_dir_all(&previous) {
+                warn!("Failed to remove replaced data {:?}: {}", previous, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Periodically creates a backup when the newest one is older than the configured
+/// interval, then prunes old backups. `source` supplies the schedule and the
+/// workspaces whose sessions and memory are backed up.
+pub fn spawn_backup_scheduler<F, Fut>(
+    path_manager: PathManager,
+    source: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = (BackupSchedule, Vec<PathBuf>)> + Send,
+{
+    tokio::spawn(async move {
+        tokio::time::sleep(SCHEDULER_STARTUP_DELAY).await;
+        let service = BackupService::new(path_manager);
+
+        loop {
+            let (schedule, workspaces) = source().await;
+            if schedule.enabled {
+                let interval = chrono::Duration::hours(schedule.interval_hours.max(1) as i64);
+                let due = match service.list().await {
+                    Ok(backups) => backups
+                        .first()
+                        .is_none_or(|newest| Utc::now() - newest.created_at >= interval),
+                    Err(e) => {
+                        warn!("Failed to list backups: {}", e);
+                        false
+                    }
+                };
+                if due {
+                    match service.create(&schedule.categories, &workspaces).await {
+                        Ok(_) => {
+                            if let Err(e) = service.prune(schedule.keep_last.max(1)).await {
+                                warn!("Failed to prune backups: {}", e);
+                            }
+                        }
+                        Err(e) => warn!("Scheduled backup failed: {}", e),
+                    }
+                }
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    })
+}
+
+fn open_archive(path: &Path) -> BitFunResult<tar::Archive<impl Read>> {
+    let file = File::open(path)
+        .map_err(|e| BitFunError::io(format!("Failed to open backup {:?}: {}", path, e)))?;
+    let decoder =
+        zstd::stream::read::Decoder::new(BufReader::new(file)).map_err(|e| corrupted(path, e))?;
+    Ok(tar::Archive::new(decoder))
+}
+
+fn read_manifest_entry<R: Read>(
+    entries: &mut tar::Entries<'_, R>,
+    archive: &Path,
+) -> BitFunResult<BackupManifest> {
+    let entry = entries
+        .next()
+        .ok_or_else(|| corrupted(archive, "the archive is empty"))?
+        .map_err(|e| corrupted(archive, e))?;
+    if entry.path().ok().as_deref() != Some(Path::new(MANIFEST_FILE)) {
+        return Err(BitFunError::validation(format!(
+            "{:?} is not a BitFun backup",
+            archive
+        )));
+    }
+
+    let manifest: BackupManifest =
+        serde_json::from_reader(entry).map_err(|e| corrupted(archive, e))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(BitFunError::validation(format!(
+            "The backup was written by a newer version ({}, format version {}); update BitFun to restore it",
+            manifest.app_version, manifest.format_version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Writes one tar entry without the end-of-archive marker `tar::Builder` adds
+fn write_tar_entry<W: Write>(writer: &mut W, name: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_path(name)?;
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    writer.write_all(header.as_bytes())?;
+    writer.write_all(data)?;
+    let padding = (TAR_BLOCK_SIZE - data.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+    writer.write_all(&vec![0u8; padding])
+}
+
+/// Regular files under `dir` with their `/`-separated relative paths, sorted;
+/// symlinks are skipped
+fn collect_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                if let Some(relative) = path
+                    .strip_prefix(dir)
+                    .ok()
+                    .and_then(normalized_archive_path)
+                {
+                    files.push((relative, path));
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// `/`-separated form of a relative path; `None` if it is absolute or leaves
+/// its root through `..`
+fn normalized_archive_path(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?.to_string()),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// `<parent>/.<name>.<suffix>`, on the same file system as `dir`
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    dir.with_file_name(format!(".{}.{}", name, suffix))
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// App version that last wrote the local config
+fn local_data_version(path_manager: &PathManager) -> Option<String> {
+    let content = std::fs::read_to_string(path_manager.app_config_file()).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&content).ok()?;
+    config.get("version")?.as_str().map(str::to_string)
+}
+
+/// Compares `major.minor.patch` versions; pre-release and build suffixes are ignored
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |version: &str| -> [u64; 3] {
+        let mut parts = [0; 3];
+        let core = version.split(['-', '+']).next().unwrap_or_default();
+        for (slot, part) in parts.iter_mut().zip(core.split('.')) {
+            *slot = part.trim().parse().unwrap_or(0);
+        }
+        parts
+    };
+    parts(a).cmp(&parts(b))
+}
+
+fn corrupted(archive: &Path, error: impl std::fmt::Display) -> BitFunError {
+    BitFunError::io(format!(
+        "Backup {:?} is corrupted or truncated: {}",
+        archive, error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    struct Fixture {
+        root: PathBuf,
+        workspace: PathBuf,
+        service: BackupService,
+        path_manager: PathManager,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("bitfun-backup-{}", uuid::Uuid::new_v4()));
+            let workspace = root.join("workspace");
+            std::fs::create_dir_all(&workspace).unwrap();
+            let path_manager = PathManager::with_user_root(root.join("user"));
+            write(
+                &path_manager.app_config_file(),
+                &format!(
+                    r#"{{"version": "{}", "app": {{}}}}"#,
+                    env!("CARGO_PKG_VERSION")
+                ),
+            );
+            write(
+                &path_manager
+                    .project_sessions_dir(&workspace)
+                    .join("s1/metadata.json"),
+                "{\"title\": \"first\"}",
+            );
+            write(
+                &path_manager
+                    .project_memory_dir(&workspace)
+                    .join("memory.md"),
+                "# Memory Index\n- remembered\n",
+            );
+            Self {
+                service: BackupService::new(path_manager.clone()),
+                root,
+                workspace,
+                path_manager,
+            }
+        }
+
+        async fn backup(&self) -> BackupInfo {
+            self.service
+                .create(
+                    &[
+                        BackupCategory::Config,
+                        BackupCategory::Sessions,
+                        BackupCategory::Memory,
+                    ],
+                    std::slice::from_ref(&self.workspace),
+                )
+                .await
+                .unwrap()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[tokio::test]
+    async fn restores_a_backup_over_changed_data() {
+        let fixture = Fixture::new();
+        let backup = fixture.backup().await;
+        assert_eq!(backup.counts[&BackupCategory::Config], 1);
+        assert_eq!(backup.counts[&BackupCategory::Sessions], 1);
+        assert_eq!(backup.counts[&BackupCategory::Memory], 1);
+        assert_eq!(read_manifest(&backup.path).unwrap().files.len(), 3);
+
+        let sessions_dir = fixture
+            .path_manager
+            .project_sessions_dir(&fixture.workspace);
+        write(
+            &sessions_dir.join("s1/metadata.json"),
+            "{\"title\": \"changed\"}",
+        );
+        write(&sessions_dir.join("s2/metadata.json"), "{}");
+        std::fs::remove_dir_all(fixture.path_manager.project_memory_dir(&fixture.workspace))
+            .unwrap();
+
+        let report = fixture
+            .service
+            .restore(&backup.path, &RestoreOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(report.files, 3);
+        assert_eq!(
+            std::fs::read_to_string(sessions_dir.join("s1/metadata.json")).unwrap(),
+            "{\"title\": \"first\"}"
+        );
+        assert!(!sessions_dir.join("s2").exists());
+        assert!(fixture
+            .path_manager
+            .project_memory_dir(&fixture.workspace)
+            .join("memory.md")
+            .exists());
+        let leftovers = std::fs::read_dir(fixture.workspace.join(".bitfun"))
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_truncated_archives_without_touching_data() {
+        let fixture = Fixture::new();
+        let backup = fixture.backup().await;
+        let sessions_dir = fixture
+            .path_manager
+            .project_sessions_dir(&fixture.workspace);
+        write(
+            &sessions_dir.join("s1/metadata.json"),
+            "{\"title\": \"changed\"}",
+        );
+
+        let content = std::fs::read(&backup.path).unwrap();
+        std::fs::write(&backup.path, &content[..content.len() - 40]).unwrap();
+        assert!(fixture
+            .service
+            .restore(&backup.path, &RestoreOptions::default())
+            .await
+            .is_err());
+        assert_eq!(
+            std::fs::read_to_string(sessions_dir.join("s1/metadata.json")).unwrap(),
+            "{\"title\": \"changed\"}"
+        );
+        let leftovers = std::fs::read_dir(fixture.workspace.join(".bitfun"))
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn refuses_to_restore_over_newer_data_without_force() {
+        let fixture = Fixture::new();
+        let backup = fixture.backup().await;
+        write(
+            &fixture.path_manager.app_config_file(),
+            r#"{"version": "999.0.0", "app": {}}"#,
+        );
+
+        let options = RestoreOptions {
+            categories: vec![BackupCategory::Memory],
+            force: false,
+        };
+        assert!(fixture
+            .service
+            .restore(&backup.path, &options)
+            .await
+            .is_err());
+
+        let options = RestoreOptions {
+            force: true,
+            ..options
+        };
+        let report = fixture
+            .service
+            .restore(&backup.path, &options)
+            .await
+            .unwrap();
+        assert_eq!(report.files, 1);
+    }
+
+    #[tokio::test]
+    async fn lists_newest_first_and_prunes() {
+        let fixture = Fixture::new();
+        let first = fixture.backup().await;
+        let second = fixture.backup().await;
+        std::fs::write(fixture.path_manager.backups_dir().join("notes.txt"), "x").unwrap();
+
+        let listed = fixture.service.list().await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].path, second.path);
+
+        let removed = fixture.service.prune(1).await.unwrap();
+        assert_eq!(removed, vec![first.path]);
+    }
+
+    #[test]
+    fn compares_versions_numerically() {
+        assert_eq!(compare_versions("0.10.0", "0.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2.0-beta", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+    }
+}
@@ -1,34 +1,155 @@
 //! Automatic cleanup module
 //!
-//! Provides storage cleanup policies and scheduling
+//! Storage cleanup is driven by policies: each names a target directory, a
+//! maximum file age, a maximum total size and how often it runs. A single
+//! scheduler task runs the policies that are due and reports every run.
 
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
+use crate::infrastructure::filesystem::{cache_consumers, CacheType};
 use crate::infrastructure::PathManager;
 use crate::util::errors::*;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use tokio::fs;
 
+/// Time between scheduler checks for due policies
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+/// Delay before the scheduler's first check, keeping startup free of disk scans
+const SCHEDULER_STARTUP_DELAY: Duration = Duration::from_secs(5 * 60);
+/// Backend event carrying the result of one policy run
+pub const CLEANUP_EVENT: &str = "storage-cleanup-completed";
+
+/// What a cleanup policy cleans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupTarget {
+    /// Temporary files: ~/.config/bitfun/temp/
+    Temp,
+    /// Application log sessions: ~/.config/bitfun/logs/
+    Logs,
+    /// Files tools moved aside instead of deleting: ~/.config/bitfun/data/trash/
+    ToolTrash,
+    /// Scratch workspaces of cowork sessions: ~/.config/bitfun/temp/cowork/
+    CoworkTemp,
+    /// Fetched web pages: ~/.config/bitfun/cache/web_fetch/
+    WebCache,
+    /// Sessions of known workspaces: {project}/.bitfun/sessions/{session_id}/, removed
+    /// whole once nothing in them has changed within the age limit
+    SessionArchives,
+    /// Cached image analysis results: ~/.config/bitfun/cache/image_analysis/
+    ImageAnalysisCache,
+}
+
+impl CleanupTarget {
+    /// Directory whose files the target cleans; `None` for session archives,
+    /// which live in each workspace
+    pub fn dir(&self, path_manager: &PathManager) -> Option<PathBuf> {
+        match self {
+            CleanupTarget::Temp => Some(path_manager.temp_dir()),
+            CleanupTarget::Logs => Some(path_manager.logs_dir()),
+            CleanupTarget::ToolTrash => Some(path_manager.tool_trash_dir()),
+            CleanupTarget::CoworkTemp => Some(path_manager.cowork_temp_dir()),
+            CleanupTarget::WebCache => Some(path_manager.cache_dir(CacheType::WebFetch)),
+            CleanupTarget::SessionArchives => None,
+            CleanupTarget::ImageAnalysisCache => {
+                Some(path_manager.cache_dir(CacheType::ImageAnalysis))
+            }
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CleanupTarget::Temp => "Temporary Files",
+            CleanupTarget::Logs => "Old Logs",
+            CleanupTarget::ToolTrash => "Tool Trash",
+            CleanupTarget::CoworkTemp => "Cowork Temp Workspaces",
+            CleanupTarget::WebCache => "Web Cache",
+            CleanupTarget::SessionArchives => "Expired Sessions",
+            CleanupTarget::ImageAnalysisCache => "Image Analysis Cache",
+        }
+    }
+}
+
+/// One cleanup rule: files in `target` older than `max_age_days` go, then the
+/// oldest files beyond `max_total_size_mb`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetCleanupPolicy {
+    pub target: CleanupTarget,
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+    #[serde(default)]
+    pub max_total_size_mb: Option<u64>,
+    /// Hours between scheduled runs; `None` runs only on demand
+    #[serde(default)]
+    pub interval_hours: Option<u64>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl TargetCleanupPolicy {
+    fn new(
+        target: CleanupTarget,
+        max_age_days: Option<u64>,
+        max_total_size_mb: Option<u64>,
+        interval_hours: Option<u64>,
+    ) -> Self {
+        Self {
+            target,
+            max_age_days,
+            max_total_size_mb,
+            interval_hours,
+            enabled: true,
+        }
+    }
+
+    /// Policies used when the config does not list any
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(CleanupTarget::Temp, Some(7), None, Some(24)),
+            Self::new(CleanupTarget::Logs, Some(30), Some(512), Some(24)),
+            Self::new(CleanupTarget::ToolTrash, Some(30), Some(1024), Some(24)),
+            Self::new(CleanupTarget::CoworkTemp, Some(1), None, Some(6)),
+            Self::new(CleanupTarget::WebCache, Some(7), None, Some(24)),
+            Self::new(CleanupTarget::SessionArchives, Some(90), None, Some(24)),
+            Self::new(CleanupTarget::ImageAnalysisCache, Some(30), None, Some(24)),
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CleanupPolicy {
-    pub temp_retention_days: u64,
-    pub log_retention_days: u64,
-    pub session_retention_days: u64,
-    pub max_cache_size_mb: u64,
-    pub backup_retention_count: usize,
     pub auto_cleanup_enabled: bool,
+    #[serde(default = "TargetCleanupPolicy::defaults")]
+    pub policies: Vec<TargetCleanupPolicy>,
+    /// Size limit per cache type; types not listed use `CacheType::default_quota_mb`
+    #[serde(default)]
+    pub cache_quotas_mb: HashMap<CacheType, u64>,
+}
+
+impl CleanupPolicy {
+    pub fn cache_quota_bytes(&self, cache_type: CacheType) -> u64 {
+        self.cache_quotas_mb
+            .get(&cache_type)
+            .copied()
+            .unwrap_or_else(|| cache_type.default_quota_mb())
+            * 1_048_576
+    }
 }
 
 impl Default for CleanupPolicy {
     fn default() -> Self {
         Self {
-            temp_retention_days: 7,
-            log_retention_days: 30,
-            session_retention_days: 90,
-            max_cache_size_mb: 1024,
-            backup_retention_count: 10,
             auto_cleanup_enabled: true,
+            policies: TargetCleanupPolicy::defaults(),
+            cache_quotas_mb: HashMap::new(),
         }
     }
 }
@@ -39,6 +160,12 @@ pub struct CleanupResult {
     pub directories_deleted: usize,
     pub bytes_freed: u64,
     pub categories: Vec<CleanupCategory>,
+    /// Nothing was deleted; the counts and paths are what would be removed
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Files removed (or that would be removed) by a single policy run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<PathBuf>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,9 +175,36 @@ pub struct CleanupCategory {
     pub bytes_freed: u64,
 }
 
+/// Result of running one policy
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyCleanupReport {
+    pub target: CleanupTarget,
+    pub result: CleanupResult,
+}
+
+/// Current size of one cache type against its quota
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheUsage {
+    pub cache_type: CacheType,
+    pub dir: PathBuf,
+    pub size_bytes: u64,
+    pub file_count: usize,
+    pub quota_bytes: u64,
+    /// Names of the registered consumers using this cache
+    pub consumers: Vec<String>,
+}
+
+/// Last use of a cache file: access time, or modification time where the
+/// filesystem does not record access
+fn last_used(metadata: &std::fs::Metadata) -> std::io::Result<SystemTime> {
+    metadata.accessed().or_else(|_| metadata.modified())
+}
+
 pub struct CleanupService {
     path_manager: PathManager,
     policy: CleanupPolicy,
+    /// Workspaces whose sessions the session archive policy cleans
+    workspaces: Vec<PathBuf>,
 }
 
 impl CleanupService {
@@ -58,9 +212,17 @@ impl CleanupService {
         Self {
             path_manager,
             policy,
+            workspaces: Vec::new(),
         }
     }
 
+    /// Sets the workspaces whose sessions the session archive policy cleans
+    pub fn with_workspaces(mut self, workspaces: Vec<PathBuf>) -> Self {
+        self.workspaces = workspaces;
+        self
+    }
+
+    /// Runs every enabled policy and the cache quotas now
     pub async fn cleanup_all(&self) -> BitFunResult<CleanupResult> {
         let mut result = CleanupResult::default();
 
@@ -70,20 +232,15 @@ impl CleanupService {
 
         info!("Starting cleanup process");
 
-        if let Ok(temp_result) = self.cleanup_temp_files().await {
-            result.merge(temp_result, "Temporary Files");
-        }
-
-        if let Ok(log_result) = self.cleanup_old_logs().await {
-            result.merge(log_result, "Old Logs");
-        }
-
-        if let Ok(session_result) = self.cleanup_old_sessions().await {
-            result.merge(session_result, "Expired Sessions");
+        for policy in self.policy.policies.iter().filter(|p| p.enabled) {
+            match self.run_policy(policy, false).await {
+                Ok(policy_result) => result.merge(policy_result, policy.target.label()),
+                Err(e) => warn!("Cleanup of {:?} failed: {}", policy.target, e),
+            }
         }
 
-        if let Ok(cache_result) = self.cleanup_oversized_cache().await {
-            result.merge(cache_result, "Oversized Cache");
+        if let Ok(quota_result) = self.enforce_cache_quotas().await {
+            result.merge(quota_result, "Cache Quotas");
         }
 
         info!(
@@ -96,121 +253,269 @@ impl CleanupService {
         Ok(result)
     }
 
-    async fn cleanup_temp_files(&self) -> BitFunResult<CleanupResult> {
-        let temp_dir = self.path_manager.temp_dir();
-        let retention = Duration::from_secs(self.policy.temp_retention_days * 24 * 3600);
-
-        self.cleanup_old_files(&temp_dir, retention).await
+    /// Runs every enabled policy now, regardless of its schedule; with
+    /// `dry_run` nothing is deleted and the reports list what would be
+    pub async fn run_policies(&self, dry_run: bool) -> Vec<PolicyCleanupReport> {
+        let mut reports = Vec::new();
+        for policy in self.policy.policies.iter().filter(|p| p.enabled) {
+            if let Some(report) = self.run_and_report(policy, dry_run).await {
+                reports.push(report);
+            }
+        }
+        reports
     }
 
-    async fn cleanup_old_logs(&self) -> BitFunResult<CleanupResult> {
-        let logs_dir = self.path_manager.logs_dir();
-        let retention = Duration::from_secs(self.policy.log_retention_days * 24 * 3600);
-
-        self.cleanup_old_files(&logs_dir, retention).await
+    /// Enabled, scheduled policies whose interval has passed since their last run
+    pub fn due_policies(
+        &self,
+        last_runs: &HashMap<CleanupTarget, SystemTime>,
+        now: SystemTime,
+    ) -> Vec<&TargetCleanupPolicy> {
+        self.policy
+            .policies
+            .iter()
+            .filter(|policy| policy.enabled)
+            .filter(
+                |policy| match (policy.interval_hours, last_runs.get(&policy.target)) {
+                    (None, _) => false,
+                    (Some(_), None) => true,
+                    (Some(hours), Some(last_run)) => now
+                        .duration_since(*last_run)
+                        .map(|elapsed| elapsed >= Duration::from_secs(hours * 3600))
+                        .unwrap_or(false),
+                },
+            )
+            .collect()
     }
 
-    async fn cleanup_old_sessions(&self) -> BitFunResult<CleanupResult> {
-        let mut result = CleanupResult::default();
-
-        let workspaces_dir = self.path_manager.workspaces_dir();
-
-        if !workspaces_dir.exists() {
+    /// Runs one policy; with `dry_run` the result lists what would be removed
+    pub async fn run_policy(
+        &self,
+        policy: &TargetCleanupPolicy,
+        dry_run: bool,
+    ) -> BitFunResult<CleanupResult> {
+        let mut result = CleanupResult {
+            dry_run,
+            ..Default::default()
+        };
+        let Some(dir) = policy.target.dir(&self.path_manager) else {
+            self.remove_expired_sessions(policy, &mut result).await?;
+            return Ok(result);
+        };
+        if !dir.exists() {
             return Ok(result);
         }
 
-        let retention = Duration::from_secs(self.policy.session_retention_days * 24 * 3600);
-
-        let mut read_dir = fs::read_dir(&workspaces_dir)
-            .await
-            .map_err(|e| BitFunError::service(format!("Failed to read workspaces: {}", e)))?;
-
-        while let Some(entry) = read_dir
-            .next_entry()
-            .await
-            .map_err(|e| BitFunError::service(format!("Failed to read workspace entry: {}", e)))?
-        {
-            if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
-                let session_result = self.cleanup_old_files(&entry.path(), retention).await?;
-                result.files_deleted += session_result.files_deleted;
-                result.directories_deleted += session_result.directories_deleted;
-                result.bytes_freed += session_result.bytes_freed;
+        let mut files = Vec::new();
+        self.collect_files_with_time(&dir, std::fs::Metadata::modified, &mut files)
+            .await?;
+        // Newest first: the newest files within the size limit are kept
+        files.sort_by_key(|file| std::cmp::Reverse(file.1));
+
+        let cutoff = policy.max_age_days.map(|days| {
+            SystemTime::now()
+                .checked_sub(Duration::from_secs(days * 24 * 3600))
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        });
+        let max_size = policy.max_total_size_mb.map(|mb| mb * 1_048_576);
+        let mut kept_size = 0u64;
+        let mut over_size = false;
+
+        for (path, modified, size) in files {
+            let too_old = cutoff.map(|cutoff| modified < cutoff).unwrap_or(false);
+            over_size = over_size
+                || max_size
+                    .map(|max_size| kept_size + size > max_size)
+                    .unwrap_or(false);
+            if !too_old && !over_size {
+                kept_size += size;
+                continue;
             }
+
+            if !dry_run {
+                if let Err(e) = fs::remove_file(&path).await {
+                    warn!("Failed to delete {:?}: {}", path, e);
+                    kept_size += size;
+                    continue;
+                }
+            }
+            result.files_deleted += 1;
+            result.bytes_freed += size;
+            result.removed.push(path);
+        }
+
+        if !dry_run {
+            self.remove_empty_dirs(&dir, &mut result).await?;
         }
 
         Ok(result)
     }
 
-    async fn cleanup_oversized_cache(&self) -> BitFunResult<CleanupResult> {
-        let cache_dir = self.path_manager.cache_root();
-        let max_size = self.policy.max_cache_size_mb * 1_048_576;
-
-        let current_size = Self::calculate_dir_size(&cache_dir).await?;
+    /// Removes whole session directories of the known workspaces that are past
+    /// the age limit, then the least recently changed beyond the size limit.
+    /// A session's age is that of its most recently modified file, so a
+    /// session still in use is never touched.
+    async fn remove_expired_sessions(
+        &self,
+        policy: &TargetCleanupPolicy,
+        result: &mut CleanupResult,
+    ) -> BitFunResult<()> {
+        // (session dir, last change, size, file count)
+        let mut sessions = Vec::new();
+        for workspace in &self.workspaces {
+            let sessions_dir = self.path_manager.project_sessions_dir(workspace);
+            let mut read_dir = match fs::read_dir(&sessions_dir).await {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            while let Some(entry) = read_dir
+                .next_entry()
+                .await
+                .map_err(|e| BitFunError::service(format!("Failed to read entry: {}", e)))?
+            {
+                let path = entry.path();
+                let metadata = match entry.metadata().await {
+                    Ok(m) if m.is_dir() => m,
+                    _ => continue,
+                };
 
-        if current_size <= max_size {
-            return Ok(CleanupResult::default());
+                let mut files = Vec::new();
+                self.collect_files_with_time(&path, std::fs::Metadata::modified, &mut files)
+                    .await?;
+                let last_change = files
+                    .iter()
+                    .map(|(_, modified, _)| *modified)
+                    .max()
+                    .or_else(|| metadata.modified().ok())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                let size = files.iter().map(|(_, _, size)| size).sum::<u64>();
+                sessions.push((path, last_change, size, files.len()));
+            }
         }
+        // Most recently changed first: those within the size limit are kept
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.1));
+
+        let cutoff = policy.max_age_days.map(|days| {
+            SystemTime::now()
+                .checked_sub(Duration::from_secs(days * 24 * 3600))
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        });
+        let max_size = policy.max_total_size_mb.map(|mb| mb * 1_048_576);
+        let mut kept_size = 0u64;
+        let mut over_size = false;
+
+        for (path, last_change, size, file_count) in sessions {
+            let too_old = cutoff.map(|cutoff| last_change < cutoff).unwrap_or(false);
+            over_size = over_size
+                || max_size
+                    .map(|max_size| kept_size + size > max_size)
+                    .unwrap_or(false);
+            if !too_old && !over_size {
+                kept_size += size;
+                continue;
+            }
 
-        debug!(
-            "Cache size {:.2} MB exceeds limit {:.2} MB, cleaning up",
-            current_size as f64 / 1_048_576.0,
-            max_size as f64 / 1_048_576.0
-        );
+            if !result.dry_run {
+                if let Err(e) = fs::remove_dir_all(&path).await {
+                    warn!("Failed to delete session {:?}: {}", path, e);
+                    kept_size += size;
+                    continue;
+                }
+            }
+            result.files_deleted += file_count;
+            result.directories_deleted += 1;
+            result.bytes_freed += size;
+            result.removed.push(path);
+        }
 
-        self.cleanup_by_size(&cache_dir, max_size).await
+        Ok(())
     }
 
-    async fn cleanup_old_files(
+    async fn run_and_report(
         &self,
-        dir: &Path,
-        retention: Duration,
-    ) -> BitFunResult<CleanupResult> {
-        let mut result = CleanupResult::default();
-
-        if !dir.exists() {
-            return Ok(result);
+        policy: &TargetCleanupPolicy,
+        dry_run: bool,
+    ) -> Option<PolicyCleanupReport> {
+        match self.run_policy(policy, dry_run).await {
+            Ok(result) => {
+                report_policy_run(policy.target, &result).await;
+                Some(PolicyCleanupReport {
+                    target: policy.target,
+                    result,
+                })
+            }
+            Err(e) => {
+                warn!("Cleanup of {:?} failed: {}", policy.target, e);
+                None
+            }
         }
+    }
 
-        let cutoff_time = SystemTime::now()
-            .checked_sub(retention)
-            .unwrap_or(SystemTime::UNIX_EPOCH);
-
-        self.cleanup_recursively(
-            dir,
-            |metadata| {
-                metadata
-                    .modified()
-                    .map(|time| time < cutoff_time)
-                    .unwrap_or(false)
-            },
-            &mut result,
-        )
-        .await?;
+    /// Usage of every cache type, for the storage settings
+    pub async fn cache_usage(&self) -> BitFunResult<Vec<CacheUsage>> {
+        let consumers = cache_consumers();
+        let mut usage = Vec::with_capacity(CacheType::ALL.len());
+
+        for cache_type in CacheType::ALL {
+            let dir = self.path_manager.cache_dir(cache_type);
+            let mut files = Vec::new();
+            self.collect_files_with_time(&dir, last_used, &mut files)
+                .await?;
+
+            usage.push(CacheUsage {
+                cache_type,
+                size_bytes: files.iter().map(|(_, _, size)| size).sum(),
+                file_count: files.len(),
+                quota_bytes: self.policy.cache_quota_bytes(cache_type),
+                consumers: consumers
+                    .iter()
+                    .filter(|consumer| consumer.cache_type == cache_type)
+                    .map(|consumer| consumer.name.clone())
+                    .collect(),
+                dir,
+            });
+        }
 
-        Ok(result)
+        Ok(usage)
     }
 
-    async fn cleanup_by_size(&self, dir: &Path, max_size: u64) -> BitFunResult<CleanupResult> {
+    /// Brings every cache type under its quota, evicting the least recently
+    /// used files first
+    pub async fn enforce_cache_quotas(&self) -> BitFunResult<CleanupResult> {
         let mut result = CleanupResult::default();
 
-        let mut files = Vec::new();
-        self.collect_files_with_time(dir, &mut files).await?;
+        for cache_type in CacheType::ALL {
+            let dir = self.path_manager.cache_dir(cache_type);
+            let quota = self.policy.cache_quota_bytes(cache_type);
 
-        files.sort_by(|a, b| b.1.cmp(&a.1));
-
-        let mut current_size = 0u64;
-
-        for (path, _, size) in files {
-            current_size += size;
+            let mut files = Vec::new();
+            self.collect_files_with_time(&dir, last_used, &mut files)
+                .await?;
+            let mut current_size: u64 = files.iter().map(|(_, _, size)| size).sum();
+            if current_size <= quota {
+                continue;
+            }
 
-            if current_size > max_size {
+            debug!(
+                "{:?} cache is {:.2} MB, over its {:.2} MB quota",
+                cache_type,
+                current_size as f64 / 1_048_576.0,
+                quota as f64 / 1_048_576.0
+            );
+
+            files.sort_by_key(|file| file.1);
+            for (path, _, size) in files {
+                if current_size <= quota {
+                    break;
+                }
                 match fs::remove_file(&path).await {
                     Ok(_) => {
+                        current_size -= size;
                         result.files_deleted += 1;
                         result.bytes_freed += size;
                     }
                     Err(e) => {
-                        warn!("Failed to delete {:?}: {}", path, e);
+                        warn!("Failed to evict {:?}: {}", path, e);
                     }
                 }
             }
@@ -219,15 +524,12 @@ impl CleanupService {
         Ok(result)
     }
 
-    fn cleanup_recursively<'a, F>(
+    /// Removes empty directories below `dir`, keeping `dir` itself
+    fn remove_empty_dirs<'a>(
         &'a self,
         dir: &'a Path,
-        should_delete: F,
         result: &'a mut CleanupResult,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = BitFunResult<()>> + Send + 'a>>
-    where
-        F: Fn(&std::fs::Metadata) -> bool + Copy + Send + 'a,
-    {
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = BitFunResult<()>> + Send + 'a>> {
         Box::pin(async move {
             let mut read_dir = match fs::read_dir(dir).await {
                 Ok(d) => d,
@@ -240,34 +542,18 @@ impl CleanupService {
                 .map_err(|e| BitFunError::service(format!("Failed to read entry: {}", e)))?
             {
                 let path = entry.path();
-                let metadata = match entry.metadata().await {
-                    Ok(m) => m,
-                    Err(_) => continue,
-                };
+                if !entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+                    continue;
+                }
 
-                if metadata.is_dir() {
-                    self.cleanup_recursively(&path, should_delete, result)
-                        .await?;
-
-                    if Self::is_empty_dir(&path).await {
-                        match fs::remove_dir(&path).await {
-                            Ok(_) => {
-                                result.directories_deleted += 1;
-                            }
-                            Err(e) => {
-                                warn!("Failed to delete empty dir {:?}: {}", path, e);
-                            }
-                        }
-                    }
-                } else if should_delete(&metadata) {
-                    let size = metadata.len();
-                    match fs::remove_file(&path).await {
+                self.remove_empty_dirs(&path, result).await?;
+                if Self::is_empty_dir(&path).await {
+                    match fs::remove_dir(&path).await {
                         Ok(_) => {
-                            result.files_deleted += 1;
-                            result.bytes_freed += size;
+                            result.directories_deleted += 1;
                         }
                         Err(e) => {
-                            warn!("Failed to delete {:?}: {}", path, e);
+                            warn!("Failed to delete empty dir {:?}: {}", path, e);
                         }
                     }
                 }
@@ -280,6 +566,7 @@ impl CleanupService {
     fn collect_files_with_time<'a>(
         &'a self,
         dir: &'a Path,
+        time_of: fn(&std::fs::Metadata) -> std::io::Result<SystemTime>,
         files: &'a mut Vec<(PathBuf, SystemTime, u64)>,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = BitFunResult<()>> + Send + 'a>> {
         Box::pin(async move {
@@ -300,9 +587,9 @@ impl CleanupService {
                 };
 
                 if metadata.is_dir() {
-                    self.collect_files_with_time(&path, files).await?;
-                } else if let Ok(modified) = metadata.modified() {
-                    files.push((path, modified, metadata.len()));
+                    self.collect_files_with_time(&path, time_of, files).await?;
+                } else if let Ok(time) = time_of(&metadata) {
+                    files.push((path, time, metadata.len()));
                 }
             }
 
@@ -310,38 +597,6 @@ impl CleanupService {
         })
     }
 
-    fn calculate_dir_size(
-        dir: &Path,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = BitFunResult<u64>> + Send + '_>> {
-        Box::pin(async move {
-            let mut total = 0u64;
-
-            let mut read_dir = match fs::read_dir(dir).await {
-                Ok(d) => d,
-                Err(_) => return Ok(0),
-            };
-
-            while let Some(entry) = read_dir
-                .next_entry()
-                .await
-                .map_err(|e| BitFunError::service(format!("Failed to read entry: {}", e)))?
-            {
-                let metadata = match entry.metadata().await {
-                    Ok(m) => m,
-                    Err(_) => continue,
-                };
-
-                if metadata.is_dir() {
-                    total += Self::calculate_dir_size(&entry.path()).await?;
-                } else {
-                    total += metadata.len();
-                }
-            }
-
-            Ok(total)
-        })
-    }
-
     async fn is_empty_dir(dir: &Path) -> bool {
         match fs::read_dir(dir).await {
             Ok(mut read_dir) => read_dir.next_entry().await.ok().flatten().is_none(),
@@ -366,6 +621,65 @@ impl CleanupResult {
     }
 }
 
+/// Logs a policy run and emits it as a backend event
+async fn report_policy_run(target: CleanupTarget, result: &CleanupResult) {
+    info!(
+        "Cleanup of {:?}{}: {} files, {} dirs, {:.2} MB",
+        target,
+        if result.dry_run { " (dry run)" } else { "" },
+        result.files_deleted,
+        result.directories_deleted,
+        result.bytes_freed as f64 / 1_048_576.0
+    );
+
+    let payload = serde_json::json!({
+        "target": target,
+        "dryRun": result.dry_run,
+        "filesDeleted": result.files_deleted,
+        "directoriesDeleted": result.directories_deleted,
+        "bytesFreed": result.bytes_freed,
+    });
+    if let Err(e) = emit_global_event(BackendEvent::Custom {
+        event_name: CLEANUP_EVENT.to_string(),
+        payload,
+    })
+    .await
+    {
+        debug!("Failed to emit cleanup event: {}", e);
+    }
+}
+
+/// Starts the task that runs due cleanup policies. `source` is asked for the
+/// current policy and the known workspaces on every check, so config changes
+/// apply without a restart.
+pub fn spawn_cleanup_scheduler<F, Fut>(
+    path_manager: PathManager,
+    source: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = (CleanupPolicy, Vec<PathBuf>)> + Send,
+{
+    tokio::spawn(async move {
+        tokio::time::sleep(SCHEDULER_STARTUP_DELAY).await;
+        let mut last_runs: HashMap<CleanupTarget, SystemTime> = HashMap::new();
+
+        loop {
+            let (policy, workspaces) = source().await;
+            if policy.auto_cleanup_enabled {
+                let service =
+                    CleanupService::new(path_manager.clone(), policy).with_workspaces(workspaces);
+                let now = SystemTime::now();
+                for due in service.due_policies(&last_runs, now) {
+                    last_runs.insert(due.target, now);
+                    service.run_and_report(due, false).await;
+                }
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,8 +687,210 @@ mod tests {
     #[test]
     fn test_cleanup_policy_default() {
         let policy = CleanupPolicy::default();
-        assert_eq!(policy.temp_retention_days, 7);
-        assert_eq!(policy.log_retention_days, 30);
+        let max_age = |target| {
+            policy
+                .policies
+                .iter()
+                .find(|p| p.target == target)
+                .and_then(|p| p.max_age_days)
+        };
+        assert_eq!(max_age(CleanupTarget::Temp), Some(7));
+        assert_eq!(max_age(CleanupTarget::Logs), Some(30));
         assert!(policy.auto_cleanup_enabled);
+        assert_eq!(
+            policy.cache_quota_bytes(CacheType::WebFetch),
+            CacheType::WebFetch.default_quota_mb() * 1_048_576
+        );
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_cache_files_over_quota() {
+        let root =
+            std::env::temp_dir().join(format!("bitfun-cache-quota-{}", uuid::Uuid::new_v4()));
+        let path_manager = PathManager::with_user_root(root.clone());
+        let cache_dir =
+            path_manager.workspace_cache_dir(Path::new("/work/project"), CacheType::WebFetch);
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        crate::infrastructure::filesystem::register_cache_consumer(
+            "quota-test-web-fetch",
+            CacheType::WebFetch,
+            cache_dir.clone(),
+        );
+
+        // Four 400 KB files, last accessed in the order b, d, a, c
+        let chunk = vec![0u8; 400 * 1024];
+        for (name, accessed) in [("a", 300), ("b", 100), ("c", 400), ("d", 200)] {
+            let path = cache_dir.join(name);
+            std::fs::write(&path, &chunk).unwrap();
+            filetime::set_file_atime(&path, filetime::FileTime::from_unix_time(accessed, 0))
+                .unwrap();
+        }
+
+        let mut policy = CleanupPolicy::default();
+        policy.cache_quotas_mb.insert(CacheType::WebFetch, 1);
+        let service = CleanupService::new(path_manager, policy);
+
+        let usage = service.cache_usage().await.unwrap();
+        let web_fetch = usage
+            .iter()
+            .find(|u| u.cache_type == CacheType::WebFetch)
+            .unwrap();
+        assert_eq!(web_fetch.size_bytes, 4 * 400 * 1024);
+        assert_eq!(web_fetch.file_count, 4);
+        assert_eq!(web_fetch.quota_bytes, 1_048_576);
+        assert!(web_fetch
+            .consumers
+            .contains(&"quota-test-web-fetch".to_string()));
+
+        // 1600 KB over a 1024 KB quota: the two least recently used go
+        let result = service.enforce_cache_quotas().await.unwrap();
+        assert_eq!(result.files_deleted, 2);
+        assert_eq!(result.bytes_freed, 2 * 400 * 1024);
+        assert!(!cache_dir.join("b").exists());
+        assert!(!cache_dir.join("d").exists());
+        assert!(cache_dir.join("a").exists());
+        assert!(cache_dir.join("c").exists());
+
+        crate::infrastructure::filesystem::unregister_cache_consumer("quota-test-web-fetch");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn policies_remove_old_files_then_the_oldest_beyond_the_size_limit() {
+        let root = std::env::temp_dir().join(format!("bitfun-cleanup-{}", uuid::Uuid::new_v4()));
+        let path_manager = PathManager::with_user_root(root.clone());
+        let trash = path_manager.tool_trash_dir();
+        std::fs::create_dir_all(trash.join("batch")).unwrap();
+
+        let now = filetime::FileTime::now().unix_seconds();
+        let day = 24 * 3600;
+        let chunk = vec![0u8; 400 * 1024];
+        // (name, age in days): "ancient" is past the age limit, the rest
+        // overflow the 1 MB limit from "old" onwards
+        for (name, age) in [("new", 0), ("recent", 1), ("old", 2), ("batch/older", 3)] {
+            let path = trash.join(name);
+            std::fs::write(&path, &chunk).unwrap();
+            filetime::set_file_mtime(
+                &path,
+                filetime::FileTime::from_unix_time(now - age * day, 0),
+            )
+            .unwrap();
+        }
+        let ancient = trash.join("ancient");
+        std::fs::write(&ancient, b"x").unwrap();
+        filetime::set_file_mtime(
+            &ancient,
+            filetime::FileTime::from_unix_time(now - 60 * day, 0),
+        )
+        .unwrap();
+
+        let policy = TargetCleanupPolicy::new(CleanupTarget::ToolTrash, Some(30), Some(1), None);
+        let service = CleanupService::new(
+            path_manager,
+            CleanupPolicy {
+                policies: vec![policy.clone()],
+                ..CleanupPolicy::default()
+            },
+        );
+
+        let dry_run = service.run_policy(&policy, true).await.unwrap();
+        assert!(dry_run.dry_run);
+        assert_eq!(dry_run.files_deleted, 3);
+        assert!(dry_run.removed.contains(&ancient));
+        assert!(dry_run.removed.contains(&trash.join("batch/older")));
+        assert!(ancient.exists());
+
+        let reports = service.run_policies(false).await;
+        assert_eq!(reports.len(), 1);
+        let result = &reports[0].result;
+        assert_eq!(result.files_deleted, 3);
+        assert_eq!(result.bytes_freed, 2 * 400 * 1024 + 1);
+        assert_eq!(result.directories_deleted, 1);
+        assert!(trash.join("new").exists());
+        assert!(trash.join("recent").exists());
+        assert!(!trash.join("old").exists());
+        assert!(!trash.join("batch").exists());
+        assert!(trash.exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn session_archives_remove_whole_sessions_idle_past_the_age_limit() {
+        let root = std::env::temp_dir().join(format!("bitfun-sessions-{}", uuid::Uuid::new_v4()));
+        let path_manager = PathManager::with_user_root(root.join("user"));
+        let workspace = root.join("project");
+        let sessions_dir = path_manager.project_sessions_dir(&workspace);
+
+        let now = filetime::FileTime::now().unix_seconds();
+        let long_ago = filetime::FileTime::from_unix_time(now - 120 * 24 * 3600, 0);
+        // "stale" has not changed for 120 days; "live" has an old file but was
+        // written to just now
+        for (file, recent) in [
+            ("stale/metadata.json", false),
+            ("stale/turns/turn-0.json", false),
+            ("live/metadata.json", false),
+            ("live/turns/turn-1.json", true),
+            ("index.json", false),
+        ] {
+            let path = sessions_dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"{}").unwrap();
+            if !recent {
+                filetime::set_file_mtime(&path, long_ago).unwrap();
+            }
+        }
+
+        let policy = TargetCleanupPolicy::new(CleanupTarget::SessionArchives, Some(90), None, None);
+        let service = CleanupService::new(path_manager, CleanupPolicy::default())
+            .with_workspaces(vec![workspace]);
+
+        let dry_run = service.run_policy(&policy, true).await.unwrap();
+        assert_eq!(dry_run.removed, vec![sessions_dir.join("stale")]);
+        assert!(sessions_dir.join("stale").exists());
+
+        let result = service.run_policy(&policy, false).await.unwrap();
+        assert_eq!(result.directories_deleted, 1);
+        assert_eq!(result.files_deleted, 2);
+        assert!(!sessions_dir.join("stale").exists());
+        assert!(sessions_dir.join("live/metadata.json").exists());
+        assert!(sessions_dir.join("live/turns/turn-1.json").exists());
+        assert!(sessions_dir.join("index.json").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn only_scheduled_policies_past_their_interval_are_due() {
+        let on_demand = TargetCleanupPolicy::new(CleanupTarget::Temp, Some(7), None, None);
+        let mut disabled = TargetCleanupPolicy::new(CleanupTarget::Logs, Some(30), None, Some(1));
+        disabled.enabled = false;
+        let service = CleanupService::new(
+            PathManager::with_user_root(std::env::temp_dir().join("bitfun-cleanup-due")),
+            CleanupPolicy {
+                policies: vec![
+                    on_demand,
+                    disabled,
+                    TargetCleanupPolicy::new(CleanupTarget::WebCache, None, Some(64), Some(6)),
+                ],
+                ..CleanupPolicy::default()
+            },
+        );
+
+        let now = SystemTime::now();
+        let due = |last_runs: &HashMap<CleanupTarget, SystemTime>| {
+            service
+                .due_policies(last_runs, now)
+                .iter()
+                .map(|p| p.target)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(due(&HashMap::new()), vec![CleanupTarget::WebCache]);
+        let mut last_runs = HashMap::new();
+        last_runs.insert(CleanupTarget::WebCache, now - Duration::from_secs(3600));
+        assert!(due(&last_runs).is_empty());
+        last_runs.insert(CleanupTarget::WebCache, now - Duration::from_secs(6 * 3600));
+        assert_eq!(due(&last_runs), vec![CleanupTarget::WebCache]);
     }
 }
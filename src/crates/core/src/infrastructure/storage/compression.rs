@@ -0,0 +1,201 @@
+//! zstd compression of persisted JSON
+//!
+//! Large JSON files are stored as `<name>.json.zst` next to where the plain
+//! `<name>.json` would be. Readers accept either form and prefer the newer
+//! one, so a write interrupted between creating one form and removing the
+//! other never loses data.
+
+use crate::util::errors::*;
+use log::debug;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Extension appended to compressed files
+pub const COMPRESSED_EXTENSION: &str = "zst";
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionSettings {
+    pub enabled: bool,
+    /// Files smaller than this stay plain JSON
+    pub min_bytes: u64,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_bytes: 64 * 1024,
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static MIN_BYTES: AtomicU64 = AtomicU64::new(64 * 1024);
+
+pub fn set_compression_settings(settings: CompressionSettings) {
+    ENABLED.store(settings.enabled, Ordering::Relaxed);
+    MIN_BYTES.store(settings.min_bytes, Ordering::Relaxed);
+}
+
+pub fn compression_settings() -> CompressionSettings {
+    CompressionSettings {
+        enabled: ENABLED.load(Ordering::Relaxed),
+        min_bytes: MIN_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Whether content of `len` bytes should be written compressed
+pub fn should_compress(len: usize) -> bool {
+    let settings = compression_settings();
+    settings.enabled && len as u64 >= settings.min_bytes
+}
+
+/// `turn-0001.json` -> `turn-0001.json.zst`
+pub fn compressed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(COMPRESSED_EXTENSION);
+    PathBuf::from(name)
+}
+
+pub fn is_compressed(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some(COMPRESSED_EXTENSION)
+}
+
+/// The file holding `path`'s content: the plain or the compressed form,
+/// whichever was written last; `None` if neither exists
+pub fn existing_form(path: &Path) -> Option<PathBuf> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let compressed = compressed_path(path);
+    match (modified(path), modified(&compressed)) {
+        (Some(plain), Some(packed)) if plain > packed => Some(path.to_path_buf()),
+        (_, Some(_)) => Some(compressed),
+        (Some(_), None) => Some(path.to_path_buf()),
+        (None, None) => None,
+    }
+}
+
+pub fn compress(data: &[u8]) -> BitFunResult<Vec<u8>> {
+    zstd::encode_all(data, ZSTD_LEVEL)
+        .map_err(|e| BitFunError::io(format!("Failed to compress data: {}", e)))
+}
+
+/// Logs how well a file compressed
+pub fn log_ratio(path: &Path, original: usize, compressed: usize) {
+    debug!(
+        "Compressed {} from {} to {} bytes ({:.1}x)",
+        path.display(),
+        original,
+        compressed,
+        original as f64 / compressed.max(1) as f64
+    );
+}
+
+/// Content of a plain or compressed file as text
+pub fn read_to_string(path: &Path) -> BitFunResult<String> {
+    let file = File::open(path)
+        .map_err(|e| BitFunError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+    let result = if is_compressed(path) {
+        zstd::stream::read::Decoder::new(file).and_then(std::io::read_to_string)
+    } else {
+        std::io::read_to_string(file)
+    };
+    result.map_err(|e| {
+        BitFunError::io(format!(
+            "{} is corrupted or truncated: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Deserializes a plain or compressed JSON file, decompressing as it parses.
+/// A corrupted or truncated file is an error, never a panic.
+pub fn read_json_file<T: DeserializeOwned>(path: &Path) -> BitFunResult<T> {
+    let file = File::open(path)
+        .map_err(|e| BitFunError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    let result = if is_compressed(path) {
+        let decoder = zstd::stream::read::Decoder::new(file).map_err(|e| {
+            BitFunError::io(format!("Failed to decompress {}: {}", path.display(), e))
+        })?;
+        serde_json::from_reader(BufReader::new(decoder))
+    } else {
+        serde_json::from_reader(BufReader::new(file))
+    };
+
+    result.map_err(|e| {
+        if e.is_io() || e.is_eof() {
+            BitFunError::io(format!(
+                "{} is corrupted or truncated: {}",
+                path.display(),
+                e
+            ))
+        } else {
+            BitFunError::Deserialization(format!(
+                "Failed to deserialize JSON file {}: {}",
+                path.display(),
+                e
+            ))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn round_trips_compressed_json() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("turn-0001.json");
+        let value = json!({"output": "tool result ".repeat(10_000)});
+        let plain = serde_json::to_vec_pretty(&value).unwrap();
+        let packed = compress(&plain).unwrap();
+        assert!(packed.len() * 10 < plain.len());
+        std::fs::write(compressed_path(&path), &packed).unwrap();
+
+        assert_eq!(existing_form(&path), Some(dir.join("turn-0001.json.zst")));
+        let loaded: Value = read_json_file(&existing_form(&path).unwrap()).unwrap();
+        assert_eq!(loaded, value);
+    }
+
+    #[test]
+    fn truncated_frames_are_errors() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = compressed_path(&dir.join("turn-0002.json"));
+        let packed =
+            compress(&serde_json::to_vec(&json!({"a": "b".repeat(5000)})).unwrap()).unwrap();
+        std::fs::write(&path, &packed[..packed.len() / 2]).unwrap();
+
+        let error = read_json_file::<Value>(&path).unwrap_err();
+        assert!(error.to_string().contains("corrupted or truncated"));
+
+        std::fs::write(&path, b"not zstd at all").unwrap();
+        assert!(read_json_file::<Value>(&path).is_err());
+    }
+
+    #[test]
+    fn prefers_the_newer_form() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("context-0003.json");
+        assert_eq!(existing_form(&path), None);
+
+        std::fs::write(compressed_path(&path), compress(b"{}").unwrap()).unwrap();
+        std::fs::write(&path, "{}").unwrap();
+        let old = filetime::FileTime::from_unix_time(1_000_000, 0);
+        filetime::set_file_mtime(compressed_path(&path), old).unwrap();
+        assert_eq!(existing_form(&path), Some(path.clone()));
+
+        filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(1, 0)).unwrap();
+        assert_eq!(existing_form(&path), Some(compressed_path(&path)));
+    }
+}
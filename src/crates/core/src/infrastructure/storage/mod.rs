@@ -2,8 +2,25 @@
 //!
 //! Data persistence, cleanup, and storage policies.
 
+pub mod backend;
+pub mod backup;
 pub mod cleanup;
+pub mod compression;
 pub mod persistence;
-pub use cleanup::{CleanupPolicy, CleanupResult, CleanupService};
+pub mod sqlite;
+pub use cleanup::{
+    spawn_cleanup_scheduler, CacheUsage, CleanupPolicy, CleanupResult, CleanupService,
+    CleanupTarget, PolicyCleanupReport, TargetCleanupPolicy,
+};
 
-pub use persistence::{PersistenceService, StorageOptions};
+pub use backend::{
+    default_storage_backend, set_default_storage_backend, FileBackend, StorageBackend,
+    StorageBackendKind,
+};
+pub use backup::{
+    read_manifest, spawn_backup_scheduler, BackupCategory, BackupFile, BackupInfo, BackupManifest,
+    BackupSchedule, BackupService, RestoreOptions, RestoreReport,
+};
+pub use compression::{compression_settings, set_compression_settings, CompressionSettings};
+pub use persistence::{migrate_json_to_sqlite, PersistenceService, StorageOptions};
+pub use sqlite::{JsonImportReport, SqliteBackend};
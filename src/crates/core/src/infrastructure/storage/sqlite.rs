@@ -0,0 +1,390 @@
+//! SQLite storage backend
+//!
+//! Keeps every entry of a storage directory in `storage.db` (WAL mode), keyed
+//! by entry key with an index on the key namespace (`sessions` for
+//! `sessions/<id>`). Existing JSON files are imported once with
+//! [`SqliteBackend::import_json_files`].
+
+use super::backend::{FileBackend, StorageBackend, StorageBackendKind};
+use super::compression;
+use super::persistence::StorageOptions;
+use crate::util::errors::*;
+use async_trait::async_trait;
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Database file inside the storage directory
+pub const SQLITE_DB_FILE: &str = "storage.db";
+/// Meta entry recording that JSON files were imported
+const JSON_IMPORTED_AT: &str = "json_imported_at";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS entries (
+    key TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_entries_namespace ON entries(namespace, updated_at);
+CREATE TABLE IF NOT EXISTS meta (
+    name TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+/// Outcome of importing a directory of JSON files
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonImportReport {
+    pub imported: usize,
+    /// Files that could not be read or are not valid JSON
+    pub skipped: Vec<PathBuf>,
+    /// The import had already run; nothing was imported again
+    pub already_imported: bool,
+}
+
+pub struct SqliteBackend {
+    path: PathBuf,
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteBackend {
+    /// Opens (or creates) `storage.db` in `base_dir`
+    pub fn open(base_dir: &Path) -> BitFunResult<Self> {
+        let path = base_dir.join(SQLITE_DB_FILE);
+        let connection = Connection::open(&path)
+            .and_then(|connection| {
+                connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| {
+                    row.get::<_, String>(0)
+                })?;
+                connection.pragma_update(None, "synchronous", "NORMAL")?;
+                connection.busy_timeout(Duration::from_secs(5))?;
+                connection.execute_batch(SCHEMA)?;
+                Ok(connection)
+            })
+            .map_err(|e| {
+                BitFunError::service(format!("Failed to open database {:?}: {}", path, e))
+            })?;
+
+        Ok(Self {
+            path,
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Runs a query on the blocking thread pool
+    async fn with_connection<T, F>(&self, query: F) -> BitFunResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection
+                .lock()
+                .map_err(|_| BitFunError::service("Database connection poisoned".to_string()))?;
+            query(&mut connection)
+                .map_err(|e| BitFunError::service(format!("Database query failed: {}", e)))
+        })
+        .await
+        .map_err(|e| BitFunError::service(format!("Database task failed: {}", e)))?
+    }
+
+    /// Imports the `<key>.json` files of `base_dir` (as the file backend
+    /// wrote them) once; later calls report `already_imported`. The files
+    /// are left in place.
+    pub async fn import_json_files(&self, base_dir: &Path) -> BitFunResult<JsonImportReport> {
+        let base_dir = base_dir.to_path_buf();
+        self.with_connection(move |connection| {
+            let imported_at: Option<String> = connection
+                .query_row(
+                    "SELECT value FROM meta WHERE name = ?1",
+                    params![JSON_IMPORTED_AT],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if imported_at.is_some() {
+                return Ok(JsonImportReport {
+                    already_imported: true,
+                    ..Default::default()
+                });
+            }
+
+            let mut report = JsonImportReport::default();
+            let transaction = connection.transaction()?;
+            for (key, path) in FileBackend::entries(&base_dir) {
+                let valid = compression::read_to_string(&path)
+                    .ok()
+                    .filter(|content| serde_json::from_str::<serde_json::Value>(content).is_ok());
+                let Some(content) = valid else {
+                    warn!("Skipping unreadable storage file {:?}", path);
+                    report.skipped.push(path);
+                    continue;
+                };
+                let updated_at = std::fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .map(|modified| {
+                        chrono::DateTime::<chrono::Utc>::from(modified).timestamp_millis()
+                    })
+                    .unwrap_or_else(|_| chrono::Utc::now().timestamp_millis());
+                upsert(&transaction, &key, &content, updated_at)?;
+                report.imported += 1;
+            }
+            transaction.execute(
+                "INSERT OR REPLACE INTO meta (name, value) VALUES (?1, ?2)",
+                params![JSON_IMPORTED_AT, chrono::Utc::now().to_rfc3339()],
+            )?;
+            transaction.commit()?;
+
+            info!(
+                "Imported {} JSON files from {:?} ({} skipped)",
+                report.imported,
+                base_dir,
+                report.skipped.len()
+            );
+            Ok(report)
+        })
+        .await
+    }
+}
+
+/// `sessions` for `sessions/<id>`, empty for top-level keys
+fn namespace(key: &str) -> &str {
+    key.split_once('/')
+        .map(|(namespace, _)| namespace)
+        .unwrap_or("")
+}
+
+fn upsert(
+    connection: &Connection,
+    key: &str,
+    value: &str,
+    updated_at: i64,
+) -> rusqlite::Result<()> {
+    connection.execute(
+        "INSERT INTO entries (key, namespace, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![key, namespace(key), value, updated_at],
+    )?;
+    Ok(())
+}
+
+/// Upper bound of the keys starting with `prefix`, for an indexed range scan
+fn prefix_end(prefix: &str) -> String {
+    format!("{}{}", prefix, char::MAX)
+}
+
+#[async_trait]
+impl StorageBackend for SqliteBackend {
+    fn kind(&self) -> StorageBackendKind {
+        StorageBackendKind::Sqlite
+    }
+
+    async fn read(&self, key: &str) -> BitFunResult<Option<String>> {
+        let key = key.to_string();
+        self.with_connection(move |connection| {
+            connection
+                .query_row(
+                    "SELECT value FROM entries WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()
+        })
+        .await
+    }
+
+    async fn write(&self, key: &str, json: String, _options: &StorageOptions) -> BitFunResult<()> {
+        // Writes are transactional, so there are no file backups to keep
+        let key = key.to_string();
+        self.with_connection(move |connection| {
+            upsert(
+                connection,
+                &key,
+                &json,
+                chrono::Utc::now().timestamp_millis(),
+            )
+        })
+        .await
+    }
+
+    async fn delete(&self, key: &str) -> BitFunResult<bool> {
+        let key = key.to_string();
+        self.with_connection(move |connection| {
+            connection
+                .execute("DELETE FROM entries WHERE key = ?1", params![key])
+                .map(|deleted| deleted > 0)
+        })
+        .await
+    }
+
+    async fn list_keys(&self, prefix: &str) -> BitFunResult<Vec<String>> {
+        let prefix = prefix.to_string();
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare_cached(
+                "SELECT key FROM entries WHERE key >= ?1 AND key < ?2 ORDER BY key",
+            )?;
+            let keys = statement
+                .query_map(params![prefix, prefix_end(&prefix)], |row| row.get(0))?
+                .collect();
+            keys
+        })
+        .await
+    }
+
+    async fn search(&self, prefix: &str, needle: &str) -> BitFunResult<Vec<String>> {
+        let prefix = prefix.to_string();
+        let needle = needle.to_string();
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare_cached(
+                "SELECT key FROM entries
+                 WHERE key >= ?1 AND key < ?2 AND instr(value, ?3) > 0
+                 ORDER BY key",
+            )?;
+            let keys = statement
+                .query_map(params![prefix, prefix_end(&prefix), needle], |row| {
+                    row.get(0)
+                })?
+                .collect();
+            keys
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn stores_lists_and_searches_entries() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let backend = SqliteBackend::open(dir).unwrap();
+        let options = StorageOptions::default();
+
+        backend
+            .write(
+                "sessions/a",
+                json!({"title": "fix parser"}).to_string(),
+                &options,
+            )
+            .await
+            .unwrap();
+        backend
+            .write("sessions/b", json!({"title": "docs"}).to_string(), &options)
+            .await
+            .unwrap();
+        backend
+            .write("jobs", json!({"jobs": []}).to_string(), &options)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            backend.list_keys("sessions/").await.unwrap(),
+            vec!["sessions/a", "sessions/b"]
+        );
+        assert_eq!(backend.list_keys("").await.unwrap().len(), 3);
+        assert_eq!(
+            backend.search("sessions/", "parser").await.unwrap(),
+            vec!["sessions/a"]
+        );
+        assert!(backend.delete("sessions/a").await.unwrap());
+        assert!(!backend.delete("sessions/a").await.unwrap());
+        assert_eq!(backend.read("sessions/a").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn imports_json_files_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("sessions")).unwrap();
+        std::fs::create_dir_all(dir.join("backups")).unwrap();
+        std::fs::write(dir.join("jobs.json"), r#"{"jobs": []}"#).unwrap();
+        std::fs::write(dir.join("sessions").join("a.json"), r#"{"id": "a"}"#).unwrap();
+        std::fs::write(dir.join("sessions").join("broken.json"), "{").unwrap();
+        std::fs::write(dir.join("backups").join("old_jobs.json"), "{}").unwrap();
+
+        let backend = SqliteBackend::open(dir).unwrap();
+        let report = backend.import_json_files(dir).await.unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(
+            report.skipped,
+            vec![dir.join("sessions").join("broken.json")]
+        );
+        assert_eq!(
+            backend.read("sessions/a").await.unwrap().as_deref(),
+            Some(r#"{"id": "a"}"#)
+        );
+
+        let again = backend.import_json_files(dir).await.unwrap();
+        assert!(again.already_imported);
+        assert_eq!(again.imported, 0);
+    }
+
+    /// `cargo test -p bitfun-core sqlite -- --ignored --nocapture`
+    #[tokio::test]
+    #[ignore]
+    async fn benchmark_listing_and_search_on_5k_sessions() {
+        let options = StorageOptions {
+            create_backup: false,
+            ..StorageOptions::default()
+        };
+        let file_temp = tempfile::tempdir().unwrap();
+        let file_dir = file_temp.path();
+        let sqlite_temp = tempfile::tempdir().unwrap();
+        let sqlite_dir = sqlite_temp.path();
+        let backends: Vec<Box<dyn StorageBackend>> = vec![
+            Box::new(FileBackend::new(file_dir.to_path_buf())),
+            Box::new(SqliteBackend::open(sqlite_dir).unwrap()),
+        ];
+
+        for backend in &backends {
+            let started = Instant::now();
+            for index in 0..5000 {
+                let session = json!({
+                    "id": index,
+                    "title": format!("session {}", index),
+                    "messages": vec!["lorem ipsum dolor sit amet"; 20],
+                });
+                backend
+                    .write(
+                        &format!("sessions/{:05}", index),
+                        session.to_string(),
+                        &options,
+                    )
+                    .await
+                    .unwrap();
+            }
+            let written = started.elapsed();
+
+            let started = Instant::now();
+            let keys = backend.list_keys("sessions/").await.unwrap();
+            let listed = started.elapsed();
+
+            let started = Instant::now();
+            let found = backend.search("sessions/", "session 4999").await.unwrap();
+            let searched = started.elapsed();
+
+            assert_eq!(keys.len(), 5000);
+            assert_eq!(found, vec!["sessions/04999"]);
+            println!(
+                "{:?}: write {:?}, list {:?}, search {:?}",
+                backend.kind(),
+                written,
+                listed,
+                searched
+            );
+        }
+    }
+}
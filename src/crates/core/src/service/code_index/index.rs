@@ -0,0 +1,363 @@
+//! In-memory code index with search and project map rendering
+
+use super::types::{CodeIndexStatus, CodeSearchHit, CodeSymbol, FileOutline, SymbolKind};
+use crate::infrastructure::filesystem::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Bumped when the stored layout or the extraction rules change, so old caches are rebuilt
+const INDEX_FORMAT_VERSION: u32 = 1;
+/// Top-level symbols listed per file in the project map
+const MAP_SYMBOLS_PER_FILE: usize = 12;
+
+const SCORE_NAME_EXACT: i64 = 100;
+const SCORE_NAME_PREFIX: i64 = 60;
+const SCORE_NAME_CONTAINS: i64 = 40;
+const SCORE_NAME_FUZZY: i64 = 15;
+const SCORE_FILE_NAME: i64 = 20;
+const SCORE_PATH: i64 = 10;
+const SCORE_SUMMARY: i64 = 5;
+
+/// Outlines of the indexed files of one workspace, keyed by relative path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeIndex {
+    version: u32,
+    files: BTreeMap<String, FileOutline>,
+    /// The last build stopped at the file limit
+    #[serde(default)]
+    truncated: bool,
+}
+
+impl Default for CodeIndex {
+    fn default() -> Self {
+        Self {
+            version: INDEX_FORMAT_VERSION,
+            files: BTreeMap::new(),
+            truncated: false,
+        }
+    }
+}
+
+impl CodeIndex {
+    /// Index stored at `path`; `None` when missing, unreadable or of an older format
+    pub fn load(path: &Path) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        let index: Self = serde_json::from_slice(&data).ok()?;
+        (index.version == INDEX_FORMAT_VERSION).then_some(index)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_vec(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    pub fn get(&self, path: &str) -> Option<&FileOutline> {
+        self.files.get(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &String> {
+        self.files.keys()
+    }
+
+    pub fn upsert(&mut self, outline: FileOutline) {
+        self.files.insert(outline.path.clone(), outline);
+    }
+
+    /// Drops `path`, or everything below it when it was a directory
+    pub fn remove(&mut self, path: &str) {
+        self.files.remove(path);
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+        self.files.retain(|file, _| !file.starts_with(&prefix));
+    }
+
+    pub fn set_truncated(&mut self, truncated: bool) {
+        self.truncated = truncated;
+    }
+
+    pub fn status(&self) -> CodeIndexStatus {
+        CodeIndexStatus {
+            files: self.files.len(),
+            symbols: self.files.values().map(|file| file.symbols.len()).sum(),
+            truncated: self.truncated,
+            ..Default::default()
+        }
+    }
+
+    /// Symbols and files matching `query`, best first.
+    ///
+    /// Each whitespace-separated term is scored against symbol names (exact,
+    /// prefix, substring, then fuzzy), the file path and the file summary; a
+    /// result needs at least one term to hit a name or a path.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<CodeSearchHit> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect();
+        if terms.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let mut matchers: Vec<FuzzyMatcher> =
+            terms.iter().map(|term| FuzzyMatcher::new(term)).collect();
+
+        let mut hits = Vec::new();
+        for file in self.files.values() {
+            let path = file.path.to_lowercase();
+            let file_name = path.rsplit('/').next().unwrap_or(&path);
+            let summary = file.summary.as_deref().unwrap_or_default().to_lowercase();
+
+            let mut file_score = 0;
+            let mut path_hit = false;
+            for term in &terms {
+                if file_name.contains(term.as_str()) {
+                    file_score += SCORE_FILE_NAME;
+                    path_hit = true;
+                } else if path.contains(term.as_str()) {
+                    file_score += SCORE_PATH;
+                    path_hit = true;
+                }
+                if summary.contains(term.as_str()) {
+                    file_score += SCORE_SUMMARY;
+                }
+            }
+
+            let mut symbol_hit = false;
+            for symbol in &file.symbols {
+                let name_score = name_score(&symbol.name, &terms, &mut matchers);
+                if name_score == 0 {
+                    continue;
+                }
+                symbol_hit = true;
+                hits.push(CodeSearchHit {
+                    path: file.path.clone(),
+                    symbol: Some(symbol.clone()),
+                    summary: None,
+                    score: name_score + file_score + kind_bonus(symbol.kind),
+                });
+            }
+
+            if path_hit && !symbol_hit {
+                hits.push(CodeSearchHit {
+                    path: file.path.clone(),
+                    symbol: None,
+                    summary: file.summary.clone(),
+                    score: file_score,
+                });
+            }
+        }
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| {
+                    let line = |hit: &CodeSearchHit| hit.symbol.as_ref().map(|s| s.start_line);
+                    line(a).cmp(&line(b))
+                })
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Overview of the indexed files and their top-level definitions, cut off at
+    /// about `max_chars`
+    pub fn project_map(&self, max_chars: usize) -> String {
+        let mut map = String::new();
+        let mut listed = 0;
+        for file in self.files.values() {
+            let entry = map_entry(file);
+            if map.len() + entry.len() > max_chars {
+                break;
+            }
+            map.push_str(&entry);
+            listed += 1;
+        }
+        let remaining = self.files.len() - listed;
+        if remaining > 0 {
+            map.push_str(&format!(
+                "... {} more files; use CodebaseSearch to look them up\n",
+                remaining
+            ));
+        }
+        map
+    }
+}
+
+fn name_score(name: &str, terms: &[String], matchers: &mut [FuzzyMatcher]) -> i64 {
+    let name_lower = name.to_lowercase();
+    terms
+        .iter()
+        .zip(matchers.iter_mut())
+        .map(|(term, matcher)| {
+            if name_lower == *term {
+                SCORE_NAME_EXACT
+            } else if name_lower.starts_with(term.as_str()) {
+                SCORE_NAME_PREFIX
+            } else if name_lower.contains(term.as_str()) {
+                SCORE_NAME_CONTAINS
+            } else if term.len() >= 3 && matcher.score(name).is_some() {
+                SCORE_NAME_FUZZY
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// Definitions people usually look for first rank above impls and constants
+fn kind_bonus(kind: SymbolKind) -> i64 {
+    match kind {
+        SymbolKind::Struct
+        | SymbolKind::Class
+        | SymbolKind::Enum
+        | SymbolKind::Interface
+        | SymbolKind::Trait => 3,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::TypeAlias => 2,
+        SymbolKind::Module | SymbolKind::Macro => 1,
+        SymbolKind::Impl | SymbolKind::Constant => 0,
+    }
+}
+
+fn map_entry(file: &FileOutline) -> String {
+    let mut entry = file.path.clone();
+    if let Some(summary) = &file.summary {
+        entry.push_str(" — ");
+        entry.push_str(summary);
+    }
+    entry.push('\n');
+
+    let top_level: Vec<&CodeSymbol> = file
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.container.is_none() && symbol.kind != SymbolKind::Impl)
+        .collect();
+    if !top_level.is_empty() {
+        let listed: Vec<String> = top_level
+            .iter()
+            .take(MAP_SYMBOLS_PER_FILE)
+            .map(|symbol| {
+                format!(
+                    "{} {}:{}",
+                    symbol.kind.as_str(),
+                    symbol.name,
+                    symbol.start_line
+                )
+            })
+            .collect();
+        entry.push_str("  ");
+        entry.push_str(&listed.join(", "));
+        if top_level.len() > MAP_SYMBOLS_PER_FILE {
+            entry.push_str(&format!(
+                ", +{} more",
+                top_level.len() - MAP_SYMBOLS_PER_FILE
+            ));
+        }
+        entry.push('\n');
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, kind: SymbolKind, container: Option<&str>, line: u32) -> CodeSymbol {
+        CodeSymbol {
+            name: name.to_string(),
+            kind,
+            container: container.map(str::to_string),
+            start_line: line,
+            end_line: line + 5,
+        }
+    }
+
+    fn file(path: &str, summary: Option<&str>, symbols: Vec<CodeSymbol>) -> FileOutline {
+        FileOutline {
+            path: path.to_string(),
+            language: "rust".to_string(),
+            size: 100,
+            modified_ms: 0,
+            summary: summary.map(str::to_string),
+            symbols,
+        }
+    }
+
+    fn sample_index() -> CodeIndex {
+        let mut index = CodeIndex::default();
+        index.upsert(file(
+            "src/session/manager.rs",
+            Some("Session lifecycle management"),
+            vec![
+                symbol("SessionManager", SymbolKind::Struct, None, 10),
+                symbol(
+                    "create_session",
+                    SymbolKind::Method,
+                    Some("SessionManager"),
+                    30,
+                ),
+            ],
+        ));
+        index.upsert(file(
+            "src/session/store.rs",
+            None,
+            vec![symbol("SessionStore", SymbolKind::Struct, None, 5)],
+        ));
+        index.upsert(file("src/util/paths.rs", None, vec![]));
+        index
+    }
+
+    #[test]
+    fn ranks_exact_names_above_partial_matches() {
+        let index = sample_index();
+        let hits = index.search("SessionManager", 10);
+        let first = hits[0].symbol.as_ref().unwrap();
+        assert_eq!(first.name, "SessionManager");
+        assert_eq!(hits[0].path, "src/session/manager.rs");
+
+        let hits = index.search("create session", 10);
+        assert_eq!(hits[0].symbol.as_ref().unwrap().name, "create_session");
+    }
+
+    #[test]
+    fn returns_files_matched_by_path_only() {
+        let index = sample_index();
+        let hits = index.search("paths", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "src/util/paths.rs");
+        assert!(hits[0].symbol.is_none());
+        assert!(index.search("nonexistent", 10).is_empty());
+    }
+
+    #[test]
+    fn removing_a_directory_drops_its_files() {
+        let mut index = sample_index();
+        index.remove("src/session");
+        assert_eq!(index.paths().collect::<Vec<_>>(), vec!["src/util/paths.rs"]);
+    }
+
+    #[test]
+    fn project_map_lists_top_level_symbols_within_budget() {
+        let index = sample_index();
+        let map = index.project_map(10_000);
+        assert!(map.contains("src/session/manager.rs — Session lifecycle management\n"));
+        assert!(map.contains("  struct SessionManager:10\n"));
+        assert!(!map.contains("create_session"));
+
+        let short = index.project_map(100);
+        assert!(short.starts_with("src/session/manager.rs"));
+        assert!(short.ends_with("2 more files; use CodebaseSearch to look them up\n"));
+    }
+}
@@ -0,0 +1,19 @@
+//! Workspace code index
+//!
+//! Keeps a symbol outline (functions, types, modules with their line ranges)
+//! and a one-line summary of every source file in a workspace, so agents can
+//! look code up by name instead of rediscovering the project with Grep/Read.
+//! The index is cached under the workspace's `index` cache directory and kept
+//! current from file watch events.
+
+mod index;
+mod outline;
+mod service;
+mod types;
+
+pub use index::CodeIndex;
+pub use outline::{CodeLanguage, OutlineExtractor};
+pub use service::{
+    get_global_code_index_service, CodeIndexLimits, CodeIndexService, WorkspaceCodeIndex,
+};
+pub use types::{CodeIndexStatus, CodeSearchHit, CodeSymbol, FileOutline, SymbolKind};
@@ -0,0 +1,392 @@
+//! Symbol extraction with tree-sitter
+//!
+//! Definitions are found by walking the syntax tree and matching node kinds
+//! per language; the name comes from the node's `name` field. Function bodies
+//! are not descended into, so local helpers and closures stay out of the
+//! outline.
+
+use super::types::{CodeSymbol, SymbolKind};
+use log::debug;
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser};
+
+/// Symbols kept per file; generated files can define thousands
+const MAX_SYMBOLS_PER_FILE: usize = 1000;
+/// Characters kept of a file's leading doc comment
+const MAX_SUMMARY_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl CodeLanguage {
+    /// Language of a source file, by extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Go => "go",
+        }
+    }
+
+    fn grammar(&self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    /// Line comment markers, longest first
+    fn comment_markers(&self) -> &'static [&'static str] {
+        match self {
+            Self::Python => &["#"],
+            _ => &["//!", "///", "//", "/**", "/*", "*/", "*"],
+        }
+    }
+}
+
+/// Reusable parser; not shareable between threads, so each indexing task owns one
+pub struct OutlineExtractor {
+    parser: Parser,
+}
+
+impl OutlineExtractor {
+    pub fn new() -> Self {
+        Self {
+            parser: Parser::new(),
+        }
+    }
+
+    /// Leading doc comment and definitions of `source`, or `None` when it can't be parsed
+    pub fn extract(
+        &mut self,
+        language: CodeLanguage,
+        source: &str,
+    ) -> Option<(Option<String>, Vec<CodeSymbol>)> {
+        if let Err(e) = self.parser.set_language(&language.grammar()) {
+            debug!("Failed to load {} grammar: {}", language.name(), e);
+            return None;
+        }
+        let tree = self.parser.parse(source, None)?;
+        let symbols = collect_symbols(language, tree.root_node(), source.as_bytes());
+        Some((leading_summary(language, source), symbols))
+    }
+}
+
+impl Default for OutlineExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn collect_symbols(language: CodeLanguage, root: Node, source: &[u8]) -> Vec<CodeSymbol> {
+    let mut symbols = Vec::new();
+    // (node, enclosing symbol name, whether the enclosing symbol is a type or impl)
+    let mut stack: Vec<(Node, Option<String>, bool)> = vec![(root, None, false)];
+
+    while let Some((node, container, in_type)) = stack.pop() {
+        if symbols.len() >= MAX_SYMBOLS_PER_FILE {
+            break;
+        }
+
+        let (child_container, child_in_type) = match classify(language, node, source) {
+            Some((mut kind, name)) => {
+                if kind == SymbolKind::Function && in_type {
+                    kind = SymbolKind::Method;
+                }
+                symbols.push(CodeSymbol {
+                    name: name.clone(),
+                    kind,
+                    container: container.clone(),
+                    start_line: node.start_position().row as u32 + 1,
+                    end_line: node.end_position().row as u32 + 1,
+                });
+                if kind.is_callable() {
+                    continue;
+                }
+                let is_type = !matches!(kind, SymbolKind::Module | SymbolKind::Constant);
+                (Some(name), is_type)
+            }
+            None => (container, in_type),
+        };
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        // Reversed so the stack yields them in source order
+        for child in children.into_iter().rev() {
+            stack.push((child, child_container.clone(), child_in_type));
+        }
+    }
+
+    symbols.sort_by_key(|symbol| symbol.start_line);
+    symbols
+}
+
+fn node_text<'a>(node: Node, source: &'a [u8]) -> Option<&'a str> {
+    node.utf8_text(source).ok()
+}
+
+fn field_text<'a>(node: Node, field: &str, source: &'a [u8]) -> Option<&'a str> {
+    node_text(node.child_by_field_name(field)?, source)
+}
+
+/// Symbol kind and name of a definition node
+fn classify(language: CodeLanguage, node: Node, source: &[u8]) -> Option<(SymbolKind, String)> {
+    let kind = match language {
+        CodeLanguage::Rust => match node.kind() {
+            "function_item" | "function_signature_item" => SymbolKind::Function,
+            "struct_item" | "union_item" => SymbolKind::Struct,
+            "enum_item" => SymbolKind::Enum,
+            "trait_item" => SymbolKind::Trait,
+            "mod_item" => SymbolKind::Module,
+            "type_item" => SymbolKind::TypeAlias,
+            "const_item" | "static_item" => SymbolKind::Constant,
+            "macro_definition" => SymbolKind::Macro,
+            "impl_item" => {
+                let target = field_text(node, "type", source)?;
+                let name = match field_text(node, "trait", source) {
+                    Some(trait_name) => format!("{} for {}", trait_name, target),
+                    None => target.to_string(),
+                };
+                return Some((SymbolKind::Impl, name));
+            }
+            _ => return None,
+        },
+        CodeLanguage::Python => match node.kind() {
+            "function_definition" => SymbolKind::Function,
+            "class_definition" => SymbolKind::Class,
+            _ => return None,
+        },
+        CodeLanguage::JavaScript | CodeLanguage::TypeScript | CodeLanguage::Tsx => {
+            match node.kind() {
+                "function_declaration" | "generator_function_declaration" => SymbolKind::Function,
+                "method_definition" => SymbolKind::Method,
+                "class_declaration" | "abstract_class_declaration" => SymbolKind::Class,
+                "interface_declaration" => SymbolKind::Interface,
+                "type_alias_declaration" => SymbolKind::TypeAlias,
+                "enum_declaration" => SymbolKind::Enum,
+                "internal_module" => SymbolKind::Module,
+                // `const handler = (...) => {...}`
+                "variable_declarator" => {
+                    let value = node.child_by_field_name("value")?;
+                    match value.kind() {
+                        "arrow_function" | "function_expression" | "function" => {
+                            SymbolKind::Function
+                        }
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            }
+        }
+        CodeLanguage::Go => match node.kind() {
+            "function_declaration" => SymbolKind::Function,
+            "method_declaration" => SymbolKind::Method,
+            "type_spec" => match node.child_by_field_name("type").map(|t| t.kind()) {
+                Some("struct_type") => SymbolKind::Struct,
+                Some("interface_type") => SymbolKind::Interface,
+                _ => SymbolKind::TypeAlias,
+            },
+            _ => return None,
+        },
+    };
+
+    let name = field_text(node, "name", source)?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((kind, name.to_string()))
+}
+
+/// The comment block (or Python docstring) a file starts with, on one line.
+/// License headers are skipped.
+fn leading_summary(language: CodeLanguage, source: &str) -> Option<String> {
+    let markers = language.comment_markers();
+    let mut lines = source.lines().map(str::trim).peekable();
+
+    // Shebang and blank lines before the comment
+    while let Some(line) = lines.peek() {
+        if line.is_empty() || line.starts_with("#!") {
+            lines.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let in_docstring = language == CodeLanguage::Python
+        && lines
+            .peek()
+            .is_some_and(|line| line.starts_with("\"\"\"") || line.starts_with("'''"));
+
+    for line in lines {
+        if in_docstring {
+            let closes = !parts.is_empty() && (line.contains("\"\"\"") || line.contains("'''"));
+            // A docstring that opens and closes on its first line
+            let single = parts.is_empty() && line.len() > 3 && line[3..].contains(&line[..3]);
+            parts.push(
+                line.trim_matches(|c| c == '"' || c == '\'')
+                    .trim()
+                    .to_string(),
+            );
+            if closes || single {
+                break;
+            }
+            continue;
+        }
+        let Some(marker) = markers.iter().find(|marker| line.starts_with(*marker)) else {
+            break;
+        };
+        parts.push(
+            line[marker.len()..]
+                .trim_end_matches("*/")
+                .trim()
+                .to_string(),
+        );
+    }
+
+    let summary = parts
+        .iter()
+        .filter(|part| {
+            !part.starts_with("Copyright")
+                && !part.starts_with("SPDX-License-Identifier")
+                && !part.starts_with("eslint-")
+                && !part.starts_with("@ts-")
+        })
+        .flat_map(|part| part.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if summary.is_empty() {
+        return None;
+    }
+    if summary.chars().count() <= MAX_SUMMARY_CHARS {
+        return Some(summary);
+    }
+    let truncated: String = summary.chars().take(MAX_SUMMARY_CHARS).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(language: CodeLanguage, source: &str) -> (Option<String>, Vec<CodeSymbol>) {
+        OutlineExtractor::new()
+            .extract(language, source)
+            .expect("source should parse")
+    }
+
+    fn names(symbols: &[CodeSymbol]) -> Vec<(SymbolKind, &str, Option<&str>)> {
+        symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.container.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_rust_definitions() {
+        let source = r#"//! Session storage
+//! Keeps sessions on disk.
+
+pub struct SessionStore {
+    root: PathBuf,
+}
+
+impl SessionStore {
+    pub fn load(&self) -> Vec<Session> {
+        fn helper() {}
+        Vec::new()
+    }
+}
+
+impl Drop for SessionStore {
+    fn drop(&mut self) {}
+}
+
+pub fn open_store() -> SessionStore {
+    todo!()
+}
+"#;
+        let (summary, symbols) = outline(CodeLanguage::Rust, source);
+        assert_eq!(
+            summary.as_deref(),
+            Some("Session storage Keeps sessions on disk.")
+        );
+        assert_eq!(
+            names(&symbols),
+            vec![
+                (SymbolKind::Struct, "SessionStore", None),
+                (SymbolKind::Impl, "SessionStore", None),
+                (SymbolKind::Method, "load", Some("SessionStore")),
+                (SymbolKind::Impl, "Drop for SessionStore", None),
+                (SymbolKind::Method, "drop", Some("Drop for SessionStore")),
+                (SymbolKind::Function, "open_store", None),
+            ]
+        );
+        let load = &symbols[2];
+        assert_eq!((load.start_line, load.end_line), (9, 12));
+    }
+
+    #[test]
+    fn extracts_python_and_typescript_definitions() {
+        let python = "\"\"\"Billing helpers.\"\"\"\n\nclass Invoice:\n    def total(self):\n        return 0\n\ndef render(invoice):\n    pass\n";
+        let (summary, symbols) = outline(CodeLanguage::Python, python);
+        assert_eq!(summary.as_deref(), Some("Billing helpers."));
+        assert_eq!(
+            names(&symbols),
+            vec![
+                (SymbolKind::Class, "Invoice", None),
+                (SymbolKind::Method, "total", Some("Invoice")),
+                (SymbolKind::Function, "render", None),
+            ]
+        );
+
+        let typescript = "export interface Props { id: string }\nexport const Button = (props: Props) => null;\nclass Store {\n  load() {}\n}\n";
+        let (_, symbols) = outline(CodeLanguage::TypeScript, typescript);
+        assert_eq!(
+            names(&symbols),
+            vec![
+                (SymbolKind::Interface, "Props", None),
+                (SymbolKind::Function, "Button", None),
+                (SymbolKind::Class, "Store", None),
+                (SymbolKind::Method, "load", Some("Store")),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_skips_license_headers_and_code() {
+        let source = "// Copyright 2024 Example\n// Parses config files.\nuse std::fs;\n// not part of the summary\n";
+        assert_eq!(
+            leading_summary(CodeLanguage::Rust, source).as_deref(),
+            Some("Parses config files.")
+        );
+        assert_eq!(leading_summary(CodeLanguage::Go, "package main\n"), None);
+    }
+}
@@ -0,0 +1,551 @@
+//! Per-workspace index lifecycle: background builds, incremental updates and persistence
+
+use super::index::CodeIndex;
+use super::outline::{CodeLanguage, OutlineExtractor};
+use super::types::{CodeIndexStatus, CodeSearchHit, FileOutline};
+use crate::infrastructure::filesystem::file_watcher::{
+    get_global_file_watcher, FileWatchEvent, FileWatchEventKind, FileWatchListener,
+};
+use crate::infrastructure::filesystem::ignore_rules::configure_walk;
+use crate::infrastructure::filesystem::{
+    register_cache_consumer, try_get_path_manager_arc, CacheType, IgnoreRules,
+};
+use dashmap::DashMap;
+use ignore::WalkBuilder;
+use log::{debug, info, warn};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+const INDEX_FILE_NAME: &str = "outline.json";
+
+static GLOBAL_CODE_INDEX_SERVICE: OnceLock<Arc<CodeIndexService>> = OnceLock::new();
+
+/// Bounds on what a workspace index covers
+#[derive(Debug, Clone, Copy)]
+pub struct CodeIndexLimits {
+    /// Files indexed per workspace; the rest is reported as truncated
+    pub max_files: usize,
+    /// Larger files (usually generated or vendored) are skipped
+    pub max_file_bytes: u64,
+}
+
+impl Default for CodeIndexLimits {
+    fn default() -> Self {
+        Self {
+            max_files: 20_000,
+            max_file_bytes: 512 * 1024,
+        }
+    }
+}
+
+#[derive(Default)]
+struct BuildState {
+    building: bool,
+    cancel: Option<CancellationToken>,
+    /// Paths changed while a build was running, applied once it finishes
+    pending: HashSet<PathBuf>,
+}
+
+/// Code index of one workspace
+pub struct WorkspaceCodeIndex {
+    root: PathBuf,
+    cache_file: Option<PathBuf>,
+    limits: CodeIndexLimits,
+    index: RwLock<CodeIndex>,
+    state: Mutex<BuildState>,
+    /// Files visited by the running build
+    progress: AtomicUsize,
+    ready: watch::Sender<bool>,
+}
+
+impl WorkspaceCodeIndex {
+    pub fn new(root: PathBuf, cache_file: Option<PathBuf>, limits: CodeIndexLimits) -> Self {
+        let index = cache_file
+            .as_deref()
+            .and_then(CodeIndex::load)
+            .unwrap_or_default();
+        Self {
+            root,
+            cache_file,
+            limits,
+            index: RwLock::new(index),
+            state: Mutex::new(BuildState::default()),
+            progress: AtomicUsize::new(0),
+            ready: watch::channel(false).0,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn status(&self) -> CodeIndexStatus {
+        let mut status = self.read_index().status();
+        status.building = self.lock_state().building;
+        status.ready = self.is_ready();
+        status
+    }
+
+    /// A build has completed, so the index covers the whole workspace
+    pub fn is_ready(&self) -> bool {
+        *self.ready.borrow()
+    }
+
+    /// Files visited so far by the running build
+    pub fn progress(&self) -> usize {
+        self.progress.load(Ordering::Relaxed)
+    }
+
+    /// Starts a background build unless one is running or has completed.
+    /// Returns whether a build was started.
+    pub fn ensure_started(self: &Arc<Self>) -> bool {
+        if self.is_ready() {
+            return false;
+        }
+        self.start_build()
+    }
+
+    /// Re-scans the whole workspace, e.g. after the ignore rules changed
+    pub fn rebuild(self: &Arc<Self>) -> bool {
+        self.start_build()
+    }
+
+    /// Stops a running build; the index keeps its previous contents
+    pub fn cancel(&self) {
+        if let Some(token) = &self.lock_state().cancel {
+            token.cancel();
+        }
+    }
+
+    /// Waits up to `timeout` for the running build to complete
+    pub async fn wait_until_ready(&self, timeout: Duration) -> bool {
+        let mut ready = self.ready.subscribe();
+        let outcome = tokio::time::timeout(timeout, ready.wait_for(|ready| *ready)).await;
+        matches!(outcome, Ok(Ok(_)))
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<CodeSearchHit> {
+        self.read_index().search(query, limit)
+    }
+
+    /// Project overview for priming a session; `None` until a build has completed
+    pub fn project_map(&self, max_chars: usize) -> Option<String> {
+        if !self.is_ready() {
+            return None;
+        }
+        let index = self.read_index();
+        (!index.is_empty()).then(|| index.project_map(max_chars))
+    }
+
+    /// Re-indexes or drops changed paths. Changes arriving during a build are
+    /// queued; before the first build they are dropped, since it will see them.
+    pub fn on_paths_changed(self: &Arc<Self>, paths: Vec<PathBuf>) {
+        {
+            let mut state = self.lock_state();
+            if state.building {
+                state.pending.extend(paths);
+                return;
+            }
+        }
+        if !self.is_ready() {
+            return;
+        }
+        let this = Arc::clone(self);
+        tokio::task::spawn_blocking(move || this.apply_changes(paths));
+    }
+
+    fn start_build(self: &Arc<Self>) -> bool {
+        let token = {
+            let mut state = self.lock_state();
+            if state.building {
+                return false;
+            }
+            let token = CancellationToken::new();
+            state.building = true;
+            state.cancel = Some(token.clone());
+            token
+        };
+
+        let this = Arc::clone(self);
+        tokio::spawn(async move {
+            let builder = Arc::clone(&this);
+            let completed = tokio::task::spawn_blocking(move || builder.build(&token))
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "Code index build panicked: root={}, error={}",
+                        this.root.display(),
+                        e
+                    );
+                    false
+                });
+
+            let pending: Vec<PathBuf> = {
+                let mut state = this.lock_state();
+                state.building = false;
+                state.cancel = None;
+                state.pending.drain().collect()
+            };
+            if completed {
+                this.ready.send_replace(true);
+                if !pending.is_empty() {
+                    let updater = Arc::clone(&this);
+                    let _ =
+                        tokio::task::spawn_blocking(move || updater.apply_changes(pending)).await;
+                }
+            }
+        });
+        true
+    }
+
+    /// Walks the workspace and replaces the index. Outlines of files whose
+    /// size and modification time are unchanged are reused. Returns `false`
+    /// when cancelled, leaving the index as it was.
+    fn build(&self, token: &CancellationToken) -> bool {
+        let started = std::time::Instant::now();
+        self.progress.store(0, Ordering::Relaxed);
+        let previous = self.read_index().clone();
+        let mut next = CodeIndex::default();
+        let mut extractor = OutlineExtractor::new();
+        let mut truncated = false;
+
+        let mut walker = WalkBuilder::new(&self.root);
+        configure_walk(&mut walker, false).hidden(true);
+        for entry in walker.build() {
+            if token.is_cancelled() {
+                debug!("Code index build cancelled: root={}", self.root.display());
+                return false;
+            }
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                continue;
+            }
+            let path = entry.path();
+            let Some(language) = CodeLanguage::from_path(path) else {
+                continue;
+            };
+            if next.len() >= self.limits.max_files {
+                truncated = true;
+                break;
+            }
+            self.progress.fetch_add(1, Ordering::Relaxed);
+            let Some(relative) = self.relative_path(path) else {
+                continue;
+            };
+            let cached = previous.get(&relative);
+            if let Some(outline) =
+                self.outline_file(path, relative.clone(), language, cached, &mut extractor)
+            {
+                next.upsert(outline);
+            }
+        }
+        next.set_truncated(truncated);
+
+        info!(
+            "Code index built: root={}, files={}, truncated={}, duration_ms={}",
+            self.root.display(),
+            next.len(),
+            truncated,
+            started.elapsed().as_millis()
+        );
+        *self.write_index() = next;
+        self.save();
+        true
+    }
+
+    fn apply_changes(&self, paths: Vec<PathBuf>) {
+        let mut extractor = OutlineExtractor::new();
+        let mut changed = false;
+        for path in paths {
+            let Some(relative) = self.relative_path(&path) else {
+                continue;
+            };
+            if path.is_file() {
+                let Some(language) = CodeLanguage::from_path(&path) else {
+                    continue;
+                };
+                if self.is_ignored(&path) {
+                    continue;
+                }
+                let mut index = self.write_index();
+                if index.get(&relative).is_none() && index.len() >= self.limits.max_files {
+                    index.set_truncated(true);
+                    continue;
+                }
+                let previous = index.get(&relative).cloned();
+                drop(index);
+                match self.outline_file(
+                    &path,
+                    relative.clone(),
+                    language,
+                    previous.as_ref(),
+                    &mut extractor,
+                ) {
+                    Some(outline) => self.write_index().upsert(outline),
+                    None => self.write_index().remove(&relative),
+                }
+                changed = true;
+            } else if !path.exists() {
+                self.write_index().remove(&relative);
+                changed = true;
+            }
+        }
+        if changed {
+            self.save();
+        }
+    }
+
+    fn outline_file(
+        &self,
+        path: &Path,
+        relative: String,
+        language: CodeLanguage,
+        previous: Option<&FileOutline>,
+        extractor: &mut OutlineExtractor,
+    ) -> Option<FileOutline> {
+        let metadata = std::fs::metadata(path).ok()?;
+        if metadata.len() > self.limits.max_file_bytes {
+            return None;
+        }
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        if let Some(previous) = previous {
+            if previous.size == metadata.len() && previous.modified_ms == modified_ms {
+                return Some(previous.clone());
+            }
+        }
+
+        // Non-UTF-8 files are almost never hand-written source
+        let source = std::fs::read_to_string(path).ok()?;
+        let (summary, symbols) = extractor.extract(language, &source)?;
+        Some(FileOutline {
+            path: relative,
+            language: language.name().to_string(),
+            size: metadata.len(),
+            modified_ms,
+            summary,
+            symbols,
+        })
+    }
+
+    /// Same exclusions as the build walk: hidden entries and anything matched
+    /// by the ignore files of `path`'s directories
+    fn is_ignored(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        let mut rules = IgnoreRules::for_dir(&self.root);
+        let mut current = self.root.clone();
+        let components: Vec<_> = relative.components().collect();
+        for (i, component) in components.iter().enumerate() {
+            current.push(component);
+            let is_dir = i + 1 < components.len();
+            let hidden = component.as_os_str().to_string_lossy().starts_with('.');
+            if hidden || rules.is_ignored(&current, is_dir) {
+                return true;
+            }
+            if is_dir {
+                rules = rules.child(&current);
+            }
+        }
+        false
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let parts: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("/"))
+    }
+
+    fn save(&self) {
+        let Some(cache_file) = &self.cache_file else {
+            return;
+        };
+        if let Err(e) = self.read_index().save(cache_file) {
+            warn!(
+                "Failed to save code index: path={}, error={}",
+                cache_file.display(),
+                e
+            );
+        }
+    }
+
+    fn read_index(&self) -> std::sync::RwLockReadGuard<'_, CodeIndex> {
+        self.index.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_index(&self) -> std::sync::RwLockWriteGuard<'_, CodeIndex> {
+        self.index.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, BuildState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Code indexes of all open workspaces
+pub struct CodeIndexService {
+    workspaces: DashMap<PathBuf, Arc<WorkspaceCodeIndex>>,
+    limits: CodeIndexLimits,
+}
+
+impl CodeIndexService {
+    pub fn new(limits: CodeIndexLimits) -> Self {
+        Self {
+            workspaces: DashMap::new(),
+            limits,
+        }
+    }
+
+    /// Index of the workspace at `root`, created (from its cache, if any) on first use.
+    /// Call [`WorkspaceCodeIndex::ensure_started`] to build it.
+    pub fn workspace(&self, root: &Path) -> Arc<WorkspaceCodeIndex> {
+        let root = dunce::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        self.workspaces
+            .entry(root.clone())
+            .or_insert_with(|| {
+                let cache_file = try_get_path_manager_arc().ok().map(|path_manager| {
+                    path_manager
+                        .workspace_cache_dir(&root, CacheType::Index)
+                        .join(INDEX_FILE_NAME)
+                });
+                Arc::new(WorkspaceCodeIndex::new(root, cache_file, self.limits))
+            })
+            .clone()
+    }
+
+    /// Cancels any build for `root` and forgets its index; the cache file stays
+    pub fn close_workspace(&self, root: &Path) {
+        let root = dunce::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        if let Some((_, workspace)) = self.workspaces.remove(&root) {
+            workspace.cancel();
+        }
+    }
+}
+
+impl FileWatchListener for CodeIndexService {
+    fn on_file_events(&self, events: &[FileWatchEvent]) {
+        if self.workspaces.is_empty() {
+            return;
+        }
+        let mut paths = Vec::new();
+        for event in events {
+            match &event.kind {
+                FileWatchEventKind::Rename { from, to } => {
+                    paths.push(PathBuf::from(from));
+                    paths.push(PathBuf::from(to));
+                }
+                _ => paths.push(PathBuf::from(&event.path)),
+            }
+        }
+
+        for workspace in self.workspaces.iter() {
+            let changed: Vec<PathBuf> = paths
+                .iter()
+                .filter(|path| path.starts_with(workspace.root()))
+                .cloned()
+                .collect();
+            if !changed.is_empty() {
+                workspace.on_paths_changed(changed);
+            }
+        }
+    }
+}
+
+/// Process-wide code index service, subscribed to file watch events
+pub fn get_global_code_index_service() -> Arc<CodeIndexService> {
+    GLOBAL_CODE_INDEX_SERVICE
+        .get_or_init(|| {
+            let service = Arc::new(CodeIndexService::new(CodeIndexLimits::default()));
+            if let Ok(path_manager) = try_get_path_manager_arc() {
+                register_cache_consumer(
+                    "code-index",
+                    CacheType::Index,
+                    path_manager.cache_dir(CacheType::Index),
+                );
+            }
+            let listener: Weak<dyn FileWatchListener> = Arc::downgrade(&service) as Weak<_>;
+            get_global_file_watcher().register_listener(listener);
+            service
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(dir: &Path) -> Arc<WorkspaceCodeIndex> {
+        Arc::new(WorkspaceCodeIndex::new(
+            dir.to_path_buf(),
+            None,
+            CodeIndexLimits::default(),
+        ))
+    }
+
+    #[test]
+    fn build_skips_ignored_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn answer() -> u32 { 42 }\n").unwrap();
+        std::fs::write(root.join("target/gen.rs"), "pub fn generated() {}\n").unwrap();
+
+        let index = workspace(root);
+        assert!(index.build(&CancellationToken::new()));
+        let status = index.read_index().status();
+        assert_eq!(status.files, 1);
+        assert_eq!(index.search("answer", 5)[0].path, "src/lib.rs");
+        assert!(index.search("generated", 5).is_empty());
+        assert!(index.is_ignored(&root.join("target/gen.rs")));
+        assert!(!index.is_ignored(&root.join("src/lib.rs")));
+    }
+
+    #[test]
+    fn cancelled_build_keeps_previous_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("main.py"), "def main():\n    pass\n").unwrap();
+
+        let index = workspace(dir);
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(!index.build(&token));
+        assert!(index.read_index().is_empty());
+    }
+
+    #[test]
+    fn applies_file_changes_incrementally() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let file = dir.join("util.go");
+        std::fs::write(&file, "package util\n\nfunc Helper() {}\n").unwrap();
+
+        let index = workspace(dir);
+        assert!(index.build(&CancellationToken::new()));
+        assert_eq!(index.search("Helper", 5).len(), 1);
+
+        std::fs::remove_file(&file).unwrap();
+        index.apply_changes(vec![file]);
+        assert!(index.search("Helper", 5).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+//! Code index data types
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Class,
+    Enum,
+    Interface,
+    Trait,
+    Impl,
+    Module,
+    TypeAlias,
+    Constant,
+    Macro,
+}
+
+impl SymbolKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Function => "fn",
+            Self::Method => "method",
+            Self::Struct => "struct",
+            Self::Class => "class",
+            Self::Enum => "enum",
+            Self::Interface => "interface",
+            Self::Trait => "trait",
+            Self::Impl => "impl",
+            Self::Module => "mod",
+            Self::TypeAlias => "type",
+            Self::Constant => "const",
+            Self::Macro => "macro",
+        }
+    }
+
+    /// Functions and methods; their bodies are not searched for nested symbols
+    pub fn is_callable(&self) -> bool {
+        matches!(self, Self::Function | Self::Method)
+    }
+}
+
+/// A named definition in a source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Enclosing type, impl or module, e.g. the struct a method belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    /// First line of the definition (1-based)
+    pub start_line: u32,
+    /// Last line of the definition (1-based, inclusive)
+    pub end_line: u32,
+}
+
+/// Outline of one indexed file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileOutline {
+    /// Path relative to the workspace root, `/`-separated
+    pub path: String,
+    pub language: String,
+    pub size: u64,
+    /// Modification time in milliseconds since the epoch; unchanged files are not re-parsed
+    pub modified_ms: u64,
+    /// Leading doc comment of the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub symbols: Vec<CodeSymbol>,
+}
+
+/// One search result: a symbol, or a whole file when only its path or summary matched
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CodeSearchHit {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<CodeSymbol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub score: i64,
+}
+
+/// Index size and progress
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CodeIndexStatus {
+    pub files: usize,
+    pub symbols: usize,
+    pub building: bool,
+    /// A build has completed, so searches cover the whole workspace
+    pub ready: bool,
+    /// The file limit was hit; some files are not indexed
+    pub truncated: bool,
+}
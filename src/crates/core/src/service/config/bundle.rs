@@ -0,0 +1,694 @@
+//! Settings bundles
+//!
+//! A settings bundle is a single JSON file that carries the user config
+//! (models, MCP servers, permission rules, themes and profiles) to another
+//! machine. Secrets are left out unless requested, and are then encrypted with
+//! a passphrase (Argon2id + AES-256-GCM). Importing can be run as a dry run
+//! that only reports the changes it would make.
+
+use super::global::{ConfigUpdateEvent, GlobalConfigManager};
+use super::hot_reload;
+use super::migrations::{self, CURRENT_SCHEMA_VERSION};
+use super::secrets;
+use super::service::ConfigService;
+use crate::infrastructure::ai::AIClientFactory;
+use crate::infrastructure::secrets::{resolve_secret, secrets_store};
+use crate::service::mcp::config::parse_cursor_format;
+use crate::service::mcp::{MCPConfigService, MCPService};
+use crate::util::errors::*;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// `format` value identifying a settings bundle.
+pub const BUNDLE_FORMAT: &str = "bitfun-settings";
+/// Bundle layout version written by this build.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Keys that describe this machine rather than the user's settings; never
+/// exported, and always kept from the local config on import.
+const LOCAL_PATHS: &[&str] = &["version", "last_modified", "ai.known_tools"];
+
+const KDF_ARGON2ID: &str = "argon2id";
+const KEY_SIZE: usize = 32;
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+
+/// Settings bundle file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    /// Always [`BUNDLE_FORMAT`].
+    pub format: String,
+    pub bundle_version: u32,
+    /// App version that wrote the bundle.
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    /// User config without machine-specific keys; secrets are redacted.
+    pub settings: Value,
+    /// Redacted secrets by config path, encrypted with the export passphrase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<EncryptedBundleSecrets>,
+}
+
+/// Passphrase-encrypted secrets of a bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBundleSecrets {
+    pub kdf: String,
+    pub salt: String,
+    pub nonce: String,
+    pub data: String,
+}
+
+/// How imported settings combine with the current ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Bundle values win; settings and MCP servers missing from the bundle are kept.
+    /// Lists of items with an `id` (such as models) are merged by id.
+    #[default]
+    Merge,
+    /// The bundle replaces the settings; MCP servers missing from it are removed.
+    Replace,
+}
+
+/// Options for [`import_settings`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportSettingsOptions {
+    pub strategy: MergeStrategy,
+    /// Passphrase for the bundle's secrets; without it they are skipped.
+    pub passphrase: Option<String>,
+    /// Only report what would change.
+    pub dry_run: bool,
+}
+
+/// Result of exporting a settings bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsExportSummary {
+    pub path: PathBuf,
+    /// Number of secrets included (encrypted) in the bundle.
+    pub secrets_included: usize,
+}
+
+/// One changed config value; secrets are redacted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsChange {
+    /// Dot-path of the value; lists are reported as a whole.
+    pub path: String,
+    /// Value before the import; `None` when added.
+    pub before: Option<Value>,
+    /// Value after the import; `None` when removed.
+    pub after: Option<Value>,
+}
+
+/// Result of importing (or dry-running the import of) a settings bundle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingsImportReport {
+    pub changes: Vec<SettingsChange>,
+    /// MCP servers from the bundle, added or updated.
+    pub mcp_servers: Vec<String>,
+    /// MCP servers removed because the bundle replaces the config.
+    pub mcp_servers_removed: Vec<String>,
+    /// Config paths whose secret was imported into the secrets store.
+    pub secrets_imported: Vec<String>,
+    /// Config paths whose secret is not in the bundle and not set locally; left empty.
+    pub missing_secrets: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Whether the changes were written; `false` for a dry run.
+    pub applied: bool,
+}
+
+/// Writes the user config to a settings bundle at `path`.
+///
+/// With `passphrase`, the secrets (plaintext or in the secrets store) are
+/// included, encrypted with it; otherwise they are left out.
+pub async fn export_settings(
+    config_service: &ConfigService,
+    path: &Path,
+    passphrase: Option<&str>,
+) -> BitFunResult<SettingsExportSummary> {
+    let config = config_service.export_config_value().await?;
+    let (bundle, secrets_included) = build_bundle(config, passphrase, &resolve_secret)?;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| {
+            BitFunError::io(format!("Failed to create directory {:?}: {}", parent, e))
+        })?;
+    }
+    tokio::fs::write(path, serde_json::to_string_pretty(&bundle)?)
+        .await
+        .map_err(|e| {
+            BitFunError::io(format!("Failed to write settings bundle {:?}: {}", path, e))
+        })?;
+
+    info!(
+        "Settings exported: path={:?} secrets_included={}",
+        path, secrets_included
+    );
+    Ok(SettingsExportSummary {
+        path: path.to_path_buf(),
+        secrets_included,
+    })
+}
+
+/// Imports the settings bundle at `path`, or with `dry_run` only reports the changes.
+///
+/// MCP servers from the bundle are registered through `mcp_service` when it
+/// is running, the same way servers added in the app are, so they start
+/// right away; otherwise they are only saved to the config.
+pub async fn import_settings(
+    config_service: &Arc<ConfigService>,
+    mcp_service: Option<&MCPService>,
+    path: &Path,
+    options: &ImportSettingsOptions,
+) -> BitFunResult<SettingsImportReport> {
+    let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+        BitFunError::io(format!("Failed to read settings bundle {:?}: {}", path, e))
+    })?;
+    let bundle = open_bundle(&content)?;
+    let mut report = SettingsImportReport::default();
+
+    let bundle_schema = migrations::schema_version(&bundle.settings);
+    if bundle_schema > CURRENT_SCHEMA_VERSION {
+        report.warnings.push(format!(
+            "The bundle was written by a newer version ({}); settings this version does not know are ignored",
+            bundle.app_version
+        ));
+    }
+    let incoming = migrations::migrate(bundle.settings)?;
+
+    let bundled_secrets = match (&bundle.secrets, options.passphrase.as_deref()) {
+        (Some(encrypted), Some(passphrase)) => decrypt_secrets(encrypted, passphrase)?,
+        (Some(_), None) => {
+            report
+                .warnings
+                .push("The bundle contains secrets; they are skipped without a passphrase".into());
+            BTreeMap::new()
+        }
+        (None, _) => BTreeMap::new(),
+    };
+
+    let current = config_service.export_config_value().await?;
+    let mut merged = merge_settings(&current, incoming.clone(), options.strategy);
+
+    let store = if options.dry_run || bundled_secrets.is_empty() {
+        None
+    } else {
+        Some(secrets_store()?)
+    };
+    report.secrets_imported =
+        secrets::place_secrets(&mut merged, &bundled_secrets, store.as_deref())?;
+    secrets::restore_redacted(&mut merged, &current);
+    report.missing_secrets = secrets::clear_redacted(&mut merged);
+    report.changes = diff_settings(&current, &merged);
+
+    let bundle_servers = server_ids(&incoming);
+    let current_servers = server_ids(&current);
+    report.mcp_servers = bundle_servers.iter().cloned().collect();
+    if options.strategy == MergeStrategy::Replace {
+        report.mcp_servers_removed = current_servers
+            .difference(&bundle_servers)
+            .cloned()
+            .collect();
+    }
+
+    if options.dry_run {
+        return Ok(report);
+    }
+
+    // MCP servers are registered one by one below rather than written with the rest
+    let merged_servers = merged.get("mcp_servers").cloned().unwrap_or(Value::Null);
+    match current.get("mcp_servers") {
+        Some(servers) => merged["mcp_servers"] = servers.clone(),
+        None => {
+            if let Some(obj) = merged.as_object_mut() {
+                obj.remove("mcp_servers");
+            }
+        }
+    }
+    config_service.import_config_value(merged).await?;
+
+    register_mcp_servers(
+        config_service,
+        mcp_service,
+        &merged_servers,
+        &bundle_servers,
+        &report.mcp_servers_removed,
+        &mut report.warnings,
+    )
+    .await?;
+
+    if let Ok(factory) = AIClientFactory::get_global().await {
+        factory.invalidate_cache();
+    }
+    GlobalConfigManager::broadcast_update(ConfigUpdateEvent::ConfigReloaded).await;
+
+    report.applied = true;
+    info!(
+        "Settings imported: path={:?} changes={} mcp_servers={} secrets={}",
+        path,
+        report.changes.len(),
+        report.mcp_servers.len(),
+        report.secrets_imported.len()
+    );
+    Ok(report)
+}
+
+/// Builds a bundle from the persisted user config `config`.
+///
+/// Secrets are redacted; with `passphrase` their values (resolved with
+/// `resolve`) are encrypted into the bundle. Returns the bundle and the
+/// number of secrets included.
+pub fn build_bundle(
+    mut config: Value,
+    passphrase: Option<&str>,
+    resolve: &dyn Fn(&str) -> BitFunResult<String>,
+) -> BitFunResult<(SettingsBundle, usize)> {
+    if passphrase.is_some_and(str::is_empty) {
+        return Err(BitFunError::validation(
+            "A passphrase is required to include secrets in the bundle",
+        ));
+    }
+    for path in LOCAL_PATHS {
+        remove_path(&mut config, path);
+    }
+
+    let secrets = match passphrase {
+        Some(passphrase) => {
+            let taken = secrets::take_secrets(&mut config, Some(resolve))?;
+            let count = taken.len();
+            let encrypted = (count > 0)
+                .then(|| encrypt_secrets(&taken, passphrase))
+                .transpose()?;
+            (encrypted, count)
+        }
+        None => {
+            secrets::take_secrets(&mut config, None)?;
+            (None, 0)
+        }
+    };
+
+    Ok((
+        SettingsBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            bundle_version: BUNDLE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            settings: config,
+            secrets: secrets.0,
+        },
+        secrets.1,
+    ))
+}
+
+/// Parses a bundle file, rejecting other files and newer bundle layouts.
+pub fn open_bundle(content: &str) -> BitFunResult<SettingsBundle> {
+    let bundle: SettingsBundle = serde_json::from_str(content)
+        .map_err(|e| BitFunError::validation(format!("Not a valid settings bundle: {}", e)))?;
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(BitFunError::validation(format!(
+            "Not a settings bundle (format '{}')",
+            bundle.format
+        )));
+    }
+    if bundle.bundle_version > BUNDLE_VERSION {
+        return Err(BitFunError::validation(format!(
+            "The settings bundle was written by a newer version ({}, bundle version {}); update BitFun to import it",
+            bundle.app_version, bundle.bundle_version
+        )));
+    }
+    if !bundle.settings.is_object() {
+        return Err(BitFunError::validation(
+            "The settings bundle has no settings object",
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Combines the current user config with imported settings.
+pub fn merge_settings(current: &Value, incoming: Value, strategy: MergeStrategy) -> Value {
+    let mut merged = match strategy {
+        MergeStrategy::Merge => merge_value(current.clone(), incoming),
+        MergeStrategy::Replace => incoming,
+    };
+    for path in LOCAL_PATHS {
+        remove_path(&mut merged, path);
+        if let Some(local) = lookup(current, path) {
+            set_path(&mut merged, path, local);
+        }
+    }
+    merged
+}
+
+/// Lists the values that differ between two configs, with secrets redacted.
+pub fn diff_settings(before: &Value, after: &Value) -> Vec<SettingsChange> {
+    let mut before = before.clone();
+    let mut after = after.clone();
+    secrets::redact_secrets(&mut before);
+    secrets::redact_secrets(&mut after);
+
+    hot_reload::changed_paths(&before, &after)
+        .into_iter()
+        .filter(|path| !LOCAL_PATHS.contains(&path.as_str()))
+        .map(|path| SettingsChange {
+            before: lookup(&before, &path),
+            after: lookup(&after, &path),
+            path,
+        })
+        .collect()
+}
+
+/// Deep merge where `overlay` wins; lists whose items all have an `id` are merged by id.
+fn merge_value(base: Value, overlay: Value) -> Value {
+    match (base, overlay) {
+        (Value::Object(mut base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                let merged = match base.remove(&key) {
+                    Some(base_value) => merge_value(base_value, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (Value::Array(mut base), Value::Array(overlay))
+            if base
+                .iter()
+                .chain(&overlay)
+                .all(|item| item_id(item).is_some()) =>
+        {
+            for item in overlay {
+                match base.iter().position(|b| item_id(b) == item_id(&item)) {
+                    Some(index) => {
+                        let existing = base[index].take();
+                        base[index] = merge_value(existing, item);
+                    }
+                    None => base.push(item),
+                }
+            }
+            Value::Array(base)
+        }
+        (_, overlay) => overlay,
+    }
+}
+
+/// Saves the bundle's MCP servers (with their secrets already placed in
+/// `servers`) and removes `removed`.
+async fn register_mcp_servers(
+    config_service: &Arc<ConfigService>,
+    mcp_service: Option<&MCPService>,
+    servers: &Value,
+    bundle_servers: &BTreeSet<String>,
+    removed: &[String],
+    warnings: &mut Vec<String>,
+) -> BitFunResult<()> {
+    let configs: Vec<_> = parse_cursor_format(servers)?
+        .into_iter()
+        .filter(|config| bundle_servers.contains(&config.id))
+        .collect();
+
+    let Some(mcp_service) = mcp_service else {
+        let mcp_config = MCPConfigService::new(config_service.clone())?;
+        for config in &configs {
+            mcp_config.save_server_config(config).await?;
+        }
+        for id in removed {
+            mcp_config.delete_server_config(id).await?;
+        }
+        return Ok(());
+    };
+
+    let manager = mcp_service.server_manager();
+    for config in configs {
+        let id = config.id.clone();
+        let exists = mcp_service
+            .config_service()
+            .get_server_config(&id)
+            .await?
+            .is_some();
+        let result = if exists {
+            manager.update_server_config(config).await
+        } else {
+            manager.add_server(config).await
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to register imported MCP server: id={} error={}",
+                id, e
+            );
+            warnings.push(format!("MCP server '{}': {}", id, e));
+        }
+    }
+    for id in removed {
+        if let Err(e) = manager.remove_server(id).await {
+            warnings.push(format!("MCP server '{}' was not removed: {}", id, e));
+        }
+    }
+    Ok(())
+}
+
+/// IDs of the MCP servers in a config.
+fn server_ids(config: &Value) -> BTreeSet<String> {
+    config
+        .pointer("/mcp_servers/mcpServers")
+        .and_then(Value::as_object)
+        .map(|servers| servers.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn encrypt_secrets(
+    secrets: &BTreeMap<String, String>,
+    passphrase: &str,
+) -> BitFunResult<EncryptedBundleSecrets> {
+    let mut salt = [0u8; SALT_SIZE];
+    let mut nonce = [0u8; NONCE_SIZE];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = passphrase_cipher(passphrase, &salt)?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            serde_json::to_vec(secrets)?.as_ref(),
+        )
+        .map_err(|_| BitFunError::service("Failed to encrypt bundle secrets"))?;
+
+    Ok(EncryptedBundleSecrets {
+        kdf: KDF_ARGON2ID.to_string(),
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        data: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt_secrets(
+    encrypted: &EncryptedBundleSecrets,
+    passphrase: &str,
+) -> BitFunResult<BTreeMap<String, String>> {
+    if encrypted.kdf != KDF_ARGON2ID {
+        return Err(BitFunError::validation(format!(
+            "Unsupported bundle secrets key derivation '{}'",
+            encrypted.kdf
+        )));
+    }
+    let salt = decode(&encrypted.salt)?;
+    let nonce = decode(&encrypted.nonce)?;
+    if nonce.len() != NONCE_SIZE {
+        return Err(BitFunError::validation("Corrupt bundle secrets: bad nonce"));
+    }
+    let plaintext = passphrase_cipher(passphrase, &salt)?
+        .decrypt(Nonce::from_slice(&nonce), decode(&encrypted.data)?.as_ref())
+        .map_err(|_| {
+            BitFunError::validation(
+                "Wrong passphrase for the bundle secrets, or the bundle is corrupt",
+            )
+        })?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+fn passphrase_cipher(passphrase: &str, salt: &[u8]) -> BitFunResult<Aes256Gcm> {
+    if passphrase.is_empty() {
+        return Err(BitFunError::validation(
+            "A passphrase is required to encrypt or decrypt bundle secrets",
+        ));
+    }
+    let mut key = [0u8; KEY_SIZE];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| BitFunError::service(format!("Failed to derive bundle key: {}", e)))?;
+    Aes256Gcm::new_from_slice(&key)
+        .map_err(|_| BitFunError::service("Invalid bundle key".to_string()))
+}
+
+fn decode(value: &str) -> BitFunResult<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| BitFunError::validation(format!("Corrupt bundle secrets: {}", e)))
+}
+
+fn item_id(item: &Value) -> Option<&str> {
+    item.get("id").and_then(Value::as_str)
+}
+
+fn lookup(value: &Value, path: &str) -> Option<Value> {
+    path.split('.')
+        .try_fold(value, |value, key| value.get(key))
+        .filter(|value| !value.is_null())
+        .cloned()
+}
+
+fn remove_path(value: &mut Value, path: &str) {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (
+            parent
+                .split('.')
+                .try_fold(&mut *value, |value, key| value.get_mut(key)),
+            key,
+        ),
+        None => (Some(value), path),
+    };
+    if let Some(Value::Object(map)) = parent {
+        map.remove(key);
+    }
+}
+
+fn set_path(value: &mut Value, path: &str, new_value: Value) {
+    let mut target = value;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        if !target.is_object() {
+            *target = Value::Object(Map::new());
+        }
+        let map = target.as_object_mut().expect("just made an object");
+        if keys.peek().is_none() {
+            map.insert(key.to_string(), new_value);
+            return;
+        }
+        target = map
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> Value {
+        json!({
+            "schema_version": CURRENT_SCHEMA_VERSION,
+            "version": "1.0.0",
+            "last_modified": 1,
+            "ai": {
+                "known_tools": ["Read"],
+                "models": [
+                    { "id": "gpt-4o", "api_key": "sk-plain", "max_tokens": 100 },
+                    { "id": "claude", "api_key": "keyring:claude" }
+                ]
+            },
+            "theme": { "id": "dark" }
+        })
+    }
+
+    #[test]
+    fn exports_without_secrets_or_local_keys() {
+        let resolve = |_: &str| -> BitFunResult<String> { panic!("secrets are not resolved") };
+        let (bundle, included) = build_bundle(config(), None, &resolve).unwrap();
+        assert_eq!(included, 0);
+        assert!(bundle.secrets.is_none());
+        assert!(bundle.settings.get("version").is_none());
+        assert!(bundle.settings["ai"].get("known_tools").is_none());
+        assert_eq!(
+            bundle.settings["ai"]["models"][0]["api_key"],
+            secrets::REDACTED_SECRET
+        );
+        assert_eq!(
+            bundle.settings["ai"]["models"][1]["api_key"],
+            secrets::REDACTED_SECRET
+        );
+    }
+
+    #[test]
+    fn encrypts_secrets_with_the_passphrase() {
+        let resolve = |value: &str| Ok(value.replace("keyring:", "sk-"));
+        let (bundle, included) = build_bundle(config(), Some("correct horse"), &resolve).unwrap();
+        assert_eq!(included, 2);
+
+        let content = serde_json::to_string(&bundle).unwrap();
+        assert!(!content.contains("sk-plain"));
+        let bundle = open_bundle(&content).unwrap();
+        let encrypted = bundle.secrets.as_ref().unwrap();
+        let secrets = decrypt_secrets(encrypted, "correct horse").unwrap();
+        assert_eq!(secrets["ai.models.gpt-4o.api_key"], "sk-plain");
+        assert_eq!(secrets["ai.models.claude.api_key"], "sk-claude");
+        assert!(decrypt_secrets(encrypted, "wrong").is_err());
+    }
+
+    #[test]
+    fn rejects_other_files_and_newer_bundles() {
+        assert!(open_bundle(r#"{"theme": {}}"#).is_err());
+        let newer = json!({
+            "format": BUNDLE_FORMAT,
+            "bundle_version": BUNDLE_VERSION + 1,
+            "app_version": "9.0.0",
+            "created_at": "2026-01-01T00:00:00Z",
+            "settings": {}
+        });
+        assert!(open_bundle(&newer.to_string()).is_err());
+    }
+
+    #[test]
+    fn merges_by_id_and_keeps_local_keys() {
+        let incoming = json!({
+            "version": "0.1.0",
+            "ai": {
+                "models": [
+                    { "id": "claude", "max_tokens": 200 },
+                    { "id": "local", "api_key": "" }
+                ]
+            }
+        });
+
+        let merged = merge_settings(&config(), incoming.clone(), MergeStrategy::Merge);
+        let ids: Vec<_> = merged["ai"]["models"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["gpt-4o", "claude", "local"]);
+        assert_eq!(merged["ai"]["models"][1]["api_key"], "keyring:claude");
+        assert_eq!(merged["ai"]["models"][1]["max_tokens"], 200);
+        assert_eq!(merged["theme"]["id"], "dark");
+        assert_eq!(merged["version"], "1.0.0");
+
+        let replaced = merge_settings(&config(), incoming, MergeStrategy::Replace);
+        assert_eq!(replaced["ai"]["models"].as_array().unwrap().len(), 2);
+        assert!(replaced.get("theme").is_none());
+        assert_eq!(replaced["version"], "1.0.0");
+        assert_eq!(replaced["ai"]["known_tools"], json!(["Read"]));
+    }
+
+    #[test]
+    fn diff_lists_changes_with_secrets_redacted() {
+        let mut after = config();
+        after["theme"]["id"] = json!("light");
+        after["ai"]["models"][0]["api_key"] = json!("sk-other");
+        after["last_modified"] = json!(2);
+
+        let changes = diff_settings(&config(), &after);
+        let paths: Vec<_> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths.len(), 1, "{:?}", paths);
+        assert_eq!(changes[0].path, "theme.id");
+        assert_eq!(changes[0].before, Some(json!("dark")));
+        assert_eq!(changes[0].after, Some(json!("light")));
+    }
+}
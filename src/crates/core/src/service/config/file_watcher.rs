@@ -0,0 +1,131 @@
+//! Debounced watching of a single config file
+//!
+//! Editors often save in several steps (truncate, write, rename), so change
+//! events are collected until the file has been quiet for the debounce
+//! interval before the reload callback runs.
+
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+
+/// Reload waiting for the debounce interval, with the time of the first change it covers.
+type PendingReload = Arc<Mutex<Option<(JoinHandle<()>, DateTime<Utc>)>>>;
+
+/// Calls a reload callback when a config file changes.
+#[derive(Default)]
+pub struct ConfigFileWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    pending_reload: PendingReload,
+}
+
+impl ConfigFileWatcher {
+    /// Watches `file`, replacing any previous watch.
+    ///
+    /// The file's directory is watched, or `fallback_dir` until that directory
+    /// exists, so creating the file is noticed too. `on_change` receives the
+    /// time of the first change since the last reload.
+    pub fn watch<F, Fut>(&self, file: &Path, fallback_dir: &Path, debounce: Duration, on_change: F)
+    where
+        F: Fn(DateTime<Utc>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut guard = lock(&self.watcher);
+        *guard = None;
+
+        let Some(dir) = file.parent() else {
+            return;
+        };
+        let target = if dir.is_dir() { dir } else { fallback_dir };
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = match RecommendedWatcher::new(tx, Config::default()) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("Failed to create config file watcher: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(target, RecursiveMode::NonRecursive) {
+            warn!(
+                "Failed to watch config file: path={} error={}",
+                target.display(),
+                e
+            );
+            return;
+        }
+        *guard = Some(watcher);
+        debug!("Watching config file: {}", file.display());
+
+        let file = file.to_path_buf();
+        let dir = dir.to_path_buf();
+        let pending_reload = self.pending_reload.clone();
+        let on_change = Arc::new(on_change);
+        let runtime = tokio::runtime::Handle::current();
+        // Ends when the watcher (and with it the sender) is dropped
+        tokio::task::spawn_blocking(move || {
+            while let Ok(result) = rx.recv() {
+                match result {
+                    Ok(event) if event.paths.iter().any(|p| *p == file || *p == dir) => {
+                        let _enter = runtime.enter();
+                        Self::schedule_reload(&pending_reload, debounce, on_change.clone());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Config file watcher error: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Stops watching and drops any pending reload.
+    pub fn unwatch(&self) {
+        *lock(&self.watcher) = None;
+        if let Some((task, _)) = lock(&self.pending_reload).take() {
+            task.abort();
+        }
+    }
+
+    fn schedule_reload<F, Fut>(
+        pending_reload: &PendingReload,
+        debounce: Duration,
+        on_change: Arc<F>,
+    ) where
+        F: Fn(DateTime<Utc>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut pending = lock(pending_reload);
+        let first_change = match pending.take() {
+            Some((task, first_change)) if !task.is_finished() => {
+                task.abort();
+                first_change
+            }
+            _ => Utc::now(),
+        };
+
+        let task = tokio::spawn({
+            let pending_reload = pending_reload.clone();
+            async move {
+                sleep(debounce).await;
+                lock(&pending_reload).take();
+                on_change(first_change).await;
+            }
+        });
+        *pending = Some((task, first_change));
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl std::fmt::Debug for ConfigFileWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigFileWatcher").finish()
+    }
+}
@@ -2,9 +2,19 @@
 //!
 //! Provides a global configuration service instance with dynamic updates and synchronization.
 
+use super::hot_reload::ConfigFileChange;
 use super::service::ConfigService;
+use super::types::AppStorageConfig;
+use crate::infrastructure::ai::AIClientFactory;
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
+use crate::infrastructure::storage::{set_compression_settings, set_default_storage_backend};
+use crate::service::workspace::get_global_workspace_service;
 use crate::util::errors::*;
+use bitfun_transport::ProfileEventPayload;
+use chrono::{DateTime, Utc};
 use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::OnceLock;
 use tokio::sync::RwLock;
@@ -53,6 +63,28 @@ pub enum ConfigUpdateEvent {
         /// New runtime log level.
         new_level: String,
     },
+    /// Per-target log level overrides updated.
+    LogTargetLevelsUpdated {
+        /// Level per log target prefix.
+        target_levels: HashMap<String, String>,
+    },
+    /// Active config profile switched.
+    ProfileSwitched {
+        /// The new profile; `None` for the base config.
+        profile: Option<String>,
+    },
+    /// Workspace config overrides re-evaluated (workspace switched or file changed).
+    WorkspaceConfigChanged {
+        /// Root of the active workspace; `None` when no workspace is open.
+        workspace_path: Option<String>,
+    },
+    /// User config file edited outside the app and reloaded.
+    ConfigFileChanged {
+        /// Dot-paths of the changed effective values.
+        changed_keys: Vec<String>,
+        /// Changed keys that only take effect after a restart.
+        requires_restart: Vec<String>,
+    },
 }
 
 /// Global configuration service manager.
@@ -72,6 +104,18 @@ impl GlobalConfigManager {
         })?;
 
         let config_service = Arc::new(ConfigService::new().await?);
+        config_service.watch_config_file().await;
+        // Storage created from here on uses the configured backend and compression
+        match config_service
+            .get_config::<AppStorageConfig>(Some("app.storage"))
+            .await
+        {
+            Ok(storage) => {
+                set_default_storage_backend(storage.backend);
+                set_compression_settings(storage.compression_settings());
+            }
+            Err(e) => warn!("Failed to read app.storage: {}", e),
+        }
         let service_wrapper = Arc::new(RwLock::new(Some(config_service)));
 
         GLOBAL_CONFIG_SERVICE.set(service_wrapper).map_err(|_| {
@@ -80,6 +124,14 @@ impl GlobalConfigManager {
 
         info!("Global config service initialized");
 
+        if let Some(workspace_service) = get_global_workspace_service() {
+            if let Some(workspace) = workspace_service.get_current_workspace().await {
+                if let Err(e) = Self::set_workspace(Some(&workspace.root_path)).await {
+                    warn!("Failed to apply workspace config: {}", e);
+                }
+            }
+        }
+
         match super::tool_config_sync::sync_tool_configs().await {
             Ok(report) => {
                 if !report.new_tools.is_empty() || !report.deleted_tools.is_empty() {
@@ -118,9 +170,19 @@ impl GlobalConfigManager {
             BitFunError::config("Global config service not initialized".to_string())
         })?;
 
-        {
+        let workspace_root = {
             let mut service_guard = service_wrapper.write().await;
-            *service_guard = Some(new_service);
+            let previous = service_guard.replace(new_service.clone());
+            previous.and_then(|service| {
+                service.unwatch_config_file();
+                service.workspace_root()
+            })
+        };
+        new_service.watch_config_file().await;
+        if let Some(root) = workspace_root {
+            if let Err(e) = new_service.set_workspace(Some(&root)).await {
+                warn!("Failed to apply workspace config: {}", e);
+            }
         }
 
         Self::broadcast_update(ConfigUpdateEvent::ConfigReloaded).await;
@@ -184,6 +246,126 @@ impl GlobalConfigManager {
         Ok(())
     }
 
+    /// Switches the active profile and notifies open sessions.
+    ///
+    /// Cached AI clients are dropped so the next turn of every session
+    /// resolves its model and provider from the new profile.
+    pub async fn switch_profile(name: Option<&str>) -> BitFunResult<()> {
+        let service = Self::get_service().await?;
+        let (_, previous) = service.list_profiles().await;
+        service.switch_profile(name).await?;
+
+        if let Ok(factory) = AIClientFactory::get_global().await {
+            factory.invalidate_cache();
+        }
+
+        Self::broadcast_update(ConfigUpdateEvent::ProfileSwitched {
+            profile: name.map(str::to_string),
+        })
+        .await;
+
+        let workspace_path = get_global_workspace_service()
+            .and_then(|service| service.try_get_current_workspace_path())
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let payload = ProfileEventPayload {
+            workspace_path,
+            event_data: serde_json::json!({
+                "action": "switched",
+                "profile": name,
+                "previous": previous,
+            }),
+        };
+        if let Err(e) = emit_global_event(BackendEvent::Custom {
+            event_name: "config-profile-switched".to_string(),
+            payload: serde_json::to_value(payload)?,
+        })
+        .await
+        {
+            warn!("Failed to emit profile switch event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the config overrides of workspace `root`; `None` clears them.
+    ///
+    /// No-op until the config service is initialized, which applies the
+    /// current workspace itself.
+    pub async fn set_workspace(root: Option<&Path>) -> BitFunResult<()> {
+        if !Self::is_initialized() {
+            return Ok(());
+        }
+        let service = Self::get_service().await?;
+        service.set_workspace(root).await?;
+
+        Self::broadcast_update(ConfigUpdateEvent::WorkspaceConfigChanged {
+            workspace_path: root.map(|root| root.to_string_lossy().to_string()),
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Re-reads the current workspace's config file after it changed.
+    pub async fn refresh_workspace_config() -> BitFunResult<()> {
+        let service = Self::get_service().await?;
+        service.refresh_workspace().await?;
+
+        Self::broadcast_update(ConfigUpdateEvent::WorkspaceConfigChanged {
+            workspace_path: service
+                .workspace_root()
+                .map(|root| root.to_string_lossy().to_string()),
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Applies an edit of the user config file made outside the app.
+    ///
+    /// Emits `config-changed` to the frontend with the changed keys and the
+    /// ones that need a restart to take effect.
+    pub async fn apply_config_file_change(changed_at: DateTime<Utc>) -> BitFunResult<()> {
+        let service = Self::get_service().await?;
+        let Some(change) = service.apply_config_file_change(changed_at).await? else {
+            return Ok(());
+        };
+        info!(
+            "Config file reloaded: changed_keys={:?} requires_restart={:?}",
+            change.changed_keys, change.requires_restart
+        );
+
+        if change.changed_keys.iter().any(|key| key.starts_with("ai.")) {
+            if let Ok(factory) = AIClientFactory::get_global().await {
+                factory.invalidate_cache();
+            }
+        }
+
+        let ConfigFileChange {
+            changed_keys,
+            requires_restart,
+        } = change;
+        let payload = serde_json::json!({
+            "source": "file",
+            "changed_keys": changed_keys,
+            "requires_restart": requires_restart,
+        });
+        Self::broadcast_update(ConfigUpdateEvent::ConfigFileChanged {
+            changed_keys,
+            requires_restart,
+        })
+        .await;
+
+        if let Err(e) = emit_global_event(BackendEvent::Custom {
+            event_name: "config-changed".to_string(),
+            payload,
+        })
+        .await
+        {
+            warn!("Failed to emit config change event: {}", e);
+        }
+        Ok(())
+    }
+
     /// Returns whether the configuration service has been initialized.
     pub fn is_initialized() -> bool {
         GLOBAL_CONFIG_SERVICE.get().is_some()
@@ -0,0 +1,185 @@
+//! Hot reload of the user config file
+//!
+//! Hand edits to `app.json` are picked up while the app runs: the file is
+//! re-read and validated, and the keys whose effective value changed are
+//! reported so the frontend and services can apply them. Keys only read at
+//! startup are reported as requiring a restart.
+
+use crate::util::errors::*;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tokio::time::{sleep, Duration};
+
+/// Debounce before an external change to the config file is applied.
+pub const RELOAD_DEBOUNCE_MS: u64 = 350;
+/// Attempts at parsing the file; a failure may be an editor's partial write.
+const PARSE_ATTEMPTS: u32 = 3;
+const PARSE_RETRY_DELAY_MS: u64 = 200;
+
+/// Config sections that are only applied at startup (or when a connection is
+/// created, like MCP server transports), so changing them needs a restart.
+pub const RESTART_REQUIRED_PATHS: &[&str] =
+    &["mcp_servers", "app.startup_behavior", "app.restore_windows"];
+
+/// Keys whose changes are bookkeeping rather than settings.
+const IGNORED_PATHS: &[&str] = &["last_modified", "schema_version", "version"];
+
+/// Effective config keys changed by an edit of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigFileChange {
+    /// Dot-paths of the changed values; arrays are reported as a whole.
+    pub changed_keys: Vec<String>,
+    /// Changed keys that only take effect after a restart.
+    pub requires_restart: Vec<String>,
+}
+
+impl ConfigFileChange {
+    /// Builds the change between two effective configs; `None` when nothing changed.
+    pub fn between(old: &Value, new: &Value) -> Option<Self> {
+        let changed_keys: Vec<String> = changed_paths(old, new)
+            .into_iter()
+            .filter(|key| !IGNORED_PATHS.contains(&key.as_str()))
+            .collect();
+        if changed_keys.is_empty() {
+            return None;
+        }
+        let requires_restart = changed_keys
+            .iter()
+            .filter(|key| requires_restart(key))
+            .cloned()
+            .collect();
+        Some(Self {
+            changed_keys,
+            requires_restart,
+        })
+    }
+}
+
+/// Returns the dot-paths of the leaf values that differ between `old` and `new`.
+pub fn changed_paths(old: &Value, new: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_changes(old, new, "", &mut paths);
+    paths
+}
+
+fn collect_changes(old: &Value, new: &Value, path: &str, paths: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let keys = old_map
+                .keys()
+                .chain(new_map.keys().filter(|key| !old_map.contains_key(*key)));
+            for key in keys {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                let null = Value::Null;
+                collect_changes(
+                    old_map.get(key).unwrap_or(&null),
+                    new_map.get(key).unwrap_or(&null),
+                    &child_path,
+                    paths,
+                );
+            }
+        }
+        _ if old != new => paths.push(path.to_string()),
+        _ => {}
+    }
+}
+
+/// Returns whether changing `key` only takes effect after a restart.
+pub fn requires_restart(key: &str) -> bool {
+    RESTART_REQUIRED_PATHS.iter().any(|path| {
+        key == *path
+            || key
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+/// Reads and parses the config file, retrying parse failures in case an
+/// editor is still writing it. Returns `None` when the file does not exist.
+pub async fn read_config_file(path: &Path) -> BitFunResult<Option<(String, Value)>> {
+    let mut attempt = 1;
+    loop {
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(BitFunError::config(format!(
+                    "Failed to read config file {:?}: {}",
+                    path, e
+                )))
+            }
+        };
+        match serde_json::from_str(&content) {
+            Ok(value) => return Ok(Some((content, value))),
+            Err(e) if attempt < PARSE_ATTEMPTS => {
+                debug!("Config file not parseable yet (attempt {}): {}", attempt, e);
+                attempt += 1;
+                sleep(Duration::from_millis(PARSE_RETRY_DELAY_MS)).await;
+            }
+            Err(e) => {
+                return Err(BitFunError::config(format!(
+                    "Failed to parse config file {:?} as JSON: {}",
+                    path, e
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_changed_leaf_paths() {
+        let old = json!({
+            "app": { "language": "en-US", "logging": { "level": "info" } },
+            "ai": { "models": [{ "id": "a" }], "proxy": { "enabled": false } },
+            "last_modified": 1
+        });
+        let new = json!({
+            "app": { "language": "en-US", "logging": { "level": "debug" } },
+            "ai": { "models": [{ "id": "b" }], "proxy": { "enabled": false, "url": "x" } },
+            "last_modified": 2
+        });
+        let mut paths = changed_paths(&old, &new);
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                "ai.models",
+                "ai.proxy.url",
+                "app.logging.level",
+                "last_modified"
+            ]
+        );
+
+        let change = ConfigFileChange::between(&old, &new).unwrap();
+        assert!(!change.changed_keys.contains(&"last_modified".to_string()));
+        assert!(change.requires_restart.is_empty());
+        assert_eq!(ConfigFileChange::between(&old, &old), None);
+    }
+
+    #[test]
+    fn flags_keys_that_need_a_restart() {
+        assert!(requires_restart("mcp_servers"));
+        assert!(requires_restart("mcp_servers.mcpServers.github.url"));
+        assert!(requires_restart("app.restore_windows"));
+        assert!(!requires_restart("app.restore_windows_extra"));
+        assert!(!requires_restart("app.logging.level"));
+
+        let change = ConfigFileChange::between(
+            &json!({ "mcp_servers": null, "theme": { "id": "dark" } }),
+            &json!({ "mcp_servers": { "mcpServers": {} }, "theme": { "id": "light" } }),
+        )
+        .unwrap();
+        assert_eq!(change.requires_restart, ["mcp_servers"]);
+    }
+}
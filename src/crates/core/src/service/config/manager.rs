@@ -2,14 +2,26 @@
 //!
 //! A complete configuration management system based on the Provider mechanism.
 
+use super::hot_reload::ConfigFileChange;
+use super::migrations::{self, CURRENT_SCHEMA_VERSION};
+use super::profiles;
 use super::providers::ConfigProviderRegistry;
+use super::schema;
+use super::secrets::{self, PlaintextSecret};
+use super::sources::{self, ConfigSource};
 use super::types::*;
+use super::workspace_overrides::{self, WorkspaceOverrides};
+use crate::infrastructure::ai::request_log;
+use crate::infrastructure::offline;
+use crate::infrastructure::secrets::secrets_store;
 use crate::infrastructure::{try_get_path_manager_arc, PathManager};
 use crate::util::errors::*;
-use log::{debug, info, warn};
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::fs;
@@ -17,9 +29,21 @@ use tokio::fs;
 /// Configuration manager.
 pub struct ConfigManager {
     config_dir: PathBuf,
+    /// Persisted config: the base values plus all profiles.
     config: GlobalConfig,
+    /// `config` with the active profile and workspace overrides applied; what readers see.
+    effective: GlobalConfig,
+    /// Overrides from the active workspace's `.bitfun/config.json`.
+    workspace: Option<WorkspaceOverrides>,
     providers: ConfigProviderRegistry,
     config_file: PathBuf,
+    /// Content of `config_file` as last loaded or saved, to tell external edits from our own writes.
+    synced_content: Option<String>,
+    /// When the config was last saved from memory.
+    saved_at: Option<DateTime<Utc>>,
+    /// Keys of a config written by a newer version that this version does not
+    /// know; merged back in on save so a downgrade does not drop them.
+    newer_keys: Option<Value>,
     path_manager: Arc<PathManager>,
 }
 
@@ -59,12 +83,32 @@ impl ConfigManager {
         let mut manager = Self {
             config_dir,
             config: GlobalConfig::default(),
+            effective: GlobalConfig::default(),
+            workspace: None,
             providers,
             config_file,
+            synced_content: None,
+            saved_at: None,
+            newer_keys: None,
             path_manager,
         };
 
         manager.load_or_create_config().await?;
+        if let Err(e) = manager.refresh_effective() {
+            warn!("{}; using the base config", e);
+            manager.effective = manager.config.clone();
+        }
+        request_log::apply_config(&manager.effective.ai.request_log);
+        offline::apply_config(manager.effective.app.offline_mode);
+        match manager.find_plaintext_secrets() {
+            Ok(found) if !found.is_empty() => warn!(
+                "{} secret(s) stored in plaintext in {:?}; move them into the secrets store with `bitfun config secrets --migrate` or from Settings",
+                found.len(),
+                manager.config_file
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to scan config for plaintext secrets: {}", e),
+        }
 
         debug!("ConfigManager initialized at {:?}", manager.config_file);
         Ok(manager)
@@ -75,6 +119,11 @@ impl ConfigManager {
         &self.path_manager
     }
 
+    /// Returns the path of the user config file.
+    pub fn config_file(&self) -> &std::path::Path {
+        &self.config_file
+    }
+
     /// Loads or creates the configuration file.
     async fn load_or_create_config(&mut self) -> BitFunResult<()> {
         if self.config_file.exists() {
@@ -84,6 +133,7 @@ impl ConfigManager {
             Self::add_default_agent_models_config(&mut self.config.ai.agent_models);
             Self::add_default_func_agent_models_config(&mut self.config.ai.func_agent_models);
             self.config.version = env!("CARGO_PKG_VERSION").to_string();
+            self.config.schema_version = CURRENT_SCHEMA_VERSION;
             self.save_config().await?;
             debug!("Created default config file");
         }
@@ -92,10 +142,15 @@ impl ConfigManager {
     }
 
     /// Loads and migrates configuration.
+    ///
+    /// A file with an older schema version is backed up next to itself and
+    /// migrated step by step. A file from a newer version is loaded as far as
+    /// this version understands it, keeping the unknown keys for saving.
     async fn load_and_migrate_config(&mut self) -> BitFunResult<()> {
         let content = fs::read_to_string(&self.config_file)
             .await
             .map_err(|e| BitFunError::config(format!("Failed to read config file: {}", e)))?;
+        self.synced_content = Some(content.clone());
 
         let mut config_value: Value = serde_json::from_str(&content).map_err(|e| {
             BitFunError::config(format!("Failed to parse config file as JSON: {}", e))
@@ -106,26 +161,28 @@ impl ConfigManager {
             .and_then(|v| v.as_str())
             .unwrap_or("0.0.0")
             .to_string();
-
         let current_version = env!("CARGO_PKG_VERSION").to_string();
 
-        let needs_migration = !versions_match(&file_version, &current_version);
+        let schema_version = migrations::schema_version(&config_value);
+        let needs_migration = schema_version < CURRENT_SCHEMA_VERSION;
         if needs_migration {
             info!(
-                "Config version change detected: {} -> {}",
-                file_version, current_version
+                "Config schema version change detected: {} -> {} (written by {})",
+                schema_version, CURRENT_SCHEMA_VERSION, file_version
             );
-            config_value = self
-                .migrate_config_version(&file_version, config_value)
+            self.write_migration_backup(&content, schema_version)
                 .await?;
-
-            if let Some(obj) = config_value.as_object_mut() {
-                obj.insert(
-                    "version".to_string(),
-                    Value::String(current_version.clone()),
-                );
-            }
+            config_value = migrations::migrate(config_value)?;
+        } else if schema_version > CURRENT_SCHEMA_VERSION {
+            warn!(
+                "Config file {:?} was written by a newer version ({}, schema version {} > {}); settings this version does not know are kept but ignored",
+                self.config_file, file_version, schema_version, CURRENT_SCHEMA_VERSION
+            );
         }
+        let needs_save = needs_migration || !versions_match(&file_version, &current_version);
+
+        let report = schema::validate_config_value(&config_value);
+        log_validation_result(&report);
 
         match serde_json::from_value::<GlobalConfig>(config_value.clone()) {
             Ok(mut config) => {
@@ -133,9 +190,13 @@ impl ConfigManager {
                 Self::add_default_agent_models_config(&mut config.ai.agent_models);
                 Self::add_default_func_agent_models_config(&mut config.ai.func_agent_models);
 
+                if schema_version > CURRENT_SCHEMA_VERSION {
+                    self.newer_keys =
+                        migrations::unknown_keys(&config_value, &serde_json::to_value(&config)?);
+                }
                 self.config = config;
 
-                if needs_migration {
+                if needs_save {
                     self.config.version = current_version;
                     self.save_config().await?;
                     info!("Config migrated and saved");
@@ -151,11 +212,43 @@ impl ConfigManager {
                     e
                 );
 
-                self.smart_merge_config_from_value(config_value).await
+                self.smart_merge_config_from_value(config_value)
+                    .await
+                    .map_err(|e| {
+                        if report.valid {
+                            e
+                        } else {
+                            BitFunError::config(format!(
+                                "Invalid config file {:?}: {}",
+                                self.config_file,
+                                describe_errors(&report)
+                            ))
+                        }
+                    })
             }
         }
     }
 
+    /// Copies the config file content to `<file>.v<schema_version>.bak` before it is migrated.
+    async fn write_migration_backup(&self, content: &str, schema_version: u32) -> BitFunResult<()> {
+        let file_name = self
+            .config_file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "app.json".to_string());
+        let backup_file = self
+            .config_file
+            .with_file_name(format!("{}.v{}.bak", file_name, schema_version));
+        fs::write(&backup_file, content).await.map_err(|e| {
+            BitFunError::config(format!(
+                "Failed to write pre-migration config backup {:?}: {}",
+                backup_file, e
+            ))
+        })?;
+        info!("Pre-migration config backup written to {:?}", backup_file);
+        Ok(())
+    }
+
     /// Performs a smart merge from a JSON value.
     async fn smart_merge_config_from_value(&mut self, user_value: Value) -> BitFunResult<()> {
         let base_config = self.providers.get_default_config();
@@ -210,7 +303,13 @@ impl ConfigManager {
     fn add_default_func_agent_models_config(
         func_agent_models: &mut std::collections::HashMap<String, String>,
     ) {
-        let func_agents_using_fast = vec!["compression", "startchat-func-agent", "git-func-agent"];
+        let func_agents_using_fast = vec![
+            "compression",
+            "startchat-func-agent",
+            "git-func-agent",
+            "changelog-func-agent",
+            "pr-description-func-agent",
+        ];
         for key in func_agents_using_fast {
             if !func_agent_models.contains_key(key) {
                 func_agent_models.insert(key.to_string(), "fast".to_string());
@@ -218,31 +317,17 @@ impl ConfigManager {
         }
     }
 
-    /// Migrates configuration versions.
-    async fn migrate_config_version(
-        &self,
-        from_version: &str,
-        mut config: Value,
-    ) -> BitFunResult<Value> {
-        let migrations: Vec<(&str, &str, fn(Value) -> BitFunResult<Value>)> =
-            vec![("0.0.0", "1.0.0", migrate_0_0_0_to_1_0_0)];
-
-        let mut current_version = from_version.to_string();
-
-        for (from, to, migrate_fn) in migrations {
-            if version_gte(&current_version, from) && version_lt(&current_version, to) {
-                debug!("Executing migration: {} -> {}", from, to);
-                config = migrate_fn(config)?;
-                current_version = to.to_string();
-            }
-        }
-
-        Ok(config)
-    }
-
     /// Saves the configuration file.
-    async fn save_config(&self) -> BitFunResult<()> {
-        let content = serde_json::to_string_pretty(&self.config)
+    ///
+    /// An edit of the file not yet picked up by the file watcher is
+    /// overwritten: the last writer wins.
+    async fn save_config(&mut self) -> BitFunResult<()> {
+        let mut value = serde_json::to_value(&self.config)
+            .map_err(|e| BitFunError::config(format!("Config serialization failed: {}", e)))?;
+        if let Some(newer_keys) = &self.newer_keys {
+            value = deep_merge(value, newer_keys.clone());
+        }
+        let content = serde_json::to_string_pretty(&value)
             .map_err(|e| BitFunError::config(format!("Config serialization failed: {}", e)))?;
 
         if let Some(parent) = self.config_file.parent() {
@@ -256,12 +341,26 @@ impl ConfigManager {
             }
         }
 
-        fs::write(&self.config_file, content).await.map_err(|e| {
+        if let (Some(synced), Ok(on_disk)) = (
+            &self.synced_content,
+            fs::read_to_string(&self.config_file).await,
+        ) {
+            if *synced != on_disk {
+                warn!(
+                    "Config file {:?} was edited on disk and not reloaded yet; overwriting the edit with the in-app change",
+                    self.config_file
+                );
+            }
+        }
+
+        fs::write(&self.config_file, &content).await.map_err(|e| {
             BitFunError::config(format!(
                 "Failed to write config file {:?}: {}",
                 self.config_file, e
             ))
         })?;
+        self.synced_content = Some(content);
+        self.saved_at = Some(Utc::now());
         Ok(())
     }
 
@@ -285,18 +384,64 @@ impl ConfigManager {
         T: serde::Serialize,
     {
         let old_config = self.config.clone();
+        let old_effective = self.effective.clone();
         let json_value = serde_json::to_value(value)
             .map_err(|e| BitFunError::config(format!("Failed to serialize config value: {}", e)))?;
 
-        self.set_value_by_path(path, json_value)?;
+        // Values the active profile overrides are edited in the profile, so
+        // what was set is what readers see.
+        let profile = self.config.active_profile.clone().filter(|_| {
+            !profiles::is_base_only(path)
+                && profiles::active_overlay(&self.config)
+                    .is_some_and(|overlay| profiles::overlay_sets(overlay, path))
+        });
+        match profile {
+            Some(name) => self.set_profile_value(&name, path, json_value)?,
+            None => self.set_value_by_path(path, json_value)?,
+        }
         self.config.last_modified = chrono::Utc::now();
+        if let Some(workspace) = self.workspace.as_ref().filter(|workspace| {
+            !path.is_empty() && profiles::overlay_sets(&workspace.overlay, path)
+        }) {
+            warn!(
+                "Config '{}' saved but overridden by workspace config {}",
+                path,
+                workspace.file.display()
+            );
+        }
 
-        if let Err(e) = self.validate_config().await {
+        if let Err(e) = self.refresh_effective() {
             self.config = old_config;
+            self.effective = old_effective;
             return Err(e);
         }
 
-        self.notify_config_changed(path, &old_config).await?;
+        let result = match self.validate_config().await {
+            Ok(result) => result,
+            Err(e) => {
+                self.config = old_config;
+                self.effective = old_effective;
+                return Err(e);
+            }
+        };
+        let rejected: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|e| schema::path_within(&e.path, path) || schema::path_within(path, &e.path))
+            .collect();
+        if !rejected.is_empty() {
+            self.config = old_config;
+            self.effective = old_effective;
+            return Err(BitFunError::validation(
+                rejected
+                    .into_iter()
+                    .map(schema::describe_error)
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ));
+        }
+
+        self.notify_config_changed(path, &old_effective).await?;
 
         self.save_config().await?;
 
@@ -305,7 +450,7 @@ impl ConfigManager {
 
     /// Resets configuration (supports dot-paths).
     pub async fn reset(&mut self, path: Option<&str>) -> BitFunResult<()> {
-        let old_config = self.config.clone();
+        let old_config = self.effective.clone();
 
         if let Some(path) = path {
             let default_config = self.providers.get_default_config();
@@ -316,6 +461,10 @@ impl ConfigManager {
         }
 
         self.config.last_modified = chrono::Utc::now();
+        if let Err(e) = self.refresh_effective() {
+            warn!("{}; using the base config", e);
+            self.effective = self.config.clone();
+        }
 
         if let Some(path) = path {
             self.notify_config_changed(path, &old_config).await?;
@@ -331,14 +480,282 @@ impl ConfigManager {
         Ok(())
     }
 
-    /// Returns the full configuration.
+    /// Returns the full configuration, with the active profile applied.
     pub fn get_config(&self) -> &GlobalConfig {
-        &self.config
+        &self.effective
     }
 
-    /// Validates configuration.
+    /// Names of all profiles, sorted.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.config.profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Name of the active profile; `None` when the base config is in use.
+    pub fn active_profile(&self) -> Option<&str> {
+        self.config.active_profile.as_deref()
+    }
+
+    /// Creates a profile from a partial config (`{}` for an empty one).
+    pub async fn create_profile(&mut self, name: &str, overlay: Value) -> BitFunResult<()> {
+        profiles::validate_profile_name(name)?;
+        if self.config.profiles.contains_key(name) {
+            return Err(BitFunError::validation(format!(
+                "Profile '{}' already exists",
+                name
+            )));
+        }
+        if !overlay.is_object() {
+            return Err(BitFunError::validation(
+                "A profile must be a JSON object".to_string(),
+            ));
+        }
+
+        let report = schema::validate_profile_value(name, &overlay);
+        if !report.valid {
+            return Err(BitFunError::validation(format!(
+                "Invalid profile '{}': {}",
+                name,
+                describe_errors(&report)
+            )));
+        }
+
+        self.config.profiles.insert(name.to_string(), overlay);
+        self.config.last_modified = chrono::Utc::now();
+        self.save_config().await?;
+        info!("Created config profile: {}", name);
+        Ok(())
+    }
+
+    /// Activates profile `name`, or the base config for `None`.
+    pub async fn switch_profile(&mut self, name: Option<&str>) -> BitFunResult<()> {
+        if let Some(name) = name {
+            if !self.config.profiles.contains_key(name) {
+                return Err(BitFunError::NotFound(format!(
+                    "Profile '{}' not found",
+                    name
+                )));
+            }
+        }
+
+        let old_config = self.config.clone();
+        let old_effective = self.effective.clone();
+        self.config.active_profile = name.map(str::to_string);
+        self.config.last_modified = chrono::Utc::now();
+        if let Err(e) = self.refresh_effective() {
+            self.config = old_config;
+            self.effective = old_effective;
+            return Err(e);
+        }
+
+        for provider_name in self.providers.get_provider_names() {
+            self.notify_config_changed(&provider_name, &old_effective)
+                .await?;
+        }
+        self.save_config().await?;
+        info!("Switched config profile: {}", name.unwrap_or("<base>"));
+        Ok(())
+    }
+
+    /// Deletes a profile; deleting the active one switches back to the base config.
+    pub async fn delete_profile(&mut self, name: &str) -> BitFunResult<()> {
+        if !self.config.profiles.contains_key(name) {
+            return Err(BitFunError::NotFound(format!(
+                "Profile '{}' not found",
+                name
+            )));
+        }
+        if self.active_profile() == Some(name) {
+            self.switch_profile(None).await?;
+        }
+
+        self.config.profiles.remove(name);
+        self.config.last_modified = chrono::Utc::now();
+        self.save_config().await?;
+        info!("Deleted config profile: {}", name);
+        Ok(())
+    }
+
+    /// Recomputes `effective` from `config` and the workspace overrides.
+    fn refresh_effective(&mut self) -> BitFunResult<()> {
+        let mut effective = profiles::effective_config(&self.config)?;
+        if let Some(workspace) = &self.workspace {
+            effective = workspace_overrides::apply(&effective, &workspace.overlay)?;
+        }
+        self.effective = effective;
+        Ok(())
+    }
+
+    /// `config` with the active profile applied and `overlay` (another
+    /// workspace's overrides) in place of the active workspace's.
+    pub fn config_with_workspace_overlay(
+        &self,
+        overlay: Option<&Value>,
+    ) -> BitFunResult<GlobalConfig> {
+        let config = profiles::effective_config(&self.config)?;
+        match overlay {
+            Some(overlay) => workspace_overrides::apply(&config, overlay),
+            None => Ok(config),
+        }
+    }
+
+    /// Overrides from the active workspace, if it has a config file.
+    pub fn workspace_overrides(&self) -> Option<&WorkspaceOverrides> {
+        self.workspace.as_ref()
+    }
+
+    /// Replaces the workspace overrides (`None` when no workspace or file).
+    pub async fn set_workspace_overrides(
+        &mut self,
+        overrides: Option<WorkspaceOverrides>,
+    ) -> BitFunResult<()> {
+        let old_workspace = std::mem::replace(&mut self.workspace, overrides);
+        let old_effective = self.effective.clone();
+        if let Err(e) = self.refresh_effective() {
+            self.workspace = old_workspace;
+            self.effective = old_effective;
+            return Err(e);
+        }
+
+        for provider_name in self.providers.get_provider_names() {
+            self.notify_config_changed(&provider_name, &old_effective)
+                .await?;
+        }
+        match &self.workspace {
+            Some(workspace) => info!("Applied workspace config: {}", workspace.file.display()),
+            None => debug!("Workspace config cleared"),
+        }
+        Ok(())
+    }
+
+    /// Source layer of every value in the effective config.
+    pub fn config_sources(&self) -> BitFunResult<BTreeMap<String, ConfigSource>> {
+        let effective = serde_json::to_value(&self.effective)?;
+        let defaults = serde_json::to_value(GlobalConfig::default())?;
+        Ok(sources::annotate_sources(
+            &effective,
+            &defaults,
+            profiles::active_overlay(&self.config),
+            self.workspace.as_ref().map(|workspace| &workspace.overlay),
+        ))
+    }
+
+    /// Sets dot-`path` inside profile `name`.
+    fn set_profile_value(&mut self, name: &str, path: &str, value: Value) -> BitFunResult<()> {
+        let overlay = self
+            .config
+            .profiles
+            .get_mut(name)
+            .ok_or_else(|| BitFunError::NotFound(format!("Profile '{}' not found", name)))?;
+
+        let mut candidate = overlay.clone();
+        profiles::set_in_overlay(&mut candidate, path, value)?;
+        let report = schema::validate_profile_value(name, &candidate);
+        if !report.valid {
+            return Err(BitFunError::validation(describe_errors(&report)));
+        }
+
+        *overlay = candidate;
+        Ok(())
+    }
+
+    /// Validates the in-memory configuration (schema and provider checks).
     pub async fn validate_config(&self) -> BitFunResult<ConfigValidationResult> {
-        self.providers.validate_config(&self.config).await
+        let config_value = serde_json::to_value(&self.config)
+            .map_err(|e| BitFunError::config(format!("Failed to serialize config: {}", e)))?;
+        let mut result = schema::validate_config_value(&config_value);
+        self.append_provider_results(&mut result).await?;
+        Ok(result)
+    }
+
+    /// Validates the config file as stored on disk, including keys the
+    /// in-memory config dropped or repaired when it was loaded, plus the keys
+    /// rejected from the workspace config.
+    pub async fn validate_file(&self) -> BitFunResult<ConfigValidationResult> {
+        let content = match fs::read_to_string(&self.config_file).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return self.validate_config().await;
+            }
+            Err(e) => {
+                return Err(BitFunError::config(format!(
+                    "Failed to read config file: {}",
+                    e
+                )))
+            }
+        };
+
+        let mut result = match serde_json::from_str::<Value>(&content) {
+            Ok(value) => schema::validate_config_value(&value),
+            Err(e) => ConfigValidationResult {
+                valid: false,
+                errors: vec![ConfigValidationError {
+                    path: String::new(),
+                    message: format!("Config file is not valid JSON: {}", e),
+                    code: "INVALID_JSON".to_string(),
+                    severity: "error".to_string(),
+                    value: None,
+                    suggestion: None,
+                }],
+                warnings: Vec::new(),
+            },
+        };
+        self.append_provider_results(&mut result).await?;
+        if let Some(workspace) = &self.workspace {
+            result.warnings.extend(workspace.rejected.iter().cloned());
+        }
+        Ok(result)
+    }
+
+    /// Adds the providers' semantic checks of the in-memory config to `result`.
+    async fn append_provider_results(
+        &self,
+        result: &mut ConfigValidationResult,
+    ) -> BitFunResult<()> {
+        let provider_result = self.providers.validate_config(&self.effective).await?;
+        result.errors.extend(provider_result.errors);
+        result.warnings.extend(provider_result.warnings);
+        result.valid = result.errors.is_empty();
+        Ok(())
+    }
+
+    /// Secrets stored in plaintext in the user config.
+    pub fn find_plaintext_secrets(&self) -> BitFunResult<Vec<PlaintextSecret>> {
+        Ok(secrets::find_plaintext_secrets(&serde_json::to_value(
+            &self.config,
+        )?))
+    }
+
+    /// Moves plaintext secrets (all of them, or those at the paths in `only`)
+    /// into the secrets store, leaving `keyring:` references in the config.
+    pub async fn migrate_plaintext_secrets(
+        &mut self,
+        only: Option<&[String]>,
+    ) -> BitFunResult<Vec<PlaintextSecret>> {
+        let store = secrets_store()?;
+        let mut value = serde_json::to_value(&self.config)?;
+        let moved = secrets::move_plaintext_secrets(&mut value, only, store.as_ref())?;
+        if moved.is_empty() {
+            return Ok(moved);
+        }
+
+        let old_config = std::mem::replace(&mut self.config, serde_json::from_value(value)?);
+        let old_effective = self.effective.clone();
+        self.config.last_modified = chrono::Utc::now();
+        if let Err(e) = self.refresh_effective() {
+            self.config = old_config;
+            self.effective = old_effective;
+            return Err(e);
+        }
+        self.save_config().await?;
+
+        info!(
+            "Moved {} plaintext secret(s) into the {} secrets store",
+            moved.len(),
+            store.backend_name()
+        );
+        Ok(moved)
     }
 
     /// Exports configuration.
@@ -350,6 +767,15 @@ impl ConfigManager {
     /// Imports configuration.
     pub async fn import_config(&mut self, config_data: serde_json::Value) -> BitFunResult<()> {
         let old_config = self.config.clone();
+        let config_data = migrations::migrate(config_data)?;
+
+        let report = schema::validate_config_value(&config_data);
+        if !report.valid {
+            return Err(BitFunError::validation(format!(
+                "Invalid imported config: {}",
+                describe_errors(&report)
+            )));
+        }
 
         let imported_config: GlobalConfig = serde_json::from_value(config_data)
             .map_err(|e| BitFunError::config(format!("Failed to parse imported config: {}", e)))?;
@@ -367,11 +793,17 @@ impl ConfigManager {
             )));
         }
 
+        let old_effective = self.effective.clone();
         self.config = imported_config;
         self.config.last_modified = chrono::Utc::now();
+        if let Err(e) = self.refresh_effective() {
+            self.config = old_config;
+            self.effective = old_effective;
+            return Err(e);
+        }
 
         for provider_name in self.providers.get_provider_names() {
-            self.notify_config_changed(&provider_name, &old_config)
+            self.notify_config_changed(&provider_name, &old_effective)
                 .await?;
         }
 
@@ -381,6 +813,90 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Applies the config file after it was edited outside the app.
+    ///
+    /// `content` and `value` are the file's text and parsed JSON. An invalid
+    /// file is rejected and the current config kept. A change saved from the
+    /// app since `window_start` (the start of the edit's debounce window) is
+    /// overwritten by the file, with a warning.
+    ///
+    /// Returns the changed effective keys; `None` for the app's own writes
+    /// and edits that change nothing.
+    pub async fn apply_external_config(
+        &mut self,
+        content: &str,
+        value: Value,
+        window_start: DateTime<Utc>,
+    ) -> BitFunResult<Option<ConfigFileChange>> {
+        if self.synced_content.as_deref() == Some(content) {
+            return Ok(None);
+        }
+
+        let report = schema::validate_config_value(&value);
+        log_validation_result(&report);
+        if !report.valid {
+            return Err(BitFunError::validation(format!(
+                "Invalid config file {:?}: {}",
+                self.config_file,
+                describe_errors(&report)
+            )));
+        }
+
+        let base_value = serde_json::to_value(self.providers.get_default_config())?;
+        let mut config: GlobalConfig = serde_json::from_value(deep_merge(base_value, value))
+            .map_err(|e| {
+                BitFunError::config(format!("Failed to deserialize edited config: {}", e))
+            })?;
+        Self::ensure_models_config(&mut config.ai.models);
+        Self::add_default_agent_models_config(&mut config.ai.agent_models);
+        Self::add_default_func_agent_models_config(&mut config.ai.func_agent_models);
+
+        let validation_result = self.providers.validate_config(&config).await?;
+        if !validation_result.valid {
+            let error_messages: Vec<String> = validation_result
+                .errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect();
+            return Err(BitFunError::validation(format!(
+                "Invalid config file {:?}: {}",
+                self.config_file,
+                error_messages.join(", ")
+            )));
+        }
+
+        if self
+            .saved_at
+            .is_some_and(|saved_at| saved_at >= window_start)
+        {
+            warn!(
+                "Config file {:?} was edited while a change from the app was being saved; the file edit wins",
+                self.config_file
+            );
+        }
+
+        let old_effective = self.effective.clone();
+        let old_config = std::mem::replace(&mut self.config, config);
+        if let Err(e) = self.refresh_effective() {
+            self.config = old_config;
+            self.effective = old_effective;
+            return Err(e);
+        }
+        self.synced_content = Some(content.to_string());
+
+        let change = ConfigFileChange::between(
+            &serde_json::to_value(&old_effective)?,
+            &serde_json::to_value(&self.effective)?,
+        );
+        if change.is_some() {
+            for provider_name in self.providers.get_provider_names() {
+                self.notify_config_changed(&provider_name, &old_effective)
+                    .await?;
+            }
+        }
+        Ok(change)
+    }
+
     /// Creates a configuration backup.
     pub async fn create_backup(&self) -> BitFunResult<PathBuf> {
         let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
@@ -413,8 +929,8 @@ impl ConfigManager {
     /// Returns configuration statistics.
     pub fn get_statistics(&self) -> ConfigStatistics {
         ConfigStatistics {
-            total_ai_models: self.config.ai.models.len(),
-            has_default_model: self.config.ai.default_models.primary.is_some(),
+            total_ai_models: self.effective.ai.models.len(),
+            has_default_model: self.effective.ai.default_models.primary.is_some(),
             config_directory: self.config_dir.clone(),
             providers_count: self.providers.get_provider_names().len(),
             last_modified: self.config.last_modified,
@@ -423,7 +939,7 @@ impl ConfigManager {
 
     /// Gets a configuration value by dot-path.
     fn get_value_by_path(&self, path: &str) -> BitFunResult<serde_json::Value> {
-        self.get_value_by_path_from_config(&self.config, path)
+        self.get_value_by_path_from_config(&self.effective, path)
     }
 
     /// Gets a configuration value by dot-path from the given config.
@@ -486,6 +1002,22 @@ impl ConfigManager {
             )));
         }
 
+        let report = schema::validate_config_value(&config_value);
+        let rejected: Vec<_> = report
+            .errors
+            .iter()
+            .filter(|e| schema::path_within(&e.path, path))
+            .map(schema::describe_error)
+            .collect();
+        if !rejected.is_empty() {
+            return Err(BitFunError::validation(rejected.join("; ")));
+        }
+        for warning in &report.warnings {
+            if schema::path_within(&warning.path, path) {
+                warn!("Config key '{}': {}", warning.path, warning.message);
+            }
+        }
+
         self.config = serde_json::from_value(config_value).map_err(|e| {
             BitFunError::config(format!("Failed to deserialize updated config: {}", e))
         })?;
@@ -501,16 +1033,18 @@ impl ConfigManager {
     ) -> BitFunResult<()> {
         self.check_and_broadcast_debug_mode_change(old_config).await;
         self.check_and_broadcast_log_level_change(old_config).await;
+        self.check_and_apply_request_log_change(old_config);
+        self.check_and_apply_offline_mode_change(old_config);
 
         self.providers
-            .notify_config_changed(path, old_config, &self.config)
+            .notify_config_changed(path, old_config, &self.effective)
             .await
     }
 
     /// Detects and broadcasts debug-mode configuration changes.
     async fn check_and_broadcast_debug_mode_change(&self, old_config: &GlobalConfig) {
         let old_debug = &old_config.ai.debug_mode_config;
-        let new_debug = &self.config.ai.debug_mode_config;
+        let new_debug = &self.effective.ai.debug_mode_config;
 
         if old_debug.ingest_port != new_debug.ingest_port
             || old_debug.log_path != new_debug.log_path
@@ -535,7 +1069,7 @@ impl ConfigManager {
     /// Detects and broadcasts runtime log-level changes.
     async fn check_and_broadcast_log_level_change(&self, old_config: &GlobalConfig) {
         let old_level = old_config.app.logging.level.trim().to_lowercase();
-        let new_level = self.config.app.logging.level.trim().to_lowercase();
+        let new_level = self.effective.app.logging.level.trim().to_lowercase();
 
         if old_level != new_level {
             debug!(
@@ -547,6 +1081,45 @@ impl ConfigManager {
             GlobalConfigManager::broadcast_update(ConfigUpdateEvent::LogLevelUpdated { new_level })
                 .await;
         }
+
+        let old_target_levels = &old_config.app.logging.target_levels;
+        let new_target_levels = &self.effective.app.logging.target_levels;
+        if old_target_levels != new_target_levels {
+            debug!(
+                "App logging target levels change detected: {:?} -> {:?}",
+                old_target_levels, new_target_levels
+            );
+
+            use super::global::{ConfigUpdateEvent, GlobalConfigManager};
+            GlobalConfigManager::broadcast_update(ConfigUpdateEvent::LogTargetLevelsUpdated {
+                target_levels: new_target_levels.clone(),
+            })
+            .await;
+        }
+    }
+
+    /// Applies AI request-logging changes to the running client immediately.
+    fn check_and_apply_request_log_change(&self, old_config: &GlobalConfig) {
+        let new_request_log = &self.effective.ai.request_log;
+        if old_config.ai.request_log != *new_request_log {
+            debug!(
+                "AI request logging change detected: enabled {} -> {}",
+                old_config.ai.request_log.enabled, new_request_log.enabled
+            );
+            request_log::apply_config(new_request_log);
+        }
+    }
+
+    /// Applies an `app.offline_mode` flip to the running app immediately.
+    fn check_and_apply_offline_mode_change(&self, old_config: &GlobalConfig) {
+        let offline_mode = self.effective.app.offline_mode;
+        if old_config.app.offline_mode != offline_mode {
+            debug!(
+                "Offline mode change detected: {} -> {}",
+                old_config.app.offline_mode, offline_mode
+            );
+            offline::apply_config(offline_mode);
+        }
     }
 }
 
@@ -582,72 +1155,33 @@ pub(crate) fn deep_merge(base: Value, overlay: Value) -> Value {
     }
 }
 
-/// Returns whether two versions match.
-pub(crate) fn versions_match(v1: &str, v2: &str) -> bool {
-    v1 == v2
-}
-
-/// Returns whether `v1 >= v2`.
-pub(crate) fn version_gte(v1: &str, v2: &str) -> bool {
-    parse_version(v1) >= parse_version(v2)
-}
-
-/// Returns whether `v1 < v2`.
-pub(crate) fn version_lt(v1: &str, v2: &str) -> bool {
-    parse_version(v1) < parse_version(v2)
-}
-
-/// Parses a version string into a tuple `(major, minor, patch)`.
-pub(crate) fn parse_version(version: &str) -> (u32, u32, u32) {
-    let parts: Vec<&str> = version.split('.').collect();
-    let major = parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
-    let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-    let patch = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
-    (major, minor, patch)
-}
-
-/// Migration function: `0.0.0 -> 1.0.0`.
-///
-/// This migration is an example showing how to handle configuration upgrades.
-pub(crate) fn migrate_0_0_0_to_1_0_0(mut config: Value) -> BitFunResult<Value> {
-    debug!("Executing config migration: 0.0.0 -> 1.0.0");
-
-    if let Some(app) = config.get_mut("app").and_then(|v| v.as_object_mut()) {
-        if !app.contains_key("ai_experience") {
-            app.insert(
-                "ai_experience".to_string(),
-                serde_json::json!({
-                    "enable_session_title_generation": true,
-                    "enable_welcome_panel_ai_analysis": false
-                }),
-            );
+/// Logs schema problems found while loading the config file.
+fn log_validation_result(result: &ConfigValidationResult) {
+    for warning in &result.warnings {
+        match &warning.suggestion {
+            Some(suggestion) => warn!(
+                "Config key '{}': {} (did you mean \"{}\"?)",
+                warning.path, warning.message, suggestion
+            ),
+            None => warn!("Config key '{}': {}", warning.path, warning.message),
         }
     }
-
-    if let Some(ai) = config.get_mut("ai").and_then(|v| v.as_object_mut()) {
-        if !ai.contains_key("super_agent_models") {
-            ai.insert(
-                "super_agent_models".to_string(),
-                Value::Object(serde_json::Map::new()),
-            );
-        }
-        if !ai.contains_key("sub_agent_models") {
-            ai.insert("sub_agent_models".to_string(), serde_json::json!({}));
-        }
-        if !ai.contains_key("func_agent_models") {
-            let func_keys = ["compression", "startchat-func-agent", "git-func-agent"];
-            let mut fa = serde_json::Map::new();
-            if let Some(am) = ai.get("agent_models").and_then(|v| v.as_object()) {
-                for k in func_keys {
-                    if let Some(v) = am.get(k) {
-                        fa.insert(k.to_string(), v.clone());
-                    }
-                }
-            }
-            ai.insert("func_agent_models".to_string(), Value::Object(fa));
-        }
+    for error in &result.errors {
+        error!("Invalid config value: {}", schema::describe_error(error));
     }
+}
+
+/// Joins all errors of `result` into one message.
+fn describe_errors(result: &ConfigValidationResult) -> String {
+    result
+        .errors
+        .iter()
+        .map(schema::describe_error)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
 
-    debug!("Migration 0.0.0 -> 1.0.0 completed");
-    Ok(config)
+/// Returns whether two versions match.
+pub(crate) fn versions_match(v1: &str, v2: &str) -> bool {
+    v1 == v2
 }
@@ -0,0 +1,297 @@
+//! Config schema versions and migrations
+//!
+//! The config file records the shape it was written in as `schema_version`,
+//! independent of the app version. Loading an older file runs the registered
+//! migrations one version at a time, so a renamed or restructured key is
+//! carried over instead of being ignored. A file from a newer version is
+//! loaded as-is with a warning, and the keys this version does not know are
+//! kept when the file is saved again.
+
+use crate::util::errors::*;
+use log::{debug, info};
+use serde_json::{Map, Value};
+
+/// Schema version written by this build.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Key holding the schema version in the config file.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// One step of the migration chain, from `from` to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub migrate: fn(Value) -> BitFunResult<Value>,
+}
+
+/// Registered migrations, in order; each upgrades by exactly one version.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "add agent model maps and AI experience settings",
+        migrate: migrate_v0_to_v1,
+    },
+    Migration {
+        from: 1,
+        description: "convert the mcp_servers array to the mcpServers map",
+        migrate: migrate_v1_to_v2,
+    },
+];
+
+/// Returns the schema version of a config document; files without one are version 0.
+pub fn schema_version(config: &Value) -> u32 {
+    config
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version.min(u32::MAX as u64) as u32)
+}
+
+/// Upgrades `config` to [`CURRENT_SCHEMA_VERSION`] step by step.
+///
+/// Configs already current, or from a newer version, are returned unchanged.
+pub fn migrate(mut config: Value) -> BitFunResult<Value> {
+    let from = schema_version(&config);
+    let mut version = from;
+    while version < CURRENT_SCHEMA_VERSION {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|migration| migration.from == version)
+            .ok_or_else(|| {
+                BitFunError::config(format!(
+                    "No config migration registered from schema version {}",
+                    version
+                ))
+            })?;
+        debug!(
+            "Migrating config schema {} -> {}: {}",
+            version,
+            version + 1,
+            migration.description
+        );
+        config = (migration.migrate)(config).map_err(|e| {
+            BitFunError::config(format!(
+                "Config migration {} -> {} failed: {}",
+                version,
+                version + 1,
+                e
+            ))
+        })?;
+        version += 1;
+        if let Some(obj) = config.as_object_mut() {
+            obj.insert(SCHEMA_VERSION_KEY.to_string(), Value::from(version));
+        }
+    }
+    if version > from {
+        info!(
+            "Config migrated from schema version {} to {}",
+            from, version
+        );
+    }
+    Ok(config)
+}
+
+/// Returns the parts of `original` that are missing from `known`, the same
+/// document after a round trip through the config types.
+///
+/// Used to keep keys written by a newer version. Arrays are compared as a
+/// whole and never reported.
+pub fn unknown_keys(original: &Value, known: &Value) -> Option<Value> {
+    let (Value::Object(original), Value::Object(known)) = (original, known) else {
+        return None;
+    };
+    let mut unknown = Map::new();
+    for (key, value) in original {
+        match known.get(key) {
+            None => {
+                unknown.insert(key.clone(), value.clone());
+            }
+            Some(known_value) => {
+                if let Some(nested) = unknown_keys(value, known_value) {
+                    unknown.insert(key.clone(), nested);
+                }
+            }
+        }
+    }
+    (!unknown.is_empty()).then_some(Value::Object(unknown))
+}
+
+/// v0 -> v1: fills in the agent model maps and AI experience settings added with 1.0.0.
+fn migrate_v0_to_v1(mut config: Value) -> BitFunResult<Value> {
+    if let Some(app) = config.get_mut("app").and_then(|v| v.as_object_mut()) {
+        if !app.contains_key("ai_experience") {
+            app.insert(
+                "ai_experience".to_string(),
+                serde_json::json!({
+                    "enable_session_title_generation": true,
+                    "enable_welcome_panel_ai_analysis": false
+                }),
+            );
+        }
+    }
+
+    if let Some(ai) = config.get_mut("ai").and_then(|v| v.as_object_mut()) {
+        if !ai.contains_key("super_agent_models") {
+            ai.insert(
+                "super_agent_models".to_string(),
+                Value::Object(serde_json::Map::new()),
+            );
+        }
+        if !ai.contains_key("sub_agent_models") {
+            ai.insert("sub_agent_models".to_string(), serde_json::json!({}));
+        }
+        if !ai.contains_key("func_agent_models") {
+            let func_keys = ["compression", "startchat-func-agent", "git-func-agent"];
+            let mut fa = serde_json::Map::new();
+            if let Some(am) = ai.get("agent_models").and_then(|v| v.as_object()) {
+                for k in func_keys {
+                    if let Some(v) = am.get(k) {
+                        fa.insert(k.to_string(), v.clone());
+                    }
+                }
+            }
+            ai.insert("func_agent_models".to_string(), Value::Object(fa));
+        }
+    }
+
+    Ok(config)
+}
+
+/// Fields of a legacy MCP server entry carried into its Cursor-format entry.
+const CURSOR_ENTRY_FIELDS: &[&str] = &[
+    "enabled",
+    "autoStart",
+    "command",
+    "args",
+    "env",
+    "headers",
+    "url",
+];
+
+/// v1 -> v2: `mcp_servers` as an array of server configs becomes the
+/// Cursor format `{"mcpServers": {"<id>": {...}}}` the MCP service reads.
+fn migrate_v1_to_v2(mut config: Value) -> BitFunResult<Value> {
+    let Some(Value::Array(servers)) = config.get("mcp_servers") else {
+        return Ok(config);
+    };
+
+    let mut entries = Map::new();
+    for (index, server) in servers.iter().enumerate() {
+        let Some(server) = server.as_object() else {
+            continue;
+        };
+        let id = server
+            .get("id")
+            .or_else(|| server.get("name"))
+            .and_then(Value::as_str)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("server-{}", index + 1));
+
+        let mut entry = Map::new();
+        let server_type = match server.get("type").and_then(Value::as_str) {
+            Some("local") => "stdio",
+            Some("remote") => "streamable-http",
+            Some(other) => other,
+            None if server.contains_key("url") => "streamable-http",
+            None => "stdio",
+        };
+        entry.insert("type".to_string(), Value::from(server_type));
+        if let Some(name) = server
+            .get("name")
+            .filter(|name| name.as_str() != Some(id.as_str()))
+        {
+            entry.insert("name".to_string(), name.clone());
+        }
+        for field in CURSOR_ENTRY_FIELDS {
+            if let Some(value) = server.get(*field) {
+                entry.insert(field.to_string(), value.clone());
+            }
+        }
+        entries.insert(id, Value::Object(entry));
+    }
+
+    config["mcp_servers"] = serde_json::json!({ "mcpServers": entries });
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Config files as written by each historical schema version, with the
+    /// result expected after migrating them to the current version.
+    const FIXTURES: &[(&str, &str, &str)] = &[
+        (
+            "v0",
+            include_str!("fixtures/v0.json"),
+            include_str!("fixtures/v0.expected.json"),
+        ),
+        (
+            "v1",
+            include_str!("fixtures/v1.json"),
+            include_str!("fixtures/v1.expected.json"),
+        ),
+        (
+            "v2",
+            include_str!("fixtures/v2.json"),
+            include_str!("fixtures/v2.json"),
+        ),
+    ];
+
+    #[test]
+    fn migrates_every_historical_shape() {
+        for (name, input, expected) in FIXTURES {
+            let input: Value = serde_json::from_str(input).unwrap();
+            let expected: Value = serde_json::from_str(expected).unwrap();
+            let migrated = migrate(input).unwrap();
+            assert_eq!(migrated, expected, "fixture {}", name);
+            assert_eq!(schema_version(&migrated), CURRENT_SCHEMA_VERSION);
+            // Migrating again is a no-op
+            assert_eq!(
+                migrate(migrated.clone()).unwrap(),
+                migrated,
+                "fixture {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn registry_covers_every_version() {
+        for version in 0..CURRENT_SCHEMA_VERSION {
+            assert!(
+                MIGRATIONS.iter().any(|m| m.from == version),
+                "no migration from schema version {}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn leaves_newer_configs_untouched() {
+        let newer = json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1, "mcp_servers": [] });
+        assert_eq!(migrate(newer.clone()).unwrap(), newer);
+    }
+
+    #[test]
+    fn finds_keys_unknown_to_this_version() {
+        let original = json!({
+            "app": { "language": "en-US", "new_flag": true },
+            "ai": { "models": [{ "id": "a", "extra": 1 }] },
+            "future_section": { "a": 1 }
+        });
+        let known = json!({
+            "app": { "language": "en-US" },
+            "ai": { "models": [{ "id": "a" }] }
+        });
+        assert_eq!(
+            unknown_keys(&original, &known),
+            Some(json!({
+                "app": { "new_flag": true },
+                "future_section": { "a": 1 }
+            }))
+        );
+        assert_eq!(unknown_keys(&known, &known), None);
+    }
+}
@@ -2,21 +2,36 @@
 //!
 //! A complete configuration management system based on the Provider mechanism.
 
+pub mod bundle;
 pub mod factory;
+pub mod file_watcher;
 pub mod global;
+pub mod hot_reload;
 pub mod manager;
+pub mod migrations;
+pub mod profiles;
 pub mod providers;
+pub mod schema;
+pub mod secrets;
 pub mod service;
+pub mod sources;
 pub mod tool_config_sync;
 pub mod types;
+pub mod workspace_overrides;
 
+pub use bundle::{
+    ImportSettingsOptions, MergeStrategy, SettingsExportSummary, SettingsImportReport,
+};
 pub use factory::ConfigFactory;
 pub use global::{
     get_global_config_service, initialize_global_config, reload_global_config,
     subscribe_config_updates, ConfigUpdateEvent, GlobalConfigManager,
 };
+pub use hot_reload::ConfigFileChange;
 pub use manager::{ConfigManager, ConfigManagerSettings, ConfigStatistics};
 pub use providers::ConfigProviderRegistry;
+pub use secrets::PlaintextSecret;
 pub use service::{ConfigExport, ConfigHealthStatus, ConfigImportResult, ConfigService};
+pub use sources::{AnnotatedConfig, ConfigSource};
 pub use tool_config_sync::{sync_tool_configs, ModeSyncInfo, SyncReport};
 pub use types::*;
@@ -0,0 +1,242 @@
+//! Configuration profiles
+//!
+//! A profile is a partial config stored under `profiles.<name>` and deep-merged
+//! over the base config while it is the `active_profile`: profile values win,
+//! objects merge key by key, and everything else (arrays included) is replaced.
+
+use super::manager::deep_merge;
+use super::types::GlobalConfig;
+use crate::util::errors::*;
+use serde_json::Value;
+
+/// Name that selects the base config (no profile) wherever a profile name is accepted.
+pub const BASE_PROFILE: &str = "base";
+
+/// Keys that belong to the base config and are never taken from a profile.
+const BASE_ONLY_KEYS: &[&str] = &[
+    "profiles",
+    "active_profile",
+    "schema_version",
+    "version",
+    "last_modified",
+];
+
+/// Returns the config with the active profile (if any) applied.
+pub fn effective_config(config: &GlobalConfig) -> BitFunResult<GlobalConfig> {
+    let Some(overlay) = active_overlay(config) else {
+        return Ok(config.clone());
+    };
+
+    let mut overlay = overlay.clone();
+    if let Some(obj) = overlay.as_object_mut() {
+        obj.retain(|key, _| !is_base_only(key));
+    }
+
+    let base = serde_json::to_value(config)
+        .map_err(|e| BitFunError::config(format!("Failed to serialize config: {}", e)))?;
+    serde_json::from_value(deep_merge(base, overlay)).map_err(|e| {
+        BitFunError::config(format!(
+            "Failed to apply profile '{}': {}",
+            config.active_profile.as_deref().unwrap_or_default(),
+            e
+        ))
+    })
+}
+
+/// Returns whether dot-`path` lies in a key that only the base config holds.
+pub fn is_base_only(path: &str) -> bool {
+    let first = path.split('.').next().unwrap_or_default();
+    first.is_empty() || BASE_ONLY_KEYS.contains(&first)
+}
+
+/// Overlay of the active profile; None when no profile is active.
+pub fn active_overlay(config: &GlobalConfig) -> Option<&Value> {
+    config
+        .active_profile
+        .as_ref()
+        .and_then(|name| config.profiles.get(name))
+}
+
+/// Returns whether the profile `overlay` sets `path` (or something containing it).
+pub fn overlay_sets(overlay: &Value, path: &str) -> bool {
+    let mut current = overlay;
+    for key in path.split('.').filter(|k| !k.is_empty()) {
+        match current.get(key) {
+            Some(value) if !value.is_object() => return true,
+            Some(value) => current = value,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Writes `value` at dot-`path` inside a profile overlay, creating objects on the way.
+pub fn set_in_overlay(overlay: &mut Value, path: &str, value: Value) -> BitFunResult<()> {
+    let keys: Vec<&str> = path.split('.').filter(|k| !k.is_empty()).collect();
+    let Some((last, parents)) = keys.split_last() else {
+        return Err(BitFunError::config(
+            "Cannot replace a whole profile through a config path".to_string(),
+        ));
+    };
+
+    let mut current = overlay;
+    for key in parents {
+        let obj = current.as_object_mut().ok_or_else(|| {
+            BitFunError::config(format!("Profile path '{}' is not an object", path))
+        })?;
+        current = obj
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+    }
+
+    current
+        .as_object_mut()
+        .ok_or_else(|| BitFunError::config(format!("Profile path '{}' is not an object", path)))?
+        .insert(last.to_string(), value);
+    Ok(())
+}
+
+/// Checks a new profile name.
+pub fn validate_profile_name(name: &str) -> BitFunResult<()> {
+    if name.trim().is_empty() {
+        return Err(BitFunError::validation(
+            "Profile name cannot be empty".to_string(),
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(BitFunError::validation(format!(
+            "Profile name '{}' may only contain letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    if name == BASE_PROFILE {
+        return Err(BitFunError::validation(format!(
+            "'{}' is reserved for the base config",
+            BASE_PROFILE
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::config::types::AIModelConfig;
+    use serde_json::json;
+
+    fn model(id: &str) -> AIModelConfig {
+        AIModelConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            provider: "openai".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn config_with_profiles() -> GlobalConfig {
+        let mut config = GlobalConfig::default();
+        config.ai.models = vec![model("work-model"), model("home-model")];
+        config.ai.default_models.primary = Some("home-model".to_string());
+        config.ai.default_models.fast = Some("home-model".to_string());
+        config.ai.skip_tool_confirmation = true;
+        config.profiles.insert(
+            "work".to_string(),
+            json!({
+                "ai": {
+                    "default_models": { "primary": "work-model" },
+                    "skip_tool_confirmation": false
+                },
+                "active_profile": "ignored"
+            }),
+        );
+        config
+    }
+
+    #[test]
+    fn profile_values_override_base_and_objects_deep_merge() {
+        let mut config = config_with_profiles();
+        config.active_profile = Some("work".to_string());
+
+        let effective = effective_config(&config).unwrap();
+        assert_eq!(
+            effective.ai.default_models.primary.as_deref(),
+            Some("work-model")
+        );
+        // Sibling keys of the overridden object come from the base
+        assert_eq!(
+            effective.ai.default_models.fast.as_deref(),
+            Some("home-model")
+        );
+        assert!(!effective.ai.skip_tool_confirmation);
+        assert_eq!(effective.ai.models.len(), 2);
+        // Base-only keys are never taken from the overlay
+        assert_eq!(effective.active_profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn base_config_applies_without_an_active_profile() {
+        let config = config_with_profiles();
+        let effective = effective_config(&config).unwrap();
+        assert_eq!(
+            effective.ai.default_models.primary.as_deref(),
+            Some("home-model")
+        );
+        assert!(effective.ai.skip_tool_confirmation);
+    }
+
+    #[test]
+    fn switching_applies_on_the_next_resolution_only() {
+        let mut config = config_with_profiles();
+
+        // A running session resolved its model for the current turn
+        let current_turn = effective_config(&config).unwrap();
+        let turn_model = current_turn.ai.resolve_model_selection("primary");
+
+        config.active_profile = Some("work".to_string());
+        let next_turn = effective_config(&config).unwrap();
+
+        assert_eq!(turn_model.as_deref(), Some("home-model"));
+        assert_eq!(
+            current_turn
+                .ai
+                .resolve_model_selection("primary")
+                .as_deref(),
+            Some("home-model")
+        );
+        assert_eq!(
+            next_turn.ai.resolve_model_selection("primary").as_deref(),
+            Some("work-model")
+        );
+
+        config.active_profile = None;
+        let back = effective_config(&config).unwrap();
+        assert_eq!(
+            back.ai.resolve_model_selection("primary").as_deref(),
+            Some("home-model")
+        );
+    }
+
+    #[test]
+    fn writes_into_overlays_by_path() {
+        let mut overlay = json!({ "ai": { "default_models": { "primary": "a" } } });
+        assert!(overlay_sets(&overlay, "ai.default_models.primary"));
+        assert!(overlay_sets(&overlay, "ai.default_models"));
+        assert!(!overlay_sets(&overlay, "ai.default_models.fast"));
+        assert!(!overlay_sets(&overlay, "theme"));
+
+        set_in_overlay(&mut overlay, "theme.id", json!("dark")).unwrap();
+        assert_eq!(overlay["theme"]["id"], "dark");
+        assert!(set_in_overlay(&mut overlay, "", json!({})).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_profile_names() {
+        assert!(validate_profile_name("work_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("a.b").is_err());
+        assert!(validate_profile_name(BASE_PROFILE).is_err());
+    }
+}
@@ -71,6 +71,11 @@ impl ConfigProvider for AIConfigProvider {
                         )));
                     }
                 }
+                if let Some(reasoning) = &model.reasoning {
+                    reasoning.validate(model.max_tokens).map_err(|e| {
+                        BitFunError::validation(format!("Model '{}' {}", model.name, e))
+                    })?;
+                }
                 if let Some(temperature) = model.temperature {
                     if temperature < 0.0 || temperature > 2.0 {
                         warnings.push(format!(
@@ -93,6 +98,34 @@ impl ConfigProvider for AIConfigProvider {
                     )));
                 }
             }
+
+            // Primary agents drive the tool loop, so their models must accept tool definitions.
+            let tool_agent_models = ai_config
+                .agent_models
+                .iter()
+                .map(|(agent_name, model_id)| (agent_name.as_str(), model_id.as_str()))
+                .chain(
+                    ai_config
+                        .default_models
+                        .primary
+                        .as_deref()
+                        .map(|model_id| ("primary", model_id)),
+                );
+            for (agent_name, model_id) in tool_agent_models {
+                let model_id = match model_id {
+                    "primary" => ai_config.default_models.primary.as_deref().unwrap_or(""),
+                    "fast" => ai_config.default_models.fast.as_deref().unwrap_or(""),
+                    other => other,
+                };
+                if let Some(model) = ai_config.models.iter().find(|m| m.id == model_id) {
+                    if !model.supports_tool_calls() {
+                        warnings.push(format!(
+                            "Agent '{}' uses model '{}', which does not support tool calls",
+                            agent_name, model.name
+                        ));
+                    }
+                }
+            }
             for (func_agent_name, model_id) in &ai_config.func_agent_models {
                 if !ai_config.models.iter().any(|m| m.id == *model_id)
                     && model_id != "primary"
@@ -471,16 +504,31 @@ impl ConfigProvider for AppConfigProvider {
                 warnings.push("Sidebar width should be between 200 and 800 pixels".to_string());
             }
 
-            let valid_log_level = matches!(
-                app_config.logging.level.to_lowercase().as_str(),
-                "trace" | "debug" | "info" | "warn" | "error" | "off"
-            );
-            if !valid_log_level {
+            let valid_log_level = |level: &str| {
+                matches!(
+                    level.to_lowercase().as_str(),
+                    "trace" | "debug" | "info" | "warn" | "error" | "off"
+                )
+            };
+            if !valid_log_level(&app_config.logging.level) {
                 return Err(BitFunError::validation(format!(
                     "Invalid app.logging.level '{}': expected one of trace/debug/info/warn/error/off",
                     app_config.logging.level
                 )));
             }
+            for (target, level) in &app_config.logging.target_levels {
+                if target.trim().is_empty() {
+                    return Err(BitFunError::validation(
+                        "Invalid app.logging.target_levels: empty target".to_string(),
+                    ));
+                }
+                if !valid_log_level(level) {
+                    return Err(BitFunError::validation(format!(
+                        "Invalid app.logging.target_levels level '{}' for '{}': expected one of trace/debug/info/warn/error/off",
+                        level, target
+                    )));
+                }
+            }
         } else {
             return Err(BitFunError::validation(
                 "Invalid app config format".to_string(),
@@ -619,6 +667,8 @@ impl ConfigProviderRegistry {
                             message: msg,
                             code: "VALIDATION_WARNING".to_string(),
                             severity: "warning".to_string(),
+                            value: None,
+                            suggestion: None,
                         }
                     }))
                 }
@@ -627,6 +677,8 @@ impl ConfigProviderRegistry {
                     message: e.to_string(),
                     code: "VALIDATION_ERROR".to_string(),
                     severity: "error".to_string(),
+                    value: None,
+                    suggestion: None,
                 }),
             }
         }
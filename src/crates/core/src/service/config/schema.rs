@@ -0,0 +1,613 @@
+//! Configuration schema validation
+//!
+//! Checks the raw config JSON against the typed config sections so a bad
+//! value is reported with its exact JSON path, the offending value and (for
+//! misspelled enum values and keys) the closest known name, instead of
+//! surfacing later as a generic deserialization error.
+
+use super::types::*;
+use crate::service::mcp::server::MCPServerConfig;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_path_to_error::Segment;
+use std::collections::HashMap;
+
+/// Top-level keys of the config file.
+pub const KNOWN_SECTIONS: &[&str] = &[
+    "app",
+    "theme",
+    "editor",
+    "terminal",
+    "workspace",
+    "ai",
+    "agents",
+    "mcp_servers",
+    "themes",
+    "profiles",
+    "active_profile",
+    "schema_version",
+    "version",
+    "last_modified",
+];
+
+/// `type` values accepted in Cursor-format MCP server entries.
+const MCP_SERVER_TYPES: &[&str] = &[
+    "stdio",
+    "local",
+    "container",
+    "sse",
+    "streamable-http",
+    "streamable_http",
+    "streamablehttp",
+    "remote",
+    "http",
+];
+
+/// Cursor-format MCP server entry (`mcp_servers.mcpServers.<id>`).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct CursorServerEntry {
+    #[serde(rename = "type")]
+    server_type: Option<String>,
+    name: Option<String>,
+    enabled: Option<bool>,
+    #[serde(alias = "auto_start")]
+    auto_start: Option<bool>,
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    url: Option<String>,
+}
+
+/// Validates a full configuration document.
+///
+/// Unknown top-level keys are warnings so configs written by newer versions
+/// still load; everything else that would not deserialize is an error.
+/// Profiles are validated as partial documents under `profiles.<name>`.
+pub fn validate_config_value(config: &Value) -> ConfigValidationResult {
+    let mut report = Report::default();
+    check_document("", config, &mut report);
+    check_agent_models(config, &mut report);
+
+    if let Some(active) = config.get("active_profile").and_then(Value::as_str) {
+        let profiles = config.get("profiles").and_then(Value::as_object);
+        if !profiles.is_some_and(|profiles| profiles.contains_key(active)) {
+            let names: Vec<&str> = profiles
+                .map(|profiles| profiles.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            report.error(
+                "active_profile",
+                "no profile with this name exists".to_string(),
+                config.get("active_profile"),
+                closest(active, &names),
+            );
+        }
+    }
+
+    report.finish()
+}
+
+/// Validates the overlay of profile `name`, reporting paths under `profiles.<name>`.
+pub fn validate_profile_value(name: &str, overlay: &Value) -> ConfigValidationResult {
+    let mut report = Report::default();
+    check_document(&format!("profiles.{}", name), overlay, &mut report);
+    report.finish()
+}
+
+/// Checks the sections of a config document (or a profile under `prefix`).
+fn check_document(prefix: &str, config: &Value, report: &mut Report) {
+    let Some(sections) = config.as_object() else {
+        report.error(
+            prefix,
+            "Config must be a JSON object".to_string(),
+            Some(config),
+            None,
+        );
+        return;
+    };
+
+    let in_profile = !prefix.is_empty();
+    for (key, value) in sections {
+        let path = if in_profile {
+            format!("{}.{}", prefix, key)
+        } else {
+            key.clone()
+        };
+        match key.as_str() {
+            "app" => check_section::<AppConfig>(&path, value, report),
+            "theme" => check_section::<ThemeConfig>(&path, value, report),
+            "editor" => check_section::<EditorConfig>(&path, value, report),
+            "terminal" => check_section::<TerminalConfig>(&path, value, report),
+            "workspace" => {
+                check_section::<WorkspaceConfig>(&path, value, report);
+                check_tool_policy(&format!("{}.tool_policy", path), value, report);
+                check_workspace_env(&format!("{}.env", path), value, report);
+            }
+            "ai" => check_section::<AIConfig>(&path, value, report),
+            "agents" => check_section::<HashMap<String, AgentOverrideConfig>>(&path, value, report),
+            "themes" => check_section::<Option<ThemesConfig>>(&path, value, report),
+            "mcp_servers" => check_mcp_servers(&path, value, report),
+            "profiles" | "active_profile" | "schema_version" | "version" | "last_modified"
+                if in_profile =>
+            {
+                report.warning(
+                    &path,
+                    format!("'{}' cannot be set by a profile and is ignored", key),
+                    None,
+                )
+            }
+            "profiles" => check_profiles(value, report),
+            "active_profile" => check_section::<Option<String>>(&path, value, report),
+            "schema_version" => check_section::<u32>(&path, value, report),
+            "version" => check_section::<String>(&path, value, report),
+            "last_modified" => check_section::<i64>(&path, value, report),
+            _ => report.warning(
+                &path,
+                format!("Unknown config key '{}' is ignored", key),
+                closest(key, KNOWN_SECTIONS),
+            ),
+        }
+    }
+}
+
+fn check_profiles(value: &Value, report: &mut Report) {
+    let Some(profiles) = value.as_object() else {
+        report.error(
+            "profiles",
+            "expected an object keyed by profile name".to_string(),
+            Some(value),
+            None,
+        );
+        return;
+    };
+    for (name, overlay) in profiles {
+        check_document(&format!("profiles.{}", name), overlay, report);
+    }
+}
+
+/// Checks that no tool is both allowed and denied by `workspace.tool_policy`.
+fn check_tool_policy(path: &str, workspace: &Value, report: &mut Report) {
+    let Some(value) = workspace.get("tool_policy") else {
+        return;
+    };
+    let Ok(policy) = serde_json::from_value::<ToolPolicyConfig>(value.clone()) else {
+        return;
+    };
+    for tool in policy.conflicts() {
+        report.error(
+            path,
+            format!("tool '{}' is both allowed and denied", tool),
+            Some(&Value::String(tool.to_string())),
+            None,
+        );
+    }
+}
+
+/// Checks that `workspace.env` only sets valid environment variable names.
+fn check_workspace_env(path: &str, workspace: &Value, report: &mut Report) {
+    let Some(env) = workspace.get("env").and_then(Value::as_object) else {
+        return;
+    };
+    for name in env.keys().filter(|name| !is_valid_env_name(name)) {
+        report.error(
+            path,
+            format!("'{}' is not a valid environment variable name", name),
+            Some(&Value::String(name.clone())),
+            None,
+        );
+    }
+}
+
+/// Model selectors accepted besides the IDs in `ai.models`.
+const MODEL_SELECTORS: &[&str] = &["primary", "fast", "auto"];
+
+/// Checks that `agents.<id>.model` names a configured model.
+fn check_agent_models(config: &Value, report: &mut Report) {
+    let Some(agents) = config.get("agents").and_then(Value::as_object) else {
+        return;
+    };
+    let mut known: Vec<&str> = config
+        .pointer("/ai/models")
+        .and_then(Value::as_array)
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|model| model.get("id").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+    known.extend_from_slice(MODEL_SELECTORS);
+
+    for (agent_id, agent) in agents {
+        let Some(model) = agent.get("model").and_then(Value::as_str) else {
+            continue;
+        };
+        if !known.contains(&model) {
+            report.error(
+                &format!("agents.{}.model", agent_id),
+                "no model with this ID is configured in ai.models".to_string(),
+                agent.get("model"),
+                closest(model, &known),
+            );
+        }
+    }
+}
+
+/// One-line description of a validation error.
+pub fn describe_error(error: &ConfigValidationError) -> String {
+    let mut text = if error.path.is_empty() {
+        error.message.clone()
+    } else {
+        format!("{}: {}", error.path, error.message)
+    };
+    if let Some(value) = &error.value {
+        text.push_str(&format!(" (got {})", value));
+    }
+    if let Some(suggestion) = &error.suggestion {
+        text.push_str(&format!("; did you mean \"{}\"?", suggestion));
+    }
+    text
+}
+
+/// Returns whether `path` is `prefix` or lies below it (`ai` covers `ai.models[0]`).
+pub fn path_within(path: &str, prefix: &str) -> bool {
+    prefix.is_empty()
+        || path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+}
+
+/// Errors and warnings collected during validation.
+#[derive(Default)]
+struct Report {
+    errors: Vec<ConfigValidationError>,
+    warnings: Vec<ConfigValidationWarning>,
+}
+
+impl Report {
+    fn error(
+        &mut self,
+        path: &str,
+        message: String,
+        value: Option<&Value>,
+        suggestion: Option<String>,
+    ) {
+        self.errors.push(ConfigValidationError {
+            path: path.to_string(),
+            message,
+            code: "SCHEMA_ERROR".to_string(),
+            severity: "error".to_string(),
+            value: value.cloned(),
+            suggestion,
+        });
+    }
+
+    fn warning(&mut self, path: &str, message: String, suggestion: Option<String>) {
+        self.warnings.push(ConfigValidationWarning {
+            path: path.to_string(),
+            message,
+            code: "UNKNOWN_KEY".to_string(),
+            severity: "warning".to_string(),
+            value: None,
+            suggestion,
+        });
+    }
+
+    fn finish(self) -> ConfigValidationResult {
+        ConfigValidationResult {
+            valid: self.errors.is_empty(),
+            errors: self.errors,
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// Deserializes `value` as `T`, recording the first failure with its path.
+fn check_section<T: DeserializeOwned>(path: &str, value: &Value, report: &mut Report) {
+    if let Err(e) = serde_path_to_error::deserialize::<_, T>(value) {
+        let (path, offending) = locate(path, e.path(), value);
+        let message = e.inner().to_string();
+        let suggestion = variant_suggestion(&message);
+        report.error(&path, message, offending, suggestion);
+    }
+}
+
+/// Accepts the Cursor format (`{"mcpServers": {...}}`) and the legacy array format.
+fn check_mcp_servers(root: &str, value: &Value, report: &mut Report) {
+    match value {
+        Value::Null => {}
+        Value::Array(servers) => {
+            for (index, server) in servers.iter().enumerate() {
+                let path = format!("{}[{}]", root, index);
+                match serde_path_to_error::deserialize::<_, MCPServerConfig>(server) {
+                    Ok(config) => {
+                        if let Err(e) = config.validate() {
+                            report.error(&path, e.to_string(), None, None);
+                        }
+                    }
+                    Err(_) => check_section::<MCPServerConfig>(&path, server, report),
+                }
+            }
+        }
+        Value::Object(obj) => match obj.get("mcpServers") {
+            Some(Value::Object(servers)) => {
+                for (id, entry) in servers {
+                    check_cursor_entry(&format!("{}.mcpServers.{}", root, id), entry, report);
+                }
+            }
+            Some(other) => report.error(
+                &format!("{}.mcpServers", root),
+                "expected an object keyed by server ID".to_string(),
+                Some(other),
+                None,
+            ),
+            None => report.error(
+                root,
+                "expected {\"mcpServers\": {...}} or an array of servers".to_string(),
+                None,
+                None,
+            ),
+        },
+        other => report.error(
+            root,
+            "expected {\"mcpServers\": {...}} or an array of servers".to_string(),
+            Some(other),
+            None,
+        ),
+    }
+}
+
+fn check_cursor_entry(path: &str, entry: &Value, report: &mut Report) {
+    let entry = match serde_path_to_error::deserialize::<_, CursorServerEntry>(entry) {
+        Ok(parsed) => parsed,
+        Err(_) => return check_section::<CursorServerEntry>(path, entry, report),
+    };
+
+    let remote = match entry.server_type.as_deref() {
+        Some(server_type) if !MCP_SERVER_TYPES.contains(&server_type) => {
+            report.error(
+                &format!("{}.type", path),
+                format!(
+                    "unknown server type, expected one of: {}",
+                    MCP_SERVER_TYPES.join(", ")
+                ),
+                Some(&Value::String(server_type.to_string())),
+                closest(server_type, MCP_SERVER_TYPES),
+            );
+            return;
+        }
+        Some("stdio" | "local" | "container") => false,
+        Some(_) => true,
+        None => entry.url.is_some(),
+    };
+
+    if remote && entry.url.is_none() {
+        report.error(
+            path,
+            "remote MCP server must have a \"url\"".to_string(),
+            None,
+            None,
+        );
+    } else if !remote && entry.command.is_none() {
+        report.error(
+            path,
+            "stdio MCP server must have a \"command\"".to_string(),
+            None,
+            None,
+        );
+    }
+}
+
+/// Renders the failing path (`ai.models[0].category`) and finds the value there.
+fn locate<'a>(
+    root: &str,
+    path: &serde_path_to_error::Path,
+    value: &'a Value,
+) -> (String, Option<&'a Value>) {
+    let mut text = root.to_string();
+    let mut current = Some(value);
+
+    for segment in path.iter() {
+        match segment {
+            Segment::Seq { index } => {
+                text.push_str(&format!("[{}]", index));
+                current = current.and_then(|v| v.get(*index));
+            }
+            Segment::Map { key } | Segment::Enum { variant: key } => {
+                if !text.is_empty() {
+                    text.push('.');
+                }
+                text.push_str(key);
+                current = current.and_then(|v| v.get(key.as_str()));
+            }
+            Segment::Unknown => {}
+        }
+    }
+
+    (text, current)
+}
+
+/// Closest known name for serde's "unknown variant `x`, expected one of `a`, `b`" errors.
+fn variant_suggestion(message: &str) -> Option<String> {
+    if !message.starts_with("unknown variant") && !message.starts_with("unknown field") {
+        return None;
+    }
+    let mut names = message.split('`').skip(1).step_by(2);
+    let given = names.next()?;
+    let known: Vec<&str> = names.collect();
+    closest(given, &known)
+}
+
+/// Known name within a small edit distance of `name`, ignoring case and `-`/`_`.
+fn closest(name: &str, known: &[&str]) -> Option<String> {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let target = normalize(name);
+    let max_distance = (target.chars().count() / 3).max(1);
+
+    known
+        .iter()
+        .map(|candidate| (candidate, edit_distance(&target, &normalize(candidate))))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate.to_string())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_path_value_and_suggestion_for_bad_enum() {
+        let config = json!({
+            "ai": {
+                "models": [{
+                    "id": "m1",
+                    "name": "Model",
+                    "provider": "openai",
+                    "category": "multimodel"
+                }]
+            }
+        });
+
+        let result = validate_config_value(&config);
+        assert!(!result.valid);
+        let error = &result.errors[0];
+        assert_eq!(error.path, "ai.models[0].category");
+        assert_eq!(error.value, Some(json!("multimodel")));
+        assert_eq!(error.suggestion.as_deref(), Some("multimodal"));
+    }
+
+    #[test]
+    fn unknown_top_level_keys_only_warn() {
+        let result = validate_config_value(&json!({ "editr": {}, "future_feature": 1 }));
+        assert!(result.valid);
+        assert_eq!(result.warnings.len(), 2);
+        let editr = result.warnings.iter().find(|w| w.path == "editr").unwrap();
+        assert_eq!(editr.suggestion.as_deref(), Some("editor"));
+    }
+
+    #[test]
+    fn checks_cursor_format_mcp_servers() {
+        let config = json!({
+            "mcp_servers": {
+                "mcpServers": {
+                    "fs": { "type": "stdo", "command": "npx" },
+                    "docs": { "type": "http" },
+                    "ok": { "command": "uvx", "args": ["server"] }
+                }
+            }
+        });
+
+        let result = validate_config_value(&config);
+        let paths: Vec<_> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(result.errors.len(), 2);
+        assert!(paths.contains(&"mcp_servers.mcpServers.fs.type"));
+        assert!(paths.contains(&"mcp_servers.mcpServers.docs"));
+        let fs = result.errors.iter().find(|e| e.path.ends_with("fs.type"));
+        assert_eq!(fs.unwrap().suggestion.as_deref(), Some("stdio"));
+    }
+
+    #[test]
+    fn validates_profiles_as_partial_configs() {
+        let config = json!({
+            "profiles": {
+                "work": { "ai": { "skip_tool_confirmation": "no" }, "version": "9" }
+            },
+            "active_profile": "wrk"
+        });
+
+        let result = validate_config_value(&config);
+        let paths: Vec<_> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["profiles.work.ai.skip_tool_confirmation", "active_profile"]
+        );
+        assert_eq!(result.errors[1].suggestion.as_deref(), Some("work"));
+        assert_eq!(result.warnings[0].path, "profiles.work.version");
+    }
+
+    #[test]
+    fn checks_agent_overrides_against_configured_models() {
+        let config = json!({
+            "ai": { "models": [{ "id": "claude-haiku" }] },
+            "agents": {
+                "Explore": { "model": "claude-haik", "removed_tools": ["Bash"] },
+                "agentic": { "model": "primary", "max_turns": 50 },
+                "Plan": { "max_turns": "many" }
+            }
+        });
+
+        let result = validate_config_value(&config);
+        let paths: Vec<_> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["agents.Plan.max_turns", "agents.Explore.model"]);
+        assert_eq!(result.errors[1].suggestion.as_deref(), Some("claude-haiku"));
+    }
+
+    #[test]
+    fn rejects_tools_both_allowed_and_denied() {
+        let config = json!({
+            "workspace": {
+                "tool_policy": {
+                    "allowed_tools": ["Read", "Bash"],
+                    "denied_tools": ["Bash", "WebFetch"]
+                }
+            }
+        });
+
+        let result = validate_config_value(&config);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "workspace.tool_policy");
+        assert_eq!(result.errors[0].value, Some(json!("Bash")));
+    }
+
+    #[test]
+    fn rejects_invalid_env_names() {
+        let config = json!({
+            "workspace": {
+                "env": { "NODE_ENV": "test", "1PASSWORD": "x", "MY-VAR": "y" }
+            }
+        });
+
+        let result = validate_config_value(&config);
+        assert_eq!(result.errors.len(), 2);
+        assert!(result.errors.iter().all(|e| e.path == "workspace.env"));
+    }
+
+    #[test]
+    fn matches_paths_below_a_prefix() {
+        assert!(path_within("ai.models[0].name", "ai.models"));
+        assert!(path_within("ai", "ai"));
+        assert!(!path_within("ai_extra", "ai"));
+        assert!(path_within("theme.colors", ""));
+    }
+}
@@ -0,0 +1,468 @@
+//! Secret-typed config values
+//!
+//! A string under a key naming a credential (`api_key`, `Authorization`,
+//! `GITHUB_TOKEN`, ...) is a secret. Secrets should be stored as references
+//! into the secrets store (`keyring:<name>`); plaintext ones are redacted
+//! before a config is sent to the frontend and can be migrated into the store.
+
+use crate::infrastructure::secrets::{self, SecretsStore};
+use crate::util::errors::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Placeholder sent to the frontend instead of a plaintext secret.
+pub const REDACTED_SECRET: &str = "[REDACTED]";
+
+/// Key names (compared without `_`/`-`, case-insensitive) that hold secrets.
+const SENSITIVE_KEY_SUFFIXES: &[&str] = &[
+    "apikey",
+    "token",
+    "secret",
+    "password",
+    "credential",
+    "credentials",
+    "authorization",
+];
+
+/// Returns whether `key` names a credential (`api_key`, `accessToken`, ...).
+pub fn is_sensitive_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    SENSITIVE_KEY_SUFFIXES
+        .iter()
+        .any(|suffix| normalized.ends_with(suffix))
+}
+
+/// Returns whether a secret-typed `value` holds the secret itself rather than
+/// a store reference, an environment variable reference or a redaction.
+fn is_plaintext(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && value != REDACTED_SECRET
+        && secrets::parse_secret_ref(value).is_none()
+        && !value.contains("${")
+}
+
+/// Plaintext secret found in a config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaintextSecret {
+    /// Dot-path of the value; array items are addressed by their `id` when they have one.
+    pub path: String,
+    /// Name the secret gets in the secrets store.
+    pub name: String,
+}
+
+/// Replaces every plaintext secret in `value` with [`REDACTED_SECRET`].
+pub fn redact_secrets(value: &mut Value) {
+    visit_secrets(value, "", &mut |_, secret| {
+        if secret.as_str().is_some_and(is_plaintext) {
+            *secret = Value::String(REDACTED_SECRET.to_string());
+        }
+    });
+}
+
+/// Puts back the secrets of `previous` wherever `value` still holds [`REDACTED_SECRET`].
+///
+/// Lets the frontend send a redacted config back without erasing the secrets.
+pub fn restore_redacted(value: &mut Value, previous: &Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let previous_child = previous.get(key);
+                if is_sensitive_key(key) && child.as_str() == Some(REDACTED_SECRET) {
+                    if let Some(secret) = previous_child.filter(|v| v.is_string()) {
+                        *child = secret.clone();
+                    }
+                } else if let Some(previous_child) = previous_child {
+                    restore_redacted(child, previous_child);
+                }
+            }
+        }
+        Value::Array(items) => {
+            let previous_items = previous.as_array().map(Vec::as_slice).unwrap_or_default();
+            for (index, item) in items.iter_mut().enumerate() {
+                let previous_item = match item_id(item) {
+                    Some(id) => previous_items.iter().find(|p| item_id(p) == Some(id)),
+                    None => previous_items.get(index),
+                };
+                if let Some(previous_item) = previous_item {
+                    restore_redacted(item, previous_item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Like [`redact_secrets`] for the config section at `path`, which may itself be a secret.
+pub fn redact_secrets_at(path: Option<&str>, value: &mut Value) {
+    with_path_key(path, value, redact_secrets);
+}
+
+/// Like [`restore_redacted`] for the config section at `path`, which may itself be a secret.
+pub fn restore_redacted_at(path: Option<&str>, value: &mut Value, previous: &Value) {
+    let mut previous = previous.clone();
+    with_path_key(path, &mut previous, |wrapped_previous| {
+        with_path_key(path, value, |wrapped| {
+            restore_redacted(wrapped, wrapped_previous)
+        });
+    });
+}
+
+/// Runs `f` on `value` wrapped under its own key, so a section that is a
+/// single secret string is recognized by its key name.
+fn with_path_key(path: Option<&str>, value: &mut Value, f: impl FnOnce(&mut Value)) {
+    let key = path
+        .and_then(|path| path.rsplit('.').next())
+        .unwrap_or_default()
+        .to_string();
+    let mut wrapped = Value::Object(serde_json::Map::from_iter([(key.clone(), value.take())]));
+    f(&mut wrapped);
+    *value = wrapped[&key].take();
+}
+
+/// Lists the plaintext secrets in `value`.
+pub fn find_plaintext_secrets(value: &Value) -> Vec<PlaintextSecret> {
+    let mut found = Vec::new();
+    visit_secrets(&mut value.clone(), "", &mut |path, secret| {
+        if secret.as_str().is_some_and(is_plaintext) {
+            found.push(PlaintextSecret {
+                path: path.to_string(),
+                name: secret_name(path),
+            });
+        }
+    });
+    found
+}
+
+/// Moves plaintext secrets from `value` into `store`, replacing each with a
+/// `keyring:` reference. `only` limits the move to the given paths.
+///
+/// Returns the secrets moved.
+pub fn move_plaintext_secrets(
+    value: &mut Value,
+    only: Option<&[String]>,
+    store: &dyn SecretsStore,
+) -> BitFunResult<Vec<PlaintextSecret>> {
+    let mut moved = Vec::new();
+    let mut error = None;
+    visit_secrets(value, "", &mut |path, secret| {
+        if error.is_some() || only.is_some_and(|only| !only.iter().any(|p| p == path)) {
+            return;
+        }
+        let Some(plaintext) = secret.as_str().filter(|s| is_plaintext(s)) else {
+            return;
+        };
+        let name = secret_name(path);
+        match store.set(&name, plaintext.trim()) {
+            Ok(()) => {
+                *secret = Value::String(secrets::secret_ref(&name));
+                moved.push(PlaintextSecret {
+                    path: path.to_string(),
+                    name,
+                });
+            }
+            Err(e) => error = Some(e),
+        }
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(moved),
+    }
+}
+
+/// Replaces every secret in `value`, plaintext or store reference, with
+/// [`REDACTED_SECRET`]. With `resolve`, returns the secret values by path.
+///
+/// Environment variable references are not secrets themselves and are kept.
+pub fn take_secrets(
+    value: &mut Value,
+    resolve: Option<&dyn Fn(&str) -> BitFunResult<String>>,
+) -> BitFunResult<BTreeMap<String, String>> {
+    let mut taken = BTreeMap::new();
+    let mut error = None;
+    visit_secrets(value, "", &mut |path, secret| {
+        let Some(current) = secret.as_str().map(str::trim) else {
+            return;
+        };
+        if error.is_some()
+            || current.is_empty()
+            || current == REDACTED_SECRET
+            || current.contains("${")
+        {
+            return;
+        }
+        if let Some(resolve) = resolve {
+            match resolve(current) {
+                Ok(resolved) => {
+                    taken.insert(path.to_string(), resolved);
+                }
+                Err(e) => {
+                    error = Some(e);
+                    return;
+                }
+            }
+        }
+        *secret = Value::String(REDACTED_SECRET.to_string());
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(taken),
+    }
+}
+
+/// Puts `secrets` (by path) into the store for the matching placeholders in
+/// `value`, replacing each with a `keyring:` reference. With `store` `None`
+/// only the references are set, nothing is written.
+///
+/// Returns the paths whose secret was placed.
+pub fn place_secrets(
+    value: &mut Value,
+    secrets: &BTreeMap<String, String>,
+    store: Option<&dyn SecretsStore>,
+) -> BitFunResult<Vec<String>> {
+    let mut placed = Vec::new();
+    let mut error = None;
+    visit_secrets(value, "", &mut |path, secret| {
+        if error.is_some() || secret.as_str() != Some(REDACTED_SECRET) {
+            return;
+        }
+        let Some(plaintext) = secrets.get(path) else {
+            return;
+        };
+        let name = secret_name(path);
+        if let Some(store) = store {
+            if let Err(e) = store.set(&name, plaintext) {
+                error = Some(e);
+                return;
+            }
+        }
+        *secret = Value::String(secrets::secret_ref(&name));
+        placed.push(path.to_string());
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(placed),
+    }
+}
+
+/// Empties the secrets still holding [`REDACTED_SECRET`] and returns their paths.
+pub fn clear_redacted(value: &mut Value) -> Vec<String> {
+    let mut cleared = Vec::new();
+    visit_secrets(value, "", &mut |path, secret| {
+        if secret.as_str() == Some(REDACTED_SECRET) {
+            *secret = Value::String(String::new());
+            cleared.push(path.to_string());
+        }
+    });
+    cleared
+}
+
+/// Calls `visit` with the path and value of every string under a sensitive key.
+fn visit_secrets(value: &mut Value, path: &str, visit: &mut dyn FnMut(&str, &mut Value)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let child_path = join(path, key);
+                if is_sensitive_key(key) && child.is_string() {
+                    visit(&child_path, child);
+                } else {
+                    visit_secrets(child, &child_path, visit);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let segment = item_id(item)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| index.to_string());
+                visit_secrets(item, &join(path, &segment), visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn item_id(item: &Value) -> Option<&str> {
+    item.get("id").and_then(Value::as_str)
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Store name for the secret at `path`, e.g. `ai.models.gpt-4o.api_key`.
+fn secret_name(path: &str) -> String {
+    path.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, String>>);
+
+    impl SecretsStore for MemoryStore {
+        fn backend_name(&self) -> &'static str {
+            "memory"
+        }
+        fn get(&self, name: &str) -> BitFunResult<Option<String>> {
+            Ok(self.0.lock().unwrap().get(name).cloned())
+        }
+        fn set(&self, name: &str, value: &str) -> BitFunResult<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        }
+        fn delete(&self, name: &str) -> BitFunResult<()> {
+            self.0.lock().unwrap().remove(name);
+            Ok(())
+        }
+    }
+
+    fn sample() -> Value {
+        json!({
+            "ai": {
+                "models": [
+                    { "id": "gpt-4o", "api_key": "sk-plain", "max_tokens": 100 },
+                    { "id": "claude", "api_key": "keyring:claude" },
+                    { "id": "local", "api_key": "" }
+                ]
+            },
+            "mcp_servers": {
+                "mcpServers": {
+                    "github": {
+                        "env": { "GITHUB_TOKEN": "ghp_plain", "DEBUG": "1" },
+                        "headers": { "Authorization": "Bearer ${GITHUB_PAT}" }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn detects_sensitive_key_names() {
+        assert!(is_sensitive_key("api_key"));
+        assert!(is_sensitive_key("apiKey"));
+        assert!(is_sensitive_key("access-token"));
+        assert!(is_sensitive_key("Authorization"));
+        assert!(!is_sensitive_key("max_tokens"));
+        assert!(!is_sensitive_key("primary"));
+    }
+
+    #[test]
+    fn finds_only_plaintext_secrets() {
+        let found = find_plaintext_secrets(&sample());
+        let paths: Vec<_> = found.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "ai.models.gpt-4o.api_key",
+                "mcp_servers.mcpServers.github.env.GITHUB_TOKEN"
+            ]
+        );
+    }
+
+    #[test]
+    fn redacts_and_restores_secrets() {
+        let original = sample();
+        let mut redacted = original.clone();
+        redact_secrets(&mut redacted);
+        assert_eq!(redacted["ai"]["models"][0]["api_key"], REDACTED_SECRET);
+        assert_eq!(redacted["ai"]["models"][1]["api_key"], "keyring:claude");
+        assert_eq!(
+            redacted["mcp_servers"]["mcpServers"]["github"]["env"]["DEBUG"],
+            "1"
+        );
+
+        // The frontend reorders the models and edits an unrelated field
+        let models = redacted["ai"]["models"].as_array_mut().unwrap();
+        models.reverse();
+        models[2]["max_tokens"] = json!(200);
+        restore_redacted(&mut redacted, &original);
+        assert_eq!(redacted["ai"]["models"][2]["api_key"], "sk-plain");
+        assert_eq!(redacted["ai"]["models"][2]["max_tokens"], 200);
+        assert_eq!(
+            redacted["mcp_servers"]["mcpServers"]["github"]["env"]["GITHUB_TOKEN"],
+            "ghp_plain"
+        );
+    }
+
+    #[test]
+    fn redacts_a_section_that_is_itself_a_secret() {
+        let mut value = json!("sk-plain");
+        redact_secrets_at(Some("ai.models.0.api_key"), &mut value);
+        assert_eq!(value, REDACTED_SECRET);
+        restore_redacted_at(Some("ai.models.0.api_key"), &mut value, &json!("sk-plain"));
+        assert_eq!(value, "sk-plain");
+
+        let mut whole = sample();
+        redact_secrets_at(None, &mut whole);
+        assert_eq!(whole["ai"]["models"][0]["api_key"], REDACTED_SECRET);
+    }
+
+    #[test]
+    fn takes_and_places_secrets() {
+        let mut config = sample();
+        let resolve = |value: &str| Ok(value.replace("keyring:", "resolved-"));
+        let taken = take_secrets(&mut config, Some(&resolve)).unwrap();
+        assert_eq!(taken["ai.models.gpt-4o.api_key"], "sk-plain");
+        assert_eq!(taken["ai.models.claude.api_key"], "resolved-claude");
+        assert!(!taken.contains_key("mcp_servers.mcpServers.github.headers.Authorization"));
+        assert_eq!(config["ai"]["models"][1]["api_key"], REDACTED_SECRET);
+
+        let store = MemoryStore::default();
+        let placed = place_secrets(&mut config, &taken, Some(&store)).unwrap();
+        assert_eq!(placed.len(), 3);
+        assert_eq!(
+            config["ai"]["models"][1]["api_key"],
+            "keyring:ai.models.claude.api_key"
+        );
+        assert_eq!(
+            store.get("ai.models.claude.api_key").unwrap().as_deref(),
+            Some("resolved-claude")
+        );
+        assert!(clear_redacted(&mut config).is_empty());
+    }
+
+    #[test]
+    fn moves_plaintext_secrets_into_the_store() {
+        let store = MemoryStore::default();
+        let mut config = sample();
+        let only = vec!["ai.models.gpt-4o.api_key".to_string()];
+
+        let moved = move_plaintext_secrets(&mut config, Some(&only), &store).unwrap();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(
+            config["ai"]["models"][0]["api_key"],
+            "keyring:ai.models.gpt-4o.api_key"
+        );
+        assert_eq!(
+            store.get("ai.models.gpt-4o.api_key").unwrap().as_deref(),
+            Some("sk-plain")
+        );
+        assert_eq!(find_plaintext_secrets(&config).len(), 1);
+    }
+}
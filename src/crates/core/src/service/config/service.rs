@@ -2,18 +2,29 @@
 //!
 //! Provides comprehensive configuration management functionality.
 
+use super::file_watcher::ConfigFileWatcher;
+use super::hot_reload::{self, ConfigFileChange};
 use super::manager::{ConfigManager, ConfigManagerSettings, ConfigStatistics};
+use super::schema;
+use super::secrets::{self, PlaintextSecret};
+use super::sources::AnnotatedConfig;
 use super::types::*;
+use super::workspace_overrides::{self, WorkspaceConfigWatcher, WorkspaceOverrides};
 use crate::util::errors::*;
+use crate::util::types::AvailableModel;
+use chrono::{DateTime, Utc};
 use log::{info, warn};
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Configuration service.
 pub struct ConfigService {
     manager: Arc<RwLock<ConfigManager>>,
+    workspace_watcher: WorkspaceConfigWatcher,
+    file_watcher: ConfigFileWatcher,
 }
 
 /// Configuration import/export format.
@@ -56,6 +67,8 @@ impl ConfigService {
 
         Ok(Self {
             manager: Arc::new(RwLock::new(manager)),
+            workspace_watcher: WorkspaceConfigWatcher::default(),
+            file_watcher: ConfigFileWatcher::default(),
         })
     }
 
@@ -75,6 +88,57 @@ impl ConfigService {
         }
     }
 
+    /// Gets the effective config (or the section at `path`) with the source
+    /// layer of every value.
+    pub async fn get_config_with_sources(
+        &self,
+        path: Option<&str>,
+    ) -> BitFunResult<AnnotatedConfig> {
+        let manager = self.manager.read().await;
+        let value = match path {
+            Some(path) => manager.get(path)?,
+            None => serde_json::to_value(manager.get_config())?,
+        };
+        let sources = manager
+            .config_sources()?
+            .into_iter()
+            .filter(|(key, _)| path.is_none_or(|path| schema::path_within(key, path)))
+            .collect();
+        Ok(AnnotatedConfig { value, sources })
+    }
+
+    /// Gets the config (or the section at `path`) with plaintext secrets
+    /// redacted, for sending to a frontend.
+    pub async fn get_redacted_config(&self, path: Option<&str>) -> BitFunResult<serde_json::Value> {
+        let mut value = self.get_config(path).await?;
+        secrets::redact_secrets_at(path, &mut value);
+        Ok(value)
+    }
+
+    /// Like [`Self::get_config_with_sources`], with plaintext secrets redacted.
+    pub async fn get_redacted_config_with_sources(
+        &self,
+        path: Option<&str>,
+    ) -> BitFunResult<AnnotatedConfig> {
+        let mut annotated = self.get_config_with_sources(path).await?;
+        secrets::redact_secrets_at(path, &mut annotated.value);
+        Ok(annotated)
+    }
+
+    /// Sets a value received from a frontend, keeping the stored secrets
+    /// wherever it still holds the redaction placeholder.
+    pub async fn set_redacted_config(
+        &self,
+        path: &str,
+        mut value: serde_json::Value,
+    ) -> BitFunResult<()> {
+        let mut manager = self.manager.write().await;
+        if let Ok(previous) = manager.get::<serde_json::Value>(path) {
+            secrets::restore_redacted_at(Some(path), &mut value, &previous);
+        }
+        manager.set(path, value).await
+    }
+
     /// Sets a configuration value (supports dot-paths).
     pub async fn set_config<T>(&self, path: &str, value: T) -> BitFunResult<()>
     where
@@ -96,6 +160,15 @@ impl ConfigService {
         manager.validate_config().await
     }
 
+    /// Validates the stored config file against the config schema.
+    ///
+    /// Errors carry the JSON path, the offending value and a suggestion for
+    /// near-miss enum values; unknown top-level keys are only warnings.
+    pub async fn validate(&self) -> BitFunResult<ConfigValidationResult> {
+        let manager = self.manager.read().await;
+        manager.validate_file().await
+    }
+
     /// Exports configuration.
     pub async fn export_config(&self) -> BitFunResult<ConfigExport> {
         let manager = self.manager.read().await;
@@ -109,6 +182,18 @@ impl ConfigService {
         })
     }
 
+    /// Returns the persisted user config (base values and profiles) as JSON.
+    pub async fn export_config_value(&self) -> BitFunResult<serde_json::Value> {
+        self.manager.read().await.export_config()
+    }
+
+    /// Replaces the persisted user config after validating it; older schema
+    /// versions are migrated first.
+    pub async fn import_config_value(&self, value: serde_json::Value) -> BitFunResult<()> {
+        let mut manager = self.manager.write().await;
+        manager.import_config(value).await
+    }
+
     /// Imports configuration.
     pub async fn import_config(&self, export: ConfigExport) -> BitFunResult<ConfigImportResult> {
         let mut manager = self.manager.write().await;
@@ -187,15 +272,185 @@ impl ConfigService {
     /// Reloads configuration.
     pub async fn reload(&self) -> BitFunResult<()> {
         let settings = ConfigManagerSettings::default();
-        let new_manager = ConfigManager::new(settings).await?;
+        let mut new_manager = ConfigManager::new(settings).await?;
 
         let mut manager = self.manager.write().await;
+        let workspace = manager.workspace_overrides().cloned();
+        if let Err(e) = new_manager.set_workspace_overrides(workspace).await {
+            warn!("Failed to reapply workspace config after reload: {}", e);
+        }
         *manager = new_manager;
 
         info!("Configuration reloaded");
         Ok(())
     }
 
+    /// Watches the user config file and applies edits made outside the app.
+    pub async fn watch_config_file(&self) {
+        let (file, config_dir) = {
+            let manager = self.manager.read().await;
+            (
+                manager.config_file().to_path_buf(),
+                manager.path_manager().user_config_dir(),
+            )
+        };
+        self.file_watcher.watch(
+            &file,
+            &config_dir,
+            tokio::time::Duration::from_millis(hot_reload::RELOAD_DEBOUNCE_MS),
+            |changed_at| async move {
+                if let Err(e) =
+                    super::GlobalConfigManager::apply_config_file_change(changed_at).await
+                {
+                    warn!("Failed to reload edited config file: {}", e);
+                }
+            },
+        );
+    }
+
+    /// Stops watching the user config file.
+    pub fn unwatch_config_file(&self) {
+        self.file_watcher.unwatch();
+    }
+
+    /// Re-reads the user config file after it changed on disk at `changed_at`.
+    ///
+    /// Parse failures are retried in case the file is still being written;
+    /// an invalid file keeps the current config. Returns the changed keys,
+    /// or `None` when the change was the app's own write or changed nothing.
+    pub async fn apply_config_file_change(
+        &self,
+        changed_at: DateTime<Utc>,
+    ) -> BitFunResult<Option<ConfigFileChange>> {
+        let file = self.manager.read().await.config_file().to_path_buf();
+        let Some((content, value)) = hot_reload::read_config_file(&file).await? else {
+            warn!(
+                "Config file {:?} was removed; keeping the current config",
+                file
+            );
+            return Ok(None);
+        };
+
+        let window_start =
+            changed_at - chrono::Duration::milliseconds(hot_reload::RELOAD_DEBOUNCE_MS as i64);
+        let mut manager = self.manager.write().await;
+        manager
+            .apply_external_config(&content, value, window_start)
+            .await
+    }
+
+    /// Returns the profile names and the active profile.
+    pub async fn list_profiles(&self) -> (Vec<String>, Option<String>) {
+        let manager = self.manager.read().await;
+        (
+            manager.profile_names(),
+            manager.active_profile().map(str::to_string),
+        )
+    }
+
+    /// Creates a profile from a partial config.
+    pub async fn create_profile(&self, name: &str, overlay: serde_json::Value) -> BitFunResult<()> {
+        let mut manager = self.manager.write().await;
+        manager.create_profile(name, overlay).await
+    }
+
+    /// Activates profile `name`, or the base config for `None`.
+    pub async fn switch_profile(&self, name: Option<&str>) -> BitFunResult<()> {
+        let mut manager = self.manager.write().await;
+        manager.switch_profile(name).await
+    }
+
+    /// Deletes a profile.
+    pub async fn delete_profile(&self, name: &str) -> BitFunResult<()> {
+        let mut manager = self.manager.write().await;
+        manager.delete_profile(name).await
+    }
+
+    /// Secrets stored in plaintext in the user config.
+    pub async fn find_plaintext_secrets(&self) -> BitFunResult<Vec<PlaintextSecret>> {
+        self.manager.read().await.find_plaintext_secrets()
+    }
+
+    /// Moves plaintext secrets (all, or those at the paths in `only`) into the secrets store.
+    pub async fn migrate_plaintext_secrets(
+        &self,
+        only: Option<&[String]>,
+    ) -> BitFunResult<Vec<PlaintextSecret>> {
+        let mut manager = self.manager.write().await;
+        manager.migrate_plaintext_secrets(only).await
+    }
+
+    /// Applies the `.bitfun/config.json` of workspace `root` (or clears the
+    /// workspace layer for `None`) and watches the file for changes.
+    ///
+    /// A workspace config that cannot be read or is invalid is ignored with a
+    /// warning; it is picked up once the file is fixed.
+    pub async fn set_workspace(&self, root: Option<&Path>) -> BitFunResult<()> {
+        let file = {
+            let manager = self.manager.read().await;
+            root.map(|root| manager.path_manager().project_config_file(root))
+        };
+        self.workspace_watcher.watch(root, file.as_deref());
+
+        let overrides = match (root, &file) {
+            (Some(root), Some(file)) => Self::load_workspace_overrides(root, file).await,
+            _ => None,
+        };
+        let mut manager = self.manager.write().await;
+        manager.set_workspace_overrides(overrides).await
+    }
+
+    /// Re-reads the config file of the current workspace.
+    pub async fn refresh_workspace(&self) -> BitFunResult<()> {
+        match self.workspace_watcher.watched() {
+            // Re-watching also moves the watch onto a newly created `.bitfun`
+            Some((root, _)) => self.set_workspace(Some(&root)).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Root of the workspace whose config is applied.
+    pub fn workspace_root(&self) -> Option<std::path::PathBuf> {
+        self.workspace_watcher.watched().map(|(root, _)| root)
+    }
+
+    /// Workspace settings of workspace `root`, or of the active workspace for `None`.
+    ///
+    /// Sessions can run in a workspace other than the active one, so that
+    /// workspace's `.bitfun/config.json` is read directly.
+    pub async fn get_workspace_config(&self, root: Option<&Path>) -> BitFunResult<WorkspaceConfig> {
+        let root = match root {
+            Some(root) if self.workspace_root().as_deref() != Some(root) => root,
+            _ => return self.get_config(Some("workspace")).await,
+        };
+
+        let file = {
+            let manager = self.manager.read().await;
+            manager.path_manager().project_config_file(root)
+        };
+        let overrides = Self::load_workspace_overrides(root, &file).await;
+        let manager = self.manager.read().await;
+        let config = manager.config_with_workspace_overlay(
+            overrides.as_ref().map(|overrides| &overrides.overlay),
+        )?;
+        Ok(config.workspace)
+    }
+
+    /// Tool policy of workspace `root`, or of the active workspace for `None`.
+    pub async fn get_tool_policy(&self, root: Option<&Path>) -> BitFunResult<ToolPolicyConfig> {
+        Ok(self.get_workspace_config(root).await?.tool_policy)
+    }
+
+    async fn load_workspace_overrides(root: &Path, file: &Path) -> Option<WorkspaceOverrides> {
+        match workspace_overrides::load(root, file).await {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                warn!("Ignoring workspace config: {}", e);
+                None
+            }
+        }
+    }
+
     /// Creates a configuration backup.
     pub async fn create_backup(&self) -> BitFunResult<std::path::PathBuf> {
         let manager = self.manager.read().await;
@@ -214,6 +469,17 @@ impl ConfigService {
         Ok(config.ai.models)
     }
 
+    /// Returns the configured models with capabilities, context window and prices resolved
+    /// against the built-in table of known models.
+    pub async fn get_available_models(&self) -> BitFunResult<Vec<AvailableModel>> {
+        Ok(self
+            .get_ai_models()
+            .await?
+            .iter()
+            .map(AIModelConfig::to_available_model)
+            .collect())
+    }
+
     /// Adds an AI model configuration.
     pub async fn add_ai_model(&self, model: AIModelConfig) -> BitFunResult<()> {
         let mut config: GlobalConfig = self.get_config(None).await?;
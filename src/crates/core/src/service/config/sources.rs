@@ -0,0 +1,129 @@
+//! Config value sources
+//!
+//! The effective config is built from layers, later ones winning: built-in
+//! defaults, the user config file, the active profile, then the workspace's
+//! `.bitfun/config.json`. This module reports which layer each value came from.
+
+use super::profiles;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Layer an effective config value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Default,
+    User,
+    Profile,
+    Workspace,
+}
+
+/// Effective config (or one section of it) with the source of every value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotatedConfig {
+    pub value: Value,
+    /// Dot-path of each leaf value (arrays count as leaves) -> its layer.
+    pub sources: BTreeMap<String, ConfigSource>,
+}
+
+/// Overlays and defaults at the same position as the effective value being annotated.
+#[derive(Clone, Copy)]
+struct Layers<'a> {
+    defaults: Option<&'a Value>,
+    profile: Option<&'a Value>,
+    workspace: Option<&'a Value>,
+}
+
+impl<'a> Layers<'a> {
+    fn child(self, key: &str) -> Self {
+        Self {
+            defaults: self.defaults.and_then(|v| v.get(key)),
+            profile: self.profile.and_then(|v| v.get(key)),
+            workspace: self.workspace.and_then(|v| v.get(key)),
+        }
+    }
+}
+
+/// Maps every leaf of `effective` to the layer that set it.
+///
+/// A leaf not set by an overlay counts as a default when it equals the
+/// built-in default, and as a user value otherwise.
+pub fn annotate_sources(
+    effective: &Value,
+    defaults: &Value,
+    profile: Option<&Value>,
+    workspace: Option<&Value>,
+) -> BTreeMap<String, ConfigSource> {
+    let mut sources = BTreeMap::new();
+    let Some(sections) = effective.as_object() else {
+        return sources;
+    };
+
+    for (key, value) in sections {
+        let base_only = profiles::is_base_only(key);
+        let layers = Layers {
+            defaults: defaults.get(key),
+            profile: profile.filter(|_| !base_only).and_then(|v| v.get(key)),
+            workspace: workspace.filter(|_| !base_only).and_then(|v| v.get(key)),
+        };
+        annotate(key, value, layers, &mut sources);
+    }
+    sources
+}
+
+fn annotate(
+    path: &str,
+    value: &Value,
+    layers: Layers<'_>,
+    sources: &mut BTreeMap<String, ConfigSource>,
+) {
+    if let Some(map) = value.as_object().filter(|map| !map.is_empty()) {
+        for (key, child) in map {
+            annotate(
+                &format!("{}.{}", path, key),
+                child,
+                layers.child(key),
+                sources,
+            );
+        }
+        return;
+    }
+
+    let source = if layers.workspace.is_some() {
+        ConfigSource::Workspace
+    } else if layers.profile.is_some() {
+        ConfigSource::Profile
+    } else if layers.defaults == Some(value) {
+        ConfigSource::Default
+    } else {
+        ConfigSource::User
+    };
+    sources.insert(path.to_string(), source);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn annotates_each_leaf_with_the_winning_layer() {
+        let defaults =
+            json!({ "ai": { "primary": null, "fast": null, "timeout": 30 }, "theme": "light" });
+        let effective = json!({
+            "ai": { "primary": "team", "fast": "profile-fast", "timeout": 30 },
+            "theme": "dark",
+            "active_profile": "work"
+        });
+        let profile = json!({ "ai": { "primary": "profile", "fast": "profile-fast" }, "active_profile": "x" });
+        let workspace = json!({ "ai": { "primary": "team" } });
+
+        let sources = annotate_sources(&effective, &defaults, Some(&profile), Some(&workspace));
+        assert_eq!(sources["ai.primary"], ConfigSource::Workspace);
+        assert_eq!(sources["ai.fast"], ConfigSource::Profile);
+        assert_eq!(sources["ai.timeout"], ConfigSource::Default);
+        assert_eq!(sources["theme"], ConfigSource::User);
+        assert_eq!(sources["active_profile"], ConfigSource::User);
+    }
+}
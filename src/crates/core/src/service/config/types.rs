@@ -2,10 +2,18 @@
 //!
 //! Defines all configuration-related types shared between backend and frontend.
 
+use crate::infrastructure::filesystem::CacheType;
+use crate::infrastructure::storage::{
+    BackupSchedule, CleanupPolicy, CompressionSettings, StorageBackendKind, TargetCleanupPolicy,
+};
 use crate::util::errors::*;
+use crate::util::types::{
+    lookup_known_model, AvailableModel, KnownModel, ModelPricing, ReasoningConfig,
+    DEFAULT_CONTEXT_WINDOW,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Global configuration structure - matches the frontend `GlobalConfig` exactly.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +25,23 @@ pub struct GlobalConfig {
     pub terminal: TerminalConfig,
     pub workspace: WorkspaceConfig,
     pub ai: AIConfig,
+    /// Per-agent overrides keyed by agent id (e.g. `agentic`, `Explore`).
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub agents: HashMap<String, AgentOverrideConfig>,
     /// MCP server configuration (stored uniformly; supports both JSON and structured formats).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mcp_servers: Option<serde_json::Value>,
     /// Theme system configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub themes: Option<ThemesConfig>,
+    /// Named partial configs deep-merged over the base config when active.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, serde_json::Value>,
+    /// Name of the profile in `profiles` currently applied; `None` uses the base config.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Shape of the persisted config; see `migrations::CURRENT_SCHEMA_VERSION`.
+    pub schema_version: u32,
     pub version: String,
     #[serde(with = "chrono::serde::ts_milliseconds")]
     pub last_modified: chrono::DateTime<chrono::Utc>,
@@ -47,6 +66,14 @@ pub struct AppConfig {
     #[serde(default)]
     pub session_config: AppSessionConfig,
     pub ai_experience: AIExperienceConfig,
+    #[serde(default)]
+    pub storage: AppStorageConfig,
+    #[serde(default)]
+    pub plugins: AppPluginsConfig,
+    /// Turn off features that need the internet: network tools, remote MCP
+    /// servers and AI models not served on this machine.
+    #[serde(default)]
+    pub offline_mode: bool,
 }
 
 /// App logging configuration.
@@ -56,6 +83,13 @@ pub struct AppLoggingConfig {
     /// Runtime backend log level.
     /// Allowed values: trace, debug, info, warn, error, off.
     pub level: String,
+    /// Level overrides per log target prefix, e.g.
+    /// `{"bitfun_core::service::mcp": "debug"}`. The longest matching prefix
+    /// wins; other targets use `level`.
+    pub target_levels: HashMap<String, String>,
+    /// Record every backend event to `events.jsonl` in the session log
+    /// directory, for replaying agent runs while debugging.
+    pub record_events: bool,
 }
 
 /// Session-related UI preferences.
@@ -67,6 +101,60 @@ pub struct AppSessionConfig {
     pub default_mode: String,
 }
 
+/// Plugin sources.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AppPluginsConfig {
+    /// URL of the plugin marketplace index (JSON); empty disables the marketplace.
+    pub marketplace_url: String,
+}
+
+/// Local storage limits and cleanup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppStorageConfig {
+    /// Where persisted data lives: `file` (one JSON file per entry) or
+    /// `sqlite`. Run `bitfun storage migrate` before switching to `sqlite`.
+    pub backend: StorageBackendKind,
+    /// zstd-compress large session turns and context snapshots (`*.json.zst`).
+    pub compression_enabled: bool,
+    /// Files smaller than this many KB stay plain JSON.
+    pub compression_min_kb: u64,
+    /// Size limit in MB per cache type (e.g. `web_fetch`); unset types use built-in defaults.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub cache_quotas_mb: HashMap<CacheType, u64>,
+    /// Whether cleanup policies run on their schedule.
+    pub auto_cleanup_enabled: bool,
+    /// Cleanup policies; empty uses the built-in ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cleanup_policies: Vec<TargetCleanupPolicy>,
+    /// Scheduled backups of config, skills, sessions and memory.
+    pub backup: BackupSchedule,
+}
+
+impl AppStorageConfig {
+    /// Cleanup policy described by this config.
+    pub fn cleanup_policy(&self) -> CleanupPolicy {
+        CleanupPolicy {
+            auto_cleanup_enabled: self.auto_cleanup_enabled,
+            policies: if self.cleanup_policies.is_empty() {
+                TargetCleanupPolicy::defaults()
+            } else {
+                self.cleanup_policies.clone()
+            },
+            cache_quotas_mb: self.cache_quotas_mb.clone(),
+        }
+    }
+
+    /// Compression settings described by this config.
+    pub fn compression_settings(&self) -> CompressionSettings {
+        CompressionSettings {
+            enabled: self.compression_enabled,
+            min_bytes: self.compression_min_kb.saturating_mul(1024),
+        }
+    }
+}
+
 /// AI experience configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -77,6 +165,8 @@ pub struct AIExperienceConfig {
     pub enable_welcome_panel_ai_analysis: bool,
     /// Whether to enable visual mode.
     pub enable_visual_mode: bool,
+    /// Whether to summarize each completed dialog turn in three bullets with the fast model.
+    pub enable_turn_summary: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -221,6 +311,41 @@ pub struct EditorConfig {
     pub format_on_save: bool,
     pub format_on_paste: bool,
     pub trim_auto_whitespace: bool,
+    pub lsp: LspConfig,
+}
+
+/// Language server settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LspConfig {
+    /// Per-language overrides keyed by language id (`rust`, `typescript`, ...).
+    pub servers: HashMap<String, LspServerConfig>,
+    /// Servers running at once per workspace; further starts are refused.
+    pub max_running_servers: usize,
+    /// Documents kept open per workspace; the oldest is closed beyond this.
+    pub max_open_documents: usize,
+    /// How long `ReadLints` waits for a server to publish fresh diagnostics.
+    pub diagnostics_timeout_ms: u64,
+}
+
+/// Settings of the language server for one language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LspServerConfig {
+    pub enabled: bool,
+    /// Server executable (a path or a command on `PATH`, e.g. `rust-analyzer`)
+    /// used instead of an installed plugin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    pub args: Vec<String>,
+}
+
+impl LspConfig {
+    pub fn is_server_enabled(&self, language: &str) -> bool {
+        self.servers
+            .get(language)
+            .is_none_or(|server| server.enabled)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -283,6 +408,47 @@ pub struct WorkspaceConfig {
     pub line_ending: String,
     pub trim_trailing_whitespace: bool,
     pub insert_final_newline: bool,
+    /// Tools agents may use; set in a workspace's `.bitfun/config.json` to restrict that repository.
+    pub tool_policy: ToolPolicyConfig,
+    /// Environment variables new sessions in this workspace start with; values
+    /// may be `keyring:<name>` references into the secrets store. Only read
+    /// from the user config: a workspace's `.bitfun/config.json` cannot set it.
+    pub env: BTreeMap<String, String>,
+}
+
+/// Whether `name` can be used as an environment variable name: ASCII letters,
+/// digits and `_`, not starting with a digit.
+pub fn is_valid_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Tools offered to agents. Denied tools are left out of the tool definitions
+/// sent to the model, so it does not plan around them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolPolicyConfig {
+    /// When not empty, only these tools are offered.
+    pub allowed_tools: Vec<String>,
+    pub denied_tools: Vec<String>,
+}
+
+impl ToolPolicyConfig {
+    /// Tools listed as both allowed and denied.
+    pub fn conflicts(&self) -> Vec<&str> {
+        self.allowed_tools
+            .iter()
+            .filter(|tool| self.denied_tools.contains(tool))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn permits(&self, tool: &str) -> bool {
+        !self.denied_tools.iter().any(|denied| denied == tool)
+            && (self.allowed_tools.is_empty()
+                || self.allowed_tools.iter().any(|allowed| allowed == tool))
+    }
 }
 
 /// Model capability type (a model can have multiple capabilities).
@@ -421,6 +587,26 @@ pub struct AIConfig {
     /// Allow Claw Computer use (desktop automation) when the desktop host is available.
     #[serde(default)]
     pub computer_use_enabled: bool,
+
+    /// Opt-in request/response logging to the `ai` log target.
+    #[serde(default)]
+    pub request_log: AIRequestLogConfig,
+
+    /// Spend thresholds enforced before each model round.
+    #[serde(default)]
+    pub spend_guardrails: SpendGuardrailsConfig,
+
+    /// Sandbox applied to Bash tool commands.
+    #[serde(default)]
+    pub bash_sandbox: BashSandboxConfig,
+
+    /// Detection of agents repeating the same tool calls.
+    #[serde(default)]
+    pub loop_guard: LoopGuardConfig,
+
+    /// Digest of recently modified workspace files attached to a session's first turn.
+    #[serde(default)]
+    pub recent_changes_context: RecentChangesContextConfig,
 }
 
 impl AIConfig {
@@ -513,6 +699,152 @@ impl Default for ModeConfig {
     }
 }
 
+/// Request/response logging to the `ai` log target; changes apply without restart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AIRequestLogConfig {
+    /// Log each request and the reconstructed response as JSON lines.
+    pub enabled: bool,
+
+    /// Replace file bodies (file-reading tool results, write/edit arguments) with a size marker.
+    pub redact_file_contents: bool,
+
+    /// Mask strings that look like API keys, tokens or private keys.
+    pub redact_secrets: bool,
+
+    /// Characters kept per message content or response field.
+    pub max_content_chars: usize,
+
+    /// Upper bound for one log entry in bytes.
+    pub max_entry_bytes: usize,
+}
+
+impl Default for AIRequestLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            redact_file_contents: true,
+            redact_secrets: true,
+            max_content_chars: 2000,
+            max_entry_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Spend thresholds in USD, computed from model `pricing`; every threshold is off by default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpendGuardrailsConfig {
+    /// Emit a warning once today's spend reaches this amount.
+    pub daily_warn_usd: Option<f64>,
+
+    /// Ask before a turn whose estimated prompt cost (from the context size) exceeds this amount.
+    pub turn_confirm_usd: Option<f64>,
+
+    /// Refuse new model rounds once today's spend reaches this amount.
+    pub daily_hard_stop_usd: Option<f64>,
+}
+
+impl SpendGuardrailsConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.daily_warn_usd.is_some()
+            || self.turn_confirm_usd.is_some()
+            || self.daily_hard_stop_usd.is_some()
+    }
+}
+
+/// Thresholds for stopping an agent that keeps making the same tool calls,
+/// counted in consecutive repetitions of a call or a short cycle of calls.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoopGuardConfig {
+    pub enabled: bool,
+
+    /// Remind the model that it is repeating itself after this many repetitions.
+    pub nudge_after: usize,
+
+    /// End the turn after this many repetitions.
+    pub stop_after: usize,
+
+    /// Longest cycle of rounds (e.g. edit A, edit B, edit A, ...) that is detected.
+    pub max_cycle_length: usize,
+}
+
+impl Default for LoopGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            nudge_after: 3,
+            stop_after: 5,
+            max_cycle_length: 3,
+        }
+    }
+}
+
+/// Digest of recently modified workspace files (from git status and the file
+/// watcher) attached to the first turn of a session, so the agent starts from
+/// the user's in-progress work. Set it in a workspace's `.bitfun/config.json`
+/// to enable it per workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentChangesContextConfig {
+    pub enabled: bool,
+
+    /// Most files listed in the digest.
+    pub max_files: usize,
+
+    /// Longest diff excerpt per file, in characters.
+    pub max_excerpt_chars: usize,
+
+    /// Estimated tokens the whole digest may use; excerpts that don't fit are left out.
+    pub token_budget: usize,
+
+    /// How far back file watcher changes count as recent.
+    pub lookback_minutes: u64,
+}
+
+impl Default for RecentChangesContextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_files: 8,
+            max_excerpt_chars: 1500,
+            token_budget: 2000,
+            lookback_minutes: 120,
+        }
+    }
+}
+
+/// Sandbox for Bash tool commands: writes are limited to the workspace, the
+/// temp directories and `writable_paths`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BashSandboxConfig {
+    /// Run Bash commands sandboxed where the platform supports it.
+    pub enabled: bool,
+
+    /// Let sandboxed commands use the network.
+    pub allow_network: bool,
+
+    /// Run sandboxed commands without asking for confirmation; commands that
+    /// run unsandboxed still ask.
+    pub auto_allow_sandboxed: bool,
+
+    /// Extra directories sandboxed commands may write to (e.g. package caches).
+    pub writable_paths: Vec<String>,
+}
+
+impl Default for BashSandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_network: false,
+            auto_allow_sandboxed: true,
+            writable_paths: Vec::new(),
+        }
+    }
+}
+
 /// Debug-mode configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -749,6 +1081,24 @@ impl Default for SubAgentConfig {
     }
 }
 
+/// Per-agent override of the model, tools and turn limit (`agents.<agent_id>`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentOverrideConfig {
+    /// Model ID from `ai.models`, or `primary` / `fast` / `auto`; overrides `ai.agent_models`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tools added to the agent's default (or mode-configured) tools.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_tools: Vec<String>,
+    /// Tools removed from the agent's tools; applied after `additional_tools`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_tools: Vec<String>,
+    /// Maximum model rounds per dialog turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AIModelConfig {
@@ -764,7 +1114,8 @@ pub struct AIModelConfig {
     pub request_url: Option<String>,
 
     pub api_key: String,
-    /// Context window size (total token limit for input + output).
+    /// Context window size (total token limit for input + output). None = the built-in value
+    /// for known models, else a conservative default.
     pub context_window: Option<u32>,
     /// Max output tokens (request parameter limiting model output length).
     pub max_tokens: Option<u32>,
@@ -797,7 +1148,8 @@ pub struct AIModelConfig {
     #[serde(default)]
     pub inline_think_in_text: bool,
 
-    /// Custom HTTP request headers.
+    /// Custom HTTP request headers. Values may reference environment variables as `${VAR}`
+    /// or `${env:VAR}`.
     #[serde(default)]
     pub custom_headers: Option<std::collections::HashMap<String, String>>,
 
@@ -815,6 +1167,102 @@ pub struct AIModelConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<String>,
 
+    /// Reasoning settings (thinking on/off, Anthropic budget, OpenAI effort). When set, takes
+    /// precedence over `enable_thinking_process` and `reasoning_effort`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ReasoningConfig>,
+
+    /// Max retries for rate-limited (429) or transient server errors before the request
+    /// fails. Only failures before any output is received are retried. None = default (3).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// Request complete JSON responses instead of SSE (OpenAI and Anthropic formats), for
+    /// gateways that break streaming.
+    #[serde(default)]
+    pub disable_streaming: bool,
+
+    /// Max seconds to wait between two streamed events before the request fails.
+    /// None = default (600).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_idle_timeout_secs: Option<u64>,
+
+    /// Max total seconds a streamed response may take, however often events arrive.
+    /// None = no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_deadline_secs: Option<u64>,
+
+    /// Whether to add Anthropic `cache_control` breakpoints to tools, system prompt and the
+    /// conversation prefix. Only applies to the "anthropic" API format.
+    #[serde(default)]
+    pub enable_prompt_caching: bool,
+
+    /// Minimum estimated prefix tokens before a cache breakpoint is placed. None = default (1024).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_min_tokens: Option<u32>,
+
+    /// Whether to let Anthropic run web searches itself (the `web_search` server tool) instead
+    /// of offering the local WebSearch tool. Only applies to the "anthropic" API format.
+    #[serde(default)]
+    pub native_web_search: bool,
+
+    /// Embedding model used when the caller doesn't name one. None = `model_name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+
+    /// OpenAI-compatible embeddings endpoint, e.g. a local server. None = derived from
+    /// `base_url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embeddings_url: Option<String>,
+
+    /// Max inputs per embeddings request. None = provider default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_batch_size: Option<usize>,
+
+    /// Prices used to compute spend from reported token usage. None = list prices for known
+    /// models, else spend not tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+
+    /// Whether image attachments are sent to this model as image parts. None = derived from
+    /// `capabilities` / `category` and the built-in table of known models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_vision: Option<bool>,
+
+    /// Whether requests to this model may carry tool definitions. None = derived from
+    /// `capabilities` / `category` and the built-in table of known models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_tools: Option<bool>,
+
+    /// Whether the model can produce reasoning output. None = derived from the built-in table
+    /// of known models and the configured reasoning settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_reasoning: Option<bool>,
+
+    /// Longest side, in pixels, that attached images are scaled down to before sending.
+    /// None = provider limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_max_dimension: Option<u32>,
+
+    /// JPEG quality (1-100) images are re-encoded at when they must be shrunk. None = 85.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_quality: Option<u8>,
+
+    /// Whether image analysis with this model runs local OCR first and skips the model for
+    /// text-dominant images. None = enabled when OCR is available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_ocr: Option<bool>,
+
+    /// Proxy for this model's requests, overriding the global `ai.proxy`. A disabled entry
+    /// forces a direct connection. None = global proxy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+
+    /// Models (by id, name, or model_name) tried in order when this model fails with a
+    /// non-retryable error or exhausts its retries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_models: Vec<String>,
+
     /// Custom request body (JSON string, used to override default request body fields).
     #[serde(default)]
     pub custom_request_body: Option<String>,
@@ -835,6 +1283,16 @@ pub struct ProxyConfig {
 
     /// Proxy password (optional).
     pub password: Option<String>,
+
+    /// Hosts reached directly, bypassing the proxy (NO_PROXY syntax: `example.com`,
+    /// `.internal.corp`, `10.0.0.0/8`, `*`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub no_proxy: Vec<String>,
+
+    /// PEM bundle trusted in addition to the system roots, e.g. the root certificate of a
+    /// TLS-inspecting corporate proxy. Applies even when the proxy itself is disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle_path: Option<String>,
 }
 
 /// Configuration provider interface.
@@ -889,6 +1347,12 @@ pub struct ConfigValidationError {
     pub message: String,
     pub code: String,
     pub severity: String,
+    /// Offending value at `path`, when it could be located.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// Closest known value or key for a misspelling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -897,6 +1361,12 @@ pub struct ConfigValidationWarning {
     pub message: String,
     pub code: String,
     pub severity: String,
+    /// Offending value at `path`, when it could be located.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// Closest known value or key for a misspelling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
 }
 
 impl Default for GlobalConfig {
@@ -908,8 +1378,12 @@ impl Default for GlobalConfig {
             terminal: TerminalConfig::default(),
             workspace: WorkspaceConfig::default(),
             ai: AIConfig::default(),
+            agents: HashMap::new(),
             mcp_servers: None,
             themes: Some(ThemesConfig::default()),
+            profiles: HashMap::new(),
+            active_profile: None,
+            schema_version: super::migrations::CURRENT_SCHEMA_VERSION,
             version: "1.0.0".to_string(),
             last_modified: chrono::Utc::now(),
         }
@@ -943,6 +1417,9 @@ impl Default for AppConfig {
             },
             session_config: AppSessionConfig::default(),
             ai_experience: AIExperienceConfig::default(),
+            storage: AppStorageConfig::default(),
+            plugins: AppPluginsConfig::default(),
+            offline_mode: false,
         }
     }
 }
@@ -952,6 +1429,22 @@ impl Default for AppLoggingConfig {
         Self {
             // Set to Debug in early development for easier diagnostics
             level: "debug".to_string(),
+            target_levels: HashMap::new(),
+            record_events: true,
+        }
+    }
+}
+
+impl Default for AppStorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackendKind::File,
+            compression_enabled: true,
+            compression_min_kb: 64,
+            cache_quotas_mb: HashMap::new(),
+            auto_cleanup_enabled: true,
+            cleanup_policies: Vec::new(),
+            backup: BackupSchedule::default(),
         }
     }
 }
@@ -970,6 +1463,7 @@ impl Default for AIExperienceConfig {
             enable_session_title_generation: true,
             enable_welcome_panel_ai_analysis: false,
             enable_visual_mode: false,
+            enable_turn_summary: false,
         }
     }
 }
@@ -1086,6 +1580,28 @@ impl Default for EditorConfig {
             format_on_save: true,
             format_on_paste: true,
             trim_auto_whitespace: true,
+            lsp: LspConfig::default(),
+        }
+    }
+}
+
+impl Default for LspConfig {
+    fn default() -> Self {
+        Self {
+            servers: HashMap::new(),
+            max_running_servers: 4,
+            max_open_documents: 200,
+            diagnostics_timeout_ms: 5000,
+        }
+    }
+}
+
+impl Default for LspServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            command: None,
+            args: Vec::new(),
         }
     }
 }
@@ -1152,6 +1668,8 @@ impl Default for WorkspaceConfig {
             line_ending: "auto".to_string(),
             trim_trailing_whitespace: true,
             insert_final_newline: true,
+            tool_policy: ToolPolicyConfig::default(),
+            env: BTreeMap::new(),
         }
     }
 }
@@ -1172,6 +1690,11 @@ impl Default for AIConfig {
             debug_mode_config: DebugModeConfig::default(),
             known_tools: Vec::new(),
             computer_use_enabled: false,
+            request_log: AIRequestLogConfig::default(),
+            spend_guardrails: SpendGuardrailsConfig::default(),
+            bash_sandbox: BashSandboxConfig::default(),
+            loop_guard: LoopGuardConfig::default(),
+            recent_changes_context: RecentChangesContextConfig::default(),
         }
     }
 }
@@ -1183,6 +1706,8 @@ impl Default for ProxyConfig {
             url: String::new(),
             username: None,
             password: None,
+            no_proxy: Vec::new(),
+            ca_bundle_path: None,
         }
     }
 }
@@ -1215,6 +1740,26 @@ impl Default for AIModelConfig {
             custom_headers_mode: None,
             skip_ssl_verify: false,
             reasoning_effort: None,
+            reasoning: None,
+            max_retries: None,
+            disable_streaming: false,
+            stream_idle_timeout_secs: None,
+            stream_deadline_secs: None,
+            enable_prompt_caching: false,
+            prompt_cache_min_tokens: None,
+            native_web_search: false,
+            embedding_model: None,
+            embeddings_url: None,
+            embedding_batch_size: None,
+            pricing: None,
+            supports_vision: None,
+            supports_tools: None,
+            supports_reasoning: None,
+            image_max_dimension: None,
+            image_quality: None,
+            image_ocr: None,
+            proxy: None,
+            fallback_models: vec![],
             custom_request_body: None,
         }
     }
@@ -1369,6 +1914,98 @@ impl AIModelConfig {
         }
     }
 
+    /// Built-in entry for this model, if `model_name` is a well-known model.
+    pub fn known_model(&self) -> Option<&'static KnownModel> {
+        lookup_known_model(&self.model_name)
+    }
+
+    /// Whether requests carrying tool definitions can be sent to this model.
+    ///
+    /// An explicit `supports_tools` wins, then the `FunctionCalling` tag, then the built-in
+    /// table. Chat-style categories are used with tools throughout the agent loop without an
+    /// explicit tag, so other unknown models need the tag to qualify.
+    pub fn supports_tool_calls(&self) -> bool {
+        self.supports_tools.unwrap_or_else(|| {
+            if self
+                .capabilities
+                .contains(&ModelCapability::FunctionCalling)
+            {
+                return true;
+            }
+            match self.known_model() {
+                Some(known) => known.supports_tools,
+                None => matches!(
+                    self.category,
+                    ModelCategory::GeneralChat
+                        | ModelCategory::Multimodal
+                        | ModelCategory::CodeSpecialized
+                ),
+            }
+        })
+    }
+
+    /// Whether the model sees attached images directly rather than a text description.
+    ///
+    /// An explicit `supports_vision` wins; otherwise the `ImageUnderstanding` capability, the
+    /// `Multimodal` category or a vision-capable known model qualifies.
+    pub fn supports_image_input(&self) -> bool {
+        self.supports_vision.unwrap_or_else(|| {
+            self.capabilities
+                .contains(&ModelCapability::ImageUnderstanding)
+                || matches!(self.category, ModelCategory::Multimodal)
+                || self
+                    .known_model()
+                    .is_some_and(|known| known.supports_vision)
+        })
+    }
+
+    /// Whether the model can produce reasoning output.
+    ///
+    /// An explicit `supports_reasoning` wins; otherwise the built-in table decides, and unknown
+    /// models qualify when reasoning is turned on in their config.
+    pub fn supports_reasoning_output(&self) -> bool {
+        self.supports_reasoning
+            .unwrap_or_else(|| match self.known_model() {
+                Some(known) => known.supports_reasoning,
+                None => match &self.reasoning {
+                    Some(reasoning) => reasoning.enabled,
+                    None => self.enable_thinking_process,
+                },
+            })
+    }
+
+    /// Configured context window, else the known model's, else [`DEFAULT_CONTEXT_WINDOW`].
+    pub fn effective_context_window(&self) -> u32 {
+        self.context_window
+            .or_else(|| self.known_model().map(|known| known.context_window))
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+    }
+
+    /// Configured prices, else the known model's list prices.
+    pub fn effective_pricing(&self) -> Option<ModelPricing> {
+        self.pricing
+            .clone()
+            .or_else(|| self.known_model().map(|known| known.pricing.clone()))
+    }
+
+    /// This model with its capabilities, context window and prices resolved for display.
+    pub fn to_available_model(&self) -> AvailableModel {
+        AvailableModel {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            provider: self.provider.clone(),
+            model_name: self.model_name.clone(),
+            enabled: self.enabled,
+            supports_tools: self.supports_tool_calls(),
+            supports_vision: self.supports_image_input(),
+            supports_reasoning: self.supports_reasoning_output(),
+            context_window: self.effective_context_window(),
+            max_tokens: self.max_tokens,
+            pricing: self.effective_pricing(),
+            known: self.known_model().is_some(),
+        }
+    }
+
     /// Auto-completes missing capability information without rewriting explicit configuration.
     ///
     /// Important: we intentionally do not upgrade `category` or append inferred capabilities
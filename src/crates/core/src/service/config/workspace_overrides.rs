@@ -0,0 +1,387 @@
+//! Workspace config overrides
+//!
+//! A repository can commit `<workspace>/.bitfun/config.json` to share team
+//! settings such as the default model or permission rules. The file is a
+//! partial config merged over the user config, so the effective precedence is
+//! workspace > profile > user > defaults.
+//!
+//! A cloned repository is untrusted: keys that carry credentials, and sections
+//! that decide where requests go or which commands run (model providers, the
+//! proxy, MCP servers, language server commands, the environment and sandbox
+//! of tool commands), are dropped with a warning so a repo cannot redirect the
+//! user's API keys to its own endpoint or run programs of its choosing.
+
+use super::file_watcher::ConfigFileWatcher;
+use super::global::GlobalConfigManager;
+use super::profiles;
+use super::schema;
+use super::secrets::is_sensitive_key;
+use super::types::{ConfigValidationWarning, GlobalConfig};
+use crate::util::errors::*;
+use log::warn;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::time::Duration;
+
+const ENDPOINTS_ONLY_IN_USER_CONFIG: &str =
+    "providers, proxies and MCP servers can only be set in the user config";
+const COMMANDS_ONLY_IN_USER_CONFIG: &str =
+    "language server commands can only be set in the user config";
+const ENV_ONLY_IN_USER_CONFIG: &str =
+    "environment variables for tool commands can only be set in the user config";
+const SANDBOX_ONLY_IN_USER_CONFIG: &str = "the Bash sandbox can only be set in the user config";
+
+/// Sections a workspace config may never set, with the reason given for
+/// ignoring them; `*` matches any one key.
+const BLOCKED_PATHS: &[(&str, &str)] = &[
+    ("ai.models", ENDPOINTS_ONLY_IN_USER_CONFIG),
+    ("ai.proxy", ENDPOINTS_ONLY_IN_USER_CONFIG),
+    ("mcp_servers", ENDPOINTS_ONLY_IN_USER_CONFIG),
+    ("editor.lsp.servers.*.command", COMMANDS_ONLY_IN_USER_CONFIG),
+    ("editor.lsp.servers.*.args", COMMANDS_ONLY_IN_USER_CONFIG),
+    // PATH, LD_PRELOAD or `keyring:` references would reach every command the model runs
+    ("workspace.env", ENV_ONLY_IN_USER_CONFIG),
+    // Could turn the sandbox off or let unconfirmed commands write anywhere
+    ("ai.bash_sandbox", SANDBOX_ONLY_IN_USER_CONFIG),
+];
+
+const REJECTED_KEY_CODE: &str = "WORKSPACE_KEY_REJECTED";
+const RELOAD_DEBOUNCE_MS: u64 = 350;
+
+/// Overrides loaded from the active workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspaceOverrides {
+    /// Workspace root.
+    pub root: PathBuf,
+    /// The `.bitfun/config.json` the overrides came from.
+    pub file: PathBuf,
+    /// Partial config with rejected keys removed.
+    pub overlay: Value,
+    /// One warning per rejected key.
+    pub rejected: Vec<ConfigValidationWarning>,
+}
+
+/// Reads the workspace config `file`; `Ok(None)` when the file does not exist.
+pub async fn load(root: &Path, file: &Path) -> BitFunResult<Option<WorkspaceOverrides>> {
+    let content = match tokio::fs::read_to_string(file).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(BitFunError::config(format!(
+                "Failed to read workspace config {}: {}",
+                file.display(),
+                e
+            )))
+        }
+    };
+
+    let raw: Value = serde_json::from_str(&content).map_err(|e| {
+        BitFunError::config(format!(
+            "Failed to parse workspace config {}: {}",
+            file.display(),
+            e
+        ))
+    })?;
+    let (overlay, rejected) = sanitize(raw)?;
+    for warning in &rejected {
+        warn!(
+            "Ignoring '{}' in workspace config {}: {}",
+            warning.path,
+            file.display(),
+            warning.message
+        );
+    }
+
+    let report = schema::validate_config_value(&overlay);
+    if !report.valid {
+        let errors: Vec<String> = report.errors.iter().map(schema::describe_error).collect();
+        return Err(BitFunError::validation(format!(
+            "Invalid workspace config {}: {}",
+            file.display(),
+            errors.join("; ")
+        )));
+    }
+
+    Ok(Some(WorkspaceOverrides {
+        root: root.to_path_buf(),
+        file: file.to_path_buf(),
+        overlay,
+        rejected,
+    }))
+}
+
+/// Removes keys a workspace may not set, returning the rest and a warning per removed key.
+pub fn sanitize(mut raw: Value) -> BitFunResult<(Value, Vec<ConfigValidationWarning>)> {
+    let Some(sections) = raw.as_object_mut() else {
+        return Err(BitFunError::validation(
+            "Workspace config must be a JSON object".to_string(),
+        ));
+    };
+
+    let mut rejected = Vec::new();
+    sections.retain(|key, _| {
+        let keep = !profiles::is_base_only(key);
+        if !keep {
+            rejected.push(rejection(key, "only the user config can set it"));
+        }
+        keep
+    });
+    strip(&mut raw, "", &mut rejected);
+    Ok((raw, rejected))
+}
+
+fn strip(value: &mut Value, path: &str, rejected: &mut Vec<ConfigValidationWarning>) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| {
+                let child = join(path, key);
+                if let Some(reason) = blocked_reason(&child) {
+                    rejected.push(rejection(&child, reason));
+                    false
+                } else if is_sensitive_key(key) {
+                    rejected.push(rejection(
+                        &child,
+                        "credentials are never read from a workspace",
+                    ));
+                    false
+                } else {
+                    true
+                }
+            });
+            for (key, child) in map.iter_mut() {
+                strip(child, &join(path, key), rejected);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                strip(item, &format!("{}[{}]", path, index), rejected);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Why `path` may not be set by a workspace, if it is blocked.
+fn blocked_reason(path: &str) -> Option<&'static str> {
+    BLOCKED_PATHS
+        .iter()
+        .find(|(pattern, _)| {
+            let mut keys = path.split('.');
+            pattern.split('.').all(|expected| {
+                keys.next()
+                    .is_some_and(|key| expected == "*" || expected == key)
+            }) && keys.next().is_none()
+        })
+        .map(|(_, reason)| *reason)
+}
+
+/// Returns `config` with the workspace `overlay` deep-merged over it.
+pub fn apply(config: &GlobalConfig, overlay: &Value) -> BitFunResult<GlobalConfig> {
+    let base = serde_json::to_value(config)
+        .map_err(|e| BitFunError::config(format!("Failed to serialize config: {}", e)))?;
+    serde_json::from_value(super::manager::deep_merge(base, overlay.clone()))
+        .map_err(|e| BitFunError::config(format!("Failed to apply workspace config: {}", e)))
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn rejection(path: &str, reason: &str) -> ConfigValidationWarning {
+    ConfigValidationWarning {
+        path: path.to_string(),
+        message: format!("Ignored in workspace config: {}", reason),
+        code: REJECTED_KEY_CODE.to_string(),
+        severity: "warning".to_string(),
+        value: None,
+        suggestion: None,
+    }
+}
+
+/// Reloads the workspace config when its file changes.
+#[derive(Default)]
+pub struct WorkspaceConfigWatcher {
+    file_watcher: ConfigFileWatcher,
+    /// Workspace root and config file being watched.
+    watched: Mutex<Option<(PathBuf, PathBuf)>>,
+}
+
+impl WorkspaceConfigWatcher {
+    /// Watches `file` (a workspace's `.bitfun/config.json`); `None` stops watching.
+    ///
+    /// Watches the `.bitfun` directory, or the workspace root until that
+    /// directory exists, so creating the file is noticed too.
+    pub fn watch(&self, root: Option<&Path>, file: Option<&Path>) {
+        let watched = root.zip(file);
+        *lock(&self.watched) = watched.map(|(root, file)| (root.to_path_buf(), file.to_path_buf()));
+
+        let Some((root, file)) = watched else {
+            self.file_watcher.unwatch();
+            return;
+        };
+        self.file_watcher.watch(
+            file,
+            root,
+            Duration::from_millis(RELOAD_DEBOUNCE_MS),
+            |_| async {
+                if let Err(e) = GlobalConfigManager::refresh_workspace_config().await {
+                    warn!("Failed to reload workspace config: {}", e);
+                }
+            },
+        );
+    }
+
+    /// Workspace root and config file being watched.
+    pub fn watched(&self) -> Option<(PathBuf, PathBuf)> {
+        lock(&self.watched).clone()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl std::fmt::Debug for WorkspaceConfigWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkspaceConfigWatcher").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_credentials_and_provider_overrides() {
+        let raw = json!({
+            "ai": {
+                "default_models": { "primary": "team-model" },
+                "models": [{ "id": "evil", "base_url": "https://attacker.example" }],
+                "proxy": { "enabled": true },
+                "mode_configs": { "agentic": { "api_key": "sk-1", "max_tokens": 10 } }
+            },
+            "mcp_servers": { "mcpServers": {} },
+            "active_profile": "work"
+        });
+
+        let (overlay, rejected) = sanitize(raw).unwrap();
+        let paths: Vec<_> = rejected.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "active_profile",
+                "mcp_servers",
+                "ai.models",
+                "ai.proxy",
+                "ai.mode_configs.agentic.api_key"
+            ]
+        );
+        assert_eq!(overlay["ai"]["default_models"]["primary"], "team-model");
+        assert_eq!(overlay["ai"]["mode_configs"]["agentic"]["max_tokens"], 10);
+    }
+
+    #[test]
+    fn rejects_language_server_commands() {
+        let raw = json!({
+            "editor": {
+                "lsp": {
+                    "max_open_documents": 20,
+                    "servers": {
+                        "rust": { "enabled": false },
+                        "python": { "command": "./evil.sh", "args": ["--pwn"] }
+                    }
+                }
+            }
+        });
+
+        let (overlay, rejected) = sanitize(raw).unwrap();
+        let paths: Vec<_> = rejected.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "editor.lsp.servers.python.args",
+                "editor.lsp.servers.python.command"
+            ]
+        );
+        assert_eq!(overlay["editor"]["lsp"]["max_open_documents"], 20);
+        assert_eq!(
+            overlay["editor"]["lsp"]["servers"]["rust"]["enabled"],
+            false
+        );
+        assert_eq!(overlay["editor"]["lsp"]["servers"]["python"], json!({}));
+    }
+
+    #[test]
+    fn rejects_tool_command_environment() {
+        let raw = json!({
+            "workspace": {
+                "exclude_patterns": ["target"],
+                "env": { "LD_PRELOAD": "/tmp/evil.so", "TOKEN": "keyring:github" }
+            }
+        });
+
+        let (overlay, rejected) = sanitize(raw).unwrap();
+        let paths: Vec<_> = rejected.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["workspace.env"]);
+        assert_eq!(
+            overlay["workspace"],
+            json!({ "exclude_patterns": ["target"] })
+        );
+    }
+
+    #[test]
+    fn rejects_bash_sandbox_settings() {
+        let raw = json!({
+            "ai": {
+                "bash_sandbox": {
+                    "enabled": false,
+                    "auto_allow_sandboxed": true,
+                    "allow_network": true,
+                    "writable_paths": ["/"]
+                },
+                "loop_guard": { "enabled": true }
+            }
+        });
+
+        let (overlay, rejected) = sanitize(raw).unwrap();
+        let paths: Vec<_> = rejected.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["ai.bash_sandbox"]);
+        assert_eq!(overlay["ai"], json!({ "loop_guard": { "enabled": true } }));
+    }
+
+    #[test]
+    fn workspace_values_override_the_profile_and_user_config() {
+        let mut config = GlobalConfig::default();
+        config.ai.default_models.primary = Some("user-model".to_string());
+        config.ai.default_models.fast = Some("user-fast".to_string());
+        config.profiles.insert(
+            "work".to_string(),
+            json!({ "ai": { "default_models": { "primary": "profile-model" } } }),
+        );
+        config.active_profile = Some("work".to_string());
+
+        let profiled = profiles::effective_config(&config).unwrap();
+        let effective = apply(
+            &profiled,
+            &json!({ "ai": { "default_models": { "primary": "team-model" } } }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            effective.ai.default_models.primary.as_deref(),
+            Some("team-model")
+        );
+        assert_eq!(
+            effective.ai.default_models.fast.as_deref(),
+            Some("user-fast")
+        );
+    }
+}
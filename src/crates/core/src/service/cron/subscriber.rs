@@ -1,7 +1,7 @@
 //! Scheduled job event subscriber.
 
 use super::service::CronService;
-use crate::agentic::events::{AgenticEvent, EventSubscriber};
+use crate::agentic::events::{AgenticEvent, EventFilter, EventSubscriber};
 use crate::util::errors::BitFunResult;
 use log::error;
 use std::sync::Arc;
@@ -47,4 +47,8 @@ impl EventSubscriber for CronEventSubscriber {
 
         result
     }
+
+    fn filter(&self) -> EventFilter {
+        EventFilter::new("agentic://dialog-turn-*")
+    }
 }
@@ -2,8 +2,10 @@
 //!
 //! Provides unified diff calculation, merge, and status management.
 
+pub mod patch;
 pub mod service;
 pub mod types;
 
+pub use patch::{apply_patch_hunks, parse_hunk_header, parse_patch, push_hunk_line, PatchFile};
 pub use service::DiffService;
 pub use types::*;
@@ -0,0 +1,296 @@
+//! Unified diff patches
+//!
+//! Parses unified diff text into hunks and applies them to file contents
+//! without git. Hand-written patches (e.g. suggested fixes) often lack
+//! `diff --git` lines or carry wrong line counts, so hunks are located by
+//! their content; header line numbers only choose among several matches.
+
+use super::types::{DiffHunk, DiffLine, DiffLineType};
+
+/// The changes a patch makes to one file
+#[derive(Debug, Clone)]
+pub struct PatchFile {
+    /// Path before the change, without the `a/` prefix; `None` for a new file
+    pub old_path: Option<String>,
+    /// Path after the change, without the `b/` prefix; `None` for a deleted file
+    pub new_path: Option<String>,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl PatchFile {
+    /// The file the patch writes, or the deleted one
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Parses `@@ -a,b +c,d @@ context`; a missing count means one line
+pub fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let ranges = line.strip_prefix("@@ ")?.split(" @@").next()?;
+    let (old, new) = ranges.split_once(' ')?;
+    let range = |text: &str, sign: char| -> Option<(usize, usize)> {
+        let text = text.strip_prefix(sign)?;
+        match text.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((text.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old, '-')?;
+    let (new_start, new_lines) = range(new, '+')?;
+
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    })
+}
+
+/// Appends a `+`, `-` or ` ` line to `hunk`, numbered from the hunk's start lines
+pub fn push_hunk_line(hunk: &mut DiffHunk, line: &str) {
+    let next_old = hunk.old_start
+        + hunk
+            .lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::Add)
+            .count();
+    let next_new = hunk.new_start
+        + hunk
+            .lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::Delete)
+            .count();
+
+    let (line_type, content) = if let Some(content) = line.strip_prefix('+') {
+        (DiffLineType::Add, content)
+    } else if let Some(content) = line.strip_prefix('-') {
+        (DiffLineType::Delete, content)
+    } else if let Some(content) = line.strip_prefix(' ') {
+        (DiffLineType::Context, content)
+    } else {
+        // `\ No newline at end of file` only lives in the raw text
+        return;
+    };
+
+    hunk.lines.push(DiffLine {
+        line_type,
+        content: content.to_string(),
+        old_line_number: (line_type != DiffLineType::Add).then_some(next_old),
+        new_line_number: (line_type != DiffLineType::Delete).then_some(next_new),
+    });
+}
+
+/// Splits a unified diff into per-file changes. Text outside of file headers
+/// and hunks (`diff --git`, `index`, prose around the patch) is ignored.
+pub fn parse_patch(text: &str) -> Result<Vec<PatchFile>, String> {
+    let mut files: Vec<PatchFile> = Vec::new();
+    let mut in_hunk = false;
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(old) = line.strip_prefix("--- ") {
+            if let Some(new) = lines.peek().and_then(|next| next.strip_prefix("+++ ")) {
+                files.push(PatchFile {
+                    old_path: patch_path(old, "a/"),
+                    new_path: patch_path(new, "b/"),
+                    hunks: Vec::new(),
+                });
+                lines.next();
+                in_hunk = false;
+                continue;
+            }
+        }
+
+        if line.starts_with("@@") {
+            let file = files
+                .last_mut()
+                .ok_or_else(|| format!("Hunk without a ---/+++ file header: {}", line))?;
+            let hunk =
+                parse_hunk_header(line).ok_or_else(|| format!("Invalid hunk header: {}", line))?;
+            file.hunks.push(hunk);
+            in_hunk = true;
+            continue;
+        }
+
+        let Some(hunk) = files
+            .last_mut()
+            .and_then(|file| file.hunks.last_mut())
+            .filter(|_| in_hunk)
+        else {
+            continue;
+        };
+        match line.chars().next() {
+            Some('+' | '-' | ' ' | '\\') => push_hunk_line(hunk, line),
+            // Blank context lines often lose their leading space
+            None => push_hunk_line(hunk, " "),
+            Some(_) => in_hunk = false,
+        }
+    }
+
+    for hunk in files.iter_mut().flat_map(|file| file.hunks.iter_mut()) {
+        // Blank lines after the patch read as context; less context still applies
+        while hunk
+            .lines
+            .last()
+            .is_some_and(|line| line.line_type == DiffLineType::Context && line.content.is_empty())
+        {
+            hunk.lines.pop();
+        }
+    }
+    files.retain(|file| !file.hunks.is_empty() || file.new_path.is_none());
+    if files.is_empty() {
+        return Err("Patch contains no hunks".to_string());
+    }
+    Ok(files)
+}
+
+/// Path of a `---`/`+++` header line; `None` for `/dev/null`
+fn patch_path(text: &str, prefix: &str) -> Option<String> {
+    // Some tools append a tab and a timestamp
+    let path = text.split('\t').next().unwrap_or_default().trim();
+    let path = path.trim_matches('"');
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+/// Applies `hunks` in order to `content`, keeping its line endings. Each
+/// hunk's context and removed lines must follow the previous hunk; among
+/// several matches the one nearest to the header's line wins.
+pub fn apply_patch_hunks(content: &str, hunks: &[DiffHunk]) -> Result<String, String> {
+    let eol = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let trailing_newline = content.is_empty() || content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut search_from = 0;
+    // Lines added minus lines removed by the hunks applied so far
+    let mut shift: isize = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter(|line| line.line_type != DiffLineType::Add)
+            .map(|line| line.content.as_str())
+            .collect();
+        let new: Vec<String> = hunk
+            .lines
+            .iter()
+            .filter(|line| line.line_type != DiffLineType::Delete)
+            .map(|line| line.content.clone())
+            .collect();
+
+        let expected = (hunk.old_start.saturating_sub(1) as isize + shift).max(0) as usize;
+        let at = if old.is_empty() {
+            expected.clamp(search_from, lines.len())
+        } else {
+            find_block(&lines, &old, search_from, expected).ok_or_else(|| {
+                format!(
+                    "Hunk {} (@@ -{},{} +{},{} @@) does not match the file",
+                    index + 1,
+                    hunk.old_start,
+                    hunk.old_lines,
+                    hunk.new_start,
+                    hunk.new_lines
+                )
+            })?
+        };
+
+        search_from = at + new.len();
+        shift += new.len() as isize - old.len() as isize;
+        lines.splice(at..at + old.len(), new);
+    }
+
+    let mut result = lines.join(eol);
+    if trailing_newline && !lines.is_empty() {
+        result.push_str(eol);
+    }
+    Ok(result)
+}
+
+/// Start of `block` in `lines` at or after `from`, nearest to `near`; trailing
+/// whitespace is only ignored when there is no exact match
+fn find_block(lines: &[String], block: &[&str], from: usize, near: usize) -> Option<usize> {
+    let last_start = lines.len().checked_sub(block.len())?;
+    let find = |matches: fn(&str, &str) -> bool| {
+        (from..=last_start)
+            .filter(|&start| {
+                block
+                    .iter()
+                    .zip(&lines[start..])
+                    .all(|(expected, line)| matches(expected, line))
+            })
+            .min_by_key(|&start| start.abs_diff(near))
+    };
+    find(|a, b| a == b).or_else(|| find(|a, b| a.trim_end() == b.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "\
+Suggested fix:
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2,3 +2,3 @@ fn main() {
+     let total = 0;
+-    for i in 0..=len {
++    for i in 0..len {
+
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,2 @@
++pub fn added() {}
++// end
+
+";
+
+    #[test]
+    fn parses_patches_without_git_headers() {
+        let files = parse_patch(PATCH).unwrap();
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].old_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(files[0].path(), "src/lib.rs");
+        let lines = &files[0].hunks[0].lines;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2].line_type, DiffLineType::Add);
+        assert_eq!(lines[2].new_line_number, Some(3));
+
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(files[1].path(), "src/new.rs");
+        assert_eq!(files[1].hunks[0].lines.len(), 2);
+
+        assert!(parse_patch("no patch here").is_err());
+    }
+
+    #[test]
+    fn applies_hunks_by_content_and_keeps_line_endings() {
+        let files = parse_patch(PATCH).unwrap();
+        // Two lines more above the hunk than its header says
+        let content =
+            "// a\r\n// b\r\nfn main() {\r\n    let total = 0;\r\n    for i in 0..=len {\r\n}\r\n";
+        let patched = apply_patch_hunks(content, &files[0].hunks).unwrap();
+        assert_eq!(
+            patched,
+            "// a\r\n// b\r\nfn main() {\r\n    let total = 0;\r\n    for i in 0..len {\r\n}\r\n"
+        );
+
+        assert_eq!(
+            apply_patch_hunks("", &files[1].hunks).unwrap(),
+            "pub fn added() {}\n// end\n"
+        );
+
+        let error = apply_patch_hunks("fn main() {}\n", &files[0].hunks).unwrap_err();
+        assert!(error.contains("Hunk 1"));
+    }
+}
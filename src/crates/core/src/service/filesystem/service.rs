@@ -1,8 +1,11 @@
+use crate::infrastructure::filesystem::file_watcher::get_global_file_watcher;
+use crate::infrastructure::filesystem::{DirectorySizeReport, FileTreePatch};
 use crate::infrastructure::{
     FileInfo, FileOperationOptions, FileOperationService, FileReadResult, FileSearchResult,
     FileTreeNode, FileTreeService, FileWriteResult,
 };
 use crate::util::errors::*;
+use std::path::Path;
 use std::sync::Arc;
 
 use super::types::{DirectoryScanResult, DirectoryStats, FileSearchOptions, FileSystemConfig};
@@ -17,6 +20,7 @@ impl FileSystemService {
     /// Creates a new file system service.
     pub fn new(config: FileSystemConfig) -> Self {
         let file_tree_service = Arc::new(FileTreeService::new(config.tree_options));
+        get_global_file_watcher().register_tree_service(&file_tree_service);
         let file_operation_service = Arc::new(FileOperationService::new(config.operation_options));
 
         Self {
@@ -38,6 +42,48 @@ impl FileSystemService {
             .map_err(|e| BitFunError::service(e))
     }
 
+    /// Version of the cached tree of a watched workspace, if it is cached.
+    pub async fn get_file_tree_version(&self, root_path: &str) -> Option<u64> {
+        self.file_tree_service
+            .tree_version(Path::new(root_path))
+            .await
+    }
+
+    /// Changes to the cached tree of `root_path` since `since_version`.
+    pub async fn get_file_tree_changes(
+        &self,
+        root_path: &str,
+        since_version: u64,
+    ) -> BitFunResult<FileTreePatch> {
+        self.file_tree_service
+            .tree_changes_since(Path::new(root_path), since_version)
+            .await
+            .ok_or_else(|| BitFunError::service(format!("File tree is not cached: {}", root_path)))
+    }
+
+    /// Fuzzy file name search, best matches first.
+    pub async fn fuzzy_search_files(
+        &self,
+        root_path: &str,
+        query: &str,
+        max_results: usize,
+    ) -> BitFunResult<Vec<FileSearchResult>> {
+        self.file_tree_service
+            .fuzzy_search_files(root_path, query, max_results)
+            .await
+    }
+
+    /// Aggregated directory sizes and the largest files and directories.
+    pub async fn directory_size_report(
+        &self,
+        root_path: &str,
+        limit: usize,
+    ) -> BitFunResult<DirectorySizeReport> {
+        self.file_tree_service
+            .directory_size_report(root_path, limit)
+            .await
+    }
+
     /// Scans a directory and returns a detailed result.
     pub async fn scan_directory(&self, root_path: &str) -> BitFunResult<DirectoryScanResult> {
         let start_time = std::time::Instant::now();
@@ -3,11 +3,12 @@
  */
 use super::git_types::*;
 use super::git_utils::*;
+use super::history;
+use super::hunks;
+use super::remote::{self, RemoteOperation};
 use git2::{BranchType, Commit, Repository};
 use std::path::Path;
-use std::time::Duration;
 use std::time::Instant;
-use tokio::time::timeout;
 
 pub struct GitService;
 
@@ -516,45 +517,88 @@ impl GitService {
         })
     }
 
-    /// Pushes changes.
+    /// Fetches from a remote without prompting for credentials.
+    pub async fn fetch<P: AsRef<Path>>(
+        path: P,
+        params: GitFetchParams,
+    ) -> Result<GitOperationResult, GitError> {
+        let start_time = Instant::now();
+        let repo_path = path.as_ref().to_string_lossy();
+        let remote_name =
+            remote::resolve_remote(&repo_path, params.remote.as_deref(), RemoteOperation::Fetch);
+
+        let mut args = vec!["fetch", "--progress"];
+
+        if params.prune.unwrap_or(false) {
+            args.push("--prune");
+        }
+
+        args.push(&remote_name);
+
+        let output =
+            remote::run_remote_command(&repo_path, RemoteOperation::Fetch, &remote_name, &args)
+                .await?;
+
+        let duration = start_time.elapsed().as_millis() as u64;
+
+        Ok(GitOperationResult {
+            success: true,
+            data: Some(serde_json::json!({
+                "remote": remote_name,
+                "prune": params.prune
+            })),
+            error: None,
+            output: Some(output),
+            duration: Some(duration),
+        })
+    }
+
+    /// Pushes changes without prompting for credentials. A force-push uses
+    /// `--force-with-lease` and is refused unless it was confirmed.
     pub async fn push<P: AsRef<Path>>(
         path: P,
         params: GitPushParams,
     ) -> Result<GitOperationResult, GitError> {
         let start_time = Instant::now();
         let repo_path = path.as_ref().to_string_lossy();
+        let force = params.force.unwrap_or(false);
 
-        let mut args = vec!["push"];
+        if force && !params.force_confirmed.unwrap_or(false) {
+            return Err(GitError::CommandFailed(
+                "Force-push refused: it can discard commits on the remote and needs explicit confirmation"
+                    .to_string(),
+            ));
+        }
 
-        if params.force.unwrap_or(false) {
-            args.push("--force");
+        let remote_name =
+            remote::resolve_remote(&repo_path, params.remote.as_deref(), RemoteOperation::Push);
+
+        let mut args = vec!["push", "--progress"];
+
+        if force {
+            args.push("--force-with-lease");
         }
 
         if params.set_upstream.unwrap_or(false) {
             args.push("-u");
         }
 
-        if let Some(remote) = &params.remote {
-            args.push(remote);
-        }
+        args.push(&remote_name);
 
         if let Some(branch) = &params.branch {
             args.push(branch);
         }
 
-        let output = timeout(
-            Duration::from_secs(30),
-            execute_git_command(&repo_path, &args),
-        )
-        .await
-        .map_err(|_| GitError::NetworkError("Push operation timed out".to_string()))??;
+        let output =
+            remote::run_remote_command(&repo_path, RemoteOperation::Push, &remote_name, &args)
+                .await?;
 
         let duration = start_time.elapsed().as_millis() as u64;
 
         Ok(GitOperationResult {
             success: true,
             data: Some(serde_json::json!({
-                "remote": params.remote,
+                "remote": remote_name,
                 "branch": params.branch,
                 "force": params.force,
                 "set_upstream": params.set_upstream
@@ -565,41 +609,42 @@ impl GitService {
         })
     }
 
-    /// Pulls changes.
+    /// Pulls changes without prompting for credentials.
     pub async fn pull<P: AsRef<Path>>(
         path: P,
         params: GitPullParams,
     ) -> Result<GitOperationResult, GitError> {
         let start_time = Instant::now();
         let repo_path = path.as_ref().to_string_lossy();
+        let remote_name =
+            remote::resolve_remote(&repo_path, params.remote.as_deref(), RemoteOperation::Pull);
 
-        let mut args = vec!["pull"];
+        // No editor can be opened for a merge commit message
+        let mut args = vec!["pull", "--progress", "--no-edit"];
 
         if params.rebase.unwrap_or(false) {
             args.push("--rebase");
         }
 
-        if let Some(remote) = &params.remote {
-            args.push(remote);
+        // Without a branch git pulls the upstream, but only if no remote is named
+        if params.remote.is_some() || params.branch.is_some() {
+            args.push(&remote_name);
         }
 
         if let Some(branch) = &params.branch {
             args.push(branch);
         }
 
-        let output = timeout(
-            Duration::from_secs(30),
-            execute_git_command(&repo_path, &args),
-        )
-        .await
-        .map_err(|_| GitError::NetworkError("Pull operation timed out".to_string()))??;
+        let output =
+            remote::run_remote_command(&repo_path, RemoteOperation::Pull, &remote_name, &args)
+                .await?;
 
         let duration = start_time.elapsed().as_millis() as u64;
 
         Ok(GitOperationResult {
             success: true,
             data: Some(serde_json::json!({
-                "remote": params.remote,
+                "remote": remote_name,
                 "branch": params.branch,
                 "rebase": params.rebase
             })),
@@ -825,6 +870,103 @@ impl GitService {
         }
     }
 
+    /// Blames a line range of a file.
+    ///
+    /// # Parameters
+    /// - `path`: Repository path
+    /// - `file_path`: File path, relative to `path` or absolute
+    /// - `start_line`/`end_line`: 1-based inclusive range (whole file when unset)
+    ///
+    /// # Returns
+    /// - Line groups per commit, capped at `history::MAX_BLAME_LINES` lines
+    pub async fn blame<P: AsRef<Path>>(
+        path: P,
+        file_path: &str,
+        start_line: Option<u32>,
+        end_line: Option<u32>,
+    ) -> Result<GitBlameResult, GitError> {
+        let repo_path = path.as_ref().to_string_lossy();
+
+        history::blame(&repo_path, file_path, start_line, end_line).await
+    }
+
+    /// Gets the commits touching a file, following renames, one page at a time.
+    ///
+    /// # Parameters
+    /// - `path`: Repository path
+    /// - `file_path`: File path, relative to `path` or absolute
+    /// - `skip`: Number of newer commits to skip
+    /// - `limit`: Page size (defaults to `history::DEFAULT_FILE_LOG_LIMIT`)
+    pub async fn get_file_log<P: AsRef<Path>>(
+        path: P,
+        file_path: &str,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> Result<GitFileLog, GitError> {
+        let repo_path = path.as_ref().to_string_lossy();
+
+        history::file_log(&repo_path, file_path, skip, limit).await
+    }
+
+    /// Lists the hunks of the unstaged diff, or of the staged diff when
+    /// `staged` is set, per file.
+    ///
+    /// # Parameters
+    /// - `path`: Repository path
+    /// - `staged`: Whether to list staged hunks instead of unstaged ones
+    /// - `files`: Restrict the listing to these paths (all files when empty)
+    pub async fn list_hunks<P: AsRef<Path>>(
+        path: P,
+        staged: bool,
+        files: &[String],
+    ) -> Result<Vec<GitFileHunks>, GitError> {
+        let repo_path = path.as_ref().to_string_lossy();
+
+        let patches = hunks::load_file_patches(&repo_path, staged, files).await?;
+        Ok(patches.into_iter().map(|patch| patch.file).collect())
+    }
+
+    /// Stages individual hunks by the ids `list_hunks` returned for the
+    /// unstaged diff.
+    pub async fn stage_hunks<P: AsRef<Path>>(
+        path: P,
+        hunk_ids: &[String],
+    ) -> Result<GitOperationResult, GitError> {
+        Self::apply_hunks(path, hunk_ids, false).await
+    }
+
+    /// Unstages individual hunks by the ids `list_hunks` returned for the
+    /// staged diff.
+    pub async fn unstage_hunks<P: AsRef<Path>>(
+        path: P,
+        hunk_ids: &[String],
+    ) -> Result<GitOperationResult, GitError> {
+        Self::apply_hunks(path, hunk_ids, true).await
+    }
+
+    async fn apply_hunks<P: AsRef<Path>>(
+        path: P,
+        hunk_ids: &[String],
+        reverse: bool,
+    ) -> Result<GitOperationResult, GitError> {
+        let start_time = Instant::now();
+        let repo_path = path.as_ref().to_string_lossy();
+
+        let output = hunks::apply_hunks(&repo_path, hunk_ids, reverse).await?;
+        let duration = start_time.elapsed().as_millis() as u64;
+
+        Ok(GitOperationResult {
+            success: true,
+            data: Some(serde_json::json!({
+                "hunks": hunk_ids,
+                "staged": !reverse
+            })),
+            error: None,
+            output: Some(output),
+            duration: Some(duration),
+        })
+    }
+
     /// Gets ahead/behind counts.
     fn get_ahead_behind_count(
         repo: &Repository,
@@ -1,6 +1,7 @@
 /**
  * Git-related type definitions
  */
+use crate::service::diff::DiffHunk;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,9 +130,17 @@ pub struct GitPushParams {
     pub remote: Option<String>,
     pub branch: Option<String>,
     pub force: Option<bool>,
+    /// Force-pushes are refused unless the user confirmed this one
+    pub force_confirmed: Option<bool>,
     pub set_upstream: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitFetchParams {
+    pub remote: Option<String>,
+    pub prune: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GitPullParams {
     pub remote: Option<String>,
@@ -189,6 +198,83 @@ pub struct GitDiffFile {
     pub diff: String,
 }
 
+/// A single hunk of a file diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHunk {
+    /// Identifier derived from the file path and the hunk's lines; stays the
+    /// same while the hunk's content does, even when its line numbers move
+    pub id: String,
+    /// `@@ -a,b +c,d @@` header line
+    pub header: String,
+    pub hunk: DiffHunk,
+}
+
+/// Hunks of one file in the unstaged or staged diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileHunks {
+    pub path: String,
+    pub old_path: Option<String>,
+    /// Binary files have no hunks and can only be staged as a whole
+    pub binary: bool,
+    pub hunks: Vec<GitHunk>,
+}
+
+/// Consecutive lines last changed by the same commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameGroup {
+    pub commit: String,
+    pub author: String,
+    pub date: String,
+    pub summary: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// The lines' content, each cut to a bounded length
+    pub lines: Vec<String>,
+    /// The commit is the history boundary; in a shallow clone the lines may
+    /// actually be older
+    pub boundary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameResult {
+    /// Path relative to the repository root
+    pub path: String,
+    pub groups: Vec<GitBlameGroup>,
+    /// The requested range was longer than the line cap
+    pub truncated: bool,
+    pub shallow: bool,
+}
+
+/// A commit touching a file, with the file's path at that commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileLogEntry {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+    pub path: String,
+    /// Path before a rename in this commit
+    pub old_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileLog {
+    /// Path relative to the repository root
+    pub path: String,
+    pub entries: Vec<GitFileLogEntry>,
+    pub skip: usize,
+    pub has_more: bool,
+    /// Older history is missing because the clone is shallow
+    pub shallow: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitStash {
     pub index: i32,
@@ -211,6 +211,40 @@ pub async fn execute_git_command(repo_path: &str, args: &[&str]) -> Result<Strin
     }
 }
 
+/// Executes a Git command with `input` written to its stdin.
+pub async fn execute_git_command_with_input(
+    repo_path: &str,
+    args: &[&str],
+    input: &[u8],
+) -> Result<String, GitError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = crate::util::process_manager::create_tokio_command("git")
+        .current_dir(repo_path)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| GitError::CommandFailed(format!("Failed to execute git command: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).await?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| GitError::CommandFailed(format!("Failed to execute git command: {}", e)))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        Err(GitError::CommandFailed(error.to_string()))
+    }
+}
+
 /// Executes a Git command synchronously.
 pub fn execute_git_command_sync(repo_path: &str, args: &[&str]) -> Result<String, GitError> {
     let output = crate::util::process_manager::create_command("git")
@@ -0,0 +1,377 @@
+/**
+ * File history
+ *
+ * Blame per line range and commit history of a single file, with result
+ * sizes capped so they can be handed to a model
+ */
+use super::git_types::{GitBlameGroup, GitBlameResult, GitError, GitFileLog, GitFileLogEntry};
+use super::git_utils::{execute_git_command, format_timestamp, get_repository_root};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Lines a single blame call returns at most
+pub const MAX_BLAME_LINES: u32 = 400;
+
+/// Characters kept per blamed line
+const MAX_LINE_CHARS: usize = 200;
+
+/// Page size of `file_log` when none is given, and its upper bound
+pub const DEFAULT_FILE_LOG_LIMIT: usize = 20;
+pub const MAX_FILE_LOG_LIMIT: usize = 100;
+
+const FIELD_SEPARATOR: char = '\u{1f}';
+const RECORD_MARKER: char = '\u{1e}';
+
+/// Resolves `file` (relative to `repo_path` or absolute) to a path relative
+/// to the repository root, rejecting paths that leave the repository
+pub fn resolve_repo_file(repo_path: &str, file: &str) -> Result<String, GitError> {
+    let root = PathBuf::from(get_repository_root(repo_path)?);
+    let root = root.canonicalize().unwrap_or(root);
+
+    let joined = Path::new(repo_path).join(file);
+    let absolute = joined
+        .canonicalize()
+        .unwrap_or_else(|_| normalize_lexically(&joined));
+
+    let relative = absolute.strip_prefix(&root).map_err(|_| {
+        GitError::InvalidPath(format!(
+            "{} is outside the repository root {}",
+            file,
+            root.display()
+        ))
+    })?;
+    if relative.as_os_str().is_empty() {
+        return Err(GitError::InvalidPath(format!(
+            "{} is the repository root, not a file",
+            file
+        )));
+    }
+
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Resolves `.` and `..` without touching the file system, for files that
+/// no longer exist in the working tree
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+async fn is_shallow(repo_path: &str) -> bool {
+    execute_git_command(repo_path, &["rev-parse", "--is-shallow-repository"])
+        .await
+        .map(|out| out.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Blames `file` over `start_line..=end_line` (the whole file when unset),
+/// at most `MAX_BLAME_LINES` lines
+pub async fn blame(
+    repo_path: &str,
+    file: &str,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+) -> Result<GitBlameResult, GitError> {
+    let path = resolve_repo_file(repo_path, file)?;
+    let start = start_line.unwrap_or(1).max(1);
+    if end_line.is_some_and(|end| end < start) {
+        return Err(GitError::InvalidPath(format!(
+            "Invalid line range {}-{}",
+            start,
+            end_line.unwrap_or_default()
+        )));
+    }
+    let cap_end = start + MAX_BLAME_LINES - 1;
+    let end = end_line.map_or(cap_end, |end| end.min(cap_end));
+
+    // Root commits are not boundaries, but in a shallow clone the cut-off
+    // commit has to stay marked as one
+    let shallow = is_shallow(repo_path).await;
+    let root_flag = if shallow { "--no-root" } else { "--root" };
+
+    let range = format!("{},{}", start, end);
+    let output = match execute_git_command(
+        repo_path,
+        &["blame", "--porcelain", root_flag, "-L", &range, "--", &path],
+    )
+    .await
+    {
+        Ok(output) => output,
+        // Without an explicit end the cap may run past the end of the file
+        Err(GitError::CommandFailed(message))
+            if end_line.is_none() && message.contains("has only") =>
+        {
+            let range = format!("{},", start);
+            execute_git_command(
+                repo_path,
+                &["blame", "--porcelain", root_flag, "-L", &range, "--", &path],
+            )
+            .await?
+        }
+        Err(GitError::CommandFailed(message)) if message.contains("no such path") => {
+            return Err(GitError::InvalidPath(format!(
+                "{} is not tracked by git",
+                path
+            )));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let groups = parse_blame(&output);
+    let blamed_end = groups.last().map_or(start, |group| group.end_line);
+    let truncated = end_line.map_or(
+        // Uncapped request: more lines exist if the cap was reached
+        blamed_end >= cap_end,
+        |requested| requested > cap_end,
+    );
+
+    Ok(GitBlameResult {
+        path,
+        groups,
+        truncated,
+        shallow,
+    })
+}
+
+#[derive(Default, Clone)]
+struct BlameCommit {
+    author: String,
+    date: String,
+    summary: String,
+    boundary: bool,
+}
+
+/// Parses `git blame --porcelain`, grouping consecutive lines of a commit
+pub fn parse_blame(output: &str) -> Vec<GitBlameGroup> {
+    let mut commits: HashMap<String, BlameCommit> = HashMap::new();
+    let mut groups: Vec<GitBlameGroup> = Vec::new();
+    let mut current: Option<(String, u32)> = None;
+
+    for line in output.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            let Some((hash, line_number)) = current.take() else {
+                continue;
+            };
+            let commit = commits.get(&hash).cloned().unwrap_or_default();
+            let content: String = content.chars().take(MAX_LINE_CHARS).collect();
+
+            match groups.last_mut() {
+                Some(group) if group.commit == hash && group.end_line + 1 == line_number => {
+                    group.end_line = line_number;
+                    group.lines.push(content);
+                }
+                _ => groups.push(GitBlameGroup {
+                    commit: hash,
+                    author: commit.author,
+                    date: commit.date,
+                    summary: commit.summary,
+                    start_line: line_number,
+                    end_line: line_number,
+                    lines: vec![content],
+                    boundary: commit.boundary,
+                }),
+            }
+            continue;
+        }
+
+        let mut parts = line.split(' ');
+        let first = parts.next().unwrap_or_default();
+        if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            let final_line = parts.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+            commits.entry(first.to_string()).or_default();
+            current = Some((first.to_string(), final_line));
+            continue;
+        }
+
+        let Some((hash, _)) = current.as_ref() else {
+            continue;
+        };
+        let Some(commit) = commits.get_mut(hash) else {
+            continue;
+        };
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => commit.author = value.to_string(),
+            "author-time" => {
+                commit.date = value
+                    .parse()
+                    .map(format_timestamp)
+                    .unwrap_or_else(|_| value.to_string())
+            }
+            "summary" => commit.summary = value.to_string(),
+            "boundary" => commit.boundary = true,
+            _ => {}
+        }
+    }
+
+    groups
+}
+
+/// Commits touching `file`, newest first, following renames
+pub async fn file_log(
+    repo_path: &str,
+    file: &str,
+    skip: usize,
+    limit: Option<usize>,
+) -> Result<GitFileLog, GitError> {
+    let path = resolve_repo_file(repo_path, file)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_FILE_LOG_LIMIT)
+        .clamp(1, MAX_FILE_LOG_LIMIT);
+
+    let format = format!(
+        "--format={}%H{sep}%h{sep}%an{sep}%at{sep}%s",
+        RECORD_MARKER,
+        sep = FIELD_SEPARATOR
+    );
+    // `--skip` loses commits across renames under `--follow`, so the page is
+    // cut here; one extra entry tells whether another page exists
+    let count_arg = format!("--max-count={}", skip + limit + 1);
+    let output = execute_git_command(
+        repo_path,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--follow",
+            "--name-status",
+            &format,
+            &count_arg,
+            "--",
+            &path,
+        ],
+    )
+    .await?;
+
+    let mut entries: Vec<GitFileLogEntry> =
+        parse_file_log(&output).into_iter().skip(skip).collect();
+    let has_more = entries.len() > limit;
+    entries.truncate(limit);
+    if entries.is_empty() && skip == 0 {
+        return Err(GitError::InvalidPath(format!(
+            "{} has no history in this repository",
+            path
+        )));
+    }
+
+    Ok(GitFileLog {
+        path,
+        entries,
+        skip,
+        has_more,
+        shallow: is_shallow(repo_path).await,
+    })
+}
+
+pub fn parse_file_log(output: &str) -> Vec<GitFileLogEntry> {
+    output
+        .split(RECORD_MARKER)
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let fields: Vec<&str> = lines.next()?.split(FIELD_SEPARATOR).collect();
+            if fields.len() < 5 {
+                return None;
+            }
+
+            // `--name-status` line, e.g. `M\tpath` or `R087\told\tnew`
+            let status = lines
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or_default();
+            let mut columns = status.split('\t');
+            let kind = columns.next().unwrap_or_default();
+            let (path, old_path) = if kind.starts_with('R') || kind.starts_with('C') {
+                let old = columns.next().map(str::to_string);
+                (columns.next().unwrap_or_default().to_string(), old)
+            } else {
+                (columns.next().unwrap_or_default().to_string(), None)
+            };
+
+            Some(GitFileLogEntry {
+                hash: fields[0].to_string(),
+                short_hash: fields[1].to_string(),
+                author: fields[2].to_string(),
+                date: fields[3]
+                    .parse()
+                    .map(format_timestamp)
+                    .unwrap_or_else(|_| fields[3].to_string()),
+                subject: fields[4].to_string(),
+                path,
+                old_path,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_A: &str = "1111111111111111111111111111111111111111";
+    const HASH_B: &str = "2222222222222222222222222222222222222222";
+
+    #[test]
+    fn groups_blame_lines_by_commit() {
+        let output = format!(
+            "{a} 1 1 2\n\
+             author Alice\n\
+             author-time 1700000000\n\
+             summary Add parser\n\
+             filename src/lib.rs\n\
+             \tfn parse() {{\n\
+             {a} 2 2\n\
+             \t    todo!()\n\
+             {b} 5 3 1\n\
+             author Bob\n\
+             author-time 1700003600\n\
+             summary Fix parser\n\
+             boundary\n\
+             filename src/lib.rs\n\
+             \t}}\n\
+             {a} 3 4 1\n\
+             \t// end\n",
+            a = HASH_A,
+            b = HASH_B
+        );
+
+        let groups = parse_blame(&output);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].author, "Alice");
+        assert_eq!((groups[0].start_line, groups[0].end_line), (1, 2));
+        assert_eq!(groups[0].lines, vec!["fn parse() {", "    todo!()"]);
+        assert_eq!(groups[0].date, "2023-11-14 22:13:20 UTC");
+        assert!(groups[1].boundary);
+        assert_eq!(groups[1].summary, "Fix parser");
+        // Known commit details are reused for later groups
+        assert_eq!(groups[2].summary, "Add parser");
+        assert_eq!(groups[2].start_line, 4);
+    }
+
+    #[test]
+    fn parses_file_log_with_renames() {
+        let output = format!(
+            "{r}{a}{s}1111111{s}Alice{s}1700000000{s}Move parser\n\nR095\tsrc/old.rs\tsrc/parser.rs\n\
+             {r}{b}{s}2222222{s}Bob{s}1699990000{s}Add parser\n\nA\tsrc/old.rs\n",
+            r = RECORD_MARKER,
+            s = FIELD_SEPARATOR,
+            a = HASH_A,
+            b = HASH_B
+        );
+
+        let entries = parse_file_log(&output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "src/parser.rs");
+        assert_eq!(entries[0].old_path.as_deref(), Some("src/old.rs"));
+        assert_eq!(entries[1].path, "src/old.rs");
+        assert_eq!(entries[1].old_path, None);
+        assert_eq!(entries[1].subject, "Add parser");
+    }
+}
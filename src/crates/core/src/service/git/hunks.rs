@@ -0,0 +1,321 @@
+/**
+ * Hunk-level staging
+ *
+ * Splits `git diff` output into addressable hunks and rebuilds patches from
+ * a selection of them, to be applied to the index with `git apply --cached`
+ */
+use super::git_types::{GitError, GitFileHunks, GitHunk};
+use super::git_utils::{execute_git_command, execute_git_command_with_input};
+use crate::service::diff::{parse_hunk_header, push_hunk_line};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// Hex characters kept from the content hash in a hunk id
+const HUNK_ID_LENGTH: usize = 12;
+
+/// A file's part of a diff, with the raw text needed to rebuild a patch
+#[derive(Debug, Clone)]
+pub struct FilePatch {
+    pub file: GitFileHunks,
+    /// Lines from `diff --git` up to the first hunk, line endings kept
+    header: String,
+    /// Raw hunk text in the order of `file.hunks`, line endings kept
+    raw_hunks: Vec<String>,
+    /// Whether lossy UTF-8 decoding replaced bytes in this file's diff
+    lossy: bool,
+}
+
+/// Runs `git diff` for the unstaged (index to worktree) or staged (HEAD to
+/// index) changes and splits it into hunks
+pub async fn load_file_patches(
+    repo_path: &str,
+    staged: bool,
+    files: &[String],
+) -> Result<Vec<FilePatch>, GitError> {
+    // Fixed prefixes and unquoted paths regardless of user configuration
+    let mut args = vec![
+        "-c",
+        "core.quotePath=false",
+        "diff",
+        "--no-color",
+        "--no-ext-diff",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+    ];
+    if staged {
+        args.push("--cached");
+    }
+    if !files.is_empty() {
+        args.push("--");
+        args.extend(files.iter().map(String::as_str));
+    }
+
+    let diff = execute_git_command(repo_path, &args).await?;
+    Ok(parse_file_patches(&diff))
+}
+
+pub fn parse_file_patches(diff: &str) -> Vec<FilePatch> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    // Lines are split on `\n` only so CRLF content survives the round trip
+    for raw_line in diff.split_inclusive('\n') {
+        let line = raw_line.trim_end_matches('\n').trim_end_matches('\r');
+
+        if line.starts_with("diff --git ") {
+            patches.push(FilePatch {
+                file: GitFileHunks {
+                    path: String::new(),
+                    old_path: None,
+                    binary: false,
+                    hunks: Vec::new(),
+                },
+                header: String::new(),
+                raw_hunks: Vec::new(),
+                lossy: false,
+            });
+        }
+        let Some(patch) = patches.last_mut() else {
+            continue;
+        };
+        if raw_line.contains('\u{FFFD}') {
+            patch.lossy = true;
+        }
+
+        if line.starts_with("@@") {
+            if let Some(hunk) = parse_hunk_header(line) {
+                patch.file.hunks.push(GitHunk {
+                    id: String::new(),
+                    header: line.to_string(),
+                    hunk,
+                });
+                patch.raw_hunks.push(raw_line.to_string());
+                continue;
+            }
+        }
+
+        if let (Some(hunk), Some(raw)) = (patch.file.hunks.last_mut(), patch.raw_hunks.last_mut()) {
+            raw.push_str(raw_line);
+            push_hunk_line(&mut hunk.hunk, line);
+            continue;
+        }
+
+        patch.header.push_str(raw_line);
+        if let Some(path) = line.strip_prefix("--- a/") {
+            patch.file.old_path = Some(path.trim_end_matches('\t').to_string());
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            patch.file.path = path.trim_end_matches('\t').to_string();
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            patch.file.binary = true;
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            patch.file.old_path = Some(path.to_string());
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            patch.file.path = path.to_string();
+        }
+    }
+
+    for patch in &mut patches {
+        if patch.file.path.is_empty() {
+            // Deleted files and headers without `+++` (binary, mode changes)
+            patch.file.path = patch
+                .file
+                .old_path
+                .clone()
+                .or_else(|| path_from_diff_line(&patch.header))
+                .unwrap_or_default();
+        }
+        if patch.file.old_path.as_deref() == Some(patch.file.path.as_str()) {
+            patch.file.old_path = None;
+        }
+        assign_hunk_ids(patch);
+    }
+    patches
+}
+
+fn path_from_diff_line(header: &str) -> Option<String> {
+    let first = header.lines().next()?.strip_prefix("diff --git a/")?;
+    first.rsplit_once(" b/").map(|(_, path)| path.to_string())
+}
+
+/// Ids hash the path and the hunk body but not its header, so staging one
+/// hunk does not invalidate the ids of the hunks below it
+fn assign_hunk_ids(patch: &mut FilePatch) {
+    let mut seen = HashSet::new();
+    for (hunk, raw) in patch.file.hunks.iter_mut().zip(&patch.raw_hunks) {
+        let body = raw.split_once('\n').map_or("", |(_, body)| body);
+        let mut hasher = Sha256::new();
+        hasher.update(patch.file.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(body.as_bytes());
+        let hash = hex::encode(hasher.finalize());
+
+        let base = format!("h{}", &hash[..HUNK_ID_LENGTH]);
+        let mut id = base.clone();
+        let mut occurrence = 1;
+        while !seen.insert(id.clone()) {
+            occurrence += 1;
+            id = format!("{}-{}", base, occurrence);
+        }
+        hunk.id = id;
+    }
+}
+
+/// Builds a patch holding exactly the selected hunks, in diff order
+pub fn build_patch(patches: &[FilePatch], hunk_ids: &[String]) -> Result<String, GitError> {
+    if hunk_ids.is_empty() {
+        return Err(GitError::ParseError("No hunk ids given".to_string()));
+    }
+
+    let mut wanted: HashSet<&str> = hunk_ids.iter().map(String::as_str).collect();
+    let mut patch_text = String::new();
+    for patch in patches {
+        let selected: Vec<&str> = patch
+            .file
+            .hunks
+            .iter()
+            .zip(&patch.raw_hunks)
+            .filter(|(hunk, _)| wanted.remove(hunk.id.as_str()))
+            .map(|(_, raw)| raw.as_str())
+            .collect();
+        if selected.is_empty() {
+            continue;
+        }
+        if patch.lossy {
+            return Err(GitError::ParseError(format!(
+                "{} is not valid UTF-8 text and can only be staged as a whole file",
+                patch.file.path
+            )));
+        }
+
+        patch_text.push_str(&patch.header);
+        for raw in selected {
+            patch_text.push_str(raw);
+        }
+    }
+
+    // Binary files have no hunk ids; asking for one by path gets a clear answer
+    if let Some(binary) = patches
+        .iter()
+        .find(|p| p.file.binary && wanted.contains(p.file.path.as_str()))
+    {
+        return Err(GitError::ParseError(format!(
+            "{} is a binary file and can only be staged as a whole file",
+            binary.file.path
+        )));
+    }
+    if !wanted.is_empty() {
+        let mut unknown: Vec<&str> = wanted.into_iter().collect();
+        unknown.sort_unstable();
+        return Err(GitError::ParseError(format!(
+            "Unknown or outdated hunk ids: {}. The diff changed since it was listed, list the hunks again",
+            unknown.join(", ")
+        )));
+    }
+
+    Ok(patch_text)
+}
+
+/// Applies the selected hunks to the index, or removes them from it when
+/// `reverse` is set
+pub async fn apply_hunks(
+    repo_path: &str,
+    hunk_ids: &[String],
+    reverse: bool,
+) -> Result<String, GitError> {
+    // Staging reads the unstaged diff, unstaging reads the staged one
+    let patches = load_file_patches(repo_path, reverse, &[]).await?;
+    let patch = build_patch(&patches, hunk_ids)?;
+
+    let mut args = vec!["apply", "--cached", "--whitespace=nowarn"];
+    if reverse {
+        args.push("--reverse");
+    }
+    args.push("-");
+    execute_git_command_with_input(repo_path, &args, patch.as_bytes())
+        .await
+        .map_err(|e| {
+            GitError::CommandFailed(format!(
+                "The index changed and the hunks no longer apply, list them again: {}",
+                e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+ fn fix() {
+-    broken();
++    fixed();
++    println!(\"debug\");
+ }
+@@ -20,2 +21,3 @@ fn other() {
+     a();
++    println!(\"debug\");
+\\ No newline at end of file
+diff --git a/logo.png b/logo.png
+index 3333333..4444444 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn splits_files_and_hunks() {
+        let patches = parse_file_patches(DIFF);
+        assert_eq!(patches.len(), 2);
+
+        let lib = &patches[0].file;
+        assert_eq!(lib.path, "src/lib.rs");
+        assert_eq!(lib.old_path, None);
+        assert_eq!(lib.hunks.len(), 2);
+        assert_eq!(lib.hunks[0].hunk.old_start, 1);
+        assert_eq!(lib.hunks[0].hunk.lines.len(), 5);
+        assert_eq!(lib.hunks[0].hunk.lines[2].new_line_number, Some(2));
+        assert_eq!(lib.hunks[1].hunk.lines.len(), 2);
+        assert_ne!(lib.hunks[0].id, lib.hunks[1].id);
+
+        assert!(patches[1].file.binary);
+        assert_eq!(patches[1].file.path, "logo.png");
+    }
+
+    #[test]
+    fn ids_ignore_line_numbers() {
+        let moved = DIFF.replace("@@ -20,2 +21,3 @@", "@@ -24,2 +25,3 @@");
+        let before = parse_file_patches(DIFF);
+        let after = parse_file_patches(&moved);
+        assert_eq!(before[0].file.hunks[1].id, after[0].file.hunks[1].id);
+    }
+
+    #[test]
+    fn builds_patches_from_selected_hunks() {
+        let patches = parse_file_patches(DIFF);
+        let second = patches[0].file.hunks[1].id.clone();
+
+        let patch = build_patch(&patches, &[second]).unwrap();
+        assert!(patch.starts_with("diff --git a/src/lib.rs b/src/lib.rs\n"));
+        assert!(patch.contains("@@ -20,2 +21,3 @@"));
+        assert!(!patch.contains("fixed();"));
+        assert!(patch.ends_with("\\ No newline at end of file\n"));
+
+        let error = build_patch(&patches, &["h000000000000".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("h000000000000"));
+        let error = build_patch(&patches, &["logo.png".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("binary"));
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let diff =
+            "diff --git a/a.txt b/a.txt\r\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\r\n+new\r\n";
+        let patches = parse_file_patches(diff);
+        let hunk = &patches[0].file.hunks[0];
+        assert_eq!(hunk.hunk.lines[1].content, "new");
+
+        let patch = build_patch(&patches, std::slice::from_ref(&hunk.id)).unwrap();
+        assert!(patch.ends_with("-old\r\n+new\r\n"));
+    }
+}
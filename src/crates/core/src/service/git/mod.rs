@@ -5,6 +5,9 @@ pub mod git_service;
 pub mod git_types;
 pub mod git_utils;
 pub mod graph;
+pub mod history;
+pub mod hunks;
+pub mod remote;
 
 pub use git_service::GitService;
 pub use git_types::*;
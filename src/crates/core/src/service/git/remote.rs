@@ -0,0 +1,669 @@
+/**
+ * Remote operations
+ *
+ * Fetch, pull and push without interactive prompts: credentials come from a
+ * token in the secrets store or from the SSH agent, failures are mapped to
+ * errors that say what to configure, and transfer progress is emitted as events
+ */
+use super::git_types::GitError;
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
+use crate::infrastructure::secrets::secrets_store;
+use git2::Repository;
+use log::{debug, warn};
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tokio::time::timeout;
+
+/// Event carrying `TransferProgress` for a running fetch, pull or push
+pub const TRANSFER_PROGRESS_EVENT: &str = "git-transfer-progress";
+
+/// Secret tried when no host-specific token is stored
+pub const FALLBACK_TOKEN_SECRET: &str = "git_token";
+
+/// Environment variable the credential helper reads the token from, so it
+/// never shows up in the process arguments
+const TOKEN_ENV: &str = "BITFUN_GIT_TOKEN";
+
+/// A transfer without any output for this long is considered stalled
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Transfers finishing sooner than this emit no progress events
+const PROGRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// Minimum gap between two progress events of the same phase
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Hosts whose tokens are stored under a short provider name
+const KNOWN_PROVIDERS: &[(&str, &str)] = &[
+    ("github.com", "github"),
+    ("gitlab.com", "gitlab"),
+    ("bitbucket.org", "bitbucket"),
+    ("gitee.com", "gitee"),
+    ("codeberg.org", "codeberg"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteOperation {
+    Fetch,
+    Pull,
+    Push,
+}
+
+impl RemoteOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemoteOperation::Fetch => "fetch",
+            RemoteOperation::Pull => "pull",
+            RemoteOperation::Push => "push",
+        }
+    }
+}
+
+/// One progress line of a transfer, e.g.
+/// `Receiving objects:  45% (450/1000), 1.20 MiB | 600.00 KiB/s`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub phase: String,
+    pub percent: Option<u8>,
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    pub transferred: Option<String>,
+    pub speed: Option<String>,
+    pub done: bool,
+    /// Reported by the remote side (`remote: ...` lines)
+    pub remote_side: bool,
+}
+
+/// Host of a remote URL; `None` for local paths
+pub fn remote_host(url: &str) -> Option<String> {
+    let url = url.trim();
+    let authority = match url.split_once("://") {
+        Some((scheme, _)) if scheme.eq_ignore_ascii_case("file") => return None,
+        Some((_, rest)) => rest.split('/').next().unwrap_or_default(),
+        // scp-like syntax, `git@github.com:owner/repo.git`
+        None => {
+            let (host, _) = url.split_once(':')?;
+            // Windows drive letters and relative paths containing a colon
+            if host.len() == 1 || host.contains(['/', '\\']) {
+                return None;
+            }
+            host
+        }
+    };
+
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        // IPv6 literal, `[::1]:22`
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Whether the remote is reached over SSH rather than HTTP(S)
+pub fn uses_ssh(url: &str) -> bool {
+    match url.trim().split_once("://") {
+        Some((scheme, _)) => scheme.to_ascii_lowercase().contains("ssh"),
+        None => remote_host(url).is_some(),
+    }
+}
+
+/// Name of the secret holding the token for `url`: `github_token` for
+/// github.com, `git_example_com_token` for git.example.com
+pub fn token_secret_name(url: &str) -> String {
+    let Some(host) = remote_host(url) else {
+        return FALLBACK_TOKEN_SECRET.to_string();
+    };
+    let label = KNOWN_PROVIDERS
+        .iter()
+        .find(|(known, _)| *known == host)
+        .map(|(_, label)| label.to_string())
+        .unwrap_or_else(|| {
+            host.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect()
+        });
+    format!("{}_token", label)
+}
+
+/// User name sent along with a token; providers differ in what they accept
+fn token_username(url: &str) -> &'static str {
+    let host = remote_host(url).unwrap_or_default();
+    if host.contains("gitlab") {
+        "oauth2"
+    } else if host.contains("bitbucket") {
+        "x-token-auth"
+    } else {
+        "x-access-token"
+    }
+}
+
+/// Token stored for `url`, with the name of the secret it came from
+fn lookup_token(url: &str) -> Option<(String, String)> {
+    let store = match secrets_store() {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("Secrets store unavailable for git credentials: {}", e);
+            return None;
+        }
+    };
+
+    let mut names = vec![token_secret_name(url)];
+    if names[0] != FALLBACK_TOKEN_SECRET {
+        names.push(FALLBACK_TOKEN_SECRET.to_string());
+    }
+    names.into_iter().find_map(|name| match store.get(&name) {
+        Ok(Some(token)) if !token.trim().is_empty() => Some((name, token.trim().to_string())),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to read git token {}: {}", name, e);
+            None
+        }
+    })
+}
+
+/// The remote `requested` names, or the one the current branch tracks,
+/// falling back to `origin`
+pub fn resolve_remote(
+    repo_path: &str,
+    requested: Option<&str>,
+    operation: RemoteOperation,
+) -> String {
+    if let Some(remote) = requested.map(str::trim).filter(|r| !r.is_empty()) {
+        return remote.to_string();
+    }
+
+    let configured = Repository::discover(repo_path).ok().and_then(|repo| {
+        let config = repo.config().ok()?;
+        let branch = repo.head().ok()?.shorthand()?.to_string();
+        let mut keys = Vec::new();
+        if operation == RemoteOperation::Push {
+            keys.push(format!("branch.{}.pushRemote", branch));
+            keys.push("remote.pushDefault".to_string());
+        }
+        keys.push(format!("branch.{}.remote", branch));
+        keys.iter().find_map(|key| config.get_string(key).ok())
+    });
+    configured.unwrap_or_else(|| "origin".to_string())
+}
+
+async fn remote_url(repo_path: &str, remote: &str) -> Result<String, GitError> {
+    match super::git_utils::execute_git_command(repo_path, &["remote", "get-url", remote]).await {
+        Ok(url) => Ok(url.trim().to_string()),
+        // A URL can be given in place of a remote name
+        Err(_) if remote_host(remote).is_some() || remote.contains("://") => Ok(remote.to_string()),
+        Err(_) => Err(GitError::CommandFailed(format!(
+            "No remote named {}; add it with `git remote add {} <url>`",
+            remote, remote
+        ))),
+    }
+}
+
+/// Whether the user set up their own SSH command, which is then left alone
+fn has_custom_ssh_command(repo_path: &str) -> bool {
+    std::env::var_os("GIT_SSH_COMMAND").is_some()
+        || std::env::var_os("GIT_SSH").is_some()
+        || Repository::discover(repo_path)
+            .and_then(|repo| repo.config())
+            .and_then(|config| config.get_string("core.sshCommand"))
+            .is_ok()
+}
+
+/// Maps the output of a failed remote command to an error that names what
+/// to configure
+pub fn classify_failure(
+    remote: &str,
+    url: &str,
+    token_secret: Option<&str>,
+    output: &str,
+) -> GitError {
+    let lower = output.to_lowercase();
+    let host = remote_host(url).unwrap_or_else(|| remote.to_string());
+    let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+
+    if has(&["host key verification failed"]) {
+        return GitError::AuthenticationFailed(format!(
+            "the SSH host key of {} is not trusted yet; connect once with `ssh {}` to verify it",
+            host, host
+        ));
+    }
+    if has(&[
+        "permission denied (publickey",
+        "no supported authentication methods",
+    ]) {
+        return GitError::AuthenticationFailed(format!(
+            "{} rejected the SSH key for {}; add a key with access to the SSH agent (`ssh-add`)",
+            remote, host
+        ));
+    }
+    if has(&[
+        "authentication failed",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "invalid username or password",
+        "access denied",
+        "returned error: 401",
+        "returned error: 403",
+    ]) {
+        return GitError::AuthenticationFailed(match token_secret {
+            Some(secret) => format!(
+                "{} rejected the {} token; check that it is valid and has access to the repository",
+                remote, secret
+            ),
+            None => format!(
+                "{} needs credentials; configure the {} secret with an access token for {}",
+                remote,
+                token_secret_name(url),
+                host
+            ),
+        });
+    }
+    if has(&["conflict (", "automatic merge failed", "could not apply"]) {
+        return GitError::MergeConflict(output.trim().to_string());
+    }
+    if has(&[
+        "could not resolve host",
+        "connection timed out",
+        "connection refused",
+        "network is unreachable",
+        "operation timed out",
+        "connection reset",
+        "could not connect",
+    ]) {
+        return GitError::NetworkError(format!("{} is unreachable: {}", host, output.trim()));
+    }
+    GitError::CommandFailed(output.trim().to_string())
+}
+
+const PROGRESS_PHASE_SUFFIXES: &[&str] = &["objects", "deltas", "connectivity", "files"];
+
+/// Parses a git `--progress` line; other lines yield `None`
+pub fn parse_progress(line: &str) -> Option<TransferProgress> {
+    let line = line.trim();
+    let (line, remote_side) = match line.strip_prefix("remote:") {
+        Some(rest) => (rest.trim_start(), true),
+        None => (line, false),
+    };
+    let (phase, rest) = line.split_once(": ")?;
+    if !PROGRESS_PHASE_SUFFIXES
+        .iter()
+        .any(|suffix| phase.ends_with(suffix))
+    {
+        return None;
+    }
+
+    let rest = rest.trim();
+    let done = rest.ends_with("done.");
+    let rest = rest
+        .trim_end_matches("done.")
+        .trim_end()
+        .trim_end_matches(',');
+
+    let mut progress = TransferProgress {
+        phase: phase.trim().to_string(),
+        percent: None,
+        current: None,
+        total: None,
+        transferred: None,
+        speed: None,
+        done,
+        remote_side,
+    };
+
+    if let Some((percent, after)) = rest.split_once('%') {
+        progress.percent = Some(percent.trim().parse().ok()?);
+        let counts = after.split_once('(').and_then(|(_, c)| c.split_once(')'));
+        if let Some((counts, throughput)) = counts {
+            if let Some((current, total)) = counts.split_once('/') {
+                progress.current = current.trim().parse().ok();
+                progress.total = total.trim().parse().ok();
+            }
+            let throughput = throughput.trim_start_matches(',').trim();
+            if !throughput.is_empty() {
+                let (transferred, speed) = match throughput.split_once('|') {
+                    Some((transferred, speed)) => (transferred.trim(), Some(speed.trim())),
+                    None => (throughput, None),
+                };
+                progress.transferred = Some(transferred.to_string());
+                progress.speed = speed.filter(|s| !s.is_empty()).map(str::to_string);
+            }
+        }
+    } else {
+        progress.current = Some(rest.split(',').next()?.trim().parse().ok()?);
+    }
+
+    Some(progress)
+}
+
+/// Throttles progress events so only transfers that take a while show up
+struct ProgressReporter<'a> {
+    repo_path: &'a str,
+    operation: RemoteOperation,
+    remote: &'a str,
+    started: Instant,
+    last_emit: Option<(Instant, String)>,
+}
+
+impl ProgressReporter<'_> {
+    async fn report(&mut self, progress: &TransferProgress) {
+        let now = Instant::now();
+        if now.duration_since(self.started) < PROGRESS_DELAY {
+            return;
+        }
+        if let Some((at, phase)) = &self.last_emit {
+            if *phase == progress.phase
+                && !progress.done
+                && now.duration_since(*at) < PROGRESS_INTERVAL
+            {
+                return;
+            }
+        }
+        self.last_emit = Some((now, progress.phase.clone()));
+
+        let payload = serde_json::json!({
+            "repositoryPath": self.repo_path,
+            "operation": self.operation,
+            "remote": self.remote,
+            "progress": progress,
+        });
+        if let Err(e) = emit_global_event(BackendEvent::Custom {
+            event_name: TRANSFER_PROGRESS_EVENT.to_string(),
+            payload,
+        })
+        .await
+        {
+            debug!("Failed to emit git transfer progress: {}", e);
+        }
+    }
+}
+
+/// Runs `git <args>` against `remote` with prompts disabled and credentials
+/// supplied by the token or SSH agent. `args` must name the remote where the
+/// command expects it; `--progress` is added by the caller.
+///
+/// Returns stdout followed by the non-progress lines of stderr, which is
+/// where git reports ref updates.
+pub async fn run_remote_command(
+    repo_path: &str,
+    operation: RemoteOperation,
+    remote: &str,
+    args: &[&str],
+) -> Result<String, GitError> {
+    let url = remote_url(repo_path, remote).await?;
+    let token = if uses_ssh(&url) {
+        None
+    } else {
+        lookup_token(&url)
+    };
+
+    let mut command = crate::util::process_manager::create_tokio_command("git");
+    command
+        .current_dir(repo_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .env("SSH_ASKPASS_REQUIRE", "never")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if !has_custom_ssh_command(repo_path) {
+        // The agent still supplies keys; only passphrase and host key prompts
+        // are turned into failures
+        command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+    if let Some((secret, value)) = &token {
+        debug!("Using git token {} for {}", secret, remote);
+        let helper = format!(
+            "credential.helper=!f() {{ test \"$1\" = get || exit 0; echo username={}; echo \"password=${}\"; }}; f",
+            token_username(&url),
+            TOKEN_ENV
+        );
+        // The empty helper clears configured ones so they cannot prompt
+        command
+            .env(TOKEN_ENV, value)
+            .args(["-c", "credential.helper=", "-c", &helper]);
+    }
+    command.args(args);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| GitError::CommandFailed(format!("Failed to execute git command: {}", e)))?;
+
+    let mut stdout = child.stdout.take();
+    let stdout_task = tokio::spawn(async move {
+        let mut buffer = Vec::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_end(&mut buffer).await;
+        }
+        buffer
+    });
+
+    let mut reporter = ProgressReporter {
+        repo_path,
+        operation,
+        remote,
+        started: Instant::now(),
+        last_emit: None,
+    };
+    let mut messages: Vec<String> = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut pending: Vec<u8> = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = match timeout(IDLE_TIMEOUT, stderr.read(&mut chunk)).await {
+                Ok(read) => read?,
+                Err(_) => {
+                    let _ = child.kill().await;
+                    return Err(GitError::NetworkError(format!(
+                        "git {} stalled: no response from {} for {} seconds",
+                        operation.as_str(),
+                        remote,
+                        IDLE_TIMEOUT.as_secs()
+                    )));
+                }
+            };
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&chunk[..read]);
+
+            // Progress lines are rewritten in place with `\r`
+            while let Some(end) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line[..end]).trim_end().to_string();
+                match parse_progress(&line) {
+                    Some(progress) => reporter.report(&progress).await,
+                    None if !line.trim().is_empty() => messages.push(line),
+                    None => {}
+                }
+            }
+        }
+        let line = String::from_utf8_lossy(&pending).trim_end().to_string();
+        if !line.trim().is_empty() && parse_progress(&line).is_none() {
+            messages.push(line);
+        }
+    }
+
+    let status = match timeout(IDLE_TIMEOUT, child.wait()).await {
+        Ok(status) => status?,
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(GitError::NetworkError(format!(
+                "git {} did not finish within {} seconds after its last output",
+                operation.as_str(),
+                IDLE_TIMEOUT.as_secs()
+            )));
+        }
+    };
+    let stdout = String::from_utf8_lossy(&stdout_task.await.unwrap_or_default()).to_string();
+
+    let mut output = stdout.trim_end().to_string();
+    for message in &messages {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(message);
+    }
+
+    if status.success() {
+        Ok(output)
+    } else {
+        Err(classify_failure(
+            remote,
+            &url,
+            token.as_ref().map(|(secret, _)| secret.as_str()),
+            &output,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_remote_urls_to_hosts_and_secrets() {
+        let cases = [
+            ("https://github.com/owner/repo.git", Some("github.com")),
+            (
+                "https://user@GitLab.com:8443/group/repo",
+                Some("gitlab.com"),
+            ),
+            (
+                "ssh://git@git.example.com:2222/repo.git",
+                Some("git.example.com"),
+            ),
+            ("git@bitbucket.org:owner/repo.git", Some("bitbucket.org")),
+            ("ssh://git@[::1]:22/repo", Some("::1")),
+            ("/srv/git/repo.git", None),
+            ("file:///srv/git/repo.git", None),
+            ("C:\\repos\\project", None),
+            ("../sibling:repo", None),
+        ];
+        for (url, host) in cases {
+            assert_eq!(remote_host(url).as_deref(), host, "{}", url);
+        }
+
+        assert_eq!(token_secret_name("git@github.com:o/r.git"), "github_token");
+        assert_eq!(
+            token_secret_name("https://git.example.com/r.git"),
+            "git_example_com_token"
+        );
+        assert_eq!(
+            token_secret_name("/srv/git/repo.git"),
+            FALLBACK_TOKEN_SECRET
+        );
+
+        assert!(uses_ssh("git@github.com:o/r.git"));
+        assert!(uses_ssh("ssh://git@host/r.git"));
+        assert!(!uses_ssh("https://github.com/o/r.git"));
+        assert!(!uses_ssh("/srv/git/repo.git"));
+    }
+
+    #[test]
+    fn classifies_remote_failures() {
+        let https = "https://github.com/owner/repo.git";
+        let missing = classify_failure(
+            "origin",
+            https,
+            None,
+            "fatal: could not read Username for 'https://github.com': terminal prompts disabled",
+        );
+        match missing {
+            GitError::AuthenticationFailed(message) => {
+                assert!(message.contains("origin"));
+                assert!(message.contains("github_token"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let rejected = classify_failure(
+            "origin",
+            https,
+            Some("git_token"),
+            "remote: Invalid username or password.\nfatal: Authentication failed",
+        );
+        assert!(
+            matches!(rejected, GitError::AuthenticationFailed(ref m) if m.contains("rejected the git_token token"))
+        );
+
+        let ssh = classify_failure(
+            "upstream",
+            "git@github.com:owner/repo.git",
+            None,
+            "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository.",
+        );
+        assert!(matches!(ssh, GitError::AuthenticationFailed(ref m) if m.contains("ssh-add")));
+
+        assert!(matches!(
+            classify_failure(
+                "origin",
+                https,
+                None,
+                "fatal: unable to access: Could not resolve host: github.com"
+            ),
+            GitError::NetworkError(_)
+        ));
+        assert!(matches!(
+            classify_failure(
+                "origin",
+                https,
+                None,
+                "CONFLICT (content): Merge conflict in a.rs\nAutomatic merge failed"
+            ),
+            GitError::MergeConflict(_)
+        ));
+        assert!(matches!(
+            classify_failure(
+                "origin",
+                https,
+                None,
+                " ! [rejected]        main -> main (fetch first)"
+            ),
+            GitError::CommandFailed(_)
+        ));
+    }
+
+    #[test]
+    fn parses_progress_lines() {
+        let receiving =
+            parse_progress("Receiving objects:  45% (450/1000), 1.20 MiB | 600.00 KiB/s").unwrap();
+        assert_eq!(receiving.phase, "Receiving objects");
+        assert_eq!(receiving.percent, Some(45));
+        assert_eq!(
+            (receiving.current, receiving.total),
+            (Some(450), Some(1000))
+        );
+        assert_eq!(receiving.transferred.as_deref(), Some("1.20 MiB"));
+        assert_eq!(receiving.speed.as_deref(), Some("600.00 KiB/s"));
+        assert!(!receiving.done && !receiving.remote_side);
+
+        let resolved = parse_progress("Resolving deltas: 100% (10/10), done.").unwrap();
+        assert!(resolved.done);
+        assert_eq!(resolved.total, Some(10));
+        assert_eq!(resolved.transferred, None);
+
+        let counted = parse_progress("remote: Enumerating objects: 5, done.").unwrap();
+        assert!(counted.remote_side && counted.done);
+        assert_eq!(counted.current, Some(5));
+
+        assert_eq!(parse_progress("From github.com:owner/repo"), None);
+        assert_eq!(
+            parse_progress("   abc123..def456  main -> origin/main"),
+            None
+        );
+        assert_eq!(
+            parse_progress("remote: Total 5 (delta 0), reused 0 (delta 0)"),
+            None
+        );
+        assert_eq!(
+            parse_progress("error: failed to push some refs to 'x'"),
+            None
+        );
+    }
+}
@@ -68,12 +68,18 @@ impl I18nService {
                 .await
             {
                 Ok(locale) => {
+                    crate::util::i18n::set_current_locale(locale.clone());
                     let mut current = self.current_locale.write().await;
                     *current = locale;
                     info!("Loaded locale from config: {}", current.as_str());
                 }
                 Err(_) => {
-                    debug!("Locale config not found, using default");
+                    let locale = crate::util::i18n::current_locale();
+                    debug!(
+                        "Locale config not found, using system locale: {}",
+                        locale.as_str()
+                    );
+                    *self.current_locale.write().await = locale;
                 }
             }
         }
@@ -125,6 +131,7 @@ impl I18nService {
             *current = locale.clone();
             old
         };
+        crate::util::i18n::set_current_locale(locale.clone());
 
         if let Some(ref config_service) = self.config_service {
             config_service
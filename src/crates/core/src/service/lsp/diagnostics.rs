@@ -0,0 +1,267 @@
+//! Diagnostics store
+//!
+//! Latest `textDocument/publishDiagnostics` report of every file, keyed by
+//! file path so tools don't need to know how a server spelled the URI. Shared
+//! by all workspaces; callers can wait for the next report of a file.
+
+use log::debug;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+use super::types::Diagnostic;
+
+const UPDATE_CHANNEL_CAPACITY: usize = 256;
+
+static GLOBAL_DIAGNOSTICS: LazyLock<DiagnosticsStore> = LazyLock::new(DiagnosticsStore::new);
+
+/// Diagnostic severity (LSP numbering; a missing severity counts as an error).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl DiagnosticSeverity {
+    pub fn from_lsp(severity: Option<u32>) -> Self {
+        match severity {
+            Some(2) => Self::Warning,
+            Some(3) => Self::Information,
+            Some(4) => Self::Hint,
+            _ => Self::Error,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Information => "info",
+            Self::Hint => "hint",
+        }
+    }
+}
+
+/// Last diagnostics report for one file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiagnostics {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Milliseconds since the epoch.
+    pub updated_at: u64,
+}
+
+impl FileDiagnostics {
+    pub fn count(&self, severity: DiagnosticSeverity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| DiagnosticSeverity::from_lsp(diagnostic.severity) == severity)
+            .count()
+    }
+}
+
+/// Diagnostics of all files, fed by the servers' diagnostics callbacks.
+pub struct DiagnosticsStore {
+    files: RwLock<HashMap<PathBuf, FileDiagnostics>>,
+    updates: broadcast::Sender<PathBuf>,
+}
+
+impl DiagnosticsStore {
+    pub fn new() -> Self {
+        Self {
+            files: RwLock::new(HashMap::new()),
+            updates: broadcast::channel(UPDATE_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Records a `publishDiagnostics` report; an empty list clears the file.
+    pub fn publish(&self, uri: &str, language: Option<&str>, diagnostics: &[serde_json::Value]) {
+        let Some(path) = uri_to_path(uri) else {
+            debug!("Ignoring diagnostics for non-file URI: {}", uri);
+            return;
+        };
+        let parsed: Vec<Diagnostic> = diagnostics
+            .iter()
+            .filter_map(|diagnostic| serde_json::from_value(diagnostic.clone()).ok())
+            .collect();
+        let updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+
+        if let Ok(mut files) = self.files.write() {
+            files.insert(
+                path.clone(),
+                FileDiagnostics {
+                    uri: uri.to_string(),
+                    language: language.map(str::to_string),
+                    diagnostics: parsed,
+                    updated_at,
+                },
+            );
+        }
+        let _ = self.updates.send(path);
+    }
+
+    pub fn get(&self, path: &Path) -> Option<FileDiagnostics> {
+        self.files.read().ok()?.get(path).cloned()
+    }
+
+    /// Files below `root` that currently have diagnostics, sorted by path.
+    pub fn under(&self, root: &Path) -> Vec<(PathBuf, FileDiagnostics)> {
+        let Ok(files) = self.files.read() else {
+            return Vec::new();
+        };
+        let mut entries: Vec<(PathBuf, FileDiagnostics)> = files
+            .iter()
+            .filter(|(path, file)| path.starts_with(root) && !file.diagnostics.is_empty())
+            .map(|(path, file)| (path.clone(), file.clone()))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+
+    /// Forgets the diagnostics of files below `root` (e.g. when its servers stop).
+    pub fn clear_under(&self, root: &Path) {
+        if let Ok(mut files) = self.files.write() {
+            files.retain(|path, _| !path.starts_with(root));
+        }
+    }
+
+    /// Receives the path of every file whose diagnostics are published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<PathBuf> {
+        self.updates.subscribe()
+    }
+}
+
+impl Default for DiagnosticsStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Process-wide diagnostics store
+pub fn get_global_diagnostics_store() -> &'static DiagnosticsStore {
+    &GLOBAL_DIAGNOSTICS
+}
+
+/// Waits on `updates` until `path` gets a new report or `timeout` passes.
+/// Returns whether a report arrived.
+pub async fn wait_for_diagnostics(
+    updates: &mut broadcast::Receiver<PathBuf>,
+    path: &Path,
+    timeout: Duration,
+) -> bool {
+    let wait = async {
+        loop {
+            match updates.recv().await {
+                Ok(updated) if updated == path => return true,
+                Ok(_) => {}
+                // Missed reports may include ours; the store has the latest either way
+                Err(broadcast::error::RecvError::Lagged(_)) => return true,
+                Err(broadcast::error::RecvError::Closed) => return false,
+            }
+        }
+    };
+    tokio::time::timeout(timeout, wait).await.unwrap_or(false)
+}
+
+/// Local path of a `file://` URI, undoing percent-encoding (`%20`, `c%3A`).
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let decoded = urlencoding::decode(rest).ok()?;
+    // `file:///C:/dir` on Windows
+    let path = match decoded.strip_prefix('/') {
+        Some(stripped) if stripped.as_bytes().get(1) == Some(&b':') => stripped,
+        _ => decoded.as_ref(),
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn diagnostic(line: u32, severity: u32, message: &str) -> serde_json::Value {
+        json!({
+            "range": {
+                "start": { "line": line, "character": 4 },
+                "end": { "line": line, "character": 9 }
+            },
+            "severity": severity,
+            "code": "E0425",
+            "source": "rustc",
+            "message": message
+        })
+    }
+
+    #[test]
+    fn decodes_file_uris() {
+        assert_eq!(
+            uri_to_path("file:///home/me/my%20project/src/main.rs"),
+            Some(PathBuf::from("/home/me/my project/src/main.rs"))
+        );
+        assert_eq!(
+            uri_to_path("file:///c%3A/work/app.ts"),
+            Some(PathBuf::from("c:/work/app.ts"))
+        );
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+    }
+
+    #[test]
+    fn keeps_latest_report_per_file() {
+        let store = DiagnosticsStore::new();
+        let uri = "file:///ws/src/lib.rs";
+        store.publish(
+            uri,
+            Some("rust"),
+            &[
+                diagnostic(3, 1, "cannot find value"),
+                diagnostic(8, 2, "unused variable"),
+            ],
+        );
+
+        let file = store.get(Path::new("/ws/src/lib.rs")).unwrap();
+        assert_eq!(file.count(DiagnosticSeverity::Error), 1);
+        assert_eq!(file.count(DiagnosticSeverity::Warning), 1);
+        assert_eq!(store.under(Path::new("/ws")).len(), 1);
+        assert!(store.under(Path::new("/other")).is_empty());
+
+        store.publish(uri, Some("rust"), &[]);
+        assert!(store
+            .get(Path::new("/ws/src/lib.rs"))
+            .unwrap()
+            .diagnostics
+            .is_empty());
+        assert!(store.under(Path::new("/ws")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn waits_for_the_requested_file() {
+        let store = DiagnosticsStore::new();
+        let mut updates = store.subscribe();
+        store.publish("file:///ws/a.rs", None, &[]);
+        store.publish("file:///ws/b.rs", None, &[diagnostic(0, 1, "oops")]);
+
+        assert!(
+            wait_for_diagnostics(&mut updates, Path::new("/ws/b.rs"), Duration::from_secs(1)).await
+        );
+        assert!(
+            !wait_for_diagnostics(
+                &mut updates,
+                Path::new("/ws/c.rs"),
+                Duration::from_millis(50)
+            )
+            .await
+        );
+    }
+}
@@ -7,12 +7,13 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use super::plugin_loader::PluginLoader;
+use super::plugin_loader::{self, PluginLoader};
 use super::process::{
     CrashCallback, DiagnosticsCallback, LspServerProcess, ProgressCallback, TokenCreateCallback,
 };
 use super::registry::PluginRegistry;
-use super::types::{CompletionItem, LspPlugin};
+use super::types::{CompletionItem, LspPlugin, PluginUpdate, PluginUpdateInfo, ServerConfig};
+use crate::service::config::LspConfig;
 
 /// LSP protocol-layer manager (stateless, pure protocol implementation).
 pub struct LspManager {
@@ -24,6 +25,8 @@ pub struct LspManager {
     processes: Arc<RwLock<HashMap<String, Arc<LspServerProcess>>>>,
     /// Diagnostics cache (`uri -> diagnostics`).
     diagnostics_cache: Arc<RwLock<HashMap<String, Vec<serde_json::Value>>>>,
+    /// Servers set up in the settings (`language -> command`); they take precedence over plugins.
+    configured_servers: Arc<RwLock<HashMap<String, ServerConfig>>>,
 }
 
 impl LspManager {
@@ -34,9 +37,38 @@ impl LspManager {
             registry: Arc::new(RwLock::new(PluginRegistry::new())),
             processes: Arc::new(RwLock::new(HashMap::new())),
             diagnostics_cache: Arc::new(RwLock::new(HashMap::new())),
+            configured_servers: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Applies the `command` overrides of the LSP settings. Running servers keep
+    /// their command until restarted.
+    pub async fn configure_servers(&self, config: &LspConfig) {
+        let servers: HashMap<String, ServerConfig> = config
+            .servers
+            .iter()
+            .filter_map(|(language, server)| {
+                let command = server.command.as_ref().filter(|c| !c.trim().is_empty())?;
+                Some((
+                    language.clone(),
+                    ServerConfig {
+                        command: command.clone(),
+                        args: server.args.clone(),
+                        env: HashMap::new(),
+                        runtime: None,
+                    },
+                ))
+            })
+            .collect();
+        *self.configured_servers.write().await = servers;
+    }
+
+    /// Whether a configured command or an installed plugin serves `language`.
+    pub async fn has_server_for_language(&self, language: &str) -> bool {
+        self.configured_servers.read().await.contains_key(language)
+            || self.find_plugin_by_language(language).await.is_some()
+    }
+
     /// Initializes the manager (loads installed plugins).
     pub async fn initialize(&self) -> Result<()> {
         info!("Initializing LSP Manager");
@@ -94,6 +126,51 @@ impl LspManager {
         Ok(plugin_id)
     }
 
+    /// Installs a package over the installed plugin with the same ID, keeping
+    /// its state. Older or equal versions are refused unless `force`.
+    pub async fn update_plugin(&self, package_path: PathBuf, force: bool) -> Result<PluginUpdate> {
+        info!("Updating plugin from: {:?}", package_path);
+
+        let (package, _) = self.plugin_loader.open_package(&package_path)?;
+        let installed = self
+            .get_plugin(&package.id)
+            .await
+            .ok_or_else(|| anyhow!("Plugin not installed: {}", package.id))?;
+        plugin_loader::check_update_version(&installed.version, &package.version, force)?;
+
+        // Running servers hold the old binary open
+        for language in &installed.languages {
+            if let Err(e) = self.stop_server(language).await {
+                warn!("Failed to stop server for {}: {}", language, e);
+            }
+        }
+
+        let update = self
+            .plugin_loader
+            .update_plugin_package(&package_path, force)
+            .await?;
+        let plugin = self.plugin_loader.load_plugin(&update.plugin_id).await?;
+
+        {
+            let mut registry = self.registry.write().await;
+            registry.unregister(&update.plugin_id)?;
+            registry.register(plugin)?;
+        }
+
+        info!(
+            "Plugin updated and registered: {} v{} -> v{}",
+            update.plugin_id, update.previous_version, update.version
+        );
+
+        Ok(update)
+    }
+
+    /// Compares installed plugins with the versions their `update_url` announces.
+    pub async fn check_plugin_updates(&self) -> Vec<PluginUpdateInfo> {
+        let plugins = self.list_plugins().await;
+        plugin_loader::check_plugin_updates(&plugins).await
+    }
+
     /// Uninstalls a plugin.
     pub async fn uninstall_plugin(&self, plugin_id: &str) -> Result<()> {
         info!("Uninstalling plugin: {}", plugin_id);
@@ -129,20 +206,6 @@ impl LspManager {
         token_create_callback: Option<TokenCreateCallback>,
         diagnostics_callback: Option<DiagnosticsCallback>,
     ) -> Result<()> {
-        let plugin = {
-            let registry = self.registry.read().await;
-            match registry.find_by_language(language).cloned() {
-                Some(plugin) => plugin,
-                None => {
-                    let err = anyhow!("No LSP plugin found for language: {}", language);
-                    warn!("{} (this is expected for plaintext)", err);
-                    return Err(err);
-                }
-            }
-        };
-
-        let plugin_id = plugin.id.clone();
-
         {
             let processes = self.processes.read().await;
             if processes.contains_key(language) {
@@ -150,15 +213,43 @@ impl LspManager {
             }
         }
 
-        let server_path = self.plugin_loader.get_server_path(&plugin).map_err(|e| {
-            error!("Failed to get server path: {}", e);
-            e
-        })?;
+        let configured = self.configured_servers.read().await.get(language).cloned();
+        let (plugin_id, server_path, server_config) = match configured {
+            Some(server) => {
+                let server_path = which::which(&server.command).map_err(|e| {
+                    anyhow!(
+                        "Configured LSP server for {} not found: {} ({})",
+                        language,
+                        server.command,
+                        e
+                    )
+                })?;
+                (format!("config:{}", language), server_path, server)
+            }
+            None => {
+                let plugin = {
+                    let registry = self.registry.read().await;
+                    match registry.find_by_language(language).cloned() {
+                        Some(plugin) => plugin,
+                        None => {
+                            let err = anyhow!("No LSP plugin found for language: {}", language);
+                            warn!("{} (this is expected for plaintext)", err);
+                            return Err(err);
+                        }
+                    }
+                };
+                let server_path = self.plugin_loader.get_server_path(&plugin).map_err(|e| {
+                    error!("Failed to get server path: {}", e);
+                    e
+                })?;
+                (plugin.id, server_path, plugin.server)
+            }
+        };
 
         let process = LspServerProcess::spawn(
             plugin_id.clone(),
             server_path.clone(),
-            &plugin.server,
+            &server_config,
             crash_callback,
             progress_callback,
             token_create_callback,
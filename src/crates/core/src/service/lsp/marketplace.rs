@@ -0,0 +1,391 @@
+//! LSP plugin marketplace
+//!
+//! Fetches the plugin index from a configurable URL, keeps the last good copy
+//! on disk for offline use, and downloads plugin packages with their size
+//! capped and their SHA-256 checked before they reach the installer.
+
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use super::plugin_loader::compare_plugin_versions;
+use super::types::LspPlugin;
+
+/// A cached index younger than this is used without asking the server.
+pub const INDEX_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Largest plugin package accepted from the marketplace.
+pub const MAX_PACKAGE_BYTES: u64 = 256 * 1024 * 1024;
+
+const INDEX_TIMEOUT: Duration = Duration::from_secs(20);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Plugin listed in the marketplace index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketplaceEntry {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Where the `.vcpkg` package is downloaded from.
+    pub download_url: String,
+    /// Hex SHA-256 of the package.
+    pub sha256: String,
+    /// Package size in bytes, when the index states it.
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+/// Marketplace index document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketplaceIndex {
+    #[serde(default)]
+    pub plugins: Vec<MarketplaceEntry>,
+}
+
+/// Index as stored in the cache file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedIndex {
+    url: String,
+    /// Unix milliseconds of the successful fetch.
+    fetched_at: i64,
+    index: MarketplaceIndex,
+}
+
+/// Index returned to callers, with where it came from.
+#[derive(Debug, Clone)]
+pub struct LoadedIndex {
+    pub index: MarketplaceIndex,
+    pub fetched_at: i64,
+    /// The server could not be reached and a cached copy older than
+    /// `INDEX_CACHE_TTL` is returned.
+    pub stale: bool,
+    /// Why the server could not be reached.
+    pub error: Option<String>,
+}
+
+/// Marketplace entry with its installation status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplacePluginStatus {
+    pub entry: MarketplaceEntry,
+    pub installed_version: Option<String>,
+    pub update_available: bool,
+}
+
+/// Marketplace listing for the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplaceListing {
+    pub plugins: Vec<MarketplacePluginStatus>,
+    pub fetched_at: i64,
+    pub stale: bool,
+    pub error: Option<String>,
+}
+
+/// Parses an index, dropping entries that could not be verified or installed.
+pub fn parse_index(content: &[u8]) -> Result<MarketplaceIndex> {
+    let mut index: MarketplaceIndex =
+        serde_json::from_slice(content).map_err(|e| anyhow!("Invalid marketplace index: {}", e))?;
+
+    index.plugins.retain(|entry| {
+        let valid = !entry.id.trim().is_empty()
+            && !entry.id.starts_with('.')
+            && !entry.id.contains(['/', '\\'])
+            && entry.download_url.starts_with("https://")
+            && is_sha256_hex(&entry.sha256);
+        if !valid {
+            warn!("Skipping invalid marketplace entry: id={}", entry.id);
+        }
+        valid
+    });
+
+    Ok(index)
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Loads the index from `url`, using the cache at `cache_path` while it is
+/// fresh (unless `refresh`) and whenever the server cannot be reached.
+pub async fn load_index(url: &str, cache_path: &Path, refresh: bool) -> Result<LoadedIndex> {
+    let cached = read_cache(cache_path, url).await;
+    let now = chrono::Utc::now().timestamp_millis();
+    let is_fresh = |cached: &CachedIndex| {
+        now.saturating_sub(cached.fetched_at) < INDEX_CACHE_TTL.as_millis() as i64
+    };
+
+    if let Some(cached) = cached
+        .as_ref()
+        .filter(|cached| !refresh && is_fresh(cached))
+    {
+        debug!("Using cached marketplace index: url={}", url);
+        return Ok(LoadedIndex {
+            index: cached.index.clone(),
+            fetched_at: cached.fetched_at,
+            stale: false,
+            error: None,
+        });
+    }
+
+    match fetch_index(url).await {
+        Ok(index) => {
+            let entry = CachedIndex {
+                url: url.to_string(),
+                fetched_at: now,
+                index,
+            };
+            if let Err(e) = write_cache(cache_path, &entry).await {
+                warn!("Failed to cache marketplace index: {}", e);
+            }
+            Ok(LoadedIndex {
+                index: entry.index,
+                fetched_at: now,
+                stale: false,
+                error: None,
+            })
+        }
+        Err(e) => match cached {
+            Some(cached) => {
+                warn!("Marketplace unreachable, using cached index: {}", e);
+                Ok(LoadedIndex {
+                    stale: !is_fresh(&cached),
+                    index: cached.index,
+                    fetched_at: cached.fetched_at,
+                    error: Some(e.to_string()),
+                })
+            }
+            None => Err(e),
+        },
+    }
+}
+
+async fn fetch_index(url: &str) -> Result<MarketplaceIndex> {
+    let client = reqwest::Client::builder().timeout(INDEX_TIMEOUT).build()?;
+    let response = client.get(url).send().await?.error_for_status()?;
+    let content = response.bytes().await?;
+    parse_index(&content)
+}
+
+async fn read_cache(cache_path: &Path, url: &str) -> Option<CachedIndex> {
+    let content = fs::read(cache_path).await.ok()?;
+    let cached: CachedIndex = serde_json::from_slice(&content).ok()?;
+    // A cache of another marketplace is no fallback for this one
+    (cached.url == url).then_some(cached)
+}
+
+async fn write_cache(cache_path: &Path, cached: &CachedIndex) -> Result<()> {
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let tmp_path = cache_path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(cached)?).await?;
+    fs::rename(&tmp_path, cache_path).await?;
+    Ok(())
+}
+
+/// Marks each entry with the installed version and whether it is newer.
+pub fn cross_reference(
+    index: &MarketplaceIndex,
+    installed: &[LspPlugin],
+) -> Vec<MarketplacePluginStatus> {
+    index
+        .plugins
+        .iter()
+        .map(|entry| {
+            let installed_version = installed
+                .iter()
+                .find(|plugin| plugin.id == entry.id)
+                .map(|plugin| plugin.version.clone());
+            let update_available = installed_version.as_deref().is_some_and(|version| {
+                compare_plugin_versions(&entry.version, version) == Ordering::Greater
+            });
+            MarketplacePluginStatus {
+                entry: entry.clone(),
+                installed_version,
+                update_available,
+            }
+        })
+        .collect()
+}
+
+/// Downloads the package of `entry` into `temp_dir`, refusing packages over
+/// `MAX_PACKAGE_BYTES` or whose SHA-256 does not match the index. The caller
+/// removes the returned file once it is installed.
+pub async fn download_package(entry: &MarketplaceEntry, temp_dir: &Path) -> Result<PathBuf> {
+    if entry.size.is_some_and(|size| size > MAX_PACKAGE_BYTES) {
+        return Err(anyhow!(
+            "Plugin package {} is larger than the {} MB limit",
+            entry.id,
+            MAX_PACKAGE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    fs::create_dir_all(temp_dir).await?;
+    let path = temp_dir.join(format!("{}-{}.vcpkg", entry.id, uuid::Uuid::new_v4()));
+
+    let result = download_to(entry, &path).await;
+    if result.is_err() {
+        let _ = fs::remove_file(&path).await;
+    }
+    result.map(|_| path)
+}
+
+async fn download_to(entry: &MarketplaceEntry, path: &Path) -> Result<()> {
+    info!(
+        "Downloading marketplace plugin: id={}, version={}",
+        entry.id, entry.version
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()?;
+    let mut response = client
+        .get(&entry.download_url)
+        .send()
+        .await?
+        .error_for_status()?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_PACKAGE_BYTES)
+    {
+        return Err(anyhow!(
+            "Plugin package {} is larger than the {} MB limit",
+            entry.id,
+            MAX_PACKAGE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let mut file = fs::File::create(path).await?;
+    let mut hasher = Sha256::new();
+    let mut written: u64 = 0;
+    while let Some(chunk) = response.chunk().await? {
+        written += chunk.len() as u64;
+        if written > MAX_PACKAGE_BYTES {
+            return Err(anyhow!(
+                "Plugin package {} is larger than the {} MB limit",
+                entry.id,
+                MAX_PACKAGE_BYTES / (1024 * 1024)
+            ));
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+
+    verify_digest(&entry.sha256, &hex::encode(hasher.finalize()))
+        .map_err(|e| anyhow!("Plugin package {} failed verification: {}", entry.id, e))
+}
+
+fn verify_digest(expected: &str, actual: &str) -> Result<()> {
+    if expected.eq_ignore_ascii_case(actual) {
+        Ok(())
+    } else {
+        Err(anyhow!("expected SHA-256 {}, got {}", expected, actual))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn index_json() -> String {
+        format!(
+            r#"{{"plugins": [
+                {{"id": "rust-analyzer", "name": "Rust", "version": "1.2.0",
+                  "download_url": "https://example.com/ra.vcpkg", "sha256": "{sha}"}},
+                {{"id": "gopls", "name": "Go", "version": "0.9.0",
+                  "download_url": "https://example.com/gopls.vcpkg", "sha256": "{sha}"}},
+                {{"id": "../escape", "name": "Bad", "version": "1.0.0",
+                  "download_url": "https://example.com/x.vcpkg", "sha256": "{sha}"}},
+                {{"id": "plain", "name": "Plain", "version": "1.0.0",
+                  "download_url": "http://example.com/x.vcpkg", "sha256": "{sha}"}},
+                {{"id": "nosum", "name": "No sum", "version": "1.0.0",
+                  "download_url": "https://example.com/x.vcpkg", "sha256": "abc"}}
+            ]}}"#,
+            sha = SHA
+        )
+    }
+
+    fn installed(id: &str, version: &str) -> LspPlugin {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": id,
+            "version": version,
+            "author": "",
+            "description": "",
+            "server": {"command": "server"},
+            "languages": [],
+            "file_extensions": [],
+            "capabilities": {}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_index_and_marks_installed_plugins() {
+        let index = parse_index(index_json().as_bytes()).unwrap();
+        let ids: Vec<&str> = index.plugins.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["rust-analyzer", "gopls"]);
+
+        let statuses = cross_reference(&index, &[installed("rust-analyzer", "1.1.0")]);
+        assert_eq!(statuses[0].installed_version.as_deref(), Some("1.1.0"));
+        assert!(statuses[0].update_available);
+        assert_eq!(statuses[1].installed_version, None);
+        assert!(!statuses[1].update_available);
+
+        assert!(verify_digest(SHA, &SHA.to_uppercase()).is_ok());
+        assert!(verify_digest(SHA, &"0".repeat(64)).is_err());
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_cached_index_when_unreachable() {
+        let dir = std::env::temp_dir().join(format!("bitfun-marketplace-{}", uuid::Uuid::new_v4()));
+        let cache_path = dir.join("index.json");
+        // Nothing listens on the discard port
+        let url = "http://127.0.0.1:9/index.json";
+
+        assert!(load_index(url, &cache_path, false).await.is_err());
+
+        let day_ago = chrono::Utc::now().timestamp_millis() - 24 * 60 * 60 * 1000;
+        write_cache(
+            &cache_path,
+            &CachedIndex {
+                url: url.to_string(),
+                fetched_at: day_ago,
+                index: parse_index(index_json().as_bytes()).unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let loaded = load_index(url, &cache_path, false).await.unwrap();
+        assert!(loaded.stale);
+        assert!(loaded.error.is_some());
+        assert_eq!(loaded.fetched_at, day_ago);
+        assert_eq!(loaded.index.plugins.len(), 2);
+
+        // The cache of another marketplace is ignored
+        assert!(
+            load_index("http://127.0.0.1:9/other.json", &cache_path, false)
+                .await
+                .is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
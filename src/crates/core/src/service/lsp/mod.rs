@@ -1,16 +1,18 @@
 //! LSP (Language Server Protocol) service module
 //!
 //! Provides full LSP support, including:
-//! - Plugin management (install/uninstall/load)
+//! - Plugin management (install/update/uninstall/load)
 //! - Server process lifecycle management
 //! - LSP protocol communication
 //! - Code completion, navigation, diagnostics, and more
 
 pub mod config_watcher;
 pub mod debouncer;
+pub mod diagnostics;
 pub mod file_sync;
 pub mod global;
 pub mod manager;
+pub mod marketplace;
 pub mod plugin_loader;
 pub mod process;
 pub mod project_detector;
@@ -19,6 +21,9 @@ pub mod registry;
 pub mod types;
 pub mod workspace_manager;
 
+pub use diagnostics::{
+    get_global_diagnostics_store, DiagnosticSeverity, DiagnosticsStore, FileDiagnostics,
+};
 pub use global::{
     close_workspace, get_all_workspace_paths, get_global_lsp_manager, get_workspace_manager,
     initialize_global_lsp_manager, is_lsp_manager_initialized, open_workspace,
@@ -26,5 +31,5 @@ pub use global::{
 };
 pub use manager::LspManager;
 pub use project_detector::{ProjectDetector, ProjectInfo};
-pub use types::{CompletionItem, LspPlugin, PluginSource};
+pub use types::{CompletionItem, LspPlugin, PluginSource, PluginUpdate, PluginUpdateInfo};
 pub use workspace_manager::{LspEvent, ServerState, ServerStatus, WorkspaceLspManager};
@@ -4,10 +4,20 @@
 
 use anyhow::{anyhow, Result};
 use log::{debug, error, info, warn};
+use serde::Deserialize;
+use std::cmp::Ordering;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 
-use super::types::LspPlugin;
+use super::types::{LspPlugin, PluginUpdate, PluginUpdateInfo};
+
+/// Directory inside a plugin that holds its state (`state.json`); kept when
+/// the plugin is updated.
+pub const PLUGIN_STATE_DIR: &str = ".bitfun-plugin";
+
+/// Timeout for fetching a plugin's `update_url`.
+const UPDATE_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
 
 /// Plugin loader.
 pub struct PluginLoader {
@@ -93,10 +103,11 @@ impl PluginLoader {
         Ok(plugins)
     }
 
-    /// Installs a plugin package (a `.vcpkg` file).
-    pub async fn install_plugin_package(&self, package_path: &Path) -> Result<String> {
-        info!("Installing plugin package: {:?}", package_path);
-
+    /// Opens a plugin package (a `.vcpkg` file) and reads its manifest.
+    pub fn open_package(
+        &self,
+        package_path: &Path,
+    ) -> Result<(LspPlugin, zip::ZipArchive<std::fs::File>)> {
         if !package_path.exists() {
             error!("Plugin package not found: {:?}", package_path);
             return Err(anyhow!("Plugin package not found: {:?}", package_path));
@@ -107,15 +118,6 @@ impl PluginLoader {
             return Err(anyhow!("Invalid plugin package format (expected .vcpkg)"));
         }
 
-        let temp_id = format!(".temp-{}", std::process::id());
-        let temp_dir = self.plugins_dir.join(&temp_id);
-
-        if temp_dir.exists() {
-            fs::remove_dir_all(&temp_dir).await?;
-        }
-
-        fs::create_dir_all(&temp_dir).await?;
-
         let file = std::fs::File::open(package_path)?;
         let mut archive = zip::ZipArchive::new(file)?;
 
@@ -126,6 +128,14 @@ impl PluginLoader {
         }
 
         let plugin: LspPlugin = serde_json::from_str(&manifest_content)?;
+        Ok((plugin, archive))
+    }
+
+    /// Installs a plugin package (a `.vcpkg` file).
+    pub async fn install_plugin_package(&self, package_path: &Path) -> Result<String> {
+        info!("Installing plugin package: {:?}", package_path);
+
+        let (plugin, mut archive) = self.open_package(package_path)?;
         let plugin_id = plugin.id.clone();
 
         let plugin_dir = self.plugins_dir.join(&plugin_id);
@@ -135,10 +145,6 @@ impl PluginLoader {
 
         archive.extract(&plugin_dir)?;
 
-        if temp_dir.exists() {
-            let _ = fs::remove_dir_all(&temp_dir).await;
-        }
-
         info!(
             "Plugin installed: {} v{} (id: {})",
             plugin.name, plugin.version, plugin_id
@@ -147,6 +153,103 @@ impl PluginLoader {
         Ok(plugin_id)
     }
 
+    /// Installs a plugin package over the installed plugin with the same ID,
+    /// keeping its state directory. The package must be newer unless `force`.
+    pub async fn update_plugin_package(
+        &self,
+        package_path: &Path,
+        force: bool,
+    ) -> Result<PluginUpdate> {
+        info!("Updating plugin from package: {:?}", package_path);
+
+        let (plugin, mut archive) = self.open_package(package_path)?;
+        let plugin_dir = self.plugins_dir.join(&plugin.id);
+        if !plugin_dir.exists() {
+            return Err(anyhow!(
+                "Plugin not installed: {}; install it instead",
+                plugin.id
+            ));
+        }
+
+        let installed = self.load_plugin(&plugin.id).await?;
+        check_update_version(&installed.version, &plugin.version, force)?;
+
+        let staging_dir =
+            self.plugins_dir
+                .join(format!(".temp-update-{}-{}", plugin.id, std::process::id()));
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir).await?;
+        }
+        if let Err(e) = archive.extract(&staging_dir) {
+            let _ = fs::remove_dir_all(&staging_dir).await;
+            return Err(e.into());
+        }
+
+        if let Err(e) = self.replace_plugin_dir(&plugin.id, &staging_dir).await {
+            let _ = fs::remove_dir_all(&staging_dir).await;
+            return Err(e);
+        }
+
+        info!(
+            "Plugin updated: {} v{} -> v{} (id: {})",
+            plugin.name, installed.version, plugin.version, plugin.id
+        );
+
+        Ok(PluginUpdate {
+            plugin_id: plugin.id,
+            previous_version: installed.version,
+            version: plugin.version,
+        })
+    }
+
+    /// Swaps the directory of `plugin_id` for `staging_dir`, moving the state
+    /// directory across. The old directory is restored if the swap fails.
+    pub async fn replace_plugin_dir(&self, plugin_id: &str, staging_dir: &Path) -> Result<()> {
+        let plugin_dir = self.plugins_dir.join(plugin_id);
+        let state_dir = plugin_dir.join(PLUGIN_STATE_DIR);
+        let staged_state_dir = staging_dir.join(PLUGIN_STATE_DIR);
+
+        let keep_state = state_dir.exists();
+        if keep_state {
+            // State from the installation wins over whatever the package ships
+            if staged_state_dir.exists() {
+                fs::remove_dir_all(&staged_state_dir).await?;
+            }
+            fs::rename(&state_dir, &staged_state_dir).await?;
+        }
+
+        let backup_dir =
+            self.plugins_dir
+                .join(format!(".temp-backup-{}-{}", plugin_id, std::process::id()));
+        if backup_dir.exists() {
+            fs::remove_dir_all(&backup_dir).await?;
+        }
+
+        if let Err(e) = fs::rename(&plugin_dir, &backup_dir).await {
+            if keep_state {
+                let _ = fs::rename(&staged_state_dir, &state_dir).await;
+            }
+            return Err(anyhow!("Failed to move old plugin version aside: {}", e));
+        }
+
+        if let Err(e) = fs::rename(staging_dir, &plugin_dir).await {
+            let _ = fs::rename(&backup_dir, &plugin_dir).await;
+            if keep_state {
+                let _ = fs::rename(&staged_state_dir, &state_dir).await;
+            }
+            return Err(anyhow!("Failed to install new plugin version: {}", e));
+        }
+
+        if let Err(e) = fs::remove_dir_all(&backup_dir).await {
+            warn!(
+                "Failed to remove old plugin version {:?}: {}",
+                backup_dir, e
+            );
+        }
+
+        Ok(())
+    }
+
     /// Uninstalls a plugin.
     pub async fn uninstall_plugin(&self, plugin_id: &str) -> Result<()> {
         info!("Uninstalling plugin: {}", plugin_id);
@@ -295,3 +398,231 @@ impl PluginLoader {
         &self.plugins_dir
     }
 }
+
+/// Latest version document served at a plugin's `update_url`.
+#[derive(Debug, Deserialize)]
+struct UpdateManifest {
+    version: String,
+    #[serde(default, alias = "downloadUrl")]
+    download_url: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+/// Checks `update_url` of each plugin that declares one.
+pub async fn check_plugin_updates(plugins: &[LspPlugin]) -> Vec<PluginUpdateInfo> {
+    let client = match reqwest::Client::builder()
+        .timeout(UPDATE_CHECK_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Failed to create HTTP client for plugin updates: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut results = Vec::new();
+    for plugin in plugins {
+        let Some(update_url) = plugin.update_url.as_deref() else {
+            continue;
+        };
+
+        let mut info = PluginUpdateInfo {
+            plugin_id: plugin.id.clone(),
+            name: plugin.name.clone(),
+            installed_version: plugin.version.clone(),
+            latest_version: None,
+            update_available: false,
+            download_url: None,
+            notes: None,
+            error: None,
+        };
+
+        match fetch_update_manifest(&client, update_url).await {
+            Ok(latest) => {
+                info.update_available =
+                    compare_plugin_versions(&latest.version, &plugin.version) == Ordering::Greater;
+                info.latest_version = Some(latest.version);
+                info.download_url = latest.download_url;
+                info.notes = latest.notes;
+            }
+            Err(e) => {
+                warn!("Update check failed for plugin {}: {}", plugin.id, e);
+                info.error = Some(e.to_string());
+            }
+        }
+        results.push(info);
+    }
+
+    results
+}
+
+async fn fetch_update_manifest(client: &reqwest::Client, url: &str) -> Result<UpdateManifest> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    let manifest: UpdateManifest = response
+        .json()
+        .await
+        .map_err(|e| anyhow!("Invalid update document at {}: {}", url, e))?;
+    Ok(manifest)
+}
+
+/// Refuses to replace `installed` with `new` unless `new` is newer or the
+/// update is forced.
+pub fn check_update_version(installed: &str, new: &str, force: bool) -> Result<()> {
+    match compare_plugin_versions(new, installed) {
+        Ordering::Greater => Ok(()),
+        _ if force => {
+            warn!(
+                "Forcing plugin version change from {} to {}",
+                installed, new
+            );
+            Ok(())
+        }
+        Ordering::Equal => Err(anyhow!(
+            "Version {} is already installed; pass force to reinstall it",
+            installed
+        )),
+        Ordering::Less => Err(anyhow!(
+            "Version {} is older than the installed {}; pass force to downgrade",
+            new,
+            installed
+        )),
+    }
+}
+
+/// Compares versions by semver precedence: `major.minor.patch` numerically,
+/// a pre-release below its release, build metadata ignored. Missing parts
+/// count as 0 and a leading `v` is allowed.
+pub fn compare_plugin_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> ([u64; 3], Option<&str>) {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        let version = version.split('+').next().unwrap_or_default();
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let mut parts = [0; 3];
+        for (slot, part) in parts.iter_mut().zip(core.split('.')) {
+            *slot = part.trim().parse().unwrap_or(0);
+        }
+        (parts, pre)
+    }
+
+    fn compare_pre(a: &str, b: &str) -> Ordering {
+        let mut a_ids = a.split('.');
+        let mut b_ids = b.split('.');
+        loop {
+            match (a_ids.next(), b_ids.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) => {
+                    let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        // Numeric identifiers rank below alphanumeric ones
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    };
+                    if order != Ordering::Equal {
+                        return order;
+                    }
+                }
+            }
+        }
+    }
+
+    let (a_core, a_pre) = split(a);
+    let (b_core, b_pre) = split(b);
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => compare_pre(x, y),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_by_semver_precedence() {
+        assert_eq!(
+            compare_plugin_versions("1.10.0", "1.9.3"),
+            Ordering::Greater
+        );
+        assert_eq!(compare_plugin_versions("v1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(
+            compare_plugin_versions("1.2.0+build.5", "1.2.0"),
+            Ordering::Equal
+        );
+        assert_eq!(
+            compare_plugin_versions("1.2.0-beta", "1.2.0"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_plugin_versions("1.2.0-alpha.10", "1.2.0-alpha.9"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_plugin_versions("1.2.0-alpha", "1.2.0-alpha.1"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_plugin_versions("1.2.0-rc.1", "1.2.0-beta.2"),
+            Ordering::Greater
+        );
+
+        assert!(check_update_version("1.0.0", "1.1.0", false).is_ok());
+        assert!(check_update_version("1.1.0", "1.1.0", false).is_err());
+        assert!(check_update_version("1.1.0", "1.0.0", false).is_err());
+        assert!(check_update_version("1.1.0", "1.0.0", true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn replacing_a_plugin_keeps_its_state() {
+        let root = std::env::temp_dir().join(format!("bitfun-lsp-update-{}", uuid::Uuid::new_v4()));
+        let loader = PluginLoader::new(root.clone());
+
+        let plugin_dir = root.join("rust-analyzer");
+        std::fs::create_dir_all(plugin_dir.join(PLUGIN_STATE_DIR)).unwrap();
+        std::fs::write(plugin_dir.join("server"), "old").unwrap();
+        std::fs::write(plugin_dir.join("obsolete"), "old").unwrap();
+        std::fs::write(
+            plugin_dir.join(PLUGIN_STATE_DIR).join("state.json"),
+            r#"{"enabled":false}"#,
+        )
+        .unwrap();
+
+        let staging_dir = root.join(".temp-update");
+        std::fs::create_dir_all(staging_dir.join(PLUGIN_STATE_DIR)).unwrap();
+        std::fs::write(staging_dir.join("server"), "new").unwrap();
+        std::fs::write(staging_dir.join(PLUGIN_STATE_DIR).join("state.json"), "{}").unwrap();
+
+        loader
+            .replace_plugin_dir("rust-analyzer", &staging_dir)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(plugin_dir.join("server")).unwrap(),
+            "new"
+        );
+        assert!(!plugin_dir.join("obsolete").exists());
+        assert_eq!(
+            std::fs::read_to_string(plugin_dir.join(PLUGIN_STATE_DIR).join("state.json")).unwrap(),
+            r#"{"enabled":false}"#
+        );
+        assert!(!staging_dir.exists());
+        let leftovers: Vec<_> = std::fs::read_dir(&root)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".temp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1085,3 +1085,111 @@ impl Drop for LspServerProcess {
         debug!("Dropping LSP server process: {}", self.id);
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Minimal language server: answers `initialize` and reports one
+    /// diagnostic for every opened document.
+    const STUB_SERVER: &str = r#"
+import json, sys
+
+def read():
+    length = 0
+    while True:
+        line = sys.stdin.buffer.readline()
+        if not line:
+            sys.exit(0)
+        line = line.strip()
+        if not line:
+            break
+        if line.lower().startswith(b"content-length:"):
+            length = int(line.split(b":")[1])
+    return json.loads(sys.stdin.buffer.read(length))
+
+def send(message):
+    body = json.dumps(message).encode()
+    sys.stdout.buffer.write(b"Content-Length: %d\r\n\r\n" % len(body) + body)
+    sys.stdout.buffer.flush()
+
+while True:
+    message = read()
+    method = message.get("method")
+    if method == "initialize":
+        send({"jsonrpc": "2.0", "id": message["id"], "result": {"capabilities": {"textDocumentSync": 1}}})
+    elif method == "textDocument/didOpen":
+        uri = message["params"]["textDocument"]["uri"]
+        send({"jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": {"uri": uri, "diagnostics": [
+            {"range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 3}},
+             "severity": 1, "source": "stub", "message": "stub error"}]}})
+    elif method == "shutdown":
+        send({"jsonrpc": "2.0", "id": message["id"], "result": None})
+    elif method == "exit":
+        sys.exit(0)
+"#;
+
+    #[tokio::test]
+    async fn receives_diagnostics_from_server() {
+        let Ok(python) = which::which("python3") else {
+            return;
+        };
+        let dir = std::env::temp_dir().join(format!("bitfun-lsp-stub-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("stub_server.py");
+        std::fs::write(&script, STUB_SERVER).unwrap();
+
+        let config = ServerConfig {
+            command: python.to_string_lossy().to_string(),
+            args: vec![script.to_string_lossy().to_string()],
+            env: HashMap::new(),
+            runtime: None,
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let diagnostics_callback: DiagnosticsCallback = Arc::new(move |uri, diagnostics| {
+            let _ = tx.send((uri, diagnostics));
+        });
+        let process = LspServerProcess::spawn(
+            "stub".to_string(),
+            python,
+            &config,
+            None,
+            None,
+            None,
+            Some(diagnostics_callback),
+        )
+        .await
+        .unwrap();
+        process
+            .initialize(Some(dir.to_string_lossy().to_string()))
+            .await
+            .unwrap();
+
+        let uri = format!("file://{}/main.txt", dir.display());
+        process
+            .send_notification(
+                "textDocument/didOpen",
+                Some(serde_json::json!({
+                    "textDocument": {
+                        "uri": uri,
+                        "languageId": "plaintext",
+                        "version": 1,
+                        "text": "foo"
+                    }
+                })),
+            )
+            .await
+            .unwrap();
+
+        let (reported_uri, diagnostics) = timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reported_uri, uri);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["message"], "stub error");
+
+        process.shutdown().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
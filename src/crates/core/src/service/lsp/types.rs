@@ -36,6 +36,34 @@ pub struct LspPlugin {
     /// Minimum BitFun version.
     #[serde(default)]
     pub min_bitfun_version: String,
+    /// URL of a JSON document announcing the latest version
+    /// (`{"version": "...", "download_url": "...", "notes": "..."}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_url: Option<String>,
+}
+
+/// Result of installing a package over an installed plugin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdate {
+    pub plugin_id: String,
+    pub previous_version: String,
+    pub version: String,
+}
+
+/// Installed version of a plugin compared with the one its `update_url` announces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdateInfo {
+    pub plugin_id: String,
+    pub name: String,
+    pub installed_version: String,
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub download_url: Option<String>,
+    pub notes: Option<String>,
+    /// Why the check failed, if it did.
+    pub error: Option<String>,
 }
 
 /// Server configuration.
@@ -12,16 +12,20 @@ use anyhow::{anyhow, Result};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 
 use super::config_watcher::ConfigWatcher;
+use super::diagnostics::get_global_diagnostics_store;
+use super::file_sync::detect_language;
 use super::manager::LspManager;
 use super::project_detector::{ProjectDetector, ProjectInfo};
 use crate::infrastructure::events::EventEmitter;
+use crate::service::config::{get_global_config_service, LspConfig};
+use bitfun_transport::LspEventPayload;
 
 /// LSP event types (pushed to the frontend).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,10 +79,56 @@ pub enum LspEvent {
     Diagnostics {
         workspace_path: String,
         uri: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
         diagnostics: Vec<serde_json::Value>,
     },
 }
 
+impl LspEvent {
+    fn workspace_path(&self) -> &str {
+        match self {
+            Self::ServerStateChanged { workspace_path, .. }
+            | Self::DocumentOpened { workspace_path, .. }
+            | Self::DocumentClosed { workspace_path, .. }
+            | Self::WorkspaceOpened { workspace_path }
+            | Self::WorkspaceClosed { workspace_path }
+            | Self::ServerError { workspace_path, .. }
+            | Self::ProjectDetected { workspace_path, .. }
+            | Self::IndexingProgress { workspace_path, .. }
+            | Self::IndexingComplete { workspace_path, .. }
+            | Self::Diagnostics { workspace_path, .. } => workspace_path,
+        }
+    }
+
+    fn language(&self) -> Option<&str> {
+        match self {
+            Self::ServerStateChanged { language, .. }
+            | Self::DocumentOpened { language, .. }
+            | Self::ServerError { language, .. }
+            | Self::IndexingProgress { language, .. }
+            | Self::IndexingComplete { language, .. } => Some(language),
+            Self::Diagnostics { language, .. } => language.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Transport payload: the event itself plus its workspace and language for routing.
+    pub fn to_payload(&self) -> serde_json::Result<LspEventPayload> {
+        Ok(LspEventPayload {
+            workspace_path: self.workspace_path().to_string(),
+            language: self.language().map(str::to_string),
+            event_data: serde_json::to_value(self)?,
+        })
+    }
+}
+
+/// Emits `event` on the `lsp-event` channel as an [`LspEventPayload`].
+async fn emit_lsp_event(emitter: &Arc<dyn EventEmitter>, event: &LspEvent) -> Result<()> {
+    let payload = serde_json::to_value(event.to_payload()?)?;
+    emitter.emit("lsp-event", payload).await
+}
+
 /// Server status.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -133,7 +183,6 @@ struct DocumentState {
     uri: String,
     language: String,
     version: i32,
-    #[allow(dead_code)]
     opened_at: SystemTime,
 }
 
@@ -269,14 +318,7 @@ impl WorkspaceLspManager {
     /// Emits an LSP event to the frontend.
     async fn emit_event(&self, event: LspEvent) {
         if let Some(emitter) = self.emitter.read().await.as_ref() {
-            let event_data = match serde_json::to_value(&event) {
-                Ok(v) => v,
-                Err(e) => {
-                    error!("Failed to serialize LSP event: {}", e);
-                    return;
-                }
-            };
-            if let Err(e) = emitter.emit("lsp-event", event_data).await {
+            if let Err(e) = emit_lsp_event(emitter, &event).await {
                 error!("Failed to emit LSP event: {}", e);
             }
         }
@@ -318,6 +360,8 @@ impl WorkspaceLspManager {
             }
         };
 
+        self.make_room_for_document().await;
+
         let lsp = self.lsp_manager.read().await;
         lsp.did_open(&server_language, &uri, &content)
             .await
@@ -416,6 +460,63 @@ impl WorkspaceLspManager {
         docs.contains_key(uri)
     }
 
+    /// Sends the on-disk content of `path` to its server (didOpen or didChange),
+    /// starting the server if one is available for the language. Returns
+    /// `false` when no server handles the file.
+    pub async fn sync_file(&self, path: &Path) -> Result<bool> {
+        let language = detect_language(path);
+        if self
+            .get_running_server_for_language(&language)
+            .await
+            .is_none()
+        {
+            let has_server = {
+                let lsp = self.lsp_manager.read().await;
+                lsp.has_server_for_language(&language).await
+            };
+            if !has_server {
+                return Ok(false);
+            }
+            self.ensure_server_running(&language).await?;
+        }
+
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read file {:?}: {}", path, e))?;
+        let uri = format!("file://{}", path.display());
+        if self.is_document_opened(&uri).await {
+            self.change_document(uri, content).await?;
+        } else {
+            self.open_document(uri, language, content).await?;
+        }
+        Ok(true)
+    }
+
+    /// Closes the oldest documents so that one more fits within `max_open_documents`.
+    async fn make_room_for_document(&self) {
+        let max_open = load_lsp_config().await.max_open_documents.max(1);
+        let oldest: Vec<String> = {
+            let docs = self.documents.read().await;
+            if docs.len() < max_open {
+                return;
+            }
+            let mut by_age: Vec<(&String, SystemTime)> =
+                docs.iter().map(|(uri, doc)| (uri, doc.opened_at)).collect();
+            by_age.sort_by_key(|(_, opened_at)| *opened_at);
+            by_age
+                .into_iter()
+                .take(docs.len() + 1 - max_open)
+                .map(|(uri, _)| uri.clone())
+                .collect()
+        };
+        for uri in oldest {
+            debug!("Closing document over max_open_documents: {}", uri);
+            if let Err(e) = self.close_document(uri).await {
+                debug!("Failed to close document: {}", e);
+            }
+        }
+    }
+
     /// Quickly checks whether a server is running (does not trigger query or startup).
     /// Returns the actual running server language key (may differ from the requested language).
     async fn get_running_server_for_language(&self, language: &str) -> Option<String> {
@@ -471,7 +572,6 @@ impl WorkspaceLspManager {
 
     /// Ensures the server is running (prevents duplicate starts).
     /// Returns the actual server language key in use (may differ from the requested one, e.g. c -> cpp).
-    #[allow(dead_code)]
     async fn ensure_server_running(&self, language: &str) -> Result<String> {
         let status = {
             let states = self.server_states.read().await;
@@ -529,6 +629,38 @@ impl WorkspaceLspManager {
 
     /// Starts a server (with retries).
     async fn start_server(&self, language: &str) -> Result<()> {
+        let config = load_lsp_config().await;
+        if !config.is_server_enabled(language) {
+            return Err(anyhow!(
+                "LSP server for {} is disabled in settings",
+                language
+            ));
+        }
+        let active = {
+            let states = self.server_states.read().await;
+            states
+                .values()
+                .filter(|state| {
+                    matches!(
+                        state.status,
+                        ServerStatus::Running | ServerStatus::Starting | ServerStatus::Restarting
+                    )
+                })
+                .count()
+        };
+        if active >= config.max_running_servers {
+            return Err(anyhow!(
+                "Not starting {} server: {} servers are already running (max_running_servers)",
+                language,
+                active
+            ));
+        }
+        self.lsp_manager
+            .read()
+            .await
+            .configure_servers(&config)
+            .await;
+
         let notify = Arc::new(tokio::sync::Notify::new());
         {
             let mut locks = self.starting_locks.write().await;
@@ -689,9 +821,7 @@ impl WorkspaceLspManager {
                     progress: overall_progress,
                     message: message.clone(),
                 };
-                if let Ok(event_data) = serde_json::to_value(&progress_event) {
-                    let _ = emit.emit("lsp-event", event_data).await;
-                }
+                let _ = emit_lsp_event(emit, &progress_event).await;
 
                 if is_completed {
                     info!("[{}] Indexing completed", language);
@@ -700,9 +830,7 @@ impl WorkspaceLspManager {
                         language: language.clone(),
                         plugin_name: plugin_name.clone(),
                     };
-                    if let Ok(event_data) = serde_json::to_value(&complete_event) {
-                        let _ = emit.emit("lsp-event", event_data).await;
-                    }
+                    let _ = emit_lsp_event(emit, &complete_event).await;
                 }
             }
 
@@ -747,9 +875,7 @@ impl WorkspaceLspManager {
                         language: language.clone(),
                         error: "Server process crashed or became unresponsive".to_string(),
                     };
-                    if let Ok(event_data) = serde_json::to_value(&error_event) {
-                        let _ = emitter.emit("lsp-event", event_data).await;
-                    }
+                    let _ = emit_lsp_event(emitter, &error_event).await;
 
                     let state_event = LspEvent::ServerStateChanged {
                         workspace_path: workspace.display().to_string(),
@@ -757,9 +883,7 @@ impl WorkspaceLspManager {
                         status: "failed".to_string(),
                         message: Some("Server crashed".to_string()),
                     };
-                    if let Ok(event_data) = serde_json::to_value(&state_event) {
-                        let _ = emitter.emit("lsp-event", event_data).await;
-                    }
+                    let _ = emit_lsp_event(emitter, &state_event).await;
                 }
             });
         }) as Arc<dyn Fn(String) + Send + Sync>;
@@ -871,13 +995,16 @@ impl WorkspaceLspManager {
         )
             as Arc<dyn Fn(String, String, Option<u32>, String) + Send + Sync>;
 
-        let _language_clone4 = language.to_string();
+        let language_clone4 = language.to_string();
         let workspace_path4 = self.workspace_path.clone();
         let emitter_for_diagnostics = self.emitter.clone();
         let lsp_manager_for_cache = self.lsp_manager.clone();
 
         let diagnostics_callback =
             Arc::new(move |uri: String, diagnostics: Vec<serde_json::Value>| {
+                get_global_diagnostics_store().publish(&uri, Some(&language_clone4), &diagnostics);
+
+                let language = language_clone4.clone();
                 let workspace = workspace_path4.clone();
                 let emitter_clone = emitter_for_diagnostics.clone();
                 let lsp_mgr = lsp_manager_for_cache.clone();
@@ -892,6 +1019,7 @@ impl WorkspaceLspManager {
                     let event = LspEvent::Diagnostics {
                         workspace_path: workspace.display().to_string(),
                         uri: uri.clone(),
+                        language: Some(language),
                         diagnostics: diagnostics.clone(),
                     };
 
@@ -902,10 +1030,8 @@ impl WorkspaceLspManager {
                             uri,
                             diagnostics.len()
                         );
-                        if let Ok(event_data) = serde_json::to_value(&event) {
-                            if let Err(e) = emitter.emit("lsp-event", event_data).await {
-                                error!("Failed to emit diagnostics event: {}", e);
-                            }
+                        if let Err(e) = emit_lsp_event(emitter, &event).await {
+                            error!("Failed to emit diagnostics event: {}", e);
                         }
                     }
                 });
@@ -924,7 +1050,6 @@ impl WorkspaceLspManager {
     }
 
     /// Waits for server startup to complete.
-    #[allow(dead_code)]
     async fn wait_for_server_start(&self, language: &str) -> Result<()> {
         let notify = {
             let locks = self.starting_locks.read().await;
@@ -1348,6 +1473,8 @@ impl WorkspaceLspManager {
             let _ = self.stop_server(&language).await;
         }
 
+        get_global_diagnostics_store().clear_under(&self.workspace_path);
+
         info!("Workspace LSP manager disposed");
         Ok(())
     }
@@ -1404,6 +1531,20 @@ impl WorkspaceLspManager {
     }
 }
 
+/// LSP settings; the defaults when the config service isn't available.
+async fn load_lsp_config() -> LspConfig {
+    match get_global_config_service().await {
+        Ok(service) => service
+            .get_config::<LspConfig>(Some("editor.lsp"))
+            .await
+            .unwrap_or_default(),
+        Err(e) => {
+            debug!("Using default LSP settings: {}", e);
+            LspConfig::default()
+        }
+    }
+}
+
 impl Drop for WorkspaceLspManager {
     fn drop(&mut self) {
         debug!("WorkspaceLspManager dropped");
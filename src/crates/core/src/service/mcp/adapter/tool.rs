@@ -2,12 +2,14 @@
 //!
 //! Wraps MCP tools as implementations of BitFun's `Tool` trait.
 
+use crate::agentic::image_analysis::{prepare_tool_image_attachments, ImageLimits};
 use crate::agentic::tools::framework::{
     Tool, ToolRenderOptions, ToolResult, ToolUseContext, ValidationResult,
 };
-use crate::service::mcp::protocol::{MCPTool, MCPToolResult};
+use crate::service::mcp::protocol::{MCPTool, MCPToolResult, MCPToolResultContent};
 use crate::service::mcp::server::connection::MCPConnection;
 use crate::util::errors::BitFunResult;
+use crate::util::types::ToolImageAttachment;
 use async_trait::async_trait;
 use log::{debug, error, info, warn};
 use serde_json::Value;
@@ -201,13 +203,29 @@ impl Tool for MCPToolWrapper {
         let elapsed = start.elapsed();
         debug!("MCP tool returned after {:?}", elapsed);
 
+        // Pass images through to vision models, sized like any other attached image.
+        let images: Vec<ToolImageAttachment> = result
+            .content
+            .iter()
+            .flatten()
+            .filter_map(|content| match content {
+                MCPToolResultContent::Image { data, mime_type } => Some(ToolImageAttachment {
+                    mime_type: mime_type.clone(),
+                    data_base64: data.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+        let image_attachments = (!result.is_error && !images.is_empty())
+            .then(|| prepare_tool_image_attachments(images, &ImageLimits::default()));
+
         let result_value = serde_json::to_value(&result)?;
 
         let result_for_assistant = self.render_result_for_assistant(&result_value);
         Ok(vec![ToolResult::Result {
             data: result_value,
             result_for_assistant: Some(result_for_assistant),
-            image_attachments: None,
+            image_attachments,
         }])
     }
 }
@@ -47,7 +47,7 @@ pub(super) fn config_to_cursor_format(config: &MCPServerConfig) -> serde_json::V
     serde_json::Value::Object(cursor_config)
 }
 
-pub(super) fn parse_cursor_format(
+pub(crate) fn parse_cursor_format(
     config: &serde_json::Value,
 ) -> BitFunResult<Vec<MCPServerConfig>> {
     let mut servers = Vec::new();
@@ -5,5 +5,6 @@ mod json_config;
 mod location;
 mod service;
 
+pub(crate) use cursor_format::parse_cursor_format;
 pub use location::ConfigLocation;
 pub use service::MCPConfigService;
@@ -45,6 +45,7 @@ impl MCPService {
     ) -> crate::util::errors::BitFunResult<Self> {
         let mcp_config_service = std::sync::Arc::new(MCPConfigService::new(config_service)?);
         let server_manager = std::sync::Arc::new(MCPServerManager::new(mcp_config_service.clone()));
+        server_manager.watch_offline_mode();
         let context_provider = std::sync::Arc::new(MCPContextProvider::new(server_manager.clone()));
 
         Ok(Self {
@@ -72,20 +72,18 @@ impl MCPTransport {
         })?;
 
         let mut stdin = self.stdin.lock().await;
-        stdin
-            .write_all(json.as_bytes())
-            .await
-            .map_err(|e| BitFunError::io(format!("Failed to write to MCP server stdin: {}", e)))?;
+        stdin.write_all(json.as_bytes()).await.map_err(|e| {
+            BitFunError::mcp_connection(format!("Failed to write to MCP server stdin: {}", e))
+        })?;
         stdin.write_all(b"\n").await.map_err(|e| {
-            BitFunError::io(format!(
+            BitFunError::mcp_connection(format!(
                 "Failed to write newline to MCP server stdin: {}",
                 e
             ))
         })?;
-        stdin
-            .flush()
-            .await
-            .map_err(|e| BitFunError::io(format!("Failed to flush MCP server stdin: {}", e)))?;
+        stdin.flush().await.map_err(|e| {
+            BitFunError::mcp_connection(format!("Failed to flush MCP server stdin: {}", e))
+        })?;
 
         debug!("Sent MCP message: {}", json);
         Ok(())
@@ -8,6 +8,7 @@ use super::types::{
     MCPTool, MCPToolResult, MCPToolResultContent, PromptsGetResult, PromptsListResult,
     ResourcesListResult, ResourcesReadResult, ToolsListResult,
 };
+use crate::infrastructure::secrets;
 use crate::util::errors::{BitFunError, BitFunResult};
 use futures::StreamExt;
 use log::{debug, error, info, warn};
@@ -111,6 +112,22 @@ enum ClientState {
 #[derive(Clone)]
 struct BitFunStreamableHttpClient {
     client: reqwest::Client,
+    /// Headers whose values are `keyring:` references, resolved on every request.
+    secret_headers: StdArc<Vec<(HeaderName, String)>>,
+}
+
+impl BitFunStreamableHttpClient {
+    fn apply_secret_headers(
+        &self,
+        mut builder: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        for (name, value) in self.secret_headers.iter() {
+            if let Some(value) = RemoteMCPTransport::resolve_header_value(name, value) {
+                builder = builder.header(name.clone(), value);
+            }
+        }
+        builder
+    }
 }
 
 impl StreamableHttpClient for BitFunStreamableHttpClient {
@@ -131,6 +148,7 @@ impl StreamableHttpClient for BitFunStreamableHttpClient {
             .get(uri.as_ref())
             .header(ACCEPT, [EVENT_STREAM_MIME_TYPE, JSON_MIME_TYPE].join(", "))
             .header(HEADER_SESSION_ID, session_id.as_ref());
+        request_builder = self.apply_secret_headers(request_builder);
         if let Some(last_event_id) = last_event_id {
             request_builder = request_builder.header(HEADER_LAST_EVENT_ID, last_event_id);
         }
@@ -169,7 +187,7 @@ impl StreamableHttpClient for BitFunStreamableHttpClient {
         session: StdArc<str>,
         auth_token: Option<String>,
     ) -> Result<(), StreamableHttpError<Self::Error>> {
-        let mut request_builder = self.client.delete(uri.as_ref());
+        let mut request_builder = self.apply_secret_headers(self.client.delete(uri.as_ref()));
         if let Some(auth_header) = auth_token {
             request_builder = request_builder.bearer_auth(auth_header);
         }
@@ -196,6 +214,7 @@ impl StreamableHttpClient for BitFunStreamableHttpClient {
             .client
             .post(uri.as_ref())
             .header(ACCEPT, [EVENT_STREAM_MIME_TYPE, JSON_MIME_TYPE].join(", "));
+        request = self.apply_secret_headers(request);
         if let Some(auth_header) = auth_token {
             request = request.bearer_auth(auth_header);
         }
@@ -282,6 +301,7 @@ impl StreamableHttpClient for BitFunStreamableHttpClient {
 pub struct RemoteMCPTransport {
     url: String,
     default_headers: HeaderMap,
+    secret_headers: StdArc<Vec<(HeaderName, String)>>,
     request_timeout: Duration,
     state: Mutex<ClientState>,
 }
@@ -305,8 +325,36 @@ impl RemoteMCPTransport {
         Some(format!("Bearer {}", trimmed))
     }
 
-    fn build_default_headers(headers: &HashMap<String, String>) -> HeaderMap {
+    /// Resolves a header value from the secrets store, normalizing `Authorization`.
+    fn resolve_header_value(name: &HeaderName, value: &str) -> Option<HeaderValue> {
+        let value = secrets::resolve_secret_or_empty(value);
+        Self::header_value(name, &value)
+    }
+
+    fn header_value(name: &HeaderName, value: &str) -> Option<HeaderValue> {
+        let header_value_str = if *name == reqwest::header::AUTHORIZATION {
+            Self::normalize_authorization_value(value)?
+        } else {
+            value.trim().to_string()
+        };
+
+        let header_value = HeaderValue::from_str(&header_value_str);
+        if header_value.is_err() {
+            warn!(
+                "Invalid HTTP header value in MCP config (skipping): header={}",
+                name
+            );
+        }
+        header_value.ok()
+    }
+
+    /// Splits configured headers into static default headers and headers
+    /// whose values are secret references.
+    fn build_default_headers(
+        headers: &HashMap<String, String>,
+    ) -> (HeaderMap, Vec<(HeaderName, String)>) {
         let mut header_map = HeaderMap::new();
+        let mut secret_headers = Vec::new();
 
         for (name, value) in headers {
             let Ok(header_name) = HeaderName::from_str(name) else {
@@ -317,24 +365,14 @@ impl RemoteMCPTransport {
                 continue;
             };
 
-            let header_value_str = if header_name == reqwest::header::AUTHORIZATION {
-                match Self::normalize_authorization_value(value) {
-                    Some(v) => v,
-                    None => continue,
-                }
-            } else {
-                value.trim().to_string()
-            };
-
-            let Ok(header_value) = HeaderValue::from_str(&header_value_str) else {
-                warn!(
-                    "Invalid HTTP header value in MCP config (skipping): header={}",
-                    name
-                );
+            if secrets::parse_secret_ref(value).is_some() {
+                secret_headers.push((header_name, value.clone()));
                 continue;
-            };
+            }
 
-            header_map.insert(header_name, header_value);
+            if let Some(header_value) = Self::header_value(&header_name, value) {
+                header_map.insert(header_name, header_value);
+            }
         }
 
         if !header_map.contains_key(USER_AGENT) {
@@ -344,12 +382,13 @@ impl RemoteMCPTransport {
             );
         }
 
-        header_map
+        (header_map, secret_headers)
     }
 
     /// Creates a new streamable HTTP remote transport instance.
     pub fn new(url: String, headers: HashMap<String, String>, request_timeout: Duration) -> Self {
-        let default_headers = Self::build_default_headers(&headers);
+        let (default_headers, secret_headers) = Self::build_default_headers(&headers);
+        let secret_headers = StdArc::new(secret_headers);
 
         let http_client = reqwest::Client::builder()
             .connect_timeout(Duration::from_secs(10))
@@ -365,6 +404,7 @@ impl RemoteMCPTransport {
         let transport = StreamableHttpClientTransport::with_client(
             BitFunStreamableHttpClient {
                 client: http_client,
+                secret_headers: secret_headers.clone(),
             },
             StreamableHttpClientTransportConfig::with_uri(url.clone()),
         );
@@ -372,6 +412,7 @@ impl RemoteMCPTransport {
         Self {
             url,
             default_headers,
+            secret_headers,
             request_timeout,
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(transport),
@@ -381,8 +422,14 @@ impl RemoteMCPTransport {
 
     /// Returns the auth token header value (if present).
     pub fn get_auth_token(&self) -> Option<String> {
-        self.default_headers
-            .get(reqwest::header::AUTHORIZATION)
+        let secret = self
+            .secret_headers
+            .iter()
+            .find(|(name, _)| *name == reqwest::header::AUTHORIZATION)
+            .and_then(|(name, value)| Self::resolve_header_value(name, value));
+        secret
+            .as_ref()
+            .or_else(|| self.default_headers.get(reqwest::header::AUTHORIZATION))
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string())
     }
@@ -393,8 +440,8 @@ impl RemoteMCPTransport {
         let guard = self.state.lock().await;
         match &*guard {
             ClientState::Ready { service } => Ok(Arc::clone(service)),
-            ClientState::Connecting { .. } => Err(BitFunError::MCPError(
-                "Remote MCP client not initialized".to_string(),
+            ClientState::Connecting { .. } => Err(BitFunError::mcp_connection(
+                "Remote MCP client not initialized",
             )),
         }
     }
@@ -449,7 +496,7 @@ impl RemoteMCPTransport {
                             self.request_timeout, self.url
                         ))
                     })?
-                    .map_err(|e| BitFunError::MCPError(format!("Handshake failed: {}", e)))?;
+                    .map_err(|e| BitFunError::mcp(format!("Handshake failed: {}", e)))?;
 
                 let service = Arc::new(service);
                 let info = service.peer().peer_info().ok_or_else(|| {
@@ -475,7 +522,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP ping timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP ping failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP ping failed: {}", e)))?;
 
         match result {
             rmcp::model::ServerResult::EmptyResult(_) => Ok(()),
@@ -497,7 +544,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP resources/list timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP resources/list failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP resources/list failed: {}", e)))?;
         Ok(ResourcesListResult {
             resources: result.resources.into_iter().map(map_resource).collect(),
             next_cursor: result.next_cursor,
@@ -512,7 +559,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP resources/read timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP resources/read failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP resources/read failed: {}", e)))?;
         Ok(ResourcesReadResult {
             contents: result
                 .contents
@@ -530,7 +577,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP prompts/list timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP prompts/list failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP prompts/list failed: {}", e)))?;
         Ok(PromptsListResult {
             prompts: result.prompts.into_iter().map(map_prompt).collect(),
             next_cursor: result.next_cursor,
@@ -559,7 +606,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP prompts/get timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP prompts/get failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP prompts/get failed: {}", e)))?;
 
         Ok(PromptsGetResult {
             description: result.description,
@@ -579,7 +626,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP tools/list timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP tools/list failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP tools/list failed: {}", e)))?;
 
         Ok(ToolsListResult {
             tools: result.tools.into_iter().map(map_tool).collect(),
@@ -612,7 +659,7 @@ impl RemoteMCPTransport {
         let result = tokio::time::timeout(self.request_timeout, fut)
             .await
             .map_err(|_| BitFunError::Timeout("MCP tools/call timeout".to_string()))?
-            .map_err(|e| BitFunError::MCPError(format!("MCP tools/call failed: {}", e)))?;
+            .map_err(|e| BitFunError::mcp(format!("MCP tools/call failed: {}", e)))?;
 
         Ok(map_tool_result(result))
     }
@@ -124,7 +124,7 @@ impl MCPConnection {
 
                 match tokio::time::timeout(self.request_timeout, rx).await {
                     Ok(Ok(response)) => Ok(response),
-                    Ok(Err(_)) => Err(BitFunError::MCPError(format!(
+                    Ok(Err(_)) => Err(BitFunError::mcp_connection(format!(
                         "Request channel closed for method: {}",
                         method
                     ))),
@@ -4,6 +4,8 @@
 
 use super::connection::{MCPConnection, MCPConnectionPool};
 use super::{MCPServerConfig, MCPServerRegistry, MCPServerStatus};
+use crate::infrastructure::offline;
+use crate::infrastructure::secrets::resolve_secret_map;
 use crate::service::mcp::adapter::tool::MCPToolAdapter;
 use crate::service::mcp::config::MCPConfigService;
 use crate::service::runtime::{RuntimeManager, RuntimeSource};
@@ -240,7 +242,11 @@ impl MCPServerManager {
                     resolved.command, source_label, server_id
                 );
 
-                proc.start(&resolved.command, &config.args, &config.env)
+                proc.start(
+                    &resolved.command,
+                    &config.args,
+                    &resolve_secret_map(&config.env),
+                )
                     .await
                     .map_err(|e| {
                         error!(
@@ -256,6 +262,15 @@ impl MCPServerManager {
                     BitFunError::Configuration("Missing URL for remote MCP server".to_string())
                 })?;
 
+                if let Err(e) = offline::check_endpoint(url) {
+                    info!(
+                        "Not connecting to remote MCP server while offline: url={} id={}",
+                        url, server_id
+                    );
+                    proc.mark_offline().await;
+                    return Err(e);
+                }
+
                 info!(
                     "Connecting to remote MCP server: url={} id={}",
                     url, server_id
@@ -353,7 +368,8 @@ impl MCPServerManager {
                     .command
                     .as_ref()
                     .ok_or_else(|| BitFunError::Configuration("Missing command".to_string()))?;
-                proc.restart(command, &config.args, &config.env).await?;
+                proc.restart(command, &config.args, &resolve_secret_map(&config.env))
+                    .await?;
             }
             super::MCPServerType::Remote => {
                 // Treat restart as reconnect for remote servers.
@@ -371,6 +387,76 @@ impl MCPServerManager {
         Ok(())
     }
 
+    /// Disconnects remote servers when offline mode turns on and reconnects
+    /// them when it turns off, so unreachable servers are not retried meanwhile.
+    pub fn watch_offline_mode(self: &Arc<Self>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let manager = Arc::downgrade(self);
+        let mut status = offline::subscribe();
+        runtime.spawn(async move {
+            while status.changed().await.is_ok() {
+                let offline = status.borrow_and_update().is_some();
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.apply_offline_mode(offline).await;
+            }
+        });
+    }
+
+    async fn apply_offline_mode(&self, offline: bool) {
+        for server_id in self.registry.get_all_server_ids().await {
+            let Ok(Some(config)) = self.config_service.get_server_config(&server_id).await else {
+                continue;
+            };
+            let needs_network = config.server_type == super::MCPServerType::Remote
+                && !config
+                    .url
+                    .as_deref()
+                    .is_some_and(offline::is_local_endpoint);
+            if !needs_network {
+                continue;
+            }
+            let Ok(status) = self.get_server_status(&server_id).await else {
+                continue;
+            };
+
+            if offline
+                && matches!(
+                    status,
+                    MCPServerStatus::Starting
+                        | MCPServerStatus::Connected
+                        | MCPServerStatus::Healthy
+                        | MCPServerStatus::Reconnecting
+                )
+            {
+                info!(
+                    "Disconnecting remote MCP server while offline: id={}",
+                    server_id
+                );
+                if let Err(e) = self.stop_server(&server_id).await {
+                    warn!(
+                        "Failed to stop remote MCP server: id={} error={}",
+                        server_id, e
+                    );
+                }
+                if let Some(process) = self.registry.get_process(&server_id).await {
+                    process.read().await.mark_offline().await;
+                }
+            } else if !offline && status == MCPServerStatus::Offline {
+                info!("Reconnecting remote MCP server: id={}", server_id);
+                if let Err(e) = self.start_server(&server_id).await {
+                    warn!(
+                        "Failed to reconnect remote MCP server: id={} error={}",
+                        server_id, e
+                    );
+                }
+            }
+        }
+    }
+
     /// Returns server status.
     pub async fn get_server_status(&self, server_id: &str) -> BitFunResult<MCPServerStatus> {
         if !self.registry.contains(server_id).await {
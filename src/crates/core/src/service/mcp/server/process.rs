@@ -32,6 +32,7 @@ pub enum MCPServerStatus {
     Failed,        // Failed
     Stopping,      // Stopping
     Stopped,       // Stopped
+    Offline,       // Remote server not connected while offline mode is on
 }
 
 /// MCP server process.
@@ -228,7 +229,7 @@ impl MCPServerProcess {
         let connection = self
             .connection
             .as_ref()
-            .ok_or_else(|| BitFunError::MCPError("Connection not established".to_string()))?;
+            .ok_or_else(|| BitFunError::mcp_connection("Connection not established"))?;
 
         debug!(
             "Initiating handshake with MCP server: name={} id={}",
@@ -310,6 +311,11 @@ impl MCPServerProcess {
         *current_status = status;
     }
 
+    /// Marks a remote server as skipped until offline mode turns off.
+    pub async fn mark_offline(&self) {
+        self.set_status(MCPServerStatus::Offline).await;
+    }
+
     /// Gets status.
     pub async fn status(&self) -> MCPServerStatus {
         *self.status.read().await
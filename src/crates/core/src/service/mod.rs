@@ -6,6 +6,7 @@ pub(crate) mod agent_memory; // Agent memory prompt helpers
 pub mod ai_memory; // AI memory point management
 pub mod ai_rules; // AI rules management
 pub(crate) mod bootstrap; // Workspace persona bootstrap helpers
+pub mod code_index; // Workspace code index
 pub mod config; // Config management
 pub mod cron; // Scheduled jobs
 pub mod diff;
@@ -8,7 +8,7 @@ use super::types::{
     CategoryInfo, ContextDocumentStatus, ContextSegment, CustomCategory, DocumentPriority,
     FileConflictAction, ImportedDocument, ProjectContextConfig,
 };
-use crate::agentic::coordination::get_global_coordinator;
+use crate::agentic::coordination::{get_global_coordinator, SubagentOptions};
 use crate::agentic::tools::pipeline::SubagentParentInfo;
 use crate::util::errors::{BitFunError, BitFunResult};
 use log::{debug, warn};
@@ -274,6 +274,7 @@ impl ProjectContextService {
             tool_call_id: format!("generate-doc-{}", doc_id),
             session_id: format!("standalone-generate-{}", uuid::Uuid::new_v4()),
             dialog_turn_id: format!("turn-{}", uuid::Uuid::new_v4()),
+            model_id: None,
         };
 
         let result = coordinator
@@ -284,6 +285,7 @@ impl ProjectContextService {
                 Some(workspace.to_string_lossy().into_owned()),
                 None,
                 Some(&cancel_token),
+                SubagentOptions::default(),
             )
             .await;
 
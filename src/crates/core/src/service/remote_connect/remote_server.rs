@@ -286,14 +286,17 @@ pub enum RemoteCommand {
         session_id: String,
     },
     ConfirmTool {
+        session_id: String,
         tool_id: String,
         updated_input: Option<serde_json::Value>,
     },
     RejectTool {
+        session_id: String,
         tool_id: String,
         reason: Option<String>,
     },
     CancelTool {
+        session_id: String,
         tool_id: String,
         reason: Option<String>,
     },
@@ -1213,7 +1216,7 @@ impl RemoteSessionStateTracker {
     fn handle_event(&self, event: &crate::agentic::events::AgenticEvent) {
         use bitfun_events::AgenticEvent as AE;
 
-        let is_direct = event.session_id() == Some(self.target_session_id.as_str());
+        let is_direct = event.session_id() == self.target_session_id;
         let is_subagent = if !is_direct {
             match event {
                 AE::TextChunk {
@@ -2836,6 +2839,7 @@ impl RemoteServer {
                 Err(e) => RemoteResponse::Error { message: e },
             },
             RemoteCommand::ConfirmTool {
+                session_id,
                 tool_id,
                 updated_input,
             } => {
@@ -2848,7 +2852,7 @@ impl RemoteServer {
                     }
                 };
                 match coordinator
-                    .confirm_tool(tool_id, updated_input.clone())
+                    .confirm_tool(session_id, tool_id, updated_input.clone())
                     .await
                 {
                     Ok(_) => RemoteResponse::InteractionAccepted {
@@ -2860,7 +2864,11 @@ impl RemoteServer {
                     },
                 }
             }
-            RemoteCommand::RejectTool { tool_id, reason } => {
+            RemoteCommand::RejectTool {
+                session_id,
+                tool_id,
+                reason,
+            } => {
                 let coordinator = match get_global_coordinator() {
                     Some(c) => c,
                     None => {
@@ -2872,7 +2880,10 @@ impl RemoteServer {
                 let reject_reason = reason
                     .clone()
                     .unwrap_or_else(|| "User rejected".to_string());
-                match coordinator.reject_tool(tool_id, reject_reason).await {
+                match coordinator
+                    .reject_tool(session_id, tool_id, reject_reason)
+                    .await
+                {
                     Ok(_) => RemoteResponse::InteractionAccepted {
                         action: "reject_tool".to_string(),
                         target_id: tool_id.clone(),
@@ -2882,7 +2893,11 @@ impl RemoteServer {
                     },
                 }
             }
-            RemoteCommand::CancelTool { tool_id, reason } => {
+            RemoteCommand::CancelTool {
+                session_id,
+                tool_id,
+                reason,
+            } => {
                 let coordinator = match get_global_coordinator() {
                     Some(c) => c,
                     None => {
@@ -2894,7 +2909,10 @@ impl RemoteServer {
                 let cancel_reason = reason
                     .clone()
                     .unwrap_or_else(|| "User cancelled".to_string());
-                match coordinator.cancel_tool(tool_id, cancel_reason).await {
+                match coordinator
+                    .cancel_tool(session_id, tool_id, cancel_reason)
+                    .await
+                {
                     Ok(_) => RemoteResponse::InteractionAccepted {
                         action: "cancel_tool".to_string(),
                         target_id: tool_id.clone(),
@@ -1,5 +1,6 @@
 //! Types for session persistence
 
+use crate::agentic::core::TurnBudgetKind;
 use serde::{Deserialize, Serialize};
 
 /// Session metadata
@@ -151,6 +152,23 @@ pub struct DialogTurnData {
 
     /// Turn status
     pub status: TurnStatus,
+
+    /// Budget that ended the turn with a summary round
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_at_budget: Option<TurnBudgetKind>,
+
+    /// Three-bullet AI summary, added after the turn completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<TurnSummary>,
+}
+
+/// What a dialog turn did, what it changed and what comes next
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnSummary {
+    pub did: String,
+    pub changed: String,
+    pub next: String,
 }
 
 /// User message data
@@ -493,6 +511,8 @@ impl DialogTurnData {
             end_time: None,
             duration_ms: None,
             status: TurnStatus::InProgress,
+            stopped_at_budget: None,
+            summary: None,
         }
     }
 
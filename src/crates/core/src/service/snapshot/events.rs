@@ -3,12 +3,19 @@
 //! Defines all event types for the snapshot/operation history system, for real-time push to the frontend.
 
 use crate::infrastructure::events::EventEmitter;
+use crate::service::snapshot::workspace_snapshot::{
+    SnapshotComparison, WorkspaceSnapshotInfo, WorkspaceSnapshotTrigger,
+};
+use bitfun_transport::SnapshotEventPayload;
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::SystemTime;
 
+/// Frontend event carrying a `SnapshotEventPayload`
+pub const SNAPSHOT_EVENT: &str = "snapshot-event";
+
 /// Snapshot event type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
@@ -84,6 +91,26 @@ pub enum SnapshotEvent {
         timestamp: u64,
     },
 
+    /// Workspace snapshot captured
+    WorkspaceSnapshotCreated {
+        snapshot_id: String,
+        session_id: Option<String>,
+        trigger: WorkspaceSnapshotTrigger,
+        file_count: usize,
+        timestamp: u64,
+    },
+
+    /// Two workspace snapshots compared
+    WorkspaceSnapshotsCompared {
+        session_id: Option<String>,
+        from_snapshot_id: String,
+        to_snapshot_id: String,
+        added: usize,
+        removed: usize,
+        modified: usize,
+        timestamp: u64,
+    },
+
     /// Error event
     Error {
         session_id: Option<String>,
@@ -105,6 +132,8 @@ impl SnapshotEvent {
             Self::DialogTurnCompleted { session_id, .. } => Some(session_id),
             Self::SessionRolledBack { session_id, .. } => Some(session_id),
             Self::DiffStateUpdated { session_id, .. } => Some(session_id),
+            Self::WorkspaceSnapshotCreated { session_id, .. } => session_id.as_deref(),
+            Self::WorkspaceSnapshotsCompared { session_id, .. } => session_id.as_deref(),
             Self::Error { session_id, .. } => session_id.as_deref(),
         }
     }
@@ -120,6 +149,8 @@ impl SnapshotEvent {
             Self::DialogTurnCompleted { timestamp, .. } => *timestamp,
             Self::SessionRolledBack { timestamp, .. } => *timestamp,
             Self::DiffStateUpdated { timestamp, .. } => *timestamp,
+            Self::WorkspaceSnapshotCreated { timestamp, .. } => *timestamp,
+            Self::WorkspaceSnapshotsCompared { timestamp, .. } => *timestamp,
             Self::Error { timestamp, .. } => *timestamp,
         }
     }
@@ -228,6 +259,33 @@ impl SnapshotEvent {
             timestamp: Self::current_timestamp(),
         }
     }
+
+    /// Creates a workspace snapshot created event.
+    pub fn workspace_snapshot_created(snapshot: &WorkspaceSnapshotInfo) -> Self {
+        Self::WorkspaceSnapshotCreated {
+            snapshot_id: snapshot.snapshot_id.clone(),
+            session_id: snapshot.session_id.clone(),
+            trigger: snapshot.trigger,
+            file_count: snapshot.file_count,
+            timestamp: Self::current_timestamp(),
+        }
+    }
+
+    /// Creates a workspace snapshots compared event.
+    pub fn workspace_snapshots_compared(
+        session_id: Option<String>,
+        comparison: &SnapshotComparison,
+    ) -> Self {
+        Self::WorkspaceSnapshotsCompared {
+            session_id,
+            from_snapshot_id: comparison.from_snapshot_id.clone(),
+            to_snapshot_id: comparison.to_snapshot_id.clone(),
+            added: comparison.added,
+            removed: comparison.removed,
+            modified: comparison.modified,
+            timestamp: Self::current_timestamp(),
+        }
+    }
 }
 
 /// Snapshot event emitter trait
@@ -238,6 +296,13 @@ pub trait SnapshotEventEmitter: Send + Sync {
 
     /// Emits an event to a specific session.
     async fn emit_to_session(&self, session_id: &str, event: SnapshotEvent) -> Result<(), String>;
+
+    /// Emits an event about a workspace snapshot.
+    async fn emit_for_snapshot(
+        &self,
+        snapshot_id: &str,
+        event: SnapshotEvent,
+    ) -> Result<(), String>;
 }
 
 /// Snapshot emitter adapter implementation - uses the generic `EventEmitter`.
@@ -299,6 +364,35 @@ impl SnapshotEventEmitter for SnapshotEmitterAdapter {
         }
         Ok(())
     }
+
+    async fn emit_for_snapshot(
+        &self,
+        snapshot_id: &str,
+        event: SnapshotEvent,
+    ) -> Result<(), String> {
+        if let Some(ref emitter) = self.emitter {
+            let payload = SnapshotEventPayload {
+                snapshot_id: snapshot_id.to_string(),
+                event_data: serde_json::to_value(&event)
+                    .map_err(|e| format!("Failed to serialize event: {}", e))?,
+            };
+            let payload = serde_json::to_value(payload)
+                .map_err(|e| format!("Failed to serialize event: {}", e))?;
+
+            emitter
+                .emit(SNAPSHOT_EVENT, payload)
+                .await
+                .map_err(|e| format!("Failed to emit event: {}", e))?;
+
+            debug!(
+                "Emitted workspace snapshot event: snapshot_id={} event_type={:?}",
+                snapshot_id, event
+            );
+        } else {
+            debug!("EventEmitter not configured, skipping event emission");
+        }
+        Ok(())
+    }
 }
 
 /// Global event emitter
@@ -328,6 +422,14 @@ pub async fn emit_snapshot_event(event: SnapshotEvent) {
     }
 }
 
+/// Helper: emits an event about a workspace snapshot.
+pub async fn emit_workspace_snapshot_event(snapshot_id: &str, event: SnapshotEvent) {
+    if let Some(emitter) = get_event_emitter() {
+        let e = emitter.read().await;
+        let _ = e.emit_for_snapshot(snapshot_id, event).await;
+    }
+}
+
 /// Helper: emits a session-scoped event.
 pub async fn emit_snapshot_session_event(session_id: &str, event: SnapshotEvent) {
     if let Some(emitter) = get_event_emitter() {
@@ -1,10 +1,14 @@
 use crate::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
 use crate::agentic::tools::registry::ToolRegistry;
+use crate::agentic::WorkspaceBinding;
 use crate::service::remote_ssh::workspace_state::is_remote_path;
 use crate::service::snapshot::service::SnapshotService;
 use crate::service::snapshot::types::{
     OperationType, SnapshotConfig, SnapshotError, SnapshotResult,
 };
+use crate::service::snapshot::workspace_snapshot::{
+    SnapshotComparison, WorkspaceSnapshotInfo, WorkspaceSnapshotTrigger,
+};
 use async_trait::async_trait;
 use log::{debug, error, info, warn};
 use serde_json::Value;
@@ -277,6 +281,50 @@ impl SnapshotManager {
         snapshot_service.get_all_modified_files().await
     }
 
+    /// Captures the files of the workspace.
+    pub async fn capture_workspace_snapshot(
+        &self,
+        trigger: WorkspaceSnapshotTrigger,
+        session_id: Option<&str>,
+    ) -> SnapshotResult<WorkspaceSnapshotInfo> {
+        let snapshot_service = self.snapshot_service.read().await;
+        snapshot_service
+            .capture_workspace_snapshot(trigger, session_id)
+            .await
+    }
+
+    /// Returns the workspace snapshots, newest first.
+    pub async fn list_workspace_snapshots(&self) -> SnapshotResult<Vec<WorkspaceSnapshotInfo>> {
+        let snapshot_service = self.snapshot_service.read().await;
+        snapshot_service.list_workspace_snapshots().await
+    }
+
+    /// Deletes a workspace snapshot.
+    pub async fn delete_workspace_snapshot(&self, snapshot_id: &str) -> SnapshotResult<()> {
+        let snapshot_service = self.snapshot_service.read().await;
+        snapshot_service
+            .delete_workspace_snapshot(snapshot_id)
+            .await
+    }
+
+    /// Compares two workspace snapshots.
+    pub async fn compare_snapshots(
+        &self,
+        from_snapshot_id: &str,
+        to_snapshot_id: &str,
+    ) -> SnapshotResult<SnapshotComparison> {
+        let snapshot_service = self.snapshot_service.read().await;
+        snapshot_service
+            .compare_snapshots(from_snapshot_id, to_snapshot_id)
+            .await
+    }
+
+    /// Whether changes should be checkpointed automatically.
+    pub async fn is_checkpointing_enabled(&self) -> bool {
+        let snapshot_service = self.snapshot_service.read().await;
+        snapshot_service.get_config().auto_snapshot_enabled
+    }
+
     /// Returns a reference to the snapshot service (for advanced operations).
     pub fn get_snapshot_service(&self) -> Arc<RwLock<SnapshotService>> {
         self.snapshot_service.clone()
@@ -372,6 +420,16 @@ impl Tool for WrappedTool {
         self.original_tool.needs_permissions(input)
     }
 
+    async fn requires_confirmation(
+        &self,
+        input: &Value,
+        workspace: Option<&WorkspaceBinding>,
+    ) -> bool {
+        self.original_tool
+            .requires_confirmation(input, workspace)
+            .await
+    }
+
     async fn validate_input(
         &self,
         input: &Value,
@@ -636,6 +694,59 @@ pub fn ensure_snapshot_manager_for_workspace(
     })
 }
 
+/// Captures a workspace snapshot before an agent run, when the workspace has a snapshot
+/// manager with checkpointing on; returns the snapshot ID to compare against afterwards.
+pub async fn snapshot_workspace_before_run(
+    workspace_dir: &Path,
+    session_id: &str,
+    trigger: WorkspaceSnapshotTrigger,
+) -> Option<String> {
+    let manager = get_snapshot_manager_for_workspace(workspace_dir)?;
+    if !manager.is_checkpointing_enabled().await {
+        return None;
+    }
+    match manager
+        .capture_workspace_snapshot(trigger, Some(session_id))
+        .await
+    {
+        Ok(snapshot) => Some(snapshot.snapshot_id),
+        Err(e) => {
+            warn!(
+                "Failed to snapshot workspace before run: session_id={} error={}",
+                session_id, e
+            );
+            None
+        }
+    }
+}
+
+/// Captures the workspace after an agent run and compares it with the snapshot taken
+/// before, which announces what changed during the run.
+pub async fn snapshot_workspace_after_run(
+    workspace_dir: &Path,
+    session_id: &str,
+    before_snapshot_id: &str,
+) {
+    let Some(manager) = get_snapshot_manager_for_workspace(workspace_dir) else {
+        return;
+    };
+    let compared = async {
+        let after = manager
+            .capture_workspace_snapshot(WorkspaceSnapshotTrigger::RunEnd, Some(session_id))
+            .await?;
+        manager
+            .compare_snapshots(before_snapshot_id, &after.snapshot_id)
+            .await
+    }
+    .await;
+    if let Err(e) = compared {
+        warn!(
+            "Failed to compare workspace after run: session_id={} error={}",
+            session_id, e
+        );
+    }
+}
+
 /// Initializes a snapshot manager for the provided workspace.
 pub async fn initialize_snapshot_manager_for_workspace(
     workspace_dir: PathBuf,
@@ -6,16 +6,22 @@ pub mod service;
 pub mod snapshot_core;
 pub mod snapshot_system;
 pub mod types;
+pub mod workspace_snapshot;
 
 pub use events::{
-    emit_snapshot_event, emit_snapshot_session_event, initialize_snapshot_event_emitter,
-    SnapshotEvent, SnapshotEventEmitter,
+    emit_snapshot_event, emit_snapshot_session_event, emit_workspace_snapshot_event,
+    initialize_snapshot_event_emitter, SnapshotEvent, SnapshotEventEmitter,
 };
 pub use manager::{
     ensure_snapshot_manager_for_workspace, get_or_create_snapshot_manager,
     get_snapshot_manager_for_workspace, get_snapshot_wrapped_tools,
-    initialize_snapshot_manager_for_workspace, wrap_tool_for_snapshot_tracking, SnapshotManager,
+    initialize_snapshot_manager_for_workspace, snapshot_workspace_after_run,
+    snapshot_workspace_before_run, wrap_tool_for_snapshot_tracking, SnapshotManager,
 };
 pub use service::{SnapshotService, SystemStats};
 pub use snapshot_core::{FileChangeEntry, FileChangeQueue, SessionStats, SnapshotCore};
 pub use types::*;
+pub use workspace_snapshot::{
+    FileChangeKind, FileComparison, SnapshotComparison, WorkspaceSnapshotInfo,
+    WorkspaceSnapshotTrigger,
+};
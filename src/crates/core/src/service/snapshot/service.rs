@@ -1,4 +1,6 @@
-use crate::service::snapshot::events::{emit_snapshot_session_event, SnapshotEvent};
+use crate::service::snapshot::events::{
+    emit_snapshot_session_event, emit_workspace_snapshot_event, SnapshotEvent,
+};
 use crate::service::snapshot::file_lock_manager::FileLockManager;
 use crate::service::snapshot::isolation_manager::IsolationManager;
 use crate::service::snapshot::snapshot_core::{SessionStats, SnapshotCore};
@@ -6,7 +8,12 @@ use crate::service::snapshot::snapshot_system::FileSnapshotSystem;
 use crate::service::snapshot::types::{
     OperationType, SessionInfo, SnapshotConfig, SnapshotError, SnapshotResult,
 };
-use log::info;
+use crate::service::snapshot::workspace_snapshot::{
+    PinnedContent, SnapshotComparison, WorkspaceSnapshotInfo, WorkspaceSnapshotStore,
+    WorkspaceSnapshotTrigger, MAX_WORKSPACE_SNAPSHOTS,
+};
+use log::{info, warn};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -17,6 +24,7 @@ pub struct SnapshotService {
     isolation_manager: Arc<RwLock<IsolationManager>>,
     file_lock_manager: Arc<FileLockManager>,
     snapshot_core: Arc<RwLock<SnapshotCore>>,
+    workspace_snapshots: WorkspaceSnapshotStore,
     workspace_dir: PathBuf,
     bitfun_dir: PathBuf,
     initialized: bool,
@@ -28,8 +36,11 @@ impl SnapshotService {
         let bitfun_dir = workspace_dir.join(".bitfun");
 
         let isolation_manager = Arc::new(RwLock::new(IsolationManager::new(workspace_dir.clone())));
-        let snapshot_system = FileSnapshotSystem::new(&bitfun_dir);
+        let pinned_content = PinnedContent::default();
+        let snapshot_system = FileSnapshotSystem::new(&bitfun_dir, pinned_content.clone());
         let snapshot_core = Arc::new(RwLock::new(SnapshotCore::new(&bitfun_dir, snapshot_system)));
+        let workspace_snapshots =
+            WorkspaceSnapshotStore::new(workspace_dir.clone(), &bitfun_dir, pinned_content);
         let file_lock_manager = Arc::new(FileLockManager::new(bitfun_dir.clone()));
 
         Self {
@@ -37,6 +48,7 @@ impl SnapshotService {
             isolation_manager,
             file_lock_manager,
             snapshot_core,
+            workspace_snapshots,
             workspace_dir,
             bitfun_dir,
             initialized: false,
@@ -60,6 +72,7 @@ impl SnapshotService {
             snapshot_core.initialize().await?;
         }
 
+        self.workspace_snapshots.initialize().await?;
         self.file_lock_manager.initialize().await?;
         self.initialized = true;
 
@@ -374,10 +387,92 @@ impl SnapshotService {
 
     pub async fn cleanup_snapshot_data(&self, keep_recent_days: u64) -> SnapshotResult<()> {
         self.ensure_initialized().await?;
-        let isolation_manager = self.isolation_manager.read().await;
-        isolation_manager
-            .cleanup_snapshot_data(keep_recent_days)
+        {
+            let isolation_manager = self.isolation_manager.read().await;
+            isolation_manager
+                .cleanup_snapshot_data(keep_recent_days)
+                .await?;
+        }
+
+        // Expired workspace snapshot manifests went with the checkpoint data
+        let released = self.workspace_snapshots.refresh().await?;
+        self.release_content(released).await;
+        Ok(())
+    }
+
+    /// Captures the files of the workspace; the oldest workspace snapshots beyond
+    /// `MAX_WORKSPACE_SNAPSHOTS` go.
+    pub async fn capture_workspace_snapshot(
+        &self,
+        trigger: WorkspaceSnapshotTrigger,
+        session_id: Option<&str>,
+    ) -> SnapshotResult<WorkspaceSnapshotInfo> {
+        self.ensure_initialized().await?;
+        let snapshot = self
+            .workspace_snapshots
+            .capture(trigger, session_id.map(str::to_string))
+            .await?;
+
+        match self
+            .workspace_snapshots
+            .prune(MAX_WORKSPACE_SNAPSHOTS)
             .await
+        {
+            Ok(released) => self.release_content(released).await,
+            Err(e) => warn!("Failed to prune workspace snapshots: error={}", e),
+        }
+
+        emit_workspace_snapshot_event(
+            &snapshot.snapshot_id,
+            SnapshotEvent::workspace_snapshot_created(&snapshot),
+        )
+        .await;
+        Ok(snapshot)
+    }
+
+    /// Workspace snapshots, newest first.
+    pub async fn list_workspace_snapshots(&self) -> SnapshotResult<Vec<WorkspaceSnapshotInfo>> {
+        self.ensure_initialized().await?;
+        self.workspace_snapshots.list().await
+    }
+
+    pub async fn delete_workspace_snapshot(&self, snapshot_id: &str) -> SnapshotResult<()> {
+        self.ensure_initialized().await?;
+        let released = self.workspace_snapshots.delete(snapshot_id).await?;
+        self.release_content(released).await;
+        Ok(())
+    }
+
+    /// Files added, removed and modified between two workspace snapshots, with
+    /// size-capped diffs.
+    pub async fn compare_snapshots(
+        &self,
+        from_snapshot_id: &str,
+        to_snapshot_id: &str,
+    ) -> SnapshotResult<SnapshotComparison> {
+        self.ensure_initialized().await?;
+        let from = self.workspace_snapshots.load(from_snapshot_id).await?;
+        let to = self.workspace_snapshots.load(to_snapshot_id).await?;
+        let session_id = to.session_id.clone();
+        let comparison = self.workspace_snapshots.compare(from, to).await?;
+
+        emit_workspace_snapshot_event(
+            to_snapshot_id,
+            SnapshotEvent::workspace_snapshots_compared(session_id, &comparison),
+        )
+        .await;
+        Ok(comparison)
+    }
+
+    /// Deletes content workspace snapshots let go of, unless file snapshots still use it.
+    async fn release_content(&self, content_hashes: HashSet<String>) {
+        if content_hashes.is_empty() {
+            return;
+        }
+        let mut snapshot_core = self.snapshot_core.write().await;
+        if let Err(e) = snapshot_core.release_content(&content_hashes) {
+            warn!("Failed to release snapshot content: error={}", e);
+        }
     }
 
     pub async fn get_file_change_history(
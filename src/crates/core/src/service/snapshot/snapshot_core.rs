@@ -383,6 +383,11 @@ impl SnapshotCore {
         self.snapshot_system.get_snapshot_content(snapshot_id).await
     }
 
+    /// Deletes stored content of `content_hashes` that nothing references anymore.
+    pub fn release_content(&mut self, content_hashes: &HashSet<String>) -> SnapshotResult<usize> {
+        self.snapshot_system.release_content(content_hashes)
+    }
+
     /// Returns the baseline snapshot ID for a file.
     pub async fn get_baseline_snapshot_id(&self, file_path: &Path) -> Option<String> {
         self.snapshot_system
@@ -2,8 +2,9 @@ use crate::service::snapshot::types::{
     FileMetadata, FileSnapshot, OptimizedContent, SnapshotError, SnapshotResult, SnapshotType,
     StorageStats,
 };
+use crate::service::snapshot::workspace_snapshot::PinnedContent;
 use log::{debug, error, info, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -216,11 +217,13 @@ pub struct FileSnapshotSystem {
     compression_enabled: bool,
     dedup_enabled: bool,
     baseline_cache: BaselineCache,
+    /// Content that workspace snapshots still reference, kept when no file snapshot uses it
+    pinned_content: PinnedContent,
 }
 
 impl FileSnapshotSystem {
     /// Creates a new file snapshot system.
-    pub fn new(bitfun_dir: &Path) -> Self {
+    pub fn new(bitfun_dir: &Path, pinned_content: PinnedContent) -> Self {
         let snapshot_dir = bitfun_dir.join("snapshots");
 
         Self {
@@ -230,6 +233,7 @@ impl FileSnapshotSystem {
             compression_enabled: true,
             dedup_enabled: true,
             baseline_cache: BaselineCache::new(bitfun_dir),
+            pinned_content,
         }
     }
 
@@ -430,7 +434,7 @@ impl FileSnapshotSystem {
 
     /// Computes content hash.
     fn calculate_content_hash(&self, content: &[u8]) -> String {
-        format!("{:x}", md5::compute(content))
+        content_hash(content)
     }
 
     /// Optimizes content storage.
@@ -460,24 +464,12 @@ impl FileSnapshotSystem {
 
     /// Compresses content.
     fn compress_content(&self, content: &[u8]) -> Result<Vec<u8>, std::io::Error> {
-        use flate2::write::GzEncoder;
-        use flate2::Compression;
-        use std::io::Write;
-
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(content)?;
-        encoder.finish()
+        compress_content(content)
     }
 
     /// Decompresses content.
     fn decompress_content(&self, compressed: &[u8]) -> Result<Vec<u8>, std::io::Error> {
-        use flate2::read::GzDecoder;
-        use std::io::Read;
-
-        let mut decoder = GzDecoder::new(compressed);
-        let mut decompressed = Vec::new();
-        decoder.read_to_end(&mut decompressed)?;
-        Ok(decompressed)
+        decompress_content(compressed)
     }
 
     /// Stores a snapshot.
@@ -658,7 +650,8 @@ impl FileSnapshotSystem {
         let content_still_used = self
             .active_snapshots
             .values()
-            .any(|s| s.content_hash == snapshot.content_hash);
+            .any(|s| s.content_hash == snapshot.content_hash)
+            || self.is_pinned(&snapshot.content_hash);
 
         if !content_still_used {
             let content_path = self.get_content_path(&snapshot.content_hash);
@@ -752,7 +745,8 @@ impl FileSnapshotSystem {
                 let is_referenced = self
                     .active_snapshots
                     .values()
-                    .any(|snapshot| snapshot.content_hash == content_hash);
+                    .any(|snapshot| snapshot.content_hash == content_hash)
+                    || self.is_pinned(content_hash);
 
                 if !is_referenced {
                     fs::remove_file(&content_file)?;
@@ -770,6 +764,42 @@ impl FileSnapshotSystem {
         Ok(cleaned_count)
     }
 
+    /// Deletes the stored content of `content_hashes` that no file snapshot, baseline or
+    /// workspace snapshot uses anymore; returns how many content files went.
+    pub fn release_content(&mut self, content_hashes: &HashSet<String>) -> SnapshotResult<usize> {
+        let empty_content_hash = self.calculate_content_hash(&[]);
+        let mut released = 0;
+
+        for content_hash in content_hashes {
+            if *content_hash == empty_content_hash
+                || self.is_pinned(content_hash)
+                || self
+                    .active_snapshots
+                    .values()
+                    .any(|snapshot| snapshot.content_hash == *content_hash)
+            {
+                continue;
+            }
+
+            let content_path = self.get_content_path(content_hash);
+            if content_path.exists() {
+                fs::remove_file(&content_path)?;
+                released += 1;
+            }
+            self.hash_to_path.remove(content_hash);
+        }
+
+        debug!("Released {} unreferenced content files", released);
+        Ok(released)
+    }
+
+    fn is_pinned(&self, content_hash: &str) -> bool {
+        self.pinned_content
+            .read()
+            .map(|pinned| pinned.contains(content_hash))
+            .unwrap_or(true)
+    }
+
     /// Lists all snapshots.
     pub fn list_snapshots(&self) -> Vec<&FileSnapshot> {
         self.active_snapshots.values().collect()
@@ -841,3 +871,30 @@ impl FileSnapshotSystem {
         self.get_baseline_snapshot_id(file_path).await.is_some()
     }
 }
+
+/// Content hash used to name stored content.
+pub(crate) fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", md5::compute(content))
+}
+
+/// Gzip-compresses content.
+pub(crate) fn compress_content(content: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content)?;
+    encoder.finish()
+}
+
+/// Decompresses gzip-compressed content.
+pub(crate) fn decompress_content(compressed: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let mut decoder = GzDecoder::new(compressed);
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
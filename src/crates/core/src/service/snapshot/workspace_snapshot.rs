@@ -0,0 +1,671 @@
+//! Workspace snapshots
+//!
+//! A workspace snapshot records every file of the workspace that is not ignored
+//! (`.gitignore`, `.bitfunignore`) by content hash. File contents go into the same
+//! content store the file snapshot system writes (`snapshots/by_hash/`), so bytes
+//! either side already holds are stored once. Manifests live in
+//! `.bitfun/checkpoints/` and age out with the rest of the checkpoint data.
+
+use crate::infrastructure::filesystem::ignore_rules::configure_walk;
+use crate::service::snapshot::snapshot_system::{
+    compress_content, content_hash, decompress_content,
+};
+use crate::service::snapshot::types::{SnapshotError, SnapshotResult};
+use ignore::WalkBuilder;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock as StdRwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Files larger than this are recorded by size and modification time only
+const MAX_CONTENT_BYTES: u64 = 2 * 1024 * 1024;
+/// Files recorded per snapshot; the rest of a larger workspace is left out
+const MAX_FILES: usize = 20_000;
+/// Characters of unified diff kept per file
+const MAX_FILE_DIFF_CHARS: usize = 64 * 1024;
+/// Characters of unified diff kept across one comparison
+const MAX_TOTAL_DIFF_CHARS: usize = 1024 * 1024;
+/// Time one file's line diff may take before it settles for a coarser result
+const DIFF_TIMEOUT: Duration = Duration::from_secs(1);
+/// Prefix of workspace snapshot IDs, which also name their manifest files
+const SNAPSHOT_ID_PREFIX: &str = "workspace_";
+
+/// Workspace snapshots kept per workspace; capturing another drops the oldest
+pub const MAX_WORKSPACE_SNAPSHOTS: usize = 20;
+
+/// Content hashes referenced by workspace snapshots, shared with the file snapshot
+/// system so neither side deletes content the other still uses
+pub type PinnedContent = Arc<StdRwLock<HashSet<String>>>;
+
+/// Why a workspace snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceSnapshotTrigger {
+    /// Requested by the user
+    Manual,
+    /// Before a Cowork turn
+    CoworkRun,
+    /// Before a turn whose tools run without confirmation
+    RiskyTurn,
+    /// After an automatically snapshotted turn ended
+    RunEnd,
+}
+
+/// One file of a workspace snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceFileEntry {
+    pub size: u64,
+    /// Modification time in milliseconds since the Unix epoch
+    pub modified_ms: u64,
+    /// Stored content; None for files over the size limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// Files of the workspace at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    pub snapshot_id: String,
+    pub trigger: WorkspaceSnapshotTrigger,
+    pub session_id: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub created_at: u64,
+    /// The workspace had more files than a snapshot records
+    pub truncated: bool,
+    /// Keyed by workspace-relative path with `/` separators
+    pub files: BTreeMap<String, WorkspaceFileEntry>,
+}
+
+/// A workspace snapshot without its file list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSnapshotInfo {
+    pub snapshot_id: String,
+    pub trigger: WorkspaceSnapshotTrigger,
+    pub session_id: Option<String>,
+    pub created_at: u64,
+    pub file_count: usize,
+    pub truncated: bool,
+}
+
+impl WorkspaceSnapshot {
+    pub fn info(&self) -> WorkspaceSnapshotInfo {
+        WorkspaceSnapshotInfo {
+            snapshot_id: self.snapshot_id.clone(),
+            trigger: self.trigger,
+            session_id: self.session_id.clone(),
+            created_at: self.created_at,
+            file_count: self.files.len(),
+            truncated: self.truncated,
+        }
+    }
+}
+
+/// How a file differs between two workspace snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// One file that differs between two workspace snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileComparison {
+    pub path: String,
+    pub change: FileChangeKind,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Unified diff; None for binary or unstored content and once the comparison's
+    /// diff budget is spent
+    pub diff: Option<String>,
+    /// `diff` was cut at the per-file limit
+    pub diff_truncated: bool,
+}
+
+/// Differences from one workspace snapshot to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotComparison {
+    pub from_snapshot_id: String,
+    pub to_snapshot_id: String,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    /// Sorted by path
+    pub files: Vec<FileComparison>,
+    /// Some files went without a diff because the comparison's diff budget ran out
+    pub diffs_truncated: bool,
+}
+
+/// Captures, lists and compares the workspace snapshots of one workspace
+pub struct WorkspaceSnapshotStore {
+    workspace_dir: PathBuf,
+    content_dir: PathBuf,
+    manifest_dir: PathBuf,
+    pinned_content: PinnedContent,
+    /// Serializes captures and deletions, which both rewrite the pinned content
+    write_lock: Mutex<()>,
+}
+
+impl WorkspaceSnapshotStore {
+    pub fn new(workspace_dir: PathBuf, bitfun_dir: &Path, pinned_content: PinnedContent) -> Self {
+        Self {
+            workspace_dir,
+            content_dir: bitfun_dir.join("snapshots").join("by_hash"),
+            manifest_dir: bitfun_dir.join("checkpoints"),
+            pinned_content,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub async fn initialize(&self) -> SnapshotResult<()> {
+        fs::create_dir_all(&self.content_dir)?;
+        fs::create_dir_all(&self.manifest_dir)?;
+        let _guard = self.write_lock.lock().await;
+        self.reload_pins().await?;
+        Ok(())
+    }
+
+    /// Captures the workspace as it is now.
+    pub async fn capture(
+        &self,
+        trigger: WorkspaceSnapshotTrigger,
+        session_id: Option<String>,
+    ) -> SnapshotResult<WorkspaceSnapshotInfo> {
+        let _guard = self.write_lock.lock().await;
+
+        let previous = match self.snapshot_ids()?.last() {
+            Some(snapshot_id) => match self.load(snapshot_id).await {
+                Ok(snapshot) => Some(snapshot),
+                Err(e) => {
+                    warn!(
+                        "Failed to load previous workspace snapshot: snapshot_id={} error={}",
+                        snapshot_id, e
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // IDs sort by capture time, so it never repeats or goes back
+        let created_at = previous
+            .as_ref()
+            .map_or(0, |previous| previous.created_at + 1)
+            .max(now_millis());
+
+        let workspace_dir = self.workspace_dir.clone();
+        let content_dir = self.content_dir.clone();
+        let pinned_content = self.pinned_content.clone();
+        let (files, truncated) = tokio::task::spawn_blocking(move || {
+            scan_workspace(
+                &workspace_dir,
+                &content_dir,
+                previous.as_ref(),
+                &pinned_content,
+            )
+        })
+        .await
+        .map_err(|e| SnapshotError::ConfigError(format!("Workspace scan failed: {}", e)))??;
+
+        let snapshot = WorkspaceSnapshot {
+            snapshot_id: format!(
+                "{}{}_{}",
+                SNAPSHOT_ID_PREFIX,
+                created_at,
+                &Uuid::new_v4().simple().to_string()[..8]
+            ),
+            trigger,
+            session_id,
+            created_at,
+            truncated,
+            files,
+        };
+        let manifest = serde_json::to_vec(&snapshot)?;
+        fs::write(self.manifest_path(&snapshot.snapshot_id), manifest)?;
+
+        info!(
+            "Workspace snapshot captured: snapshot_id={} files={} truncated={}",
+            snapshot.snapshot_id,
+            snapshot.files.len(),
+            snapshot.truncated
+        );
+        Ok(snapshot.info())
+    }
+
+    /// Deletes the oldest snapshots beyond `keep`; returns the content hashes no
+    /// snapshot references anymore.
+    pub async fn prune(&self, keep: usize) -> SnapshotResult<HashSet<String>> {
+        let _guard = self.write_lock.lock().await;
+        let snapshot_ids = self.snapshot_ids()?;
+        if snapshot_ids.len() <= keep {
+            return Ok(HashSet::new());
+        }
+        for snapshot_id in &snapshot_ids[..snapshot_ids.len() - keep] {
+            fs::remove_file(self.manifest_path(snapshot_id))?;
+            debug!("Pruned workspace snapshot: snapshot_id={}", snapshot_id);
+        }
+        self.reload_pins().await
+    }
+
+    /// Deletes a snapshot; returns the content hashes no snapshot references anymore.
+    pub async fn delete(&self, snapshot_id: &str) -> SnapshotResult<HashSet<String>> {
+        let _guard = self.write_lock.lock().await;
+        let manifest_path = self.checked_manifest_path(snapshot_id)?;
+        if !manifest_path.exists() {
+            return Err(SnapshotError::SnapshotNotFound(snapshot_id.to_string()));
+        }
+        fs::remove_file(manifest_path)?;
+        self.reload_pins().await
+    }
+
+    /// Re-reads which content the manifests on disk reference, after manifests were
+    /// removed; returns the content hashes no snapshot references anymore.
+    pub async fn refresh(&self) -> SnapshotResult<HashSet<String>> {
+        let _guard = self.write_lock.lock().await;
+        self.reload_pins().await
+    }
+
+    /// Snapshots, newest first.
+    pub async fn list(&self) -> SnapshotResult<Vec<WorkspaceSnapshotInfo>> {
+        let mut infos = Vec::new();
+        for snapshot_id in self.snapshot_ids()?.iter().rev() {
+            match self.load(snapshot_id).await {
+                Ok(snapshot) => infos.push(snapshot.info()),
+                Err(e) => warn!(
+                    "Failed to load workspace snapshot: snapshot_id={} error={}",
+                    snapshot_id, e
+                ),
+            }
+        }
+        Ok(infos)
+    }
+
+    pub async fn load(&self, snapshot_id: &str) -> SnapshotResult<WorkspaceSnapshot> {
+        let manifest_path = self.checked_manifest_path(snapshot_id)?;
+        let manifest = tokio::fs::read(&manifest_path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                SnapshotError::SnapshotNotFound(snapshot_id.to_string())
+            } else {
+                SnapshotError::Io(e)
+            }
+        })?;
+        Ok(serde_json::from_slice(&manifest)?)
+    }
+
+    /// Files added, removed and modified from `from` to `to`.
+    pub async fn compare(
+        &self,
+        from: WorkspaceSnapshot,
+        to: WorkspaceSnapshot,
+    ) -> SnapshotResult<SnapshotComparison> {
+        let content_dir = self.content_dir.clone();
+        tokio::task::spawn_blocking(move || Ok(compare_snapshots(&from, &to, &content_dir)))
+            .await
+            .map_err(|e| SnapshotError::ConfigError(format!("Snapshot comparison failed: {}", e)))?
+    }
+
+    /// Caller holds `write_lock`.
+    async fn reload_pins(&self) -> SnapshotResult<HashSet<String>> {
+        let mut referenced = HashSet::new();
+        for snapshot_id in self.snapshot_ids()? {
+            match self.load(&snapshot_id).await {
+                Ok(snapshot) => referenced.extend(
+                    snapshot
+                        .files
+                        .into_values()
+                        .filter_map(|entry| entry.content_hash),
+                ),
+                Err(e) => warn!(
+                    "Failed to load workspace snapshot: snapshot_id={} error={}",
+                    snapshot_id, e
+                ),
+            }
+        }
+
+        let mut pinned = self
+            .pinned_content
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let released = pinned.difference(&referenced).cloned().collect();
+        *pinned = referenced;
+        Ok(released)
+    }
+
+    /// IDs of the snapshots on disk, oldest first.
+    fn snapshot_ids(&self) -> SnapshotResult<Vec<String>> {
+        if !self.manifest_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut snapshot_ids: Vec<String> = fs::read_dir(&self.manifest_dir)?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let snapshot_id = name.strip_suffix(".json")?;
+                is_valid_snapshot_id(snapshot_id).then(|| snapshot_id.to_string())
+            })
+            .collect();
+        // IDs start with the capture time in milliseconds
+        snapshot_ids.sort();
+        Ok(snapshot_ids)
+    }
+
+    fn checked_manifest_path(&self, snapshot_id: &str) -> SnapshotResult<PathBuf> {
+        if !is_valid_snapshot_id(snapshot_id) {
+            return Err(SnapshotError::SnapshotNotFound(snapshot_id.to_string()));
+        }
+        Ok(self.manifest_path(snapshot_id))
+    }
+
+    fn manifest_path(&self, snapshot_id: &str) -> PathBuf {
+        self.manifest_dir.join(format!("{}.json", snapshot_id))
+    }
+}
+
+fn is_valid_snapshot_id(snapshot_id: &str) -> bool {
+    snapshot_id.starts_with(SNAPSHOT_ID_PREFIX)
+        && snapshot_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Records the files of `workspace_dir`, storing content that changed since `previous`.
+fn scan_workspace(
+    workspace_dir: &Path,
+    content_dir: &Path,
+    previous: Option<&WorkspaceSnapshot>,
+    pinned_content: &PinnedContent,
+) -> SnapshotResult<(BTreeMap<String, WorkspaceFileEntry>, bool)> {
+    let mut builder = WalkBuilder::new(workspace_dir);
+    let walker = configure_walk(&mut builder, false)
+        .hidden(false)
+        .follow_links(false)
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !matches!(entry.file_name().to_str(), Some(".git" | ".bitfun"))
+        })
+        .build();
+
+    let mut files = BTreeMap::new();
+    let mut truncated = false;
+    for entry in walker.flatten() {
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        if files.len() >= MAX_FILES {
+            truncated = true;
+            break;
+        }
+        let Ok(relative) = entry.path().strip_prefix(workspace_dir) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let size = metadata.len();
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map_or(0, |since_epoch| since_epoch.as_millis() as u64);
+
+        let unchanged = previous
+            .and_then(|previous| previous.files.get(&relative))
+            .filter(|entry| {
+                modified_ms != 0 && entry.size == size && entry.modified_ms == modified_ms
+            })
+            .and_then(|entry| entry.content_hash.clone())
+            .filter(|hash| {
+                pin(pinned_content, hash);
+                content_path(content_dir, hash).exists()
+            });
+        let content_hash = match unchanged {
+            Some(hash) => Some(hash),
+            None if size > MAX_CONTENT_BYTES => None,
+            None => match fs::read(entry.path()) {
+                Ok(content) => Some(store_content(content_dir, &content, pinned_content)?),
+                Err(e) => {
+                    debug!(
+                        "Skipped unreadable file in workspace snapshot: path={} error={}",
+                        entry.path().display(),
+                        e
+                    );
+                    continue;
+                }
+            },
+        };
+
+        files.insert(
+            relative,
+            WorkspaceFileEntry {
+                size,
+                modified_ms,
+                content_hash,
+            },
+        );
+    }
+
+    Ok((files, truncated))
+}
+
+/// Writes `content` to the content store unless it is there already; returns its hash.
+fn store_content(
+    content_dir: &Path,
+    content: &[u8],
+    pinned_content: &PinnedContent,
+) -> SnapshotResult<String> {
+    let hash = content_hash(content);
+    // Pinned before the existence check, so the file snapshot system can't delete it in between
+    pin(pinned_content, &hash);
+
+    let path = content_path(content_dir, &hash);
+    if path.exists() {
+        return Ok(hash);
+    }
+
+    // Same rule as the file snapshot system: compress when it saves at least a fifth
+    let stored = match compress_content(content) {
+        Ok(compressed) if content.len() > 1024 && compressed.len() < content.len() * 4 / 5 => {
+            compressed
+        }
+        _ => content.to_vec(),
+    };
+    let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+    fs::write(&temp_path, stored)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(hash)
+}
+
+fn load_content(content_dir: &Path, content_hash: &str) -> Option<Vec<u8>> {
+    let stored = fs::read(content_path(content_dir, content_hash)).ok()?;
+    Some(decompress_content(&stored).unwrap_or(stored))
+}
+
+fn pin(pinned_content: &PinnedContent, content_hash: &str) {
+    pinned_content
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(content_hash.to_string());
+}
+
+fn content_path(content_dir: &Path, content_hash: &str) -> PathBuf {
+    content_dir.join(format!("{}.snap", content_hash))
+}
+
+fn compare_snapshots(
+    from: &WorkspaceSnapshot,
+    to: &WorkspaceSnapshot,
+    content_dir: &Path,
+) -> SnapshotComparison {
+    let mut comparison = SnapshotComparison {
+        from_snapshot_id: from.snapshot_id.clone(),
+        to_snapshot_id: to.snapshot_id.clone(),
+        added: 0,
+        removed: 0,
+        modified: 0,
+        files: Vec::new(),
+        diffs_truncated: false,
+    };
+    let mut diff_budget = MAX_TOTAL_DIFF_CHARS;
+
+    let paths: BTreeSet<&String> = from.files.keys().chain(to.files.keys()).collect();
+    for path in paths {
+        let old = from.files.get(path);
+        let new = to.files.get(path);
+        let change = match (old, new) {
+            (None, Some(_)) => FileChangeKind::Added,
+            (Some(_), None) => FileChangeKind::Removed,
+            (Some(old), Some(new)) if !same_content(old, new) => FileChangeKind::Modified,
+            _ => continue,
+        };
+        match change {
+            FileChangeKind::Added => comparison.added += 1,
+            FileChangeKind::Removed => comparison.removed += 1,
+            FileChangeKind::Modified => comparison.modified += 1,
+        }
+
+        let mut file = FileComparison {
+            path: path.clone(),
+            change,
+            old_size: old.map(|entry| entry.size),
+            new_size: new.map(|entry| entry.size),
+            lines_added: 0,
+            lines_removed: 0,
+            diff: None,
+            diff_truncated: false,
+        };
+        if let (Some(old_text), Some(new_text)) =
+            (entry_text(old, content_dir), entry_text(new, content_dir))
+        {
+            let text_diff = similar::TextDiff::configure()
+                .timeout(DIFF_TIMEOUT)
+                .diff_lines(old_text.as_str(), new_text.as_str());
+            for line_change in text_diff.iter_all_changes() {
+                match line_change.tag() {
+                    similar::ChangeTag::Insert => file.lines_added += 1,
+                    similar::ChangeTag::Delete => file.lines_removed += 1,
+                    similar::ChangeTag::Equal => {}
+                }
+            }
+
+            if diff_budget == 0 {
+                comparison.diffs_truncated = true;
+            } else {
+                let mut diff = text_diff
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&format!("a/{}", path), &format!("b/{}", path))
+                    .to_string();
+                let limit = MAX_FILE_DIFF_CHARS.min(diff_budget);
+                if let Some((cut, _)) = diff.char_indices().nth(limit) {
+                    diff.truncate(cut);
+                    file.diff_truncated = true;
+                }
+                diff_budget = diff_budget.saturating_sub(diff.chars().count());
+                file.diff = Some(diff);
+            }
+        }
+        comparison.files.push(file);
+    }
+
+    comparison
+}
+
+fn same_content(old: &WorkspaceFileEntry, new: &WorkspaceFileEntry) -> bool {
+    match (&old.content_hash, &new.content_hash) {
+        (Some(old_hash), Some(new_hash)) => old_hash == new_hash,
+        _ => old == new,
+    }
+}
+
+/// Text of a file entry; "" for a missing side, None when the content is binary or unstored
+fn entry_text(entry: Option<&WorkspaceFileEntry>, content_dir: &Path) -> Option<String> {
+    let Some(entry) = entry else {
+        return Some(String::new());
+    };
+    let content = load_content(content_dir, entry.content_hash.as_deref()?)?;
+    String::from_utf8(content).ok()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn compares_captured_snapshots() {
+        let root = std::env::temp_dir().join(format!("bitfun-ws-snapshot-{}", Uuid::new_v4()));
+        let workspace = root.join("workspace");
+        fs::create_dir_all(workspace.join("src")).unwrap();
+        fs::write(workspace.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(workspace.join("old.txt"), "old\n").unwrap();
+        fs::write(workspace.join("same.txt"), "same\n").unwrap();
+
+        let pinned = PinnedContent::default();
+        let store =
+            WorkspaceSnapshotStore::new(workspace.clone(), &root.join(".bitfun"), pinned.clone());
+        store.initialize().await.unwrap();
+        let before = store
+            .capture(WorkspaceSnapshotTrigger::Manual, None)
+            .await
+            .unwrap();
+
+        fs::write(workspace.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        fs::remove_file(workspace.join("old.txt")).unwrap();
+        fs::write(workspace.join("new.txt"), "new\n").unwrap();
+        let after = store
+            .capture(WorkspaceSnapshotTrigger::RunEnd, None)
+            .await
+            .unwrap();
+
+        let comparison = store
+            .compare(
+                store.load(&before.snapshot_id).await.unwrap(),
+                store.load(&after.snapshot_id).await.unwrap(),
+            )
+            .await
+            .unwrap();
+        let changes: Vec<(&str, FileChangeKind)> = comparison
+            .files
+            .iter()
+            .map(|file| (file.path.as_str(), file.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("new.txt", FileChangeKind::Added),
+                ("old.txt", FileChangeKind::Removed),
+                ("src/lib.rs", FileChangeKind::Modified),
+            ]
+        );
+        let modified = &comparison.files[2];
+        assert_eq!((modified.lines_added, modified.lines_removed), (1, 0));
+        assert!(modified.diff.as_deref().unwrap().contains("+fn b() {}"));
+
+        // Dropping the first snapshot releases only the content nothing else references
+        let released = store.prune(1).await.unwrap();
+        assert_eq!(
+            released,
+            HashSet::from([content_hash(b"fn a() {}\n"), content_hash(b"old\n")])
+        );
+        assert!(pinned.read().unwrap().contains(&content_hash(b"same\n")));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
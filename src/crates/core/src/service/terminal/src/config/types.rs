@@ -34,6 +34,15 @@ pub struct TerminalConfig {
     /// Terminal dimensions
     pub default_cols: u16,
     pub default_rows: u16,
+
+    /// Idle time after which agent-owned sessions (bound through
+    /// `TerminalSessionBinding`) are closed, in seconds (0 = never)
+    #[serde(default = "default_agent_idle_timeout_secs")]
+    pub agent_idle_timeout_secs: u64,
+}
+
+fn default_agent_idle_timeout_secs() -> u64 {
+    30 * 60
 }
 
 impl Default for TerminalConfig {
@@ -49,6 +58,7 @@ impl Default for TerminalConfig {
             shell_integration: ShellIntegrationConfig::default(),
             default_cols: 80,
             default_rows: 24,
+            agent_idle_timeout_secs: default_agent_idle_timeout_secs(),
         }
     }
 }
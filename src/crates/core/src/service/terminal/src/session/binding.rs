@@ -12,9 +12,11 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::Utc;
 use dashmap::DashMap;
-use log::warn;
+use log::{debug, warn};
 
 use crate::session::get_session_manager;
 use crate::session::SessionSource;
@@ -215,6 +217,116 @@ impl TerminalSessionBinding {
             .unwrap_or_default()
     }
 
+    /// Check whether `session_id` is the primary or one of the background
+    /// sessions of `owner_id`
+    pub fn owns(&self, owner_id: &str, session_id: &str) -> bool {
+        self.get(owner_id).as_deref() == Some(session_id)
+            || self
+                .background_bindings
+                .get(owner_id)
+                .map(|sessions| sessions.iter().any(|id| id == session_id))
+                .unwrap_or(false)
+    }
+
+    /// Forget a closed background session so it is no longer listed for its owner
+    pub fn forget_background_session(&self, session_id: &str) {
+        self.background_bindings
+            .iter_mut()
+            .for_each(|mut sessions| sessions.retain(|id| id != session_id));
+        self.background_bindings
+            .retain(|_, sessions| !sessions.is_empty());
+    }
+
+    /// Close the background sessions of `owner_id` that are not running a
+    /// command (e.g. finished builds); long-running ones such as dev servers
+    /// are kept.
+    ///
+    /// # Returns
+    /// The IDs of the closed sessions
+    pub async fn close_finished_background_sessions(
+        &self,
+        owner_id: &str,
+    ) -> TerminalResult<Vec<String>> {
+        let session_manager = get_session_manager()
+            .ok_or_else(|| TerminalError::Session("SessionManager not initialized".to_string()))?;
+
+        let mut closed = Vec::new();
+        for session_id in self.list_background_sessions(owner_id) {
+            if session_manager.is_command_running(&session_id).await {
+                continue;
+            }
+            match session_manager.close_session(&session_id, false).await {
+                Ok(()) | Err(TerminalError::SessionNotFound(_)) => {
+                    self.forget_background_session(&session_id);
+                    closed.push(session_id);
+                }
+                Err(e) => warn!(
+                    "Failed to close background terminal session {}: {}",
+                    session_id, e
+                ),
+            }
+        }
+
+        Ok(closed)
+    }
+
+    /// Close bound sessions (primary and background) that have produced no
+    /// output for `max_idle` and are not running a command
+    ///
+    /// # Returns
+    /// The IDs of the closed sessions
+    pub async fn close_idle_sessions(&self, max_idle: Duration) -> TerminalResult<Vec<String>> {
+        let session_manager = get_session_manager()
+            .ok_or_else(|| TerminalError::Session("SessionManager not initialized".to_string()))?;
+
+        let candidates: Vec<(String, String)> = self
+            .list_bindings()
+            .into_iter()
+            .chain(self.background_bindings.iter().flat_map(|entry| {
+                entry
+                    .value()
+                    .iter()
+                    .map(|session_id| (entry.key().clone(), session_id.clone()))
+                    .collect::<Vec<_>>()
+            }))
+            .collect();
+
+        let mut closed = Vec::new();
+        for (owner_id, session_id) in candidates {
+            let is_idle = match session_manager.get_session(&session_id).await {
+                Some(session) => (Utc::now() - session.last_activity)
+                    .to_std()
+                    .map(|idle| idle >= max_idle)
+                    .unwrap_or(false),
+                // Already gone; only the binding is left to clean up
+                None => true,
+            };
+            if !is_idle || session_manager.is_command_running(&session_id).await {
+                continue;
+            }
+
+            debug!(
+                "Closing idle terminal session {} (owner: {})",
+                session_id, owner_id
+            );
+            match session_manager.close_session(&session_id, false).await {
+                Ok(()) | Err(TerminalError::SessionNotFound(_)) => {
+                    if self.get(&owner_id).as_deref() == Some(session_id.as_str()) {
+                        self.unbind(&owner_id);
+                    }
+                    self.forget_background_session(&session_id);
+                    closed.push(session_id);
+                }
+                Err(e) => warn!(
+                    "Failed to close idle terminal session {}: {}",
+                    session_id, e
+                ),
+            }
+        }
+
+        Ok(closed)
+    }
+
     /// Remove binding and close the associated terminal session
     ///
     /// This is the recommended way to clean up when an owner is being destroyed.
@@ -338,4 +450,29 @@ mod tests {
         binding.clear();
         assert_eq!(binding.count(), 0);
     }
+
+    #[test]
+    fn test_background_session_ownership() {
+        let binding = TerminalSessionBinding::new();
+        binding.bind("owner1", "primary1");
+        binding.background_bindings.insert(
+            "owner1".to_string(),
+            vec!["bg1".to_string(), "bg2".to_string()],
+        );
+
+        assert!(binding.owns("owner1", "primary1"));
+        assert!(binding.owns("owner1", "bg2"));
+        assert!(!binding.owns("owner2", "bg2"));
+        assert!(!binding.owns("owner1", "other"));
+
+        binding.forget_background_session("bg1");
+        assert_eq!(
+            binding.list_background_sessions("owner1"),
+            vec!["bg2".to_string()]
+        );
+
+        binding.forget_background_session("bg2");
+        assert!(binding.list_background_sessions("owner1").is_empty());
+        assert!(!binding.owns("owner1", "bg2"));
+    }
 }
@@ -23,6 +23,8 @@ use crate::{TerminalError, TerminalResult};
 use super::{SessionSource, SessionStatus, TerminalSession};
 
 const COMMAND_TIMEOUT_INTERRUPT_GRACE_MS: Duration = Duration::from_millis(500);
+/// How often agent-owned sessions are checked for idleness
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Why a command stream reached completion.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -178,6 +180,8 @@ impl SessionManager {
         // Start event forwarding
         manager.start_event_forwarding();
 
+        manager.start_idle_reaper();
+
         manager
     }
 
@@ -189,6 +193,32 @@ impl SessionManager {
         self.binding.clone()
     }
 
+    /// Periodically close agent-owned sessions that have been idle longer
+    /// than `agent_idle_timeout_secs`
+    fn start_idle_reaper(&self) {
+        if self.config.agent_idle_timeout_secs == 0 {
+            return;
+        }
+        let max_idle = Duration::from_secs(self.config.agent_idle_timeout_secs);
+        let binding = self.binding.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL.min(max_idle));
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match binding.close_idle_sessions(max_idle).await {
+                    Ok(closed) if !closed.is_empty() => {
+                        debug!("Closed idle terminal sessions: {:?}", closed);
+                    }
+                    Ok(_) => {}
+                    Err(e) => debug!("Skipped idle terminal session check: {}", e),
+                }
+            }
+        });
+    }
+
     /// Start forwarding PTY service events to terminal events
     fn start_event_forwarding(&self) {
         let pty_service = self.pty_service.clone();
@@ -1278,6 +1308,7 @@ impl SessionManager {
         // creates a fresh session rather than returning a stale ID.
         // For primary sessions owner_id == session_id, so unbind(session_id) is sufficient.
         self.binding.unbind(session_id);
+        self.binding.forget_background_session(session_id);
 
         // Emit session destroyed event for frontend
         let _ = self
@@ -1329,6 +1360,14 @@ impl SessionManager {
         integrations.get(session_id).map(|i| i.state().clone())
     }
 
+    /// Check whether a command is currently executing in a session
+    pub async fn is_command_running(&self, session_id: &str) -> bool {
+        matches!(
+            self.get_command_state(session_id).await,
+            Some(CommandState::Executing)
+        )
+    }
+
     /// Shutdown all sessions
     pub async fn shutdown_all(&self) {
         let session_ids: Vec<String> = {
@@ -1411,4 +1450,56 @@ mod tests {
             "\"timedOut\""
         );
     }
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shell_state_persists_across_commands() {
+        use super::{SessionManager, ShellType};
+        use crate::config::TerminalConfig;
+        use crate::session::SessionSource;
+
+        if !std::path::Path::new("/bin/bash").exists() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("bitfun-terminal-{}", uuid::Uuid::new_v4()));
+        let work_dir = dir.join("work");
+        std::fs::create_dir_all(&work_dir).unwrap();
+
+        let mut config = TerminalConfig::default();
+        config.shell_integration.scripts_dir = Some(dir.join("scripts"));
+        config.agent_idle_timeout_secs = 0;
+        let manager = SessionManager::new(config);
+        let session = manager
+            .create_session(
+                None,
+                None,
+                Some(ShellType::Bash),
+                Some(dir.to_string_lossy().to_string()),
+                None,
+                None,
+                None,
+                Some(SessionSource::Agent),
+            )
+            .await
+            .unwrap();
+
+        manager
+            .execute_command(&session.id, "export BITFUN_PERSIST_TEST=kept")
+            .await
+            .unwrap();
+        let echoed = manager
+            .execute_command(&session.id, "echo \"value=$BITFUN_PERSIST_TEST\"")
+            .await
+            .unwrap();
+        assert!(echoed.output.contains("value=kept"), "{}", echoed.output);
+
+        manager
+            .execute_command(&session.id, "cd work")
+            .await
+            .unwrap();
+        let pwd = manager.execute_command(&session.id, "pwd").await.unwrap();
+        assert!(pwd.output.contains("/work"), "{}", pwd.output);
+
+        manager.close_session(&session.id, true).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
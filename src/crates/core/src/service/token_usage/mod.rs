@@ -3,10 +3,14 @@
 //! Tracks and persists token consumption statistics per model, session, and turn.
 
 mod service;
+mod spend;
 mod subscriber;
 mod types;
 
-pub use service::TokenUsageService;
+pub use service::{
+    get_global_token_usage_service, set_global_token_usage_service, TokenUsageService,
+};
+pub use spend::{check_spend, SpendCheck, SpendSummary};
 pub use subscriber::TokenUsageSubscriber;
 pub use types::{
     ModelTokenStats, SessionTokenStats, TimeRange, TokenUsageQuery, TokenUsageRecord,
@@ -1,5 +1,6 @@
 //! Token usage tracking service implementation
 
+use super::spend::{DailySpend, SpendSummary};
 use super::types::{
     ModelTokenStats, SessionTokenStats, TimeRange, TokenUsageQuery, TokenUsageRecord,
     TokenUsageSummary,
@@ -11,7 +12,7 @@ use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::fs;
 use tokio::sync::RwLock;
 
@@ -19,11 +20,14 @@ const TOKEN_USAGE_DIR: &str = "token_usage";
 const MODEL_STATS_FILE: &str = "model_stats.json";
 const RECORDS_DIR: &str = "records";
 
+static GLOBAL_TOKEN_USAGE_SERVICE: OnceLock<Arc<TokenUsageService>> = OnceLock::new();
+
 /// Token usage tracking service
 pub struct TokenUsageService {
     path_manager: Arc<PathManager>,
     model_stats: Arc<RwLock<HashMap<String, ModelTokenStats>>>,
     session_cache: Arc<RwLock<HashMap<String, SessionTokenStats>>>,
+    daily_spend: Arc<RwLock<DailySpend>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -38,6 +42,7 @@ impl TokenUsageService {
             path_manager,
             model_stats: Arc::new(RwLock::new(HashMap::new())),
             session_cache: Arc::new(RwLock::new(HashMap::new())),
+            daily_spend: Arc::new(RwLock::new(DailySpend::new(Utc::now().date_naive()))),
         };
 
         // Initialize storage directories
@@ -45,6 +50,7 @@ impl TokenUsageService {
 
         // Load existing statistics
         service.load_model_stats().await?;
+        service.load_daily_spend().await;
 
         info!("Token usage service initialized");
         Ok(service)
@@ -122,6 +128,36 @@ impl TokenUsageService {
         Ok(())
     }
 
+    /// Rebuild today's spend totals from today's records
+    async fn load_daily_spend(&self) {
+        let query = TokenUsageQuery {
+            model_id: None,
+            session_id: None,
+            time_range: TimeRange::Today,
+            limit: None,
+            offset: None,
+            include_subagent: true,
+        };
+        let records = match self.query_records(query).await {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("Failed to load today's token usage records: {}", e);
+                return;
+            }
+        };
+
+        let mut daily_spend = self.daily_spend.write().await;
+        for record in &records {
+            if let Some(cost_usd) = record.cost_usd {
+                daily_spend.add(
+                    record.timestamp.date_naive(),
+                    cost_usd,
+                    record.workspace_path.as_deref(),
+                );
+            }
+        }
+    }
+
     /// Save model statistics to disk
     async fn save_model_stats(&self) -> Result<()> {
         let path = self.get_model_stats_path();
@@ -149,33 +185,50 @@ impl TokenUsageService {
         cached_tokens: u32,
         is_subagent: bool,
     ) -> Result<()> {
-        let now = Utc::now();
-        let total_tokens = input_tokens + output_tokens;
-
-        let record = TokenUsageRecord {
-            model_id: model_id.clone(),
-            session_id: session_id.clone(),
+        self.record(TokenUsageRecord {
+            model_id,
+            session_id,
             turn_id,
-            timestamp: now,
+            timestamp: Utc::now(),
             input_tokens,
             output_tokens,
             cached_tokens,
-            total_tokens,
+            total_tokens: input_tokens + output_tokens,
             is_subagent,
-        };
+            cost_usd: None,
+            workspace_path: None,
+        })
+        .await
+    }
 
+    /// Record a complete usage record, including its cost
+    pub async fn record(&self, record: TokenUsageRecord) -> Result<()> {
         // Update model statistics (all-time aggregation, includes everything)
         self.update_model_stats(&record).await?;
 
         // Update session cache
         self.update_session_cache(&record).await?;
 
+        if let Some(cost_usd) = record.cost_usd {
+            self.daily_spend.write().await.add(
+                record.timestamp.date_naive(),
+                cost_usd,
+                record.workspace_path.as_deref(),
+            );
+        }
+
         // Persist record to disk
         self.persist_record(&record).await?;
 
         debug!(
-            "Recorded token usage: model={}, session={}, input={}, output={}, total={}, is_subagent={}",
-            model_id, session_id, input_tokens, output_tokens, total_tokens, is_subagent
+            "Recorded token usage: model={}, session={}, input={}, output={}, total={}, cost_usd={:?}, is_subagent={}",
+            record.model_id,
+            record.session_id,
+            record.input_tokens,
+            record.output_tokens,
+            record.total_tokens,
+            record.cost_usd,
+            record.is_subagent
         );
 
         Ok(())
@@ -196,6 +249,7 @@ impl TokenUsageService {
         stats.total_output += record.output_tokens as u64;
         stats.total_cached += record.cached_tokens as u64;
         stats.total_tokens += record.total_tokens as u64;
+        stats.total_cost_usd += record.cost_usd.unwrap_or(0.0);
         stats.request_count += 1;
 
         // Track unique sessions
@@ -230,6 +284,8 @@ impl TokenUsageService {
                 total_cached: 0,
                 total_tokens: 0,
                 request_count: 0,
+                cache_hit_rate: 0.0,
+                total_cost_usd: 0.0,
                 created_at: record.timestamp,
                 last_updated: record.timestamp,
             });
@@ -238,7 +294,9 @@ impl TokenUsageService {
         stats.total_output += record.output_tokens;
         stats.total_cached += record.cached_tokens;
         stats.total_tokens += record.total_tokens;
+        stats.total_cost_usd += record.cost_usd.unwrap_or(0.0);
         stats.request_count += 1;
+        stats.update_cache_hit_rate();
         stats.last_updated = record.timestamp;
 
         Ok(())
@@ -307,6 +365,7 @@ impl TokenUsageService {
             stats.total_output += record.output_tokens as u64;
             stats.total_cached += record.cached_tokens as u64;
             stats.total_tokens += record.total_tokens as u64;
+            stats.total_cost_usd += record.cost_usd.unwrap_or(0.0);
             stats.request_count += 1;
             stats.session_ids.insert(record.session_id.clone());
 
@@ -334,6 +393,39 @@ impl TokenUsageService {
         session_cache.get(session_id).cloned()
     }
 
+    /// Spend today, plus the given session's and workspace's share when requested
+    pub async fn get_spend_summary(
+        &self,
+        session_id: Option<&str>,
+        workspace_path: Option<&str>,
+    ) -> SpendSummary {
+        let today = Utc::now().date_naive();
+        let (today_usd, workspace_usd) = self
+            .daily_spend
+            .read()
+            .await
+            .totals_on(today, workspace_path);
+
+        let session_usd = match session_id {
+            Some(session_id) => Some(
+                self.session_cache
+                    .read()
+                    .await
+                    .get(session_id)
+                    .map(|stats| stats.total_cost_usd)
+                    .unwrap_or(0.0),
+            ),
+            None => None,
+        };
+
+        SpendSummary {
+            date: today.format("%Y-%m-%d").to_string(),
+            today_usd,
+            session_usd,
+            workspace_usd,
+        }
+    }
+
     /// Query token usage records
     pub async fn query_records(&self, query: TokenUsageQuery) -> Result<Vec<TokenUsageRecord>> {
         let (start_date, end_date) = self.get_date_range(&query.time_range);
@@ -430,6 +522,7 @@ impl TokenUsageService {
         let mut total_output = 0u64;
         let mut total_cached = 0u64;
         let mut total_tokens = 0u64;
+        let mut total_cost_usd = 0.0;
 
         let mut by_model: HashMap<String, ModelTokenStats> = HashMap::new();
         let mut by_session: HashMap<String, SessionTokenStats> = HashMap::new();
@@ -439,6 +532,8 @@ impl TokenUsageService {
             total_output += record.output_tokens as u64;
             total_cached += record.cached_tokens as u64;
             total_tokens += record.total_tokens as u64;
+            let cost_usd = record.cost_usd.unwrap_or(0.0);
+            total_cost_usd += cost_usd;
 
             // Aggregate by model
             let model_stats =
@@ -453,6 +548,7 @@ impl TokenUsageService {
             model_stats.total_output += record.output_tokens as u64;
             model_stats.total_cached += record.cached_tokens as u64;
             model_stats.total_tokens += record.total_tokens as u64;
+            model_stats.total_cost_usd += cost_usd;
             model_stats.request_count += 1;
             model_stats.session_ids.insert(record.session_id.clone());
 
@@ -474,6 +570,8 @@ impl TokenUsageService {
                     total_cached: 0,
                     total_tokens: 0,
                     request_count: 0,
+                    cache_hit_rate: 0.0,
+                    total_cost_usd: 0.0,
                     created_at: record.timestamp,
                     last_updated: record.timestamp,
                 });
@@ -482,7 +580,9 @@ impl TokenUsageService {
             session_stats.total_output += record.output_tokens;
             session_stats.total_cached += record.cached_tokens;
             session_stats.total_tokens += record.total_tokens;
+            session_stats.total_cost_usd += cost_usd;
             session_stats.request_count += 1;
+            session_stats.update_cache_hit_rate();
 
             if record.timestamp < session_stats.created_at {
                 session_stats.created_at = record.timestamp;
@@ -505,6 +605,7 @@ impl TokenUsageService {
             by_model,
             by_session,
             record_count: records.len(),
+            total_cost_usd,
         })
     }
 
@@ -530,6 +631,8 @@ impl TokenUsageService {
         session_cache.clear();
         drop(session_cache);
 
+        *self.daily_spend.write().await = DailySpend::new(Utc::now().date_naive());
+
         self.save_model_stats().await?;
 
         // Optionally delete all record files
@@ -543,3 +646,11 @@ impl TokenUsageService {
         Ok(())
     }
 }
+
+pub fn get_global_token_usage_service() -> Option<Arc<TokenUsageService>> {
+    GLOBAL_TOKEN_USAGE_SERVICE.get().cloned()
+}
+
+pub fn set_global_token_usage_service(service: Arc<TokenUsageService>) {
+    let _ = GLOBAL_TOKEN_USAGE_SERVICE.set(service);
+}
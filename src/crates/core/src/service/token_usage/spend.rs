@@ -0,0 +1,182 @@
+//! Spend accounting and guardrail checks
+//!
+//! Costs come from each model's configured pricing; guardrails compare today's spend and the
+//! estimated cost of the next model round against `SpendGuardrailsConfig`.
+
+use crate::service::config::types::SpendGuardrailsConfig;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Current spend in USD
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendSummary {
+    /// UTC date the daily totals refer to (YYYY-MM-DD)
+    pub date: String,
+    pub today_usd: f64,
+    /// Requested session since app start
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_usd: Option<f64>,
+    /// Requested workspace, today
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_usd: Option<f64>,
+}
+
+/// Today's running totals, rolled over at UTC midnight
+#[derive(Debug, Clone)]
+pub(crate) struct DailySpend {
+    pub date: NaiveDate,
+    pub total_usd: f64,
+    pub by_workspace: HashMap<String, f64>,
+}
+
+impl DailySpend {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            total_usd: 0.0,
+            by_workspace: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, date: NaiveDate, cost_usd: f64, workspace_path: Option<&str>) {
+        if date != self.date {
+            *self = Self::new(date);
+        }
+        self.total_usd += cost_usd;
+        if let Some(workspace_path) = workspace_path {
+            *self
+                .by_workspace
+                .entry(workspace_path.to_string())
+                .or_insert(0.0) += cost_usd;
+        }
+    }
+
+    /// Totals for `today`; a stale day counts as nothing spent yet
+    pub fn totals_on(&self, today: NaiveDate, workspace_path: Option<&str>) -> (f64, Option<f64>) {
+        if today != self.date {
+            return (0.0, workspace_path.map(|_| 0.0));
+        }
+        let workspace =
+            workspace_path.map(|path| self.by_workspace.get(path).copied().unwrap_or(0.0));
+        (self.total_usd, workspace)
+    }
+}
+
+/// Thresholds crossed before a model round
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpendCheck {
+    /// Hard stop reached by today's spend
+    pub stop_at: Option<f64>,
+    /// Warning threshold reached by today's spend
+    pub warn_at: Option<f64>,
+    /// Per-turn threshold exceeded by the estimated round cost
+    pub confirm_at: Option<f64>,
+}
+
+/// Compare today's spend and the next round's estimated cost against the guardrails
+pub fn check_spend(
+    guardrails: &SpendGuardrailsConfig,
+    spent_today_usd: f64,
+    estimated_turn_usd: Option<f64>,
+) -> SpendCheck {
+    SpendCheck {
+        stop_at: guardrails
+            .daily_hard_stop_usd
+            .filter(|limit| spent_today_usd >= *limit),
+        warn_at: guardrails
+            .daily_warn_usd
+            .filter(|limit| spent_today_usd >= *limit),
+        confirm_at: guardrails
+            .turn_confirm_usd
+            .filter(|limit| estimated_turn_usd.is_some_and(|estimate| estimate > *limit)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::types::ModelPricing;
+
+    #[test]
+    fn cost_bills_cached_prompt_tokens_at_cached_rate() {
+        let pricing = ModelPricing {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cached_input_per_mtok: Some(0.3),
+            web_search_per_request: None,
+        };
+        // 600k uncached * $3 + 400k cached * $0.3 + 100k output * $15
+        let cost = pricing.cost_usd(1_000_000, 100_000, 400_000);
+        assert!((cost - (1.8 + 0.12 + 1.5)).abs() < 1e-9);
+
+        let without_cache_rate = ModelPricing {
+            cached_input_per_mtok: None,
+            ..pricing
+        };
+        assert!((without_cache_rate.cost_usd(1_000_000, 0, 400_000) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn web_searches_are_billed_per_request() {
+        let pricing = ModelPricing {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cached_input_per_mtok: None,
+            web_search_per_request: None,
+        };
+        assert!((pricing.web_search_cost_usd(3) - 0.03).abs() < 1e-9);
+
+        let custom = ModelPricing {
+            web_search_per_request: Some(0.005),
+            ..pricing
+        };
+        assert!((custom.web_search_cost_usd(2) - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn guardrails_default_off() {
+        let check = check_spend(&SpendGuardrailsConfig::default(), 1_000.0, Some(1_000.0));
+        assert_eq!(check, SpendCheck::default());
+    }
+
+    #[test]
+    fn guardrails_trip_at_their_thresholds() {
+        let guardrails = SpendGuardrailsConfig {
+            daily_warn_usd: Some(5.0),
+            turn_confirm_usd: Some(0.5),
+            daily_hard_stop_usd: Some(20.0),
+        };
+
+        assert_eq!(
+            check_spend(&guardrails, 4.99, Some(0.5)),
+            SpendCheck::default()
+        );
+
+        let check = check_spend(&guardrails, 5.0, Some(0.51));
+        assert_eq!(check.warn_at, Some(5.0));
+        assert_eq!(check.confirm_at, Some(0.5));
+        assert_eq!(check.stop_at, None);
+
+        // Unpriced models have no estimate and never ask for confirmation
+        let check = check_spend(&guardrails, 20.0, None);
+        assert_eq!(check.stop_at, Some(20.0));
+        assert_eq!(check.confirm_at, None);
+    }
+
+    #[test]
+    fn daily_spend_rolls_over_and_tracks_workspaces() {
+        let day1 = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+
+        let mut spend = DailySpend::new(day1);
+        spend.add(day1, 1.0, Some("/repo"));
+        spend.add(day1, 0.5, None);
+        assert_eq!(spend.totals_on(day1, Some("/repo")), (1.5, Some(1.0)));
+        assert_eq!(spend.totals_on(day2, Some("/repo")), (0.0, Some(0.0)));
+
+        spend.add(day2, 0.25, Some("/other"));
+        assert_eq!(spend.totals_on(day2, Some("/repo")), (0.25, Some(0.0)));
+    }
+}
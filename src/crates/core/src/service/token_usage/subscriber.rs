@@ -1,8 +1,9 @@
 //! Token usage event subscriber
 
-use crate::agentic::events::{AgenticEvent, EventSubscriber};
-use crate::service::token_usage::TokenUsageService;
+use crate::agentic::events::{AgenticEvent, EventFilter, EventSubscriber};
+use crate::service::token_usage::{TokenUsageRecord, TokenUsageService};
 use crate::util::errors::BitFunResult;
+use chrono::Utc;
 use log::{debug, error};
 use std::sync::Arc;
 
@@ -31,12 +32,15 @@ impl EventSubscriber for TokenUsageSubscriber {
             input_tokens,
             output_tokens,
             total_tokens,
+            cached_tokens,
             is_subagent,
+            cost_usd,
+            workspace_path,
             ..
         } = event
         {
             let output = output_tokens.unwrap_or(0);
-            let cached = 0;
+            let cached = cached_tokens.unwrap_or(0);
 
             debug!(
                 "Recording token usage: model={}, session={}, turn={}, input={}, output={}, total={}, is_subagent={}",
@@ -45,15 +49,19 @@ impl EventSubscriber for TokenUsageSubscriber {
 
             if let Err(e) = self
                 .token_usage_service
-                .record_usage(
-                    model_id.clone(),
-                    session_id.clone(),
-                    turn_id.clone(),
-                    *input_tokens as u32,
-                    output as u32,
-                    cached,
-                    *is_subagent,
-                )
+                .record(TokenUsageRecord {
+                    model_id: model_id.clone(),
+                    session_id: session_id.clone(),
+                    turn_id: turn_id.clone(),
+                    timestamp: Utc::now(),
+                    input_tokens: *input_tokens as u32,
+                    output_tokens: output as u32,
+                    cached_tokens: cached as u32,
+                    total_tokens: (*input_tokens + output) as u32,
+                    is_subagent: *is_subagent,
+                    cost_usd: *cost_usd,
+                    workspace_path: workspace_path.clone(),
+                })
                 .await
             {
                 error!("Failed to record token usage: {}", e);
@@ -62,4 +70,8 @@ impl EventSubscriber for TokenUsageSubscriber {
 
         Ok(())
     }
+
+    fn filter(&self) -> EventFilter {
+        EventFilter::new("agentic://token-usage-updated")
+    }
 }
@@ -18,6 +18,12 @@ pub struct TokenUsageRecord {
     /// Whether this record is from a subagent call
     #[serde(default)]
     pub is_subagent: bool,
+    /// Cost in USD; None when the model has no pricing configured
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    /// Workspace the session was working in
+    #[serde(default)]
+    pub workspace_path: Option<String>,
 }
 
 /// Aggregated token statistics for a model
@@ -37,6 +43,9 @@ pub struct ModelTokenStats {
     pub session_ids: HashSet<String>,
     pub first_used: Option<DateTime<Utc>>,
     pub last_used: Option<DateTime<Utc>>,
+    /// Spend in USD from priced requests
+    #[serde(default)]
+    pub total_cost_usd: f64,
 }
 
 impl Default for ModelTokenStats {
@@ -52,6 +61,7 @@ impl Default for ModelTokenStats {
             session_ids: HashSet::new(),
             first_used: None,
             last_used: None,
+            total_cost_usd: 0.0,
         }
     }
 }
@@ -66,10 +76,27 @@ pub struct SessionTokenStats {
     pub total_cached: u32,
     pub total_tokens: u32,
     pub request_count: u32,
+    /// Share of input tokens served from the prompt cache (0.0 - 1.0)
+    #[serde(default)]
+    pub cache_hit_rate: f64,
+    /// Spend in USD from priced requests
+    #[serde(default)]
+    pub total_cost_usd: f64,
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
 }
 
+impl SessionTokenStats {
+    /// Recompute `cache_hit_rate` from the current totals
+    pub fn update_cache_hit_rate(&mut self) {
+        self.cache_hit_rate = if self.total_input == 0 {
+            0.0
+        } else {
+            self.total_cached as f64 / self.total_input as f64
+        };
+    }
+}
+
 /// Time range for querying statistics
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum TimeRange {
@@ -106,4 +133,6 @@ pub struct TokenUsageSummary {
     pub by_model: HashMap<String, ModelTokenStats>,
     pub by_session: HashMap<String, SessionTokenStats>,
     pub record_count: usize,
+    #[serde(default)]
+    pub total_cost_usd: f64,
 }
@@ -193,6 +193,9 @@ pub struct WorkspaceInfo {
     pub opened_at: chrono::DateTime<chrono::Utc>,
     #[serde(rename = "lastAccessed")]
     pub last_accessed: chrono::DateTime<chrono::Utc>,
+    /// Times the workspace was opened by the user.
+    #[serde(rename = "openCount", default)]
+    pub open_count: u32,
     pub description: Option<String>,
     pub tags: Vec<String>,
     pub statistics: Option<WorkspaceStatistics>,
@@ -339,6 +342,7 @@ impl WorkspaceInfo {
             languages: Vec::new(),
             opened_at: now,
             last_accessed: now,
+            open_count: 0,
             description: None,
             tags: Vec::new(),
             statistics: None,
@@ -699,6 +703,8 @@ pub struct WorkspaceManager {
     current_workspace_id: Option<String>,
     recent_workspaces: Vec<String>,
     recent_assistant_workspaces: Vec<String>,
+    /// Workspaces pinned to the top of the recents list, in pin order.
+    pinned_workspaces: Vec<String>,
     max_recent_workspaces: usize,
 }
 
@@ -729,6 +735,7 @@ impl WorkspaceManager {
             current_workspace_id: None,
             recent_workspaces: Vec::new(),
             recent_assistant_workspaces: Vec::new(),
+            pinned_workspaces: Vec::new(),
             max_recent_workspaces: config.max_recent_workspaces,
         }
     }
@@ -783,6 +790,11 @@ impl WorkspaceManager {
                 *rid = new_id.clone();
             }
         }
+        for pid in &mut self.pinned_workspaces {
+            if pid.as_str() == old_id {
+                *pid = new_id.clone();
+            }
+        }
         Ok(())
     }
 
@@ -817,6 +829,12 @@ impl WorkspaceManager {
                 self.opened_workspace_ids.retain(|x| x != &old_id);
                 self.recent_workspaces.retain(|x| x != &old_id);
                 self.recent_assistant_workspaces.retain(|x| x != &old_id);
+                if self.pinned_workspaces.contains(&old_id) {
+                    self.pinned_workspaces.retain(|x| x != &old_id);
+                    if !self.pinned_workspaces.contains(&new_id) {
+                        self.pinned_workspaces.push(new_id.clone());
+                    }
+                }
                 if self.current_workspace_id.as_deref() == Some(old_id.as_str()) {
                     self.current_workspace_id = Some(new_id.clone());
                 }
@@ -986,6 +1004,9 @@ impl WorkspaceManager {
                 workspace.load_worktree().await;
             }
             self.ensure_workspace_open(&workspace_id);
+            if options.add_to_recent {
+                self.record_workspace_open(&workspace_id);
+            }
             if options.auto_set_current {
                 self.set_current_workspace_with_recent_policy(
                     workspace_id.clone(),
@@ -1005,9 +1026,11 @@ impl WorkspaceManager {
         let workspace = WorkspaceInfo::new(path, options.clone()).await?;
         let workspace_id = workspace.id.clone();
 
-        self.workspaces
-            .insert(workspace_id.clone(), workspace.clone());
+        self.workspaces.insert(workspace_id.clone(), workspace);
         self.ensure_workspace_open(&workspace_id);
+        if options.add_to_recent {
+            self.record_workspace_open(&workspace_id);
+        }
         if options.auto_set_current {
             self.set_current_workspace_with_recent_policy(
                 workspace_id.clone(),
@@ -1017,7 +1040,12 @@ impl WorkspaceManager {
             self.touch_workspace_access(&workspace_id, options.add_to_recent);
         }
 
-        Ok(workspace)
+        self.workspaces.get(&workspace_id).cloned().ok_or_else(|| {
+            BitFunError::service(format!(
+                "Workspace '{}' disappeared after opening it",
+                workspace_id
+            ))
+        })
     }
 
     /// Closes the current workspace.
@@ -1199,6 +1227,7 @@ impl WorkspaceManager {
             self.recent_workspaces.retain(|id| id != workspace_id);
             self.recent_assistant_workspaces
                 .retain(|id| id != workspace_id);
+            self.pinned_workspaces.retain(|id| id != workspace_id);
 
             Ok(())
         } else {
@@ -1250,6 +1279,46 @@ impl WorkspaceManager {
         }
     }
 
+    fn record_workspace_open(&mut self, workspace_id: &str) {
+        if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
+            workspace.open_count = workspace.open_count.saturating_add(1);
+        }
+    }
+
+    /// Pins or unpins a workspace in the recents list.
+    pub fn set_workspace_pinned(&mut self, workspace_id: &str, pinned: bool) -> BitFunResult<()> {
+        if !self.workspaces.contains_key(workspace_id) {
+            return Err(BitFunError::service(format!(
+                "Workspace not found: {}",
+                workspace_id
+            )));
+        }
+
+        let is_pinned = self.pinned_workspaces.iter().any(|id| id == workspace_id);
+        if pinned && !is_pinned {
+            self.pinned_workspaces.push(workspace_id.to_string());
+        } else if !pinned {
+            self.pinned_workspaces.retain(|id| id != workspace_id);
+        }
+        Ok(())
+    }
+
+    /// Removes a workspace from the recents and pinned lists, keeping its
+    /// record. Returns whether it was listed.
+    pub fn remove_from_recent(&mut self, workspace_id: &str) -> bool {
+        let before = self.recent_workspaces.len()
+            + self.recent_assistant_workspaces.len()
+            + self.pinned_workspaces.len();
+        self.recent_workspaces.retain(|id| id != workspace_id);
+        self.recent_assistant_workspaces
+            .retain(|id| id != workspace_id);
+        self.pinned_workspaces.retain(|id| id != workspace_id);
+        let after = self.recent_workspaces.len()
+            + self.recent_assistant_workspaces.len()
+            + self.pinned_workspaces.len();
+        before != after
+    }
+
     fn touch_workspace_access(&mut self, workspace_id: &str, add_to_recent: bool) {
         if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
             workspace.touch();
@@ -1370,6 +1439,19 @@ impl WorkspaceManager {
             .collect();
     }
 
+    /// Returns a reference to the pinned-workspaces list.
+    pub fn get_pinned_workspaces(&self) -> &Vec<String> {
+        &self.pinned_workspaces
+    }
+
+    /// Sets the pinned-workspaces list.
+    pub fn set_pinned_workspaces(&mut self, pinned: Vec<String>) {
+        self.pinned_workspaces = pinned
+            .into_iter()
+            .filter(|id| self.workspaces.contains_key(id))
+            .collect();
+    }
+
     /// Returns a reference to the recent assistant-workspaces list.
     pub fn get_recent_assistant_workspaces(&self) -> &Vec<String> {
         &self.recent_assistant_workspaces
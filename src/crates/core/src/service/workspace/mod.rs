@@ -25,7 +25,8 @@ pub use manager::{
 pub use provider::{WorkspaceCleanupResult, WorkspaceProvider, WorkspaceSystemSummary};
 pub use service::{
     get_global_workspace_service, set_global_workspace_service, BatchImportResult,
-    BatchRemoveResult, WorkspaceCreateOptions, WorkspaceExport, WorkspaceHealthStatus,
+    BatchRemoveResult, RecentSessionInfo, RecentWorkspaceEntry, RecentWorkspaceOpenResult,
+    WorkspaceCreateOptions, WorkspaceExport, WorkspaceGitState, WorkspaceHealthStatus,
     WorkspaceIdentityChangedEvent, WorkspaceImportResult, WorkspaceInfoUpdates,
-    WorkspaceQuickSummary, WorkspaceService,
+    WorkspaceQuickStats, WorkspaceQuickSummary, WorkspaceService, RECENT_WORKSPACES_CHANGED_EVENT,
 };
@@ -7,12 +7,18 @@ use super::manager::{
     WorkspaceManagerConfig, WorkspaceManagerStatistics, WorkspaceOpenOptions, WorkspaceStatus,
     WorkspaceSummary, WorkspaceType,
 };
+use crate::agentic::persistence::PersistenceManager;
+use crate::infrastructure::events::{emit_global_event, BackendEvent};
 use crate::infrastructure::storage::{PersistenceService, StorageOptions};
 use crate::infrastructure::{try_get_path_manager_arc, PathManager};
 use crate::service::bootstrap::initialize_workspace_persona_files;
+use crate::service::code_index::get_global_code_index_service;
+use crate::service::config::GlobalConfigManager;
+use crate::service::git::GitService;
 use crate::service::remote_ssh::workspace_state::local_workspace_roots_equal;
+use crate::service::session::{SessionMetadata, SessionStatus};
 use crate::util::errors::*;
-use log::{info, warn};
+use log::{debug, info, warn};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -21,6 +27,9 @@ use std::sync::Arc;
 use tokio::fs;
 use tokio::sync::RwLock;
 
+/// Emitted with the new recents list when it changes.
+pub const RECENT_WORKSPACES_CHANGED_EVENT: &str = "workspace-recents-changed";
+
 /// Workspace service.
 pub struct WorkspaceService {
     manager: Arc<RwLock<WorkspaceManager>>,
@@ -175,6 +184,10 @@ impl WorkspaceService {
             if let Err(e) = self.save_workspace_data().await {
                 warn!("Failed to save workspace data after opening: {}", e);
             }
+            self.sync_workspace_config().await;
+            if options.add_to_recent {
+                self.emit_recent_workspaces_changed().await;
+            }
         }
 
         result
@@ -269,6 +282,7 @@ impl WorkspaceService {
             if let Err(e) = self.save_workspace_data().await {
                 warn!("Failed to save workspace data after closing: {}", e);
             }
+            self.sync_workspace_config().await;
         }
 
         result
@@ -276,15 +290,22 @@ impl WorkspaceService {
 
     /// Closes the specified workspace.
     pub async fn close_workspace(&self, workspace_id: &str) -> BitFunResult<()> {
-        let result = {
+        let (result, root_path) = {
             let mut manager = self.manager.write().await;
-            manager.close_workspace(workspace_id)
+            let root_path = manager
+                .get_workspace(workspace_id)
+                .map(|workspace| workspace.root_path.clone());
+            (manager.close_workspace(workspace_id), root_path)
         };
 
         if result.is_ok() {
+            if let Some(root_path) = root_path {
+                get_global_code_index_service().close_workspace(&root_path);
+            }
             if let Err(e) = self.save_workspace_data().await {
                 warn!("Failed to save workspace data after closing: {}", e);
             }
+            self.sync_workspace_config().await;
         }
 
         result
@@ -304,6 +325,7 @@ impl WorkspaceService {
                     e
                 );
             }
+            self.sync_workspace_config().await;
         }
 
         result
@@ -358,6 +380,19 @@ impl WorkspaceService {
         self.set_active_workspace(workspace_id).await
     }
 
+    /// Applies the current workspace's `.bitfun/config.json` overrides to the
+    /// config service (or clears them when no local workspace is active).
+    async fn sync_workspace_config(&self) {
+        let root = self
+            .get_current_workspace()
+            .await
+            .filter(|workspace| workspace.workspace_kind != WorkspaceKind::Remote)
+            .map(|workspace| workspace.root_path);
+        if let Err(e) = GlobalConfigManager::set_workspace(root.as_deref()).await {
+            warn!("Failed to apply workspace config: {}", e);
+        }
+    }
+
     /// Returns the current workspace.
     pub async fn get_current_workspace(&self) -> Option<WorkspaceInfo> {
         let manager = self.manager.read().await;
@@ -518,6 +553,200 @@ impl WorkspaceService {
         recent_workspaces
     }
 
+    /// Returns the recents list for the start screen: pinned workspaces first,
+    /// then recent ones. Workspaces whose folder is gone are flagged.
+    pub async fn list_recent_workspaces(&self) -> Vec<RecentWorkspaceEntry> {
+        let workspaces: Vec<(WorkspaceInfo, bool)> = {
+            let manager = self.manager.read().await;
+            let pinned = manager.get_pinned_workspaces();
+            pinned
+                .iter()
+                .map(|id| (id, true))
+                .chain(
+                    manager
+                        .get_recent_workspaces()
+                        .iter()
+                        .filter(|id| !pinned.contains(id))
+                        .map(|id| (id, false)),
+                )
+                .filter_map(|(id, pinned)| {
+                    manager
+                        .get_workspaces()
+                        .get(id)
+                        .map(|workspace| (workspace.clone(), pinned))
+                })
+                .collect()
+        };
+
+        let mut entries = Vec::with_capacity(workspaces.len());
+        for (workspace, pinned) in workspaces {
+            let exists = workspace.is_valid().await;
+            entries.push(RecentWorkspaceEntry {
+                workspace,
+                pinned,
+                exists,
+            });
+        }
+        entries
+    }
+
+    /// Pins or unpins a workspace in the recents list.
+    pub async fn set_workspace_pinned(&self, workspace_id: &str, pinned: bool) -> BitFunResult<()> {
+        {
+            let mut manager = self.manager.write().await;
+            manager.set_workspace_pinned(workspace_id, pinned)?;
+        }
+
+        if let Err(e) = self.save_workspace_data().await {
+            warn!("Failed to save workspace data after pinning: {}", e);
+        }
+        self.emit_recent_workspaces_changed().await;
+        Ok(())
+    }
+
+    /// Removes a workspace from the recents list without forgetting it.
+    pub async fn remove_from_recent_workspaces(&self, workspace_id: &str) -> BitFunResult<()> {
+        let removed = {
+            let mut manager = self.manager.write().await;
+            manager.remove_from_recent(workspace_id)
+        };
+        if !removed {
+            return Ok(());
+        }
+
+        if let Err(e) = self.save_workspace_data().await {
+            warn!(
+                "Failed to save workspace data after removing from recents: {}",
+                e
+            );
+        }
+        self.emit_recent_workspaces_changed().await;
+        Ok(())
+    }
+
+    /// Opens a workspace from the recents list and returns the session that
+    /// was active there last, so the caller can restore it.
+    pub async fn open_recent_workspace(
+        &self,
+        workspace_id: &str,
+    ) -> BitFunResult<RecentWorkspaceOpenResult> {
+        let workspace = self.get_workspace(workspace_id).await.ok_or_else(|| {
+            BitFunError::service(format!("Workspace not found: {}", workspace_id))
+        })?;
+        if workspace.workspace_kind == WorkspaceKind::Remote {
+            return Err(BitFunError::service(
+                "Remote workspaces are reopened through their SSH connection".to_string(),
+            ));
+        }
+        if !workspace.is_valid().await {
+            return Err(BitFunError::service(format!(
+                "Workspace folder no longer exists: {}",
+                workspace.root_path.display()
+            )));
+        }
+
+        let workspace = self.open_workspace(workspace.root_path.clone()).await?;
+        let last_session = self.last_session(&workspace).await;
+        Ok(RecentWorkspaceOpenResult {
+            workspace,
+            last_session_id: last_session.map(|session| session.session_id),
+        })
+    }
+
+    /// Quick facts about a workspace for the recents list: the last session
+    /// and the git working-tree state.
+    pub async fn get_workspace_quick_stats(
+        &self,
+        workspace_id: &str,
+    ) -> BitFunResult<WorkspaceQuickStats> {
+        let workspace = self.get_workspace(workspace_id).await.ok_or_else(|| {
+            BitFunError::service(format!("Workspace not found: {}", workspace_id))
+        })?;
+
+        let exists = workspace.is_valid().await;
+        if !exists || workspace.workspace_kind == WorkspaceKind::Remote {
+            return Ok(WorkspaceQuickStats {
+                workspace_id: workspace.id,
+                exists,
+                last_session: None,
+                git: None,
+            });
+        }
+
+        let last_session = self
+            .last_session(&workspace)
+            .await
+            .map(|session| RecentSessionInfo {
+                session_id: session.session_id,
+                title: session.session_name,
+                last_active_at: session.last_active_at,
+            });
+
+        let git = match GitService::is_repository(&workspace.root_path).await {
+            Ok(true) => match GitService::get_status(&workspace.root_path).await {
+                Ok(status) => {
+                    let changed_files =
+                        status.staged.len() + status.unstaged.len() + status.untracked.len();
+                    Some(WorkspaceGitState {
+                        branch: status.current_branch,
+                        dirty: changed_files > 0,
+                        changed_files,
+                    })
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to read git status for workspace quick stats: path={}, error={}",
+                        workspace.root_path.display(),
+                        e
+                    );
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        Ok(WorkspaceQuickStats {
+            workspace_id: workspace.id,
+            exists,
+            last_session,
+            git,
+        })
+    }
+
+    /// Most recently active, non-archived session of a local workspace.
+    async fn last_session(&self, workspace: &WorkspaceInfo) -> Option<SessionMetadata> {
+        let persistence = PersistenceManager::new(self.path_manager.clone()).ok()?;
+        match persistence
+            .list_session_metadata(&workspace.root_path)
+            .await
+        {
+            Ok(sessions) => sessions
+                .into_iter()
+                .filter(|session| session.status != SessionStatus::Archived)
+                .max_by_key(|session| session.last_active_at),
+            Err(e) => {
+                warn!(
+                    "Failed to list sessions for workspace: path={}, error={}",
+                    workspace.root_path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    async fn emit_recent_workspaces_changed(&self) {
+        let recent = self.list_recent_workspaces().await;
+        if let Err(e) = emit_global_event(BackendEvent::Custom {
+            event_name: RECENT_WORKSPACES_CHANGED_EVENT.to_string(),
+            payload: serde_json::json!({ "recent": recent }),
+        })
+        .await
+        {
+            debug!("Failed to emit recent workspaces event: {}", e);
+        }
+    }
+
     /// Searches workspaces.
     pub async fn search_workspaces(&self, query: &str) -> Vec<WorkspaceSummary> {
         let manager = self.manager.read().await;
@@ -980,6 +1209,7 @@ impl WorkspaceService {
             current_workspace_id: manager.get_current_workspace().map(|w| w.id.clone()),
             recent_workspaces: manager.get_recent_workspaces().clone(),
             recent_assistant_workspaces: manager.get_recent_assistant_workspaces().clone(),
+            pinned_workspaces: manager.get_pinned_workspaces().clone(),
             saved_at: chrono::Utc::now(),
         };
 
@@ -1007,6 +1237,7 @@ impl WorkspaceService {
             manager.set_opened_workspace_ids(data.opened_workspace_ids);
             manager.set_recent_workspaces(data.recent_workspaces);
             manager.set_recent_assistant_workspaces(data.recent_assistant_workspaces);
+            manager.set_pinned_workspaces(data.pinned_workspaces);
             let id_remap = manager.migrate_local_workspace_ids_to_stable_storage();
 
             if let Some(raw_current) = data.current_workspace_id {
@@ -1051,6 +1282,7 @@ impl WorkspaceService {
             manager.set_opened_workspace_ids(data.opened_workspace_ids.clone());
             manager.set_recent_workspaces(data.recent_workspaces);
             manager.set_recent_assistant_workspaces(data.recent_assistant_workspaces);
+            manager.set_pinned_workspaces(data.pinned_workspaces);
             let id_remap = manager.migrate_local_workspace_ids_to_stable_storage();
 
             let raw_current = data
@@ -1580,6 +1812,53 @@ pub struct WorkspaceQuickSummary {
     pub workspace_types: std::collections::HashMap<WorkspaceType, usize>,
 }
 
+/// Recents list entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspaceEntry {
+    pub workspace: WorkspaceInfo,
+    pub pinned: bool,
+    /// Whether the workspace folder still exists.
+    pub exists: bool,
+}
+
+/// Result of opening a workspace from the recents list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspaceOpenResult {
+    pub workspace: WorkspaceInfo,
+    /// Session that was active last in the workspace.
+    pub last_session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentSessionInfo {
+    pub session_id: String,
+    pub title: String,
+    /// Unix timestamp in milliseconds.
+    pub last_active_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceGitState {
+    pub branch: String,
+    pub dirty: bool,
+    pub changed_files: usize,
+}
+
+/// Per-workspace facts shown in the recents list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceQuickStats {
+    pub workspace_id: String,
+    pub exists: bool,
+    pub last_session: Option<RecentSessionInfo>,
+    /// `None` outside git repositories.
+    pub git: Option<WorkspaceGitState>,
+}
+
 /// Workspace persistence data.
 #[derive(Debug, Serialize, Deserialize)]
 struct WorkspacePersistenceData {
@@ -1591,6 +1870,8 @@ struct WorkspacePersistenceData {
     pub recent_workspaces: Vec<String>,
     #[serde(default)]
     pub recent_assistant_workspaces: Vec<String>,
+    #[serde(default)]
+    pub pinned_workspaces: Vec<String>,
     pub saved_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -0,0 +1,67 @@
+//! Environment variable references in configuration values.
+//!
+//! Supports `${VAR}` and the `${env:VAR}` form used by MCP configs written for Cursor and
+//! VS Code. Unset variables expand to an empty string; text without a closing `}` is kept
+//! as-is.
+
+/// Expand `${VAR}` / `${env:VAR}` references from the process environment.
+pub fn expand_env_vars(input: &str) -> String {
+    expand_with(input, |name| std::env::var(name).ok())
+}
+
+fn expand_with(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+
+        let reference = &after[..end];
+        let name = reference.strip_prefix("env:").unwrap_or(reference).trim();
+        if name.is_empty() {
+            out.push_str(&rest[start..start + 2 + end + 1]);
+        } else {
+            out.push_str(&lookup(name).unwrap_or_default());
+        }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_with;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "ORG_ID" => Some("org-123".to_string()),
+            "TOKEN" => Some("s3cret".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_both_reference_forms() {
+        assert_eq!(expand_with("${ORG_ID}", lookup), "org-123");
+        assert_eq!(expand_with("Bearer ${env:TOKEN}", lookup), "Bearer s3cret");
+        assert_eq!(
+            expand_with("${ORG_ID}/${TOKEN}-x", lookup),
+            "org-123/s3cret-x"
+        );
+    }
+
+    #[test]
+    fn leaves_non_references_alone() {
+        assert_eq!(expand_with("plain", lookup), "plain");
+        assert_eq!(expand_with("${MISSING}", lookup), "");
+        assert_eq!(expand_with("cost ${", lookup), "cost ${");
+        assert_eq!(expand_with("${}", lookup), "${}");
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Provide unified error types and handling for the whole application
 
+use crate::util::i18n::{self, LocalizedText};
 use serde::Serialize;
 use thiserror::Error;
 
@@ -20,6 +21,14 @@ pub enum BitFunError {
     #[error("AI client error: {0}")]
     AIClient(String),
 
+    /// AI provider request failure; `status` is the HTTP status, `None` when no response
+    /// arrived (connection or transport failure)
+    #[error("AI client error: {message}")]
+    AIRequest {
+        status: Option<u16>,
+        message: String,
+    },
+
     #[error("Session error: {0}")]
     Session(String),
 
@@ -51,6 +60,9 @@ pub enum BitFunError {
     #[error("MCP error: {0}")]
     MCPError(String),
 
+    #[error("MCP connection error: {0}")]
+    MCPConnection(String),
+
     #[error("Process error: {0}")]
     ProcessError(String),
 
@@ -71,6 +83,13 @@ pub enum BitFunError {
 
     #[error("Cancelled: {0}")]
     Cancelled(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// Network access refused because offline mode is on
+    #[error("Offline: {0}")]
+    Offline(String),
 }
 
 pub type BitFunResult<T> = Result<T, BitFunError>;
@@ -129,6 +148,44 @@ impl BitFunError {
         Self::AIClient(msg.into())
     }
 
+    /// AI provider answered with a non-success HTTP status
+    pub fn ai_status<T: Into<String>>(status: u16, msg: T) -> Self {
+        Self::AIRequest {
+            status: Some(status),
+            message: msg.into(),
+        }
+    }
+
+    /// AI provider could not be reached or the connection dropped before a response
+    pub fn ai_network<T: Into<String>>(msg: T) -> Self {
+        Self::AIRequest {
+            status: None,
+            message: msg.into(),
+        }
+    }
+
+    /// Recovers a `BitFunError` raised inside the AI client; other failures become `AIClient`
+    pub fn from_ai_error(error: anyhow::Error) -> Self {
+        match error.downcast::<BitFunError>() {
+            Ok(error) => error,
+            Err(error) => Self::AIClient(error.to_string()),
+        }
+    }
+
+    /// MCP failure, classified as a connection error when the message says so
+    pub fn mcp<T: Into<String>>(msg: T) -> Self {
+        let msg = msg.into();
+        if is_connection_failure(&msg) {
+            Self::MCPConnection(msg)
+        } else {
+            Self::MCPError(msg)
+        }
+    }
+
+    pub fn mcp_connection<T: Into<String>>(msg: T) -> Self {
+        Self::MCPConnection(msg.into())
+    }
+
     pub fn parse<T: Into<String>>(msg: T) -> Self {
         Self::Deserialization(msg.into())
     }
@@ -155,6 +212,198 @@ impl BitFunError {
     pub fn cancelled<T: Into<String>>(msg: T) -> Self {
         Self::Cancelled(msg.into())
     }
+
+    pub fn conflict<T: Into<String>>(msg: T) -> Self {
+        Self::Conflict(msg.into())
+    }
+}
+
+// ============ Classification ============
+
+impl BitFunError {
+    /// Stable machine-readable code, e.g. `ai_rate_limited` or `mcp_connection`
+    pub fn error_code(&self) -> &'static str {
+        self.classify().0
+    }
+
+    /// Whether the same operation may succeed if tried again later
+    pub fn is_retryable(&self) -> bool {
+        self.classify().1
+    }
+
+    /// Short explanation for end users in the current locale; falls back to the full error text
+    pub fn user_message(&self) -> String {
+        self.localized_message().text
+    }
+
+    /// [`Self::user_message`] with its translation key, so clients can render it in their
+    /// own locale
+    pub fn localized_message(&self) -> LocalizedText {
+        let key = match self.error_code() {
+            "ai_rate_limited" => "error-ai-rate-limited",
+            "ai_auth" => "error-ai-auth",
+            "ai_server_error" => "error-ai-server",
+            "ai_timeout" => "error-ai-timeout",
+            "ai_network" => "error-ai-network",
+            "mcp_connection" => "error-mcp-connection",
+            "offline" => "error-offline",
+            "timeout" => "error-operation-timeout",
+            "cancelled" => "error-cancelled",
+            _ => {
+                let message = self.to_string();
+                return i18n::localize("error-generic", &[("message", message.as_str())]);
+            }
+        };
+        i18n::localize(key, &[])
+    }
+
+    fn classify(&self) -> (&'static str, bool) {
+        match self {
+            Self::Service(_) => ("service", false),
+            Self::Agent(_) => ("agent", false),
+            Self::Tool(_) => ("tool", false),
+            Self::AIClient(msg) => classify_ai_message(msg),
+            Self::AIRequest {
+                status: Some(status),
+                ..
+            } => classify_ai_status(*status),
+            Self::AIRequest { status: None, .. } => ("ai_network", true),
+            Self::Session(_) => ("session", false),
+            Self::Workspace(_) => ("workspace", false),
+            Self::Validation(_) => ("validation", false),
+            Self::Io(e) => (
+                "io",
+                matches!(
+                    e.kind(),
+                    std::io::ErrorKind::TimedOut
+                        | std::io::ErrorKind::Interrupted
+                        | std::io::ErrorKind::ConnectionReset
+                        | std::io::ErrorKind::ConnectionAborted
+                        | std::io::ErrorKind::ConnectionRefused
+                        | std::io::ErrorKind::BrokenPipe
+                ),
+            ),
+            Self::Serialization(_) => ("serialization", false),
+            Self::Http(e) => (
+                "http",
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status()
+                        .is_some_and(|status| is_transient_http_status(status.as_u16())),
+            ),
+            Self::Other(_) => ("other", false),
+            Self::Semaphore(_) => ("semaphore", false),
+            Self::MCPError(msg) if is_connection_failure(msg) => ("mcp_connection", true),
+            Self::MCPError(_) => ("mcp_error", false),
+            Self::MCPConnection(_) => ("mcp_connection", true),
+            Self::ProcessError(_) => ("process", false),
+            Self::NotFound(_) => ("not_found", false),
+            Self::NotImplemented(_) => ("not_implemented", false),
+            Self::Timeout(_) => ("timeout", true),
+            Self::Configuration(_) => ("configuration", false),
+            Self::Deserialization(_) => ("deserialization", false),
+            Self::Cancelled(_) => ("cancelled", false),
+            Self::Conflict(_) => ("conflict", false),
+            Self::Offline(_) => ("offline", false),
+        }
+    }
+}
+
+/// Rate limits, overload and transient gateway failures
+fn is_transient_http_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502 | 503 | 504 | 529)
+}
+
+fn classify_ai_status(status: u16) -> (&'static str, bool) {
+    match status {
+        429 => ("ai_rate_limited", true),
+        401 | 403 | 407 => ("ai_auth", false),
+        408 => ("ai_timeout", true),
+        _ if is_transient_http_status(status) => ("ai_server_error", true),
+        500..=599 => ("ai_server_error", false),
+        _ => ("ai_request_rejected", false),
+    }
+}
+
+/// Classifies AI failures that only carry text (stream errors, wrapped provider errors).
+/// Permanent failures win over transient keywords.
+fn classify_ai_message(message: &str) -> (&'static str, bool) {
+    const AUTH_KEYWORDS: &[&str] = &[
+        "invalid api key",
+        "unauthorized",
+        "forbidden",
+        "proxy authentication required",
+        "client error 401",
+        "client error 403",
+    ];
+    const REJECTED_KEYWORDS: &[&str] = &[
+        "model not found",
+        "unsupported model",
+        "invalid request",
+        "bad request",
+        "prompt is too long",
+        "content policy",
+        "client error 400",
+        "client error 404",
+        "client error 422",
+        "sse parsing error",
+        "schema error",
+        "unknown api format",
+    ];
+    const RATE_LIMIT_KEYWORDS: &[&str] = &["rate limit", "too many requests", "429"];
+    const TIMEOUT_KEYWORDS: &[&str] = &["timeout", "etimedout"];
+    const NETWORK_KEYWORDS: &[&str] = &[
+        "transport error",
+        "error decoding response body",
+        "stream closed before response completed",
+        "stream processing error",
+        "sse stream error",
+        "sse error",
+        "connection reset",
+        "broken pipe",
+        "unexpected eof",
+        "connection refused",
+        "temporarily unavailable",
+        "proxy",
+        "tunnel",
+        "dns",
+        "network",
+        "econnreset",
+        "econnrefused",
+    ];
+
+    let msg = message.to_lowercase();
+    let contains_any = |keywords: &[&str]| keywords.iter().any(|k| msg.contains(k));
+
+    if contains_any(AUTH_KEYWORDS) {
+        ("ai_auth", false)
+    } else if contains_any(REJECTED_KEYWORDS) {
+        ("ai_request_rejected", false)
+    } else if contains_any(RATE_LIMIT_KEYWORDS) {
+        ("ai_rate_limited", true)
+    } else if contains_any(TIMEOUT_KEYWORDS) {
+        ("ai_timeout", true)
+    } else if contains_any(NETWORK_KEYWORDS) {
+        ("ai_network", true)
+    } else {
+        ("ai_error", false)
+    }
+}
+
+/// Connection-level MCP failures: the server went away or was never reached
+fn is_connection_failure(message: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "connection",
+        "transport",
+        "channel closed",
+        "disconnected",
+        "broken pipe",
+        "not initialized",
+        "econnrefused",
+        "econnreset",
+    ];
+    let msg = message.to_lowercase();
+    KEYWORDS.iter().any(|k| msg.contains(k))
 }
 
 impl From<BitFunError> for String {
@@ -180,3 +429,144 @@ impl From<tokio::sync::AcquireError> for BitFunError {
         BitFunError::Semaphore(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::i18n::LocaleId;
+    use crate::util::i18n::translate_in;
+
+    fn classification(error: BitFunError) -> (&'static str, bool) {
+        (error.error_code(), error.is_retryable())
+    }
+
+    #[test]
+    fn classifies_ai_http_statuses() {
+        let cases = [
+            (429, "ai_rate_limited", true),
+            (401, "ai_auth", false),
+            (403, "ai_auth", false),
+            (408, "ai_timeout", true),
+            (500, "ai_server_error", true),
+            (503, "ai_server_error", true),
+            (529, "ai_server_error", true),
+            (501, "ai_server_error", false),
+            (400, "ai_request_rejected", false),
+            (404, "ai_request_rejected", false),
+            (422, "ai_request_rejected", false),
+        ];
+        for (status, code, retryable) in cases {
+            assert_eq!(
+                classification(BitFunError::ai_status(status, "provider error")),
+                (code, retryable),
+                "status {}",
+                status
+            );
+        }
+        assert_eq!(
+            classification(BitFunError::ai_network("connection failed")),
+            ("ai_network", true)
+        );
+    }
+
+    #[test]
+    fn classifies_ai_messages() {
+        let cases = [
+            (
+                "Error: Stream processing error: SSE Error: Transport Error: Error decoding response body",
+                "ai_network",
+                true,
+            ),
+            ("OpenAI Streaming API client error 401: unauthorized", "ai_auth", false),
+            (
+                "Stream processing error: SSE data schema error: missing field choices",
+                "ai_request_rejected",
+                false,
+            ),
+            ("Anthropic error 429: Too Many Requests", "ai_rate_limited", true),
+            (
+                "Stream data timeout (no data received for 600 seconds)",
+                "ai_timeout",
+                true,
+            ),
+            ("Failed to get model ID: no default model", "ai_error", false),
+        ];
+        for (message, code, retryable) in cases {
+            assert_eq!(
+                classification(BitFunError::ai(message)),
+                (code, retryable),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn classifies_mcp_and_other_variants() {
+        assert_eq!(
+            classification(BitFunError::mcp("MCP tools/call failed: Transport closed")),
+            ("mcp_connection", true)
+        );
+        assert!(matches!(
+            BitFunError::mcp("MCP tools/call failed: Transport closed"),
+            BitFunError::MCPConnection(_)
+        ));
+        assert_eq!(
+            classification(BitFunError::MCPError("Request channel closed".to_string())),
+            ("mcp_connection", true)
+        );
+        assert_eq!(
+            classification(BitFunError::mcp("MCP Error -32602: Invalid params")),
+            ("mcp_error", false)
+        );
+        assert_eq!(
+            classification(BitFunError::Timeout("Request timeout".to_string())),
+            ("timeout", true)
+        );
+        assert_eq!(
+            classification(BitFunError::validation("bad input")),
+            ("validation", false)
+        );
+        assert_eq!(
+            classification(BitFunError::Offline("offline mode is on".to_string())),
+            ("offline", false)
+        );
+        assert_eq!(
+            classification(BitFunError::Io(std::io::Error::from(
+                std::io::ErrorKind::ConnectionReset
+            ))),
+            ("io", true)
+        );
+        assert_eq!(
+            classification(BitFunError::Io(std::io::Error::from(
+                std::io::ErrorKind::NotFound
+            ))),
+            ("io", false)
+        );
+    }
+
+    #[test]
+    fn ai_client_errors_survive_anyhow() {
+        let wrapped: anyhow::Error = BitFunError::ai_status(429, "rate limited").into();
+        let recovered = BitFunError::from_ai_error(wrapped);
+        assert_eq!(recovered.error_code(), "ai_rate_limited");
+        assert_eq!(recovered.to_string(), "AI client error: rate limited");
+
+        let plain = BitFunError::from_ai_error(anyhow::anyhow!("unexpected EOF"));
+        assert!(matches!(plain, BitFunError::AIClient(_)));
+        assert!(plain.is_retryable());
+    }
+
+    #[test]
+    fn user_message_explains_known_codes() {
+        let auth = BitFunError::ai_status(401, "client error 401").localized_message();
+        assert_eq!(auth.key, "error-ai-auth");
+        assert!(translate_in(&LocaleId::EnUS, &auth.key, &[]).contains("API key"));
+
+        let validation = BitFunError::validation("name is empty");
+        let localized = validation.localized_message();
+        assert_eq!(localized.key, "error-generic");
+        assert_eq!(localized.args["message"], validation.to_string());
+        assert_eq!(validation.user_message(), validation.to_string());
+    }
+}
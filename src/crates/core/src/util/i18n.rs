@@ -0,0 +1,239 @@
+//! Lightweight synchronous translation
+//!
+//! Renders user-facing backend text (error explanations, permission prompts, greetings, CLI
+//! status) from the Fluent resources in `locales/`. Unlike `I18nService`, lookups don't need
+//! the async runtime, so they work from plain functions like `BitFunError::user_message`.
+//! The locale is process-wide: it follows the OS until `I18nService` applies the configured
+//! one.
+//!
+//! Payloads sent to frontends carry a [`LocalizedText`] so clients can re-render the message
+//! in their own locale.
+
+use crate::service::i18n::LocaleId;
+pub use bitfun_events::LocalizedText;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use log::warn;
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+type Bundle = FluentBundle<FluentResource>;
+
+const RESOURCES: [(LocaleId, &str, &str); 2] = [
+    (
+        LocaleId::EnUS,
+        "en-US",
+        include_str!("../../locales/en-US.ftl"),
+    ),
+    (
+        LocaleId::ZhCN,
+        "zh-CN",
+        include_str!("../../locales/zh-CN.ftl"),
+    ),
+];
+
+static BUNDLES: LazyLock<HashMap<LocaleId, Bundle>> = LazyLock::new(|| {
+    RESOURCES
+        .into_iter()
+        .filter_map(|(locale, tag, source)| Some((locale, build_bundle(tag, source)?)))
+        .collect()
+});
+
+static OS_LOCALE: LazyLock<LocaleId> = LazyLock::new(detect_os_locale);
+
+static CURRENT_LOCALE: RwLock<Option<LocaleId>> = RwLock::new(None);
+
+fn build_bundle(tag: &str, source: &str) -> Option<Bundle> {
+    let langid: LanguageIdentifier = tag.parse().ok()?;
+    let resource = match FluentResource::try_new(source.to_string()) {
+        Ok(resource) => resource,
+        Err((_, errors)) => {
+            warn!("Invalid Fluent resource for {}: {:?}", tag, errors);
+            return None;
+        }
+    };
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Isolation marks around arguments would leak into terminals and logs
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).ok()?;
+    Some(bundle)
+}
+
+/// Locale used for backend text
+pub fn current_locale() -> LocaleId {
+    CURRENT_LOCALE
+        .read()
+        .ok()
+        .and_then(|locale| locale.clone())
+        .unwrap_or_else(|| OS_LOCALE.clone())
+}
+
+/// Switch backend text to `locale` (called when the configured language changes)
+pub fn set_current_locale(locale: LocaleId) {
+    if let Ok(mut current) = CURRENT_LOCALE.write() {
+        *current = Some(locale);
+    }
+}
+
+/// Locale from the `LC_ALL` / `LC_MESSAGES` / `LANG` environment; English when the OS
+/// language isn't one we ship
+pub fn detect_os_locale() -> LocaleId {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find_map(|value| locale_from_tag(&value))
+        .unwrap_or(LocaleId::EnUS)
+}
+
+/// Maps POSIX or BCP 47 tags (`zh_CN.UTF-8`, `zh-Hans`, `en-GB`) to a supported locale.
+/// `None` for unset values and the `C`/`POSIX` locales.
+fn locale_from_tag(tag: &str) -> Option<LocaleId> {
+    let language = tag
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match language.as_str() {
+        "" | "c" | "posix" => None,
+        "zh" => Some(LocaleId::ZhCN),
+        _ => Some(LocaleId::EnUS),
+    }
+}
+
+/// Renders `key` in the current locale
+pub fn translate(key: &str, args: &[(&str, &str)]) -> String {
+    translate_in(&current_locale(), key, args)
+}
+
+/// Renders `key` in `locale`, falling back to English, then to the key itself
+pub fn translate_in(locale: &LocaleId, key: &str, args: &[(&str, &str)]) -> String {
+    format_message(locale, key, args)
+        .or_else(|| format_message(&LocaleId::EnUS, key, args))
+        .unwrap_or_else(|| {
+            warn!("Missing translation: key={}, locale={}", key, locale);
+            key.to_string()
+        })
+}
+
+/// Renders `key` in the current locale and keeps the key and arguments for clients
+pub fn localize(key: &str, args: &[(&str, &str)]) -> LocalizedText {
+    localize_in(&current_locale(), key, args)
+}
+
+/// [`localize`] for an explicit locale
+pub fn localize_in(locale: &LocaleId, key: &str, args: &[(&str, &str)]) -> LocalizedText {
+    LocalizedText {
+        key: key.to_string(),
+        args: args
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        text: translate_in(locale, key, args),
+    }
+}
+
+fn format_message(locale: &LocaleId, key: &str, args: &[(&str, &str)]) -> Option<String> {
+    let bundle = BUNDLES.get(locale)?;
+    let pattern = bundle.get_message(key)?.value()?;
+
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, *value);
+    }
+
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+    if !errors.is_empty() {
+        warn!(
+            "Translation formatting errors for key '{}': {:?}",
+            key, errors
+        );
+    }
+    Some(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    /// Key namespaces owned by backend code; every literal in them must be translated
+    const KEY_PATTERN: &str = r#""((?:error|permission|welcome|cli)-[a-z0-9-]+)""#;
+
+    fn collect_keys(dir: &Path, pattern: &Regex, keys: &mut BTreeSet<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if path.file_name().is_some_and(|name| name == "target") {
+                    continue;
+                }
+                collect_keys(&path, pattern, keys);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap_or_default();
+                keys.extend(
+                    pattern
+                        .captures_iter(&source)
+                        .map(|captures| captures[1].to_string()),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_referenced_key_exists_in_all_locales() {
+        let pattern = Regex::new(KEY_PATTERN).unwrap();
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut keys = BTreeSet::new();
+        collect_keys(&manifest_dir.join("src"), &pattern, &mut keys);
+        collect_keys(&manifest_dir.join("../../apps"), &pattern, &mut keys);
+        assert!(keys.contains("error-ai-rate-limited"));
+
+        for (locale, tag, _) in RESOURCES {
+            let bundle = BUNDLES
+                .get(&locale)
+                .unwrap_or_else(|| panic!("{} resource failed to load", tag));
+            let missing: Vec<&String> =
+                keys.iter().filter(|key| !bundle.has_message(key)).collect();
+            assert!(missing.is_empty(), "{} is missing {:?}", tag, missing);
+        }
+    }
+
+    #[test]
+    fn renders_arguments_without_isolation_marks() {
+        let text = translate_in(
+            &LocaleId::EnUS,
+            "permission-run-command",
+            &[("command", "cargo test")],
+        );
+        assert_eq!(text, "Allow running `cargo test`?");
+
+        let zh = translate_in(
+            &LocaleId::ZhCN,
+            "permission-run-command",
+            &[("command", "cargo test")],
+        );
+        assert!(zh.contains("`cargo test`"));
+        assert_ne!(zh, text);
+
+        assert_eq!(
+            translate_in(&LocaleId::ZhCN, "no-such-key", &[]),
+            "no-such-key"
+        );
+    }
+
+    #[test]
+    fn maps_os_locale_tags() {
+        assert_eq!(locale_from_tag("zh_CN.UTF-8"), Some(LocaleId::ZhCN));
+        assert_eq!(locale_from_tag("zh-Hans"), Some(LocaleId::ZhCN));
+        assert_eq!(locale_from_tag("en_GB.UTF-8"), Some(LocaleId::EnUS));
+        assert_eq!(locale_from_tag("de_DE"), Some(LocaleId::EnUS));
+        assert_eq!(locale_from_tag("C.UTF-8"), None);
+        assert_eq!(locale_from_tag(""), None);
+    }
+}
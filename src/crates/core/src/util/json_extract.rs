@@ -191,6 +191,79 @@ fn try_repair_json(input: &str) -> Option<String> {
     }
 }
 
+/// Lenient repair of a JSON object that was cut off or sloppily terminated, as happens with
+/// streamed tool-call arguments.
+///
+/// Drops trailing commas before `}`/`]`, closes an unterminated string, fills a dangling
+/// `"key":` with `null`, and closes any containers left open. Returns `None` if the result
+/// still isn't a JSON object.
+pub fn repair_truncated_json(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+
+    let mut out = String::with_capacity(trimmed.len() + 8);
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    for ch in trimmed.chars() {
+        if in_string {
+            out.push(ch);
+            if escape_next {
+                escape_next = false;
+            } else if ch == '\\' {
+                escape_next = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match ch {
+            '"' => in_string = true,
+            '{' => stack.push('}'),
+            '[' => stack.push(']'),
+            '}' | ']' => {
+                if stack.pop() != Some(ch) {
+                    return None;
+                }
+                strip_trailing_comma(&mut out);
+            }
+            _ => {}
+        }
+        out.push(ch);
+    }
+
+    if in_string {
+        if escape_next {
+            out.pop();
+        }
+        out.push('"');
+    }
+    strip_trailing_comma(&mut out);
+    if out.ends_with(':') {
+        out.push_str("null");
+    }
+    while let Some(close) = stack.pop() {
+        strip_trailing_comma(&mut out);
+        out.push(close);
+    }
+
+    match serde_json::from_str::<serde_json::Value>(&out) {
+        Ok(serde_json::Value::Object(_)) => Some(out),
+        _ => None,
+    }
+}
+
+fn strip_trailing_comma(out: &mut String) {
+    let kept = out.trim_end().len();
+    if out[..kept].ends_with(',') {
+        out.truncate(kept - 1);
+    }
+}
+
 fn next_non_whitespace(chars: &[char], start: usize) -> Option<char> {
     chars[start..].iter().find(|c| !c.is_ascii_whitespace()).copied()
 }
@@ -343,6 +416,38 @@ mod tests {
         );
     }
 
+    // ── Repair: truncated / sloppily terminated objects ──
+
+    #[test]
+    fn truncated_repair_drops_trailing_commas() {
+        let repaired = repair_truncated_json(r#"{"paths": ["a", "b",], "recursive": true,}"#);
+        assert_eq!(
+            repaired.as_deref(),
+            Some(r#"{"paths": ["a", "b"], "recursive": true}"#)
+        );
+    }
+
+    #[test]
+    fn truncated_repair_closes_unterminated_string_and_containers() {
+        let repaired =
+            repair_truncated_json(r#"{"file_path": "src/main.rs", "content": "fn main() {\n"#)
+                .expect("repairable");
+        let parsed: serde_json::Value = serde_json::from_str(&repaired).unwrap();
+        assert_eq!(parsed["content"], "fn main() {\n");
+
+        let repaired = repair_truncated_json(r#"{"edits": [{"old": "a", "new": "b"}, {"old": "#)
+            .expect("repairable");
+        let parsed: serde_json::Value = serde_json::from_str(&repaired).unwrap();
+        assert!(parsed["edits"][1]["old"].is_null());
+    }
+
+    #[test]
+    fn truncated_repair_rejects_non_objects_and_mismatched_brackets() {
+        assert_eq!(repair_truncated_json(r#"["a", "b""#), None);
+        assert_eq!(repair_truncated_json(r#"{"a": [1, 2}"#), None);
+        assert_eq!(repair_truncated_json(r#"{"a": tru"#), None);
+    }
+
     // ── Repair: unescaped interior quotes ──
 
     #[test]
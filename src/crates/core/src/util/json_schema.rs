@@ -0,0 +1,249 @@
+//! Minimal JSON Schema validation
+//!
+//! Covers the keywords used to describe structured agent responses: `type`,
+//! `enum`, `const`, `anyOf`, `properties`, `required`, `additionalProperties`,
+//! `items`, `minItems`/`maxItems`, `minLength`/`maxLength` and
+//! `minimum`/`maximum`. Other keywords are ignored.
+
+use serde_json::Value;
+
+/// Validates `value` against `schema`; returns one message per violation,
+/// prefixed with the JSON path (`$.steps[0].title`).
+pub fn validate_json_schema(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "$", &mut errors);
+    errors
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        // Unknown types are not enforced
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` / `{}` accept anything; `false` accepts nothing
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{}: no value is allowed here", path));
+        }
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(single) => vec![single.as_str()],
+            Value::Array(many) => many.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| type_matches(t, value)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                path,
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{}: must be one of {}",
+                path,
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{}: must be {}", path, expected));
+        }
+    }
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        let matches_one = options
+            .iter()
+            .any(|option| validate_json_schema(option, value).is_empty());
+        if !matches_one {
+            errors.push(format!("{}: does not match any allowed shape", path));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(key) {
+                        errors.push(format!("{}: missing required property \"{}\"", path, key));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let additional = schema.get("additionalProperties");
+            for (key, item) in object {
+                let item_path = format!("{}.{}", path, key);
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(item_schema) => validate_at(item_schema, item, &item_path, errors),
+                    None => match additional {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unexpected property", item_path))
+                        }
+                        Some(item_schema @ Value::Object(_)) => {
+                            validate_at(item_schema, item, &item_path, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{}: expected at least {} items", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    errors.push(format!("{}: expected at most {} items", path, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}[{}]", path, index), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    errors.push(format!("{}: expected at least {} characters", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    errors.push(format!("{}: expected at most {} characters", path, max));
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    errors.push(format!("{}: must be at least {}", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    errors.push(format!("{}: must be at most {}", path, max));
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["summary", "steps"],
+            "additionalProperties": false,
+            "properties": {
+                "summary": { "type": "string", "minLength": 1 },
+                "steps": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "object",
+                        "required": ["title"],
+                        "properties": {
+                            "title": { "type": "string" },
+                            "risk": { "enum": ["low", "medium", "high"] },
+                            "estimate_hours": { "type": ["integer", "null"], "minimum": 0 }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_matching_values() {
+        let value = json!({
+            "summary": "Add caching",
+            "steps": [
+                { "title": "Add cache type", "risk": "low", "estimate_hours": 2 },
+                { "title": "Wire it up", "estimate_hours": null }
+            ]
+        });
+        assert!(validate_json_schema(&plan_schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let value = json!({
+            "steps": [
+                { "risk": "extreme", "estimate_hours": -1 },
+                "not an object"
+            ],
+            "notes": "extra"
+        });
+        let mut errors = validate_json_schema(&plan_schema(), &value);
+        // Property order depends on serde_json's map implementation
+        errors.sort();
+        assert_eq!(
+            errors,
+            [
+                "$.notes: unexpected property",
+                "$.steps[0].estimate_hours: must be at least 0",
+                "$.steps[0].risk: must be one of [\"low\",\"medium\",\"high\"]",
+                "$.steps[0]: missing required property \"title\"",
+                "$.steps[1]: expected object, got string",
+                "$: missing required property \"summary\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn integers_accept_whole_floats_only() {
+        let schema = json!({ "type": "integer" });
+        assert!(validate_json_schema(&schema, &json!(3.0)).is_empty());
+        assert_eq!(validate_json_schema(&schema, &json!(3.5)).len(), 1);
+    }
+
+    #[test]
+    fn any_of_needs_one_match() {
+        let schema = json!({ "anyOf": [{ "type": "string" }, { "type": "array" }] });
+        assert!(validate_json_schema(&schema, &json!([])).is_empty());
+        assert_eq!(validate_json_schema(&schema, &json!(1)).len(), 1);
+    }
+}
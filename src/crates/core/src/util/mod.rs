@@ -1,17 +1,24 @@
 //! Common utilities and type definitions
 
+pub mod env_expand;
 pub mod errors;
 pub mod front_matter_markdown;
+pub mod i18n;
 pub mod json_checker;
 pub mod json_extract;
+pub mod json_schema;
 pub mod process_manager;
+pub mod similarity;
 pub mod token_counter;
 pub mod types;
 
+pub use env_expand::expand_env_vars;
 pub use errors::*;
 pub use front_matter_markdown::FrontMatterMarkdown;
 pub use json_checker::JsonChecker;
-pub use json_extract::extract_json_from_ai_response;
+pub use json_extract::{extract_json_from_ai_response, repair_truncated_json};
+pub use json_schema::validate_json_schema;
 pub use process_manager::*;
+pub use similarity::cosine_similarity;
 pub use token_counter::*;
 pub use types::*;
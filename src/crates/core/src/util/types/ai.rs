@@ -32,6 +32,9 @@ pub struct GeminiUsage {
     #[serde(rename = "cachedContentTokenCount")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cached_content_token_count: Option<u32>,
+    #[serde(rename = "webSearchRequests")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search_requests: Option<u32>,
 }
 
 /// Structured message codes for localized connection test messaging.
@@ -69,3 +72,286 @@ pub struct RemoteModelInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display_name: Option<String>,
 }
+
+/// Per-model reasoning (extended thinking) settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasoningConfig {
+    /// Whether the model should reason before answering
+    pub enabled: bool,
+    /// Anthropic extended thinking budget; must be below the model's max_tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u32>,
+    /// OpenAI reasoning effort ("minimal", "low", "medium", "high", "xhigh")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<String>,
+}
+
+impl ReasoningConfig {
+    /// Smallest thinking budget Anthropic accepts
+    pub const MIN_BUDGET_TOKENS: u32 = 1024;
+    pub const EFFORT_LEVELS: [&'static str; 5] = ["minimal", "low", "medium", "high", "xhigh"];
+
+    /// Check the settings against the model's output limit
+    pub fn validate(&self, max_tokens: Option<u32>) -> Result<(), String> {
+        if let Some(budget) = self.budget_tokens {
+            if budget < Self::MIN_BUDGET_TOKENS {
+                return Err(format!(
+                    "reasoning budget_tokens must be at least {}, got {}",
+                    Self::MIN_BUDGET_TOKENS,
+                    budget
+                ));
+            }
+            if let Some(max_tokens) = max_tokens {
+                if budget >= max_tokens {
+                    return Err(format!(
+                        "reasoning budget_tokens ({}) must be less than max_tokens ({})",
+                        budget, max_tokens
+                    ));
+                }
+            }
+        }
+        if let Some(effort) = &self.effort {
+            if !Self::EFFORT_LEVELS.contains(&effort.as_str()) {
+                return Err(format!(
+                    "reasoning effort '{}' is not one of {}",
+                    effort,
+                    Self::EFFORT_LEVELS.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-model prices in USD per million tokens
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Uncached prompt tokens
+    pub input_per_mtok: f64,
+    /// Completion tokens, including reasoning tokens
+    pub output_per_mtok: f64,
+    /// Prompt tokens served from the provider's cache; None = billed as input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_mtok: Option<f64>,
+    /// Each server-side web search the provider runs; None = Anthropic's $10 per 1,000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search_per_request: Option<f64>,
+}
+
+impl ModelPricing {
+    const DEFAULT_WEB_SEARCH_PER_REQUEST: f64 = 0.01;
+
+    /// Cost of one request; `prompt_tokens` includes `cached_tokens`
+    pub fn cost_usd(&self, prompt_tokens: u32, output_tokens: u32, cached_tokens: u32) -> f64 {
+        let cached = cached_tokens.min(prompt_tokens) as f64;
+        let uncached = prompt_tokens as f64 - cached;
+        let cached_rate = self.cached_input_per_mtok.unwrap_or(self.input_per_mtok);
+
+        (uncached * self.input_per_mtok
+            + cached * cached_rate
+            + output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+
+    /// Charge for server-side web searches, billed per request on top of tokens
+    pub fn web_search_cost_usd(&self, requests: u32) -> f64 {
+        requests as f64
+            * self
+                .web_search_per_request
+                .unwrap_or(Self::DEFAULT_WEB_SEARCH_PER_REQUEST)
+    }
+}
+
+/// Context window assumed for models that neither configure one nor appear in the built-in table
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 128128;
+
+/// Built-in capabilities, context window and list prices of a well-known model
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownModel {
+    /// Lowercase model name prefix; the longest matching prefix wins
+    pub prefix: &'static str,
+    pub context_window: u32,
+    pub supports_tools: bool,
+    pub supports_vision: bool,
+    pub supports_reasoning: bool,
+    pub pricing: ModelPricing,
+}
+
+const fn known(
+    prefix: &'static str,
+    context_window: u32,
+    (supports_tools, supports_vision, supports_reasoning): (bool, bool, bool),
+    (input_per_mtok, output_per_mtok, cached_input_per_mtok): (f64, f64, f64),
+) -> KnownModel {
+    KnownModel {
+        prefix,
+        context_window,
+        supports_tools,
+        supports_vision,
+        supports_reasoning,
+        pricing: ModelPricing {
+            input_per_mtok,
+            output_per_mtok,
+            cached_input_per_mtok: Some(cached_input_per_mtok),
+            web_search_per_request: None,
+        },
+    }
+}
+
+/// Flags are (tools, vision, reasoning); prices are (input, output, cached input) per MTok.
+/// Values in a model's own config always take precedence over these.
+static KNOWN_MODELS: &[KnownModel] = &[
+    known(
+        "claude-opus-4-5",
+        200_000,
+        (true, true, true),
+        (5.0, 25.0, 0.5),
+    ),
+    known(
+        "claude-opus-4",
+        200_000,
+        (true, true, true),
+        (15.0, 75.0, 1.5),
+    ),
+    known(
+        "claude-sonnet-4",
+        200_000,
+        (true, true, true),
+        (3.0, 15.0, 0.3),
+    ),
+    known(
+        "claude-haiku-4-5",
+        200_000,
+        (true, true, true),
+        (1.0, 5.0, 0.1),
+    ),
+    known(
+        "claude-3-7-sonnet",
+        200_000,
+        (true, true, true),
+        (3.0, 15.0, 0.3),
+    ),
+    known(
+        "claude-3-5-sonnet",
+        200_000,
+        (true, true, false),
+        (3.0, 15.0, 0.3),
+    ),
+    known(
+        "claude-3-5-haiku",
+        200_000,
+        (true, true, false),
+        (0.8, 4.0, 0.08),
+    ),
+    known("gpt-5", 400_000, (true, true, true), (1.25, 10.0, 0.125)),
+    known(
+        "gpt-5-mini",
+        400_000,
+        (true, true, true),
+        (0.25, 2.0, 0.025),
+    ),
+    known(
+        "gpt-5-nano",
+        400_000,
+        (true, true, true),
+        (0.05, 0.4, 0.005),
+    ),
+    known("gpt-4.1", 1_047_576, (true, true, false), (2.0, 8.0, 0.5)),
+    known(
+        "gpt-4.1-mini",
+        1_047_576,
+        (true, true, false),
+        (0.4, 1.6, 0.1),
+    ),
+    known(
+        "gpt-4.1-nano",
+        1_047_576,
+        (true, true, false),
+        (0.1, 0.4, 0.025),
+    ),
+    known("gpt-4o", 128_000, (true, true, false), (2.5, 10.0, 1.25)),
+    known(
+        "gpt-4o-mini",
+        128_000,
+        (true, true, false),
+        (0.15, 0.6, 0.075),
+    ),
+    known("o1", 200_000, (true, true, true), (15.0, 60.0, 7.5)),
+    known("o1-mini", 128_000, (false, false, true), (1.1, 4.4, 0.55)),
+    known("o3", 200_000, (true, true, true), (2.0, 8.0, 0.5)),
+    known("o3-mini", 200_000, (true, false, true), (1.1, 4.4, 0.55)),
+    known("o4-mini", 200_000, (true, true, true), (1.1, 4.4, 0.275)),
+    known(
+        "gemini-2.5-pro",
+        1_048_576,
+        (true, true, true),
+        (1.25, 10.0, 0.31),
+    ),
+    known(
+        "gemini-2.5-flash",
+        1_048_576,
+        (true, true, true),
+        (0.3, 2.5, 0.075),
+    ),
+    known(
+        "gemini-2.5-flash-lite",
+        1_048_576,
+        (true, true, true),
+        (0.1, 0.4, 0.025),
+    ),
+    known(
+        "gemini-2.0-flash",
+        1_048_576,
+        (true, true, false),
+        (0.1, 0.4, 0.025),
+    ),
+    known(
+        "deepseek-chat",
+        128_000,
+        (true, false, false),
+        (0.28, 0.42, 0.028),
+    ),
+    known(
+        "deepseek-reasoner",
+        128_000,
+        (true, false, true),
+        (0.28, 0.42, 0.028),
+    ),
+    known("glm-4.6", 200_000, (true, false, true), (0.6, 2.2, 0.11)),
+    known("kimi-k2", 256_000, (true, false, false), (0.6, 2.5, 0.15)),
+];
+
+/// Look up a model in the built-in table by name, ignoring any `vendor/` routing prefix
+pub fn lookup_known_model(model_name: &str) -> Option<&'static KnownModel> {
+    let name = model_name
+        .rsplit('/')
+        .next()
+        .unwrap_or(model_name)
+        .trim()
+        .to_ascii_lowercase();
+    KNOWN_MODELS
+        .iter()
+        .filter(|model| name.starts_with(model.prefix))
+        .max_by_key(|model| model.prefix.len())
+}
+
+/// A configured model with capabilities, context window and prices resolved for display
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AvailableModel {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub model_name: String,
+    pub enabled: bool,
+    pub supports_tools: bool,
+    pub supports_vision: bool,
+    pub supports_reasoning: bool,
+    pub context_window: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// None = no configured or built-in prices, so spend isn't tracked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+    /// Whether `model_name` matched the built-in table of known models
+    pub known: bool,
+}
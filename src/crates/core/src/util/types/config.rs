@@ -1,4 +1,4 @@
-use crate::service::config::types::AIModelConfig;
+use crate::service::config::types::{AIModelConfig, ProxyConfig};
 use log::warn;
 use serde::{Deserialize, Serialize};
 
@@ -89,13 +89,135 @@ pub struct AIConfig {
     pub skip_ssl_verify: bool,
     /// Reasoning effort for OpenAI Responses API ("low", "medium", "high", "xhigh")
     pub reasoning_effort: Option<String>,
+    /// Anthropic extended thinking budget; None = derived from max_tokens
+    pub thinking_budget_tokens: Option<u32>,
+    /// Max retries for rate-limited or transient failures before any output; None = client default
+    pub max_retries: Option<u32>,
+    /// Request complete JSON responses instead of SSE
+    pub disable_streaming: bool,
+    /// Max seconds between streamed events; None = stream handler default
+    pub stream_idle_timeout_secs: Option<u64>,
+    /// Max total seconds for a streamed response; None = no limit
+    pub stream_deadline_secs: Option<u64>,
+    /// Add Anthropic prompt cache breakpoints to requests
+    pub enable_prompt_caching: bool,
+    /// Minimum estimated prefix tokens for a cache breakpoint; None = client default
+    pub prompt_cache_min_tokens: Option<u32>,
+    /// Declare Anthropic's server-side web search tool in requests
+    pub native_web_search: bool,
+    /// Model used by `embed` when the caller doesn't name one; None = `model`
+    pub embedding_model: Option<String>,
+    /// Embeddings endpoint (e.g. a local server); None = derived from `base_url`
+    pub embeddings_url: Option<String>,
+    /// Max inputs per embeddings request; None = provider default
+    pub embedding_batch_size: Option<usize>,
+    /// Prices for spend tracking, configured or built-in; None = spend not tracked
+    pub pricing: Option<super::ModelPricing>,
+    /// Longest side for attached images in pixels; None = provider limit
+    pub image_max_dimension: Option<u32>,
+    /// JPEG quality for re-encoded images; None = default (85)
+    pub image_quality: Option<u8>,
+    /// Per-model proxy overriding the global one; None = global proxy
+    pub proxy: Option<ProxyConfig>,
+    /// Whether the model accepts tool definitions (guards fallback candidates)
+    pub supports_tools: bool,
     /// Custom JSON overriding default request body fields
     pub custom_request_body: Option<serde_json::Value>,
 }
 
+impl AIConfig {
+    /// Whether the provider runs web searches itself, replacing the local WebSearch tool
+    pub fn uses_native_web_search(&self) -> bool {
+        self.native_web_search && self.format.eq_ignore_ascii_case("anthropic")
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::resolve_request_url;
+    use super::{resolve_request_url, AIConfig};
+    use crate::service::config::types::AIModelConfig;
+    use crate::util::types::{ModelPricing, ReasoningConfig, DEFAULT_CONTEXT_WINDOW};
+
+    #[test]
+    fn rejects_reasoning_budget_not_below_max_tokens() {
+        let model = AIModelConfig {
+            name: "claude".to_string(),
+            max_tokens: Some(8192),
+            reasoning: Some(ReasoningConfig {
+                enabled: true,
+                budget_tokens: Some(8192),
+                effort: None,
+            }),
+            ..Default::default()
+        };
+        assert!(AIConfig::try_from(model.clone()).is_err());
+
+        let model = AIModelConfig {
+            reasoning: Some(ReasoningConfig {
+                enabled: true,
+                budget_tokens: Some(4096),
+                effort: None,
+            }),
+            ..model
+        };
+        let config = AIConfig::try_from(model).expect("valid reasoning config");
+        assert!(config.enable_thinking_process);
+        assert_eq!(config.thinking_budget_tokens, Some(4096));
+    }
+
+    #[test]
+    fn known_models_fill_unset_context_window_pricing_and_capabilities() {
+        let model = AIModelConfig {
+            name: "gpt".to_string(),
+            model_name: "openai/gpt-4o-mini-2024-07-18".to_string(),
+            ..Default::default()
+        };
+        let config = AIConfig::try_from(model.clone()).expect("valid config");
+        assert_eq!(config.context_window, 128_000);
+        assert_eq!(
+            config.pricing.as_ref().map(|p| p.input_per_mtok),
+            Some(0.15)
+        );
+        assert!(model.supports_image_input());
+
+        let model = AIModelConfig {
+            context_window: Some(32_000),
+            pricing: Some(ModelPricing {
+                input_per_mtok: 1.0,
+                output_per_mtok: 2.0,
+                cached_input_per_mtok: None,
+                web_search_per_request: None,
+            }),
+            supports_vision: Some(false),
+            ..model
+        };
+        let available = model.to_available_model();
+        assert_eq!(available.context_window, 32_000);
+        assert_eq!(available.pricing.map(|p| p.input_per_mtok), Some(1.0));
+        assert!(!available.supports_vision);
+        assert!(available.known);
+    }
+
+    #[test]
+    fn unknown_models_fall_back_to_category_and_default_context_window() {
+        let model = AIModelConfig {
+            name: "local".to_string(),
+            model_name: "my-local-llm".to_string(),
+            ..Default::default()
+        };
+        let available = model.to_available_model();
+        assert!(!available.known);
+        assert!(available.supports_tools);
+        assert!(!available.supports_reasoning);
+        assert_eq!(available.context_window, DEFAULT_CONTEXT_WINDOW);
+        assert!(available.pricing.is_none());
+
+        let no_tools = AIModelConfig {
+            model_name: "o1-mini".to_string(),
+            ..Default::default()
+        };
+        assert!(!no_tools.supports_tool_calls());
+    }
 
     #[test]
     fn resolves_openai_request_url() {
@@ -189,6 +311,30 @@ impl TryFrom<AIModelConfig> for AIConfig {
             None
         };
 
+        let supports_tools = other.supports_tool_calls();
+        let context_window = other.effective_context_window();
+        let pricing = other.effective_pricing();
+
+        // Structured reasoning settings override the legacy thinking flag and effort field
+        let (enable_thinking_process, reasoning_effort, thinking_budget_tokens) =
+            match &other.reasoning {
+                Some(reasoning) => {
+                    reasoning
+                        .validate(other.max_tokens)
+                        .map_err(|e| format!("Model '{}': {}", other.name, e))?;
+                    if reasoning.enabled {
+                        (true, reasoning.effort.clone(), reasoning.budget_tokens)
+                    } else {
+                        (false, None, None)
+                    }
+                }
+                None => (
+                    other.enable_thinking_process,
+                    other.reasoning_effort.clone(),
+                    None,
+                ),
+            };
+
         // Use stored request_url if present; otherwise derive from base_url + provider for legacy configs.
         let request_url = other
             .request_url
@@ -204,17 +350,33 @@ impl TryFrom<AIModelConfig> for AIConfig {
             api_key: other.api_key.clone(),
             model: other.model_name.clone(),
             format: other.provider.clone(),
-            context_window: other.context_window.unwrap_or(128128),
+            context_window,
             max_tokens: other.max_tokens,
             temperature: other.temperature,
             top_p: other.top_p,
-            enable_thinking_process: other.enable_thinking_process,
+            enable_thinking_process,
             support_preserved_thinking: other.support_preserved_thinking,
             inline_think_in_text: other.inline_think_in_text,
             custom_headers: other.custom_headers,
             custom_headers_mode: other.custom_headers_mode,
             skip_ssl_verify: other.skip_ssl_verify,
-            reasoning_effort: other.reasoning_effort,
+            reasoning_effort,
+            thinking_budget_tokens,
+            max_retries: other.max_retries,
+            disable_streaming: other.disable_streaming,
+            stream_idle_timeout_secs: other.stream_idle_timeout_secs,
+            stream_deadline_secs: other.stream_deadline_secs,
+            enable_prompt_caching: other.enable_prompt_caching,
+            prompt_cache_min_tokens: other.prompt_cache_min_tokens,
+            native_web_search: other.native_web_search,
+            embedding_model: other.embedding_model,
+            embeddings_url: other.embeddings_url,
+            embedding_batch_size: other.embedding_batch_size,
+            pricing,
+            image_max_dimension: other.image_max_dimension,
+            image_quality: other.image_quality,
+            proxy: other.proxy,
+            supports_tools,
             custom_request_body,
         })
     }
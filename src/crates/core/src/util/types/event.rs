@@ -47,3 +47,33 @@ pub struct ToolExecutionErrorInfo {
     pub duration_ms: Option<u64>,
     pub timestamp: u64,
 }
+
+/// Emitted when an AI request failed before any output and is about to be retried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIRequestRetryInfo {
+    pub session_id: Option<String>,
+    pub dialog_turn_id: Option<String>,
+    pub model: String,
+    /// 1-based number of the attempt that just failed
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// Wait before the next attempt (ms)
+    pub delay_ms: u64,
+    pub status_code: Option<u16>,
+    /// True when the provider returned 429 (rate limited)
+    pub rate_limited: bool,
+    pub error: String,
+    pub timestamp: u64,
+}
+
+/// Emitted when a request is re-issued against the next model in the fallback chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIModelFallbackInfo {
+    pub session_id: Option<String>,
+    pub dialog_turn_id: Option<String>,
+    pub from_model: String,
+    pub to_model: String,
+    /// Error that triggered the fallback
+    pub error: String,
+    pub timestamp: u64,
+}
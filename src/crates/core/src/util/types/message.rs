@@ -1,5 +1,6 @@
 use super::tool::ToolCall;
 use super::tool_image_attachment::ToolImageAttachment;
+use ai_stream_handlers::UnifiedServerToolEvent;
 use serde::{Deserialize, Serialize};
 
 /// Internal message representation
@@ -22,6 +23,9 @@ pub struct Message {
     /// Images attached to a tool result (Anthropic multimodal tool_result).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tool_image_attachments: Option<Vec<ToolImageAttachment>>,
+    /// Server-side tool calls (Anthropic web search) made while producing an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_tool_events: Option<Vec<UnifiedServerToolEvent>>,
 }
 
 impl Message {
@@ -35,6 +39,7 @@ impl Message {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     }
 
@@ -48,6 +53,7 @@ impl Message {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     }
 
@@ -61,6 +67,7 @@ impl Message {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     }
 
@@ -74,6 +81,7 @@ impl Message {
             tool_call_id: None,
             name: None,
             tool_image_attachments: None,
+            server_tool_events: None,
         }
     }
 }
@@ -0,0 +1,321 @@
+//! Sessions running at the same time keep their streams, tool calls and cancellation apart,
+//! even when the model gives their tool calls the same ID.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ai_stream_handlers::{UnifiedResponse, UnifiedToolCall};
+use async_trait::async_trait;
+use bitfun_core::agentic::events::{AgenticEvent, EventQueue};
+use bitfun_core::agentic::execution::StreamProcessor;
+use bitfun_core::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use bitfun_core::agentic::tools::pipeline::{
+    ToolExecutionContext, ToolExecutionOptions, ToolExecutionResult, ToolPipeline, ToolStateManager,
+};
+use bitfun_core::agentic::turn_cancellation::{ensure_turn_scope, remove_turn_scope, turn_scope};
+use bitfun_core::util::errors::BitFunResult;
+use bitfun_core::ToolRegistry;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use tokio::sync::RwLock as TokioRwLock;
+
+const TURNS_PER_SESSION: usize = 20;
+
+/// Records which session and turn each call ran in, then waits `millis` so calls overlap.
+/// Calls with `confirm` set wait for the user to confirm them first.
+#[derive(Default)]
+struct ProbeTool {
+    calls: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl Tool for ProbeTool {
+    fn name(&self) -> &str {
+        "Probe"
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok("Reports the session it runs in".to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "millis": { "type": "integer" } } })
+    }
+
+    fn is_readonly(&self) -> bool {
+        true
+    }
+
+    fn needs_permissions(&self, input: Option<&Value>) -> bool {
+        input
+            .and_then(|input| input.get("confirm"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    async fn call_impl(
+        &self,
+        input: &Value,
+        context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        let session_id = context.session_id.clone().unwrap_or_default();
+        let dialog_turn_id = context.dialog_turn_id.clone().unwrap_or_default();
+        self.calls
+            .lock()
+            .unwrap()
+            .push((session_id.clone(), dialog_turn_id.clone()));
+
+        let millis = input.get("millis").and_then(Value::as_u64).unwrap_or(0);
+        tokio::time::sleep(Duration::from_millis(millis)).await;
+
+        Ok(vec![ToolResult::Result {
+            data: json!({ "session_id": session_id, "dialog_turn_id": dialog_turn_id }),
+            result_for_assistant: None,
+            image_attachments: None,
+        }])
+    }
+}
+
+struct Harness {
+    event_queue: Arc<EventQueue>,
+    processor: StreamProcessor,
+    pipeline: ToolPipeline,
+    probe: Arc<ProbeTool>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let event_queue = Arc::new(EventQueue::new(Default::default()));
+        let probe = Arc::new(ProbeTool::default());
+        let mut registry = ToolRegistry::new();
+        registry.register_tool(probe.clone());
+        let pipeline = ToolPipeline::new(
+            Arc::new(TokioRwLock::new(registry)),
+            Arc::new(ToolStateManager::new(event_queue.clone())),
+            None,
+            None,
+        );
+        Self {
+            processor: StreamProcessor::new(event_queue.clone()),
+            event_queue,
+            pipeline,
+            probe,
+        }
+    }
+
+    /// Streams a model response that says which session it is for and calls Probe as
+    /// `call_0`, then runs the call.
+    async fn run_turn(
+        &self,
+        session_id: &str,
+        dialog_turn_id: &str,
+        millis: u64,
+        confirm: bool,
+    ) -> ToolExecutionResult {
+        let scope = ensure_turn_scope(session_id, dialog_turn_id);
+
+        let mut chunks: Vec<UnifiedResponse> = (0..3)
+            .map(|n| UnifiedResponse {
+                text: Some(format!("[{}] part {} ", session_id, n)),
+                ..Default::default()
+            })
+            .collect();
+        chunks.push(UnifiedResponse {
+            tool_call: Some(UnifiedToolCall {
+                id: Some("call_0".to_string()),
+                name: Some("Probe".to_string()),
+                arguments: Some(json!({ "millis": millis, "confirm": confirm }).to_string()),
+                index: Some(0),
+            }),
+            ..Default::default()
+        });
+        let stream = futures::stream::iter(chunks)
+            .then(|chunk| async move {
+                tokio::task::yield_now().await;
+                Ok(chunk)
+            })
+            .boxed();
+
+        let streamed = self
+            .processor
+            .process_stream(
+                stream,
+                None,
+                session_id.to_string(),
+                dialog_turn_id.to_string(),
+                format!("{}-round", dialog_turn_id),
+                None,
+                scope.token(),
+            )
+            .await
+            .map_err(|e| e.error)
+            .expect("stream processed");
+        assert_eq!(streamed.full_text.matches(session_id).count(), 3);
+        assert_eq!(streamed.tool_calls.len(), 1);
+        assert_eq!(streamed.tool_calls[0].tool_id, "call_0");
+
+        let context = ToolExecutionContext {
+            session_id: session_id.to_string(),
+            dialog_turn_id: dialog_turn_id.to_string(),
+            agent_type: "agentic".to_string(),
+            workspace: None,
+            context_vars: Default::default(),
+            subagent_parent_info: None,
+            allowed_tools: vec![],
+            workspace_services: None,
+        };
+        let options = ToolExecutionOptions {
+            confirm_before_run: confirm,
+            confirmation_timeout_secs: Some(10),
+            ..Default::default()
+        };
+        let mut results = self
+            .pipeline
+            .execute_tools(streamed.tool_calls, context, options)
+            .await
+            .expect("tools executed");
+        remove_turn_scope(session_id, dialog_turn_id);
+
+        assert_eq!(results.len(), 1);
+        results.remove(0)
+    }
+
+    async fn run_session(&self, session_id: &str) {
+        for n in 0..TURNS_PER_SESSION {
+            let dialog_turn_id = format!("{}-turn-{}", session_id, n);
+            let executed = self.run_turn(session_id, &dialog_turn_id, 2, false).await;
+            assert!(!executed.result.is_error, "{:?}", executed.result);
+            assert_eq!(executed.result.result["session_id"], session_id);
+            assert_eq!(
+                executed.result.result["dialog_turn_id"],
+                dialog_turn_id.as_str()
+            );
+        }
+    }
+}
+
+/// Turn IDs in these tests start with the ID of their session.
+fn turn_of_event(event: &AgenticEvent) -> Option<&str> {
+    match event {
+        AgenticEvent::TextChunk { turn_id, .. } | AgenticEvent::ToolEvent { turn_id, .. } => {
+            Some(turn_id)
+        }
+        _ => None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_sessions_keep_streams_and_tool_calls_apart() {
+    let harness = Harness::new();
+
+    tokio::join!(
+        harness.run_session("session-a"),
+        harness.run_session("session-b"),
+    );
+
+    let calls = harness.probe.calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 2 * TURNS_PER_SESSION);
+    for (session_id, dialog_turn_id) in &calls {
+        assert!(dialog_turn_id.starts_with(&format!("{}-turn-", session_id)));
+    }
+
+    let mut checked = 0;
+    loop {
+        let batch = harness.event_queue.dequeue_batch(256).await;
+        if batch.is_empty() {
+            break;
+        }
+        for envelope in batch {
+            let event = &envelope.event;
+            if let AgenticEvent::TextChunk {
+                session_id, text, ..
+            } = event
+            {
+                assert!(text.contains(session_id.as_str()), "{:?}", event);
+            }
+            if let Some(turn_id) = turn_of_event(event) {
+                assert!(
+                    turn_id.starts_with(&format!("{}-turn-", event.session_id())),
+                    "{:?}",
+                    event
+                );
+                checked += 1;
+            }
+        }
+    }
+    // At least the completion of every tool call
+    assert!(checked >= 2 * TURNS_PER_SESSION);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn cancelling_a_turn_leaves_the_other_session_running() {
+    let harness = Harness::new();
+
+    let cancel_a = async {
+        // Wait for session A's tool call to start, then cancel its turn
+        loop {
+            if let Some(scope) = turn_scope("session-cancel-a", "shared-turn") {
+                if !scope.running().is_empty() {
+                    scope.cancel();
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    };
+
+    // Both sessions use the same turn and tool call IDs
+    let (cancelled, completed, _) = tokio::time::timeout(Duration::from_secs(10), async {
+        tokio::join!(
+            harness.run_turn("session-cancel-a", "shared-turn", 30_000, false),
+            harness.run_turn("session-cancel-b", "shared-turn", 200, false),
+            cancel_a,
+        )
+    })
+    .await
+    .expect("both turns ended");
+
+    assert!(cancelled.result.is_error);
+    assert!(!completed.result.is_error, "{:?}", completed.result);
+    assert_eq!(completed.result.result["session_id"], "session-cancel-b");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn confirming_and_rejecting_reach_the_session_they_are_sent_to() {
+    let harness = Harness::new();
+
+    let answer = async {
+        // Retry until session A's call waits for confirmation; B's call has the same ID
+        while harness
+            .pipeline
+            .confirm_tool("session-confirm-a", "call_0", None)
+            .await
+            .is_err()
+        {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        while harness
+            .pipeline
+            .reject_tool("session-confirm-b", "call_0", "Denied".to_string())
+            .await
+            .is_err()
+        {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    };
+
+    let (confirmed, rejected, _) = tokio::time::timeout(Duration::from_secs(10), async {
+        tokio::join!(
+            harness.run_turn("session-confirm-a", "shared-turn", 0, true),
+            harness.run_turn("session-confirm-b", "shared-turn", 0, true),
+            answer,
+        )
+    })
+    .await
+    .expect("both turns ended");
+
+    assert!(!confirmed.result.is_error, "{:?}", confirmed.result);
+    assert_eq!(confirmed.result.result["session_id"], "session-confirm-a");
+    assert!(rejected.result.is_error);
+    assert_eq!(harness.probe.calls.lock().unwrap().len(), 1);
+}
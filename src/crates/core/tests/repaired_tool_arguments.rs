@@ -0,0 +1,134 @@
+//! Tool calls whose arguments only parsed after repair run read-only tools, but not
+//! tools that make changes.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bitfun_core::agentic::core::ToolCall;
+use bitfun_core::agentic::events::EventQueue;
+use bitfun_core::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use bitfun_core::agentic::tools::pipeline::{
+    ToolExecutionContext, ToolExecutionOptions, ToolPipeline, ToolStateManager,
+};
+use bitfun_core::util::errors::BitFunResult;
+use bitfun_core::ToolRegistry;
+use serde_json::{json, Value};
+use tokio::sync::RwLock as TokioRwLock;
+
+/// Counts its calls; `readonly` decides whether it counts as making changes.
+struct CountingTool {
+    name: &'static str,
+    readonly: bool,
+    calls: AtomicUsize,
+}
+
+impl CountingTool {
+    fn new(name: &'static str, readonly: bool) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            readonly,
+            calls: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for CountingTool {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok("Counts its calls".to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object", "properties": { "content": { "type": "string" } } })
+    }
+
+    fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    async fn call_impl(
+        &self,
+        _input: &Value,
+        _context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![ToolResult::Result {
+            data: json!({ "ok": true }),
+            result_for_assistant: None,
+            image_attachments: None,
+        }])
+    }
+}
+
+fn repaired_call(tool_id: &str, tool_name: &str) -> ToolCall {
+    ToolCall {
+        tool_id: tool_id.to_string(),
+        tool_name: tool_name.to_string(),
+        arguments: json!({ "content": "cut sho" }),
+        is_error: false,
+        repaired: true,
+        argument_error: None,
+    }
+}
+
+#[tokio::test]
+async fn repaired_arguments_only_run_readonly_tools() {
+    let event_queue = Arc::new(EventQueue::new(Default::default()));
+    let writer = CountingTool::new("Writer", false);
+    let reader = CountingTool::new("Reader", true);
+    let mut registry = ToolRegistry::new();
+    registry.register_tool(writer.clone());
+    registry.register_tool(reader.clone());
+    let pipeline = ToolPipeline::new(
+        Arc::new(TokioRwLock::new(registry)),
+        Arc::new(ToolStateManager::new(event_queue)),
+        None,
+        None,
+    );
+
+    let context = ToolExecutionContext {
+        session_id: "session-repaired".to_string(),
+        dialog_turn_id: "turn-repaired".to_string(),
+        agent_type: "agentic".to_string(),
+        workspace: None,
+        context_vars: Default::default(),
+        subagent_parent_info: None,
+        allowed_tools: vec![],
+        workspace_services: None,
+    };
+    let options = ToolExecutionOptions {
+        confirm_before_run: false,
+        ..Default::default()
+    };
+    let results = pipeline
+        .execute_tools(
+            vec![
+                repaired_call("call_write", "Writer"),
+                repaired_call("call_read", "Reader"),
+            ],
+            context,
+            options,
+        )
+        .await
+        .expect("tools executed");
+
+    let write = results
+        .iter()
+        .find(|r| r.tool_id == "call_write")
+        .expect("write result");
+    assert!(write.result.is_error);
+    assert_eq!(write.result.result["error"], "repaired_tool_arguments");
+    assert_eq!(writer.calls.load(Ordering::SeqCst), 0);
+
+    let read = results
+        .iter()
+        .find(|r| r.tool_id == "call_read")
+        .expect("read result");
+    assert!(!read.result.is_error, "{:?}", read.result);
+    assert_eq!(reader.calls.load(Ordering::SeqCst), 1);
+}
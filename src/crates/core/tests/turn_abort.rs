@@ -0,0 +1,145 @@
+//! Aborting a turn stops its tool calls and model stream before the grace period ends.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ai_stream_handlers::UnifiedResponse;
+use async_trait::async_trait;
+use bitfun_core::agentic::events::EventQueue;
+use bitfun_core::agentic::execution::StreamProcessor;
+use bitfun_core::agentic::tools::framework::{Tool, ToolResult, ToolUseContext};
+use bitfun_core::agentic::turn_cancellation::TurnScope;
+use bitfun_core::util::errors::{BitFunError, BitFunResult};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use tokio_util::sync::CancellationToken;
+
+const GRACE: Duration = Duration::from_secs(2);
+
+/// A tool that never finishes on its own.
+struct SlowTool;
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> &str {
+        "Slow"
+    }
+
+    async fn description(&self) -> BitFunResult<String> {
+        Ok("Waits forever".to_string())
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call_impl(
+        &self,
+        _input: &Value,
+        _context: &ToolUseContext,
+    ) -> BitFunResult<Vec<ToolResult>> {
+        loop {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+fn tool_context(cancellation_token: CancellationToken) -> ToolUseContext {
+    ToolUseContext {
+        tool_call_id: Some("call-slow".to_string()),
+        message_id: None,
+        agent_type: None,
+        session_id: Some("session".to_string()),
+        dialog_turn_id: Some("turn".to_string()),
+        workspace: None,
+        safe_mode: None,
+        abort_controller: None,
+        read_file_timestamps: Default::default(),
+        options: None,
+        response_state: None,
+        image_context_provider: None,
+        computer_use_host: None,
+        subagent_parent_info: None,
+        cancellation_token: Some(cancellation_token),
+        workspace_services: None,
+    }
+}
+
+/// An SSE response that keeps sending text chunks and never completes.
+fn endless_stream() -> futures::stream::BoxStream<'static, Result<UnifiedResponse, anyhow::Error>> {
+    futures::stream::unfold(0u64, |n| async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let chunk = UnifiedResponse {
+            text: Some(format!("chunk {} ", n)),
+            ..Default::default()
+        };
+        Some((Ok(chunk), n + 1))
+    })
+    .boxed()
+}
+
+#[tokio::test]
+async fn abort_stops_slow_tool_and_stream_within_grace_period() {
+    let scope = TurnScope::new(CancellationToken::new());
+
+    let tool_child = scope.register("tool Slow");
+    let tool_task = tokio::spawn(async move {
+        let context = tool_context(tool_child.token().clone());
+        let result = SlowTool.call(&json!({}), &context).await;
+        drop(tool_child);
+        result
+    });
+
+    let stream_child = scope.register("model stream");
+    let stream_task = tokio::spawn(async move {
+        let processor = StreamProcessor::new(Arc::new(EventQueue::new(Default::default())));
+        let result = processor
+            .process_stream(
+                endless_stream(),
+                None,
+                "session".to_string(),
+                "turn".to_string(),
+                "round".to_string(),
+                None,
+                stream_child.token(),
+            )
+            .await;
+        drop(stream_child);
+        result.map(|_| ()).map_err(|e| e.error)
+    });
+
+    // Let both get going
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(scope.running(), ["tool Slow", "model stream"]);
+
+    let started = Instant::now();
+    let unfinished = scope.abort(GRACE).await;
+    assert!(unfinished.is_empty(), "still running: {:?}", unfinished);
+    assert!(started.elapsed() < GRACE);
+
+    let tool_result = tokio::time::timeout(GRACE, tool_task)
+        .await
+        .expect("tool task ended")
+        .unwrap();
+    assert!(matches!(tool_result, Err(BitFunError::Cancelled(_))));
+    let stream_result = tokio::time::timeout(GRACE, stream_task)
+        .await
+        .expect("stream task ended")
+        .unwrap();
+    assert!(matches!(stream_result, Err(BitFunError::Cancelled(_))));
+}
+
+#[tokio::test]
+async fn abort_reports_work_that_ignores_cancellation() {
+    let scope = TurnScope::new(CancellationToken::new());
+    let stuck = scope.register("tool Stuck");
+    let stuck_task = tokio::spawn(async move {
+        // Ignores its token
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        drop(stuck);
+    });
+
+    let unfinished = scope.abort(Duration::from_millis(100)).await;
+    assert_eq!(unfinished, ["tool Stuck"]);
+    stuck_task.abort();
+}
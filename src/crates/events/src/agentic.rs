@@ -1,4 +1,5 @@
 ///! Agentic Events Definition
+use crate::types::LocalizedText;
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
@@ -18,6 +19,9 @@ pub struct SubagentParentInfo {
     pub session_id: String,
     #[serde(rename = "dialogTurnId")]
     pub dialog_turn_id: String,
+    /// Model the subagent actually used
+    #[serde(rename = "modelId", default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,10 +91,27 @@ pub enum AgenticEvent {
         subagent_parent_info: Option<SubagentParentInfo>,
     },
 
+    /// Last event of an aborted turn, sent once its work has stopped
+    DialogTurnAborted {
+        session_id: String,
+        turn_id: String,
+        /// Work that had not stopped when the grace period ran out
+        unfinished: Vec<String>,
+    },
+
     DialogTurnFailed {
         session_id: String,
         turn_id: String,
         error: String,
+        /// Stable machine-readable error code, e.g. `ai_rate_limited`
+        #[serde(default)]
+        error_code: Option<String>,
+        /// Whether sending the turn again may succeed
+        #[serde(default)]
+        retryable: bool,
+        /// End-user explanation of the error
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_message: Option<LocalizedText>,
         subagent_parent_info: Option<SubagentParentInfo>,
     },
 
@@ -102,7 +123,15 @@ pub enum AgenticEvent {
         output_tokens: Option<usize>,
         total_tokens: usize,
         max_context_tokens: Option<usize>,
+        /// Prompt tokens served from the provider's prompt cache
+        #[serde(default)]
+        cached_tokens: Option<usize>,
         is_subagent: bool,
+        /// Cost in USD from the model's configured pricing
+        #[serde(default)]
+        cost_usd: Option<f64>,
+        #[serde(default)]
+        workspace_path: Option<String>,
     },
 
     ContextCompressionStarted {
@@ -137,6 +166,99 @@ pub enum AgenticEvent {
         subagent_parent_info: Option<SubagentParentInfo>,
     },
 
+    /// Pre-flight estimate says the request may not fit the model's context window
+    ContextWindowWarning {
+        session_id: String,
+        turn_id: String,
+        model: String,
+        estimated_tokens: usize,
+        context_window: usize,
+        subagent_parent_info: Option<SubagentParentInfo>,
+    },
+
+    /// Today's spend reached the configured warning threshold
+    SpendWarning {
+        session_id: String,
+        turn_id: String,
+        spent_today_usd: f64,
+        threshold_usd: f64,
+    },
+
+    /// The agent kept repeating the same tool calls and the turn was ended
+    LoopDetected {
+        session_id: String,
+        turn_id: String,
+        /// Rounds per repeated cycle; 1 when one round repeats
+        cycle_length: usize,
+        repetitions: usize,
+        tool_names: Vec<String>,
+        description: String,
+        subagent_parent_info: Option<SubagentParentInfo>,
+    },
+
+    /// A turn budget (rounds, tool calls or duration) ran out; the turn ended with a
+    /// tool-free summary round
+    TurnBudgetExhausted {
+        session_id: String,
+        turn_id: String,
+        /// `rounds`, `tool_calls` or `duration`
+        budget: String,
+        /// In rounds, tool calls or seconds
+        used: u64,
+        limit: u64,
+        subagent_parent_info: Option<SubagentParentInfo>,
+    },
+
+    /// A subagent task was answered with the result of an identical earlier run; no
+    /// model calls were made for it
+    SubagentResultCached {
+        /// Parent session
+        session_id: String,
+        turn_id: String,
+        tool_call_id: String,
+        subagent_type: String,
+        /// Age of the reused result
+        age_secs: u64,
+    },
+
+    /// Recently modified workspace files were summarized into the first turn's system prompt
+    RecentChangesAttached {
+        session_id: String,
+        turn_id: String,
+        /// Listed files, relative to the workspace root, newest first
+        files: Vec<String>,
+        estimated_tokens: usize,
+    },
+
+    /// A Review agent submitted its findings (normalized `submit_review_findings` input)
+    ReviewResult {
+        session_id: String,
+        turn_id: String,
+        tool_call_id: String,
+        report: serde_json::Value,
+    },
+
+    /// A completed turn was summarized; follows its `DialogTurnCompleted`
+    TurnSummaryGenerated {
+        session_id: String,
+        turn_id: String,
+        /// What the turn did
+        did: String,
+        /// What it changed
+        changed: String,
+        /// What comes next
+        next: String,
+    },
+
+    /// The next model round is estimated to cost more than the per-turn threshold; the turn
+    /// waits for `confirm_turn_spend`
+    SpendConfirmationRequired {
+        session_id: String,
+        turn_id: String,
+        estimated_cost_usd: f64,
+        threshold_usd: f64,
+    },
+
     ModelRoundStarted {
         session_id: String,
         turn_id: String,
@@ -150,6 +272,9 @@ pub enum AgenticEvent {
         turn_id: String,
         round_id: String,
         has_tool_calls: bool,
+        /// Model that served the round when a fallback took over
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        served_by_model: Option<String>,
         subagent_parent_info: Option<SubagentParentInfo>,
     },
 
@@ -178,8 +303,10 @@ pub enum AgenticEvent {
         subagent_parent_info: Option<SubagentParentInfo>,
     },
 
+    /// Errors outside a session are logged, not sent as events, so every event
+    /// can be routed to the session it belongs to
     SystemError {
-        session_id: Option<String>,
+        session_id: String,
         error: String,
         recoverable: bool,
     },
@@ -232,6 +359,9 @@ pub enum ToolEventData {
         tool_id: String,
         tool_name: String,
         params: serde_json::Value,
+        /// One-line question shown in the permission prompt
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        summary: Option<LocalizedText>,
     },
     Confirmed {
         tool_id: String,
@@ -305,7 +435,7 @@ impl AgenticEventEnvelope {
 
 impl AgenticEvent {
     /// Get the session ID of the event
-    pub fn session_id(&self) -> Option<&str> {
+    pub fn session_id(&self) -> &str {
         match self {
             Self::SessionCreated { session_id, .. }
             | Self::SessionStateChanged { session_id, .. }
@@ -319,14 +449,60 @@ impl AgenticEvent {
             | Self::ContextCompressionStarted { session_id, .. }
             | Self::ContextCompressionCompleted { session_id, .. }
             | Self::ContextCompressionFailed { session_id, .. }
+            | Self::ContextWindowWarning { session_id, .. }
+            | Self::SpendWarning { session_id, .. }
+            | Self::LoopDetected { session_id, .. }
+            | Self::TurnBudgetExhausted { session_id, .. }
+            | Self::SubagentResultCached { session_id, .. }
+            | Self::RecentChangesAttached { session_id, .. }
+            | Self::ReviewResult { session_id, .. }
+            | Self::TurnSummaryGenerated { session_id, .. }
+            | Self::SpendConfirmationRequired { session_id, .. }
             | Self::DialogTurnCancelled { session_id, .. }
+            | Self::DialogTurnAborted { session_id, .. }
             | Self::DialogTurnFailed { session_id, .. }
             | Self::ModelRoundStarted { session_id, .. }
             | Self::TextChunk { session_id, .. }
             | Self::ThinkingChunk { session_id, .. }
             | Self::ModelRoundCompleted { session_id, .. }
-            | Self::ToolEvent { session_id, .. } => Some(session_id),
-            Self::SystemError { session_id, .. } => session_id.as_deref(),
+            | Self::ToolEvent { session_id, .. }
+            | Self::SystemError { session_id, .. } => session_id,
+        }
+    }
+
+    /// Name of the event, as the frontend receives it (`agentic://...`)
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::SessionCreated { .. } => "agentic://session-created",
+            Self::SessionStateChanged { .. } => "agentic://session-state-changed",
+            Self::SessionDeleted { .. } => "agentic://session-deleted",
+            Self::SessionTitleGenerated { .. } => "agentic://session-title-generated",
+            Self::ImageAnalysisStarted { .. } => "agentic://image-analysis-started",
+            Self::ImageAnalysisCompleted { .. } => "agentic://image-analysis-completed",
+            Self::DialogTurnStarted { .. } => "agentic://dialog-turn-started",
+            Self::DialogTurnCompleted { .. } => "agentic://dialog-turn-completed",
+            Self::DialogTurnCancelled { .. } => "agentic://dialog-turn-cancelled",
+            Self::DialogTurnAborted { .. } => "agentic://dialog-turn-aborted",
+            Self::DialogTurnFailed { .. } => "agentic://dialog-turn-failed",
+            Self::TokenUsageUpdated { .. } => "agentic://token-usage-updated",
+            Self::ContextCompressionStarted { .. } => "agentic://context-compression-started",
+            Self::ContextCompressionCompleted { .. } => "agentic://context-compression-completed",
+            Self::ContextCompressionFailed { .. } => "agentic://context-compression-failed",
+            Self::ContextWindowWarning { .. } => "agentic://context-window-warning",
+            Self::SpendWarning { .. } => "agentic://spend-warning",
+            Self::LoopDetected { .. } => "agentic://loop-detected",
+            Self::TurnBudgetExhausted { .. } => "agentic://turn-budget-exhausted",
+            Self::SubagentResultCached { .. } => "agentic://subagent-result-cached",
+            Self::RecentChangesAttached { .. } => "agentic://recent-changes-attached",
+            Self::ReviewResult { .. } => "agentic://review-result",
+            Self::TurnSummaryGenerated { .. } => "agentic://turn-summary-generated",
+            Self::SpendConfirmationRequired { .. } => "agentic://spend-confirmation-required",
+            Self::ModelRoundStarted { .. } => "agentic://model-round-started",
+            Self::ModelRoundCompleted { .. } => "agentic://model-round-completed",
+            Self::TextChunk { .. } => "agentic://text-chunk",
+            Self::ThinkingChunk { .. } => "agentic://thinking-chunk",
+            Self::ToolEvent { .. } => "agentic://tool-event",
+            Self::SystemError { .. } => "agentic://system-error",
         }
     }
 
@@ -335,11 +511,17 @@ impl AgenticEvent {
         match self {
             Self::SystemError { .. }
             | Self::DialogTurnFailed { .. }
-            | Self::DialogTurnCancelled { .. } => AgenticEventPriority::Critical,
+            | Self::DialogTurnCancelled { .. }
+            | Self::DialogTurnAborted { .. } => AgenticEventPriority::Critical,
 
             Self::SessionStateChanged { .. }
             | Self::SessionTitleGenerated { .. }
-            | Self::ContextCompressionFailed { .. } => AgenticEventPriority::High,
+            | Self::ContextCompressionFailed { .. }
+            | Self::ContextWindowWarning { .. }
+            | Self::SpendWarning { .. }
+            | Self::LoopDetected { .. }
+            | Self::TurnBudgetExhausted { .. }
+            | Self::SpendConfirmationRequired { .. } => AgenticEventPriority::High,
 
             Self::ImageAnalysisStarted { .. }
             | Self::ImageAnalysisCompleted { .. }
@@ -350,7 +532,11 @@ impl AgenticEvent {
             | Self::TokenUsageUpdated { .. }
             | Self::DialogTurnCompleted { .. }
             | Self::ContextCompressionStarted { .. }
-            | Self::ContextCompressionCompleted { .. } => AgenticEventPriority::Normal,
+            | Self::ContextCompressionCompleted { .. }
+            | Self::SubagentResultCached { .. }
+            | Self::RecentChangesAttached { .. }
+            | Self::ReviewResult { .. }
+            | Self::TurnSummaryGenerated { .. } => AgenticEventPriority::Normal,
 
             Self::ToolEvent { tool_event, .. } => tool_event.default_priority(),
 
@@ -2,6 +2,7 @@
 ///
 /// Define cross-platform event data structures
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Event priority
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -16,3 +17,15 @@ impl Default for EventPriority {
         Self::Normal
     }
 }
+
+/// User-facing text together with the translation key and arguments it was rendered from,
+/// so clients can re-render it in their own locale
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedText {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+    /// Rendered in the backend's locale
+    pub text: String,
+}
@@ -231,10 +231,27 @@ impl TransportAdapter for TauriTransportAdapter {
                     }),
                 )?;
             }
+            AgenticEvent::DialogTurnAborted {
+                session_id,
+                turn_id,
+                unfinished,
+            } => {
+                self.app_handle.emit(
+                    "agentic://dialog-turn-aborted",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "unfinished": unfinished,
+                    }),
+                )?;
+            }
             AgenticEvent::DialogTurnFailed {
                 session_id,
                 turn_id,
                 error,
+                error_code,
+                retryable,
+                user_message,
                 subagent_parent_info,
             } => {
                 self.app_handle.emit(
@@ -243,6 +260,9 @@ impl TransportAdapter for TauriTransportAdapter {
                         "sessionId": session_id,
                         "turnId": turn_id,
                         "error": error,
+                        "errorCode": error_code,
+                        "retryable": retryable,
+                        "userMessage": user_message,
                         "subagentParentInfo": subagent_parent_info,
                     }),
                 )?;
@@ -255,7 +275,10 @@ impl TransportAdapter for TauriTransportAdapter {
                 output_tokens,
                 total_tokens,
                 max_context_tokens,
+                cached_tokens,
                 is_subagent,
+                cost_usd,
+                workspace_path,
             } => {
                 self.app_handle.emit(
                     "agentic://token-usage-updated",
@@ -267,7 +290,10 @@ impl TransportAdapter for TauriTransportAdapter {
                         "outputTokens": output_tokens,
                         "totalTokens": total_tokens,
                         "maxContextTokens": max_context_tokens,
+                        "cachedTokens": cached_tokens,
                         "isSubagent": is_subagent,
+                        "costUsd": cost_usd,
+                        "workspacePath": workspace_path,
                     }),
                 )?;
             }
@@ -341,6 +367,168 @@ impl TransportAdapter for TauriTransportAdapter {
                     }),
                 )?;
             }
+            AgenticEvent::ContextWindowWarning {
+                session_id,
+                turn_id,
+                model,
+                estimated_tokens,
+                context_window,
+                subagent_parent_info,
+            } => {
+                self.app_handle.emit(
+                    "agentic://context-window-warning",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "model": model,
+                        "estimatedTokens": estimated_tokens,
+                        "contextWindow": context_window,
+                        "subagentParentInfo": subagent_parent_info,
+                    }),
+                )?;
+            }
+            AgenticEvent::SpendWarning {
+                session_id,
+                turn_id,
+                spent_today_usd,
+                threshold_usd,
+            } => {
+                self.app_handle.emit(
+                    "agentic://spend-warning",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "spentTodayUsd": spent_today_usd,
+                        "thresholdUsd": threshold_usd,
+                    }),
+                )?;
+            }
+            AgenticEvent::LoopDetected {
+                session_id,
+                turn_id,
+                cycle_length,
+                repetitions,
+                tool_names,
+                description,
+                subagent_parent_info,
+            } => {
+                self.app_handle.emit(
+                    "agentic://loop-detected",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "cycleLength": cycle_length,
+                        "repetitions": repetitions,
+                        "toolNames": tool_names,
+                        "description": description,
+                        "subagentParentInfo": subagent_parent_info,
+                    }),
+                )?;
+            }
+            AgenticEvent::TurnBudgetExhausted {
+                session_id,
+                turn_id,
+                budget,
+                used,
+                limit,
+                subagent_parent_info,
+            } => {
+                self.app_handle.emit(
+                    "agentic://turn-budget-exhausted",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "budget": budget,
+                        "used": used,
+                        "limit": limit,
+                        "subagentParentInfo": subagent_parent_info,
+                    }),
+                )?;
+            }
+            AgenticEvent::SubagentResultCached {
+                session_id,
+                turn_id,
+                tool_call_id,
+                subagent_type,
+                age_secs,
+            } => {
+                self.app_handle.emit(
+                    "agentic://subagent-result-cached",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "toolCallId": tool_call_id,
+                        "subagentType": subagent_type,
+                        "ageSecs": age_secs,
+                    }),
+                )?;
+            }
+            AgenticEvent::RecentChangesAttached {
+                session_id,
+                turn_id,
+                files,
+                estimated_tokens,
+            } => {
+                self.app_handle.emit(
+                    "agentic://recent-changes-attached",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "files": files,
+                        "estimatedTokens": estimated_tokens,
+                    }),
+                )?;
+            }
+            AgenticEvent::ReviewResult {
+                session_id,
+                turn_id,
+                tool_call_id,
+                report,
+            } => {
+                self.app_handle.emit(
+                    "agentic://review-result",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "toolCallId": tool_call_id,
+                        "report": report,
+                    }),
+                )?;
+            }
+            AgenticEvent::TurnSummaryGenerated {
+                session_id,
+                turn_id,
+                did,
+                changed,
+                next,
+            } => {
+                self.app_handle.emit(
+                    "agentic://turn-summary-generated",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "did": did,
+                        "changed": changed,
+                        "next": next,
+                    }),
+                )?;
+            }
+            AgenticEvent::SpendConfirmationRequired {
+                session_id,
+                turn_id,
+                estimated_cost_usd,
+                threshold_usd,
+            } => {
+                self.app_handle.emit(
+                    "agentic://spend-confirmation-required",
+                    json!({
+                        "sessionId": session_id,
+                        "turnId": turn_id,
+                        "estimatedCostUsd": estimated_cost_usd,
+                        "thresholdUsd": threshold_usd,
+                    }),
+                )?;
+            }
             AgenticEvent::SessionStateChanged {
                 session_id,
                 new_state,
@@ -358,6 +546,7 @@ impl TransportAdapter for TauriTransportAdapter {
                 turn_id,
                 round_id,
                 has_tool_calls,
+                served_by_model,
                 subagent_parent_info,
             } => {
                 self.app_handle.emit(
@@ -367,6 +556,7 @@ impl TransportAdapter for TauriTransportAdapter {
                         "turnId": turn_id,
                         "roundId": round_id,
                         "hasToolCalls": has_tool_calls,
+                        "servedByModel": served_by_model,
                         "subagentParentInfo": subagent_parent_info,
                     }),
                 )?;
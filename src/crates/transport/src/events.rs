@@ -16,6 +16,9 @@ pub enum UnifiedEvent {
     /// File watch event
     FileWatch(FileWatchEventPayload),
 
+    /// Incremental file tree update
+    FileTreeDelta(FileTreeDeltaPayload),
+
     /// Profile generation event
     Profile(ProfileEventPayload),
 
@@ -48,6 +51,20 @@ pub struct FileWatchEventPayload {
     pub path: String,
     pub event_type: String, // "create", "modify", "delete"
     pub timestamp: i64,
+    /// Tree node of a created or modified entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<serde_json::Value>,
+}
+
+/// File tree delta payload: the changes from `base_version` to `version`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTreeDeltaPayload {
+    pub workspace_path: String,
+    pub base_version: u64,
+    pub version: u64,
+    /// The changes no longer reach back to `base_version`; reload the tree
+    pub reset: bool,
+    pub changes: Vec<FileWatchEventPayload>,
 }
 
 /// Profile event payload
@@ -1556,6 +1556,7 @@ function renderOrderedItems(
 function renderActiveTurnItems(
   rawItems: ChatMessageItem[],
   now: number,
+  sessionId: string,
   sessionMgr: RemoteSessionManager,
   setError: (e: string) => void,
   onAnswer: (toolId: string, answers: any) => Promise<void>,
@@ -1565,7 +1566,7 @@ function renderActiveTurnItems(
   const items = filterSubagentItems(rawItems);
   const askEntries = items.filter(item => isPendingAskUserQuestion(item.tool));
   const onCancel = (toolId: string) => {
-    sessionMgr.cancelTool(toolId, 'User cancelled').catch(err => { setError(String(err)); });
+    sessionMgr.cancelTool(sessionId, toolId, 'User cancelled').catch(err => { setError(String(err)); });
   };
 
   if (askEntries.length === 0) {
@@ -2550,7 +2551,7 @@ const ChatPage: React.FC<ChatPageProps> = ({ sessionMgr, sessionId, sessionName,
             return (
               <div className="chat-msg chat-msg--assistant">
                 {turnIsActive
-                  ? renderActiveTurnItems(turn.items, now, sessionMgr, setError, handleAnswerQuestion, handleFileDownload, handleGetFileInfo)
+                  ? renderActiveTurnItems(turn.items, now, sessionId, sessionMgr, setError, handleAnswerQuestion, handleFileDownload, handleGetFileInfo)
                   : renderOrderedItems(turn.items, now, undefined, undefined, handleFileDownload, handleGetFileInfo)}
                 {turnIsActive && !turn.thinking && !turn.text && turn.tools.length === 0 && (
                   <div className="chat-msg__assistant-content"><TypingDots /></div>
@@ -2573,7 +2574,7 @@ const ChatPage: React.FC<ChatPageProps> = ({ sessionMgr, sessionId, sessionName,
               ]
             : [];
           const onCancel = (toolId: string) => {
-            sessionMgr.cancelTool(toolId, t('common.cancel')).catch(err => { setError(String(err)); });
+            sessionMgr.cancelTool(sessionId, toolId, t('common.cancel')).catch(err => { setError(String(err)); });
           };
 
           return (
@@ -318,9 +318,10 @@ export class RemoteSessionManager {
     });
   }
 
-  async cancelTool(toolId: string, reason?: string): Promise<void> {
+  async cancelTool(sessionId: string, toolId: string, reason?: string): Promise<void> {
     await this.request({
       cmd: 'cancel_tool',
+      session_id: sessionId,
       tool_id: toolId,
       reason: reason ?? undefined,
     });
@@ -21,6 +21,7 @@ import { FlowChatManager } from '../../flow_chat/services/FlowChatManager';
 import WorkspaceBody from './WorkspaceBody';
 import { ToolbarMode, useToolbarModeContext } from '../../flow_chat';
 import { FloatingMiniChat } from './FloatingMiniChat';
+import { OfflineBanner } from './OfflineBanner';
 import { NewProjectDialog } from '../components/NewProjectDialog';
 import { AboutDialog } from '../components/AboutDialog';
 import { WorkspaceManager } from '../../tools/workspace';
@@ -411,6 +412,8 @@ const AppLayout: React.FC<AppLayoutProps> = ({ className = '' }) => {
   return (
     <>
       <div className={containerClassName} data-testid="app-layout">
+        <OfflineBanner />
+
         {/* Main content — always render WorkspaceBody; WelcomeScene in viewport handles no-workspace state */}
         <main className="bitfun-app-main-workspace" data-testid="app-main-content">
           <WorkspaceBody
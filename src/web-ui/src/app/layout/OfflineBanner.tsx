@@ -0,0 +1,49 @@
+/**
+ * Offline banner — thin strip above the workspace while offline mode is on,
+ * either because `app.offline_mode` is set or the network stopped resolving.
+ * Hidden as soon as the backend reports it is back online.
+ */
+
+import React, { useEffect, useState } from 'react';
+import { WifiOff } from 'lucide-react';
+import { useI18n } from '@/infrastructure/i18n';
+import { systemAPI, type OfflineStatus } from '@/infrastructure/api/service-api/SystemAPI';
+import { createLogger } from '@/shared/utils/logger';
+import './OfflineBanner.scss';
+
+const log = createLogger('OfflineBanner');
+
+export const OfflineBanner: React.FC = () => {
+  const { t } = useI18n('components');
+  const [status, setStatus] = useState<OfflineStatus | null>(null);
+
+  useEffect(() => {
+    let disposed = false;
+    const unlisten = systemAPI.onOfflineModeChanged(next => setStatus(next));
+    systemAPI
+      .getOfflineStatus()
+      .then(initial => {
+        if (!disposed) setStatus(prev => prev ?? initial);
+      })
+      .catch(error => log.debug('Failed to load offline status', error));
+    return () => {
+      disposed = true;
+      unlisten();
+    };
+  }, []);
+
+  if (!status?.offline) return null;
+
+  const message = status.reason === 'dns_failures'
+    ? t('appLayout.offlineDnsFailures')
+    : t('appLayout.offlineConfigured');
+
+  return (
+    <div className="bitfun-offline-banner" role="status">
+      <WifiOff size={14} className="bitfun-offline-banner__icon" />
+      <span className="bitfun-offline-banner__text">{message}</span>
+    </div>
+  );
+};
+
+export default OfflineBanner;
@@ -144,9 +144,9 @@ export const ChatInput: React.FC<ChatInputProps> = ({
     [isAssistantWorkspace, modeState.available]
   );
 
-  /** Code session: only Plan and debug are optional on top of default agentic */
+  /** Code session: only Plan, debug and Review are optional on top of default agentic */
   const incrementalCodeModes = useMemo(
-    () => switchableModes.filter(m => m.id === 'Plan' || m.id === 'debug'),
+    () => switchableModes.filter(m => m.id === 'Plan' || m.id === 'debug' || m.id === 'Review'),
     [switchableModes]
   );
 
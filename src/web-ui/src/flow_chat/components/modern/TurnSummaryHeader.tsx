@@ -0,0 +1,73 @@
+/**
+ * Turn summary header.
+ * Three-bullet AI summary shown under the user message once the turn completed.
+ */
+
+import React, { useCallback } from 'react';
+import { useTranslation } from 'react-i18next';
+import { EyeOff, ListChecks } from 'lucide-react';
+import type { TurnSummary } from '@/shared/types/session-history';
+import { agentAPI } from '@/infrastructure/api/service-api/AgentAPI';
+import { notificationService } from '@/shared/notification-system';
+import { Tooltip } from '@/component-library';
+import { createLogger } from '@/shared/utils/logger';
+import { useFlowChatContext } from './FlowChatContext';
+import './TurnSummaryHeader.scss';
+
+const log = createLogger('TurnSummaryHeader');
+
+interface TurnSummaryHeaderProps {
+  summary: TurnSummary;
+}
+
+export const TurnSummaryHeader = React.memo<TurnSummaryHeaderProps>(({ summary }) => {
+  const { t } = useTranslation('flow-chat');
+  const { sessionId } = useFlowChatContext();
+
+  const handleSkipSession = useCallback(async () => {
+    if (!sessionId) return;
+    try {
+      await agentAPI.updateSessionTurnSummary({ sessionId, skip: true });
+    } catch (error) {
+      log.error('Failed to turn off turn summaries', { sessionId, error });
+      notificationService.error(t('turnSummary.skipFailed'));
+    }
+  }, [sessionId, t]);
+
+  const rows: Array<[string, string]> = [
+    [t('turnSummary.did'), summary.did],
+    [t('turnSummary.changed'), summary.changed],
+    [t('turnSummary.next'), summary.next],
+  ];
+
+  return (
+    <div className="turn-summary-header">
+      <div className="turn-summary-header__title">
+        <ListChecks size={12} />
+        <span>{t('turnSummary.title')}</span>
+        {sessionId && (
+          <Tooltip content={t('turnSummary.skipSession')} placement="top">
+            <button
+              type="button"
+              className="turn-summary-header__skip-btn"
+              onClick={handleSkipSession}
+              aria-label={t('turnSummary.skipSession')}
+            >
+              <EyeOff size={12} />
+            </button>
+          </Tooltip>
+        )}
+      </div>
+      <ul className="turn-summary-header__list">
+        {rows.map(([label, text]) => (
+          <li key={label} className="turn-summary-header__row">
+            <span className="turn-summary-header__label">{label}</span>
+            <span className="turn-summary-header__text">{text}</span>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+});
+
+TurnSummaryHeader.displayName = 'TurnSummaryHeader';
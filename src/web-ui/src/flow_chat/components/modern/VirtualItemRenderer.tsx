@@ -1,12 +1,13 @@
 /**
  * Virtual item renderer.
- * Renders user messages, model rounds, explore groups, or image-analyzing indicators by type.
+ * Renders user messages, turn summaries, model rounds, explore groups, or image-analyzing indicators by type.
  */
 
 import React from 'react';
 import { Loader2 } from 'lucide-react';
 import type { VirtualItem } from '../../store/modernFlowChatStore';
 import { UserMessageItem } from './UserMessageItem';
+import { TurnSummaryHeader } from './TurnSummaryHeader';
 import { ModelRoundItem } from './ModelRoundItem';
 import { ExploreGroupRenderer } from './ExploreGroupRenderer';
 import { CompactToolCard, CompactToolCardHeader } from '../../tool-cards/CompactToolCard';
@@ -23,6 +24,9 @@ export const VirtualItemRenderer = React.memo<VirtualItemRendererProps>(
       switch (item.type) {
         case 'user-message':
           return <UserMessageItem message={item.data} turnId={item.turnId} />;
+
+        case 'turn-summary':
+          return <TurnSummaryHeader summary={item.data} />;
         
         case 'model-round':
           return (
@@ -8,7 +8,7 @@
  */
 
 import { agentAPI } from '@/infrastructure/api/service-api/AgentAPI';
-import type { TextChunkEvent, ToolEvent, AgenticEvent, SessionTitleGeneratedEvent, ImageAnalysisEvent } from '@/infrastructure/api/service-api/AgentAPI';
+import type { TextChunkEvent, ToolEvent, AgenticEvent, SessionTitleGeneratedEvent, ImageAnalysisEvent, TurnSummaryGeneratedEvent } from '@/infrastructure/api/service-api/AgentAPI';
 import { createLogger } from '@/shared/utils/logger';
 
 type UnlistenFn = () => void;
@@ -33,6 +33,7 @@ export interface AgenticEventCallbacks {
   onContextCompressionCompleted?: (event: AgenticEvent) => void;
   onContextCompressionFailed?: (event: AgenticEvent) => void;
   onSessionTitleGenerated?: (event: SessionTitleGeneratedEvent) => void;
+  onTurnSummaryGenerated?: (event: TurnSummaryGeneratedEvent) => void;
 }
 
 export class AgenticEventListener {
@@ -182,6 +183,14 @@ export class AgenticEventListener {
         this.unlistenFunctions.push(unlisten);
       }
 
+      if (callbacks.onTurnSummaryGenerated) {
+        const unlisten = agentAPI.onTurnSummaryGenerated((event) => {
+          logger.debug('Turn summary generated:', event);
+          callbacks.onTurnSummaryGenerated?.(event);
+        });
+        this.unlistenFunctions.push(unlisten);
+      }
+
       this.isListening = true;
       logger.info(`Registered ${this.unlistenFunctions.length} event listeners`);
     } catch (error) {
@@ -183,6 +183,8 @@ export interface SubagentParentInfo {
   sessionId: string;
   toolCallId: string;
   dialogTurnId: string;
+  /** Model the subagent actually used */
+  modelId?: string;
 }
 
 export type ToolEventType =
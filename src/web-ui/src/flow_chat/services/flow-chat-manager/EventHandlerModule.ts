@@ -19,7 +19,7 @@ import {
 } from '../EventBatcher';
 import { notificationService } from '../../../shared/notification-system';
 import { createLogger } from '@/shared/utils/logger';
-import type { ImageAnalysisEvent } from '@/infrastructure/api/service-api/AgentAPI';
+import type { ImageAnalysisEvent, TurnSummaryGeneratedEvent } from '@/infrastructure/api/service-api/AgentAPI';
 import type { FlowChatContext, DialogTurn, ModelRound, FlowToolItem } from './types';
 
 const pendingImageAnalysisTurns = new Map<string, string>();
@@ -231,6 +231,9 @@ export async function initializeEventListeners(
     },
     onSessionTitleGenerated: (event) => {
       handleSessionTitleGenerated(event);
+    },
+    onTurnSummaryGenerated: (event) => {
+      handleTurnSummaryGenerated(context, event);
     }
   };
 
@@ -475,6 +478,19 @@ function handleSessionTitleGenerated(event: any): void {
   store.updateSessionTitle(sessionId, title, 'generated');
 }
 
+/**
+ * Handle turn summary generated event (arrives after the turn completed; already persisted by the backend)
+ */
+function handleTurnSummaryGenerated(context: FlowChatContext, event: TurnSummaryGeneratedEvent): void {
+  const { sessionId, turnId, did, changed, next } = event;
+  if (!sessionId || !turnId) return;
+
+  context.flowChatStore.updateDialogTurn(sessionId, turnId, turn => ({
+    ...turn,
+    summary: { did, changed, next },
+  }));
+}
+
 /**
  * Handle session deleted event (backend already deleted; only remove from store)
  */
@@ -1259,13 +1275,13 @@ function handleDialogTurnComplete(
  * Handle dialog turn failed event
  */
 function handleDialogTurnFailed(context: FlowChatContext, event: any): void {
-  const { sessionId, turnId, error, subagentParentInfo } = event;
+  const { sessionId, turnId, error, errorCode, retryable, subagentParentInfo } = event;
 
   if (subagentParentInfo) {
     return;
   }
   
-  log.error('Dialog turn failed', { sessionId, turnId, error });
+  log.error('Dialog turn failed', { sessionId, turnId, error, errorCode, retryable });
   clearPendingTurnCompletion(context, sessionId, turnId);
   
   const store = FlowChatStore.getInstance();
@@ -1311,6 +1327,8 @@ function handleDialogTurnFailed(context: FlowChatContext, event: any): void {
         modelRounds: updatedModelRounds,
         status: 'error' as const,
         error: error || 'Execution failed',
+        errorCode,
+        retryable: retryable === true,
         endTime: Date.now()
       };
     });
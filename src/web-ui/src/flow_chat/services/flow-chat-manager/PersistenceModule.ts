@@ -387,6 +387,7 @@ export function convertDialogTurnToBackendFormat(dialogTurn: DialogTurn, turnInd
     status: dialogTurn.status === 'completed' ? 'completed' : 
             dialogTurn.status === 'error' ? 'error' : 
             dialogTurn.status === 'cancelled' ? 'cancelled' : 'inprogress',
+    summary: dialogTurn.summary,
   };
 }
 
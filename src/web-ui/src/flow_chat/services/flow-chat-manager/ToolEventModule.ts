@@ -255,6 +255,7 @@ function handleEarlyDetected(
   flushPendingBatchedEvents(context);
   
   const shouldDisplayInMainFlow = toolEvent.tool_name === 'submit_code_review' || 
+                                 toolEvent.tool_name === 'submit_review_findings' ||
                                  toolEvent.tool_name === 'AskUserQuestion';
   
   const preparingToolItem: FlowToolItem = {
@@ -1555,7 +1555,7 @@ export class FlowChatStore {
             return prev;
           }
           
-          const VALID_AGENT_TYPES = ['agentic', 'debug', 'Plan', 'Cowork', 'Claw'];
+          const VALID_AGENT_TYPES = ['agentic', 'debug', 'Plan', 'Cowork', 'Claw', 'Review'];
           const rawAgentType = metadata.agentType || 'agentic';
           const validatedAgentType = VALID_AGENT_TYPES.includes(rawAgentType) ? rawAgentType : 'agentic';
           
@@ -1779,6 +1779,7 @@ export class FlowChatStore {
       status: turn.status,
       startTime: turn.startTime,
       backendTurnIndex: turn.turnIndex,
+      summary: turn.summary,
     };
     });
   }
@@ -38,6 +38,7 @@ export interface ExploreGroupData {
  */
 export type VirtualItem =
   | { type: 'user-message'; data: DialogTurn['userMessage']; turnId: string }
+  | { type: 'turn-summary'; data: NonNullable<DialogTurn['summary']>; turnId: string }
   | { type: 'model-round'; data: ModelRound; turnId: string; isLastRound: boolean }
   | { type: 'explore-group'; data: ExploreGroupData; turnId: string }
   | { type: 'image-analyzing'; turnId: string };
@@ -176,6 +177,10 @@ export function sessionToVirtualItems(session: Session | null): VirtualItem[] {
       });
     }
 
+    if (turn.summary) {
+      items.push({ type: 'turn-summary', data: turn.summary, turnId: turn.id });
+    }
+
     if (turn.status === 'image_analyzing' && turn.modelRounds.length === 0) {
       items.push({ type: 'image-analyzing', turnId: turn.id });
       return;
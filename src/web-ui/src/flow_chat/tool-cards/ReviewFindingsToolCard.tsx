@@ -0,0 +1,334 @@
+/**
+ * Review findings tool display component
+ * Shows the findings submitted by the Review agent as per-file annotations;
+ * findings with a suggested patch can be applied with one click.
+ */
+
+import React, { useState, useMemo, useCallback } from 'react';
+import { Loader2, AlertTriangle, AlertCircle, Info, Clock, ChevronDown, ChevronUp, Check, Wrench } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { Tooltip } from '@/component-library';
+import type { ToolCardProps } from '../types/flow-chat';
+import type { ReviewFinding, ReviewReport, ReviewSeverity } from '@/infrastructure/api/service-api/AgentAPI';
+import { toolAPI } from '@/infrastructure/api/service-api/ToolAPI';
+import { useCurrentWorkspace } from '../../infrastructure/contexts/WorkspaceContext';
+import { notificationService } from '@/shared/notification-system';
+import { BaseToolCard, ToolCardHeader } from './BaseToolCard';
+import { createLogger } from '@/shared/utils/logger';
+import { useToolCardHeightContract } from './useToolCardHeightContract';
+import './CodeReviewToolCard.scss';
+import './ReviewFindingsToolCard.scss';
+
+const log = createLogger('ReviewFindingsToolCard');
+
+const SEVERITY_ORDER: ReviewSeverity[] = ['critical', 'high', 'medium', 'low', 'info'];
+
+const severityColors: Record<ReviewSeverity, string> = {
+  critical: '#ef4444',
+  high: '#f97316',
+  medium: '#f59e0b',
+  low: '#22c55e',
+  info: '#6b7280'
+};
+
+type ApplyState = 'applying' | 'applied' | 'failed';
+
+const getSeverityIcon = (severity: ReviewSeverity) => {
+  const color = severityColors[severity] ?? severityColors.info;
+  switch (severity) {
+    case 'critical':
+      return <AlertCircle size={14} style={{ color }} />;
+    case 'high':
+    case 'medium':
+      return <AlertTriangle size={14} style={{ color }} />;
+    default:
+      return <Info size={14} style={{ color }} />;
+  }
+};
+
+const formatLocation = (finding: ReviewFinding) => {
+  if (!finding.start_line) return finding.file;
+  if (!finding.end_line || finding.end_line === finding.start_line) {
+    return `${finding.file}:${finding.start_line}`;
+  }
+  return `${finding.file}:${finding.start_line}-${finding.end_line}`;
+};
+
+export const ReviewFindingsToolCard: React.FC<ToolCardProps> = React.memo(({
+  toolItem
+}) => {
+  const { t } = useTranslation('flow-chat');
+  const { toolResult, status } = toolItem;
+  const { workspacePath } = useCurrentWorkspace();
+  const [isExpanded, setIsExpanded] = useState(false);
+  const [applyStates, setApplyStates] = useState<Record<number, ApplyState>>({});
+  const toolId = toolItem.id ?? toolItem.toolCall?.id;
+  const { cardRootRef, applyExpandedState } = useToolCardHeightContract({
+    toolId,
+    toolName: toolItem.toolName,
+  });
+
+  const report = useMemo<ReviewReport | null>(() => {
+    if (!toolResult?.result) return null;
+
+    try {
+      const result = typeof toolResult.result === 'string'
+        ? JSON.parse(toolResult.result)
+        : toolResult.result;
+      return Array.isArray(result?.findings) ? result as ReviewReport : null;
+    } catch (error) {
+      log.error('Failed to parse result', error);
+      return null;
+    }
+  }, [toolResult?.result]);
+
+  /** Findings grouped by file, most severe first; indices point into report.findings */
+  const findingsByFile = useMemo(() => {
+    if (!report) return [];
+
+    const groups = new Map<string, number[]>();
+    report.findings.forEach((finding, index) => {
+      const indices = groups.get(finding.file) ?? [];
+      indices.push(index);
+      groups.set(finding.file, indices);
+    });
+
+    const rank = (finding: ReviewFinding) => SEVERITY_ORDER.indexOf(finding.severity);
+    return Array.from(groups.entries()).map(([file, indices]) => ({
+      file,
+      indices: indices.sort((a, b) => {
+        const left = report.findings[a];
+        const right = report.findings[b];
+        return rank(left) - rank(right) || (left.start_line ?? 0) - (right.start_line ?? 0);
+      })
+    }));
+  }, [report]);
+
+  const severityCounts = useMemo(() => {
+    const counts: Partial<Record<ReviewSeverity, number>> = {};
+    report?.findings.forEach(finding => {
+      counts[finding.severity] = (counts[finding.severity] ?? 0) + 1;
+    });
+    return counts;
+  }, [report]);
+
+  const handleApplyFix = useCallback(async (index: number, finding: ReviewFinding) => {
+    if (!finding.suggested_patch || !workspacePath) return;
+
+    setApplyStates(prev => ({ ...prev, [index]: 'applying' }));
+    try {
+      await toolAPI.executeTool({
+        toolName: 'ApplyPatch',
+        parameters: { patch: finding.suggested_patch },
+        workspacePath
+      });
+      setApplyStates(prev => ({ ...prev, [index]: 'applied' }));
+      notificationService.success(t('toolCards.reviewFindings.fixApplied', { location: formatLocation(finding) }), { duration: 3000 });
+    } catch (error) {
+      log.error('Failed to apply suggested patch', { file: finding.file, error });
+      setApplyStates(prev => ({ ...prev, [index]: 'failed' }));
+      notificationService.error(t('toolCards.reviewFindings.fixFailed', {
+        error: error instanceof Error ? error.message : String(error)
+      }));
+    }
+  }, [t, workspacePath]);
+
+  const handleJumpToFinding = useCallback(async (finding: ReviewFinding) => {
+    if (!workspacePath) return;
+    try {
+      const { editorJumpService } = await import('../../shared/services/EditorJumpService');
+      const absolutePath = `${workspacePath.replace(/[\\/]+$/, '')}/${finding.file}`;
+      await editorJumpService.jumpToFile(absolutePath, finding.start_line ?? 1, 1);
+    } catch (error) {
+      log.error('Failed to open finding location', { file: finding.file, error });
+    }
+  }, [workspacePath]);
+
+  const hasData = report !== null;
+
+  const toggleExpanded = useCallback(() => {
+    applyExpandedState(isExpanded, !isExpanded, setIsExpanded);
+  }, [applyExpandedState, isExpanded]);
+
+  const handleCardClick = useCallback((e: React.MouseEvent) => {
+    const target = e.target as HTMLElement;
+    if (target.closest('.preview-toggle-btn') || target.closest('.review-finding-actions')) {
+      return;
+    }
+
+    if (hasData) {
+      toggleExpanded();
+    }
+  }, [hasData, toggleExpanded]);
+
+  const handleToggleExpand = useCallback((e: React.MouseEvent) => {
+    e.stopPropagation();
+    toggleExpanded();
+  }, [toggleExpanded]);
+
+  const getStatusIcon = () => {
+    switch (status) {
+      case 'running':
+      case 'streaming':
+        return <Loader2 className="animate-spin" size={12} />;
+      case 'completed':
+        return null;
+      case 'pending':
+      default:
+        return <Clock size={12} />;
+    }
+  };
+
+  const renderContent = () => {
+    if (status === 'completed' && report) {
+      const parts = SEVERITY_ORDER
+        .filter(severity => severityCounts[severity])
+        .map(severity => (
+          <span key={severity} style={{ color: severityColors[severity] }}>
+            {severityCounts[severity]} {t(`toolCards.codeReview.severities.${severity}`)}
+          </span>
+        ));
+
+      if (parts.length === 0) {
+        return <>{t('toolCards.reviewFindings.title')} — {t('toolCards.reviewFindings.noFindings')}</>;
+      }
+
+      return (
+        <>
+          {t('toolCards.reviewFindings.title')} —{' '}
+          {parts.reduce<React.ReactNode[]>((acc, part, i) => {
+            if (i > 0) acc.push(<span key={`sep-${i}`}>, </span>);
+            acc.push(part);
+            return acc;
+          }, [])}
+        </>
+      );
+    }
+
+    if (status === 'running' || status === 'streaming' || status === 'pending') {
+      return <>{t('toolCards.reviewFindings.submitting')}</>;
+    }
+
+    if (status === 'error') {
+      return <>{t('toolCards.reviewFindings.failed', { error: toolResult?.error || t('toolCards.codeReview.unknownError') })}</>;
+    }
+
+    return null;
+  };
+
+  const renderApplyButton = (index: number, finding: ReviewFinding) => {
+    if (!finding.suggested_patch) return null;
+
+    const state = applyStates[index];
+    if (state === 'applied') {
+      return (
+        <span className="review-finding-applied">
+          <Check size={12} />
+          {t('toolCards.reviewFindings.applied')}
+        </span>
+      );
+    }
+
+    return (
+      <button
+        className="review-finding-apply-btn"
+        disabled={state === 'applying' || !workspacePath}
+        onClick={() => handleApplyFix(index, finding)}
+      >
+        {state === 'applying' ? <Loader2 className="animate-spin" size={12} /> : <Wrench size={12} />}
+        {state === 'failed' ? t('toolCards.reviewFindings.retryFix') : t('toolCards.reviewFindings.applyFix')}
+      </button>
+    );
+  };
+
+  const renderExpandedContent = () => {
+    if (!report) return null;
+
+    return (
+      <div className="code-review-details review-findings-details">
+        {report.summary && (
+          <div className="review-summary">
+            <div className="summary-header">{t('toolCards.reviewFindings.summary')}</div>
+            <div className="summary-value">{report.summary}</div>
+          </div>
+        )}
+
+        {findingsByFile.map(({ file, indices }) => (
+          <div key={file} className="review-issues">
+            <div className="issues-header">
+              {file} ({indices.length})
+            </div>
+            <div className="issues-list">
+              {indices.map(index => {
+                const finding = report.findings[index];
+                return (
+                  <div
+                    key={index}
+                    className={`review-issue-item severity-${finding.severity}`}
+                  >
+                    <div className="issue-header">
+                      <div className="issue-left">
+                        {getSeverityIcon(finding.severity)}
+                        <span className="issue-category">[{finding.category}]</span>
+                        <span
+                          className="issue-location review-finding-location"
+                          onClick={() => handleJumpToFinding(finding)}
+                        >
+                          {formatLocation(finding)}
+                        </span>
+                      </div>
+                      <div className="review-finding-actions">
+                        {renderApplyButton(index, finding)}
+                      </div>
+                    </div>
+                    <div className="issue-description">{finding.message}</div>
+                    {finding.suggested_patch && (
+                      <pre className="review-finding-patch">{finding.suggested_patch}</pre>
+                    )}
+                  </div>
+                );
+              })}
+            </div>
+          </div>
+        ))}
+      </div>
+    );
+  };
+
+  const normalizedStatus = status === 'analyzing' ? 'running' : status;
+
+  return (
+    <div ref={cardRootRef} data-tool-card-id={toolId ?? ''}>
+      <BaseToolCard
+        status={normalizedStatus as 'pending' | 'preparing' | 'streaming' | 'running' | 'completed' | 'error' | 'cancelled'}
+        isExpanded={isExpanded}
+        onClick={handleCardClick}
+        className="code-review-card"
+        header={
+          <ToolCardHeader
+            icon={null}
+            iconClassName="code-review-icon"
+            content={renderContent()}
+            extra={
+              hasData && (
+                <Tooltip
+                  content={isExpanded ? t('toolCards.codeReview.collapseDetails') : t('toolCards.codeReview.expandDetails')}
+                  placement="top"
+                >
+                  <button
+                    className="preview-toggle-btn"
+                    onClick={handleToggleExpand}
+                  >
+                    {isExpanded ? <ChevronUp size={14} /> : <ChevronDown size={14} />}
+                  </button>
+                </Tooltip>
+              )
+            }
+            statusIcon={getStatusIcon()}
+          />
+        }
+        expandedContent={renderExpandedContent() ?? undefined}
+      />
+    </div>
+  );
+});
@@ -73,6 +73,7 @@ export const TerminalToolCard: React.FC<TerminalToolCardProps> = ({
   onConfirm,
   onReject,
   onExpand,
+  sessionId,
   terminalSessionId: propTerminalSessionId
 }) => {
   const { t } = useTranslation('flow-chat');
@@ -305,7 +306,7 @@ export const TerminalToolCard: React.FC<TerminalToolCardProps> = ({
     e.stopPropagation();
     
     const toolUseId = toolCall?.id;
-    if (!toolUseId) {
+    if (!toolUseId || !sessionId) {
       return;
     }
 
@@ -315,14 +316,15 @@ export const TerminalToolCard: React.FC<TerminalToolCardProps> = ({
       const { invoke } = await import('@tauri-apps/api/core');
       await invoke('cancel_tool', {
         request: {
+          sessionId,
           toolUseId: toolUseId,
           reason: 'User cancelled'
         }
       });
     } catch (error) {
-      log.error('Failed to send cancel signal', { toolUseId, error });
+      log.error('Failed to send cancel signal', { sessionId, toolUseId, error });
     }
-  }, [toolCall?.id]);
+  }, [sessionId, toolCall?.id]);
 
   const toggleExpand = useCallback(() => {
     const newExpanded = !isExpanded;
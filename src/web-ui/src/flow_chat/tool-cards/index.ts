@@ -16,6 +16,7 @@ import { TodoWriteDisplay } from './TodoWriteDisplay';
 import { TaskToolDisplay } from './TaskToolDisplay';
 import { MermaidInteractiveDisplay } from './MermaidInteractiveDisplay';
 import { CodeReviewToolCard } from './CodeReviewToolCard';
+import { ReviewFindingsToolCard } from './ReviewFindingsToolCard';
 import { FileOperationToolCard } from './FileOperationToolCard';
 import { DefaultToolCard } from './DefaultToolCard';
 import { WebSearchCard } from './WebSearchCard'; // Temporary until WebSearchDisplay exists.
@@ -172,6 +173,16 @@ export const TOOL_CARD_CONFIGS: Record<string, ToolCardConfig> = {
     displayMode: 'compact',
     primaryColor: '#8b5cf6'
   },
+  'submit_review_findings': {
+    toolName: 'submit_review_findings',
+    displayName: 'Review Findings',
+    icon: 'RF',
+    requiresConfirmation: false,
+    resultDisplayType: 'detailed',
+    description: 'Submit review findings with suggested patches',
+    displayMode: 'compact',
+    primaryColor: '#8b5cf6'
+  },
   'ContextCompression': {
     toolName: 'ContextCompression',
     displayName: 'Context Compression',
@@ -339,6 +350,7 @@ export const TOOL_CARD_COMPONENTS = {
   'MermaidInteractive': MermaidInteractiveDisplay,
   
   'submit_code_review': CodeReviewToolCard,
+  'submit_review_findings': ReviewFindingsToolCard,
   
   // Context compression
   'ContextCompression': ContextCompressionDisplay,
@@ -3,7 +3,7 @@
  * Supports mixed streaming output.
  */
 
-import type { SessionKind } from '@/shared/types/session-history';
+import type { SessionKind, TurnSummary } from '@/shared/types/session-history';
 
 // Base type for streaming items.
 export interface FlowItem {
@@ -135,9 +135,15 @@ export interface DialogTurn {
   startTime: number;
   endTime?: number;
   error?: string;
+  /** Stable backend error code for failed turns, e.g. `ai_rate_limited`. */
+  errorCode?: string;
+  /** Whether resending the failed turn may succeed. */
+  retryable?: boolean;
   tokenUsage?: TokenUsage;
   todos?: TodoItem[];
   backendTurnIndex?: number;
+  /** AI summary, arriving shortly after the turn completes */
+  summary?: TurnSummary;
 }
 
 export interface FlowChatState {
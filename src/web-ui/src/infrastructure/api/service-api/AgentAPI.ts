@@ -3,6 +3,7 @@
 import { api } from './ApiClient';
 import { createTauriCommandError } from '../errors/TauriCommandError';
 import type { ImageContextData as ImageInputContextData } from './ImageContextTypes';
+import type { TurnSummary } from '@/shared/types/session-history';
 
 
 
@@ -20,11 +21,20 @@ export interface SessionConfig {
   autoCompact?: boolean;
   enableTools?: boolean;
   safeMode?: boolean;
+  /** Model rounds per dialog turn */
   maxTurns?: number;
+  /** Tool calls per dialog turn before it wraps up */
+  maxToolCallsPerTurn?: number;
+  /** Wall-clock seconds per dialog turn before it wraps up */
+  maxTurnDurationSecs?: number;
   enableContextCompression?: boolean;
   compressionThreshold?: number;
   remoteConnectionId?: string;
   remoteSshHost?: string;
+  /** Environment variables for Bash commands, added to the workspace's `workspace.env` defaults. */
+  env?: Record<string, string>;
+  /** Leave this session's turns without an AI summary even when turn summaries are on. */
+  skipTurnSummary?: boolean;
 }
 
  
@@ -94,6 +104,17 @@ export interface UpdateSessionModelRequest {
   modelName: string;
 }
 
+export interface UpdateSessionTurnSummaryRequest {
+  sessionId: string;
+  skip: boolean;
+}
+
+export interface UpdateSessionEnvRequest {
+  sessionId: string;
+  /** Complete set of variables; values may be `keyring:<name>` secret references. */
+  env: Record<string, string>;
+}
+
  
 export interface Message {
   id: string;
@@ -118,6 +139,15 @@ export interface SubagentParentInfo {
   toolCallId: string;
   sessionId: string;
   dialogTurnId: string;
+  /** Model the subagent actually used */
+  modelId?: string;
+}
+
+/** Backend-rendered text with the key and arguments it was rendered from. */
+export interface LocalizedText {
+  key: string;
+  args?: Record<string, string>;
+  text: string;
 }
 
 export interface AgenticEvent {
@@ -141,6 +171,16 @@ export interface ToolEvent extends AgenticEvent {
 }
 
  
+export interface DialogTurnFailedEvent extends AgenticEvent {
+  error: string;
+  /** Stable error code, e.g. `ai_rate_limited`, `ai_auth`, `mcp_connection`. */
+  errorCode?: string;
+  /** Whether the frontend may offer to resend the turn. */
+  retryable?: boolean;
+  /** End-user explanation; re-render `key` with the frontend locale when available. */
+  userMessage?: LocalizedText;
+}
+
 export interface ImageAnalysisEvent extends AgenticEvent {
   imageCount?: number;
   userInput?: string;
@@ -166,6 +206,95 @@ export interface CompressionEvent extends AgenticEvent {
   subagentParentInfo?: SubagentParentInfo;
 }
 
+export interface ContextWindowWarningEvent extends AgenticEvent {
+  model: string;
+  estimatedTokens: number;
+  contextWindow: number;
+  subagentParentInfo?: SubagentParentInfo;
+}
+
+export interface SpendWarningEvent extends AgenticEvent {
+  spentTodayUsd: number;
+  thresholdUsd: number;
+}
+
+/** The turn was ended because the agent kept repeating the same tool calls */
+export interface LoopDetectedEvent extends AgenticEvent {
+  /** Rounds per repeated cycle; 1 when one round repeats */
+  cycleLength: number;
+  repetitions: number;
+  toolNames: string[];
+  description: string;
+  subagentParentInfo?: SubagentParentInfo;
+}
+
+/** Last event of an aborted turn, sent once its streams, tools and subagents stopped */
+export interface DialogTurnAbortedEvent extends AgenticEvent {
+  /** Work that had not stopped when the grace period ran out */
+  unfinished: string[];
+}
+
+/** A turn budget ran out and the turn ended with a summary instead of more tool calls */
+export interface TurnBudgetExhaustedEvent extends AgenticEvent {
+  budget: 'rounds' | 'tool_calls' | 'duration';
+  /** In rounds, tool calls or seconds */
+  used: number;
+  limit: number;
+  subagentParentInfo?: SubagentParentInfo;
+}
+
+/** A subagent task reused the result of an identical earlier run; it used no tokens */
+export interface SubagentResultCachedEvent extends AgenticEvent {
+  toolCallId: string;
+  subagentType: string;
+  ageSecs: number;
+}
+
+export interface RecentChangesAttachedEvent extends AgenticEvent {
+  /** Relative to the workspace root, newest first */
+  files: string[];
+  estimatedTokens: number;
+}
+
+export type ReviewSeverity = 'critical' | 'high' | 'medium' | 'low' | 'info';
+
+export interface ReviewFinding {
+  /** Relative to the workspace root */
+  file: string;
+  start_line: number | null;
+  end_line: number | null;
+  severity: ReviewSeverity;
+  category: string;
+  message: string;
+  /** Unified diff, applicable with the ApplyPatch tool */
+  suggested_patch?: string;
+}
+
+export interface ReviewReport {
+  summary: string;
+  findings: ReviewFinding[];
+}
+
+export interface ReviewResultEvent extends AgenticEvent {
+  toolCallId: string;
+  report: ReviewReport;
+}
+
+export interface TurnSummaryGeneratedEvent extends AgenticEvent, TurnSummary {}
+
+export interface SpendConfirmationRequiredEvent extends AgenticEvent {
+  estimatedCostUsd: number;
+  thresholdUsd: number;
+}
+
+export interface SpendSummary {
+  /** UTC date the daily totals refer to (YYYY-MM-DD) */
+  date: string;
+  todayUsd: number;
+  sessionUsd?: number;
+  workspaceUsd?: number;
+}
+
 
 
 export class AgentAPI {
@@ -270,6 +399,24 @@ export class AgentAPI {
     }
   }
 
+  /** Takes effect when the session's next turn completes. */
+  async updateSessionTurnSummary(request: UpdateSessionTurnSummaryRequest): Promise<void> {
+    try {
+      await api.invoke<void>('update_session_turn_summary', { request });
+    } catch (error) {
+      throw createTauriCommandError('update_session_turn_summary', error, request);
+    }
+  }
+
+  /** Takes effect on the session's next tool call. */
+  async updateSessionEnv(request: UpdateSessionEnvRequest): Promise<void> {
+    try {
+      await api.invoke<void>('update_session_env', { request });
+    } catch (error) {
+      throw createTauriCommandError('update_session_env', error, request);
+    }
+  }
+
 
    
   async listSessions(
@@ -314,6 +461,33 @@ export class AgentAPI {
     }
   }
 
+  async confirmTurnSpend(sessionId: string, turnId: string, approved: boolean): Promise<void> {
+    try {
+      await api.invoke<void>('confirm_turn_spend', {
+        request: {
+          sessionId,
+          turnId,
+          approved
+        }
+      });
+    } catch (error) {
+      throw createTauriCommandError('confirm_turn_spend', error, { sessionId, turnId, approved });
+    }
+  }
+
+  async getSpendSummary(sessionId?: string, workspacePath?: string): Promise<SpendSummary> {
+    try {
+      return await api.invoke<SpendSummary>('get_spend_summary', {
+        request: {
+          sessionId,
+          workspacePath
+        }
+      });
+    } catch (error) {
+      throw createTauriCommandError('get_spend_summary', error, { sessionId, workspacePath });
+    }
+  }
+
    
   async rejectToolExecution(sessionId: string, toolId: string, reason?: string): Promise<void> {
     try {
@@ -369,8 +543,8 @@ export class AgentAPI {
   }
 
    
-  onDialogTurnFailed(callback: (event: AgenticEvent) => void): () => void {
-    return api.listen<AgenticEvent>('agentic://dialog-turn-failed', callback);
+  onDialogTurnFailed(callback: (event: DialogTurnFailedEvent) => void): () => void {
+    return api.listen<DialogTurnFailedEvent>('agentic://dialog-turn-failed', callback);
   }
 
    
@@ -378,6 +552,10 @@ export class AgentAPI {
     return api.listen<AgenticEvent>('agentic://dialog-turn-cancelled', callback);
   }
 
+  onDialogTurnAborted(callback: (event: DialogTurnAbortedEvent) => void): () => void {
+    return api.listen<DialogTurnAbortedEvent>('agentic://dialog-turn-aborted', callback);
+  }
+
    
   onTokenUsageUpdated(callback: (event: AgenticEvent) => void): () => void {
     return api.listen<AgenticEvent>('agentic://token-usage-updated', callback);
@@ -398,6 +576,42 @@ export class AgentAPI {
     return api.listen<CompressionEvent>('agentic://context-compression-failed', callback);
   }
 
+  onContextWindowWarning(callback: (event: ContextWindowWarningEvent) => void): () => void {
+    return api.listen<ContextWindowWarningEvent>('agentic://context-window-warning', callback);
+  }
+
+  onSpendWarning(callback: (event: SpendWarningEvent) => void): () => void {
+    return api.listen<SpendWarningEvent>('agentic://spend-warning', callback);
+  }
+
+  onLoopDetected(callback: (event: LoopDetectedEvent) => void): () => void {
+    return api.listen<LoopDetectedEvent>('agentic://loop-detected', callback);
+  }
+
+  onTurnBudgetExhausted(callback: (event: TurnBudgetExhaustedEvent) => void): () => void {
+    return api.listen<TurnBudgetExhaustedEvent>('agentic://turn-budget-exhausted', callback);
+  }
+
+  onSubagentResultCached(callback: (event: SubagentResultCachedEvent) => void): () => void {
+    return api.listen<SubagentResultCachedEvent>('agentic://subagent-result-cached', callback);
+  }
+
+  onRecentChangesAttached(callback: (event: RecentChangesAttachedEvent) => void): () => void {
+    return api.listen<RecentChangesAttachedEvent>('agentic://recent-changes-attached', callback);
+  }
+
+  onReviewResult(callback: (event: ReviewResultEvent) => void): () => void {
+    return api.listen<ReviewResultEvent>('agentic://review-result', callback);
+  }
+
+  onTurnSummaryGenerated(callback: (event: TurnSummaryGeneratedEvent) => void): () => void {
+    return api.listen<TurnSummaryGeneratedEvent>('agentic://turn-summary-generated', callback);
+  }
+
+  onSpendConfirmationRequired(callback: (event: SpendConfirmationRequiredEvent) => void): () => void {
+    return api.listen<SpendConfirmationRequiredEvent>('agentic://spend-confirmation-required', callback);
+  }
+
   onImageAnalysisStarted(callback: (event: ImageAnalysisEvent) => void): () => void {
     return api.listen<ImageAnalysisEvent>('agentic://image-analysis-started', callback);
   }
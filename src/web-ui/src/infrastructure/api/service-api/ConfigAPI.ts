@@ -3,7 +3,20 @@
 import { api } from './ApiClient';
 import { createTauriCommandError } from '../errors/TauriCommandError';
 import type {
+  AnnotatedConfig,
+  AvailableModel,
+  BackendLogLevel,
+  ConfigChangedEvent,
+  EffectiveAgentTools,
+  LogLineFilter,
+  LogSessionInfo,
+  LogTailResult,
+  LogTarget,
+  PlaintextSecret,
   RuntimeLoggingInfo,
+  SettingsExportSummary,
+  SettingsImportReport,
+  SettingsMergeStrategy,
   SkillInfo,
   SkillLevel,
   SkillMarketDownloadResult,
@@ -62,6 +75,84 @@ export class ConfigAPI {
     }
   }
 
+  /** Effective config (or the section at `path`) with the source layer of each value. */
+  async getConfigWithSources<T = unknown>(path?: string): Promise<AnnotatedConfig<T>> {
+    try {
+      return await api.invoke('get_config', {
+        request: path ? { path, with_sources: true } : { with_sources: true },
+      });
+    } catch (error) {
+      throw createTauriCommandError('get_config', error, { path });
+    }
+  }
+
+  /** Configured models with capabilities, context window and prices resolved against known models. */
+  async getAvailableModels(): Promise<AvailableModel[]> {
+    try {
+      return await api.invoke('get_available_models');
+    } catch (error) {
+      throw createTauriCommandError('get_available_models', error);
+    }
+  }
+
+  /** Secrets stored in plaintext in the config; `get_config` returns them redacted. */
+  async findPlaintextSecrets(): Promise<PlaintextSecret[]> {
+    try {
+      return await api.invoke('find_plaintext_secrets');
+    } catch (error) {
+      throw createTauriCommandError('find_plaintext_secrets', error);
+    }
+  }
+
+  /** Moves plaintext secrets (all, or those at `paths`) into the OS keyring. */
+  async migratePlaintextSecrets(paths?: string[]): Promise<PlaintextSecret[]> {
+    try {
+      return await api.invoke('migrate_plaintext_secrets', {
+        request: paths ? { paths } : {},
+      });
+    } catch (error) {
+      throw createTauriCommandError('migrate_plaintext_secrets', error, { paths });
+    }
+  }
+
+  /**
+   * Writes the settings to a bundle file. Secrets are only included with
+   * `passphrase`, encrypted with it.
+   */
+  async exportSettings(path: string, passphrase?: string): Promise<SettingsExportSummary> {
+    try {
+      return await api.invoke('export_settings', {
+        request: { path, include_secrets: passphrase !== undefined, passphrase },
+      });
+    } catch (error) {
+      throw createTauriCommandError('export_settings', error, { path });
+    }
+  }
+
+  /** Imports a settings bundle file; with `dryRun` only reports what would change. */
+  async importSettings(
+    path: string,
+    options: { mergeStrategy?: SettingsMergeStrategy; passphrase?: string; dryRun?: boolean } = {}
+  ): Promise<SettingsImportReport> {
+    try {
+      return await api.invoke('import_settings', {
+        request: {
+          path,
+          merge_strategy: options.mergeStrategy ?? 'merge',
+          passphrase: options.passphrase,
+          dry_run: options.dryRun ?? false,
+        },
+      });
+    } catch (error) {
+      throw createTauriCommandError('import_settings', error, { path });
+    }
+  }
+
+  /** Fires when the config file was edited outside the app and reloaded. */
+  onConfigChanged(callback: (event: ConfigChangedEvent) => void): () => void {
+    return api.listen<ConfigChangedEvent>('config-changed', callback);
+  }
+
    
   async setConfig(path: string, value: any): Promise<void> {
     try {
@@ -127,6 +218,76 @@ export class ConfigAPI {
     }
   }
 
+  /** Set the level override of a log target; `null` removes it. */
+  async setLogTargetLevel(
+    target: string,
+    level: BackendLogLevel | null
+  ): Promise<RuntimeLoggingInfo> {
+    try {
+      return await api.invoke('set_log_target_level', {
+        request: { target, level },
+      });
+    } catch (error) {
+      throw createTauriCommandError('set_log_target_level', error);
+    }
+  }
+
+  /** Read the last lines of a log file; `session` defaults to the running session. */
+  async readLogTail(
+    target: LogTarget,
+    lines?: number,
+    filter?: LogLineFilter,
+    session?: string
+  ): Promise<LogTailResult> {
+    try {
+      return await api.invoke('read_log_tail', {
+        request: { target, lines, filter, session },
+      });
+    } catch (error) {
+      throw createTauriCommandError('read_log_tail', error);
+    }
+  }
+
+  /** Stream appended lines as `log://lines` events. Returns the subscription ID. */
+  async tailLog(target: LogTarget, filter?: LogLineFilter): Promise<string> {
+    try {
+      return await api.invoke('tail_log', {
+        request: { target, filter },
+      });
+    } catch (error) {
+      throw createTauriCommandError('tail_log', error);
+    }
+  }
+
+  async stopTailLog(subscriptionId: string): Promise<void> {
+    try {
+      await api.invoke('stop_tail_log', {
+        request: { subscriptionId },
+      });
+    } catch (error) {
+      throw createTauriCommandError('stop_tail_log', error);
+    }
+  }
+
+  async listLogSessions(): Promise<LogSessionInfo[]> {
+    try {
+      return await api.invoke('list_log_sessions', {});
+    } catch (error) {
+      throw createTauriCommandError('list_log_sessions', error);
+    }
+  }
+
+  /** Zip a log session for a bug report. Returns the archive path. */
+  async exportLogSession(destinationPath: string, session?: string): Promise<string> {
+    try {
+      return await api.invoke('export_log_session', {
+        request: { destinationPath, session },
+      });
+    } catch (error) {
+      throw createTauriCommandError('export_log_session', error);
+    }
+  }
+
    
   async getModelConfigs(): Promise<any[]> {
     try {
@@ -198,6 +359,15 @@ export class ConfigAPI {
     }
   }
 
+  /** Tools `agentType` is offered in `workspacePath` (the active workspace when omitted). */
+  async getEffectiveAgentTools(agentType: string, workspacePath?: string): Promise<EffectiveAgentTools> {
+    try {
+      return await api.invoke('get_effective_agent_tools', { agentType, workspacePath });
+    } catch (error) {
+      throw createTauriCommandError('get_effective_agent_tools', error, { agentType, workspacePath });
+    }
+  }
+
   
 
    
@@ -79,9 +79,33 @@ export interface GitPushParams {
   remote?: string;
   branch?: string;
   force?: boolean;
+  /** Set once the user confirmed a force-push; the backend refuses it otherwise */
+  forceConfirmed?: boolean;
   setUpstream?: boolean;
 }
 
+export interface GitFetchParams {
+  remote?: string;
+  prune?: boolean;
+}
+
+/** Payload of the `git-transfer-progress` event */
+export interface GitTransferProgressEvent {
+  repositoryPath: string;
+  operation: 'fetch' | 'pull' | 'push';
+  remote: string;
+  progress: {
+    phase: string;
+    percent?: number | null;
+    current?: number | null;
+    total?: number | null;
+    transferred?: string | null;
+    speed?: string | null;
+    done: boolean;
+    remoteSide: boolean;
+  };
+}
+
 export interface GitPullParams {
   remote?: string;
   branch?: string;
@@ -243,6 +267,17 @@ export class GitAPI {
   }
 
    
+  async fetch(repositoryPath: string, params: GitFetchParams = {}): Promise<GitOperationResult> {
+    try {
+      return await api.invoke('git_fetch', { 
+        request: { repositoryPath, params } 
+      });
+    } catch (error) {
+      throw createTauriCommandError('git_fetch', error, { repositoryPath, params });
+    }
+  }
+
+   
   async push(repositoryPath: string, params: GitPushParams = {}): Promise<GitOperationResult> {
     try {
       
@@ -250,6 +285,7 @@ export class GitAPI {
         remote: params.remote,
         branch: params.branch,
         force: params.force,
+        force_confirmed: params.forceConfirmed,
         set_upstream: params.setUpstream
       };
       
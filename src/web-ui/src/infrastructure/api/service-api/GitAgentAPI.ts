@@ -61,6 +61,10 @@ export interface CommitMessageOptions {
   maxTitleLength?: number;
   includeBody?: boolean;
   language?: Language;
+  inferScope?: boolean;
+  allowedTypes?: CommitType[];
+  detectBreakingChanges?: boolean;
+  maxReprompts?: number;
 }
 
 
@@ -83,6 +87,7 @@ export interface ChangesSummary {
   fileChanges: FileChange[];
   affectedModules: string[];
   changePatterns: ChangePattern[];
+  partiallyStagedFiles: string[];
 }
 
 export interface FileChange {
@@ -93,6 +98,81 @@ export interface FileChange {
   fileType: string;
 }
 
+export type ChangelogSection = 'Added' | 'Changed' | 'Deprecated' | 'Removed' | 'Fixed' | 'Security';
+
+export interface ChangelogOptions {
+  /** Revision range such as `v0.3.0..HEAD`; defaults to everything since the last tag */
+  range?: string;
+  version?: string;
+  includeReferences?: boolean;
+  humanize?: boolean;
+  language?: Language;
+}
+
+export interface GenerateChangelogRequest {
+  repoPath: string;
+  options?: ChangelogOptions;
+  write?: boolean;
+}
+
+export interface ChangelogEntry {
+  text: string;
+  references: string[];
+  commits: string[];
+}
+
+export interface Changelog {
+  version: string;
+  date?: string;
+  range: string;
+  commitCount: number;
+  sections: Partial<Record<ChangelogSection, ChangelogEntry[]>>;
+  notes: string[];
+  markdown: string;
+}
+
+export interface GenerateChangelogResponse {
+  changelog: Changelog;
+  writtenTo?: string;
+}
+
+export interface PrDescriptionOptions {
+  /** Branch the PR merges into; detected from origin/HEAD, then main/master */
+  base?: string;
+  /** Markdown with {title}, {summary}, {changes}, {test_notes}, {risk} and {rollback} placeholders */
+  template?: string;
+  maxTitleLength?: number;
+  maxDiffChars?: number;
+  commitBatchSize?: number;
+  language?: Language;
+}
+
+export interface GeneratePrDescriptionRequest {
+  repoPath: string;
+  options?: PrDescriptionOptions;
+  /** Also write the markdown here, relative to the repository */
+  outputPath?: string;
+}
+
+export interface PrCommit {
+  shortHash: string;
+  subject: string;
+}
+
+export interface PrDescription {
+  title: string;
+  summary: string;
+  changes: string[];
+  testNotes: string[];
+  risk: string;
+  rollback: string;
+  base: string;
+  head: string;
+  commits: PrCommit[];
+  fileChanges: FileChange[];
+  markdown: string;
+}
+
 export interface PreviewCommitMessageResponse {
   title: string;
   commitType: string;
@@ -142,6 +222,24 @@ export class GitAgentAPI {
   async previewCommit(repoPath: string): Promise<PreviewCommitMessageResponse> {
     return this.previewCommitMessage({ repoPath });
   }
+
+   
+  async generateChangelog(request: GenerateChangelogRequest): Promise<GenerateChangelogResponse> {
+    try {
+      return await api.invoke<GenerateChangelogResponse>('generate_changelog', { request });
+    } catch (error) {
+      throw createTauriCommandError('generate_changelog', error, request);
+    }
+  }
+
+   
+  async generatePrDescription(request: GeneratePrDescriptionRequest): Promise<PrDescription> {
+    try {
+      return await api.invoke<PrDescription>('generate_pr_description', { request });
+    } catch (error) {
+      throw createTauriCommandError('generate_pr_description', error, request);
+    }
+  }
 }
 
 
@@ -49,6 +49,7 @@ export interface WorkspaceInfo {
   languages: string[];
   openedAt: string;
   lastAccessed: string;
+  openCount: number;
   description?: string | null;
   tags: string[];
   statistics?: ProjectStatistics | null;
@@ -60,6 +61,34 @@ export interface WorkspaceInfo {
   sshHost?: string;
 }
 
+export interface RecentWorkspace {
+  workspace: WorkspaceInfo;
+  pinned: boolean;
+  /** False when the workspace folder has been deleted or moved. */
+  exists: boolean;
+}
+
+export interface RecentWorkspaceOpenResult {
+  workspace: WorkspaceInfo;
+  /** Last active session in the workspace, to restore after opening. */
+  lastSessionId: string | null;
+}
+
+export interface WorkspaceQuickStats {
+  workspaceId: string;
+  exists: boolean;
+  lastSession: {
+    sessionId: string;
+    title: string;
+    lastActiveAt: number;
+  } | null;
+  git: {
+    branch: string;
+    dirty: boolean;
+    changedFiles: number;
+  } | null;
+}
+
 export interface UpdateAppStatusRequest {
   status: AppStatus;
 }
@@ -256,6 +285,54 @@ export class GlobalAPI {
     }
   }
 
+  async listRecentWorkspaces(): Promise<RecentWorkspace[]> {
+    try {
+      return await api.invoke('list_recent_workspaces');
+    } catch (error) {
+      throw createTauriCommandError('list_recent_workspaces', error);
+    }
+  }
+
+  async setWorkspacePinned(workspaceId: string, pinned: boolean): Promise<void> {
+    try {
+      await api.invoke('set_workspace_pinned', {
+        request: { workspaceId, pinned }
+      });
+    } catch (error) {
+      throw createTauriCommandError('set_workspace_pinned', error, { workspaceId, pinned });
+    }
+  }
+
+  async removeRecentWorkspace(workspaceId: string): Promise<void> {
+    try {
+      await api.invoke('remove_recent_workspace', {
+        request: { workspaceId }
+      });
+    } catch (error) {
+      throw createTauriCommandError('remove_recent_workspace', error, { workspaceId });
+    }
+  }
+
+  async getWorkspaceQuickStats(workspaceId: string): Promise<WorkspaceQuickStats> {
+    try {
+      return await api.invoke('get_workspace_quick_stats', {
+        request: { workspaceId }
+      });
+    } catch (error) {
+      throw createTauriCommandError('get_workspace_quick_stats', error, { workspaceId });
+    }
+  }
+
+  async openRecentWorkspace(workspaceId: string): Promise<RecentWorkspaceOpenResult> {
+    try {
+      return await api.invoke('open_recent_workspace', {
+        request: { workspaceId }
+      });
+    } catch (error) {
+      throw createTauriCommandError('open_recent_workspace', error, { workspaceId });
+    }
+  }
+
   async cleanupInvalidWorkspaces(): Promise<number> {
     try {
       return await api.invoke('cleanup_invalid_workspaces');
@@ -13,7 +13,8 @@ export type MCPServerStatus =
   | 'Reconnecting'
   | 'Failed'
   | 'Stopping'
-  | 'Stopped';
+  | 'Stopped'
+  | 'Offline';
 
  
 export interface MCPServerInfo {
@@ -543,6 +543,75 @@ export class SnapshotAPI {
       throw createTauriCommandError('get_all_modified_files', error, { workspacePath });
     }
   }
+
+  async captureWorkspaceSnapshot(workspacePath?: string, sessionId?: string): Promise<WorkspaceSnapshotInfo> {
+    try {
+      const resolvedWorkspacePath = requireWorkspacePath(workspacePath);
+      return await api.invoke('capture_workspace_snapshot', {
+        request: { workspacePath: resolvedWorkspacePath, sessionId }
+      });
+    } catch (error) {
+      throw createTauriCommandError('capture_workspace_snapshot', error, { workspacePath, sessionId });
+    }
+  }
+
+  async listWorkspaceSnapshots(workspacePath?: string): Promise<WorkspaceSnapshotInfo[]> {
+    try {
+      const resolvedWorkspacePath = requireWorkspacePath(workspacePath);
+      return await api.invoke('list_workspace_snapshots', {
+        request: { workspacePath: resolvedWorkspacePath }
+      });
+    } catch (error) {
+      throw createTauriCommandError('list_workspace_snapshots', error, { workspacePath });
+    }
+  }
+
+  async deleteWorkspaceSnapshot(snapshotId: string, workspacePath?: string): Promise<void> {
+    try {
+      const resolvedWorkspacePath = requireWorkspacePath(workspacePath);
+      await api.invoke('delete_workspace_snapshot', {
+        request: { workspacePath: resolvedWorkspacePath, snapshotId }
+      });
+    } catch (error) {
+      throw createTauriCommandError('delete_workspace_snapshot', error, { snapshotId, workspacePath });
+    }
+  }
+
+  /**
+   * Files added, removed and modified between two workspace snapshots.
+   * Diffs are size-capped; `diff` is null for binary or oversized files.
+   */
+  async compareWorkspaceSnapshots(
+    fromSnapshotId: string,
+    toSnapshotId: string,
+    workspacePath?: string,
+  ): Promise<SnapshotComparison> {
+    try {
+      const resolvedWorkspacePath = requireWorkspacePath(workspacePath);
+      return await api.invoke('compare_workspace_snapshots', {
+        request: { workspacePath: resolvedWorkspacePath, fromSnapshotId, toSnapshotId }
+      });
+    } catch (error) {
+      throw createTauriCommandError('compare_workspace_snapshots', error, {
+        fromSnapshotId,
+        toSnapshotId,
+        workspacePath,
+      });
+    }
+  }
+
+  /**
+   * Workspace snapshot events. A `WorkspaceSnapshotsCompared` event follows each
+   * Cowork run or unconfirmed-tools turn that was snapshotted before it started.
+   */
+  onWorkspaceSnapshotEvent(callback: (payload: SnapshotEventPayload) => void): () => void {
+    return api.listen<SnapshotEventPayload>('snapshot-event', (payload) => {
+      const type = payload?.event_data?.type;
+      if (type === 'WorkspaceSnapshotCreated' || type === 'WorkspaceSnapshotsCompared') {
+        callback(payload);
+      }
+    });
+  }
 }
 
 
@@ -567,4 +636,72 @@ export interface FileChangeEntry {
 }
 
 
+export type WorkspaceSnapshotTrigger = 'manual' | 'cowork_run' | 'risky_turn' | 'run_end';
+
+
+export interface WorkspaceSnapshotInfo {
+  snapshot_id: string;
+  trigger: WorkspaceSnapshotTrigger;
+  session_id: string | null;
+  /** Milliseconds since the Unix epoch */
+  created_at: number;
+  file_count: number;
+  truncated: boolean;
+}
+
+
+export interface FileComparison {
+  path: string;
+  change: 'added' | 'removed' | 'modified';
+  old_size: number | null;
+  new_size: number | null;
+  lines_added: number;
+  lines_removed: number;
+  diff: string | null;
+  diff_truncated: boolean;
+}
+
+
+export interface SnapshotComparison {
+  from_snapshot_id: string;
+  to_snapshot_id: string;
+  added: number;
+  removed: number;
+  modified: number;
+  files: FileComparison[];
+  diffs_truncated: boolean;
+}
+
+
+export type WorkspaceSnapshotEvent =
+  | {
+      type: 'WorkspaceSnapshotCreated';
+      data: {
+        snapshot_id: string;
+        session_id: string | null;
+        trigger: WorkspaceSnapshotTrigger;
+        file_count: number;
+        timestamp: number;
+      };
+    }
+  | {
+      type: 'WorkspaceSnapshotsCompared';
+      data: {
+        session_id: string | null;
+        from_snapshot_id: string;
+        to_snapshot_id: string;
+        added: number;
+        removed: number;
+        modified: number;
+        timestamp: number;
+      };
+    };
+
+
+export interface SnapshotEventPayload {
+  snapshot_id: string;
+  event_data: WorkspaceSnapshotEvent;
+}
+
+
 export const snapshotAPI = new SnapshotAPI();
@@ -2,6 +2,7 @@
 
 import { api } from './ApiClient';
 import { createTauriCommandError } from '../errors/TauriCommandError';
+import type { LocalizedText } from './AgentAPI';
 
  
 export interface WorkStateOptions {
@@ -23,6 +24,10 @@ export interface GreetingMessage {
   subtitle: string;
    
   tagline?: string;
+   
+  titleI18n?: LocalizedText;
+   
+  subtitleI18n?: LocalizedText;
 }
 
  
@@ -111,7 +116,9 @@ export interface QuickAction {
    
   icon: string;
    
-  actionType: 'Continue' | 'ViewStatus' | 'Commit' | 'Visualize' | 'Custom';
+  actionType: 'Continue' | 'ViewStatus' | 'Commit' | 'Visualize' | 'CreateBranch' | 'Custom';
+   
+  branchName?: string;
 }
 
  
@@ -145,6 +152,20 @@ export interface WorkStateSummaryResponse {
 }
 
  
+export interface BranchCreation {
+   
+  branchName: string;
+   
+  greeting: GreetingMessage;
+}
+
+ 
+export interface GreetingUpdatedEvent extends BranchCreation {
+   
+  repoPath: string;
+}
+
+ 
 export class StartchatAgentAPI {
    
   async analyzeWorkState(
@@ -205,6 +226,25 @@ export class StartchatAgentAPI {
       throw createTauriCommandError('get_work_state_summary', error, { repoPath });
     }
   }
+
+   
+  async createSuggestedBranch(repoPath: string, branchName: string): Promise<BranchCreation> {
+    try {
+      return await api.invoke('create_suggested_branch', {
+        request: {
+          repoPath,
+          branchName
+        }
+      });
+    } catch (error) {
+      throw createTauriCommandError('create_suggested_branch', error, { repoPath, branchName });
+    }
+  }
+
+   
+  onGreetingUpdated(callback: (event: GreetingUpdatedEvent) => void): () => void {
+    return api.listen<GreetingUpdatedEvent>('startchat://greeting-updated', callback);
+  }
 }
 
 
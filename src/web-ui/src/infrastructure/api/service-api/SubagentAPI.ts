@@ -31,6 +31,12 @@ export interface ReloadSubagentsOptions {
   workspacePath?: string;
 }
 
+/** Custom agent file that could not be registered (parse error, built-in name). */
+export interface SubagentLoadError {
+  path: string;
+  message: string;
+}
+
 export type SubagentLevel = 'user' | 'project';
 
 export interface CreateSubagentPayload {
@@ -61,8 +67,8 @@ export const SubagentAPI = {
   },
 
    
-  async reloadSubagents(options: ReloadSubagentsOptions = {}): Promise<void> {
-    return api.invoke('reload_subagents', {
+  async reloadSubagents(options: ReloadSubagentsOptions = {}): Promise<SubagentLoadError[]> {
+    return api.invoke<SubagentLoadError[]>('reload_subagents', {
       request: options,
     });
   },
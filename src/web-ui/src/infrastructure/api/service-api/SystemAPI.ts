@@ -9,6 +9,60 @@ import { createLogger } from '@/shared/utils/logger';
 
 const log = createLogger('SystemAPI');
 
+export interface HttpClientPoolInfo {
+  origin: string;
+  proxied: boolean;
+  customCa: boolean;
+  skipSslVerify: boolean;
+  ageSecs: number;
+  reuses: number;
+  requests: number;
+  failures: number;
+  coldLatencyMs: number | null;
+  avgWarmLatencyMs: number | null;
+}
+
+export interface HttpTransportStats {
+  poolMaxIdlePerHost: number;
+  poolIdleTimeoutSecs: number;
+  http2KeepAliveIntervalSecs: number;
+  cacheHits: number;
+  cacheMisses: number;
+  clients: HttpClientPoolInfo[];
+}
+
+/** Why offline mode is on: `app.offline_mode` is set, or host names stopped resolving. */
+export type OfflineReason = 'configured' | 'dns_failures';
+
+export interface OfflineStatus {
+  offline: boolean;
+  reason: OfflineReason | null;
+}
+
+/** Payload of the `crash-reports-available` event. */
+export interface CrashReportSummary {
+  id: string;
+  createdAt: string;
+  message: string;
+  path: string;
+}
+
+export interface CrashReport {
+  id: string;
+  createdAt: string;
+  appVersion: string;
+  os: string;
+  arch: string;
+  thread: string | null;
+  task: string | null;
+  message: string;
+  location: string | null;
+  backtrace: string;
+  sessionId: string | null;
+  /** Last log lines, with secrets masked and long lines cut. */
+  logTail: string[];
+}
+
 export class SystemAPI {
    
   async getSystemInfo(): Promise<any> {
@@ -152,6 +206,35 @@ export class SystemAPI {
     }
   }
 
+  /** Connection pool settings and per-endpoint latency of the shared AI HTTP clients. */
+  async getHttpTransportStats(): Promise<HttpTransportStats> {
+    try {
+      return await api.invoke('get_http_transport_stats');
+    } catch (error) {
+      throw createTauriCommandError('get_http_transport_stats', error);
+    }
+  }
+
+  /** Whether network features are currently turned off, and why. */
+  async getOfflineStatus(): Promise<OfflineStatus> {
+    try {
+      return await api.invoke('get_offline_status');
+    } catch (error) {
+      throw createTauriCommandError('get_offline_status', error);
+    }
+  }
+
+  /** Called whenever offline mode turns on or off. */
+  onOfflineModeChanged(callback: (status: OfflineStatus) => void): () => void {
+    return api.listen<{ value?: OfflineStatus } & OfflineStatus>(
+      'backend-event-offlinemodechanged',
+      (event) => {
+        const info = event?.value ?? event;
+        callback({ offline: info.offline, reason: info.reason ?? null });
+      }
+    );
+  }
+
   /** Desktop only: register or unregister launch at OS login. */
   async setLaunchAtLoginEnabled(enabled: boolean): Promise<void> {
     if (typeof window === 'undefined' || !('__TAURI__' in window)) {
@@ -168,6 +251,31 @@ export class SystemAPI {
       throw createTauriCommandError('autostart_set', error, { enabled });
     }
   }
+
+  /** Stored crash reports, newest first. */
+  async listCrashReports(): Promise<CrashReportSummary[]> {
+    try {
+      return await api.invoke('list_crash_reports', {});
+    } catch (error) {
+      throw createTauriCommandError('list_crash_reports', error);
+    }
+  }
+
+  async getCrashReport(id: string): Promise<CrashReport> {
+    try {
+      return await api.invoke('get_crash_report', { request: { id } });
+    } catch (error) {
+      throw createTauriCommandError('get_crash_report', error, { id });
+    }
+  }
+
+  async deleteCrashReport(id: string): Promise<void> {
+    try {
+      await api.invoke('delete_crash_report', { request: { id } });
+    } catch (error) {
+      throw createTauriCommandError('delete_crash_report', error, { id });
+    }
+  }
 }
 
 
@@ -4,7 +4,9 @@ import { api } from './ApiClient';
 import { createTauriCommandError } from '../errors/TauriCommandError';
 import type {
   WorkspaceInfo,
-  FileSearchResult
+  FileSearchResult,
+  FileTreeDelta,
+  DirectorySizeReport
 } from './tauri-commands';
 import { createLogger } from '@/shared/utils/logger';
 
@@ -155,6 +157,16 @@ export class WorkspaceAPI {
     }
   }
 
+  async getFileTreeChanges(path: string, sinceVersion: number): Promise<FileTreeDelta> {
+    try {
+      return await api.invoke('get_file_tree_changes', {
+        request: { path, sinceVersion }
+      });
+    } catch (error) {
+      throw createTauriCommandError('get_file_tree_changes', error, { path, sinceVersion });
+    }
+  }
+
    
   async getDirectoryChildren(path: string): Promise<any[]> {
     try {
@@ -246,6 +258,28 @@ export class WorkspaceAPI {
     }
   }
 
+  /** Directory sizes and the largest files/directories of a workspace, for the workspace panel. */
+  async getDirectorySizeReport(rootPath: string, limit: number = 20): Promise<DirectorySizeReport> {
+    try {
+      return await api.invoke('get_directory_size_report', {
+        request: { rootPath, limit }
+      });
+    } catch (error) {
+      throw createTauriCommandError('get_directory_size_report', error, { rootPath, limit });
+    }
+  }
+
+  /** Fuzzy file name search for quick open, best matches first. */
+  async fuzzySearchFiles(rootPath: string, query: string, maxResults: number = 50): Promise<FileSearchResult[]> {
+    try {
+      return await api.invoke('fuzzy_search_files', {
+        request: { rootPath, query, maxResults }
+      });
+    } catch (error) {
+      throw createTauriCommandError('fuzzy_search_files', error, { rootPath, query, maxResults });
+    }
+  }
+
    
   async searchFilenamesOnly(
     rootPath: string, 
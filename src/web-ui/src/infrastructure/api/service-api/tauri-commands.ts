@@ -149,7 +149,7 @@ export interface SearchFilesRequest {
   searchContent?: boolean;
 }
 
-export type SearchMatchType = 'fileName' | 'content';
+export type SearchMatchType = 'fileName' | 'content' | 'fuzzy';
 
 export interface FileSearchResult {
   path: string;
@@ -158,4 +158,42 @@ export interface FileSearchResult {
   matchType: SearchMatchType;
   lineNumber?: number;
   matchedContent?: string;
+  /** Fuzzy matches only: ranking score, higher is better */
+  score?: number;
+  /** Fuzzy matches only: character positions of the match in the workspace-relative path */
+  matchIndices?: number[];
+}
+
+export interface LargestEntry {
+  path: string;
+  size_bytes: number;
+  is_directory: boolean;
+}
+
+/** Sizes counted over the entries the file tree shows (`get_directory_size_report`) */
+export interface DirectorySizeReport {
+  root_path: string;
+  total_size_bytes: number;
+  /** Top-level entries, largest first; directory sizes are aggregated */
+  entries: Array<{ name: string; path: string; isDirectory: boolean; size?: number }>;
+  /** Largest files and directories anywhere in the tree, biggest first */
+  largest_entries: LargestEntry[];
+}
+
+export interface FileTreeDeltaChange {
+  path: string;
+  event_type: 'create' | 'modify' | 'delete';
+  timestamp: number;
+  /** Tree node of a created or modified entry */
+  node?: Record<string, any>;
+}
+
+/** Changes that bring a cached file tree from `base_version` to `version` (`file-tree-changed` event) */
+export interface FileTreeDelta {
+  workspace_path: string;
+  base_version: number;
+  version: number;
+  /** The changes no longer reach back to `base_version`; reload the tree */
+  reset: boolean;
+  changes: FileTreeDeltaChange[];
 }
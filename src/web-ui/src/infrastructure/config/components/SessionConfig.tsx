@@ -451,6 +451,22 @@ const SessionConfig: React.FC = () => {
           </ConfigPageRow>
         </ConfigPageSection>
 
+        {/* ── Turn summary ───────────────────────────────────────── */}
+        <ConfigPageSection
+          title={t('features.turnSummary.title')}
+          description={t('features.turnSummary.subtitle')}
+        >
+          <ConfigPageRow label={t('common.enable')} align="center">
+            <div className="bitfun-func-agent-config__row-control">
+              <Switch
+                checked={settings.enable_turn_summary}
+                onChange={(e) => updateSetting('enable_turn_summary', e.target.checked)}
+                size="small"
+              />
+            </div>
+          </ConfigPageRow>
+        </ConfigPageSection>
+
         {/* ── Tool execution behavior ────────────────────────────── */}
         <ConfigPageSection
           title={t('toolExecution.sectionTitle')}
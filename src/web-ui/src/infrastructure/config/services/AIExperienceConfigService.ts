@@ -8,6 +8,7 @@ const log = createLogger('AIExperienceConfig');
 export interface AIExperienceSettings {
   enable_session_title_generation: boolean;
   enable_visual_mode: boolean;
+  enable_turn_summary: boolean;
 }
 
 const CONFIG_PATH = 'app.ai_experience';
@@ -15,6 +16,7 @@ const CONFIG_PATH = 'app.ai_experience';
 const defaultSettings: AIExperienceSettings = {
   enable_session_title_generation: true,
   enable_visual_mode: false,
+  enable_turn_summary: false,
 };
 
  
@@ -4,6 +4,7 @@ import {
   IConfigManager,
   ConfigValidationResult,
   ConfigExport,
+  ConfigChangedEvent,
 } from '../types';
 import { configAPI } from '@/infrastructure/api';
 import { i18nService } from '@/infrastructure/i18n';
@@ -20,6 +21,44 @@ class ConfigManagerImpl implements IConfigManager {
 
   constructor() {
     log.info('Initializing config manager (proxy mode)');
+    try {
+      configAPI.onConfigChanged(event => {
+        void this.handleExternalConfigChange(event);
+      });
+    } catch (error) {
+      log.warn('Failed to subscribe to config file changes', error);
+    }
+  }
+
+  /** Drops cached values and notifies listeners after the config file was edited outside the app. */
+  private async handleExternalConfigChange(event: ConfigChangedEvent): Promise<void> {
+    log.info('Config file changed on disk', {
+      changedKeys: event.changed_keys,
+      requiresRestart: event.requires_restart,
+    });
+
+    const paths = new Set<string>();
+    for (const key of event.changed_keys) {
+      const segments = key.split('.');
+      for (let i = segments.length; i > 0; i--) {
+        paths.add(segments.slice(0, i).join('.'));
+      }
+    }
+
+    const oldValues = new Map(this.configCache);
+    this.configCache.clear();
+
+    for (const path of paths) {
+      if (this.listeners.size === 0 && !this.pathListeners.has(path)) {
+        continue;
+      }
+      try {
+        const newValue = await this.getConfig(path);
+        this.notifyConfigChange(path, oldValues.get(path), newValue);
+      } catch (error) {
+        log.error('Failed to read changed config', { path, error });
+      }
+    }
   }
 
   private async migrateLegacyAiModelsIfNeeded(config: unknown): Promise<unknown> {
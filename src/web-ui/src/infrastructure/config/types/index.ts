@@ -8,10 +8,28 @@ export interface GlobalConfig {
   terminal: TerminalConfig;
   workspace: WorkspaceConfig;
   ai: AIConfig;
+  /** Per-agent overrides keyed by agent id. */
+  agents?: Record<string, AgentOverrideConfig>;
+  /** Named partial configs deep-merged over the base config when active. */
+  profiles?: Record<string, Partial<Record<string, unknown>>>;
+  active_profile?: string;
+  /** Shape of the persisted config; older files are migrated on load. */
+  schema_version: number;
   version: string;
   last_modified: number; 
 }
 
+/** Per-agent override of the model, tools and turn limit. */
+export interface AgentOverrideConfig {
+  /** Model ID from `ai.models`, or `primary` / `fast` / `auto` */
+  model?: string;
+  additional_tools?: string[];
+  /** Applied after `additional_tools` */
+  removed_tools?: string[];
+  /** Maximum model rounds per dialog turn */
+  max_turns?: number;
+}
+
 export interface AppConfig {
   language: string;
   auto_update: boolean;
@@ -26,12 +44,79 @@ export interface AppConfig {
   notifications: NotificationConfig;
   session_config: AppSessionConfig;
   ai_experience: AIExperienceConfig;
+  storage?: AppStorageConfig;
+  plugins?: AppPluginsConfig;
+  /** Turns off web tools, remote MCP servers and cloud models. */
+  offline_mode?: boolean;
+}
+
+export interface AppPluginsConfig {
+  /** URL of the plugin marketplace index; empty disables the marketplace. */
+  marketplace_url: string;
+}
+
+export type CacheType =
+  | 'models'
+  | 'embeddings'
+  | 'git'
+  | 'index'
+  | 'web_fetch'
+  | 'checkpoints'
+  | 'image_analysis';
+
+export type CleanupTarget =
+  | 'temp'
+  | 'logs'
+  | 'tool_trash'
+  | 'cowork_temp'
+  | 'web_cache'
+  | 'session_archives'
+  | 'image_analysis_cache';
+
+export interface TargetCleanupPolicy {
+  target: CleanupTarget;
+  max_age_days?: number | null;
+  max_total_size_mb?: number | null;
+  /** Hours between scheduled runs; unset runs only on demand */
+  interval_hours?: number | null;
+  enabled?: boolean;
+}
+
+export type StorageBackendKind = 'file' | 'sqlite';
+
+export type BackupCategory = 'config' | 'skills' | 'sessions' | 'memory';
+
+export interface BackupSchedule {
+  enabled?: boolean;
+  interval_hours?: number;
+  /** Backups kept; older ones are deleted after each scheduled backup */
+  keep_last?: number;
+  categories?: BackupCategory[];
+}
+
+export interface AppStorageConfig {
+  /** Where persisted data lives; run `bitfun storage migrate` before switching to sqlite */
+  backend?: StorageBackendKind;
+  /** zstd-compress large session turns and context snapshots */
+  compression_enabled?: boolean;
+  /** Files smaller than this many KB stay plain JSON */
+  compression_min_kb?: number;
+  /** Size limit in MB per cache type; unset types use built-in defaults */
+  cache_quotas_mb?: Partial<Record<CacheType, number>>;
+  auto_cleanup_enabled?: boolean;
+  /** Cleanup policies; empty uses the built-in ones */
+  cleanup_policies?: TargetCleanupPolicy[];
+  /** Scheduled backups of config, skills, sessions and memory */
+  backup?: BackupSchedule;
 }
 
 export type BackendLogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error' | 'off';
 
 export interface AppLoggingConfig {
   level: BackendLogLevel;
+  /** Level overrides per log target prefix, e.g. { 'bitfun_core::service::mcp': 'debug' }. */
+  target_levels?: Record<string, BackendLogLevel>;
+  record_events: boolean;
 }
 
 export interface AppSessionConfig {
@@ -61,6 +146,9 @@ export interface AIExperienceConfig {
 
   /** Whether to enable visual mode (use Mermaid diagrams to illustrate complex logic and flows). */
   enable_visual_mode: boolean;
+
+  /** Whether to summarize each completed turn in three bullets with the fast model. */
+  enable_turn_summary?: boolean;
 }
 
 
@@ -137,6 +225,101 @@ export interface AIModelConfig {
 
   /** Reasoning effort for OpenAI Responses API ("low" | "medium" | "high" | "xhigh") */
   reasoning_effort?: string;
+
+  /** Reasoning settings; when set, overrides enable_thinking_process and reasoning_effort. */
+  reasoning?: ReasoningConfig;
+
+  /** Max retries for rate-limited (429) or transient 5xx errors before any output. Default 3. */
+  max_retries?: number;
+
+  /** Request complete JSON responses instead of SSE (OpenAI and Anthropic formats). */
+  disable_streaming?: boolean;
+
+  /** Seconds to wait between streamed events before failing the request. Default 600. */
+  stream_idle_timeout_secs?: number;
+
+  /** Max total seconds for a streamed response. Unset means no limit. */
+  stream_deadline_secs?: number;
+
+  /** Add Anthropic prompt cache breakpoints (anthropic format only). */
+  enable_prompt_caching?: boolean;
+
+  /** Minimum estimated prefix tokens before a cache breakpoint is placed. Default 1024. */
+  prompt_cache_min_tokens?: number;
+
+  /** Let Anthropic run web searches itself instead of the local WebSearch tool (anthropic format only). */
+  native_web_search?: boolean;
+
+  /** Embedding model used when the caller doesn't name one. Defaults to model_name. */
+  embedding_model?: string;
+
+  /** OpenAI-compatible embeddings endpoint (e.g. a local server). Derived from base_url when unset. */
+  embeddings_url?: string;
+
+  /** Max inputs per embeddings request. Provider default when unset. */
+  embedding_batch_size?: number;
+
+  /** Prices (USD per million tokens) used for spend tracking. List prices for known models when unset; otherwise spend is not tracked. */
+  pricing?: ModelPricing;
+
+  /** Send image attachments to this model as image parts. Derived from capabilities/category and known models when unset. */
+  supports_vision?: boolean;
+
+  /** Send tool definitions to this model. Derived from capabilities/category and known models when unset. */
+  supports_tools?: boolean;
+
+  /** The model can produce reasoning output. Derived from known models and reasoning settings when unset. */
+  supports_reasoning?: boolean;
+
+  /** Longest side (px) attached images are scaled down to before sending. Provider limit when unset. */
+  image_max_dimension?: number;
+
+  /** JPEG quality (1-100) used when attached images must be re-encoded. 85 when unset. */
+  image_quality?: number;
+
+  /** Run local OCR before image analysis and skip the model for text-dominant images. Enabled when OCR is available if unset. */
+  image_ocr?: boolean;
+
+  /** Proxy for this model, overriding the global proxy. A disabled entry forces a direct connection. */
+  proxy?: ProxyConfig;
+
+  /** Models (id, name, or model_name) tried in order when this model fails. */
+  fallback_models?: string[];
+}
+
+export interface ModelPricing {
+  input_per_mtok: number;
+  output_per_mtok: number;
+  /** Billed as input when unset. */
+  cached_input_per_mtok?: number;
+  /** USD per server-side web search. Defaults to 0.01. */
+  web_search_per_request?: number;
+}
+
+/** A configured model with capabilities, context window and prices resolved for display. */
+export interface AvailableModel {
+  id: string;
+  name: string;
+  provider: string;
+  model_name: string;
+  enabled: boolean;
+  supports_tools: boolean;
+  supports_vision: boolean;
+  supports_reasoning: boolean;
+  context_window: number;
+  max_tokens?: number;
+  /** Unset when neither the config nor the built-in table has prices. */
+  pricing?: ModelPricing;
+  /** Whether model_name matched the built-in table of known models. */
+  known: boolean;
+}
+
+export interface ReasoningConfig {
+  enabled: boolean;
+  /** Anthropic extended thinking budget; must be below max_tokens (min 1024). */
+  budget_tokens?: number;
+  /** OpenAI reasoning effort ("minimal" | "low" | "medium" | "high" | "xhigh") */
+  effort?: string;
 }
 
 export interface ProxyConfig {
@@ -144,6 +327,10 @@ export interface ProxyConfig {
   url: string;
   username?: string;
   password?: string;
+  /** Hosts reached directly, bypassing the proxy (NO_PROXY syntax). */
+  no_proxy?: string[];
+  /** PEM CA bundle trusted in addition to the system roots (applies even when disabled). */
+  ca_bundle_path?: string;
 }
 
  
@@ -174,6 +361,11 @@ export interface AIConfig {
   tool_confirmation_timeout_secs?: number | null;
   skip_tool_confirmation?: boolean;
   computer_use_enabled?: boolean;
+  request_log?: AIRequestLogConfig;
+  spend_guardrails?: SpendGuardrailsConfig;
+  bash_sandbox?: BashSandboxConfig;
+  loop_guard?: LoopGuardConfig;
+  recent_changes_context?: RecentChangesContextConfig;
 }
 
 
@@ -224,6 +416,46 @@ export interface SkillMarketDownloadResult {
 
 
  
+export interface AIRequestLogConfig {
+  enabled: boolean;
+  redact_file_contents: boolean;
+  redact_secrets: boolean;
+  max_content_chars: number;
+  max_entry_bytes: number;
+}
+
+/** USD thresholds; each is off when null. */
+export interface SpendGuardrailsConfig {
+  daily_warn_usd?: number | null;
+  turn_confirm_usd?: number | null;
+  daily_hard_stop_usd?: number | null;
+}
+
+/** Limits Bash commands to writing inside the workspace, temp dirs and writable_paths. */
+export interface BashSandboxConfig {
+  enabled: boolean;
+  allow_network: boolean;
+  auto_allow_sandboxed: boolean;
+  writable_paths: string[];
+}
+
+/** Repetition counts at which a looping agent is reminded and then stopped. */
+export interface LoopGuardConfig {
+  enabled: boolean;
+  nudge_after: number;
+  stop_after: number;
+  max_cycle_length: number;
+}
+
+/** Digest of recently modified workspace files attached to a session's first turn. */
+export interface RecentChangesContextConfig {
+  enabled: boolean;
+  max_files: number;
+  max_excerpt_chars: number;
+  token_budget: number;
+  lookback_minutes: number;
+}
+
 export interface DebugModeConfig {
    
   log_path: string;
@@ -404,6 +636,23 @@ export interface EditorConfig {
   
   semantic_highlighting?: boolean;   
   bracket_pair_colorization?: boolean; 
+
+  lsp?: LspConfig;
+}
+
+export interface LspConfig {
+  /** Per-language overrides keyed by language id. */
+  servers: Record<string, LspServerConfig>;
+  max_running_servers: number;
+  max_open_documents: number;
+  diagnostics_timeout_ms: number;
+}
+
+export interface LspServerConfig {
+  enabled: boolean;
+  /** Server executable used instead of an installed plugin. */
+  command?: string;
+  args: string[];
 }
 
 export interface MinimapConfig {
@@ -440,6 +689,22 @@ export interface WorkspaceConfig {
   include_patterns: string[];
   file_associations: Record<string, string>;
   search_exclude_patterns: string[];
+  tool_policy: ToolPolicyConfig;
+  /** Environment variables new sessions start with; values may be `keyring:<name>` references. */
+  env?: Record<string, string>;
+}
+
+/** Tools offered to agents; empty `allowed_tools` allows every tool not denied. */
+export interface ToolPolicyConfig {
+  allowed_tools: string[];
+  denied_tools: string[];
+}
+
+/** Tools an agent is offered in a workspace, with the tool policy applied there. */
+export interface EffectiveAgentTools {
+  agentType: string;
+  tools: string[];
+  toolPolicy: ToolPolicyConfig;
 }
 
 
@@ -473,12 +738,81 @@ export interface ConfigValidationError {
   path: string;
   message: string;
   code: string;
+  /** Offending value at `path`, when it could be located */
+  value?: unknown;
+  /** Closest known value or key for a misspelling */
+  suggestion?: string;
 }
 
 export interface ConfigValidationWarning {
   path: string;
   message: string;
   code: string;
+  /** Offending value at `path`, when it could be located */
+  value?: unknown;
+  /** Closest known value or key for a misspelling */
+  suggestion?: string;
+}
+
+/** Layer an effective config value comes from (workspace > profile > user > default). */
+export type ConfigSource = 'default' | 'user' | 'profile' | 'workspace';
+
+export interface AnnotatedConfig<T = unknown> {
+  value: T;
+  /** Dot-path of each leaf value -> the layer that set it */
+  sources: Record<string, ConfigSource>;
+}
+
+/** Secret stored in plaintext in the user config. */
+export interface PlaintextSecret {
+  /** Dot-path of the value; array items are addressed by their id */
+  path: string;
+  /** Name the secret gets in the secrets store (`keyring:<name>`) */
+  name: string;
+}
+
+/** Payload of the `config-changed` event, sent when the config file was edited outside the app. */
+export interface ConfigChangedEvent {
+  source: 'file';
+  /** Dot-paths of the changed values; arrays are reported as a whole */
+  changed_keys: string[];
+  /** Changed keys that only take effect after a restart */
+  requires_restart: string[];
+}
+
+/** How imported settings combine with the current ones. */
+export type SettingsMergeStrategy = 'merge' | 'replace';
+
+/** Result of exporting a settings bundle. */
+export interface SettingsExportSummary {
+  path: string;
+  /** Number of secrets included (encrypted) in the bundle */
+  secrets_included: number;
+}
+
+/** One changed config value; secrets are redacted. */
+export interface SettingsChange {
+  path: string;
+  /** Absent when the value is added */
+  before?: unknown;
+  /** Absent when the value is removed */
+  after?: unknown;
+}
+
+/** Result of importing, or dry-running the import of, a settings bundle. */
+export interface SettingsImportReport {
+  changes: SettingsChange[];
+  /** MCP servers from the bundle, added or updated */
+  mcp_servers: string[];
+  /** MCP servers removed because the bundle replaces the config */
+  mcp_servers_removed: string[];
+  /** Config paths whose secret was imported */
+  secrets_imported: string[];
+  /** Config paths whose secret is not in the bundle and must be set again */
+  missing_secrets: string[];
+  warnings: string[];
+  /** False for a dry run */
+  applied: boolean;
 }
 
 export interface ConfigExport {
@@ -521,6 +855,8 @@ export type ConfigPath =
   | 'app.sidebar'
   | 'app.sidebar.width'
   | 'app.sidebar.collapsed'
+  | 'app.plugins'
+  | 'app.plugins.marketplace_url'
   | 'editor'
   | 'editor.font_size'
   | 'editor.theme'
@@ -544,12 +880,49 @@ export interface ConfigPanelProps {
 
 export interface RuntimeLoggingInfo {
   effectiveLevel: BackendLogLevel;
+  targetLevels: Record<string, BackendLogLevel>;
   sessionLogDir: string;
   appLogPath: string;
   aiLogPath: string;
   webviewLogPath: string;
 }
 
+export type LogTarget = 'app' | 'ai' | 'webview';
+
+export interface LogLineFilter {
+  /** Most verbose level to keep, e.g. 'warn' keeps warnings and errors. */
+  level?: BackendLogLevel;
+  /** Case-insensitive text the line must contain. */
+  contains?: string;
+}
+
+export interface LogTailResult {
+  path: string;
+  lines: string[];
+  fileSize: number;
+}
+
+export interface LogLinesEvent {
+  subscriptionId: string;
+  target: LogTarget;
+  lines: string[];
+  /** Matching lines left out because the batch was too large. */
+  dropped: number;
+}
+
+export interface LogFileInfo {
+  name: string;
+  sizeBytes: number;
+}
+
+export interface LogSessionInfo {
+  name: string;
+  path: string;
+  sizeBytes: number;
+  isCurrent: boolean;
+  files: LogFileInfo[];
+}
+
 
 
  
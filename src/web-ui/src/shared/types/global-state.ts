@@ -92,6 +92,7 @@ export interface WorkspaceInfo {
   languages: string[];
   openedAt: string;
   lastAccessed: string;
+  openCount?: number;
   description?: string;
   tags: string[];
   statistics?: ProjectStatistics;
@@ -286,6 +287,7 @@ function mapWorkspaceInfo(workspace: APIWorkspaceInfo): WorkspaceInfo {
     languages: workspace.languages,
     openedAt: workspace.openedAt,
     lastAccessed: workspace.lastAccessed,
+    openCount: workspace.openCount,
     description: workspace.description ?? undefined,
     tags: workspace.tags,
     statistics: workspace.statistics
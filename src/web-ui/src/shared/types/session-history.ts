@@ -54,6 +54,17 @@ export interface DialogTurnData {
   endTime?: number;
   durationMs?: number;
   status: TurnStatus;
+  /** Budget that ended the turn with a summary round */
+  stoppedAtBudget?: 'rounds' | 'tool_calls' | 'duration';
+  /** AI summary added after the turn completed */
+  summary?: TurnSummary;
+}
+
+/** Three-bullet summary of a completed dialog turn */
+export interface TurnSummary {
+  did: string;
+  changed: string;
+  next: string;
 }
 
 export interface UserMessageData {
@@ -7,6 +7,8 @@ const log = createLogger('FileSystemService');
 
 interface FileWatchEvent {
   path: string;
+  /** Every path with this kind in the batch; `path` is the first of them */
+  paths?: string[];
   kind: string;
   timestamp: number;
   from?: string;
@@ -90,27 +92,29 @@ class FileSystemService implements IFileSystemService {
             absPath === normalizedRoot || absPath.startsWith(`${normalizedRoot}/`);
 
           events.forEach((fileEvent) => {
-            const normalizedEventPath = normalizeForCompare(fileEvent.path);
             const normalizedFrom = fileEvent.from
               ? normalizeForCompare(fileEvent.from)
               : '';
 
-            const relevant =
-              isUnderRoot(normalizedEventPath) ||
-              (fileEvent.kind === 'rename' && normalizedFrom !== '' && isUnderRoot(normalizedFrom));
-
-            if (!relevant) {
-              return;
-            }
-
-            const fsEvent: FileSystemChangeEvent = {
-              type: this.mapEventKind(fileEvent.kind),
-              path: fileEvent.path,
-              oldPath: fileEvent.from,
-              timestamp: new Date(fileEvent.timestamp * 1000)
-            };
-
-            callback(fsEvent);
+            (fileEvent.paths ?? [fileEvent.path]).forEach((path) => {
+              const normalizedEventPath = normalizeForCompare(path);
+              const relevant =
+                isUnderRoot(normalizedEventPath) ||
+                (fileEvent.kind === 'rename' && normalizedFrom !== '' && isUnderRoot(normalizedFrom));
+
+              if (!relevant) {
+                return;
+              }
+
+              const fsEvent: FileSystemChangeEvent = {
+                type: this.mapEventKind(fileEvent.kind),
+                path,
+                oldPath: fileEvent.from,
+                timestamp: new Date(fileEvent.timestamp * 1000)
+              };
+
+              callback(fsEvent);
+            });
           });
         });
       } catch (error) {
@@ -4,7 +4,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { createLogger } from '@/shared/utils/logger';
-import type { LspPlugin, CompletionItem, TextEdit } from '../types';
+import type { LspPlugin, CompletionItem, TextEdit, PluginUpdate, PluginUpdateInfo, MarketplaceListing } from '../types';
 
 const log = createLogger('LspService');
 
@@ -258,6 +258,56 @@ export class LspService {
     }
   }
 
+  /** Install a newer package over an installed plugin, keeping its state; `force` allows downgrades. */
+  async updatePlugin(packagePath: string, force = false): Promise<PluginUpdate> {
+    try {
+      const update = await invoke('lsp_update_plugin', {
+        request: { packagePath, force }
+      }) as PluginUpdate;
+      log.info('Plugin updated', update);
+      return update;
+    } catch (error) {
+      log.error('Failed to update plugin', error);
+      throw error;
+    }
+  }
+
+  /** Check installed plugins against the versions their update URLs announce. */
+  async checkPluginUpdates(): Promise<PluginUpdateInfo[]> {
+    try {
+      return await invoke('lsp_check_plugin_updates') as PluginUpdateInfo[];
+    } catch (error) {
+      log.error('Failed to check plugin updates', error);
+      throw error;
+    }
+  }
+
+  /** List marketplace plugins with their installation status. */
+  async listMarketplacePlugins(refresh = false): Promise<MarketplaceListing> {
+    try {
+      return await invoke('list_marketplace_plugins', {
+        request: { refresh }
+      }) as MarketplaceListing;
+    } catch (error) {
+      log.error('Failed to list marketplace plugins', error);
+      throw error;
+    }
+  }
+
+  /** Download and install (or update) a marketplace plugin. Returns the plugin ID. */
+  async installMarketplacePlugin(pluginId: string): Promise<string> {
+    try {
+      const installedId = await invoke('install_marketplace_plugin', {
+        request: { pluginId }
+      }) as string;
+      log.info('Marketplace plugin installed', { pluginId: installedId });
+      return installedId;
+    } catch (error) {
+      log.error('Failed to install marketplace plugin', error);
+      throw error;
+    }
+  }
+
   /** Uninstall an installed plugin by ID. */
   async uninstallPlugin(pluginId: string): Promise<void> {
     try {
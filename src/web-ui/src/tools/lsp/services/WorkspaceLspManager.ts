@@ -29,6 +29,13 @@ interface LspEvent {
   };
 }
 
+/** Envelope of every `lsp-event` (backend `LspEventPayload`). */
+interface LspEventPayload {
+  workspace_path: string;
+  language?: string;
+  event_data: LspEvent;
+}
+
 interface ServerState {
   status: 'stopped' | 'starting' | 'running' | 'failed' | 'restarting';
   language: string;
@@ -103,8 +110,11 @@ export class WorkspaceLspManager {
       });
       
 
-      this.eventUnlisten = await listen<LspEvent>('lsp-event', (event) => {
-        this.handleLspEvent(event.payload);
+      this.eventUnlisten = await listen<LspEventPayload>('lsp-event', (event) => {
+        if (event.payload.workspace_path !== this.workspacePath) {
+          return;
+        }
+        this.handleLspEvent(event.payload.event_data);
       });
       
       this.isInitialized = true;
@@ -117,11 +127,6 @@ export class WorkspaceLspManager {
   
   
   private handleLspEvent(event: LspEvent) {
-
-    if (event.data.workspace_path !== this.workspacePath) {
-      return;
-    }
-
     switch (event.type) {
       case 'ServerStateChanged':
         this.onServerStateChanged(event.data);
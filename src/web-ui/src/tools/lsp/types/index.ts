@@ -15,6 +15,52 @@ export interface LspPlugin {
   settings: Record<string, any>;
   checksum: string;
   min_bitfun_version: string;
+  update_url?: string;
+}
+
+export interface PluginUpdate {
+  pluginId: string;
+  previousVersion: string;
+  version: string;
+}
+
+export interface PluginUpdateInfo {
+  pluginId: string;
+  name: string;
+  installedVersion: string;
+  latestVersion: string | null;
+  updateAvailable: boolean;
+  downloadUrl: string | null;
+  notes: string | null;
+  error: string | null;
+}
+
+/** Plugin entry as published in the marketplace index (snake_case). */
+export interface MarketplaceEntry {
+  id: string;
+  name: string;
+  version: string;
+  description: string;
+  author: string;
+  languages: string[];
+  download_url: string;
+  sha256: string;
+  size: number | null;
+}
+
+export interface MarketplacePluginStatus {
+  entry: MarketplaceEntry;
+  installedVersion: string | null;
+  updateAvailable: boolean;
+}
+
+export interface MarketplaceListing {
+  plugins: MarketplacePluginStatus[];
+  /** Unix milliseconds of the last successful index fetch. */
+  fetchedAt: number;
+  /** The index could not be refreshed and the cached copy is outdated. */
+  stale: boolean;
+  error: string | null;
 }
 
 export interface ServerConfig {